@@ -2,6 +2,7 @@
 //! using public key(s) and signature threshold (minimum number of signatures
 //! needed to authorize an action) stored on-chain.
 
+pub mod name_service;
 mod storage;
 mod storage_key;
 mod types;
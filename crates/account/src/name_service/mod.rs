@@ -0,0 +1,216 @@
+//! A registry of human-readable names of addresses, such as `alice.nam`.
+//!
+//! A name is registered for a number of epochs and resolves to the address
+//! of its owner until it expires, after which anyone can register it again.
+//! The owner can renew a name to extend its expiration and transfer it to
+//! another address. The registration fee is charged per epoch in the native
+//! token and credited to the name service account, whose balance can't be
+//! debited. The fee and the bounds of a registration period are governance
+//! parameters.
+//!
+//! Every address has a name for the reverse lookup, which is the last name
+//! that it was given, as long as it still owns it.
+
+mod storage;
+mod storage_key;
+
+use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::types::address::{self, Address};
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+use serde::{Deserialize, Serialize};
+pub use storage::*;
+pub use storage_key::*;
+
+/// The name service internal address
+pub const ADDRESS: Address = address::NAME_SERVICE;
+
+/// The suffix of the names, that is not part of their storage keys
+pub const NAME_SUFFIX: &str = ".nam";
+
+/// The minimum length of a name, without its suffix
+pub const MIN_NAME_LEN: usize = 3;
+
+/// The maximum length of a name, without its suffix
+pub const MAX_NAME_LEN: usize = 32;
+
+/// The registration of a name
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct NameRecord {
+    /// The address that the name resolves to
+    pub owner: Address,
+    /// The first epoch in which the name is expired
+    pub expiration: Epoch,
+}
+
+impl NameRecord {
+    /// Check if the name is expired in the given epoch.
+    pub fn is_expired(&self, epoch: Epoch) -> bool {
+        epoch >= self.expiration
+    }
+}
+
+/// The governance parameters of the name service
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct NameServiceParams {
+    /// The fee of the registration of a name for an epoch, in the native
+    /// token
+    pub fee_per_epoch: token::Amount,
+    /// The minimum number of epochs of a registration or a renewal
+    pub min_epochs: u64,
+    /// The maximum number of epochs for which a name can be registered
+    /// ahead of the current epoch
+    pub max_epochs: u64,
+}
+
+impl Default for NameServiceParams {
+    fn default() -> Self {
+        Self {
+            fee_per_epoch: token::Amount::native_whole(1),
+            min_epochs: 1,
+            max_epochs: 10_000,
+        }
+    }
+}
+
+impl NameServiceParams {
+    /// Get the fee of the registration of a name for the given number of
+    /// epochs. Returns `None` on overflow.
+    pub fn fee(&self, epochs: u64) -> Option<token::Amount> {
+        self.fee_per_epoch
+            .checked_mul(token::Amount::from_u64(epochs))
+    }
+}
+
+/// Check if the given name, without its suffix, can be registered. A name
+/// is made of lowercase ASCII letters, digits and hyphens, and neither
+/// starts nor ends with a hyphen.
+pub fn is_valid_name(name: &str) -> bool {
+    (MIN_NAME_LEN..=MAX_NAME_LEN).contains(&name.len())
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+}
+
+/// Parse a name with its suffix, such as `alice.nam`, into the registered
+/// name, such as `alice`.
+pub fn parse_name(name: &str) -> Option<&str> {
+    name.strip_suffix(NAME_SUFFIX)
+        .filter(|name| is_valid_name(name))
+}
+
+/// A tx data type to register a name
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct RegisterName {
+    /// The name, without its suffix
+    pub name: String,
+    /// The address that pays the fee and owns the name
+    pub owner: Address,
+    /// The number of epochs of the registration
+    pub epochs: u64,
+}
+
+/// A tx data type to extend the registration of a name
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct RenewName {
+    /// The name, without its suffix
+    pub name: String,
+    /// The number of epochs by which to extend the registration
+    pub epochs: u64,
+}
+
+/// A tx data type to transfer a name to another owner
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct TransferName {
+    /// The name, without its suffix
+    pub name: String,
+    /// The new owner of the name
+    pub new_owner: Address,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the validation of the names.
+    #[test]
+    fn test_names() {
+        for name in ["alice", "bob-2", "123", "a-b-c"] {
+            assert!(is_valid_name(name), "{name} must be valid");
+        }
+        for name in ["al", "-alice", "alice-", "Alice", "al.ice", "alice_1"] {
+            assert!(!is_valid_name(name), "{name} must be invalid");
+        }
+        assert!(is_valid_name(&"a".repeat(MAX_NAME_LEN)));
+        assert!(!is_valid_name(&"a".repeat(MAX_NAME_LEN + 1)));
+
+        assert_eq!(parse_name("alice.nam"), Some("alice"));
+        assert_eq!(parse_name("alice"), None);
+        assert_eq!(parse_name("al.nam"), None);
+        assert_eq!(
+            parse_name("tnam1q99c37u38grkdcc2qze0hz4zjjd8zr3yucd3mzgz"),
+            None
+        );
+    }
+
+    /// Test the registration fees.
+    #[test]
+    fn test_fee() {
+        let params = NameServiceParams::default();
+        assert_eq!(params.fee(10), Some(token::Amount::native_whole(10)));
+        let params = NameServiceParams {
+            fee_per_epoch: token::Amount::max(),
+            ..params
+        };
+        assert_eq!(params.fee(2), None);
+    }
+}
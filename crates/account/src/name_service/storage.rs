@@ -0,0 +1,153 @@
+//! Name service storage API
+
+use namada_core::types::address::Address;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+use namada_storage::{Error, OptionExt, Result, StorageRead, StorageWrite};
+
+use super::*;
+
+/// Read the name service parameters, or their defaults if governance
+/// hasn't set them.
+pub fn read_params<S>(storage: &S) -> Result<NameServiceParams>
+where
+    S: StorageRead,
+{
+    Ok(storage.read(&params_key())?.unwrap_or_default())
+}
+
+/// Get the registration of a name, even if it's expired
+pub fn name_record<S>(storage: &S, name: &str) -> Result<Option<NameRecord>>
+where
+    S: StorageRead,
+{
+    storage.read(&name_key(name))
+}
+
+/// Get the registration of a name, if it's not expired
+pub fn live_name_record<S>(
+    storage: &S,
+    name: &str,
+) -> Result<Option<NameRecord>>
+where
+    S: StorageRead,
+{
+    let epoch = storage.get_block_epoch()?;
+    Ok(name_record(storage, name)?.filter(|record| !record.is_expired(epoch)))
+}
+
+/// Resolve a name, without its suffix, to the address of its owner
+pub fn resolve_name<S>(storage: &S, name: &str) -> Result<Option<Address>>
+where
+    S: StorageRead,
+{
+    Ok(live_name_record(storage, name)?.map(|record| record.owner))
+}
+
+/// Find the name of an address, without its suffix, for the reverse lookup
+pub fn reverse_name<S>(storage: &S, owner: &Address) -> Result<Option<String>>
+where
+    S: StorageRead,
+{
+    let Some(name) = storage.read::<String>(&reverse_name_key(owner))? else {
+        return Ok(None);
+    };
+    let is_owned = live_name_record(storage, &name)?
+        .map_or(false, |record| record.owner == *owner);
+    Ok(is_owned.then_some(name))
+}
+
+/// Get the expiration of a registration of the given number of epochs,
+/// starting from `start`, if it's within the bounds of the parameters.
+fn checked_expiration(
+    params: &NameServiceParams,
+    epoch: Epoch,
+    start: Epoch,
+    epochs: u64,
+) -> Result<Epoch> {
+    if epochs < params.min_epochs {
+        return Err(Error::new_const("The registration period is too short"));
+    }
+    let expiration = start
+        .0
+        .checked_add(epochs)
+        .ok_or_err_msg("Name expiration overflow")?;
+    if expiration - epoch.0 > params.max_epochs {
+        return Err(Error::new_const("The registration period is too long"));
+    }
+    Ok(Epoch(expiration))
+}
+
+/// Register a name that is either free or expired. Returns the fee to be
+/// paid by the owner to the name service account.
+pub fn register_name<S>(
+    storage: &mut S,
+    name: &str,
+    owner: &Address,
+    epochs: u64,
+) -> Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    if !is_valid_name(name) {
+        return Err(Error::new_const("Invalid name"));
+    }
+    if live_name_record(storage, name)?.is_some() {
+        return Err(Error::new_const("The name is already registered"));
+    }
+    let params = read_params(storage)?;
+    let epoch = storage.get_block_epoch()?;
+    let expiration = checked_expiration(&params, epoch, epoch, epochs)?;
+    let fee = params.fee(epochs).ok_or_err_msg("Name fee overflow")?;
+    let record = NameRecord {
+        owner: owner.clone(),
+        expiration,
+    };
+    storage.write(&name_key(name), record)?;
+    storage.write(&reverse_name_key(owner), name.to_string())?;
+    Ok(fee)
+}
+
+/// Extend the registration of a name that is not expired. Returns the owner
+/// of the name and the fee to be paid to the name service account.
+pub fn renew_name<S>(
+    storage: &mut S,
+    name: &str,
+    epochs: u64,
+) -> Result<(Address, token::Amount)>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut record = live_name_record(storage, name)?
+        .ok_or_err_msg("The name is not registered")?;
+    let params = read_params(storage)?;
+    let epoch = storage.get_block_epoch()?;
+    record.expiration =
+        checked_expiration(&params, epoch, record.expiration, epochs)?;
+    let fee = params.fee(epochs).ok_or_err_msg("Name fee overflow")?;
+    storage.write(&name_key(name), &record)?;
+    Ok((record.owner, fee))
+}
+
+/// Transfer a name that is not expired to a new owner. Returns the previous
+/// owner.
+pub fn transfer_name<S>(
+    storage: &mut S,
+    name: &str,
+    new_owner: &Address,
+) -> Result<Address>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut record = live_name_record(storage, name)?
+        .ok_or_err_msg("The name is not registered")?;
+    let owner = std::mem::replace(&mut record.owner, new_owner.clone());
+    storage.write(&name_key(name), record)?;
+
+    let owner_reverse_key = reverse_name_key(&owner);
+    if storage.read::<String>(&owner_reverse_key)?.as_deref() == Some(name) {
+        storage.delete(&owner_reverse_key)?;
+    }
+    storage.write(&reverse_name_key(new_owner), name.to_string())?;
+    Ok(owner)
+}
@@ -0,0 +1,90 @@
+use namada_core::types::address::Address;
+use namada_core::types::storage::{self, DbKeySeg};
+use namada_macros::StorageKeys;
+
+use super::ADDRESS;
+
+/// Storage keys for the name service.
+#[derive(StorageKeys)]
+struct Keys {
+    names: &'static str,
+    reverse_names: &'static str,
+    params: &'static str,
+}
+
+/// Obtain the storage key of the registration of a name
+pub fn name_key(name: &str) -> storage::Key {
+    storage::Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS),
+            DbKeySeg::StringSeg(Keys::VALUES.names.to_string()),
+            DbKeySeg::StringSeg(name.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is the registration of a name. If it is,
+/// returns the name.
+pub fn is_name_key(key: &storage::Key) -> Option<&str> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(address),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(name),
+        ] if *address == ADDRESS && prefix.as_str() == Keys::VALUES.names => {
+            Some(name)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain the storage key of the name for the reverse lookup of an address
+pub fn reverse_name_key(owner: &Address) -> storage::Key {
+    storage::Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS),
+            DbKeySeg::StringSeg(Keys::VALUES.reverse_names.to_string()),
+            DbKeySeg::AddressSeg(owner.to_owned()),
+        ],
+    }
+}
+
+/// Check if the given storage key is the name for the reverse lookup of an
+/// address. If it is, returns the address.
+pub fn is_reverse_name_key(key: &storage::Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(address),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(owner),
+        ] if *address == ADDRESS
+            && prefix.as_str() == Keys::VALUES.reverse_names =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain the storage key of the name service parameters
+pub fn params_key() -> storage::Key {
+    storage::Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS),
+            DbKeySeg::StringSeg(Keys::VALUES.params.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is the name service parameters.
+pub fn is_params_key(key: &storage::Key) -> bool {
+    *key == params_key()
+}
+
+/// Check if the given storage key is in the name service sub-space.
+pub fn is_name_service_key(key: &storage::Key) -> bool {
+    matches!(
+        key.segments.first(),
+        Some(DbKeySeg::AddressSeg(address)) if *address == ADDRESS
+    )
+}
@@ -2,8 +2,9 @@
 
 use namada_core::types::address::Address;
 use namada_core::types::key::common;
-use namada_core::types::storage;
-use namada_storage::{Result, StorageRead, StorageWrite};
+use namada_core::types::storage::{self, Epoch};
+use namada_core::types::token;
+use namada_storage::{OptionExt, Result, StorageRead, StorageWrite};
 
 use super::*;
 
@@ -121,3 +122,75 @@ where
     }
     Ok(())
 }
+
+/// Get the spending policy associated with an account
+pub fn policy<S>(storage: &S, owner: &Address) -> Result<Option<AccountPolicy>>
+where
+    S: StorageRead,
+{
+    let policy_key = policy_key(owner);
+    storage.read(&policy_key)
+}
+
+/// Set or remove the spending policy of an account
+pub fn update_policy<S>(
+    storage: &mut S,
+    owner: &Address,
+    policy: Option<AccountPolicy>,
+) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    let policy_key = policy_key(owner);
+    match policy {
+        Some(policy) => storage.write(&policy_key, policy),
+        None => storage.delete(&policy_key),
+    }
+}
+
+/// Get the amount of a token debited from a policy account in the given epoch
+pub fn policy_outflow<S>(
+    storage: &S,
+    owner: &Address,
+    token: &Address,
+    epoch: Epoch,
+) -> Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let outflow = policy_outflows_handle(owner).get(storage, token)?;
+    Ok(outflow
+        .filter(|outflow| outflow.epoch == epoch)
+        .map(|outflow| outflow.amount)
+        .unwrap_or_default())
+}
+
+/// Record a debit of a token from an account in the current epoch, if the
+/// account's spending policy limits the outflow of the token. The record is
+/// checked against the limit by the policy account VP.
+pub fn record_policy_outflow<S>(
+    storage: &mut S,
+    owner: &Address,
+    token: &Address,
+    amount: token::Amount,
+) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    let is_limited = policy(storage, owner)?
+        .map(|policy| policy.outflow_limits.contains_key(token))
+        .unwrap_or_default();
+    if !is_limited || amount.is_zero() {
+        return Ok(());
+    }
+    let epoch = storage.get_block_epoch()?;
+    let amount = policy_outflow(storage, owner, token, epoch)?
+        .checked_add(amount)
+        .ok_or_err_msg("Policy outflow overflow")?;
+    policy_outflows_handle(owner).insert(
+        storage,
+        token.clone(),
+        PolicyOutflow { epoch, amount },
+    )?;
+    Ok(())
+}
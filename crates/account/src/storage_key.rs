@@ -5,12 +5,16 @@ use namada_macros::StorageKeys;
 use namada_storage::collections::lazy_map::LazyMap;
 use namada_storage::collections::{lazy_map, LazyCollection};
 
+use crate::PolicyOutflow;
+
 /// Storage keys for account.
 #[derive(StorageKeys)]
 struct Keys {
     public_keys: &'static str,
     threshold: &'static str,
     protocol_public_keys: &'static str,
+    policy: &'static str,
+    policy_outflows: &'static str,
 }
 
 /// Obtain a storage key for user's public key.
@@ -89,3 +93,63 @@ pub fn is_protocol_pk_key(key: &storage::Key) -> Option<&Address> {
         _ => None,
     }
 }
+
+/// Obtain the storage key for a user spending policy
+pub fn policy_key(owner: &Address) -> storage::Key {
+    storage::Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.policy.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is a spending policy key. If it is, returns
+/// the owner.
+pub fn is_policy_key(key: &storage::Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(prefix)]
+            if prefix.as_str() == Keys::VALUES.policy =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain a storage key prefix for user's policy outflows.
+pub fn policy_outflows_key_prefix(owner: &Address) -> storage::Key {
+    storage::Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.policy_outflows.to_string()),
+        ],
+    }
+}
+
+/// LazyMap handler for the user's policy outflows, keyed by token
+pub fn policy_outflows_handle(
+    owner: &Address,
+) -> LazyMap<Address, PolicyOutflow> {
+    LazyMap::open(policy_outflows_key_prefix(owner))
+}
+
+/// Check if the given storage key is a policy outflow key. If it is, returns
+/// the owner and the token.
+pub fn is_policy_outflow_key(
+    key: &storage::Key,
+) -> Option<(&Address, &Address)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(owner),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(data),
+            DbKeySeg::AddressSeg(token),
+        ] if prefix.as_str() == Keys::VALUES.policy_outflows
+            && data.as_str() == lazy_map::DATA_SUBKEY =>
+        {
+            Some((owner, token))
+        }
+        _ => None,
+    }
+}
@@ -1,7 +1,11 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::types::address::Address;
 use namada_core::types::hash::Hash;
 use namada_core::types::key::common;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
 use serde::{Deserialize, Serialize};
 
 /// A tx data type to initialize a new established account
@@ -50,12 +54,84 @@ pub struct UpdateAccount {
     pub threshold: Option<u8>,
 }
 
+/// A spending policy of a custodial account. The policy is enforced by the
+/// policy account VP on every transaction that debits the account.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct AccountPolicy {
+    /// When non-empty, the only addresses that may be credited by a
+    /// transaction debiting the account
+    pub allowed_targets: BTreeSet<Address>,
+    /// The maximum amount of a token that may be debited from the account
+    /// within a single epoch
+    pub outflow_limits: BTreeMap<Address, token::Amount>,
+    /// The accounts that must all sign a transaction debiting more than the
+    /// co-signing threshold of a token
+    pub cosigners: BTreeSet<Address>,
+    /// The amount of a token above which a debit must be co-signed
+    pub cosign_thresholds: BTreeMap<Address, token::Amount>,
+    /// The number of signatures from the account's keys needed to edit the
+    /// policy, the account's keys or its VP
+    pub quorum: u8,
+}
+
+/// The amount of a token debited from a policy account within an epoch
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct PolicyOutflow {
+    /// The epoch in which the outflow occurred
+    pub epoch: Epoch,
+    /// The total amount debited in the epoch
+    pub amount: token::Amount,
+}
+
+/// A tx data type to update or remove an account's spending policy
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct UpdatePolicy {
+    /// An address of the account
+    pub addr: Address,
+    /// The new policy. The current policy is removed if `None`.
+    pub policy: Option<AccountPolicy>,
+}
+
 #[cfg(any(test, feature = "testing"))]
 /// Tests and strategies for accounts
 pub mod tests {
-    use namada_core::types::address::testing::arb_non_internal_address;
+    use namada_core::types::address::testing::{
+        arb_address, arb_non_internal_address,
+    };
     use namada_core::types::hash::testing::arb_hash;
     use namada_core::types::key::testing::arb_common_pk;
+    use namada_core::types::token::testing::arb_amount;
     use proptest::prelude::Just;
     use proptest::{collection, option, prop_compose};
 
@@ -96,4 +172,44 @@ pub mod tests {
             }
         }
     }
+
+    prop_compose! {
+        /// Generate an arbitrary account policy
+        pub fn arb_account_policy()(
+            allowed_targets in collection::btree_set(arb_address(), 0..5),
+            outflow_limits in collection::btree_map(
+                arb_address(),
+                arb_amount(),
+                0..5,
+            ),
+            cosigners in collection::btree_set(
+                arb_non_internal_address(),
+                0..5,
+            ),
+            cosign_thresholds in collection::btree_map(
+                arb_address(),
+                arb_amount(),
+                0..5,
+            ),
+            quorum in 1..=u8::MAX,
+        ) -> AccountPolicy {
+            AccountPolicy {
+                allowed_targets,
+                outflow_limits,
+                cosigners,
+                cosign_thresholds,
+                quorum,
+            }
+        }
+    }
+
+    prop_compose! {
+        /// Generate an arbitrary account policy update
+        pub fn arb_update_policy()(
+            addr in arb_non_internal_address(),
+            policy in option::of(arb_account_policy()),
+        ) -> UpdatePolicy {
+            UpdatePolicy { addr, policy }
+        }
+    }
 }
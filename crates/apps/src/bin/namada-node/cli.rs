@@ -11,6 +11,11 @@ pub fn main() -> Result<()> {
     match cmd {
         cmds::NamadaNode::Ledger(sub) => match sub {
             cmds::Ledger::Run(cmds::LedgerRun(args)) => {
+                if let Some(dev) = args.dev {
+                    sleep_until(args.start_time);
+                    ledger::dev::run(dev);
+                    return Ok(());
+                }
                 let chain_ctx = ctx.take_chain_or_exit();
                 let wasm_dir = chain_ctx.wasm_dir();
                 sleep_until(args.start_time);
@@ -33,6 +38,11 @@ pub fn main() -> Result<()> {
                 let chain_ctx = ctx.take_chain_or_exit();
                 ledger::dump_db(chain_ctx.config.ledger, args);
             }
+            cmds::Ledger::DumpTxQuarantine(_) => {
+                let chain_ctx = ctx.take_chain_or_exit();
+                ledger::dump_tx_quarantine(chain_ctx.config.ledger)
+                    .wrap_err("Failed to dump the tx quarantine")?;
+            }
             cmds::Ledger::RollBack(_) => {
                 let chain_ctx = ctx.take_chain_or_exit();
                 ledger::rollback(chain_ctx.config.ledger)
@@ -11,6 +11,7 @@ use std::sync::Once;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
+use masp_primitives::memo::MemoBytes;
 use masp_primitives::transaction::Transaction;
 use masp_primitives::zip32::ExtendedFullViewingKey;
 use masp_proofs::prover::LocalTxProver;
@@ -50,13 +51,13 @@ use namada::ibc::core::host::types::path::{
 use namada::ibc::primitives::proto::{Any, Protobuf};
 use namada::ibc::primitives::{Msg, Timestamp as IbcTimestamp};
 use namada::ibc::storage::port_key;
-use namada::ledger::dry_run_tx;
 use namada::ledger::gas::TxGasMeter;
 use namada::ledger::ibc::storage::{channel_key, connection_key};
 use namada::ledger::native_vp::ibc::get_dummy_header;
 use namada::ledger::queries::{
     Client, EncodedResponseQuery, RequestCtx, RequestQuery, Router, RPC,
 };
+use namada::ledger::{dry_run_tx, simulate_tx};
 use namada::state::StorageRead;
 use namada::tendermint_rpc::{self};
 use namada::tx::data::pos::Bond;
@@ -222,6 +223,7 @@ impl Default for BenchShell {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: Some(defaults::albert_address()),
+            start_epoch: None,
         };
         let params =
             proof_of_stake::storage::read_pos_params(&bench_shell.wl_storage)
@@ -735,10 +737,10 @@ impl Client for BenchShell {
             storage_read_past_height_limit: None,
         };
 
-        if request.path == "/shell/dry_run_tx" {
-            dry_run_tx(ctx, &request)
-        } else {
-            RPC.handle(ctx, &request)
+        match request.path.as_str() {
+            "/shell/dry_run_tx" => dry_run_tx(ctx, &request),
+            "/shell/simulate_tx" => simulate_tx(ctx, &request),
+            _ => RPC.handle(ctx, &request),
         }
         .map_err(|_| std::io::Error::from(std::io::ErrorKind::NotFound))
     }
@@ -904,6 +906,7 @@ impl Default for BenchShieldedCtx {
             chain_id: Some(shell.inner.chain_id.clone()),
             base_dir,
             wasm_dir: Some(WASM_DIR.into()),
+            pretty_amounts: false,
         })
         .unwrap();
 
@@ -1006,6 +1009,7 @@ impl BenchShieldedCtx {
                     &target,
                     &address::nam(),
                     denominated_amount,
+                    MemoBytes::empty(),
                 ),
             )
             .unwrap()
@@ -216,9 +216,16 @@ pub mod cmds {
                 .subcommand(TxCustom::def().display_order(1))
                 .subcommand(TxTransfer::def().display_order(1))
                 .subcommand(TxIbcTransfer::def().display_order(1))
+                .subcommand(TxApprove::def().display_order(1))
+                .subcommand(TxTransferFrom::def().display_order(1))
+                .subcommand(TxMultiTransfer::def().display_order(1))
                 .subcommand(TxUpdateAccount::def().display_order(1))
+                .subcommand(TxUpdatePolicy::def().display_order(1))
                 .subcommand(TxInitAccount::def().display_order(1))
                 .subcommand(TxRevealPk::def().display_order(1))
+                .subcommand(TxRegisterName::def().display_order(1))
+                .subcommand(TxRenewName::def().display_order(1))
+                .subcommand(TxTransferName::def().display_order(1))
                 // Governance transactions
                 .subcommand(TxInitProposal::def().display_order(1))
                 .subcommand(TxVoteProposal::def().display_order(1))
@@ -241,12 +248,14 @@ pub mod cmds {
                 // PGF transactions
                 .subcommand(TxUpdateStewardCommission::def().display_order(4))
                 .subcommand(TxResignSteward::def().display_order(4))
+                .subcommand(TxClaimPgfFunding::def().display_order(4))
                 // Queries
                 .subcommand(QueryEpoch::def().display_order(5))
                 .subcommand(QueryAccount::def().display_order(5))
                 .subcommand(QueryTransfers::def().display_order(5))
                 .subcommand(QueryConversions::def().display_order(5))
                 .subcommand(QueryMaspRewardTokens::def().display_order(5))
+                .subcommand(QueryIbcEscrow::def().display_order(5))
                 .subcommand(QueryBlock::def().display_order(5))
                 .subcommand(QueryBalance::def().display_order(5))
                 .subcommand(QueryBonds::def().display_order(5))
@@ -268,6 +277,8 @@ pub mod cmds {
                 // Actions
                 .subcommand(SignTx::def().display_order(6))
                 .subcommand(GenIbcShieldedTransafer::def().display_order(6))
+                .subcommand(GenReceipt::def().display_order(6))
+                .subcommand(GenChainRegistry::def().display_order(6))
                 // Utils
                 .subcommand(Utils::def().display_order(7))
         }
@@ -277,8 +288,15 @@ pub mod cmds {
             let tx_custom = Self::parse_with_ctx(matches, TxCustom);
             let tx_transfer = Self::parse_with_ctx(matches, TxTransfer);
             let tx_ibc_transfer = Self::parse_with_ctx(matches, TxIbcTransfer);
+            let tx_approve = Self::parse_with_ctx(matches, TxApprove);
+            let tx_transfer_from =
+                Self::parse_with_ctx(matches, TxTransferFrom);
+            let tx_multi_transfer =
+                Self::parse_with_ctx(matches, TxMultiTransfer);
             let tx_update_account =
                 Self::parse_with_ctx(matches, TxUpdateAccount);
+            let tx_update_policy =
+                Self::parse_with_ctx(matches, TxUpdatePolicy);
             let tx_init_account = Self::parse_with_ctx(matches, TxInitAccount);
             let tx_become_validator =
                 Self::parse_with_ctx(matches, TxBecomeValidator);
@@ -291,6 +309,11 @@ pub mod cmds {
             let tx_reactivate_validator =
                 Self::parse_with_ctx(matches, TxReactivateValidator);
             let tx_reveal_pk = Self::parse_with_ctx(matches, TxRevealPk);
+            let tx_register_name =
+                Self::parse_with_ctx(matches, TxRegisterName);
+            let tx_renew_name = Self::parse_with_ctx(matches, TxRenewName);
+            let tx_transfer_name =
+                Self::parse_with_ctx(matches, TxTransferName);
             let tx_init_proposal =
                 Self::parse_with_ctx(matches, TxInitProposal);
             let tx_vote_proposal =
@@ -299,6 +322,8 @@ pub mod cmds {
                 Self::parse_with_ctx(matches, TxUpdateStewardCommission);
             let tx_resign_steward =
                 Self::parse_with_ctx(matches, TxResignSteward);
+            let tx_claim_pgf_funding =
+                Self::parse_with_ctx(matches, TxClaimPgfFunding);
             let tx_commission_rate_change =
                 Self::parse_with_ctx(matches, TxCommissionRateChange);
             let tx_change_consensus_key =
@@ -317,6 +342,8 @@ pub mod cmds {
                 Self::parse_with_ctx(matches, QueryConversions);
             let query_masp_reward_tokens =
                 Self::parse_with_ctx(matches, QueryMaspRewardTokens);
+            let query_ibc_escrow =
+                Self::parse_with_ctx(matches, QueryIbcEscrow);
             let query_block = Self::parse_with_ctx(matches, QueryBlock);
             let query_balance = Self::parse_with_ctx(matches, QueryBalance);
             let query_bonds = Self::parse_with_ctx(matches, QueryBonds);
@@ -348,13 +375,23 @@ pub mod cmds {
             let sign_tx = Self::parse_with_ctx(matches, SignTx);
             let gen_ibc_shielded =
                 Self::parse_with_ctx(matches, GenIbcShieldedTransafer);
+            let gen_receipt = Self::parse_with_ctx(matches, GenReceipt);
+            let gen_chain_registry =
+                Self::parse_with_ctx(matches, GenChainRegistry);
             let utils = SubCmd::parse(matches).map(Self::WithoutContext);
             tx_custom
                 .or(tx_transfer)
                 .or(tx_ibc_transfer)
+                .or(tx_approve)
+                .or(tx_transfer_from)
+                .or(tx_multi_transfer)
                 .or(tx_update_account)
+                .or(tx_update_policy)
                 .or(tx_init_account)
                 .or(tx_reveal_pk)
+                .or(tx_register_name)
+                .or(tx_renew_name)
+                .or(tx_transfer_name)
                 .or(tx_init_proposal)
                 .or(tx_vote_proposal)
                 .or(tx_become_validator)
@@ -373,10 +410,12 @@ pub mod cmds {
                 .or(add_to_eth_bridge_pool)
                 .or(tx_update_steward_commission)
                 .or(tx_resign_steward)
+                .or(tx_claim_pgf_funding)
                 .or(query_epoch)
                 .or(query_transfers)
                 .or(query_conversions)
                 .or(query_masp_reward_tokens)
+                .or(query_ibc_escrow)
                 .or(query_block)
                 .or(query_balance)
                 .or(query_bonds)
@@ -398,6 +437,8 @@ pub mod cmds {
                 .or(query_account)
                 .or(sign_tx)
                 .or(gen_ibc_shielded)
+                .or(gen_receipt)
+                .or(gen_chain_registry)
                 .or(utils)
         }
     }
@@ -438,8 +479,15 @@ pub mod cmds {
         TxCustom(TxCustom),
         TxTransfer(TxTransfer),
         TxIbcTransfer(TxIbcTransfer),
+        TxApprove(TxApprove),
+        TxTransferFrom(TxTransferFrom),
+        TxMultiTransfer(TxMultiTransfer),
         QueryResult(QueryResult),
         TxUpdateAccount(TxUpdateAccount),
+        TxUpdatePolicy(TxUpdatePolicy),
+        TxRegisterName(TxRegisterName),
+        TxRenewName(TxRenewName),
+        TxTransferName(TxTransferName),
         TxInitAccount(TxInitAccount),
         TxBecomeValidator(TxBecomeValidator),
         TxInitValidator(TxInitValidator),
@@ -460,11 +508,13 @@ pub mod cmds {
         AddToEthBridgePool(AddToEthBridgePool),
         TxUpdateStewardCommission(TxUpdateStewardCommission),
         TxResignSteward(TxResignSteward),
+        TxClaimPgfFunding(TxClaimPgfFunding),
         QueryEpoch(QueryEpoch),
         QueryAccount(QueryAccount),
         QueryTransfers(QueryTransfers),
         QueryConversions(QueryConversions),
         QueryMaspRewardTokens(QueryMaspRewardTokens),
+        QueryIbcEscrow(QueryIbcEscrow),
         QueryBlock(QueryBlock),
         QueryBalance(QueryBalance),
         QueryBonds(QueryBonds),
@@ -484,6 +534,8 @@ pub mod cmds {
         QueryRewards(QueryRewards),
         SignTx(SignTx),
         GenIbcShieldedTransafer(GenIbcShieldedTransafer),
+        GenReceipt(GenReceipt),
+        GenChainRegistry(GenChainRegistry),
     }
 
     #[allow(clippy::large_enum_variant)]
@@ -507,6 +559,8 @@ pub mod cmds {
         KeyAddrAdd(WalletAddKeyAddress),
         /// Key / address remove
         KeyAddrRemove(WalletRemoveKeyAddress),
+        /// Wallet daemon
+        Daemon(WalletDaemon),
     }
 
     impl Cmd for NamadaWallet {
@@ -520,6 +574,7 @@ pub mod cmds {
                 .subcommand(WalletImportKey::def())
                 .subcommand(WalletAddKeyAddress::def())
                 .subcommand(WalletRemoveKeyAddress::def())
+                .subcommand(WalletDaemon::def())
         }
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
@@ -533,6 +588,7 @@ pub mod cmds {
             let key_addr_add = SubCmd::parse(matches).map(Self::KeyAddrAdd);
             let key_addr_remove =
                 SubCmd::parse(matches).map(Self::KeyAddrRemove);
+            let daemon = SubCmd::parse(matches).map(Self::Daemon);
             gen.or(derive)
                 .or(pay_addr_gen)
                 .or(key_addr_list)
@@ -541,6 +597,7 @@ pub mod cmds {
                 .or(import)
                 .or(key_addr_add)
                 .or(key_addr_remove)
+                .or(daemon)
         }
     }
 
@@ -698,7 +755,7 @@ pub mod cmds {
             App::new(Self::CMD)
                 .about(
                     "Exports a transparent keypair / shielded spending key to \
-                     a file.",
+                     a file, optionally as a unified key or a JSON keystore.",
                 )
                 .add_args::<args::KeyExport>()
         }
@@ -770,6 +827,36 @@ pub mod cmds {
         }
     }
 
+    /// Serve the wallet over a local socket
+    #[derive(Clone, Debug)]
+    pub struct WalletDaemon(pub args::WalletDaemon);
+
+    impl SubCmd for WalletDaemon {
+        const CMD: &'static str = "daemon";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::WalletDaemon::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Serve the wallet operations over an authenticated local \
+                     JSON-RPC socket.",
+                )
+                .long_about(
+                    "Serve the wallet operations over an authenticated local \
+                     JSON-RPC socket, so that several frontends can share one \
+                     wallet state and one shielded context. The clients must \
+                     send the token written next to the socket with every \
+                     request.",
+                )
+                .add_args::<args::WalletDaemon>()
+        }
+    }
+
     /// Generate a payment address from a viewing key or payment address
     #[derive(Clone, Debug)]
     pub struct WalletGenPaymentAddress(pub args::PayAddressGen<args::CliTypes>);
@@ -798,6 +885,7 @@ pub mod cmds {
         RunUntil(LedgerRunUntil),
         Reset(LedgerReset),
         DumpDb(LedgerDumpDb),
+        DumpTxQuarantine(LedgerDumpTxQuarantine),
         RollBack(LedgerRollBack),
     }
 
@@ -809,15 +897,19 @@ pub mod cmds {
                 let run = SubCmd::parse(matches).map(Self::Run);
                 let reset = SubCmd::parse(matches).map(Self::Reset);
                 let dump_db = SubCmd::parse(matches).map(Self::DumpDb);
+                let dump_tx_quarantine =
+                    SubCmd::parse(matches).map(Self::DumpTxQuarantine);
                 let rollback = SubCmd::parse(matches).map(Self::RollBack);
                 let run_until = SubCmd::parse(matches).map(Self::RunUntil);
                 run.or(reset)
                     .or(dump_db)
+                    .or(dump_tx_quarantine)
                     .or(rollback)
                     .or(run_until)
                     // The `run` command is the default if no sub-command given
                     .or(Some(Self::Run(LedgerRun(args::LedgerRun {
                         start_time: None,
+                        dev: None,
                     }))))
             })
         }
@@ -832,6 +924,7 @@ pub mod cmds {
                 .subcommand(LedgerRunUntil::def())
                 .subcommand(LedgerReset::def())
                 .subcommand(LedgerDumpDb::def())
+                .subcommand(LedgerDumpTxQuarantine::def())
                 .subcommand(LedgerRollBack::def())
         }
     }
@@ -914,6 +1007,24 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct LedgerDumpTxQuarantine;
+
+    impl SubCmd for LedgerDumpTxQuarantine {
+        const CMD: &'static str = "dump-tx-quarantine";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|_matches| Self)
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD).about(
+                "Dump the malformed txs quarantined by the mempool of Namada \
+                 ledger node, decoding them where possible.",
+            )
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct LedgerRollBack;
 
@@ -1127,7 +1238,9 @@ pub mod cmds {
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query pgf stewards and continuous funding.")
+                .about(
+                    "Query pgf stewards, continuous funding and retro claims.",
+                )
                 .add_args::<args::QueryPgf<args::CliTypes>>()
         }
     }
@@ -1189,6 +1302,73 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct TxApprove(pub args::TxApprove<args::CliTypes>);
+
+    impl SubCmd for TxApprove {
+        const CMD: &'static str = "approve";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxApprove(args::TxApprove::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to allow a spender to transfer \
+                     the owner's tokens, up to an allowance.",
+                )
+                .add_args::<args::TxApprove<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxTransferFrom(pub args::TxTransferFrom<args::CliTypes>);
+
+    impl SubCmd for TxTransferFrom {
+        const CMD: &'static str = "transfer-from";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxTransferFrom(args::TxTransferFrom::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to transfer the owner's tokens \
+                     by an approved spender.",
+                )
+                .add_args::<args::TxTransferFrom<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxMultiTransfer(pub args::TxMultiTransfer<args::CliTypes>);
+
+    impl SubCmd for TxMultiTransfer {
+        const CMD: &'static str = "multi-transfer";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxMultiTransfer(args::TxMultiTransfer::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction with a batch of transparent \
+                     transfers from a single source, that are applied \
+                     atomically.",
+                )
+                .add_args::<args::TxMultiTransfer<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxUpdateAccount(pub args::TxUpdateAccount<args::CliTypes>);
 
@@ -1211,6 +1391,94 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct TxUpdatePolicy(pub args::TxUpdatePolicy<args::CliTypes>);
+
+    impl SubCmd for TxUpdatePolicy {
+        const CMD: &'static str = "update-policy";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxUpdatePolicy(args::TxUpdatePolicy::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to set or remove an account's \
+                     spending policy.",
+                )
+                .add_args::<args::TxUpdatePolicy<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxRegisterName(pub args::RegisterName<args::CliTypes>);
+
+    impl SubCmd for TxRegisterName {
+        const CMD: &'static str = "register-name";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxRegisterName(args::RegisterName::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to register a name for an \
+                     address and pay its fee.",
+                )
+                .add_args::<args::RegisterName<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxRenewName(pub args::RenewName<args::CliTypes>);
+
+    impl SubCmd for TxRenewName {
+        const CMD: &'static str = "renew-name";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxRenewName(args::RenewName::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to extend the registration of \
+                     a name and pay its fee.",
+                )
+                .add_args::<args::RenewName<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxTransferName(pub args::TransferName<args::CliTypes>);
+
+    impl SubCmd for TxTransferName {
+        const CMD: &'static str = "transfer-name";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxTransferName(args::TransferName::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to transfer a name to a new \
+                     owner.",
+                )
+                .add_args::<args::TransferName<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxInitAccount(pub args::TxInitAccount<args::CliTypes>);
 
@@ -1526,6 +1794,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryIbcEscrow(pub args::Query<args::CliTypes>);
+
+    impl SubCmd for QueryIbcEscrow {
+        const CMD: &'static str = "ibc-escrow";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| QueryIbcEscrow(args::Query::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Reconcile the IBC escrow balances and voucher supplies \
+                     with the amounts transferred per channel and token.",
+                )
+                .add_args::<args::Query<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryBlock(pub args::Query<args::CliTypes>);
 
@@ -1881,6 +2171,31 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct TxClaimPgfFunding(pub args::ClaimPgfFunding<args::CliTypes>);
+
+    impl SubCmd for TxClaimPgfFunding {
+        const CMD: &'static str = "claim-pgf-funding";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxClaimPgfFunding(args::ClaimPgfFunding::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Craft a transaction to claim tokens of a pgf retro \
+                     funding.",
+                )
+                .add_args::<args::ClaimPgfFunding<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxCommissionRateChange(
         pub args::CommissionRateChange<args::CliTypes>,
@@ -2031,6 +2346,50 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct GenReceipt(pub args::GenReceipt<args::CliTypes>);
+
+    impl SubCmd for GenReceipt {
+        const CMD: &'static str = "gen-receipt";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| GenReceipt(args::GenReceipt::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Generate a receipt of an applied transfer, verifiable \
+                     offline with the `utils verify-receipt` command.",
+                )
+                .add_args::<args::GenReceipt<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct GenChainRegistry(pub args::GenChainRegistry<args::CliTypes>);
+
+    impl SubCmd for GenChainRegistry {
+        const CMD: &'static str = "gen-chain-registry";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                GenChainRegistry(args::GenChainRegistry::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Generate the chain registry metadata of the chain as \
+                     JSON, from the state of the node.",
+                )
+                .add_args::<args::GenChainRegistry<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct EpochSleep(pub args::Query<args::CliTypes>);
 
@@ -2058,6 +2417,7 @@ pub mod cmds {
         JoinNetwork(JoinNetwork),
         FetchWasms(FetchWasms),
         ValidateWasm(ValidateWasm),
+        VerifyReceipt(VerifyReceipt),
         InitNetwork(InitNetwork),
         DeriveGenesisAddresses(DeriveGenesisAddresses),
         GenesisBond(GenesisBond),
@@ -2081,6 +2441,8 @@ pub mod cmds {
                 let fetch_wasms = SubCmd::parse(matches).map(Self::FetchWasms);
                 let validate_wasm =
                     SubCmd::parse(matches).map(Self::ValidateWasm);
+                let verify_receipt =
+                    SubCmd::parse(matches).map(Self::VerifyReceipt);
                 let init_network =
                     SubCmd::parse(matches).map(Self::InitNetwork);
                 let derive_addresses =
@@ -2105,6 +2467,7 @@ pub mod cmds {
                 join_network
                     .or(fetch_wasms)
                     .or(validate_wasm)
+                    .or(verify_receipt)
                     .or(init_network)
                     .or(derive_addresses)
                     .or(genesis_bond)
@@ -2125,6 +2488,7 @@ pub mod cmds {
                 .subcommand(JoinNetwork::def())
                 .subcommand(FetchWasms::def())
                 .subcommand(ValidateWasm::def())
+                .subcommand(VerifyReceipt::def())
                 .subcommand(InitNetwork::def())
                 .subcommand(DeriveGenesisAddresses::def())
                 .subcommand(GenesisBond::def())
@@ -2201,6 +2565,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct VerifyReceipt(pub args::VerifyReceipt);
+
+    impl SubCmd for VerifyReceipt {
+        const CMD: &'static str = "verify-receipt";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::VerifyReceipt::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Verify a receipt of an applied transfer offline, without \
+                     access to a node.",
+                )
+                .add_args::<args::VerifyReceipt>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct InitNetwork(pub args::InitNetwork);
 
@@ -2826,31 +3212,37 @@ pub mod args {
     use std::path::PathBuf;
     use std::str::FromStr;
 
+    use namada::account::name_service;
     use namada::ibc::core::host::types::identifiers::{ChannelId, PortId};
     use namada::tx::data::GasLimit;
     use namada::types::address::{Address, EstablishedAddress};
     use namada::types::chain::{ChainId, ChainIdPrefix};
     use namada::types::dec::Dec;
     use namada::types::ethereum_events::EthAddress;
+    use namada::types::hash::Hash;
     use namada::types::keccak::KeccakHash;
     use namada::types::key::*;
-    use namada::types::masp::PaymentAddress;
+    use namada::types::masp::{PaymentAddress, TransferTarget};
     use namada::types::storage::{self, BlockHeight, Epoch};
     use namada::types::time::DateTimeUtc;
     use namada::types::token;
     use namada::types::token::NATIVE_MAX_DECIMAL_PLACES;
     pub use namada_sdk::args::*;
     pub use namada_sdk::tx::{
-        TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM, TX_BRIDGE_POOL_WASM,
-        TX_CHANGE_COMMISSION_WASM, TX_CHANGE_CONSENSUS_KEY_WASM,
-        TX_CHANGE_METADATA_WASM, TX_CLAIM_REWARDS_WASM,
+        TX_APPROVE_WASM, TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM,
+        TX_BRIDGE_POOL_WASM, TX_CHANGE_COMMISSION_WASM,
+        TX_CHANGE_CONSENSUS_KEY_WASM, TX_CHANGE_METADATA_WASM,
+        TX_CLAIM_PGF_FUNDING_WASM, TX_CLAIM_REWARDS_WASM,
         TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM, TX_INIT_ACCOUNT_WASM,
-        TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM, TX_REDELEGATE_WASM,
-        TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_WASM, TX_UNBOND_WASM,
-        TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
-        TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
-        VP_USER_WASM,
+        TX_INIT_PROPOSAL, TX_MULTI_TRANSFER_WASM, TX_REACTIVATE_VALIDATOR_WASM,
+        TX_REDELEGATE_WASM, TX_REGISTER_NAME_WASM, TX_RENEW_NAME_WASM,
+        TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_SHIELDED_CLAIM_REWARDS_WASM,
+        TX_TRANSFER_FROM_WASM, TX_TRANSFER_NAME_WASM, TX_TRANSFER_WASM,
+        TX_UNBOND_WASM, TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
+        TX_UPDATE_POLICY_WASM, TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL,
+        TX_WITHDRAW_WASM, VP_USER_WASM,
     };
+    use namada_sdk::wallet::interop::KeyExportFormat;
 
     use super::context::*;
     use super::utils::*;
@@ -2861,6 +3253,12 @@ pub mod args {
     use crate::facade::tendermint::Timeout;
     use crate::facade::tendermint_config::net::Address as TendermintAddress;
 
+    /// The publicly known BIP39 test mnemonic from which the accounts of the
+    /// development chains are derived by default
+    pub const DEFAULT_DEV_MNEMONIC: &str = "abandon abandon abandon abandon \
+                                            abandon abandon abandon abandon \
+                                            abandon abandon abandon about";
+
     pub const ADDRESS: Arg<WalletAddress> = arg("address");
     pub const ALIAS_OPT: ArgOpt<String> = ALIAS.opt();
     pub const ALIAS: Arg<String> = arg("alias");
@@ -2868,6 +3266,7 @@ pub mod args {
     pub const ALIAS_MANY: ArgMulti<String, GlobPlus> = arg_multi("aliases");
     pub const ALLOW_DUPLICATE_IP: ArgFlag = flag("allow-duplicate-ip");
     pub const AMOUNT: Arg<token::DenominatedAmount> = arg("amount");
+    pub const AMOUNT_OPT: ArgOpt<token::DenominatedAmount> = AMOUNT.opt();
     pub const ARCHIVE_DIR: ArgOpt<PathBuf> = arg_opt("archive-dir");
     pub const AVATAR_OPT: ArgOpt<String> = arg_opt("avatar");
     pub const BALANCE_OWNER: ArgOpt<WalletBalanceOwner> = arg_opt("owner");
@@ -2924,6 +3323,21 @@ pub mod args {
     pub const DISPOSABLE_SIGNING_KEY: ArgFlag = flag("disposable-gas-payer");
     pub const DESTINATION_VALIDATOR: Arg<WalletAddress> =
         arg("destination-validator");
+    pub const DEV: ArgFlag = flag("dev");
+    pub const DEV_ACCOUNTS: ArgDefault<u64> =
+        arg_default("dev-accounts", DefaultFn(|| 10));
+    pub const DEV_MNEMONIC: ArgDefault<String> = arg_default(
+        "dev-mnemonic",
+        DefaultFn(|| DEFAULT_DEV_MNEMONIC.to_string()),
+    );
+    pub const DEV_TEMPLATES_PATH: ArgDefault<PathBuf> = arg_default(
+        "dev-templates-path",
+        DefaultFn(|| PathBuf::from("genesis/localnet")),
+    );
+    pub const DEV_WASM_CHECKSUMS_PATH: ArgDefault<PathBuf> = arg_default(
+        "dev-wasm-checksums-path",
+        DefaultFn(|| PathBuf::from("wasm/checksums.json")),
+    );
     pub const DISCORD_OPT: ArgOpt<String> = arg_opt("discord-handle");
     pub const DO_IT: ArgFlag = flag("do-it");
     pub const DONT_ARCHIVE: ArgFlag = flag("dont-archive");
@@ -2953,6 +3367,7 @@ pub mod args {
     pub const FEE_PAYER_OPT: ArgOpt<WalletPublicKey> = arg_opt("gas-payer");
     pub const FILE_PATH: Arg<String> = arg("file");
     pub const FORCE: ArgFlag = flag("force");
+    pub const FROM_HEIGHT_OPT: ArgOpt<BlockHeight> = arg_opt("from-height");
     pub const GAS_LIMIT: ArgDefault<GasLimit> =
         arg_default("gas-limit", DefaultFn(|| GasLimit::from(25_000)));
     pub const FEE_TOKEN: ArgDefaultFromCtx<WalletAddrOrNativeToken> =
@@ -2984,6 +3399,8 @@ pub mod args {
     pub const HISTORIC: ArgFlag = flag("historic");
     pub const IBC_TRANSFER_MEMO_PATH: ArgOpt<PathBuf> = arg_opt("memo-path");
     pub const INPUT_OPT: ArgOpt<PathBuf> = arg_opt("input");
+    pub const KEY_EXPORT_FORMAT: ArgDefault<KeyExportFormat> =
+        arg_default("format", DefaultFn(|| KeyExportFormat::Raw));
     pub const LEDGER_ADDRESS_ABOUT: &str =
         "Address of a ledger node as \"{scheme}://{host}:{port}\". If the \
          scheme is not supplied, it is assumed to be TCP.";
@@ -3001,8 +3418,13 @@ pub mod args {
         arg("max-commission-rate-change");
     pub const MAX_ETH_GAS: ArgOpt<u64> = arg_opt("max_eth-gas");
     pub const MEMO_OPT: ArgOpt<String> = arg_opt("memo");
+    pub const MEMO_RECIPIENT_OPT: ArgOpt<common::PublicKey> =
+        arg_opt("memo-recipient");
     pub const MODE: ArgOpt<String> = arg_opt("mode");
+    pub const NAME: Arg<String> = arg("name");
+    pub const NAME_EPOCHS: Arg<u64> = arg("epochs");
     pub const NET_ADDRESS: Arg<SocketAddr> = arg("net-address");
+    pub const NEW_OWNER: Arg<WalletAddress> = arg("new-owner");
     pub const NAMADA_START_TIME: ArgOpt<DateTimeUtc> = arg_opt("time");
     pub const NO_CONVERSIONS: ArgFlag = flag("no-conversions");
     pub const NUT: ArgFlag = flag("nut");
@@ -3019,12 +3441,16 @@ pub mod args {
         DefaultFn(|| PortId::from_str("transfer").unwrap()),
     );
     pub const PRE_GENESIS: ArgFlag = flag("pre-genesis");
+    pub const PRETTY_AMOUNTS: ArgFlag = flag("pretty-amounts");
+    pub const PREVIEW: ArgFlag = flag("preview");
     pub const PROPOSAL_ETH: ArgFlag = flag("eth");
     pub const PROPOSAL_PGF_STEWARD: ArgFlag = flag("pgf-stewards");
     pub const PROPOSAL_PGF_FUNDING: ArgFlag = flag("pgf-funding");
+    pub const PROPOSAL_SLASH_POOL: ArgFlag = flag("slash-pool");
     pub const PROPOSAL_OFFLINE: ArgFlag = flag("offline");
     pub const PROTOCOL_KEY: ArgOpt<WalletPublicKey> = arg_opt("protocol-key");
     pub const PRE_GENESIS_PATH: ArgOpt<PathBuf> = arg_opt("pre-genesis-path");
+    pub const RECEIPT_PATH: Arg<PathBuf> = arg("receipt");
     pub const PUBLIC_KEY: Arg<WalletPublicKey> = arg("public-key");
     pub const PUBLIC_KEYS: ArgMulti<WalletPublicKey, GlobStar> =
         arg_multi("public-keys");
@@ -3048,6 +3474,8 @@ pub mod args {
         RAW_PUBLIC_KEY_HASH.opt();
     pub const RECEIVER: Arg<String> = arg("receiver");
     pub const RELAYER: Arg<Address> = arg("relayer");
+    pub const RPC_ENDPOINTS: ArgMulti<String, GlobStar> =
+        arg_multi("rpc-endpoints");
     pub const SAFE_MODE: ArgFlag = flag("safe-mode");
     pub const SCHEME: ArgDefault<SchemeType> =
         arg_default("scheme", DefaultFn(|| SchemeType::Ed25519));
@@ -3055,16 +3483,22 @@ pub mod args {
         arg("self-bond-amount");
     pub const SENDER: Arg<String> = arg("sender");
     pub const SHIELDED: ArgFlag = flag("shielded");
+    pub const SHIELDED_TARGET_OPT: ArgOpt<PaymentAddress> =
+        arg_opt("shielded-target");
     pub const SIGNER: ArgOpt<WalletAddress> = arg_opt("signer");
     pub const SIGNING_KEYS: ArgMulti<WalletPublicKey, GlobStar> =
         arg_multi("signing-keys");
     pub const SIGNATURES: ArgMulti<PathBuf, GlobStar> = arg_multi("signatures");
+    pub const SOCKET_PATH: ArgOpt<PathBuf> = arg_opt("socket-path");
     pub const SOURCE: Arg<WalletAddress> = arg("source");
     pub const SOURCE_OPT: ArgOpt<WalletAddress> = SOURCE.opt();
+    pub const SPENDER: Arg<WalletAddress> = arg("spender");
+    pub const START_EPOCH_OPT: ArgOpt<Epoch> = arg_opt("start-epoch");
     pub const STEWARD: Arg<WalletAddress> = arg("steward");
     pub const SOURCE_VALIDATOR: Arg<WalletAddress> = arg("source-validator");
     pub const STORAGE_KEY: Arg<storage::Key> = arg("storage-key");
     pub const SUSPEND_ACTION: ArgFlag = flag("suspend");
+    pub const TARGET: Arg<WalletAddress> = arg("target");
     pub const TEMPLATES_PATH: Arg<PathBuf> = arg("templates-path");
     pub const TIMEOUT_HEIGHT: ArgOpt<u64> = arg_opt("timeout-height");
     pub const TIMEOUT_SEC_OFFSET: ArgOpt<u64> = arg_opt("timeout-sec-offset");
@@ -3074,6 +3508,7 @@ pub mod args {
     pub const TOKEN_STR: Arg<String> = arg("token");
     pub const TRANSFER_SOURCE: Arg<WalletTransferSource> = arg("source");
     pub const TRANSFER_TARGET: Arg<WalletTransferTarget> = arg("target");
+    pub const TRANSFERS_PATH: Arg<PathBuf> = arg("transfers-path");
     pub const TRANSPARENT: ArgFlag = flag("transparent");
     pub const TX_HASH: Arg<String> = arg("tx-hash");
     pub const THRESHOLD: ArgOpt<u8> = arg_opt("threshold");
@@ -3094,6 +3529,8 @@ pub mod args {
         arg_opt("eth-cold-key");
     pub const VALIDATOR_ETH_HOT_KEY: ArgOpt<WalletPublicKey> =
         arg_opt("eth-hot-key");
+    pub const VALIDATOR_HANDLE_OPT: ArgOpt<String> = arg_opt("handle");
+    pub const VALIDATORS_HASH: ArgOpt<Hash> = arg_opt("validators-hash");
     pub const VALUE: Arg<String> = arg("value");
     pub const VOTER_OPT: ArgOpt<WalletAddress> = arg_opt("voter");
     pub const VIEWING_KEY: Arg<WalletViewingKey> = arg("key");
@@ -3112,6 +3549,7 @@ pub mod args {
         pub chain_id: Option<ChainId>,
         pub base_dir: PathBuf,
         pub wasm_dir: Option<PathBuf>,
+        pub pretty_amounts: bool,
     }
 
     impl Global {
@@ -3121,11 +3559,13 @@ pub mod args {
             let chain_id = CHAIN_ID_OPT.parse(matches);
             let base_dir = BASE_DIR.parse(matches);
             let wasm_dir = WASM_DIR.parse(matches);
+            let pretty_amounts = PRETTY_AMOUNTS.parse(matches);
             Global {
                 is_pre_genesis,
                 chain_id,
                 base_dir,
                 wasm_dir,
+                pretty_amounts,
             }
         }
 
@@ -3154,18 +3594,42 @@ pub mod args {
                         .def()
                         .help("Dispatch pre-genesis specific logic."),
                 )
+                .arg(PRETTY_AMOUNTS.def().help(
+                    "Format the amounts with a comma between the groups of \
+                     thousands, e.g. `1,234,567.891011`.",
+                ))
         }
     }
 
     #[derive(Clone, Debug)]
     pub struct LedgerRun {
         pub start_time: Option<DateTimeUtc>,
+        /// Set to run a single-node development chain
+        pub dev: Option<LedgerDev>,
+    }
+
+    /// A single-node development chain, initialized from the genesis
+    /// templates of a local network in an ephemeral base directory
+    #[derive(Clone, Debug)]
+    pub struct LedgerDev {
+        pub templates_path: PathBuf,
+        pub wasm_checksums_path: PathBuf,
+        /// The mnemonic from which the pre-funded accounts are derived
+        pub mnemonic: String,
+        /// The number of pre-funded accounts
+        pub accounts: u64,
     }
 
     impl Args for LedgerRun {
         fn parse(matches: &ArgMatches) -> Self {
             let start_time = NAMADA_START_TIME.parse(matches);
-            Self { start_time }
+            let dev = DEV.parse(matches).then(|| LedgerDev {
+                templates_path: DEV_TEMPLATES_PATH.parse(matches),
+                wasm_checksums_path: DEV_WASM_CHECKSUMS_PATH.parse(matches),
+                mnemonic: DEV_MNEMONIC.parse(matches),
+                accounts: DEV_ACCOUNTS.parse(matches),
+            });
+            Self { start_time, dev }
         }
 
         fn def(app: App) -> App {
@@ -3177,6 +3641,52 @@ pub mod args {
                  equivalent:\n2023-01-20T12:12:12Z\n2023-01-20 \
                  12:12:12Z\n2023-  01-20T12:  12:12Z",
             ))
+            .arg(DEV.def().help(
+                "Run a single-validator development chain instead of the \
+                 configured one. The chain is initialized from the genesis \
+                 templates in a temporary base directory, which is removed \
+                 once the node is stopped, and its blocks are only produced \
+                 when txs arrive.",
+            ))
+            .arg(
+                DEV_TEMPLATES_PATH
+                    .def()
+                    .help(
+                        "Path to the genesis templates of the development \
+                         chain. They must contain the `validator-0` \
+                         pre-genesis wallet of the local network templates.",
+                    )
+                    .requires(DEV.name),
+            )
+            .arg(
+                DEV_WASM_CHECKSUMS_PATH
+                    .def()
+                    .help(
+                        "Path to the WASM checksums file of the development \
+                         chain.",
+                    )
+                    .requires(DEV.name),
+            )
+            .arg(
+                DEV_MNEMONIC
+                    .def()
+                    .help(
+                        "The BIP39 mnemonic from which the pre-funded \
+                         accounts of the development chain are derived. \
+                         Defaults to a publicly known mnemonic, so these \
+                         accounts must never hold real funds.",
+                    )
+                    .requires(DEV.name),
+            )
+            .arg(
+                DEV_ACCOUNTS
+                    .def()
+                    .help(
+                        "The number of pre-funded accounts of the development \
+                         chain.",
+                    )
+                    .requires(DEV.name),
+            )
         }
     }
 
@@ -3892,14 +4402,22 @@ pub mod args {
 
     impl CliToSdk<TxTransfer<SdkTypes>> for TxTransfer<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> TxTransfer<SdkTypes> {
-            let tx = self.tx.to_sdk(ctx);
+            let mut tx = self.tx.to_sdk(ctx);
             let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            let target = chain_ctx.get(&self.target);
+            // The memo of a transfer to a payment address is rather encrypted
+            // to its receiver in the shielded output
+            let shielded_memo = match target {
+                TransferTarget::PaymentAddress(_) => tx.memo.take(),
+                TransferTarget::Address(_) => self.shielded_memo,
+            };
             TxTransfer::<SdkTypes> {
                 tx,
                 source: chain_ctx.get_cached(&self.source),
-                target: chain_ctx.get(&self.target),
+                target,
                 token: chain_ctx.get(&self.token),
                 amount: self.amount,
+                shielded_memo,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
         }
@@ -3919,6 +4437,7 @@ pub mod args {
                 target,
                 token,
                 amount,
+                shielded_memo: None,
                 tx_code_path,
             }
         }
@@ -3938,13 +4457,182 @@ pub mod args {
         }
     }
 
-    impl CliToSdk<TxIbcTransfer<SdkTypes>> for TxIbcTransfer<CliTypes> {
-        fn to_sdk(self, ctx: &mut Context) -> TxIbcTransfer<SdkTypes> {
+    impl CliToSdk<TxApprove<SdkTypes>> for TxApprove<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxApprove<SdkTypes> {
             let tx = self.tx.to_sdk(ctx);
             let chain_ctx = ctx.borrow_mut_chain_or_exit();
-            TxIbcTransfer::<SdkTypes> {
+            TxApprove::<SdkTypes> {
                 tx,
-                source: chain_ctx.get_cached(&self.source),
+                owner: chain_ctx.get(&self.owner),
+                spender: chain_ctx.get(&self.spender),
+                token: chain_ctx.get(&self.token),
+                amount: self.amount,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for TxApprove<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let owner = OWNER.parse(matches);
+            let spender = SPENDER.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
+            let tx_code_path = PathBuf::from(TX_APPROVE_WASM);
+            Self {
+                tx,
+                owner,
+                spender,
+                token,
+                amount,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(OWNER.def().help(
+                    "The owner of the tokens. The owner's key is used to \
+                     produce the signature.",
+                ))
+                .arg(SPENDER.def().help(
+                    "The address allowed to transfer the owner's tokens.",
+                ))
+                .arg(TOKEN.def().help("The approved token."))
+                .arg(AMOUNT.def().help(
+                    "The allowance in decimal, replacing the previous one. A \
+                     zero allowance revokes the approval.",
+                ))
+        }
+    }
+
+    impl CliToSdk<TxTransferFrom<SdkTypes>> for TxTransferFrom<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxTransferFrom<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            TxTransferFrom::<SdkTypes> {
+                tx,
+                owner: chain_ctx.get(&self.owner),
+                spender: chain_ctx.get(&self.spender),
+                target: chain_ctx.get(&self.target),
+                token: chain_ctx.get(&self.token),
+                amount: self.amount,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for TxTransferFrom<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let owner = OWNER.parse(matches);
+            let spender = SPENDER.parse(matches);
+            let target = TARGET.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
+            let tx_code_path = PathBuf::from(TX_TRANSFER_FROM_WASM);
+            Self {
+                tx,
+                owner,
+                spender,
+                target,
+                token,
+                amount,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(OWNER.def().help("The owner of the tokens."))
+                .arg(SPENDER.def().help(
+                    "The approved spender. The spender's key is used to \
+                     produce the signature.",
+                ))
+                .arg(TARGET.def().help("The target account address."))
+                .arg(TOKEN.def().help("The transfer token."))
+                .arg(AMOUNT.def().help("The amount to transfer in decimal."))
+        }
+    }
+
+    impl CliToSdk<TxMultiTransfer<SdkTypes>> for TxMultiTransfer<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxMultiTransfer<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            let legs = self
+                .legs
+                .into_iter()
+                .map(|leg| TxTransferLeg::<SdkTypes> {
+                    source: chain_ctx.get(&leg.source),
+                    target: chain_ctx.get(&leg.target),
+                    token: chain_ctx.get(&leg.token),
+                    amount: leg.amount,
+                })
+                .collect();
+            TxMultiTransfer::<SdkTypes> {
+                tx,
+                legs,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for TxMultiTransfer<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            /// A leg as written in the transfers file, with addresses or
+            /// their aliases
+            #[derive(serde::Deserialize)]
+            struct TransferLeg {
+                source: String,
+                target: String,
+                token: String,
+                amount: token::DenominatedAmount,
+            }
+
+            let tx = Tx::parse(matches);
+            let transfers_path = TRANSFERS_PATH.parse(matches);
+            let file = std::io::BufReader::new(
+                std::fs::File::open(transfers_path)
+                    .expect("Failed to open the provided transfers file"),
+            );
+            let legs: Vec<TransferLeg> = serde_json::from_reader(file)
+                .expect("Failed to parse the transfers file");
+            let legs = legs
+                .into_iter()
+                .map(|leg| TxTransferLeg {
+                    source: FromContext::new(leg.source),
+                    target: FromContext::new(leg.target),
+                    token: FromContext::new(leg.token),
+                    amount: InputAmount::Unvalidated(leg.amount),
+                })
+                .collect();
+            let tx_code_path = PathBuf::from(TX_MULTI_TRANSFER_WASM);
+            Self {
+                tx,
+                legs,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(TRANSFERS_PATH.def().help(
+                    "The path to a JSON file with a list of the transfers, \
+                     each with its `source`, `target`, `token` and `amount` \
+                     in decimal. All the transfers must have the same source, \
+                     whose key is used to produce the signature.",
+                ))
+        }
+    }
+
+    impl CliToSdk<TxIbcTransfer<SdkTypes>> for TxIbcTransfer<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxIbcTransfer<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            TxIbcTransfer::<SdkTypes> {
+                tx,
+                source: chain_ctx.get_cached(&self.source),
                 receiver: self.receiver,
                 token: chain_ctx.get(&self.token),
                 amount: self.amount,
@@ -4340,6 +5028,179 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<TxUpdatePolicy<SdkTypes>> for TxUpdatePolicy<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxUpdatePolicy<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            TxUpdatePolicy::<SdkTypes> {
+                tx,
+                addr: chain_ctx.get(&self.addr),
+                policy_data: self.policy_data.map(|path| {
+                    std::fs::read(path)
+                        .expect("Should be able to read the file.")
+                }),
+                tx_code_path: self.tx_code_path,
+            }
+        }
+    }
+
+    impl Args for TxUpdatePolicy<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let addr = ADDRESS.parse(matches);
+            let policy_data = DATA_PATH_OPT.parse(matches);
+            let tx_code_path = PathBuf::from(TX_UPDATE_POLICY_WASM);
+            Self {
+                tx,
+                addr,
+                policy_data,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(ADDRESS.def().help(
+                    "The account's address. It's key is used to produce the \
+                     signature.",
+                ))
+                .arg(DATA_PATH_OPT.def().help(
+                    "The path to a JSON file with the new spending policy. \
+                     The current policy is removed if not given.",
+                ))
+        }
+    }
+
+    /// Strip the suffix of a name given to the CLI, e.g. `alice.nam`
+    fn strip_name_suffix(name: String) -> String {
+        match name.strip_suffix(name_service::NAME_SUFFIX) {
+            Some(name) => name.to_string(),
+            None => name,
+        }
+    }
+
+    impl CliToSdk<RegisterName<SdkTypes>> for RegisterName<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> RegisterName<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            RegisterName::<SdkTypes> {
+                tx,
+                name: strip_name_suffix(self.name),
+                owner: chain_ctx.get(&self.owner),
+                epochs: self.epochs,
+                tx_code_path: self.tx_code_path,
+            }
+        }
+    }
+
+    impl Args for RegisterName<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let name = NAME.parse(matches);
+            let owner = OWNER.parse(matches);
+            let epochs = NAME_EPOCHS.parse(matches);
+            let tx_code_path = PathBuf::from(TX_REGISTER_NAME_WASM);
+            Self {
+                tx,
+                name,
+                owner,
+                epochs,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(NAME.def().help(
+                    "The name to register, with or without its \".nam\" \
+                     suffix.",
+                ))
+                .arg(OWNER.def().help(
+                    "The address that the name resolves to. It pays the fee \
+                     and its key is used to produce the signature.",
+                ))
+                .arg(NAME_EPOCHS.def().help(
+                    "The number of epochs for which to register the name.",
+                ))
+        }
+    }
+
+    impl CliToSdk<RenewName<SdkTypes>> for RenewName<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> RenewName<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            RenewName::<SdkTypes> {
+                tx,
+                name: strip_name_suffix(self.name),
+                epochs: self.epochs,
+                tx_code_path: self.tx_code_path,
+            }
+        }
+    }
+
+    impl Args for RenewName<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let name = NAME.parse(matches);
+            let epochs = NAME_EPOCHS.parse(matches);
+            let tx_code_path = PathBuf::from(TX_RENEW_NAME_WASM);
+            Self {
+                tx,
+                name,
+                epochs,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(NAME.def().help(
+                    "The name to renew, with or without its \".nam\" suffix. \
+                     Its owner pays the fee.",
+                ))
+                .arg(NAME_EPOCHS.def().help(
+                    "The number of epochs by which to extend the registration.",
+                ))
+        }
+    }
+
+    impl CliToSdk<TransferName<SdkTypes>> for TransferName<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TransferName<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            TransferName::<SdkTypes> {
+                tx,
+                name: strip_name_suffix(self.name),
+                new_owner: chain_ctx.get(&self.new_owner),
+                tx_code_path: self.tx_code_path,
+            }
+        }
+    }
+
+    impl Args for TransferName<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let name = NAME.parse(matches);
+            let new_owner = NEW_OWNER.parse(matches);
+            let tx_code_path = PathBuf::from(TX_TRANSFER_NAME_WASM);
+            Self {
+                tx,
+                name,
+                new_owner,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(NAME.def().help(
+                    "The name to transfer, with or without its \".nam\" \
+                     suffix. Its current owner's key is used to produce the \
+                     signature.",
+                ))
+                .arg(NEW_OWNER.def().help("The new owner of the name."))
+        }
+    }
+
     impl CliToSdk<TxUpdateAccount<SdkTypes>> for TxUpdateAccount<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> TxUpdateAccount<SdkTypes> {
             let tx = self.tx.to_sdk(ctx);
@@ -4409,6 +5270,7 @@ pub mod args {
                 validator: chain_ctx.get(&self.validator),
                 amount: self.amount,
                 source: self.source.map(|x| chain_ctx.get(&x)),
+                start_epoch: self.start_epoch,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
         }
@@ -4418,22 +5280,16 @@ pub mod args {
         fn parse(matches: &ArgMatches) -> Self {
             let tx = Tx::parse(matches);
             let validator = VALIDATOR.parse(matches);
-            let amount = AMOUNT.parse(matches);
-            let amount = amount
-                .canonical()
-                .increase_precision(NATIVE_MAX_DECIMAL_PLACES.into())
-                .unwrap_or_else(|e| {
-                    println!("Could not parse bond amount: {:?}", e);
-                    safe_exit(1);
-                })
-                .amount();
+            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
             let source = SOURCE_OPT.parse(matches);
+            let start_epoch = START_EPOCH_OPT.parse(matches);
             let tx_code_path = PathBuf::from(TX_BOND_WASM);
             Self {
                 tx,
                 validator,
                 amount,
                 source,
+                start_epoch,
                 tx_code_path,
             }
         }
@@ -4446,6 +5302,11 @@ pub mod args {
                     "Source address for delegations. For self-bonds, the \
                      validator is also the source.",
                 ))
+                .arg(START_EPOCH_OPT.def().help(
+                    "An optional future epoch in which the bond should be \
+                     applied. It must be after the current epoch and within \
+                     the maximum scheduling offset of the PoS parameters.",
+                ))
         }
     }
 
@@ -4458,6 +5319,7 @@ pub mod args {
                 validator: chain_ctx.get(&self.validator),
                 amount: self.amount,
                 source: self.source.map(|x| chain_ctx.get(&x)),
+                start_epoch: self.start_epoch,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
         }
@@ -4467,22 +5329,16 @@ pub mod args {
         fn parse(matches: &ArgMatches) -> Self {
             let tx = Tx::parse(matches);
             let validator = VALIDATOR.parse(matches);
-            let amount = AMOUNT.parse(matches);
-            let amount = amount
-                .canonical()
-                .increase_precision(NATIVE_MAX_DECIMAL_PLACES.into())
-                .unwrap_or_else(|e| {
-                    println!("Could not parse bond amount: {:?}", e);
-                    safe_exit(1);
-                })
-                .amount();
+            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
             let source = SOURCE_OPT.parse(matches);
+            let start_epoch = START_EPOCH_OPT.parse(matches);
             let tx_code_path = PathBuf::from(TX_UNBOND_WASM);
             Self {
                 tx,
                 validator,
                 amount,
                 source,
+                start_epoch,
                 tx_code_path,
             }
         }
@@ -4500,6 +5356,11 @@ pub mod args {
                      unbonding from self-bonds, the validator is also the \
                      source.",
                 ))
+                .arg(START_EPOCH_OPT.def().help(
+                    "An optional future epoch in which the unbond should be \
+                     applied. It must be after the current epoch and within \
+                     the maximum scheduling offset of the PoS parameters.",
+                ))
         }
     }
 
@@ -4572,6 +5433,42 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<ClaimPgfFunding<SdkTypes>> for ClaimPgfFunding<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> ClaimPgfFunding<SdkTypes> {
+            ClaimPgfFunding::<SdkTypes> {
+                tx: self.tx.to_sdk(ctx),
+                target: ctx.borrow_chain_or_exit().get(&self.target),
+                amount: self.amount,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for ClaimPgfFunding<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let target = TARGET.parse(matches);
+            let amount =
+                AMOUNT_OPT.parse(matches).map(InputAmount::Unvalidated);
+            let tx_code_path = PathBuf::from(TX_CLAIM_PGF_FUNDING_WASM);
+            Self {
+                tx,
+                target,
+                amount,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(TARGET.def().help("The target address of the funding."))
+                .arg(AMOUNT_OPT.def().help(
+                    "The amount of native tokens to claim. Defaults to all \
+                     the tokens that can be claimed in the current epoch.",
+                ))
+        }
+    }
+
     impl CliToSdk<Redelegate<SdkTypes>> for Redelegate<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> Redelegate<SdkTypes> {
             let tx = self.tx.to_sdk(ctx);
@@ -4593,15 +5490,7 @@ pub mod args {
             let src_validator = SOURCE_VALIDATOR.parse(matches);
             let dest_validator = DESTINATION_VALIDATOR.parse(matches);
             let owner = OWNER.parse(matches);
-            let amount = AMOUNT.parse(matches);
-            let amount = amount
-                .canonical()
-                .increase_precision(NATIVE_MAX_DECIMAL_PLACES.into())
-                .unwrap_or_else(|e| {
-                    println!("Could not parse bond amount: {:?}", e);
-                    safe_exit(1);
-                })
-                .amount();
+            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
             let tx_code_path = PathBuf::from(TX_REDELEGATE_WASM);
             Self {
                 tx,
@@ -4639,6 +5528,7 @@ pub mod args {
                 is_offline: self.is_offline,
                 is_pgf_stewards: self.is_pgf_stewards,
                 is_pgf_funding: self.is_pgf_funding,
+                is_slash_pool: self.is_slash_pool,
                 tx_code_path: self.tx_code_path,
             }
         }
@@ -4651,6 +5541,7 @@ pub mod args {
             let is_offline = PROPOSAL_OFFLINE.parse(matches);
             let is_pgf_stewards = PROPOSAL_PGF_STEWARD.parse(matches);
             let is_pgf_funding = PROPOSAL_PGF_FUNDING.parse(matches);
+            let is_slash_pool = PROPOSAL_SLASH_POOL.parse(matches);
             let tx_code_path = PathBuf::from(TX_INIT_PROPOSAL);
 
             Self {
@@ -4660,6 +5551,7 @@ pub mod args {
                 is_offline,
                 is_pgf_stewards,
                 is_pgf_funding,
+                is_slash_pool,
             }
         }
 
@@ -4678,6 +5570,7 @@ pub mod args {
                         .conflicts_with_all([
                             PROPOSAL_PGF_FUNDING.name,
                             PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_SLASH_POOL.name,
                             PROPOSAL_ETH.name,
                         ]),
                 )
@@ -4688,6 +5581,7 @@ pub mod args {
                         .conflicts_with_all([
                             PROPOSAL_PGF_FUNDING.name,
                             PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_SLASH_POOL.name,
                         ]),
                 )
                 .arg(
@@ -4700,6 +5594,7 @@ pub mod args {
                         .conflicts_with_all([
                             PROPOSAL_ETH.name,
                             PROPOSAL_PGF_FUNDING.name,
+                            PROPOSAL_SLASH_POOL.name,
                         ]),
                 )
                 .arg(
@@ -4712,6 +5607,20 @@ pub mod args {
                         .conflicts_with_all([
                             PROPOSAL_ETH.name,
                             PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_SLASH_POOL.name,
+                        ]),
+                )
+                .arg(
+                    PROPOSAL_SLASH_POOL
+                        .def()
+                        .help(
+                            "Flag if the proposal is of type slash-pool. Used \
+                             to disburse or burn slashed tokens.",
+                        )
+                        .conflicts_with_all([
+                            PROPOSAL_ETH.name,
+                            PROPOSAL_PGF_FUNDING.name,
+                            PROPOSAL_PGF_STEWARD.name,
                         ]),
                 )
         }
@@ -5032,6 +5941,7 @@ pub mod args {
                 tx,
                 validator: chain_ctx.get(&self.validator),
                 source: self.source.map(|x| chain_ctx.get(&x)),
+                shielded_target: self.shielded_target,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
         }
@@ -5042,11 +5952,17 @@ pub mod args {
             let tx = Tx::parse(matches);
             let validator = VALIDATOR.parse(matches);
             let source = SOURCE_OPT.parse(matches);
-            let tx_code_path = PathBuf::from(TX_CLAIM_REWARDS_WASM);
+            let shielded_target = SHIELDED_TARGET_OPT.parse(matches);
+            let tx_code_path = if shielded_target.is_some() {
+                PathBuf::from(TX_SHIELDED_CLAIM_REWARDS_WASM)
+            } else {
+                PathBuf::from(TX_CLAIM_REWARDS_WASM)
+            };
             Self {
                 tx,
                 validator,
                 source,
+                shielded_target,
                 tx_code_path,
             }
         }
@@ -5058,6 +5974,12 @@ pub mod args {
                     "Source address for claiming rewards for a bond. For \
                      self-bonds, the validator is also the source.",
                 ))
+                .arg(SHIELDED_TARGET_OPT.def().help(
+                    "A payment address to deliver the rewards to in the \
+                     shielded pool, instead of the source. All the claimable \
+                     rewards are shielded, so the tx must be applied in the \
+                     epoch in which it's built.",
+                ))
         }
     }
 
@@ -5133,6 +6055,7 @@ pub mod args {
                 owner: self.owner.map(|x| chain_ctx.get_cached(&x)),
                 token: self.token.map(|x| chain_ctx.get(&x)),
                 no_conversions: self.no_conversions,
+                from_height: self.from_height,
             }
         }
     }
@@ -5143,11 +6066,13 @@ pub mod args {
             let owner = BALANCE_OWNER.parse(matches);
             let token = TOKEN_OPT.parse(matches);
             let no_conversions = NO_CONVERSIONS.parse(matches);
+            let from_height = FROM_HEIGHT_OPT.parse(matches);
             Self {
                 query,
                 owner,
                 token,
                 no_conversions,
+                from_height,
             }
         }
 
@@ -5168,6 +6093,11 @@ pub mod args {
                         "Whether not to automatically perform conversions.",
                     ),
                 )
+                .arg(FROM_HEIGHT_OPT.def().help(
+                    "The block height from which to search the notes of the \
+                     viewing keys that were never synced, e.g. the height at \
+                     which they were created. Defaults to the first block.",
+                ))
         }
     }
 
@@ -5413,6 +6343,7 @@ pub mod args {
                 website: self.website,
                 discord_handle: self.discord_handle,
                 avatar: self.avatar,
+                handle: self.handle,
                 commission_rate: self.commission_rate,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
@@ -5428,6 +6359,7 @@ pub mod args {
             let website = WEBSITE_OPT.parse(matches);
             let discord_handle = DISCORD_OPT.parse(matches);
             let avatar = AVATAR_OPT.parse(matches);
+            let handle = VALIDATOR_HANDLE_OPT.parse(matches);
             let commission_rate = COMMISSION_RATE_OPT.parse(matches);
             let tx_code_path = PathBuf::from(TX_CHANGE_METADATA_WASM);
             Self {
@@ -5438,6 +6370,7 @@ pub mod args {
                 website,
                 discord_handle,
                 avatar,
+                handle,
                 commission_rate,
                 tx_code_path,
             }
@@ -5470,6 +6403,12 @@ pub mod args {
                     "The desired new validator avatar url. To remove the \
                      existing avatar, pass an empty string to this argument.",
                 ))
+                .arg(VALIDATOR_HANDLE_OPT.def().help(
+                    "The desired new validator handle, unique among the \
+                     validators, by which the client commands can refer to \
+                     the validator as `@<handle>`. To remove the existing \
+                     handle, pass an empty string to this argument.",
+                ))
                 .arg(
                     COMMISSION_RATE_OPT
                         .def()
@@ -5608,6 +6547,77 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<GenReceipt<SdkTypes>> for GenReceipt<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> GenReceipt<SdkTypes> {
+            GenReceipt::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                tx_hash: self.tx_hash,
+                output: self.output,
+            }
+        }
+    }
+
+    impl Args for GenReceipt<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let tx_hash = TX_HASH.parse(matches);
+            let output = OUTPUT.parse(matches);
+            Self {
+                query,
+                tx_hash,
+                output,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>()
+                .arg(
+                    TX_HASH
+                        .def()
+                        .help("The hash of the applied transfer transaction."),
+                )
+                .arg(OUTPUT.def().help(
+                    "The path of the file to write the receipt to. Defaults \
+                     to `receipt-{tx-hash}.json` in the current directory.",
+                ))
+        }
+    }
+
+    impl CliToSdk<GenChainRegistry<SdkTypes>> for GenChainRegistry<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> GenChainRegistry<SdkTypes> {
+            GenChainRegistry::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                rpc_endpoints: self.rpc_endpoints,
+                output: self.output,
+            }
+        }
+    }
+
+    impl Args for GenChainRegistry<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let rpc_endpoints = RPC_ENDPOINTS.parse(matches);
+            let output = OUTPUT.parse(matches);
+            Self {
+                query,
+                rpc_endpoints,
+                output,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>()
+                .arg(RPC_ENDPOINTS.def().help(
+                    "The comma-separated public RPC endpoints of the chain. \
+                     Defaults to the address of the queried node.",
+                ))
+                .arg(OUTPUT.def().help(
+                    "The path of the file to write the metadata to. When not \
+                     supplied, the metadata will be printed to stdout instead.",
+                ))
+        }
+    }
+
     impl CliToSdk<GenIbcShieldedTransafer<SdkTypes>>
         for GenIbcShieldedTransafer<CliTypes>
     {
@@ -5905,6 +6915,7 @@ pub mod args {
                 dry_run: self.dry_run,
                 dry_run_wrapper: self.dry_run_wrapper,
                 dump_tx: self.dump_tx,
+                preview: self.preview,
                 output_folder: self.output_folder,
                 force: self.force,
                 broadcast_only: self.broadcast_only,
@@ -5959,6 +6970,11 @@ pub mod args {
                     .conflicts_with(DRY_RUN_TX.name),
             )
             .arg(DUMP_TX.def().help("Dump transaction bytes to a file."))
+            .arg(PREVIEW.def().help(
+                "Simulate the transaction and show the balance and storage \
+                 changes it would make, asking for confirmation before \
+                 signing it.",
+            ))
             .arg(FORCE.def().help(
                 "Submit the transaction even if it doesn't pass client checks.",
             ))
@@ -6050,10 +7066,20 @@ pub mod args {
                 "Use an attached hardware wallet device to sign the \
                  transaction.",
             ))
+            .arg(MEMO_OPT.def().help(
+                "Attach a plaintext memo to the transaction. The memo of a \
+                 transfer to a payment address is rather encrypted to its \
+                 receiver in the shielded output, and can't be longer than \
+                 512 bytes.",
+            ))
             .arg(
-                MEMO_OPT
+                MEMO_RECIPIENT_OPT
                     .def()
-                    .help("Attach a plaintext memo to the transaction."),
+                    .help(
+                        "Encrypt the memo to the given ed25519 public key, \
+                         such that only its holder can read it.",
+                    )
+                    .requires(MEMO_OPT.name),
             )
         }
 
@@ -6061,6 +7087,7 @@ pub mod args {
             let dry_run = DRY_RUN_TX.parse(matches);
             let dry_run_wrapper = DRY_RUN_WRAPPER_TX.parse(matches);
             let dump_tx = DUMP_TX.parse(matches);
+            let preview = PREVIEW.parse(matches);
             let force = FORCE.parse(matches);
             let broadcast_only = BROADCAST_ONLY.parse(matches);
             let ledger_address = CONFIG_RPC_LEDGER_ADDRESS.parse(matches);
@@ -6079,7 +7106,18 @@ pub mod args {
             let tx_reveal_code_path = PathBuf::from(TX_REVEAL_PK);
             let chain_id = CHAIN_ID_OPT.parse(matches);
             let password = None;
-            let memo = MEMO_OPT.parse(matches).map(String::into_bytes);
+            let memo = MEMO_OPT.parse(matches).map(|memo| {
+                match MEMO_RECIPIENT_OPT.parse(matches) {
+                    Some(recipient) => {
+                        encrypt_memo(&recipient, memo.as_bytes())
+                            .unwrap_or_else(|err| {
+                                eprintln!("Failed to encrypt the memo: {err}");
+                                safe_exit(1)
+                            })
+                    }
+                    None => memo.into_bytes(),
+                }
+            });
             let wrapper_fee_payer = FEE_PAYER_OPT.parse(matches);
             let output_folder = OUTPUT_FOLDER_PATH.parse(matches);
             let use_device = USE_DEVICE.parse(matches);
@@ -6087,6 +7125,7 @@ pub mod args {
                 dry_run,
                 dry_run_wrapper,
                 dump_tx,
+                preview,
                 force,
                 broadcast_only,
                 ledger_address,
@@ -6569,16 +7608,43 @@ pub mod args {
         }
     }
 
+    impl Args for WalletDaemon {
+        fn parse(matches: &ArgMatches) -> Self {
+            let socket_path = SOCKET_PATH.parse(matches);
+            Self { socket_path }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(SOCKET_PATH.def().help(
+                "The path of the unix socket to listen on. Defaults to \
+                 `wallet.sock` in the base directory of the chain.",
+            ))
+        }
+    }
+
     impl Args for KeyExport {
         fn parse(matches: &ArgMatches) -> Self {
             let alias = ALIAS.parse(matches);
-            Self { alias }
+            let format = KEY_EXPORT_FORMAT.parse(matches);
+            Self { alias, format }
         }
 
         fn def(app: App) -> App {
             app.arg(
                 ALIAS.def().help("The alias of the key you wish to export."),
             )
+            .arg(KEY_EXPORT_FORMAT.def().help(
+                "The format of the exported key. One of \"raw\" for the \
+                 wallet's binary encoding, \"unified\" for a unified spending \
+                 key string, \"unified-viewing\" for a unified viewing key \
+                 string, \"zip316-viewing\" or \"zip316-incoming-viewing\" \
+                 for a Zcash ZIP 316 unified full or incoming viewing key \
+                 string or \"keystore\" for a password encrypted JSON \
+                 keystore. The unified formats bundle the transparent and \
+                 shielded keys with the given alias, while the ZIP 316 \
+                 formats only hold its shielded viewing key. Defaults to \
+                 \"raw\".",
+            ))
         }
     }
 
@@ -6598,7 +7664,9 @@ pub mod args {
 
         fn def(app: App) -> App {
             app.arg(FILE_PATH.def().help(
-                "Path to the file containing the key you wish to import.",
+                "Path to the file containing the key you wish to import. The \
+                 key may be in any of the formats supported by the export \
+                 command, except for the ZIP 316 incoming viewing keys.",
             ))
             .arg(ALIAS.def().help("The alias assigned to the."))
             .arg(
@@ -6721,6 +7789,36 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct VerifyReceipt {
+        pub receipt_path: PathBuf,
+        pub validators_hash: Option<Hash>,
+    }
+
+    impl Args for VerifyReceipt {
+        fn parse(matches: &ArgMatches) -> Self {
+            let receipt_path = RECEIPT_PATH.parse(matches);
+            let validators_hash = VALIDATORS_HASH.parse(matches);
+            Self {
+                receipt_path,
+                validators_hash,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                RECEIPT_PATH
+                    .def()
+                    .help("The path to the receipt file to verify."),
+            )
+            .arg(VALIDATORS_HASH.def().help(
+                "The hash of the trusted validator set of the block of the \
+                 transfer. When not supplied, the receipt only proves that \
+                 the transfer was signed by the validators it contains.",
+            ))
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct InitNetwork {
         pub templates_path: PathBuf,
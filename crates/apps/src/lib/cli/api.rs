@@ -2,7 +2,7 @@ use namada::tendermint_rpc::HttpClient;
 use namada::types::io::Io;
 use namada_sdk::error::Error;
 use namada_sdk::queries::Client;
-use namada_sdk::rpc::wait_until_node_is_synched;
+use namada_sdk::rpc::{query_capabilities, wait_until_node_is_synched};
 use tendermint_config::net::Address as TendermintAddress;
 
 /// Trait for clients that can be used with the CLI.
@@ -25,7 +25,9 @@ impl CliClient for HttpClient {
         &self,
         io: &impl Io,
     ) -> Result<(), Error> {
-        wait_until_node_is_synched(self, io).await
+        wait_until_node_is_synched(self, io).await?;
+        // Refuse the nodes with an incompatible protocol of the queries
+        query_capabilities(self).await.map(|_capabilities| ())
     }
 }
 
@@ -86,6 +86,90 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_update_account(&namada, args).await?;
                     }
+                    Sub::TxUpdatePolicy(TxUpdatePolicy(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_update_policy(&namada, args).await?;
+                    }
+                    Sub::TxApprove(TxApprove(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_approve(&namada, args).await?;
+                    }
+                    Sub::TxTransferFrom(TxTransferFrom(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_transfer_from(&namada, args).await?;
+                    }
+                    Sub::TxMultiTransfer(TxMultiTransfer(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_multi_transfer(&namada, args).await?;
+                    }
+                    Sub::TxRegisterName(TxRegisterName(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_register_name(&namada, args).await?;
+                    }
+                    Sub::TxRenewName(TxRenewName(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_renew_name(&namada, args).await?;
+                    }
+                    Sub::TxTransferName(TxTransferName(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_transfer_name(&namada, args).await?;
+                    }
                     Sub::TxInitAccount(TxInitAccount(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
                         let ledger_address =
@@ -392,6 +476,18 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_resign_steward(&namada, args).await?;
                     }
+                    Sub::TxClaimPgfFunding(TxClaimPgfFunding(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.tx.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_claim_pgf_funding(&namada, args).await?;
+                    }
                     // Ledger queries
                     Sub::QueryEpoch(QueryEpoch(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
@@ -452,6 +548,17 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_masp_reward_tokens(&namada).await;
                     }
+                    Sub::QueryIbcEscrow(QueryIbcEscrow(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_ibc_escrow_reconciliation(&namada).await;
+                    }
                     Sub::QueryBlock(QueryBlock(args)) => {
                         let chain_ctx = ctx.borrow_mut_chain_or_exit();
                         let ledger_address =
@@ -698,6 +805,30 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         tx::gen_ibc_shielded_transfer(&namada, args).await?;
                     }
+                    Sub::GenReceipt(GenReceipt(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.query.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::gen_receipt(&namada, args).await?;
+                    }
+                    Sub::GenChainRegistry(GenChainRegistry(args)) => {
+                        let chain_ctx = ctx.borrow_mut_chain_or_exit();
+                        let ledger_address =
+                            chain_ctx.get(&args.query.ledger_address);
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(&ledger_address)
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::gen_chain_registry(&namada, args).await?;
+                    }
                 }
             }
             cli::NamadaClient::WithoutContext(cmd, global_args) => match cmd {
@@ -711,6 +842,9 @@ impl CliApi {
                 Utils::ValidateWasm(ValidateWasm(args)) => {
                     utils::validate_wasm(args)
                 }
+                Utils::VerifyReceipt(VerifyReceipt(args)) => {
+                    utils::verify_receipt(args)
+                }
                 Utils::InitNetwork(InitNetwork(args)) => {
                     utils::init_network(global_args, args)
                 }
@@ -6,11 +6,14 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use color_eyre::eyre::Result;
+use namada::account::name_service;
 use namada::ledger::ibc::storage::ibc_token;
-use namada::types::address::{Address, InternalAddress};
+use namada::tendermint_rpc::HttpClient;
+use namada::types::address::{
+    Address, AnyAddress, AnyAddressError, InternalAddress,
+};
 use namada::types::chain::ChainId;
 use namada::types::ethereum_events::EthAddress;
-use namada::types::ibc::is_ibc_denom;
 use namada::types::io::Io;
 use namada::types::key::*;
 use namada::types::masp::*;
@@ -213,14 +216,20 @@ impl Context {
         C: namada::ledger::queries::Client + Sync,
         IO: Io,
     {
+        let pretty_amounts = self.global_args.pretty_amounts;
         let chain_ctx = self.take_chain_or_exit();
-        NamadaImpl::native_new(
+        let namada = NamadaImpl::native_new(
             client,
             chain_ctx.wallet,
             chain_ctx.shielded,
             io,
             chain_ctx.native_token,
-        )
+        );
+        if pretty_amounts {
+            namada.with_amount_separator(',')
+        } else {
+            namada
+        }
     }
 }
 
@@ -412,34 +421,39 @@ impl ArgFromContext for Address {
     ) -> Result<Self, String> {
         struct Skip;
         let raw = raw.as_ref();
-        // An address can be either raw (bech32m encoding)
-        FromStr::from_str(raw)
-            // An Ethereum address
-            .or_else(|_| {
-                (raw.len() == 42 && raw.starts_with("0x"))
-                    .then(|| {
-                        raw.parse::<EthAddress>()
-                            .map(|addr| {
-                                Address::Internal(InternalAddress::Erc20(addr))
-                            })
-                            .map_err(|_| Skip)
-                    })
-                    .unwrap_or(Err(Skip))
-            })
-            // An IBC token
-            .or_else(|_| {
-                is_ibc_denom(raw)
-                    .map(|(trace_path, base_denom)| {
-                        let base_token = ctx
-                            .wallet
-                            .find_address(&base_denom)
-                            .map(|addr| addr.to_string())
-                            .unwrap_or(base_denom);
-                        let ibc_denom = format!("{trace_path}/{base_token}");
-                        ibc_token(ibc_denom)
-                    })
-                    .ok_or(Skip)
+        // An address can be either raw (bech32m encoding) or an IBC token
+        match AnyAddress::from_str(raw) {
+            Ok(AnyAddress::Transparent(address)) => return Ok(address),
+            Ok(AnyAddress::IbcDenom {
+                trace_path,
+                base_denom,
+            }) => {
+                let base_token = ctx
+                    .wallet
+                    .find_address(&base_denom)
+                    .map(|addr| addr.to_string())
+                    .unwrap_or(base_denom);
+                return Ok(ibc_token(format!("{trace_path}/{base_token}")));
+            }
+            Ok(AnyAddress::Payment(_)) => {
+                return Err(format!(
+                    "Expected a transparent address, but got the payment \
+                     address {raw}"
+                ));
+            }
+            Err(err @ AnyAddressError::Transparent(..)) => {
+                return Err(err.to_string());
+            }
+            Err(_) => {}
+        }
+        // An Ethereum address
+        (raw.len() == 42 && raw.starts_with("0x"))
+            .then(|| {
+                raw.parse::<EthAddress>()
+                    .map(|addr| Address::Internal(InternalAddress::Erc20(addr)))
+                    .map_err(|_| Skip)
             })
+            .unwrap_or(Err(Skip))
             // Or it can be an alias that may be found in the wallet
             .or_else(|_| {
                 ctx.wallet
@@ -447,7 +461,55 @@ impl ArgFromContext for Address {
                     .map(|x| x.into_owned())
                     .ok_or(Skip)
             })
-            .map_err(|_| format!("Unknown address {raw}"))
+            .or_else(|Skip| {
+                // Or the handle of a validator, such as `@alice`
+                if let Some(handle) = raw.strip_prefix('@') {
+                    return resolve_validator_handle(ctx, handle);
+                }
+                // Or a registered name, such as `alice.nam`
+                match name_service::parse_name(raw) {
+                    Some(name) => resolve_name(ctx, name),
+                    None => Err(format!("Unknown address {raw}")),
+                }
+            })
+    }
+}
+
+/// Resolve the handle of a validator with a query to the ledger node of the
+/// chain configuration.
+fn resolve_validator_handle(
+    ctx: &ChainContext,
+    handle: &str,
+) -> Result<Address, String> {
+    let client = HttpClient::new(ctx.config.ledger.cometbft.rpc.laddr.clone())
+        .map_err(|err| format!("Failed to resolve @{handle}: {err}"))?;
+    // The args are parsed synchronously from within the async runtime
+    let resolved = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current()
+            .block_on(rpc::query_validator_by_handle(&client, handle))
+    });
+    match resolved {
+        Ok(Some(address)) => Ok(address),
+        Ok(None) => Err(format!("No validator has the handle @{handle}")),
+        Err(err) => Err(format!("Failed to resolve @{handle}: {err}")),
+    }
+}
+
+/// Resolve a name with a query to the ledger node of the chain
+/// configuration.
+fn resolve_name(ctx: &ChainContext, name: &str) -> Result<Address, String> {
+    let full_name = format!("{name}{}", name_service::NAME_SUFFIX);
+    let client = HttpClient::new(ctx.config.ledger.cometbft.rpc.laddr.clone())
+        .map_err(|err| format!("Failed to resolve {full_name}: {err}"))?;
+    // The args are parsed synchronously from within the async runtime
+    let resolved = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current()
+            .block_on(rpc::resolve_name(&client, name))
+    });
+    match resolved {
+        Ok(Some(address)) => Ok(address),
+        Ok(None) => Err(format!("The name {full_name} is not registered")),
+        Err(err) => Err(format!("Failed to resolve {full_name}: {err}")),
     }
 }
 
@@ -586,6 +648,9 @@ impl ArgFromContext for TransferTarget {
         raw: impl AsRef<str>,
     ) -> Result<Self, String> {
         let raw = raw.as_ref();
+        if let Ok(AnyAddress::Payment(address)) = AnyAddress::from_str(raw) {
+            return Ok(Self::PaymentAddress(address));
+        }
         // Either the string is a transparent address or a payment address
         Address::arg_from_ctx(ctx, raw)
             .map(Self::Address)
@@ -601,6 +666,9 @@ impl ArgFromMutContext for BalanceOwner {
         raw: impl AsRef<str>,
     ) -> Result<Self, String> {
         let raw = raw.as_ref();
+        if let Ok(AnyAddress::Payment(address)) = AnyAddress::from_str(raw) {
+            return Ok(Self::PaymentAddress(address));
+        }
         // Either the string is a transparent address or a viewing key
         Address::arg_from_ctx(ctx, raw)
             .map(Self::Address)
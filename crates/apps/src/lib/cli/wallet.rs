@@ -17,8 +17,13 @@ use namada::types::io::Io;
 use namada::types::key::*;
 use namada::types::masp::{ExtendedSpendingKey, MaspValue, PaymentAddress};
 use namada_sdk::masp::find_valid_diversifier;
+use namada_sdk::wallet::interop::{
+    KeyExportFormat, Keystore, UnifiedSpendingKey, UnifiedViewingKey,
+    Zip316FullViewingKey, Zip316IncomingViewingKey,
+};
 use namada_sdk::wallet::{
     DecryptionError, DerivationPath, DerivationPathError, FindKeyError, Wallet,
+    WalletIo,
 };
 use namada_sdk::{display_line, edisplay_line};
 use rand_core::OsRng;
@@ -63,6 +68,10 @@ impl CliApi {
             cmds::NamadaWallet::KeyAddrRemove(
                 cmds::WalletRemoveKeyAddress(args),
             ) => key_address_remove(ctx, io, args),
+            cmds::NamadaWallet::Daemon(cmds::WalletDaemon(args)) => {
+                wallet::daemon::run(ctx.take_chain_or_exit(), args.socket_path)
+                    .await?
+            }
             cmds::NamadaWallet::PayAddrGen(cmds::WalletGenPaymentAddress(
                 args,
             )) => {
@@ -1185,30 +1194,116 @@ fn transparent_keys_list(
 fn key_export(
     ctx: Context,
     io: &impl Io,
-    args::KeyExport { alias }: args::KeyExport,
+    args::KeyExport { alias, format }: args::KeyExport,
 ) {
     let alias = alias.to_lowercase();
     let mut wallet = load_wallet(ctx);
+    let file_data = match format {
+        KeyExportFormat::Raw => raw_key_export(&mut wallet, io, &alias),
+        KeyExportFormat::Unified => unified_key_export(&mut wallet, io, &alias)
+            .to_string()
+            .into_bytes(),
+        KeyExportFormat::UnifiedViewing => {
+            unified_key_export(&mut wallet, io, &alias)
+                .to_viewing_key()
+                .to_string()
+                .into_bytes()
+        }
+        KeyExportFormat::Zip316Viewing => {
+            zip316_key_export(&mut wallet, io, &alias)
+                .to_string()
+                .into_bytes()
+        }
+        KeyExportFormat::Zip316IncomingViewing => {
+            zip316_key_export(&mut wallet, io, &alias)
+                .to_incoming_viewing_key()
+                .to_string()
+                .into_bytes()
+        }
+        KeyExportFormat::Keystore => {
+            let key = unified_key_export(&mut wallet, io, &alias);
+            let password = CliWalletUtils::read_password(true);
+            let keystore =
+                Keystore::encrypt(&key, Some(alias.clone()), &password);
+            serde_json::to_vec_pretty(&keystore)
+                .expect("Keystore should be serializable to JSON")
+        }
+    };
+    let file_name = match format {
+        KeyExportFormat::Keystore => format!("key_{}.json", alias),
+        _ => format!("key_{}", alias),
+    };
+    let mut file = File::create(&file_name).unwrap();
+    file.write_all(file_data.as_ref()).unwrap();
+    display_line!(io, "Exported to file {}", file_name);
+}
+
+/// Encode a transparent keypair / MASP spending key in the wallet's binary
+/// encoding.
+fn raw_key_export(
+    wallet: &mut Wallet<CliWalletUtils>,
+    io: &impl Io,
+    alias: &str,
+) -> Vec<u8> {
     let key_to_export = wallet
-        .find_secret_key(&alias, None)
+        .find_secret_key(alias, None)
         .map(|sk| Box::new(sk) as Box<dyn BorshSerializeExt>)
         .or(wallet
-            .find_spending_key(&alias, None)
+            .find_spending_key(alias, None)
             .map(|spk| Box::new(spk) as Box<dyn BorshSerializeExt>));
     key_to_export
-        .map(|key| {
-            let file_data = key.serialize_to_vec();
-            let file_name = format!("key_{}", alias);
-            let mut file = File::create(&file_name).unwrap();
-            file.write_all(file_data.as_ref()).unwrap();
-            display_line!(io, "Exported to file {}", file_name);
-        })
+        .map(|key| key.serialize_to_vec())
         .unwrap_or_else(|err| {
             edisplay_line!(io, "{}", err);
             cli::safe_exit(1)
         })
 }
 
+/// Bundle the transparent keypair and MASP spending key with the given alias
+/// into a unified spending key.
+fn unified_key_export(
+    wallet: &mut Wallet<CliWalletUtils>,
+    io: &impl Io,
+    alias: &str,
+) -> UnifiedSpendingKey {
+    let key = UnifiedSpendingKey {
+        transparent: found_key(io, wallet.find_secret_key(alias, None)),
+        shielded: found_key(io, wallet.find_spending_key(alias, None)),
+    };
+    if key.transparent.is_none() && key.shielded.is_none() {
+        edisplay_line!(io, "No key matching {} found", alias);
+        cli::safe_exit(1)
+    }
+    key
+}
+
+/// Get the MASP viewing key with the given alias as a ZIP 316 unified full
+/// viewing key.
+fn zip316_key_export(
+    wallet: &mut Wallet<CliWalletUtils>,
+    io: &impl Io,
+    alias: &str,
+) -> Zip316FullViewingKey {
+    let shielded = *wallet.find_viewing_key(alias).unwrap_or_else(|err| {
+        edisplay_line!(io, "{}", err);
+        cli::safe_exit(1)
+    });
+    Zip316FullViewingKey { shielded }
+}
+
+/// Get the key that was found in the wallet, if any. Exits on any other error
+/// than a missing key.
+fn found_key<T>(io: &impl Io, result: Result<T, FindKeyError>) -> Option<T> {
+    match result {
+        Ok(key) => Some(key),
+        Err(FindKeyError::KeyNotFound(_)) => None,
+        Err(err) => {
+            edisplay_line!(io, "{}", err);
+            cli::safe_exit(1)
+        }
+    }
+}
+
 /// Import a transparent keypair / MASP spending key from a file.
 fn key_import(
     ctx: Context,
@@ -1225,6 +1320,58 @@ fn key_import(
         display_line!(io, "No changes are persisted. Exiting.");
         cli::safe_exit(1)
     });
+    // Try the interoperable text encodings first
+    if let Ok(contents) = std::str::from_utf8(&file_data) {
+        let contents = contents.trim();
+        if let Ok(keystore) = serde_json::from_str::<Keystore>(contents) {
+            let password = CliWalletUtils::read_password(false);
+            let key = keystore.decrypt(&password).unwrap_or_else(|err| {
+                edisplay_line!(io, "{}", err);
+                display_line!(io, "No changes are persisted. Exiting.");
+                cli::safe_exit(1)
+            });
+            return unified_key_add(
+                ctx,
+                io,
+                alias,
+                alias_force,
+                key,
+                unsafe_dont_encrypt,
+            );
+        }
+        if let Ok(key) = UnifiedSpendingKey::from_str(contents) {
+            return unified_key_add(
+                ctx,
+                io,
+                alias,
+                alias_force,
+                key,
+                unsafe_dont_encrypt,
+            );
+        }
+        if let Ok(key) = UnifiedViewingKey::from_str(contents) {
+            return unified_viewing_key_add(ctx, io, alias, alias_force, key);
+        }
+        if let Ok(key) = Zip316FullViewingKey::from_str(contents) {
+            return shielded_key_address_add(
+                ctx,
+                io,
+                alias,
+                alias_force,
+                MaspValue::FullViewingKey(key.shielded),
+                unsafe_dont_encrypt,
+            );
+        }
+        if Zip316IncomingViewingKey::from_str(contents).is_ok() {
+            edisplay_line!(
+                io,
+                "Incoming viewing keys can't be imported, the wallet only \
+                 holds full viewing keys."
+            );
+            display_line!(io, "No changes are persisted. Exiting.");
+            cli::safe_exit(1)
+        }
+    }
     if let Ok(sk) = common::SecretKey::try_from_slice(&file_data) {
         transparent_secret_key_add(
             ctx,
@@ -1253,6 +1400,99 @@ fn key_import(
     }
 }
 
+/// Add the keys bundled in a unified spending key to the wallet.
+fn unified_key_add(
+    ctx: Context,
+    io: &impl Io,
+    alias: String,
+    alias_force: bool,
+    key: UnifiedSpendingKey,
+    unsafe_dont_encrypt: bool,
+) {
+    let mut alias = alias.to_lowercase();
+    let mut wallet = load_wallet(ctx);
+    let encryption_password =
+        read_and_confirm_encryption_password(unsafe_dont_encrypt);
+    if let Some(sk) = key.transparent {
+        alias = wallet
+            .insert_keypair(
+                alias,
+                alias_force,
+                sk,
+                encryption_password.clone(),
+                None,
+                None,
+            )
+            .unwrap_or_else(|| {
+                edisplay_line!(io, "Failed to add a keypair.");
+                display_line!(io, "No changes are persisted. Exiting.");
+                cli::safe_exit(1);
+            });
+    }
+    if let Some(spend_key) = key.shielded {
+        alias = wallet
+            .insert_spending_key(
+                alias,
+                alias_force,
+                spend_key,
+                encryption_password,
+                None,
+            )
+            .unwrap_or_else(|| {
+                edisplay_line!(io, "Spending key not added");
+                display_line!(io, "No changes are persisted. Exiting.");
+                cli::safe_exit(1);
+            });
+    }
+    wallet
+        .save()
+        .unwrap_or_else(|err| edisplay_line!(io, "{}", err));
+    display_line!(
+        io,
+        "Successfully added the keys of a unified key with alias: \"{}\"",
+        alias
+    );
+}
+
+/// Add the keys bundled in a unified viewing key to the wallet.
+fn unified_viewing_key_add(
+    ctx: Context,
+    io: &impl Io,
+    alias: String,
+    alias_force: bool,
+    key: UnifiedViewingKey,
+) {
+    let mut alias = alias.to_lowercase();
+    let mut wallet = load_wallet(ctx);
+    if let Some(pubkey) = key.transparent {
+        alias = wallet
+            .insert_public_key(alias, pubkey, None, None, alias_force)
+            .unwrap_or_else(|| {
+                edisplay_line!(io, "Public key not added");
+                display_line!(io, "No changes are persisted. Exiting.");
+                cli::safe_exit(1);
+            });
+    }
+    if let Some(viewing_key) = key.shielded {
+        alias = wallet
+            .insert_viewing_key(alias, viewing_key, alias_force)
+            .unwrap_or_else(|| {
+                edisplay_line!(io, "Viewing key not added");
+                display_line!(io, "No changes are persisted. Exiting.");
+                cli::safe_exit(1);
+            });
+    }
+    wallet
+        .save()
+        .unwrap_or_else(|err| edisplay_line!(io, "{}", err));
+    display_line!(
+        io,
+        "Successfully added the keys of a unified viewing key with alias: \
+         \"{}\"",
+        alias
+    );
+}
+
 /// List all known transparent addresses.
 fn transparent_addresses_list(
     wallet: &Wallet<CliWalletUtils>,
@@ -24,7 +24,7 @@ use namada::governance::pgf::parameters::PgfParameters;
 use namada::governance::pgf::storage::steward::StewardDetail;
 use namada::governance::storage::keys as governance_storage;
 use namada::governance::storage::proposal::{
-    StoragePgfFunding, StorageProposal,
+    StoragePgfClaim, StoragePgfFunding, StorageProposal,
 };
 use namada::governance::utils::{
     compute_proposal_result, ProposalVotes, TallyType, TallyVote, VotePower,
@@ -37,15 +37,17 @@ use namada::ledger::parameters::{storage as param_storage, EpochDuration};
 use namada::ledger::pos::types::{CommissionPair, Slash};
 use namada::ledger::pos::PosParams;
 use namada::ledger::queries::RPC;
-use namada::proof_of_stake::types::{ValidatorState, WeightedValidator};
-use namada::types::address::{Address, InternalAddress, MASP};
+use namada::proof_of_stake::types::{
+    Provenance, ValidatorState, WeightedValidator,
+};
+use namada::types::address::{Address, AnyAddress, InternalAddress, MASP};
 use namada::types::hash::Hash;
-use namada::types::ibc::{is_ibc_denom, IbcTokenHash};
+use namada::types::ibc::IbcTokenHash;
 use namada::types::io::Io;
 use namada::types::key::*;
 use namada::types::masp::{BalanceOwner, ExtendedViewingKey, PaymentAddress};
 use namada::types::storage::{
-    BlockHeight, BlockResults, Epoch, IndexedTx, Key, KeySeg,
+    BlockHeight, BlockResults, Epoch, IndexedTx, Key, KeySeg, TxIndex,
 };
 use namada::types::token::{Change, MaspDigitPos};
 use namada::{state as storage, token};
@@ -53,6 +55,7 @@ use namada_sdk::error::{
     is_pinned_error, Error, PinnedBalanceError, QueryError,
 };
 use namada_sdk::masp::{Conversions, MaspChange, MaspTokenRewardData};
+use namada_sdk::memo::EncryptedMemo;
 use namada_sdk::proof_of_stake::types::ValidatorMetaData;
 use namada_sdk::rpc::{
     self, enriched_bonds_and_unbonds, query_epoch, TxResponse,
@@ -150,6 +153,8 @@ pub async fn query_transfers(
         .values()
         .map(|fvk| (ExtendedFullViewingKey::from(*fvk).fvk.vk, fvk))
         .collect();
+    // Release the wallet to be able to look up the keys of encrypted memos
+    drop(wallet);
     // Now display historical shielded and transparent transactions
     for (IndexedTx { height, index: idx }, (epoch, tfer_delta, tx_delta)) in
         transfers
@@ -264,6 +269,53 @@ pub async fn query_transfers(
                 display_line!(context.io(), "");
             }
         }
+        // Then display the memos of the notes received by our viewing keys
+        for (vk, memo) in shielded.tx_memos(&IndexedTx { height, index: idx }) {
+            if let Some(fvk) = fvk_map.get(&vk) {
+                display_line!(
+                    context.io(),
+                    "  Shielded memo to {}: {}",
+                    fvk,
+                    String::from_utf8_lossy(memo)
+                );
+            }
+        }
+        // Finally display the memo of the tx, if any
+        if let Some(memo) = display_memo(context, height, idx).await {
+            display_line!(context.io(), "  Memo: {}", memo);
+        }
+    }
+}
+
+/// Get the memo of the given tx to display. If it's encrypted to a key of
+/// the wallet, it's decrypted.
+async fn display_memo(
+    context: &impl Namada,
+    height: BlockHeight,
+    index: TxIndex,
+) -> Option<String> {
+    let memo = match rpc::query_tx_memo(context.client(), height, index).await {
+        Ok(memo) => memo?,
+        Err(err) => {
+            edisplay_line!(context.io(), "Failed to query the memo: {}", err);
+            return None;
+        }
+    };
+    let encrypted = match EncryptedMemo::from_memo(&memo) {
+        Ok(Some(encrypted)) => encrypted,
+        Ok(None) => return Some(String::from_utf8_lossy(&memo).into_owned()),
+        Err(err) => return Some(format!("<{err}>")),
+    };
+    let sk = context
+        .wallet_mut()
+        .await
+        .find_key_by_pkh(&encrypted.recipient, None);
+    match sk {
+        Ok(sk) => match encrypted.open(&sk) {
+            Ok(memo) => Some(String::from_utf8_lossy(&memo).into_owned()),
+            Err(err) => Some(format!("<{err}>")),
+        },
+        Err(_) => Some(format!("<encrypted to {}>", encrypted.recipient)),
     }
 }
 
@@ -752,19 +804,19 @@ async fn get_ibc_denom_alias(
     ibc_denom: impl AsRef<str>,
 ) -> String {
     let wallet = context.wallet().await;
-    is_ibc_denom(&ibc_denom)
-        .map(|(trace_path, base_token)| {
-            let base_token_alias = match Address::decode(&base_token) {
+    match AnyAddress::from_str(ibc_denom.as_ref()) {
+        Ok(AnyAddress::IbcDenom {
+            trace_path,
+            base_denom,
+        }) => {
+            let base_token_alias = match Address::decode(&base_denom) {
                 Ok(base_token) => wallet.lookup_alias(&base_token),
-                Err(_) => base_token,
+                Err(_) => base_denom,
             };
-            if trace_path.is_empty() {
-                base_token_alias
-            } else {
-                format!("{}/{}", trace_path, base_token_alias)
-            }
-        })
-        .unwrap_or(ibc_denom.as_ref().to_string())
+            format!("{}/{}", trace_path, base_token_alias)
+        }
+        _ => ibc_denom.as_ref().to_string(),
+    }
 }
 
 /// Query votes for the given proposal
@@ -882,7 +934,13 @@ pub async fn query_shielded_balance(
             .iter()
             .map(|fvk| ExtendedFullViewingKey::from(*fvk).fvk.vk)
             .collect();
-        shielded.fetch(context.client(), &[], &fvks).await.unwrap();
+        match args.from_height {
+            Some(from_height) => shielded
+                .fetch_from_height(context.client(), &[], &fvks, from_height)
+                .await
+                .unwrap(),
+            None => shielded.fetch(context.client(), &[], &fvks).await.unwrap(),
+        }
         // Precompute asset types to increase chances of success in decoding
         let _ = shielded.precompute_asset_types(context).await;
         // Save the update state so that future fetches can be short-circuited
@@ -1286,11 +1344,20 @@ pub async fn query_proposal_result(
         )
         .await;
 
-        let proposal_result = compute_proposal_result(
+        let proposal_result = match compute_proposal_result(
             proposal_votes,
             total_voting_power,
             TallyType::TwoThirds,
-        );
+        ) {
+            Ok(proposal_result) => proposal_result,
+            Err(err) => {
+                edisplay_line!(
+                    context.io(),
+                    "Couldn't tally the offline proposal: {err}"
+                );
+                return;
+            }
+        };
 
         display_line!(
             context.io(),
@@ -1321,6 +1388,7 @@ pub async fn query_account(context: &impl Namada, args: args::QueryAccount) {
 pub async fn query_pgf(context: &impl Namada, _args: args::QueryPgf) {
     let stewards = query_pgf_stewards(context.client()).await;
     let fundings = query_pgf_fundings(context.client()).await;
+    let claims = query_pgf_claims(context.client()).await;
 
     match stewards.is_empty() {
         true => {
@@ -1367,6 +1435,38 @@ pub async fn query_pgf(context: &impl Namada, _args: args::QueryPgf) {
             }
         }
     }
+
+    match claims.is_empty() {
+        true => {
+            display_line!(
+                context.io(),
+                "Pgf retro claims: no retro fundings are currently claimable."
+            )
+        }
+        false => {
+            let current_epoch =
+                rpc::query_epoch(context.client()).await.unwrap();
+            display_line!(context.io(), "Pgf retro claims:");
+            for claim in claims {
+                display_line!(
+                    context.io(),
+                    "{:4}- {} remaining for {}, expiring at epoch {}",
+                    "",
+                    claim.remaining.to_string_native(),
+                    claim.target,
+                    claim.expiration
+                );
+                display_line!(
+                    context.io(),
+                    "{:4}  Claimable in the current epoch: {} (limit {} per \
+                     epoch)",
+                    "",
+                    claim.claimable(current_epoch).to_string_native(),
+                    claim.limit_per_epoch.to_string_native()
+                );
+            }
+        }
+    }
 }
 
 pub async fn query_protocol_parameters(
@@ -1481,6 +1581,17 @@ pub async fn query_protocol_parameters(
         .expect("Parameter should be defined.");
     display_line!(context.io(), "{:4}Max block gas: {:?}", "", max_block_gas);
 
+    // The max tx gas is not set if it's the same as the max block gas
+    let key = param_storage::get_max_tx_gas_key();
+    let max_tx_gas: Option<u64> =
+        query_storage_value(context.client(), &key).await.ok();
+    display_line!(
+        context.io(),
+        "{:4}Max tx gas: {:?}",
+        "",
+        max_tx_gas.unwrap_or(max_block_gas)
+    );
+
     let key = param_storage::get_fee_unshielding_gas_limit_key();
     let fee_unshielding_gas_limit: u64 =
         query_storage_value(context.client(), &key)
@@ -1515,6 +1626,42 @@ pub async fn query_protocol_parameters(
         display_line!(context.io(), "{:8}{}: {:?}", "", token, gas_cost);
     }
 
+    // The max native mint is not set if there's no limit
+    let key = param_storage::get_max_native_mint_per_epoch_key();
+    let max_native_mint: Option<token::Amount> =
+        query_storage_value(context.client(), &key).await.ok();
+    match max_native_mint {
+        Some(max_native_mint) => display_line!(
+            context.io(),
+            "{:4}Max native mint per epoch: {}",
+            "",
+            max_native_mint.to_string_native()
+        ),
+        None => display_line!(
+            context.io(),
+            "{:4}Max native mint per epoch: no limit",
+            ""
+        ),
+    }
+
+    // The MASP conversion retention is not set if they're all kept
+    let key = param_storage::get_masp_conversion_retention_epochs_key();
+    let retention: Option<u64> =
+        query_storage_value(context.client(), &key).await.ok();
+    match retention {
+        Some(retention) => display_line!(
+            context.io(),
+            "{:4}MASP conversion retention: {} epochs",
+            "",
+            retention
+        ),
+        None => display_line!(
+            context.io(),
+            "{:4}MASP conversion retention: all epochs",
+            ""
+        ),
+    }
+
     display_line!(context.io(), "PoS parameters");
     let pos_params = query_pos_parameters(context.client()).await;
     display_line!(
@@ -1621,6 +1768,12 @@ pub async fn query_pgf_fundings<C: namada::ledger::queries::Client + Sync>(
     unwrap_client_response::<C, _>(RPC.vp().pgf().funding(client).await)
 }
 
+pub async fn query_pgf_claims<C: namada::ledger::queries::Client + Sync>(
+    client: &C,
+) -> Vec<StoragePgfClaim> {
+    unwrap_client_response::<C, _>(RPC.vp().pgf().claims(client).await)
+}
+
 pub async fn query_pgf_parameters<C: namada::ledger::queries::Client + Sync>(
     client: &C,
 ) -> PgfParameters {
@@ -1818,18 +1971,25 @@ pub async fn query_bonded_stake<N: Namada>(
 
     match args.validator {
         Some(validator) => {
-            let validator = validator;
             // Find bonded stake for the given validator
-            let stake =
-                get_validator_stake(context.client(), epoch, &validator).await;
+            let stake = unwrap_historical(
+                context,
+                rpc::get_historical_validator_stake(
+                    context.client(),
+                    epoch,
+                    &validator,
+                )
+                .await,
+            );
             match stake {
                 Some(stake) => {
                     // TODO: show if it's in consensus set, below capacity, or
                     // below threshold set
                     display_line!(
                         context.io(),
-                        "Bonded stake of validator {validator}: {}",
-                        stake.to_string_native()
+                        "Bonded stake of validator {validator}: {}{}",
+                        stake.value.to_string_native(),
+                        provenance_note(stake.provenance, epoch)
                     )
                 }
                 None => {
@@ -1841,31 +2001,42 @@ pub async fn query_bonded_stake<N: Namada>(
             }
         }
         None => {
-            let consensus: BTreeSet<WeightedValidator> =
-                unwrap_client_response::<N::Client, _>(
-                    RPC.vp()
-                        .pos()
-                        .consensus_validator_set(context.client(), &Some(epoch))
-                        .await,
-                );
+            let consensus = unwrap_historical(
+                context,
+                rpc::get_historical_consensus_validator_set(
+                    context.client(),
+                    epoch,
+                )
+                .await,
+            );
+            // The below-capacity validators aren't archived separately from
+            // the other validators that aren't in the consensus set
             let below_capacity: BTreeSet<WeightedValidator> =
-                unwrap_client_response::<N::Client, _>(
-                    RPC.vp()
-                        .pos()
-                        .below_capacity_validator_set(
-                            context.client(),
-                            &Some(epoch),
-                        )
-                        .await,
-                );
+                match consensus.provenance {
+                    Provenance::Live => unwrap_client_response::<N::Client, _>(
+                        RPC.vp()
+                            .pos()
+                            .below_capacity_validator_set(
+                                context.client(),
+                                &Some(epoch),
+                            )
+                            .await,
+                    ),
+                    Provenance::Archived => BTreeSet::new(),
+                };
 
             // Iterate all validators
             let stdout = io::stdout();
             let mut w = stdout.lock();
 
-            display_line!(context.io(), &mut w; "Consensus validators:")
-                .unwrap();
-            for val in consensus.into_iter().rev() {
+            display_line!(
+                context.io(),
+                &mut w;
+                "Consensus validators{}:",
+                provenance_note(consensus.provenance, epoch)
+            )
+            .unwrap();
+            for val in consensus.value.into_iter().rev() {
                 display_line!(
                     context.io(),
                     &mut w;
@@ -1892,15 +2063,39 @@ pub async fn query_bonded_stake<N: Namada>(
         }
     }
 
-    let total_staked_tokens =
-        get_total_staked_tokens(context.client(), epoch).await;
+    let total_staked_tokens = unwrap_historical(
+        context,
+        rpc::get_historical_total_staked_tokens(context.client(), epoch).await,
+    );
     display_line!(
         context.io(),
-        "Total bonded stake: {}",
-        total_staked_tokens.to_string_native()
+        "Total bonded stake: {}{}",
+        total_staked_tokens.value.to_string_native(),
+        provenance_note(total_staked_tokens.provenance, epoch)
     );
 }
 
+/// Unwrap the result of a historical PoS query or exit with its error, e.g.
+/// when the node has pruned the data of the queried epoch without archiving
+/// a summary of it
+fn unwrap_historical<N: Namada, T>(context: &N, result: Result<T, Error>) -> T {
+    result.unwrap_or_else(|err| {
+        edisplay_line!(context.io(), "{err}");
+        cli::safe_exit(1)
+    })
+}
+
+/// A note on the values read from the archived summary of an epoch, whose
+/// epoched data has been pruned by the node
+fn provenance_note(provenance: Provenance, epoch: Epoch) -> String {
+    match provenance {
+        Provenance::Live => String::new(),
+        Provenance::Archived => {
+            format!(" (from the archived summary of epoch {epoch})")
+        }
+    }
+}
+
 /// Query and return validator's commission rate and max commission rate change
 /// per epoch
 pub async fn query_commission_rate<
@@ -2081,6 +2276,19 @@ pub async fn query_and_print_metadata(
             } else {
                 display_line!(context.io(), "No avatar");
             }
+            match rpc::query_validator_handle(context.client(), &validator)
+                .await
+            {
+                Ok(Some(handle)) => {
+                    display_line!(context.io(), "Handle: @{}", handle)
+                }
+                Ok(None) => display_line!(context.io(), "No handle"),
+                Err(err) => edisplay_line!(
+                    context.io(),
+                    "Failed to query the handle: {}",
+                    err
+                ),
+            }
         }
         None => display_line!(
             context.io(),
@@ -2538,6 +2746,137 @@ pub async fn query_masp_reward_tokens(context: &impl Namada) {
     }
 }
 
+/// Generate the chain registry metadata of the chain and write it to a file,
+/// or print it if no output file is given.
+pub async fn gen_chain_registry(
+    context: &impl Namada,
+    args: args::GenChainRegistry,
+) -> Result<(), error::Error> {
+    let rpc_endpoints = if args.rpc_endpoints.is_empty() {
+        vec![args.query.ledger_address.to_string()]
+    } else {
+        args.rpc_endpoints
+    };
+    let registry = namada_sdk::chain_registry::gen_chain_registry(
+        context.client(),
+        rpc_endpoints,
+    )
+    .await?;
+    let json = serde_json::to_string_pretty(&registry)
+        .expect("Chain registry metadata should be serializable");
+    match args.output {
+        Some(output_path) => {
+            fs::write(&output_path, json).map_err(|err| {
+                error::Error::Other(format!(
+                    "Failed to write the chain registry metadata to {}: {err}",
+                    output_path.to_string_lossy()
+                ))
+            })?;
+            display_line!(
+                context.io(),
+                "Wrote the chain registry metadata of {} to {}",
+                registry.chain_id,
+                output_path.to_string_lossy()
+            );
+        }
+        None => display_line!(context.io(), "{json}"),
+    }
+    Ok(())
+}
+
+/// Query the reconciliation of the IBC escrow accounting per channel and token
+/// with the escrow balances and the voucher supplies.
+pub async fn query_ibc_escrow_reconciliation(context: &impl Namada) {
+    let reconciliations =
+        match namada_sdk::rpc::query_ibc_escrow_reconciliation(context.client())
+            .await
+        {
+            Ok(reconciliations) => reconciliations,
+            Err(err) => {
+                edisplay_line!(
+                    context.io(),
+                    "Failed to query the IBC escrow reconciliation: {err}"
+                );
+                cli::safe_exit(1)
+            }
+        };
+    if reconciliations.is_empty() {
+        display_line!(context.io(), "No IBC transfer has been accounted yet");
+        return;
+    }
+    for reconciliation in reconciliations {
+        let token = &reconciliation.token;
+        let status = if reconciliation.is_reconciled() {
+            "reconciled"
+        } else {
+            "discrepancy found"
+        };
+        display_line!(
+            context.io(),
+            "{}: {}",
+            context.wallet().await.lookup_alias(token),
+            status
+        );
+        display_line!(
+            context.io(),
+            "  Escrow balance: {}",
+            context
+                .format_amount(token, reconciliation.escrow_balance)
+                .await
+        );
+        if let Some(supply) = reconciliation.voucher_supply {
+            display_line!(
+                context.io(),
+                "  Voucher supply: {}",
+                context.format_amount(token, supply).await
+            );
+        }
+        for channel in &reconciliation.channels {
+            display_line!(
+                context.io(),
+                "  {}/{}: escrowed {}, minted {}",
+                channel.port_id,
+                channel.channel_id,
+                format_change(context, token, channel.accounting.escrowed)
+                    .await,
+                format_change(context, token, channel.accounting.minted).await
+            );
+        }
+        let escrow_discrepancy = reconciliation.escrow_discrepancy();
+        if !escrow_discrepancy.is_zero() {
+            display_line!(
+                context.io(),
+                "  Unaccounted escrow balance: {}",
+                format_change(context, token, escrow_discrepancy).await
+            );
+        }
+        if let Some(supply_discrepancy) = reconciliation
+            .supply_discrepancy()
+            .filter(|discrepancy| !discrepancy.is_zero())
+        {
+            display_line!(
+                context.io(),
+                "  Unaccounted voucher supply: {}",
+                format_change(context, token, supply_discrepancy).await
+            );
+        }
+    }
+}
+
+/// Format a change of the token amount with its sign
+async fn format_change(
+    context: &impl Namada,
+    token: &Address,
+    change: Change,
+) -> String {
+    let sign = if change.is_negative() { "-" } else { "" };
+    format!(
+        "{}{}",
+        sign,
+        context.format_amount(token, change.into()).await
+    )
+}
+
 /// Query a wasm code hash
 pub async fn query_wasm_code_hash(
     context: &impl Namada,
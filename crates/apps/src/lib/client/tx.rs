@@ -11,7 +11,7 @@ use namada::governance::cli::offline::{
     OfflineProposal, OfflineSignedProposal, OfflineVote,
 };
 use namada::governance::cli::onchain::{
-    DefaultProposal, PgfFundingProposal, PgfStewardProposal,
+    DefaultProposal, PgfFundingProposal, PgfStewardProposal, SlashPoolProposal,
 };
 use namada::governance::ProposalVote;
 use namada::ibc::apps::transfer::types::Memo;
@@ -20,6 +20,7 @@ use namada::tx::data::pos::{BecomeValidator, ConsensusKeyChange};
 use namada::tx::{CompressedSignature, Section, Signer, Tx};
 use namada::types::address::{Address, ImplicitAddress};
 use namada::types::dec::Dec;
+use namada::types::hash::Hash;
 use namada::types::io::Io;
 use namada::types::key::{self, *};
 use namada_sdk::rpc::{InnerTxResult, TxBroadcastData, TxResponse};
@@ -294,6 +295,146 @@ where
     Ok(())
 }
 
+pub async fn submit_update_policy<N: Namada>(
+    namada: &N,
+    args: args::TxUpdatePolicy,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_multi_transfer<N: Namada>(
+    namada: &N,
+    args: args::TxMultiTransfer,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_approve<N: Namada>(
+    namada: &N,
+    args: args::TxApprove,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_transfer_from<N: Namada>(
+    namada: &N,
+    args: args::TxTransferFrom,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_register_name<N: Namada>(
+    namada: &N,
+    args: args::RegisterName,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_renew_name<N: Namada>(
+    namada: &N,
+    args: args::RenewName,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_transfer_name<N: Namada>(
+    namada: &N,
+    args: args::TransferName,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
 pub async fn submit_init_account<N: Namada>(
     namada: &N,
     args: args::TxInitAccount,
@@ -302,6 +443,11 @@ where
     <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
 {
     let (mut tx, signing_data) = tx::build_init_account(namada, &args).await?;
+    display_line!(
+        namada.io(),
+        "The account will be initialized at {}",
+        tx::predict_init_account_address(&tx)
+    );
 
     if args.tx.dump_tx {
         tx::dump_tx(namada.io(), &args.tx, tx);
@@ -1061,6 +1207,22 @@ where
             .await?;
 
         tx::build_pgf_funding_proposal(namada, &args, proposal).await?
+    } else if args.is_slash_pool {
+        let proposal = SlashPoolProposal::try_from(args.proposal_data.as_ref())
+            .map_err(|e| {
+                error::TxSubmitError::FailedGovernaneProposalDeserialize(
+                    e.to_string(),
+                )
+            })?
+            .validate(&governance_parameters, current_epoch, args.tx.force)
+            .map_err(|e| {
+                error::TxSubmitError::InvalidProposal(e.to_string())
+            })?;
+
+        submit_reveal_aux(namada, args.tx.clone(), &proposal.proposal.author)
+            .await?;
+
+        tx::build_slash_pool_proposal(namada, &args, proposal).await?
     } else if args.is_pgf_stewards {
         let proposal = PgfStewardProposal::try_from(
             args.proposal_data.as_ref(),
@@ -1555,6 +1717,26 @@ where
     Ok(())
 }
 
+pub async fn submit_claim_pgf_funding<N: Namada>(
+    namada: &N,
+    args: args::ClaimPgfFunding,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data) = args.build(namada).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
 /// Save accounts initialized from a tx into the wallet, if any.
 pub async fn save_initialized_accounts(
     namada: &impl Namada,
@@ -1590,6 +1772,37 @@ pub async fn submit_tx(
     tx::submit_tx(namada, to_broadcast).await
 }
 
+/// Generate the receipt of an applied transfer and write it to a file
+pub async fn gen_receipt(
+    context: &impl Namada,
+    args: args::GenReceipt,
+) -> Result<(), error::Error> {
+    let tx_hash = args.tx_hash.parse::<Hash>().map_err(|err| {
+        error::Error::Other(format!("Invalid tx hash {}: {err}", args.tx_hash))
+    })?;
+    let receipt =
+        namada_sdk::receipt::gen_payment_receipt(context.client(), tx_hash)
+            .await?;
+    let output_path = args
+        .output
+        .unwrap_or_else(|| format!("receipt-{tx_hash}.json").into());
+    let json = serde_json::to_string_pretty(&receipt)
+        .expect("Receipt should be serializable");
+    std::fs::write(&output_path, json).map_err(|err| {
+        error::Error::Other(format!(
+            "Failed to write the receipt to {}: {err}",
+            output_path.to_string_lossy()
+        ))
+    })?;
+    display_line!(
+        context.io(),
+        "Wrote the receipt of the transfer {tx_hash} at height {} to {}",
+        receipt.signed_header.header.height,
+        output_path.to_string_lossy()
+    );
+    Ok(())
+}
+
 pub async fn gen_ibc_shielded_transfer(
     context: &impl Namada,
     args: args::GenIbcShieldedTransafer,
@@ -17,6 +17,7 @@ use namada::types::string_encoding::StringEncoded;
 use namada::types::token;
 use namada::types::uint::Uint;
 use namada::vm::validate_untrusted_wasm;
+use namada_sdk::receipt::PaymentReceipt;
 use namada_sdk::wallet::{alias, Wallet};
 use prost::bytes::Bytes;
 use serde_json::json;
@@ -235,6 +236,73 @@ pub async fn join_network(
             safe_exit(1)
         });
 
+    // Try to load pre-genesis wallet, if any
+    let pre_genesis_wallet_path = base_dir.join(PRE_GENESIS_DIR);
+    let pre_genesis_wallet =
+        if let Some(wallet) = crate::wallet::load(&pre_genesis_wallet_path) {
+            Some(wallet)
+        } else {
+            validator_alias_and_dir
+                .as_ref()
+                .and_then(|(_, path)| crate::wallet::load(path))
+        };
+
+    configure_node(
+        &base_dir,
+        &chain_dir,
+        &genesis,
+        pre_genesis_wallet,
+        validator_alias_and_pre_genesis_wallet,
+        allow_duplicate_ip,
+    );
+
+    // Move wasm-dir and update config if it's non-default
+    if let Some(wasm_dir) = wasm_dir.as_ref() {
+        if wasm_dir.to_string_lossy() != config::DEFAULT_WASM_DIR {
+            tokio::fs::rename(
+                base_dir_full
+                    .join(chain_id.as_str())
+                    .join(config::DEFAULT_WASM_DIR),
+                chain_dir.join(wasm_dir),
+            )
+            .await
+            .unwrap();
+
+            // Update the config
+            let wasm_dir = wasm_dir.clone();
+            let base_dir = base_dir.clone();
+            let chain_id = chain_id.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut config = Config::load(&base_dir, &chain_id, None);
+                config.wasm_dir = wasm_dir;
+                config.write(&base_dir, &chain_id, true).unwrap();
+            })
+            .await
+            .unwrap();
+        }
+    }
+
+    if !dont_prefetch_wasm {
+        fetch_wasms_aux(&base_dir, &chain_id).await;
+    }
+
+    println!("Successfully configured for chain ID {}", chain_id);
+}
+
+/// Derive the config and the wallet of a node from the finalized genesis of
+/// its chain and save them, together with the CometBFT keys of the genesis
+/// validator, if any.
+pub fn configure_node(
+    base_dir: &Path,
+    chain_dir: &Path,
+    genesis: &genesis::chain::Finalized,
+    pre_genesis_wallet: Option<Wallet<CliWalletUtils>>,
+    validator_alias_and_pre_genesis_wallet: Option<(
+        alias::Alias,
+        pre_genesis::ValidatorWallet,
+    )>,
+    allow_duplicate_ip: bool,
+) {
     // Try to find validator data when using a pre-genesis validator
     let validator_alias = validator_alias_and_pre_genesis_wallet
         .as_ref()
@@ -255,32 +323,22 @@ pub async fn join_network(
 
     // Derive config from genesis
     let config = genesis.derive_config(
-        &chain_dir,
+        chain_dir,
         node_mode,
         validator_keys.as_ref().map(|(sk, _)| sk.ref_to()).as_ref(),
         allow_duplicate_ip,
     );
 
-    // Try to load pre-genesis wallet, if any
-    let pre_genesis_wallet_path = base_dir.join(PRE_GENESIS_DIR);
-    let pre_genesis_wallet =
-        if let Some(wallet) = crate::wallet::load(&pre_genesis_wallet_path) {
-            Some(wallet)
-        } else {
-            validator_alias_and_dir
-                .as_ref()
-                .and_then(|(_, path)| crate::wallet::load(path))
-        };
-
     // Derive wallet from genesis
     let wallet = genesis.derive_wallet(
-        &chain_dir,
+        chain_dir,
         pre_genesis_wallet,
         validator_alias_and_pre_genesis_wallet,
     );
 
     // Save the config and the wallet
-    config.write(&base_dir, &chain_id, true).unwrap();
+    let chain_id = &genesis.metadata.chain_id;
+    config.write(base_dir, chain_id, true).unwrap();
     crate::wallet::save(&wallet).unwrap();
 
     // Setup the node for a genesis validator, if used
@@ -305,38 +363,6 @@ pub async fn join_network(
              specify `--genesis-validator`?"
         );
     }
-
-    // Move wasm-dir and update config if it's non-default
-    if let Some(wasm_dir) = wasm_dir.as_ref() {
-        if wasm_dir.to_string_lossy() != config::DEFAULT_WASM_DIR {
-            tokio::fs::rename(
-                base_dir_full
-                    .join(chain_id.as_str())
-                    .join(config::DEFAULT_WASM_DIR),
-                chain_dir.join(wasm_dir),
-            )
-            .await
-            .unwrap();
-
-            // Update the config
-            let wasm_dir = wasm_dir.clone();
-            let base_dir = base_dir.clone();
-            let chain_id = chain_id.clone();
-            tokio::task::spawn_blocking(move || {
-                let mut config = Config::load(&base_dir, &chain_id, None);
-                config.wasm_dir = wasm_dir;
-                config.write(&base_dir, &chain_id, true).unwrap();
-            })
-            .await
-            .unwrap();
-        }
-    }
-
-    if !dont_prefetch_wasm {
-        fetch_wasms_aux(&base_dir, &chain_id).await;
-    }
-
-    println!("Successfully configured for chain ID {}", chain_id);
 }
 
 pub async fn fetch_wasms(
@@ -368,6 +394,54 @@ pub fn validate_wasm(args::ValidateWasm { code_path }: args::ValidateWasm) {
     }
 }
 
+/// Verify a receipt of an applied transfer offline
+pub fn verify_receipt(
+    args::VerifyReceipt {
+        receipt_path,
+        validators_hash,
+    }: args::VerifyReceipt,
+) {
+    let receipt: PaymentReceipt = fs::read(&receipt_path)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| {
+            serde_json::from_slice(&bytes).map_err(|err| err.to_string())
+        })
+        .unwrap_or_else(|err| {
+            eprintln!(
+                "Failed to read the receipt at {}: {err}",
+                receipt_path.to_string_lossy()
+            );
+            safe_exit(1)
+        });
+    match receipt.verify(validators_hash) {
+        Ok(()) => {
+            let header = &receipt.signed_header.header;
+            let transfer = &receipt.transfer;
+            println!("The receipt is valid.");
+            println!("Chain ID: {}", header.chain_id);
+            println!("Block height: {}, time: {}", header.height, header.time);
+            println!("Validator set hash: {}", header.validators_hash);
+            println!("Tx hash: {}", receipt.tx_hash);
+            println!(
+                "Transfer of {} of token {} from {} to {}",
+                transfer.amount,
+                transfer.token,
+                transfer.source,
+                transfer.target
+            );
+            if validators_hash.is_none() {
+                println!(
+                    "The validator set wasn't checked against a trusted one."
+                );
+            }
+        }
+        Err(err) => {
+            eprintln!("The receipt is invalid: {err}");
+            safe_exit(1)
+        }
+    }
+}
+
 /// Length of a Tendermint Node ID in bytes
 const TENDERMINT_NODE_ID_LENGTH: usize = 20;
 
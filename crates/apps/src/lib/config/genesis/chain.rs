@@ -273,6 +273,9 @@ impl Finalized {
             max_block_gas,
             minimum_gas_price,
             max_tx_bytes,
+            max_native_mint_per_epoch,
+            masp_conversion_retention_epochs,
+            max_tx_gas,
             ..
         } = self.parameters.parameters.clone();
 
@@ -326,6 +329,10 @@ impl Finalized {
                     )
                 })
                 .collect(),
+            max_native_mint_per_epoch: max_native_mint_per_epoch
+                .map(|amt| amt.amount()),
+            masp_conversion_retention_epochs,
+            max_tx_gas,
         }
     }
 
@@ -347,6 +354,7 @@ impl Finalized {
             validator_stake_threshold,
             liveness_window_check,
             liveness_threshold,
+            max_scheduling_offset,
             rewards_gain_p,
             rewards_gain_d,
         } = self.parameters.pos_params.clone();
@@ -364,9 +372,10 @@ impl Finalized {
                 duplicate_vote_min_slash_rate,
                 light_client_attack_min_slash_rate,
                 cubic_slashing_window_length,
-                validator_stake_threshold,
+                validator_stake_threshold: validator_stake_threshold.into(),
                 liveness_window_check,
                 liveness_threshold,
+                max_scheduling_offset,
                 rewards_gain_p,
                 rewards_gain_d,
             },
@@ -407,6 +416,7 @@ impl Finalized {
         if let Some(templates::EthBridgeParams {
             eth_start_height,
             min_confirmations,
+            checkpoint_interval,
             contracts,
             erc20_whitelist,
         }) = self.parameters.eth_bridge_params.clone()
@@ -415,6 +425,7 @@ impl Finalized {
                 eth_start_height,
                 min_confirmations,
                 erc20_whitelist,
+                checkpoint_interval,
                 contracts,
             })
         } else {
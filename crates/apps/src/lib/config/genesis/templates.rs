@@ -2,6 +2,7 @@
 
 use std::collections::{BTreeMap, BTreeSet};
 use std::marker::PhantomData;
+use std::num::NonZeroU64;
 use std::path::Path;
 
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -210,6 +211,8 @@ pub struct Tokens {
 pub struct TokenConfig {
     pub denom: Denomination,
     pub masp_params: Option<token::MaspParams>,
+    #[serde(default)]
+    pub metadata: Option<token::Metadata>,
 }
 
 #[derive(
@@ -288,6 +291,19 @@ pub struct ChainParams<T: TemplateValidation> {
     pub fee_unshielding_descriptions_limit: u64,
     /// Map of the cost per gas unit for every token allowed for fee payment
     pub minimum_gas_price: T::GasMinimums,
+    /// Max amount of native tokens minted by the protocol per epoch, across
+    /// all the sources of inflation. No limit if not set.
+    #[serde(default)]
+    pub max_native_mint_per_epoch: Option<DenominatedAmount>,
+    /// Number of epochs that the conversions of the older MASP asset types
+    /// are kept in the conversion state, before they're archived. They're
+    /// all kept if not set.
+    #[serde(default)]
+    pub masp_conversion_retention_epochs: Option<u64>,
+    /// Max gas of a transaction, capped by the max block gas. Only limited
+    /// by the max block gas if not set.
+    #[serde(default)]
+    pub max_tx_gas: Option<u64>,
 }
 
 impl ChainParams<Unvalidated> {
@@ -310,6 +326,9 @@ impl ChainParams<Unvalidated> {
             fee_unshielding_gas_limit,
             fee_unshielding_descriptions_limit,
             minimum_gas_price,
+            max_native_mint_per_epoch,
+            masp_conversion_retention_epochs,
+            max_tx_gas,
         } = self;
         let mut min_gas_prices = BTreeMap::default();
         for (token, amount) in minimum_gas_price.into_iter() {
@@ -339,6 +358,31 @@ impl ChainParams<Unvalidated> {
             })?;
             min_gas_prices.insert(token, amount);
         }
+        let max_native_mint_per_epoch = match max_native_mint_per_epoch {
+            Some(amount) => {
+                let denom = tokens
+                    .token
+                    .get(&native_token)
+                    .map(|TokenConfig { denom, .. }| *denom)
+                    .ok_or_else(|| {
+                        eyre::eyre!(
+                            "The native token {} is not in the `tokens.toml` \
+                             file",
+                            native_token
+                        )
+                    })?;
+                let amount = amount.increase_precision(denom).map_err(|e| {
+                    eprintln!(
+                        "The max native mint per epoch in the parameters.toml \
+                         file was incorrectly formatted:\n{}",
+                        e
+                    );
+                    e
+                })?;
+                Some(amount)
+            }
+            None => None,
+        };
 
         Ok(ChainParams {
             max_tx_bytes,
@@ -355,6 +399,9 @@ impl ChainParams<Unvalidated> {
             fee_unshielding_gas_limit,
             fee_unshielding_descriptions_limit,
             minimum_gas_price: min_gas_prices,
+            max_native_mint_per_epoch,
+            masp_conversion_retention_epochs,
+            max_tx_gas,
         })
     }
 }
@@ -397,13 +444,16 @@ pub struct PosParams {
     pub cubic_slashing_window_length: u64,
     /// The minimum amount of bonded tokens that a validator needs to be in
     /// either the `consensus` or `below_capacity` validator sets
-    pub validator_stake_threshold: token::Amount,
+    pub validator_stake_threshold: token::RawAmount,
     /// The length, in blocks, of the sliding window for consensus validators
     /// inactivity verification
     pub liveness_window_check: u64,
     /// The minimum required activity of consensus validators, in percentage,
     /// over the `liveness_window_check`
     pub liveness_threshold: Dec,
+    /// The maximum number of epochs after the current epoch at which a bond
+    /// or an unbond may be scheduled to be applied
+    pub max_scheduling_offset: u64,
     /// PoS gain p (read only)
     pub rewards_gain_p: Dec,
     /// PoS gain d (read only)
@@ -478,6 +528,11 @@ pub struct EthBridgeParams {
     /// Minimum number of confirmations needed to trust an Ethereum branch.
     /// This must be at least one.
     pub min_confirmations: MinimumConfirmations,
+    /// The number of blocks between two checkpoints of the application
+    /// state co-signed by the validators. No checkpoints are made if this
+    /// is not set.
+    #[serde(default)]
+    pub checkpoint_interval: Option<NonZeroU64>,
     /// List of ERC20 token types whitelisted at genesis time.
     pub erc20_whitelist: Vec<Erc20WhitelistEntry>,
     /// The addresses of the Ethereum contracts that need to be directly known
@@ -70,6 +70,7 @@ fn get_tx_args(use_device: bool) -> TxArgs {
         dry_run: false,
         dry_run_wrapper: false,
         dump_tx: false,
+        preview: false,
         output_folder: None,
         force: false,
         broadcast_only: false,
@@ -890,6 +891,7 @@ where
                     .unwrap()
                     .amount(),
                 source: Some(self.source.address()),
+                start_epoch: None,
             },
         )
     }
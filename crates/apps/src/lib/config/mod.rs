@@ -5,15 +5,18 @@ pub mod genesis;
 pub mod global;
 pub mod utils;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{create_dir_all, File};
 use std::io::Write;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 
 use directories::ProjectDirs;
+use namada::types::address::Address;
 use namada::types::chain::ChainId;
 use namada::types::storage::BlockHeight;
-use namada::types::time::Rfc3339String;
+use namada::types::time::{DurationSecs, Rfc3339String};
+use namada::types::token;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -35,6 +38,14 @@ pub const FILENAME: &str = "config.toml";
 pub const COMETBFT_DIR: &str = "cometbft";
 /// Chain-specific Namada DB. Nested in chain dirs.
 pub const DB_DIR: &str = "db";
+/// Chain-specific secondary DB instance of the query snapshot. Nested in chain
+/// dirs.
+pub const QUERY_SNAPSHOT_DIR: &str = "db_query_snapshot";
+/// Chain-specific buffer of the quarantined malformed txs. Nested in chain
+/// dirs.
+pub const TX_QUARANTINE_DIR: &str = "tx_quarantine";
+/// The default maximum number of quarantined malformed txs
+pub const DEFAULT_TX_QUARANTINE_CAPACITY: usize = 100;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -45,7 +56,7 @@ pub struct Config {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidatorLocalConfig {
     pub accepted_gas_tokens:
-        HashMap<namada::types::address::Address, namada::types::token::Amount>,
+        HashMap<namada::types::address::Address, token::RawAmount>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -111,6 +122,45 @@ pub struct Shell {
     /// When set, will limit the how many block heights in the past can the
     /// storage be queried for reading values.
     pub storage_read_past_height_limit: Option<u64>,
+    /// When set, queries are served from a read-only snapshot of the storage
+    /// instead of contending with the processing of blocks. The snapshot is
+    /// refreshed once it lags more than the given number of blocks behind
+    /// the last committed block; with `0`, it's refreshed after every block.
+    pub query_snapshot_staleness: Option<u64>,
+    /// The local minimum gas prices per fee token, under which transactions
+    /// are rejected from the mempool. A price is only effective above the
+    /// governance-set gas cost of its token, and only for the tokens allowed
+    /// by governance for fee payment.
+    #[serde(default)]
+    pub minimum_gas_prices: BTreeMap<Address, token::RawAmount>,
+    /// When set, the bytes of the txs that repeatedly fail to be decoded or
+    /// to pass the signature checks in the mempool are quarantined on disk,
+    /// up to the given number of txs, to be inspected with the `ledger
+    /// dump-tx-quarantine` command.
+    #[serde(default)]
+    pub tx_quarantine_capacity: Option<usize>,
+    /// When set, the node is one of the validator nodes of an active-passive
+    /// failover setup, in which only the holder of the leader lease signs.
+    /// Ignored unless the node is started in the validator mode.
+    #[serde(default)]
+    pub validator_failover: Option<ValidatorFailover>,
+    /// When set, the data of the blocks older than the configured number of
+    /// epochs is offloaded from the DB to an archive, from which it's fetched
+    /// back to serve the queries at past heights. Only useful together with
+    /// an unset `storage_read_past_height_limit`.
+    #[serde(default)]
+    pub archive: Option<ArchiveConfig>,
+    /// When set, the CometBFT RPC is also served by a gateway enforcing
+    /// CORS, auth and TLS controls, such that it can be exposed publicly
+    /// without a separate reverse-proxy.
+    #[serde(default)]
+    pub rpc_gateway: Option<RpcGateway>,
+    /// When set, no empty blocks are produced and a block is only proposed
+    /// once there are txs in the mempool. Meant for the single-node
+    /// development chains of `ledger run --dev`, with a short consensus
+    /// `timeout_commit`.
+    #[serde(default)]
+    pub instant_blocks: bool,
     /// Use the [`Ledger::db_dir()`] method to read the value.
     db_dir: PathBuf,
     /// Use the [`Ledger::cometbft_dir()`] method to read the value.
@@ -121,6 +171,108 @@ pub struct Shell {
     pub tendermint_mode: TendermintMode,
 }
 
+/// The config of a validator node in an active-passive failover setup
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValidatorFailover {
+    /// The directory shared by all the validator nodes of the setup, e.g. on
+    /// a network file system, holding the leader lease and the last signing
+    /// state of the validator
+    pub shared_dir: PathBuf,
+    /// The ID of this node, unique amongst the nodes of the setup
+    pub node_id: String,
+    /// The duration of the leader lease. The leader renews it three times
+    /// per duration, and a stand-by node takes over once it has expired.
+    pub lease_duration: DurationSecs,
+}
+
+/// The config of the archive of the data of the old blocks
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    /// The number of past epochs of blocks data kept in the DB. The data of
+    /// the blocks of older epochs is offloaded to the archive.
+    pub offload_after_epochs: u64,
+    /// The maximum number of blocks offloaded after each committed block, for
+    /// the node not to stall while it catches up with a long history.
+    pub blocks_per_commit: u64,
+    /// The object store holding the archived blocks.
+    pub store: ArchiveStore,
+}
+
+/// The object store of the archive of the old blocks
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArchiveStore {
+    /// A local directory, e.g. on a cold storage drive
+    Dir {
+        /// The path of the directory
+        path: PathBuf,
+    },
+    /// An S3-compatible bucket, addressed with path-style requests. The
+    /// credentials of the bucket are read from the
+    /// `NAMADA_ARCHIVE_S3_ACCESS_KEY_ID` and
+    /// `NAMADA_ARCHIVE_S3_SECRET_ACCESS_KEY` env vars.
+    S3 {
+        /// The URL of the S3 endpoint, e.g. `https://s3.amazonaws.com`
+        endpoint: String,
+        /// The region of the bucket
+        region: String,
+        /// The name of the bucket
+        bucket: String,
+        /// The prefix of the names of the archived objects in the bucket
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
+/// The config of the gateway of the CometBFT RPC. The bearer token
+/// authorizing the requests to the protected endpoints is read from the
+/// `NAMADA_RPC_GATEWAY_AUTH_TOKEN` env var. When it's not set, the protected
+/// endpoints are not served by the gateway at all.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcGateway {
+    /// The address on which the gateway listens
+    pub listen_addr: SocketAddr,
+    /// The origins allowed to make cross-origin requests, with `*` allowing
+    /// any origin. When empty, cross-origin requests are not allowed.
+    #[serde(default)]
+    pub cors_allowed_origins: Vec<String>,
+    /// The CometBFT RPC methods requiring the bearer token. A trailing `*`
+    /// matches any method with the given prefix.
+    #[serde(default = "RpcGateway::default_protected_methods")]
+    pub protected_methods: Vec<String>,
+    /// The prefixes of the paths of the ABCI queries requiring the bearer
+    /// token.
+    #[serde(default = "RpcGateway::default_protected_abci_paths")]
+    pub protected_abci_paths: Vec<String>,
+    /// When set, the gateway terminates TLS with the given certificate.
+    #[serde(default)]
+    pub tls: Option<RpcGatewayTls>,
+}
+
+impl RpcGateway {
+    /// The default value of [`RpcGateway::protected_methods`].
+    pub fn default_protected_methods() -> Vec<String> {
+        vec!["dump_consensus_state".to_string(), "unsafe_*".to_string()]
+    }
+
+    /// The default value of [`RpcGateway::protected_abci_paths`].
+    pub fn default_protected_abci_paths() -> Vec<String> {
+        vec![
+            "/shell/dry_run_tx".to_string(),
+            "/shell/mempool_queue".to_string(),
+        ]
+    }
+}
+
+/// The TLS certificate of the gateway of the CometBFT RPC
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcGatewayTls {
+    /// The path of the PEM-encoded certificate chain
+    pub cert_file: PathBuf,
+    /// The path of the PEM-encoded private key
+    pub key_file: PathBuf,
+}
+
 impl Ledger {
     pub fn new(
         base_dir: impl AsRef<Path>,
@@ -143,6 +295,13 @@ impl Ledger {
                 tx_wasm_compilation_cache_bytes: None,
                 // Default corresponds to 1 hour of past blocks at 1 block/sec
                 storage_read_past_height_limit: Some(3600),
+                query_snapshot_staleness: None,
+                minimum_gas_prices: BTreeMap::new(),
+                tx_quarantine_capacity: Some(DEFAULT_TX_QUARANTINE_CAPACITY),
+                validator_failover: None,
+                archive: None,
+                rpc_gateway: None,
+                instant_blocks: false,
                 db_dir: DB_DIR.into(),
                 cometbft_dir: COMETBFT_DIR.into(),
                 action_at_height: None,
@@ -181,6 +340,21 @@ impl Shell {
             .join(chain_id.as_str())
             .join(&self.cometbft_dir)
     }
+
+    /// Get the directory path to the secondary DB instance of the query
+    /// snapshot
+    pub fn query_snapshot_dir(&self, chain_id: &ChainId) -> PathBuf {
+        self.base_dir
+            .join(chain_id.as_str())
+            .join(QUERY_SNAPSHOT_DIR)
+    }
+
+    /// Get the directory path to the buffer of the quarantined malformed txs
+    pub fn tx_quarantine_dir(&self, chain_id: &ChainId) -> PathBuf {
+        self.base_dir
+            .join(chain_id.as_str())
+            .join(TX_QUARANTINE_DIR)
+    }
 }
 
 #[derive(Error, Debug)]
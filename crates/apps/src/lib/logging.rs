@@ -1,8 +1,9 @@
 //! A module for anything related to logging
 use std::env;
+use std::sync::{Mutex, OnceLock};
 
 use color_eyre::eyre::Result;
-use eyre::WrapErr;
+use eyre::{eyre, WrapErr};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_log::LogTracer;
 use tracing_subscriber::filter::{Directive, EnvFilter};
@@ -21,6 +22,15 @@ const ROLLING_ENV_KEY: &str = "NAMADA_LOG_ROLLING";
 
 const LOG_FILE_NAME_PREFIX: &str = "namada.log";
 
+/// Replaces the filter of the global subscriber
+type FilterReload = Box<dyn Fn(EnvFilter) -> Result<()> + Send + Sync>;
+
+/// The reload of the filter of the global subscriber, once it's set
+static FILTER_RELOAD: OnceLock<FilterReload> = OnceLock::new();
+
+/// The directives of the current filter of the global subscriber
+static FILTER_DIRECTIVES: Mutex<String> = Mutex::new(String::new());
+
 #[derive(Clone, Debug)]
 enum Fmt {
     Full,
@@ -58,6 +68,34 @@ pub fn init_log_tracer() -> Result<()> {
     LogTracer::init().wrap_err("Failed to initialize log adapter")
 }
 
+/// The directives of the current filter of the logs.
+pub fn log_filter() -> String {
+    FILTER_DIRECTIVES.lock().unwrap().clone()
+}
+
+/// Change the filter of the logs at runtime. The given directives (e.g.
+/// `namada::ledger::protocol=debug`) are added to the current ones, replacing
+/// the ones with the same targets, or they replace all of them if `replace` is
+/// set. Returns the directives of the new filter.
+pub fn update_log_filter(directives: &str, replace: bool) -> Result<String> {
+    let reload = FILTER_RELOAD
+        .get()
+        .ok_or_else(|| eyre!("The log subscriber is not set"))?;
+    let mut current = FILTER_DIRECTIVES.lock().unwrap();
+    let directives = if replace || current.is_empty() {
+        directives.to_string()
+    } else {
+        format!("{current},{directives}")
+    };
+    let filter = EnvFilter::try_new(directives)
+        .wrap_err("Invalid log filter directives")?;
+    let new_directives = filter.to_string();
+    reload(filter)?;
+    tracing::info!("Changed the log filter to {new_directives}");
+    *current = new_directives.clone();
+    Ok(new_directives)
+}
+
 pub fn set_subscriber(filter: EnvFilter) -> Result<Option<WorkerGuard>> {
     let with_color = if let Ok(val) = env::var(COLOR_ENV_KEY) {
         val.to_ascii_lowercase() != "false"
@@ -74,17 +112,26 @@ pub fn set_subscriber(filter: EnvFilter) -> Result<Option<WorkerGuard>> {
         })
         .unwrap_or_default();
     let log_dir = env::var(DIR_ENV_KEY).ok();
+    *FILTER_DIRECTIVES.lock().unwrap() = filter.to_string();
 
     let builder = Subscriber::builder()
         .with_ansi(with_color)
         .with_env_filter(filter);
 
     // We're using macros here to help as the `format` match arms and `log_dir`
-    // if/else branches have incompatible types.
+    // if/else branches have incompatible types. The reload handle of the
+    // filter is typed after them too, so it's kept behind a closure.
     macro_rules! finish {
         ($($builder:tt)*) => {
             {
-                let my_collector = $($builder)*.finish();
+                let builder = $($builder)*.with_filter_reloading();
+                let handle = builder.reload_handle();
+                let _ = FILTER_RELOAD.set(Box::new(move |filter| {
+                    handle
+                        .reload(filter)
+                        .wrap_err("Failed to reload the log filter")
+                }));
+                let my_collector = builder.finish();
                 tracing::subscriber::set_global_default(my_collector)
                     .wrap_err("Failed to set log subscriber")
             }
@@ -0,0 +1,199 @@
+//! A single-node development chain, run with `ledger run --dev`.
+//!
+//! The chain is initialized in a temporary base directory from the genesis
+//! templates of a local network, with its `validator-0` as the only genesis
+//! validator. A number of accounts are derived from a mnemonic and funded at
+//! genesis, such that the same accounts are available on every run. No empty
+//! blocks are produced: a block is only proposed once there are txs in the
+//! mempool, so that the chain's epochs only advance with the txs.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use namada::bip39::{Language, Mnemonic};
+use namada::types::address::Address;
+use namada::types::chain::ChainIdPrefix;
+use namada::types::key::{RefTo, SchemeType};
+use namada::types::string_encoding::StringEncoded;
+use namada::types::time::DateTimeUtc;
+use namada::types::token;
+use namada_sdk::wallet::alias::Alias;
+use namada_sdk::wallet::DerivationPath;
+use tempfile::TempDir;
+use zeroize::Zeroizing;
+
+use crate::cli::{args, safe_exit};
+use crate::client::utils::{self, PRE_GENESIS_DIR};
+use crate::config::genesis::{self, GenesisAddress};
+use crate::config::global::GlobalConfig;
+use crate::config::{self, Config};
+use crate::facade::tendermint::Timeout;
+use crate::node::ledger;
+use crate::wallet::pre_genesis;
+
+/// The chain ID prefix of the development chains
+const DEV_CHAIN_ID_PREFIX: &str = "dev";
+/// The alias of the genesis validator in the local network templates
+const DEV_VALIDATOR_ALIAS: &str = "validator-0";
+/// The alias of the native token in the genesis templates
+const NATIVE_TOKEN_ALIAS: &str = "NAM";
+/// The native token balance of every derived account, in whole tokens
+const DEV_ACCOUNT_BALANCE: u64 = 1_000_000;
+/// The consensus timeout commit, i.e. the delay between the arrival of txs
+/// and the proposal of their block
+const DEV_TIMEOUT_COMMIT: &str = "100ms";
+
+/// Initialize a development chain and run its node until it's shut down. The
+/// base directory of the chain is removed after that.
+pub fn run(args: args::LedgerDev) {
+    let base_dir = TempDir::new().unwrap_or_else(|err| {
+        eprintln!("Failed to create the base directory of the chain: {err}");
+        safe_exit(1)
+    });
+    let (config, wasm_dir) = init(base_dir.path(), args);
+    ledger::run(config, wasm_dir);
+}
+
+/// Initialize a development chain in the given base directory and return
+/// the config of its node and its WASM directory
+fn init(
+    base_dir: &Path,
+    args::LedgerDev {
+        templates_path,
+        wasm_checksums_path,
+        mnemonic,
+        accounts,
+    }: args::LedgerDev,
+) -> (config::Ledger, PathBuf) {
+    let pre_genesis_dir = templates_path.join("src").join(PRE_GENESIS_DIR);
+    let validator_wallet =
+        pre_genesis::load(&pre_genesis_dir.join(DEV_VALIDATOR_ALIAS))
+            .unwrap_or_else(|err| {
+                eprintln!(
+                    "Error loading the pre-genesis wallet of \
+                     {DEV_VALIDATOR_ALIAS}: {err}"
+                );
+                safe_exit(1)
+            });
+    let mut pre_genesis_wallet = crate::wallet::load(&pre_genesis_dir)
+        .unwrap_or_else(|| {
+            crate::wallet::load_or_new(&base_dir.join(PRE_GENESIS_DIR))
+        });
+
+    // Derive the accounts from the mnemonic
+    let mnemonic = Mnemonic::from_phrase(&mnemonic, Language::English)
+        .unwrap_or_else(|err| {
+            eprintln!("Invalid mnemonic: {err}");
+            safe_exit(1)
+        });
+    let scheme = SchemeType::Ed25519;
+    let dev_accounts: Vec<_> = (0..accounts)
+        .map(|index| {
+            let derivation_path =
+                DerivationPath::from_path_string_for_transparent_scheme(
+                    scheme,
+                    &format!("m/44'/877'/0'/0'/{index}'"),
+                )
+                .expect("The derivation path must be valid");
+            let (alias, sk) = pre_genesis_wallet
+                .derive_store_key_from_mnemonic_code(
+                    scheme,
+                    Some(format!("dev-{index}")),
+                    true,
+                    derivation_path,
+                    Some((mnemonic.clone(), Zeroizing::new(String::new()))),
+                    None,
+                )
+                .expect("Failed to derive a key from the mnemonic");
+            (alias, sk.ref_to())
+        })
+        .collect();
+
+    // Fund the accounts at genesis
+    let mut templates =
+        genesis::templates::All::read_toml_files(&templates_path)
+            .unwrap_or_else(|err| {
+                eprintln!(
+                    "Failed to read the genesis templates from {}: {err}",
+                    templates_path.to_string_lossy()
+                );
+                safe_exit(1)
+            });
+    let native_balances = templates
+        .balances
+        .token
+        .get_mut(&Alias::from(NATIVE_TOKEN_ALIAS))
+        .unwrap_or_else(|| {
+            eprintln!("No {NATIVE_TOKEN_ALIAS} balances in the templates");
+            safe_exit(1)
+        });
+    for (_alias, pk) in &dev_accounts {
+        native_balances.0.insert(
+            GenesisAddress::PublicKey(StringEncoded::new(pk.clone())),
+            token::DenominatedAmount::native(token::Amount::native_whole(
+                DEV_ACCOUNT_BALANCE,
+            )),
+        );
+    }
+    let dev_templates_path = base_dir.join("templates");
+    std::fs::create_dir_all(&dev_templates_path).unwrap();
+    templates.write_toml_files(&dev_templates_path).unwrap();
+
+    // Initialize the chain and configure its validator node
+    let global_args = args::Global {
+        is_pre_genesis: true,
+        chain_id: None,
+        base_dir: base_dir.to_path_buf(),
+        wasm_dir: None,
+        pretty_amounts: false,
+    };
+    utils::init_network(
+        global_args,
+        args::InitNetwork {
+            templates_path: dev_templates_path,
+            wasm_checksums_path,
+            chain_id_prefix: ChainIdPrefix::from_str(DEV_CHAIN_ID_PREFIX)
+                .unwrap(),
+            genesis_time: DateTimeUtc::now(),
+            consensus_timeout_commit: Timeout::from_str(DEV_TIMEOUT_COMMIT)
+                .unwrap(),
+            dont_archive: true,
+            archive_dir: None,
+        },
+    );
+    let chain_id = GlobalConfig::read(base_dir)
+        .ok()
+        .and_then(|global_config| global_config.default_chain_id)
+        .expect("The chain must have been initialized");
+    let chain_dir = base_dir.join(chain_id.as_str());
+    let genesis = genesis::chain::Finalized::read_toml_files(&chain_dir)
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to read the finalized genesis: {err}");
+            safe_exit(1)
+        });
+    utils::configure_node(
+        base_dir,
+        &chain_dir,
+        &genesis,
+        Some(pre_genesis_wallet),
+        Some((Alias::from(DEV_VALIDATOR_ALIAS), validator_wallet)),
+        false,
+    );
+
+    let mut config = Config::load(base_dir, &chain_id, None);
+    config.ledger.shell.instant_blocks = true;
+    config.write(base_dir, &chain_id, true).unwrap();
+
+    println!("Development chain {chain_id} with the pre-funded accounts:");
+    for (alias, pk) in &dev_accounts {
+        println!("  {alias}: {}", Address::from(pk));
+    }
+    println!(
+        "Use the client with `--base-dir {}`. The base directory is removed \
+         once the node is shut down.",
+        base_dir.to_string_lossy()
+    );
+
+    let wasm_dir = config.ledger.chain_dir().join(&config.wasm_dir);
+    (config.ledger, wasm_dir)
+}
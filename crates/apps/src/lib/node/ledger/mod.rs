@@ -1,8 +1,11 @@
 mod abortable;
 mod broadcaster;
+pub mod dev;
 pub mod ethereum_oracle;
+pub mod rpc_gateway;
 pub mod shell;
 pub mod shims;
+pub mod signing_state;
 pub mod storage;
 pub mod tendermint_node;
 
@@ -27,6 +30,7 @@ use tower::ServiceBuilder;
 
 use self::abortable::AbortableSpawner;
 use self::ethereum_oracle::last_processed_block;
+use self::shell::tx_quarantine::TxQuarantine;
 use self::shell::EthereumOracleChannels;
 use self::shims::abcipp_shim::AbciService;
 use crate::cli::args;
@@ -224,6 +228,45 @@ pub fn dump_db(
     db.dump_block(out_file_path, historic, block_height);
 }
 
+/// Dump the malformed txs quarantined by the mempool, decoding them where
+/// possible
+pub fn dump_tx_quarantine(config: config::Ledger) -> std::io::Result<()> {
+    use data_encoding::HEXUPPER;
+    use namada::tx::Tx;
+
+    let quarantine = TxQuarantine::new(
+        config.shell.tx_quarantine_dir(&config.chain_id),
+        config
+            .shell
+            .tx_quarantine_capacity
+            .unwrap_or(config::DEFAULT_TX_QUARANTINE_CAPACITY),
+    );
+    let entries = quarantine.entries()?;
+    if entries.is_empty() {
+        println!("No quarantined txs.");
+    }
+    for (hash, entry) in entries {
+        println!("Quarantined tx {hash}:");
+        println!(
+            "  Failures: {}, first seen: {}, last seen: {}",
+            entry.failures, entry.first_seen, entry.last_seen
+        );
+        println!("  Last failure: {}", entry.reason);
+        println!("  Size: {} bytes", entry.bytes.len());
+        match Tx::try_from(entry.bytes.as_slice()) {
+            Ok(tx) => {
+                let header = serde_json::to_string_pretty(&tx.header)
+                    .unwrap_or_else(|err| err.to_string());
+                println!("  Decoded header: {header}");
+                println!("  Sections: {}", tx.sections.len());
+            }
+            Err(err) => println!("  Decoding failed: {err}"),
+        }
+        println!("  Bytes: {}", HEXUPPER.encode(&entry.bytes));
+    }
+    Ok(())
+}
+
 /// Roll Namada state back to the previous height
 pub fn rollback(config: config::Ledger) -> Result<(), shell::Error> {
     shell::rollback(config)
@@ -257,6 +300,9 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
     // Start Tendermint node
     let tendermint_node = start_tendermint(&mut spawner, &config);
 
+    // Start the RPC gateway if configured
+    let rpc_gateway = maybe_start_rpc_gateway(&mut spawner, &config);
+
     // Start oracle if necessary
     let (eth_oracle_channels, eth_oracle) =
         match maybe_start_ethereum_oracle(&mut spawner, &config).await {
@@ -284,10 +330,16 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
     let aborted = spawner.wait_for_abort().await.child_terminated();
 
     // Wait for all managed tasks to finish.
-    let res = tokio::try_join!(tendermint_node, abci, eth_oracle, broadcaster);
+    let res = tokio::try_join!(
+        tendermint_node,
+        abci,
+        eth_oracle,
+        broadcaster,
+        rpc_gateway
+    );
 
     match res {
-        Ok((tendermint_res, abci_res, _, _)) => {
+        Ok((tendermint_res, abci_res, _, _, _)) => {
             // we ignore errors on user-initiated shutdown
             if aborted {
                 if let Err(err) = tendermint_res {
@@ -785,6 +837,58 @@ pub fn test_genesis_files(
 
 /// Spawn a dummy asynchronous task into the runtime,
 /// which will resolve instantly.
+/// Launches the gateway of the CometBFT RPC, if it's configured.
+fn maybe_start_rpc_gateway(
+    spawner: &mut AbortableSpawner,
+    config: &config::Ledger,
+) -> task::JoinHandle<()> {
+    let Some(gateway_config) = config.shell.rpc_gateway.clone() else {
+        return spawn_dummy_task(());
+    };
+    let rpc_addr = convert_tm_addr_to_socket_addr(&config.cometbft.rpc.laddr);
+    let instrumentation = &config.cometbft.instrumentation;
+    let metrics_addr = if instrumentation.prometheus {
+        // The host of the Prometheus listen address is optional
+        let listen_addr = &instrumentation.prometheus_listen_addr;
+        let listen_addr = if listen_addr.starts_with(':') {
+            format!("127.0.0.1{listen_addr}")
+        } else {
+            listen_addr.clone()
+        };
+        match listen_addr.parse::<SocketAddr>() {
+            Ok(addr) => Some(addr),
+            Err(err) => {
+                tracing::warn!(
+                    %err,
+                    %listen_addr,
+                    "Invalid Prometheus listen address, the metrics will \
+                     not be served by the RPC gateway"
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let (abort_send, abort_recv) = tokio::sync::oneshot::channel::<()>();
+
+    spawner
+        .spawn_abortable("RPC gateway", move |aborter| async move {
+            rpc_gateway::serve(
+                gateway_config,
+                rpc_addr,
+                metrics_addr,
+                abort_recv,
+            )
+            .await;
+
+            drop(aborter);
+        })
+        .with_cleanup(async move {
+            let _ = abort_send.send(());
+        })
+}
+
 fn spawn_dummy_task<T: Send + 'static>(ready: T) -> task::JoinHandle<T> {
     tokio::spawn(async { std::future::ready(ready).await })
 }
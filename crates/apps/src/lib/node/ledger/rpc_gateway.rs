@@ -0,0 +1,478 @@
+//! A gateway of the CometBFT RPC, enforcing CORS, auth and TLS controls.
+//!
+//! The requests are forwarded to the RPC of the local CometBFT node as is,
+//! except for the ones to the protected endpoints, which require a bearer
+//! token. These are the protected CometBFT methods, the ABCI queries of the
+//! protected paths (e.g. the dry-runs of txs) and the `/metrics` endpoint,
+//! which is forwarded to the Prometheus exporter of the node. The websocket
+//! endpoint is not served by the gateway.
+//!
+//! The gateway also serves the `/admin/log_filter` endpoint, protected the
+//! same way, to change the log filter of the node at runtime:
+//!
+//! - `GET` returns the current filter directives
+//! - `POST` adds the directives of the body (e.g.
+//!   `namada::ledger::protocol=debug`) to the current ones
+//! - `PUT` replaces the current directives with the ones of the body
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use serde_json::Value;
+use tokio::sync::oneshot;
+use warp::http::{header, HeaderMap, Method, StatusCode};
+use warp::path::FullPath;
+use warp::Filter;
+
+use crate::config::RpcGateway;
+use crate::logging;
+
+/// Env. var to set the bearer token of the protected endpoints
+pub const ENV_VAR_RPC_GATEWAY_AUTH_TOKEN: &str =
+    "NAMADA_RPC_GATEWAY_AUTH_TOKEN";
+
+/// The path of the metrics endpoint
+const METRICS_PATH: &str = "/metrics";
+/// The path of the admin endpoint of the log filter
+const LOG_FILTER_PATH: &str = "/admin/log_filter";
+
+/// The state shared by the handlers of the gateway.
+struct Gateway {
+    client: reqwest::Client,
+    rpc_addr: SocketAddr,
+    metrics_addr: Option<SocketAddr>,
+    auth_token: Option<String>,
+    protected_methods: Vec<String>,
+    protected_abci_paths: Vec<String>,
+}
+
+/// The result of the authorization of a request.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Auth {
+    Allowed,
+    Unauthorized,
+    Forbidden,
+}
+
+/// Serves the gateway until a signal is received on `abort_recv`.
+pub async fn serve(
+    config: RpcGateway,
+    rpc_addr: SocketAddr,
+    metrics_addr: Option<SocketAddr>,
+    abort_recv: oneshot::Receiver<()>,
+) {
+    let auth_token = std::env::var(ENV_VAR_RPC_GATEWAY_AUTH_TOKEN)
+        .ok()
+        .filter(|token| !token.is_empty());
+    if auth_token.is_none() {
+        tracing::warn!(
+            "{ENV_VAR_RPC_GATEWAY_AUTH_TOKEN} is not set, the protected RPC \
+             endpoints will not be served by the gateway"
+        );
+    }
+    let gateway = Arc::new(Gateway {
+        client: reqwest::Client::new(),
+        rpc_addr,
+        metrics_addr,
+        auth_token,
+        protected_methods: config.protected_methods,
+        protected_abci_paths: config.protected_abci_paths,
+    });
+
+    let cors = if config.cors_allowed_origins.iter().any(|o| o == "*") {
+        warp::cors().allow_any_origin()
+    } else {
+        warp::cors().allow_origins(
+            config.cors_allowed_origins.iter().map(String::as_str),
+        )
+    }
+    .allow_methods([Method::GET, Method::POST, Method::PUT])
+    .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+
+    let routes = warp::path::full()
+        .and(warp::method())
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(warp::header::headers_cloned())
+        .and(warp::body::bytes())
+        .and_then(
+            move |path: FullPath,
+                  method: Method,
+                  query: String,
+                  headers: HeaderMap,
+                  body: Bytes| {
+                let gateway = gateway.clone();
+                async move {
+                    Ok::<_, Infallible>(
+                        gateway
+                            .handle(
+                                path.as_str(),
+                                method,
+                                &query,
+                                &headers,
+                                body,
+                            )
+                            .await,
+                    )
+                }
+            },
+        )
+        .with(cors);
+
+    let listen_addr = config.listen_addr;
+    let shutdown = async move {
+        let _ = abort_recv.await;
+    };
+    tracing::info!(
+        ?listen_addr,
+        tls = config.tls.is_some(),
+        "Starting the RPC gateway"
+    );
+    match config.tls {
+        Some(tls) => {
+            let (_, server) = warp::serve(routes)
+                .tls()
+                .cert_path(tls.cert_file)
+                .key_path(tls.key_file)
+                .bind_with_graceful_shutdown(listen_addr, shutdown);
+            server.await
+        }
+        None => {
+            let (_, server) = warp::serve(routes)
+                .bind_with_graceful_shutdown(listen_addr, shutdown);
+            server.await
+        }
+    }
+    tracing::info!("RPC gateway is no longer running.");
+}
+
+impl Gateway {
+    /// Authorizes and forwards a request.
+    async fn handle(
+        &self,
+        path: &str,
+        method: Method,
+        query: &str,
+        headers: &HeaderMap,
+        body: Bytes,
+    ) -> warp::http::Response<Bytes> {
+        if path == LOG_FILTER_PATH {
+            return self.handle_log_filter(method, headers, &body);
+        }
+        let (target_addr, protected) = if path == METRICS_PATH {
+            match self.metrics_addr {
+                Some(addr) => (addr, true),
+                None => {
+                    return reply(
+                        StatusCode::NOT_FOUND,
+                        "The metrics are not enabled",
+                    );
+                }
+            }
+        } else {
+            (self.rpc_addr, self.is_protected(path, query, &body))
+        };
+        if protected {
+            if let Err(response) = self.check_auth(headers) {
+                return response;
+            }
+        }
+
+        let mut url = format!("http://{target_addr}{path}");
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(query);
+        }
+        let mut request = self.client.request(method, url).body(body);
+        if let Some(content_type) = headers.get(header::CONTENT_TYPE) {
+            request = request.header(header::CONTENT_TYPE, content_type);
+        }
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(err) => {
+                tracing::debug!(%err, "Failed to forward an RPC request");
+                return reply(StatusCode::BAD_GATEWAY, "The node is down");
+            }
+        };
+        let status = response.status();
+        let content_type =
+            response.headers().get(header::CONTENT_TYPE).cloned();
+        let body = match response.bytes().await {
+            Ok(body) => body,
+            Err(err) => {
+                tracing::debug!(%err, "Failed to read an RPC response");
+                return reply(StatusCode::BAD_GATEWAY, "The node is down");
+            }
+        };
+        let mut builder = warp::http::Response::builder().status(status);
+        if let Some(content_type) = content_type {
+            builder = builder.header(header::CONTENT_TYPE, content_type);
+        }
+        builder.body(body).expect("The response should be valid")
+    }
+
+    /// Gets or changes the log filter of the node.
+    fn handle_log_filter(
+        &self,
+        method: Method,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> warp::http::Response<Bytes> {
+        if let Err(response) = self.check_auth(headers) {
+            return response;
+        }
+        let replace = match method {
+            Method::GET => {
+                return reply(StatusCode::OK, logging::log_filter());
+            }
+            Method::POST => false,
+            Method::PUT => true,
+            _ => {
+                return reply(
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    "Expected a GET, POST or PUT request",
+                );
+            }
+        };
+        let Ok(directives) = std::str::from_utf8(body) else {
+            return reply(
+                StatusCode::BAD_REQUEST,
+                "The directives must be UTF-8",
+            );
+        };
+        match logging::update_log_filter(directives.trim(), replace) {
+            Ok(directives) => reply(StatusCode::OK, directives),
+            Err(err) => reply(StatusCode::BAD_REQUEST, format!("{err:#}")),
+        }
+    }
+
+    /// Responds with an error if a request to a protected endpoint isn't
+    /// authorized.
+    #[allow(clippy::result_large_err)]
+    fn check_auth(
+        &self,
+        headers: &HeaderMap,
+    ) -> Result<(), warp::http::Response<Bytes>> {
+        match self.authorize(headers) {
+            Auth::Allowed => Ok(()),
+            Auth::Unauthorized => Err(reply(
+                StatusCode::UNAUTHORIZED,
+                "A valid bearer token is required",
+            )),
+            Auth::Forbidden => {
+                Err(reply(StatusCode::FORBIDDEN, "The endpoint is not served"))
+            }
+        }
+    }
+
+    /// Checks the bearer token of a request to a protected endpoint.
+    fn authorize(&self, headers: &HeaderMap) -> Auth {
+        let Some(token) = self.auth_token.as_ref() else {
+            return Auth::Forbidden;
+        };
+        let provided = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        match provided {
+            Some(provided) if constant_time_eq(provided, token) => {
+                Auth::Allowed
+            }
+            _ => Auth::Unauthorized,
+        }
+    }
+
+    /// Checks if a request to the CometBFT RPC targets a protected endpoint,
+    /// either with the URI of the method or with a JSON-RPC body.
+    fn is_protected(&self, path: &str, query: &str, body: &[u8]) -> bool {
+        let method = path.trim_matches('/');
+        if !method.is_empty() {
+            if method == "abci_query" {
+                let abci_path = form_urlencoded_param(query, "path")
+                    .map(|path| path.trim_matches('"').to_string());
+                return abci_path.map_or(false, |abci_path| {
+                    self.is_protected_abci_path(&abci_path)
+                });
+            }
+            return self.is_protected_method(method);
+        }
+        if body.is_empty() {
+            return false;
+        }
+        // A batch of JSON-RPC calls is protected if any of them is
+        match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(calls)) => {
+                calls.iter().any(|call| self.is_protected_call(call))
+            }
+            Ok(call) => self.is_protected_call(&call),
+            // Malformed bodies are rejected by the node
+            Err(_) => false,
+        }
+    }
+
+    /// Checks if a JSON-RPC call targets a protected endpoint.
+    fn is_protected_call(&self, call: &Value) -> bool {
+        let Some(method) = call.get("method").and_then(Value::as_str) else {
+            return false;
+        };
+        if method == "abci_query" {
+            return call
+                .get("params")
+                .and_then(|params| params.get("path"))
+                .and_then(Value::as_str)
+                .map_or(false, |path| self.is_protected_abci_path(path));
+        }
+        self.is_protected_method(method)
+    }
+
+    fn is_protected_method(&self, method: &str) -> bool {
+        self.protected_methods.iter().any(|protected| {
+            match protected.strip_suffix('*') {
+                Some(prefix) => method.starts_with(prefix),
+                None => method == protected,
+            }
+        })
+    }
+
+    fn is_protected_abci_path(&self, path: &str) -> bool {
+        self.protected_abci_paths
+            .iter()
+            .any(|prefix| path.starts_with(prefix.as_str()))
+    }
+}
+
+/// Builds a plain text response.
+fn reply(
+    status: StatusCode,
+    msg: impl Into<Bytes>,
+) -> warp::http::Response<Bytes> {
+    warp::http::Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body(msg.into())
+        .expect("The response should be valid")
+}
+
+/// Finds the decoded value of a parameter of a query string.
+fn form_urlencoded_param(query: &str, name: &str) -> Option<String> {
+    let url =
+        reqwest::Url::parse(&format!("http://localhost/?{query}")).ok()?;
+    url.query_pairs()
+        .find_map(|(key, value)| (key == name).then(|| value.into_owned()))
+}
+
+/// Compares the tokens without short-circuiting on the first difference.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use warp::http::HeaderValue;
+
+    use super::*;
+
+    fn gateway(auth_token: Option<&str>) -> Gateway {
+        Gateway {
+            client: reqwest::Client::new(),
+            rpc_addr: "127.0.0.1:26657".parse().unwrap(),
+            metrics_addr: None,
+            auth_token: auth_token.map(str::to_string),
+            protected_methods: RpcGateway::default_protected_methods(),
+            protected_abci_paths: RpcGateway::default_protected_abci_paths(),
+        }
+    }
+
+    /// Test that the protected endpoints are found in the URIs and in the
+    /// JSON-RPC bodies of the requests.
+    #[test]
+    fn test_protected_endpoints() {
+        let gateway = gateway(None);
+        // URI requests
+        assert!(gateway.is_protected("/dump_consensus_state", "", &[]));
+        assert!(gateway.is_protected("/unsafe_flush_mempool", "", &[]));
+        assert!(!gateway.is_protected("/status", "", &[]));
+        assert!(gateway.is_protected(
+            "/abci_query",
+            "path=%22%2Fshell%2Fdry_run_tx%22&data=0x00",
+            &[]
+        ));
+        assert!(!gateway.is_protected(
+            "/abci_query",
+            "path=%22%2Fshell%2Fepoch%22",
+            &[]
+        ));
+        // The whole mempool is only served to the operator, unlike the txs
+        // of a fee payer
+        assert!(gateway.is_protected(
+            "/abci_query",
+            "path=%22%2Fshell%2Fmempool_queue%22",
+            &[]
+        ));
+        assert!(!gateway.is_protected(
+            "/abci_query",
+            "path=%22%2Fshell%2Fmempool%2Ftnam1q%22",
+            &[]
+        ));
+        // JSON-RPC requests
+        let call = br#"{"jsonrpc":"2.0","id":0,"method":"abci_query",
+            "params":{"path":"/shell/dry_run_tx","data":"00"}}"#;
+        assert!(gateway.is_protected("/", "", call));
+        let call = br#"{"jsonrpc":"2.0","id":0,"method":"status"}"#;
+        assert!(!gateway.is_protected("/", "", call));
+        let batch = br#"[{"jsonrpc":"2.0","id":0,"method":"status"},
+            {"jsonrpc":"2.0","id":1,"method":"unsafe_dial_peers"}]"#;
+        assert!(gateway.is_protected("/", "", batch));
+        assert!(!gateway.is_protected("/", "", b"not json"));
+    }
+
+    /// Test the authorization of the requests to the protected endpoints.
+    #[test]
+    fn test_authorize() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(gateway(None).authorize(&headers), Auth::Forbidden);
+
+        let gateway = gateway(Some("secret"));
+        assert_eq!(gateway.authorize(&headers), Auth::Unauthorized);
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer wrong!"),
+        );
+        assert_eq!(gateway.authorize(&headers), Auth::Unauthorized);
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret"),
+        );
+        assert_eq!(gateway.authorize(&headers), Auth::Allowed);
+    }
+
+    /// Test that the log filter endpoint requires the bearer token and
+    /// rejects invalid requests.
+    #[test]
+    fn test_log_filter_endpoint() {
+        let gateway = gateway(Some("secret"));
+        let mut headers = HeaderMap::new();
+        let response = gateway.handle_log_filter(Method::GET, &headers, &[]);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response =
+            gateway.handle_log_filter(Method::POST, &headers, b"namada=debug");
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret"),
+        );
+        let response = gateway.handle_log_filter(Method::GET, &headers, &[]);
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = gateway.handle_log_filter(Method::DELETE, &headers, &[]);
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        let response =
+            gateway.handle_log_filter(Method::PUT, &headers, b"\xff");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,95 @@
+//! The double-entry events of the balances changed while finalizing a block.
+//!
+//! The shell records a step after every part of a block that can change
+//! balances: the epoch transition hooks, the txs and the payments of their
+//! fees. The balances written since the last step are compared with their
+//! values as of the last step, and their changes are paired as ledger entries
+//! that are emitted as events with the reason of the step. The events of a
+//! block are added to its response once all its steps are recorded.
+
+use std::collections::HashMap;
+
+use namada::ledger::events::balance::{ledger_entries, BalanceChangeReason};
+use namada::ledger::events::EventLevel;
+use namada::token::storage_key::is_any_token_balance_key;
+
+use super::*;
+
+/// The balances changed in the block being finalized
+#[derive(Debug, Default)]
+pub struct BalanceEvents {
+    /// The balances as of the last step, by balance key
+    balances: HashMap<Key, token::Amount>,
+    /// The id of the next ledger entry of the block
+    next_entry: u64,
+    /// The events of the block
+    events: Vec<Event>,
+}
+
+impl BalanceEvents {
+    /// Take the events of the block and forget its balances, for the next
+    /// block
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(self).events
+    }
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Record the balances changed since the last step of the block being
+    /// finalized, as a step with the given reason. The events are tx events
+    /// if the hash of the tx that made the step is given. Must be called once
+    /// the write log of the current tx has been committed or dropped.
+    pub(super) fn record_balance_changes(
+        &mut self,
+        reason: BalanceChangeReason,
+        tx_hash: Option<&str>,
+    ) {
+        let keys = self.wl_storage.write_log.take_balance_keys();
+        let mut changes = Vec::with_capacity(keys.len());
+        for key in keys {
+            let [token, owner] = is_any_token_balance_key(&key)
+                .expect("Only balance keys should be recorded");
+            let pre = match self.balance_events.balances.get(&key) {
+                Some(balance) => *balance,
+                None => {
+                    let (value, _gas) = self
+                        .wl_storage
+                        .storage
+                        .read(&key)
+                        .expect("Must be able to read a committed balance");
+                    value
+                        .map(|bytes| {
+                            token::Amount::try_from_slice(&bytes)
+                                .expect("Balances must be decodable")
+                        })
+                        .unwrap_or_default()
+                }
+            };
+            let post = self
+                .wl_storage
+                .read::<token::Amount>(&key)
+                .expect("Must be able to read a balance")
+                .unwrap_or_default();
+            changes.push((token.clone(), owner.clone(), pre, post));
+            self.balance_events.balances.insert(key, post);
+        }
+
+        let height = self.wl_storage.storage.block.height;
+        for entry in ledger_entries(changes) {
+            let id = self.balance_events.next_entry;
+            self.balance_events.next_entry += 1;
+            for mut event in entry.to_events(id, reason) {
+                event["height"] = height.to_string();
+                if let Some(hash) = tx_hash {
+                    event.level = EventLevel::Tx;
+                    event["hash"] = hash.to_string();
+                }
+                self.balance_events.events.push(event);
+            }
+        }
+    }
+}
@@ -16,12 +16,17 @@
 //! In the current implementation, we allocate space for transactions
 //! in the following order of preference:
 //!
+//! - Before anything else, we reserve a lane of 1/3 of the total block space
+//!   for protocol txs (see [`PROTOCOL_TXS_LANE`]). Neither DKG encrypted nor
+//!   decrypted txs can use the space of this lane, such that user txs can't
+//!   crowd out the vote extensions of validators.
 //! - First, we allot space for DKG encrypted txs. We allow DKG encrypted txs to
 //!   take up at most 1/3 of the total block space.
 //! - Next, we allot space for DKG decrypted txs. Decrypted txs take up as much
-//!   space as needed. We will see, shortly, why in practice this is fine.
-//! - Finally, we allot space for protocol txs. Protocol txs get half of the
-//!   remaining block space allotted to them.
+//!   space as needed, outside of the lane of protocol txs. We will see,
+//!   shortly, why in practice this is fine.
+//! - Finally, we allot space for protocol txs. Protocol txs get all of the
+//!   remaining block space allotted to them, which includes their lane.
 //!
 //! Since at some fixed height `H` decrypted txs only take up as
 //! much space as the encrypted txs from height `H - 1`, and we
@@ -61,6 +66,9 @@ use namada::state::{self, WlStorage};
 #[allow(unused_imports)]
 use crate::facade::tendermint_proto::abci::RequestPrepareProposal;
 
+/// The portion of the block space reserved for protocol txs
+pub const PROTOCOL_TXS_LANE: threshold::Threshold = threshold::ONE_THIRD;
+
 /// Block allocation failure status responses.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum AllocFailure {
@@ -135,6 +143,9 @@ pub struct BlockAllocator<State> {
     block: TxBin<BlockSpace>,
     /// The current space utilized by protocol transactions.
     protocol_txs: TxBin<BlockSpace>,
+    /// The space reserved for protocol transactions, which DKG encrypted and
+    /// decrypted transactions may not use.
+    protocol_txs_lane: u64,
     /// The current space and gas utilized by DKG encrypted transactions.
     encrypted_txs: EncryptedTxsBins,
     /// The current space utilized by DKG decrypted transactions.
@@ -170,6 +181,7 @@ impl<M> BlockAllocator<states::BuildingEncryptedTxBatch<M>> {
             _state: PhantomData,
             block: TxBin::init(max),
             protocol_txs: TxBin::default(),
+            protocol_txs_lane: PROTOCOL_TXS_LANE.over(max),
             encrypted_txs: EncryptedTxsBins::new(max, max_block_gas),
             decrypted_txs: TxBin::default(),
         }
@@ -190,6 +202,30 @@ impl<State> BlockAllocator<State> {
             + self.decrypted_txs.allotted;
         self.block.allotted - total_bin_space
     }
+
+    /// Return the amount of space left to initialize in all [`TxBin`]
+    /// instances, outside of the lane reserved for protocol txs.
+    #[inline]
+    fn unreserved_space_in_bytes(&self) -> u64 {
+        let reserved = self.protocol_txs_lane.saturating_sub(
+            // the space already allotted to protocol txs counts
+            // towards their lane
+            self.protocol_txs.allotted,
+        );
+        self.uninitialized_space_in_bytes().saturating_sub(reserved)
+    }
+}
+
+/// Usage of the block space by protocol transactions.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ProtocolTxsSpace {
+    /// The space utilized by protocol txs.
+    pub occupied: u64,
+    /// The space allotted to protocol txs.
+    pub allotted: u64,
+    /// The space of the lane reserved for protocol txs, included in the
+    /// allotted space.
+    pub reserved: u64,
 }
 
 /// Allotted resource for a batch of transactions of the same kind in some
@@ -375,8 +411,12 @@ mod tests {
         // the total space we actually used up
         assert_eq!(alloc.encrypted_txs.space.allotted, 18);
 
-        // check that the allotted space for decrypted txs is correct
-        assert_eq!(alloc.decrypted_txs.allotted, BLOCK_SIZE - 18);
+        // check that the allotted space for decrypted txs is correct,
+        // excluding the lane of protocol txs
+        assert_eq!(
+            alloc.decrypted_txs.allotted,
+            BLOCK_SIZE - 18 - PROTOCOL_TXS_LANE.over(BLOCK_SIZE)
+        );
 
         // add about ~1/3 worth of decrypted txs
         assert!(alloc.try_alloc(&[0; 17]).is_ok());
@@ -397,6 +437,41 @@ mod tests {
         );
     }
 
+    /// Check that decrypted txs can't use the lane reserved for protocol
+    /// txs, but protocol txs can use the space left by decrypted txs.
+    #[test]
+    fn test_protocol_txs_lane() {
+        const BLOCK_SIZE: u64 = 60;
+        const BLOCK_GAS: u64 = 1_000;
+        let lane = PROTOCOL_TXS_LANE.over(BLOCK_SIZE);
+
+        let alloc = BsaNoWrapperTxs::init(BLOCK_SIZE, BLOCK_GAS);
+        let mut alloc = alloc.next_state();
+
+        // decrypted txs can take all the space outside of the lane
+        assert_eq!(alloc.decrypted_txs.allotted, BLOCK_SIZE - lane);
+        assert_matches!(
+            alloc.try_alloc(&vec![0; (BLOCK_SIZE - lane) as usize + 1][..]),
+            Err(AllocFailure::OverflowsBin { .. })
+        );
+        assert!(alloc.try_alloc(&[0; 10]).is_ok());
+
+        // protocol txs get their lane and the space left by decrypted txs
+        let mut alloc = alloc.next_state();
+        assert_eq!(
+            alloc.protocol_txs_space(),
+            ProtocolTxsSpace {
+                occupied: 0,
+                allotted: BLOCK_SIZE - 10,
+                reserved: lane,
+            }
+        );
+        assert!(alloc
+            .try_alloc(&vec![0; (BLOCK_SIZE - 10) as usize][..])
+            .is_ok());
+        assert_eq!(alloc.protocol_txs_space().occupied, BLOCK_SIZE - 10);
+    }
+
     // Test that we cannot include encrypted txs in a block
     // when the state invariants banish them from inclusion.
     #[test]
@@ -24,7 +24,8 @@ impl NextStateImpl for BlockAllocator<BuildingDecryptedTxBatch> {
     fn next_state_impl(mut self) -> Self::Next {
         self.decrypted_txs.shrink_to_fit();
 
-        // the remaining space is allocated to protocol txs
+        // the remaining space, including the lane reserved for
+        // them, is allocated to protocol txs
         let remaining_free_space = self.uninitialized_space_in_bytes();
         self.protocol_txs = TxBin::init(remaining_free_space);
 
@@ -32,6 +33,7 @@ impl NextStateImpl for BlockAllocator<BuildingDecryptedTxBatch> {
         let Self {
             block,
             protocol_txs,
+            protocol_txs_lane,
             encrypted_txs,
             decrypted_txs,
             ..
@@ -41,6 +43,7 @@ impl NextStateImpl for BlockAllocator<BuildingDecryptedTxBatch> {
             _state: PhantomData,
             block,
             protocol_txs,
+            protocol_txs_lane,
             encrypted_txs,
             decrypted_txs,
         }
@@ -65,16 +65,18 @@ fn next_state<Mode>(
 ) -> BlockAllocator<BuildingDecryptedTxBatch> {
     alloc.encrypted_txs.space.shrink_to_fit();
 
-    // decrypted txs can use as much space as they need - which
-    // in practice will only be, at most, 1/3 of the block space
-    // used by encrypted txs at the prev height
-    let remaining_free_space = alloc.uninitialized_space_in_bytes();
+    // decrypted txs can use as much space as they need outside
+    // of the lane of protocol txs - which in practice will only
+    // be, at most, 1/3 of the block space used by encrypted txs
+    // at the prev height
+    let remaining_free_space = alloc.unreserved_space_in_bytes();
     alloc.decrypted_txs = TxBin::init(remaining_free_space);
 
     // cast state
     let BlockAllocator {
         block,
         protocol_txs,
+        protocol_txs_lane,
         encrypted_txs,
         decrypted_txs,
         ..
@@ -84,6 +86,7 @@ fn next_state<Mode>(
         _state: PhantomData,
         block,
         protocol_txs,
+        protocol_txs_lane,
         encrypted_txs,
         decrypted_txs,
     }
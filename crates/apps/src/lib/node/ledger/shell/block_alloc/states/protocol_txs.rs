@@ -1,6 +1,18 @@
-use super::super::{AllocFailure, BlockAllocator};
+use super::super::{AllocFailure, BlockAllocator, ProtocolTxsSpace};
 use super::{BuildingProtocolTxBatch, TryAlloc};
 
+impl BlockAllocator<BuildingProtocolTxBatch> {
+    /// Return the usage of the block space by protocol txs.
+    #[inline]
+    pub fn protocol_txs_space(&self) -> ProtocolTxsSpace {
+        ProtocolTxsSpace {
+            occupied: self.protocol_txs.occupied,
+            allotted: self.protocol_txs.allotted,
+            reserved: self.protocol_txs_lane,
+        }
+    }
+}
+
 impl TryAlloc for BlockAllocator<BuildingProtocolTxBatch> {
     type Resources<'tx> = &'tx [u8];
 
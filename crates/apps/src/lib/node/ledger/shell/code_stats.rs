@@ -0,0 +1,258 @@
+//! Statistics of the executions of the tx and VP codes, kept in the memory of
+//! the node.
+//!
+//! For every code hash, the gas used by its last [`WINDOW_SIZE`] executions
+//! and whether they failed are kept to compute rolling statistics, served by
+//! the `/shell/code_stats` query. At most [`MAX_TRACKED_CODES`] codes of each
+//! kind are tracked, the least recently executed ones are forgotten first.
+
+use std::collections::{HashMap, VecDeque};
+
+use namada::ledger::gas::Gas;
+use namada::ledger::queries::{CodeStats, ExecutionStats};
+use namada::tx::data::TxResult;
+use namada::types::hash::Hash;
+use namada::types::storage::BlockHeight;
+
+/// The number of last executions of a code the statistics are computed over
+pub const WINDOW_SIZE: usize = 1000;
+
+/// The maximum number of tracked codes of each kind
+pub const MAX_TRACKED_CODES: usize = 1000;
+
+/// The kind of an executed code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeKind {
+    /// The code of a tx
+    Tx,
+    /// The code of a wasm VP
+    Vp,
+}
+
+/// An execution of a tx or VP code
+#[derive(Debug, Clone)]
+pub struct CodeExecution {
+    /// The kind of the code
+    pub kind: CodeKind,
+    /// The hash of the code
+    pub code_hash: Hash,
+    /// The gas used by the execution
+    pub gas: Gas,
+    /// Whether the execution failed or was rejected
+    pub failed: bool,
+}
+
+impl CodeExecution {
+    /// The executions of the code of a tx and of the wasm VPs it triggered,
+    /// given the result of the tx
+    pub fn of_tx_result(code_hash: Hash, result: &TxResult) -> Vec<Self> {
+        let tx = Self {
+            kind: CodeKind::Tx,
+            code_hash,
+            gas: result.gas_used,
+            failed: !result.is_accepted(),
+        };
+        let vps = result.vps_result.wasm_vps.iter().map(
+            |(addr, (code_hash, gas))| Self {
+                kind: CodeKind::Vp,
+                code_hash: *code_hash,
+                gas: *gas,
+                failed: result.vps_result.rejected_vps.contains(addr),
+            },
+        );
+        std::iter::once(tx).chain(vps).collect()
+    }
+}
+
+/// The recorded executions of a code
+#[derive(Debug, Default)]
+struct CodeRecord {
+    /// The number of executions since the node started
+    count: u64,
+    /// The gas used by the last executions, in sub-units, and whether they
+    /// failed
+    window: VecDeque<(u64, bool)>,
+    /// The height of the block of the last execution
+    last_height: BlockHeight,
+}
+
+impl CodeRecord {
+    fn record(&mut self, height: BlockHeight, gas: Gas, failed: bool) {
+        self.count += 1;
+        if self.window.len() == WINDOW_SIZE {
+            self.window.pop_front();
+        }
+        self.window.push_back((u64::from(gas), failed));
+        self.last_height = height;
+    }
+
+    fn stats(&self) -> CodeStats {
+        let mut gas: Vec<u64> =
+            self.window.iter().map(|(gas, _)| *gas).collect();
+        gas.sort_unstable();
+        let whole_units = |sub: u64| Gas::from(sub).get_whole_gas_units();
+        // The nearest-rank percentile of the sorted gas
+        let percentile = |p: usize| {
+            let rank = (gas.len() * p + 99) / 100;
+            gas.get(rank.saturating_sub(1)).copied().map(whole_units)
+        };
+        let total: u128 = gas.iter().map(|gas| u128::from(*gas)).sum();
+        let avg_gas = u128::checked_div(total, gas.len() as u128)
+            .and_then(|avg| u64::try_from(avg).ok())
+            .map(whole_units);
+        CodeStats {
+            count: self.count,
+            window: self.window.len() as u64,
+            failures: self.window.iter().filter(|(_, failed)| *failed).count()
+                as u64,
+            avg_gas: avg_gas.unwrap_or_default(),
+            p50_gas: percentile(50).unwrap_or_default(),
+            p90_gas: percentile(90).unwrap_or_default(),
+            p99_gas: percentile(99).unwrap_or_default(),
+        }
+    }
+}
+
+/// The log of the executions of the tx and VP codes
+#[derive(Debug, Default)]
+pub struct CodeStatsLog {
+    txs: HashMap<Hash, CodeRecord>,
+    vps: HashMap<Hash, CodeRecord>,
+}
+
+impl CodeStatsLog {
+    /// Record the executions of the codes in the block at the given height
+    pub fn record<E>(&mut self, height: BlockHeight, executions: E)
+    where
+        E: IntoIterator<Item = CodeExecution>,
+    {
+        for CodeExecution {
+            kind,
+            code_hash,
+            gas,
+            failed,
+        } in executions
+        {
+            let records = match kind {
+                CodeKind::Tx => &mut self.txs,
+                CodeKind::Vp => &mut self.vps,
+            };
+            records
+                .entry(code_hash)
+                .or_default()
+                .record(height, gas, failed);
+        }
+        prune(&mut self.txs);
+        prune(&mut self.vps);
+    }
+
+    /// Get the statistics of all the tracked codes
+    pub fn stats(&self) -> ExecutionStats {
+        let stats = |records: &HashMap<Hash, CodeRecord>| {
+            records
+                .iter()
+                .map(|(code_hash, record)| (*code_hash, record.stats()))
+                .collect()
+        };
+        ExecutionStats {
+            txs: stats(&self.txs),
+            vps: stats(&self.vps),
+        }
+    }
+}
+
+/// Forget the least recently executed codes above [`MAX_TRACKED_CODES`]
+fn prune(records: &mut HashMap<Hash, CodeRecord>) {
+    let excess = records.len().saturating_sub(MAX_TRACKED_CODES);
+    if excess == 0 {
+        return;
+    }
+    let mut by_height: Vec<_> = records
+        .iter()
+        .map(|(code_hash, record)| (record.last_height, *code_hash))
+        .collect();
+    by_height.sort_unstable();
+    for (_, code_hash) in by_height.into_iter().take(excess) {
+        records.remove(&code_hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution(
+        kind: CodeKind,
+        code: u8,
+        gas: u64,
+        failed: bool,
+    ) -> CodeExecution {
+        CodeExecution {
+            kind,
+            code_hash: Hash([code; 32]),
+            gas: Gas::from_whole_units(gas),
+            failed,
+        }
+    }
+
+    #[test]
+    fn test_code_stats() {
+        let mut log = CodeStatsLog::default();
+        log.record(
+            BlockHeight(1),
+            (1..=100).map(|gas| execution(CodeKind::Tx, 1, gas, gas > 95)),
+        );
+        log.record(BlockHeight(2), [execution(CodeKind::Vp, 1, 7, false)]);
+
+        let stats = log.stats();
+        let tx_stats = &stats.txs[&Hash([1; 32])];
+        assert_eq!(tx_stats.count, 100);
+        assert_eq!(tx_stats.window, 100);
+        assert_eq!(tx_stats.failures, 5);
+        // The gas is rounded up to whole units
+        assert_eq!(tx_stats.avg_gas, 51);
+        assert_eq!(tx_stats.p50_gas, 50);
+        assert_eq!(tx_stats.p90_gas, 90);
+        assert_eq!(tx_stats.p99_gas, 99);
+        // The VPs are tracked apart from the txs
+        let vp_stats = &stats.vps[&Hash([1; 32])];
+        assert_eq!(vp_stats.count, 1);
+        assert_eq!(vp_stats.p99_gas, 7);
+    }
+
+    #[test]
+    fn test_code_stats_rolling_window() {
+        let mut log = CodeStatsLog::default();
+        log.record(
+            BlockHeight(1),
+            (0..WINDOW_SIZE).map(|_| execution(CodeKind::Tx, 1, 1, true)),
+        );
+        log.record(BlockHeight(2), [execution(CodeKind::Tx, 1, 1, false)]);
+
+        let stats = &log.stats().txs[&Hash([1; 32])];
+        assert_eq!(stats.count, WINDOW_SIZE as u64 + 1);
+        assert_eq!(stats.window, WINDOW_SIZE as u64);
+        assert_eq!(stats.failures, WINDOW_SIZE as u64 - 1);
+    }
+
+    #[test]
+    fn test_code_stats_pruning() {
+        let mut log = CodeStatsLog::default();
+        log.record(BlockHeight(1), [execution(CodeKind::Tx, 0, 1, false)]);
+        for code in 1..=MAX_TRACKED_CODES {
+            let code_hash = Hash::sha256(code.to_le_bytes());
+            log.record(
+                BlockHeight(2),
+                [CodeExecution {
+                    code_hash,
+                    ..execution(CodeKind::Tx, 0, 1, false)
+                }],
+            );
+        }
+
+        // The least recently executed code is forgotten
+        let stats = log.stats();
+        assert_eq!(stats.txs.len(), MAX_TRACKED_CODES);
+        assert!(!stats.txs.contains_key(&Hash([0; 32])));
+    }
+}
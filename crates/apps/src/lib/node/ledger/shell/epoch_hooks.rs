@@ -0,0 +1,686 @@
+//! Hooks through which the protocol modules do their work when a new epoch
+//! begins.
+//!
+//! Each module registers an [`EpochTransitionHook`] with the shell. The hooks
+//! are run while finalizing the first block of a new epoch, stage by stage in
+//! the order of [`EpochTransitionStage`] and, within a stage, in the order in
+//! which they were registered. The time spent in each hook is logged and kept
+//! for profiling.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use namada::governance::pgf::inflation as pgf_inflation;
+use namada::ledger::events::balance::BalanceChangeReason;
+use namada::ledger::events::{EventLevel, EventType};
+use namada::ledger::pos::namada_proof_of_stake;
+use namada::proof_of_stake::PosParams;
+use namada::token::conversion::update_allowed_conversions;
+use namada::types::storage::Epoch;
+
+use super::governance::execute_governance_proposals;
+use super::*;
+
+/// The stage of `FinalizeBlock` at which an epoch transition hook runs. The
+/// stages run in the order in which they are declared.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EpochTransitionStage {
+    /// Right after the new epoch has begun, before the votes and the evidence
+    /// included in the block are processed
+    Begin,
+    /// After the evidence included in the block has been recorded
+    Rewards,
+    /// After the validators have been jailed for inactivity
+    End,
+}
+
+/// The context passed to the epoch transition hooks
+pub struct EpochTransitionCtx<'a> {
+    /// The new epoch
+    pub current_epoch: Epoch,
+    /// The PoS parameters
+    pub pos_params: &'a PosParams,
+    /// The response of the block being finalized, to which hooks may add
+    /// events
+    pub response: &'a mut shim::response::FinalizeBlock,
+}
+
+/// The work that a protocol module does when a new epoch begins
+pub trait EpochTransitionHook<D, H>: Send + Sync
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// The name of the hook, used in logs and timing metrics
+    fn name(&self) -> &'static str;
+
+    /// The stage at which the hook runs
+    fn stage(&self) -> EpochTransitionStage;
+
+    /// Do the work of the hook for the new epoch
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()>;
+}
+
+/// The registered epoch transition hooks
+pub struct EpochTransitionHooks<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    hooks: Vec<Box<dyn EpochTransitionHook<D, H>>>,
+    last_timings: Vec<(&'static str, Duration)>,
+}
+
+impl<D, H> EpochTransitionHooks<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// No hooks registered
+    pub fn empty() -> Self {
+        Self {
+            hooks: vec![],
+            last_timings: vec![],
+        }
+    }
+
+    /// Register a hook. It runs after all the hooks already registered for
+    /// the same stage.
+    pub fn register(&mut self, hook: Box<dyn EpochTransitionHook<D, H>>) {
+        let stage = hook.stage();
+        let position = self
+            .hooks
+            .iter()
+            .position(|hook| hook.stage() > stage)
+            .unwrap_or(self.hooks.len());
+        self.hooks.insert(position, hook);
+    }
+
+    /// The names of the registered hooks, in the order in which they run
+    pub fn names(&self) -> Vec<&'static str> {
+        self.hooks.iter().map(|hook| hook.name()).collect()
+    }
+
+    /// The time spent in each hook during the last epoch transition
+    pub fn last_timings(&self) -> &[(&'static str, Duration)] {
+        &self.last_timings
+    }
+
+    /// Run the hooks of the given stage
+    fn run_stage(
+        &mut self,
+        stage: EpochTransitionStage,
+        shell: &mut Shell<D, H>,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        if stage == EpochTransitionStage::Begin {
+            self.last_timings.clear();
+        }
+        for hook in self.hooks.iter().filter(|hook| hook.stage() == stage) {
+            let start = Instant::now();
+            hook.on_new_epoch(shell, ctx)?;
+            let elapsed = start.elapsed();
+            shell.record_balance_changes(
+                BalanceChangeReason::EpochHook(hook.name()),
+                None,
+            );
+            tracing::debug!(
+                "Epoch transition hook {} for epoch {} took {:?}",
+                hook.name(),
+                ctx.current_epoch,
+                elapsed
+            );
+            self.last_timings.push((hook.name(), elapsed));
+        }
+        if stage == EpochTransitionStage::End {
+            let total: Duration =
+                self.last_timings.iter().map(|(_, time)| *time).sum();
+            tracing::info!(
+                "Epoch transition hooks for epoch {} took {:?}",
+                ctx.current_epoch,
+                total
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<D, H> Default for EpochTransitionHooks<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// The hooks of the built-in protocol modules
+    fn default() -> Self {
+        let mut hooks = Self::empty();
+        hooks.register(Box::new(MaspConversionsHook));
+        hooks.register(Box::new(GovernanceHook));
+        hooks.register(Box::new(PosEpochBeginHook));
+        // Invariant: Process slashes before inflation as they may affect the
+        // rewards in the current epoch.
+        hooks.register(Box::new(PosSlashesHook));
+        // The deferred mints are released before any new inflation
+        hooks.register(Box::new(NativeMintDeferredHook));
+        hooks.register(Box::new(PosInflationHook));
+        hooks.register(Box::new(PgfInflationHook));
+        hooks.register(Box::new(PosPerformanceHook));
+        hooks.register(Box::new(PosEpochSummaryHook));
+        hooks.register(Box::new(PosLivenessPruningHook));
+        hooks.register(Box::new(NativeMintLimitHook));
+        hooks
+    }
+}
+
+impl<D, H> fmt::Debug for EpochTransitionHooks<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EpochTransitionHooks")
+            .field("hooks", &self.names())
+            .field("last_timings", &self.last_timings)
+            .finish()
+    }
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Register a hook to be run when a new epoch begins
+    pub fn register_epoch_transition_hook(
+        &mut self,
+        hook: Box<dyn EpochTransitionHook<D, H>>,
+    ) {
+        self.epoch_transition_hooks.register(hook);
+    }
+
+    /// Run the epoch transition hooks of the given stage
+    pub(super) fn run_epoch_transition_hooks(
+        &mut self,
+        stage: EpochTransitionStage,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        // The hooks are taken out of the shell while they run, so that they
+        // can be given mutable access to it
+        let mut hooks = std::mem::replace(
+            &mut self.epoch_transition_hooks,
+            EpochTransitionHooks::empty(),
+        );
+        let result = hooks.run_stage(stage, self, ctx);
+        self.epoch_transition_hooks = hooks;
+        result
+    }
+}
+
+/// Update the allowed conversions of the MASP
+struct MaspConversionsHook;
+
+impl<D, H> EpochTransitionHook<D, H> for MaspConversionsHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "masp_conversions"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::Begin
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        _ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        update_allowed_conversions(&mut shell.wl_storage)?;
+        Ok(())
+    }
+}
+
+/// Execute the governance proposals whose grace epoch has been reached
+struct GovernanceHook;
+
+impl<D, H> EpochTransitionHook<D, H> for GovernanceHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "governance"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::Begin
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        execute_governance_proposals(shell, ctx.response)?;
+        Ok(())
+    }
+}
+
+/// Prepare the PoS validator sets, the consensus stake and the scheduled
+/// bonds of the new epoch
+struct PosEpochBeginHook;
+
+impl<D, H> EpochTransitionHook<D, H> for PosEpochBeginHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "pos_epoch_begin"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::Begin
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        let current_epoch = ctx.current_epoch;
+        let pos_params = ctx.pos_params;
+
+        // Copy the new_epoch + pipeline_len - 1 validator set into
+        // new_epoch + pipeline_len
+        namada_proof_of_stake::validator_set_update::copy_validator_sets_and_positions(
+            &mut shell.wl_storage,
+            pos_params,
+            current_epoch,
+            current_epoch + pos_params.pipeline_len,
+        )?;
+
+        // Compute the total stake of the consensus validator set and record
+        // it in storage
+        namada_proof_of_stake::compute_and_store_total_consensus_stake(
+            &mut shell.wl_storage,
+            current_epoch,
+        )?;
+
+        // Apply the bonds and unbonds scheduled for the new epoch
+        namada_proof_of_stake::process_scheduled_bonds(
+            &mut shell.wl_storage,
+            current_epoch,
+        )?;
+        Ok(())
+    }
+}
+
+/// Process the slashes recorded for the new epoch
+struct PosSlashesHook;
+
+impl<D, H> EpochTransitionHook<D, H> for PosSlashesHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "pos_slashes"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::Rewards
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        _ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        shell.process_slashes();
+        Ok(())
+    }
+}
+
+/// Mint the native tokens that were deferred in the previous epochs because
+/// they exceeded the limit of native tokens minted per epoch
+struct NativeMintDeferredHook;
+
+impl<D, H> EpochTransitionHook<D, H> for NativeMintDeferredHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "native_mint_deferred"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::Rewards
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        _ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        token::mint_deferred_native_tokens(&mut shell.wl_storage)?;
+        Ok(())
+    }
+}
+
+/// Calculate the new PoS inflation, mint the new tokens to the PoS account,
+/// then update the reward products of the validators. This is applied with
+/// respect to the previous epoch.
+struct PosInflationHook;
+
+impl<D, H> EpochTransitionHook<D, H> for PosInflationHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "pos_inflation"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::Rewards
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        let last_epoch = ctx.current_epoch.prev();
+
+        // Get the number of blocks in the last epoch
+        let first_block_of_last_epoch = shell
+            .wl_storage
+            .storage
+            .block
+            .pred_epochs
+            .first_block_heights[last_epoch.0 as usize]
+            .0;
+        let num_blocks_in_last_epoch =
+            shell.wl_storage.storage.block.height.0 - first_block_of_last_epoch;
+
+        namada_proof_of_stake::rewards::apply_inflation(
+            &mut shell.wl_storage,
+            last_epoch,
+            num_blocks_in_last_epoch,
+        )?;
+        Ok(())
+    }
+}
+
+/// Mint the PGF inflation and pay the continuous PGF fundings
+struct PgfInflationHook;
+
+impl<D, H> EpochTransitionHook<D, H> for PgfInflationHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "pgf_inflation"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::Rewards
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        pgf_inflation::apply_inflation(
+            &mut shell.wl_storage,
+            namada::ibc::transfer_over_ibc,
+        )?;
+        for ibc_event in shell.wl_storage.write_log_mut().take_ibc_events() {
+            let mut event = Event::from(ibc_event.clone());
+            // Add the height for IBC event query
+            let height = shell.wl_storage.storage.get_last_block_height() + 1;
+            event["height"] = height.to_string();
+            ctx.response.events.push(event);
+        }
+        Ok(())
+    }
+}
+
+/// Record the performance of the validators from their liveness data in the
+/// epoch that just ended, before it gets pruned
+struct PosPerformanceHook;
+
+impl<D, H> EpochTransitionHook<D, H> for PosPerformanceHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "pos_performance"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::End
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        // The liveness data has been recorded up to the votes on the last
+        // block of the previous epoch
+        let last_votes_height =
+            shell.wl_storage.storage.block.height.prev_height();
+        namada_proof_of_stake::record_validator_performance(
+            &mut shell.wl_storage,
+            ctx.pos_params,
+            ctx.current_epoch,
+            last_votes_height,
+        )?;
+        Ok(())
+    }
+}
+
+/// Archive the summary of the epoch that just ended, before its epoched PoS
+/// data gets pruned
+struct PosEpochSummaryHook;
+
+impl<D, H> EpochTransitionHook<D, H> for PosEpochSummaryHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "pos_epoch_summary"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::End
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        namada_proof_of_stake::history::record_epoch_summary(
+            &mut shell.wl_storage,
+            ctx.pos_params,
+            ctx.current_epoch.prev(),
+        )?;
+        Ok(())
+    }
+}
+
+/// Prune the liveness data of the validators that are no longer in the
+/// consensus set
+struct PosLivenessPruningHook;
+
+impl<D, H> EpochTransitionHook<D, H> for PosLivenessPruningHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "pos_liveness_pruning"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::End
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        namada_proof_of_stake::prune_liveness_data(
+            &mut shell.wl_storage,
+            ctx.current_epoch,
+        )?;
+        Ok(())
+    }
+}
+
+/// Emit an event if minting native tokens had to be deferred in the new epoch
+/// because it exceeded the limit of native tokens minted per epoch
+struct NativeMintLimitHook;
+
+impl<D, H> EpochTransitionHook<D, H> for NativeMintLimitHook
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "native_mint_limit"
+    }
+
+    fn stage(&self) -> EpochTransitionStage {
+        EpochTransitionStage::End
+    }
+
+    fn on_new_epoch(
+        &self,
+        shell: &mut Shell<D, H>,
+        ctx: &mut EpochTransitionCtx<'_>,
+    ) -> Result<()> {
+        let volume = token::read_mint_volume(&shell.wl_storage)?;
+        if volume.deferred.is_zero() {
+            return Ok(());
+        }
+        let limit =
+            parameters::read_max_native_mint_per_epoch(&shell.wl_storage)?
+                .unwrap_or_default();
+        tracing::error!(
+            "The native tokens minted in epoch {} reached the limit of {}. \
+             Minted {} tokens and deferred {} tokens to the next epochs. \
+             Please check the inflation parameters.",
+            ctx.current_epoch,
+            limit.to_string_native(),
+            volume.minted.to_string_native(),
+            volume.deferred.to_string_native(),
+        );
+        let height = shell.wl_storage.storage.block.height;
+        ctx.response.events.push(Event {
+            event_type: EventType::MintLimit,
+            level: EventLevel::Block,
+            attributes: HashMap::from([
+                ("epoch".to_string(), ctx.current_epoch.to_string()),
+                ("height".to_string(), height.to_string()),
+                ("limit".to_string(), limit.to_string()),
+                ("minted".to_string(), volume.minted.to_string()),
+                ("deferred".to_string(), volume.deferred.to_string()),
+            ]),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_epoch_hooks {
+    use namada::state::mockdb::MockDB;
+
+    use super::*;
+
+    /// A hook that doesn't do anything
+    struct NoopHook(&'static str, EpochTransitionStage);
+
+    impl<D, H> EpochTransitionHook<D, H> for NoopHook
+    where
+        D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+        H: StorageHasher + Sync + 'static,
+    {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn stage(&self) -> EpochTransitionStage {
+            self.1
+        }
+
+        fn on_new_epoch(
+            &self,
+            _shell: &mut Shell<D, H>,
+            _ctx: &mut EpochTransitionCtx<'_>,
+        ) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Test that the hooks run by stage and then in the order of
+    /// registration
+    #[test]
+    fn test_epoch_transition_hooks_order() {
+        let mut hooks = EpochTransitionHooks::<MockDB, Sha256Hasher>::default();
+        assert_eq!(
+            hooks.names(),
+            vec![
+                "masp_conversions",
+                "governance",
+                "pos_epoch_begin",
+                "pos_slashes",
+                "native_mint_deferred",
+                "pos_inflation",
+                "pgf_inflation",
+                "pos_performance",
+                "pos_epoch_summary",
+                "pos_liveness_pruning",
+                "native_mint_limit",
+            ]
+        );
+
+        hooks
+            .register(Box::new(NoopHook("dex", EpochTransitionStage::Rewards)));
+        hooks
+            .register(Box::new(NoopHook("first", EpochTransitionStage::Begin)));
+        assert_eq!(
+            hooks.names(),
+            vec![
+                "masp_conversions",
+                "governance",
+                "pos_epoch_begin",
+                "first",
+                "pos_slashes",
+                "native_mint_deferred",
+                "pos_inflation",
+                "pgf_inflation",
+                "dex",
+                "pos_performance",
+                "pos_epoch_summary",
+                "pos_liveness_pruning",
+                "native_mint_limit",
+            ]
+        );
+    }
+}
@@ -1,9 +1,15 @@
 //! Implementation of the `FinalizeBlock` ABCI++ method for the Shell
 
+use std::time::Instant;
+
 use data_encoding::HEXUPPER;
 use masp_primitives::merkle_tree::CommitmentTree;
 use masp_primitives::sapling::Node;
-use namada::governance::pgf::inflation as pgf_inflation;
+use masp_primitives::transaction::Transaction;
+use namada::ethereum_bridge::protocol::transactions::{
+    checkpoints, withdrawal_vetoes,
+};
+use namada::ledger::events::balance::BalanceChangeReason;
 use namada::ledger::events::EventType;
 use namada::ledger::gas::{GasMetering, TxGasMeter};
 use namada::ledger::pos::namada_proof_of_stake;
@@ -17,18 +23,25 @@ use namada::state::write_log::StorageModification;
 use namada::state::{
     ResultExt, StorageRead, StorageWrite, EPOCH_SWITCH_BLOCKS_DELAY,
 };
-use namada::token::conversion::update_allowed_conversions;
+use namada::token::compact::{CompactBlock, CompactTx};
+use namada::token::Transfer;
 use namada::tx::data::protocol::ProtocolTxType;
+use namada::tx::data::TxErrorData;
 use namada::types::key::tm_raw_hash_to_string;
 use namada::types::storage::{BlockHash, BlockResults, Epoch, Header};
 use namada::vote_ext::ethereum_events::MultiSignedEthEvent;
 use namada::vote_ext::ethereum_tx_data_variants;
 
-use super::governance::execute_governance_proposals;
+use super::code_stats::{CodeExecution, CodeKind};
+use super::epoch_hooks::{EpochTransitionCtx, EpochTransitionStage};
 use super::*;
 use crate::facade::tendermint::abci::types::{Misbehavior, VoteInfo};
 use crate::node::ledger::shell::stats::InternalStats;
 
+/// The maximum number of expired tx hashes pruned from the replay protection
+/// storage per block, to bound the extra work of a block
+const MAX_PRUNED_TX_HASHES_PER_BLOCK: usize = 10_000;
+
 impl<D, H> Shell<D, H>
 where
     D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
@@ -60,7 +73,11 @@ where
     ) -> Result<shim::response::FinalizeBlock> {
         let mut response = shim::response::FinalizeBlock::default();
 
+        #[cfg(any(test, feature = "time-travel"))]
+        let req = self.time_travel_block(req);
+
         // Begin the new block and check if a new epoch has begun
+        let block_time = req.header.time;
         let (height, new_epoch) =
             self.update_state(req.header, req.hash, req.byzantine_validators);
 
@@ -87,29 +104,36 @@ where
                 .finalize_tx_hash(hash)
                 .expect("Failed tx hashes finalization")
         }
+        // Prune the hashes of the expired txs, which can't be replayed
+        let pruned_tx_hashes = self
+            .wl_storage
+            .prune_expired_tx_hashes(
+                &block_time,
+                MAX_PRUNED_TX_HASHES_PER_BLOCK,
+            )
+            .expect("Failed tx hashes pruning");
+        if pruned_tx_hashes > 0 {
+            tracing::debug!("Pruned {pruned_tx_hashes} expired tx hashes");
+        }
 
         let pos_params =
             namada_proof_of_stake::storage::read_pos_params(&self.wl_storage)?;
 
-        if new_epoch {
-            update_allowed_conversions(&mut self.wl_storage)?;
+        self.record_balance_changes(BalanceChangeReason::Protocol, None);
 
-            execute_governance_proposals(self, &mut response)?;
+        #[cfg(any(test, feature = "time-travel"))]
+        let run_epoch_hooks = new_epoch || self.take_forced_epoch_hooks();
+        #[cfg(not(any(test, feature = "time-travel")))]
+        let run_epoch_hooks = new_epoch;
 
-            // Copy the new_epoch + pipeline_len - 1 validator set into
-            // new_epoch + pipeline_len
-            namada_proof_of_stake::validator_set_update::copy_validator_sets_and_positions(
-                &mut self.wl_storage,
-                &pos_params,
-                current_epoch,
-                current_epoch + pos_params.pipeline_len,
-            )?;
-
-            // Compute the total stake of the consensus validator set and record
-            // it in storage
-            namada_proof_of_stake::compute_and_store_total_consensus_stake(
-                &mut self.wl_storage,
-                current_epoch,
+        if run_epoch_hooks {
+            self.run_epoch_transition_hooks(
+                EpochTransitionStage::Begin,
+                &mut EpochTransitionCtx {
+                    current_epoch,
+                    pos_params: &pos_params,
+                    response: &mut response,
+                },
             )?;
         }
 
@@ -133,11 +157,15 @@ where
         self.record_slashes_from_evidence();
         // Invariant: This has to be applied after
         // `copy_validator_sets_and_positions` if we're starting a new epoch
-        if new_epoch {
-            // Invariant: Process slashes before inflation as they may affect
-            // the rewards in the current epoch.
-            self.process_slashes();
-            self.apply_inflation(current_epoch, &mut response)?;
+        if run_epoch_hooks {
+            self.run_epoch_transition_hooks(
+                EpochTransitionStage::Rewards,
+                &mut EpochTransitionCtx {
+                    current_epoch,
+                    pos_params: &pos_params,
+                    response: &mut response,
+                },
+            )?;
         }
 
         // Consensus set liveness check
@@ -173,15 +201,19 @@ where
             validator_set_update_epoch,
         )?;
 
-        if new_epoch {
-            // Prune liveness data from validators that are no longer in the
-            // consensus set
-            namada_proof_of_stake::prune_liveness_data(
-                &mut self.wl_storage,
-                current_epoch,
+        if run_epoch_hooks {
+            self.run_epoch_transition_hooks(
+                EpochTransitionStage::End,
+                &mut EpochTransitionCtx {
+                    current_epoch,
+                    pos_params: &pos_params,
+                    response: &mut response,
+                },
             )?;
         }
 
+        self.record_balance_changes(BalanceChangeReason::Protocol, None);
+
         let mut stats = InternalStats::default();
 
         let native_block_proposer_address = {
@@ -198,6 +230,11 @@ where
         // Tracks the accepted transactions
         self.wl_storage.storage.block.results = BlockResults::default();
         let mut changed_keys = BTreeSet::new();
+        // Tracks the valid masp transactions for shielded sync
+        let mut compact_block = CompactBlock::default();
+        let mut code_executions = vec![];
+        // The hashes of the wrapper txs of the block, which leave the mempool
+        let mut included_wrappers = vec![];
         for (tx_index, processed_tx) in req.txs.iter().enumerate() {
             let tx = if let Ok(tx) = Tx::try_from(processed_tx.tx.as_ref()) {
                 tx
@@ -209,6 +246,9 @@ where
                 );
                 continue;
             };
+            if matches!(tx.header.tx_type, TxType::Wrapper(_)) {
+                included_wrappers.push(tx.header_hash());
+            }
             // If [`process_proposal`] rejected a Tx due to invalid signature,
             // emit an event here and move on to next tx.
             if ResultCode::from_u32(processed_tx.result.code).unwrap()
@@ -254,20 +294,32 @@ where
                 tx_event["info"] =
                     format!("Tx rejected: {}", &processed_tx.result.info);
                 tx_event["gas_used"] = "0".into();
-                response.events.push(tx_event);
                 // if the rejected tx was decrypted, remove it
-                // from the queue of txs to be processed
+                // from the queue of txs to be processed and refund the gas
+                // reserved for it
                 if let TxType::Decrypted(_) = &tx_header.tx_type {
-                    self.wl_storage
+                    let tx_in_queue = self
+                        .wl_storage
                         .storage
                         .tx_queue
                         .pop()
                         .expect("Missing wrapper tx in queue");
+                    self.settle_wrapper_fee(
+                        &tx_in_queue,
+                        tx_in_queue.gas,
+                        &mut tx_event,
+                    );
                 }
+                response.events.push(tx_event);
 
                 continue;
             }
 
+            // The escrowed fee of the wrapper of a decrypted tx, with the
+            // proposer of the block that included the wrapper
+            let mut escrowed_fee = None;
+            // The hash of the code of a decrypted tx, for its statistics
+            let mut tx_code_hash = None;
             let (
                 mut tx_event,
                 embedding_wrapper,
@@ -277,6 +329,10 @@ where
             ) = match &tx_header.tx_type {
                 TxType::Wrapper(wrapper) => {
                     stats.increment_wrapper_txs();
+                    if let Some(expiration) = tx.header.expiration {
+                        self.wl_storage
+                            .write_tx_expiration(tx.header_hash(), &expiration);
+                    }
                     let tx_event = Event::new_tx_event(&tx, height.0);
                     let gas_meter = TxGasMeter::new(wrapper.gas_limit);
                     (
@@ -309,6 +365,7 @@ where
                                 stats.increment_tx_type(
                                     code_sec.code.hash().to_string(),
                                 );
+                                tx_code_hash = Some(code_sec.code.hash());
                             }
                         }
                         DecryptedTx::Undecryptable => {
@@ -320,11 +377,24 @@ where
                             event["log"] =
                                 "Transaction could not be decrypted.".into();
                             event["code"] = ResultCode::Undecryptable.into();
+                            self.settle_wrapper_fee(
+                                &tx_in_queue,
+                                tx_in_queue.gas,
+                                &mut event,
+                            );
                             response.events.push(event);
                             continue;
                         }
                     }
 
+                    escrowed_fee = Some((
+                        tx_in_queue
+                            .tx
+                            .header()
+                            .wrapper()
+                            .expect("Missing wrapper header in queue"),
+                        tx_in_queue.block_proposer,
+                    ));
                     (
                         event,
                         Some(tx_in_queue.tx),
@@ -344,7 +414,9 @@ where
                     ProtocolTxType::BridgePoolVext
                     | ProtocolTxType::BridgePool
                     | ProtocolTxType::ValSetUpdateVext
-                    | ProtocolTxType::ValidatorSetUpdate => (
+                    | ProtocolTxType::ValidatorSetUpdate
+                    | ProtocolTxType::WithdrawalVetoVext
+                    | ProtocolTxType::CheckpointVext => (
                         Event::new_tx_event(&tx, height.0),
                         None,
                         TxGasMeter::new_from_sub_limit(0.into()),
@@ -408,6 +480,12 @@ where
                 },
             };
 
+            // The tx is consumed when applied, so keep its masp transaction
+            // in case it turns out to be valid
+            let masp_transaction = masp_transaction_of(&tx);
+            let mut masp_changed_keys = None;
+
+            let tx_start = Instant::now();
             let tx_result = protocol::check_tx_allowed(&tx, &self.wl_storage)
                 .and_then(|()| {
                     protocol::dispatch_tx(
@@ -426,6 +504,7 @@ where
                     )
                 })
                 .map_err(Error::TxApply);
+            stats.add_tx_exec_time(tx_start.elapsed());
             match tx_result {
                 Ok(result) => {
                     if result.is_accepted() {
@@ -445,6 +524,8 @@ where
                             self.wl_storage.storage.tx_queue.push(TxInQueue {
                                 tx: wrapper.expect("Missing expected wrapper"),
                                 gas: tx_gas_meter.get_available_gas(),
+                                block_proposer: native_block_proposer_address
+                                    .clone(),
                             });
                         } else {
                             tracing::trace!(
@@ -518,10 +599,22 @@ where
                         stats.increment_rejected_txs();
                         self.wl_storage.drop_tx();
                         tx_event["code"] = ResultCode::InvalidTx.into();
+                        set_tx_error(
+                            &mut tx_event,
+                            TxErrorData::from_vps_result(&result.vps_result),
+                        );
                     }
                     tx_event["gas_used"] = result.gas_used.to_string();
                     tx_event["info"] = "Check inner_tx for result.".to_string();
                     tx_event["inner_tx"] = result.to_string();
+                    if tx_event.contains_key("is_valid_masp_tx") {
+                        masp_changed_keys = Some(result.changed_keys.clone());
+                    }
+                    if let Some(code_hash) = tx_code_hash {
+                        code_executions.extend(CodeExecution::of_tx_result(
+                            code_hash, &result,
+                        ));
+                    }
                 }
                 Err(msg) => {
                     tracing::info!(
@@ -563,10 +656,25 @@ where
 
                     stats.increment_errored_txs();
                     self.wl_storage.drop_tx();
+                    if let Some(code_hash) = tx_code_hash {
+                        code_executions.push(CodeExecution {
+                            kind: CodeKind::Tx,
+                            code_hash,
+                            gas: tx_gas_meter.get_tx_consumed_gas(),
+                            failed: true,
+                        });
+                    }
 
                     tx_event["gas_used"] =
                         tx_gas_meter.get_tx_consumed_gas().to_string();
                     tx_event["info"] = msg.to_string();
+                    set_tx_error(
+                        &mut tx_event,
+                        match &msg {
+                            Error::TxApply(err) => err.tx_error_data(),
+                            _ => TxErrorData::Other,
+                        },
+                    );
                     if let EventType::Accepted = tx_event.event_type {
                         // If wrapper, invalid tx error code
                         tx_event["code"] = ResultCode::InvalidTx.into();
@@ -584,6 +692,41 @@ where
                     }
                 }
             }
+            // The only balances changed by a wrapper are those of its fee
+            let reason = if let EventType::Accepted = tx_event.event_type {
+                BalanceChangeReason::Fee
+            } else {
+                BalanceChangeReason::Tx
+            };
+            self.record_balance_changes(
+                reason,
+                Some(tx_event["hash"].as_str()),
+            );
+            if let Some((wrapper, block_proposer)) = escrowed_fee {
+                self.settle_fee(
+                    &wrapper,
+                    &block_proposer,
+                    tx_gas_meter.get_available_gas(),
+                    &mut tx_event,
+                );
+            }
+            if tx_event.contains_key("is_valid_masp_tx") {
+                let index = TxIndex(
+                    tx_index
+                        .try_into()
+                        .expect("transaction index out of bounds"),
+                );
+                match (masp_transaction, masp_changed_keys) {
+                    (Some(transaction), Some(keys)) => compact_block
+                        .txs
+                        .push(CompactTx::new(index, &keys, &transaction)),
+                    // The masp transaction couldn't be extracted, e.g.
+                    // because it came over IBC
+                    _ => {
+                        compact_block.full_txs.insert(index);
+                    }
+                }
+            }
             response.events.push(tx_event);
         }
 
@@ -598,6 +741,24 @@ where
 
         tracing::info!("{}", stats);
         tracing::info!("{}", stats.format_tx_executed());
+        tracing::info!("{}", stats.format_tx_latency());
+
+        // Delay the new transfers to Ethereum above the withdrawal veto
+        // threshold of their asset, before the Bridge pool is committed
+        let delayed_changed_keys = withdrawal_vetoes::update_delayed_transfers(
+            &mut self.wl_storage,
+            &changed_keys,
+        )
+        .expect("Must be able to update the delayed transfers to Ethereum");
+        changed_keys.extend(delayed_changed_keys);
+
+        // Record the checkpoint of the app hash committed at the last
+        // height, for the validators to sign it once this block is committed
+        let checkpoint_changed_keys = checkpoints::record_checkpoint(
+            &mut self.wl_storage,
+        )
+        .expect("Must be able to record the checkpoint of the app hash");
+        changed_keys.extend(checkpoint_changed_keys);
 
         // Update the MASP commitment tree anchor if the tree was updated
         let tree_key = token::storage_key::masp_commitment_tree_key();
@@ -612,6 +773,17 @@ where
             self.wl_storage.write(&anchor_key, ())?;
         }
 
+        self.record_balance_changes(BalanceChangeReason::Protocol, None);
+        response.events.extend(self.balance_events.take_events());
+
+        // Store the compact block for the shielded sync clients
+        if !compact_block.is_empty() {
+            self.wl_storage.write(
+                &token::storage_key::masp_compact_block_key(height),
+                compact_block,
+            )?;
+        }
+
         if update_for_tendermint {
             self.update_epoch(&mut response);
             // send the latest oracle configs. These may have changed due to
@@ -625,6 +797,14 @@ where
         )?;
 
         self.event_log_mut().log_events(response.events.clone());
+        self.code_stats
+            .write()
+            .expect("The code stats lock must not be poisoned")
+            .record(height, code_executions);
+        self.mempool_txs
+            .write()
+            .expect("The mempool txs lock must not be poisoned")
+            .finalize_block(height, included_wrappers);
         tracing::debug!("End finalize_block {height} of epoch {current_epoch}");
 
         Ok(response)
@@ -682,51 +862,6 @@ where
             .expect("Must be able to update validator set");
     }
 
-    /// Calculate the new inflation rate, mint the new tokens to the PoS
-    /// account, then update the reward products of the validators. This is
-    /// executed while finalizing the first block of a new epoch and is applied
-    /// with respect to the previous epoch.
-    fn apply_inflation(
-        &mut self,
-        current_epoch: Epoch,
-        response: &mut shim::response::FinalizeBlock,
-    ) -> Result<()> {
-        let last_epoch = current_epoch.prev();
-
-        // Get the number of blocks in the last epoch
-        let first_block_of_last_epoch = self
-            .wl_storage
-            .storage
-            .block
-            .pred_epochs
-            .first_block_heights[last_epoch.0 as usize]
-            .0;
-        let num_blocks_in_last_epoch =
-            self.wl_storage.storage.block.height.0 - first_block_of_last_epoch;
-
-        // PoS inflation
-        namada_proof_of_stake::rewards::apply_inflation(
-            &mut self.wl_storage,
-            last_epoch,
-            num_blocks_in_last_epoch,
-        )?;
-
-        // Pgf inflation
-        pgf_inflation::apply_inflation(
-            &mut self.wl_storage,
-            namada::ibc::transfer_over_ibc,
-        )?;
-        for ibc_event in self.wl_storage.write_log_mut().take_ibc_events() {
-            let mut event = Event::from(ibc_event.clone());
-            // Add the height for IBC event query
-            let height = self.wl_storage.storage.get_last_block_height() + 1;
-            event["height"] = height.to_string();
-            response.events.push(event);
-        }
-
-        Ok(())
-    }
-
     // Process the proposer and votes in the block to assign their PoS rewards.
     fn log_block_rewards(
         &mut self,
@@ -772,10 +907,71 @@ where
     // hash since it's redundant (we check the inner tx hash too when validating
     // the wrapper). Requires the wrapper transaction as argument to recover
     // both the hashes.
+    /// Settle the escrowed fee of a wrapper removed from the queue, given the
+    /// gas left unused by it and its inner tx
+    fn settle_wrapper_fee(
+        &mut self,
+        tx_in_queue: &TxInQueue,
+        unused_gas: Gas,
+        tx_event: &mut Event,
+    ) {
+        let wrapper = tx_in_queue
+            .tx
+            .header()
+            .wrapper()
+            .expect("Missing wrapper header in queue");
+        self.settle_fee(
+            &wrapper,
+            &tx_in_queue.block_proposer,
+            unused_gas,
+            tx_event,
+        );
+    }
+
+    /// Pay the fee for the gas used from the escrow of the given wrapper to
+    /// the block proposer, refund the rest to the fee payer and record the
+    /// amounts in the event of the inner tx
+    fn settle_fee(
+        &mut self,
+        wrapper: &WrapperTx,
+        block_proposer: &Address,
+        unused_gas: Gas,
+        tx_event: &mut Event,
+    ) {
+        match protocol::settle_fee(
+            &mut self.wl_storage,
+            block_proposer,
+            wrapper,
+            unused_gas,
+        ) {
+            Ok(protocol::FeeSettlement { paid, refund }) => {
+                self.wl_storage.commit_tx();
+                tx_event["fee_paid"] = paid.to_string();
+                tx_event["fee_refund"] = refund.to_string();
+            }
+            Err(err) => {
+                self.wl_storage.drop_tx();
+                tracing::error!(
+                    "Failed to settle the escrowed fee of tx {}: {}",
+                    tx_event["hash"],
+                    err
+                );
+            }
+        }
+        self.record_balance_changes(
+            BalanceChangeReason::Fee,
+            Some(tx_event["hash"].as_str()),
+        );
+    }
+
     fn commit_inner_tx_hash(&mut self, wrapper_tx: Tx) {
         self.wl_storage
             .write_tx_hash(wrapper_tx.raw_header_hash())
             .expect("Error while writing tx hash to storage");
+        if let Some(expiration) = wrapper_tx.header.expiration {
+            self.wl_storage
+                .write_tx_expiration(wrapper_tx.raw_header_hash(), &expiration);
+        }
 
         self.wl_storage
             .delete_tx_hash(wrapper_tx.header_hash())
@@ -783,6 +979,23 @@ where
     }
 }
 
+/// Record the machine-readable data of the failure of a tx in its event.
+fn set_tx_error(event: &mut Event, error: TxErrorData) {
+    event["error_code"] = error.code().to_string();
+    event["error"] = error.to_string();
+}
+
+/// Get the masp transaction carried by the given tx, if any. This is either
+/// the fee unshielding section of a wrapper or the shielded section of a
+/// transfer.
+fn masp_transaction_of(tx: &Tx) -> Option<Transaction> {
+    let hash = match tx.header().tx_type {
+        TxType::Wrapper(wrapper) => wrapper.unshield_section_hash?,
+        _ => Transfer::try_from_slice(&tx.data()?).ok()?.shielded?,
+    };
+    tx.get_section(&hash)?.masp_tx()
+}
+
 /// Convert ABCI vote info to PoS vote info. Any info which fails the conversion
 /// will be skipped and errors logged.
 ///
@@ -884,8 +1097,9 @@ mod test_finalize_block {
     use namada::proof_of_stake::{unjail_validator, ADDRESS as pos_address};
     use namada::state::StorageWrite;
     use namada::token::{Amount, DenominatedAmount, NATIVE_MAX_DECIMAL_PLACES};
-    use namada::tx::data::{Fee, WrapperTx};
+    use namada::tx::data::{Fee, TxErrorCode, WrapperTx};
     use namada::tx::{Code, Data, Section, Signature};
+    use namada::types::address::FEE_ESCROW;
     use namada::types::dec::{Dec, POS_DECIMAL_PRECISION};
     use namada::types::ethereum_events::{EthAddress, Uint as ethUint};
     use namada::types::hash::Hash;
@@ -3007,6 +3221,8 @@ mod test_finalize_block {
         assert_eq!(event[1].event_type.to_string(), String::from("applied"));
         let code = event[1].attributes.get("code").unwrap().as_str();
         assert_eq!(code, String::from(ResultCode::WasmRuntimeError).as_str());
+        let error_code = event[1].attributes.get("error_code").unwrap();
+        assert_eq!(error_code, &TxErrorCode::ReplayTx.to_string());
 
         for (inner, wrapper) in [(inner, wrapper), (new_inner, new_wrapper)] {
             assert!(
@@ -3342,6 +3558,18 @@ mod test_finalize_block {
         assert_eq!(event.event_type.to_string(), String::from("accepted"));
         let code = event.attributes.get("code").expect("Testfailed").as_str();
         assert_eq!(code, String::from(ResultCode::InvalidTx).as_str());
+        let error = TxErrorData::from_str(
+            event.attributes.get("error").expect("Test failed"),
+        )
+        .expect("Test failed");
+        match error {
+            TxErrorData::InsufficientFunds(funds) => {
+                assert_eq!(funds.owner, Address::from(&keypair.to_public()));
+                assert_eq!(funds.token, shell.wl_storage.storage.native_token);
+                assert!(funds.available < funds.needed);
+            }
+            _ => panic!("Test failed: unexpected error {error:?}"),
+        }
         let balance_key = token::storage_key::balance_key(
             &shell.wl_storage.storage.native_token,
             &Address::from(&keypair.to_public()),
@@ -3355,8 +3583,9 @@ mod test_finalize_block {
         assert_eq!(balance, 0.into())
     }
 
-    // Test that the fees collected from a block are withdrew from the wrapper
-    // signer and credited to the block proposer
+    // Test that the fees of a wrapper are withdrew from the wrapper signer and
+    // escrowed until its inner tx is applied, at which point the fees for the
+    // used gas are credited to the block proposer and the rest is refunded
     #[test]
     fn test_fee_payment_to_block_proposer() {
         let (mut shell, _, _, _) = setup();
@@ -3442,32 +3671,225 @@ mod test_finalize_block {
             })
             .expect("Test failed")[0];
 
-        // Check fee payment
+        // Check that the fee is escrowed
         assert_eq!(event.event_type.to_string(), String::from("accepted"));
         let code = event.attributes.get("code").expect("Test failed").as_str();
         assert_eq!(code, String::from(ResultCode::Ok).as_str());
-
-        let new_proposer_balance = namada::token::read_balance(
-            &shell.wl_storage,
-            &shell.wl_storage.storage.native_token,
-            &validator,
-        )
-        .unwrap();
+        let read_balance = |shell: &TestShell, owner: &Address| {
+            namada::token::read_balance(
+                &shell.wl_storage,
+                &shell.wl_storage.storage.native_token,
+                owner,
+            )
+            .unwrap()
+        };
+        let fee_payer = wrapper.header().wrapper().unwrap().fee_payer();
+        assert_eq!(read_balance(&shell, &FEE_ESCROW), fee_amount);
+        assert_eq!(read_balance(&shell, &validator), proposer_balance);
         assert_eq!(
-            new_proposer_balance,
-            proposer_balance.checked_add(fee_amount).unwrap()
+            read_balance(&shell, &fee_payer),
+            signer_balance.checked_sub(fee_amount).unwrap()
         );
 
-        let new_signer_balance = namada::token::read_balance(
-            &shell.wl_storage,
-            &shell.wl_storage.storage.native_token,
-            &wrapper.header().wrapper().unwrap().fee_payer(),
-        )
-        .unwrap();
+        // Apply the inner tx in the next block
+        let mut decrypted = wrapper.clone();
+        decrypted.update_header(TxType::Decrypted(DecryptedTx::Decrypted));
+        let processed_tx = ProcessedTx {
+            tx: decrypted.to_bytes().into(),
+            result: TxResult {
+                code: ResultCode::Ok.into(),
+                info: "".into(),
+            },
+        };
+        let event = &shell
+            .finalize_block(FinalizeBlock {
+                txs: vec![processed_tx],
+                ..Default::default()
+            })
+            .expect("Test failed")[0];
+
+        // Check the settlement of the fee
+        assert_eq!(event.event_type.to_string(), String::from("applied"));
+        assert!(event.attributes.contains_key("fee_paid"));
+        assert!(event.attributes.contains_key("fee_refund"));
+        assert!(read_balance(&shell, &FEE_ESCROW).is_zero());
+        let paid = read_balance(&shell, &validator)
+            .checked_sub(proposer_balance)
+            .unwrap();
+        assert!(!paid.is_zero());
+        assert!(paid < fee_amount);
         assert_eq!(
-            new_signer_balance,
-            signer_balance.checked_sub(fee_amount).unwrap()
-        )
+            read_balance(&shell, &fee_payer),
+            signer_balance.checked_sub(paid).unwrap()
+        );
+    }
+
+    /// Read the balances of all the tokens, by token and owner
+    fn read_all_balances(
+        shell: &TestShell,
+    ) -> BTreeMap<(String, String), token::Amount> {
+        let prefix = Key::from(address::MULTITOKEN.to_db_key());
+        namada::state::iter_prefix_bytes(&shell.wl_storage, &prefix)
+            .unwrap()
+            .filter_map(|result| {
+                let (key, bytes) = result.unwrap();
+                let [token, owner] =
+                    token::storage_key::is_any_token_balance_key(&key)?;
+                let balance = token::Amount::try_from_slice(&bytes).unwrap();
+                Some(((token.to_string(), owner.to_string()), balance))
+            })
+            .collect()
+    }
+
+    /// Finalize a block and check that the sums of its balance change events
+    /// match the changes of the balances in storage
+    fn finalize_block_and_check_balance_events(
+        shell: &mut TestShell,
+        req: FinalizeBlock,
+    ) -> Vec<Event> {
+        let pre = read_all_balances(shell);
+        let events = shell.shell.finalize_block(req).unwrap().events;
+        let post = read_all_balances(shell);
+
+        // The credits and the debits of every account
+        let mut sums: BTreeMap<
+            (String, String),
+            (token::Amount, token::Amount),
+        > = BTreeMap::new();
+        let mut entries: BTreeMap<String, Vec<&Event>> = BTreeMap::new();
+        for event in &events {
+            if event.event_type != EventType::BalanceChange {
+                continue;
+            }
+            entries
+                .entry(event["entry"].clone())
+                .or_default()
+                .push(event);
+            if event["account"] == "supply" {
+                continue;
+            }
+            let amount = token::Amount::from_str(&event["amount"], 0).unwrap();
+            let sum = sums
+                .entry((event["token"].clone(), event["account"].clone()))
+                .or_default();
+            match event["side"].as_str() {
+                "credit" => sum.0 += amount,
+                "debit" => sum.1 += amount,
+                side => panic!("Unexpected side of an entry {side}"),
+            }
+        }
+
+        // Every entry has a debit and a credit of the same amount
+        for entry in entries.values() {
+            let [debit, credit] = entry.as_slice() else {
+                panic!("An entry must have two events");
+            };
+            assert_eq!(debit["side"], "debit");
+            assert_eq!(credit["side"], "credit");
+            assert_eq!(debit["account"], credit["counterparty"]);
+            assert_eq!(debit["counterparty"], credit["account"]);
+            assert_eq!(debit["amount"], credit["amount"]);
+            assert_eq!(debit["token"], credit["token"]);
+        }
+
+        // The events sum to the changes of the balances
+        let accounts = pre.keys().chain(post.keys()).collect::<BTreeSet<_>>();
+        for account in accounts {
+            let pre = pre.get(account).copied().unwrap_or_default();
+            let post = post.get(account).copied().unwrap_or_default();
+            let (credits, debits) = sums.remove(account).unwrap_or_default();
+            assert_eq!(
+                pre + credits,
+                post + debits,
+                "The balance change events of {account:?} must match its \
+                 balance"
+            );
+        }
+        assert!(sums.is_empty(), "Events of unknown balances {sums:?}");
+        events
+    }
+
+    /// Test that the balance change events of the blocks that escrow, pay and
+    /// refund a fee sum to the changes of the balances.
+    #[test]
+    fn test_balance_change_events() {
+        let (mut shell, _, _, _) = setup();
+
+        let mut wrapper =
+            Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: DenominatedAmount::native(1.into()),
+                    token: shell.wl_storage.storage.native_token.clone(),
+                },
+                crate::wallet::defaults::albert_keypair().ref_to(),
+                Epoch(0),
+                5_000_000.into(),
+                None,
+            ))));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.set_code(Code::new(TestWasms::TxNoOp.read_bytes(), None));
+        wrapper.set_data(Data::new(
+            "Enxrypted transaction data".as_bytes().to_owned(),
+        ));
+        wrapper.add_section(Section::Signature(Signature::new(
+            wrapper.sechashes(),
+            [(0, crate::wallet::defaults::albert_keypair())]
+                .into_iter()
+                .collect(),
+            None,
+        )));
+        let processed_tx = ProcessedTx {
+            tx: wrapper.to_bytes().into(),
+            result: TxResult {
+                code: ResultCode::Ok.into(),
+                info: "".into(),
+            },
+        };
+        let events = finalize_block_and_check_balance_events(
+            &mut shell,
+            FinalizeBlock {
+                txs: vec![processed_tx],
+                ..Default::default()
+            },
+        );
+        // The fee is escrowed
+        let escrow_credit = events
+            .iter()
+            .find(|event| {
+                event.event_type == EventType::BalanceChange
+                    && event["side"] == "credit"
+                    && event["account"] == FEE_ESCROW.to_string()
+            })
+            .expect("The fee escrow must be credited");
+        assert_eq!(escrow_credit["reason"], "fee");
+        assert_eq!(escrow_credit["hash"], wrapper.header_hash().to_string());
+
+        // The fee is paid and refunded with the inner tx
+        let mut decrypted = wrapper.clone();
+        decrypted.update_header(TxType::Decrypted(DecryptedTx::Decrypted));
+        let processed_tx = ProcessedTx {
+            tx: decrypted.to_bytes().into(),
+            result: TxResult {
+                code: ResultCode::Ok.into(),
+                info: "".into(),
+            },
+        };
+        let events = finalize_block_and_check_balance_events(
+            &mut shell,
+            FinalizeBlock {
+                txs: vec![processed_tx],
+                ..Default::default()
+            },
+        );
+        let escrow_debits = events
+            .iter()
+            .filter(|event| {
+                event.event_type == EventType::BalanceChange
+                    && event["side"] == "debit"
+                    && event["account"] == FEE_ESCROW.to_string()
+            })
+            .count();
+        assert_eq!(escrow_debits, 2);
     }
 
     #[test]
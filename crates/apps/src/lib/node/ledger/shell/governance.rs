@@ -1,11 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use namada::governance::pgf::storage::keys as pgf_storage;
 use namada::governance::pgf::storage::steward::StewardDetail;
 use namada::governance::pgf::{storage as pgf, ADDRESS};
 use namada::governance::storage::keys as gov_storage;
 use namada::governance::storage::proposal::{
-    AddRemove, PGFAction, PGFTarget, ProposalType, StoragePgfFunding,
+    AddRemove, PGFAction, PGFTarget, ProposalType, SlashPoolAction,
+    StoragePgfClaim, StoragePgfFunding,
 };
 use namada::governance::utils::{
     compute_proposal_result, ProposalVotes, TallyResult, TallyType, TallyVote,
@@ -15,10 +16,14 @@ use namada::governance::{storage as gov_api, ADDRESS as gov_address};
 use namada::ledger::governance::utils::ProposalEvent;
 use namada::ledger::pos::BondId;
 use namada::ledger::protocol;
-use namada::proof_of_stake::bond_amount;
 use namada::proof_of_stake::parameters::PosParams;
-use namada::proof_of_stake::storage::read_total_stake;
-use namada::state::{DBIter, StorageHasher, StorageWrite, DB};
+use namada::proof_of_stake::storage::{
+    read_total_stake, record_slash_pool_outflow,
+};
+use namada::proof_of_stake::{
+    bond_amount, record_governance_participation, SLASH_POOL_ADDRESS,
+};
+use namada::state::{DBIter, ResultExt, StorageHasher, StorageWrite, DB};
 use namada::tx::{Code, Data};
 use namada::types::address::Address;
 use namada::types::encode;
@@ -73,8 +78,19 @@ where
             id,
             proposal_end_epoch,
         )?;
+        let voters = votes
+            .validators_vote
+            .keys()
+            .cloned()
+            .collect::<HashSet<Address>>();
+        record_governance_participation(
+            &mut shell.wl_storage,
+            proposal_end_epoch,
+            &voters,
+        )?;
         let proposal_result =
-            compute_proposal_result(votes, total_voting_power, tally_type);
+            compute_proposal_result(votes, total_voting_power, tally_type)
+                .into_storage_result()?;
         gov_api::write_proposal_result(
             &mut shell.wl_storage,
             id,
@@ -157,6 +173,21 @@ where
                         ProposalEvent::pgf_payments_proposal_event(id, result)
                             .into()
                     }
+                    ProposalType::SlashPoolDisbursement(actions) => {
+                        let result = execute_slash_pool_proposal(
+                            &mut shell.wl_storage,
+                            actions,
+                            id,
+                        )?;
+                        tracing::info!(
+                            "Governance proposal (slash pool disbursement) {} \
+                             has been executed and passed.",
+                            id
+                        );
+
+                        ProposalEvent::slash_pool_proposal_event(id, result)
+                            .into()
+                    }
                 };
                 response.events.push(proposal_event);
                 proposals_result.passed.push(id);
@@ -431,8 +462,78 @@ where
                     ),
                 }
             }
+            PGFAction::RetroClaim(claim) => {
+                let current_epoch = storage.storage.block.epoch;
+                let target = claim.target.clone();
+                let claim =
+                    StoragePgfClaim::new(claim, proposal_id, current_epoch);
+                tracing::info!(
+                    "Added RetroPgf claim from proposal id {}: {} claimable \
+                     by {} until epoch {}, up to {} per epoch.",
+                    proposal_id,
+                    claim.remaining.to_string_native(),
+                    target,
+                    claim.expiration,
+                    claim.limit_per_epoch.to_string_native(),
+                );
+                pgf_storage::claims_handle().insert(storage, target, claim)?;
+            }
         }
     }
 
     Ok(true)
 }
+
+fn execute_slash_pool_proposal<D, H>(
+    storage: &mut WlStorage<D, H>,
+    actions: BTreeSet<SlashPoolAction>,
+    proposal_id: u64,
+) -> Result<bool>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let staking_token = storage.get_native_token()?;
+    let current_epoch = storage.storage.block.epoch;
+    let mut result = true;
+
+    for action in actions {
+        let amount = action.amount();
+        let outcome = match &action {
+            SlashPoolAction::Disburse(target) => token::transfer(
+                storage,
+                &staking_token,
+                &SLASH_POOL_ADDRESS,
+                &target.target,
+                target.amount,
+            ),
+            SlashPoolAction::Burn(amount) => token::burn_tokens(
+                storage,
+                &staking_token,
+                &SLASH_POOL_ADDRESS,
+                *amount,
+            ),
+        };
+        match outcome {
+            Ok(()) => {
+                record_slash_pool_outflow(storage, current_epoch, amount)?;
+                tracing::info!(
+                    "Execute slash pool action from proposal id {}: {:?}.",
+                    proposal_id,
+                    action
+                );
+            }
+            Err(e) => {
+                result = false;
+                tracing::warn!(
+                    "Error in slash pool action from proposal id {}, {:?}: {}",
+                    proposal_id,
+                    action,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(result)
+}
@@ -10,11 +10,12 @@ use namada::ledger::parameters::Parameters;
 use namada::ledger::{ibc, pos};
 use namada::proof_of_stake::BecomeValidator;
 use namada::state::{DBIter, StorageHasher, StorageWrite, DB};
-use namada::token::{credit_tokens, write_denom};
+use namada::token::{credit_tokens, write_denom, write_metadata};
 use namada::types::address::Address;
 use namada::types::hash::Hash as CodeHash;
 use namada::types::time::{DateTimeUtc, TimeZone, Utc};
 use namada::vm::validate_untrusted_wasm;
+use namada::vm::wasm::run::instrumented_code_hash;
 use namada_sdk::eth_bridge::EthBridgeStatus;
 use namada_sdk::proof_of_stake::PosParams;
 
@@ -374,11 +375,20 @@ where
                 || tx_allowlist.contains(&code_hash.to_string().to_lowercase())
                 || vp_allowlist.contains(&code_hash.to_string().to_lowercase())
             {
-                self.validate(
-                    validate_untrusted_wasm(&code)
-                        .map_err(|e| Error::LoadingWasm(e.to_string())),
-                )
-                .or_placeholder(None)?;
+                // The hash of the instrumented code is recorded to detect a
+                // drift of the gas metering in the nodes compiling it
+                let instrumented_hash = self
+                    .validate(
+                        validate_untrusted_wasm(&code)
+                            .map_err(|e| e.to_string())
+                            .and_then(|()| {
+                                instrumented_code_hash(&code)
+                                    .map_err(|e| e.to_string())
+                            })
+                            .map_err(Error::LoadingWasm),
+                    )
+                    .or_placeholder(Some(CodeHash::zero()))?
+                    .unwrap();
 
                 #[cfg(not(test))]
                 if name.starts_with("tx_") {
@@ -391,10 +401,15 @@ where
                 let code_len_key = Key::wasm_code_len(&code_hash);
                 let hash_key = Key::wasm_hash(name);
                 let code_name_key = Key::wasm_code_name(name.to_owned());
+                let instrumented_hash_key =
+                    Key::wasm_instrumented_hash(&code_hash);
 
                 self.wl_storage.write_bytes(&code_key, code).unwrap();
                 self.wl_storage.write(&code_len_key, code_len).unwrap();
                 self.wl_storage.write_bytes(&hash_key, code_hash).unwrap();
+                self.wl_storage
+                    .write_bytes(&instrumented_hash_key, instrumented_hash)
+                    .unwrap();
                 if &Some(code_hash) == implicit_vp_code_hash {
                     is_implicit_vp_stored = true;
                 }
@@ -427,10 +442,19 @@ where
 
             let FinalizedTokenConfig {
                 address,
-                config: TokenConfig { denom, masp_params },
+                config:
+                    TokenConfig {
+                        denom,
+                        masp_params,
+                        metadata,
+                    },
             } = token;
             // associate a token with its denomination.
             write_denom(&mut self.wl_storage, address, *denom).unwrap();
+            if let Some(metadata) = metadata {
+                write_metadata(&mut self.wl_storage, address, metadata)
+                    .unwrap();
+            }
             namada::token::write_params(
                 masp_params,
                 &mut self.wl_storage,
@@ -0,0 +1,225 @@
+//! The wrapper txs checked by the mempool of the node, kept in its memory.
+//!
+//! Every check of a wrapper tx by the mempool, on its submission and on the
+//! recheck that follows each committed block, is recorded with its outcome,
+//! served by the `/shell/mempool` and `/shell/mempool_queue` queries. The txs
+//! included in a block are forgotten, as are the txs that haven't been checked
+//! in the last [`MAX_UNCHECKED_BLOCKS`] blocks, which have left the mempool
+//! since the pending txs are rechecked after every block. At most
+//! [`MAX_TRACKED_TXS`] txs are tracked, the least recently checked ones are
+//! forgotten first.
+
+use std::collections::HashMap;
+
+use namada::ledger::queries::{MempoolTx, MempoolTxStatus};
+use namada::tx::data::WrapperTx;
+use namada::types::address::Address;
+use namada::types::hash::Hash;
+use namada::types::storage::BlockHeight;
+
+/// The maximum number of tracked txs
+pub const MAX_TRACKED_TXS: usize = 10_000;
+
+/// The number of blocks after which the txs that haven't been checked again
+/// are forgotten
+pub const MAX_UNCHECKED_BLOCKS: u64 = 10;
+
+/// The log of the wrapper txs checked by the mempool
+#[derive(Debug, Default)]
+pub struct MempoolTxLog {
+    txs: HashMap<Hash, MempoolTx>,
+}
+
+impl MempoolTxLog {
+    /// Record the check of a wrapper tx of the given hash and size, with the
+    /// height of the last committed block
+    pub fn record(
+        &mut self,
+        height: BlockHeight,
+        hash: Hash,
+        wrapper: &WrapperTx,
+        size: usize,
+        status: MempoolTxStatus,
+    ) {
+        let first_checked = self
+            .txs
+            .get(&hash)
+            .map(|tx| tx.first_checked)
+            .unwrap_or(height);
+        self.txs.insert(
+            hash,
+            MempoolTx {
+                hash,
+                fee_payer: wrapper.fee_payer(),
+                fee: wrapper.fee.clone(),
+                gas_limit: wrapper.gas_limit.into(),
+                size: size as u64,
+                status,
+                first_checked,
+                last_checked: height,
+            },
+        );
+        self.prune();
+    }
+
+    /// Forget the txs included in the block at the given height and the txs
+    /// that haven't been checked in the last blocks
+    pub fn finalize_block<I>(&mut self, height: BlockHeight, included: I)
+    where
+        I: IntoIterator<Item = Hash>,
+    {
+        for hash in included {
+            self.txs.remove(&hash);
+        }
+        self.txs.retain(|_, tx| {
+            tx.last_checked.0 + MAX_UNCHECKED_BLOCKS > height.0
+        });
+    }
+
+    /// Get the tracked txs of the given fee payer, oldest first
+    pub fn fee_payer_txs(&self, fee_payer: &Address) -> Vec<MempoolTx> {
+        let mut txs: Vec<_> = self
+            .txs
+            .values()
+            .filter(|tx| &tx.fee_payer == fee_payer)
+            .cloned()
+            .collect();
+        sort(&mut txs);
+        txs
+    }
+
+    /// Get all the tracked txs, oldest first
+    pub fn queue(&self) -> Vec<MempoolTx> {
+        let mut txs: Vec<_> = self.txs.values().cloned().collect();
+        sort(&mut txs);
+        txs
+    }
+
+    /// Forget the least recently checked txs above [`MAX_TRACKED_TXS`]
+    fn prune(&mut self) {
+        let excess = self.txs.len().saturating_sub(MAX_TRACKED_TXS);
+        if excess == 0 {
+            return;
+        }
+        let mut by_height: Vec<_> = self
+            .txs
+            .values()
+            .map(|tx| (tx.last_checked, tx.hash))
+            .collect();
+        by_height.sort_unstable();
+        for (_, hash) in by_height.into_iter().take(excess) {
+            self.txs.remove(&hash);
+        }
+    }
+}
+
+/// Sort the txs by the height they were first checked at, then by hash
+fn sort(txs: &mut [MempoolTx]) {
+    txs.sort_unstable_by_key(|tx| (tx.first_checked, tx.hash));
+}
+
+#[cfg(test)]
+mod tests {
+    use namada::tx::data::Fee;
+    use namada::types::address;
+    use namada::types::key::testing::{keypair_1, keypair_2};
+    use namada::types::key::{common, RefTo};
+    use namada::types::token::DenominatedAmount;
+
+    use super::*;
+
+    fn wrapper(keypair: &common::SecretKey) -> WrapperTx {
+        WrapperTx::new(
+            Fee {
+                amount_per_gas_unit: DenominatedAmount::native(1.into()),
+                token: address::nam(),
+            },
+            keypair.ref_to(),
+            Default::default(),
+            100.into(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_mempool_txs() {
+        let mut log = MempoolTxLog::default();
+        let albert = wrapper(&keypair_1());
+        let bertha = wrapper(&keypair_2());
+        log.record(
+            BlockHeight(1),
+            Hash([1; 32]),
+            &albert,
+            10,
+            MempoolTxStatus::Accepted,
+        );
+        log.record(
+            BlockHeight(1),
+            Hash([2; 32]),
+            &bertha,
+            20,
+            MempoolTxStatus::Accepted,
+        );
+        // The recheck of a tx keeps the height it was first checked at
+        let rejected = MempoolTxStatus::Rejected {
+            code: 1,
+            log: "Insufficient balance for fee".to_string(),
+        };
+        log.record(
+            BlockHeight(2),
+            Hash([1; 32]),
+            &albert,
+            10,
+            rejected.clone(),
+        );
+
+        let txs = log.fee_payer_txs(&albert.fee_payer());
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].hash, Hash([1; 32]));
+        assert_eq!(txs[0].gas_limit, 100);
+        assert_eq!(txs[0].size, 10);
+        assert_eq!(txs[0].status, rejected);
+        assert!(!txs[0].is_pending());
+        assert_eq!(txs[0].first_checked, BlockHeight(1));
+        assert_eq!(txs[0].last_checked, BlockHeight(2));
+        assert_eq!(log.queue().len(), 2);
+
+        // The included txs are forgotten
+        log.finalize_block(BlockHeight(3), [Hash([2; 32])]);
+        assert!(log.fee_payer_txs(&bertha.fee_payer()).is_empty());
+        assert_eq!(log.queue().len(), 1);
+
+        // As are the txs that haven't been checked in the last blocks
+        log.finalize_block(BlockHeight(2 + MAX_UNCHECKED_BLOCKS - 1), []);
+        assert_eq!(log.queue().len(), 1);
+        log.finalize_block(BlockHeight(2 + MAX_UNCHECKED_BLOCKS), []);
+        assert!(log.queue().is_empty());
+    }
+
+    #[test]
+    fn test_mempool_txs_pruning() {
+        let mut log = MempoolTxLog::default();
+        let wrapper = wrapper(&keypair_1());
+        log.record(
+            BlockHeight(1),
+            Hash([0; 32]),
+            &wrapper,
+            1,
+            MempoolTxStatus::Accepted,
+        );
+        for tx in 1..=MAX_TRACKED_TXS {
+            log.record(
+                BlockHeight(2),
+                Hash::sha256(tx.to_le_bytes()),
+                &wrapper,
+                1,
+                MempoolTxStatus::Accepted,
+            );
+        }
+
+        // The least recently checked tx is forgotten
+        let txs = log.queue();
+        assert_eq!(txs.len(), MAX_TRACKED_TXS);
+        assert!(txs.iter().all(|tx| tx.hash != Hash([0; 32])));
+    }
+}
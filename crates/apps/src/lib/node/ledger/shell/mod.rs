@@ -5,36 +5,49 @@
 //! and [`Shell::process_proposal`] must be also reverted
 //! (unless we can simply overwrite them in the next block).
 //! More info in <https://github.com/anoma/namada/issues/362>.
+mod balance_events;
 pub mod block_alloc;
+mod code_stats;
+pub mod epoch_hooks;
 mod finalize_block;
 mod governance;
 mod init_chain;
+mod mempool_txs;
 pub use init_chain::InitChainValidation;
 use namada_sdk::tx::data::GasLimit;
 pub mod prepare_proposal;
 pub mod process_proposal;
 pub(super) mod queries;
+pub mod query_snapshot;
+mod replay_filter;
 mod stats;
 #[cfg(any(test, feature = "testing"))]
 #[allow(dead_code)]
 pub mod testing;
+#[cfg(any(test, feature = "time-travel"))]
+pub mod time_travel;
+pub mod tx_quarantine;
 pub mod utils;
 mod vote_extensions;
 
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::convert::{TryFrom, TryInto};
 use std::mem;
 use std::path::{Path, PathBuf};
 #[allow(unused_imports)]
 use std::rc::Rc;
+use std::sync::{Arc, RwLock};
 
 use borsh::BorshDeserialize;
 use borsh_ext::BorshSerializeExt;
 use masp_primitives::transaction::Transaction;
 use namada::core::hints;
+use namada::ethereum_bridge::protocol::transactions::checkpoints;
 use namada::ethereum_bridge::protocol::validation::bridge_pool_roots::validate_bp_roots_vext;
+use namada::ethereum_bridge::protocol::validation::checkpoints::validate_checkpoint_vext;
 use namada::ethereum_bridge::protocol::validation::ethereum_events::validate_eth_events_vext;
 use namada::ethereum_bridge::protocol::validation::validator_set_update::validate_valset_upd_vext;
+use namada::ethereum_bridge::protocol::validation::withdrawal_vetoes::validate_withdrawal_veto_vext;
 use namada::ledger::events::log::EventLog;
 use namada::ledger::events::Event;
 use namada::ledger::gas::{Gas, TxGasMeter};
@@ -46,6 +59,7 @@ use namada::ledger::protocol::{
     apply_wasm_tx, get_fee_unshielding_transaction,
     get_transfer_hash_from_storage, ShellParams,
 };
+use namada::ledger::queries::MempoolTxStatus;
 use namada::ledger::{parameters, pos, protocol};
 use namada::parameters::validate_tx_bytes;
 use namada::proof_of_stake::slashing::{process_slashes, slash};
@@ -66,6 +80,7 @@ use namada::types::address;
 use namada::types::address::Address;
 use namada::types::chain::ChainId;
 use namada::types::ethereum_events::EthereumEvent;
+use namada::types::hash::Hash;
 use namada::types::key::*;
 use namada::types::storage::{BlockHeight, Key, TxIndex};
 use namada::types::time::DateTimeUtc;
@@ -77,6 +92,12 @@ use namada_sdk::tendermint::AppHash;
 use thiserror::Error;
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
 
+use self::balance_events::BalanceEvents;
+use self::code_stats::CodeStatsLog;
+use self::epoch_hooks::EpochTransitionHooks;
+use self::mempool_txs::MempoolTxLog;
+use self::replay_filter::ReplayFilter;
+use self::tx_quarantine::TxQuarantine;
 use super::ethereum_oracle::{self as oracle, last_processed_block};
 use crate::config::{self, genesis, TendermintMode, ValidatorLocalConfig};
 use crate::facade::tendermint::abci::types::{Misbehavior, MisbehaviorKind};
@@ -129,6 +150,8 @@ pub enum Error {
     Storage(#[from] namada::state::StorageError),
     #[error("Transaction replay attempt: {0}")]
     ReplayAttempt(String),
+    #[error("Error opening the query snapshot: {0}")]
+    QuerySnapshot(String),
 }
 
 impl From<Error> for TxResult {
@@ -149,6 +172,11 @@ pub fn reset(config: config::Ledger) -> Result<()> {
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
         res => res.map_err(Error::RemoveDB)?,
     };
+    let query_snapshot_path = config.shell.query_snapshot_dir(&config.chain_id);
+    match std::fs::remove_dir_all(query_snapshot_path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+        res => res.map_err(Error::RemoveDB)?,
+    };
     // reset Tendermint state
     tendermint_node::reset(config.cometbft_dir()).map_err(Error::Tendermint)?;
     Ok(())
@@ -357,10 +385,31 @@ where
     /// limit the how many block heights in the past can the storage be
     /// queried for reading values.
     storage_read_past_height_limit: Option<u64>,
+    /// Taken from config `minimum_gas_prices`. The local minimum gas prices
+    /// per fee token, enforced in the mempool above the governance floor.
+    minimum_gas_prices: BTreeMap<Address, token::Amount>,
     /// Proposal execution tracking
     pub proposal_data: HashSet<u64>,
     /// Log of events emitted by `FinalizeBlock` ABCI calls.
     event_log: EventLog,
+    /// Log of the executions of the tx and VP codes, shared with the query
+    /// snapshot
+    code_stats: Arc<RwLock<CodeStatsLog>>,
+    /// Log of the wrapper txs checked by the mempool, shared with the query
+    /// snapshot
+    mempool_txs: Arc<RwLock<MempoolTxLog>>,
+    /// Quarantine of the malformed txs received by the mempool, if enabled
+    tx_quarantine: Option<TxQuarantine>,
+    /// Bloom filter of the tx hashes in the replay protection storage, for
+    /// the replay checks of the mempool
+    replay_filter: ReplayFilter,
+    /// Hooks run when a new epoch begins
+    epoch_transition_hooks: EpochTransitionHooks<D, H>,
+    /// The balances changed in the block being finalized
+    balance_events: BalanceEvents,
+    /// Test-only controls of the epochs and the block time
+    #[cfg(any(test, feature = "time-travel"))]
+    time_travel: time_travel::TimeTravel,
 }
 
 /// Merkle tree storage key filter. Return `false` for keys that shouldn't be
@@ -415,6 +464,19 @@ where
         let mode = config.shell.tendermint_mode;
         let storage_read_past_height_limit =
             config.shell.storage_read_past_height_limit;
+        let minimum_gas_prices: BTreeMap<Address, token::Amount> = config
+            .shell
+            .minimum_gas_prices
+            .into_iter()
+            .map(|(token, price)| (token, price.into()))
+            .collect();
+        let tx_quarantine =
+            config.shell.tx_quarantine_capacity.map(|capacity| {
+                TxQuarantine::new(
+                    config.shell.tx_quarantine_dir(&chain_id),
+                    capacity,
+                )
+            });
         if !Path::new(&base_dir).is_dir() {
             std::fs::create_dir(&base_dir)
                 .expect("Creating directory for Namada should not fail");
@@ -514,10 +576,18 @@ where
             TendermintMode::Seed => ShellMode::Seed,
         };
 
+        let mut replay_filter = ReplayFilter::default();
+        replay_filter.rebuild(
+            storage
+                .iter_replay_protection()
+                .chain(storage.iter_all_replay_protection()),
+        );
+
         let wl_storage = WlStorage {
             storage,
             write_log: WriteLog::default(),
         };
+        warn_ineffective_gas_prices(&wl_storage, &minimum_gas_prices);
         let mut shell = Self {
             chain_id,
             wl_storage,
@@ -534,9 +604,18 @@ where
                 tx_wasm_compilation_cache as usize,
             ),
             storage_read_past_height_limit,
+            minimum_gas_prices,
             proposal_data: HashSet::new(),
             // TODO: config event log params
             event_log: EventLog::default(),
+            code_stats: Arc::default(),
+            mempool_txs: Arc::default(),
+            tx_quarantine,
+            replay_filter,
+            epoch_transition_hooks: EpochTransitionHooks::default(),
+            balance_events: BalanceEvents::default(),
+            #[cfg(any(test, feature = "time-travel"))]
+            time_travel: Default::default(),
         };
         shell.update_eth_oracle(&Default::default());
         shell
@@ -773,6 +852,10 @@ where
             retain_height: tendermint::block::Height::from(0_u32),
             ..Default::default()
         };
+        // Add the tx hashes of the block to the replay filter of the mempool
+        for hash in self.wl_storage.write_log.written_tx_hashes() {
+            self.replay_filter.insert(hash);
+        }
         // commit block's data from write log and store the in DB
         self.wl_storage.commit_block().unwrap_or_else(|e| {
             tracing::error!(
@@ -780,6 +863,7 @@ where
                 e
             )
         });
+        self.update_replay_filter();
 
         let root = self.wl_storage.storage.merkle_root();
         tracing::info!(
@@ -795,6 +879,28 @@ where
         response
     }
 
+    /// Rebuild the replay filter of the mempool from storage once it's full,
+    /// dropping the hashes pruned from storage, and log its counters
+    fn update_replay_filter(&mut self) {
+        if self.replay_filter.is_full() {
+            let storage = &self.wl_storage.storage;
+            self.replay_filter.rebuild(
+                storage
+                    .iter_replay_protection()
+                    .chain(storage.iter_all_replay_protection()),
+            );
+            tracing::info!("Rebuilt the replay protection filter");
+        }
+        let stats = self.replay_filter.stats();
+        tracing::debug!(
+            "Replay protection filter: {} checks, {} storage reads, {} false \
+             positives",
+            stats.checks,
+            stats.fallback_reads,
+            stats.false_positives,
+        );
+    }
+
     /// Updates the Ethereum oracle's last processed block.
     #[inline]
     fn bump_last_processed_eth_block(&mut self) {
@@ -832,10 +938,35 @@ where
     fn broadcast_queued_txs(&mut self) {
         if let ShellMode::Validator { .. } = &self.mode {
             self.broadcast_protocol_txs();
+            self.broadcast_checkpoint_sig();
             self.broadcast_expired_txs();
         }
     }
 
+    /// Broadcast our signature over the checkpoint of the app hash
+    /// recorded in the last block, if any.
+    fn broadcast_checkpoint_sig(&mut self) {
+        let (Some(validator_addr), Some(eth_hot_key), Some(protocol_key)) = (
+            self.mode.get_validator_address(),
+            self.mode.get_eth_bridge_keypair(),
+            self.mode.get_protocol_key(),
+        ) else {
+            return;
+        };
+        let Some(ext) = checkpoints::sign_checkpoint(
+            &self.wl_storage,
+            validator_addr,
+            eth_hot_key,
+            protocol_key,
+        ) else {
+            return;
+        };
+        let tx = EthereumTxData::CheckpointVext(ext)
+            .sign(protocol_key, self.chain_id.clone())
+            .to_bytes();
+        self.mode.broadcast(tx);
+    }
+
     /// Broadcast any pending protocol transactions.
     fn broadcast_protocol_txs(&mut self) {
         use crate::node::ledger::shell::vote_extensions::iter_protocol_txs;
@@ -998,6 +1129,25 @@ where
         }
     }
 
+    /// Record a failure of the malformed tx with the given bytes in the
+    /// quarantine, if enabled
+    fn quarantine_tx(&self, tx_bytes: &[u8], reason: impl ToString) {
+        if let Some(quarantine) = &self.tx_quarantine {
+            quarantine.record_failure(tx_bytes, reason);
+        }
+    }
+
+    /// Check if the given tx hash is in the replay protection storage, reading
+    /// the storage only if it may be in the replay filter
+    fn has_committed_tx_hash(
+        &self,
+        hash: &Hash,
+    ) -> namada::state::Result<bool> {
+        self.replay_filter.contains(hash, || {
+            self.wl_storage.storage.has_replay_protection_entry(hash)
+        })
+    }
+
     /// Validate a transaction request. On success, the transaction will
     /// included in the mempool and propagated to peers, otherwise it will be
     /// rejected.
@@ -1005,6 +1155,40 @@ where
         &self,
         tx_bytes: &[u8],
         r#_type: MempoolTxType,
+    ) -> response::CheckTx {
+        let mut checked_wrapper = None;
+        let response = self.check_mempool_tx(tx_bytes, &mut checked_wrapper);
+        // Record the outcome of the check of the wrapper txs with a valid
+        // signature, for the queries of their fee payers
+        if let Some((hash, wrapper)) = checked_wrapper {
+            let status = if response.code == ResultCode::Ok.into() {
+                MempoolTxStatus::Accepted
+            } else {
+                MempoolTxStatus::Rejected {
+                    code: response.code.value(),
+                    log: response.log.clone(),
+                }
+            };
+            self.mempool_txs
+                .write()
+                .expect("The mempool txs lock must not be poisoned")
+                .record(
+                    self.wl_storage.storage.get_last_block_height(),
+                    hash,
+                    &wrapper,
+                    tx_bytes.len(),
+                    status,
+                );
+        }
+        response
+    }
+
+    /// Run the mempool checks of a tx. The hash and the header of a wrapper
+    /// tx are set in `checked_wrapper` once its signature is validated.
+    fn check_mempool_tx(
+        &self,
+        tx_bytes: &[u8],
+        checked_wrapper: &mut Option<(Hash, Box<WrapperTx>)>,
     ) -> response::CheckTx {
         use namada::tx::data::protocol::ProtocolTxType;
         use namada::vote_ext::ethereum_tx_data_variants;
@@ -1030,6 +1214,7 @@ where
         let tx = match Tx::try_from(tx_bytes).map_err(Error::TxDecoding) {
             Ok(t) => t,
             Err(msg) => {
+                self.quarantine_tx(tx_bytes, &msg);
                 response.code = ResultCode::InvalidTx.into();
                 response.log = format!("{INVALID_MSG}: {msg}");
                 return response;
@@ -1069,6 +1254,7 @@ where
         let tx_type = match tx.validate_tx() {
             Ok(_) => tx.header(),
             Err(msg) => {
+                self.quarantine_tx(tx_bytes, &msg);
                 response.code = ResultCode::InvalidSig.into();
                 response.log = format!("{INVALID_MSG}: {msg}");
                 return response;
@@ -1170,6 +1356,50 @@ where
                         response.priority = i64::MAX;
                     }
                 }
+                ProtocolTxType::WithdrawalVetoVext => {
+                    let ext = try_vote_extension!(
+                        "withdrawal veto",
+                        response,
+                        ethereum_tx_data_variants::WithdrawalVetoVext::try_from(
+                            &tx
+                        ),
+                    );
+                    if let Err(err) = validate_withdrawal_veto_vext(
+                        &self.wl_storage,
+                        &ext.0,
+                        self.wl_storage.storage.get_last_block_height(),
+                    ) {
+                        response.code = ResultCode::InvalidVoteExtension.into();
+                        response.log = format!(
+                            "{INVALID_MSG}: Invalid withdrawal veto vote \
+                             extension: {err}",
+                        );
+                    } else {
+                        response.log = String::from(VALID_MSG);
+                    }
+                }
+                ProtocolTxType::CheckpointVext => {
+                    let ext = try_vote_extension!(
+                        "checkpoint",
+                        response,
+                        ethereum_tx_data_variants::CheckpointVext::try_from(
+                            &tx
+                        ),
+                    );
+                    if let Err(err) = validate_checkpoint_vext(
+                        &self.wl_storage,
+                        &ext.0,
+                        self.wl_storage.storage.get_last_block_height(),
+                    ) {
+                        response.code = ResultCode::InvalidVoteExtension.into();
+                        response.log = format!(
+                            "{INVALID_MSG}: Invalid checkpoint vote \
+                             extension: {err}",
+                        );
+                    } else {
+                        response.log = String::from(VALID_MSG);
+                    }
+                }
                 _ => {
                     response.code = ResultCode::InvalidTx.into();
                     response.log = format!(
@@ -1179,6 +1409,8 @@ where
                 }
             },
             TxType::Wrapper(wrapper) => {
+                *checked_wrapper = Some((tx.header_hash(), wrapper.clone()));
+
                 // Tx gas limit
                 let mut gas_meter = TxGasMeter::new(wrapper.gas_limit);
                 if gas_meter.add_wrapper_gas(tx_bytes).is_err() {
@@ -1202,12 +1434,24 @@ where
                     return response;
                 }
 
+                // Max tx gas
+                let max_tx_gas: Gas = Gas::from_whole_units(
+                    namada::parameters::get_max_tx_gas(&self.wl_storage)
+                        .unwrap(),
+                );
+                if gas_meter.tx_gas_limit > max_tx_gas {
+                    response.code = ResultCode::TxGasLimit.into();
+                    response.log = format!(
+                        "{INVALID_MSG}: Wrapper transaction exceeds the \
+                         maximum gas of a transaction"
+                    );
+                    return response;
+                }
+
                 // Replay protection check
                 let inner_tx_hash = tx.raw_header_hash();
                 if self
-                    .wl_storage
-                    .storage
-                    .has_replay_protection_entry(&tx.raw_header_hash())
+                    .has_committed_tx_hash(&inner_tx_hash)
                     .expect("Error while checking inner tx hash key in storage")
                 {
                     response.code = ResultCode::ReplayTx.into();
@@ -1222,14 +1466,9 @@ where
                 let tx = Tx::try_from(tx_bytes)
                     .expect("Deserialization shouldn't fail");
                 let wrapper_hash = &tx.header_hash();
-                if self
-                    .wl_storage
-                    .storage
-                    .has_replay_protection_entry(wrapper_hash)
-                    .expect(
-                        "Error while checking wrapper tx hash key in storage",
-                    )
-                {
+                if self.has_committed_tx_hash(wrapper_hash).expect(
+                    "Error while checking wrapper tx hash key in storage",
+                ) {
                     response.code = ResultCode::ReplayTx.into();
                     response.log = format!(
                         "{INVALID_MSG}: Wrapper transaction hash {} already \
@@ -1243,6 +1482,7 @@ where
                 if let Err(e) = mempool_fee_check(
                     &wrapper,
                     get_fee_unshielding_transaction(&tx, &wrapper),
+                    &self.minimum_gas_prices,
                     &mut TempWlStorage::new(&self.wl_storage.storage),
                     &mut self.vp_wasm_cache.clone(),
                     &mut self.tx_wasm_cache.clone(),
@@ -1340,6 +1580,38 @@ where
     }
 }
 
+/// Warn about the local minimum gas prices that have no effect, because they
+/// are not above the governance floor or their token is not allowed for fee
+/// payment
+fn warn_ineffective_gas_prices<D, H>(
+    wl_storage: &WlStorage<D, H>,
+    minimum_gas_prices: &BTreeMap<Address, token::Amount>,
+) where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    // The parameters are only known once the chain is initialized
+    if minimum_gas_prices.is_empty() || wl_storage.storage.last_block.is_none()
+    {
+        return;
+    }
+    let gas_cost_table = parameters::read_gas_cost_table(wl_storage)
+        .expect("Must be able to read gas cost parameter");
+    for (token, price) in minimum_gas_prices {
+        match gas_cost_table.get(token) {
+            None => tracing::warn!(
+                "The minimum gas price of {token} is ignored, the token is \
+                 not allowed for fee payment"
+            ),
+            Some(floor) if price <= floor => tracing::warn!(
+                "The minimum gas price {price} of {token} is not above the \
+                 governance floor {floor}, the floor applies"
+            ),
+            Some(_) => {}
+        }
+    }
+}
+
 /// Checks that neither the wrapper nor the inner transaction have already
 /// been applied. Requires a [`TempWlStorage`] to perform the check during
 /// block construction and validation
@@ -1381,10 +1653,12 @@ where
         .map_err(|e| Error::ReplayAttempt(e.to_string()))
 }
 
-// Perform the fee check in mempool
+// Perform the fee check in mempool, with the local minimum gas prices of the
+// node above the governance floor
 fn mempool_fee_check<D, H, CA>(
     wrapper: &WrapperTx,
     masp_transaction: Option<Transaction>,
+    minimum_gas_prices: &BTreeMap<Address, token::Amount>,
     temp_wl_storage: &mut TempWlStorage<D, H>,
     vp_wasm_cache: &mut VpCache<CA>,
     tx_wasm_cache: &mut TxCache<CA>,
@@ -1394,8 +1668,9 @@ where
     H: StorageHasher + Sync + 'static,
     CA: 'static + WasmCacheAccess + Sync,
 {
-    let minimum_gas_price = namada::ledger::parameters::read_gas_cost(
+    let minimum_gas_price = parameters::read_minimum_gas_price(
         temp_wl_storage,
+        minimum_gas_prices,
         &wrapper.fee.token,
     )
     .expect("Must be able to read gas cost parameter")
@@ -1554,6 +1829,7 @@ mod test_utils {
     use std::path::PathBuf;
 
     use data_encoding::HEXUPPER;
+    use namada::ledger::events::EventType;
     use namada::ledger::parameters::{EpochDuration, Parameters};
     use namada::proof_of_stake::parameters::PosParams;
     use namada::proof_of_stake::storage::validator_consensus_key_handle;
@@ -1829,13 +2105,20 @@ mod test_utils {
         }
 
         /// Forward a FinalizeBlock request return a vector of
-        /// the events created for each transaction
+        /// the events created for each transaction, without the balance
+        /// change events
         pub fn finalize_block(
             &mut self,
             req: FinalizeBlock,
         ) -> Result<Vec<Event>> {
             match self.shell.finalize_block(req) {
-                Ok(resp) => Ok(resp.events),
+                Ok(resp) => Ok(resp
+                    .events
+                    .into_iter()
+                    .filter(|event| {
+                        event.event_type != EventType::BalanceChange
+                    })
+                    .collect()),
                 Err(err) => Err(err),
             }
         }
@@ -1865,6 +2148,7 @@ mod test_utils {
             self.shell.wl_storage.storage.tx_queue.push(TxInQueue {
                 tx,
                 gas: inner_tx_gas,
+                block_proposer: crate::wallet::defaults::validator_address(),
             });
         }
 
@@ -2111,6 +2395,7 @@ mod test_utils {
         shell.wl_storage.storage.tx_queue.push(TxInQueue {
             tx: wrapper,
             gas: u64::MAX.into(),
+            block_proposer: crate::wallet::defaults::validator_address(),
         });
         // Artificially increase the block height so that chain
         // will read the new block when restarted
@@ -2140,6 +2425,9 @@ mod test_utils {
             fee_unshielding_gas_limit: 0,
             fee_unshielding_descriptions_limit: 0,
             minimum_gas_price: Default::default(),
+            max_native_mint_per_epoch: None,
+            masp_conversion_retention_epochs: None,
+            max_tx_gas: None,
         };
         parameters::init_storage(&params, &mut shell.wl_storage)
             .expect("Test failed");
@@ -2629,6 +2917,33 @@ mod shell_tests {
         assert_eq!(result.code, ResultCode::InvalidSig.into());
     }
 
+    /// Mempool validation must quarantine the bytes of the txs that
+    /// repeatedly fail to be decoded
+    #[test]
+    fn test_malformed_tx_quarantine() {
+        let (shell, _recv, _, _) = test_utils::setup();
+        let tx_bytes = b"malformed tx".to_vec();
+
+        for _ in 0..tx_quarantine::QUARANTINE_AFTER_FAILURES {
+            let result = shell
+                .mempool_validate(&tx_bytes, MempoolTxType::NewTransaction);
+            assert_eq!(result.code, ResultCode::InvalidTx.into());
+        }
+
+        let entries = shell
+            .tx_quarantine
+            .as_ref()
+            .expect("The quarantine is enabled by default")
+            .entries()
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].1.bytes, tx_bytes);
+        assert_eq!(
+            entries[0].1.failures,
+            tx_quarantine::QUARANTINE_AFTER_FAILURES
+        );
+    }
+
     /// Mempool validation must reject non-wrapper txs
     #[test]
     fn test_wrong_tx_type() {
@@ -2689,6 +3004,7 @@ mod shell_tests {
             .storage
             .write_replay_protection_entry(&mut batch, &wrapper_hash_key)
             .expect("Test failed");
+        shell.replay_filter.insert(&wrapper_hash);
 
         // Try wrapper tx replay attack
         let result = shell.mempool_validate(
@@ -2727,6 +3043,7 @@ mod shell_tests {
             .storage
             .write_replay_protection_entry(&mut batch, &inner_hash_key)
             .expect("Test failed");
+        shell.replay_filter.insert(&inner_tx_hash);
 
         // Try inner tx replay attack
         let result = shell.mempool_validate(
@@ -2842,6 +3159,53 @@ mod shell_tests {
         assert_eq!(result.code, ResultCode::AllocationError.into());
     }
 
+    /// Check that a tx exceeding the max gas of a transaction, but not the
+    /// one of a block, gets rejected
+    #[test]
+    fn test_exceeding_max_tx_gas_tx() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+
+        let block_gas_limit =
+            parameters::get_max_block_gas(&shell.wl_storage).unwrap();
+        let max_tx_gas = block_gas_limit / 2;
+        parameters::update_max_tx_gas_parameter(
+            &mut shell.wl_storage,
+            Some(max_tx_gas),
+        )
+        .unwrap();
+        assert_eq!(
+            parameters::get_max_tx_gas(&shell.wl_storage).unwrap(),
+            max_tx_gas
+        );
+        let keypair = super::test_utils::gen_keypair();
+
+        let mut wrapper =
+            Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: DenominatedAmount::native(100.into()),
+                    token: shell.wl_storage.storage.native_token.clone(),
+                },
+                keypair.ref_to(),
+                Epoch(0),
+                (max_tx_gas + 1).into(),
+                None,
+            ))));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.set_code(Code::new("wasm_code".as_bytes().to_owned(), None));
+        wrapper.set_data(Data::new("transaction data".as_bytes().to_owned()));
+        wrapper.add_section(Section::Signature(Signature::new(
+            wrapper.sechashes(),
+            [(0, keypair)].into_iter().collect(),
+            None,
+        )));
+
+        let result = shell.mempool_validate(
+            wrapper.to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, ResultCode::TxGasLimit.into());
+    }
+
     // Check that a tx requiring more gas than its limit gets rejected
     #[test]
     fn test_exceeding_gas_limit_tx() {
@@ -2951,6 +3315,66 @@ mod shell_tests {
         assert_eq!(result.code, ResultCode::FeeError.into());
     }
 
+    // Check that a wrapper paying the governance minimum gas price is rejected
+    // if it's below the local minimum gas price of the node
+    #[test]
+    fn test_fee_below_local_minimum_gas_price() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        let floor = parameters::read_gas_cost(&shell.wl_storage, &native_token)
+            .expect("Failed to read from storage")
+            .expect("The native token must be allowed for fee payment");
+
+        let mut wrapper =
+            Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: DenominatedAmount::native(floor),
+                    token: native_token.clone(),
+                },
+                crate::wallet::defaults::albert_keypair().ref_to(),
+                Epoch(0),
+                GAS_LIMIT_MULTIPLIER.into(),
+                None,
+            ))));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.set_code(Code::new("wasm_code".as_bytes().to_owned(), None));
+        wrapper.set_data(Data::new("transaction data".as_bytes().to_owned()));
+        wrapper.add_section(Section::Signature(Signature::new(
+            wrapper.sechashes(),
+            [(0, crate::wallet::defaults::albert_keypair())]
+                .into_iter()
+                .collect(),
+            None,
+        )));
+
+        let result = shell.mempool_validate(
+            wrapper.to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_ne!(result.code, ResultCode::FeeError.into());
+
+        // A local price above the floor is enforced
+        let local_price = floor.checked_add(1.into()).unwrap();
+        shell.minimum_gas_prices = BTreeMap::from([
+            (native_token.clone(), local_price),
+            (address::apfel(), 1.into()),
+        ]);
+        let result = shell.mempool_validate(
+            wrapper.to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, ResultCode::FeeError.into());
+
+        // The local prices of tokens not allowed for fee payment are ignored
+        let prices = parameters::read_minimum_gas_prices(
+            &shell.wl_storage,
+            &shell.minimum_gas_prices,
+        )
+        .expect("Failed to read from storage");
+        assert_eq!(prices.get(&native_token), Some(&local_price));
+        assert!(!prices.contains_key(&address::apfel()));
+    }
+
     // Check that a wrapper transactions whose fees cannot be paid is rejected
     #[test]
     fn test_insufficient_balance_for_fee() {
@@ -20,7 +20,9 @@ use super::block_alloc::states::{
     BuildingDecryptedTxBatch, BuildingProtocolTxBatch,
     EncryptedTxBatchAllocator, NextState, TryAlloc,
 };
-use super::block_alloc::{AllocFailure, BlockAllocator, BlockResources};
+use super::block_alloc::{
+    AllocFailure, BlockAllocator, BlockResources, ProtocolTxsSpace,
+};
 use crate::config::ValidatorLocalConfig;
 use crate::facade::tendermint_proto::google::protobuf::Timestamp;
 use crate::facade::tendermint_proto::v0_37::abci::RequestPrepareProposal;
@@ -217,6 +219,7 @@ where
                 |TxInQueue {
                      tx,
                      gas: _,
+                     block_proposer: _,
                 }| {
                     let mut tx = tx.clone();
                     tx.update_header(TxType::Decrypted(DecryptedTx::Decrypted));
@@ -274,7 +277,7 @@ where
 
         let deserialized_iter = self.deserialize_vote_extensions(txs);
 
-        deserialized_iter.take_while(|tx_bytes|
+        let txs = deserialized_iter.take_while(|tx_bytes|
             alloc.try_alloc(&tx_bytes[..])
                 .map_or_else(
                     |status| match status {
@@ -312,7 +315,22 @@ where
                     |()| true,
                 )
         )
-        .collect()
+        .collect();
+
+        let ProtocolTxsSpace {
+            occupied,
+            allotted,
+            reserved,
+        } = alloc.protocol_txs_space();
+        tracing::debug!(
+            num_of_txs = txs.len(),
+            occupied,
+            allotted,
+            reserved,
+            proposal_height = ?self.get_current_decision_height(),
+            "Allocated block space to protocol txs",
+        );
+        txs
     }
 }
 
@@ -351,6 +369,13 @@ where
         let mut tx_gas_meter = TxGasMeter::new(wrapper.gas_limit);
         tx_gas_meter.add_wrapper_gas(tx_bytes).map_err(|_| ())?;
 
+        // Check tx gas limit against the max gas of a transaction
+        let max_tx_gas = namada::parameters::get_max_tx_gas(&*temp_wl_storage)
+            .map_err(|_| ())?;
+        if u64::from(wrapper.gas_limit) > max_tx_gas {
+            return Err(());
+        }
+
         super::replay_protection_checks(&tx, temp_wl_storage)
             .map_err(|_| ())?;
 
@@ -393,12 +418,12 @@ where
             Some(config) => config
                 .accepted_gas_tokens
                 .get(&wrapper.fee.token)
+                .map(|price| price.0)
                 .ok_or(Error::TxApply(protocol::Error::FeeError(format!(
                     "The provided {} token is not accepted by the block \
                      proposer for fee payment",
                     wrapper.fee.token
-                ))))?
-                .to_owned(),
+                ))))?,
             None => namada::ledger::parameters::read_gas_cost(
                 temp_wl_storage,
                 &wrapper.fee.token,
@@ -1185,7 +1210,7 @@ mod test_prepare_proposal {
             *local_config = Some(ValidatorLocalConfig {
                 accepted_gas_tokens: std::collections::HashMap::from([(
                     namada::types::address::nam(),
-                    Amount::from(1),
+                    Amount::from(1).into(),
                 )]),
             });
         }
@@ -1291,7 +1316,7 @@ mod test_prepare_proposal {
             *local_config = Some(ValidatorLocalConfig {
                 accepted_gas_tokens: std::collections::HashMap::from([(
                     namada::types::address::nam(),
-                    Amount::from(100),
+                    Amount::from(100).into(),
                 )]),
             });
         }
@@ -4,8 +4,10 @@
 use data_encoding::HEXUPPER;
 use namada::core::hints;
 use namada::ethereum_bridge::protocol::validation::bridge_pool_roots::validate_bp_roots_vext;
+use namada::ethereum_bridge::protocol::validation::checkpoints::validate_checkpoint_vext;
 use namada::ethereum_bridge::protocol::validation::ethereum_events::validate_eth_events_vext;
 use namada::ethereum_bridge::protocol::validation::validator_set_update::validate_valset_upd_vext;
+use namada::ethereum_bridge::protocol::validation::withdrawal_vetoes::validate_withdrawal_veto_vext;
 use namada::ledger::pos::PosQueries;
 use namada::ledger::protocol::get_fee_unshielding_transaction;
 use namada::ledger::storage::tx_queue::TxInQueue;
@@ -437,6 +439,64 @@ where
                             }
                         })
                     }
+                    ProtocolTxType::WithdrawalVetoVext => {
+                        ethereum_tx_data_variants::WithdrawalVetoVext::try_from(
+                            &tx,
+                        )
+                        .map_err(|err| err.to_string())
+                        .and_then(|ext| {
+                            validate_withdrawal_veto_vext(
+                                &self.wl_storage,
+                                &ext.0,
+                                self.wl_storage.storage.get_last_block_height(),
+                            )
+                            .map(|_| TxResult {
+                                code: ResultCode::Ok.into(),
+                                info: "Process Proposal accepted this \
+                                       transaction"
+                                    .into(),
+                            })
+                            .map_err(|err| err.to_string())
+                        })
+                        .unwrap_or_else(|err| {
+                            TxResult {
+                                code: ResultCode::InvalidVoteExtension.into(),
+                                info: format!(
+                                    "Process proposal rejected this proposal \
+                                     because one of the included withdrawal \
+                                     veto vote extensions was invalid: {err}"
+                                ),
+                            }
+                        })
+                    }
+                    ProtocolTxType::CheckpointVext => {
+                        ethereum_tx_data_variants::CheckpointVext::try_from(&tx)
+                            .map_err(|err| err.to_string())
+                            .and_then(|ext| {
+                                validate_checkpoint_vext(
+                                    &self.wl_storage,
+                                    &ext.0,
+                                    self.wl_storage
+                                        .storage
+                                        .get_last_block_height(),
+                                )
+                                .map(|_| TxResult {
+                                    code: ResultCode::Ok.into(),
+                                    info: "Process Proposal accepted this \
+                                           transaction"
+                                        .into(),
+                                })
+                                .map_err(|err| err.to_string())
+                            })
+                            .unwrap_or_else(|err| TxResult {
+                                code: ResultCode::InvalidVoteExtension.into(),
+                                info: format!(
+                                    "Process proposal rejected this proposal \
+                                     because one of the included checkpoint \
+                                     vote extensions was invalid: {err}"
+                                ),
+                            })
+                    }
                     ProtocolTxType::EthereumEvents
                     | ProtocolTxType::BridgePool
                     | ProtocolTxType::ValidatorSetUpdate => TxResult {
@@ -524,6 +584,23 @@ where
                             .to_string(),
                     };
                 }
+                let max_tx_gas =
+                    namada::parameters::get_max_tx_gas(&*temp_wl_storage)
+                        .expect("Failed to get max tx gas param from storage");
+                if u64::from(wrapper.gas_limit) > max_tx_gas {
+                    // Account for the tx's resources even in case of an error.
+                    // Ignore any allocation error
+                    let _ = metadata
+                        .encrypted_txs_bins
+                        .try_dump(tx_bytes, u64::from(wrapper.gas_limit));
+
+                    return TxResult {
+                        code: ResultCode::TxGasLimit.into(),
+                        info: "Wrapper transaction exceeds the maximum gas of \
+                               a transaction"
+                            .to_string(),
+                    };
+                }
 
                 // try to allocate space and gas for this encrypted tx
                 if let Err(e) = metadata
@@ -682,6 +759,10 @@ mod test_process_proposal {
     use namada::vote_ext::{
         bridge_pool_roots, ethereum_events, EthereumTxData,
     };
+    use namada_sdk::testing::{
+        arb_invalid_signed_tx, arb_signed_tx, TxInvalidity,
+    };
+    use proptest::prelude::*;
 
     use super::*;
     use crate::node::ledger::shell::test_utils::{
@@ -2244,4 +2325,47 @@ mod test_process_proposal {
             assert!(rsp.is_ok());
         }
     }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        /// Test that the generated signed txs are correctly signed, and that
+        /// they are rejected once made invalid, for the expected reason when
+        /// it's checked before the contents of the tx
+        #[test]
+        fn test_reject_invalid_signed_txs(
+            (valid_tx, _) in arb_signed_tx(
+                ChainId::default(),
+                DateTimeUtc::now(),
+                GAS_LIMIT_MULTIPLIER.into(),
+            ),
+            (invalid_tx, _, invalidity) in arb_invalid_signed_tx(
+                ChainId::default(),
+                DateTimeUtc::now(),
+                GAS_LIMIT_MULTIPLIER.into(),
+            ),
+        ) {
+            assert!(valid_tx.validate_tx().is_ok());
+
+            let (shell, _recv, _, _) = test_utils::setup();
+            let request = ProcessProposal {
+                txs: vec![invalid_tx.to_bytes()],
+            };
+            let Err(TestError::RejectProposal(response)) =
+                shell.process_proposal(request)
+            else {
+                panic!("Test failed: an invalid tx was accepted");
+            };
+            assert_ne!(response[0].result.code, u32::from(ResultCode::Ok));
+            if matches!(
+                invalidity,
+                TxInvalidity::WrongWrapperSigner | TxInvalidity::TamperedHeader
+            ) {
+                assert_eq!(
+                    response[0].result.code,
+                    u32::from(ResultCode::InvalidSig)
+                );
+            }
+        }
+    }
 }
@@ -1,7 +1,15 @@
 //! Shell methods for querying state
 
-use namada::ledger::dry_run_tx;
-use namada::ledger::queries::{RequestCtx, ResponseQuery};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use namada::ledger::queries::{
+    require_latest_height, require_no_data, require_no_proof,
+    EncodedResponseQuery, RequestCtx, ResponseQuery,
+};
+use namada::ledger::{dry_run_tx, simulate_tx};
+use namada::state::ResultExt;
 use namada::token;
 use namada::types::address::Address;
 
@@ -25,26 +33,13 @@ where
             tx_wasm_cache: self.tx_wasm_cache.read_only(),
             storage_read_past_height_limit: self.storage_read_past_height_limit,
         };
-
-        // Invoke the root RPC handler - returns borsh-encoded data on success
-        let result = if query.path == "/shell/dry_run_tx" {
-            dry_run_tx(ctx, &query)
-        } else {
-            namada::ledger::queries::handle_path(ctx, &query)
-        };
-        match result {
-            Ok(ResponseQuery { data, info, proof }) => response::Query {
-                value: data.into(),
-                info,
-                proof: proof.map(Into::into),
-                ..Default::default()
-            },
-            Err(err) => response::Query {
-                code: 1.into(),
-                info: format!("RPC error: {}", err),
-                ..Default::default()
-            },
-        }
+        handle_query(
+            ctx,
+            &query,
+            &self.minimum_gas_prices,
+            &self.code_stats,
+            &self.mempool_txs,
+        )
     }
 
     /// Simple helper function for the ledger to get balances
@@ -61,6 +56,134 @@ where
     }
 }
 
+/// Forward the query to the right query method, given its `path`, and return
+/// the result (which may be the default if `path` is not a supported string).
+pub(super) fn handle_query<D, H, CA>(
+    ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+    query: &request::Query,
+    minimum_gas_prices: &BTreeMap<Address, token::Amount>,
+    code_stats: &RwLock<CodeStatsLog>,
+    mempool_txs: &RwLock<MempoolTxLog>,
+) -> response::Query
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+    CA: WasmCacheAccess + Sync + 'static,
+{
+    // Invoke the root RPC handler - returns borsh-encoded data on success
+    let result = match query.path.as_str() {
+        "/shell/dry_run_tx" => dry_run_tx(ctx, query),
+        "/shell/simulate_tx" => simulate_tx(ctx, query),
+        "/shell/gas_prices" => gas_prices(ctx, query, minimum_gas_prices),
+        "/shell/code_stats" => code_stats_query(ctx, query, code_stats),
+        "/shell/mempool_queue" => {
+            mempool_txs_query(ctx, query, None, mempool_txs)
+        }
+        path => match path.strip_prefix("/shell/mempool/") {
+            Some(fee_payer) => {
+                mempool_txs_query(ctx, query, Some(fee_payer), mempool_txs)
+            }
+            None => namada::ledger::queries::handle_path(ctx, query),
+        },
+    };
+    match result {
+        Ok(ResponseQuery { data, info, proof }) => response::Query {
+            value: data.into(),
+            info,
+            proof: proof.map(Into::into),
+            ..Default::default()
+        },
+        Err(err) => response::Query {
+            code: 1.into(),
+            info: format!("RPC error: {}", err),
+            ..Default::default()
+        },
+    }
+}
+
+/// Serve the minimum gas prices accepted by the node for all the tokens
+/// allowed for fee payment, with its local prices above the governance floor
+fn gas_prices<D, H, CA>(
+    ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+    query: &request::Query,
+    minimum_gas_prices: &BTreeMap<Address, token::Amount>,
+) -> namada::state::StorageResult<EncodedResponseQuery>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+    CA: WasmCacheAccess + Sync + 'static,
+{
+    require_latest_height(&ctx, query)?;
+    require_no_proof(query)?;
+    require_no_data(query)?;
+    let prices = parameters::read_minimum_gas_prices(
+        ctx.wl_storage,
+        minimum_gas_prices,
+    )?;
+    Ok(EncodedResponseQuery {
+        data: prices.serialize_to_vec(),
+        ..Default::default()
+    })
+}
+
+/// Serve the execution statistics of the tx and VP codes recorded by the node
+fn code_stats_query<D, H, CA>(
+    ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+    query: &request::Query,
+    code_stats: &RwLock<CodeStatsLog>,
+) -> namada::state::StorageResult<EncodedResponseQuery>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+    CA: WasmCacheAccess + Sync + 'static,
+{
+    require_latest_height(&ctx, query)?;
+    require_no_proof(query)?;
+    require_no_data(query)?;
+    let stats = code_stats
+        .read()
+        .expect("The code stats lock must not be poisoned")
+        .stats();
+    Ok(EncodedResponseQuery {
+        data: stats.serialize_to_vec(),
+        ..Default::default()
+    })
+}
+
+/// Serve the wrapper txs checked by the mempool of the node, of the given fee
+/// payer or all of them. The whole queue is meant for the operator of the
+/// node, its path is protected by the RPC gateway by default.
+fn mempool_txs_query<D, H, CA>(
+    ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+    query: &request::Query,
+    fee_payer: Option<&str>,
+    mempool_txs: &RwLock<MempoolTxLog>,
+) -> namada::state::StorageResult<EncodedResponseQuery>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+    CA: WasmCacheAccess + Sync + 'static,
+{
+    require_latest_height(&ctx, query)?;
+    require_no_proof(query)?;
+    require_no_data(query)?;
+    let fee_payer = fee_payer
+        .map(Address::from_str)
+        .transpose()
+        .into_storage_result()?;
+    let mempool_txs = mempool_txs
+        .read()
+        .expect("The mempool txs lock must not be poisoned");
+    let txs = match fee_payer {
+        Some(fee_payer) => mempool_txs.fee_payer_txs(&fee_payer),
+        None => mempool_txs.queue(),
+    };
+    Ok(EncodedResponseQuery {
+        data: txs.serialize_to_vec(),
+        ..Default::default()
+    })
+}
+
 // NOTE: we are testing `namada::ledger::queries_ext`,
 // which is not possible from `namada` since we do not have
 // access to the `Shell` there
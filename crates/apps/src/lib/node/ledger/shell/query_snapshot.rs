@@ -0,0 +1,232 @@
+//! Serving queries from a read-only snapshot of the storage.
+//!
+//! When the `query_snapshot_staleness` config is set, queries are not served
+//! by the shell, where they have to wait for the processing of blocks, but on
+//! a dedicated thread from a secondary instance of the DB of the shell. The
+//! snapshot only changes when it's refreshed to the last committed block, by
+//! catching up with the DB of the shell and loading its last state. This
+//! happens on the query thread once the snapshot lags more than the
+//! configured staleness behind the last committed block.
+
+use std::mem;
+use std::path::Path;
+use std::sync::{mpsc, Arc, RwLock};
+use std::time::Instant;
+
+use namada::ledger::queries::RequestCtx;
+use namada::vm::WasmCacheRoAccess;
+
+use super::queries::handle_query;
+use super::*;
+use crate::facade::tendermint::v0_37::abci::Response as Resp;
+use crate::facade::tower_abci::BoxError;
+use crate::node::ledger::storage::{
+    rocksdb, PersistentDB, PersistentStorageHasher,
+};
+
+/// A message sent to the query thread
+#[derive(Debug)]
+pub enum Message {
+    /// A query to serve from the snapshot
+    Query(
+        request::Query,
+        tokio::sync::oneshot::Sender<std::result::Result<Resp, BoxError>>,
+    ),
+    /// Refresh the snapshot to the last committed block and log the events
+    /// of the blocks committed since the last refresh
+    Refresh(Vec<Event>),
+}
+
+/// A read-only snapshot of the storage at a committed block
+#[derive(Debug)]
+pub struct QuerySnapshot {
+    wl_storage: WlStorage<PersistentDB, PersistentStorageHasher>,
+    event_log: EventLog,
+    vp_wasm_cache: VpCache<WasmCacheRoAccess>,
+    tx_wasm_cache: TxCache<WasmCacheRoAccess>,
+    storage_read_past_height_limit: Option<u64>,
+    minimum_gas_prices: BTreeMap<Address, token::Amount>,
+    code_stats: Arc<RwLock<CodeStatsLog>>,
+    mempool_txs: Arc<RwLock<MempoolTxLog>>,
+}
+
+impl QuerySnapshot {
+    /// Serve the messages received from the shell and the ABCI service,
+    /// until all their senders are dropped
+    pub fn run(mut self, receiver: mpsc::Receiver<Message>) {
+        while let Ok(msg) = receiver.recv() {
+            match msg {
+                Message::Query(query, resp_sender) => {
+                    let resp = self.query(query);
+                    if resp_sender.send(Ok(Resp::Query(resp))).is_err() {
+                        tracing::info!("ABCI response channel is closed")
+                    }
+                }
+                Message::Refresh(events) => self.refresh(events),
+            }
+        }
+    }
+
+    /// Serve a query from the snapshot
+    pub fn query(&self, query: request::Query) -> response::Query {
+        let ctx = RequestCtx {
+            wl_storage: &self.wl_storage,
+            event_log: &self.event_log,
+            vp_wasm_cache: self.vp_wasm_cache.clone(),
+            tx_wasm_cache: self.tx_wasm_cache.clone(),
+            storage_read_past_height_limit: self.storage_read_past_height_limit,
+        };
+        handle_query(
+            ctx,
+            &query,
+            &self.minimum_gas_prices,
+            &self.code_stats,
+            &self.mempool_txs,
+        )
+    }
+
+    /// Refresh the snapshot to the last committed block. If it fails, the
+    /// snapshot keeps serving the previous state.
+    fn refresh(&mut self, events: Vec<Event>) {
+        let start = Instant::now();
+        let storage = &mut self.wl_storage.storage;
+        let result = storage
+            .db
+            .catch_up_with_primary()
+            .map_err(|e| e.to_string())
+            .and_then(|()| {
+                storage.load_last_state().map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(()) => tracing::debug!(
+                "Refreshed the query snapshot to block height {} in {:?}",
+                storage.get_last_block_height(),
+                start.elapsed()
+            ),
+            Err(err) => {
+                tracing::error!("Failed to refresh the query snapshot: {err}")
+            }
+        }
+        self.event_log.log_events(events);
+    }
+}
+
+/// The shell's handle on the query snapshot, used to refresh it as blocks are
+/// committed
+#[derive(Debug)]
+pub struct QuerySnapshotHandle {
+    sender: mpsc::Sender<Message>,
+    /// The maximum number of blocks by which the snapshot may lag behind the
+    /// last committed block
+    staleness: u64,
+    /// The number of blocks committed since the last refresh
+    lag: u64,
+    /// The events of the blocks finalized since the last refresh
+    events: Vec<Event>,
+}
+
+impl QuerySnapshotHandle {
+    /// Create a handle sending refreshes with the given maximum staleness
+    pub fn new(sender: mpsc::Sender<Message>, staleness: u64) -> Self {
+        Self {
+            sender,
+            staleness,
+            lag: 0,
+            events: vec![],
+        }
+    }
+
+    /// Record the events of a finalized block, to be logged in the snapshot
+    /// once it's refreshed to include the block
+    pub fn finalized_block(&mut self, events: Vec<Event>) {
+        self.events.extend(events);
+    }
+
+    /// Refresh the snapshot after a block was committed, if it now lags more
+    /// than the configured staleness
+    pub fn committed_block(&mut self) {
+        self.lag += 1;
+        if self.lag > self.staleness {
+            let events = mem::take(&mut self.events);
+            if self.sender.send(Message::Refresh(events)).is_err() {
+                tracing::info!("The query snapshot thread has shut down");
+            }
+            self.lag = 0;
+        }
+    }
+}
+
+impl Shell {
+    /// Open a snapshot of the last committed state of the shell, from a
+    /// secondary instance of the DB at `db_path`
+    pub fn open_query_snapshot(
+        &self,
+        db_path: impl AsRef<Path>,
+        secondary_path: impl AsRef<Path>,
+        db_cache: Option<&::rocksdb::Cache>,
+    ) -> Result<QuerySnapshot> {
+        let mut db = rocksdb::open_secondary(db_path, secondary_path, db_cache)
+            .map_err(|e| Error::QuerySnapshot(e.to_string()))?;
+        if let Some(archive) = self.wl_storage.storage.db.archive() {
+            db.set_archive(archive.clone());
+        }
+        let mut storage = State::with_db(
+            db,
+            self.chain_id.clone(),
+            self.wl_storage.storage.native_token.clone(),
+            self.storage_read_past_height_limit,
+            is_merklized_storage_key,
+        );
+        storage
+            .load_last_state()
+            .map_err(|e| Error::QuerySnapshot(e.to_string()))?;
+        Ok(QuerySnapshot {
+            wl_storage: WlStorage {
+                storage,
+                write_log: WriteLog::default(),
+            },
+            event_log: EventLog::default(),
+            vp_wasm_cache: self.vp_wasm_cache.read_only(),
+            tx_wasm_cache: self.tx_wasm_cache.read_only(),
+            storage_read_past_height_limit: self.storage_read_past_height_limit,
+            minimum_gas_prices: self.minimum_gas_prices.clone(),
+            code_stats: self.code_stats.clone(),
+            mempool_txs: self.mempool_txs.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada::ledger::events::{EventLevel, EventType};
+
+    use super::*;
+
+    /// Test that the snapshot is refreshed once it lags more than the
+    /// configured staleness, with the events of the blocks since the last
+    /// refresh.
+    #[test]
+    fn test_query_snapshot_refresh() {
+        let (sender, receiver) = mpsc::channel();
+        let mut handle = QuerySnapshotHandle::new(sender, 1);
+        let event = || Event {
+            event_type: EventType::Accepted,
+            level: EventLevel::Tx,
+            attributes: Default::default(),
+        };
+
+        handle.finalized_block(vec![event()]);
+        handle.committed_block();
+        assert!(receiver.try_recv().is_err());
+
+        handle.finalized_block(vec![event()]);
+        handle.committed_block();
+        match receiver.try_recv() {
+            Ok(Message::Refresh(events)) => assert_eq!(events.len(), 2),
+            msg => panic!("Expected a refresh, got {msg:?}"),
+        }
+
+        handle.committed_block();
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! An in-memory bloom filter of the tx hashes in the replay protection
+//! storage, for the replay checks of the mempool.
+//!
+//! A hash that is absent from the filter is absent from storage, so that its
+//! storage read is skipped. A hash that may be in the filter is read from
+//! storage, which is a false positive if it's absent. Since the hashes can't
+//! be removed from the filter, the hashes pruned or deleted from storage stay
+//! in it until it's rebuilt from storage once it's full.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use namada::types::hash::Hash;
+
+/// The number of bits of the filter per hash, for a false positive rate of
+/// about 1% at full capacity
+const BITS_PER_HASH: usize = 10;
+
+/// The number of bits set by each hash
+const BITS_SET_PER_HASH: u64 = 7;
+
+/// The minimum number of hashes held by the filter before it's full
+pub const MIN_CAPACITY: usize = 100_000;
+
+/// The counters of the checks done with the filter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayFilterStats {
+    /// The number of checked hashes
+    pub checks: u64,
+    /// The number of checked hashes that were read from storage
+    pub fallback_reads: u64,
+    /// The number of hashes read from storage that were absent
+    pub false_positives: u64,
+}
+
+/// A bloom filter of the tx hashes in the replay protection storage
+#[derive(Debug)]
+pub struct ReplayFilter {
+    bits: Vec<u64>,
+    /// The number of hashes held by the filter
+    len: usize,
+    /// The number of hashes after which the filter is full
+    capacity: usize,
+    checks: AtomicU64,
+    fallback_reads: AtomicU64,
+    false_positives: AtomicU64,
+}
+
+impl ReplayFilter {
+    /// Create an empty filter that can hold the given number of hashes before
+    /// it's full
+    pub fn new(capacity: usize) -> Self {
+        let words = (capacity.max(1) * BITS_PER_HASH + 63) / 64;
+        Self {
+            bits: vec![0; words],
+            len: 0,
+            capacity,
+            checks: AtomicU64::default(),
+            fallback_reads: AtomicU64::default(),
+            false_positives: AtomicU64::default(),
+        }
+    }
+
+    /// Rebuild the filter from the given hashes, with room for as many more
+    /// hashes. The counters of the checks are kept.
+    pub fn rebuild(&mut self, hashes: impl IntoIterator<Item = Hash>) {
+        let hashes: Vec<Hash> = hashes.into_iter().collect();
+        let capacity = (2 * hashes.len()).max(MIN_CAPACITY);
+        self.bits = vec![0; (capacity * BITS_PER_HASH + 63) / 64];
+        self.len = 0;
+        self.capacity = capacity;
+        for hash in &hashes {
+            self.insert(hash);
+        }
+    }
+
+    /// Add a hash to the filter
+    pub fn insert(&mut self, hash: &Hash) {
+        for index in self.bit_indices(hash) {
+            self.bits[index / 64] |= 1 << (index % 64);
+        }
+        self.len += 1;
+    }
+
+    /// Check if a hash may be in the filter. If `false`, it's definitely not
+    /// in it.
+    pub fn may_contain(&self, hash: &Hash) -> bool {
+        self.bit_indices(hash)
+            .all(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    /// Check if the filter holds as many hashes as its capacity, such that it
+    /// should be rebuilt
+    pub fn is_full(&self) -> bool {
+        self.len >= self.capacity
+    }
+
+    /// Check if a hash is in the replay protection storage, with the given
+    /// read of the storage done only if it may be in the filter
+    pub fn contains<E>(
+        &self,
+        hash: &Hash,
+        read_storage: impl FnOnce() -> Result<bool, E>,
+    ) -> Result<bool, E> {
+        self.checks.fetch_add(1, Ordering::Relaxed);
+        if !self.may_contain(hash) {
+            return Ok(false);
+        }
+        self.fallback_reads.fetch_add(1, Ordering::Relaxed);
+        let present = read_storage()?;
+        if !present {
+            self.false_positives.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(present)
+    }
+
+    /// Get the counters of the checks done with the filter
+    pub fn stats(&self) -> ReplayFilterStats {
+        ReplayFilterStats {
+            checks: self.checks.load(Ordering::Relaxed),
+            fallback_reads: self.fallback_reads.load(Ordering::Relaxed),
+            false_positives: self.false_positives.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The indices of the bits of a hash, by double hashing. The tx hashes
+    /// are already uniformly distributed, so their bytes are used directly.
+    fn bit_indices(&self, hash: &Hash) -> impl Iterator<Item = usize> {
+        let num_bits = self.bits.len() as u64 * 64;
+        let word = |offset: usize| {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(&hash.0[offset..offset + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        let first = word(0);
+        // An odd step never cycles back to the first bit early
+        let step = word(8) | 1;
+        (0..BITS_SET_PER_HASH).map(move |i| {
+            (first.wrapping_add(i.wrapping_mul(step)) % num_bits) as usize
+        })
+    }
+}
+
+impl Default for ReplayFilter {
+    fn default() -> Self {
+        Self::new(MIN_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the filter has no false negatives, that its false positive
+    /// rate is low at full capacity and that the storage reads are counted.
+    #[test]
+    fn test_replay_filter() {
+        let hash = |i: usize| Hash::sha256(i.to_le_bytes());
+        let capacity = 10_000;
+        let mut filter = ReplayFilter::new(capacity);
+        for i in 0..capacity {
+            assert!(!filter.is_full());
+            filter.insert(&hash(i));
+        }
+        assert!(filter.is_full());
+        assert!((0..capacity).all(|i| filter.may_contain(&hash(i))));
+        filter.rebuild((0..capacity).map(hash));
+        assert!(!filter.is_full());
+        assert!((0..capacity).all(|i| filter.may_contain(&hash(i))));
+        let mut filter = ReplayFilter::new(capacity);
+        for i in 0..capacity {
+            filter.insert(&hash(i));
+        }
+        let false_positives = (capacity..2 * capacity)
+            .filter(|i| filter.may_contain(&hash(*i)))
+            .count();
+        assert!(false_positives < capacity / 50, "{false_positives}");
+
+        let read_storage = |present| move || Ok::<_, ()>(present);
+        assert!(filter.contains(&hash(0), read_storage(true)).unwrap());
+        let absent = (capacity..).map(hash);
+        let filtered = absent
+            .clone()
+            .find(|hash| !filter.may_contain(hash))
+            .unwrap();
+        assert!(!filter.contains(&filtered, read_storage(true)).unwrap());
+        let false_positive = absent
+            .clone()
+            .find(|hash| filter.may_contain(hash))
+            .unwrap();
+        assert!(!filter
+            .contains(&false_positive, read_storage(false))
+            .unwrap());
+        assert_eq!(
+            filter.stats(),
+            ReplayFilterStats {
+                checks: 3,
+                fallback_reads: 2,
+                false_positives: 1,
+            }
+        );
+    }
+}
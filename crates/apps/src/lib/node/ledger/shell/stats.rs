@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::time::Duration;
 
 #[derive(Debug, Default)]
 pub struct InternalStats {
@@ -10,6 +11,8 @@ pub struct InternalStats {
     tx_cache_size: (usize, usize),
     tx_executed: HashMap<String, u64>,
     wrapper_txs: u64,
+    tx_exec_time: Duration,
+    timed_txs: u32,
 }
 
 impl InternalStats {
@@ -55,6 +58,22 @@ impl InternalStats {
     pub fn increment_wrapper_txs(&mut self) {
         self.wrapper_txs += 1;
     }
+
+    pub fn add_tx_exec_time(&mut self, elapsed: Duration) {
+        self.tx_exec_time += elapsed;
+        self.timed_txs += 1;
+    }
+
+    pub fn format_tx_latency(&self) -> String {
+        let mean = self
+            .tx_exec_time
+            .checked_div(self.timed_txs)
+            .unwrap_or_default();
+        format!(
+            "tx execution time: {:?}, mean tx latency: {:?}",
+            self.tx_exec_time, mean
+        )
+    }
 }
 
 impl Display for InternalStats {
@@ -11,11 +11,11 @@ use data_encoding::HEXUPPER;
 use itertools::Either;
 use lazy_static::lazy_static;
 use namada::eth_bridge::oracle::config::Config as OracleConfig;
-use namada::ledger::dry_run_tx;
 use namada::ledger::events::log::dumb_queries;
 use namada::ledger::queries::{
     EncodedResponseQuery, RequestCtx, RequestQuery, Router, RPC,
 };
+use namada::ledger::{dry_run_tx, simulate_tx};
 use namada::proof_of_stake::pos_queries::PosQueries;
 use namada::proof_of_stake::storage::{
     read_consensus_validator_set_addresses_with_stake,
@@ -696,10 +696,10 @@ impl<'a> Client for &'a MockNode {
             tx_wasm_cache: borrowed.tx_wasm_cache.read_only(),
             storage_read_past_height_limit: None,
         };
-        if request.path == "/shell/dry_run_tx" {
-            dry_run_tx(ctx, &request)
-        } else {
-            rpc.handle(ctx, &request)
+        match request.path.as_str() {
+            "/shell/dry_run_tx" => dry_run_tx(ctx, &request),
+            "/shell/simulate_tx" => simulate_tx(ctx, &request),
+            _ => rpc.handle(ctx, &request),
         }
         .map_err(Report::new)
     }
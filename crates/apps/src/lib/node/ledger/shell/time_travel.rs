@@ -0,0 +1,255 @@
+//! Test-only controls to fast-forward the epochs and the block time of a
+//! chain, for the integration tests of the unbonding and governance flows
+//! not to wait for the epochs to pass.
+//!
+//! The controls are only built with the `time-travel` feature and only
+//! enabled on the chains whose ID has one of the
+//! [`TIME_TRAVEL_CHAIN_ID_PREFIXES`]. The commands are posted as JSON to the
+//! `/time_travel` endpoint served on the address of the
+//! `NAMADA_TIME_TRAVEL_ADDR` env var, and applied from the next finalized
+//! block. They alter the state outside of consensus, so on a chain with more
+//! than one validator, all of them must be sent the same commands.
+
+use std::sync::mpsc;
+
+use namada::types::time::DurationSecs;
+use serde::{Deserialize, Serialize};
+use warp::Filter;
+
+use super::*;
+
+/// Env. var to set the address of the time travel endpoint
+pub const ENV_VAR_TIME_TRAVEL_ADDR: &str = "NAMADA_TIME_TRAVEL_ADDR";
+
+/// The prefixes of the IDs of the chains on which time travel can be
+/// enabled
+pub const TIME_TRAVEL_CHAIN_ID_PREFIXES: &[&str] = &[
+    "e2e-test",
+    "integration-test",
+    "local",
+    "devnet",
+    "namada-internal",
+];
+
+/// The endpoint to which the commands are posted
+const TIME_TRAVEL_ENDPOINT: &str = "time_travel";
+
+/// A time travel command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Command {
+    /// Begin the given number of epochs one after the other, regardless of
+    /// the minimum duration of the epochs. Each epoch still lasts for
+    /// [`EPOCH_SWITCH_BLOCKS_DELAY`] + 1 blocks, for the validator set
+    /// updates to stay aligned with CometBFT.
+    AdvanceEpochs {
+        /// The number of epochs
+        epochs: u64,
+    },
+    /// Shift the time of all the next blocks forward by the given number of
+    /// seconds
+    ShiftBlockTime {
+        /// The number of seconds
+        seconds: u64,
+    },
+    /// Run the epoch transition hooks in the next block, without beginning
+    /// a new epoch
+    RunEpochHooks,
+}
+
+/// The state of the time travel controls
+#[derive(Debug, Default)]
+pub struct TimeTravel {
+    /// The commands received from the endpoint
+    commands: Option<mpsc::Receiver<Command>>,
+    /// The number of epochs still to be begun
+    pending_epochs: u64,
+    /// The shift of the time of the blocks
+    time_offset: u64,
+    /// Whether to run the epoch transition hooks in the next block
+    run_epoch_hooks: bool,
+}
+
+impl TimeTravel {
+    /// Apply a command.
+    fn apply(&mut self, command: Command) {
+        tracing::info!(?command, "Applying a time travel command");
+        match command {
+            Command::AdvanceEpochs { epochs } => {
+                self.pending_epochs = self.pending_epochs.saturating_add(epochs)
+            }
+            Command::ShiftBlockTime { seconds } => {
+                self.time_offset = self.time_offset.saturating_add(seconds)
+            }
+            Command::RunEpochHooks => self.run_epoch_hooks = true,
+        }
+    }
+}
+
+/// Check if time travel can be enabled on the given chain.
+pub fn is_allowed_chain(chain_id: &ChainId) -> bool {
+    let chain_id = chain_id.as_str();
+    TIME_TRAVEL_CHAIN_ID_PREFIXES.iter().any(|prefix| {
+        chain_id
+            .strip_prefix(prefix)
+            .map_or(false, |rest| rest.starts_with('.'))
+    })
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Start serving the time travel endpoint, if its address is set and
+    /// the chain allows it. Must be called from a Tokio runtime.
+    pub fn open_time_travel(&mut self) {
+        let Ok(listen_addr) = std::env::var(ENV_VAR_TIME_TRAVEL_ADDR) else {
+            return;
+        };
+        if !is_allowed_chain(&self.chain_id) {
+            tracing::warn!(
+                chain_id = %self.chain_id,
+                "Time travel is not allowed on this chain, the endpoint will \
+                 not be served"
+            );
+            return;
+        }
+        let listen_addr: std::net::SocketAddr = listen_addr
+            .parse()
+            .expect("Failed to parse the time travel endpoint address");
+        let (command_send, command_recv) = mpsc::channel();
+        self.time_travel.commands = Some(command_recv);
+
+        let endpoint = warp::post()
+            .and(warp::path(TIME_TRAVEL_ENDPOINT))
+            .and(warp::body::json())
+            .map(move |command: Command| {
+                let status = match command_send.send(command) {
+                    Ok(()) => warp::http::StatusCode::OK,
+                    Err(_) => warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                };
+                warp::reply::with_status(warp::reply(), status)
+            });
+        tracing::warn!(
+            ?listen_addr,
+            "Serving the time travel endpoint, this node must only be used \
+             for testing"
+        );
+        tokio::spawn(warp::serve(endpoint).bind(listen_addr));
+    }
+
+    /// Apply the received time travel commands to the block being
+    /// finalized.
+    pub(super) fn time_travel_block(
+        &mut self,
+        mut req: shim::request::FinalizeBlock,
+    ) -> shim::request::FinalizeBlock {
+        if let Some(commands) = self.time_travel.commands.as_ref() {
+            let commands: Vec<_> = commands.try_iter().collect();
+            for command in commands {
+                self.time_travel.apply(command);
+            }
+        }
+
+        if self.time_travel.time_offset > 0 {
+            req.header.time =
+                req.header.time + DurationSecs(self.time_travel.time_offset);
+        }
+        // Queue the next epoch, unless one is already queued
+        let storage = &mut self.wl_storage.storage;
+        if self.time_travel.pending_epochs > 0
+            && storage.update_epoch_blocks_delay.is_none()
+        {
+            storage.next_epoch_min_start_height =
+                storage.get_last_block_height() + 1;
+            storage.next_epoch_min_start_time = req.header.time;
+            self.time_travel.pending_epochs -= 1;
+        }
+        req
+    }
+
+    /// Check if the epoch transition hooks have to be run in the current
+    /// block, even though no new epoch has begun.
+    pub(super) fn take_forced_epoch_hooks(&mut self) -> bool {
+        std::mem::take(&mut self.time_travel.run_epoch_hooks)
+    }
+
+    /// Apply a time travel command, as if received from the endpoint.
+    #[cfg(test)]
+    pub(super) fn apply_time_travel(&mut self, command: Command) {
+        self.time_travel.apply(command)
+    }
+}
+
+#[cfg(test)]
+mod test_time_travel {
+    use namada::types::time::DateTimeUtc;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils::setup;
+
+    /// Test that time travel is only allowed on test chains.
+    #[test]
+    fn test_is_allowed_chain() {
+        assert!(is_allowed_chain(&ChainId::default()));
+        assert!(is_allowed_chain(&ChainId("e2e-test.abcdef".to_string())));
+        assert!(!is_allowed_chain(&ChainId("localnet.abcdef".to_string())));
+        assert!(!is_allowed_chain(&ChainId("namada.abcdef".to_string())));
+    }
+
+    /// Test that the epochs are advanced regardless of their minimum
+    /// duration.
+    #[test]
+    fn test_advance_epochs() {
+        let (mut shell, _, _, _) = setup();
+        let start_epoch = shell.wl_storage.storage.get_current_epoch().0;
+        shell.wl_storage.storage.next_epoch_min_start_height =
+            BlockHeight(u64::MAX);
+
+        shell.apply_time_travel(Command::AdvanceEpochs { epochs: 2 });
+        let blocks_per_epoch = u64::from(EPOCH_SWITCH_BLOCKS_DELAY) + 1;
+        for _ in 0..2 * blocks_per_epoch {
+            shell.finalize_and_commit(None);
+        }
+        assert_eq!(
+            shell.wl_storage.storage.get_current_epoch().0,
+            start_epoch + 2
+        );
+
+        // No more epochs are begun early
+        shell.wl_storage.storage.next_epoch_min_start_height =
+            BlockHeight(u64::MAX);
+        for _ in 0..blocks_per_epoch {
+            shell.finalize_and_commit(None);
+        }
+        assert_eq!(
+            shell.wl_storage.storage.get_current_epoch().0,
+            start_epoch + 2
+        );
+    }
+
+    /// Test that the block time is shifted forward.
+    #[test]
+    fn test_shift_block_time() {
+        let (mut shell, _, _, _) = setup();
+        shell.apply_time_travel(Command::ShiftBlockTime { seconds: 3600 });
+        let before = DateTimeUtc::now();
+        shell.finalize_and_commit(None);
+        let block_time =
+            shell.wl_storage.storage.get_last_block_timestamp().unwrap();
+        assert!(block_time >= before + DurationSecs(3600));
+    }
+
+    /// Test that the commands are deserialized from their JSON.
+    #[test]
+    fn test_command_json() {
+        let command: Command =
+            serde_json::from_str(r#"{"command":"advance_epochs","epochs":3}"#)
+                .unwrap();
+        assert_eq!(command, Command::AdvanceEpochs { epochs: 3 });
+        let command: Command =
+            serde_json::from_str(r#"{"command":"run_epoch_hooks"}"#).unwrap();
+        assert_eq!(command, Command::RunEpochHooks);
+    }
+}
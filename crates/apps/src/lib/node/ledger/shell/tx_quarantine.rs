@@ -0,0 +1,225 @@
+//! Quarantine of the malformed txs received by the mempool.
+//!
+//! The bytes of the txs that fail to be decoded or to pass the signature
+//! checks are quarantined in a bounded on-disk buffer once they failed
+//! [`QUARANTINE_AFTER_FAILURES`] times, so that encoding bugs of the wallets
+//! can be debugged from the actual bytes. Each quarantined tx is stored in its
+//! own file, named after the hash of its bytes. Once the buffer is full, the
+//! least recently failed txs are evicted first.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::{fs, io};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use borsh_ext::BorshSerializeExt;
+use namada::tx::data::hash_tx;
+use namada::types::hash::Hash;
+use namada::types::time::DateTimeUtc;
+
+/// The number of failures of a tx after which its bytes are quarantined
+pub const QUARANTINE_AFTER_FAILURES: u64 = 2;
+
+/// The maximum number of txs whose failures are counted in memory before
+/// they get quarantined. The counts are reset once it's reached.
+const MAX_COUNTED_TXS: usize = 10_000;
+
+/// The file extension of the quarantined txs
+const FILE_EXTENSION: &str = "tx";
+
+/// A quarantined malformed tx
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct QuarantinedTx {
+    /// The bytes of the tx, as received by the mempool
+    pub bytes: Vec<u8>,
+    /// The reason of the last failure
+    pub reason: String,
+    /// The number of failures
+    pub failures: u64,
+    /// The time of the first failure since the tx was quarantined
+    pub first_seen: DateTimeUtc,
+    /// The time of the last failure
+    pub last_seen: DateTimeUtc,
+}
+
+/// The on-disk buffer of the quarantined malformed txs
+#[derive(Debug)]
+pub struct TxQuarantine {
+    dir: PathBuf,
+    capacity: usize,
+    /// The number of failures of the txs that are not quarantined yet
+    failures: Mutex<HashMap<Hash, u64>>,
+}
+
+impl TxQuarantine {
+    /// Open the quarantine in the given directory, holding at most
+    /// `capacity` txs
+    pub fn new(dir: impl AsRef<Path>, capacity: usize) -> Self {
+        Self {
+            dir: dir.as_ref().to_owned(),
+            capacity,
+            failures: Mutex::default(),
+        }
+    }
+
+    /// Record a failure of the tx with the given bytes, quarantining it if it
+    /// failed repeatedly
+    pub fn record_failure(&self, tx_bytes: &[u8], reason: impl ToString) {
+        if self.capacity == 0 {
+            return;
+        }
+        let hash = hash_tx(tx_bytes);
+        let path = self.tx_path(&hash);
+        let result = match read_entry(&path) {
+            Ok(mut entry) => {
+                entry.failures += 1;
+                entry.reason = reason.to_string();
+                entry.last_seen = DateTimeUtc::now();
+                fs::write(&path, entry.serialize_to_vec())
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let failures = {
+                    let mut counts = self
+                        .failures
+                        .lock()
+                        .expect("Lock must not be poisoned");
+                    if counts.len() >= MAX_COUNTED_TXS
+                        && !counts.contains_key(&hash)
+                    {
+                        counts.clear();
+                    }
+                    let failures = counts.entry(hash).or_default();
+                    *failures += 1;
+                    let count = *failures;
+                    if count >= QUARANTINE_AFTER_FAILURES {
+                        counts.remove(&hash);
+                    }
+                    count
+                };
+                if failures < QUARANTINE_AFTER_FAILURES {
+                    return;
+                }
+                let now = DateTimeUtc::now();
+                let entry = QuarantinedTx {
+                    bytes: tx_bytes.to_vec(),
+                    reason: reason.to_string(),
+                    failures,
+                    first_seen: now,
+                    last_seen: now,
+                };
+                fs::create_dir_all(&self.dir)
+                    .and_then(|()| fs::write(&path, entry.serialize_to_vec()))
+                    .and_then(|()| self.evict())
+            }
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(()) => tracing::debug!("Quarantined malformed tx {hash}"),
+            Err(err) => {
+                tracing::warn!(
+                    "Failed to quarantine malformed tx {hash}: {err}"
+                )
+            }
+        }
+    }
+
+    /// Read all the quarantined txs, from the least to the most recently
+    /// failed. Entries that cannot be read are skipped.
+    pub fn entries(&self) -> io::Result<Vec<(Hash, QuarantinedTx)>> {
+        let mut entries: Vec<_> = self
+            .paths()?
+            .into_iter()
+            .filter_map(|path| {
+                let hash = path.file_stem()?.to_str()?.parse().ok()?;
+                let entry = read_entry(&path).ok()?;
+                Some((hash, entry))
+            })
+            .collect();
+        entries.sort_by(|(_, a), (_, b)| a.last_seen.cmp(&b.last_seen));
+        Ok(entries)
+    }
+
+    /// Evict the least recently failed txs above the capacity
+    fn evict(&self) -> io::Result<()> {
+        let entries = self.entries()?;
+        let excess = entries.len().saturating_sub(self.capacity);
+        for (hash, _) in entries.into_iter().take(excess) {
+            fs::remove_file(self.tx_path(&hash))?;
+        }
+        Ok(())
+    }
+
+    /// The paths of the files of the quarantined txs
+    fn paths(&self) -> io::Result<Vec<PathBuf>> {
+        match fs::read_dir(&self.dir) {
+            Ok(dir) => Ok(dir
+                .filter_map(|entry| Some(entry.ok()?.path()))
+                .filter(|path| {
+                    path.extension().and_then(|ext| ext.to_str())
+                        == Some(FILE_EXTENSION)
+                })
+                .collect()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn tx_path(&self, hash: &Hash) -> PathBuf {
+        self.dir.join(format!("{hash}.{FILE_EXTENSION}"))
+    }
+}
+
+fn read_entry(path: &Path) -> io::Result<QuarantinedTx> {
+    let bytes = fs::read(path)?;
+    QuarantinedTx::try_from_slice(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tx_quarantine() {
+        let dir = tempfile::tempdir().unwrap();
+        let quarantine = TxQuarantine::new(dir.path(), 2);
+
+        // A single failure doesn't quarantine the tx
+        quarantine.record_failure(b"tx1", "invalid encoding");
+        assert!(quarantine.entries().unwrap().is_empty());
+
+        // Repeated failures do
+        quarantine.record_failure(b"tx1", "invalid encoding");
+        quarantine.record_failure(b"tx1", "invalid signature");
+        let entries = quarantine.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        let (hash, entry) = &entries[0];
+        assert_eq!(*hash, hash_tx(b"tx1"));
+        assert_eq!(entry.bytes, b"tx1".to_vec());
+        assert_eq!(entry.failures, 3);
+        assert_eq!(entry.reason, "invalid signature");
+
+        // The least recently failed txs are evicted above the capacity
+        for tx in [b"tx2", b"tx3"] {
+            quarantine.record_failure(tx, "invalid encoding");
+            quarantine.record_failure(tx, "invalid encoding");
+        }
+        let hashes: Vec<_> = quarantine
+            .entries()
+            .unwrap()
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .collect();
+        assert_eq!(hashes, vec![hash_tx(b"tx2"), hash_tx(b"tx3")]);
+    }
+
+    #[test]
+    fn test_tx_quarantine_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let quarantine = TxQuarantine::new(dir.path(), 0);
+        for _ in 0..QUARANTINE_AFTER_FAILURES {
+            quarantine.record_failure(b"tx", "invalid encoding");
+        }
+        assert!(quarantine.entries().unwrap().is_empty());
+    }
+}
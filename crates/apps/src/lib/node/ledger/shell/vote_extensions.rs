@@ -162,6 +162,23 @@ where
                         .valset_upd_seen(ext.data.signing_epoch.next()))
                     .then(|| tx_bytes.clone())
                 }
+                EthereumTxData::WithdrawalVetoVext(ext) => {
+                    // only propose the vetoes of the transfers
+                    // which are still delayed
+                    self.wl_storage
+                        .ethbridge_queries()
+                        .get_delayed_transfer(&ext.data.transfer_hash)
+                        .map(|_| tx_bytes.clone())
+                }
+                EthereumTxData::CheckpointVext(ext) => {
+                    // only propose signatures over checkpoints
+                    // whose certificate is not yet complete
+                    let queries = self.wl_storage.ethbridge_queries();
+                    let height = ext.data.block_height;
+                    (queries.get_checkpoint(height).is_some()
+                        && !queries.checkpoint_certified(height))
+                    .then(|| tx_bytes.clone())
+                }
                 _ => None,
             }
         })
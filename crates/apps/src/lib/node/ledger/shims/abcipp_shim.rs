@@ -25,6 +25,7 @@ use crate::facade::tendermint::v0_37::abci::{
 };
 use crate::facade::tendermint_proto::v0_37::abci::ResponseDeliverTx;
 use crate::facade::tower_abci::BoxError;
+use crate::node::ledger::shell::query_snapshot::{self, QuerySnapshotHandle};
 use crate::node::ledger::shell::{EthereumOracleChannels, Shell};
 
 /// The shim wraps the shell, which implements ABCI++.
@@ -39,6 +40,8 @@ pub struct AbcippShim {
         Req,
         tokio::sync::oneshot::Sender<Result<Resp, BoxError>>,
     )>,
+    /// The handle on the snapshot serving queries, if enabled
+    query_snapshot: Option<QuerySnapshotHandle>,
 }
 
 impl AbcippShim {
@@ -60,23 +63,65 @@ impl AbcippShim {
         let (shell_send, shell_recv) = std::sync::mpsc::channel();
         let (server_shutdown, _) = broadcast::channel::<()>(1);
         let action_at_height = config.shell.action_at_height.clone();
+        let query_snapshot_staleness = config.shell.query_snapshot_staleness;
+        let archive = config.shell.archive.clone();
+        let db_path = config.db_dir();
+        let query_snapshot_path =
+            config.shell.query_snapshot_dir(&config.chain_id);
+        let mut service = Shell::new(
+            config,
+            wasm_dir,
+            broadcast_sender,
+            eth_oracle,
+            Some(db_cache),
+            vp_wasm_compilation_cache,
+            tx_wasm_compilation_cache,
+        );
+        // Open the archive before the query snapshot, for the snapshot to
+        // share it
+        if let Some(archive) = archive {
+            service
+                .open_archive(&archive)
+                .expect("Failed to open the archive of the old blocks");
+        }
+        #[cfg(feature = "time-travel")]
+        service.open_time_travel();
+        let (query_snapshot, query_send) = query_snapshot_staleness
+            .map(|staleness| {
+                let snapshot = service
+                    .open_query_snapshot(
+                        db_path,
+                        query_snapshot_path,
+                        Some(db_cache),
+                    )
+                    .expect("Failed to open the query snapshot");
+                let (query_send, query_recv) = std::sync::mpsc::channel();
+                std::thread::Builder::new()
+                    .name("ledger-query".into())
+                    .spawn(move || snapshot.run(query_recv))
+                    .expect("Must be able to start a thread for queries");
+                tracing::info!(
+                    "Serving queries from a snapshot with a staleness of up \
+                     to {} blocks",
+                    staleness
+                );
+                (
+                    QuerySnapshotHandle::new(query_send.clone(), staleness),
+                    query_send,
+                )
+            })
+            .unzip();
         (
             Self {
-                service: Shell::new(
-                    config,
-                    wasm_dir,
-                    broadcast_sender,
-                    eth_oracle,
-                    Some(db_cache),
-                    vp_wasm_compilation_cache,
-                    tx_wasm_compilation_cache,
-                ),
+                service,
                 begin_block_request: None,
                 delivered_txs: vec![],
                 shell_recv,
+                query_snapshot,
             },
             AbciService {
                 shell_send,
+                query_send,
                 shutdown: server_shutdown.clone(),
                 action_at_height,
                 suspended: false,
@@ -96,6 +141,7 @@ impl AbcippShim {
     /// [`AbciService`].
     pub fn run(mut self) {
         while let Ok((req, resp_sender)) = self.shell_recv.recv() {
+            let is_commit = matches!(req, Req::Commit);
             let resp = match req {
                 Req::ProcessProposal(proposal) => self
                     .service
@@ -167,6 +213,12 @@ impl AbcippShim {
                         .map_err(Error::from)
                         .and_then(|res| match res {
                             Response::FinalizeBlock(resp) => {
+                                if let Some(query_snapshot) =
+                                    self.query_snapshot.as_mut()
+                                {
+                                    query_snapshot
+                                        .finalized_block(resp.events.clone());
+                                }
                                 Ok(Resp::EndBlock(crate::facade::tendermint_proto::v0_37::abci::ResponseEndBlock::from(resp).try_into().unwrap()))
                             }
                             _ => Err(Error::ConvertResp(res)),
@@ -182,6 +234,12 @@ impl AbcippShim {
                     Err(err) => Err(err),
                 },
             };
+            if is_commit && resp.is_ok() {
+                self.service.archive_old_blocks();
+                if let Some(query_snapshot) = self.query_snapshot.as_mut() {
+                    query_snapshot.committed_block();
+                }
+            }
             let resp = resp.map_err(|e| e.into());
             if resp_sender.send(resp).is_err() {
                 tracing::info!("ABCI response channel is closed")
@@ -210,6 +268,8 @@ pub struct AbciService {
         Req,
         tokio::sync::oneshot::Sender<Result<Resp, BoxError>>,
     )>,
+    /// A channel for forwarding queries to the query snapshot, if enabled
+    query_send: Option<std::sync::mpsc::Sender<query_snapshot::Message>>,
     /// Indicates if the consensus connection is suspended.
     suspended: bool,
     /// This resolves the non-completing futures returned to tower-abci
@@ -292,12 +352,20 @@ impl AbciService {
     /// forward it normally.
     fn forward_request(&mut self, req: Req) -> <Self as Service<Req>>::Future {
         let (resp_send, recv) = tokio::sync::oneshot::channel();
-        let result = self.shell_send.send((req, resp_send));
+        let result = match (req, &self.query_send) {
+            (Req::Query(query), Some(query_send)) => query_send
+                .send(query_snapshot::Message::Query(query, resp_send))
+                .map_err(BoxError::from),
+            (req, _) => self
+                .shell_send
+                .send((req, resp_send))
+                .map_err(BoxError::from),
+        };
 
         async move {
             if let Err(err) = result {
                 // The shell has shut-down
-                return Err(err.into());
+                return Err(err);
             }
             match recv.await {
                 Ok(resp) => resp,
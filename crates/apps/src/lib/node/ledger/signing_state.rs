@@ -0,0 +1,470 @@
+//! Double-sign protection of the validator nodes of an active-passive
+//! failover setup.
+//!
+//! The last height, round and step signed by a validator, i.e. its signing
+//! state, is kept in the format of CometBFT's `priv_validator_state.json`, the
+//! format also used by remote signers. Before a node starts signing, its local
+//! signing state and the one in the directory shared by the nodes of the setup
+//! are merged into the most advanced one, so that CometBFT refuses to sign
+//! again what another node already signed. While signing, the local state is
+//! mirrored into the shared directory every [`MIRROR_INTERVAL`].
+//!
+//! Only the holder of the leader lease, a file in the shared directory, may
+//! sign. The leader renews its lease three times per lease duration and stops
+//! signing as soon as a renewal fails, while a stand-by node only takes over
+//! once the lease has expired. As with Raft's terms, the term of the lease is
+//! incremented on every change of leader.
+//!
+//! The state signed by a leader that crashes in between two mirrors isn't
+//! shared, so the next leader should also set CometBFT's
+//! `double_sign_check_height`.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fs, io};
+
+use namada::types::time::{DateTimeUtc, DurationSecs};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config::ValidatorFailover;
+
+/// The interval at which the leader mirrors its signing state into the shared
+/// directory
+pub const MIRROR_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The file name of the leader lease in the shared directory
+const LEASE_FILE: &str = "leader_lease.json";
+
+/// The file name of the signing state in the shared directory
+const SHARED_STATE_FILE: &str = "priv_validator_state.json";
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Failed to read {0}: {1}")]
+    Read(String, io::Error),
+    #[error("Failed to decode {0}: {1}")]
+    Decode(String, serde_json::Error),
+    #[error("Failed to encode {0}: {1}")]
+    Encode(String, serde_json::Error),
+    #[error("Failed to write {0}: {1}")]
+    Write(String, io::Error),
+    #[error(
+        "Refusing to sign height {height}, round {round}, step {step}, the \
+         validator already signed height {}, round {}, step {}",
+        .last.height, .last.round, .last.step
+    )]
+    DoubleSign {
+        height: u64,
+        round: i32,
+        step: i8,
+        last: SigningState,
+    },
+    #[error("The leader lease of term {0} was lost")]
+    LeaseLost(u64),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The last signing state of a validator, in the format of CometBFT's
+/// `priv_validator_state.json`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningState {
+    /// The last signed block height
+    #[serde(with = "height_as_string")]
+    pub height: u64,
+    /// The last signed round
+    pub round: i32,
+    /// The last signed step, i.e. proposal, prevote or precommit
+    pub step: i8,
+    /// The last signature, in base64
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// The last signed bytes, in hex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signbytes: Option<String>,
+}
+
+impl SigningState {
+    /// Read the signing state from the given file, if it exists
+    pub fn read(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        read_json(path.as_ref())
+    }
+
+    /// Atomically replace the signing state in the given file
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        write_json(path.as_ref(), self)
+    }
+
+    /// Record a signature at the given height, round and step. It's refused
+    /// unless it's strictly ahead of the last signed state.
+    pub fn record_signed(
+        &mut self,
+        height: u64,
+        round: i32,
+        step: i8,
+        signature: Option<String>,
+        signbytes: Option<String>,
+    ) -> Result<()> {
+        if (height, round, step) <= self.hrs() {
+            return Err(Error::DoubleSign {
+                height,
+                round,
+                step,
+                last: self.clone(),
+            });
+        }
+        *self = Self {
+            height,
+            round,
+            step,
+            signature,
+            signbytes,
+        };
+        Ok(())
+    }
+
+    /// The most advanced of the two signing states
+    pub fn most_advanced(self, other: Self) -> Self {
+        if other.hrs() > self.hrs() {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn hrs(&self) -> (u64, i32, i8) {
+        (self.height, self.round, self.step)
+    }
+}
+
+/// Merge the local and the shared signing states into the most advanced one
+/// and write it back to both files
+pub fn sync_signing_state(
+    local: impl AsRef<Path>,
+    shared: impl AsRef<Path>,
+) -> Result<SigningState> {
+    let local_state = SigningState::read(&local)?;
+    let shared_state = SigningState::read(&shared)?;
+    let state = local_state
+        .clone()
+        .unwrap_or_default()
+        .most_advanced(shared_state.clone().unwrap_or_default());
+    if local_state.as_ref() != Some(&state) {
+        state.write(&local)?;
+    }
+    if shared_state.as_ref() != Some(&state) {
+        state.write(&shared)?;
+    }
+    Ok(state)
+}
+
+/// A lease on the leadership of the validator nodes of a failover setup
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderLease {
+    /// The ID of the node holding the lease
+    pub holder: String,
+    /// The term of the lease, incremented on every change of leader
+    pub term: u64,
+    /// The time at which the lease expires, unless it's renewed
+    pub expires_at: DateTimeUtc,
+}
+
+impl LeaderLease {
+    /// Whether the lease has expired at the given time
+    pub fn is_expired(&self, now: DateTimeUtc) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// Try to acquire or renew the leader lease in the given file for the given
+/// node. Returns `None` if another node holds an unexpired lease.
+pub fn try_acquire_lease(
+    path: impl AsRef<Path>,
+    holder: &str,
+    duration: DurationSecs,
+    now: DateTimeUtc,
+) -> Result<Option<LeaderLease>> {
+    let path = path.as_ref();
+    let term = match read_json::<LeaderLease>(path)? {
+        None => 1,
+        Some(lease) if lease.holder == holder => lease.term,
+        Some(lease) if !lease.is_expired(now) => return Ok(None),
+        Some(lease) => lease.term + 1,
+    };
+    let lease = LeaderLease {
+        holder: holder.to_owned(),
+        term,
+        expires_at: now + duration,
+    };
+    write_json(path, &lease)?;
+    // Another node may have written the lease at the same time, only the one
+    // that's read back is held
+    let held = read_json::<LeaderLease>(path)?;
+    Ok((held.as_ref() == Some(&lease)).then_some(lease))
+}
+
+/// The leader of the validator nodes of a failover setup, i.e. the only node
+/// allowed to sign
+#[derive(Debug)]
+pub struct Leader {
+    lease: LeaderLease,
+    lease_path: PathBuf,
+    lease_duration: DurationSecs,
+    local_state: PathBuf,
+    shared_state: PathBuf,
+}
+
+impl Leader {
+    /// Wait until the leader lease is acquired and sync the local signing
+    /// state at the given path with the shared one
+    pub async fn acquire(
+        config: &ValidatorFailover,
+        local_state: PathBuf,
+    ) -> Result<Self> {
+        fs::create_dir_all(&config.shared_dir).map_err(|err| {
+            Error::Write(config.shared_dir.to_string_lossy().into_owned(), err)
+        })?;
+        let lease_path = config.shared_dir.join(LEASE_FILE);
+        let renew_interval = renew_interval(config.lease_duration);
+        let mut waiting = false;
+        let lease = loop {
+            if let Some(lease) = try_acquire_lease(
+                &lease_path,
+                &config.node_id,
+                config.lease_duration,
+                DateTimeUtc::now(),
+            )? {
+                break lease;
+            }
+            if !waiting {
+                tracing::info!(
+                    "Another validator node holds the leader lease, standing \
+                     by."
+                );
+                waiting = true;
+            }
+            tokio::time::sleep(renew_interval).await;
+        };
+        let shared_state = config.shared_dir.join(SHARED_STATE_FILE);
+        let state = sync_signing_state(&local_state, &shared_state)?;
+        tracing::info!(
+            "Acquired the leader lease of term {}, the last signed height is \
+             {}.",
+            lease.term,
+            state.height
+        );
+        Ok(Self {
+            lease,
+            lease_path,
+            lease_duration: config.lease_duration,
+            local_state,
+            shared_state,
+        })
+    }
+
+    /// Keep renewing the leader lease and mirroring the local signing state
+    /// into the shared directory. Only returns once the lease couldn't be
+    /// renewed, after which the node must stop signing.
+    pub async fn hold(&mut self) -> Error {
+        let renew_interval = renew_interval(self.lease_duration);
+        let mut last_renewal = tokio::time::Instant::now();
+        loop {
+            tokio::time::sleep(MIRROR_INTERVAL).await;
+            if let Err(err) = self.mirror() {
+                return err;
+            }
+            if last_renewal.elapsed() >= renew_interval {
+                if let Err(err) = self.renew() {
+                    return err;
+                }
+                last_renewal = tokio::time::Instant::now();
+            }
+        }
+    }
+
+    /// Mirror the last signing state and release the lease, once the node
+    /// stopped signing
+    pub fn step_down(self) {
+        if let Err(err) = self.mirror() {
+            tracing::error!("Failed to mirror the signing state: {err}");
+        }
+        let lease = LeaderLease {
+            expires_at: DateTimeUtc::now(),
+            ..self.lease.clone()
+        };
+        match self.renew_with(lease) {
+            Ok(()) => tracing::info!("Released the leader lease."),
+            Err(err) => {
+                tracing::error!("Failed to release the leader lease: {err}")
+            }
+        }
+    }
+
+    fn mirror(&self) -> Result<()> {
+        sync_signing_state(&self.local_state, &self.shared_state).map(|_| ())
+    }
+
+    fn renew(&mut self) -> Result<()> {
+        let lease = LeaderLease {
+            expires_at: DateTimeUtc::now() + self.lease_duration,
+            ..self.lease.clone()
+        };
+        self.renew_with(lease.clone())?;
+        self.lease = lease;
+        Ok(())
+    }
+
+    /// Replace the held lease, unless it was lost or has expired
+    fn renew_with(&self, lease: LeaderLease) -> Result<()> {
+        let current = read_json::<LeaderLease>(&self.lease_path)?;
+        if current.as_ref() != Some(&self.lease)
+            || self.lease.is_expired(DateTimeUtc::now())
+        {
+            return Err(Error::LeaseLost(self.lease.term));
+        }
+        write_json(&self.lease_path, &lease)
+    }
+}
+
+/// The lease is renewed three times per lease duration
+fn renew_interval(lease_duration: DurationSecs) -> Duration {
+    Duration::from(lease_duration) / 3
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    let display = || path.to_string_lossy().into_owned();
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|err| Error::Decode(display(), err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(Error::Read(display(), err)),
+    }
+}
+
+/// Write the value to a temporary file first, then rename it over the target
+/// file so that the latter is never left half-written
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let display = || path.to_string_lossy().into_owned();
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    let bytes = serde_json::to_vec_pretty(value)
+        .map_err(|err| Error::Encode(display(), err))?;
+    fs::write(&tmp_path, bytes)
+        .and_then(|()| fs::File::open(&tmp_path)?.sync_all())
+        .and_then(|()| fs::rename(&tmp_path, path))
+        .map_err(|err| Error::Write(display(), err))
+}
+
+/// CometBFT encodes the height as a string
+mod height_as_string {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        height: &u64,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(height)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_state_format() {
+        let json = r#"{"height":"10","round":1,"step":3}"#;
+        let state: SigningState = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            state,
+            SigningState {
+                height: 10,
+                round: 1,
+                step: 3,
+                ..Default::default()
+            }
+        );
+        assert_eq!(serde_json::to_string(&state).unwrap(), json);
+    }
+
+    #[test]
+    fn test_signing_state_double_sign() {
+        let mut state = SigningState::default();
+        state.record_signed(1, 0, 1, None, None).unwrap();
+        state.record_signed(1, 0, 2, None, None).unwrap();
+        state.record_signed(1, 1, 1, None, None).unwrap();
+        // Signing again, or behind the last signed state, is refused
+        for (height, round, step) in [(1, 1, 1), (1, 0, 3), (0, 2, 3)] {
+            assert!(matches!(
+                state.record_signed(height, round, step, None, None),
+                Err(Error::DoubleSign { .. })
+            ));
+        }
+        state.record_signed(2, 0, 1, None, None).unwrap();
+    }
+
+    #[test]
+    fn test_sync_signing_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("local.json");
+        let shared = dir.path().join("shared.json");
+        let state = |height| SigningState {
+            height,
+            ..Default::default()
+        };
+
+        state(5).write(&local).unwrap();
+        assert_eq!(sync_signing_state(&local, &shared).unwrap(), state(5));
+        assert_eq!(SigningState::read(&shared).unwrap(), Some(state(5)));
+
+        // A local state behind the shared one is advanced
+        state(3).write(&local).unwrap();
+        state(7).write(&shared).unwrap();
+        assert_eq!(sync_signing_state(&local, &shared).unwrap(), state(7));
+        assert_eq!(SigningState::read(&local).unwrap(), Some(state(7)));
+    }
+
+    #[test]
+    fn test_leader_lease() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LEASE_FILE);
+        let duration = DurationSecs(10);
+        let start = DateTimeUtc::unix_epoch();
+        let at = |secs| start + DurationSecs(secs);
+
+        let lease = try_acquire_lease(&path, "a", duration, start)
+            .unwrap()
+            .unwrap();
+        assert_eq!(lease.term, 1);
+
+        // The lease can't be acquired by another node until it expires
+        assert!(try_acquire_lease(&path, "b", duration, at(9))
+            .unwrap()
+            .is_none());
+        // The holder renews it in the same term
+        let lease = try_acquire_lease(&path, "a", duration, at(9))
+            .unwrap()
+            .unwrap();
+        assert_eq!(lease.term, 1);
+        assert_eq!(lease.expires_at, at(19));
+
+        // Once expired, another node takes over in the next term
+        let lease = try_acquire_lease(&path, "b", duration, at(19))
+            .unwrap()
+            .unwrap();
+        assert_eq!(lease.holder, "b");
+        assert_eq!(lease.term, 2);
+    }
+}
@@ -0,0 +1,586 @@
+//! The archive of the data of the old blocks.
+//!
+//! When the `archive` config is set, the data of the blocks older than the
+//! configured number of epochs is moved out of the DB into an object store,
+//! either a local directory or an S3-compatible bucket. Each block is archived
+//! as a single compressed object, holding its entries from the `block` and
+//! `diffs` column families of the DB. The objects are fetched back from the
+//! store when the state at the height of an archived block is queried.
+//!
+//! The format of an archived block is:
+//! - the magic bytes `NAMB`
+//! - a format version byte
+//! - the zlib compressed Borsh encoding of an [`ArchivedBlock`]
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Debug;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use borsh_ext::BorshSerializeExt;
+use data_encoding::HEXLOWER;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use namada::types::storage::{BlockHeight, Epoch};
+use namada::types::time::DateTimeUtc;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::config::{ArchiveConfig, ArchiveStore};
+use crate::node::ledger::shell::Shell;
+
+/// Env. var to set the access key ID of the S3 bucket of the archive
+pub const ENV_VAR_S3_ACCESS_KEY_ID: &str = "NAMADA_ARCHIVE_S3_ACCESS_KEY_ID";
+/// Env. var to set the secret access key of the S3 bucket of the archive
+pub const ENV_VAR_S3_SECRET_ACCESS_KEY: &str =
+    "NAMADA_ARCHIVE_S3_SECRET_ACCESS_KEY";
+
+/// The magic bytes at the start of an archived block
+const MAGIC: &[u8; 4] = b"NAMB";
+/// The version of the format of the archived blocks
+const FORMAT_VERSION: u8 = 1;
+/// The number of recently fetched blocks kept in memory
+const CACHE_CAPACITY: usize = 32;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid archived block: {0}")]
+    InvalidBlock(String),
+    #[error("The archived block at height {0} is missing from the store")]
+    MissingBlock(BlockHeight),
+    #[error("Archive IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Archive request error: {0}")]
+    Request(String),
+    #[error("The {0} env var must be set to access the S3 bucket")]
+    MissingCredentials(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The data of a block offloaded to the archive
+#[derive(
+    Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub struct ArchivedBlock {
+    /// The height of the block
+    pub height: BlockHeight,
+    /// The entries of the block in the `block` column family
+    pub block: BTreeMap<String, Vec<u8>>,
+    /// The entries of the block in the `diffs` column family
+    pub diffs: BTreeMap<String, Vec<u8>>,
+}
+
+impl ArchivedBlock {
+    /// Encode the block in the archive format
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(FORMAT_VERSION);
+        let mut encoder = ZlibEncoder::new(bytes, Compression::default());
+        encoder
+            .write_all(&self.serialize_to_vec())
+            .expect("Compressing to memory shouldn't fail");
+        encoder
+            .finish()
+            .expect("Compressing to memory shouldn't fail")
+    }
+
+    /// Decode a block from the archive format
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let Some(compressed) = bytes.strip_prefix(MAGIC.as_slice()) else {
+            return Err(Error::InvalidBlock("missing magic bytes".into()));
+        };
+        match compressed.split_first() {
+            Some((&FORMAT_VERSION, compressed)) => {
+                let mut decoded = vec![];
+                ZlibDecoder::new(compressed)
+                    .read_to_end(&mut decoded)
+                    .map_err(|e| Error::InvalidBlock(e.to_string()))?;
+                Self::try_from_slice(&decoded)
+                    .map_err(|e| Error::InvalidBlock(e.to_string()))
+            }
+            Some((version, _)) => Err(Error::InvalidBlock(format!(
+                "unsupported format version {version}"
+            ))),
+            None => Err(Error::InvalidBlock("missing format version".into())),
+        }
+    }
+}
+
+/// The name of the object of the archived block at the given height
+fn object_name(height: BlockHeight) -> String {
+    // Zero-padded for the objects to be listed in order of height
+    format!("blocks/{:020}.namb", height.0)
+}
+
+/// A store of named objects
+pub trait ObjectStore: Debug + Send + Sync {
+    /// Write an object
+    fn put(&self, name: &str, bytes: Vec<u8>) -> Result<()>;
+
+    /// Read an object, if it exists
+    fn get(&self, name: &str) -> Result<Option<Vec<u8>>>;
+}
+
+/// An object store in a local directory
+#[derive(Debug)]
+pub struct DirStore {
+    root: PathBuf,
+}
+
+impl DirStore {
+    /// Open a store in the given directory, creating it if needed
+    pub fn open(root: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+}
+
+impl ObjectStore for DirStore {
+    fn put(&self, name: &str, bytes: Vec<u8>) -> Result<()> {
+        let path = self.root.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write to a temporary file first, for an interrupted write not to
+        // leave a truncated object behind
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.root.join(name)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// An object store in an S3-compatible bucket, with requests signed with AWS
+/// Signature Version 4
+pub struct S3Store {
+    endpoint: String,
+    region: String,
+    bucket: String,
+    prefix: String,
+    access_key_id: String,
+    secret_access_key: String,
+    client: reqwest::Client,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl Debug for S3Store {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The credentials are left out, for them not to end up in the logs
+        f.debug_struct("S3Store")
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("bucket", &self.bucket)
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3Store {
+    /// Open a store in the given bucket, with the credentials read from the
+    /// env vars
+    pub fn open(
+        endpoint: String,
+        region: String,
+        bucket: String,
+        prefix: String,
+    ) -> Result<Self> {
+        let access_key_id = std::env::var(ENV_VAR_S3_ACCESS_KEY_ID)
+            .map_err(|_| Error::MissingCredentials(ENV_VAR_S3_ACCESS_KEY_ID))?;
+        let secret_access_key = std::env::var(ENV_VAR_S3_SECRET_ACCESS_KEY)
+            .map_err(|_| {
+                Error::MissingCredentials(ENV_VAR_S3_SECRET_ACCESS_KEY)
+            })?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            endpoint: endpoint.trim_end_matches('/').to_owned(),
+            region,
+            bucket,
+            prefix: prefix.trim_matches('/').to_owned(),
+            access_key_id,
+            secret_access_key,
+            client: reqwest::Client::new(),
+            runtime,
+        })
+    }
+
+    /// Build a signed request for the given object
+    fn request(
+        &self,
+        method: reqwest::Method,
+        name: &str,
+        body: Vec<u8>,
+    ) -> Result<reqwest::RequestBuilder> {
+        let object = if self.prefix.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}/{name}", self.prefix)
+        };
+        let url = reqwest::Url::parse(&format!(
+            "{}/{}/{object}",
+            self.endpoint, self.bucket
+        ))
+        .map_err(|e| Error::Request(e.to_string()))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_owned(),
+            (None, _) => {
+                return Err(Error::Request(format!(
+                    "Missing host in the S3 endpoint {}",
+                    self.endpoint
+                )));
+            }
+        };
+        let now = DateTimeUtc::now().0;
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = HEXLOWER.encode(&Sha256::digest(&body));
+        let authorization = sign_v4(
+            &SigningParams {
+                method: method.as_str(),
+                path: url.path(),
+                host: &host,
+                payload_hash: &payload_hash,
+                amz_date: &amz_date,
+                date: &date,
+                region: &self.region,
+            },
+            &self.access_key_id,
+            &self.secret_access_key,
+        );
+        Ok(self
+            .client
+            .request(method, url)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .header("authorization", authorization)
+            .body(body))
+    }
+}
+
+impl ObjectStore for S3Store {
+    fn put(&self, name: &str, bytes: Vec<u8>) -> Result<()> {
+        let request = self.request(reqwest::Method::PUT, name, bytes)?;
+        self.runtime.block_on(async {
+            let response = request
+                .send()
+                .await
+                .map_err(|e| Error::Request(e.to_string()))?;
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                Err(Error::Request(format!(
+                    "Writing {name} to the S3 bucket failed with status {}",
+                    response.status()
+                )))
+            }
+        })
+    }
+
+    fn get(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let request = self.request(reqwest::Method::GET, name, vec![])?;
+        self.runtime.block_on(async {
+            let response = request
+                .send()
+                .await
+                .map_err(|e| Error::Request(e.to_string()))?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            if !response.status().is_success() {
+                return Err(Error::Request(format!(
+                    "Reading {name} from the S3 bucket failed with status {}",
+                    response.status()
+                )));
+            }
+            let bytes = response
+                .bytes()
+                .await
+                .map_err(|e| Error::Request(e.to_string()))?;
+            Ok(Some(bytes.to_vec()))
+        })
+    }
+}
+
+/// The parameters of a request to sign with AWS Signature Version 4
+struct SigningParams<'a> {
+    method: &'a str,
+    path: &'a str,
+    host: &'a str,
+    payload_hash: &'a str,
+    amz_date: &'a str,
+    date: &'a str,
+    region: &'a str,
+}
+
+/// Compute the `authorization` header of an S3 request, signing over its
+/// `host`, `x-amz-content-sha256` and `x-amz-date` headers
+fn sign_v4(
+    params: &SigningParams<'_>,
+    access_key_id: &str,
+    secret_access_key: &str,
+) -> String {
+    const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+    let SigningParams {
+        method,
+        path,
+        host,
+        payload_hash,
+        amz_date,
+        date,
+        region,
+    } = params;
+    let canonical_request = format!(
+        "{method}\n{path}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\\
+         \
+         nx-amz-date:{amz_date}\n\n{SIGNED_HEADERS}\n{payload_hash}"
+    );
+    let scope = format!("{date}/{region}/s3/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        HEXLOWER.encode(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = [
+        region.as_bytes(),
+        b"s3".as_slice(),
+        b"aws4_request".as_slice(),
+    ]
+    .into_iter()
+    .fold(
+        hmac_sha256(
+            format!("AWS4{secret_access_key}").as_bytes(),
+            date.as_bytes(),
+        ),
+        |key, data| hmac_sha256(&key, data),
+    );
+    let signature =
+        HEXLOWER.encode(&hmac_sha256(&key, string_to_sign.as_bytes()));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={access_key_id}/{scope}, \
+         SignedHeaders={SIGNED_HEADERS}, Signature={signature}"
+    )
+}
+
+/// Compute the HMAC-SHA256 of some data
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+    let mut block_key = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block_key[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block_key.map(|b| b ^ 0x36));
+    inner.update(data);
+    let mut outer = Sha256::new();
+    outer.update(block_key.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// The archive of the data of the old blocks
+#[derive(Debug)]
+pub struct Archive {
+    store: Box<dyn ObjectStore>,
+    /// The recently fetched blocks
+    cache: Mutex<VecDeque<Arc<ArchivedBlock>>>,
+    /// The number of past epochs of blocks data kept in the DB
+    pub offload_after_epochs: u64,
+    /// The maximum number of blocks offloaded after each committed block
+    pub blocks_per_commit: u64,
+}
+
+impl Archive {
+    /// Open the archive with the given config
+    pub fn open(config: &ArchiveConfig) -> Result<Self> {
+        let store: Box<dyn ObjectStore> = match &config.store {
+            ArchiveStore::Dir { path } => {
+                Box::new(DirStore::open(path.clone())?)
+            }
+            ArchiveStore::S3 {
+                endpoint,
+                region,
+                bucket,
+                prefix,
+            } => Box::new(S3Store::open(
+                endpoint.clone(),
+                region.clone(),
+                bucket.clone(),
+                prefix.clone(),
+            )?),
+        };
+        Ok(Self::new(
+            store,
+            config.offload_after_epochs,
+            config.blocks_per_commit,
+        ))
+    }
+
+    /// Create an archive in the given store
+    pub fn new(
+        store: Box<dyn ObjectStore>,
+        offload_after_epochs: u64,
+        blocks_per_commit: u64,
+    ) -> Self {
+        Self {
+            store,
+            cache: Mutex::new(VecDeque::with_capacity(CACHE_CAPACITY)),
+            offload_after_epochs,
+            blocks_per_commit,
+        }
+    }
+
+    /// Write a block to the archive
+    pub fn put_block(&self, block: &ArchivedBlock) -> Result<()> {
+        self.store.put(&object_name(block.height), block.encode())
+    }
+
+    /// Read an archived block, from the cache of the recently fetched blocks
+    /// or else from the store
+    pub fn get_block(&self, height: BlockHeight) -> Result<Arc<ArchivedBlock>> {
+        if let Some(block) = self
+            .cache
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|block| block.height == height)
+        {
+            return Ok(block.clone());
+        }
+        let bytes = self
+            .store
+            .get(&object_name(height))?
+            .ok_or(Error::MissingBlock(height))?;
+        let block = Arc::new(ArchivedBlock::decode(&bytes)?);
+        if block.height != height {
+            return Err(Error::InvalidBlock(format!(
+                "expected the block at height {height}, got the block at \
+                 height {}",
+                block.height
+            )));
+        }
+        let mut cache = self.cache.lock().unwrap();
+        if cache.len() >= CACHE_CAPACITY {
+            cache.pop_front();
+        }
+        cache.push_back(block.clone());
+        Ok(block)
+    }
+}
+
+impl Shell {
+    /// Open the archive of the data of the old blocks of the shell's DB
+    pub fn open_archive(&mut self, config: &ArchiveConfig) -> Result<()> {
+        let archive = Archive::open(config)?;
+        self.wl_storage.storage.db.set_archive(Arc::new(archive));
+        Ok(())
+    }
+
+    /// Offload the data of the blocks older than the configured number of
+    /// epochs to the archive, if any
+    pub fn archive_old_blocks(&mut self) {
+        let storage = &mut self.wl_storage.storage;
+        let Some(archive) = storage.db.archive().cloned() else {
+            return;
+        };
+        let Some(epoch) = storage
+            .last_epoch
+            .0
+            .checked_sub(archive.offload_after_epochs)
+        else {
+            return;
+        };
+        let Some(below) = storage
+            .block
+            .pred_epochs
+            .get_start_height_of_epoch(Epoch(epoch))
+        else {
+            return;
+        };
+        match storage.db.archive_blocks(below, archive.blocks_per_commit) {
+            Ok(0) => {}
+            Ok(offloaded) => tracing::info!(
+                "Offloaded the data of {offloaded} blocks to the archive, up \
+                 to height {below}"
+            ),
+            Err(err) => tracing::error!(
+                "Failed to offload the data of old blocks to the archive: \
+                 {err}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    /// A test block
+    fn test_block(height: u64) -> ArchivedBlock {
+        ArchivedBlock {
+            height: BlockHeight(height),
+            block: BTreeMap::from([(format!("{height}/hash"), vec![1; 32])]),
+            diffs: BTreeMap::from([(format!("{height}/new/key"), vec![2; 8])]),
+        }
+    }
+
+    /// Test that an archived block is decoded back from its encoding, and
+    /// that unknown encodings are rejected.
+    #[test]
+    fn test_archived_block_encoding() {
+        let block = test_block(7);
+        let bytes = block.encode();
+        assert!(bytes.starts_with(b"NAMB\x01"));
+        assert_eq!(ArchivedBlock::decode(&bytes).unwrap(), block);
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[MAGIC.len()] = FORMAT_VERSION + 1;
+        assert!(ArchivedBlock::decode(&wrong_version).is_err());
+        assert!(ArchivedBlock::decode(&bytes[1..]).is_err());
+    }
+
+    /// Test that the archived blocks are read back from a directory store.
+    #[test]
+    fn test_dir_archive() {
+        let dir = tempdir().unwrap();
+        let store = DirStore::open(dir.path().join("archive")).unwrap();
+        let archive = Archive::new(Box::new(store), 1, 1);
+
+        let block = test_block(1);
+        archive.put_block(&block).unwrap();
+        assert_eq!(*archive.get_block(BlockHeight(1)).unwrap(), block);
+        // served from the cache
+        assert_eq!(*archive.get_block(BlockHeight(1)).unwrap(), block);
+        assert!(matches!(
+            archive.get_block(BlockHeight(2)),
+            Err(Error::MissingBlock(BlockHeight(2)))
+        ));
+    }
+
+    /// Test the HMAC-SHA256 against the test case 2 of RFC 4231.
+    #[test]
+    fn test_hmac_sha256() {
+        assert_eq!(
+            HEXLOWER
+                .encode(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
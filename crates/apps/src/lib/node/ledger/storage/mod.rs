@@ -1,7 +1,8 @@
 //! The storage module handles both the current state in-memory and the stored
 //! state in DB.
 
-mod rocksdb;
+pub mod archive;
+pub mod rocksdb;
 
 use std::fmt;
 
@@ -172,6 +173,9 @@ mod tests {
             fee_unshielding_gas_limit: 0,
             fee_unshielding_descriptions_limit: 0,
             minimum_gas_price: Default::default(),
+            max_native_mint_per_epoch: None,
+            masp_conversion_retention_epochs: None,
+            max_tx_gas: None,
         };
         parameters::init_storage(&params, &mut wl_storage)
             .expect("Test failed");
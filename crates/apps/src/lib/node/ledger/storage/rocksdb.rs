@@ -18,6 +18,8 @@
 //!     - `next_epoch_min_start_height`
 //!     - `next_epoch_min_start_time`
 //!   - `conversion_state`: MASP conversion state
+//!   - `archived_height`: the height below which the data of the blocks in the
+//!     `diffs` and `block` column families was offloaded to the archive
 //! - `subspace`: accounts sub-spaces
 //!   - `{address}/{dyn}`: any byte data associated with accounts
 //! - `diffs`: diffs in account subspaces' key-vals
@@ -38,11 +40,12 @@
 //!     - `all`: the hashes included up to the last block
 //!     - `last`: the hashes included in the last block
 
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::str::FromStr;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use borsh::BorshDeserialize;
 use borsh_ext::BorshSerializeExt;
@@ -53,7 +56,7 @@ use namada::ledger::eth_bridge::storage::bridge_pool;
 use namada::ledger::replay_protection;
 use namada::ledger::storage::tx_queue::TxQueue;
 use namada::state::merkle_tree::{base_tree_key_prefix, subtree_key_prefix};
-use namada::state::types::PrefixIterator;
+use namada::state::types::{KVBytes, PrefixIterator};
 use namada::state::{
     BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, DbError as Error,
     DbResult as Result, MerkleTreeStoresRead, StoreType, DB,
@@ -73,6 +76,7 @@ use rocksdb::{
     ReadOptions, WriteBatch,
 };
 
+use super::archive::{Archive, ArchivedBlock};
 use crate::config::utils::num_of_threads;
 
 // TODO the DB schema will probably need some kind of versioning
@@ -91,9 +95,21 @@ const REPLAY_PROTECTION_CF: &str = "replay_protection";
 const OLD_DIFF_PREFIX: &str = "old";
 const NEW_DIFF_PREFIX: &str = "new";
 
-/// RocksDB handle
+const ARCHIVED_HEIGHT_KEY: &str = "archived_height";
+
+/// RocksDB handle, with the archive of the data of the old blocks, if any
 #[derive(Debug)]
-pub struct RocksDB(rocksdb::DB);
+pub struct RocksDB(rocksdb::DB, DbAccess, Option<Arc<Archive>>);
+
+/// The kind of access to the DB of a RocksDB handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DbAccess {
+    /// The primary instance of the DB, with read-write access
+    Primary,
+    /// A secondary instance of the DB, with read-only access, following the
+    /// writes of the primary instance
+    Secondary,
+}
 
 /// DB Handle for batch writes.
 #[derive(Default)]
@@ -104,6 +120,39 @@ pub fn open(
     path: impl AsRef<Path>,
     cache: Option<&rocksdb::Cache>,
 ) -> Result<RocksDB> {
+    let (db_opts, cfs) = db_options(cache);
+    rocksdb::DB::open_cf_descriptors(&db_opts, path, cfs)
+        .map(|db| RocksDB(db, DbAccess::Primary, None))
+        .map_err(|e| Error::DBError(e.into_string()))
+}
+
+/// Open a read-only secondary instance of the DB at `primary_path`. The
+/// secondary instance sees the state of the DB at the time it was opened or
+/// last caught up with the primary instance (see
+/// [`RocksDB::catch_up_with_primary`]). Its info logs are kept in
+/// `secondary_path`.
+pub fn open_secondary(
+    primary_path: impl AsRef<Path>,
+    secondary_path: impl AsRef<Path>,
+    cache: Option<&rocksdb::Cache>,
+) -> Result<RocksDB> {
+    let (mut db_opts, cfs) = db_options(cache);
+    // A secondary instance has to keep all the files of the DB open
+    db_opts.set_max_open_files(-1);
+    rocksdb::DB::open_cf_descriptors_as_secondary(
+        &db_opts,
+        primary_path.as_ref(),
+        secondary_path.as_ref(),
+        cfs,
+    )
+    .map(|db| RocksDB(db, DbAccess::Secondary, None))
+    .map_err(|e| Error::DBError(e.into_string()))
+}
+
+/// The options of the DB and its column families
+fn db_options(
+    cache: Option<&rocksdb::Cache>,
+) -> (Options, Vec<ColumnFamilyDescriptor>) {
     let logical_cores = num_cpus::get();
     let compaction_threads = num_of_threads(
         ENV_VAR_ROCKSDB_COMPACTION_THREADS,
@@ -190,18 +239,27 @@ pub fn open(
         replay_protection_cf_opts,
     ));
 
-    rocksdb::DB::open_cf_descriptors(&db_opts, path, cfs)
-        .map(RocksDB)
-        .map_err(|e| Error::DBError(e.into_string()))
+    (db_opts, cfs)
 }
 
 impl Drop for RocksDB {
     fn drop(&mut self) {
-        self.flush(true).expect("flush failed");
+        // A secondary instance has nothing to flush
+        if self.1 == DbAccess::Primary {
+            self.flush(true).expect("flush failed");
+        }
     }
 }
 
 impl RocksDB {
+    /// Catch up a secondary instance of the DB with the writes of the primary
+    /// instance
+    pub fn catch_up_with_primary(&self) -> Result<()> {
+        self.0
+            .try_catch_up_with_primary()
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
     fn get_column_family(&self, cf_name: &str) -> Result<&ColumnFamily> {
         self.0
             .cf_handle(cf_name)
@@ -327,6 +385,141 @@ impl RocksDB {
             .map_err(|e| Error::DBError(e.into_string()))
     }
 
+    /// Set the archive of the data of the old blocks. The data of the blocks
+    /// that were offloaded to the archive is only served with the archive set.
+    pub fn set_archive(&mut self, archive: Arc<Archive>) {
+        self.2 = Some(archive);
+    }
+
+    /// Get the archive of the data of the old blocks, if any
+    pub fn archive(&self) -> Option<&Arc<Archive>> {
+        self.2.as_ref()
+    }
+
+    /// Read the height below which the data of the blocks was offloaded to
+    /// the archive
+    fn read_archived_height(&self) -> Result<BlockHeight> {
+        let state_cf = self.get_column_family(STATE_CF)?;
+        match self
+            .0
+            .get_cf(state_cf, ARCHIVED_HEIGHT_KEY)
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(bytes) => types::decode(bytes).map_err(Error::CodingError),
+            None => Ok(BlockHeight::default()),
+        }
+    }
+
+    /// Read the data of the block at the given height from the archive, if
+    /// it was offloaded to it
+    fn read_archived_block(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<Arc<ArchivedBlock>>> {
+        let Some(archive) = self.2.as_ref() else {
+            return Ok(None);
+        };
+        if height >= self.read_archived_height()? {
+            return Ok(None);
+        }
+        archive
+            .get_block(height)
+            .map(Some)
+            .map_err(|e| Error::DBError(e.to_string()))
+    }
+
+    /// Read a value of the `block` column family, from the archive if the
+    /// block at the given height was offloaded to it
+    fn read_block_val(
+        &self,
+        key: String,
+        height: BlockHeight,
+    ) -> Result<Option<Vec<u8>>> {
+        if let Some(block) = self.read_archived_block(height)? {
+            return Ok(block.block.get(&key).cloned());
+        }
+        let block_cf = self.get_column_family(BLOCK_CF)?;
+        self.0
+            .get_cf(block_cf, key)
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
+    /// Offload the data of the blocks below the given height to the archive,
+    /// oldest first and up to `max_blocks` blocks. Returns the number of
+    /// offloaded blocks.
+    pub fn archive_blocks(
+        &mut self,
+        below: BlockHeight,
+        max_blocks: u64,
+    ) -> Result<u64> {
+        let Some(archive) = self.2.clone() else {
+            return Ok(0);
+        };
+        let mut height = self.read_archived_height()?;
+        let mut offloaded = 0;
+        while height < below && offloaded < max_blocks {
+            let block = self.collect_block_data(height)?;
+            archive
+                .put_block(&block)
+                .map_err(|e| Error::DBError(e.to_string()))?;
+
+            // Only delete the data from the DB once it's archived
+            let state_cf = self.get_column_family(STATE_CF)?;
+            let block_cf = self.get_column_family(BLOCK_CF)?;
+            let diffs_cf = self.get_column_family(DIFFS_CF)?;
+            let mut batch = WriteBatch::default();
+            for key in block.block.keys() {
+                batch.delete_cf(block_cf, key);
+            }
+            for key in block.diffs.keys() {
+                batch.delete_cf(diffs_cf, key);
+            }
+            height = height.next_height();
+            batch.put_cf(state_cf, ARCHIVED_HEIGHT_KEY, types::encode(&height));
+            self.exec_batch(batch)?;
+            offloaded += 1;
+        }
+        Ok(offloaded)
+    }
+
+    /// Collect the data of a block in the `block` and `diffs` column families
+    fn collect_block_data(&self, height: BlockHeight) -> Result<ArchivedBlock> {
+        let prefix = format!("{}/", Key::from(height.to_db_key()));
+        // The subtrees stores are keyed by epoch and not by height, only the
+        // base tree stores belong to the block
+        let subtrees_prefix = format!("{prefix}tree/");
+        let base_tree_prefix = format!("{}/", base_tree_key_prefix(height));
+        let collect = |cf: &ColumnFamily| {
+            let read_opts = make_iter_read_opts(Some(prefix.clone()));
+            self.0
+                .iterator_cf_opt(
+                    cf,
+                    read_opts,
+                    IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+                )
+                .map(|result| {
+                    let (key, val) =
+                        result.map_err(|e| Error::DBError(e.into_string()))?;
+                    let key = String::from_utf8(key.to_vec())
+                        .map_err(|e| Error::DBError(e.to_string()))?;
+                    Ok((key, val.to_vec()))
+                })
+                .filter(|result| match result {
+                    Ok((key, _)) => {
+                        !key.starts_with(&subtrees_prefix)
+                            || key.starts_with(&base_tree_prefix)
+                    }
+                    Err(_) => true,
+                })
+                .collect::<Result<BTreeMap<_, _>>>()
+        };
+        Ok(ArchivedBlock {
+            height,
+            block: collect(self.get_column_family(BLOCK_CF)?)?,
+            diffs: collect(self.get_column_family(DIFFS_CF)?)?,
+        })
+    }
+
     /// Dump last known block
     pub fn dump_block(
         &self,
@@ -465,7 +658,7 @@ impl RocksDB {
 
         let mut buf = BufWriter::new(file);
         for (key, raw_val, _gas) in PersistentPrefixIterator(
-            PrefixIterator::new(iter, String::default()),
+            PrefixIterator::new(Either::Left(iter), String::default()),
             // Empty string to prevent prefix stripping, the prefix is
             // already in the enclosed iterator
         ) {
@@ -618,7 +811,7 @@ impl RocksDB {
             );
             for (key, _value, _gas) in PersistentPrefixIterator(
                 // Empty prefix string to prevent stripping
-                PrefixIterator::new(iter, String::default()),
+                PrefixIterator::new(Either::Left(iter), String::default()),
             ) {
                 batch.delete_cf(cf, key);
             }
@@ -1137,15 +1330,11 @@ impl DB for RocksDB {
     }
 
     fn read_block_header(&self, height: BlockHeight) -> Result<Option<Header>> {
-        let block_cf = self.get_column_family(BLOCK_CF)?;
         let prefix_key = Key::from(height.to_db_key());
         let key = prefix_key
             .push(&"header".to_owned())
             .map_err(Error::KeyError)?;
-        let value = self
-            .0
-            .get_cf(block_cf, key.to_string())
-            .map_err(|e| Error::DBError(e.into_string()))?;
+        let value = self.read_block_val(key.to_string(), height)?;
         match value {
             Some(v) => Ok(Some(
                 Header::try_from_slice(&v[..])
@@ -1163,6 +1352,7 @@ impl DB for RocksDB {
     ) -> Result<Option<MerkleTreeStoresRead>> {
         // Get the latest height at which the tree stores were written
         let block_cf = self.get_column_family(BLOCK_CF)?;
+        let archived_block = self.read_archived_block(base_height)?;
         let mut merkle_tree_stores = MerkleTreeStoresRead::default();
         let store_types = store_type
             .as_ref()
@@ -1175,10 +1365,16 @@ impl DB for RocksDB {
                 subtree_key_prefix(st, epoch)
             };
             let root_key = key_prefix.clone().with_segment("root".to_owned());
-            let bytes = self
-                .0
-                .get_cf(block_cf, root_key.to_string())
-                .map_err(|e| Error::DBError(e.into_string()))?;
+            let bytes = match &archived_block {
+                // Only the base tree stores are archived with the blocks
+                Some(block) if *st == StoreType::Base => {
+                    block.block.get(&root_key.to_string()).cloned()
+                }
+                _ => self
+                    .0
+                    .get_cf(block_cf, root_key.to_string())
+                    .map_err(|e| Error::DBError(e.into_string()))?,
+            };
             match bytes {
                 Some(b) => {
                     let root = types::decode(b).map_err(Error::CodingError)?;
@@ -1188,10 +1384,15 @@ impl DB for RocksDB {
             }
 
             let store_key = key_prefix.with_segment("store".to_owned());
-            let bytes = self
-                .0
-                .get_cf(block_cf, store_key.to_string())
-                .map_err(|e| Error::DBError(e.into_string()))?;
+            let bytes = match &archived_block {
+                Some(block) if *st == StoreType::Base => {
+                    block.block.get(&store_key.to_string()).cloned()
+                }
+                _ => self
+                    .0
+                    .get_cf(block_cf, store_key.to_string())
+                    .map_err(|e| Error::DBError(e.into_string()))?,
+            };
             match bytes {
                 Some(b) => {
                     merkle_tree_stores.set_store(st.decode_store(b)?);
@@ -1238,6 +1439,9 @@ impl DB for RocksDB {
             old_and_new_diff_key(key, height)?.1
         };
 
+        if let Some(block) = self.read_archived_block(height)? {
+            return Ok(block.diffs.get(&key).cloned());
+        }
         self.0
             .get_cf(diffs_cf, key)
             .map_err(|e| Error::DBError(e.into_string()))
@@ -1260,26 +1464,37 @@ impl DB for RocksDB {
         let diffs_cf = self.get_column_family(DIFFS_CF)?;
         let (old_val_key, new_val_key) = old_and_new_diff_key(key, height)?;
 
-        // If it has a "new" val, it was written at this height
-        match self
-            .0
-            .get_cf(diffs_cf, new_val_key)
-            .map_err(|e| Error::DBError(e.into_string()))?
-        {
-            Some(new_val) => {
-                return Ok(Some(new_val));
+        if let Some(block) = self.read_archived_block(height)? {
+            // If it has a "new" val, it was written at this height
+            if let Some(new_val) = block.diffs.get(&new_val_key) {
+                return Ok(Some(new_val.clone()));
             }
-            None => {
-                // If it has an "old" val, it was deleted at this height
-                if self.0.key_may_exist_cf(diffs_cf, &old_val_key) {
-                    // check if it actually exists
-                    if self
-                        .0
-                        .get_cf(diffs_cf, old_val_key)
-                        .map_err(|e| Error::DBError(e.into_string()))?
-                        .is_some()
-                    {
-                        return Ok(None);
+            // If it has an "old" val, it was deleted at this height
+            if block.diffs.contains_key(&old_val_key) {
+                return Ok(None);
+            }
+        } else {
+            // If it has a "new" val, it was written at this height
+            match self
+                .0
+                .get_cf(diffs_cf, new_val_key)
+                .map_err(|e| Error::DBError(e.into_string()))?
+            {
+                Some(new_val) => {
+                    return Ok(Some(new_val));
+                }
+                None => {
+                    // If it has an "old" val, it was deleted at this height
+                    if self.0.key_may_exist_cf(diffs_cf, &old_val_key) {
+                        // check if it actually exists
+                        if self
+                            .0
+                            .get_cf(diffs_cf, old_val_key)
+                            .map_err(|e| Error::DBError(e.into_string()))?
+                            .is_some()
+                        {
+                            return Ok(None);
+                        }
                     }
                 }
             }
@@ -1292,17 +1507,26 @@ impl DB for RocksDB {
             // Try to find the next diff on this key
             let (old_val_key, new_val_key) =
                 old_and_new_diff_key(key, BlockHeight(raw_height))?;
-            let old_val = self
-                .0
-                .get_cf(diffs_cf, &old_val_key)
-                .map_err(|e| Error::DBError(e.into_string()))?;
+            let archived_block =
+                self.read_archived_block(BlockHeight(raw_height))?;
+            let old_val = match &archived_block {
+                Some(block) => block.diffs.get(&old_val_key).cloned(),
+                None => self
+                    .0
+                    .get_cf(diffs_cf, &old_val_key)
+                    .map_err(|e| Error::DBError(e.into_string()))?,
+            };
             // If it has an "old" val, it's the one we're looking for
             match old_val {
                 Some(bytes) => return Ok(Some(bytes)),
                 None => {
                     // Check if the value was created at this height instead,
                     // which would mean that it wasn't present before
-                    if self.0.key_may_exist_cf(diffs_cf, &new_val_key) {
+                    if let Some(block) = &archived_block {
+                        if block.diffs.contains_key(&new_val_key) {
+                            return Ok(None);
+                        }
+                    } else if self.0.key_may_exist_cf(diffs_cf, &new_val_key) {
                         // check if it actually exists
                         if self
                             .0
@@ -1586,7 +1810,10 @@ impl<'iter> DBIter<'iter> for RocksDB {
             read_opts,
             IteratorMode::From(prefix.as_bytes(), Direction::Forward),
         );
-        PersistentPrefixIterator(PrefixIterator::new(iter, db_prefix))
+        PersistentPrefixIterator(PrefixIterator::new(
+            Either::Left(iter),
+            db_prefix,
+        ))
     }
 
     fn iter_old_diffs(
@@ -1606,12 +1833,18 @@ impl<'iter> DBIter<'iter> for RocksDB {
     }
 
     fn iter_replay_protection(&'iter self) -> Self::PrefixIter {
+        self.iter_replay_protection_prefix(&replay_protection::last_prefix())
+    }
+
+    fn iter_replay_protection_prefix(
+        &'iter self,
+        prefix: &Key,
+    ) -> Self::PrefixIter {
         let replay_protection_cf = self
             .get_column_family(REPLAY_PROTECTION_CF)
             .expect("{REPLAY_PROTECTION_CF} column family should exist");
 
-        let stripped_prefix = Some(replay_protection::last_prefix());
-        iter_prefix(self, replay_protection_cf, stripped_prefix.as_ref(), None)
+        iter_prefix(self, replay_protection_cf, Some(prefix), None)
     }
 }
 
@@ -1645,6 +1878,16 @@ fn iter_diffs_prefix<'a>(
             .push(&kind.to_string())
             .unwrap(),
     );
+    if let Some(block) = db
+        .read_archived_block(height)
+        .expect("Archived block should be readable")
+    {
+        return iter_archived_prefix(
+            &block.diffs,
+            stripped_prefix.as_ref(),
+            prefix,
+        );
+    }
     // get keys without the `stripped_prefix`
     iter_prefix(db, diffs_cf, stripped_prefix.as_ref(), prefix)
 }
@@ -1659,6 +1902,49 @@ fn iter_prefix<'a>(
     stripped_prefix: Option<&Key>,
     prefix: Option<&Key>,
 ) -> PersistentPrefixIterator<'a> {
+    let (stripped_prefix, prefix) = iter_prefixes(stripped_prefix, prefix);
+    let read_opts = make_iter_read_opts(Some(prefix.clone()));
+    let iter = db.0.iterator_cf_opt(
+        cf,
+        read_opts,
+        IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+    );
+    PersistentPrefixIterator(PrefixIterator::new(
+        Either::Left(iter),
+        stripped_prefix,
+    ))
+}
+
+/// Create an iterator over the key-vals of an archived block matching the
+/// given prefix(es), like [`iter_prefix`] does for a CF.
+fn iter_archived_prefix<'a>(
+    entries: &BTreeMap<String, Vec<u8>>,
+    stripped_prefix: Option<&Key>,
+    prefix: Option<&Key>,
+) -> PersistentPrefixIterator<'a> {
+    let (stripped_prefix, prefix) = iter_prefixes(stripped_prefix, prefix);
+    let matched: Vec<_> = entries
+        .range(prefix.clone()..)
+        .take_while(|(key, _)| key.starts_with(&prefix))
+        .map(|(key, val)| {
+            Ok((
+                key.as_bytes().to_vec().into_boxed_slice(),
+                val.clone().into_boxed_slice(),
+            ))
+        })
+        .collect();
+    PersistentPrefixIterator(PrefixIterator::new(
+        Either::Right(matched.into_iter()),
+        stripped_prefix,
+    ))
+}
+
+/// Get the stripped prefix and the full prefix of the keys matched by an
+/// iterator
+fn iter_prefixes(
+    stripped_prefix: Option<&Key>,
+    prefix: Option<&Key>,
+) -> (String, String) {
     let stripped_prefix = match stripped_prefix {
         Some(p) if !p.is_empty() => format!("{p}/"),
         _ => "".to_owned(),
@@ -1669,19 +1955,18 @@ fn iter_prefix<'a>(
         }
         _ => stripped_prefix.clone(),
     };
-    let read_opts = make_iter_read_opts(Some(prefix.clone()));
-    let iter = db.0.iterator_cf_opt(
-        cf,
-        read_opts,
-        IteratorMode::From(prefix.as_bytes(), Direction::Forward),
-    );
-    PersistentPrefixIterator(PrefixIterator::new(iter, stripped_prefix))
+    (stripped_prefix, prefix)
 }
 
+/// The key-vals of a prefix iterator, either from the DB or from an archived
+/// block
+type PrefixIterKVs<'a> = Either<
+    rocksdb::DBIterator<'a>,
+    std::vec::IntoIter<std::result::Result<KVBytes, rocksdb::Error>>,
+>;
+
 #[derive(Debug)]
-pub struct PersistentPrefixIterator<'a>(
-    PrefixIterator<rocksdb::DBIterator<'a>>,
-);
+pub struct PersistentPrefixIterator<'a>(PrefixIterator<PrefixIterKVs<'a>>);
 
 impl<'a> Iterator for PersistentPrefixIterator<'a> {
     type Item = (String, Vec<u8>, u64);
@@ -1823,6 +2108,7 @@ mod test {
     use test_log::test;
 
     use super::*;
+    use crate::node::ledger::storage::archive::DirStore;
 
     /// Test that a block written can be loaded back from DB.
     #[test]
@@ -1858,6 +2144,29 @@ mod test {
             .expect("Block should have been written");
     }
 
+    /// Test that a secondary instance sees the writes of the primary instance
+    /// only once it has caught up with it.
+    #[test]
+    fn test_secondary_catch_up() {
+        let dir = tempdir().unwrap();
+        let secondary_dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+        let key = Key::parse("test").unwrap();
+        let height = BlockHeight::default();
+        db.write_subspace_val(height, &key, vec![1_u8], true)
+            .unwrap();
+
+        let secondary =
+            open_secondary(dir.path(), secondary_dir.path(), None).unwrap();
+        assert_eq!(secondary.read_subspace_val(&key).unwrap(), Some(vec![1]));
+
+        db.write_subspace_val(height, &key, vec![2_u8], true)
+            .unwrap();
+        assert_eq!(secondary.read_subspace_val(&key).unwrap(), Some(vec![1]));
+        secondary.catch_up_with_primary().unwrap();
+        assert_eq!(secondary.read_subspace_val(&key).unwrap(), Some(vec![2]));
+    }
+
     #[test]
     fn test_read() {
         let dir = tempdir().unwrap();
@@ -2271,6 +2580,79 @@ mod test {
         }
     }
 
+    /// Test that the data of the blocks offloaded to the archive is moved out
+    /// of the DB, and served back from the archive.
+    #[test]
+    fn test_archive_blocks() {
+        let dir = tempdir().unwrap();
+        let archive_dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+
+        let key = Key::parse("test").unwrap();
+        let last_height = BlockHeight(2);
+        for height in 0..=last_height.0 {
+            let mut batch = RocksDB::batch();
+            db.batch_write_subspace_val(
+                &mut batch,
+                BlockHeight(height),
+                &key,
+                vec![height as u8],
+                true,
+            )
+            .unwrap();
+            add_block_to_batch(
+                &db,
+                &mut batch,
+                BlockHeight(height),
+                Epoch::default(),
+                Epochs::default(),
+                &ConversionState::default(),
+            )
+            .unwrap();
+            db.exec_batch(batch.0).unwrap();
+        }
+
+        let store = DirStore::open(archive_dir.path().to_owned()).unwrap();
+        db.set_archive(Arc::new(Archive::new(Box::new(store), 1, 10)));
+        assert_eq!(db.archive_blocks(last_height, 10).unwrap(), 2);
+        // Nothing is left to offload
+        assert_eq!(db.archive_blocks(last_height, 10).unwrap(), 0);
+
+        // The data was moved out of the DB
+        let diffs_cf = db.get_column_family(DIFFS_CF).unwrap();
+        let (_, new_val_key) =
+            old_and_new_diff_key(&key, BlockHeight(1)).unwrap();
+        assert!(db.0.get_cf(diffs_cf, new_val_key).unwrap().is_none());
+
+        // And it's served from the archive
+        for height in 0..=last_height.0 {
+            assert_eq!(
+                db.read_subspace_val_with_height(
+                    &key,
+                    BlockHeight(height),
+                    last_height,
+                )
+                .unwrap(),
+                Some(vec![height as u8])
+            );
+        }
+        assert_eq!(
+            db.read_diffs_val(&key, BlockHeight(1), false).unwrap(),
+            Some(vec![1])
+        );
+        let new_diffs: Vec<_> = db
+            .iter_new_diffs(BlockHeight(1), None)
+            .map(|(key, val, _gas)| (key, val))
+            .collect();
+        assert_eq!(new_diffs, vec![("test".to_owned(), vec![1])]);
+        // The base tree stores come from the archive, and the subtrees stores
+        // of the epoch from the DB
+        assert!(db
+            .read_merkle_tree_stores(Epoch::default(), BlockHeight(1), None)
+            .unwrap()
+            .is_some());
+    }
+
     /// A test helper to write a block
     fn add_block_to_batch(
         db: &RocksDB,
@@ -17,8 +17,9 @@ use tokio::process::{Child, Command};
 use tokio::sync::oneshot::error::RecvError;
 use tokio::sync::oneshot::{Receiver, Sender};
 
+use super::signing_state::{self, Leader};
 use crate::cli::namada_version;
-use crate::config;
+use crate::config::{self, TendermintMode};
 use crate::facade::tendermint::node::Id as TendermintNodeId;
 use crate::facade::tendermint::{block, Genesis, Moniker};
 use crate::facade::tendermint_config::{
@@ -54,6 +55,8 @@ pub enum Error {
     CantCreate(String),
     #[error("Couldn't encode {0}")]
     CantEncode(&'static str),
+    #[error("Validator failover error: {0}")]
+    Failover(signing_state::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -82,16 +85,38 @@ pub async fn run(
     genesis_time: DateTimeUtc,
     proxy_app_address: String,
     config: config::Ledger,
-    abort_recv: Receiver<Sender<()>>,
+    mut abort_recv: Receiver<Sender<()>>,
 ) -> Result<()> {
+    let failover =
+        config.shell.validator_failover.clone().filter(|_| {
+            config.shell.tendermint_mode == TendermintMode::Validator
+        });
+    let local_state = validator_state(&home_dir);
     let (home_dir_string, tendermint_path) =
         initalize_config(home_dir, chain_id, genesis_time, config).await?;
+
+    // In a failover setup, only start signing once the leader lease is held
+    let leader = match failover {
+        Some(failover) => tokio::select! {
+            leader = Leader::acquire(&failover, local_state) => {
+                Some(leader.map_err(Error::Failover)?)
+            }
+            resp_sender = &mut abort_recv => {
+                if let Ok(resp_sender) = resp_sender {
+                    let _ = resp_sender.send(());
+                }
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
     let tendermint_node =
         start_node(proxy_app_address, home_dir_string, tendermint_path)?;
 
     tracing::info!("CometBFT node started");
 
-    handle_node_response(tendermint_node, abort_recv).await
+    handle_node_response(tendermint_node, abort_recv, leader).await
 }
 
 /// Setup the tendermint configuration. We return the tendermint path and home
@@ -118,7 +143,12 @@ async fn initalize_config(
 
     write_tm_genesis(&home_dir, chain_id, genesis_time).await?;
 
-    update_tendermint_config(&home_dir, config.cometbft).await?;
+    update_tendermint_config(
+        &home_dir,
+        config.cometbft,
+        config.shell.instant_blocks,
+    )
+    .await?;
     Ok((home_dir_string, tendermint_path))
 }
 
@@ -155,8 +185,15 @@ fn start_node(
 async fn handle_node_response(
     mut tendermint_node: Child,
     abort_recv: Receiver<Sender<()>>,
+    mut leader: Option<Leader>,
 ) -> Result<()> {
-    tokio::select! {
+    let hold_lease = async {
+        match leader.as_mut() {
+            Some(leader) => leader.hold().await,
+            None => std::future::pending().await,
+        }
+    };
+    let res = tokio::select! {
         status = tendermint_node.wait() => {
             match status {
                 Ok(status) => {
@@ -175,7 +212,16 @@ async fn handle_node_response(
             handle_abort(resp_sender, &mut tendermint_node).await;
             Ok(())
         }
+        err = hold_lease => {
+            tracing::error!("Stopping the CometBFT node to not double-sign");
+            tracing_kill(&mut tendermint_node).await;
+            Err(Error::Failover(err))
+        }
+    };
+    if let Some(leader) = leader {
+        leader.step_down();
     }
+    res
 }
 // Handle tendermint aborting
 async fn handle_abort(
@@ -372,6 +418,7 @@ pub fn id_from_pk(pk: &common::PublicKey) -> TendermintNodeId {
 async fn update_tendermint_config(
     home_dir: impl AsRef<Path>,
     mut config: TendermintConfig,
+    instant_blocks: bool,
 ) -> Result<()> {
     let path = configuration(home_dir);
 
@@ -379,7 +426,9 @@ async fn update_tendermint_config(
         Moniker::from_str(&format!("{}-{}", config.moniker, namada_version()))
             .expect("Invalid moniker");
 
-    config.consensus.create_empty_blocks = true;
+    // With instant blocks, a block is only produced once there are txs in
+    // the mempool
+    config.consensus.create_empty_blocks = !instant_blocks;
 
     // mempool config
     // https://forum.cosmos.network/t/our-understanding-of-the-cosmos-hub-mempool-issues/12040
@@ -0,0 +1,501 @@
+//! The wallet daemon, that serves the wallet operations over a local socket.
+//!
+//! The daemon owns the wallet and the shielded context of a chain, so that
+//! several frontends (GUIs, scripts) can share them instead of racing on their
+//! files. It listens on a unix socket for line-delimited JSON-RPC 2.0
+//! requests. A random token is written next to the socket, readable only by
+//! its owner, and every connection must first authenticate with it using the
+//! `auth` method. The methods are:
+//!
+//! - `auth {token}`: authenticate the connection
+//! - `list_keys {}`: list the public keys, addresses, viewing keys and payment
+//!   addresses of the wallet
+//! - `sign {signer, data, password?}`: sign the hex-encoded data with the
+//!   secret key of the given alias, public key hash or public key
+//! - `derive_address {alias}`: derive the implicit address of a public key, or
+//!   a new payment address of a viewing key
+//! - `shielded_sync_status {}`: report how far the shielded context is synced
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use color_eyre::eyre::{Result, WrapErr};
+use data_encoding::HEXLOWER_PERMISSIVE;
+use masp_primitives::zip32::ExtendedFullViewingKey;
+use namada::types::address::Address;
+use namada::types::key::*;
+use namada::types::masp::PaymentAddress;
+use namada::types::secret::ct_eq;
+use namada_sdk::masp::fs::FsShieldedUtils;
+use namada_sdk::masp::{find_valid_diversifier, ShieldedContext};
+use namada_sdk::wallet::Wallet;
+use rand_core::{OsRng, RngCore};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::Mutex;
+use zeroize::Zeroizing;
+
+use crate::cli::context::ChainContext;
+use crate::wallet::CliWalletUtils;
+
+/// The file name of the default socket, in the base directory of the chain
+pub const DEFAULT_SOCKET_FILE: &str = "wallet.sock";
+
+/// The JSON-RPC error code of a malformed request
+const PARSE_ERROR: i64 = -32700;
+/// The JSON-RPC error code of an unknown method
+const METHOD_NOT_FOUND: i64 = -32601;
+/// The JSON-RPC error code of invalid method parameters
+const INVALID_PARAMS: i64 = -32602;
+/// The error code of a request on an unauthenticated connection
+const UNAUTHORIZED: i64 = -32001;
+/// The error code of a failed wallet operation
+const WALLET_ERROR: i64 = -32000;
+
+/// The state shared by the connections to the daemon
+struct DaemonState {
+    wallet: Wallet<CliWalletUtils>,
+    shielded: ShieldedContext<FsShieldedUtils>,
+}
+
+/// An error of a request, sent back as a JSON-RPC error object
+#[derive(Debug, PartialEq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// A JSON-RPC request
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthParams {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignParams {
+    signer: String,
+    data: String,
+    password: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeriveAddressParams {
+    alias: String,
+}
+
+/// The path of the token file of the given socket
+pub fn token_path(socket_path: &Path) -> PathBuf {
+    socket_path.with_extension("token")
+}
+
+/// Serve the wallet and the shielded context of the given chain on the given
+/// socket, until the process is interrupted.
+pub async fn run(
+    chain: ChainContext,
+    socket_path: Option<PathBuf>,
+) -> Result<()> {
+    let ChainContext {
+        wallet,
+        config,
+        mut shielded,
+        ..
+    } = chain;
+    let socket_path = socket_path
+        .unwrap_or_else(|| config.ledger.chain_dir().join(DEFAULT_SOCKET_FILE));
+    if socket_path.exists() {
+        std::fs::remove_file(&socket_path).wrap_err_with(|| {
+            format!(
+                "Failed to remove the stale socket {}",
+                socket_path.display()
+            )
+        })?;
+    }
+    shielded
+        .load()
+        .await
+        .wrap_err("Failed to load the shielded context")?;
+
+    let token = write_token(&token_path(&socket_path))?;
+    let listener = UnixListener::bind(&socket_path).wrap_err_with(|| {
+        format!("Failed to bind the socket {}", socket_path.display())
+    })?;
+    tracing::info!("Wallet daemon listening on {}", socket_path.display());
+
+    let state = Arc::new(Mutex::new(DaemonState { wallet, shielded }));
+    let token = Arc::new(token);
+    let serve = async {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _addr)) => {
+                    let state = state.clone();
+                    let token = token.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) =
+                            handle_connection(stream, state, token).await
+                        {
+                            tracing::debug!("Wallet daemon connection: {err}");
+                        }
+                    });
+                }
+                Err(err) => {
+                    tracing::error!("Failed to accept a connection: {err}")
+                }
+            }
+        }
+    };
+    tokio::select! {
+        _ = serve => {}
+        result = tokio::signal::ctrl_c() => {
+            result.wrap_err("Failed to listen for the interrupt signal")?;
+        }
+    }
+
+    tracing::info!("Wallet daemon shutting down");
+    let _ = std::fs::remove_file(&socket_path);
+    let _ = std::fs::remove_file(token_path(&socket_path));
+    Ok(())
+}
+
+/// Generate a random token and write it to the given file, readable only by
+/// its owner
+fn write_token(path: &Path) -> Result<Zeroizing<String>> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut bytes = Zeroizing::new([0_u8; 32]);
+    OsRng.fill_bytes(bytes.as_mut());
+    let token = Zeroizing::new(HEXLOWER_PERMISSIVE.encode(bytes.as_ref()));
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .wrap_err_with(|| {
+            format!("Failed to create the token file {}", path.display())
+        })?;
+    file.write_all(token.as_bytes())?;
+    Ok(token)
+}
+
+/// Serve the requests of a connection, one per line
+async fn handle_connection(
+    stream: UnixStream,
+    state: Arc<Mutex<DaemonState>>,
+    token: Arc<Zeroizing<String>>,
+) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut authenticated = false;
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (id, result) = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                let result = if request.method == "auth" {
+                    authenticate(request.params, &token).map(|()| {
+                        authenticated = true;
+                        json!(true)
+                    })
+                } else if !authenticated {
+                    Err(RpcError::new(
+                        UNAUTHORIZED,
+                        "The connection must authenticate first",
+                    ))
+                } else {
+                    let mut state = state.lock().await;
+                    dispatch(&mut state, &request.method, request.params).await
+                };
+                (request.id, result)
+            }
+            Err(err) => (Value::Null, Err(RpcError::new(PARSE_ERROR, err))),
+        };
+        let mut response = response(id, result).to_string();
+        response.push('\n');
+        writer.write_all(response.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Check the token of an `auth` request
+fn authenticate(params: Value, token: &str) -> Result<(), RpcError> {
+    let AuthParams { token: given } = parse_params(params)?;
+    if ct_eq(given.as_bytes(), token.as_bytes()) {
+        Ok(())
+    } else {
+        Err(RpcError::new(UNAUTHORIZED, "Invalid token"))
+    }
+}
+
+/// The JSON-RPC response of a request
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(RpcError { code, message }) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {"code": code, "message": message},
+        }),
+    }
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(
+    params: Value,
+) -> Result<T, RpcError> {
+    serde_json::from_value(params)
+        .map_err(|err| RpcError::new(INVALID_PARAMS, err))
+}
+
+/// Serve a request of an authenticated connection
+async fn dispatch(
+    state: &mut DaemonState,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    match method {
+        "list_keys" => Ok(list_keys(&state.wallet)),
+        "sign" => sign(&mut state.wallet, parse_params(params)?),
+        "derive_address" => {
+            derive_address(&mut state.wallet, parse_params(params)?)
+        }
+        "shielded_sync_status" => shielded_sync_status(state).await,
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method {method}"),
+        )),
+    }
+}
+
+/// List the public keys and addresses of the wallet. The secret and spending
+/// keys are never served.
+fn list_keys(wallet: &Wallet<CliWalletUtils>) -> Value {
+    let to_strings = |map: BTreeMap<String, String>| json!(map);
+    json!({
+        "public_keys": to_strings(
+            wallet
+                .get_public_keys()
+                .into_iter()
+                .map(|(alias, pk)| (alias, pk.to_string()))
+                .collect()
+        ),
+        "addresses": to_strings(
+            wallet
+                .get_addresses()
+                .into_iter()
+                .map(|(alias, addr)| (alias, addr.encode()))
+                .collect()
+        ),
+        "viewing_keys": to_strings(
+            wallet
+                .get_viewing_keys()
+                .into_iter()
+                .map(|(alias, vk)| (alias, vk.to_string()))
+                .collect()
+        ),
+        "payment_addresses": to_strings(
+            wallet
+                .get_payment_addrs()
+                .into_iter()
+                .map(|(alias, pa)| (alias, pa.to_string()))
+                .collect()
+        ),
+    })
+}
+
+/// Sign the given data with a secret key of the wallet. An encrypted key
+/// requires its password, as the daemon cannot prompt for it.
+fn sign(
+    wallet: &mut Wallet<CliWalletUtils>,
+    SignParams {
+        signer,
+        data,
+        password,
+    }: SignParams,
+) -> Result<Value, RpcError> {
+    let data = HEXLOWER_PERMISSIVE
+        .decode(data.as_bytes())
+        .map_err(|err| RpcError::new(INVALID_PARAMS, err))?;
+    let password = password.map(Zeroizing::new);
+    if password.is_none()
+        && wallet.is_encrypted_secret_key(&signer) == Some(true)
+    {
+        return Err(RpcError::new(
+            INVALID_PARAMS,
+            format!("The key {signer} is encrypted, a password is required"),
+        ));
+    }
+    let secret_key = wallet
+        .find_secret_key(&signer, password)
+        .map_err(|err| RpcError::new(WALLET_ERROR, err))?;
+    let signature = common::SigScheme::sign(&secret_key, data);
+    Ok(json!({
+        "public_key": secret_key.ref_to().to_string(),
+        "signature": signature.to_string(),
+    }))
+}
+
+/// Derive the implicit address of a public key, or a new payment address of
+/// a viewing key. The derived payment address isn't stored in the wallet.
+fn derive_address(
+    wallet: &mut Wallet<CliWalletUtils>,
+    DeriveAddressParams { alias }: DeriveAddressParams,
+) -> Result<Value, RpcError> {
+    if let Ok(pk) = wallet.find_public_key(&alias) {
+        let address = Address::from(&pk);
+        return Ok(json!({"address": address.encode()}));
+    }
+    let viewing_key = wallet
+        .find_viewing_key(&alias)
+        .map_err(|_| {
+            RpcError::new(
+                WALLET_ERROR,
+                format!("No public key nor viewing key with alias {alias}"),
+            )
+        })?
+        .to_owned();
+    let viewing_key = ExtendedFullViewingKey::from(viewing_key).fvk.vk;
+    let (div, _g_d) = find_valid_diversifier(&mut OsRng);
+    let payment_addr = viewing_key
+        .to_payment_address(div)
+        .ok_or_else(|| RpcError::new(WALLET_ERROR, "Invalid diversifier"))?;
+    Ok(json!({
+        "payment_address": PaymentAddress::from(payment_addr).to_string(),
+    }))
+}
+
+/// Report how far the shielded context is synced, reloading it as it may
+/// have been synced by a client since the previous request
+async fn shielded_sync_status(
+    state: &mut DaemonState,
+) -> Result<Value, RpcError> {
+    state
+        .shielded
+        .load()
+        .await
+        .map_err(|err| RpcError::new(WALLET_ERROR, err))?;
+    let last_indexed = state.shielded.last_indexed.map(|indexed| {
+        json!({
+            "height": indexed.height.0,
+            "index": indexed.index.0,
+        })
+    });
+    let unsynced_viewing_keys: Vec<String> = state
+        .wallet
+        .get_viewing_keys()
+        .into_iter()
+        .filter(|(_alias, vk)| {
+            let vk = ExtendedFullViewingKey::from(*vk).fvk.vk;
+            !state.shielded.pos_map.contains_key(&vk)
+        })
+        .map(|(alias, _vk)| alias)
+        .collect();
+    Ok(json!({
+        "last_indexed": last_indexed,
+        "notes": state.shielded.note_map.len(),
+        "spent_notes": state.shielded.spents.len(),
+        "unsynced_viewing_keys": unsynced_viewing_keys,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the connections authenticate with the token and get
+    /// JSON-RPC error objects.
+    #[test]
+    fn test_authenticate_and_respond() {
+        let token = "00ff";
+        assert!(authenticate(json!({"token": "00ff"}), token).is_ok());
+        assert_eq!(
+            authenticate(json!({"token": "00fe"}), token)
+                .unwrap_err()
+                .code,
+            UNAUTHORIZED
+        );
+        assert_eq!(
+            authenticate(json!({}), token).unwrap_err().code,
+            INVALID_PARAMS
+        );
+
+        let ok = response(json!(1), Ok(json!(true)));
+        assert_eq!(ok, json!({"jsonrpc": "2.0", "id": 1, "result": true}));
+        let err = response(
+            json!("a"),
+            Err(RpcError::new(METHOD_NOT_FOUND, "Unknown method x")),
+        );
+        assert_eq!(err["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(err["id"], "a");
+    }
+
+    /// Test that the payment addresses and implicit addresses are derived,
+    /// and that the data is signed with the key of the signer.
+    #[test]
+    fn test_derive_address_and_sign() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut wallet = CliWalletUtils::new(dir.path().to_path_buf());
+        let (alias, sk) = wallet
+            .gen_store_secret_key(
+                SchemeType::Ed25519,
+                Some("alice".to_string()),
+                true,
+                None,
+                &mut OsRng,
+            )
+            .unwrap();
+        let pk = sk.ref_to();
+
+        let derived = derive_address(
+            &mut wallet,
+            DeriveAddressParams {
+                alias: alias.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(derived["address"], Address::from(&pk).encode());
+        assert!(derive_address(
+            &mut wallet,
+            DeriveAddressParams {
+                alias: "bob".to_string(),
+            },
+        )
+        .is_err());
+
+        let signed = sign(
+            &mut wallet,
+            SignParams {
+                signer: alias,
+                data: "0102".to_string(),
+                password: None,
+            },
+        )
+        .unwrap();
+        let signature: common::Signature =
+            signed["signature"].as_str().unwrap().parse().unwrap();
+        common::SigScheme::verify_signature(&pk, &vec![1_u8, 2], &signature)
+            .unwrap();
+        assert!(list_keys(&wallet)["public_keys"]["alice"].is_string());
+    }
+}
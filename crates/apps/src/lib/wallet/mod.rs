@@ -1,3 +1,4 @@
+pub mod daemon;
 pub mod defaults;
 pub mod pre_genesis;
 mod store;
@@ -8,6 +9,7 @@ use std::{env, fs};
 
 use namada::bip39::{Language, Mnemonic};
 use namada::types::key::*;
+use namada::types::secret::ct_eq;
 pub use namada_sdk::wallet::alias::Alias;
 use namada_sdk::wallet::fs::FsWalletStorage;
 use namada_sdk::wallet::store::Store;
@@ -177,7 +179,7 @@ pub fn read_and_confirm_passphrase_tty(
             "Enter same passphrase again: ",
         ))
         .map(Zeroizing::new)?;
-        if confirmed != passphrase {
+        if !ct_eq(confirmed.as_bytes(), passphrase.as_bytes()) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 "Passphrases did not match",
@@ -0,0 +1,99 @@
+//! Benchmarks of the trial decryption of the shielded outputs scanned by a
+//! sync of the shielded context, over a chain of about a million notes, on a
+//! single thread and on all the available threads
+
+use std::collections::BTreeSet;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use masp_primitives::sapling::ViewingKey;
+use namada::core::types::storage::TxIndex;
+use namada::sdk::masp::{to_viewing_key, trial_decrypt_txs, FetchedShieldedTx};
+use namada::token::compact::CompactTx;
+use namada::token::Amount;
+use namada::tx::Section;
+use namada::types::masp::{TransferSource, TransferTarget};
+use namada_apps::bench_utils::{
+    BenchShieldedCtx, ALBERT_PAYMENT_ADDRESS, ALBERT_SPENDING_KEY,
+};
+use namada_apps::wallet::defaults;
+
+/// The number of notes of the chain
+const NOTES: usize = 1 << 20;
+/// The number of shielded outputs of each tx
+const OUTPUTS_PER_TX: usize = 4;
+
+/// Build the compact txs of a chain of [`NOTES`] notes, all copies of the
+/// output of a shielding transfer, and the viewing key it's sent to
+fn compact_chain() -> (Vec<FetchedShieldedTx>, Vec<ViewingKey>) {
+    let mut shielded_ctx = BenchShieldedCtx::default();
+    let albert_payment_addr = shielded_ctx
+        .wallet
+        .find_payment_addr(ALBERT_PAYMENT_ADDRESS)
+        .unwrap()
+        .to_owned();
+    let albert_spending_key = shielded_ctx
+        .wallet
+        .find_spending_key(ALBERT_SPENDING_KEY, None)
+        .unwrap()
+        .to_owned();
+    let (_, shield_tx) = shielded_ctx.generate_masp_tx(
+        Amount::native_whole(500),
+        TransferSource::Address(defaults::albert_address()),
+        TransferTarget::PaymentAddress(albert_payment_addr),
+    );
+    let masp_tx = shield_tx
+        .sections
+        .iter()
+        .find_map(|section| match section {
+            Section::MaspTx(masp_tx) => Some(masp_tx),
+            _ => None,
+        })
+        .unwrap();
+    let output = CompactTx::new(TxIndex(0), &BTreeSet::new(), masp_tx)
+        .outputs
+        .pop()
+        .unwrap();
+
+    let txs = (0..NOTES / OUTPUTS_PER_TX)
+        .map(|_| {
+            FetchedShieldedTx::Compact(CompactTx {
+                index: TxIndex(0),
+                changed_balance_keys: BTreeSet::new(),
+                outputs: vec![output.clone(); OUTPUTS_PER_TX],
+                nullifiers: vec![],
+                vin: vec![],
+                vout: vec![],
+            })
+        })
+        .collect();
+    let vk = to_viewing_key(&albert_spending_key.into()).vk;
+    (txs, vec![vk])
+}
+
+fn masp_trial_decryption(c: &mut Criterion) {
+    let (txs, vks) = compact_chain();
+    let txs: Vec<_> = txs.iter().collect();
+    let mut group = c.benchmark_group("masp_trial_decryption");
+    group.sample_size(10);
+
+    for threads in [1, rayon::current_num_threads()] {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        group.bench_function(format!("{threads}_threads"), |b| {
+            b.iter(|| {
+                pool.install(|| {
+                    for decryptions in trial_decrypt_txs(&txs, &vks) {
+                        assert_eq!(decryptions.unwrap().len(), OUTPUTS_PER_TX);
+                    }
+                })
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(masp_sync, masp_trial_decryption);
+criterion_main!(masp_sync);
@@ -0,0 +1,190 @@
+//! Benchmarks of the merkle tree updates of a block over a large state and
+//! comparisons of the backends of the subtrees
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use namada::core::types::address::{Address, InternalAddress};
+use namada::core::types::hash::Sha256Hasher;
+use namada::core::types::storage::{Key, KeySeg};
+use namada::ledger::storage::merkle_tree::{MerkleTreeStoresRead, StoreType};
+use namada::ledger::storage::MerkleTree;
+use namada_merkle_tree::jellyfish::Jmt;
+use namada_merkle_tree::{
+    MembershipProof, Smt, StorageBytes, SubTreeRead, SubTreeWrite,
+};
+use prost::Message;
+
+/// The number of keys of the state in the account subtree
+const ACCOUNT_KEYS: u64 = 200_000;
+/// The number of keys of the state in the PoS subtree
+const POS_KEYS: u64 = 50_000;
+/// The number of keys written by a block
+const BLOCK_KEYS: u64 = 1_000;
+
+fn account_key(index: u64) -> Key {
+    Key::parse(format!("account/{index}")).unwrap()
+}
+
+fn pos_key(index: u64) -> Key {
+    Key::from(Address::Internal(InternalAddress::PoS).to_db_key())
+        .push(&index.to_string())
+        .unwrap()
+}
+
+/// Build a tree with a large state
+fn large_tree() -> MerkleTree<Sha256Hasher> {
+    let mut tree = MerkleTree::<Sha256Hasher>::default();
+    for index in 0..ACCOUNT_KEYS {
+        tree.update(&account_key(index), index.to_le_bytes())
+            .unwrap();
+    }
+    for index in 0..POS_KEYS {
+        tree.update(&pos_key(index), index.to_le_bytes()).unwrap();
+    }
+    tree.update_base().unwrap();
+    tree
+}
+
+/// Restore a copy of a tree from its stores
+fn restore_tree(tree: &MerkleTree<Sha256Hasher>) -> MerkleTree<Sha256Hasher> {
+    let stores_write = tree.stores();
+    let mut stores_read = MerkleTreeStoresRead::default();
+    for st in StoreType::iter() {
+        stores_read.set_root(st, *stores_write.root(st));
+        stores_read.set_store(stores_write.store(st).to_owned());
+    }
+    MerkleTree::new(stores_read).unwrap()
+}
+
+/// The keys written by a block: updates of existing keys of both subtrees
+/// and new keys, half of them written twice
+fn block_keys() -> Vec<Key> {
+    let step = ACCOUNT_KEYS / BLOCK_KEYS;
+    (0..BLOCK_KEYS)
+        .flat_map(|index| {
+            let key = match index % 4 {
+                0 => pos_key(index * (POS_KEYS / BLOCK_KEYS)),
+                1 => account_key(ACCOUNT_KEYS + index),
+                _ => account_key(index * step),
+            };
+            let repeats = if index % 2 == 0 { 2 } else { 1 };
+            std::iter::repeat(key).take(repeats)
+        })
+        .collect()
+}
+
+// Benchmarks the update of the merkle tree with the writes of a block and the
+// computation of its root, either with the base tree updated once per block
+// with the roots of the dirty subtrees, or once per written key
+fn merkle_tree_block_update(c: &mut Criterion) {
+    let tree = large_tree();
+    let keys = block_keys();
+    let mut group = c.benchmark_group("merkle_tree_block_update");
+
+    for (bench_name, update_base_per_key) in
+        [("dirty_subtrees", false), ("base_per_key", true)]
+    {
+        group.bench_function(bench_name, |b| {
+            b.iter_batched_ref(
+                || restore_tree(&tree),
+                |tree| {
+                    for key in &keys {
+                        tree.update(key, [1_u8; 32]).unwrap();
+                        if update_base_per_key {
+                            tree.update_base().unwrap();
+                        }
+                    }
+                    tree.update_base().unwrap();
+                    tree.root()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+/// The number of bytes of the encoded ICS-23 proof of a key of a subtree
+fn proof_bytes(
+    tree: impl SubTreeRead,
+    key: &Key,
+    value: StorageBytes,
+) -> usize {
+    match tree
+        .subtree_membership_proof(std::array::from_ref(key), vec![value])
+        .unwrap()
+    {
+        MembershipProof::ICS23(proof) => proof.encoded_len(),
+        MembershipProof::BridgePool(_) => unreachable!(),
+    }
+}
+
+// Benchmarks the writes of a block to a subtree with a large state, either
+// with the sparse merkle tree or with the Jellyfish Merkle tree. The JMT
+// writes the block at once, as a single version. The sizes of the proofs and
+// the number of nodes written by the JMT are printed for comparison.
+fn merkle_subtree_backends(c: &mut Criterion) {
+    let value = [1_u8; 32];
+    let keys: Vec<Key> = (0..ACCOUNT_KEYS).map(account_key).collect();
+    let mut smt = Smt::<Sha256Hasher>::default();
+    for key in &keys {
+        (&mut smt).subtree_update(key, &value).unwrap();
+    }
+    let mut jmt = Jmt::default();
+    jmt.update_batch(keys.iter().map(|key| (key, Some(value.as_slice()))))
+        .unwrap();
+    let step = ACCOUNT_KEYS / BLOCK_KEYS;
+    let block_keys: Vec<Key> = (0..BLOCK_KEYS)
+        .map(|index| account_key(index * step))
+        .collect();
+
+    println!(
+        "Proof bytes: SMT {}, JMT {}",
+        proof_bytes(&smt, &block_keys[0], &value),
+        proof_bytes(&jmt, &block_keys[0], &value),
+    );
+    let mut block_jmt = jmt.clone();
+    let written_nodes = block_jmt.written_nodes();
+    block_jmt
+        .update_batch(
+            block_keys.iter().map(|key| (key, Some([2_u8].as_slice()))),
+        )
+        .unwrap();
+    println!(
+        "JMT nodes written by a block of {BLOCK_KEYS} keys: {}",
+        block_jmt.written_nodes() - written_nodes
+    );
+
+    let mut group = c.benchmark_group("merkle_subtree_backends");
+    group.bench_function("smt", |b| {
+        b.iter_batched_ref(
+            || Smt::<Sha256Hasher>::new(*smt.root(), smt.store().clone()),
+            |smt| {
+                for key in &block_keys {
+                    (&mut *smt).subtree_update(key, &[2_u8]).unwrap();
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("jmt", |b| {
+        b.iter_batched_ref(
+            || jmt.clone(),
+            |jmt| {
+                jmt.update_batch(
+                    block_keys.iter().map(|key| (key, Some([2_u8].as_slice()))),
+                )
+                .unwrap()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(
+    merkle_tree,
+    merkle_tree_block_update,
+    merkle_subtree_backends
+);
+criterion_main!(merkle_tree);
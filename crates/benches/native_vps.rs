@@ -3,6 +3,7 @@ use std::collections::{BTreeSet, HashMap};
 use std::rc::Rc;
 use std::str::FromStr;
 
+use borsh_ext::BorshSerializeExt;
 use criterion::{criterion_group, criterion_main, Criterion};
 use masp_primitives::sapling::Node;
 use namada::core::types::address::{self, Address};
@@ -42,6 +43,7 @@ use namada::sdk::masp::verify_shielded_tx;
 use namada::sdk::masp_primitives::merkle_tree::CommitmentTree;
 use namada::sdk::masp_primitives::transaction::Transaction;
 use namada::state::{Epoch, StorageRead, StorageWrite, TxIndex};
+use namada::token::storage_key::balance_key;
 use namada::token::{Amount, Transfer};
 use namada::tx::{Code, Section, Tx};
 use namada::types::address::InternalAddress;
@@ -426,12 +428,50 @@ fn vp_multitoken(c: &mut Criterion) {
         vec![&defaults::albert_keypair()],
     );
 
-    for (signed_tx, bench_name) in [foreign_key_write, transfer]
-        .iter()
+    let mut shells = vec![];
+    for (signed_tx, bench_name) in [&foreign_key_write, &transfer]
+        .into_iter()
         .zip(["foreign_key_write", "transfer"])
     {
         let mut shell = BenchShell::default();
         shell.execute_tx(signed_tx);
+        shells.push((shell, signed_tx, bench_name));
+    }
+
+    // The balance changes of a batch transfer from a single source to 1000
+    // targets
+    let mut shell = BenchShell::default();
+    let outputs = 1000;
+    let source_key = balance_key(&address::nam(), &defaults::albert_address());
+    let source_balance: Amount = shell
+        .wl_storage
+        .read(&source_key)
+        .unwrap()
+        .unwrap_or_default();
+    let source_balance = source_balance
+        .checked_sub(Amount::native_whole(outputs))
+        .unwrap();
+    shell
+        .wl_storage
+        .write_log
+        .write(&source_key, source_balance.serialize_to_vec())
+        .unwrap();
+    for output in 0..outputs {
+        let target = address::gen_deterministic_established_address(format!(
+            "batch-transfer-target-{output}"
+        ));
+        shell
+            .wl_storage
+            .write_log
+            .write(
+                &balance_key(&address::nam(), &target),
+                Amount::native_whole(1).serialize_to_vec(),
+            )
+            .unwrap();
+    }
+    shells.push((shell, &transfer, "batch_transfer_1000"));
+
+    for (shell, signed_tx, bench_name) in &shells {
         let (verifiers, keys_changed) = shell
             .wl_storage
             .write_log
@@ -453,7 +493,7 @@ fn vp_multitoken(c: &mut Criterion) {
             ),
         };
 
-        group.bench_function(bench_name, |b| {
+        group.bench_function(*bench_name, |b| {
             b.iter(|| {
                 assert!(
                     multitoken
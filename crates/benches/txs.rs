@@ -140,6 +140,7 @@ fn bond(c: &mut Criterion) {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: Some(defaults::albert_address()),
+            start_epoch: None,
         },
         None,
         None,
@@ -152,6 +153,7 @@ fn bond(c: &mut Criterion) {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: None,
+            start_epoch: None,
         },
         None,
         None,
@@ -183,6 +185,7 @@ fn unbond(c: &mut Criterion) {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: Some(defaults::albert_address()),
+            start_epoch: None,
         },
         None,
         None,
@@ -195,6 +198,7 @@ fn unbond(c: &mut Criterion) {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: None,
+            start_epoch: None,
         },
         None,
         None,
@@ -259,6 +263,7 @@ fn withdraw(c: &mut Criterion) {
                                 validator: defaults::validator_address(),
                                 amount: Amount::native_whole(1000),
                                 source: Some(defaults::albert_address()),
+                                start_epoch: None,
                             },
                             None,
                             None,
@@ -270,6 +275,7 @@ fn withdraw(c: &mut Criterion) {
                                 validator: defaults::validator_address(),
                                 amount: Amount::native_whole(1000),
                                 source: None,
+                                start_epoch: None,
                             },
                             None,
                             None,
@@ -720,6 +726,7 @@ fn change_validator_metadata(c: &mut Criterion) {
         website: None,
         discord_handle: None,
         avatar: None,
+        handle: None,
         commission_rate: None,
     };
 
@@ -112,6 +112,7 @@ fn vp_user(c: &mut Criterion) {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: Some(defaults::albert_address()),
+            start_epoch: None,
         },
         None,
         None,
@@ -161,7 +162,8 @@ fn vp_user(c: &mut Criterion) {
                         &verifiers,
                         shell.vp_wasm_cache.clone(),
                     )
-                    .unwrap(),
+                    .unwrap()
+                    .0,
                     "VP \"{bench_name}\" bench call failed"
                 );
             })
@@ -227,6 +229,7 @@ fn vp_implicit(c: &mut Criterion) {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: Some(Address::from(&implicit_account.to_public())),
+            start_epoch: None,
         },
         None,
         None,
@@ -308,6 +311,7 @@ fn vp_implicit(c: &mut Criterion) {
                         shell.vp_wasm_cache.clone(),
                     )
                     .unwrap()
+                    .0
                 )
             })
         });
@@ -409,6 +413,7 @@ fn vp_validator(c: &mut Criterion) {
             validator: defaults::validator_address(),
             amount: Amount::native_whole(1000),
             source: None,
+            start_epoch: None,
         },
         None,
         None,
@@ -460,6 +465,7 @@ fn vp_validator(c: &mut Criterion) {
                         shell.vp_wasm_cache.clone(),
                     )
                     .unwrap()
+                    .0
                 );
             })
         });
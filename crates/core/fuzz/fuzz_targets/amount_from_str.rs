@@ -0,0 +1,19 @@
+//! Fuzz the parsing of the denominated amounts: it must never panic, and the
+//! precise string of a parsed amount must parse back to it.
+
+#![no_main]
+
+use std::str::FromStr;
+
+use libfuzzer_sys::fuzz_target;
+use namada_core::types::token::DenominatedAmount;
+
+fuzz_target!(|string: &str| {
+    if let Ok(amount) = DenominatedAmount::from_str(string) {
+        let precise = amount.to_string_precise();
+        assert_eq!(DenominatedAmount::from_str(&precise).ok(), Some(amount));
+        // The other formats lose the denomination, but not the value
+        let display = DenominatedAmount::from_str(&amount.to_string()).unwrap();
+        assert_eq!(display.cmp(&amount), std::cmp::Ordering::Equal);
+    }
+});
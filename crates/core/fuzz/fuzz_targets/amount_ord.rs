@@ -0,0 +1,39 @@
+//! Fuzz the ordering of the denominated amounts against the one of their
+//! exact rational values.
+
+#![no_main]
+
+use std::str::FromStr;
+
+use libfuzzer_sys::fuzz_target;
+use namada_core::types::token::{Amount, DenominatedAmount, Denomination};
+use namada_core::types::uint::Uint;
+use num_rational::BigRational;
+
+/// Read a denominated amount from 33 bytes: the raw amount in little endian,
+/// followed by the denomination
+fn read_amount(bytes: &[u8]) -> DenominatedAmount {
+    let raw = Uint::from_little_endian(&bytes[..32]);
+    let amount = Amount::from_uint(raw, 0).unwrap();
+    DenominatedAmount::new(amount, Denomination(bytes[32]))
+}
+
+/// The exact rational value of a denominated amount
+fn exact_value(amount: &DenominatedAmount) -> BigRational {
+    let ratio = format!(
+        "{}/1{}",
+        amount.amount().raw_amount(),
+        "0".repeat(amount.denom().0.into())
+    );
+    BigRational::from_str(&ratio).unwrap()
+}
+
+fuzz_target!(|bytes: [u8; 66]| {
+    let lhs = read_amount(&bytes[..33]);
+    let rhs = read_amount(&bytes[33..]);
+    let expected = exact_value(&lhs).cmp(&exact_value(&rhs));
+    assert_eq!(lhs.cmp(&rhs), expected);
+    assert_eq!(rhs.cmp(&lhs), expected.reverse());
+    let precise = lhs.to_string_precise();
+    assert_eq!(DenominatedAmount::from_str(&precise).ok(), Some(lhs));
+});
@@ -2,9 +2,77 @@
 //! proof-of-stake, providing liquity to shielded asset pools, and public goods
 //! funding.
 
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
 use crate::types::dec::Dec;
+use crate::types::token::{Amount, AmountParseError};
 use crate::types::uint::Uint;
 
+/// The yearly rate of the rewards earned on some locked tokens
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct RewardRate {
+    /// Annual percentage rate, without compounding
+    pub apr: Dec,
+    /// Annual percentage yield, with the rewards compounded every epoch
+    pub apy: Dec,
+}
+
+impl RewardRate {
+    /// Get the reward rate from the given annual percentage rate, assuming
+    /// that the rewards are compounded every epoch. Returns `None` if the
+    /// yield overflows.
+    pub fn from_apr(apr: Dec, epochs_per_year: u64) -> Option<Self> {
+        if epochs_per_year == 0 || apr.is_negative() {
+            return Some(Self { apr, apy: apr });
+        }
+        // (1 + apr / epochs_per_year) ^ epochs_per_year, by squaring
+        let mut base = Dec::one().checked_add(&(apr / epochs_per_year))?;
+        let mut exp = epochs_per_year;
+        let mut compounded = Dec::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                compounded = compounded.checked_mul(&base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(&base)?;
+            }
+        }
+        Some(Self {
+            apr,
+            apy: compounded.checked_signed_sub(&Dec::one())?,
+        })
+    }
+}
+
+/// Adjust the last inflation amount by the output of the PD controller,
+/// `p_gain * error - d_gain * delta_error`. Returns `None` if any of the
+/// terms overflowed.
+fn pd_control(
+    last_inflation_amount: Dec,
+    p_gain: Option<Dec>,
+    d_gain: Option<Dec>,
+    error: Option<Dec>,
+    delta_error: Option<Dec>,
+) -> Option<Dec> {
+    let p_val = p_gain?.checked_mul(&error?)?;
+    let d_val = d_gain?.checked_mul(&delta_error?)?;
+    last_inflation_amount.checked_add(&p_val.checked_signed_sub(&d_val)?)
+}
+
 /// Holds the PD controller values that should be updated in storage
 #[allow(missing_docs)]
 pub struct PosValsToUpdate {
@@ -44,8 +112,9 @@ pub struct PosRewardsController {
 impl PosRewardsController {
     /// Calculate a new inflation rate for the Proof-of-stake rewards system.
     /// Uses the ratios of locked (staked) tokens to the total native token
-    /// supply to determine the new inflation amount.
-    pub fn run(self) -> PosValsToUpdate {
+    /// supply to determine the new inflation amount. Fails if the parameters
+    /// give a negative max inflation or if the computation overflows.
+    pub fn run(self) -> Result<PosValsToUpdate, AmountParseError> {
         let Self {
             locked_tokens,
             total_native_tokens,
@@ -73,21 +142,32 @@ impl PosRewardsController {
         let locked_ratio = if total_native.is_zero() {
             Dec::one()
         } else {
-            locked / total_native
+            locked
+                .trunc_div(&total_native)
+                .ok_or(AmountParseError::Overflow)?
         };
 
         // Max inflation amount for this epoch
-        let max_inflation = total_native * max_reward_rate / epochs_py;
+        let max_inflation = total_native
+            .checked_mul(&max_reward_rate)
+            .and_then(|max| max.trunc_div(&epochs_py))
+            .ok_or(AmountParseError::Overflow)?;
 
         // Intermediate values
-        let p_gain = p_gain_nom * max_inflation;
-        let d_gain = d_gain_nom * max_inflation;
-        let error = locked_ratio_target - locked_ratio;
-        let delta_error = locked_ratio_last - locked_ratio;
-        let control_val = p_gain * error - d_gain * delta_error;
+        let p_gain = p_gain_nom.checked_mul(&max_inflation);
+        let d_gain = d_gain_nom.checked_mul(&max_inflation);
+        let error = locked_ratio_target.checked_signed_sub(&locked_ratio);
+        let delta_error = locked_ratio_last.checked_signed_sub(&locked_ratio);
 
         // New inflation amount
-        let new_inflation_amount_raw = last_inflation_amount + control_val;
+        let new_inflation_amount_raw = pd_control(
+            last_inflation_amount,
+            p_gain,
+            d_gain,
+            error,
+            delta_error,
+        )
+        .ok_or(AmountParseError::Overflow)?;
         let new_inflation_amount = if new_inflation_amount_raw.is_negative() {
             Uint::zero()
         } else {
@@ -95,15 +175,13 @@ impl PosRewardsController {
                 .to_uint()
                 .expect("Should not fail to convert Dec to Uint")
         };
-        let max_inflation = max_inflation
-            .to_uint()
-            .expect("Should not fail to convert Dec to Uint");
+        let max_inflation = Amount::try_from(max_inflation)?.raw_amount();
 
         let inflation = std::cmp::min(new_inflation_amount, max_inflation);
-        PosValsToUpdate {
+        Ok(PosValsToUpdate {
             locked_ratio,
             inflation,
-        }
+        })
     }
 }
 
@@ -133,8 +211,9 @@ pub struct ShieldedRewardsController {
 impl ShieldedRewardsController {
     /// Calculate a new inflation rate for the Proof-of-stake rewards system.
     /// Uses the ratios of locked (staked) tokens to the total native token
-    /// supply to determine the new inflation amount.
-    pub fn run(self) -> ShieldedValsToUpdate {
+    /// supply to determine the new inflation amount. Fails if the parameters
+    /// give a negative max inflation or if the computation overflows.
+    pub fn run(self) -> Result<ShieldedValsToUpdate, AmountParseError> {
         let Self {
             locked_tokens,
             total_native_tokens,
@@ -163,17 +242,30 @@ impl ShieldedRewardsController {
         let epochs_py: Dec = epochs_per_year.into();
 
         // Max inflation amount for this epoch
-        let max_inflation = total_native * max_reward_rate / epochs_py;
+        let max_inflation = total_native
+            .checked_mul(&max_reward_rate)
+            .and_then(|max| max.trunc_div(&epochs_py))
+            .ok_or(AmountParseError::Overflow)?;
 
         // Intermediate values
-        let p_gain = p_gain_nom * max_reward_rate / epochs_py;
-        let d_gain = d_gain_nom * max_reward_rate / epochs_py;
-        let error = locked_amount_target - locked;
-        let delta_error = locked_amount_last - locked;
-        let control_val = p_gain * error - d_gain * delta_error;
+        let p_gain = p_gain_nom
+            .checked_mul(&max_reward_rate)
+            .and_then(|gain| gain.trunc_div(&epochs_py));
+        let d_gain = d_gain_nom
+            .checked_mul(&max_reward_rate)
+            .and_then(|gain| gain.trunc_div(&epochs_py));
+        let error = locked_amount_target.checked_signed_sub(&locked);
+        let delta_error = locked_amount_last.checked_signed_sub(&locked);
 
         // New inflation amount
-        let new_inflation_amount_raw = last_inflation_amount + control_val;
+        let new_inflation_amount_raw = pd_control(
+            last_inflation_amount,
+            p_gain,
+            d_gain,
+            error,
+            delta_error,
+        )
+        .ok_or(AmountParseError::Overflow)?;
         let new_inflation_amount = if new_inflation_amount_raw.is_negative() {
             Uint::zero()
         } else {
@@ -181,12 +273,10 @@ impl ShieldedRewardsController {
                 .to_uint()
                 .expect("Should not fail to convert Dec to Uint")
         };
-        let max_inflation = max_inflation
-            .to_uint()
-            .expect("Should not fail to convert Dec to Uint");
+        let max_inflation = Amount::try_from(max_inflation)?.raw_amount();
 
         let inflation = std::cmp::min(new_inflation_amount, max_inflation);
-        ShieldedValsToUpdate { inflation }
+        Ok(ShieldedValsToUpdate { inflation })
     }
 }
 
@@ -196,6 +286,26 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn test_reward_rate_from_apr() {
+        let zero = RewardRate::from_apr(Dec::zero(), 365).unwrap();
+        assert_eq!(zero.apy, Dec::zero());
+
+        // Without compounding, the yield is the same as the rate
+        let apr = Dec::from_str("0.1").unwrap();
+        let once = RewardRate::from_apr(apr, 1).unwrap();
+        assert_eq!(once.apy, apr);
+
+        // Compounding twice a year: 1.05^2 - 1
+        let twice = RewardRate::from_apr(apr, 2).unwrap();
+        assert_eq!(twice.apy, Dec::from_str("0.1025").unwrap());
+
+        // Compounding every day gets close to e^0.1 - 1
+        let daily = RewardRate::from_apr(apr, 365).unwrap();
+        assert!(daily.apy > Dec::from_str("0.10515").unwrap());
+        assert!(daily.apy < Dec::from_str("0.10518").unwrap());
+    }
+
     #[test]
     fn test_inflation_calc_up() {
         let mut controller = PosRewardsController {
@@ -214,7 +324,7 @@ mod test {
         let PosValsToUpdate {
             locked_ratio: locked_ratio_0,
             inflation: inflation_0,
-        } = controller.clone().run();
+        } = controller.clone().run().unwrap();
         println!(
             "Round 0: Locked ratio: {locked_ratio_0}, inflation: {inflation_0}"
         );
@@ -228,7 +338,7 @@ mod test {
         let PosValsToUpdate {
             locked_ratio: locked_ratio_1,
             inflation: inflation_1,
-        } = controller.clone().run();
+        } = controller.clone().run().unwrap();
         println!(
             "Round 1: Locked ratio: {locked_ratio_1}, inflation: {inflation_1}"
         );
@@ -244,7 +354,7 @@ mod test {
         let PosValsToUpdate {
             locked_ratio: locked_ratio_2,
             inflation: inflation_2,
-        } = controller.run();
+        } = controller.run().unwrap();
         println!(
             "Round 2: Locked ratio: {locked_ratio_2}, inflation: {inflation_2}",
         );
@@ -272,7 +382,7 @@ mod test {
         let PosValsToUpdate {
             locked_ratio: locked_ratio_0,
             inflation: inflation_0,
-        } = controller.clone().run();
+        } = controller.clone().run().unwrap();
         println!(
             "Round 0: Locked ratio: {locked_ratio_0}, inflation: {inflation_0}",
         );
@@ -286,7 +396,7 @@ mod test {
         let PosValsToUpdate {
             locked_ratio: locked_ratio_1,
             inflation: inflation_1,
-        } = controller.clone().run();
+        } = controller.clone().run().unwrap();
         println!(
             "Round 1: Locked ratio: {locked_ratio_1}, inflation: {inflation_1}",
         );
@@ -302,7 +412,7 @@ mod test {
         let PosValsToUpdate {
             locked_ratio: locked_ratio_2,
             inflation: inflation_2,
-        } = controller.run();
+        } = controller.run().unwrap();
         println!(
             "Round 2: Locked ratio: {locked_ratio_2}, inflation: {inflation_2}",
         );
@@ -310,6 +420,7 @@ mod test {
         assert_eq!(inflation_2, Uint::zero());
     }
 
+    #[cfg(not(feature = "strict-arith"))]
     #[test]
     fn test_inflation_playground() {
         let init_locked_ratio = Dec::from_str("0.1").unwrap();
@@ -341,7 +452,7 @@ mod test {
             let PosValsToUpdate {
                 locked_ratio,
                 inflation,
-            } = controller.clone().run();
+            } = controller.clone().run().unwrap();
             let rate = Dec::try_from(inflation).unwrap()
                 * Dec::from(epochs_per_year)
                 / Dec::from(total_tokens);
@@ -394,4 +505,49 @@ mod test {
         // controller.total_tokens += inflation_1;
         // controller.locked_tokens += inflation_1;
     }
+
+    #[test]
+    fn test_inflation_negative_max_reward_rate() {
+        let controller = PosRewardsController {
+            locked_tokens: Uint::from(2_000_000_000),
+            total_native_tokens: Uint::from(4_000_000_000_u64),
+            locked_ratio_target: Dec::from_str("0.66666666").unwrap(),
+            locked_ratio_last: Dec::from_str("0.5").unwrap(),
+            max_reward_rate: Dec::from_str("-0.1").unwrap(),
+            last_inflation_amount: Uint::zero(),
+            p_gain_nom: Dec::from_str("0.1").unwrap(),
+            d_gain_nom: Dec::from_str("0.1").unwrap(),
+            epochs_per_year: 365,
+        };
+        assert!(controller.run().is_err());
+
+        let controller = ShieldedRewardsController {
+            locked_tokens: Uint::from(2_000_000_000),
+            total_native_tokens: Uint::from(4_000_000_000_u64),
+            locked_tokens_target: Uint::from(3_000_000_000_u64),
+            locked_tokens_last: Uint::from(2_000_000_000),
+            max_reward_rate: Dec::from_str("-0.1").unwrap(),
+            last_inflation_amount: Uint::zero(),
+            p_gain_nom: Dec::from_str("0.1").unwrap(),
+            d_gain_nom: Dec::from_str("0.1").unwrap(),
+            epochs_per_year: 365,
+        };
+        assert!(controller.run().is_err());
+    }
+
+    #[test]
+    fn test_inflation_zero_epochs_per_year() {
+        let controller = PosRewardsController {
+            locked_tokens: Uint::from(2_000_000_000),
+            total_native_tokens: Uint::from(4_000_000_000_u64),
+            locked_ratio_target: Dec::from_str("0.66666666").unwrap(),
+            locked_ratio_last: Dec::from_str("0.5").unwrap(),
+            max_reward_rate: Dec::from_str("0.1").unwrap(),
+            last_inflation_amount: Uint::zero(),
+            p_gain_nom: Dec::from_str("0.1").unwrap(),
+            d_gain_nom: Dec::from_str("0.1").unwrap(),
+            epochs_per_year: 0,
+        };
+        assert!(matches!(controller.run(), Err(AmountParseError::Overflow)));
+    }
 }
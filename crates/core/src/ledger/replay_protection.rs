@@ -2,6 +2,7 @@
 
 use crate::types::hash::Hash;
 use crate::types::storage::Key;
+use crate::types::time::DateTimeUtc;
 
 const ERROR_MSG: &str = "Cannot obtain a valid db key";
 
@@ -24,3 +25,57 @@ pub fn last_prefix() -> Key {
 pub fn last_key(hash: &Hash) -> Key {
     last_prefix().push(&hash.to_string()).expect(ERROR_MSG)
 }
+
+/// Get the prefix of the expirations of the transaction hashes under the
+/// `expiration` subkey
+pub fn expiration_prefix() -> Key {
+    Key::parse("expiration").expect(ERROR_MSG)
+}
+
+/// Get the key of the expiration of a transaction hash under the
+/// `expiration` subkey. The expiration is a unix timestamp in seconds, padded
+/// such that the keys are ordered by the expiration.
+pub fn expiration_key(expiration: u64, hash: &Hash) -> Key {
+    expiration_prefix()
+        .push(&format!("{expiration:020}"))
+        .expect(ERROR_MSG)
+        .push(&hash.to_string())
+        .expect(ERROR_MSG)
+}
+
+/// Get the unix timestamp in seconds of the expiration of a transaction,
+/// truncated to the second as in the [`expiration_key`]
+pub fn expiration_timestamp(expiration: &DateTimeUtc) -> u64 {
+    // An expiration before the unix epoch has passed anyway
+    u64::try_from(expiration.0.timestamp()).unwrap_or_default()
+}
+
+/// Parse the expiration and the transaction hash of a key under the
+/// `expiration` subkey, with the subkey stripped
+pub fn parse_expiration_key(key: &str) -> Option<(u64, Hash)> {
+    let (expiration, hash) = key.split_once('/')?;
+    Some((expiration.parse().ok()?, hash.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the expiration keys are ordered by the expiration and parsed
+    /// back.
+    #[test]
+    fn test_expiration_keys() {
+        let hash = Hash::sha256(b"tx");
+        let earlier = expiration_key(999, &hash);
+        let later = expiration_key(1_000, &hash);
+        assert!(earlier.to_string() < later.to_string());
+
+        let stripped = format!("{}/", expiration_prefix());
+        let key = later.to_string();
+        assert_eq!(
+            parse_expiration_key(key.strip_prefix(&stripped).unwrap()),
+            Some((1_000, hash))
+        );
+        assert_eq!(parse_expiration_key("1000"), None);
+    }
+}
@@ -1,19 +1,30 @@
 //! The core public types, storage_api, VpEnv and TxEnv.
+//!
+//! Without the default `std` feature, the crate is `no_std` and only has the
+//! token amounts of the [`types::token`] module with their codecs.
 
 #![doc(html_favicon_url = "https://dev.namada.net/master/favicon.png")]
 #![doc(html_logo_url = "https://dev.namada.net/master/rustdoc-logo.png")]
 #![warn(missing_docs)]
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(rustdoc::private_intra_doc_links)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub mod bytes;
+#[cfg(feature = "std")]
 pub mod hints;
+#[cfg(feature = "std")]
 pub mod ledger;
 pub mod types;
 
+#[cfg(feature = "std")]
 pub use {ibc, tendermint, tendermint_proto};
 /// Borsh binary encoding (re-exported) from official crate with custom ext.
 pub mod borsh {
     pub use borsh::*;
+    #[cfg(feature = "std")]
     pub use borsh_ext::*;
 }
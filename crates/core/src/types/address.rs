@@ -8,23 +8,32 @@ use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::str::FromStr;
 
+use bech32::{FromBase32, ToBase32};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
 use data_encoding::HEXUPPER;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use thiserror::Error;
 
+use crate::ibc::apps::transfer::types::TracePath;
 use crate::ibc::primitives::Signer;
 use crate::impl_display_and_from_str_via_format;
 use crate::types::ethereum_events::EthAddress;
-use crate::types::ibc::IbcTokenHash;
+use crate::types::ibc::{is_ibc_denom, IbcTokenHash};
 use crate::types::key::PublicKeyHash;
+use crate::types::masp::PaymentAddress;
+use crate::types::string_encoding::{ADDRESS_HRP, MASP_PAYMENT_ADDRESS_HRP};
 use crate::types::token::Denomination;
 use crate::types::{key, string_encoding};
 
 /// The length of an established [`Address`] encoded with Borsh.
 pub const ESTABLISHED_ADDRESS_BYTES_LEN: usize = 21;
 
+/// The length of the raw bytes of an [`Address`] of any kind, which are
+/// encoded by its Bech32m encoding and its fixed-width storage key segment.
+pub const ADDRESS_RAW_LEN: usize = raw::ADDR_ENCODING_LEN;
+
 /// The length of [`Address`] encoded with Bech32m.
 // NOTE: This must be kept in sync with the bech32 HRP.
 // Uppercase prefixes might result in a different length,
@@ -49,6 +58,10 @@ pub const HASH_LEN: usize = 20;
 /// ```
 pub const SHA_HASH_LEN: usize = 32;
 
+/// The domain of the hash that derives the established addresses of the
+/// accounts initialized by txs
+const ESTABLISHED_ADDRESS_DOMAIN: &[u8] = b"namada-established-address";
+
 /// Internal IBC address
 pub const IBC: Address = Address::Internal(InternalAddress::Ibc);
 /// Internal ledger parameters address
@@ -64,6 +77,16 @@ pub const GOV: Address = Address::Internal(InternalAddress::Governance);
 pub const MASP: Address = Address::Internal(InternalAddress::Masp);
 /// Internal Multitoken address
 pub const MULTITOKEN: Address = Address::Internal(InternalAddress::Multitoken);
+/// Internal fee escrow address
+pub const FEE_ESCROW: Address = Address::Internal(InternalAddress::FeeEscrow);
+/// Internal name service address
+pub const NAME_SERVICE: Address =
+    Address::Internal(InternalAddress::NameService);
+/// Internal non-fungible tokens address
+pub const NFT: Address = Address::Internal(InternalAddress::Nft);
+/// Internal token factory address
+pub const TOKEN_FACTORY: Address =
+    Address::Internal(InternalAddress::TokenFactory);
 
 /// Error from decoding address from string
 pub type DecodeError = string_encoding::DecodeError;
@@ -84,144 +107,49 @@ pub enum Address {
     Internal(InternalAddress),
 }
 
-impl From<raw::Address<'_, raw::Validated>> for Address {
-    fn from(raw_addr: raw::Address<'_, raw::Validated>) -> Self {
-        match raw_addr.discriminant() {
-            raw::Discriminant::Implicit => Address::Implicit(ImplicitAddress(
-                PublicKeyHash(*raw_addr.data()),
-            )),
-            raw::Discriminant::Established => {
-                Address::Established(EstablishedAddress {
-                    hash: *raw_addr.data(),
-                })
-            }
-            raw::Discriminant::Pos => Address::Internal(InternalAddress::PoS),
-            raw::Discriminant::SlashPool => {
-                Address::Internal(InternalAddress::PosSlashPool)
-            }
-            raw::Discriminant::Parameters => {
-                Address::Internal(InternalAddress::Parameters)
-            }
-            raw::Discriminant::Governance => {
-                Address::Internal(InternalAddress::Governance)
-            }
-            raw::Discriminant::Ibc => Address::Internal(InternalAddress::Ibc),
-            raw::Discriminant::EthBridge => {
-                Address::Internal(InternalAddress::EthBridge)
-            }
-            raw::Discriminant::BridgePool => {
-                Address::Internal(InternalAddress::EthBridgePool)
-            }
-            raw::Discriminant::Multitoken => {
-                Address::Internal(InternalAddress::Multitoken)
-            }
-            raw::Discriminant::Pgf => Address::Internal(InternalAddress::Pgf),
-            raw::Discriminant::Erc20 => Address::Internal(
-                InternalAddress::Erc20(EthAddress(*raw_addr.data())),
-            ),
-            raw::Discriminant::Nut => Address::Internal(InternalAddress::Nut(
-                EthAddress(*raw_addr.data()),
-            )),
-            raw::Discriminant::IbcToken => Address::Internal(
-                InternalAddress::IbcToken(IbcTokenHash(*raw_addr.data())),
-            ),
-            raw::Discriminant::Masp => Address::Internal(InternalAddress::Masp),
-        }
-    }
+/// The scheme of a kind of [`Address`]: how the payload of the addresses of
+/// the kind is encoded in their raw bytes and validated, and the Bech32m
+/// human-readable part of their string encoding.
+///
+/// A new kind of address is added with a variant of [`Address`] holding a
+/// payload that implements this trait, in the `with_address_scheme` macro and
+/// in [`Address::from_raw_parts`]. Its discriminant bytes must be added to
+/// the raw address discriminants, which must never be reused.
+pub trait AddressScheme: Sized {
+    /// The Bech32m human-readable part of the addresses of the scheme
+    const HRP: &'static str = string_encoding::ADDRESS_HRP;
+
+    /// Encode the payload of an address to the discriminant byte and the
+    /// data of its raw encoding.
+    fn to_raw_parts(&self) -> (u8, [u8; HASH_LEN]);
+
+    /// Decode the payload of an address from the discriminant byte and the
+    /// data of its raw encoding. Returns `None` if the discriminant isn't one
+    /// of the scheme, or an error if the data are not valid for it.
+    fn from_raw_parts(
+        discriminant: u8,
+        data: &[u8; HASH_LEN],
+    ) -> Option<Result<Self>>;
+
+    /// A human-readable description of the kind of the address
+    fn kind(&self) -> String;
 }
 
-impl From<Address> for raw::Address<'static, raw::Validated> {
-    #[inline]
-    fn from(address: Address) -> Self {
-        raw::Address::from(&address).to_owned()
-    }
+/// Get the Bech32m human-readable part of the scheme of an address payload
+const fn scheme_hrp<S: AddressScheme>(_payload: &S) -> &'static str {
+    S::HRP
 }
 
-impl<'addr> From<&'addr Address> for raw::Address<'addr, raw::Validated> {
-    fn from(address: &'addr Address) -> Self {
-        match address {
-            Address::Established(EstablishedAddress { hash }) => {
-                raw::Address::from_discriminant(raw::Discriminant::Established)
-                    .with_data_array_ref(hash)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Implicit(ImplicitAddress(key::PublicKeyHash(pkh))) => {
-                raw::Address::from_discriminant(raw::Discriminant::Implicit)
-                    .with_data_array_ref(pkh)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::PoS) => {
-                raw::Address::from_discriminant(raw::Discriminant::Pos)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::PosSlashPool) => {
-                raw::Address::from_discriminant(raw::Discriminant::SlashPool)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::Parameters) => {
-                raw::Address::from_discriminant(raw::Discriminant::Parameters)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::Governance) => {
-                raw::Address::from_discriminant(raw::Discriminant::Governance)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::Ibc) => {
-                raw::Address::from_discriminant(raw::Discriminant::Ibc)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::IbcToken(IbcTokenHash(
-                hash,
-            ))) => raw::Address::from_discriminant(raw::Discriminant::IbcToken)
-                .with_data_array_ref(hash)
-                .validate()
-                .expect("This raw address is valid"),
-            Address::Internal(InternalAddress::EthBridge) => {
-                raw::Address::from_discriminant(raw::Discriminant::EthBridge)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::EthBridgePool) => {
-                raw::Address::from_discriminant(raw::Discriminant::BridgePool)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::Erc20(EthAddress(eth_addr))) => {
-                raw::Address::from_discriminant(raw::Discriminant::Erc20)
-                    .with_data_array_ref(eth_addr)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::Nut(EthAddress(eth_addr))) => {
-                raw::Address::from_discriminant(raw::Discriminant::Nut)
-                    .with_data_array_ref(eth_addr)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::Multitoken) => {
-                raw::Address::from_discriminant(raw::Discriminant::Multitoken)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::Pgf) => {
-                raw::Address::from_discriminant(raw::Discriminant::Pgf)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
-            Address::Internal(InternalAddress::Masp) => {
-                raw::Address::from_discriminant(raw::Discriminant::Masp)
-                    .validate()
-                    .expect("This raw address is valid")
-            }
+/// Evaluate an expression with the payload of an [`Address`], which
+/// implements [`AddressScheme`], whatever its kind.
+macro_rules! with_address_scheme {
+    ($address:expr, $payload:ident => $body:expr) => {
+        match $address {
+            Address::Established($payload) => $body,
+            Address::Implicit($payload) => $body,
+            Address::Internal($payload) => $body,
         }
-    }
+    };
 }
 
 // We're using the string format of addresses (bech32m) for ordering to ensure
@@ -240,6 +168,13 @@ impl Ord for Address {
 }
 
 impl Address {
+    /// The Bech32m human-readable parts of the addresses of all the kinds
+    pub const HRPS: &'static [&'static str] = &[
+        EstablishedAddress::HRP,
+        ImplicitAddress::HRP,
+        InternalAddress::HRP,
+    ];
+
     /// Encode an address with Bech32m encoding
     pub fn encode(&self) -> String {
         string_encoding::Format::encode(self)
@@ -250,6 +185,30 @@ impl Address {
         string_encoding::Format::decode(string)
     }
 
+    /// The Bech32m human-readable part of the address
+    pub fn hrp(&self) -> &'static str {
+        with_address_scheme!(self, payload => scheme_hrp(payload))
+    }
+
+    /// Decode an address of any kind from the discriminant byte and the data
+    /// of its raw encoding. Returns `None` if the discriminant isn't one of
+    /// any kind.
+    pub fn from_raw_parts(
+        discriminant: u8,
+        data: &[u8; HASH_LEN],
+    ) -> Option<Result<Self>> {
+        EstablishedAddress::from_raw_parts(discriminant, data)
+            .map(|res| res.map(Address::Established))
+            .or_else(|| {
+                ImplicitAddress::from_raw_parts(discriminant, data)
+                    .map(|res| res.map(Address::Implicit))
+            })
+            .or_else(|| {
+                InternalAddress::from_raw_parts(discriminant, data)
+                    .map(|res| res.map(Address::Internal))
+            })
+    }
+
     /// Try to get a raw hash of an address, only defined for established and
     /// implicit addresses.
     pub fn raw_hash(&self) -> Option<String> {
@@ -272,17 +231,8 @@ impl Address {
 
     /// Print the type of the address and its bech32m encoded value
     pub fn to_pretty_string(&self) -> String {
-        match self {
-            Address::Established(_) => {
-                format!("Established: {}", self.encode())
-            }
-            Address::Implicit(_) => {
-                format!("Implicit: {}", self.encode())
-            }
-            Address::Internal(kind) => {
-                format!("Internal {}: {}", kind, self.encode())
-            }
-        }
+        let kind = with_address_scheme!(self, payload => payload.kind());
+        format!("{}: {}", kind, self.encode())
     }
 
     /// If the address established?
@@ -294,6 +244,21 @@ impl Address {
     pub fn is_implicit(&self) -> bool {
         matches!(self, Address::Implicit(_))
     }
+
+    /// Derive the established address of an account initialized by a tx,
+    /// from the hash of the tx (its raw header hash) and the number of
+    /// accounts that the tx initialized before it. The address is thus known
+    /// before the tx is submitted.
+    pub fn derive_established(
+        tx_hash: &crate::types::hash::Hash,
+        index: u64,
+    ) -> Self {
+        let bytes =
+            [ESTABLISHED_ADDRESS_DOMAIN, &tx_hash.0, &index.to_le_bytes()]
+                .concat();
+        let hash: [u8; SHA_HASH_LEN] = Sha256::digest(bytes).into();
+        Address::Established(EstablishedAddress::from(hash))
+    }
 }
 
 impl string_encoding::Format for Address {
@@ -301,25 +266,67 @@ impl string_encoding::Format for Address {
 
     const HRP: &'static str = string_encoding::ADDRESS_HRP;
 
+    fn encode(&self) -> String {
+        let base32 = self.to_bytes().to_base32();
+        bech32::encode(self.hrp(), base32, string_encoding::BECH32M_VARIANT)
+            .unwrap_or_else(|_| {
+                panic!(
+                    "The human-readable part {} should never cause a failure",
+                    self.hrp()
+                )
+            })
+    }
+
+    fn decode(string: impl AsRef<str>) -> Result<Self> {
+        let string = string_encoding::to_lowercase(string.as_ref())?;
+        let (hrp, hash_base32, variant) =
+            bech32::decode(&string).map_err(DecodeError::DecodeBech32)?;
+        if !Self::HRPS.contains(&hrp.as_str()) {
+            return Err(DecodeError::UnexpectedBech32Hrp(
+                hrp,
+                Self::HRP.into(),
+            ));
+        }
+        if variant != string_encoding::BECH32M_VARIANT {
+            return Err(DecodeError::UnexpectedBech32Variant(variant));
+        }
+        let bytes: Vec<u8> = FromBase32::from_base32(&hash_base32)
+            .map_err(DecodeError::DecodeBase32)?;
+        let address = Self::decode_bytes(&bytes)?;
+        // The human-readable part must be the one of the kind of the address
+        if address.hrp() != hrp {
+            return Err(DecodeError::UnexpectedBech32Hrp(
+                hrp,
+                address.hrp().into(),
+            ));
+        }
+        Ok(address)
+    }
+
     fn to_bytes(&self) -> [u8; raw::ADDR_ENCODING_LEN] {
-        let raw_addr: raw::Address<'_, _> = self.into();
-        raw_addr.to_bytes()
+        let (discriminant, data) =
+            with_address_scheme!(self, payload => payload.to_raw_parts());
+        let mut bytes = [0; raw::ADDR_ENCODING_LEN];
+        bytes[0] = discriminant;
+        bytes[1..].copy_from_slice(&data);
+        bytes
     }
 
     fn decode_bytes(bytes: &[u8]) -> Result<Self> {
-        let unvalidated_raw_addr = raw::Address::try_from_slice(bytes)
-            .ok_or_else(|| {
-                DecodeError::InvalidInnerEncoding(
-                    "Invalid raw address length".to_string(),
-                )
-            })?;
-        let validated_raw_addr =
-            unvalidated_raw_addr.validate().ok_or_else(|| {
-                DecodeError::InvalidInnerEncoding(
-                    "Invalid address discriminant and data pair".to_string(),
-                )
-            })?;
-        Ok(validated_raw_addr.into())
+        let Some((discriminant, data)) =
+            bytes.split_first().and_then(|(discriminant, data)| {
+                Some((*discriminant, <&[u8; HASH_LEN]>::try_from(data).ok()?))
+            })
+        else {
+            return Err(DecodeError::InvalidInnerEncoding(
+                "Invalid raw address length".to_string(),
+            ));
+        };
+        Self::from_raw_parts(discriminant, data).unwrap_or_else(|| {
+            Err(DecodeError::InvalidInnerEncoding(
+                "Invalid address discriminant and data pair".to_string(),
+            ))
+        })
     }
 }
 
@@ -363,15 +370,99 @@ impl TryFrom<Signer> for Address {
         // The given address should be an address or payment address. When
         // sending a token from a spending key, it has been already
         // replaced with the MASP address.
-        Address::decode(signer.as_ref()).or(
-            match crate::types::masp::PaymentAddress::from_str(signer.as_ref())
-            {
-                Ok(_) => Ok(MASP),
-                Err(_) => Err(DecodeError::InvalidInnerEncoding(format!(
+        AnyAddress::from_str(signer.as_ref())
+            .ok()
+            .and_then(|address| address.effective_address())
+            .ok_or_else(|| {
+                DecodeError::InvalidInnerEncoding(format!(
                     "Invalid address for IBC transfer: {signer}"
-                ))),
-            },
-        )
+                ))
+            })
+    }
+}
+
+/// An error of parsing an [`AnyAddress`]
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum AnyAddressError {
+    #[error("Invalid transparent address {0}: {1}")]
+    Transparent(String, DecodeError),
+    #[error("Invalid payment address {0}: {1}")]
+    Payment(String, DecodeError),
+    #[error(
+        "Invalid address {0}, expected a transparent address \
+         ({ADDRESS_HRP}1...), a payment address \
+         ({MASP_PAYMENT_ADDRESS_HRP}1...) or an IBC denom \
+         (<port>/<channel>/<base denom>)"
+    )]
+    Unknown(String),
+}
+
+/// Any of the addresses that can own or receive tokens: a transparent
+/// address, a MASP payment address or the owner of an IBC token given by its
+/// prefixed denom. Its string is parsed by its Bech32m human-readable part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnyAddress {
+    /// A transparent address
+    Transparent(Address),
+    /// A MASP payment address
+    Payment(PaymentAddress),
+    /// An IBC token, by its prefixed denom
+    IbcDenom {
+        /// The trace path of the denom, which is never empty
+        trace_path: TracePath,
+        /// The base denom, which can be the address of a Namada token or the
+        /// denom of a token of another chain
+        base_denom: String,
+    },
+}
+
+impl AnyAddress {
+    /// Get the address of the account holding the tokens on the chain: the
+    /// MASP address for a payment address. An IBC denom is `None`, since its
+    /// base denom may have to be resolved first.
+    pub fn effective_address(&self) -> Option<Address> {
+        match self {
+            Self::Transparent(address) => Some(address.clone()),
+            Self::Payment(_) => Some(MASP),
+            Self::IbcDenom { .. } => None,
+        }
+    }
+}
+
+impl Display for AnyAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transparent(address) => write!(f, "{address}"),
+            Self::Payment(address) => write!(f, "{address}"),
+            Self::IbcDenom {
+                trace_path,
+                base_denom,
+            } => write!(f, "{trace_path}/{base_denom}"),
+        }
+    }
+}
+
+impl FromStr for AnyAddress {
+    type Err = AnyAddressError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // The human-readable part of Bech32m is before its last separator
+        let hrp = s.rsplit_once('1').map(|(hrp, _)| hrp.to_ascii_lowercase());
+        match hrp.as_deref() {
+            Some(hrp) if Address::HRPS.contains(&hrp) => Address::decode(s)
+                .map(Self::Transparent)
+                .map_err(|err| AnyAddressError::Transparent(s.to_owned(), err)),
+            Some(MASP_PAYMENT_ADDRESS_HRP) => PaymentAddress::from_str(s)
+                .map(Self::Payment)
+                .map_err(|err| AnyAddressError::Payment(s.to_owned(), err)),
+            _ => is_ibc_denom(s)
+                .map(|(trace_path, base_denom)| Self::IbcDenom {
+                    trace_path,
+                    base_denom,
+                })
+                .ok_or_else(|| AnyAddressError::Unknown(s.to_owned())),
+        }
     }
 }
 
@@ -431,6 +522,24 @@ impl string_encoding::Format for EstablishedAddress {
 
 impl_display_and_from_str_via_format!(EstablishedAddress);
 
+impl AddressScheme for EstablishedAddress {
+    fn to_raw_parts(&self) -> (u8, [u8; HASH_LEN]) {
+        (raw::Discriminant::Established as u8, self.hash)
+    }
+
+    fn from_raw_parts(
+        discriminant: u8,
+        data: &[u8; HASH_LEN],
+    ) -> Option<Result<Self>> {
+        (discriminant == raw::Discriminant::Established as u8)
+            .then_some(Ok(Self { hash: *data }))
+    }
+
+    fn kind(&self) -> String {
+        "Established".to_string()
+    }
+}
+
 /// A generator of established addresses
 #[derive(
     Debug,
@@ -503,6 +612,24 @@ impl From<&key::common::PublicKey> for Address {
     }
 }
 
+impl AddressScheme for ImplicitAddress {
+    fn to_raw_parts(&self) -> (u8, [u8; HASH_LEN]) {
+        (raw::Discriminant::Implicit as u8, self.0 .0)
+    }
+
+    fn from_raw_parts(
+        discriminant: u8,
+        data: &[u8; HASH_LEN],
+    ) -> Option<Result<Self>> {
+        (discriminant == raw::Discriminant::Implicit as u8)
+            .then_some(Ok(Self(PublicKeyHash(*data))))
+    }
+
+    fn kind(&self) -> String {
+        "Implicit".to_string()
+    }
+}
+
 /// An internal address represents a module with a native VP
 #[derive(
     Debug,
@@ -545,6 +672,15 @@ pub enum InternalAddress {
     Pgf,
     /// Masp
     Masp,
+    /// Escrow of the fees of the wrapper txs whose inner txs have yet to be
+    /// applied
+    FeeEscrow,
+    /// Registry of the human-readable names of the addresses
+    NameService,
+    /// Non-fungible tokens
+    Nft,
+    /// Factory of the tokens created by txs
+    TokenFactory,
 }
 
 impl Display for InternalAddress {
@@ -566,11 +702,91 @@ impl Display for InternalAddress {
                 Self::Multitoken => "Multitoken".to_string(),
                 Self::Pgf => "PublicGoodFundings".to_string(),
                 Self::Masp => "MASP".to_string(),
+                Self::FeeEscrow => "FeeEscrow".to_string(),
+                Self::NameService => "NameService".to_string(),
+                Self::Nft => "Nft".to_string(),
+                Self::TokenFactory => "TokenFactory".to_string(),
             }
         )
     }
 }
 
+impl AddressScheme for InternalAddress {
+    fn to_raw_parts(&self) -> (u8, [u8; HASH_LEN]) {
+        let (discriminant, data) = match self {
+            Self::PoS => (raw::Discriminant::Pos, None),
+            Self::PosSlashPool => (raw::Discriminant::SlashPool, None),
+            Self::Parameters => (raw::Discriminant::Parameters, None),
+            Self::Governance => (raw::Discriminant::Governance, None),
+            Self::Ibc => (raw::Discriminant::Ibc, None),
+            Self::IbcToken(IbcTokenHash(hash)) => {
+                (raw::Discriminant::IbcToken, Some(hash))
+            }
+            Self::EthBridge => (raw::Discriminant::EthBridge, None),
+            Self::EthBridgePool => (raw::Discriminant::BridgePool, None),
+            Self::Erc20(EthAddress(eth_addr)) => {
+                (raw::Discriminant::Erc20, Some(eth_addr))
+            }
+            Self::Nut(EthAddress(eth_addr)) => {
+                (raw::Discriminant::Nut, Some(eth_addr))
+            }
+            Self::Multitoken => (raw::Discriminant::Multitoken, None),
+            Self::Pgf => (raw::Discriminant::Pgf, None),
+            Self::Masp => (raw::Discriminant::Masp, None),
+            Self::FeeEscrow => (raw::Discriminant::FeeEscrow, None),
+            Self::NameService => (raw::Discriminant::NameService, None),
+            Self::Nft => (raw::Discriminant::Nft, None),
+            Self::TokenFactory => (raw::Discriminant::TokenFactory, None),
+        };
+        (discriminant as u8, data.copied().unwrap_or_default())
+    }
+
+    fn from_raw_parts(
+        discriminant: u8,
+        data: &[u8; HASH_LEN],
+    ) -> Option<Result<Self>> {
+        let discriminant = raw::Discriminant::try_from(discriminant).ok()?;
+        let address = match discriminant {
+            raw::Discriminant::Implicit | raw::Discriminant::Established => {
+                return None;
+            }
+            raw::Discriminant::Pos => Self::PoS,
+            raw::Discriminant::SlashPool => Self::PosSlashPool,
+            raw::Discriminant::Parameters => Self::Parameters,
+            raw::Discriminant::Governance => Self::Governance,
+            raw::Discriminant::Ibc => Self::Ibc,
+            raw::Discriminant::EthBridge => Self::EthBridge,
+            raw::Discriminant::BridgePool => Self::EthBridgePool,
+            raw::Discriminant::Multitoken => Self::Multitoken,
+            raw::Discriminant::Pgf => Self::Pgf,
+            raw::Discriminant::Erc20 => Self::Erc20(EthAddress(*data)),
+            raw::Discriminant::Nut => Self::Nut(EthAddress(*data)),
+            raw::Discriminant::IbcToken => Self::IbcToken(IbcTokenHash(*data)),
+            raw::Discriminant::Masp => Self::Masp,
+            raw::Discriminant::FeeEscrow => Self::FeeEscrow,
+            raw::Discriminant::NameService => Self::NameService,
+            raw::Discriminant::Nft => Self::Nft,
+            raw::Discriminant::TokenFactory => Self::TokenFactory,
+        };
+        // The addresses without a payload must have the default data
+        let is_valid = raw::Address::from_discriminant(discriminant)
+            .with_data_array_ref(data)
+            .validate()
+            .is_some();
+        Some(if is_valid {
+            Ok(address)
+        } else {
+            Err(DecodeError::InvalidInnerEncoding(
+                "Invalid address discriminant and data pair".to_string(),
+            ))
+        })
+    }
+
+    fn kind(&self) -> String {
+        format!("Internal {self}")
+    }
+}
+
 impl InternalAddress {
     /// Certain internal addresses have reserved aliases.
     pub fn try_from_alias(alias: &str) -> Option<Self> {
@@ -659,6 +875,7 @@ pub mod tests {
     use proptest::prelude::*;
 
     use super::*;
+    use crate::types::string_encoding::Format;
 
     /// Run `cargo test gen_established_address -- --nocapture` to generate a
     /// new established address.
@@ -680,6 +897,23 @@ pub mod tests {
         }
     }
 
+    /// Test that the derived established addresses are distinct for each tx
+    /// and for each index.
+    #[test]
+    fn test_derive_established() {
+        use crate::types::hash::Hash as TxHash;
+
+        let tx_hash = TxHash::sha256(b"tx");
+        let address = Address::derive_established(&tx_hash, 0);
+        assert!(address.is_established());
+        assert_eq!(address, Address::derive_established(&tx_hash, 0));
+        assert_ne!(address, Address::derive_established(&tx_hash, 1));
+        assert_ne!(
+            address,
+            Address::derive_established(&TxHash::sha256(b"other tx"), 0)
+        );
+    }
+
     #[test]
     fn test_address_serde_serialize() {
         let original_address =
@@ -694,6 +928,84 @@ pub mod tests {
         assert_eq!(encoded_address, expect);
     }
 
+    /// Test that the addresses are decoded from each of their parts in either
+    /// case, but not from a part of mixed case.
+    #[test]
+    fn test_address_decode_case() {
+        let lower = "tnam1q8j5s6xp55p05yznwnftkv3kr9gjtsw3nq7x6tw5";
+        let address = Address::decode(lower).unwrap();
+        let (hrp, data) = lower.split_once('1').unwrap();
+        let upper_data = format!("{hrp}1{}", data.to_ascii_uppercase());
+        for string in [lower.to_ascii_uppercase(), upper_data] {
+            assert_eq!(Address::decode(&string).unwrap(), address);
+            assert_eq!(
+                AnyAddress::from_str(&string).unwrap().to_string(),
+                lower
+            );
+        }
+        assert_eq!(address.encode(), lower);
+
+        let mixed_data =
+            format!("{hrp}1{}{}", &data[..5].to_ascii_uppercase(), &data[5..]);
+        let mixed_hrp = format!("tNAM1{data}");
+        for string in [mixed_data, mixed_hrp] {
+            assert!(matches!(
+                Address::decode(&string),
+                Err(DecodeError::MixedCase(_))
+            ));
+        }
+    }
+
+    /// Test that the strings of all the formats of addresses are parsed by
+    /// their human-readable part and that they roundtrip.
+    #[test]
+    fn test_any_address_from_str() {
+        let transparent = "tnam1q8j5s6xp55p05yznwnftkv3kr9gjtsw3nq7x6tw5";
+        let payment = "znam1qr57pyghrt5ek7v42nxsqdqggltwqrgj2hjlvm5sj0nr8hezzr\
+                       yxcu44qzcea7qdx6wh02cvt9jlu";
+        let ibc_denom = format!("transfer/channel-1/{transparent}");
+        for string in [transparent, payment, "transfer/channel-0/uatom"]
+            .into_iter()
+            .chain([ibc_denom.as_str()])
+        {
+            let address = AnyAddress::from_str(string).unwrap();
+            assert_eq!(address.to_string(), string);
+        }
+
+        assert_eq!(
+            AnyAddress::from_str(transparent).unwrap(),
+            AnyAddress::Transparent(Address::decode(transparent).unwrap())
+        );
+        let address = AnyAddress::from_str(payment).unwrap();
+        assert!(matches!(address, AnyAddress::Payment(_)));
+        assert_eq!(address.effective_address(), Some(MASP));
+        let address = AnyAddress::from_str(&ibc_denom).unwrap();
+        assert!(matches!(
+            &address,
+            AnyAddress::IbcDenom { trace_path, base_denom }
+                if trace_path.to_string() == "transfer/channel-1"
+                    && base_denom == transparent
+        ));
+        assert_eq!(address.effective_address(), None);
+
+        let mut invalid = transparent.to_string();
+        invalid.push('q');
+        assert!(matches!(
+            AnyAddress::from_str(&invalid),
+            Err(AnyAddressError::Transparent(..))
+        ));
+        assert!(matches!(
+            AnyAddress::from_str(&payment[..payment.len() - 1]),
+            Err(AnyAddressError::Payment(..))
+        ));
+        for unknown in ["uatom", "zsknam1qqqq", ""] {
+            assert!(matches!(
+                AnyAddress::from_str(unknown),
+                Err(AnyAddressError::Unknown(_))
+            ));
+        }
+    }
+
     proptest! {
         #[test]
         /// Check that all the address types are of the same length
@@ -713,6 +1025,36 @@ pub mod tests {
             let bytes = address.serialize_to_vec();
             assert_eq!(bytes.len(), ESTABLISHED_ADDRESS_BYTES_LEN);
         }
+
+        /// Check that the addresses of any kind are decoded back from the
+        /// raw parts of their scheme
+        #[test]
+        fn test_address_scheme_raw_parts(address in testing::arb_address()) {
+            let (discriminant, data) =
+                with_address_scheme!(&address, payload => payload.to_raw_parts());
+            let decoded = Address::from_raw_parts(discriminant, &data)
+                .unwrap()
+                .unwrap();
+            assert_eq!(decoded, address);
+            assert_eq!(address.hrp(), ADDRESS_HRP);
+        }
+    }
+
+    /// Test that the raw parts that don't match any address scheme are
+    /// rejected.
+    #[test]
+    fn test_invalid_address_raw_parts() {
+        // An unknown discriminant
+        assert!(Address::from_raw_parts(u8::MAX, &[0; HASH_LEN]).is_none());
+        // An internal address without payload with non-default data
+        let (discriminant, _) = InternalAddress::PoS.to_raw_parts();
+        assert!(Address::from_raw_parts(discriminant, &[1; HASH_LEN])
+            .unwrap()
+            .is_err());
+        let mut bytes = POS.to_bytes();
+        bytes[1] = 1;
+        assert!(Address::decode_bytes(&bytes).is_err());
+        assert!(Address::decode_bytes(&bytes[1..]).is_err());
     }
 }
 
@@ -855,6 +1197,10 @@ pub mod testing {
             InternalAddress::Nut(_) => {}
             InternalAddress::Pgf => {}
             InternalAddress::Masp => {}
+            InternalAddress::FeeEscrow => {}
+            InternalAddress::NameService => {}
+            InternalAddress::Nft => {}
+            InternalAddress::TokenFactory => {}
             InternalAddress::Multitoken => {} /* Add new addresses in the
                                                * `prop_oneof` below. */
         };
@@ -872,6 +1218,10 @@ pub mod testing {
             Just(InternalAddress::Multitoken),
             Just(InternalAddress::Pgf),
             Just(InternalAddress::Masp),
+            Just(InternalAddress::FeeEscrow),
+            Just(InternalAddress::NameService),
+            Just(InternalAddress::Nft),
+            Just(InternalAddress::TokenFactory),
         ]
     }
 
@@ -65,6 +65,14 @@ pub enum Discriminant {
     IbcToken = 13,
     /// MASP raw address.
     Masp = 14,
+    /// Fee escrow raw address.
+    FeeEscrow = 15,
+    /// Name service raw address.
+    NameService = 16,
+    /// Non-fungible tokens raw address.
+    Nft = 17,
+    /// Token factory raw address.
+    TokenFactory = 18,
 }
 
 /// Raw address representation.
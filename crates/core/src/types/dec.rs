@@ -4,17 +4,22 @@
 //! precision.
 
 use std::fmt::{Debug, Display, Formatter};
+#[cfg(not(feature = "strict-arith"))]
 use std::iter::Sum;
-use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+#[cfg(not(feature = "strict-arith"))]
+use std::ops::{Add, AddAssign, Mul, Sub};
+use std::ops::{Div, Neg};
 use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use eyre::eyre;
-use num_traits::CheckedMul;
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub};
 use serde::{Deserialize, Serialize};
 
 use super::token::NATIVE_MAX_DECIMAL_PLACES;
-use crate::types::token::{Amount, Change};
+#[cfg(not(feature = "strict-arith"))]
+use crate::types::token::Change;
+use crate::types::token::{Amount, AmountParseError};
 use crate::types::uint::{Uint, I256};
 
 /// The number of Dec places for PoS rational calculations
@@ -28,6 +33,15 @@ pub struct Error(#[from] eyre::Error);
 /// Generic result type for fallible [`Dec`] operations
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The natural logarithm of 2 with 24 Dec places, i.e. with twice the
+/// precision of a [`Dec`], for the range reductions of [`Dec::ln`] and
+/// [`Dec::exp`]
+const LN_2_SCALED: u128 = 693_147_180_559_945_309_417_232;
+
+/// The value below which the exponential of a [`Dec`] is truncated to 0,
+/// since e^-28 is below 10^-12
+const EXP_MIN_ARG: i32 = -28;
+
 /// A 256 bit number with [`POS_DECIMAL_PRECISION`] number of Dec places.
 ///
 /// To be precise, an instance X of this type should be interpreted as the Dec
@@ -123,7 +137,9 @@ impl Dec {
 
     /// The representation of 2
     pub fn two() -> Self {
-        Self::one() + Self::one()
+        Self(I256(
+            Uint::from(2) * Uint::exp10(POS_DECIMAL_PRECISION as usize),
+        ))
     }
 
     /// Create a new [`Dec`] using a mantissa and a scale.
@@ -149,11 +165,14 @@ impl Dec {
 
     /// Get the non-negative difference between two [`Dec`]s.
     pub fn abs_diff(&self, other: &Self) -> Self {
-        if self > other {
-            *self - *other
+        let (larger, smaller) = if self > other {
+            (self, other)
         } else {
-            *other - *self
-        }
+            (other, self)
+        };
+        // The difference of two signed values always fits in 256 bits
+        let (diff, _) = larger.0.0.overflowing_sub(smaller.0.0);
+        Self(I256(diff))
     }
 
     /// Get the absolute value of self as integer
@@ -179,17 +198,29 @@ impl Dec {
     /// greater
     pub fn checked_sub(&self, other: &Self) -> Option<Self> {
         if self > other {
-            Some(*self - *other)
+            self.checked_signed_sub(other)
         } else {
             None
         }
     }
 
+    /// Do subtraction of two [`Dec`]s, of any sign. Return `None` if
+    /// overflow.
+    pub fn checked_signed_sub(&self, other: &Self) -> Option<Self> {
+        self.0.checked_sub(&other.0).map(Dec)
+    }
+
     /// Do addition of two [`Dec`]s
+    #[cfg(not(feature = "strict-arith"))]
     pub fn add(&self, other: &Self) -> Self {
         Dec(self.0 + other.0)
     }
 
+    /// Do addition of two [`Dec`]s. Return `None` if overflow.
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        self.0.checked_add(&other.0).map(Dec)
+    }
+
     /// Do multiply two [`Dec`]s. Return `None` if overflow.
     /// This methods will overflow incorrectly if both arguments are greater
     /// than 128bit.
@@ -205,19 +236,183 @@ impl Dec {
 
     /// Return the integer value of a [`Dec`] by rounding up.
     pub fn ceil(&self) -> I256 {
-        if self.0.is_negative() {
-            self.to_i256()
+        let floor = self.to_i256();
+        let scaling = Uint::exp10(POS_DECIMAL_PRECISION as usize);
+        if self.0.is_negative() || (self.0.abs() % scaling).is_zero() {
+            floor
         } else {
-            let floor = self.to_i256();
-            if (*self - Dec(floor)).is_zero() {
-                floor
+            // The integer part is far below the maximum value
+            I256(floor.0 + Uint::one())
+        }
+    }
+
+    /// Raise a [`Dec`] to an integer power by repeated squaring. Return
+    /// `None` if overflow.
+    ///
+    /// Every product is truncated to the precision of a [`Dec`]. For a value
+    /// of at least 1, the relative error of the result is at most `exp`
+    /// times 10^-12.
+    pub fn checked_pow(&self, exp: u32) -> Option<Self> {
+        let mut result = Self::one();
+        let mut base = *self;
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.checked_mul(&base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(&base)?;
+            }
+        }
+        Some(result)
+    }
+
+    /// The square root of a [`Dec`], rounded down. Return `None` if the value
+    /// is negative.
+    ///
+    /// The error is below 10^-12. For values too large to be scaled by
+    /// 10^12 before taking the integer root, the error is below 10^-6.
+    pub fn sqrt(&self) -> Option<Self> {
+        if self.is_negative() {
+            return None;
+        }
+        let raw = self.0.abs();
+        let scaling = Uint::exp10(POS_DECIMAL_PRECISION as usize);
+        let root = match raw.checked_mul(scaling) {
+            Some(scaled) => integer_sqrt(scaled),
+            // The root of the scaling is 10^6
+            None => {
+                integer_sqrt(raw)
+                    * Uint::exp10(POS_DECIMAL_PRECISION as usize / 2)
+            }
+        };
+        // The root of a non-negative value always fits in 128 bits
+        Some(Self(I256(root)))
+    }
+
+    /// The natural logarithm of a [`Dec`]. Return `None` if the value isn't
+    /// positive.
+    ///
+    /// The value is reduced to `m * 2^k`, with `m` in [1, 2), and ln(m) is
+    /// summed from the series of 2 atanh((m - 1) / (m + 1)). The absolute
+    /// error is below 10^-10.
+    pub fn ln(&self) -> Option<Self> {
+        if self.is_negative() || self.is_zero() {
+            return None;
+        }
+        let raw = self.0.abs();
+        let scaling = Uint::exp10(POS_DECIMAL_PRECISION as usize);
+        let mantissa = |k: i64| {
+            if k >= 0 {
+                raw >> k as usize
             } else {
-                floor + I256::one()
+                raw << k.unsigned_abs() as usize
+            }
+        };
+        let mut k = raw.bits() as i64 - scaling.bits() as i64;
+        let mut m = mantissa(k);
+        if m < scaling {
+            k -= 1;
+            m = mantissa(k);
+        }
+        let m = Self(I256(m));
+
+        // The ratio is in [0, 1/3), so the terms quickly vanish
+        let z = m
+            .checked_signed_sub(&Self::one())?
+            .trunc_div(&m.checked_add(&Self::one())?)?;
+        let z_squared = z.checked_mul(&z)?;
+        let mut power = z;
+        let mut divisor = 1_u64;
+        let mut series = Self::zero();
+        loop {
+            let term = power / divisor;
+            if term.is_zero() {
+                break;
             }
+            series = series.checked_add(&term)?;
+            power = power.checked_mul(&z_squared)?;
+            divisor += 2;
+        }
+        let ln_m = series.checked_add(&series)?;
+
+        let k_ln_2 =
+            Uint::from(k.unsigned_abs()) * Uint::from(LN_2_SCALED) / scaling;
+        let k_ln_2 = Self(I256(k_ln_2));
+        if k >= 0 {
+            ln_m.checked_add(&k_ln_2)
+        } else {
+            ln_m.checked_signed_sub(&k_ln_2)
+        }
+    }
+
+    /// The exponential of a [`Dec`]. Return `None` if overflow.
+    ///
+    /// The value is reduced to `k ln(2) + r`, with `r` in [0, ln(2)), and
+    /// e^r is summed from its Taylor series. The relative error is below
+    /// 10^-10 for non-negative values, and the absolute error is below
+    /// 10^-11 for negative ones.
+    pub fn exp(&self) -> Option<Self> {
+        if self.is_negative() {
+            if *self < Self::from(EXP_MIN_ARG) {
+                return Some(Self::zero());
+            }
+            return Self::one().trunc_div(&(-*self).exp()?);
+        }
+        let raw = self.0.abs();
+        let scaling = Uint::exp10(POS_DECIMAL_PRECISION as usize);
+        let ln_2 = Uint::from(LN_2_SCALED);
+        let k = raw.checked_mul(scaling)? / ln_2;
+        // 2^k doesn't fit in 256 bits
+        if k > Uint::from(255_u64) {
+            return None;
+        }
+        let r = Self(I256(raw - k * ln_2 / scaling));
+        let k = k.low_u64() as usize;
+
+        let mut series = Self::one();
+        let mut term = Self::one();
+        let mut n = 1_u64;
+        loop {
+            term = term.checked_mul(&r)? / n;
+            if term.is_zero() {
+                break;
+            }
+            series = series.checked_add(&term)?;
+            n += 1;
+        }
+        let series = series.abs();
+        // Keep the sign bit clear
+        if series.bits() + k > 255 {
+            return None;
         }
+        Some(Self(I256(series << k)))
     }
 }
 
+/// The integer square root of a [`Uint`], rounded down
+fn integer_sqrt(n: Uint) -> Uint {
+    if n.is_zero() {
+        return n;
+    }
+    // Newton's iterations decrease from an initial value above the root
+    let mut x = Uint::one() << ((n.bits() + 1) / 2);
+    loop {
+        let y = (x + n / x) >> 1_usize;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Scale an integer to the precision of a [`Dec`]. Returns `None` if
+/// overflow.
+fn checked_scale(int: I256) -> Option<I256> {
+    int.checked_mul_uint(Uint::exp10(POS_DECIMAL_PRECISION as usize))
+}
+
 impl FromStr for Dec {
     type Err = Error;
 
@@ -281,17 +476,33 @@ impl TryFrom<String> for Dec {
     }
 }
 
+/// Scale a native token amount to the precision of a [`Dec`]
+fn checked_scale_amount(amt: Amount) -> Option<I256> {
+    I256::try_from(amt.raw_amount())
+        .ok()?
+        .checked_mul_uint(Uint::exp10(
+            (POS_DECIMAL_PRECISION - NATIVE_MAX_DECIMAL_PLACES) as usize,
+        ))
+}
+
+/// Converts a native token amount. Gives zero if the amount overflows.
+#[cfg(not(feature = "strict-arith"))]
 impl From<Amount> for Dec {
     fn from(amt: Amount) -> Self {
-        match I256::try_from(amt.raw_amount()).ok() {
-            Some(raw) => Self(
-                raw * Uint::exp10(
-                    (POS_DECIMAL_PRECISION - NATIVE_MAX_DECIMAL_PLACES)
-                        as usize,
-                ),
-            ),
-            None => Self::zero(),
-        }
+        checked_scale_amount(amt).map(Self).unwrap_or_default()
+    }
+}
+
+/// Converts a native token amount. Fails if the amount overflows.
+#[cfg(feature = "strict-arith")]
+impl TryFrom<Amount> for Dec {
+    type Error = Error;
+
+    fn try_from(amt: Amount) -> Result<Self> {
+        checked_scale_amount(amt).map(Self).ok_or_else(|| {
+            eyre!("The amount {} is too large to fit in the Dec type.", amt)
+                .into()
+        })
     }
 }
 
@@ -301,13 +512,19 @@ impl TryFrom<Uint> for Dec {
     fn try_from(value: Uint) -> std::result::Result<Self, Self::Error> {
         let i256 = I256::try_from(value)
             .map_err(|e| eyre!("Could not convert Uint to I256: {}", e))?;
-        Ok(Self(i256 * Uint::exp10(POS_DECIMAL_PRECISION as usize)))
+        checked_scale(i256).map(Self).ok_or_else(|| {
+            eyre!("The number {} is too large to fit in the Dec type.", value)
+                .into()
+        })
     }
 }
 
 impl From<u64> for Dec {
     fn from(num: u64) -> Self {
-        Self(I256::from(num) * Uint::exp10(POS_DECIMAL_PRECISION as usize))
+        Self(
+            checked_scale(I256::from(num))
+                .expect("A u64 will always fit in this type"),
+        )
     }
 }
 
@@ -319,7 +536,10 @@ impl From<usize> for Dec {
 
 impl From<i128> for Dec {
     fn from(num: i128) -> Self {
-        Self(I256::from(num) * Uint::exp10(POS_DECIMAL_PRECISION as usize))
+        Self(
+            checked_scale(I256::from(num))
+                .expect("An i128 will always fit in this type"),
+        )
     }
 }
 
@@ -333,15 +553,16 @@ impl TryFrom<u128> for Dec {
     type Error = Box<dyn 'static + std::error::Error>;
 
     fn try_from(num: u128) -> std::result::Result<Self, Self::Error> {
-        Ok(Self(
-            I256::try_from(Uint::from(num))?
-                * Uint::exp10(POS_DECIMAL_PRECISION as usize),
-        ))
+        checked_scale(I256::try_from(Uint::from(num))?)
+            .map(Self)
+            .ok_or_else(|| {
+                "The number is too large to fit in the Dec type".into()
+            })
     }
 }
 
 impl TryFrom<Dec> for i128 {
-    type Error = std::io::Error;
+    type Error = AmountParseError;
 
     fn try_from(value: Dec) -> std::result::Result<Self, Self::Error> {
         value.0.try_into()
@@ -349,18 +570,32 @@ impl TryFrom<Dec> for i128 {
 }
 
 // Is error handling needed for this?
+#[cfg(not(feature = "strict-arith"))]
 impl From<I256> for Dec {
     fn from(num: I256) -> Self {
         Self(num * Uint::exp10(POS_DECIMAL_PRECISION as usize))
     }
 }
 
+#[cfg(feature = "strict-arith")]
+impl TryFrom<I256> for Dec {
+    type Error = Error;
+
+    fn try_from(num: I256) -> Result<Self> {
+        checked_scale(num).map(Self).ok_or_else(|| {
+            eyre!("The number {} is too large to fit in the Dec type.", num)
+                .into()
+        })
+    }
+}
+
 impl From<Dec> for String {
     fn from(value: Dec) -> String {
         value.to_string()
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Add<Dec> for Dec {
     type Output = Self;
 
@@ -369,6 +604,7 @@ impl Add<Dec> for Dec {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Add<u64> for Dec {
     type Output = Self;
 
@@ -377,18 +613,21 @@ impl Add<u64> for Dec {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl AddAssign<Dec> for Dec {
     fn add_assign(&mut self, rhs: Dec) {
         *self = *self + rhs;
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Sum for Dec {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
         iter.fold(Dec::default(), |acc, next| acc + next)
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Sub<Dec> for Dec {
     type Output = Self;
 
@@ -397,6 +636,7 @@ impl Sub<Dec> for Dec {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<u64> for Dec {
     type Output = Dec;
 
@@ -405,6 +645,7 @@ impl Mul<u64> for Dec {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<u128> for Dec {
     type Output = Dec;
 
@@ -413,6 +654,7 @@ impl Mul<u128> for Dec {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<Amount> for Dec {
     type Output = Amount;
 
@@ -425,6 +667,7 @@ impl Mul<Amount> for Dec {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<Change> for Dec {
     type Output = Change;
 
@@ -436,6 +679,7 @@ impl Mul<Change> for Dec {
 }
 
 // TODO: is some checked arithmetic needed here to prevent overflows?
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<Dec> for Dec {
     type Output = Self;
 
@@ -445,6 +689,7 @@ impl Mul<Dec> for Dec {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Div<Dec> for Dec {
     type Output = Self;
 
@@ -548,6 +793,9 @@ pub mod testing {
 
 #[cfg(test)]
 mod test_dec {
+    use proptest::prelude::*;
+
+    use super::testing::arb_non_negative_dec;
     use super::*;
     use crate::types::token::{Amount, Change};
 
@@ -565,6 +813,7 @@ mod test_dec {
     }
 
     /// Fill in tests later
+    #[cfg(not(feature = "strict-arith"))]
     #[test]
     fn test_dec_basics() {
         assert_eq!(
@@ -641,6 +890,7 @@ mod test_dec {
     }
 
     /// Test the `Dec` and `Amount` interplay
+    #[cfg(not(feature = "strict-arith"))]
     #[test]
     fn test_dec_and_amount() {
         let amt = Amount::from(1018u64);
@@ -656,6 +906,43 @@ mod test_dec {
         debug_assert_eq!(dec * chg, Change::from(-2809i64));
     }
 
+    /// Test the checked arithmetic of `Dec`, `Amount` and `Change`
+    #[test]
+    fn test_checked_arithmetic() {
+        let dec = Dec::from_str("2.76").unwrap();
+        let half = Dec::from_str("0.5").unwrap();
+        let max = Dec(I256::maximum());
+
+        assert_eq!(
+            dec.checked_add(&half),
+            Some(Dec::from_str("3.26").unwrap())
+        );
+        assert!(max.checked_add(&half).is_none());
+        assert_eq!(
+            half.checked_signed_sub(&dec),
+            Some(Dec::from_str("-2.26").unwrap())
+        );
+        assert!((-max).checked_signed_sub(&dec).is_none());
+        assert!(half.checked_sub(&dec).is_none());
+        assert!(max.checked_sub(&-dec).is_none());
+        assert_eq!(half.abs_diff(&dec), Dec::from_str("2.26").unwrap());
+        assert_eq!(dec.abs_diff(&-half), Dec::from_str("3.26").unwrap());
+
+        let amt = Amount::from(1018u64);
+        assert_eq!(amt.checked_mul_dec(dec), Some(Amount::from(2809u64)));
+        assert!(amt.checked_mul_dec(-dec).is_none());
+        let chg: Change = -amt.change();
+        assert_eq!(
+            chg.checked_mul_uint(Uint::from(3)),
+            Some(Change::from(-3054i64))
+        );
+
+        // Scaling the integer to the precision of a `Dec` overflows
+        assert!(Dec::try_from(crate::types::uint::MAX_SIGNED_VALUE).is_err());
+        assert!(Dec::try_from(u128::MAX).is_ok());
+        assert_eq!(Dec::two().ceil(), I256::from(2));
+    }
+
     #[test]
     fn test_into() {
         assert_eq!(
@@ -759,4 +1046,154 @@ mod test_dec {
         let s = format!("{}", num);
         assert_eq!(s, String::from("14000"));
     }
+
+    /// Assert that two [`Dec`]s are equal up to the given relative and
+    /// absolute tolerances.
+    fn assert_close(actual: Dec, expected: Dec, relative: Dec, absolute: Dec) {
+        let tolerance = expected
+            .abs_diff(&Dec::zero())
+            .checked_mul(&relative)
+            .and_then(|tolerance| tolerance.checked_add(&absolute))
+            .unwrap();
+        assert!(
+            actual.abs_diff(&expected) <= tolerance,
+            "{actual} is not within {tolerance} of {expected}"
+        );
+    }
+
+    /// Test raising [`Dec`]s to integer powers and taking their square roots.
+    #[test]
+    fn test_dec_pow_and_sqrt() {
+        let dec = |s| Dec::from_str(s).expect("Test failed");
+        assert_eq!(Dec::two().checked_pow(10), Some(dec("1024")));
+        assert_eq!(dec("1.5").checked_pow(3), Some(dec("3.375")));
+        assert_eq!(dec("-0.5").checked_pow(3), Some(dec("-0.125")));
+        assert_eq!(dec("123.456").checked_pow(0), Some(Dec::one()));
+        assert_eq!(Dec::from(u64::MAX).checked_pow(4), None);
+
+        assert_eq!(dec("4").sqrt(), Some(Dec::two()));
+        assert_eq!(dec("2").sqrt(), Some(dec("1.414213562373")));
+        assert_eq!(dec("0.0001").sqrt(), Some(dec("0.01")));
+        assert_eq!(Dec::zero().sqrt(), Some(Dec::zero()));
+        assert_eq!(dec("-1").sqrt(), None);
+        // Too large to be scaled before taking the integer root
+        let large = Dec(I256(Uint::exp10(70)));
+        assert_eq!(large.sqrt(), Some(Dec(I256(Uint::exp10(41)))));
+    }
+
+    /// Test the logarithms and the exponentials of known values.
+    #[test]
+    fn test_dec_ln_and_exp() {
+        let dec = |s| Dec::from_str(s).expect("Test failed");
+        let ulps = |n| Dec::new(n, POS_DECIMAL_PRECISION).expect("Test failed");
+        assert_eq!(Dec::one().ln(), Some(Dec::zero()));
+        assert_eq!(Dec::zero().ln(), None);
+        assert_eq!(dec("-1").ln(), None);
+        assert_close(
+            Dec::two().ln().unwrap(),
+            dec("0.693147180559"),
+            Dec::zero(),
+            ulps(100),
+        );
+        assert_close(
+            dec("0.001").ln().unwrap(),
+            dec("-6.907755278982"),
+            Dec::zero(),
+            ulps(100),
+        );
+        assert_close(
+            dec("1000000").ln().unwrap(),
+            dec("13.815510557964"),
+            Dec::zero(),
+            ulps(100),
+        );
+
+        assert_eq!(Dec::zero().exp(), Some(Dec::one()));
+        assert_close(
+            Dec::one().exp().unwrap(),
+            dec("2.718281828459"),
+            ulps(100),
+            Dec::zero(),
+        );
+        assert_close(
+            dec("20").exp().unwrap(),
+            dec("485165195.409790277969"),
+            ulps(100),
+            Dec::zero(),
+        );
+        assert_close(
+            dec("-1").exp().unwrap(),
+            dec("0.367879441171"),
+            Dec::zero(),
+            ulps(10),
+        );
+        assert_eq!(dec("-30").exp(), Some(Dec::zero()));
+        assert_eq!(dec("200").exp(), None);
+    }
+
+    proptest! {
+        /// Test that the exponential is the inverse of the logarithm.
+        #[test]
+        fn test_dec_exp_of_ln(x in arb_non_negative_dec()) {
+            prop_assume!(!x.is_zero());
+            assert_close(
+                x.ln().unwrap().exp().unwrap(),
+                x,
+                Dec::new(1, 9).unwrap(),
+                Dec::new(1, 10).unwrap(),
+            );
+        }
+
+        /// Test that the logarithm of a product is the sum of the
+        /// logarithms.
+        #[test]
+        fn test_dec_ln_of_product(
+            x in arb_dec_in(1_000_000_000_000..1_000_000_000_000_000_000),
+            y in arb_dec_in(1_000_000_000_000..1_000_000_000_000_000_000),
+        ) {
+            let product = x.checked_mul(&y).unwrap();
+            let sum = x.ln().unwrap().checked_add(&y.ln().unwrap()).unwrap();
+            assert_close(
+                product.ln().unwrap(),
+                sum,
+                Dec::zero(),
+                Dec::new(1, 9).unwrap(),
+            );
+        }
+
+        /// Test that the square root is rounded down to the precision of a
+        /// [`Dec`].
+        #[test]
+        fn test_dec_sqrt_squared(x in arb_non_negative_dec()) {
+            let root = x.sqrt().unwrap();
+            let next = root
+                .checked_add(&Dec::new(1, POS_DECIMAL_PRECISION).unwrap())
+                .unwrap();
+            prop_assert!(root.checked_mul(&root).unwrap() <= x);
+            prop_assert!(next.checked_mul(&next).unwrap() >= x);
+        }
+
+        /// Test that raising to a power agrees with repeated
+        /// multiplications.
+        #[test]
+        fn test_dec_pow_of_products(
+            base in arb_dec_in(1_000_000_000_000..10_000_000_000_000),
+            exp in 0_u32..16,
+        ) {
+            let product = (0..exp).fold(Dec::one(), |product, _| {
+                product.checked_mul(&base).unwrap()
+            });
+            assert_close(
+                base.checked_pow(exp).unwrap(),
+                product,
+                Dec::new(2 * i128::from(exp), POS_DECIMAL_PRECISION).unwrap(),
+                Dec::new(1, POS_DECIMAL_PRECISION).unwrap(),
+            );
+        }
+    }
+
+    /// Generate a [`Dec`] from its raw value in the given range
+    fn arb_dec_in(raw: std::ops::Range<u64>) -> impl Strategy<Value = Dec> {
+        raw.prop_map(|raw| Dec(I256::from(raw)))
+    }
 }
@@ -0,0 +1,260 @@
+//! Golden vectors of the string representations of the user-facing types.
+//!
+//! Wallets and other clients depend on the `Display` and `FromStr` formats
+//! of amounts, addresses, keys and hashes, so any change to them must be
+//! deliberate. These tests render a fixed set of values and compare them
+//! against the fixture files in `test_fixtures/golden`. An intended change
+//! of format is recorded by re-running the tests with the
+//! `NAMADA_UPDATE_GOLDEN` env var set, which rewrites the fixture files for
+//! the change to show up as an explicit diff.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use pretty_assertions::assert_eq;
+
+use crate::types::address::{
+    Address, EstablishedAddress, ImplicitAddress, InternalAddress,
+};
+use crate::types::ethereum_events::EthAddress;
+use crate::types::hash::Hash;
+use crate::types::ibc::IbcTokenHash;
+use crate::types::key::{common, testing, PublicKeyHash, RefTo};
+use crate::types::token::{Amount, DenominatedAmount, Denomination};
+use crate::types::uint::{self, Uint};
+
+/// Env. var to rewrite the golden files instead of checking them
+const ENV_VAR_UPDATE_GOLDEN: &str = "NAMADA_UPDATE_GOLDEN";
+
+/// The separator of the fields of a golden line
+const SEP: &str = " | ";
+
+/// The maximum number of decimal places tested
+const MAX_DENOM: u8 = 77;
+
+/// Get the path of a golden file.
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../test_fixtures/golden")
+        .join(name)
+}
+
+/// Compare the rendered lines to the golden file with the given name, or
+/// rewrite the file if [`ENV_VAR_UPDATE_GOLDEN`] is set.
+fn check_golden(name: &str, lines: Vec<String>) {
+    let path = golden_path(name);
+    let rendered = lines.join("\n") + "\n";
+    if std::env::var(ENV_VAR_UPDATE_GOLDEN).is_ok() {
+        std::fs::write(&path, rendered).unwrap_or_else(|err| {
+            panic!("Failed to write the golden file {}: {err}", path.display())
+        });
+        return;
+    }
+    let golden = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!("Failed to read the golden file {}: {err}", path.display())
+    });
+    assert_eq!(
+        golden, rendered,
+        "The string format changed, re-run the tests with \
+         {ENV_VAR_UPDATE_GOLDEN} set if this is intended"
+    );
+}
+
+/// Render the result of parsing an amount.
+fn render_parsed_amount(string: &str) -> String {
+    match DenominatedAmount::from_str(string) {
+        Ok(amount) => amount.to_string_precise(),
+        Err(err) => format!("error: {err}"),
+    }
+}
+
+/// Test the formats of the amounts of every denomination, around the
+/// powers of ten and at the bounds of their range.
+#[test]
+fn test_golden_amounts() {
+    let mut lines = vec![];
+    for denom in 0..=MAX_DENOM {
+        let unit = Uint::exp10(denom as usize);
+        let cases = [
+            ("zero", Uint::zero()),
+            ("one", Uint::one()),
+            ("unit_minus_one", unit - Uint::one()),
+            ("unit", unit),
+            ("unit_plus_one", unit + Uint::one()),
+            ("max", uint::MAX_VALUE),
+        ];
+        for (label, raw) in cases {
+            let amount = DenominatedAmount::new(
+                Amount::from_uint(raw, 0u8).unwrap(),
+                Denomination(denom),
+            );
+            let display = amount.to_string();
+            let precise = amount.to_string_precise();
+
+            // Every value that can be parsed back must round-trip
+            if let Ok(parsed) = DenominatedAmount::from_str(&precise) {
+                assert_eq!(parsed, amount);
+            }
+            if let Ok(parsed) = DenominatedAmount::from_str(&display) {
+                assert_eq!(parsed.canonical(), amount.canonical());
+            }
+
+            lines.push(
+                [
+                    format!("denom={denom} {label}"),
+                    format!("display={display}"),
+                    format!("precise={precise}"),
+                    format!(
+                        "parsed_display={}",
+                        render_parsed_amount(&display)
+                    ),
+                    format!(
+                        "parsed_precise={}",
+                        render_parsed_amount(&precise)
+                    ),
+                ]
+                .join(SEP),
+            );
+        }
+    }
+    check_golden("amounts.txt", lines);
+}
+
+/// Test the formats of the addresses of every kind.
+#[test]
+fn test_golden_addresses() {
+    let bytes: [u8; 20] = std::array::from_fn(|i| i as u8 + 1);
+    let cases = [
+        ("pos", Address::Internal(InternalAddress::PoS)),
+        (
+            "pos_slash_pool",
+            Address::Internal(InternalAddress::PosSlashPool),
+        ),
+        ("parameters", Address::Internal(InternalAddress::Parameters)),
+        ("governance", Address::Internal(InternalAddress::Governance)),
+        ("ibc", Address::Internal(InternalAddress::Ibc)),
+        (
+            "ibc_token",
+            Address::Internal(InternalAddress::IbcToken(IbcTokenHash(bytes))),
+        ),
+        ("eth_bridge", Address::Internal(InternalAddress::EthBridge)),
+        (
+            "eth_bridge_pool",
+            Address::Internal(InternalAddress::EthBridgePool),
+        ),
+        (
+            "erc20",
+            Address::Internal(InternalAddress::Erc20(EthAddress(bytes))),
+        ),
+        (
+            "nut",
+            Address::Internal(InternalAddress::Nut(EthAddress(bytes))),
+        ),
+        ("multitoken", Address::Internal(InternalAddress::Multitoken)),
+        ("pgf", Address::Internal(InternalAddress::Pgf)),
+        ("masp", Address::Internal(InternalAddress::Masp)),
+        ("fee_escrow", Address::Internal(InternalAddress::FeeEscrow)),
+        (
+            "name_service",
+            Address::Internal(InternalAddress::NameService),
+        ),
+        ("nft", Address::Internal(InternalAddress::Nft)),
+        (
+            "token_factory",
+            Address::Internal(InternalAddress::TokenFactory),
+        ),
+        (
+            "established_zero",
+            Address::Established(EstablishedAddress::from([0; 20])),
+        ),
+        (
+            "established_max",
+            Address::Established(EstablishedAddress::from([0xff; 20])),
+        ),
+        (
+            "established",
+            Address::Established(EstablishedAddress::from(bytes)),
+        ),
+        (
+            "implicit_zero",
+            Address::Implicit(ImplicitAddress(PublicKeyHash([0; 20]))),
+        ),
+        (
+            "implicit",
+            Address::Implicit(ImplicitAddress(PublicKeyHash(bytes))),
+        ),
+    ];
+    let lines = cases
+        .into_iter()
+        .map(|(label, address)| {
+            let encoded = address.to_string();
+            assert_eq!(Address::from_str(&encoded).unwrap(), address);
+            assert_eq!(Address::decode(&encoded).unwrap(), address);
+            [label.to_string(), encoded, address.to_pretty_string()].join(SEP)
+        })
+        .collect();
+    check_golden("addresses.txt", lines);
+}
+
+/// Test the formats of the keys, their hashes and implicit addresses.
+#[test]
+fn test_golden_keys() {
+    let cases = [
+        ("ed25519_1", testing::keypair_1()),
+        ("ed25519_2", testing::keypair_2()),
+        ("secp256k1_3", testing::keypair_3()),
+        ("secp256k1_4", testing::keypair_4()),
+    ];
+    let lines = cases
+        .into_iter()
+        .map(|(label, sk)| {
+            let pk = sk.ref_to();
+            let pkh = PublicKeyHash::from(&pk);
+            let address = Address::from(&pk);
+            let (sk_str, pk_str, pkh_str, address_str) = (
+                sk.to_string(),
+                pk.to_string(),
+                pkh.to_string(),
+                address.to_string(),
+            );
+            assert_eq!(
+                common::SecretKey::from_str(&sk_str).unwrap().to_string(),
+                sk_str
+            );
+            assert_eq!(common::PublicKey::from_str(&pk_str).unwrap(), pk);
+            assert_eq!(PublicKeyHash::from_str(&pkh_str).unwrap(), pkh);
+            assert_eq!(Address::from_str(&address_str).unwrap(), address);
+            [
+                label.to_string(),
+                format!("sk={sk_str}"),
+                format!("pk={pk_str}"),
+                format!("pkh={pkh_str}"),
+                format!("address={address_str}"),
+            ]
+            .join(SEP)
+        })
+        .collect();
+    check_golden("keys.txt", lines);
+}
+
+/// Test the formats of the hashes, as used for the txs and the blocks.
+#[test]
+fn test_golden_hashes() {
+    let cases = [
+        ("zero", Hash::zero()),
+        ("max", Hash([0xff; 32])),
+        ("sha256_empty", Hash::sha256(b"")),
+        ("sha256_namada", Hash::sha256(b"namada")),
+    ];
+    let lines = cases
+        .into_iter()
+        .map(|(label, hash)| {
+            let encoded = hash.to_string();
+            assert_eq!(Hash::from_str(&encoded).unwrap(), hash);
+            // Lowercase hashes are accepted too
+            assert_eq!(Hash::from_str(&encoded.to_lowercase()).unwrap(), hash);
+            [label.to_string(), encoded].join(SEP)
+        })
+        .collect();
+    check_golden("hashes.txt", lines);
+}
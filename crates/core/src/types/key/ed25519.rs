@@ -13,12 +13,14 @@ use data_encoding::HEXLOWER;
 #[cfg(any(test, feature = "rand"))]
 use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use super::{
     ParsePublicKeyError, ParseSecretKeyError, ParseSignatureError, RefTo,
     SchemeType, SigScheme as SigSchemeTrait, SignableBytes, VerifySigError,
 };
+use crate::types::hash::Sha256Hasher;
 use crate::types::key::StorageHasher;
 
 const PUBLIC_KEY_LENGTH: usize = 32;
@@ -384,3 +386,104 @@ impl super::SigScheme for SigScheme {
             .map_err(|err| VerifySigError::SigVerifyError(err.to_string()))
     }
 }
+
+/// A batch of Ed25519 signatures over the same data, verified together in a
+/// single pass. This is faster than verifying the signatures one by one,
+/// and the batch only verifies if all its signatures do.
+#[derive(Debug, Default)]
+pub struct BatchVerifier {
+    signatures: Vec<(ed25519_consensus::VerificationKeyBytes, Signature)>,
+}
+
+impl BatchVerifier {
+    /// Add the signature of the given public key to the batch
+    pub fn queue(&mut self, pk: &PublicKey, sig: &Signature) {
+        self.signatures.push((pk.0.into(), sig.clone()));
+    }
+
+    /// The number of signatures in the batch
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    /// Check if the batch has no signatures
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Check that all the signatures of the batch are valid signatures of
+    /// the given data, using a SHA256 hasher.
+    pub fn verify(
+        self,
+        data: &impl SignableBytes,
+    ) -> Result<(), VerifySigError> {
+        self.verify_with_hasher::<Sha256Hasher>(data)
+    }
+
+    /// Check that all the signatures of the batch are valid signatures of
+    /// the given data, using the given hasher.
+    pub fn verify_with_hasher<H>(
+        self,
+        data: &impl SignableBytes,
+    ) -> Result<(), VerifySigError>
+    where
+        H: 'static + StorageHasher,
+    {
+        let msg = data.signable_hash::<H>();
+        let mut verifier = ed25519_consensus::batch::Verifier::new();
+        // The random coefficients of the batch equation are derived from the
+        // whole batch, so that they are deterministic but can't be known
+        // before the signatures are fixed
+        let mut transcript = Sha256::new();
+        transcript.update(msg);
+        for (pk, sig) in self.signatures {
+            transcript.update(pk.as_ref());
+            transcript.update(sig.0.to_bytes());
+            verifier.queue((pk, sig.0, &msg));
+        }
+        let rng = TranscriptRng {
+            seed: transcript.finalize().into(),
+            counter: 0,
+        };
+        verifier
+            .verify(rng)
+            .map_err(|err| VerifySigError::SigVerifyError(err.to_string()))
+    }
+}
+
+/// A random number generator expanding the hash of a transcript
+struct TranscriptRng {
+    seed: [u8; 32],
+    counter: u64,
+}
+
+impl rand_core::RngCore for TranscriptRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(32) {
+            let block = Sha256::new()
+                .chain_update(self.seed)
+                .chain_update(self.counter.to_le_bytes())
+                .finalize();
+            self.counter += 1;
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        dest: &mut [u8],
+    ) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl rand_core::CryptoRng for TranscriptRng {}
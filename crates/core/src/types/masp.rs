@@ -89,6 +89,74 @@ pub fn encode_asset_type(
     .encode()
 }
 
+/// Make the asset type of the given digit of the token, timestamped with the
+/// given epoch. Tokens that earn MASP rewards use the epoched asset types.
+pub fn epoched_asset_type(
+    token: &Address,
+    denom: Denomination,
+    position: MaspDigitPos,
+    epoch: Epoch,
+) -> Result<AssetType, std::io::Error> {
+    encode_asset_type(token.clone(), denom, position, Some(epoch))
+}
+
+/// Make the asset type of the given digit of the token without an epoch.
+/// Tokens that don't earn MASP rewards use the unepoched asset types.
+pub fn unepoched_asset_type(
+    token: &Address,
+    denom: Denomination,
+    position: MaspDigitPos,
+) -> Result<AssetType, std::io::Error> {
+    encode_asset_type(token.clone(), denom, position, None)
+}
+
+/// Make the asset types of the four digits of the token, in the order of
+/// [`MaspDigitPos::iter`]
+pub fn digit_asset_types(
+    token: &Address,
+    denom: Denomination,
+    epoch: Option<Epoch>,
+) -> Result<[AssetType; 4], std::io::Error> {
+    let asset_types = MaspDigitPos::iter()
+        .map(|position| {
+            encode_asset_type(token.clone(), denom, position, epoch)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(asset_types
+        .try_into()
+        .expect("there must be exactly 4 digits"))
+}
+
+/// Derive the asset type that the MASP VP accepts for the given digit of the
+/// token in a tx applied at the given epoch. This is the asset type
+/// timestamped with the epoch if the conversion state contains it, as
+/// determined by `has_conversion`, and the unepoched one otherwise.
+///
+/// The epoch must be the one of the block that applies the tx, not the epoch
+/// in which the tx is built. At an epoch rollover, the conversion state gains
+/// the asset types of the new epoch, so an asset type derived at the previous
+/// epoch is rejected for the tokens that earn MASP rewards.
+pub fn derive_asset_type(
+    token: &Address,
+    denom: Denomination,
+    position: MaspDigitPos,
+    epoch: Epoch,
+    has_conversion: impl FnOnce(&AssetType) -> bool,
+) -> Result<(AssetType, AssetData), std::io::Error> {
+    let mut asset_data = AssetData {
+        token: token.clone(),
+        denom,
+        position,
+        epoch: Some(epoch),
+    };
+    let asset_type = asset_data.encode()?;
+    if has_conversion(&asset_type) {
+        return Ok((asset_type, asset_data));
+    }
+    asset_data.undate();
+    Ok((asset_data.encode()?, asset_data))
+}
+
 // enough capacity to store the payment address
 // plus the pinned/unpinned discriminant
 const PAYMENT_ADDRESS_SIZE: usize = 43 + 1;
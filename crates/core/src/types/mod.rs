@@ -1,35 +1,64 @@
 //! Types definitions.
 
+#[cfg(feature = "std")]
 pub mod account;
+#[cfg(feature = "std")]
 pub mod address;
+#[cfg(feature = "std")]
 pub mod chain;
+#[cfg(feature = "std")]
 pub mod dec;
+#[cfg(feature = "std")]
 pub mod eth_abi;
+#[cfg(feature = "std")]
 pub mod eth_bridge_pool;
+#[cfg(feature = "std")]
 pub mod ethereum_events;
+#[cfg(feature = "std")]
 pub mod ethereum_structs;
+#[cfg(test)]
+mod golden_tests;
+#[cfg(feature = "std")]
 pub mod hash;
+#[cfg(feature = "std")]
 pub mod ibc;
+#[cfg(feature = "std")]
 pub mod internal;
+#[cfg(feature = "std")]
 pub mod keccak;
+#[cfg(feature = "std")]
 pub mod key;
+#[cfg(feature = "std")]
 pub mod masp;
+#[cfg(feature = "std")]
 pub mod parameters;
+#[cfg(feature = "std")]
+pub mod secret;
+#[cfg(feature = "std")]
 pub mod sign;
+#[cfg(feature = "std")]
 pub mod storage;
+#[cfg(feature = "std")]
 pub mod string_encoding;
+#[cfg(feature = "std")]
 pub mod time;
 pub mod token;
 pub mod uint;
+#[cfg(feature = "std")]
 pub mod validity_predicate;
+#[cfg(feature = "std")]
 pub mod voting_power;
 
+#[cfg(feature = "std")]
 use borsh_ext::BorshSerializeExt;
+#[cfg(feature = "std")]
 use thiserror::Error;
 
+#[cfg(feature = "std")]
 use crate::borsh::{BorshDeserialize, BorshSerialize};
 
 #[allow(missing_docs)]
+#[cfg(feature = "std")]
 #[derive(Error, Debug)]
 pub enum DecodeError {
     #[error("Deserialization error: {0}")]
@@ -37,6 +66,7 @@ pub enum DecodeError {
 }
 
 /// Encode a value with borsh
+#[cfg(feature = "std")]
 pub fn encode<T>(value: &T) -> Vec<u8>
 where
     T: BorshSerialize,
@@ -45,6 +75,7 @@ where
 }
 
 /// Decode a value with borsh
+#[cfg(feature = "std")]
 pub fn decode<T>(bytes: impl AsRef<[u8]>) -> Result<T, DecodeError>
 where
     T: BorshDeserialize,
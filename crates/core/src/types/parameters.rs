@@ -54,6 +54,16 @@ pub struct Parameters {
     pub fee_unshielding_descriptions_limit: u64,
     /// Map of the cost per gas unit for every token allowed for fee payment
     pub minimum_gas_price: BTreeMap<Address, token::Amount>,
+    /// Max amount of native tokens minted by the protocol per epoch, across
+    /// all the sources of inflation. `None` for no limit.
+    pub max_native_mint_per_epoch: Option<token::Amount>,
+    /// Number of epochs that the conversions of the older MASP asset types
+    /// are kept in the conversion state, before they're archived. At least
+    /// the previous epoch is kept. `None` to keep them all.
+    pub masp_conversion_retention_epochs: Option<u64>,
+    /// Max gas of a transaction, capped by the max block gas. `None` to only
+    /// be limited by the max block gas.
+    pub max_tx_gas: Option<u64>,
 }
 
 /// Epoch duration. A new epoch begins as soon as both the `min_num_of_blocks`
@@ -0,0 +1,97 @@
+//! Secret material, i.e. the bytes of private keys and seeds.
+//!
+//! The secret bytes are zeroized when dropped and they don't implement
+//! `Debug` nor `Display`, so that they can't end up in logs or error
+//! messages. They are compared in constant time, to not leak their content
+//! through timing.
+
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The bytes of a private key or seed
+#[derive(Clone, Default, Zeroize, ZeroizeOnDrop)]
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    /// Take ownership of the given secret bytes
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Expose the secret bytes. The returned slice must not be copied into
+    /// a buffer that isn't zeroized.
+    pub fn expose(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The number of secret bytes
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no secret bytes
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<&[u8]> for SecretBytes {
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(bytes.to_vec())
+    }
+}
+
+impl PartialEq for SecretBytes {
+    fn eq(&self, other: &Self) -> bool {
+        ct_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SecretBytes {}
+
+/// Compare secret bytes in constant time. Only the lengths of the bytes may
+/// be leaked.
+pub fn ct_eq(a: impl AsRef<[u8]>, b: impl AsRef<[u8]>) -> bool {
+    a.as_ref().ct_eq(b.as_ref()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_bytes_eq() {
+        let secret = SecretBytes::new(vec![1, 2, 3]);
+        assert!(secret == SecretBytes::from(&[1, 2, 3][..]));
+        assert!(secret != SecretBytes::new(vec![1, 2, 4]));
+        assert!(secret != SecretBytes::new(vec![1, 2]));
+        assert!(ct_eq("passphrase", "passphrase"));
+        assert!(!ct_eq("passphrase", "passphrasE"));
+    }
+
+    #[test]
+    fn test_secret_bytes_zeroize() {
+        let mut secret = SecretBytes::new(vec![1, 2, 3]);
+        secret.zeroize();
+        assert!(secret.is_empty());
+    }
+}
+
+/// Compile-time assertions that the secret types can't be printed, enabled
+/// with the `secrets-audit` feature
+#[cfg(all(test, feature = "secrets-audit"))]
+mod audit {
+    use std::fmt::{Debug, Display};
+
+    use static_assertions::assert_not_impl_any;
+
+    use super::*;
+
+    assert_not_impl_any!(SecretBytes: Debug, Display);
+}
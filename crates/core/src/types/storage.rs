@@ -22,6 +22,7 @@ use crate::types::address::{self, Address};
 use crate::types::ethereum_events::{GetEventNonce, TransfersToNamada, Uint};
 use crate::types::hash::Hash;
 use crate::types::keccak::{KeccakHash, TryFromError};
+use crate::types::string_encoding;
 use crate::types::time::DateTimeUtc;
 
 /// The maximum size of an IBC key (in bytes) allowed in merkle-ized storage
@@ -34,6 +35,8 @@ pub enum Error {
     ParseAddress(address::DecodeError),
     #[error("Error parsing address from a storage key")]
     ParseAddressFromKey,
+    #[error("The address of a storage key must be in lowercase: {0}")]
+    NonCanonicalAddress(String),
     #[error("Reserved prefix or string is specified: {0}")]
     InvalidKeySeg(String),
     #[error("Error parsing key segment: {0}")]
@@ -62,6 +65,12 @@ pub const EPOCH_TYPE_LENGTH: usize = 8;
 pub const KEY_SEGMENT_SEPARATOR: char = '/';
 /// The reserved storage key prefix for addresses
 pub const RESERVED_ADDRESS_PREFIX: char = '#';
+/// The reserved storage key prefix for fixed-width addresses
+pub const RESERVED_FIXED_ADDRESS_PREFIX: char = '$';
+/// The length of a fixed-width address key segment, with its prefix and the
+/// base32hex encoding of the raw bytes of the address
+pub const FIXED_ADDRESS_SEG_LEN: usize =
+    1 + (address::ADDRESS_RAW_LEN * 8 + 4) / 5;
 /// The reserved storage key prefix for validity predicates
 pub const VP_KEY_PREFIX: char = '?';
 /// The reserved storage key for validity predicates
@@ -76,6 +85,9 @@ pub const WASM_CODE_NAME_PREFIX: &str = "name";
 pub const WASM_CODE_LEN_PREFIX: &str = "len";
 /// The reserved storage key prefix for wasm code hashes
 pub const WASM_HASH_PREFIX: &str = "hash";
+/// The reserved storage key prefix for the hashes of the instrumented wasm
+/// codes
+pub const WASM_INSTRUMENTED_HASH_PREFIX: &str = "instrumented";
 
 /// Transaction index within block.
 #[derive(
@@ -312,6 +324,61 @@ impl BlockHeight {
     pub fn prev_height(&self) -> BlockHeight {
         BlockHeight(self.0 - 1)
     }
+
+    /// Get the height of the previous block, returning `None` if the given
+    /// height is the sentinel height `0`.
+    pub fn checked_prev_height(&self) -> Option<BlockHeight> {
+        self.checked_sub(1)
+    }
+
+    /// Checked height addition. Computes self + rhs, returning None if
+    /// overflow occurred.
+    #[must_use = "this returns the result of the operation, without modifying \
+                  the original"]
+    pub fn checked_add(self, rhs: u64) -> Option<Self> {
+        self.0.checked_add(rhs).map(Self)
+    }
+
+    /// Saturating height addition. Computes self + rhs, saturating at the
+    /// maximum height.
+    #[must_use = "this returns the result of the operation, without modifying \
+                  the original"]
+    pub fn saturating_add(self, rhs: u64) -> Self {
+        Self(self.0.saturating_add(rhs))
+    }
+
+    /// Checked height subtraction. Computes self - rhs, returning None if
+    /// overflow occurred.
+    #[must_use = "this returns the result of the operation, without modifying \
+                  the original"]
+    pub fn checked_sub(self, rhs: u64) -> Option<Self> {
+        self.0.checked_sub(rhs).map(Self)
+    }
+
+    /// Saturating height subtraction. Computes self - rhs, saturating at the
+    /// sentinel height `0`.
+    #[must_use = "this returns the result of the operation, without modifying \
+                  the original"]
+    pub fn saturating_sub(self, rhs: u64) -> Self {
+        Self(self.0.saturating_sub(rhs))
+    }
+
+    /// Iterate a range of consecutive heights starting from `self` of a given
+    /// length. The range stops at the maximum height.
+    pub fn iter_range(
+        self,
+        len: u64,
+    ) -> impl DoubleEndedIterator<Item = BlockHeight> + Clone {
+        (self.0..self.0.saturating_add(len)).map(BlockHeight)
+    }
+
+    /// Iterate a range of heights, inclusive of the start and end.
+    pub fn iter_bounds_inclusive(
+        start: Self,
+        end: Self,
+    ) -> impl DoubleEndedIterator<Item = BlockHeight> + Clone {
+        (start.0..=end.0).map(BlockHeight)
+    }
 }
 
 impl TryFrom<&[u8]> for BlockHash {
@@ -583,7 +650,9 @@ impl Key {
         &'this self,
     ) -> impl Iterator<Item = &'_ Address> + 'k {
         self.segments.iter().filter_map(|s| match s {
-            DbKeySeg::AddressSeg(addr) => Some(addr),
+            DbKeySeg::AddressSeg(addr) | DbKeySeg::FixedAddressSeg(addr) => {
+                Some(addr)
+            }
             _ => None,
         })
     }
@@ -647,6 +716,18 @@ impl Key {
         Key { segments }
     }
 
+    /// Returns a key of the hash of the wasm code of the given hash, once
+    /// instrumented with the gas meter and stack limiter
+    pub fn wasm_instrumented_hash(code_hash: &Hash) -> Self {
+        let mut segments =
+            Self::from(WASM_KEY_PREFIX.to_owned().to_db_key()).segments;
+        segments.push(DbKeySeg::StringSeg(
+            WASM_INSTRUMENTED_HASH_PREFIX.to_owned(),
+        ));
+        segments.push(DbKeySeg::StringSeg(code_hash.to_string()));
+        Key { segments }
+    }
+
     /// Returns a key of the wasm code hash of the given code path
     pub fn wasm_hash(code_path: impl AsRef<str>) -> Self {
         let mut segments =
@@ -774,6 +855,180 @@ impl KeyRef<'_> {
     }
 }
 
+/// Check that a constant string is a valid key segment that's kept as a
+/// string segment, i.e. that it has no separator and no reserved address
+/// prefix. The constant segments of the [`key!`] and [`define_key!`] macros
+/// are checked with it at compile time.
+///
+/// [`key!`]: crate::key
+/// [`define_key!`]: crate::define_key
+pub const fn is_valid_const_key_seg(seg: &str) -> bool {
+    let bytes = seg.as_bytes();
+    if !bytes.is_empty()
+        && (bytes[0] == RESERVED_ADDRESS_PREFIX as u8
+            || bytes[0] == RESERVED_FIXED_ADDRESS_PREFIX as u8)
+    {
+        return false;
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == KEY_SEGMENT_SEPARATOR as u8 {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Build a storage [`Key`] from its segments separated by `/`, e.g.
+/// `key!(ADDRESS / "balance" / owner)`. A segment is one of:
+///
+/// - a string literal, e.g. `"balance"`, or a constant string in brackets, e.g.
+///   `[Keys::VALUES.balance]`, which are checked at compile time to be valid
+///   key segments
+/// - an identifier or a parenthesized expression of a value implementing
+///   [`KeySeg`], which is pushed as with [`Key::push`]
+#[macro_export]
+macro_rules! key {
+    ($($seg:tt)/+) => {
+        $crate::types::storage::Key {
+            segments: ::std::vec![$($crate::key!(@seg $seg)),+],
+        }
+    };
+    (@seg $seg:literal) => {{
+        const _: () = ::std::assert!(
+            $crate::types::storage::is_valid_const_key_seg($seg),
+            "Invalid constant key segment"
+        );
+        $crate::types::storage::DbKeySeg::StringSeg(
+            ::std::string::String::from($seg),
+        )
+    }};
+    (@seg [$seg:expr]) => {{
+        const SEG: &str = $seg;
+        const _: () = ::std::assert!(
+            $crate::types::storage::is_valid_const_key_seg(SEG),
+            "Invalid constant key segment"
+        );
+        $crate::types::storage::DbKeySeg::StringSeg(
+            ::std::string::String::from(SEG),
+        )
+    }};
+    (@seg $seg:tt) => {
+        $crate::types::storage::DbKeySeg::parse(
+            $crate::types::storage::KeySeg::raw(&$seg),
+        )
+        .expect("Cannot obtain a storage key")
+    };
+}
+
+/// Define a function building a storage key and a function parsing it back,
+/// from the segments of the key, as with [`key!`]. The parameters of the key
+/// are the segments in braces, e.g. `{owner: Address}`, of types implementing
+/// [`KeySeg`]. The builder takes them by reference and the parser returns them
+/// in a tuple if the key matches all the segments:
+///
+/// ```
+/// use namada_core::define_key;
+/// use namada_core::types::address::{self, Address};
+/// use namada_core::types::storage::{Key, KeySeg};
+///
+/// const ADDRESS: Address = address::MASP;
+///
+/// define_key! {
+///     /// Get the key of the note of an owner
+///     pub fn note_key;
+///     /// Get the owner of a note key
+///     pub fn parse_note_key;
+///     ADDRESS / "note" / {owner: Address}
+/// }
+///
+/// let owner =
+///     Address::decode("tnam1q8j5s6xp55p05yznwnftkv3kr9gjtsw3nq7x6tw5")
+///         .unwrap();
+/// let key = note_key(&owner);
+/// assert_eq!(key.to_string(), format!("#{}/note/#{}", ADDRESS, owner));
+/// assert_eq!(parse_note_key(&key), Some((owner,)));
+/// assert_eq!(parse_note_key(&Key::from(ADDRESS.to_db_key())), None);
+/// ```
+///
+/// [`key!`]: crate::key
+#[macro_export]
+macro_rules! define_key {
+    (
+        $(#[$build_attr:meta])*
+        $build_vis:vis fn $build:ident;
+        $(#[$parse_attr:meta])*
+        $parse_vis:vis fn $parse:ident;
+        $($seg:tt)/+
+    ) => {
+        $crate::define_key!(
+            @params
+            [$(#[$build_attr])* $build_vis fn $build]
+            [$(#[$parse_attr])* $parse_vis fn $parse]
+            []
+            [$($seg)/+]
+            $(/ $seg)+
+        );
+    };
+    // Collect the parameters of the key
+    (
+        @params $build:tt $parse:tt [$($params:tt)*] $segs:tt
+        / { $name:ident : $ty:ty } $($rest:tt)*
+    ) => {
+        $crate::define_key!(
+            @params $build $parse [$($params)* ($name : $ty)] $segs $($rest)*
+        );
+    };
+    (
+        @params $build:tt $parse:tt $params:tt $segs:tt
+        / $seg:tt $($rest:tt)*
+    ) => {
+        $crate::define_key!(@params $build $parse $params $segs $($rest)*);
+    };
+    (
+        @params
+        [$($build:tt)*]
+        [$($parse:tt)*]
+        [$(($name:ident : $ty:ty))*]
+        [$($seg:tt)/+]
+    ) => {
+        $($build)* ($($name: &$ty),*) -> $crate::types::storage::Key {
+            $crate::types::storage::Key {
+                segments: ::std::vec![$($crate::define_key!(@build $seg)),+],
+            }
+        }
+
+        $($parse)* (
+            key: &$crate::types::storage::Key,
+        ) -> ::std::option::Option<($($ty,)*)> {
+            let mut segments = key.segments.iter();
+            $($crate::define_key!(@parse segments $seg);)+
+            if segments.next().is_some() {
+                return ::std::option::Option::None;
+            }
+            ::std::option::Option::Some(($($name,)*))
+        }
+    };
+    (@build { $name:ident : $ty:ty }) => {
+        $crate::key!(@seg (*$name))
+    };
+    (@build $seg:tt) => {
+        $crate::key!(@seg $seg)
+    };
+    (@parse $segments:ident { $name:ident : $ty:ty }) => {
+        let $name: $ty = $crate::types::storage::KeySeg::parse(
+            $crate::types::storage::KeySeg::raw($segments.next()?),
+        )
+        .ok()?;
+    };
+    (@parse $segments:ident $seg:tt) => {
+        if *$segments.next()? != $crate::key!(@seg $seg) {
+            return ::std::option::Option::None;
+        }
+    };
+}
+
 // TODO use std::convert::{TryFrom, Into}?
 /// Represents a segment in a path that may be used as a database key
 pub trait KeySeg {
@@ -810,6 +1065,10 @@ pub enum DbKeySeg {
     AddressSeg(Address),
     /// Any other key segment
     StringSeg(String),
+    /// A segment made of the raw bytes of an address, of the same width for
+    /// all the addresses. It's only used by the keys of the storage prefixes
+    /// that opt into [`FixedAddress`].
+    FixedAddressSeg(Address),
 }
 
 impl KeySeg for DbKeySeg {
@@ -822,9 +1081,12 @@ impl KeySeg for DbKeySeg {
             // address hashes are prefixed with `'#'`
             Some(c) if c == RESERVED_ADDRESS_PREFIX => {
                 let _ = string.remove(0);
-                Address::decode(&string)
-                    .map_err(Error::ParseAddress)
-                    .map(DbKeySeg::AddressSeg)
+                parse_address_seg(&string).map(DbKeySeg::AddressSeg)
+            }
+            // fixed-width addresses are prefixed with `'$'`
+            Some(c) if c == RESERVED_FIXED_ADDRESS_PREFIX => {
+                let _ = string.remove(0);
+                parse_fixed_address_seg(&string).map(DbKeySeg::FixedAddressSeg)
             }
             _ => Ok(DbKeySeg::StringSeg(string)),
         }
@@ -836,6 +1098,12 @@ impl KeySeg for DbKeySeg {
                 format!("{}{}", RESERVED_ADDRESS_PREFIX, addr.encode())
             }
             DbKeySeg::StringSeg(seg) => seg.to_owned(),
+            DbKeySeg::FixedAddressSeg(addr) => format!(
+                "{}{}",
+                RESERVED_FIXED_ADDRESS_PREFIX,
+                BASE32HEX_NOPAD
+                    .encode(&string_encoding::Format::to_bytes(addr))
+            ),
         }
     }
 
@@ -883,7 +1151,7 @@ impl KeySeg for Address {
         match seg.chars().next() {
             Some(c) if c == RESERVED_ADDRESS_PREFIX => {
                 let _ = seg.remove(0);
-                Address::decode(seg).map_err(Error::ParseAddress)
+                parse_address_seg(&seg)
             }
             _ => Err(Error::ParseAddressFromKey),
         }
@@ -898,6 +1166,59 @@ impl KeySeg for Address {
     }
 }
 
+/// Parse the Bech32m address of an address key segment without its prefix.
+/// Only the lowercase encoding is accepted, so that the segment of an address
+/// is unique.
+fn parse_address_seg(seg: &str) -> Result<Address> {
+    if seg.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(Error::NonCanonicalAddress(seg.to_owned()));
+    }
+    Address::decode(seg).map_err(Error::ParseAddress)
+}
+
+/// Parse the raw bytes of a fixed-width address key segment without its
+/// prefix
+fn parse_fixed_address_seg(seg: &str) -> Result<Address> {
+    let bytes = BASE32HEX_NOPAD.decode(seg.as_bytes()).map_err(|err| {
+        Error::ParseKeySeg(format!(
+            "Failed parsing the fixed-width address {seg} with {err}"
+        ))
+    })?;
+    <Address as string_encoding::Format>::decode_bytes(&bytes)
+        .map_err(Error::ParseAddress)
+}
+
+/// An address in a fixed-width key segment of [`FIXED_ADDRESS_SEG_LEN`]
+/// characters, made of the base32hex encoding of its raw bytes. It's shorter
+/// than the Bech32m segment of an address and it has no checksum. The order
+/// of the segments is the order of the raw bytes, so that the addresses of a
+/// kind share the prefix of their segments.
+///
+/// The keys of the existing storage prefixes keep their Bech32m segments. The
+/// new storage prefixes indexed by addresses should use this segment instead.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FixedAddress(pub Address);
+
+impl KeySeg for FixedAddress {
+    fn parse(mut seg: String) -> Result<Self> {
+        match seg.chars().next() {
+            Some(c) if c == RESERVED_FIXED_ADDRESS_PREFIX => {
+                let _ = seg.remove(0);
+                parse_fixed_address_seg(&seg).map(Self)
+            }
+            _ => Err(Error::ParseAddressFromKey),
+        }
+    }
+
+    fn raw(&self) -> String {
+        self.to_db_key().raw()
+    }
+
+    fn to_db_key(&self) -> DbKeySeg {
+        DbKeySeg::FixedAddressSeg(self.0.clone())
+    }
+}
+
 impl KeySeg for Hash {
     fn parse(seg: String) -> Result<Self> {
         seg.try_into().map_err(|e: crate::types::hash::Error| {
@@ -1073,11 +1394,20 @@ impl Epoch {
         Self(self.0 - 1)
     }
 
+    /// Change to the previous epoch, returning `None` if the given epoch is
+    /// `0`.
+    pub fn checked_prev(&self) -> Option<Self> {
+        self.checked_sub(1)
+    }
+
     /// Iterate a range of consecutive epochs starting from `self` of a given
-    /// length. Work-around for `Step` implementation pending on stabilization of <https://github.com/rust-lang/rust/issues/42168>.
-    pub fn iter_range(self, len: u64) -> impl Iterator<Item = Epoch> + Clone {
+    /// length. The range stops at the maximum epoch. Work-around for `Step` implementation pending on stabilization of <https://github.com/rust-lang/rust/issues/42168>.
+    pub fn iter_range(
+        self,
+        len: impl Into<EpochOffset>,
+    ) -> impl DoubleEndedIterator<Item = Epoch> + Clone {
         let start_ix: u64 = self.into();
-        let end_ix: u64 = start_ix + len;
+        let end_ix: u64 = self.saturating_add(len).into();
         (start_ix..end_ix).map(Epoch::from)
     }
 
@@ -1085,23 +1415,54 @@ impl Epoch {
     pub fn iter_bounds_inclusive(
         start: Self,
         end: Self,
-    ) -> impl Iterator<Item = Epoch> + Clone {
+    ) -> impl DoubleEndedIterator<Item = Epoch> + Clone {
         let start_ix = start.0;
         let end_ix = end.0;
         (start_ix..=end_ix).map(Epoch::from)
     }
 
+    /// Checked epoch addition. Computes self + rhs, returning None if
+    /// overflow occurred.
+    #[must_use = "this returns the result of the operation, without modifying \
+                  the original"]
+    pub fn checked_add(self, rhs: impl Into<EpochOffset>) -> Option<Self> {
+        let EpochOffset(rhs) = rhs.into();
+        self.0.checked_add(rhs).map(Self)
+    }
+
+    /// Saturating epoch addition. Computes self + rhs, saturating at the
+    /// maximum epoch.
+    #[must_use = "this returns the result of the operation, without modifying \
+                  the original"]
+    pub fn saturating_add(self, rhs: impl Into<EpochOffset>) -> Self {
+        let EpochOffset(rhs) = rhs.into();
+        Self(self.0.saturating_add(rhs))
+    }
+
     /// Checked epoch subtraction. Computes self - rhs, returning None if
     /// overflow occurred.
     #[must_use = "this returns the result of the operation, without modifying \
                   the original"]
-    pub fn checked_sub(self, rhs: impl Into<Epoch>) -> Option<Self> {
-        let Epoch(rhs) = rhs.into();
-        if rhs > self.0 {
-            None
-        } else {
-            Some(Self(self.0 - rhs))
-        }
+    pub fn checked_sub(self, rhs: impl Into<EpochOffset>) -> Option<Self> {
+        let EpochOffset(rhs) = rhs.into();
+        self.0.checked_sub(rhs).map(Self)
+    }
+
+    /// Saturating epoch subtraction. Computes self - rhs, saturating at
+    /// `Epoch(0)`.
+    #[must_use = "this returns the result of the operation, without modifying \
+                  the original"]
+    pub fn saturating_sub(self, rhs: impl Into<EpochOffset>) -> Self {
+        let EpochOffset(rhs) = rhs.into();
+        Self(self.0.saturating_sub(rhs))
+    }
+
+    /// The number of epochs from an earlier epoch to this one, returning None
+    /// if the other epoch is later than this one.
+    #[must_use = "this returns the result of the operation, without modifying \
+                  the original"]
+    pub fn checked_offset_from(self, earlier: Epoch) -> Option<EpochOffset> {
+        self.0.checked_sub(earlier.0).map(EpochOffset)
     }
 
     /// Checked epoch subtraction. Computes self - rhs, returning default
@@ -1109,7 +1470,7 @@ impl Epoch {
     #[must_use = "this returns the result of the operation, without modifying \
                   the original"]
     pub fn sub_or_default(self, rhs: Epoch) -> Self {
-        self.checked_sub(rhs).unwrap_or_default()
+        self.saturating_sub(rhs)
     }
 }
 
@@ -1205,6 +1566,51 @@ impl Mul for Epoch {
     }
 }
 
+/// A number of epochs, e.g. the distance between two epochs or the length of
+/// an epoched parameter such as the pipeline length
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct EpochOffset(pub u64);
+
+impl Display for EpochOffset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u64> for EpochOffset {
+    fn from(offset: u64) -> Self {
+        EpochOffset(offset)
+    }
+}
+
+impl From<EpochOffset> for u64 {
+    fn from(offset: EpochOffset) -> Self {
+        offset.0
+    }
+}
+
+/// The offset of an epoch from the genesis epoch
+impl From<Epoch> for EpochOffset {
+    fn from(epoch: Epoch) -> Self {
+        EpochOffset(epoch.0)
+    }
+}
+
 /// Predecessor block epochs
 #[derive(
     Clone,
@@ -1481,20 +1887,21 @@ pub mod tests {
 
     proptest! {
         /// Tests that any key that doesn't contain reserved prefixes is valid.
-        /// This test excludes key segments starting with `#` or `?`
-        /// because they are reserved for `Address` or a validity predicate.
+        /// This test excludes key segments starting with `#`, `$` or `?`
+        /// because they are reserved for `Address`, `FixedAddress` or a
+        /// validity predicate.
         #[test]
-        fn test_key_parse(s in "[^#?/][^/]*/[^#?/][^/]*/[^#?/][^/]*") {
+        fn test_key_parse(s in "[^#$?/][^/]*/[^#$?/][^/]*/[^#$?/][^/]*") {
             let key = Key::parse(s.clone()).expect("cannot parse the string");
             assert_eq!(key.to_string(), s);
         }
 
         /// Tests that any key that doesn't contain reserved prefixes and
         /// separators is valid. This test excludes key segments including `/`
-        /// or starting with `#` or `?` because they are reserved for separator,
-        /// `Address` or validity predicate.
+        /// or starting with `#`, `$` or `?` because they are reserved for
+        /// separator, `Address`, `FixedAddress` or validity predicate.
         #[test]
-        fn test_key_push(s in "[^#?/][^/]*") {
+        fn test_key_push(s in "[^#$?/][^/]*") {
             let addr = address::testing::established_address_1();
             let key = Key::from(addr.to_db_key()).push(&s).expect("cannot push the segment");
             assert_eq!(key.segments[1].raw(), s);
@@ -1512,6 +1919,33 @@ pub mod tests {
             let parsed_epoch: Epoch = KeySeg::parse(key_seg).expect("Test failed");
             assert_eq!(original_epoch, parsed_epoch);
         }
+
+        /// Test that the fixed-width segments of all the kinds of addresses
+        /// have the same length, that they roundtrip and that their order is
+        /// the order of the raw bytes of the addresses.
+        #[test]
+        fn test_fixed_address_key_segment(
+            addr1 in arb_address(),
+            addr2 in arb_address(),
+        ) {
+            let raw1 = FixedAddress(addr1.clone()).raw();
+            let raw2 = FixedAddress(addr2.clone()).raw();
+            assert_eq!(raw1.len(), FIXED_ADDRESS_SEG_LEN);
+            assert!(raw1.len() < addr1.raw().len());
+            let parsed = <FixedAddress as KeySeg>::parse(raw1.clone()).unwrap();
+            assert_eq!(parsed.0, addr1);
+            let key = Key::from(addr2.to_db_key())
+                .push(&FixedAddress(addr1.clone()))
+                .unwrap();
+            assert_eq!(key.to_string(), format!("{}/{raw1}", addr2.raw()));
+            assert_eq!(Key::parse(key.to_string()).unwrap(), key);
+            let addresses = vec![addr2.clone(), addr1.clone()];
+            assert_eq!(key.find_addresses(), addresses);
+
+            let bytes1 = string_encoding::Format::to_bytes(&addr1);
+            let bytes2 = string_encoding::Format::to_bytes(&addr2);
+            assert_eq!(raw1.cmp(&raw2), bytes1.cmp(&bytes2));
+        }
     }
 
     /// Test that providing an [`EthEventsQueue`] with an event containing
@@ -1673,6 +2107,91 @@ pub mod tests {
         assert_eq!(key.segments[1].raw(), target);
     }
 
+    /// Test that only the lowercase Bech32m addresses are parsed from key
+    /// segments, and that invalid fixed-width addresses are rejected.
+    #[test]
+    fn test_key_parse_non_canonical_address() {
+        let addr = address::testing::established_address_1();
+        let upper = format!("#{}", addr.encode().to_ascii_uppercase());
+        assert!(matches!(
+            Key::parse(&upper),
+            Err(Error::NonCanonicalAddress(_))
+        ));
+        assert!(matches!(
+            <Address as KeySeg>::parse(upper),
+            Err(Error::NonCanonicalAddress(_))
+        ));
+
+        let fixed = FixedAddress(addr).raw();
+        assert!(matches!(
+            Key::parse(fixed.to_ascii_lowercase()),
+            Err(Error::ParseKeySeg(_))
+        ));
+        assert!(matches!(
+            Key::parse(&fixed[..fixed.len() - 2]),
+            Err(Error::ParseAddress(_))
+        ));
+    }
+
+    /// Test that the checked and saturating epoch arithmetic doesn't
+    /// overflow near the first and the last epochs.
+    #[test]
+    fn test_epoch_arithmetic() {
+        let epoch = Epoch(2);
+        assert_eq!(epoch.checked_sub(3), None);
+        assert_eq!(epoch.checked_sub(EpochOffset(2)), Some(Epoch(0)));
+        assert_eq!(epoch.saturating_sub(3), Epoch(0));
+        assert_eq!(epoch.sub_or_default(Epoch(1)), Epoch(1));
+        assert_eq!(Epoch(0).checked_prev(), None);
+        assert_eq!(epoch.checked_prev(), Some(Epoch(1)));
+        assert_eq!(epoch.checked_add(3), Some(Epoch(5)));
+        assert_eq!(Epoch(u64::MAX).checked_add(1), None);
+        assert_eq!(Epoch(u64::MAX).saturating_add(1), Epoch(u64::MAX));
+        assert_eq!(Epoch(5).checked_offset_from(epoch), Some(EpochOffset(3)));
+        assert_eq!(epoch.checked_offset_from(Epoch(5)), None);
+        assert_eq!(
+            epoch.iter_range(3).collect::<Vec<_>>(),
+            vec![Epoch(2), Epoch(3), Epoch(4)]
+        );
+        assert_eq!(Epoch(u64::MAX - 1).iter_range(3).count(), 1);
+        assert_eq!(
+            Epoch::iter_bounds_inclusive(Epoch(1), epoch)
+                .rev()
+                .collect::<Vec<_>>(),
+            vec![Epoch(2), Epoch(1)]
+        );
+        assert_eq!(Epoch::from_str(&epoch.to_string()), Ok(epoch));
+        assert!(Epoch::from_str("-1").is_err());
+    }
+
+    #[test]
+    fn test_block_height_arithmetic() {
+        let height = BlockHeight(2);
+        assert_eq!(height.checked_sub(3), None);
+        assert_eq!(height.checked_sub(2), Some(BlockHeight::sentinel()));
+        assert_eq!(height.saturating_sub(3), BlockHeight(0));
+        assert_eq!(BlockHeight(0).checked_prev_height(), None);
+        assert_eq!(height.checked_prev_height(), Some(BlockHeight::first()));
+        assert_eq!(height.checked_add(3), Some(BlockHeight(5)));
+        assert_eq!(BlockHeight(u64::MAX).checked_add(1), None);
+        assert_eq!(
+            BlockHeight(u64::MAX).saturating_add(1),
+            BlockHeight(u64::MAX)
+        );
+        assert_eq!(
+            height.iter_range(2).collect::<Vec<_>>(),
+            vec![BlockHeight(2), BlockHeight(3)]
+        );
+        assert_eq!(BlockHeight(u64::MAX).iter_range(2).count(), 0);
+        assert_eq!(
+            BlockHeight::iter_bounds_inclusive(BlockHeight::first(), height)
+                .collect::<Vec<_>>(),
+            vec![BlockHeight(1), BlockHeight(2)]
+        );
+        assert_eq!(BlockHeight::from_str(&height.to_string()), Ok(height));
+        assert!(BlockHeight::from_str("2a").is_err());
+    }
+
     #[test]
     fn test_key_push_invalid() {
         let addr = address::testing::established_address_1();
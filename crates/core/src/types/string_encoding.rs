@@ -35,6 +35,14 @@ pub const MASP_EXT_SPENDING_KEY_HRP: &str = "zsknam";
 pub const COMMON_PK_HRP: &str = "tpknam";
 /// `common::Signature` human-readable part
 pub const COMMON_SIG_HRP: &str = "signam";
+/// Unified spending key human-readable part
+pub const UNIFIED_SPENDING_KEY_HRP: &str = "usknam";
+/// Unified viewing key human-readable part
+pub const UNIFIED_VIEWING_KEY_HRP: &str = "uvknam";
+/// ZIP 316 unified full viewing key human-readable part
+pub const ZIP316_FULL_VIEWING_KEY_HRP: &str = "uviewnam";
+/// ZIP 316 unified incoming viewing key human-readable part
+pub const ZIP316_INCOMING_VIEWING_KEY_HRP: &str = "uivknam";
 
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
@@ -53,6 +61,11 @@ pub enum DecodeError {
     InvalidBytes(std::io::Error),
     #[error("Unexpected discriminant byte: {0}")]
     UnexpectedDiscriminant(u8),
+    #[error(
+        "Mixed case Bech32m string {0}, expected its human-readable part and \
+         its data part to be either lowercase or uppercase"
+    )]
+    MixedCase(String),
 }
 
 /// Format to string with bech32m
@@ -78,10 +91,12 @@ pub trait Format: Sized {
         )
     }
 
-    /// Try to decode `Self` from a string
+    /// Try to decode `Self` from a string. See [`to_lowercase`] for the
+    /// accepted cases.
     fn decode(string: impl AsRef<str>) -> Result<Self, DecodeError> {
-        let (hrp, hash_base32, variant) = bech32::decode(string.as_ref())
-            .map_err(DecodeError::DecodeBech32)?;
+        let string = to_lowercase(string.as_ref())?;
+        let (hrp, hash_base32, variant) =
+            bech32::decode(&string).map_err(DecodeError::DecodeBech32)?;
         if hrp != Self::HRP {
             return Err(DecodeError::UnexpectedBech32Hrp(
                 hrp,
@@ -130,6 +145,24 @@ macro_rules! impl_display_and_from_str_via_format {
     };
 }
 
+/// Normalize a Bech32m string to its canonical lowercase. The characters of
+/// both the human-readable part and the data part are case-insensitive, so
+/// each of the parts can be either lowercase or uppercase, such as an
+/// uppercase data part from a QR code. A part with both cases is more likely
+/// a typo than an intended case, so it's rejected.
+pub fn to_lowercase(string: &str) -> Result<String, DecodeError> {
+    let is_mixed_case = |part: &str| {
+        part.bytes().any(|b| b.is_ascii_lowercase())
+            && part.bytes().any(|b| b.is_ascii_uppercase())
+    };
+    // The human-readable part is before the last separator
+    let (hrp, data) = string.rsplit_once('1').unwrap_or((string, ""));
+    if is_mixed_case(hrp) || is_mixed_case(data) {
+        return Err(DecodeError::MixedCase(string.to_owned()));
+    }
+    Ok(string.to_ascii_lowercase())
+}
+
 /// Get the length of the human-readable part
 // Not in the `Format` trait, cause functions in traits cannot be const
 pub const fn hrp_len<T: Format>() -> usize {
@@ -1,31 +1,65 @@
 //! A basic fungible token
-
-use std::cmp::Ordering;
+//!
+//! Without the `std` feature, only the amounts, their denominations and their
+//! codecs are compiled, so that they can be used in `no_std` environments,
+//! e.g. to verify the amounts of Namada in the light client of another chain.
+
+#[cfg(feature = "std")]
+pub mod factory;
+#[cfg(feature = "std")]
+pub mod non_fungible;
+#[cfg(feature = "std")]
+pub mod typed;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::Display;
+#[cfg(not(feature = "strict-arith"))]
+use core::iter::Sum;
+#[cfg(not(feature = "strict-arith"))]
+use core::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
+use core::str::FromStr;
+#[cfg(feature = "std")]
 use std::collections::BTreeMap;
-use std::fmt::Display;
-use std::iter::Sum;
-use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
-use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+#[cfg(feature = "std")]
 use data_encoding::BASE32HEX_NOPAD;
+#[cfg(feature = "std")]
 use ethabi::ethereum_types::U256;
+#[cfg(feature = "std")]
 use masp_primitives::asset_type::AssetType;
+#[cfg(feature = "std")]
 use masp_primitives::convert::AllowedConversion;
+#[cfg(feature = "std")]
 use masp_primitives::merkle_tree::FrozenCommitmentTree;
+#[cfg(feature = "std")]
 use masp_primitives::sapling;
+#[cfg(feature = "std")]
+use num_traits::{CheckedAdd, CheckedSub};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use thiserror::Error;
 
+#[cfg(feature = "std")]
 use crate::ibc::apps::transfer::types::Amount as IbcAmount;
+#[cfg(feature = "std")]
 use crate::types::address::{Address, DecodeError as AddressError};
+#[cfg(feature = "std")]
 use crate::types::dec::{Dec, POS_DECIMAL_PRECISION};
+#[cfg(feature = "std")]
 use crate::types::hash::Hash;
+#[cfg(feature = "std")]
 use crate::types::storage;
+#[cfg(feature = "std")]
 use crate::types::storage::{DbKeySeg, Epoch, KeySeg};
 use crate::types::uint::{self, Uint, I256};
 
 /// A representation of the conversion state
+#[cfg(feature = "std")]
 #[derive(Debug, Default, BorshSerialize, BorshDeserialize)]
 pub struct ConversionState {
     /// The last amount of the native token distributed
@@ -35,16 +69,61 @@ pub struct ConversionState {
     /// A map from token alias to actual address.
     pub tokens: BTreeMap<String, Address>,
     /// Map assets to their latest conversion and position in Merkle tree
-    #[allow(clippy::type_complexity)]
-    pub assets: BTreeMap<
-        AssetType,
-        (
-            (Address, Denomination, MaspDigitPos),
-            Epoch,
-            AllowedConversion,
-            usize,
-        ),
-    >,
+    pub assets: BTreeMap<AssetType, ConversionEntry>,
+    /// The cached parts of the tree of the conversions. It's not persisted,
+    /// so the first update of the tree after a restart rebuilds it in full.
+    #[borsh(skip)]
+    pub tree_cache: ConversionTreeCache,
+}
+
+/// The token, denomination and digit of an asset type, with its epoch, its
+/// latest conversion and its position in the tree of the conversions
+#[cfg(feature = "std")]
+pub type ConversionEntry = (
+    (Address, Denomination, MaspDigitPos),
+    Epoch,
+    AllowedConversion,
+    usize,
+);
+
+/// A missing conversion of an asset type: an epoch without a conversion
+/// between the first and the last epochs with a conversion of the same token,
+/// denomination and digit
+#[cfg(feature = "std")]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ConversionGap {
+    /// The token of the asset type
+    pub token: Address,
+    /// The denomination of the asset type
+    pub denom: Denomination,
+    /// The digit of the asset type
+    pub digit: MaspDigitPos,
+    /// The epoch of the asset type without a conversion
+    pub epoch: Epoch,
+}
+
+/// The leaves and the sub-trees of the last tree of the conversions, so that
+/// an update of the tree only recomputes the leaves of the changed conversions
+/// and the sub-trees that contain them
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct ConversionTreeCache {
+    /// The commitments of the conversions, by position in the tree
+    pub leaves: Vec<sapling::Node>,
+    /// The sub-trees of consecutive chunks of the leaves
+    pub chunks: Vec<FrozenCommitmentTree<sapling::Node>>,
 }
 
 /// Amount in micro units. For different granularity another representation
@@ -177,6 +256,17 @@ impl Amount {
         })
     }
 
+    /// Checked sum of amounts. Returns `None` on overflow or if the sum
+    /// exceeds [`uint::MAX_VALUE`]
+    #[must_use]
+    pub fn checked_sum<I>(iter: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Amount>,
+    {
+        iter.into_iter()
+            .try_fold(Amount::zero(), |acc, amount| acc.checked_add(amount))
+    }
+
     /// Checked subtraction. Returns `None` on underflow.
     #[must_use]
     pub fn checked_sub(&self, amount: Amount) -> Option<Self> {
@@ -206,6 +296,17 @@ impl Amount {
             .map(|result| Self { raw: result })
     }
 
+    /// Checked multiplication by the ratio `num / denom`, rounded down. The
+    /// product is computed in 512 bits, so it can't overflow before the
+    /// division. Returns `None` if `denom` is zero or if the result doesn't
+    /// fit in an amount.
+    #[must_use]
+    pub fn checked_mul_div(&self, num: Uint, denom: Uint) -> Option<Self> {
+        self.raw
+            .checked_mul_div(num, denom)
+            .map(|(raw, _remainder)| Self { raw })
+    }
+
     /// Given a string and a denomination, parse an amount from string.
     pub fn from_str(
         string: impl AsRef<str>,
@@ -271,6 +372,17 @@ impl Amount {
         .to_string_precise()
     }
 
+    /// Format the amount in the given denomination with the given separator
+    /// between the groups of thousands of its integer part. See
+    /// [`DenominatedAmount::format_with_separators`].
+    pub fn format_with_separators(
+        &self,
+        denom: Denomination,
+        sep: char,
+    ) -> String {
+        DenominatedAmount::new(*self, denom).format_with_separators(sep)
+    }
+
     /// Return a denominated native token amount.
     #[inline]
     pub const fn native_denominated(self) -> DenominatedAmount {
@@ -283,10 +395,26 @@ impl Amount {
         DenominatedAmount::from_str(string).map(|den| den.amount)
     }
 
+    /// Checked multiplication by a decimal [`Dec`], with the result rounded
+    /// down. Returns `None` if the `dec` is negative or if the result doesn't
+    /// fit in an amount.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn checked_mul_dec(&self, dec: Dec) -> Option<Self> {
+        if dec.is_negative() {
+            return None;
+        }
+        self.checked_mul_div(
+            dec.abs(),
+            Uint::exp10(POS_DECIMAL_PRECISION as usize),
+        )
+    }
+
     /// Multiply by a decimal [`Dec`] with the result rounded up.
     ///
     /// # Panics
     /// Panics when the `dec` is negative.
+    #[cfg(feature = "std")]
     #[must_use]
     pub fn mul_ceil(&self, dec: Dec) -> Self {
         assert!(!dec.is_negative());
@@ -305,11 +433,80 @@ impl Amount {
 }
 
 impl Display for Amount {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "{}", self.raw)
     }
 }
 
+/// An [`Amount`] written in its raw units, without a denomination, as in the
+/// genesis and config files. It's displayed, parsed and (de)serialized as the
+/// same decimal integer, so that it round-trips, and a string with decimal
+/// places is rejected instead of being scaled by its own number of decimal
+/// places. The user-facing amounts are [`DenominatedAmount`]s.
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Debug,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct RawAmount(pub Amount);
+
+impl Display for RawAmount {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}", self.0.raw)
+    }
+}
+
+impl FromStr for RawAmount {
+    type Err = AmountParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(AmountParseError::NotNumeric);
+        }
+        let raw =
+            Uint::from_dec_str(s).map_err(|_| AmountParseError::Overflow)?;
+        Ok(Self(Amount { raw }))
+    }
+}
+
+impl TryFrom<String> for RawAmount {
+    type Error = AmountParseError;
+
+    fn try_from(string: String) -> Result<Self, Self::Error> {
+        Self::from_str(&string)
+    }
+}
+
+impl From<RawAmount> for String {
+    fn from(amount: RawAmount) -> Self {
+        amount.to_string()
+    }
+}
+
+impl From<Amount> for RawAmount {
+    fn from(amount: Amount) -> Self {
+        Self(amount)
+    }
+}
+
+impl From<RawAmount> for Amount {
+    fn from(amount: RawAmount) -> Self {
+        amount.0
+    }
+}
+
 /// Given a number represented as `M*B^D`, then
 /// `M` is the matissa, `B` is the base and `D`
 /// is the denomination, represented by this struct.
@@ -343,6 +540,17 @@ impl From<Denomination> for u8 {
     }
 }
 
+/// The rounding mode of the divisions of the [`DenominatedAmount`]s.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Round towards zero
+    Floor,
+    /// Round away from zero
+    Ceil,
+    /// Round to the nearest value, with the halves rounded away from zero
+    HalfUp,
+}
+
 /// An amount with its denomination.
 #[derive(
     Debug,
@@ -405,6 +613,30 @@ impl DenominatedAmount {
         string
     }
 
+    /// Format the amount like its `Display` implementation, with the given
+    /// separator between the groups of thousands of its integer part, e.g.
+    /// `1234567.891011` is formatted as `1,234,567.891011`.
+    pub fn format_with_separators(&self, sep: char) -> String {
+        let string = self.to_string();
+        let (integer, fraction) = match string.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (string.as_str(), None),
+        };
+        let mut formatted =
+            String::with_capacity(string.len() + integer.len() / 3);
+        for (ix, digit) in integer.chars().enumerate() {
+            if ix > 0 && (integer.len() - ix) % 3 == 0 {
+                formatted.push(sep);
+            }
+            formatted.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            formatted.push('.');
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+
     /// Find the minimal precision that holds this value losslessly.
     /// This equates to stripping trailing zeros after the decimal
     /// place.
@@ -491,6 +723,50 @@ impl DenominatedAmount {
         })
     }
 
+    /// Checked division, with the quotient computed at the given precision
+    /// and rounded with the given mode. Returns `None` on a division by zero
+    /// or on overflow.
+    pub fn checked_div_precision(
+        &self,
+        rhs: DenominatedAmount,
+        precision: Denomination,
+        rounding: RoundingMode,
+    ) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let ten = Uint::from(10);
+        // The quotient of the mantissas has to be scaled by
+        // 10^(precision + rhs.denom - self.denom)
+        let exponent = i32::from(precision.0) + i32::from(rhs.denom.0)
+            - i32::from(self.denom.0);
+        let (quotient, remainder, divisor) = if exponent >= 0 {
+            let scaling = ten.checked_pow(Uint::from(exponent))?;
+            let (quotient, remainder) =
+                self.amount.raw.checked_mul_div(scaling, rhs.amount.raw)?;
+            (quotient, remainder, rhs.amount.raw)
+        } else {
+            let scaling = ten.checked_pow(Uint::from(-exponent))?;
+            let divisor = rhs.amount.raw.checked_mul(scaling)?;
+            let (quotient, remainder) = self.amount.raw.div_mod(divisor);
+            (quotient, remainder, divisor)
+        };
+        let round_up = match rounding {
+            RoundingMode::Floor => false,
+            RoundingMode::Ceil => !remainder.is_zero(),
+            RoundingMode::HalfUp => remainder >= divisor - remainder,
+        };
+        let raw = if round_up {
+            quotient.checked_add(Uint::one())?
+        } else {
+            quotient
+        };
+        Some(Self {
+            amount: Amount { raw },
+            denom: precision,
+        })
+    }
+
     /// Returns the significand of this number
     pub const fn amount(&self) -> Amount {
         self.amount
@@ -503,7 +779,7 @@ impl DenominatedAmount {
 }
 
 impl Display for DenominatedAmount {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let string = self.to_string_precise();
         let string = if self.denom.0 > 0 {
             string.trim_end_matches(&['0'])
@@ -515,10 +791,10 @@ impl Display for DenominatedAmount {
     }
 }
 
-impl FromStr for DenominatedAmount {
-    type Err = AmountParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl DenominatedAmount {
+    /// Parse the digits of an amount, with an optional decimal point that
+    /// sets the denomination
+    fn from_digits(s: &str) -> Result<Self, AmountParseError> {
         let precision = s.find('.').map(|pos| s.len() - pos - 1);
         let digits = s
             .chars()
@@ -529,83 +805,159 @@ impl FromStr for DenominatedAmount {
                     None
                 }
             })
-            .rev()
             .collect::<Vec<_>>();
         if digits.len() != s.len() && precision.is_none()
             || digits.len() != s.len() - 1 && precision.is_some()
         {
             return Err(AmountParseError::NotNumeric);
         }
-        if digits.len() > 77 {
-            return Err(AmountParseError::ScaleTooLarge(
-                digits.len() as u32,
-                77,
-            ));
-        }
+        let precision = precision.unwrap_or_default();
+        let denom =
+            u8::try_from(precision).map(Denomination).map_err(|_| {
+                AmountParseError::ScaleTooLarge(precision as u32, u8::MAX)
+            })?;
+        // The leading zeros don't count towards the range of the value, e.g.
+        // in the precise strings of the small amounts with large
+        // denominations
         let mut value = Uint::default();
         let ten = Uint::from(10);
-        for (pow, digit) in digits.into_iter().enumerate() {
-            value = ten
-                .checked_pow(Uint::from(pow))
-                .and_then(|scaling| scaling.checked_mul(digit))
-                .and_then(|scaled| value.checked_add(scaled))
+        for digit in digits {
+            value = value
+                .checked_mul(ten)
+                .and_then(|scaled| scaled.checked_add(digit))
                 .ok_or(AmountParseError::InvalidRange)?;
         }
-        let denom = Denomination(precision.unwrap_or_default() as u8);
         Ok(Self {
             amount: Amount { raw: value },
             denom,
         })
     }
+
+    /// Scale the amount by the given power of ten, by lowering its
+    /// denomination first and then multiplying its raw amount by the rest
+    fn scale_by_exp10(self, exp: i32) -> Result<Self, AmountParseError> {
+        let denom = i64::from(self.denom.0) - i64::from(exp);
+        if denom >= 0 {
+            let denom = u8::try_from(denom).map_err(|_| {
+                AmountParseError::ScaleTooLarge(denom as u32, u8::MAX)
+            })?;
+            return Ok(Self {
+                amount: self.amount,
+                denom: Denomination(denom),
+            });
+        }
+        let raw = Uint::from(10)
+            .checked_pow(Uint::from(denom.unsigned_abs()))
+            .and_then(|scaling| scaling.checked_mul(self.amount.raw))
+            .ok_or(AmountParseError::InvalidRange)?;
+        Ok(Self {
+            amount: Amount { raw },
+            denom: Denomination(0),
+        })
+    }
 }
 
-impl PartialOrd for DenominatedAmount {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.denom < other.denom {
-            let diff = other.denom.0 - self.denom.0;
-            let (div, rem) =
-                other.amount.raw.div_mod(Uint::exp10(diff as usize));
-            let div_ceil = if rem.is_zero() {
-                div
-            } else {
-                div + Uint::one()
-            };
-            let ord = self.amount.raw.partial_cmp(&div_ceil);
-            if let Some(Ordering::Equal) = ord {
-                if rem.is_zero() {
-                    Some(Ordering::Equal)
-                } else {
-                    Some(Ordering::Greater)
-                }
-            } else {
-                ord
-            }
+/// Remove the underscores that separate the groups of digits of the integer
+/// or of the fractional part of an amount. The groups must have three digits,
+/// except for the first one of an integer part and the last one of a
+/// fractional part, which have one to three digits.
+fn strip_digit_separators(
+    part: &str,
+    is_fraction: bool,
+) -> Result<String, AmountParseError> {
+    if !part.contains('_') {
+        return Ok(part.to_string());
+    }
+    let groups: Vec<&str> = part.split('_').collect();
+    let last = groups.len() - 1;
+    let is_grouped = groups.iter().enumerate().all(|(ix, group)| {
+        let is_outer = if is_fraction { ix == last } else { ix == 0 };
+        if is_outer {
+            (1..=3).contains(&group.len())
         } else {
-            let diff = self.denom.0 - other.denom.0;
-            let (div, rem) =
-                self.amount.raw.div_mod(Uint::exp10(diff as usize));
-            let div_ceil = if rem.is_zero() {
-                div
-            } else {
-                div + Uint::one()
-            };
-            let ord = div_ceil.partial_cmp(&other.amount.raw);
-            if let Some(Ordering::Equal) = ord {
-                if rem.is_zero() {
-                    Some(Ordering::Equal)
-                } else {
-                    Some(Ordering::Less)
+            group.len() == 3
+        }
+    });
+    if is_grouped {
+        Ok(groups.concat())
+    } else {
+        Err(AmountParseError::InvalidSeparator)
+    }
+}
+
+/// Parses the digits of an amount with an optional decimal point, which sets
+/// the denomination, e.g. `1.50` has the denomination 2. The digits can be
+/// grouped by three with underscores, e.g. `1_000_000.5`, and followed by a
+/// decimal exponent, e.g. `1.5e3` or `15e-1`. A positive exponent lowers the
+/// denomination before multiplying the amount, so `1.5e3` is `1500` in the
+/// denomination 0.
+impl FromStr for DenominatedAmount {
+    type Err = AmountParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mantissa, exponent) =
+            match s.split_once(|c: char| c == 'e' || c == 'E') {
+                Some((mantissa, exponent)) => {
+                    let exponent = exponent
+                        .parse::<i32>()
+                        .map_err(|_| AmountParseError::InvalidExponent)?;
+                    if !mantissa.chars().any(|c| c.is_ascii_digit()) {
+                        return Err(AmountParseError::InvalidExponent);
+                    }
+                    (mantissa, Some(exponent))
                 }
-            } else {
-                ord
+                None => (s, None),
+            };
+        let mantissa = match mantissa.split_once('.') {
+            Some((integer, fraction)) => {
+                let mut digits = strip_digit_separators(integer, false)?;
+                digits.push('.');
+                digits.push_str(&strip_digit_separators(fraction, true)?);
+                digits
             }
+            None => strip_digit_separators(mantissa, false)?,
+        };
+        let amount = Self::from_digits(&mantissa)?;
+        match exponent {
+            Some(exponent) => amount.scale_by_exp10(exponent),
+            None => Ok(amount),
         }
     }
 }
 
+impl PartialOrd for DenominatedAmount {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compare `lhs * 10^exp` with `rhs` exactly, without computing the product
+/// that may overflow.
+fn cmp_scaled(lhs: Uint, rhs: Uint, exp: u8) -> Ordering {
+    // The scale of an exponent above 77 overflows, but then it's also above
+    // any `rhs`
+    let (div, rem) = match Uint::from(10).checked_pow(Uint::from(exp)) {
+        Some(scale) => rhs.div_mod(scale),
+        None => (Uint::zero(), rhs),
+    };
+    // `rhs` lies in `[div * 10^exp, (div + 1) * 10^exp)`, so only the
+    // remainder decides when `lhs` equals `div`
+    match lhs.cmp(&div) {
+        Ordering::Equal if !rem.is_zero() => Ordering::Less,
+        ord => ord,
+    }
+}
+
 impl Ord for DenominatedAmount {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        // Compare the raw amounts at the larger denomination
+        if self.denom < other.denom {
+            let diff = other.denom.0 - self.denom.0;
+            cmp_scaled(self.amount.raw, other.amount.raw, diff)
+        } else {
+            let diff = self.denom.0 - other.denom.0;
+            cmp_scaled(other.amount.raw, self.amount.raw, diff).reverse()
+        }
     }
 }
 
@@ -613,7 +965,7 @@ impl serde::Serialize for Amount {
     fn serialize<S>(
         &self,
         serializer: S,
-    ) -> std::result::Result<S::Ok, S::Error>
+    ) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
@@ -623,7 +975,7 @@ impl serde::Serialize for Amount {
 }
 
 impl<'de> serde::Deserialize<'de> for Amount {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
@@ -638,7 +990,7 @@ impl serde::Serialize for DenominatedAmount {
     fn serialize<S>(
         &self,
         serializer: S,
-    ) -> std::result::Result<S::Ok, S::Error>
+    ) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
@@ -648,7 +1000,7 @@ impl serde::Serialize for DenominatedAmount {
 }
 
 impl<'de> serde::Deserialize<'de> for DenominatedAmount {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
@@ -674,12 +1026,39 @@ impl From<u64> for Amount {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<Amount> for U256 {
     fn from(amt: Amount) -> Self {
         Self(amt.raw.0)
     }
 }
 
+/// Truncates the [`Dec`] to its integer part, as a raw amount. Fails for
+/// negative values.
+#[cfg(all(feature = "std", not(feature = "deprecated-dec-into-amount")))]
+impl TryFrom<Dec> for Amount {
+    type Error = AmountParseError;
+
+    fn try_from(dec: Dec) -> Result<Self, Self::Error> {
+        if dec.is_negative() {
+            return Err(AmountParseError::NegativeDec);
+        }
+        Ok(Amount {
+            raw: dec.0.abs() / Uint::exp10(POS_DECIMAL_PRECISION as usize),
+        })
+    }
+}
+
+#[cfg(all(feature = "deprecated-dec-into-amount", feature = "strict-arith"))]
+compile_error!(
+    "The `deprecated-dec-into-amount` feature provides a panicking \
+     conversion, which the `strict-arith` feature forbids"
+);
+
+/// Deprecated: panics on negative values. Because it also provides the
+/// `TryFrom` conversion, it replaces the fallible one when the
+/// `deprecated-dec-into-amount` feature is enabled.
+#[cfg(feature = "deprecated-dec-into-amount")]
 impl From<Dec> for Amount {
     fn from(dec: Dec) -> Amount {
         if !dec.is_negative() {
@@ -695,23 +1074,28 @@ impl From<Dec> for Amount {
     }
 }
 
+/// The conversion from a [`Dec`] to an [`Amount`] can't fail when the
+/// `deprecated-dec-into-amount` feature is enabled.
+#[cfg(feature = "deprecated-dec-into-amount")]
+impl From<std::convert::Infallible> for AmountParseError {
+    fn from(err: std::convert::Infallible) -> Self {
+        match err {}
+    }
+}
+
 impl TryFrom<Amount> for u128 {
-    type Error = std::io::Error;
+    type Error = AmountParseError;
 
     fn try_from(value: Amount) -> Result<Self, Self::Error> {
         let Uint(arr) = value.raw;
-        for word in arr.iter().skip(2) {
-            if *word != 0 {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidInput,
-                    "Integer overflow when casting to u128",
-                ));
-            }
+        if arr.iter().skip(2).any(|word| *word != 0) {
+            return Err(AmountParseError::InvalidRange);
         }
         Ok(value.raw.low_u128())
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Add for Amount {
     type Output = Amount;
 
@@ -721,6 +1105,7 @@ impl Add for Amount {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Add<u64> for Amount {
     type Output = Self;
 
@@ -731,6 +1116,7 @@ impl Add<u64> for Amount {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<u64> for Amount {
     type Output = Amount;
 
@@ -740,6 +1126,7 @@ impl Mul<u64> for Amount {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<Amount> for u64 {
     type Output = Amount;
 
@@ -749,6 +1136,7 @@ impl Mul<Amount> for u64 {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<Uint> for Amount {
     type Output = Amount;
 
@@ -758,6 +1146,7 @@ impl Mul<Uint> for Amount {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<Amount> for Amount {
     type Output = Amount;
 
@@ -768,7 +1157,9 @@ impl Mul<Amount> for Amount {
 }
 
 /// A combination of Euclidean division and fractions:
-/// x*(a,b) = (a*(x//b), x%b).
+/// x*(a,b) = (a*(x//b), x%b). The remainder is not scaled by the ratio, see
+/// [`Amount::checked_mul_div`] for an exact multiplication by a ratio.
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<(u128, u128)> for Amount {
     type Output = (Amount, Amount);
 
@@ -782,7 +1173,9 @@ impl Mul<(u128, u128)> for Amount {
 }
 
 /// A combination of Euclidean division and fractions:
-/// x*(a,b) = (a*(x//b), x%b).
+/// x*(a,b) = (a*(x//b), x%b). The remainder is not scaled by the ratio, see
+/// [`Amount::checked_mul_div`] for an exact multiplication by a ratio.
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<(u64, u64)> for Amount {
     type Output = (Amount, Amount);
 
@@ -796,7 +1189,9 @@ impl Mul<(u64, u64)> for Amount {
 }
 
 /// A combination of Euclidean division and fractions:
-/// x*(a,b) = (a*(x//b), x%b).
+/// x*(a,b) = (a*(x//b), x%b). The remainder is not scaled by the ratio, see
+/// [`Amount::checked_mul_div`] for an exact multiplication by a ratio.
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<(u32, u32)> for Amount {
     type Output = (Amount, Amount);
 
@@ -809,6 +1204,7 @@ impl Mul<(u32, u32)> for Amount {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Div<u64> for Amount {
     type Output = Self;
 
@@ -819,12 +1215,14 @@ impl Div<u64> for Amount {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl AddAssign for Amount {
     fn add_assign(&mut self, rhs: Self) {
         self.raw += rhs.raw
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Sub for Amount {
     type Output = Amount;
 
@@ -834,18 +1232,47 @@ impl Sub for Amount {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl SubAssign for Amount {
     fn sub_assign(&mut self, rhs: Self) {
         self.raw -= rhs.raw
     }
 }
 
+/// Panics on overflow, use [`TrySum::try_sum`] with untrusted amounts
+#[cfg(not(feature = "strict-arith"))]
 impl Sum for Amount {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
         iter.fold(Amount::default(), |acc, next| acc + next)
     }
 }
 
+/// An overflow or an underflow of the arithmetics of amounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AmountOverflowError;
+
+impl Display for AmountOverflowError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(
+            "The arithmetic of the token amounts overflowed or underflowed.",
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AmountOverflowError {}
+
+/// Overflow-safe sum of the amounts of an iterator
+pub trait TrySum: Iterator<Item = Amount> + Sized {
+    /// Sum the amounts, or return an error on overflow
+    fn try_sum(self) -> Result<Amount, AmountOverflowError> {
+        Amount::checked_sum(self).ok_or(AmountOverflowError)
+    }
+}
+
+impl<I: Iterator<Item = Amount>> TrySum for I {}
+
+#[cfg(feature = "std")]
 impl KeySeg for Amount {
     fn parse(string: String) -> super::storage::Result<Self>
     where
@@ -874,37 +1301,90 @@ impl KeySeg for Amount {
 }
 
 #[allow(missing_docs)]
-#[derive(Error, Debug)]
+#[derive(Debug)]
 pub enum AmountParseError {
-    #[error(
-        "Error decoding token amount, too many decimal places: {0}. Maximum \
-         {1}"
-    )]
     ScaleTooLarge(u32, u8),
-    #[error(
-        "Error decoding token amount, the value is not within invalid range."
-    )]
     InvalidRange,
-    #[error("Error converting amount to decimal, number too large.")]
     ConvertToDecimal,
-    #[error(
-        "Could not convert from string, expected an unsigned 256-bit integer."
-    )]
     FromString,
-    #[error("Could not parse string as a correctly formatted number.")]
     NotNumeric,
-    #[error("This amount cannot handle the requested precision in 256 bits.")]
     PrecisionOverflow,
-    #[error("More precision given in the amount than requested.")]
     PrecisionDecrease,
+    NegativeDec,
+    Overflow,
+    InvalidSeparator,
+    InvalidExponent,
 }
 
+// The `Display` is implemented by hand, as `thiserror` requires `std`
+impl Display for AmountParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ScaleTooLarge(scale, max) => write!(
+                f,
+                "Error decoding token amount, too many decimal places: \
+                 {scale}. Maximum {max}"
+            ),
+            Self::InvalidRange => f.write_str(
+                "Error decoding token amount, the value is not within invalid \
+                 range.",
+            ),
+            Self::ConvertToDecimal => f.write_str(
+                "Error converting amount to decimal, number too large.",
+            ),
+            Self::FromString => f.write_str(
+                "Could not convert from string, expected an unsigned 256-bit \
+                 integer.",
+            ),
+            Self::NotNumeric => f.write_str(
+                "Could not parse string as a correctly formatted number.",
+            ),
+            Self::PrecisionOverflow => f.write_str(
+                "This amount cannot handle the requested precision in 256 \
+                 bits.",
+            ),
+            Self::PrecisionDecrease => f.write_str(
+                "More precision given in the amount than requested.",
+            ),
+            Self::NegativeDec => f.write_str(
+                "A negative decimal cannot be converted to an amount.",
+            ),
+            Self::Overflow => {
+                f.write_str("The arithmetic overflowed or divided by zero.")
+            }
+            Self::InvalidSeparator => f.write_str(
+                "The digits of an amount must be separated by single \
+                 underscores in groups of three, e.g. 1_000_000.5.",
+            ),
+            Self::InvalidExponent => f.write_str(
+                "The exponent of an amount must be an integer following its \
+                 digits, e.g. 1.5e3.",
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AmountParseError {}
+
+/// Panics if the amount exceeds [`uint::MAX_SIGNED_VALUE`]
+#[cfg(not(feature = "strict-arith"))]
 impl From<Amount> for Change {
     fn from(amount: Amount) -> Self {
         amount.raw.try_into().unwrap()
     }
 }
 
+/// Fails if the amount exceeds [`uint::MAX_SIGNED_VALUE`]
+#[cfg(feature = "strict-arith")]
+impl TryFrom<Amount> for Change {
+    type Error = AmountParseError;
+
+    fn try_from(amount: Amount) -> Result<Self, Self::Error> {
+        amount.raw.try_into()
+    }
+}
+
 impl From<Change> for Amount {
     fn from(change: Change) -> Self {
         Amount { raw: change.abs() }
@@ -979,12 +1459,14 @@ impl MaspDigitPos {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<Amount> for IbcAmount {
     fn from(amount: Amount) -> Self {
         primitive_types::U256(amount.raw.0).into()
     }
 }
 
+#[cfg(feature = "std")]
 impl From<DenominatedAmount> for IbcAmount {
     fn from(amount: DenominatedAmount) -> Self {
         amount.canonical().amount.into()
@@ -992,6 +1474,7 @@ impl From<DenominatedAmount> for IbcAmount {
 }
 
 /// Token parameters for each kind of asset held on chain
+#[cfg(feature = "std")]
 #[derive(
     Clone,
     Debug,
@@ -1013,11 +1496,53 @@ pub struct MaspParams {
     pub kd_gain_nom: Dec,
     /// Shielded Pool nominal proportional gain for the given token
     pub kp_gain_nom: Dec,
-    /// Target amount for the given token that is locked in the shielded pool
-    /// TODO: should this be a Uint or DenominatedAmount???
+    /// Target amount for the given token that is locked in the shielded
+    /// pool, in whole units of the token. It's scaled by the token's
+    /// denomination when it's written to storage.
     pub locked_amount_target: u64,
 }
 
+/// The maximum reward rate of the shielded pool for a token
+#[cfg(feature = "std")]
+pub const MAX_MASP_REWARD_RATE: Dec =
+    Dec(I256(Uint([1_000_000_000_000u64, 0u64, 0u64, 0u64])));
+
+#[cfg(feature = "std")]
+impl MaspParams {
+    /// Check that the gains are non-negative and that the max reward rate is
+    /// between zero and [`MAX_MASP_REWARD_RATE`].
+    pub fn validate(&self) -> Result<(), MaspParamsError> {
+        if self.kp_gain_nom.is_negative() {
+            return Err(MaspParamsError::NegativeGain("kp_gain_nom"));
+        }
+        if self.kd_gain_nom.is_negative() {
+            return Err(MaspParamsError::NegativeGain("kd_gain_nom"));
+        }
+        if self.max_reward_rate.is_negative()
+            || self.max_reward_rate > MAX_MASP_REWARD_RATE
+        {
+            return Err(MaspParamsError::MaxRewardRateOutOfRange(
+                self.max_reward_rate,
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[allow(missing_docs)]
+#[cfg(feature = "std")]
+#[derive(Error, Debug)]
+pub enum MaspParamsError {
+    #[error("The MASP {0} must not be negative")]
+    NegativeGain(&'static str),
+    #[error(
+        "The MASP max reward rate {0} must be between 0 and \
+         {MAX_MASP_REWARD_RATE}"
+    )]
+    MaxRewardRateOutOfRange(Dec),
+}
+
+#[cfg(feature = "std")]
 impl Default for MaspParams {
     fn default() -> Self {
         Self {
@@ -1029,7 +1554,54 @@ impl Default for MaspParams {
     }
 }
 
+/// The maximum length of a token's symbol
+pub const MAX_METADATA_SYMBOL_LEN: usize = 12;
+/// The maximum length of a token's display name
+pub const MAX_METADATA_NAME_LEN: usize = 64;
+/// The maximum length of a token's URI
+pub const MAX_METADATA_URI_LEN: usize = 256;
+
+/// Human-readable metadata of a token, for the wallets to display
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Deserialize,
+    Serialize,
+)]
+pub struct Metadata {
+    /// The ticker symbol, e.g. `NAM`
+    pub symbol: String,
+    /// The display name, e.g. `Namada`
+    pub name: String,
+    /// The number of decimal places to display the amounts with. This is
+    /// only a hint, the amounts are denominated by the token's denomination.
+    pub decimals: Denomination,
+    /// An optional URI of the token's logo or description
+    #[serde(default)]
+    pub uri: Option<String>,
+}
+
+impl Metadata {
+    /// Check that the symbol is a non-empty ASCII alphanumeric string and
+    /// that the fields are within their maximum lengths.
+    pub fn is_valid(&self) -> bool {
+        (1..=MAX_METADATA_SYMBOL_LEN).contains(&self.symbol.len())
+            && self.symbol.bytes().all(|b| b.is_ascii_alphanumeric())
+            && (1..=MAX_METADATA_NAME_LEN).contains(&self.name.len())
+            && self
+                .uri
+                .as_ref()
+                .map_or(true, |uri| uri.len() <= MAX_METADATA_URI_LEN)
+    }
+}
+
 /// A simple bilateral token transfer
+#[cfg(feature = "std")]
 #[derive(
     Debug,
     Clone,
@@ -1058,7 +1630,112 @@ pub struct Transfer {
     pub shielded: Option<Hash>,
 }
 
+/// An approval of a spender to transfer the owner's tokens, up to an
+/// allowance
+#[cfg(feature = "std")]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct Approve {
+    /// The owner of the tokens
+    pub owner: Address,
+    /// The address allowed to transfer the owner's tokens
+    pub spender: Address,
+    /// Token's address
+    pub token: Address,
+    /// The allowance, replacing the previous one. A zero allowance revokes
+    /// the approval.
+    pub amount: DenominatedAmount,
+}
+
+/// A transfer of the owner's tokens by an approved spender, within its
+/// allowance
+#[cfg(feature = "std")]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct TransferFrom {
+    /// The owner of the tokens
+    pub owner: Address,
+    /// The approved spender that makes the transfer
+    pub spender: Address,
+    /// Target address will receive the tokens
+    pub target: Address,
+    /// Token's address
+    pub token: Address,
+    /// The amount of tokens
+    pub amount: DenominatedAmount,
+}
+
+/// A transparent transfer that is a leg of a [`MultiTransfer`]
+#[cfg(feature = "std")]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct TransferLeg {
+    /// Source address will spend the tokens
+    pub source: Address,
+    /// Target address will receive the tokens
+    pub target: Address,
+    /// Token's address
+    pub token: Address,
+    /// The amount of tokens
+    pub amount: DenominatedAmount,
+}
+
+/// A batch of transparent transfers that are applied atomically, such that
+/// either all of them or none of them are applied
+#[cfg(feature = "std")]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct MultiTransfer {
+    /// The transfers, applied in order
+    pub legs: Vec<TransferLeg>,
+}
+
 #[allow(missing_docs)]
+#[cfg(feature = "std")]
 #[derive(Error, Debug)]
 pub enum TransferError {
     #[error("Invalid address is specified: {0}")]
@@ -1069,6 +1746,110 @@ pub enum TransferError {
     NoToken,
 }
 
+/// The net changes of token amounts made by a tx, by token. Accumulating the
+/// changes of every changed key is checked, so that VPs don't have to compute
+/// the differences between the pre and post amounts themselves.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BalanceDelta {
+    changes: BTreeMap<Address, Change>,
+}
+
+#[cfg(feature = "std")]
+impl BalanceDelta {
+    /// Accumulate the change of an amount of the token from `pre` to `post`
+    pub fn record(
+        &mut self,
+        token: &Address,
+        pre: Amount,
+        post: Amount,
+    ) -> Result<(), BalanceDeltaError> {
+        let change = checked_change(token, pre, post)?;
+        self.add(token, change)
+    }
+
+    /// Accumulate the opposite of the change of an amount of the token from
+    /// `pre` to `post`, e.g. of the minted supply that must offset the
+    /// changes of the balances
+    pub fn record_opposite(
+        &mut self,
+        token: &Address,
+        pre: Amount,
+        post: Amount,
+    ) -> Result<(), BalanceDeltaError> {
+        let change = checked_change(token, pre, post)?;
+        self.add(token, -change)
+    }
+
+    /// Accumulate a change of the token
+    pub fn add(
+        &mut self,
+        token: &Address,
+        change: Change,
+    ) -> Result<(), BalanceDeltaError> {
+        let acc = self.changes.entry(token.clone()).or_default();
+        *acc = acc
+            .checked_add(&change)
+            .ok_or_else(|| BalanceDeltaError::Overflow(token.clone()))?;
+        Ok(())
+    }
+
+    /// The net change of the token
+    pub fn get(&self, token: &Address) -> Change {
+        self.changes.get(token).copied().unwrap_or_default()
+    }
+
+    /// Iterate over the net changes of the tokens
+    pub fn iter(&self) -> impl Iterator<Item = (&Address, &Change)> {
+        self.changes.iter()
+    }
+
+    /// Check that no token amount decreased
+    pub fn is_non_negative(&self) -> bool {
+        self.changes.values().all(Change::non_negative)
+    }
+
+    /// Check that the net change of every token is zero
+    pub fn is_net_zero(&self) -> bool {
+        self.changes.values().all(Change::is_zero)
+    }
+
+    /// Check that the net change of every token is zero, returning the first
+    /// unbalanced token otherwise
+    pub fn assert_net_zero(&self) -> Result<(), BalanceDeltaError> {
+        match self.changes.iter().find(|(_, change)| !change.is_zero()) {
+            Some((token, change)) => Err(BalanceDeltaError::NotNetZero {
+                token: token.clone(),
+                change: *change,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The change of an amount of the token from `pre` to `post`
+#[cfg(feature = "std")]
+fn checked_change(
+    token: &Address,
+    pre: Amount,
+    post: Amount,
+) -> Result<Change, BalanceDeltaError> {
+    let overflow = || BalanceDeltaError::Overflow(token.clone());
+    let pre = Change::try_from(pre.raw).map_err(|_| overflow())?;
+    let post = Change::try_from(post.raw).map_err(|_| overflow())?;
+    post.checked_sub(&pre).ok_or_else(overflow)
+}
+
+#[allow(missing_docs)]
+#[cfg(feature = "std")]
+#[derive(Error, Debug)]
+pub enum BalanceDeltaError {
+    #[error("Overflowed in the balance changes of token {0}")]
+    Overflow(Address),
+    #[error("The balances of token {token} changed by {change} instead of 0")]
+    NotNetZero { token: Address, change: Change },
+}
+
 #[cfg(any(test, feature = "testing"))]
 /// Testing helpers and strategies for tokens
 pub mod testing {
@@ -1138,6 +1919,9 @@ pub mod testing {
 
 #[cfg(test)]
 mod tests {
+    use num_rational::BigRational;
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -1199,6 +1983,78 @@ mod tests {
         assert_eq!("200", amount.to_string_precise());
     }
 
+    #[test]
+    fn test_denominated_amount_from_str_notations() {
+        let amount = |raw: u64, denom: u8| {
+            DenominatedAmount::new(Amount::from_u64(raw), denom.into())
+        };
+        let parse = |s: &str| DenominatedAmount::from_str(s);
+
+        // Digit separators
+        assert_eq!(parse("1_000_000").unwrap(), amount(1_000_000, 0));
+        assert_eq!(parse("12_345.678_9").unwrap(), amount(123_456_789, 4));
+        assert_eq!(parse("0.000_001").unwrap(), amount(1, 6));
+        for invalid in [
+            "_1", "1_", "1__000", "1_00", "1_0000", "1_.5", "1._5", "0.00_1",
+        ] {
+            assert!(
+                matches!(
+                    parse(invalid),
+                    Err(AmountParseError::InvalidSeparator)
+                ),
+                "{invalid}"
+            );
+        }
+
+        // Exponents
+        assert_eq!(parse("1e6").unwrap(), amount(1_000_000, 0));
+        assert_eq!(parse("1.5e3").unwrap(), amount(1500, 0));
+        assert_eq!(parse("1.2345E2").unwrap(), amount(12345, 2));
+        assert_eq!(parse("15e-1").unwrap(), amount(15, 1));
+        assert_eq!(parse("1.5e+3").unwrap(), amount(1500, 0));
+        assert_eq!(parse("1_000.5e3").unwrap(), amount(1_000_500, 0));
+        for invalid in ["e6", ".e6", "1e", "1e1.5", "1e6e3", "1e1_0", "1e-"] {
+            assert!(
+                matches!(
+                    parse(invalid),
+                    Err(AmountParseError::InvalidExponent)
+                ),
+                "{invalid}"
+            );
+        }
+        assert!(matches!(
+            parse("1e-256"),
+            Err(AmountParseError::ScaleTooLarge(256, 255))
+        ));
+        assert!(matches!(parse("1e78"), Err(AmountParseError::InvalidRange)));
+        assert!(matches!(parse("1.5x3"), Err(AmountParseError::NotNumeric)));
+    }
+
+    #[test]
+    fn test_format_with_separators() {
+        let amount = |raw: u64, denom: u8| {
+            DenominatedAmount::new(Amount::from_u64(raw), denom.into())
+        };
+        assert_eq!(
+            amount(1234567891011, 6).format_with_separators(','),
+            "1,234,567.891011"
+        );
+        assert_eq!(amount(1234567, 0).format_with_separators(','), "1,234,567");
+        assert_eq!(amount(123456, 0).format_with_separators(','), "123,456");
+        assert_eq!(amount(123, 0).format_with_separators(','), "123");
+        assert_eq!(amount(0, 0).format_with_separators(','), "0");
+        assert_eq!(amount(0, 6).format_with_separators(','), "0");
+        assert_eq!(amount(1000, 6).format_with_separators(','), "0.001");
+        assert_eq!(
+            amount(100000000000, 6).format_with_separators('\''),
+            "100'000"
+        );
+        assert_eq!(
+            Amount::from_u64(12345678).format_with_separators(3.into(), ' '),
+            "12 345.678"
+        );
+    }
+
     #[test]
     fn test_amount_checked_sub() {
         let max = Amount::native_whole(u64::MAX);
@@ -1210,7 +2066,12 @@ mod tests {
         assert_eq!(zero.checked_sub(max), None);
 
         assert_eq!(max.checked_sub(zero), Some(max));
-        assert_eq!(max.checked_sub(one), Some(max - one));
+        assert_eq!(
+            max.checked_sub(one),
+            Some(Amount {
+                raw: max.raw - one.raw
+            })
+        );
         assert_eq!(max.checked_sub(max), Some(zero));
     }
 
@@ -1237,10 +2098,16 @@ mod tests {
         assert_eq!(zero.checked_add(zero), Some(zero));
         assert_eq!(zero.checked_signed_add(zero), Some(zero));
         assert_eq!(zero.checked_add(one), Some(one));
-        assert_eq!(zero.checked_add(max - one), Some(max - one));
+        let max_minus_one = Amount {
+            raw: max.raw - one.raw,
+        };
+        let max_signed_minus_one = Amount {
+            raw: max_signed.raw - one.raw,
+        };
+        assert_eq!(zero.checked_add(max_minus_one), Some(max_minus_one));
         assert_eq!(
-            zero.checked_signed_add(max_signed - one),
-            Some(max_signed - one)
+            zero.checked_signed_add(max_signed_minus_one),
+            Some(max_signed_minus_one)
         );
         assert_eq!(zero.checked_add(max), Some(max));
         assert_eq!(zero.checked_signed_add(max_signed), Some(max_signed));
@@ -1251,8 +2118,25 @@ mod tests {
         assert_eq!(max.checked_add(max), None);
 
         assert_eq!(max_signed.checked_add(zero), Some(max_signed));
-        assert_eq!(max_signed.checked_add(one), Some(max_signed + one));
+        assert_eq!(
+            max_signed.checked_add(one),
+            Some(Amount {
+                raw: max_signed.raw + one.raw
+            })
+        );
         assert_eq!(max_signed.checked_signed_add(max_signed), None);
+
+        assert_eq!(Amount::checked_sum([]), Some(zero));
+        assert_eq!(Amount::checked_sum([max_minus_one, one]), Some(max));
+        assert_eq!(Amount::checked_sum([max_minus_one, one, one]), None);
+        assert_eq!(
+            [one, one].into_iter().try_sum(),
+            Ok(Amount::native_whole(2))
+        );
+        assert_eq!(
+            [max, zero, one].into_iter().try_sum(),
+            Err(AmountOverflowError)
+        );
     }
 
     #[test]
@@ -1313,6 +2197,61 @@ mod tests {
         assert!(!non_zero.is_zero());
     }
 
+    #[cfg(not(feature = "deprecated-dec-into-amount"))]
+    #[test]
+    fn test_amount_try_from_dec() {
+        let dec = Dec::from_str("12.75").expect("Test failed");
+        assert_eq!(Amount::try_from(dec).unwrap(), Amount::from(12));
+        assert_eq!(Amount::try_from(Dec::zero()).unwrap(), Amount::zero());
+        assert!(matches!(
+            Amount::try_from(-dec),
+            Err(AmountParseError::NegativeDec)
+        ));
+    }
+
+    #[test]
+    fn test_amount_checked_mul_div() {
+        let amount = Amount::native_whole(7);
+        assert_eq!(
+            amount.checked_mul_div(Uint::from(2), Uint::from(3)),
+            Some(Amount::from(4_666_666))
+        );
+        assert_eq!(
+            amount.checked_mul_div(Uint::from(3), Uint::from(3)),
+            Some(amount)
+        );
+        assert_eq!(amount.checked_mul_div(Uint::one(), Uint::zero()), None);
+
+        // The product may overflow as long as the quotient fits
+        let max = Amount::max();
+        assert_eq!(max.checked_mul(Amount::from(3)), None);
+        assert_eq!(
+            max.checked_mul_div(Uint::from(3), Uint::from(3)),
+            Some(max)
+        );
+        assert_eq!(
+            max.checked_mul_div(Uint::from(2), Uint::from(4)),
+            Some(Amount::from_uint(uint::MAX_VALUE / 2, 0).unwrap())
+        );
+        assert_eq!(max.checked_mul_div(Uint::from(4), Uint::from(3)), None);
+
+        // Unlike the tuple multiplication, the remainder is scaled too
+        let amount = Amount::from(10);
+        #[cfg(not(feature = "strict-arith"))]
+        assert_eq!((amount * (2u64, 3u64)).0, Amount::from(6));
+        assert_eq!(
+            amount.checked_mul_div(Uint::from(2), Uint::from(3)),
+            Some(Amount::from(6))
+        );
+        let amount = Amount::from(11);
+        #[cfg(not(feature = "strict-arith"))]
+        assert_eq!((amount * (2u64, 3u64)).0, Amount::from(6));
+        assert_eq!(
+            amount.checked_mul_div(Uint::from(2), Uint::from(3)),
+            Some(Amount::from(7))
+        );
+    }
+
     #[test]
     fn test_token_amount_mul_ceil() {
         let one = Amount::from(1);
@@ -1341,6 +2280,78 @@ mod tests {
         assert_eq!(c.checked_sub(c).unwrap(), g);
     }
 
+    #[test]
+    fn test_denominated_div_precision() {
+        let amount = |raw: u64, denom: u8| {
+            DenominatedAmount::new(Amount::from_u64(raw), denom.into())
+        };
+        let div = |lhs: DenominatedAmount,
+                   rhs: DenominatedAmount,
+                   precision: u8,
+                   rounding: RoundingMode| {
+            lhs.checked_div_precision(rhs, precision.into(), rounding)
+        };
+
+        // 1 / 3 = 0.333...
+        let (one, three) = (amount(1, 0), amount(3, 0));
+        assert_eq!(
+            div(one, three, 2, RoundingMode::Floor),
+            Some(amount(33, 2))
+        );
+        assert_eq!(div(one, three, 2, RoundingMode::Ceil), Some(amount(34, 2)));
+        assert_eq!(
+            div(one, three, 2, RoundingMode::HalfUp),
+            Some(amount(33, 2))
+        );
+
+        // 2 / 3 = 0.666...
+        let two = amount(2, 0);
+        assert_eq!(div(two, three, 1, RoundingMode::Floor), Some(amount(6, 1)));
+        assert_eq!(
+            div(two, three, 1, RoundingMode::HalfUp),
+            Some(amount(7, 1))
+        );
+
+        // The halves are rounded up: 0.25 / 1 = 0.3 at 1 decimal place
+        let quarter = amount(25, 2);
+        assert_eq!(
+            div(quarter, one, 1, RoundingMode::HalfUp),
+            Some(amount(3, 1))
+        );
+        assert_eq!(
+            div(quarter, one, 1, RoundingMode::Floor),
+            Some(amount(2, 1))
+        );
+
+        // Exact divisions are not rounded: 1.5 / 0.05 = 30
+        let (lhs, rhs) = (amount(15, 1), amount(5, 2));
+        for rounding in [
+            RoundingMode::Floor,
+            RoundingMode::Ceil,
+            RoundingMode::HalfUp,
+        ] {
+            assert_eq!(div(lhs, rhs, 0, rounding), Some(amount(30, 0)));
+            assert_eq!(div(lhs, rhs, 3, rounding), Some(amount(30_000, 3)));
+        }
+
+        // A precision lower than the one of the dividend: 1.234 / 2 = 0.617
+        let lhs = amount(1234, 3);
+        assert_eq!(div(lhs, two, 1, RoundingMode::Floor), Some(amount(6, 1)));
+        assert_eq!(div(lhs, two, 1, RoundingMode::Ceil), Some(amount(7, 1)));
+        assert_eq!(div(lhs, two, 2, RoundingMode::HalfUp), Some(amount(62, 2)));
+
+        // Division by zero
+        assert_eq!(div(one, amount(0, 3), 2, RoundingMode::Floor), None);
+
+        // The quotient overflows
+        let max = DenominatedAmount::new(Amount::max(), 0.into());
+        assert_eq!(div(max, amount(1, 1), 0, RoundingMode::Floor), None);
+        assert_eq!(
+            div(max, one, 0, RoundingMode::Floor),
+            Some(DenominatedAmount::new(Amount::max(), 0.into()))
+        );
+    }
+
     #[test]
     fn test_denominated_amt_ord() {
         let denom_1 = DenominatedAmount {
@@ -1396,4 +2407,241 @@ mod tests {
             Ordering::Less
         );
     }
+
+    #[test]
+    fn test_balance_delta() {
+        use crate::types::address::testing::{
+            established_address_1, established_address_2,
+        };
+
+        let token = established_address_1();
+        let other_token = established_address_2();
+        let mut delta = BalanceDelta::default();
+        assert!(delta.is_net_zero());
+
+        // A transfer of 10 from one balance to another
+        delta
+            .record(&token, Amount::from(100), Amount::from(90))
+            .unwrap();
+        assert_eq!(delta.get(&token), -Change::from(10));
+        assert!(!delta.is_non_negative());
+        delta
+            .record(&token, Amount::from(5), Amount::from(15))
+            .unwrap();
+        assert_eq!(delta.get(&token), Change::zero());
+        delta.assert_net_zero().unwrap();
+
+        // A mint of 7 offset by the change of the supply
+        delta
+            .record(&other_token, Amount::zero(), Amount::from(7))
+            .unwrap();
+        assert!(matches!(
+            delta.assert_net_zero(),
+            Err(BalanceDeltaError::NotNetZero { token, change })
+                if token == other_token && change == Change::from(7)
+        ));
+        delta
+            .record_opposite(&other_token, Amount::from(1), Amount::from(8))
+            .unwrap();
+        assert!(delta.is_net_zero());
+
+        // Amounts that don't fit a change overflow
+        assert!(matches!(
+            delta.record(&token, Amount::zero(), Amount::max()),
+            Err(BalanceDeltaError::Overflow(_))
+        ));
+        delta
+            .record(&token, Amount::zero(), Amount::max_signed())
+            .unwrap();
+        assert!(matches!(
+            delta.record(&token, Amount::zero(), Amount::from(1)),
+            Err(BalanceDeltaError::Overflow(_))
+        ));
+    }
+
+    /// Test that the integer conversions of the amounts fail out of range.
+    #[test]
+    fn test_amount_int_conversions() {
+        let amount = Amount::from_u128(u128::MAX);
+        assert_eq!(u128::try_from(amount).unwrap(), u128::MAX);
+        assert!(matches!(
+            u128::try_from(Amount {
+                raw: amount.raw + Uint::one()
+            }),
+            Err(AmountParseError::InvalidRange)
+        ));
+        assert!(matches!(
+            Change::try_from(Amount::max().raw_amount()),
+            Err(AmountParseError::InvalidRange)
+        ));
+        let change = Change::from(i128::MIN + 1);
+        assert_eq!(i128::try_from(change).unwrap(), i128::MIN + 1);
+        assert!(matches!(
+            i128::try_from(Amount::max_signed().change()),
+            Err(AmountParseError::InvalidRange)
+        ));
+        assert_eq!(
+            AmountParseError::ScaleTooLarge(80, 77).to_string(),
+            "Error decoding token amount, too many decimal places: 80. \
+             Maximum 77"
+        );
+    }
+
+    #[test]
+    fn test_metadata_validation() {
+        let metadata = Metadata {
+            symbol: "NAM".to_string(),
+            name: "Namada".to_string(),
+            decimals: NATIVE_MAX_DECIMAL_PLACES.into(),
+            uri: None,
+        };
+        assert!(metadata.is_valid());
+        assert!(Metadata {
+            uri: Some("https://namada.net/logo.svg".to_string()),
+            ..metadata.clone()
+        }
+        .is_valid());
+        for symbol in ["", "N-AM", "NAM ", &"N".repeat(13)] {
+            let metadata = Metadata {
+                symbol: symbol.to_string(),
+                ..metadata.clone()
+            };
+            assert!(!metadata.is_valid(), "{symbol:?} must be invalid");
+        }
+        assert!(!Metadata {
+            name: String::new(),
+            ..metadata.clone()
+        }
+        .is_valid());
+        assert!(!Metadata {
+            uri: Some("u".repeat(MAX_METADATA_URI_LEN + 1)),
+            ..metadata
+        }
+        .is_valid());
+    }
+
+    /// The exact rational value of a denominated amount
+    fn exact_value(amount: &DenominatedAmount) -> BigRational {
+        let ratio = format!(
+            "{}/1{}",
+            amount.amount.raw,
+            "0".repeat(amount.denom.0.into())
+        );
+        BigRational::from_str(&ratio).unwrap()
+    }
+
+    /// Generate a denominated amount over the whole range of the raw amounts
+    /// and of the denominations
+    fn arb_raw_denominated_amount() -> impl Strategy<Value = DenominatedAmount>
+    {
+        (any::<[u64; 4]>(), any::<u8>()).prop_map(|(raw, denom)| {
+            DenominatedAmount::new(Amount { raw: Uint(raw) }, denom.into())
+        })
+    }
+
+    /// Test the comparisons of the amounts whose denominations differ by more
+    /// than the scales that fit in a raw amount
+    #[test]
+    fn test_denominated_amount_cmp_large_denoms() {
+        let big = DenominatedAmount::new(Amount::from(1_u64), 0.into());
+        let small = DenominatedAmount::new(Amount::max(), 200.into());
+        assert_eq!(big.cmp(&small), Ordering::Greater);
+        assert_eq!(small.cmp(&big), Ordering::Less);
+        let zero = DenominatedAmount::new(Amount::zero(), 255.into());
+        assert_eq!(zero.cmp(&Amount::zero().into()), Ordering::Equal);
+    }
+
+    /// Test that the raw amounts only parse the decimal integers.
+    #[test]
+    fn test_raw_amount_from_str() {
+        assert_eq!(
+            RawAmount::from_str("1000000").unwrap(),
+            RawAmount(Amount::from(1_000_000_u64))
+        );
+        for invalid in ["", "1.5", "-1", "1e6", " 1", "1_000"] {
+            assert!(RawAmount::from_str(invalid).is_err(), "{invalid}");
+        }
+        let too_large = format!("{}0", Amount::max().raw);
+        assert!(RawAmount::from_str(&too_large).is_err());
+
+        #[derive(Deserialize, Serialize)]
+        struct Config {
+            threshold: RawAmount,
+        }
+        let config: Config = toml::from_str("threshold = \"15\"").unwrap();
+        assert_eq!(config.threshold, RawAmount(Amount::from(15_u64)));
+        assert!(toml::from_str::<Config>("threshold = \"1.5\"").is_err());
+        assert_eq!(
+            toml::to_string(&config).unwrap().trim(),
+            "threshold = \"15\""
+        );
+    }
+
+    proptest! {
+        /// Test that the raw string of an amount parses back to it.
+        #[test]
+        fn test_raw_amount_round_trip(
+            amount in arb_raw_denominated_amount(),
+        ) {
+            let raw = RawAmount(amount.amount);
+            let string = raw.to_string();
+            prop_assert_eq!(RawAmount::from_str(&string).ok(), Some(raw));
+            prop_assert_eq!(String::from(raw), string);
+        }
+
+        /// Test that the precise string of an amount parses back to it.
+        #[test]
+        fn test_denominated_amount_precise_round_trip(
+            amount in arb_raw_denominated_amount(),
+        ) {
+            let string = amount.to_string_precise();
+            prop_assert_eq!(
+                DenominatedAmount::from_str(&string).ok(),
+                Some(amount)
+            );
+        }
+
+        /// Test that the ordering of the amounts is the one of their exact
+        /// values.
+        #[test]
+        fn test_denominated_amount_ord(
+            lhs in arb_raw_denominated_amount(),
+            rhs in arb_raw_denominated_amount(),
+        ) {
+            let expected = exact_value(&lhs).cmp(&exact_value(&rhs));
+            prop_assert_eq!(lhs.cmp(&rhs), expected);
+            prop_assert_eq!(rhs.cmp(&lhs), expected.reverse());
+            prop_assert_eq!(lhs.partial_cmp(&rhs), Some(expected));
+        }
+
+        /// Test the ordering of the amounts next to an amount rescaled to a
+        /// larger denomination, where the ceiled division of the comparison
+        /// matters.
+        #[test]
+        fn test_denominated_amount_ord_rescaled(
+            amount in arb_raw_denominated_amount(),
+            exp in 0u8..6,
+            offset in -1i8..=1,
+        ) {
+            let raw = amount
+                .amount
+                .raw
+                .checked_mul(Uint::exp10(exp.into()))
+                .and_then(|raw| match offset {
+                    -1 => raw.checked_sub(Uint::one()),
+                    0 => Some(raw),
+                    _ => raw.checked_add(Uint::one()),
+                });
+            let denom = amount.denom.0.checked_add(exp);
+            prop_assume!(raw.is_some() && denom.is_some());
+            let rescaled = DenominatedAmount::new(
+                Amount { raw: raw.unwrap() },
+                denom.unwrap().into(),
+            );
+            let expected = exact_value(&amount).cmp(&exact_value(&rescaled));
+            prop_assert_eq!(expected, 0.cmp(&offset));
+            prop_assert_eq!(amount.cmp(&rescaled), expected);
+            prop_assert_eq!(rescaled.cmp(&amount), expected.reverse());
+        }
+    }
 }
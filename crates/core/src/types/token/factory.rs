@@ -0,0 +1,159 @@
+//! The token factory, creating fungible tokens with txs.
+//!
+//! The address of a token created by the factory is derived from its creator
+//! and a salt chosen by the creator, such that it's known before the token is
+//! created and can't be taken by anyone else. The origin of every created
+//! token is recorded under the [`ADDRESS`] of the factory:
+//!
+//! - `#TokenFactory/origin/$<token>` holds the [`TokenOrigin`] of a token
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use borsh_ext::BorshSerializeExt;
+use serde::{Deserialize, Serialize};
+
+use crate::types::address::{Address, EstablishedAddress, InternalAddress};
+use crate::types::hash::Hash;
+use crate::types::storage::{self, DbKeySeg, FixedAddress, KeySeg};
+use crate::types::token::{Amount, Denomination, Metadata};
+
+/// The internal address of the token factory
+pub const ADDRESS: Address = Address::Internal(InternalAddress::TokenFactory);
+/// The maximum number of bytes of the salt of a created token
+pub const MAX_SALT_LEN: usize = 32;
+/// Key segment for the origin of a created token
+pub const ORIGIN_STORAGE_KEY: &str = "origin";
+/// The name of the WASM VP installed in the accounts of the created tokens
+pub const TOKEN_VP_NAME: &str = "vp_token.wasm";
+/// The domain of the hash that derives the addresses of the created tokens
+const TOKEN_ADDRESS_DOMAIN: &[u8] = b"namada-token-factory";
+
+/// The creator and the salt from which the address of a created token is
+/// derived
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct TokenOrigin {
+    /// The creator of the token
+    pub creator: Address,
+    /// The salt chosen by the creator
+    pub salt: Vec<u8>,
+}
+
+impl TokenOrigin {
+    /// The address of the token created with this origin
+    pub fn token_address(&self) -> Address {
+        token_address(&self.creator, &self.salt)
+    }
+}
+
+/// A creation of a new token, authorized by its creator
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct CreateToken {
+    /// The creator and the salt of the token
+    pub origin: TokenOrigin,
+    /// The denomination of the token
+    pub denom: Denomination,
+    /// The metadata of the token
+    pub metadata: Option<Metadata>,
+    /// The supply of the token minted to its creator
+    pub initial_supply: Amount,
+    /// The hash of the section of the tx with the code of the token VP
+    pub vp_code_hash: Hash,
+}
+
+/// Derive the address of the token created by a creator with a salt. The
+/// salt must have at most [`MAX_SALT_LEN`] bytes.
+pub fn token_address(creator: &Address, salt: &[u8]) -> Address {
+    let bytes =
+        [TOKEN_ADDRESS_DOMAIN, &creator.serialize_to_vec(), salt].concat();
+    Address::Established(EstablishedAddress::from(Hash::sha256(bytes).0))
+}
+
+/// Obtain a storage key for the origin of a created token.
+pub fn origin_key(token: &Address) -> storage::Key {
+    storage::Key::from(ADDRESS.to_db_key())
+        .push(&ORIGIN_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&FixedAddress(token.clone()))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a token factory key.
+pub fn is_token_factory_key(key: &storage::Key) -> bool {
+    matches!(
+        key.segments.first(),
+        Some(DbKeySeg::AddressSeg(addr)) if *addr == ADDRESS
+    )
+}
+
+/// Check if the given storage key is a key of the origin of a created token.
+/// If it is, return the token.
+pub fn is_any_origin_key(key: &storage::Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(seg), DbKeySeg::FixedAddressSeg(token)]
+            if *addr == ADDRESS && seg == ORIGIN_STORAGE_KEY =>
+        {
+            Some(token)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    /// Test that the addresses of the created tokens only depend on their
+    /// creator and salt.
+    #[test]
+    fn test_token_address() {
+        let creator = established_address_1();
+        let token = token_address(&creator, b"salt");
+        assert!(matches!(token, Address::Established(_)));
+        assert_eq!(token, token_address(&creator, b"salt"));
+        assert_ne!(token, token_address(&creator, b"other salt"));
+        assert_ne!(token, token_address(&established_address_2(), b"salt"));
+        let origin = TokenOrigin {
+            creator,
+            salt: b"salt".to_vec(),
+        };
+        assert_eq!(origin.token_address(), token);
+    }
+
+    /// Test that the origin keys are parsed back to their token.
+    #[test]
+    fn test_origin_key() {
+        let token = token_address(&established_address_1(), b"salt");
+        let key = origin_key(&token);
+        assert!(is_token_factory_key(&key));
+        assert_eq!(is_any_origin_key(&key), Some(&token));
+        let parsed = storage::Key::parse(key.to_string()).unwrap();
+        assert_eq!(parsed, key);
+        assert!(!is_token_factory_key(&storage::Key::from(
+            token.to_db_key()
+        )));
+    }
+}
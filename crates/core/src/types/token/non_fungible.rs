@@ -0,0 +1,326 @@
+//! Non-fungible tokens (NFTs), mirroring the layout of the fungible ones.
+//!
+//! A class of NFTs is identified by the address of its account, which
+//! authorizes the minting of its tokens. Each token of a class is identified
+//! by an [`NftId`] and it's owned by a single address, recorded under the
+//! [`ADDRESS`] of the NFTs:
+//!
+//! - `#Nft/<class>/owner/<id>` holds the owner's address
+//! - `#Nft/<class>/metadata/<id>` holds the optional metadata URI
+
+use std::fmt::Display;
+use std::io::{Error as IoError, ErrorKind, Read};
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::{self, DbKeySeg, KeySeg};
+
+/// The internal address of the NFTs
+pub const ADDRESS: Address = Address::Internal(InternalAddress::Nft);
+/// The maximum number of characters of an NFT id
+pub const MAX_NFT_ID_LEN: usize = 128;
+/// Key segment for the owner of an NFT
+pub const OWNER_STORAGE_KEY: &str = "owner";
+/// Key segment for the metadata of an NFT
+pub const METADATA_STORAGE_KEY: &str = "metadata";
+
+/// An invalid NFT id
+#[allow(missing_docs)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NftIdError {
+    #[error("An NFT id can't be empty")]
+    Empty,
+    #[error(
+        "An NFT id can have at most {MAX_NFT_ID_LEN} characters, but it has \
+         {0}"
+    )]
+    TooLong(usize),
+    #[error("Invalid character {0:?} in an NFT id")]
+    InvalidChar(char),
+}
+
+/// The id of an NFT within its class. It's made of at most [`MAX_NFT_ID_LEN`]
+/// ASCII alphanumeric characters, `-`, `_` or `.`, so that it's always a
+/// valid storage key segment.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct NftId(String);
+
+impl NftId {
+    /// Validate an NFT id
+    pub fn new(id: impl Into<String>) -> Result<Self, NftIdError> {
+        let id = id.into();
+        if id.is_empty() {
+            return Err(NftIdError::Empty);
+        }
+        if id.len() > MAX_NFT_ID_LEN {
+            return Err(NftIdError::TooLong(id.len()));
+        }
+        if let Some(c) = id
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && !"-_.".contains(*c))
+        {
+            return Err(NftIdError::InvalidChar(c));
+        }
+        Ok(Self(id))
+    }
+
+    /// The id as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for NftId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for NftId {
+    type Err = NftIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for NftId {
+    type Error = NftIdError;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        Self::new(id)
+    }
+}
+
+impl From<NftId> for String {
+    fn from(id: NftId) -> Self {
+        id.0
+    }
+}
+
+impl BorshDeserialize for NftId {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let id = String::deserialize_reader(reader)?;
+        Self::new(id).map_err(|err| IoError::new(ErrorKind::InvalidData, err))
+    }
+}
+
+impl KeySeg for NftId {
+    fn parse(string: String) -> storage::Result<Self> {
+        Self::new(string)
+            .map_err(|err| storage::Error::ParseKeySeg(err.to_string()))
+    }
+
+    fn raw(&self) -> String {
+        self.0.clone()
+    }
+
+    fn to_db_key(&self) -> DbKeySeg {
+        DbKeySeg::StringSeg(self.0.clone())
+    }
+}
+
+/// A mint of a new NFT, authorized by its class
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct MintNft {
+    /// The class of the NFT
+    pub class: Address,
+    /// The id of the NFT within its class
+    pub id: NftId,
+    /// The first owner of the NFT
+    pub owner: Address,
+    /// The metadata URI of the NFT
+    pub metadata: Option<String>,
+}
+
+/// A transfer of an NFT, authorized by its owner
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct TransferNft {
+    /// The class of the NFT
+    pub class: Address,
+    /// The id of the NFT within its class
+    pub id: NftId,
+    /// The owner of the NFT
+    pub source: Address,
+    /// The new owner of the NFT
+    pub target: Address,
+}
+
+/// A burn of an NFT, authorized by its owner
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct BurnNft {
+    /// The class of the NFT
+    pub class: Address,
+    /// The id of the NFT within its class
+    pub id: NftId,
+    /// The owner of the NFT
+    pub owner: Address,
+}
+
+/// Obtain a storage key prefix for all the NFTs of a class.
+pub fn class_prefix(class: &Address) -> storage::Key {
+    storage::Key::from(ADDRESS.to_db_key())
+        .push(&class.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for the owners of all the NFTs of a class.
+pub fn owner_prefix(class: &Address) -> storage::Key {
+    class_prefix(class)
+        .push(&OWNER_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the owner of an NFT.
+pub fn owner_key(class: &Address, id: &NftId) -> storage::Key {
+    owner_prefix(class)
+        .push(id)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the metadata of an NFT.
+pub fn metadata_key(class: &Address, id: &NftId) -> storage::Key {
+    class_prefix(class)
+        .push(&METADATA_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(id)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is an NFT key.
+pub fn is_nft_key(key: &storage::Key) -> bool {
+    matches!(
+        key.segments.first(),
+        Some(DbKeySeg::AddressSeg(addr)) if *addr == ADDRESS
+    )
+}
+
+/// Check if the given storage key is a key of the owner of an NFT. If it is,
+/// return the class and the id of the NFT.
+pub fn is_any_owner_key(key: &storage::Key) -> Option<(&Address, &str)> {
+    is_any_nft_key_of(key, OWNER_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a key of the metadata of an NFT. If it
+/// is, return the class and the id of the NFT.
+pub fn is_any_metadata_key(key: &storage::Key) -> Option<(&Address, &str)> {
+    is_any_nft_key_of(key, METADATA_STORAGE_KEY)
+}
+
+fn is_any_nft_key_of<'a>(
+    key: &'a storage::Key,
+    specific_key: &str,
+) -> Option<(&'a Address, &'a str)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::AddressSeg(class),
+            DbKeySeg::StringSeg(seg),
+            DbKeySeg::StringSeg(id),
+        ] if *addr == ADDRESS && seg == specific_key =>
+        {
+            Some((class, id))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh_ext::BorshSerializeExt;
+
+    use super::*;
+    use crate::types::address::testing::established_address_1;
+
+    /// Test the validation of the NFT ids.
+    #[test]
+    fn test_nft_id() {
+        assert_eq!(NftId::new(""), Err(NftIdError::Empty));
+        assert_eq!(
+            NftId::new("a".repeat(MAX_NFT_ID_LEN + 1)),
+            Err(NftIdError::TooLong(MAX_NFT_ID_LEN + 1))
+        );
+        assert_eq!(NftId::new("a/b"), Err(NftIdError::InvalidChar('/')));
+        assert_eq!(NftId::new("#nft"), Err(NftIdError::InvalidChar('#')));
+        let id = NftId::new("kitty-1_v2.0").unwrap();
+        assert_eq!(id.to_string(), "kitty-1_v2.0");
+
+        let bytes = "a/b".to_string().serialize_to_vec();
+        assert!(NftId::try_from_slice(&bytes).is_err());
+        let bytes = id.serialize_to_vec();
+        assert_eq!(NftId::try_from_slice(&bytes).unwrap(), id);
+    }
+
+    /// Test that the NFT keys are parsed back to their class and id.
+    #[test]
+    fn test_nft_keys() {
+        let class = established_address_1();
+        let id = NftId::new("kitty").unwrap();
+
+        let key = owner_key(&class, &id);
+        assert!(is_nft_key(&key));
+        assert_eq!(is_any_owner_key(&key), Some((&class, "kitty")));
+        assert_eq!(is_any_metadata_key(&key), None);
+        let parsed = storage::Key::parse(key.to_string()).unwrap();
+        assert_eq!(parsed, key);
+
+        let key = metadata_key(&class, &id);
+        assert!(is_nft_key(&key));
+        assert_eq!(is_any_metadata_key(&key), Some((&class, "kitty")));
+        assert_eq!(is_any_owner_key(&key), None);
+        assert!(!is_nft_key(&storage::Key::from(class.to_db_key())));
+    }
+}
@@ -0,0 +1,332 @@
+//! Token amounts typed by their unit.
+//!
+//! A raw [`Amount`] doesn't carry its unit, so an amount of namnam can be
+//! mixed with an amount of whole NAM (e.g. from `Amount::native_whole`)
+//! without any complaint from the compiler. A [`TypedAmount`] tags the amount
+//! with a [`DenomTag`] at no runtime cost, so that only the amounts of the
+//! same unit can be added or compared and the conversions between the units
+//! have to be explicit.
+
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+#[cfg(not(feature = "strict-arith"))]
+use std::iter::Sum;
+use std::marker::PhantomData;
+#[cfg(not(feature = "strict-arith"))]
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::{
+    Amount, AmountParseError, DenominatedAmount, Denomination,
+    NATIVE_MAX_DECIMAL_PLACES,
+};
+use crate::types::dec::Dec;
+use crate::types::uint::Uint;
+
+/// The unit of a [`TypedAmount`]
+pub trait DenomTag:
+    Copy + Debug + Default + Eq + Ord + Hash + Send + Sync + 'static
+{
+    /// The asset of the unit. Amounts can only be converted between the
+    /// units of the same asset.
+    type Asset;
+
+    /// The number of decimal places of a unit, relative to a whole token of
+    /// the asset
+    const DENOM: u8;
+}
+
+/// The native token asset
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Nam;
+
+/// The smallest unit of the native token, 10^-6 NAM
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NamNam;
+
+impl DenomTag for NamNam {
+    type Asset = Nam;
+
+    const DENOM: u8 = NATIVE_MAX_DECIMAL_PLACES;
+}
+
+/// Whole units of the native token
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WholeNam;
+
+impl DenomTag for WholeNam {
+    type Asset = Nam;
+
+    const DENOM: u8 = 0;
+}
+
+/// The smallest unit of an ERC20 token bridged from Ethereum, with the given
+/// number of decimal places
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Erc20<const DECIMALS: u8>;
+
+impl<const DECIMALS: u8> DenomTag for Erc20<DECIMALS> {
+    type Asset = Self;
+
+    const DENOM: u8 = DECIMALS;
+}
+
+/// The smallest unit of an ERC20 token with 18 decimal places, like wrapped
+/// ETH
+pub type Wei = Erc20<18>;
+
+/// An [`Amount`] in the unit given by `D`.
+///
+/// Amounts of different units cannot be mixed:
+///
+/// ```compile_fail
+/// use namada_core::types::token::typed::{NamNam, TypedAmount, WholeNam};
+///
+/// let namnam = TypedAmount::<NamNam>::from_u64(1);
+/// let whole = TypedAmount::<WholeNam>::from_u64(1);
+/// let _ = namnam + whole;
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct TypedAmount<D: DenomTag> {
+    amount: Amount,
+    _unit: PhantomData<D>,
+}
+
+impl<D: DenomTag> TypedAmount<D> {
+    /// Tag a raw amount, which must be in the unit `D`
+    pub const fn from_raw(amount: Amount) -> Self {
+        Self {
+            amount,
+            _unit: PhantomData,
+        }
+    }
+
+    /// Make an amount of the given number of units `D`
+    pub const fn from_u64(units: u64) -> Self {
+        Self::from_raw(Amount::from_u64(units))
+    }
+
+    /// Zero amount
+    pub const fn zero() -> Self {
+        Self::from_u64(0)
+    }
+
+    /// Check if the amount is zero
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    /// Get the raw amount, in the unit `D`
+    pub const fn raw(self) -> Amount {
+        self.amount
+    }
+
+    /// The amount as a number of whole tokens
+    pub const fn denominated(self) -> DenominatedAmount {
+        DenominatedAmount::new(self.amount, Denomination(D::DENOM))
+    }
+
+    /// Convert a number of whole tokens to the unit `D`. Fails if the given
+    /// amount has more decimal places than the unit or if it overflows.
+    pub fn from_denominated(
+        amount: DenominatedAmount,
+    ) -> Result<Self, AmountParseError> {
+        amount.scale(D::DENOM).map(Self::from_raw)
+    }
+
+    /// Convert the amount to the unit `E` of the same asset. Fails if the
+    /// amount cannot be represented exactly in the unit `E`.
+    pub fn try_rescale<E>(self) -> Result<TypedAmount<E>, AmountParseError>
+    where
+        E: DenomTag<Asset = D::Asset>,
+    {
+        if E::DENOM >= D::DENOM {
+            return self
+                .denominated()
+                .scale(E::DENOM)
+                .map(TypedAmount::from_raw);
+        }
+        let (quotient, remainder) = self.div_mod_scaling::<E>();
+        if remainder.is_zero() {
+            Ok(TypedAmount::from_raw(Amount { raw: quotient }))
+        } else {
+            Err(AmountParseError::PrecisionDecrease)
+        }
+    }
+
+    /// Convert the amount to the unit `E` of the same asset, rounding down
+    /// the units that cannot be represented. Fails if the amount overflows.
+    pub fn rescale_floor<E>(self) -> Result<TypedAmount<E>, AmountParseError>
+    where
+        E: DenomTag<Asset = D::Asset>,
+    {
+        if E::DENOM >= D::DENOM {
+            return self.try_rescale();
+        }
+        let (quotient, _remainder) = self.div_mod_scaling::<E>();
+        Ok(TypedAmount::from_raw(Amount { raw: quotient }))
+    }
+
+    /// Divide the raw amount by the scaling from the unit `D` to the coarser
+    /// unit `E`
+    fn div_mod_scaling<E: DenomTag>(self) -> (Uint, Uint) {
+        match Uint::from(10).checked_pow(Uint::from(D::DENOM - E::DENOM)) {
+            Some(scaling) => self.amount.raw.div_mod(scaling),
+            // The scaling is larger than any amount
+            None => (Uint::zero(), self.amount.raw),
+        }
+    }
+
+    /// Checked addition. Returns `None` on overflow.
+    #[must_use]
+    pub fn checked_add(&self, amount: Self) -> Option<Self> {
+        self.amount.checked_add(amount.amount).map(Self::from_raw)
+    }
+
+    /// Checked subtraction. Returns `None` on underflow.
+    #[must_use]
+    pub fn checked_sub(&self, amount: Self) -> Option<Self> {
+        self.amount.checked_sub(amount.amount).map(Self::from_raw)
+    }
+
+    /// Multiply by a decimal [`Dec`] with the result rounded up.
+    ///
+    /// # Panics
+    /// Panics when the `dec` is negative.
+    #[must_use]
+    pub fn mul_ceil(&self, dec: Dec) -> Self {
+        Self::from_raw(self.amount.mul_ceil(dec))
+    }
+}
+
+impl TypedAmount<NamNam> {
+    /// Tag a native token amount, which is always in namnam
+    pub const fn native(amount: Amount) -> Self {
+        Self::from_raw(amount)
+    }
+}
+
+impl<D: DenomTag> Display for TypedAmount<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.denominated())
+    }
+}
+
+#[cfg(not(feature = "strict-arith"))]
+impl<D: DenomTag> Add for TypedAmount<D> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::from_raw(self.amount + rhs.amount)
+    }
+}
+
+#[cfg(not(feature = "strict-arith"))]
+impl<D: DenomTag> AddAssign for TypedAmount<D> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.amount += rhs.amount
+    }
+}
+
+#[cfg(not(feature = "strict-arith"))]
+impl<D: DenomTag> Sub for TypedAmount<D> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::from_raw(self.amount - rhs.amount)
+    }
+}
+
+#[cfg(not(feature = "strict-arith"))]
+impl<D: DenomTag> SubAssign for TypedAmount<D> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.amount -= rhs.amount
+    }
+}
+
+#[cfg(not(feature = "strict-arith"))]
+impl<D: DenomTag> Mul<u64> for TypedAmount<D> {
+    type Output = Self;
+
+    fn mul(self, rhs: u64) -> Self::Output {
+        Self::from_raw(self.amount * rhs)
+    }
+}
+
+#[cfg(not(feature = "strict-arith"))]
+impl<D: DenomTag> Sum for TypedAmount<D> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, amount| acc + amount)
+    }
+}
+
+/// Encoded the same as the raw [`Amount`]
+impl<D: DenomTag> BorshSerialize for TypedAmount<D> {
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        BorshSerialize::serialize(&self.amount, writer)
+    }
+}
+
+impl<D: DenomTag> BorshDeserialize for TypedAmount<D> {
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut R,
+    ) -> std::io::Result<Self> {
+        Amount::deserialize_reader(reader).map(Self::from_raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use borsh_ext::BorshSerializeExt;
+
+    use super::*;
+
+    #[test]
+    fn test_typed_amount_rescale() {
+        let whole = TypedAmount::<WholeNam>::from_u64(3);
+        let namnam: TypedAmount<NamNam> = whole.try_rescale().unwrap();
+        assert_eq!(namnam.raw(), Amount::native_whole(3));
+        assert_eq!(namnam.try_rescale::<WholeNam>().unwrap(), whole);
+        assert_eq!(namnam.denominated().canonical(), whole.denominated());
+
+        // Converting to a coarser unit is only exact without a remainder
+        let namnam = namnam.checked_add(TypedAmount::from_u64(1)).unwrap();
+        assert!(matches!(
+            namnam.try_rescale::<WholeNam>(),
+            Err(AmountParseError::PrecisionDecrease)
+        ));
+        assert_eq!(namnam.rescale_floor::<WholeNam>().unwrap(), whole);
+        assert_eq!(namnam.to_string(), "3.000001");
+    }
+
+    #[test]
+    fn test_typed_amount_from_denominated() {
+        let amount = DenominatedAmount::from_str("1.5").unwrap();
+        assert_eq!(
+            TypedAmount::<NamNam>::from_denominated(amount).unwrap(),
+            TypedAmount::from_u64(1_500_000)
+        );
+        assert_eq!(
+            TypedAmount::<Wei>::from_denominated(amount).unwrap(),
+            TypedAmount::from_raw(Amount::from_u128(1_500_000_000_000_000_000))
+        );
+        assert!(TypedAmount::<WholeNam>::from_denominated(amount).is_err());
+    }
+
+    #[test]
+    fn test_typed_amount_encoding() {
+        let namnam = TypedAmount::<NamNam>::from_u64(1_234);
+        assert_eq!(namnam.serialize_to_vec(), namnam.raw().serialize_to_vec());
+        let decoded =
+            TypedAmount::<NamNam>::try_from_slice(&namnam.serialize_to_vec())
+                .unwrap();
+        assert_eq!(decoded, namnam);
+    }
+}
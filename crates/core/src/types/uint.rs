@@ -1,18 +1,27 @@
 #![allow(clippy::assign_op_pattern)]
 //! An unsigned 256 integer type. Used for, among other things,
 //! the backing type of token amounts.
-use std::cmp::Ordering;
-use std::fmt;
-use std::ops::{Add, AddAssign, BitAnd, Div, Mul, Neg, Rem, Sub, SubAssign};
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+#[cfg(not(feature = "strict-arith"))]
+use core::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use core::ops::{BitAnd, Div, Neg, Rem};
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use impl_num_traits::impl_uint_num_traits;
 use num_integer::Integer;
-use num_traits::{CheckedAdd, CheckedMul, CheckedSub};
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
 use uint::construct_uint;
 
+#[cfg(feature = "std")]
 use super::dec::{Dec, POS_DECIMAL_PRECISION};
 use crate::types::token;
+#[cfg(feature = "std")]
+use crate::types::token::RoundingMode;
 use crate::types::token::{Amount, AmountParseError, MaspDigitPos};
 
 /// The value zero.
@@ -310,7 +319,7 @@ impl serde::Serialize for Uint {
     fn serialize<S>(
         &self,
         serializer: S,
-    ) -> std::result::Result<S::Ok, S::Error>
+    ) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
@@ -320,7 +329,7 @@ impl serde::Serialize for Uint {
 }
 
 impl<'de> serde::Deserialize<'de> for Uint {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
@@ -392,7 +401,7 @@ impl Integer for Uint {
         loop {
             v >>= v.trailing_zeros();
             if u > v {
-                std::mem::swap(&mut u, &mut v);
+                core::mem::swap(&mut u, &mut v);
             }
             v -= u; // here v >= u
             if v.is_zero() {
@@ -588,7 +597,91 @@ impl I256 {
         }
     }
 
+    /// Checked multiplication by an unsigned integer. Returns `None` if the
+    /// absolute value of the product exceeds [`MAX_SIGNED_VALUE`].
+    #[must_use]
+    pub fn checked_mul_uint(&self, rhs: Uint) -> Option<Self> {
+        let prod = Self::try_from(self.abs().checked_mul(rhs)?).ok()?;
+        Some(if self.is_negative() { -prod } else { prod })
+    }
+
+    /// Checked remainder of the truncated division, with the sign of `self`.
+    /// Returns `None` if `rhs` is zero.
+    #[must_use]
+    pub fn checked_rem(&self, rhs: &Self) -> Option<Self> {
+        let rem = Self(self.abs().checked_rem(rhs.abs())?);
+        Some(if self.is_negative() { -rem } else { rem })
+    }
+
+    /// Checked sum of the values. Returns `None` on overflow.
+    pub fn checked_sum(iter: impl IntoIterator<Item = Self>) -> Option<Self> {
+        iter.into_iter()
+            .try_fold(Self::zero(), |acc, val| acc.checked_add(&val))
+    }
+
+    /// Add two [`I256`]s of opposite signs, which can't overflow.
+    fn add_opposite_signs(self, rhs: Self) -> Self {
+        let (pos, neg) = if self.non_negative() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        if pos.0 >= neg.abs() {
+            Self(pos.0 - neg.abs())
+        } else {
+            -Self(neg.abs() - pos.0)
+        }
+        .canonical()
+    }
+
+    /// Checked multiplication by a decimal [`Dec`], with the result rounded
+    /// to an integer with the given mode. The product is computed in 512
+    /// bits, so it can't overflow before the rounding. Returns `None` if the
+    /// result doesn't fit in an [`I256`].
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn checked_mul_dec(
+        &self,
+        dec: Dec,
+        rounding: RoundingMode,
+    ) -> Option<Self> {
+        let scaling = Uint::exp10(POS_DECIMAL_PRECISION as usize);
+        let (quotient, remainder) =
+            self.abs().checked_mul_div(dec.abs(), scaling)?;
+        let abs = round_quotient(quotient, remainder, scaling, rounding)?;
+        let abs = Self::try_from(abs).ok()?;
+        Some(if self.is_negative() ^ dec.is_negative() {
+            -abs
+        } else {
+            abs
+        })
+    }
+
+    /// Get the integer part of a decimal [`Dec`], rounded with the given
+    /// mode.
+    #[cfg(feature = "std")]
+    pub fn from_dec(dec: Dec, rounding: RoundingMode) -> Self {
+        let scaling = Uint::exp10(POS_DECIMAL_PRECISION as usize);
+        let (quotient, remainder) = dec.abs().div_mod(scaling);
+        let abs = round_quotient(quotient, remainder, scaling, rounding)
+            .expect("The integer part of a Dec is far below the maximum");
+        if dec.is_negative() {
+            -Self(abs)
+        } else {
+            Self(abs)
+        }
+    }
+
+    /// Convert to a decimal [`Dec`]. Returns `None` if the value doesn't fit
+    /// at the precision of a [`Dec`].
+    #[cfg(feature = "std")]
+    pub fn checked_to_dec(&self) -> Option<Dec> {
+        self.checked_mul_uint(Uint::exp10(POS_DECIMAL_PRECISION as usize))
+            .map(Dec)
+    }
+
     /// Multiply by a decimal [`Dec`] with the result rounded up.
+    #[cfg(feature = "std")]
     #[must_use]
     pub fn mul_ceil(&self, dec: Dec) -> Self {
         let is_res_negative = self.is_negative() ^ dec.is_negative();
@@ -605,6 +698,27 @@ impl I256 {
     }
 }
 
+/// Round the quotient of a division of absolute values away from zero when it
+/// is required by the rounding mode. Returns `None` on overflow.
+#[cfg(feature = "std")]
+fn round_quotient(
+    quotient: Uint,
+    remainder: Uint,
+    divisor: Uint,
+    rounding: RoundingMode,
+) -> Option<Uint> {
+    let round_up = match rounding {
+        RoundingMode::Floor => false,
+        RoundingMode::Ceil => !remainder.is_zero(),
+        RoundingMode::HalfUp => remainder >= divisor - remainder,
+    };
+    if round_up {
+        quotient.checked_add(Uint::one())
+    } else {
+        Some(quotient)
+    }
+}
+
 impl From<u64> for I256 {
     fn from(val: u64) -> Self {
         I256::try_from(Uint::from(val))
@@ -613,15 +727,13 @@ impl From<u64> for I256 {
 }
 
 impl TryFrom<Uint> for I256 {
-    type Error = Box<dyn 'static + std::error::Error>;
+    type Error = AmountParseError;
 
     fn try_from(value: Uint) -> Result<Self, Self::Error> {
         if value <= MAX_SIGNED_VALUE {
             Ok(Self(value))
         } else {
-            Err("The given integer is too large to be represented asa \
-                 SignedUint"
-                .into())
+            Err(AmountParseError::InvalidRange)
         }
     }
 }
@@ -659,38 +771,27 @@ impl Ord for I256 {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Add<I256> for I256 {
     type Output = Self;
 
     fn add(self, rhs: I256) -> Self::Output {
         match (self.non_negative(), rhs.non_negative()) {
-            (true, true) => Self(self.0 + rhs.0),
-            (false, false) => -Self(self.abs() + rhs.abs()),
-            (true, false) => {
-                if self.0 >= rhs.abs() {
-                    Self(self.0 - rhs.abs())
-                } else {
-                    -Self(rhs.abs() - self.0)
-                }
-            }
-            (false, true) => {
-                if rhs.0 >= self.abs() {
-                    Self(rhs.0 - self.abs())
-                } else {
-                    -Self(self.abs() - rhs.0)
-                }
-            }
+            (true, true) => Self(self.0 + rhs.0).canonical(),
+            (false, false) => (-Self(self.abs() + rhs.abs())).canonical(),
+            _ => self.add_opposite_signs(rhs),
         }
-        .canonical()
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl AddAssign for I256 {
     fn add_assign(&mut self, rhs: Self) {
         *self = *self + rhs;
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Sub for I256 {
     type Output = Self;
 
@@ -699,6 +800,7 @@ impl Sub for I256 {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl SubAssign for I256 {
     fn sub_assign(&mut self, rhs: Self) {
         *self = *self - rhs;
@@ -706,6 +808,7 @@ impl SubAssign for I256 {
 }
 
 // NOTE: watch the overflow
+#[cfg(not(feature = "strict-arith"))]
 impl Mul<Uint> for I256 {
     type Output = Self;
 
@@ -727,7 +830,7 @@ impl CheckedAdd for I256 {
                     .map(|val| if !self.non_negative() { -val } else { val })
             })
         } else {
-            Some(*self + *other)
+            Some(self.add_opposite_signs(*other))
         }
     }
 }
@@ -753,6 +856,20 @@ impl CheckedMul for I256 {
     }
 }
 
+impl CheckedDiv for I256 {
+    /// Divides two [`I256`]s, with the quotient truncated towards zero.
+    /// Returns `None` if the divisor is zero.
+    fn checked_div(&self, v: &Self) -> Option<Self> {
+        let quot = Self(self.abs().checked_div(v.abs())?);
+        Some(if self.is_negative() != v.is_negative() {
+            -quot
+        } else {
+            quot
+        })
+    }
+}
+
+#[cfg(not(feature = "strict-arith"))]
 impl Mul for I256 {
     type Output = Self;
 
@@ -790,6 +907,7 @@ impl Div<I256> for I256 {
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
 impl Rem for I256 {
     type Output = Self;
 
@@ -824,14 +942,22 @@ impl From<i32> for I256 {
     }
 }
 
-impl std::iter::Sum for I256 {
+#[cfg(not(feature = "strict-arith"))]
+impl core::iter::Sum for I256 {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
         iter.fold(I256::zero(), |acc, amt| acc + amt)
     }
 }
 
+#[cfg(not(feature = "strict-arith"))]
+impl<'a> core::iter::Sum<&'a I256> for I256 {
+    fn sum<I: Iterator<Item = &'a I256>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
 impl TryFrom<I256> for i128 {
-    type Error = std::io::Error;
+    type Error = AmountParseError;
 
     fn try_from(value: I256) -> Result<Self, Self::Error> {
         if !value.non_negative() {
@@ -846,6 +972,8 @@ impl TryFrom<I256> for i128 {
 mod test_uint {
     use std::str::FromStr;
 
+    use proptest::prelude::*;
+
     use super::*;
 
     /// Test that dividing two [`Uint`]s with the specified precision
@@ -889,11 +1017,14 @@ mod test_uint {
     fn test_max_signed_value() {
         let signed = I256::try_from(MAX_SIGNED_VALUE).expect("Test failed");
         let one = I256::try_from(Uint::from(1u64)).expect("Test failed");
-        let overflow = signed + one;
-        assert_eq!(
-            overflow,
-            I256::try_from(Uint::zero()).expect("Test failed")
-        );
+        #[cfg(not(feature = "strict-arith"))]
+        {
+            let overflow = signed + one;
+            assert_eq!(
+                overflow,
+                I256::try_from(Uint::zero()).expect("Test failed")
+            );
+        }
         assert!(signed.checked_add(&one).is_none());
         assert!((-signed).checked_sub(&one).is_none());
     }
@@ -961,6 +1092,7 @@ mod test_uint {
     }
 
     /// Test that we correctly handle arithmetic with two's complement
+    #[cfg(not(feature = "strict-arith"))]
     #[test]
     fn test_arithmetic() {
         let zero = I256::try_from(Uint::zero()).expect("Test failed");
@@ -983,6 +1115,24 @@ mod test_uint {
         assert!((two - two).is_zero());
     }
 
+    /// Test the checked arithmetic of [`I256`]
+    #[test]
+    fn test_checked_arithmetic() {
+        let neg_eight = -I256::from(8);
+        let three = I256::from(3);
+
+        assert_eq!(three.checked_add(&neg_eight), Some(-I256::from(5)));
+        assert_eq!(neg_eight.checked_sub(&three), Some(-I256::from(11)));
+        assert_eq!(
+            neg_eight.checked_mul_uint(Uint::from(3)),
+            Some(-I256::from(24))
+        );
+        assert!(I256::maximum().checked_mul_uint(Uint::from(2)).is_none());
+        assert_eq!(neg_eight.checked_rem(&three), Some(-I256::from(2)));
+        assert_eq!(neg_eight.checked_rem(&-three), Some(-I256::from(2)));
+        assert!(three.checked_rem(&I256::zero()).is_none());
+    }
+
     /// Test that ordering is correctly implemented
     #[test]
     fn test_ord() {
@@ -1059,4 +1209,142 @@ mod test_uint {
         assert_eq!(e.checked_mul_div(c, b), Some((Uint::zero(), c)));
         assert_eq!(d.checked_mul_div(a, e), None);
     }
+
+    /// Test the rounding of the conversions and multiplications by a [`Dec`]
+    #[test]
+    fn test_i256_dec_rounding() {
+        let dec = |s: &str| Dec::from_str(s).unwrap();
+        let from_dec = I256::from_dec;
+
+        assert_eq!(from_dec(dec("2.5"), RoundingMode::Floor), I256::from(2));
+        assert_eq!(from_dec(dec("2.5"), RoundingMode::Ceil), I256::from(3));
+        assert_eq!(from_dec(dec("2.5"), RoundingMode::HalfUp), I256::from(3));
+        assert_eq!(from_dec(dec("2.4"), RoundingMode::HalfUp), I256::from(2));
+        assert_eq!(from_dec(dec("-2.5"), RoundingMode::Floor), -I256::from(2));
+        assert_eq!(from_dec(dec("-2.5"), RoundingMode::Ceil), -I256::from(3));
+        assert_eq!(from_dec(dec("2"), RoundingMode::Ceil), I256::from(2));
+
+        let seven = I256::from(7);
+        assert_eq!(
+            seven.checked_mul_dec(dec("0.5"), RoundingMode::Floor),
+            Some(I256::from(3))
+        );
+        assert_eq!(
+            (-seven).checked_mul_dec(dec("0.5"), RoundingMode::HalfUp),
+            Some(-I256::from(4))
+        );
+        assert!(I256::maximum()
+            .checked_mul_dec(dec("1.5"), RoundingMode::Floor)
+            .is_none());
+        assert_eq!(
+            I256::maximum().checked_mul_dec(dec("0.5"), RoundingMode::Ceil),
+            Some(I256(MAX_SIGNED_VALUE / Uint::from(2) + Uint::one()))
+        );
+
+        assert_eq!(seven.checked_to_dec(), Some(Dec::from(7u64)));
+        assert!(I256::maximum().checked_to_dec().is_none());
+    }
+
+    /// Test the checked division and sum of [`I256`]s
+    #[test]
+    fn test_i256_checked_div_sum() {
+        let seven = I256::from(7);
+        assert_eq!(seven.checked_div(&-I256::from(2)), Some(-I256::from(3)));
+        assert!(seven.checked_div(&I256::zero()).is_none());
+
+        assert_eq!(
+            I256::checked_sum([seven, -I256::from(10), I256::one()]),
+            Some(-I256::from(2))
+        );
+        assert!(I256::checked_sum([I256::maximum(), I256::one()]).is_none());
+    }
+
+    /// The rounding of `exact / divisor` of the given mode, computed with
+    /// native integers
+    fn round_i128(exact: i128, divisor: i128, rounding: RoundingMode) -> i128 {
+        let (quotient, remainder) = (exact / divisor, exact % divisor);
+        let round_up = match rounding {
+            RoundingMode::Floor => false,
+            RoundingMode::Ceil => remainder != 0,
+            RoundingMode::HalfUp => 2 * remainder.abs() >= divisor,
+        };
+        if round_up {
+            quotient + exact.signum()
+        } else {
+            quotient
+        }
+    }
+
+    fn arb_rounding() -> impl Strategy<Value = RoundingMode> {
+        prop_oneof![
+            Just(RoundingMode::Floor),
+            Just(RoundingMode::Ceil),
+            Just(RoundingMode::HalfUp),
+        ]
+    }
+
+    proptest! {
+        /// Test that the checked arithmetic of [`I256`] agrees with the one
+        /// of `i128`, for operands that can't overflow it.
+        #[test]
+        fn test_i256_checked_ops_match_i128(a: i64, b: i64) {
+            let (x, y) = (I256::from(a), I256::from(b));
+            let (a, b) = (i128::from(a), i128::from(b));
+            let to_i128 = |val: Option<I256>| {
+                val.map(|val| i128::try_from(val).unwrap())
+            };
+
+            prop_assert_eq!(to_i128(x.checked_add(&y)), Some(a + b));
+            prop_assert_eq!(to_i128(x.checked_sub(&y)), Some(a - b));
+            prop_assert_eq!(to_i128(x.checked_mul(&y)), Some(a * b));
+            prop_assert_eq!(to_i128(x.checked_div(&y)), a.checked_div(b));
+            prop_assert_eq!(to_i128(x.checked_rem(&y)), a.checked_rem(b));
+            prop_assert_eq!(
+                to_i128(I256::checked_sum([x, y, x])),
+                Some(a + b + a)
+            );
+        }
+
+        /// Test that the checked arithmetic of [`I256`] fails instead of
+        /// overflowing.
+        #[test]
+        fn test_i256_checked_ops_overflow(a in 2..u64::MAX) {
+            let max = I256::maximum();
+            let x = I256::from(a);
+            prop_assert!(max.checked_add(&x).is_none());
+            prop_assert!((-max).checked_sub(&x).is_none());
+            prop_assert!(max.checked_mul(&x).is_none());
+            prop_assert!(max.checked_mul_uint(Uint::from(a)).is_none());
+            prop_assert_eq!(
+                max.checked_sub(&x).and_then(|val| val.checked_add(&x)),
+                Some(max)
+            );
+        }
+
+        /// Test the rounding of the multiplications by a [`Dec`] against the
+        /// one of `i128`.
+        #[test]
+        fn test_i256_checked_mul_dec(
+            a: i64,
+            mantissa: i64,
+            scale in 0..=POS_DECIMAL_PRECISION,
+            rounding in arb_rounding(),
+        ) {
+            let dec = Dec::new(mantissa.into(), scale).unwrap();
+            let exact = i128::from(a) * i128::from(mantissa);
+            let divisor = 10_i128.pow(scale.into());
+            let expected = round_i128(exact, divisor, rounding);
+            let result = I256::from(a).checked_mul_dec(dec, rounding).unwrap();
+            prop_assert_eq!(i128::try_from(result).unwrap(), expected);
+        }
+
+        /// Test that converting an integer to a [`Dec`] and back is exact in
+        /// every rounding mode.
+        #[test]
+        fn test_i256_dec_round_trip(a: i64, rounding in arb_rounding()) {
+            let x = I256::from(a);
+            let dec = x.checked_to_dec().unwrap();
+            prop_assert_eq!(I256::from_dec(dec, rounding), x);
+        }
+    }
 }
@@ -1,5 +1,10 @@
 //! Types that are used in validity predicates.
 
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::types::storage::Key;
+
 /// Sentinel used in validity predicates to signal events that require special
 /// replay protection handling back to the protocol.
 #[derive(Debug, Default)]
@@ -34,3 +39,50 @@ impl VpSentinel {
         *self = Self::InvalidSignature
     }
 }
+
+/// The reason given by a VP for rejecting a transaction, for the clients to
+/// tell the users why their transaction failed.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct VpRejection {
+    /// The code of the reason, specific to the VP. The code
+    /// [`VpRejection::ERROR_CODE`] is reserved for the VPs that errored.
+    pub code: u32,
+    /// The description of the reason
+    pub message: String,
+    /// The storage key whose change was rejected, if any
+    pub key: Option<Key>,
+}
+
+impl VpRejection {
+    /// The code of the rejections of the VPs that errored
+    pub const ERROR_CODE: u32 = 0;
+
+    /// The rejection of a VP that errored
+    pub fn error(message: impl ToString) -> Self {
+        Self {
+            code: Self::ERROR_CODE,
+            message: message.to_string(),
+            key: None,
+        }
+    }
+}
+
+impl std::fmt::Display for VpRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rejected with code {}: {}", self.code, self.message)?;
+        if let Some(key) = &self.key {
+            write!(f, " (key {key})")?;
+        }
+        Ok(())
+    }
+}
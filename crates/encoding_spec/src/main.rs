@@ -7,6 +7,9 @@
 //!   md table
 //! - For each non-top-level definition, format to md table
 //!
+//! It also generates a TypeScript module with the types of the same
+//! declarations and their borsh codecs, see the `typescript` module.
+//!
 //! Rebuild on changes with:
 //! `cargo watch -x "run --bin namada_encoding_spec" -i docs`
 
@@ -32,10 +35,16 @@ use namada::types::key::ed25519::{PublicKey, Signature};
 use namada::types::storage::{self, Epoch};
 use namada::types::token;
 
+mod typescript;
+
 /// This generator will write output into this `docs` file.
 const OUTPUT_PATH: &str =
     "documentation/dev/src/specs/encoding/generated-borsh-spec.md";
 
+/// The TypeScript types will be written into this file.
+const TS_OUTPUT_PATH: &str =
+    "documentation/dev/src/specs/encoding/generated-borsh-types.ts";
+
 lazy_static! {
     /// Borsh types may be used by declarations. These are displayed differently in the [`md_fmt_type`].
     static ref BORSH_TYPES: HashSet<&'static str> =
@@ -118,6 +127,37 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     definitions.extend(btree(&tx_type_schema));
     definitions.extend(btree(&prefix_value_schema));
     // definitions.extend(btree(&pos_bonds_schema));
+
+    // Generate the TypeScript types of the top-level declarations, including
+    // the denominated amounts that clients display
+    let denominated_amount_schema =
+        schema_container_of::<token::DenominatedAmount>();
+    let mut ts_definitions = definitions.clone();
+    ts_definitions.extend(btree(&denominated_amount_schema));
+    let ts_roots = [
+        &address_schema,
+        &token_amount_schema,
+        &denominated_amount_schema,
+        &epoch_schema,
+        &parameters_schema,
+        &public_key_schema,
+        &signature_schema,
+        &init_account_schema,
+        &init_validator_schema,
+        &token_transfer_schema,
+        &update_account,
+        &pos_bond_schema,
+        &pos_withdraw_schema,
+        &wrapper_tx_schema,
+        &tx_type_schema,
+        &prefix_value_schema,
+    ]
+    .map(|schema| schema.declaration().clone());
+    std::fs::write(
+        TS_OUTPUT_PATH,
+        typescript::generate(&ts_definitions, ts_roots),
+    )?;
+
     let mut tables: Vec<Table> = Vec::with_capacity(definitions.len());
 
     // Add the top-level definitions first
@@ -0,0 +1,297 @@
+// The runtime of the generated types: the borsh codecs of the primitive types
+// and the combinators the codecs of the generated types are made of.
+
+/** A borsh encoder and decoder of the values of type `T` */
+export interface Codec<T> {
+  encode(writer: Writer, value: T): void;
+  decode(reader: Reader): T;
+}
+
+/** A buffer the borsh encoding of values is written into */
+export class Writer {
+  private readonly bytes: number[] = [];
+
+  push(...bytes: number[]): void {
+    this.bytes.push(...bytes);
+  }
+
+  writeBytes(bytes: Uint8Array): void {
+    bytes.forEach((byte) => this.bytes.push(byte));
+  }
+
+  finish(): Uint8Array {
+    return Uint8Array.from(this.bytes);
+  }
+}
+
+/** A cursor over the borsh encoding of values */
+export class Reader {
+  private offset = 0;
+
+  constructor(private readonly bytes: Uint8Array) {}
+
+  readBytes(len: number): Uint8Array {
+    if (this.offset + len > this.bytes.length) {
+      throw new Error("Unexpected end of the borsh encoding");
+    }
+    const bytes = this.bytes.subarray(this.offset, this.offset + len);
+    this.offset += len;
+    return bytes;
+  }
+
+  finish(): void {
+    if (this.offset !== this.bytes.length) {
+      throw new Error("Unexpected bytes after the borsh encoding");
+    }
+  }
+}
+
+/** Encode a value with the given codec */
+export function serialize<T>(codec: Codec<T>, value: T): Uint8Array {
+  const writer = new Writer();
+  codec.encode(writer, value);
+  return writer.finish();
+}
+
+/** Decode a value with the given codec, rejecting trailing bytes */
+export function deserialize<T>(codec: Codec<T>, bytes: Uint8Array): T {
+  const reader = new Reader(bytes);
+  const value = codec.decode(reader);
+  reader.finish();
+  return value;
+}
+
+/** The little-endian codec of the unsigned integers of the given size */
+export function uint(size: number): Codec<bigint> {
+  const bound = 1n << BigInt(8 * size);
+  return {
+    encode(writer, value) {
+      if (value < 0n || value >= bound) {
+        throw new RangeError(`${value} doesn't fit in ${size} bytes`);
+      }
+      for (let i = 0; i < size; i++) {
+        writer.push(Number(value & 0xffn));
+        value >>= 8n;
+      }
+    },
+    decode(reader) {
+      const bytes = reader.readBytes(size);
+      let value = 0n;
+      for (let i = size - 1; i >= 0; i--) {
+        value = (value << 8n) | BigInt(bytes[i]);
+      }
+      return value;
+    },
+  };
+}
+
+/** The two's complement codec of the signed integers of the given size */
+export function int(size: number): Codec<bigint> {
+  const unsigned = uint(size);
+  const bound = 1n << BigInt(8 * size);
+  return {
+    encode(writer, value) {
+      if (value < -(bound >> 1n) || value >= bound >> 1n) {
+        throw new RangeError(`${value} doesn't fit in ${size} bytes`);
+      }
+      unsigned.encode(writer, value < 0n ? value + bound : value);
+    },
+    decode(reader) {
+      const value = unsigned.decode(reader);
+      return value >= bound >> 1n ? value - bound : value;
+    },
+  };
+}
+
+function small(codec: Codec<bigint>): Codec<number> {
+  return {
+    encode: (writer, value) => codec.encode(writer, BigInt(value)),
+    decode: (reader) => Number(codec.decode(reader)),
+  };
+}
+
+function float(size: 4 | 8): Codec<number> {
+  return {
+    encode(writer, value) {
+      const view = new DataView(new ArrayBuffer(size));
+      if (size === 4) {
+        view.setFloat32(0, value, true);
+      } else {
+        view.setFloat64(0, value, true);
+      }
+      writer.writeBytes(new Uint8Array(view.buffer));
+    },
+    decode(reader) {
+      const bytes = reader.readBytes(size);
+      const view = new DataView(bytes.buffer, bytes.byteOffset, size);
+      return size === 4 ? view.getFloat32(0, true) : view.getFloat64(0, true);
+    },
+  };
+}
+
+export const u8 = small(uint(1));
+export const u16 = small(uint(2));
+export const u32 = small(uint(4));
+export const u64 = uint(8);
+export const u128 = uint(16);
+export const i8 = small(int(1));
+export const i16 = small(int(2));
+export const i32 = small(int(4));
+export const i64 = int(8);
+export const i128 = int(16);
+export const f32 = float(4);
+export const f64 = float(8);
+
+export const bool: Codec<boolean> = {
+  encode: (writer, value) => writer.push(value ? 1 : 0),
+  decode(reader) {
+    const [byte] = reader.readBytes(1);
+    if (byte > 1) {
+      throw new Error(`Invalid bool ${byte}`);
+    }
+    return byte === 1;
+  },
+};
+
+export const unit: Codec<null> = {
+  encode: () => undefined,
+  decode: () => null,
+};
+
+export const string: Codec<string> = {
+  encode(writer, value) {
+    const bytes = new TextEncoder().encode(value);
+    u32.encode(writer, bytes.length);
+    writer.writeBytes(bytes);
+  },
+  decode(reader) {
+    const len = u32.decode(reader);
+    return new TextDecoder("utf-8", { fatal: true }).decode(
+      reader.readBytes(len),
+    );
+  },
+};
+
+/** The codec of byte arrays of the given length, or of byte vectors */
+export function bytes(len?: number): Codec<Uint8Array> {
+  return {
+    encode(writer, value) {
+      if (len === undefined) {
+        u32.encode(writer, value.length);
+      } else if (value.length !== len) {
+        throw new RangeError(`Expected ${len} bytes, got ${value.length}`);
+      }
+      writer.writeBytes(value);
+    },
+    decode: (reader) =>
+      Uint8Array.from(reader.readBytes(len ?? u32.decode(reader))),
+  };
+}
+
+/** The codec of arrays of the given length, or of vectors */
+export function array<T>(item: Codec<T>, len?: number): Codec<Array<T>> {
+  return {
+    encode(writer, value) {
+      if (len === undefined) {
+        u32.encode(writer, value.length);
+      } else if (value.length !== len) {
+        throw new RangeError(`Expected ${len} items, got ${value.length}`);
+      }
+      value.forEach((element) => item.encode(writer, element));
+    },
+    decode(reader) {
+      const count = len ?? u32.decode(reader);
+      return Array.from({ length: count }, () => item.decode(reader));
+    },
+  };
+}
+
+/** The codec of optional values, `null` standing for `None` */
+export function option<T>(item: Codec<T>): Codec<T | null> {
+  return {
+    encode(writer, value) {
+      if (value === null) {
+        writer.push(0);
+      } else {
+        writer.push(1);
+        item.encode(writer, value);
+      }
+    },
+    decode(reader) {
+      const [tag] = reader.readBytes(1);
+      if (tag > 1) {
+        throw new Error(`Invalid option tag ${tag}`);
+      }
+      return tag === 1 ? item.decode(reader) : null;
+    },
+  };
+}
+
+/** The codec of tuples, encoded as their items in order */
+export function tuple<T extends unknown[]>(
+  items: { [K in keyof T]: Codec<T[K]> },
+): Codec<T> {
+  return {
+    encode(writer, value) {
+      items.forEach((item, i) => item.encode(writer, value[i]));
+    },
+    decode: (reader) => items.map((item) => item.decode(reader)) as T,
+  };
+}
+
+/** The codec of structs, encoded as their fields in order */
+export function struct<T>(fields: { [K in keyof T]: Codec<T[K]> }): Codec<T> {
+  const keys = Object.keys(fields) as Array<keyof T>;
+  return {
+    encode(writer, value) {
+      keys.forEach((key) => fields[key].encode(writer, value[key]));
+    },
+    decode(reader) {
+      const value = {} as T;
+      keys.forEach((key) => {
+        value[key] = fields[key].decode(reader);
+      });
+      return value;
+    },
+  };
+}
+
+/**
+ * The codec of enums, whose values are objects with the name of their
+ * variant as single key, encoded as the discriminant of their variant
+ * followed by its value
+ */
+export function enumeration<T>(
+  variants: Array<[number, string, Codec<unknown>]>,
+): Codec<T> {
+  return {
+    encode(writer, value) {
+      const [name, ...rest] = Object.keys(value as object);
+      const variant = variants.find(([, variant]) => variant === name);
+      if (variant === undefined || rest.length > 0) {
+        throw new Error(`Invalid enum variant ${Object.keys(value as object)}`);
+      }
+      const [discriminant, , codec] = variant;
+      writer.push(discriminant);
+      codec.encode(writer, (value as Record<string, unknown>)[name]);
+    },
+    decode(reader) {
+      const [discriminant] = reader.readBytes(1);
+      const variant = variants.find(([d]) => d === discriminant);
+      if (variant === undefined) {
+        throw new Error(`Invalid enum discriminant ${discriminant}`);
+      }
+      const [, name, codec] = variant;
+      return { [name]: codec.decode(reader) } as T;
+    },
+  };
+}
+
+/** Defer the construction of a codec until it's first used */
+export function lazy<T>(thunk: () => Codec<T>): Codec<T> {
+  let codec: Codec<T> | undefined;
+  return {
+    encode: (writer, value) => (codec ??= thunk()).encode(writer, value),
+    decode: (reader) => (codec ??= thunk()).decode(reader),
+  };
+}
@@ -0,0 +1,396 @@
+//! TypeScript code generator.
+//!
+//! Generates a TypeScript module with the types of the given borsh schema
+//! declarations and all the types they use, each with a codec of its borsh
+//! encoding, so that JS clients don't have to maintain them by hand. The
+//! codecs are made of the combinators of the runtime at the top of the
+//! module.
+//!
+//! The types are mapped to TypeScript as follows:
+//! - integers of up to 32 bits are `number`s, larger ones are `bigint`s
+//! - byte arrays and vectors are `Uint8Array`s, other arrays and vectors are
+//!   `Array`s and tuples are TypeScript tuples
+//! - `Option`s are nullable
+//! - structs with named fields are objects, structs with a single unnamed field
+//!   are their field and the other structs with unnamed fields are tuples
+//! - enums are objects with the name of their variant as single key
+//! - token amounts are decimal strings, see [`OVERRIDES`]
+
+use std::collections::BTreeMap;
+
+use borsh::schema::{Declaration, Definition, Fields};
+
+/// The runtime of the generated codecs
+const RUNTIME: &str = include_str!("runtime.ts");
+
+/// The hand-written TypeScript types and codecs of the declarations whose
+/// encoding doesn't map well to TypeScript
+const OVERRIDES: &[(&str, &str)] = &[("Amount", AMOUNT)];
+
+/// Amounts are `Uint`s of four little-endian `u64` limbs, i.e. 256-bit
+/// little-endian integers. They're rather handled as decimal strings.
+const AMOUNT: &str = r#"/**
+ * A token amount, as the decimal string of its value in the smallest unit of
+ * its token. Its denomination, i.e. the number of decimal places of the unit
+ * of the token, is carried along by a `DenominatedAmount`.
+ */
+export type Amount = string;
+export const Amount: Codec<Amount> = {
+  encode(writer, value) {
+    if (!/^[0-9]+$/.test(value)) {
+      throw new Error(`Invalid amount ${value}`);
+    }
+    uint(32).encode(writer, BigInt(value));
+  },
+  decode: (reader) => uint(32).decode(reader).toString(),
+};
+
+/** Format an amount with the given number of decimal places, e.g. `1.5` */
+export function formatAmount(amount: Amount, denom: number): string {
+  const digits = amount.padStart(denom + 1, "0");
+  const whole = digits.slice(0, digits.length - denom);
+  const fraction = digits.slice(digits.length - denom).replace(/0+$/, "");
+  return fraction ? `${whole}.${fraction}` : whole;
+}"#;
+
+/// Generate the TypeScript module of the given declarations and all the types
+/// they use.
+pub fn generate(
+    definitions: &BTreeMap<Declaration, Definition>,
+    roots: impl IntoIterator<Item = Declaration>,
+) -> String {
+    let mut generator = Generator {
+        definitions,
+        queue: roots.into_iter().collect(),
+        types: BTreeMap::new(),
+    };
+    while let Some(declaration) = generator.queue.pop() {
+        let name = type_name(&declaration);
+        if generator.types.contains_key(&name) {
+            continue;
+        }
+        // Reserve the name before generating the type, in case it's recursive
+        generator.types.insert(name.clone(), String::new());
+        let code = generator.named_type(&declaration, &name);
+        generator.types.insert(name, code);
+    }
+
+    let mut module = format!(
+        "// THIS FILE IS GENERATED FROM CODE: {}. Do not edit manually!\n\n{}",
+        std::file!(),
+        RUNTIME
+    );
+    for code in generator.types.values() {
+        module.push('\n');
+        module.push_str(code);
+        module.push('\n');
+    }
+    module
+}
+
+/// A TypeScript type and the expression of the codec of its values
+struct TsType {
+    ty: String,
+    codec: String,
+}
+
+impl TsType {
+    fn new(ty: impl Into<String>, codec: impl Into<String>) -> Self {
+        Self {
+            ty: ty.into(),
+            codec: codec.into(),
+        }
+    }
+}
+
+struct Generator<'a> {
+    definitions: &'a BTreeMap<Declaration, Definition>,
+    /// The named types that are used but not yet generated
+    queue: Vec<Declaration>,
+    /// The code of the named types, by name
+    types: BTreeMap<String, String>,
+}
+
+impl Generator<'_> {
+    /// Generate the code of a named type, i.e. of a struct or an enum
+    fn named_type(&mut self, declaration: &Declaration, name: &str) -> String {
+        if let Some((_, code)) = OVERRIDES
+            .iter()
+            .find(|(overridden, _)| overridden == declaration)
+        {
+            return code.to_string();
+        }
+        let TsType { ty, codec } = self.definition(declaration);
+        // The variants of enums start on a new line
+        let separator = if ty.starts_with('\n') { "" } else { " " };
+        format!(
+            "/** The `{declaration}` Rust type */\nexport type {name} \
+             ={separator}{ty};\nexport const {name}: Codec<{name}> = lazy(() \
+             => {codec});"
+        )
+    }
+
+    /// The TypeScript type of a declaration. Structs and enums are referred
+    /// to by name, the other types are inlined.
+    fn reference(&mut self, declaration: &Declaration) -> TsType {
+        let primitive = match declaration.as_str() {
+            "u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" | "f64" => {
+                Some("number")
+            }
+            "u64" | "u128" | "i64" | "i128" => Some("bigint"),
+            "bool" => Some("boolean"),
+            "String" | "string" | "str" => {
+                return TsType::new("string", "string")
+            }
+            "()" | "nil" => return TsType::new("null", "unit"),
+            _ => None,
+        };
+        if let Some(ty) = primitive {
+            return TsType::new(ty, declaration.as_str());
+        }
+        if OVERRIDES
+            .iter()
+            .any(|(overridden, _)| overridden == declaration)
+        {
+            self.queue.push(declaration.clone());
+            let name = type_name(declaration);
+            return TsType::new(&name, name);
+        }
+        match self.lookup(declaration) {
+            Definition::Enum { variants, .. }
+                if declaration.starts_with("Option<") =>
+            {
+                let (_, _, some) = variants
+                    .iter()
+                    .find(|(_, name, _)| name == "Some")
+                    .unwrap_or_else(|| {
+                        panic!("{declaration} has no Some variant")
+                    });
+                let TsType { ty, codec } = self.reference(some);
+                TsType::new(format!("{ty} | null"), format!("option({codec})"))
+            }
+            Definition::Struct { .. } | Definition::Enum { .. } => {
+                self.queue.push(declaration.clone());
+                let name = type_name(declaration);
+                TsType::new(&name, name)
+            }
+            _ => self.definition(declaration),
+        }
+    }
+
+    /// The TypeScript type of the definition of a declaration
+    fn definition(&mut self, declaration: &Declaration) -> TsType {
+        match self.lookup(declaration) {
+            Definition::Primitive(_) => {
+                panic!("Unsupported primitive type {declaration}")
+            }
+            Definition::Sequence {
+                length_width,
+                length_range,
+                elements,
+            } => {
+                let len = match length_width {
+                    // A fixed-size array
+                    0 => length_range.end().to_string(),
+                    // A vector
+                    4 => String::new(),
+                    _ => panic!(
+                        "Unsupported length width {length_width} of \
+                         {declaration}"
+                    ),
+                };
+                if elements == "u8" {
+                    TsType::new("Uint8Array", format!("bytes({len})"))
+                } else {
+                    let TsType { ty, codec } = self.reference(&elements);
+                    let len = if len.is_empty() {
+                        len
+                    } else {
+                        format!(", {len}")
+                    };
+                    TsType::new(
+                        format!("Array<{ty}>"),
+                        format!("array({codec}{len})"),
+                    )
+                }
+            }
+            Definition::Tuple { elements } => self.tuple(&elements),
+            Definition::Struct { fields } => match fields {
+                Fields::NamedFields(fields) => {
+                    let fields: Vec<_> = fields
+                        .iter()
+                        .map(|(name, declaration)| {
+                            (name, self.reference(declaration))
+                        })
+                        .collect();
+                    let ty = fields
+                        .iter()
+                        .map(|(name, field)| {
+                            format!("  {name}: {};\n", field.ty)
+                        })
+                        .collect::<String>();
+                    let codec = fields
+                        .iter()
+                        .map(|(name, field)| {
+                            format!("    {name}: {},\n", field.codec)
+                        })
+                        .collect::<String>();
+                    TsType::new(
+                        format!("{{\n{ty}}}"),
+                        format!("struct({{\n{codec}  }})"),
+                    )
+                }
+                // A newtype is encoded as its field
+                Fields::UnnamedFields(fields) if fields.len() == 1 => {
+                    self.reference(&fields[0])
+                }
+                Fields::UnnamedFields(fields) => self.tuple(&fields),
+                Fields::Empty => {
+                    TsType::new("Record<string, never>", "struct({})")
+                }
+            },
+            Definition::Enum {
+                tag_width,
+                variants,
+            } => {
+                if tag_width != 1 {
+                    panic!("Unsupported tag width {tag_width} of {declaration}")
+                }
+                let mut ty = String::new();
+                let mut codec = String::new();
+                for (discriminant, name, variant) in variants {
+                    // The variants are structs named after the enum, so
+                    // they're inlined
+                    let variant = self.definition(&variant);
+                    ty.push_str(&format!("\n  | {{ {name}: {} }}", variant.ty));
+                    codec.push_str(&format!(
+                        "    [{discriminant}, \"{name}\", {}],\n",
+                        variant.codec
+                    ));
+                }
+                TsType::new(ty, format!("enumeration([\n{codec}  ])"))
+            }
+        }
+    }
+
+    fn tuple(&mut self, elements: &[Declaration]) -> TsType {
+        let elements: Vec<_> = elements
+            .iter()
+            .map(|element| self.reference(element))
+            .collect();
+        let ty = elements.iter().map(|element| element.ty.as_str());
+        let codec = elements.iter().map(|element| element.codec.as_str());
+        TsType::new(
+            format!("[{}]", ty.collect::<Vec<_>>().join(", ")),
+            format!("tuple([{}])", codec.collect::<Vec<_>>().join(", ")),
+        )
+    }
+
+    fn lookup(&self, declaration: &Declaration) -> Definition {
+        self.definitions
+            .get(declaration)
+            .cloned()
+            .unwrap_or_else(|| panic!("Missing definition of {declaration}"))
+    }
+}
+
+/// The TypeScript name of a declaration, without the characters of its
+/// generic arguments, e.g. `BTreeMap_Address_Amount`
+fn type_name(declaration: &Declaration) -> String {
+    declaration
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::{schema_container_of, BorshSchema};
+
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    struct Height(u64);
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    enum Payload {
+        Empty,
+        Bytes(Vec<u8>),
+        Pair(Height, Option<u16>),
+    }
+
+    #[allow(dead_code)]
+    #[derive(BorshSchema)]
+    struct Block {
+        height: Height,
+        payloads: Vec<Payload>,
+        hash: [u8; 32],
+        proposer: Option<String>,
+    }
+
+    /// Test the TypeScript types and codecs of structs, enums and the types
+    /// they use.
+    #[test]
+    fn test_generate_typescript() {
+        let schema = schema_container_of::<Block>();
+        let definitions = schema
+            .definitions()
+            .map(|(x, y)| (x.clone(), y.clone()))
+            .collect();
+        let module = generate(&definitions, [schema.declaration().clone()]);
+        assert!(module.contains(RUNTIME));
+
+        let expected_block = r#"/** The `Block` Rust type */
+export type Block = {
+  height: Height;
+  payloads: Array<Payload>;
+  hash: Uint8Array;
+  proposer: string | null;
+};
+export const Block: Codec<Block> = lazy(() => struct({
+    height: Height,
+    payloads: array(Payload),
+    hash: bytes(32),
+    proposer: option(string),
+  }));"#;
+        assert!(module.contains(expected_block), "{module}");
+
+        let expected_height = "/** The `Height` Rust type */
+export type Height = bigint;
+export const Height: Codec<Height> = lazy(() => u64);";
+        assert!(module.contains(expected_height), "{module}");
+
+        let expected_payload = r#"/** The `Payload` Rust type */
+export type Payload =
+  | { Empty: Record<string, never> }
+  | { Bytes: Uint8Array }
+  | { Pair: [Height, number | null] };
+export const Payload: Codec<Payload> = lazy(() => enumeration([
+    [0, "Empty", struct({})],
+    [1, "Bytes", bytes()],
+    [2, "Pair", tuple([Height, option(u16)])],
+  ]));"#;
+        assert!(module.contains(expected_payload), "{module}");
+    }
+
+    /// Test that amounts are decimal strings.
+    #[test]
+    fn test_amount_override() {
+        let schema = schema_container_of::<namada::types::token::Transfer>();
+        let definitions = schema
+            .definitions()
+            .map(|(x, y)| (x.clone(), y.clone()))
+            .collect();
+        let module = generate(&definitions, [schema.declaration().clone()]);
+        assert!(
+            module.contains("  amount: DenominatedAmount;\n"),
+            "{module}"
+        );
+        assert!(module.contains("  amount: Amount;\n"), "{module}");
+        assert!(module.contains(AMOUNT), "{module}");
+        assert!(!module.contains("export type Uint "), "{module}");
+    }
+}
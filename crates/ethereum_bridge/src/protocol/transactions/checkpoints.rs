@@ -0,0 +1,372 @@
+//! Code for recording the checkpoints of the application state and
+//! for tallying the signatures of the validators over them.
+
+use std::collections::{HashMap, HashSet};
+
+use eyre::Result;
+use namada_core::types::address::Address;
+use namada_core::types::eth_abi::Encode;
+use namada_core::types::keccak::KeccakHash;
+use namada_core::types::key::{common, SignableEthMessage};
+use namada_core::types::storage::BlockHeight;
+use namada_core::types::token::Amount;
+use namada_proof_of_stake::pos_queries::PosQueries;
+use namada_state::{DBIter, StorageHasher, WlStorage, DB};
+use namada_storage::{StorageRead, StorageWrite};
+use namada_tx::data::TxResult;
+use namada_tx::Signed;
+use namada_vote_ext::checkpoints::{self, SignedVext};
+
+use crate::protocol::transactions::utils::GetVoters;
+use crate::protocol::transactions::votes::update::NewVotes;
+use crate::protocol::transactions::votes::{calculate_new, Votes};
+use crate::protocol::transactions::{utils, votes, ChangedKeys};
+use crate::storage::checkpoints::{
+    get_checkpoint_key, get_latest_certified_checkpoint_key, Checkpoint,
+};
+use crate::storage::eth_bridge_queries::EthBridgeQueries;
+use crate::storage::proof::CheckpointProof;
+use crate::storage::vote_tallies;
+
+/// Record the checkpoint of the application state committed at the last
+/// block height, if a checkpoint is made at that height.
+///
+/// This must be called while finalizing a block, before it is committed,
+/// such that the merkle root in storage is still the one of the last block.
+pub fn record_checkpoint<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+) -> Result<ChangedKeys>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let height = wl_storage.storage.get_last_block_height();
+    if !wl_storage.ethbridge_queries().is_checkpoint_height(height) {
+        return Ok(ChangedKeys::default());
+    }
+    let key = get_checkpoint_key(height);
+    if wl_storage.has_key(&key)? {
+        return Ok(ChangedKeys::default());
+    }
+    let checkpoint = Checkpoint {
+        height,
+        app_hash: KeccakHash(wl_storage.storage.merkle_root().0),
+    };
+    tracing::info!(
+        ?height,
+        app_hash = %checkpoint.app_hash,
+        "Recording a checkpoint of the application state"
+    );
+    wl_storage.write(&key, checkpoint)?;
+    Ok(ChangedKeys::from([key]))
+}
+
+/// Sign the checkpoint of the application state recorded in the last
+/// block, and return the associated vote extension protocol transaction.
+pub fn sign_checkpoint<D, H>(
+    wl_storage: &WlStorage<D, H>,
+    validator_addr: &Address,
+    eth_hot_key: &common::SecretKey,
+    protocol_key: &common::SecretKey,
+) -> Option<checkpoints::SignedVext>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let height = wl_storage.storage.get_last_block_height().prev_height();
+    if !wl_storage.ethbridge_queries().is_checkpoint_height(height) {
+        return None;
+    }
+    let checkpoint = wl_storage.ethbridge_queries().get_checkpoint(height)?;
+    let signed = Signed::<_, SignableEthMessage>::new(
+        eth_hot_key,
+        checkpoint.keccak256(),
+    );
+    let ext = checkpoints::Vext {
+        block_height: height,
+        validator_addr: validator_addr.clone(),
+        sig: signed.sig,
+    };
+    Some(ext.sign(protocol_key))
+}
+
+/// Applies the signature of a checkpoint of the application state by
+/// some validator.
+///
+/// Once a quorum of validators have signed the checkpoint, its
+/// certificate is complete, and it becomes the latest certified
+/// checkpoint if it is more recent than the existing one.
+pub fn apply_derived_tx<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    ext: SignedVext,
+) -> Result<TxResult>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let height = ext.data.block_height;
+    let Some(checkpoint) =
+        wl_storage.ethbridge_queries().get_checkpoint(height)
+    else {
+        tracing::debug!(
+            ?height,
+            "Ignoring the signature of a checkpoint which was never recorded"
+        );
+        return Ok(TxResult::default());
+    };
+    let keys = vote_tallies::Keys::from(&height);
+    let seen =
+        votes::storage::maybe_read_seen(wl_storage, &keys)?.unwrap_or(false);
+    if seen {
+        tracing::debug!(?height, "Checkpoint certificate is already complete");
+        return Ok(TxResult::default());
+    }
+    tracing::info!(
+        ?height,
+        validator = %ext.data.validator_addr,
+        "Applying the signature of a checkpoint of the application state"
+    );
+
+    let voting_powers = utils::get_voting_powers(wl_storage, &ext)?;
+    let epoch = wl_storage.pos_queries().get_epoch(height);
+    let mut partial_proof = CheckpointProof::new(checkpoint);
+    partial_proof.attach_signature(
+        wl_storage
+            .ethbridge_queries()
+            .get_eth_addr_book(&ext.data.validator_addr, epoch)
+            .expect("A validator should have an Ethereum address book"),
+        ext.data.sig.clone(),
+    );
+    let seen_by: Votes = [(ext.data.validator_addr.clone(), height)]
+        .into_iter()
+        .collect();
+
+    let (mut changed, confirmed) = apply_update(
+        wl_storage,
+        &keys,
+        partial_proof,
+        seen_by,
+        &voting_powers,
+    )?;
+
+    if confirmed {
+        let latest_key = get_latest_certified_checkpoint_key();
+        let is_latest = wl_storage
+            .read::<BlockHeight>(&latest_key)?
+            .map(|latest_height| latest_height < height)
+            .unwrap_or(true);
+        if is_latest {
+            tracing::info!(?height, "New checkpoint certificate acquired");
+            wl_storage.write(&latest_key, height)?;
+            changed.insert(latest_key);
+        }
+    }
+
+    Ok(TxResult {
+        changed_keys: changed,
+        ..Default::default()
+    })
+}
+
+impl GetVoters for &SignedVext {
+    fn get_voters(self) -> HashSet<(Address, BlockHeight)> {
+        HashSet::from([(
+            self.data.validator_addr.clone(),
+            self.data.block_height,
+        )])
+    }
+}
+
+/// This vote updates the voting power backing a checkpoint in storage.
+///
+/// Returns the changed storage keys, and whether a quorum of validators
+/// has signed the checkpoint.
+fn apply_update<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    keys: &vote_tallies::Keys<CheckpointProof>,
+    mut update: CheckpointProof,
+    seen_by: Votes,
+    voting_powers: &HashMap<(Address, BlockHeight), Amount>,
+) -> Result<(ChangedKeys, bool)>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let partial_proof = votes::storage::read_body(wl_storage, keys);
+    let (vote_tracking, changed, confirmed, already_present) =
+        if let Ok(partial) = partial_proof {
+            tracing::debug!(
+                %keys.prefix,
+                "Signatures for this checkpoint already exist in storage",
+            );
+            update.attach_signature_batch(partial.signatures);
+            let new_votes = NewVotes::new(seen_by, voting_powers)?;
+            let (vote_tracking, changed) =
+                votes::update::calculate(wl_storage, keys, new_votes)?;
+            if changed.is_empty() {
+                return Ok((changed, false));
+            }
+            let confirmed =
+                vote_tracking.seen && changed.contains(&keys.seen());
+            (vote_tracking, changed, confirmed, true)
+        } else {
+            tracing::debug!(
+                %keys.prefix,
+                "No validator has signed this checkpoint before."
+            );
+            let vote_tracking =
+                calculate_new(wl_storage, seen_by, voting_powers)?;
+            let changed = keys.into_iter().collect();
+            let confirmed = vote_tracking.seen;
+            (vote_tracking, changed, confirmed, false)
+        };
+
+    votes::storage::write(
+        wl_storage,
+        keys,
+        &update,
+        &vote_tracking,
+        already_present,
+    )?;
+    Ok((changed, confirmed))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use namada_core::types::address;
+    use namada_core::types::storage::BlockHash;
+    use namada_core::types::time::DateTimeUtc;
+    use namada_state::testing::TestWlStorage;
+    use namada_state::LastBlock;
+
+    use super::*;
+    use crate::storage::checkpoint_interval_key;
+    use crate::test_utils;
+
+    /// The checkpoint interval used in tests.
+    const INTERVAL: u64 = 10;
+
+    /// Set the last committed block height of the given storage.
+    fn set_last_height(wl_storage: &mut TestWlStorage, height: u64) {
+        wl_storage.storage.last_block = Some(LastBlock {
+            height: BlockHeight(height),
+            hash: BlockHash::default(),
+            time: DateTimeUtc::now(),
+        });
+    }
+
+    /// Setup storage with three validators, two of which hold a quorum
+    /// together, and a checkpoint interval of [`INTERVAL`].
+    fn setup() -> (
+        [Address; 3],
+        HashMap<Address, test_utils::TestValidatorKeys>,
+        TestWlStorage,
+    ) {
+        let validator_a = address::testing::established_address_2();
+        let validator_b = address::testing::established_address_3();
+        let validator_c = address::testing::established_address_4();
+        let (mut wl_storage, keys) = test_utils::setup_storage_with_validators(
+            HashMap::from_iter(vec![
+                (validator_a.clone(), Amount::native_whole(100)),
+                (validator_b.clone(), Amount::native_whole(100)),
+                (validator_c.clone(), Amount::native_whole(40)),
+            ]),
+        );
+        wl_storage
+            .write(
+                &checkpoint_interval_key(),
+                NonZeroU64::new(INTERVAL).unwrap(),
+            )
+            .expect("Test failed");
+        ([validator_a, validator_b, validator_c], keys, wl_storage)
+    }
+
+    /// Test that checkpoints are only recorded at the heights
+    /// multiple of the checkpoint interval, and only once.
+    #[test]
+    fn test_record_checkpoint() {
+        let (_, _, mut wl_storage) = setup();
+
+        set_last_height(&mut wl_storage, INTERVAL - 1);
+        let changed_keys =
+            record_checkpoint(&mut wl_storage).expect("Test failed");
+        assert!(changed_keys.is_empty());
+
+        set_last_height(&mut wl_storage, INTERVAL);
+        let key = get_checkpoint_key(BlockHeight(INTERVAL));
+        let changed_keys =
+            record_checkpoint(&mut wl_storage).expect("Test failed");
+        assert_eq!(changed_keys, ChangedKeys::from([key.clone()]));
+        assert_eq!(
+            wl_storage.read::<Checkpoint>(&key).expect("Test failed"),
+            Some(Checkpoint {
+                height: BlockHeight(INTERVAL),
+                app_hash: KeccakHash(wl_storage.storage.merkle_root().0),
+            })
+        );
+
+        let changed_keys =
+            record_checkpoint(&mut wl_storage).expect("Test failed");
+        assert!(changed_keys.is_empty());
+    }
+
+    /// Test that a checkpoint is certified once a quorum of validators
+    /// has signed it.
+    #[test]
+    fn test_checkpoint_certified_with_quorum() {
+        let (validators, keys, mut wl_storage) = setup();
+        let height = BlockHeight(INTERVAL);
+
+        set_last_height(&mut wl_storage, INTERVAL);
+        _ = record_checkpoint(&mut wl_storage).expect("Test failed");
+        wl_storage.commit_block().expect("Test failed");
+
+        // nothing is signed until the checkpoint has been committed
+        assert!(sign_checkpoint(
+            &wl_storage,
+            &validators[0],
+            &keys[&validators[0]].eth_bridge,
+            &keys[&validators[0]].protocol,
+        )
+        .is_none());
+
+        set_last_height(&mut wl_storage, INTERVAL + 1);
+        let sign = |validator: &Address| {
+            sign_checkpoint(
+                &wl_storage,
+                validator,
+                &keys[validator].eth_bridge,
+                &keys[validator].protocol,
+            )
+            .expect("Test failed")
+        };
+        let sig_a = sign(&validators[0]);
+        let sig_b = sign(&validators[1]);
+
+        // a single validator does not have a quorum
+        _ = apply_derived_tx(&mut wl_storage, sig_a).expect("Test failed");
+        assert!(!wl_storage.ethbridge_queries().checkpoint_certified(height));
+        assert!(!wl_storage
+            .has_key(&get_latest_certified_checkpoint_key())
+            .expect("Test failed"));
+
+        let TxResult { changed_keys, .. } =
+            apply_derived_tx(&mut wl_storage, sig_b).expect("Test failed");
+        assert!(changed_keys.contains(&get_latest_certified_checkpoint_key()));
+        assert!(wl_storage.ethbridge_queries().checkpoint_certified(height));
+        assert_eq!(
+            wl_storage
+                .read::<BlockHeight>(&get_latest_certified_checkpoint_key())
+                .expect("Test failed"),
+            Some(height)
+        );
+        let proof: CheckpointProof = votes::storage::read_body(
+            &wl_storage,
+            &vote_tallies::Keys::from(&height),
+        )
+        .expect("Test failed");
+        assert_eq!(proof.signatures.len(), 2);
+    }
+}
@@ -449,7 +449,7 @@ where
     Ok((changed_keys, tx_events))
 }
 
-fn refund_transfer_fees<D, H>(
+pub(in crate::protocol::transactions) fn refund_transfer_fees<D, H>(
     wl_storage: &mut WlStorage<D, H>,
     transfer: &PendingTransfer,
 ) -> Result<BTreeSet<Key>>
@@ -476,7 +476,7 @@ where
     Ok(changed_keys)
 }
 
-fn refund_transferred_assets<D, H>(
+pub(in crate::protocol::transactions) fn refund_transferred_assets<D, H>(
     wl_storage: &mut WlStorage<D, H>,
     transfer: &PendingTransfer,
 ) -> Result<BTreeSet<Key>>
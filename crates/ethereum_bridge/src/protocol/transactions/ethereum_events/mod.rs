@@ -1,7 +1,7 @@
 //! Code for handling Ethereum events protocol txs.
 
 mod eth_msgs;
-mod events;
+pub(super) mod events;
 
 use std::collections::{BTreeSet, HashMap, HashSet};
 
@@ -5,12 +5,14 @@
 //! natively rather than via the wasm environment as happens with regular
 //! transactions.
 pub mod bridge_pool_roots;
+pub mod checkpoints;
 pub mod ethereum_events;
 mod read;
 mod update;
 mod utils;
 pub mod validator_set_update;
 pub mod votes;
+pub mod withdrawal_vetoes;
 
 use std::collections::BTreeSet;
 
@@ -0,0 +1,557 @@
+//! Code for delaying the large transfers to Ethereum and
+//! for tallying the vetoes of the delayed transfers.
+//!
+//! A transfer added to the Bridge pool whose amount exceeds the
+//! withdrawal veto threshold of its asset is moved out of the
+//! pool at the end of the block, before the pool is committed.
+//! It sits there for the delay of the veto window, until it is
+//! released back to the Bridge pool. In the meantime, a
+//! supermajority of the validators can veto it, in which case
+//! its assets and fees are refunded.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use eyre::Result;
+use namada_core::types::address::Address;
+use namada_core::types::eth_abi::Encode;
+use namada_core::types::eth_bridge_pool::PendingTransfer;
+use namada_core::types::ethereum_structs::EthBridgeEvent;
+use namada_core::types::keccak::KeccakHash;
+use namada_core::types::storage::{BlockHeight, Key};
+use namada_core::types::token::Amount;
+use namada_state::{DBIter, StorageHasher, WlStorage, DB};
+use namada_storage::{StorageRead, StorageWrite};
+use namada_tx::data::TxResult;
+use namada_vote_ext::withdrawal_vetoes::SignedVext;
+
+use crate::protocol::transactions::ethereum_events::events::{
+    refund_transfer_fees, refund_transferred_assets,
+};
+use crate::protocol::transactions::utils::GetVoters;
+use crate::protocol::transactions::votes::update::NewVotes;
+use crate::protocol::transactions::votes::{calculate_new, Votes};
+use crate::protocol::transactions::{utils, votes, ChangedKeys};
+use crate::storage::bridge_pool::{
+    get_delayed_transfer_key, get_delayed_transfers_prefix, get_pending_key,
+    is_pending_transfer_key, DelayedTransfer,
+};
+use crate::storage::eth_bridge_queries::EthBridgeQueries;
+use crate::storage::vote_tallies;
+
+/// Delay the transfers added to the Bridge pool in the current block whose
+/// amount exceeds the withdrawal veto threshold of their asset, then release
+/// the delayed transfers whose veto window is over back to the Bridge pool.
+///
+/// This must be called at the end of every block, after all its txs were
+/// applied and before it is committed, such that the delayed transfers never
+/// make it to the Bridge pool's merkle tree. The given `changed_keys` are the
+/// keys changed by the txs of the block.
+pub fn update_delayed_transfers<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    changed_keys: &BTreeSet<Key>,
+) -> Result<ChangedKeys>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let height = wl_storage.storage.get_block_height().0;
+    let mut changed = delay_new_transfers(wl_storage, changed_keys, height)?;
+    changed.append(&mut release_delayed_transfers(wl_storage, height)?);
+    Ok(changed)
+}
+
+/// Move the new transfers above the withdrawal veto threshold of their asset
+/// out of the Bridge pool.
+fn delay_new_transfers<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    changed_keys: &BTreeSet<Key>,
+    height: BlockHeight,
+) -> Result<ChangedKeys>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let mut changed = ChangedKeys::default();
+    for key in changed_keys
+        .iter()
+        .filter(|key| is_pending_transfer_key(key))
+    {
+        // the transfers in the committed merkle tree were added to the
+        // Bridge pool in some previous block
+        if wl_storage.storage.block.tree.has_key(key)? {
+            continue;
+        }
+        // the transfer may have been removed, instead of added
+        let Some(transfer) = wl_storage.read::<PendingTransfer>(key)? else {
+            continue;
+        };
+        let Some(veto) = wl_storage
+            .ethbridge_queries()
+            .get_withdrawal_veto(&transfer.transfer.asset)
+        else {
+            continue;
+        };
+        if transfer.transfer.amount <= veto.threshold.amount() {
+            continue;
+        }
+
+        let release_height = height + veto.delay.get();
+        let transfer_hash = transfer.keccak256();
+        tracing::info!(
+            %transfer_hash,
+            ?release_height,
+            "Delaying a transfer to Ethereum above the withdrawal veto \
+             threshold of its asset"
+        );
+        let delayed_key = get_delayed_transfer_key(&transfer_hash);
+        wl_storage.delete(key)?;
+        wl_storage.write(
+            &delayed_key,
+            DelayedTransfer {
+                transfer,
+                release_height,
+            },
+        )?;
+        _ = changed.insert(key.clone());
+        _ = changed.insert(delayed_key);
+    }
+    Ok(changed)
+}
+
+/// Move the delayed transfers whose veto window is over back to the Bridge
+/// pool.
+fn release_delayed_transfers<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    height: BlockHeight,
+) -> Result<ChangedKeys>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let prefix = get_delayed_transfers_prefix();
+    let mut released = vec![];
+    for result in
+        namada_storage::iter_prefix::<DelayedTransfer>(wl_storage, &prefix)?
+    {
+        let (key, delayed) = result?;
+        if delayed.release_height <= height {
+            released.push((key, delayed.transfer));
+        }
+    }
+
+    let mut changed = ChangedKeys::default();
+    for (delayed_key, transfer) in released {
+        let transfer_hash = transfer.keccak256();
+        tracing::info!(
+            %transfer_hash,
+            "Releasing a delayed transfer to Ethereum to the Bridge pool"
+        );
+        let pending_key = get_pending_key(&transfer);
+        wl_storage.delete(&delayed_key)?;
+        wl_storage.write(&pending_key, transfer)?;
+        _ = changed.insert(delayed_key);
+        _ = changed.insert(pending_key);
+
+        // the vetoes short of a quorum are dropped, such that they are
+        // not counted against the same transfer if it is delayed again
+        let keys = vote_tallies::Keys::from(&transfer_hash);
+        if wl_storage.has_key(&keys.seen())? {
+            _ = votes::storage::delete(wl_storage, &keys)?;
+            changed.extend(&keys);
+        }
+    }
+    Ok(changed)
+}
+
+/// Applies the veto of a delayed transfer to Ethereum by some validator.
+///
+/// Once a quorum of validators have vetoed the transfer, it is refunded and
+/// it won't ever be released to the Bridge pool.
+pub fn apply_derived_tx<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    ext: SignedVext,
+) -> Result<TxResult>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let transfer_hash = ext.data.transfer_hash.clone();
+    let delayed_key = get_delayed_transfer_key(&transfer_hash);
+    let Some(delayed) = wl_storage.read::<DelayedTransfer>(&delayed_key)?
+    else {
+        tracing::debug!(
+            %transfer_hash,
+            "Ignoring the veto of a transfer to Ethereum which is no longer \
+             delayed"
+        );
+        return Ok(TxResult::default());
+    };
+    tracing::info!(
+        %transfer_hash,
+        validator = %ext.data.validator_addr,
+        "Applying the veto of a delayed transfer to Ethereum"
+    );
+
+    let voting_powers = utils::get_voting_powers(wl_storage, &ext)?;
+    let seen_by: Votes =
+        [(ext.data.validator_addr.clone(), ext.data.block_height)]
+            .into_iter()
+            .collect();
+    let keys = vote_tallies::Keys::from(&transfer_hash);
+    let (mut changed, vetoed) = apply_update(
+        wl_storage,
+        &keys,
+        &transfer_hash,
+        seen_by,
+        &voting_powers,
+    )?;
+
+    let mut eth_bridge_events = BTreeSet::new();
+    if vetoed {
+        tracing::info!(
+            %transfer_hash,
+            "A delayed transfer to Ethereum was vetoed by a quorum of \
+             validators, refunding it"
+        );
+        changed
+            .append(&mut refund_transfer_fees(wl_storage, &delayed.transfer)?);
+        changed.append(&mut refund_transferred_assets(
+            wl_storage,
+            &delayed.transfer,
+        )?);
+        wl_storage.delete(&delayed_key)?;
+        _ = changed.insert(delayed_key);
+
+        // the tally is dropped, such that the same transfer can be vetoed
+        // again if it is ever delayed again
+        _ = votes::storage::delete(wl_storage, &keys)?;
+        changed.extend(&keys);
+        _ = eth_bridge_events
+            .insert(EthBridgeEvent::new_bridge_pool_vetoed(transfer_hash));
+    }
+
+    Ok(TxResult {
+        changed_keys: changed,
+        eth_bridge_events,
+        ..Default::default()
+    })
+}
+
+impl GetVoters for &SignedVext {
+    fn get_voters(self) -> HashSet<(Address, BlockHeight)> {
+        HashSet::from([(
+            self.data.validator_addr.clone(),
+            self.data.block_height,
+        )])
+    }
+}
+
+/// This vote updates the voting power backing the veto of a delayed
+/// transfer in storage.
+///
+/// Returns the changed storage keys, and whether a quorum of validators
+/// has vetoed the transfer.
+fn apply_update<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    keys: &vote_tallies::Keys<KeccakHash>,
+    transfer_hash: &KeccakHash,
+    seen_by: Votes,
+    voting_powers: &HashMap<(Address, BlockHeight), Amount>,
+) -> Result<(ChangedKeys, bool)>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let (vote_tracking, changed, confirmed, already_present) = if wl_storage
+        .has_key(&keys.seen())?
+    {
+        tracing::debug!(
+            %keys.prefix,
+            "Vetoes for this delayed transfer already exist in storage",
+        );
+        let new_votes = NewVotes::new(seen_by, voting_powers)?;
+        let (vote_tracking, changed) =
+            votes::update::calculate(wl_storage, keys, new_votes)?;
+        if changed.is_empty() {
+            return Ok((changed, false));
+        }
+        let confirmed = vote_tracking.seen && changed.contains(&keys.seen());
+        (vote_tracking, changed, confirmed, true)
+    } else {
+        tracing::debug!(
+            %keys.prefix,
+            "No validator has vetoed this delayed transfer before."
+        );
+        let vote_tracking = calculate_new(wl_storage, seen_by, voting_powers)?;
+        let changed = keys.into_iter().collect();
+        let confirmed = vote_tracking.seen;
+        (vote_tracking, changed, confirmed, false)
+    };
+
+    votes::storage::write(
+        wl_storage,
+        keys,
+        transfer_hash,
+        &vote_tracking,
+        already_present,
+    )?;
+    Ok((changed, confirmed))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use namada_core::types::address::{self, nam};
+    use namada_core::types::eth_bridge_pool::{
+        GasFee, TransferToEthereum, TransferToEthereumKind,
+    };
+    use namada_core::types::ethereum_events::EthAddress;
+    use namada_core::types::token::{DenominatedAmount, Denomination};
+    use namada_state::testing::TestWlStorage;
+    use namada_trans_token::storage_key::balance_key;
+    use namada_vote_ext::withdrawal_vetoes::WithdrawalVetoVext;
+
+    use super::*;
+    use crate::storage::bridge_pool::BRIDGE_POOL_ADDRESS;
+    use crate::storage::parameters::WithdrawalVeto;
+    use crate::storage::whitelist;
+    use crate::test_utils::{self, WhitelistMeta};
+
+    /// The asset with a withdrawal veto window.
+    const ASSET: EthAddress = EthAddress([0xaa; 20]);
+
+    /// The withdrawal veto threshold of [`ASSET`].
+    const THRESHOLD: u64 = 100;
+
+    /// The withdrawal veto delay of [`ASSET`].
+    const DELAY: u64 = 5;
+
+    /// The data needed to run a test.
+    struct TestPackage {
+        /// Three validators
+        validators: [Address; 3],
+        /// The validator keys.
+        keys: HashMap<Address, test_utils::TestValidatorKeys>,
+        /// Storage.
+        wl_storage: TestWlStorage,
+    }
+
+    /// Setup storage for tests.
+    ///
+    ///  * Creates three validators, two of which hold a quorum together.
+    ///  * Whitelists [`ASSET`] with a withdrawal veto window.
+    ///  * Funds the escrow of the Bridge pool, to be able to refund the vetoed
+    ///    transfers.
+    fn setup() -> TestPackage {
+        let validator_a = address::testing::established_address_2();
+        let validator_b = address::testing::established_address_3();
+        let validator_c = address::testing::established_address_4();
+        let (mut wl_storage, keys) = test_utils::setup_storage_with_validators(
+            HashMap::from_iter(vec![
+                (validator_a.clone(), Amount::native_whole(100)),
+                (validator_b.clone(), Amount::native_whole(100)),
+                (validator_c.clone(), Amount::native_whole(40)),
+            ]),
+        );
+        test_utils::whitelist_tokens(
+            &mut wl_storage,
+            [(
+                ASSET,
+                WhitelistMeta {
+                    cap: Amount::max(),
+                    denom: 0,
+                },
+            )],
+        );
+        let veto_key = whitelist::Key {
+            asset: ASSET,
+            suffix: whitelist::KeyType::WithdrawalVeto,
+        }
+        .into();
+        wl_storage
+            .write(
+                &veto_key,
+                WithdrawalVeto {
+                    threshold: DenominatedAmount::new(
+                        Amount::from(THRESHOLD),
+                        Denomination(0),
+                    ),
+                    delay: NonZeroU64::new(DELAY).unwrap(),
+                },
+            )
+            .expect("Test failed");
+        let transfer = new_transfer(0);
+        wl_storage
+            .write(
+                &balance_key(&transfer.token_address(), &BRIDGE_POOL_ADDRESS),
+                Amount::from(10_000),
+            )
+            .expect("Test failed");
+        wl_storage
+            .write(
+                &balance_key(&nam(), &BRIDGE_POOL_ADDRESS),
+                Amount::from(10_000),
+            )
+            .expect("Test failed");
+        wl_storage.commit_block().expect("Test failed");
+        TestPackage {
+            validators: [validator_a, validator_b, validator_c],
+            keys,
+            wl_storage,
+        }
+    }
+
+    /// Make a transfer of the given amount of [`ASSET`].
+    fn new_transfer(amount: u64) -> PendingTransfer {
+        PendingTransfer {
+            transfer: TransferToEthereum {
+                kind: TransferToEthereumKind::Erc20,
+                asset: ASSET,
+                sender: address::testing::established_address_1(),
+                recipient: EthAddress([1; 20]),
+                amount: Amount::from(amount),
+            },
+            gas_fee: GasFee {
+                token: nam(),
+                amount: Amount::from(10),
+                payer: address::testing::established_address_1(),
+            },
+        }
+    }
+
+    /// Add the given transfer to the Bridge pool, and delay it as
+    /// if the current block was finalized.
+    fn add_transfer(
+        wl_storage: &mut TestWlStorage,
+        transfer: &PendingTransfer,
+    ) -> ChangedKeys {
+        let key = get_pending_key(transfer);
+        wl_storage
+            .write(&key, transfer.clone())
+            .expect("Test failed");
+        update_delayed_transfers(wl_storage, &BTreeSet::from([key]))
+            .expect("Test failed")
+    }
+
+    /// Test that only the transfers above the withdrawal veto
+    /// threshold are delayed, and that they are released to the
+    /// Bridge pool at the end of their veto window.
+    #[test]
+    fn test_delay_and_release_transfers() {
+        let TestPackage { mut wl_storage, .. } = setup();
+        let height = wl_storage.storage.get_block_height().0;
+
+        let small_transfer = new_transfer(THRESHOLD);
+        let changed_keys = add_transfer(&mut wl_storage, &small_transfer);
+        assert!(changed_keys.is_empty());
+
+        let large_transfer = new_transfer(THRESHOLD + 1);
+        let pending_key = get_pending_key(&large_transfer);
+        let delayed_key = get_delayed_transfer_key(&large_transfer.keccak256());
+        let changed_keys = add_transfer(&mut wl_storage, &large_transfer);
+        assert_eq!(
+            changed_keys,
+            BTreeSet::from([pending_key.clone(), delayed_key.clone()])
+        );
+        assert!(!wl_storage.has_key(&pending_key).expect("Test failed"));
+        assert_eq!(
+            wl_storage
+                .read::<DelayedTransfer>(&delayed_key)
+                .expect("Test failed"),
+            Some(DelayedTransfer {
+                transfer: large_transfer.clone(),
+                release_height: height + DELAY,
+            })
+        );
+        wl_storage.commit_block().expect("Test failed");
+
+        // the transfer is held until the end of its veto window
+        wl_storage.storage.block.height = height + (DELAY - 1);
+        let changed_keys =
+            update_delayed_transfers(&mut wl_storage, &BTreeSet::new())
+                .expect("Test failed");
+        assert!(changed_keys.is_empty());
+
+        wl_storage.storage.block.height = height + DELAY;
+        let changed_keys =
+            update_delayed_transfers(&mut wl_storage, &BTreeSet::new())
+                .expect("Test failed");
+        assert_eq!(
+            changed_keys,
+            BTreeSet::from([pending_key.clone(), delayed_key.clone()])
+        );
+        assert!(!wl_storage.has_key(&delayed_key).expect("Test failed"));
+        assert_eq!(
+            wl_storage
+                .read::<PendingTransfer>(&pending_key)
+                .expect("Test failed"),
+            Some(large_transfer)
+        );
+    }
+
+    /// Test that a delayed transfer vetoed by a quorum of validators
+    /// is refunded and never released to the Bridge pool.
+    #[test]
+    fn test_vetoed_transfer_is_refunded() {
+        let TestPackage {
+            validators,
+            keys,
+            mut wl_storage,
+        } = setup();
+        let transfer = new_transfer(THRESHOLD + 1);
+        let transfer_hash = transfer.keccak256();
+        let delayed_key = get_delayed_transfer_key(&transfer_hash);
+        _ = add_transfer(&mut wl_storage, &transfer);
+        wl_storage.commit_block().expect("Test failed");
+
+        let block_height = wl_storage.storage.get_last_block_height();
+        let veto = |validator: &Address| {
+            WithdrawalVetoVext {
+                validator_addr: validator.clone(),
+                block_height,
+                transfer_hash: transfer_hash.clone(),
+            }
+            .sign(&keys[validator].protocol)
+        };
+        let veto_a = veto(&validators[0]);
+        let veto_b = veto(&validators[1]);
+
+        // a single validator does not have a quorum
+        let TxResult {
+            eth_bridge_events, ..
+        } = apply_derived_tx(&mut wl_storage, veto_a).expect("Test failed");
+        assert!(eth_bridge_events.is_empty());
+        assert!(wl_storage.has_key(&delayed_key).expect("Test failed"));
+
+        let TxResult {
+            changed_keys,
+            eth_bridge_events,
+            ..
+        } = apply_derived_tx(&mut wl_storage, veto_b).expect("Test failed");
+        assert_eq!(
+            eth_bridge_events,
+            BTreeSet::from([EthBridgeEvent::new_bridge_pool_vetoed(
+                transfer_hash.clone()
+            )])
+        );
+        assert!(changed_keys.contains(&delayed_key));
+        assert!(!wl_storage.has_key(&delayed_key).expect("Test failed"));
+        let sender_balance: Amount = wl_storage
+            .read(&balance_key(
+                &transfer.token_address(),
+                &transfer.transfer.sender,
+            ))
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(sender_balance, transfer.transfer.amount);
+        let tally_keys = vote_tallies::Keys::from(&transfer_hash);
+        assert!(!wl_storage.has_key(&tally_keys.seen()).expect("Test failed"));
+
+        // the vetoes of the transfer are now ignored
+        let veto_c = veto(&validators[2]);
+        let TxResult { changed_keys, .. } =
+            apply_derived_tx(&mut wl_storage, veto_c).expect("Test failed");
+        assert!(changed_keys.is_empty());
+    }
+}
@@ -1,8 +1,10 @@
 //! Validation logic for Ethereum bridge protocol actions.
 
 pub mod bridge_pool_roots;
+pub mod checkpoints;
 pub mod ethereum_events;
 pub mod validator_set_update;
+pub mod withdrawal_vetoes;
 
 use thiserror::Error;
 
@@ -47,4 +49,10 @@ pub enum VoteExtensionError {
          not active"
     )]
     EthereumBridgeInactive,
+    #[error("The vetoed transfer is not delayed in the Ethereum bridge pool")]
+    TransferNotDelayed,
+    #[error("No checkpoint was recorded at the signed block height")]
+    CheckpointNotRecorded,
+    #[error("The signature of the checkpoint is invalid")]
+    InvalidCheckpointSig,
 }
@@ -0,0 +1,111 @@
+//! Checkpoints validation.
+
+use namada_core::types::eth_abi::Encode;
+use namada_core::types::storage::BlockHeight;
+use namada_proof_of_stake::pos_queries::PosQueries;
+use namada_state::{DBIter, StorageHasher, WlStorage, DB};
+use namada_tx::{SignableEthMessage, Signed};
+use namada_vote_ext::checkpoints;
+
+use super::VoteExtensionError;
+use crate::storage::eth_bridge_queries::EthBridgeQueries;
+
+/// Validates a vote extension signing over the checkpoint of
+/// the application state recorded at the provided block height.
+///
+/// Checks that at epoch of the provided height:
+///  * A checkpoint was recorded at the provided height.
+///  * The inner Namada address corresponds to a consensus validator.
+///  * The validator correctly signed the extension.
+///  * Check that the inner signature of the checkpoint is valid.
+pub fn validate_checkpoint_vext<D, H>(
+    wl_storage: &WlStorage<D, H>,
+    ext: &Signed<checkpoints::Vext>,
+    last_height: BlockHeight,
+) -> Result<(), VoteExtensionError>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    let ext_height_epoch =
+        match wl_storage.pos_queries().get_epoch(ext.data.block_height) {
+            Some(epoch) => epoch,
+            _ => {
+                tracing::debug!(
+                    block_height = ?ext.data.block_height,
+                    "The epoch of the checkpoint's vote extension's block \
+                     height should always be known",
+                );
+                return Err(VoteExtensionError::UnexpectedEpoch);
+            }
+        };
+
+    if ext.data.block_height > last_height {
+        tracing::debug!(
+            ext_height = ?ext.data.block_height,
+            ?last_height,
+            "Checkpoint's vote extension issued for a block height higher \
+             than the chain's last height."
+        );
+        return Err(VoteExtensionError::UnexpectedBlockHeight);
+    }
+    let Some(checkpoint) = wl_storage
+        .ethbridge_queries()
+        .get_checkpoint(ext.data.block_height)
+    else {
+        tracing::debug!(
+            block_height = ?ext.data.block_height,
+            "No checkpoint was recorded at the height of the checkpoint's \
+             vote extension"
+        );
+        return Err(VoteExtensionError::CheckpointNotRecorded);
+    };
+
+    // get the public key associated with this validator
+    let validator = &ext.data.validator_addr;
+    let (_, pk) = wl_storage
+        .pos_queries()
+        .get_validator_from_address(validator, Some(ext_height_epoch))
+        .map_err(|err| {
+            tracing::debug!(
+                ?err,
+                %validator,
+                "Could not get public key from Storage for some validator, \
+                 while validating checkpoint's vote extension"
+            );
+            VoteExtensionError::PubKeyNotInStorage
+        })?;
+    // verify the signature of the vote extension
+    ext.verify(&pk).map_err(|err| {
+        tracing::debug!(
+            ?err,
+            ?ext.sig,
+            ?pk,
+            %validator,
+            "Failed to verify the signature of a checkpoint's vote extension \
+             issued by some validator"
+        );
+        VoteExtensionError::VerifySigFailed
+    })?;
+
+    let signed = Signed::<_, SignableEthMessage>::new_from(
+        checkpoint.keccak256(),
+        ext.data.sig.clone(),
+    );
+    let pk = wl_storage
+        .pos_queries()
+        .read_validator_eth_hot_key(validator, Some(ext_height_epoch))
+        .expect("A validator should have an Ethereum hot key in storage.");
+    signed.verify(&pk).map_err(|err| {
+        tracing::debug!(
+            ?err,
+            ?signed.sig,
+            ?pk,
+            %validator,
+            "Failed to verify the signature of a checkpoint issued by some \
+             validator."
+        );
+        VoteExtensionError::InvalidCheckpointSig
+    })?;
+    Ok(())
+}
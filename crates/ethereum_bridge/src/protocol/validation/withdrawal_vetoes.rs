@@ -0,0 +1,100 @@
+//! Withdrawal vetoes validation.
+
+use namada_core::types::storage::BlockHeight;
+use namada_proof_of_stake::pos_queries::PosQueries;
+use namada_state::{DBIter, StorageHasher, WlStorage, DB};
+use namada_tx::Signed;
+use namada_vote_ext::withdrawal_vetoes;
+
+use super::VoteExtensionError;
+use crate::storage::eth_bridge_queries::EthBridgeQueries;
+
+/// Validates a vote extension issued at the provided
+/// block height vetoing a delayed transfer to Ethereum.
+///
+/// Checks that at epoch of the provided height:
+///  * The inner Namada address corresponds to a consensus validator.
+///  * The validator correctly signed the extension.
+///  * The validator signed over the correct height inside of the extension.
+///  * The vetoed transfer is still delayed.
+pub fn validate_withdrawal_veto_vext<D, H>(
+    wl_storage: &WlStorage<D, H>,
+    ext: &Signed<withdrawal_vetoes::Vext>,
+    last_height: BlockHeight,
+) -> Result<(), VoteExtensionError>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    // NOTE: the vetoes are not checked against the status of the
+    // bridge, such that the transfers delayed before the bridge
+    // was disabled can still be vetoed
+    let ext_height_epoch =
+        match wl_storage.pos_queries().get_epoch(ext.data.block_height) {
+            Some(epoch) => epoch,
+            _ => {
+                tracing::debug!(
+                    block_height = ?ext.data.block_height,
+                    "The epoch of the withdrawal veto's vote extension's \
+                     block height should always be known",
+                );
+                return Err(VoteExtensionError::UnexpectedEpoch);
+            }
+        };
+
+    if ext.data.block_height > last_height {
+        tracing::debug!(
+            ext_height = ?ext.data.block_height,
+            ?last_height,
+            "Withdrawal veto's vote extension issued for a block height \
+             higher than the chain's last height."
+        );
+        return Err(VoteExtensionError::UnexpectedBlockHeight);
+    }
+    if ext.data.block_height.0 == 0 {
+        tracing::debug!("Dropping vote extension issued at genesis");
+        return Err(VoteExtensionError::UnexpectedBlockHeight);
+    }
+
+    // get the public key associated with this validator
+    let validator = &ext.data.validator_addr;
+    let (_, pk) = wl_storage
+        .pos_queries()
+        .get_validator_from_address(validator, Some(ext_height_epoch))
+        .map_err(|err| {
+            tracing::debug!(
+                ?err,
+                %validator,
+                "Could not get public key from Storage for some validator, \
+                 while validating withdrawal veto's vote extension"
+            );
+            VoteExtensionError::PubKeyNotInStorage
+        })?;
+    // verify the signature of the vote extension
+    ext.verify(&pk).map_err(|err| {
+        tracing::debug!(
+            ?err,
+            ?ext.sig,
+            ?pk,
+            %validator,
+            "Failed to verify the signature of a withdrawal veto's vote \
+             extension issued by some validator"
+        );
+        VoteExtensionError::VerifySigFailed
+    })?;
+
+    if wl_storage
+        .ethbridge_queries()
+        .get_delayed_transfer(&ext.data.transfer_hash)
+        .is_none()
+    {
+        tracing::debug!(
+            transfer_hash = %ext.data.transfer_hash,
+            %validator,
+            "The transfer vetoed by some validator is not delayed, it might \
+             have been released or vetoed already"
+        );
+        return Err(VoteExtensionError::TransferNotDelayed);
+    }
+    Ok(())
+}
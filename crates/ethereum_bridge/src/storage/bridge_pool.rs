@@ -1,14 +1,37 @@
 //! Tools for accessing the storage subspaces of the Ethereum
 //! bridge pool
 
-use namada_core::types::eth_bridge_pool::Segments;
+use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::ledger::eth_bridge::ADDRESS as BRIDGE_ADDRESS;
 pub use namada_core::types::eth_bridge_pool::{
     get_key_from_hash, get_pending_key, is_pending_transfer_key,
     BRIDGE_POOL_ADDRESS,
 };
-use namada_core::types::storage::{DbKeySeg, Key};
+use namada_core::types::eth_bridge_pool::{PendingTransfer, Segments};
+use namada_core::types::keccak::KeccakHash;
+use namada_core::types::storage::{BlockHeight, DbKeySeg, Key};
 pub use namada_state::merkle_tree::eth_bridge_pool::BridgePoolTree;
 
+/// Storage sub-key space of the Ethereum bridge account reserved to the
+/// transfers delayed by their withdrawal veto window.
+///
+/// The delayed transfers are kept out of the Bridge pool, so that they
+/// can't be signed over nor relayed to Ethereum until they're released.
+pub const DELAYED_TRANSFERS_KEY_SEGMENT: &str = "delayed_transfers";
+
+/// A transfer to Ethereum held back from the Bridge pool while it can be
+/// vetoed by the validators
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct DelayedTransfer {
+    /// The delayed transfer, whose assets and fees are already escrowed
+    pub transfer: PendingTransfer,
+    /// The block height at which the transfer is released to the Bridge
+    /// pool, unless it has been vetoed before
+    pub release_height: BlockHeight,
+}
+
 /// Get the storage key for the root of the Merkle tree
 /// containing the transfers in the pool
 pub fn get_signed_root_key() -> Key {
@@ -35,3 +58,34 @@ pub fn get_nonce_key() -> Key {
 pub fn is_bridge_pool_key(key: &Key) -> bool {
     matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &BRIDGE_POOL_ADDRESS)
 }
+
+/// Get the storage key prefix of the delayed transfers
+pub fn get_delayed_transfers_prefix() -> Key {
+    super::prefix()
+        .push(&DELAYED_TRANSFERS_KEY_SEGMENT.to_owned())
+        .expect("should always be able to construct this key")
+}
+
+/// Get the storage key of the delayed transfer with the given hash
+pub fn get_delayed_transfer_key(hash: &KeccakHash) -> Key {
+    get_delayed_transfers_prefix()
+        .push(hash)
+        .expect("should always be able to construct this key")
+}
+
+/// Get the hash of the delayed transfer stored at the given key, if it is a
+/// delayed transfer key
+pub fn is_delayed_transfer_key(key: &Key) -> Option<KeccakHash> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(hash),
+        ] if addr == &BRIDGE_ADDRESS
+            && prefix == DELAYED_TRANSFERS_KEY_SEGMENT =>
+        {
+            hash.as_str().try_into().ok()
+        }
+        _ => None,
+    }
+}
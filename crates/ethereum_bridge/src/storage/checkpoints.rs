@@ -0,0 +1,94 @@
+//! Storage of the checkpoints of the application state.
+//!
+//! When a checkpoint interval is set, the app hash of every block
+//! height multiple of the interval is recorded in storage, for the
+//! validators to co-sign it with their Ethereum hot keys. Once a
+//! quorum of validators signed a checkpoint, its certificate can be
+//! posted to external chains.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::types::eth_abi::{self, Encode};
+use namada_core::types::keccak::KeccakHash;
+use namada_core::types::storage::{BlockHeight, Key};
+
+/// Sub-key space of the recorded checkpoints.
+pub const CHECKPOINTS_KEY_SEGMENT: &str = "app_checkpoints";
+
+/// Sub-key of the height of the latest checkpoint signed by a quorum of
+/// validators.
+pub const LATEST_CERTIFIED_KEY_SEGMENT: &str = "latest_certified_checkpoint";
+
+/// A checkpoint of the application state, at some block height.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct Checkpoint {
+    /// The block height of the checkpoint.
+    pub height: BlockHeight,
+    /// The app hash committed at `height`.
+    pub app_hash: KeccakHash,
+}
+
+impl Encode<2> for Checkpoint {
+    fn tokenize(&self) -> [eth_abi::Token; 2] {
+        let height = eth_abi::Token::Uint(self.height.0.into());
+        let app_hash = eth_abi::Token::FixedBytes(self.app_hash.0.to_vec());
+        [height, app_hash]
+    }
+}
+
+/// Get the key prefix of the recorded checkpoints.
+pub fn get_checkpoints_prefix() -> Key {
+    super::prefix()
+        .push(&CHECKPOINTS_KEY_SEGMENT.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the checkpoint recorded at the given height.
+pub fn get_checkpoint_key(height: BlockHeight) -> Key {
+    get_checkpoints_prefix()
+        .push(&height)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the height of the latest checkpoint signed by a quorum
+/// of validators.
+pub fn get_latest_certified_checkpoint_key() -> Key {
+    super::prefix()
+        .push(&LATEST_CERTIFIED_KEY_SEGMENT.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::ledger::eth_bridge::ADDRESS;
+    use namada_core::types::storage::{DbKeySeg, KeySeg};
+
+    use super::*;
+
+    /// Test that the checkpoint keys are under the Ethereum bridge
+    /// storage sub-space.
+    #[test]
+    fn test_checkpoint_keys() {
+        let key = get_checkpoint_key(BlockHeight(100));
+        assert_eq!(
+            key.segments,
+            vec![
+                DbKeySeg::AddressSeg(ADDRESS),
+                DbKeySeg::StringSeg(CHECKPOINTS_KEY_SEGMENT.to_owned()),
+                BlockHeight(100).to_db_key(),
+            ]
+        );
+        let key = get_latest_certified_checkpoint_key();
+        assert!(crate::storage::has_eth_addr_segment(&key));
+    }
+}
@@ -1,3 +1,5 @@
+use std::num::NonZeroU64;
+
 use borsh::{BorshDeserialize, BorshSerialize};
 use namada_core::hints;
 use namada_core::types::address::Address;
@@ -22,8 +24,13 @@ use namada_vote_ext::validator_set_update::{
     EthAddrBook, ValidatorSetArgs, VotingPowersMap, VotingPowersMapExt,
 };
 
-use crate::storage::proof::BridgePoolRootProof;
-use crate::storage::{active_key, bridge_pool, vote_tallies, whitelist};
+use crate::storage::bridge_pool::DelayedTransfer;
+use crate::storage::checkpoints::{self, Checkpoint};
+use crate::storage::parameters::WithdrawalVeto;
+use crate::storage::proof::{BridgePoolRootProof, CheckpointProof};
+use crate::storage::{
+    active_key, bridge_pool, checkpoint_interval_key, vote_tallies, whitelist,
+};
 
 /// This enum is used as a parameter to
 /// [`EthBridgeQueriesHook::must_send_valset_upd`].
@@ -144,6 +151,43 @@ where
             .unwrap_or(false)
     }
 
+    /// Fetch the number of blocks between two checkpoints of the
+    /// application state, if checkpoints are enabled.
+    pub fn get_checkpoint_interval(self) -> Option<NonZeroU64> {
+        self.wl_storage
+            .read(&checkpoint_interval_key())
+            .expect("Reading a value from storage should not fail")
+    }
+
+    /// Check if a checkpoint of the application state is made at the
+    /// given [`BlockHeight`].
+    pub fn is_checkpoint_height(self, height: BlockHeight) -> bool {
+        height.0 != 0
+            && self
+                .get_checkpoint_interval()
+                .map(|interval| height.0 % interval.get() == 0)
+                .unwrap_or(false)
+    }
+
+    /// Fetch the checkpoint of the application state recorded at the
+    /// given [`BlockHeight`].
+    pub fn get_checkpoint(self, height: BlockHeight) -> Option<Checkpoint> {
+        self.wl_storage
+            .read(&checkpoints::get_checkpoint_key(height))
+            .expect("Reading a value from storage should not fail")
+    }
+
+    /// Check if the checkpoint at the given [`BlockHeight`] has been
+    /// signed by a quorum of validators.
+    pub fn checkpoint_certified(self, height: BlockHeight) -> bool {
+        let checkpoint_keys: vote_tallies::Keys<CheckpointProof> =
+            vote_tallies::Keys::from(&height);
+        self.wl_storage
+            .read(&checkpoint_keys.seen())
+            .expect("Reading a value from storage should not fail")
+            .unwrap_or(false)
+    }
+
     /// Check if the bridge is disabled, enabled, or
     /// scheduled to be enabled at a specified epoch.
     pub fn check_bridge_status(self) -> EthBridgeStatus {
@@ -456,6 +500,36 @@ where
             .expect("Reading from storage should not fail")
     }
 
+    /// Fetch the withdrawal veto window of the asset associated with the
+    /// given [`EthAddress`].
+    ///
+    /// If the withdrawals of the asset are never delayed, return [`None`].
+    pub fn get_withdrawal_veto(
+        self,
+        &token: &EthAddress,
+    ) -> Option<WithdrawalVeto> {
+        let key = whitelist::Key {
+            asset: token,
+            suffix: whitelist::KeyType::WithdrawalVeto,
+        }
+        .into();
+
+        self.wl_storage
+            .read(&key)
+            .expect("Reading from storage should not fail")
+    }
+
+    /// Fetch the delayed transfer with the given hash, if it is still
+    /// waiting for the end of its veto window.
+    pub fn get_delayed_transfer(
+        self,
+        hash: &KeccakHash,
+    ) -> Option<DelayedTransfer> {
+        self.wl_storage
+            .read(&bridge_pool::get_delayed_transfer_key(hash))
+            .expect("Reading from storage should not fail")
+    }
+
     /// Fetch the token supply of the asset associated with the given
     /// [`EthAddress`].
     ///
@@ -1,6 +1,7 @@
 //! Functionality for accessing the storage subspace
 
 pub mod bridge_pool;
+pub mod checkpoints;
 pub mod eth_bridge_queries;
 pub mod parameters;
 pub mod proof;
@@ -65,6 +66,12 @@ pub fn bridge_contract_key() -> Key {
     get_bridge_contract_address_key_at_addr(PARAM_ADDRESS)
 }
 
+/// Storage key for the number of blocks between two checkpoints of the
+/// application state.
+pub fn checkpoint_interval_key() -> Key {
+    get_checkpoint_interval_key_at_addr(PARAM_ADDRESS)
+}
+
 #[cfg(test)]
 mod test {
     use namada_core::types::address;
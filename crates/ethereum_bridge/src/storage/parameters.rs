@@ -35,6 +35,36 @@ pub struct Erc20WhitelistEntry {
     pub token_address: EthAddress,
     /// The token cap of the whitelisted ERC20 token.
     pub token_cap: DenominatedAmount,
+    /// The withdrawal veto window of the whitelisted ERC20 token. Without
+    /// it, the withdrawals of the token are never delayed.
+    #[serde(default)]
+    pub withdrawal_veto: Option<WithdrawalVeto>,
+}
+
+/// The delay of the large withdrawals of an ERC20 token to Ethereum, during
+/// which a supermajority of the validators can veto them.
+///
+/// This protects the bridge from being drained with compromised keys, by
+/// giving the validators the time to react to suspicious withdrawals before
+/// they can be relayed.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    Debug,
+    Deserialize,
+    Serialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct WithdrawalVeto {
+    /// The withdrawals of more than this amount are delayed. It must have
+    /// the same denomination as the token cap.
+    pub threshold: DenominatedAmount,
+    /// The number of blocks during which a delayed withdrawal can be
+    /// vetoed, before it is released to the Bridge pool.
+    pub delay: NonZeroU64,
 }
 
 /// Represents a configuration value for the minimum number of
@@ -155,6 +185,11 @@ pub struct EthereumBridgeParams {
     /// Minimum number of confirmations needed to trust an Ethereum branch.
     /// This must be at least one.
     pub min_confirmations: MinimumConfirmations,
+    /// The number of blocks between two checkpoints of the application
+    /// state co-signed by the validators. No checkpoints are made if this
+    /// is not set.
+    #[serde(default)]
+    pub checkpoint_interval: Option<NonZeroU64>,
     /// List of ERC20 token types whitelisted at genesis time.
     pub erc20_whitelist: Vec<Erc20WhitelistEntry>,
     /// The addresses of the Ethereum contracts that need to be directly known
@@ -176,6 +211,7 @@ impl EthereumBridgeParams {
             erc20_whitelist,
             eth_start_height,
             min_confirmations,
+            checkpoint_interval,
             contracts:
                 Contracts {
                     native_erc20,
@@ -201,9 +237,15 @@ impl EthereumBridgeParams {
         wl_storage
             .write(&eth_start_height_key, eth_start_height)
             .unwrap();
+        if let Some(interval) = checkpoint_interval {
+            wl_storage
+                .write(&bridge_storage::checkpoint_interval_key(), interval)
+                .unwrap();
+        }
         for Erc20WhitelistEntry {
             token_address: addr,
             token_cap,
+            withdrawal_veto,
         } in erc20_whitelist
         {
             let cap = token_cap.amount();
@@ -236,6 +278,22 @@ impl EthereumBridgeParams {
             }
             .into();
             wl_storage.write(&key, denom).unwrap();
+
+            if let Some(veto) = withdrawal_veto {
+                if veto.threshold.denom() != denom {
+                    panic!(
+                        "Error writing Ethereum bridge config: The withdrawal \
+                         veto threshold of {addr} should have the same \
+                         denomination as its token cap"
+                    );
+                }
+                let key = whitelist::Key {
+                    asset: *addr,
+                    suffix: whitelist::KeyType::WithdrawalVeto,
+                }
+                .into();
+                wl_storage.write(&key, veto).unwrap();
+            }
         }
         // Initialize the storage for the Ethereum Bridge VP.
         vp::ethereum_bridge::init_storage(wl_storage);
@@ -378,6 +436,7 @@ mod tests {
             erc20_whitelist: vec![],
             eth_start_height: Default::default(),
             min_confirmations: MinimumConfirmations::default(),
+            checkpoint_interval: None,
             contracts: Contracts {
                 native_erc20: EthAddress([42; 20]),
                 bridge: UpgradeableContract {
@@ -400,6 +459,7 @@ mod tests {
             erc20_whitelist: vec![],
             eth_start_height: Default::default(),
             min_confirmations: MinimumConfirmations::default(),
+            checkpoint_interval: None,
             contracts: Contracts {
                 native_erc20: EthAddress([42; 20]),
                 bridge: UpgradeableContract {
@@ -432,6 +492,7 @@ mod tests {
             erc20_whitelist: vec![],
             eth_start_height: Default::default(),
             min_confirmations: MinimumConfirmations::default(),
+            checkpoint_interval: None,
             contracts: Contracts {
                 native_erc20: EthAddress([42; 20]),
                 bridge: UpgradeableContract {
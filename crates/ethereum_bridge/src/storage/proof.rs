@@ -14,6 +14,8 @@ use namada_vote_ext::validator_set_update::{
     valset_upd_toks_to_hashes, EthAddrBook, VotingPowersMap, VotingPowersMapExt,
 };
 
+use crate::storage::checkpoints::Checkpoint;
+
 /// Ethereum proofs contain the [`secp256k1`] signatures of validators
 /// over some data to be signed.
 ///
@@ -30,6 +32,9 @@ pub struct EthereumProof<T> {
 
 pub type BridgePoolRootProof = EthereumProof<(KeccakHash, Uint)>;
 
+/// The certificate of a checkpoint of the application state.
+pub type CheckpointProof = EthereumProof<Checkpoint>;
+
 impl<T> EthereumProof<T> {
     /// Return an incomplete [`EthereumProof`].
     pub fn new(data: T) -> Self {
@@ -118,6 +123,21 @@ impl Encode<1> for EthereumProof<(Epoch, VotingPowersMap)> {
     }
 }
 
+/// The checkpoint is encoded along with the signatures of the validators,
+/// sorted by the voting powers of the bridge validator set of the epoch of
+/// the checkpoint.
+impl Encode<1> for EthereumProof<(Checkpoint, VotingPowersMap)> {
+    fn tokenize(&self) -> [eth_abi::Token; 1] {
+        let signatures = sort_sigs(&self.data.1, &self.signatures);
+        let [height, app_hash] = self.data.0.tokenize();
+        [eth_abi::Token::Tuple(vec![
+            height,
+            app_hash,
+            Tokenizable::into_token(signatures),
+        ])]
+    }
+}
+
 #[cfg(test)]
 mod test_ethbridge_proofs {
     //! Test ethereum bridge proofs.
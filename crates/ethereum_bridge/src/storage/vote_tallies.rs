@@ -13,6 +13,7 @@ use namada_core::types::storage::{BlockHeight, DbKeySeg, Epoch, Key};
 use namada_macros::StorageKeys;
 use namada_vote_ext::validator_set_update::VotingPowersMap;
 
+use crate::storage::checkpoints::Checkpoint;
 use crate::storage::proof::{BridgePoolRootProof, EthereumProof};
 
 /// Storage sub-key space reserved to keeping track of the
@@ -28,6 +29,16 @@ pub const BRIDGE_POOL_ROOT_PREFIX_KEY_SEGMENT: &str = "bp_root_and_nonce";
 /// voting power assigned to validator set updates.
 pub const VALSET_UPDS_PREFIX_KEY_SEGMENT: &str = "validator_set_updates";
 
+/// Storage sub-key space reserved to keeping track of the
+/// voting power assigned to checkpoints of the application
+/// state.
+pub const CHECKPOINTS_PREFIX_KEY_SEGMENT: &str = "checkpoints";
+
+/// Storage sub-key space reserved to keeping track of the
+/// voting power assigned to vetoes of delayed transfers to
+/// Ethereum.
+pub const WITHDRAWAL_VETOES_PREFIX_KEY_SEGMENT: &str = "withdrawal_vetoes";
+
 /// Storage segments of [`Keys`].
 #[derive(StorageKeys)]
 pub struct KeysSegments {
@@ -231,6 +242,50 @@ impl From<(&BridgePoolRoot, BlockHeight)> for Keys<BridgePoolRoot> {
     }
 }
 
+/// Get the key prefix corresponding to the storage location of the vetoes
+/// of delayed transfers whose "seen" state is being tracked.
+pub fn withdrawal_vetoes_prefix() -> Key {
+    super::prefix()
+        .push(&WITHDRAWAL_VETOES_PREFIX_KEY_SEGMENT.to_owned())
+        .expect("should always be able to construct this key")
+}
+
+/// The vetoes of a delayed transfer are tallied under the hash of the
+/// transfer.
+impl From<&KeccakHash> for Keys<KeccakHash> {
+    fn from(transfer_hash: &KeccakHash) -> Self {
+        let prefix = withdrawal_vetoes_prefix()
+            .push(transfer_hash)
+            .expect("should always be able to construct this key");
+        Keys {
+            prefix,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Get the key prefix corresponding to the storage location of checkpoints
+/// of the application state whose "seen" state is being tracked.
+pub fn checkpoints_prefix() -> Key {
+    super::prefix()
+        .push(&CHECKPOINTS_PREFIX_KEY_SEGMENT.to_owned())
+        .expect("should always be able to construct this key")
+}
+
+/// The signatures of a checkpoint are tallied under its height, since
+/// at most a single checkpoint is recorded at any given height.
+impl From<&BlockHeight> for Keys<EthereumProof<Checkpoint>> {
+    fn from(height: &BlockHeight) -> Self {
+        let prefix = checkpoints_prefix()
+            .push(height)
+            .expect("should always be able to construct this key");
+        Keys {
+            prefix,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
 /// Get the key prefix corresponding to the storage location of validator set
 /// updates whose "seen" state is being tracked.
 pub fn valset_upds_prefix() -> Key {
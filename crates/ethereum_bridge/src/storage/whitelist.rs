@@ -29,6 +29,8 @@ mod segments {
         pub whitelisted: &'static str,
         /// The token cap of an ERC20 asset.
         pub cap: &'static str,
+        /// The withdrawal veto window of an ERC20 asset.
+        pub withdrawal_veto: &'static str,
     }
 
     /// All the values of the generated [`Segments`].
@@ -50,6 +52,9 @@ pub enum KeyType {
     WrappedSupply,
     /// The denomination of the ERC20 asset.
     Denomination,
+    /// The withdrawal veto window of an ERC20 asset, if the large
+    /// withdrawals of the asset are delayed.
+    WithdrawalVeto,
 }
 
 /// Whitelisted ERC20 token storage sub-space.
@@ -93,12 +98,16 @@ impl From<&Key> for storage::Key {
                 let token = erc20_token_address(&key.asset);
                 denom_key(&token)
             }
+            KeyType::WithdrawalVeto => whitelist_prefix(&key.asset)
+                .push(&segments::VALUES.withdrawal_veto.to_owned())
+                .expect("Should be able to push a storage key segment"),
         }
     }
 }
 
 /// Check if some [`storage::Key`] is an Ethereum bridge whitelist key
-/// of type [`KeyType::Cap`] or [`KeyType::Whitelisted`].
+/// of type [`KeyType::Cap`], [`KeyType::Whitelisted`] or
+/// [`KeyType::WithdrawalVeto`].
 pub fn is_cap_or_whitelisted_key(key: &storage::Key) -> bool {
     match &key.segments[..] {
         [
@@ -153,6 +162,13 @@ mod tests {
         .into();
         assert!(is_cap_or_whitelisted_key(&cap_key));
 
+        let withdrawal_veto_key: storage::Key = Key {
+            asset: DAI_ERC20_ETH_ADDRESS,
+            suffix: KeyType::WithdrawalVeto,
+        }
+        .into();
+        assert!(is_cap_or_whitelisted_key(&withdrawal_veto_key));
+
         let unexpected_key = {
             let mut k: storage::Key = Key {
                 asset: DAI_ERC20_ETH_ADDRESS,
@@ -112,6 +112,7 @@ pub fn bootstrap_ethereum_bridge(
             // of this type using 0 as argument.
             NonZeroU64::new_unchecked(10)
         }),
+        checkpoint_interval: None,
         contracts: Contracts {
             native_erc20: wnam(),
             bridge: UpgradeableContract {
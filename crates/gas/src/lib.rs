@@ -94,7 +94,7 @@ impl Gas {
 
     /// Converts the sub gas units to whole ones. If the sub units are not a
     /// multiple of the `SCALE` than ceil the quotient
-    fn get_whole_gas_units(&self) -> u64 {
+    pub fn get_whole_gas_units(&self) -> u64 {
         let quotient = self.sub / SCALE;
         if self.sub % SCALE == 0 {
             quotient
@@ -325,6 +325,11 @@ impl VpGasMeter {
             current_gas: Gas::default(),
         }
     }
+
+    /// Get the gas consumed by the VP alone
+    pub fn get_vp_consumed_gas(&self) -> Gas {
+        self.current_gas
+    }
 }
 
 impl VpsGas {
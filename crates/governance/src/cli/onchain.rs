@@ -9,11 +9,11 @@ use serde::{Deserialize, Serialize};
 use super::validation::{
     is_valid_author_balance, is_valid_content, is_valid_default_proposal_data,
     is_valid_end_epoch, is_valid_grace_epoch, is_valid_pgf_funding_data,
-    is_valid_pgf_stewards_data, is_valid_proposal_period, is_valid_start_epoch,
-    ProposalValidation,
+    is_valid_pgf_stewards_data, is_valid_proposal_period,
+    is_valid_slash_pool_data, is_valid_start_epoch, ProposalValidation,
 };
 use crate::parameters::GovernanceParameters;
-use crate::storage::proposal::PGFTarget;
+use crate::storage::proposal::{PGFRetroClaim, PGFTarget, SlashPoolAction};
 
 #[derive(
     Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
@@ -243,6 +243,69 @@ impl TryFrom<&[u8]> for PgfFundingProposal {
     }
 }
 
+/// Slash pool disbursement proposal
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+pub struct SlashPoolProposal {
+    /// The proposal data
+    pub proposal: OnChainProposal,
+    /// The slash pool actions to execute
+    pub data: Vec<SlashPoolAction>,
+}
+
+impl SlashPoolProposal {
+    /// Validate a slash pool disbursement proposal
+    pub fn validate(
+        self,
+        governance_parameters: &GovernanceParameters,
+        current_epoch: Epoch,
+        force: bool,
+    ) -> Result<Self, ProposalValidation> {
+        if force {
+            return Ok(self);
+        }
+        is_valid_start_epoch(
+            self.proposal.voting_start_epoch,
+            current_epoch,
+            governance_parameters.min_proposal_voting_period,
+        )?;
+        is_valid_end_epoch(
+            self.proposal.voting_start_epoch,
+            self.proposal.voting_end_epoch,
+            current_epoch,
+            governance_parameters.min_proposal_voting_period,
+            governance_parameters.min_proposal_voting_period,
+            governance_parameters.max_proposal_period,
+        )?;
+        is_valid_grace_epoch(
+            self.proposal.grace_epoch,
+            self.proposal.voting_end_epoch,
+            governance_parameters.min_proposal_grace_epochs,
+        )?;
+        is_valid_proposal_period(
+            self.proposal.voting_start_epoch,
+            self.proposal.grace_epoch,
+            governance_parameters.max_proposal_period,
+        )?;
+        is_valid_content(
+            &self.proposal.content,
+            governance_parameters.max_proposal_content_size,
+        )?;
+        is_valid_slash_pool_data(&self.data)?;
+
+        Ok(self)
+    }
+}
+
+impl TryFrom<&[u8]> for SlashPoolProposal {
+    type Error = serde_json::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        serde_json::from_slice(value)
+    }
+}
+
 /// Pgf stewards
 #[derive(
     Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
@@ -281,6 +344,9 @@ pub struct PgfFunding {
     pub continuous: Vec<PGFTarget>,
     /// pgf retro fundings
     pub retro: Vec<PGFTarget>,
+    /// pgf retro fundings claimed by their targets over time
+    #[serde(default)]
+    pub retro_claims: Vec<PGFRetroClaim>,
 }
 
 /// Pgf continuous funding
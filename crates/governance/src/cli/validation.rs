@@ -6,6 +6,7 @@ use namada_core::types::token;
 use thiserror::Error;
 
 use super::onchain::{PgfFunding, StewardsUpdate};
+use crate::storage::proposal::{PGFRetroClaim, SlashPoolAction};
 
 /// This enum raprresent a proposal data
 #[derive(Clone, Debug, PartialEq, Error)]
@@ -69,6 +70,17 @@ pub enum ProposalValidation {
     /// The pgf funding data is not valid
     #[error("invalid proposal extra data: cannot be empty.")]
     InvalidPgfFundingExtraData,
+    /// The pgf retro claims are not valid
+    #[error(
+        "Invalid proposal extra data: retro claims must have a non-zero \
+         amount, limit per epoch and duration."
+    )]
+    InvalidPgfRetroClaims,
+    /// The slash pool data is not valid
+    #[error(
+        "Invalid proposal extra data: cannot be empty or contain zero amounts."
+    )]
+    InvalidSlashPoolExtraData,
 }
 
 pub fn is_valid_author_balance(
@@ -113,7 +125,10 @@ pub fn is_valid_end_epoch(
     min_proposal_voting_period: u64,
     max_proposal_period: u64,
 ) -> Result<(), ProposalValidation> {
-    let voting_period = proposal_end_epoch.0 - proposal_start_epoch.0;
+    let voting_period = proposal_end_epoch
+        .checked_offset_from(proposal_start_epoch)
+        .unwrap_or_default()
+        .0;
     let end_epoch_is_multipler =
         proposal_end_epoch % proposal_epoch_multiplier == 0;
     let is_valid_voting_period = voting_period > 0
@@ -135,7 +150,10 @@ pub fn is_valid_grace_epoch(
     proposal_end_epoch: Epoch,
     min_proposal_grace_epoch: u64,
 ) -> Result<(), ProposalValidation> {
-    let grace_period = proposal_grace_epoch.0 - proposal_end_epoch.0;
+    let grace_period = proposal_grace_epoch
+        .checked_offset_from(proposal_end_epoch)
+        .unwrap_or_default()
+        .0;
 
     if grace_period > 0 && grace_period >= min_proposal_grace_epoch {
         Ok(())
@@ -152,7 +170,10 @@ pub fn is_valid_proposal_period(
     proposal_grace_epoch: Epoch,
     max_proposal_period: u64,
 ) -> Result<(), ProposalValidation> {
-    let proposal_period = proposal_grace_epoch.0 - proposal_start_epoch.0;
+    let proposal_period = proposal_grace_epoch
+        .checked_offset_from(proposal_start_epoch)
+        .unwrap_or_default()
+        .0;
 
     if proposal_period > 0 && proposal_period <= max_proposal_period {
         Ok(())
@@ -244,9 +265,25 @@ pub fn is_valid_pgf_stewards_data(
 pub fn is_valid_pgf_funding_data(
     data: &PgfFunding,
 ) -> Result<(), ProposalValidation> {
-    if !data.continuous.is_empty() || !data.retro.is_empty() {
+    if data.continuous.is_empty()
+        && data.retro.is_empty()
+        && data.retro_claims.is_empty()
+    {
+        Err(ProposalValidation::InvalidPgfFundingExtraData)
+    } else if !data.retro_claims.iter().all(PGFRetroClaim::is_valid) {
+        Err(ProposalValidation::InvalidPgfRetroClaims)
+    } else {
+        Ok(())
+    }
+}
+
+pub fn is_valid_slash_pool_data(
+    data: &[SlashPoolAction],
+) -> Result<(), ProposalValidation> {
+    if !data.is_empty() && data.iter().all(|action| !action.amount().is_zero())
+    {
         Ok(())
     } else {
-        Err(ProposalValidation::InvalidPgfFundingExtraData)
+        Err(ProposalValidation::InvalidSlashPoolExtraData)
     }
 }
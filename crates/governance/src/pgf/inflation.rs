@@ -6,10 +6,12 @@ use namada_parameters::storage as params_storage;
 use namada_state::{
     DBIter, StorageHasher, StorageRead, StorageResult, WlStorage, DB,
 };
-use namada_trans_token::credit_tokens;
+use namada_trans_token::mint_native_tokens;
 use namada_trans_token::storage_key::minted_balance_key;
 
-use crate::pgf::storage::{get_parameters, get_payments, get_stewards};
+use crate::pgf::storage::{
+    get_parameters, get_payments, get_stewards, remove_expired_claims,
+};
 use crate::storage::proposal::{PGFIbcTarget, PGFTarget};
 
 /// Apply the PGF inflation.
@@ -30,6 +32,19 @@ where
     let pgf_parameters = get_parameters(storage)?;
     let staking_token = storage.get_native_token()?;
 
+    // the unclaimed amount of the expired retro fundings stays in the pgf
+    // account
+    let current_epoch = storage.storage.block.epoch;
+    for claim in remove_expired_claims(storage, current_epoch)? {
+        tracing::info!(
+            "Expired {} unclaimed tokens of the retro funding for {} from \
+             proposal id {}.",
+            claim.remaining.to_string_native(),
+            claim.target,
+            claim.id,
+        );
+    }
+
     let epochs_per_year: u64 = storage
         .read(&params_storage::get_epochs_per_year_key())?
         .expect("Epochs per year should exist in storage");
@@ -40,12 +55,8 @@ where
     let pgf_inflation_amount =
         (pgf_parameters.pgf_inflation_rate * total_supply) / epochs_per_year;
 
-    credit_tokens(
-        storage,
-        &staking_token,
-        &super::ADDRESS,
-        pgf_inflation_amount,
-    )?;
+    let deferred =
+        mint_native_tokens(storage, &super::ADDRESS, pgf_inflation_amount)?;
 
     tracing::info!(
         "Minting {} tokens for PGF rewards distribution into the PGF account \
@@ -53,6 +64,13 @@ where
         pgf_inflation_amount.to_string_native(),
         total_supply.to_string_native()
     );
+    if !deferred.is_zero() {
+        tracing::warn!(
+            "Deferred minting {} tokens for PGF rewards distribution that \
+             exceed the limit of native tokens minted per epoch.",
+            deferred.to_string_native()
+        );
+    }
 
     let mut pgf_fundings = get_payments(storage)?;
     // we want to pay first the oldest fundings
@@ -102,13 +120,7 @@ where
         for (address, percentage) in steward.reward_distribution {
             let pgf_steward_reward = percentage * pgf_steward_inflation;
 
-            if credit_tokens(
-                storage,
-                &staking_token,
-                &address,
-                pgf_steward_reward,
-            )
-            .is_ok()
+            if mint_native_tokens(storage, &address, pgf_steward_reward).is_ok()
             {
                 tracing::info!(
                     "Minting {} tokens for steward {} (total supply {})..",
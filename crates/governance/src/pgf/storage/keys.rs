@@ -5,13 +5,14 @@ use namada_state::collections::{lazy_map, LazyCollection, LazyMap};
 
 use crate::pgf::storage::steward::StewardDetail;
 use crate::pgf::ADDRESS;
-use crate::storage::proposal::StoragePgfFunding;
+use crate::storage::proposal::{StoragePgfClaim, StoragePgfFunding};
 
 /// Storage keys for pgf internal address.
 #[derive(StorageKeys)]
 struct Keys {
     stewards: &'static str,
     fundings: &'static str,
+    claims: &'static str,
     pgf_inflation_rate: &'static str,
     steward_inflation_rate: &'static str,
 }
@@ -72,6 +73,40 @@ pub fn is_fundings_key(key: &Key) -> bool {
                 && data.as_str() == lazy_map::DATA_SUBKEY)
 }
 
+/// Obtain a storage key for pgf retro claims.
+pub fn claims_key_prefix() -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.claims.to_string()),
+        ],
+    }
+}
+
+/// LazyMap handler for the pgf retro claims substorage
+pub fn claims_handle() -> LazyMap<Address, StoragePgfClaim> {
+    LazyMap::open(claims_key_prefix())
+}
+
+/// Check if the given storage key is a pgf retro claim key. If it is,
+/// returns the target address.
+pub fn is_claims_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(pgf),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(data),
+            DbKeySeg::AddressSeg(target),
+        ] if pgf.eq(&ADDRESS)
+            && prefix.as_str() == Keys::VALUES.claims
+            && data.as_str() == lazy_map::DATA_SUBKEY =>
+        {
+            Some(target)
+        }
+        _ => None,
+    }
+}
+
 /// Check if key is inside governance address space
 pub fn is_pgf_key(key: &Key) -> bool {
     matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &ADDRESS)
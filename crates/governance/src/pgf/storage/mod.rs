@@ -9,12 +9,17 @@ use std::collections::HashMap;
 
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
-use namada_state::{StorageRead, StorageResult, StorageWrite};
+use namada_core::types::storage::Epoch;
+use namada_core::types::token::Amount;
+use namada_state::{
+    OptionExt, StorageError, StorageRead, StorageResult, StorageWrite,
+};
 
 use crate::pgf::parameters::PgfParameters;
 use crate::pgf::storage::keys as pgf_keys;
 use crate::pgf::storage::steward::StewardDetail;
-use crate::storage::proposal::StoragePgfFunding;
+use crate::pgf::ADDRESS;
+use crate::storage::proposal::{StoragePgfClaim, StoragePgfFunding};
 
 /// Query the current pgf steward set
 pub fn get_stewards<S>(storage: &S) -> StorageResult<Vec<StewardDetail>>
@@ -80,6 +85,83 @@ where
     Ok(fundings)
 }
 
+/// Query the pgf retro fundings that are claimed by their targets
+pub fn get_claims<S>(storage: &S) -> StorageResult<Vec<StoragePgfClaim>>
+where
+    S: StorageRead,
+{
+    pgf_keys::claims_handle()
+        .iter(storage)?
+        .map(|data| data.map(|(_, claim)| claim))
+        .collect()
+}
+
+/// Query the pgf retro funding claimed by the given target
+pub fn get_claim<S>(
+    storage: &S,
+    target: &Address,
+) -> StorageResult<Option<StoragePgfClaim>>
+where
+    S: StorageRead,
+{
+    pgf_keys::claims_handle().get(storage, target)
+}
+
+/// Claim tokens of the pgf retro funding of the given target, or all the
+/// tokens that are currently claimable if no amount is given. Returns the
+/// claimed amount, that is transferred from the pgf account to the target.
+pub fn claim_retro_funding<S>(
+    storage: &mut S,
+    target: &Address,
+    amount: Option<Amount>,
+) -> StorageResult<Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    let claim = get_claim(storage, target)?
+        .ok_or_err_msg("No pgf retro funding to claim")?;
+    let epoch = storage.get_block_epoch()?;
+    let amount = amount.unwrap_or_else(|| claim.claimable(epoch));
+    let claim = claim.claim(epoch, amount).ok_or_else(|| {
+        StorageError::new_const(
+            "The amount exceeds the claimable pgf retro funding",
+        )
+    })?;
+    if claim.remaining.is_zero() {
+        pgf_keys::claims_handle().remove(storage, target)?;
+    } else {
+        pgf_keys::claims_handle().insert(storage, target.clone(), claim)?;
+    }
+    let native_token = storage.get_native_token()?;
+    namada_trans_token::transfer(
+        storage,
+        &native_token,
+        &ADDRESS,
+        target,
+        amount,
+    )?;
+    Ok(amount)
+}
+
+/// Remove the pgf retro fundings whose unclaimed amount is expired in the
+/// given epoch. Returns the removed fundings.
+pub fn remove_expired_claims<S>(
+    storage: &mut S,
+    epoch: Epoch,
+) -> StorageResult<Vec<StoragePgfClaim>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let expired = get_claims(storage)?
+        .into_iter()
+        .filter(|claim| claim.is_expired(epoch))
+        .collect::<Vec<_>>();
+    for claim in &expired {
+        pgf_keys::claims_handle().remove(storage, &claim.target)?;
+    }
+    Ok(expired)
+}
+
 /// Query the pgf parameters
 pub fn get_parameters<S>(storage: &S) -> StorageResult<PgfParameters>
 where
@@ -1,5 +1,6 @@
+use namada_core::key;
 use namada_core::types::address::Address;
-use namada_core::types::storage::{DbKeySeg, Key, KeySeg};
+use namada_core::types::storage::{DbKeySeg, Key};
 use namada_macros::StorageKeys;
 
 use crate::ADDRESS;
@@ -287,139 +288,132 @@ pub fn is_start_or_end_epoch_key(key: &Key) -> bool {
 
 /// Get governance prefix key
 pub fn proposal_prefix() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&Keys::VALUES.proposal.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [Keys::VALUES.proposal])
 }
 
 /// Get key for the minimum proposal fund
 pub fn get_min_proposal_fund_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&Keys::VALUES.min_fund.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [Keys::VALUES.min_fund])
 }
 
 /// Get maximum proposal code size key
 pub fn get_max_proposal_code_size_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&Keys::VALUES.max_code_size.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [Keys::VALUES.max_code_size])
 }
 
 /// Get minimum proposal period key
 pub fn get_min_proposal_voting_period_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&Keys::VALUES.min_period.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [Keys::VALUES.min_period])
 }
 
 /// Get maximum proposal period key
 pub fn get_max_proposal_period_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&Keys::VALUES.max_period.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [Keys::VALUES.max_period])
 }
 
 /// Get maximum proposal content key
 pub fn get_max_proposal_content_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&Keys::VALUES.max_content.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [Keys::VALUES.max_content])
 }
 
 /// Get min grace epoch proposal key
 pub fn get_min_proposal_grace_epoch_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&Keys::VALUES.min_grace_epoch.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [Keys::VALUES.min_grace_epoch])
 }
 
 /// Get key of proposal ids counter
 pub fn get_counter_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&Keys::VALUES.counter.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [Keys::VALUES.counter])
 }
 
 /// Get key of proposal content
 pub fn get_content_key(id: u64) -> Key {
-    proposal_prefix()
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
-        .push(&Keys::VALUES.content.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / (id.to_string())
+            / [Keys::VALUES.content]
+    )
 }
 
 /// Get key of proposal author
 pub fn get_author_key(id: u64) -> Key {
-    proposal_prefix()
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
-        .push(&Keys::VALUES.author.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / (id.to_string())
+            / [Keys::VALUES.author]
+    )
 }
 
 /// Get key of a proposal type
 pub fn get_proposal_type_key(id: u64) -> Key {
-    proposal_prefix()
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
-        .push(&Keys::VALUES.proposal_type.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / (id.to_string())
+            / [Keys::VALUES.proposal_type]
+    )
 }
 
 /// Get key of proposal voting start epoch
 pub fn get_voting_start_epoch_key(id: u64) -> Key {
-    proposal_prefix()
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
-        .push(&Keys::VALUES.start_epoch.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / (id.to_string())
+            / [Keys::VALUES.start_epoch]
+    )
 }
 
 /// Get key of proposal voting end epoch
 pub fn get_voting_end_epoch_key(id: u64) -> Key {
-    proposal_prefix()
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
-        .push(&Keys::VALUES.end_epoch.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / (id.to_string())
+            / [Keys::VALUES.end_epoch]
+    )
 }
 
 /// Get key of proposal funds
 pub fn get_funds_key(id: u64) -> Key {
-    proposal_prefix()
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
-        .push(&Keys::VALUES.funds.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / (id.to_string())
+            / [Keys::VALUES.funds]
+    )
 }
 
 /// Get proposal grace epoch key
 pub fn get_grace_epoch_key(id: u64) -> Key {
-    proposal_prefix()
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
-        .push(&Keys::VALUES.grace_epoch.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / (id.to_string())
+            / [Keys::VALUES.grace_epoch]
+    )
 }
 
 /// Get the proposal committing key prefix
 pub fn get_commiting_proposals_prefix(epoch: u64) -> Key {
-    proposal_prefix()
-        .push(&Keys::VALUES.committing_epoch.to_owned())
-        .expect("Cannot obtain a storage key")
-        .push(&epoch.to_string())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / [Keys::VALUES.committing_epoch]
+            / (epoch.to_string())
+    )
 }
 
 /// Get proposal code key
 pub fn get_proposal_code_key(id: u64) -> Key {
-    proposal_prefix()
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
-        .push(&Keys::VALUES.proposal_code.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / (id.to_string())
+            / [Keys::VALUES.proposal_code]
+    )
 }
 
 /// Get the committing proposal key
@@ -431,11 +425,12 @@ pub fn get_committing_proposals_key(id: u64, epoch: u64) -> Key {
 
 /// Get proposal vote prefix key
 pub fn get_proposal_vote_prefix_key(id: u64) -> Key {
-    proposal_prefix()
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
-        .push(&Keys::VALUES.vote.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / (id.to_string())
+            / [Keys::VALUES.vote]
+    )
 }
 
 /// Get the vote key for a specific proposal id
@@ -453,28 +448,25 @@ pub fn get_vote_proposal_key(
 
 /// Get the proposal execution key
 pub fn get_proposal_execution_key(id: u64) -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&Keys::VALUES.pending.to_owned())
-        .expect("Cannot obtain a storage key")
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [Keys::VALUES.pending] / (id.to_string()))
 }
 
 /// Get the proposal result key
 pub fn get_proposal_result_key(id: u64) -> Key {
-    proposal_prefix()
-        .push(&id.to_string())
-        .expect("Cannot obtain a storage key")
-        .push(&Keys::VALUES.result.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [Keys::VALUES.proposal]
+            / (id.to_string())
+            / [Keys::VALUES.result]
+    )
 }
 
 /// Get proposal id from key
 pub fn get_proposal_id(key: &Key) -> Option<u64> {
     match key.get_at(2) {
         Some(id) => match id {
-            DbKeySeg::AddressSeg(_) => None,
             DbKeySeg::StringSeg(res) => res.parse::<u64>().ok(),
+            _ => None,
         },
         None => None,
     }
@@ -484,8 +476,8 @@ pub fn get_proposal_id(key: &Key) -> Option<u64> {
 pub fn get_commit_proposal_epoch(key: &Key) -> Option<u64> {
     match key.get_at(3) {
         Some(id) => match id {
-            DbKeySeg::AddressSeg(_) => None,
             DbKeySeg::StringSeg(res) => res.parse::<u64>().ok(),
+            _ => None,
         },
         None => None,
     }
@@ -495,8 +487,8 @@ pub fn get_commit_proposal_epoch(key: &Key) -> Option<u64> {
 pub fn get_commit_proposal_id(key: &Key) -> Option<u64> {
     match key.get_at(4) {
         Some(id) => match id {
-            DbKeySeg::AddressSeg(_) => None,
             DbKeySeg::StringSeg(res) => res.parse::<u64>().ok(),
+            _ => None,
         },
         None => None,
     }
@@ -506,7 +498,7 @@ pub fn get_vote_delegation_address(key: &Key) -> Option<&Address> {
     match key.get_at(4) {
         Some(addr) => match addr {
             DbKeySeg::AddressSeg(res) => Some(res),
-            DbKeySeg::StringSeg(_) => None,
+            _ => None,
         },
         None => None,
     }
@@ -517,7 +509,7 @@ pub fn get_voter_address(key: &Key) -> Option<&Address> {
     match key.get_at(5) {
         Some(addr) => match addr {
             DbKeySeg::AddressSeg(res) => Some(res),
-            DbKeySeg::StringSeg(_) => None,
+            _ => None,
         },
         None => None,
     }
@@ -13,7 +13,7 @@ use thiserror::Error;
 use super::vote::ProposalVote;
 use crate::cli::onchain::{
     DefaultProposal, PgfAction, PgfContinuous, PgfFundingProposal, PgfRetro,
-    PgfSteward, PgfStewardProposal, StewardsUpdate,
+    PgfSteward, PgfStewardProposal, SlashPoolProposal, StewardsUpdate,
 };
 use crate::utils::{ProposalStatus, TallyType};
 
@@ -143,7 +143,16 @@ impl TryFrom<PgfFundingProposal> for InitProposalData {
             .map(PGFAction::Retro)
             .collect::<BTreeSet<PGFAction>>();
 
+        let retro_claims = value
+            .data
+            .retro_claims
+            .iter()
+            .cloned()
+            .map(PGFAction::RetroClaim)
+            .collect::<BTreeSet<PGFAction>>();
+
         continous_fundings.extend(retro_fundings);
+        continous_fundings.extend(retro_claims);
 
         Ok(InitProposalData {
             id: value.proposal.id,
@@ -157,6 +166,24 @@ impl TryFrom<PgfFundingProposal> for InitProposalData {
     }
 }
 
+impl TryFrom<SlashPoolProposal> for InitProposalData {
+    type Error = ProposalError;
+
+    fn try_from(value: SlashPoolProposal) -> Result<Self, Self::Error> {
+        Ok(InitProposalData {
+            id: value.proposal.id,
+            content: Hash::default(),
+            author: value.proposal.author,
+            r#type: ProposalType::SlashPoolDisbursement(
+                value.data.into_iter().collect(),
+            ),
+            voting_start_epoch: value.proposal.voting_start_epoch,
+            voting_end_epoch: value.proposal.voting_end_epoch,
+            grace_epoch: value.proposal.grace_epoch,
+        })
+    }
+}
+
 /// Storage struture for pgf fundings
 #[derive(
     Debug,
@@ -183,6 +210,93 @@ impl StoragePgfFunding {
     }
 }
 
+/// Storage struture for the pgf retro fundings that are claimed by their
+/// target
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct StoragePgfClaim {
+    /// The id of the proposal that approved this funding
+    pub id: u64,
+    /// The target address
+    pub target: Address,
+    /// The amount of token that is left to be claimed
+    pub remaining: Amount,
+    /// The maximum amount of token that can be claimed in an epoch
+    pub limit_per_epoch: Amount,
+    /// The first epoch in which the unclaimed amount is expired
+    pub expiration: Epoch,
+    /// The last epoch in which the target claimed some tokens
+    pub last_claim_epoch: Option<Epoch>,
+    /// The amount of token claimed in the last claim epoch
+    pub claimed_in_last_epoch: Amount,
+}
+
+impl StoragePgfClaim {
+    /// Init a new pgf claim struct for a proposal executed in the given
+    /// epoch
+    pub fn new(claim: PGFRetroClaim, id: u64, epoch: Epoch) -> Self {
+        Self {
+            id,
+            target: claim.target,
+            remaining: claim.amount,
+            limit_per_epoch: claim.limit_per_epoch,
+            expiration: Epoch(epoch.0.saturating_add(claim.duration)),
+            last_claim_epoch: None,
+            claimed_in_last_epoch: Amount::zero(),
+        }
+    }
+
+    /// Check if the unclaimed amount is expired in the given epoch
+    pub fn is_expired(&self, epoch: Epoch) -> bool {
+        epoch >= self.expiration
+    }
+
+    /// Get the amount of token that can still be claimed in the given epoch
+    pub fn claimable(&self, epoch: Epoch) -> Amount {
+        if self.is_expired(epoch) {
+            return Amount::zero();
+        }
+        let claimed = if self.last_claim_epoch == Some(epoch) {
+            self.claimed_in_last_epoch
+        } else {
+            Amount::zero()
+        };
+        let allowance = self
+            .limit_per_epoch
+            .checked_sub(claimed)
+            .unwrap_or_default();
+        std::cmp::min(self.remaining, allowance)
+    }
+
+    /// Claim the given amount of token in the given epoch. Returns the
+    /// updated claim, or `None` if the amount is zero or exceeds the
+    /// claimable amount.
+    pub fn claim(&self, epoch: Epoch, amount: Amount) -> Option<Self> {
+        if amount.is_zero() || amount > self.claimable(epoch) {
+            return None;
+        }
+        let claimed = if self.last_claim_epoch == Some(epoch) {
+            self.claimed_in_last_epoch
+        } else {
+            Amount::zero()
+        };
+        Some(Self {
+            remaining: self.remaining.checked_sub(amount)?,
+            last_claim_epoch: Some(epoch),
+            claimed_in_last_epoch: claimed.checked_add(amount)?,
+            ..self.clone()
+        })
+    }
+}
+
 /// The type of a Proposal
 #[derive(
     Debug,
@@ -200,6 +314,8 @@ pub enum ProposalType {
     PGFSteward(BTreeSet<AddRemove<Address>>),
     /// PGF funding proposal
     PGFPayment(BTreeSet<PGFAction>),
+    /// Disbursement or burn of the funds held by the PoS slash pool
+    SlashPoolDisbursement(BTreeSet<SlashPoolAction>),
 }
 
 /// An add or remove action for PGF
@@ -281,6 +397,41 @@ pub struct PGFInternalTarget {
     pub amount: Amount,
 }
 
+/// A PGF retro payment that the target claims over time, rather than
+/// receiving it in a single transfer
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Ord,
+    Eq,
+    PartialOrd,
+)]
+pub struct PGFRetroClaim {
+    /// The target address, that has to sign the claims
+    pub target: Address,
+    /// The total amount of token approved for the target address
+    pub amount: Amount,
+    /// The maximum amount of token that can be claimed in an epoch
+    pub limit_per_epoch: Amount,
+    /// The number of epochs after the execution of the proposal in which
+    /// the funding can be claimed, after which the unclaimed amount expires
+    pub duration: u64,
+}
+
+impl PGFRetroClaim {
+    /// Check if the claim has a non-zero amount, rate limit and duration
+    pub fn is_valid(&self) -> bool {
+        !self.amount.is_zero()
+            && !self.limit_per_epoch.is_zero()
+            && self.duration > 0
+    }
+}
+
 /// The target of a PGF payment
 #[derive(
     Debug, Clone, PartialEq, Serialize, Deserialize, Ord, Eq, PartialOrd,
@@ -378,6 +529,38 @@ pub enum PGFAction {
     Continuous(AddRemove<PGFTarget>),
     /// A retro payment
     Retro(PGFTarget),
+    /// A retro payment claimed by the target over time
+    RetroClaim(PGFRetroClaim),
+}
+
+/// The actions that can be executed on the funds held by the slash pool
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Eq,
+    Ord,
+    PartialOrd,
+)]
+pub enum SlashPoolAction {
+    /// Transfer slashed tokens to the target address
+    Disburse(PGFInternalTarget),
+    /// Burn the given amount of slashed tokens
+    Burn(Amount),
+}
+
+impl SlashPoolAction {
+    /// Returns the amount of tokens moved out of the slash pool
+    pub fn amount(&self) -> Amount {
+        match self {
+            SlashPoolAction::Disburse(target) => target.amount,
+            SlashPoolAction::Burn(amount) => *amount,
+        }
+    }
 }
 
 impl ProposalType {
@@ -393,6 +576,9 @@ impl Display for ProposalType {
             ProposalType::Default(_) => write!(f, "Default"),
             ProposalType::PGFSteward(_) => write!(f, "Pgf steward"),
             ProposalType::PGFPayment(_) => write!(f, "Pgf funding"),
+            ProposalType::SlashPoolDisbursement(_) => {
+                write!(f, "Slash pool disbursement")
+            }
         }
     }
 }
@@ -604,11 +790,44 @@ pub mod testing {
         }
     }
 
+    prop_compose! {
+        /// Generate an arbitrary PGF retro claim
+        pub fn arb_pgf_retro_claim()(
+            target in arb_non_internal_address(),
+            amount in arb_amount(),
+            limit_per_epoch in arb_amount(),
+            duration: u64,
+        ) -> PGFRetroClaim {
+            PGFRetroClaim {
+                target,
+                amount,
+                limit_per_epoch,
+                duration,
+            }
+        }
+    }
+
     /// Generate an arbitrary PGF action
     pub fn arb_pgf_action() -> impl Strategy<Value = PGFAction> {
         prop_oneof![
             arb_add_remove(arb_pgf_target()).prop_map(PGFAction::Continuous),
             arb_pgf_target().prop_map(PGFAction::Retro),
+            arb_pgf_retro_claim().prop_map(PGFAction::RetroClaim),
+        ]
+    }
+
+    /// Generate an arbitrary slash pool action
+    pub fn arb_slash_pool_action() -> impl Strategy<Value = SlashPoolAction> {
+        prop_oneof![
+            (arb_non_internal_address(), arb_amount()).prop_map(
+                |(target, amount)| {
+                    SlashPoolAction::Disburse(PGFInternalTarget {
+                        target,
+                        amount,
+                    })
+                }
+            ),
+            arb_amount().prop_map(SlashPoolAction::Burn),
         ]
     }
 
@@ -623,6 +842,8 @@ pub mod testing {
             .prop_map(ProposalType::PGFSteward),
             collection::btree_set(arb_pgf_action(), 0..10)
                 .prop_map(ProposalType::PGFPayment),
+            collection::btree_set(arb_slash_pool_action(), 0..10)
+                .prop_map(ProposalType::SlashPoolDisbursement),
         ]
     }
 
@@ -666,3 +887,52 @@ pub mod testing {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::address::testing::established_address_1;
+
+    use super::*;
+
+    /// Test that the claims of a pgf retro funding are rate-limited per
+    /// epoch and expire.
+    #[test]
+    fn test_pgf_claim() {
+        let retro_claim = PGFRetroClaim {
+            target: established_address_1(),
+            amount: Amount::native_whole(25),
+            limit_per_epoch: Amount::native_whole(10),
+            duration: 3,
+        };
+        assert!(retro_claim.is_valid());
+        assert!(!PGFRetroClaim {
+            duration: 0,
+            ..retro_claim.clone()
+        }
+        .is_valid());
+        let claim = StoragePgfClaim::new(retro_claim, 1, Epoch(5));
+        assert_eq!(claim.expiration, Epoch(8));
+
+        // The claims in an epoch are limited
+        let epoch = Epoch(5);
+        assert_eq!(claim.claimable(epoch), Amount::native_whole(10));
+        let claim = claim.claim(epoch, Amount::native_whole(4)).unwrap();
+        assert_eq!(claim.remaining, Amount::native_whole(21));
+        assert_eq!(claim.claimable(epoch), Amount::native_whole(6));
+        assert!(claim.claim(epoch, Amount::native_whole(7)).is_none());
+        assert!(claim.claim(epoch, Amount::zero()).is_none());
+        let claim = claim.claim(epoch, Amount::native_whole(6)).unwrap();
+        assert_eq!(claim.claimable(epoch), Amount::zero());
+
+        // The limit is reset in the next epoch, up to the remaining amount
+        let epoch = Epoch(6);
+        let claim = claim.claim(epoch, Amount::native_whole(10)).unwrap();
+        let epoch = Epoch(7);
+        assert_eq!(claim.claimable(epoch), Amount::native_whole(5));
+
+        // The unclaimed amount expires
+        assert!(claim.is_expired(Epoch(8)));
+        assert_eq!(claim.claimable(Epoch(8)), Amount::zero());
+        assert!(claim.claim(Epoch(8), Amount::native_whole(1)).is_none());
+    }
+}
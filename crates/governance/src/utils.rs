@@ -5,7 +5,7 @@ use namada_core::borsh::{BorshDeserialize, BorshSerialize};
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
 use namada_core::types::storage::Epoch;
-use namada_core::types::token;
+use namada_core::types::token::{self, AmountOverflowError, TrySum};
 
 use super::cli::offline::OfflineVote;
 use super::storage::proposal::ProposalType;
@@ -85,6 +85,7 @@ impl TallyType {
             (ProposalType::PGFPayment(_), false) => {
                 TallyType::OneHalfOverOneThird
             }
+            (ProposalType::SlashPoolDisbursement(_), _) => TallyType::TwoThirds,
         }
     }
 }
@@ -349,26 +350,54 @@ impl ProposalVotes {
     }
 }
 
-/// Compute the result of a proposal
+/// The voting power of one side of a tally
+#[derive(Default)]
+struct TallySide {
+    /// The voting power of the votes on this side
+    votes: Vec<VotePower>,
+    /// The voting power of the delegations to the validators on this side,
+    /// whose delegators voted on another side
+    overridden: Vec<VotePower>,
+}
+
+impl TallySide {
+    /// The side of a vote, as an index of the yay, nay and abstain sides
+    fn index(vote: &TallyVote) -> Option<usize> {
+        if vote.is_yay() {
+            Some(0)
+        } else if vote.is_nay() {
+            Some(1)
+        } else if vote.is_abstain() {
+            Some(2)
+        } else {
+            None
+        }
+    }
+
+    fn voting_power(self) -> Result<VotePower, AmountOverflowError> {
+        let votes = self.votes.into_iter().try_sum()?;
+        let overridden = self.overridden.into_iter().try_sum()?;
+        votes.checked_sub(overridden).ok_or(AmountOverflowError)
+    }
+}
+
+/// Compute the result of a proposal. The voting powers are summed without
+/// overflowing, such that any set of votes either yields a result or an
+/// error.
 pub fn compute_proposal_result(
     votes: ProposalVotes,
     total_voting_power: VotePower,
     tally_type: TallyType,
-) -> ProposalResult {
-    let mut yay_voting_power = VotePower::default();
-    let mut nay_voting_power = VotePower::default();
-    let mut abstain_voting_power = VotePower::default();
+) -> Result<ProposalResult, AmountOverflowError> {
+    let mut sides: [TallySide; 3] = Default::default();
 
     for (address, vote_power) in votes.validator_voting_power {
-        let vote_type = votes.validators_vote.get(&address);
-        if let Some(vote) = vote_type {
-            if vote.is_yay() {
-                yay_voting_power += vote_power;
-            } else if vote.is_nay() {
-                nay_voting_power += vote_power;
-            } else if vote.is_abstain() {
-                abstain_voting_power += vote_power;
-            }
+        let side = votes
+            .validators_vote
+            .get(&address)
+            .and_then(TallySide::index);
+        if let Some(side) = side {
+            sides[side].votes.push(vote_power);
         }
     }
 
@@ -377,46 +406,41 @@ pub fn compute_proposal_result(
             Some(vote) => vote,
             None => continue,
         };
+        let Some(delegator_side) = TallySide::index(delegator_vote) else {
+            continue;
+        };
         for (validator, voting_power) in delegations {
-            let validator_vote = votes.validators_vote.get(&validator);
-            if let Some(validator_vote) = validator_vote {
-                let validator_vote_is_same_side =
-                    validator_vote.is_same_side(delegator_vote);
-
-                if !validator_vote_is_same_side {
-                    if delegator_vote.is_yay() {
-                        yay_voting_power += voting_power;
-                        if validator_vote.is_nay() {
-                            nay_voting_power -= voting_power;
-                        } else if validator_vote.is_abstain() {
-                            abstain_voting_power -= voting_power;
-                        }
-                    } else if delegator_vote.is_nay() {
-                        nay_voting_power += voting_power;
-                        if validator_vote.is_yay() {
-                            yay_voting_power -= voting_power;
-                        } else if validator_vote.is_abstain() {
-                            abstain_voting_power -= voting_power;
-                        }
-                    } else if delegator_vote.is_abstain() {
-                        abstain_voting_power += voting_power;
-                        if validator_vote.is_yay() {
-                            yay_voting_power -= voting_power;
-                        } else if validator_vote.is_nay() {
-                            nay_voting_power -= voting_power;
+            match votes.validators_vote.get(&validator) {
+                Some(validator_vote) => {
+                    if validator_vote.is_same_side(delegator_vote) {
+                        continue;
+                    }
+                    sides[delegator_side].votes.push(voting_power);
+                    // The delegation is removed from the side of its
+                    // validator
+                    match TallySide::index(validator_vote) {
+                        Some(validator_side)
+                            if validator_side != delegator_side =>
+                        {
+                            sides[validator_side].overridden.push(voting_power);
                         }
+                        _ => {}
                     }
                 }
-            } else if delegator_vote.is_yay() {
-                yay_voting_power += voting_power;
-            } else if delegator_vote.is_nay() {
-                nay_voting_power += voting_power;
-            } else if delegator_vote.is_abstain() {
-                abstain_voting_power += voting_power;
+                None => sides[delegator_side].votes.push(voting_power),
             }
         }
     }
 
+    let [yay, nay, abstain] = sides;
+    let yay_voting_power = yay.voting_power()?;
+    let nay_voting_power = nay.voting_power()?;
+    let abstain_voting_power = abstain.voting_power()?;
+    // The sums of the tally result must not overflow either
+    [yay_voting_power, nay_voting_power, abstain_voting_power]
+        .into_iter()
+        .try_sum()?;
+
     let tally_result = TallyResult::new(
         &tally_type,
         yay_voting_power,
@@ -425,14 +449,14 @@ pub fn compute_proposal_result(
         total_voting_power,
     );
 
-    ProposalResult {
+    Ok(ProposalResult {
         result: tally_result,
         tally_type,
         total_voting_power,
         total_yay_power: yay_voting_power,
         total_nay_power: nay_voting_power,
         total_abstain_power: abstain_voting_power,
-    }
+    })
 }
 
 /// Calculate the valid voting window for validator given a proposal epoch
@@ -442,12 +466,13 @@ pub fn is_valid_validator_voting_period(
     voting_start_epoch: Epoch,
     voting_end_epoch: Epoch,
 ) -> bool {
-    if voting_start_epoch >= voting_end_epoch {
-        false
-    } else {
-        let duration = voting_end_epoch - voting_start_epoch;
-        let two_third_duration = (duration / 3) * 2;
-        current_epoch <= voting_start_epoch + two_third_duration
+    match voting_end_epoch.checked_offset_from(voting_start_epoch) {
+        Some(duration) if duration.0 > 0 => {
+            let two_third_duration = (duration.0 / 3) * 2;
+            current_epoch
+                <= voting_start_epoch.saturating_add(two_third_duration)
+        }
+        _ => false,
     }
 }
 
@@ -472,7 +497,8 @@ mod test {
                 proposal_votes.clone(),
                 token::Amount::from_u64(1),
                 tally_type,
-            );
+            )
+            .unwrap();
             let _result = if matches!(
                 tally_type,
                 TallyType::LessOneHalfOverOneThirdNay
@@ -509,7 +535,8 @@ mod test {
                 proposal_votes.clone(),
                 validator_voting_power,
                 tally_type,
-            );
+            )
+            .unwrap();
             assert!(
                 matches!(proposal_result.result, TallyResult::Passed),
                 "{tally_type:?}"
@@ -557,7 +584,8 @@ mod test {
                 proposal_votes.clone(),
                 validator_voting_power,
                 tally_type,
-            );
+            )
+            .unwrap();
             assert!(
                 matches!(proposal_result.result, TallyResult::Passed),
                 "{tally_type:?}"
@@ -605,7 +633,8 @@ mod test {
                 proposal_votes.clone(),
                 validator_voting_power,
                 tally_type,
-            );
+            )
+            .unwrap();
             assert!(
                 matches!(proposal_result.result, TallyResult::Rejected),
                 "{tally_type:?}"
@@ -665,7 +694,8 @@ mod test {
                 proposal_votes.clone(),
                 validator_voting_power,
                 tally_type,
-            );
+            )
+            .unwrap();
             assert!(
                 matches!(proposal_result.result, TallyResult::Rejected),
                 "{tally_type:?}"
@@ -732,7 +762,8 @@ mod test {
                 proposal_votes.clone(),
                 validator_voting_power,
                 tally_type,
-            );
+            )
+            .unwrap();
             assert!(
                 matches!(proposal_result.result, TallyResult::Passed),
                 "{tally_type:?}"
@@ -790,7 +821,8 @@ mod test {
                 proposal_votes.clone(),
                 validator_voting_power,
                 tally_type,
-            );
+            )
+            .unwrap();
             assert!(
                 matches!(proposal_result.result, TallyResult::Passed),
                 "{tally_type:?}"
@@ -846,7 +878,8 @@ mod test {
                 proposal_votes.clone(),
                 validator_voting_power.add(validator_voting_power_two),
                 tally_type,
-            );
+            )
+            .unwrap();
             let _result = if matches!(
                 tally_type,
                 TallyType::LessOneHalfOverOneThirdNay
@@ -918,7 +951,8 @@ mod test {
                 proposal_votes.clone(),
                 validator_voting_power.add(validator_voting_power_two),
                 tally_type,
-            );
+            )
+            .unwrap();
             let _result =
                 if matches!(tally_type, TallyType::OneHalfOverOneThird) {
                     TallyResult::Passed
@@ -985,7 +1019,8 @@ mod test {
             proposal_votes.clone(),
             validator_voting_power.add(validator_voting_power_two),
             TallyType::TwoThirds,
-        );
+        )
+        .unwrap();
 
         assert!(matches!(proposal_result.result, TallyResult::Passed));
         assert_eq!(
@@ -1053,7 +1088,8 @@ mod test {
             proposal_votes.clone(),
             validator_voting_power.add(validator_voting_power_two),
             TallyType::TwoThirds,
-        );
+        )
+        .unwrap();
 
         assert!(matches!(proposal_result.result, TallyResult::Rejected));
         assert_eq!(
@@ -1108,7 +1144,8 @@ mod test {
             proposal_votes.clone(),
             delegator_voting_power_two.add(delegator_voting_power),
             TallyType::TwoThirds,
-        );
+        )
+        .unwrap();
 
         assert!(matches!(proposal_result.result, TallyResult::Rejected));
         assert_eq!(
@@ -1160,7 +1197,8 @@ mod test {
             proposal_votes.clone(),
             token::Amount::from(200),
             TallyType::TwoThirds,
-        );
+        )
+        .unwrap();
 
         assert!(matches!(proposal_result.result, TallyResult::Passed));
         assert_eq!(
@@ -1214,7 +1252,8 @@ mod test {
             proposal_votes.clone(),
             token::Amount::from(403),
             TallyType::OneHalfOverOneThird,
-        );
+        )
+        .unwrap();
 
         assert!(matches!(proposal_result.result, TallyResult::Rejected));
         assert_eq!(
@@ -1268,7 +1307,8 @@ mod test {
             proposal_votes.clone(),
             token::Amount::from(402),
             TallyType::OneHalfOverOneThird,
-        );
+        )
+        .unwrap();
 
         assert!(matches!(proposal_result.result, TallyResult::Passed));
         assert_eq!(
@@ -1322,7 +1362,8 @@ mod test {
             proposal_votes.clone(),
             token::Amount::from(100),
             TallyType::LessOneHalfOverOneThirdNay,
-        );
+        )
+        .unwrap();
 
         assert!(matches!(proposal_result.result, TallyResult::Rejected));
         assert_eq!(
@@ -1378,7 +1419,8 @@ mod test {
             proposal_votes.clone(),
             token::Amount::from(271),
             TallyType::LessOneHalfOverOneThirdNay,
-        );
+        )
+        .unwrap();
 
         assert!(matches!(proposal_result.result, TallyResult::Passed));
         assert_eq!(
@@ -1404,4 +1446,41 @@ mod test {
 
         assert!(!proposal_result.two_thirds_nay_over_two_thirds_total())
     }
+
+    #[test]
+    fn test_proposal_result_overflow() {
+        let mut proposal_votes = ProposalVotes::default();
+        proposal_votes.add_validator(
+            &address::testing::established_address_1(),
+            token::Amount::max(),
+            ProposalVote::Yay.into(),
+        );
+        proposal_votes.add_validator(
+            &address::testing::established_address_2(),
+            token::Amount::from_u64(1),
+            ProposalVote::Nay.into(),
+        );
+        // The votes don't overflow on their own side, but their total does
+        assert_eq!(
+            compute_proposal_result(
+                proposal_votes.clone(),
+                token::Amount::max(),
+                TallyType::TwoThirds,
+            )
+            .err(),
+            Some(AmountOverflowError)
+        );
+
+        proposal_votes.add_validator(
+            &address::testing::established_address_3(),
+            token::Amount::from_u64(1),
+            ProposalVote::Yay.into(),
+        );
+        assert!(compute_proposal_result(
+            proposal_votes,
+            token::Amount::max(),
+            TallyType::TwoThirds,
+        )
+        .is_err());
+    }
 }
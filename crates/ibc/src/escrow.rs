@@ -0,0 +1,316 @@
+//! Accounting of the IBC escrow to reconcile it with the transfers.
+//!
+//! All the tokens sent through IBC are escrowed in the single IBC account, no
+//! matter which channel they have been sent through, and the vouchers of the
+//! received tokens are minted without any trace of the amount received per
+//! channel. To be able to detect the escrow accounting bugs or the stuck
+//! refunds, the changes of the escrow balance and of the minted supply of the
+//! transferred token are accumulated per channel and token in the storage by
+//! each transfer, refund and receipt.
+//!
+//! The accounting only covers the transfers executed since it was introduced,
+//! so the tokens that were already escrowed or minted before show up as
+//! discrepancies.
+
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::ibc::apps::transfer::types::packet::PacketData;
+use namada_core::ibc::apps::transfer::types::{
+    is_receiver_chain_source, PrefixedDenom, TracePrefix,
+};
+use namada_core::ibc::core::channel::types::msgs::PacketMsg;
+use namada_core::ibc::core::channel::types::packet::Packet;
+use namada_core::ibc::core::handler::types::msgs::MsgEnvelope;
+use namada_core::ibc::core::host::types::identifiers::{ChannelId, PortId};
+use namada_core::types::address::{Address, InternalAddress};
+use namada_core::types::token::{Amount, Change};
+use namada_storage::{StorageRead, StorageWrite};
+use namada_trans_token::{read_balance, read_total_supply};
+
+use crate::{storage, Error, IbcMessage};
+
+/// The accumulated changes of the escrow balance and of the minted supply of
+/// a token transferred through a channel
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct EscrowAccounting {
+    /// The net amount escrowed by the transfers through the channel, minus
+    /// the refunds and the receipts of the tokens sent back
+    pub escrowed: Change,
+    /// The net amount of vouchers minted by the receipts through the channel,
+    /// minus the burns by the transfers of the vouchers sent back
+    pub minted: Change,
+}
+
+/// The escrow accounting of a channel
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct ChannelAccounting {
+    /// The port ID on Namada
+    pub port_id: String,
+    /// The channel ID on Namada
+    pub channel_id: String,
+    /// The accumulated changes through the channel
+    pub accounting: EscrowAccounting,
+}
+
+/// The reconciliation of the escrow accounting of a token with its escrow
+/// balance and its voucher supply
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct TokenReconciliation {
+    /// The token
+    pub token: Address,
+    /// The accounting of each channel the token has been transferred through
+    pub channels: Vec<ChannelAccounting>,
+    /// The balance of the token in the escrow account
+    pub escrow_balance: Amount,
+    /// The total supply of the token, only when it is an IBC voucher. The
+    /// supply of the other tokens isn't minted by IBC.
+    pub voucher_supply: Option<Amount>,
+}
+
+impl TokenReconciliation {
+    /// The net amount escrowed through all the channels
+    pub fn total_escrowed(&self) -> Change {
+        self.channels.iter().map(|c| c.accounting.escrowed).sum()
+    }
+
+    /// The net amount of vouchers minted through all the channels
+    pub fn total_minted(&self) -> Change {
+        self.channels.iter().map(|c| c.accounting.minted).sum()
+    }
+
+    /// The escrow balance which isn't accounted for by the transfers. It's
+    /// negative when more tokens have been accounted than escrowed.
+    pub fn escrow_discrepancy(&self) -> Change {
+        self.escrow_balance.change() - self.total_escrowed()
+    }
+
+    /// The voucher supply which isn't accounted for by the transfers, if the
+    /// token is an IBC voucher
+    pub fn supply_discrepancy(&self) -> Option<Change> {
+        self.voucher_supply
+            .map(|supply| supply.change() - self.total_minted())
+    }
+
+    /// The channels whose net escrowed amount or net minted amount is
+    /// negative, i.e. more tokens have been released than received through
+    /// the channel
+    pub fn overdrawn_channels(
+        &self,
+    ) -> impl Iterator<Item = &ChannelAccounting> {
+        self.channels.iter().filter(|c| {
+            c.accounting.escrowed.is_negative()
+                || c.accounting.minted.is_negative()
+        })
+    }
+
+    /// Check if the escrow balance and the voucher supply match the
+    /// accounting of the channels
+    pub fn is_reconciled(&self) -> bool {
+        self.escrow_discrepancy().is_zero()
+            && self.supply_discrepancy().map_or(true, |d| d.is_zero())
+            && self.overdrawn_channels().next().is_none()
+    }
+}
+
+/// Reconcile the escrow accounting of all the transferred tokens with their
+/// escrow balances and their voucher supplies
+pub fn reconcile_escrow<S>(
+    storage: &S,
+) -> namada_storage::Result<Vec<TokenReconciliation>>
+where
+    S: StorageRead,
+{
+    let mut channels: BTreeMap<Address, Vec<ChannelAccounting>> =
+        BTreeMap::new();
+    let prefix = storage::escrow_accounting_prefix();
+    for result in
+        namada_storage::iter_prefix::<EscrowAccounting>(storage, &prefix)?
+    {
+        let (key, accounting) = result?;
+        if let Some((port_id, channel_id, token)) =
+            storage::is_escrow_accounting_key(&key)
+        {
+            channels.entry(token).or_default().push(ChannelAccounting {
+                port_id: port_id.to_string(),
+                channel_id: channel_id.to_string(),
+                accounting,
+            });
+        }
+    }
+
+    let escrow = Address::Internal(InternalAddress::Ibc);
+    channels
+        .into_iter()
+        .map(|(token, channels)| {
+            let escrow_balance = read_balance(storage, &token, &escrow)?;
+            let voucher_supply = match token {
+                Address::Internal(InternalAddress::IbcToken(_)) => {
+                    Some(read_total_supply(storage, &token)?)
+                }
+                _ => None,
+            };
+            Ok(TokenReconciliation {
+                token,
+                channels,
+                escrow_balance,
+                voucher_supply,
+            })
+        })
+        .collect()
+}
+
+/// The escrow balance and the supply of the token transferred by an IBC
+/// message before its execution
+#[derive(Debug)]
+pub(crate) struct EscrowSnapshot {
+    port_id: PortId,
+    channel_id: ChannelId,
+    token: Address,
+    escrow_balance: Amount,
+    supply: Amount,
+}
+
+impl EscrowSnapshot {
+    /// Take the snapshot of the token transferred by the given message.
+    /// Returns `None` if the message doesn't transfer any token.
+    pub fn take<S>(
+        storage: &S,
+        message: &IbcMessage,
+    ) -> Result<Option<Self>, Error>
+    where
+        S: StorageRead,
+    {
+        let (port_id, channel_id, token) = match transferred_token(message) {
+            Some(transferred) => transferred,
+            None => return Ok(None),
+        };
+        let escrow = Address::Internal(InternalAddress::Ibc);
+        let escrow_balance = read_balance(storage, &token, &escrow)
+            .map_err(|e| Error::EscrowAccounting(e.to_string()))?;
+        let supply = read_total_supply(storage, &token)
+            .map_err(|e| Error::EscrowAccounting(e.to_string()))?;
+        Ok(Some(Self {
+            port_id,
+            channel_id,
+            token,
+            escrow_balance,
+            supply,
+        }))
+    }
+
+    /// Accumulate the changes of the escrow balance and of the supply since
+    /// the snapshot into the accounting of the channel
+    pub fn record<S>(self, storage: &mut S) -> Result<(), Error>
+    where
+        S: StorageRead + StorageWrite,
+    {
+        let escrow = Address::Internal(InternalAddress::Ibc);
+        let escrow_balance = read_balance(storage, &self.token, &escrow)
+            .map_err(|e| Error::EscrowAccounting(e.to_string()))?;
+        let supply = read_total_supply(storage, &self.token)
+            .map_err(|e| Error::EscrowAccounting(e.to_string()))?;
+        let escrowed = escrow_balance.change() - self.escrow_balance.change();
+        let minted = supply.change() - self.supply.change();
+        if escrowed.is_zero() && minted.is_zero() {
+            return Ok(());
+        }
+
+        let key = storage::escrow_accounting_key(
+            &self.port_id,
+            &self.channel_id,
+            &self.token,
+        );
+        let mut accounting: EscrowAccounting = storage
+            .read(&key)
+            .map_err(|e| Error::EscrowAccounting(e.to_string()))?
+            .unwrap_or_default();
+        accounting.escrowed += escrowed;
+        accounting.minted += minted;
+        storage
+            .write(&key, accounting)
+            .map_err(|e| Error::EscrowAccounting(e.to_string()))
+    }
+}
+
+/// Get the port ID and the channel ID on Namada and the token of the transfer
+/// by the given message
+fn transferred_token(
+    message: &IbcMessage,
+) -> Option<(PortId, ChannelId, Address)> {
+    match message {
+        IbcMessage::Transfer(msg) => Some((
+            msg.port_id_on_a.clone(),
+            msg.chan_id_on_a.clone(),
+            token_address(&msg.packet_data.token.denom),
+        )),
+        IbcMessage::ShieldedTransfer(msg) => Some((
+            msg.message.port_id_on_a.clone(),
+            msg.message.chan_id_on_a.clone(),
+            token_address(&msg.message.packet_data.token.denom),
+        )),
+        IbcMessage::Envelope(MsgEnvelope::Packet(PacketMsg::Recv(msg))) => {
+            let packet = &msg.packet;
+            let mut denom = packet_data(packet)?.token.denom;
+            if is_receiver_chain_source(
+                packet.port_id_on_a.clone(),
+                packet.chan_id_on_a.clone(),
+                &denom,
+            ) {
+                denom.remove_trace_prefix(&TracePrefix::new(
+                    packet.port_id_on_a.clone(),
+                    packet.chan_id_on_a.clone(),
+                ));
+            } else {
+                denom.add_trace_prefix(TracePrefix::new(
+                    packet.port_id_on_b.clone(),
+                    packet.chan_id_on_b.clone(),
+                ));
+            }
+            Some((
+                packet.port_id_on_b.clone(),
+                packet.chan_id_on_b.clone(),
+                token_address(&denom),
+            ))
+        }
+        // The refunds are made on the sender chain
+        IbcMessage::Envelope(MsgEnvelope::Packet(PacketMsg::Ack(msg))) => {
+            sent_token(&msg.packet)
+        }
+        IbcMessage::Envelope(MsgEnvelope::Packet(PacketMsg::Timeout(msg))) => {
+            sent_token(&msg.packet)
+        }
+        IbcMessage::Envelope(MsgEnvelope::Packet(
+            PacketMsg::TimeoutOnClose(msg),
+        )) => sent_token(&msg.packet),
+        IbcMessage::Envelope(_) => None,
+    }
+}
+
+/// Get the port ID and the channel ID on Namada and the token of the packet
+/// sent from Namada
+fn sent_token(packet: &Packet) -> Option<(PortId, ChannelId, Address)> {
+    let data = packet_data(packet)?;
+    Some((
+        packet.port_id_on_a.clone(),
+        packet.chan_id_on_a.clone(),
+        token_address(&data.token.denom),
+    ))
+}
+
+/// Decode the token transfer data of the packet. Returns `None` for the
+/// packets of the other applications.
+fn packet_data(packet: &Packet) -> Option<PacketData> {
+    serde_json::from_slice::<PacketData>(&packet.data).ok()
+}
+
+/// Get the token address of the denom on Namada, in the same way as the token
+/// transfer context
+fn token_address(denom: &PrefixedDenom) -> Address {
+    match Address::decode(denom.base_denom.as_str()) {
+        Ok(token) if denom.trace_path.is_empty() => token,
+        _ => storage::ibc_token(denom.to_string()),
+    }
+}
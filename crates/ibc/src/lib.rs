@@ -2,6 +2,7 @@
 
 mod actions;
 pub mod context;
+pub mod escrow;
 pub mod storage;
 
 use std::cell::RefCell;
@@ -18,6 +19,7 @@ pub use context::token_transfer::TokenTransferContext;
 pub use context::transfer_mod::{ModuleWrapper, TransferModule};
 use context::IbcContext;
 pub use context::ValidationParams;
+use escrow::EscrowSnapshot;
 use namada_core::ibc::apps::transfer::handler::{
     send_transfer_execute, send_transfer_validate,
 };
@@ -62,6 +64,8 @@ pub enum Error {
     ChainId(IdentifierError),
     #[error("Handling MASP transaction error: {0}")]
     MaspTx(String),
+    #[error("IBC escrow accounting error: {0}")]
+    EscrowAccounting(String),
 }
 
 /// IBC actions to handle IBC operations
@@ -103,6 +107,8 @@ where
     /// Execute according to the message in an IBC transaction or VP
     pub fn execute(&mut self, tx_data: &[u8]) -> Result<(), Error> {
         let message = decode_message(tx_data)?;
+        let escrow_snapshot =
+            EscrowSnapshot::take(&*self.ctx.inner.borrow(), &message)?;
         match &message {
             IbcMessage::Transfer(msg) => {
                 let mut token_transfer_ctx =
@@ -112,7 +118,7 @@ where
                     &mut token_transfer_ctx,
                     msg.clone(),
                 )
-                .map_err(Error::TokenTransfer)
+                .map_err(Error::TokenTransfer)?
             }
             IbcMessage::ShieldedTransfer(msg) => {
                 let mut token_transfer_ctx =
@@ -123,7 +129,7 @@ where
                     msg.message.clone(),
                 )
                 .map_err(Error::TokenTransfer)?;
-                self.handle_masp_tx(message)
+                self.handle_masp_tx(message)?
             }
             IbcMessage::Envelope(envelope) => {
                 execute(&mut self.ctx, &mut self.router, envelope.clone())
@@ -132,8 +138,15 @@ where
                 // token hash when transfer with MsgRecvPacket
                 self.store_denom(envelope)?;
                 // For receiving the token to a shielded address
-                self.handle_masp_tx(message)
+                self.handle_masp_tx(message)?
+            }
+        }
+        // Account the escrowed and minted amounts per channel
+        match escrow_snapshot {
+            Some(snapshot) => {
+                snapshot.record(&mut *self.ctx.inner.borrow_mut())
             }
+            None => Ok(()),
         }
     }
 
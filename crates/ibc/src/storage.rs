@@ -24,6 +24,7 @@ const CONNECTIONS_COUNTER_PREFIX: &str = "connections";
 const CHANNELS_COUNTER_PREFIX: &str = "channelEnds";
 const COUNTER_SEG: &str = "counter";
 const DENOM: &str = "ibc_denom";
+const ESCROW_ACCOUNTING: &str = "escrow_accounting";
 
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
@@ -397,6 +398,52 @@ pub fn ibc_denom_key(
         .expect("Cannot obtain a storage key")
 }
 
+/// The storage key prefix of the escrow accounting of all the channels
+pub fn escrow_accounting_prefix() -> Key {
+    Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+        .push(&ESCROW_ACCOUNTING.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// The storage key of the escrow accounting of the token transferred through
+/// the given port and channel
+pub fn escrow_accounting_key(
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    token: &Address,
+) -> Key {
+    escrow_accounting_prefix()
+        .push(&port_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&channel_id.to_string().to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(token)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Returns the port ID, the channel ID and the token if the given key is the
+/// escrow accounting key
+pub fn is_escrow_accounting_key(
+    key: &Key,
+) -> Option<(PortId, ChannelId, Address)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(port_id),
+            DbKeySeg::StringSeg(channel_id),
+            DbKeySeg::AddressSeg(token),
+        ] if addr == &Address::Internal(InternalAddress::Ibc)
+            && prefix == ESCROW_ACCOUNTING =>
+        {
+            let port_id = PortId::from_str(port_id).ok()?;
+            let channel_id = ChannelId::from_str(channel_id).ok()?;
+            Some((port_id, channel_id, token.clone()))
+        }
+        _ => None,
+    }
+}
+
 /// Hash the denom
 #[inline]
 pub fn calc_hash(denom: impl AsRef<str>) -> String {
@@ -40,6 +40,7 @@ impl Bond {
             validator,
             amount,
             source,
+            start_epoch: None,
         };
 
         Self(transaction::build_tx(
@@ -96,6 +97,7 @@ impl Unbond {
             validator,
             amount,
             source,
+            start_epoch: None,
         };
 
         Self(transaction::build_tx(
@@ -464,6 +466,7 @@ impl ChangeMetaData {
         website: Option<String>,
         discord_handle: Option<String>,
         avatar: Option<String>,
+        handle: Option<String>,
         commission_rate: Option<Dec>,
         args: GlobalArgs,
     ) -> Self {
@@ -474,6 +477,7 @@ impl ChangeMetaData {
             website,
             discord_handle,
             avatar,
+            handle,
             commission_rate,
         };
 
@@ -198,6 +198,9 @@ pub fn validity_predicate(
                 Ok(false) => 0,
                 Err(err) => {
                     namada_vp_prelude::debug_log!("Validity predicate error: {}", err);
+                    namada_vp_prelude::set_rejection(
+                        &namada_vp_prelude::validity_predicate::VpRejection::error(&err),
+                    );
                     0
                 },
             }
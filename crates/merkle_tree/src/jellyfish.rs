@@ -0,0 +1,290 @@
+//! A Jellyfish Merkle tree (JMT) backend of the subtrees, opted into with the
+//! `jmt` feature.
+//!
+//! The [`Jmt`] implements the [`SubTreeRead`] and [`SubTreeWrite`] traits of
+//! the other subtrees, and its ICS-23 proofs are chained with the ones of the
+//! base tree with the [`proof_specs`]. Unlike the sparse merkle tree, it can
+//! write a batch of changes at once with [`Jmt::update_batch`], so that the
+//! nodes shared by the paths of the changed keys are only written once per
+//! batch. The number of nodes written so far is kept to compare the write
+//! amplification of the backends.
+
+use std::collections::BTreeMap;
+
+use ics23::ProofSpec;
+use jmt::storage::{LeafNode, Node, NodeKey, TreeReader, TreeUpdateBatch};
+use jmt::{KeyHash, OwnedValue, RootHash, Sha256Jmt, Version};
+use namada_core::types::hash::{Hash, Sha256Hasher, StorageHasher};
+use namada_core::types::storage::Key;
+
+use crate::ics23_specs::base_leaf_spec;
+use crate::{
+    Error, MembershipProof, MerkleRoot, Result, StorageBytes, SubTreeRead,
+    SubTreeWrite,
+};
+
+/// The store of a JMT. Only the nodes and the values of the latest version
+/// are kept, the nodes made stale by an update are removed right away.
+#[derive(Clone, Debug, Default)]
+pub struct JmtStore {
+    nodes: BTreeMap<NodeKey, Node>,
+    values: BTreeMap<KeyHash, (Version, OwnedValue)>,
+}
+
+impl JmtStore {
+    /// Apply the changes of a version to the store and return the number of
+    /// nodes written
+    fn apply(&mut self, batch: TreeUpdateBatch) -> u64 {
+        for stale in batch.stale_node_index_batch {
+            self.nodes.remove(&stale.node_key);
+        }
+        let nodes = batch.node_batch.nodes();
+        for (node_key, node) in nodes {
+            self.nodes.insert(node_key.clone(), node.clone());
+        }
+        for ((version, key_hash), value) in batch.node_batch.values() {
+            match value {
+                Some(value) => {
+                    self.values.insert(*key_hash, (*version, value.clone()));
+                }
+                None => {
+                    self.values.remove(key_hash);
+                }
+            }
+        }
+        nodes.len() as u64
+    }
+}
+
+impl TreeReader for JmtStore {
+    fn get_node_option(
+        &self,
+        node_key: &NodeKey,
+    ) -> anyhow::Result<Option<Node>> {
+        Ok(self.nodes.get(node_key).cloned())
+    }
+
+    fn get_value_option(
+        &self,
+        max_version: Version,
+        key_hash: KeyHash,
+    ) -> anyhow::Result<Option<OwnedValue>> {
+        Ok(self
+            .values
+            .get(&key_hash)
+            .filter(|(version, _)| *version <= max_version)
+            .map(|(_, value)| value.clone()))
+    }
+
+    fn get_rightmost_leaf(
+        &self,
+    ) -> anyhow::Result<Option<(NodeKey, LeafNode)>> {
+        Ok(self
+            .nodes
+            .iter()
+            .filter_map(|(node_key, node)| match node {
+                Node::Leaf(leaf) => Some((node_key.clone(), leaf.clone())),
+                _ => None,
+            })
+            .max_by_key(|(_, leaf)| leaf.key_hash()))
+    }
+}
+
+/// A Jellyfish Merkle tree with a SHA-256 hasher. Every update or batch of
+/// updates is written at a new version.
+#[derive(Clone, Debug, Default)]
+pub struct Jmt {
+    store: JmtStore,
+    /// The latest version and its root, if anything was written
+    latest: Option<(Version, RootHash)>,
+    /// The number of nodes written by all the versions
+    written_nodes: u64,
+}
+
+impl Jmt {
+    /// Write the given changes at a new version and return the new root. A
+    /// `None` value deletes the key.
+    pub fn update_batch<'a>(
+        &mut self,
+        changes: impl IntoIterator<Item = (&'a Key, Option<StorageBytes<'a>>)>,
+    ) -> Result<Hash> {
+        let value_set: Vec<(KeyHash, Option<OwnedValue>)> = changes
+            .into_iter()
+            .map(|(key, value)| (key_hash(key), value.map(<[u8]>::to_vec)))
+            .collect();
+        let version = self.latest.map_or(0, |(version, _)| version + 1);
+        let (root, batch) = Sha256Jmt::new(&self.store)
+            .put_value_set(value_set, version)
+            .map_err(|err| Error::MerkleTree(err.to_string()))?;
+        self.written_nodes += self.store.apply(batch);
+        self.latest = Some((version, root));
+        Ok(Hash(root.0))
+    }
+
+    /// The number of nodes written by all the versions, to be compared with
+    /// the number of changed keys
+    pub fn written_nodes(&self) -> u64 {
+        self.written_nodes
+    }
+
+    /// Get the ICS-23 proof of the existence or of the non-existence of a key
+    pub fn ics23_proof(&self, key: &Key) -> Result<ics23::CommitmentProof> {
+        let (version, _) = self.latest.ok_or_else(|| {
+            Error::MerkleTree("No proof can be made from an empty JMT".into())
+        })?;
+        let (_, proof) = Sha256Jmt::new(&self.store)
+            .get_with_ics23_proof(key.to_string().into_bytes(), version)
+            .map_err(|err| Error::MerkleTree(err.to_string()))?;
+        Ok(proof)
+    }
+
+    fn get_value(&self, key: &Key) -> Result<Option<OwnedValue>> {
+        match self.latest {
+            Some((version, _)) => self
+                .store
+                .get_value_option(version, key_hash(key))
+                .map_err(|err| Error::MerkleTree(err.to_string())),
+            None => Ok(None),
+        }
+    }
+}
+
+/// The hash of a key in a JMT, as done by the ICS-23 spec of the JMT
+fn key_hash(key: &Key) -> KeyHash {
+    KeyHash(Sha256Hasher::hash(key.to_string()).into())
+}
+
+/// Get the proof specs of a JMT subtree and of the base tree
+pub fn proof_specs() -> Vec<ProofSpec> {
+    let spec = arse_merkle_tree::proof_ics23::get_spec(Sha256Hasher::hash_op());
+    let base_tree_spec = ProofSpec {
+        leaf_spec: Some(base_leaf_spec::<Sha256Hasher>()),
+        ..spec
+    };
+    vec![jmt::ics23_spec(), base_tree_spec]
+}
+
+impl<'a> SubTreeRead for &'a Jmt {
+    fn root(&self) -> MerkleRoot {
+        match self.latest {
+            Some((_, root)) => MerkleRoot(root.0),
+            None => MerkleRoot([0; 32]),
+        }
+    }
+
+    fn subtree_has_key(&self, key: &Key) -> Result<bool> {
+        Ok(self.get_value(key)?.is_some())
+    }
+
+    fn subtree_get(&self, key: &Key) -> Result<Vec<u8>> {
+        self.get_value(key)?.ok_or_else(|| {
+            Error::MerkleTree(format!("The key {key} is not in the JMT"))
+        })
+    }
+
+    fn subtree_membership_proof(
+        &self,
+        keys: &[Key],
+        values: Vec<StorageBytes>,
+    ) -> Result<MembershipProof> {
+        if keys.len() != 1 || values.len() != 1 {
+            return Err(Error::Ics23MultiLeaf);
+        }
+        // The proof holds the value written in the tree
+        self.ics23_proof(&keys[0]).map(MembershipProof::from)
+    }
+}
+
+impl<'a> SubTreeWrite for &'a mut Jmt {
+    fn subtree_update(
+        &mut self,
+        key: &Key,
+        value: StorageBytes,
+    ) -> Result<Hash> {
+        self.update_batch([(key, Some(value))])
+    }
+
+    fn subtree_delete(&mut self, key: &Key) -> Result<Hash> {
+        self.update_batch([(key, None)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ics23::HostFunctionsManager;
+
+    use super::*;
+
+    fn key(index: u64) -> Key {
+        Key::parse(format!("account/{index}")).unwrap()
+    }
+
+    /// Test that the root of a JMT only depends on its key-value pairs, and
+    /// that a batch writes fewer nodes than the same updates one by one.
+    #[test]
+    fn test_jmt_root_and_write_amplification() {
+        let keys: Vec<Key> = (0..64).map(key).collect();
+        let mut one_by_one = Jmt::default();
+        for key in &keys {
+            (&mut one_by_one).subtree_update(key, b"value").unwrap();
+        }
+        (&mut one_by_one).subtree_delete(&keys[0]).unwrap();
+
+        let mut batched = Jmt::default();
+        let root = batched
+            .update_batch(
+                keys[1..]
+                    .iter()
+                    .rev()
+                    .map(|key| (key, Some(b"value".as_slice()))),
+            )
+            .unwrap();
+        assert_eq!(root.0, (&one_by_one).root().0);
+        assert!(batched.written_nodes() < one_by_one.written_nodes());
+
+        assert!(!(&batched).subtree_has_key(&keys[0]).unwrap());
+        assert!((&batched).subtree_has_key(&keys[1]).unwrap());
+        assert_eq!((&batched).subtree_get(&keys[1]).unwrap(), b"value");
+    }
+
+    /// Test that the ICS-23 proofs of a JMT are verified with its spec.
+    #[test]
+    fn test_jmt_ics23_proofs() {
+        let keys: Vec<Key> = (0..16).map(key).collect();
+        let mut tree = Jmt::default();
+        let root = tree
+            .update_batch(
+                keys.iter().map(|key| (key, Some(b"value".as_slice()))),
+            )
+            .unwrap();
+        let root = root.0.to_vec();
+        let specs = proof_specs();
+
+        let existing = key(3);
+        let MembershipProof::ICS23(proof) = (&tree)
+            .subtree_membership_proof(
+                std::array::from_ref(&existing),
+                vec![b"value".as_slice()],
+            )
+            .unwrap()
+        else {
+            unreachable!()
+        };
+        assert!(ics23::verify_membership::<HostFunctionsManager>(
+            &proof,
+            &specs[0],
+            &root,
+            existing.to_string().as_bytes(),
+            b"value",
+        ));
+
+        let missing = key(42);
+        let proof = tree.ics23_proof(&missing).unwrap();
+        assert!(ics23::verify_non_membership::<HostFunctionsManager>(
+            &proof,
+            &specs[0],
+            &root,
+            missing.to_string().as_bytes(),
+        ));
+    }
+}
@@ -2,7 +2,11 @@
 
 pub mod eth_bridge_pool;
 pub mod ics23_specs;
+#[cfg(feature = "jmt")]
+pub mod jellyfish;
 
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::fmt;
 use std::str::FromStr;
 
@@ -133,6 +137,7 @@ pub type Amt<H> =
     PartialEq,
     Eq,
     PartialOrd,
+    Ord,
     BorshSerialize,
     BorshDeserialize,
 )]
@@ -356,6 +361,12 @@ pub fn subtree_key_prefix(st: &StoreType, epoch: Epoch) -> Key {
 }
 
 /// Merkle tree storage
+///
+/// The roots of the subtrees are written to the base tree lazily: an update
+/// of a subtree only marks it as dirty, and the base tree is brought
+/// up-to-date with [`MerkleTree::update_base`], once per block. Until then,
+/// the root and the proofs are computed against a copy of the base tree with
+/// the roots of the dirty subtrees.
 #[derive(Default)]
 pub struct MerkleTree<H: StorageHasher + Default> {
     base: Smt<H>,
@@ -363,11 +374,14 @@ pub struct MerkleTree<H: StorageHasher + Default> {
     ibc: Amt<H>,
     pos: Smt<H>,
     bridge_pool: BridgePoolTree,
+    /// The subtrees whose roots have changed since they were last written to
+    /// the base tree
+    dirty_subtrees: BTreeSet<StoreType>,
 }
 
 impl<H: StorageHasher + Default> core::fmt::Debug for MerkleTree<H> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let root_hash = format!("{}", ByteBuf(self.base.root().as_slice()));
+        let root_hash = format!("{}", ByteBuf(&self.root().0));
         f.debug_struct("MerkleTree")
             .field("root_hash", &root_hash)
             .finish()
@@ -389,6 +403,7 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
             ibc,
             pos,
             bridge_pool,
+            dirty_subtrees: BTreeSet::new(),
         };
 
         // validate
@@ -432,6 +447,7 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
             ibc,
             pos,
             bridge_pool,
+            dirty_subtrees: BTreeSet::new(),
         }
     }
 
@@ -464,17 +480,55 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
         key: &Key,
         value: impl AsRef<[u8]>,
     ) -> Result<()> {
-        let sub_root = self
-            .tree_mut(store_type)
+        self.tree_mut(store_type)
             .subtree_update(key, value.as_ref())?;
-        // update the base tree with the updated sub root without hashing
+        self.mark_dirty(store_type);
+        Ok(())
+    }
+
+    /// Mark a subtree as dirty, for its root to be written to the base tree
+    fn mark_dirty(&mut self, store_type: &StoreType) {
         if *store_type != StoreType::Base {
+            self.dirty_subtrees.insert(*store_type);
+        }
+    }
+
+    /// Write the roots of the dirty subtrees to the base tree. Only the paths
+    /// of the base tree to these roots are recomputed, however many keys of
+    /// the subtrees have been updated.
+    pub fn update_base(&mut self) -> Result<()> {
+        while let Some(store_type) = self.dirty_subtrees.first().copied() {
+            // update the base tree with the sub root without hashing
+            let sub_root = Hash::from(self.tree(&store_type).root());
             let base_key = H::hash(store_type.to_string());
             self.base.update(base_key.into(), sub_root)?;
+            self.dirty_subtrees.remove(&store_type);
         }
         Ok(())
     }
 
+    /// Check if some subtrees have roots that are yet to be written to the
+    /// base tree
+    pub fn has_dirty_subtrees(&self) -> bool {
+        !self.dirty_subtrees.is_empty()
+    }
+
+    /// Get a copy of the base tree with the roots of the dirty subtrees, or
+    /// `None` if the base tree is up-to-date.
+    fn updated_base(&self) -> Option<Smt<H>> {
+        if self.dirty_subtrees.is_empty() {
+            return None;
+        }
+        let mut base = Smt::new(*self.base.root(), self.base.store().clone());
+        for store_type in &self.dirty_subtrees {
+            let sub_root = Hash::from(self.tree(store_type).root());
+            let base_key = H::hash(store_type.to_string());
+            base.update(base_key.into(), sub_root)
+                .expect("Updating a copy of the base tree shouldn't fail");
+        }
+        Some(base)
+    }
+
     /// Check if the key exists in the tree
     pub fn has_key(&self, key: &Key) -> Result<bool> {
         let (store_type, sub_key) = StoreType::sub_key(key)?;
@@ -496,17 +550,17 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
     /// Delete the value corresponding to the given key
     pub fn delete(&mut self, key: &Key) -> Result<()> {
         let (store_type, sub_key) = StoreType::sub_key(key)?;
-        let sub_root = self.tree_mut(&store_type).subtree_delete(&sub_key)?;
-        if store_type != StoreType::Base {
-            let base_key = H::hash(store_type.to_string());
-            self.base.update(base_key.into(), sub_root)?;
-        }
+        self.tree_mut(&store_type).subtree_delete(&sub_key)?;
+        self.mark_dirty(&store_type);
         Ok(())
     }
 
     /// Get the root
     pub fn root(&self) -> MerkleRoot {
-        self.base.root().into()
+        match self.updated_base() {
+            Some(base) => base.root().into(),
+            None => self.base.root().into(),
+        }
     }
 
     /// Get the root of a sub-tree
@@ -514,10 +568,17 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
         self.tree(store_type).root()
     }
 
-    /// Get the stores of the base and sub trees
+    /// Get the stores of the base and sub trees. The store of the base tree is
+    /// copied if it's not up-to-date, see [`MerkleTree::update_base`].
     pub fn stores(&self) -> MerkleTreeStoresWrite {
+        let base = match self.updated_base() {
+            Some(base) => {
+                (base.root().into(), Cow::Owned(base.store().clone()))
+            }
+            None => (self.base.root().into(), Cow::Borrowed(self.base.store())),
+        };
         MerkleTreeStoresWrite {
-            base: (self.base.root().into(), self.base.store()),
+            base,
             account: (self.account.root().into(), self.account.store()),
             ibc: (self.ibc.root().into(), self.ibc.store()),
             pos: (self.pos.root().into(), self.pos.store()),
@@ -601,7 +662,9 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
         // exist
         let (store_type, _) = StoreType::sub_key(key)?;
         let base_key = store_type.to_string();
-        let cp = self.base.membership_proof(&H::hash(&base_key).into())?;
+        let updated_base = self.updated_base();
+        let base = updated_base.as_ref().unwrap_or(&self.base);
+        let cp = base.membership_proof(&H::hash(&base_key).into())?;
         // Replace the values and the leaf op for the verification
         let base_proof = match cp.proof.expect("The proof should exist") {
             Ics23Proof::Exist(ep) => CommitmentProof {
@@ -722,7 +785,7 @@ impl MerkleTreeStoresRead {
 
 /// The root and store pairs to be persistent
 pub struct MerkleTreeStoresWrite<'a> {
-    base: (Hash, &'a SmtStore),
+    base: (Hash, Cow<'a, SmtStore>),
     account: (Hash, &'a SmtStore),
     ibc: (Hash, &'a AmtStore),
     pos: (Hash, &'a SmtStore),
@@ -744,7 +807,7 @@ impl<'a> MerkleTreeStoresWrite<'a> {
     /// Get the store of the given store type
     pub fn store(&self, store_type: &StoreType) -> StoreRef {
         match store_type {
-            StoreType::Base => StoreRef::Base(self.base.1),
+            StoreType::Base => StoreRef::Base(&self.base.1),
             StoreType::Account => StoreRef::Account(self.account.1),
             StoreType::Ibc => StoreRef::Ibc(self.ibc.1),
             StoreType::PoS => StoreRef::PoS(self.pos.1),
@@ -1003,9 +1066,12 @@ impl<'a> SubTreeWrite for &'a mut BridgePoolTree {
 
 #[cfg(test)]
 mod test {
+    use std::collections::BTreeMap;
+
     use ics23::HostFunctionsManager;
     use namada_core::types::hash::Sha256Hasher;
     use namada_core::types::storage::KeySeg;
+    use proptest::prelude::*;
 
     use super::*;
     use crate::ics23_specs::{ibc_proof_specs, proof_specs};
@@ -1313,4 +1379,123 @@ mod test {
             );
         assert!(basetree_verification_res);
     }
+
+    /// Get a key of the IBC, PoS or account subtree
+    fn subtree_key(store: u8, index: u8) -> Key {
+        let addr = match store {
+            0 => InternalAddress::Ibc,
+            1 => InternalAddress::PoS,
+            _ => InternalAddress::Parameters,
+        };
+        let prefix: Key = Address::Internal(addr).to_db_key().into();
+        prefix.push(&format!("key{index}")).unwrap()
+    }
+
+    /// Restore a tree from the stores of another one
+    fn restore_tree(
+        tree: &MerkleTree<Sha256Hasher>,
+    ) -> MerkleTree<Sha256Hasher> {
+        let stores_write = tree.stores();
+        let mut stores_read = MerkleTreeStoresRead::default();
+        for st in StoreType::iter() {
+            stores_read.set_root(st, *stores_write.root(st));
+            stores_read.set_store(stores_write.store(st).to_owned());
+        }
+        MerkleTree::<Sha256Hasher>::new(stores_read).unwrap()
+    }
+
+    /// Test that the proofs of a tree with dirty subtrees are checked against
+    /// its root.
+    #[test]
+    fn test_dirty_subtrees_proof() {
+        let mut tree = MerkleTree::<Sha256Hasher>::default();
+        let ibc_key = subtree_key(0, 0);
+        let pos_key = subtree_key(1, 0);
+        let ibc_val = [1u8; 8].to_vec();
+        tree.update(&ibc_key, ibc_val.clone()).unwrap();
+        tree.update_base().unwrap();
+        assert!(!tree.has_dirty_subtrees());
+        tree.update(&pos_key, [2u8; 8]).unwrap();
+        assert!(tree.has_dirty_subtrees());
+
+        let proof = tree
+            .get_sub_tree_existence_proof(
+                std::array::from_ref(&ibc_key),
+                vec![&ibc_val],
+            )
+            .unwrap();
+        let MembershipProof::ICS23(sub_proof) = proof else {
+            unreachable!()
+        };
+        let proof = tree.get_sub_tree_proof(&ibc_key, sub_proof).unwrap();
+        let base_ep = match proof.base_proof.proof.unwrap() {
+            Ics23Proof::Exist(ep) => ep,
+            _ => unreachable!(),
+        };
+        let base_root =
+            ics23::calculate_existence_root::<HostFunctionsManager>(&base_ep)
+                .unwrap();
+        assert_eq!(base_root, tree.root().0.to_vec());
+
+        tree.update_base().unwrap();
+        assert!(!tree.has_dirty_subtrees());
+        assert_eq!(base_root, tree.root().0.to_vec());
+    }
+
+    proptest! {
+        /// Test that the root of a tree whose base tree is updated lazily,
+        /// with the roots of the dirty subtrees only, is the one of a tree
+        /// recomputed from scratch with the final values.
+        #[test]
+        fn test_incremental_root_matches_full_recomputation(
+            ops in proptest::collection::vec(
+                (
+                    0..3_u8,
+                    0..16_u8,
+                    proptest::option::of(any::<u8>()),
+                    any::<bool>(),
+                ),
+                0..64,
+            )
+        ) {
+            let mut tree = MerkleTree::<Sha256Hasher>::default();
+            let mut values = BTreeMap::new();
+            for (store, index, value, update_base) in ops {
+                let key = subtree_key(store, index);
+                match value {
+                    Some(value) => {
+                        tree.update(&key, [value; 8]).unwrap();
+                        values.insert(key, value);
+                    }
+                    None => {
+                        if values.remove(&key).is_some() {
+                            tree.delete(&key).unwrap();
+                        }
+                    }
+                }
+                if update_base {
+                    tree.update_base().unwrap();
+                    prop_assert!(!tree.has_dirty_subtrees());
+                }
+            }
+
+            let mut full_tree = MerkleTree::<Sha256Hasher>::default();
+            for (key, value) in &values {
+                full_tree.update(key, [*value; 8]).unwrap();
+            }
+            full_tree.update_base().unwrap();
+
+            let root = tree.root().0;
+            prop_assert_eq!(root, full_tree.root().0);
+            for st in StoreType::iter_subtrees() {
+                prop_assert_eq!(tree.sub_root(st).0, full_tree.sub_root(st).0);
+            }
+            // The stores of a tree with dirty subtrees hold the updated base
+            // tree
+            prop_assert_eq!(restore_tree(&tree).root().0, root);
+            tree.update_base().unwrap();
+            prop_assert_eq!(tree.root().0, root);
+            prop_assert_eq!(restore_tree(&tree).root().0, root);
+        }
+    }
 }
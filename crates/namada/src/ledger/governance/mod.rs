@@ -406,15 +406,32 @@ where
                     .filter(|funding| matches!(funding, PGFAction::Retro(_)))
                     .count();
 
+                // collect all the retro claim targets, that must be unique
+                // and have valid claims
+                let retro_claims = fundings
+                    .iter()
+                    .filter_map(|funding| match funding {
+                        PGFAction::RetroClaim(claim) => Some(claim),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                let are_retro_claims_valid =
+                    retro_claims.iter().all(|claim| claim.is_valid());
+                let retro_claim_targets = retro_claims
+                    .iter()
+                    .map(|claim| &claim.target)
+                    .collect::<BTreeSet<_>>();
+
                 let is_total_fundings_valid = fundings.len() < MAX_PGF_ACTIONS;
 
                 // check that they are unique by checking that the set of add
-                // plus the set of remove plus the set of retro is equal to the
-                // total fundings
+                // plus the set of remove plus the set of retro plus the set of
+                // retro claims is equal to the total fundings
                 let are_continous_fundings_unique =
                     are_continous_add_targets_unique.len()
                         + are_continous_remove_targets_unique.len()
                         + total_retro_targerts
+                        + retro_claim_targets.len()
                         == fundings.len();
 
                 // can't remove and add the same target in the same proposal
@@ -425,7 +442,16 @@ where
 
                 Ok(is_total_fundings_valid
                     && are_continous_fundings_unique
-                    && are_targets_unique)
+                    && are_targets_unique
+                    && are_retro_claims_valid)
+            }
+            ProposalType::SlashPoolDisbursement(actions) => {
+                let is_total_actions_valid =
+                    !actions.is_empty() && actions.len() < MAX_PGF_ACTIONS;
+                let are_amounts_valid =
+                    actions.iter().all(|action| !action.amount().is_zero());
+
+                Ok(is_total_actions_valid && are_amounts_valid)
             }
             _ => Ok(true), // default proposal
         }
@@ -494,8 +520,9 @@ where
             tracing::info!("Committing proposal key is missing present");
         }
 
-        let is_valid_grace_epoch = end_epoch < grace_epoch
-            && (grace_epoch - end_epoch).0 >= min_grace_epoch;
+        let is_valid_grace_epoch = grace_epoch
+            .checked_offset_from(end_epoch)
+            .map_or(false, |grace| grace.0 > 0 && grace.0 >= min_grace_epoch);
         if !is_valid_grace_epoch {
             tracing::info!(
                 "Expected min duration between the end and grace epoch \
@@ -504,8 +531,11 @@ where
                 end_epoch
             );
         }
-        let is_valid_max_proposal_period = start_epoch < grace_epoch
-            && grace_epoch.0 - start_epoch.0 <= max_proposal_period;
+        let is_valid_max_proposal_period = grace_epoch
+            .checked_offset_from(start_epoch)
+            .map_or(false, |period| {
+                period.0 > 0 && period.0 <= max_proposal_period
+            });
         if !is_valid_max_proposal_period {
             tracing::info!(
                 "Expected max duration between the start and grace epoch \
@@ -120,6 +120,17 @@ impl ProposalEvent {
         )
     }
 
+    /// Create a new proposal event for slash pool disbursement proposal
+    pub fn slash_pool_proposal_event(proposal_id: u64, result: bool) -> Self {
+        ProposalEvent::new(
+            EventType::Proposal.to_string(),
+            TallyResult::Passed,
+            proposal_id,
+            false,
+            result,
+        )
+    }
+
     /// Create a new proposal event for eth proposal
     pub fn eth_proposal_event(proposal_id: u64, result: bool) -> Self {
         ProposalEvent::new(
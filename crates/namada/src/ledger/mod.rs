@@ -13,7 +13,7 @@ pub mod storage;
 pub mod vp_host_fns;
 
 #[cfg(feature = "wasm-runtime")]
-pub use dry_run_tx::dry_run_tx;
+pub use dry_run_tx::{dry_run_tx, simulate_tx};
 pub use namada_core::ledger::replay_protection;
 pub use {
     namada_gas as gas, namada_parameters as parameters,
@@ -22,11 +22,23 @@ pub use {
 
 #[cfg(feature = "wasm-runtime")]
 mod dry_run_tx {
-    use namada_sdk::queries::{EncodedResponseQuery, RequestCtx, RequestQuery};
-    use namada_state::{DBIter, ResultExt, StorageHasher, DB};
-    use namada_tx::data::GasLimit;
+    use borsh_ext::BorshSerializeExt;
+    use namada_gas::{Gas, GasMetering, TxGasMeter};
+    use namada_sdk::queries::{
+        EncodedResponseQuery, RequestCtx, RequestQuery, StorageDiff,
+        TxSimulation,
+    };
+    use namada_state::write_log::StorageModification;
+    use namada_state::{
+        DBIter, ResultExt, StorageHasher, StorageRead, StorageResult,
+        TempWlStorage, DB,
+    };
+    use namada_tx::data::{DecryptedTx, GasLimit, TxResult, TxType};
+    use namada_tx::Tx;
 
     use super::protocol;
+    use crate::ledger::protocol::ShellParams;
+    use crate::types::storage::{Key, TxIndex};
     use crate::vm::wasm::{TxCache, VpCache};
     use crate::vm::WasmCacheAccess;
 
@@ -34,25 +46,97 @@ mod dry_run_tx {
     pub fn dry_run_tx<D, H, CA>(
         mut ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
         request: &RequestQuery,
-    ) -> namada_state::StorageResult<EncodedResponseQuery>
+    ) -> StorageResult<EncodedResponseQuery>
     where
         D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
         H: 'static + StorageHasher + Sync,
         CA: 'static + WasmCacheAccess + Sync,
     {
-        use borsh_ext::BorshSerializeExt;
-        use namada_gas::{Gas, GasMetering, TxGasMeter};
-        use namada_state::TempWlStorage;
-        use namada_tx::data::{DecryptedTx, TxType};
-        use namada_tx::Tx;
+        let wl_storage = ctx.wl_storage;
+        let mut temp_wl_storage = TempWlStorage::new(&wl_storage.storage);
+        let data = apply_tx(&mut ctx, &mut temp_wl_storage, request)?;
+        Ok(EncodedResponseQuery {
+            data: data.serialize_to_vec(),
+            proof: None,
+            info: Default::default(),
+        })
+    }
 
-        use crate::ledger::protocol::ShellParams;
-        use crate::types::storage::TxIndex;
+    /// Dry run a transaction and return the changes of the storage values
+    /// written by its inner transaction together with its result
+    pub fn simulate_tx<D, H, CA>(
+        mut ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+        request: &RequestQuery,
+    ) -> StorageResult<EncodedResponseQuery>
+    where
+        D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+        H: 'static + StorageHasher + Sync,
+        CA: 'static + WasmCacheAccess + Sync,
+    {
+        let wl_storage = ctx.wl_storage;
+        let mut temp_wl_storage = TempWlStorage::new(&wl_storage.storage);
+        let result = apply_tx(&mut ctx, &mut temp_wl_storage, request)?;
+        let diff = result
+            .changed_keys
+            .iter()
+            .map(|key| {
+                Ok(StorageDiff {
+                    key: key.clone(),
+                    pre: read_pre_bytes(&temp_wl_storage, key)?,
+                    post: temp_wl_storage.read_bytes(key)?,
+                })
+            })
+            .collect::<StorageResult<Vec<_>>>()?;
+        let data = TxSimulation { result, diff }.serialize_to_vec();
+        Ok(EncodedResponseQuery {
+            data,
+            proof: None,
+            info: Default::default(),
+        })
+    }
 
+    /// Read the value of the given key from before the execution of the
+    /// inner transaction, but after the wrapper one (if any)
+    fn read_pre_bytes<D, H>(
+        temp_wl_storage: &TempWlStorage<'_, D, H>,
+        key: &Key,
+    ) -> StorageResult<Option<Vec<u8>>>
+    where
+        D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+        H: 'static + StorageHasher + Sync,
+    {
+        match temp_wl_storage.write_log.read_pre(key).0 {
+            Some(StorageModification::Write { value })
+            | Some(StorageModification::Temp { value }) => {
+                Ok(Some(value.clone()))
+            }
+            Some(StorageModification::Delete) => Ok(None),
+            Some(StorageModification::InitAccount { vp_code_hash }) => {
+                Ok(Some(vp_code_hash.to_vec()))
+            }
+            None => temp_wl_storage
+                .storage
+                .db
+                .read_subspace_val(key)
+                .into_storage_result(),
+        }
+    }
+
+    /// Apply the transaction of the request on top of the given temporary
+    /// storage
+    fn apply_tx<D, H, CA>(
+        ctx: &mut RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+        temp_wl_storage: &mut TempWlStorage<'_, D, H>,
+        request: &RequestQuery,
+    ) -> StorageResult<TxResult>
+    where
+        D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+        H: 'static + StorageHasher + Sync,
+        CA: 'static + WasmCacheAccess + Sync,
+    {
         let mut tx = Tx::try_from(&request.data[..]).into_storage_result()?;
         tx.validate_tx().into_storage_result()?;
 
-        let mut temp_wl_storage = TempWlStorage::new(&ctx.wl_storage.storage);
         let mut cumulated_gas = Gas::default();
 
         // Wrapper dry run to allow estimating the gas cost of a transaction
@@ -67,7 +151,7 @@ mod dry_run_tx {
                     &request.data,
                     ShellParams::new(
                         &mut tx_gas_meter,
-                        &mut temp_wl_storage,
+                        temp_wl_storage,
                         &mut ctx.vp_wasm_cache,
                         &mut ctx.tx_wasm_cache,
                     ),
@@ -107,7 +191,7 @@ mod dry_run_tx {
             &TxIndex(0),
             ShellParams::new(
                 &mut tx_gas_meter,
-                &mut temp_wl_storage,
+                temp_wl_storage,
                 &mut ctx.vp_wasm_cache,
                 &mut ctx.tx_wasm_cache,
             ),
@@ -122,12 +206,7 @@ mod dry_run_tx {
         data.gas_used = cumulated_gas;
         // NOTE: the keys changed by the wrapper transaction (if any) are not
         // returned from this function
-        let data = data.serialize_to_vec();
-        Ok(EncodedResponseQuery {
-            data,
-            proof: None,
-            info: Default::default(),
-        })
+        Ok(data)
     }
 }
 
@@ -137,16 +216,17 @@ mod test {
     use borsh_ext::BorshSerializeExt;
     use namada_core::types::address;
     use namada_core::types::hash::Hash;
-    use namada_core::types::storage::{BlockHeight, Key};
+    use namada_core::types::storage::{BlockHeight, Epoch, Key};
     use namada_sdk::queries::{
         EncodedResponseQuery, RequestCtx, RequestQuery, Router, RPC,
     };
+    use namada_sdk::rpc::{query_batch, QueryBatch};
     use namada_sdk::tendermint_rpc::{self, Error as RpcError, Response};
     use namada_state::testing::TestWlStorage;
     use namada_state::StorageWrite;
     use namada_test_utils::TestWasms;
     use namada_tx::data::decrypted::DecryptedTx;
-    use namada_tx::data::TxType;
+    use namada_tx::data::{TxResult, TxType};
     use namada_tx::{Code, Data, Tx};
     use tempfile::TempDir;
 
@@ -250,10 +330,10 @@ mod test {
             };
             // TODO: this is a hack to propagate errors to the caller, we should
             // really permit error types other than [`std::io::Error`]
-            if request.path == "/shell/dry_run_tx" {
-                super::dry_run_tx(ctx, &request)
-            } else {
-                self.rpc.handle(ctx, &request)
+            match request.path.as_str() {
+                "/shell/dry_run_tx" => super::dry_run_tx(ctx, &request),
+                "/shell/simulate_tx" => super::simulate_tx(ctx, &request),
+                _ => self.rpc.handle(ctx, &request),
             }
             .map_err(|err| {
                 std::io::Error::new(std::io::ErrorKind::Other, err.to_string())
@@ -304,6 +384,18 @@ mod test {
             .unwrap();
         assert!(result.data.is_accepted());
 
+        // Request a simulation of the same tx
+        let simulation = RPC
+            .shell()
+            .simulate_tx(&client, Some(outer_tx.to_bytes()), None, false)
+            .await
+            .unwrap();
+        assert!(simulation.data.result.is_accepted());
+        assert_eq!(
+            simulation.data.diff.len(),
+            simulation.data.result.changed_keys.len()
+        );
+
         // Request storage value for a balance key ...
         let token_addr = address::testing::established_address_1();
         let owner = address::testing::established_address_2();
@@ -367,6 +459,21 @@ mod test {
             .unwrap();
         assert!(has_balance_key);
 
+        // Request a batch of queries, with an invalid one in between
+        let mut batch = QueryBatch::default();
+        let epoch_item = batch.push::<Epoch>(RPC.shell().epoch_path());
+        let dry_run_item = batch.push_with_data::<TxResult>(
+            RPC.shell().dry_run_tx_path(),
+            outer_tx.to_bytes(),
+        );
+        let balance_item = batch.push::<token::Amount>(
+            RPC.shell().storage_value_path(&balance_key),
+        );
+        let results = query_batch(&client, &batch, None).await.unwrap();
+        assert_eq!(results.get(&epoch_item).unwrap(), current_epoch);
+        assert!(results.get(&dry_run_item).is_err());
+        assert_eq!(results.get(&balance_item).unwrap(), balance);
+
         Ok(())
     }
 }
@@ -20,7 +20,8 @@ use eyre::eyre;
 use namada_core::hints;
 use namada_core::types::eth_bridge_pool::erc20_token_address;
 use namada_ethereum_bridge::storage::bridge_pool::{
-    get_pending_key, is_bridge_pool_key, BRIDGE_POOL_ADDRESS,
+    get_delayed_transfer_key, get_pending_key, is_bridge_pool_key,
+    DelayedTransfer, BRIDGE_POOL_ADDRESS,
 };
 use namada_ethereum_bridge::storage::parameters::read_native_erc20_address;
 use namada_ethereum_bridge::storage::whitelist;
@@ -32,6 +33,7 @@ use crate::ledger::native_vp::{Ctx, NativeVp, StorageReader};
 use crate::token::storage_key::balance_key;
 use crate::token::Amount;
 use crate::types::address::{Address, InternalAddress};
+use crate::types::eth_abi::Encode;
 use crate::types::eth_bridge_pool::{PendingTransfer, TransferToEthereumKind};
 use crate::types::ethereum_events::EthAddress;
 use crate::types::storage::Key;
@@ -564,6 +566,26 @@ where
             }
             _ => {}
         }
+        // check that the same transfer is not delayed by its veto window
+        let delayed_key = get_delayed_transfer_key(&transfer.keccak256());
+        match (&self.ctx).read_pre_value::<DelayedTransfer>(&delayed_key) {
+            Ok(Some(_)) => {
+                tracing::debug!(
+                    "Rejecting transaction as the transfer is already delayed \
+                     by its withdrawal veto window."
+                );
+                return Ok(false);
+            }
+            Err(e) => {
+                return Err(eyre!(
+                    "Could not read the storage key associated with the \
+                     delayed transfer: {:?}",
+                    e
+                )
+                .into());
+            }
+            _ => {}
+        }
         for key in keys_changed.iter().filter(|k| is_bridge_pool_key(k)) {
             if *key != pending_key {
                 tracing::debug!(
@@ -662,7 +684,7 @@ mod test_bridge_pool_vp {
     use crate::types::chain::ChainId;
     use crate::types::eth_bridge_pool::{GasFee, TransferToEthereum};
     use crate::types::hash::Hash;
-    use crate::types::storage::TxIndex;
+    use crate::types::storage::{BlockHeight, TxIndex};
     use crate::vm::wasm::VpCache;
     use crate::vm::WasmCacheRwAccess;
 
@@ -905,6 +927,7 @@ mod test_bridge_pool_vp {
             erc20_whitelist: vec![],
             eth_start_height: Default::default(),
             min_confirmations: Default::default(),
+            checkpoint_interval: None,
             contracts: Contracts {
                 native_erc20: wnam(),
                 bridge: UpgradeableContract {
@@ -1386,6 +1409,93 @@ mod test_bridge_pool_vp {
         assert!(!res.expect("Test failed"));
     }
 
+    /// Test that adding a transfer to the pool while the same
+    /// transfer is delayed by its withdrawal veto window fails.
+    #[test]
+    fn test_adding_delayed_transfer_fails() {
+        // setup
+        let mut wl_storage = setup_storage();
+        let tx = Tx::from_type(TxType::Raw);
+
+        // the transfer to be added to the pool
+        let transfer = initial_pool();
+
+        // move the transfer out of the pool, as if it were delayed
+        wl_storage
+            .write_log
+            .delete(&get_pending_key(&transfer))
+            .expect("Test failed");
+        wl_storage
+            .write_log
+            .write(
+                &get_delayed_transfer_key(&transfer.keccak256()),
+                DelayedTransfer {
+                    transfer: transfer.clone(),
+                    release_height: BlockHeight(10),
+                }
+                .serialize_to_vec(),
+            )
+            .expect("Test failed");
+        wl_storage.commit_block().expect("Test failed");
+
+        // add transfer to pool
+        let mut keys_changed = {
+            wl_storage
+                .write_log
+                .write(&get_pending_key(&transfer), transfer.serialize_to_vec())
+                .unwrap();
+            BTreeSet::from([get_pending_key(&transfer)])
+        };
+
+        // update Bertha's balances
+        let mut new_keys_changed = update_balances(
+            &mut wl_storage.write_log,
+            Balance {
+                asset: ASSET,
+                kind: TransferToEthereumKind::Erc20,
+                owner: bertha_address(),
+                gas: BERTHA_WEALTH.into(),
+                token: BERTHA_TOKENS.into(),
+            },
+            SignedAmount::Negative(GAS_FEE.into()),
+            SignedAmount::Negative(TOKENS.into()),
+        );
+        keys_changed.append(&mut new_keys_changed);
+
+        // update the bridge pool balances
+        let mut new_keys_changed = update_balances(
+            &mut wl_storage.write_log,
+            Balance {
+                asset: ASSET,
+                kind: TransferToEthereumKind::Erc20,
+                owner: BRIDGE_POOL_ADDRESS,
+                gas: ESCROWED_AMOUNT.into(),
+                token: ESCROWED_TOKENS.into(),
+            },
+            SignedAmount::Positive(GAS_FEE.into()),
+            SignedAmount::Positive(TOKENS.into()),
+        );
+        keys_changed.append(&mut new_keys_changed);
+        let verifiers = BTreeSet::default();
+
+        // create the data to be given to the vp
+        let vp = BridgePoolVp {
+            ctx: setup_ctx(
+                &tx,
+                &wl_storage.storage,
+                &wl_storage.write_log,
+                &keys_changed,
+                &verifiers,
+            ),
+        };
+
+        let mut tx = Tx::new(wl_storage.storage.chain_id.clone(), None);
+        tx.add_data(transfer);
+
+        let res = vp.validate_tx(&tx, &keys_changed, &verifiers);
+        assert!(!res.expect("Test failed"));
+    }
+
     /// Test that a transfer added to the pool with zero gas fees
     /// is rejected.
     #[test]
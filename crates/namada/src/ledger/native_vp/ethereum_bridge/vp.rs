@@ -230,6 +230,7 @@ mod tests {
             erc20_whitelist: vec![],
             eth_start_height: Default::default(),
             min_confirmations: Default::default(),
+            checkpoint_interval: None,
             contracts: Contracts {
                 native_erc20: wnam(),
                 bridge: UpgradeableContract {
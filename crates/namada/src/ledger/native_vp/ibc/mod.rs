@@ -388,6 +388,7 @@ mod tests {
         ChannelId, ClientId, ConnectionId, PortId, Sequence,
     };
     use crate::ibc::core::router::types::event::ModuleEvent;
+    use crate::ibc::escrow::EscrowAccounting;
     use crate::ibc::primitives::proto::{Any, Protobuf};
     use crate::ibc::primitives::{Msg, Timestamp};
     use crate::ibc::storage::{
@@ -395,8 +396,8 @@ mod tests {
         client_connections_key, client_counter_key, client_state_key,
         client_update_height_key, client_update_timestamp_key, commitment_key,
         connection_counter_key, connection_key, consensus_state_key,
-        ibc_denom_key, next_sequence_ack_key, next_sequence_recv_key,
-        next_sequence_send_key, receipt_key,
+        escrow_accounting_key, ibc_denom_key, ibc_token, next_sequence_ack_key,
+        next_sequence_recv_key, next_sequence_send_key, receipt_key,
     };
     use crate::ledger::gas::VpGasMeter;
     use crate::ledger::parameters::storage::{
@@ -406,7 +407,7 @@ mod tests {
     use crate::ledger::{ibc, pos};
     use crate::tendermint::time::Time as TmTime;
     use crate::token::storage_key::balance_key;
-    use crate::token::Amount;
+    use crate::token::{Amount, Change};
     use crate::types::key::testing::keypair_1;
     use crate::types::storage::{BlockHash, BlockHeight, TxIndex};
     use crate::types::time::DurationSecs;
@@ -2035,6 +2036,18 @@ mod tests {
             .write(&commitment_key, bytes)
             .expect("write failed");
         keys_changed.insert(commitment_key);
+        // escrow accounting
+        let accounting_key =
+            escrow_accounting_key(&get_port_id(), &get_channel_id(), &nam());
+        let accounting = EscrowAccounting {
+            escrowed: Amount::native_whole(100).change(),
+            minted: Change::zero(),
+        };
+        wl_storage
+            .write_log
+            .write(&accounting_key, accounting.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(accounting_key);
         // event
         let transfer_event = TransferEvent {
             sender: msg.packet_data.sender.clone(),
@@ -2208,6 +2221,21 @@ mod tests {
             .write(&denom_key, bytes)
             .expect("write failed");
         keys_changed.insert(denom_key);
+        // escrow accounting
+        let accounting_key = escrow_accounting_key(
+            &get_port_id(),
+            &get_channel_id(),
+            &ibc_token(coin.denom.to_string()),
+        );
+        let accounting = EscrowAccounting {
+            escrowed: Change::zero(),
+            minted: Amount::from_u64(100).change(),
+        };
+        wl_storage
+            .write_log
+            .write(&accounting_key, accounting.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(accounting_key);
         // event
         let recv_event = RecvEvent {
             sender: sender.to_string().into(),
@@ -2528,6 +2556,18 @@ mod tests {
             .delete(&commitment_key)
             .expect("delete failed");
         keys_changed.insert(commitment_key);
+        // escrow accounting
+        let accounting_key =
+            escrow_accounting_key(&get_port_id(), &get_channel_id(), &nam());
+        let accounting = EscrowAccounting {
+            escrowed: -Amount::native_whole(100).change(),
+            minted: Change::zero(),
+        };
+        wl_storage
+            .write_log
+            .write(&accounting_key, accounting.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(accounting_key);
         // event
         let data = serde_json::from_slice::<PacketData>(&packet.data)
             .expect("decoding packet data failed");
@@ -2680,6 +2720,18 @@ mod tests {
             .delete(&commitment_key)
             .expect("delete failed");
         keys_changed.insert(commitment_key);
+        // escrow accounting
+        let accounting_key =
+            escrow_accounting_key(&get_port_id(), &get_channel_id(), &nam());
+        let accounting = EscrowAccounting {
+            escrowed: -Amount::native_whole(100).change(),
+            minted: Change::zero(),
+        };
+        wl_storage
+            .write_log
+            .write(&accounting_key, accounting.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(accounting_key);
         // event
         let data = serde_json::from_slice::<PacketData>(&packet.data)
             .expect("decoding packet data failed");
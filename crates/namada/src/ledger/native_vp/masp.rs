@@ -11,7 +11,7 @@ use masp_primitives::transaction::components::I128Sum;
 use masp_primitives::transaction::Transaction;
 use namada_core::types::address::Address;
 use namada_core::types::address::InternalAddress::Masp;
-use namada_core::types::masp::encode_asset_type;
+use namada_core::types::masp::{derive_asset_type, unepoched_asset_type};
 use namada_core::types::storage::{IndexedTx, Key};
 use namada_gas::MASP_VERIFY_SHIELDED_TX_GAS;
 use namada_sdk::masp::verify_shielded_tx;
@@ -390,7 +390,7 @@ fn unepoched_tokens(
 ) -> Result<HashMap<AssetType, (Address, token::Denomination, MaspDigitPos)>> {
     let mut unepoched_tokens = HashMap::new();
     for digit in MaspDigitPos::iter() {
-        let asset_type = encode_asset_type(token.clone(), denom, digit, None)
+        let asset_type = unepoched_asset_type(token, denom, digit)
             .wrap_err("unable to create asset type")?;
         unepoched_tokens.insert(asset_type, (token.clone(), denom, digit));
     }
@@ -508,19 +508,18 @@ where
                     None if unepoched_tokens.contains_key(&vin.asset_type) => {
                         let (token, denom, digit) =
                             &unepoched_tokens[&vin.asset_type];
-                        // Determine what the asset type would be if it were
-                        // epoched
-                        let epoched_asset_type = encode_asset_type(
-                            token.clone(),
+                        // Determine what the asset type must be at this epoch
+                        let (expected_asset_type, _) = derive_asset_type(
+                            token,
                             *denom,
                             *digit,
-                            Some(epoch),
+                            epoch,
+                            |asset_type| {
+                                conversion_state.assets.contains_key(asset_type)
+                            },
                         )
                         .wrap_err("unable to create asset type")?;
-                        if conversion_state
-                            .assets
-                            .contains_key(&epoched_asset_type)
-                        {
+                        if expected_asset_type != vin.asset_type {
                             // If such an epoched asset type is available in the
                             // conversion tree, then we must reject the
                             // unepoched variant
@@ -5,7 +5,11 @@ pub mod ethereum_bridge;
 pub mod ibc;
 pub mod masp;
 pub mod multitoken;
+pub mod name_service;
+pub mod non_fungible;
 pub mod parameters;
+pub mod slash_pool;
+pub mod token_factory;
 
 use std::cell::RefCell;
 use std::collections::BTreeSet;
@@ -536,6 +540,8 @@ where
             let mut iterators: PrefixIterators<'_, DB> =
                 PrefixIterators::default();
             let mut result_buffer: Option<Vec<u8>> = None;
+            // The reason given by the evaluated VP is not surfaced
+            let mut rejection = None;
             let mut vp_wasm_cache = self.vp_wasm_cache.clone();
 
             let ctx = VpCtx::new(
@@ -544,6 +550,7 @@ where
                 self.write_log,
                 &mut self.gas_meter.borrow_mut(),
                 &mut self.sentinel.borrow_mut(),
+                &mut rejection,
                 self.tx,
                 self.tx_index,
                 &mut iterators,
@@ -1,19 +1,23 @@
 //! Native VP for multitokens
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::BTreeSet;
 
 use namada_governance::is_proposal_accepted;
-use namada_token::storage_key::is_any_token_parameter_key;
+use namada_token::read_masp_params;
+use namada_token::storage_key::{
+    is_any_token_parameter_key, is_masp_param_key,
+};
 use namada_tx::Tx;
 use namada_vp_env::VpEnv;
 use thiserror::Error;
 
 use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::token::factory::{self, origin_key};
 use crate::token::storage_key::{
-    is_any_minted_balance_key, is_any_minter_key, is_any_token_balance_key,
-    minter_key,
+    is_any_allowance_key, is_any_minted_balance_key, is_any_minter_key,
+    is_any_token_balance_key, minter_key,
 };
-use crate::token::Amount;
+use crate::token::{Amount, BalanceDelta, BalanceDeltaError};
 use crate::types::address::{Address, InternalAddress};
 use crate::types::storage::{Key, KeySeg};
 use crate::vm::WasmCacheAccess;
@@ -23,6 +27,8 @@ use crate::vm::WasmCacheAccess;
 pub enum Error {
     #[error("Native VP error: {0}")]
     NativeVpError(#[from] native_vp::Error),
+    #[error("Balance change error: {0}")]
+    BalanceDelta(#[from] BalanceDeltaError),
 }
 
 /// Multitoken functions result
@@ -53,82 +59,58 @@ where
         keys_changed: &BTreeSet<Key>,
         verifiers: &BTreeSet<Address>,
     ) -> Result<bool> {
-        let mut inc_changes: HashMap<Address, Amount> = HashMap::new();
-        let mut dec_changes: HashMap<Address, Amount> = HashMap::new();
-        let mut inc_mints: HashMap<Address, Amount> = HashMap::new();
-        let mut dec_mints: HashMap<Address, Amount> = HashMap::new();
+        // Validate the changed keys in a single pass, accumulating the
+        // changes of every token touched by the tx. The changes of the
+        // balances must be offset by the changes of the minted supply.
+        let mut changes = BalanceDelta::default();
+        let mut valid_minters: BTreeSet<&Address> = BTreeSet::new();
+        let mut valid_parameter: Option<bool> = None;
+        let mut masp_params_changed: BTreeSet<&Address> = BTreeSet::new();
         for key in keys_changed {
             if let Some([token, _]) = is_any_token_balance_key(key) {
                 let pre: Amount = self.ctx.read_pre(key)?.unwrap_or_default();
                 let post: Amount = self.ctx.read_post(key)?.unwrap_or_default();
-                match post.checked_sub(pre) {
-                    Some(diff) => {
-                        let change =
-                            inc_changes.entry(token.clone()).or_default();
-                        *change =
-                            change.checked_add(diff).ok_or_else(|| {
-                                Error::NativeVpError(
-                                    native_vp::Error::SimpleMessage(
-                                        "Overflowed in balance check",
-                                    ),
-                                )
-                            })?;
-                    }
-                    None => {
-                        let diff = pre
-                            .checked_sub(post)
-                            .expect("Underflow shouldn't happen here");
-                        let change =
-                            dec_changes.entry(token.clone()).or_default();
-                        *change =
-                            change.checked_add(diff).ok_or_else(|| {
-                                Error::NativeVpError(
-                                    native_vp::Error::SimpleMessage(
-                                        "Overflowed in balance check",
-                                    ),
-                                )
-                            })?;
+                changes.record(token, pre, post)?;
+            } else if let Some(token) = is_any_minted_balance_key(key) {
+                // Check if the minter is set
+                if !valid_minters.contains(token) {
+                    if !self.is_valid_minter(token, verifiers)? {
+                        return Ok(false);
                     }
+                    valid_minters.insert(token);
                 }
-            } else if let Some(token) = is_any_minted_balance_key(key) {
                 let pre: Amount = self.ctx.read_pre(key)?.unwrap_or_default();
                 let post: Amount = self.ctx.read_post(key)?.unwrap_or_default();
-                match post.checked_sub(pre) {
-                    Some(diff) => {
-                        let mint = inc_mints.entry(token.clone()).or_default();
-                        *mint = mint.checked_add(diff).ok_or_else(|| {
-                            Error::NativeVpError(
-                                native_vp::Error::SimpleMessage(
-                                    "Overflowed in balance check",
-                                ),
-                            )
-                        })?;
-                    }
-                    None => {
-                        let diff = pre
-                            .checked_sub(post)
-                            .expect("Underflow shouldn't happen here");
-                        let mint = dec_mints.entry(token.clone()).or_default();
-                        *mint = mint.checked_add(diff).ok_or_else(|| {
-                            Error::NativeVpError(
-                                native_vp::Error::SimpleMessage(
-                                    "Overflowed in balance check",
-                                ),
-                            )
-                        })?;
+                changes.record_opposite(token, pre, post)?;
+            } else if let Some(token) = is_any_minter_key(key) {
+                if !valid_minters.contains(token) {
+                    if !self.is_valid_minter(token, verifiers)? {
+                        return Ok(false);
                     }
+                    valid_minters.insert(token);
                 }
-                // Check if the minter is set
-                if !self.is_valid_minter(token, verifiers)? {
+            } else if let Some([_, owner, spender]) = is_any_allowance_key(key)
+            {
+                // The spenders are authorized by the owners' VPs, an owner
+                // can't be its own spender
+                if owner == spender {
                     return Ok(false);
                 }
-            } else if let Some(token) = is_any_minter_key(key) {
-                if !self.is_valid_minter(token, verifiers)? {
+                // The allowance must be a valid amount
+                let _post: Option<Amount> = self.ctx.read_post(key)?;
+            } else if is_any_token_parameter_key(key).is_some() {
+                let is_valid = match valid_parameter {
+                    Some(is_valid) => is_valid,
+                    None => *valid_parameter
+                        .insert(self.is_valid_parameter(tx_data)?),
+                };
+                if !is_valid {
                     return Ok(false);
                 }
-            } else if is_any_token_parameter_key(key).is_some() {
-                return self.is_valid_parameter(tx_data);
-            } else if key.segments.get(0)
+                if let Some(token) = is_masp_param_key(key) {
+                    masp_params_changed.insert(token);
+                }
+            } else if key.segments.first()
                 == Some(
                     &Address::Internal(InternalAddress::Multitoken).to_db_key(),
                 )
@@ -139,32 +121,15 @@ where
             }
         }
 
-        let mut all_tokens = BTreeSet::new();
-        all_tokens.extend(inc_changes.keys().cloned());
-        all_tokens.extend(dec_changes.keys().cloned());
-        all_tokens.extend(inc_mints.keys().cloned());
-        all_tokens.extend(dec_mints.keys().cloned());
-
-        Ok(all_tokens.iter().all(|token| {
-            let inc_change =
-                inc_changes.get(token).cloned().unwrap_or_default();
-            let dec_change =
-                dec_changes.get(token).cloned().unwrap_or_default();
-            let inc_mint = inc_mints.get(token).cloned().unwrap_or_default();
-            let dec_mint = dec_mints.get(token).cloned().unwrap_or_default();
-
-            if inc_change >= dec_change && inc_mint >= dec_mint {
-                inc_change.checked_sub(dec_change)
-                    == inc_mint.checked_sub(dec_mint)
-            } else if (inc_change < dec_change && inc_mint >= dec_mint)
-                || (inc_change >= dec_change && inc_mint < dec_mint)
-            {
-                false
-            } else {
-                dec_change.checked_sub(inc_change)
-                    == dec_mint.checked_sub(inc_mint)
+        // The MASP rewards parameters updated by governance must be within
+        // their ranges
+        for token in masp_params_changed {
+            if !self.is_valid_masp_params(token)? {
+                return Ok(false);
             }
-        }))
+        }
+
+        Ok(changes.is_net_zero())
     }
 }
 
@@ -194,6 +159,14 @@ where
                     _ => Ok(false),
                 }
             }
+            Address::Established(_) => {
+                // The initial supply of a token can be minted when it's
+                // created by the token factory
+                let origin_key = origin_key(token);
+                Ok(!self.ctx.has_key_pre(&origin_key)?
+                    && self.ctx.has_key_post(&origin_key)?
+                    && verifiers.contains(&factory::ADDRESS))
+            }
             _ => {
                 // ERC20 and other tokens should not be minted by a wasm
                 // transaction
@@ -202,6 +175,23 @@ where
         }
     }
 
+    /// Return if the MASP rewards parameters of a token are valid after the
+    /// tx
+    pub fn is_valid_masp_params(&self, token: &Address) -> Result<bool> {
+        match read_masp_params(&self.ctx.post(), token)? {
+            Some(params) => match params.validate() {
+                Ok(()) => Ok(true),
+                Err(err) => {
+                    tracing::info!(
+                        "Rejected the MASP parameters of token {token}: {err}"
+                    );
+                    Ok(false)
+                }
+            },
+            None => Ok(false),
+        }
+    }
+
     /// Return if the parameter change was done via a governance proposal
     pub fn is_valid_parameter(&self, tx: &Tx) -> Result<bool> {
         match tx.data() {
@@ -223,17 +213,18 @@ mod tests {
     use namada_tx::{Code, Data, Section, Signature, Tx};
 
     use super::*;
-    use crate::core::types::address::nam;
     use crate::core::types::address::testing::{
-        established_address_1, established_address_2,
+        established_address_1, established_address_2, established_address_3,
     };
+    use crate::core::types::address::{btc, nam};
     use crate::ledger::gas::VpGasMeter;
     use crate::ledger::ibc::storage::ibc_token;
     use crate::token::storage_key::{
-        balance_key, minted_balance_key, minter_key,
+        allowance_key, balance_key, minted_balance_key, minter_key,
     };
     use crate::token::Amount;
     use crate::types::address::{Address, InternalAddress};
+    use crate::types::hash::Hash;
     use crate::types::key::testing::keypair_1;
     use crate::types::storage::TxIndex;
     use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
@@ -429,6 +420,62 @@ mod tests {
         );
     }
 
+    /// Test that the initial supply of a token is minted only when it's
+    /// created by the token factory.
+    #[test]
+    fn test_valid_factory_mint() {
+        let mut wl_storage = TestWlStorage::default();
+        let creator = established_address_1();
+        let create = factory::CreateToken {
+            origin: factory::TokenOrigin {
+                creator: creator.clone(),
+                salt: b"coin".to_vec(),
+            },
+            denom: 6u8.into(),
+            metadata: None,
+            initial_supply: Amount::native_whole(100),
+            vp_code_hash: Hash::zero(),
+        };
+        let token = factory::create_token(&mut wl_storage, &create).unwrap();
+
+        let validate = |wl_storage: &TestWlStorage| {
+            let keys_changed = wl_storage.write_log.get_keys();
+            let tx_index = TxIndex::default();
+            let tx = dummy_tx(wl_storage);
+            let gas_meter = VpGasMeter::new_from_tx_meter(
+                &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+            );
+            let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+            let verifiers = BTreeSet::from([factory::ADDRESS, creator.clone()]);
+            let ctx = Ctx::new(
+                &ADDRESS,
+                &wl_storage.storage,
+                &wl_storage.write_log,
+                &tx,
+                &tx_index,
+                gas_meter,
+                &keys_changed,
+                &verifiers,
+                vp_wasm_cache,
+            );
+            let vp = MultitokenVp { ctx };
+            vp.validate_tx(&tx, &keys_changed, &verifiers)
+                .expect("validation failed")
+        };
+        assert!(validate(&wl_storage));
+        wl_storage.commit_tx();
+
+        // No more tokens can be minted after the creation
+        crate::token::credit_tokens(
+            &mut wl_storage,
+            &token,
+            &creator,
+            Amount::native_whole(100),
+        )
+        .unwrap();
+        assert!(!validate(&wl_storage));
+    }
+
     #[test]
     fn test_invalid_mint() {
         let mut wl_storage = TestWlStorage::default();
@@ -688,4 +735,199 @@ mod tests {
                 .expect("validation failed")
         );
     }
+
+    #[test]
+    fn test_cross_token_transfer() {
+        let mut wl_storage = TestWlStorage::default();
+        let mut keys_changed = BTreeSet::new();
+
+        // Debit 10 NAM from the sender
+        let sender = established_address_1();
+        let sender_key = balance_key(&nam(), &sender);
+        let amount = Amount::native_whole(100);
+        wl_storage
+            .storage
+            .write(&sender_key, amount.serialize_to_vec())
+            .expect("write failed");
+        let amount = Amount::native_whole(90);
+        wl_storage
+            .write_log
+            .write(&sender_key, amount.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(sender_key);
+        // Credit 10 of another token to the receiver
+        let receiver = established_address_2();
+        let receiver_key = balance_key(&btc(), &receiver);
+        let amount = Amount::native_whole(10);
+        wl_storage
+            .write_log
+            .write(&receiver_key, amount.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(receiver_key);
+
+        let tx_index = TxIndex::default();
+        let tx = dummy_tx(&wl_storage);
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let mut verifiers = BTreeSet::new();
+        verifiers.insert(sender);
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+
+        // The net changes have to balance out for every token
+        let vp = MultitokenVp { ctx };
+        assert!(!vp
+            .validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("validation failed"));
+    }
+
+    #[test]
+    fn test_multi_transfer() {
+        // Debit 30 NAM and 30 BTC from the sender in three legs, in which
+        // the second receiver is credited one too many tokens if unbalanced
+        for unbalanced in [false, true] {
+            let mut wl_storage = TestWlStorage::default();
+            let mut keys_changed = BTreeSet::new();
+
+            let sender = established_address_1();
+            let receiver_1 = established_address_2();
+            let receiver_2 = established_address_3();
+            let credit_2 = if unbalanced { 21 } else { 20 };
+            let changes = [
+                (nam(), &sender, 100, 70),
+                (btc(), &sender, 100, 70),
+                (nam(), &receiver_1, 0, 10),
+                (btc(), &receiver_1, 0, 30),
+                (nam(), &receiver_2, 0, credit_2),
+            ];
+            for (token, owner, pre, post) in changes {
+                let key = balance_key(&token, owner);
+                wl_storage
+                    .storage
+                    .write(&key, Amount::native_whole(pre).serialize_to_vec())
+                    .expect("write failed");
+                wl_storage
+                    .write_log
+                    .write(&key, Amount::native_whole(post).serialize_to_vec())
+                    .expect("write failed");
+                keys_changed.insert(key);
+            }
+
+            let tx_index = TxIndex::default();
+            let tx = dummy_tx(&wl_storage);
+            let gas_meter = VpGasMeter::new_from_tx_meter(
+                &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+            );
+            let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+            let mut verifiers = BTreeSet::new();
+            verifiers.insert(sender);
+            let ctx = Ctx::new(
+                &ADDRESS,
+                &wl_storage.storage,
+                &wl_storage.write_log,
+                &tx,
+                &tx_index,
+                gas_meter,
+                &keys_changed,
+                &verifiers,
+                vp_wasm_cache,
+            );
+
+            let vp = MultitokenVp { ctx };
+            assert_eq!(
+                vp.validate_tx(&tx, &keys_changed, &verifiers)
+                    .expect("validation failed"),
+                !unbalanced
+            );
+        }
+    }
+
+    #[test]
+    fn test_token_changes_balance() {
+        let mut changes = BalanceDelta::default();
+        changes
+            .record(&nam(), Amount::native_whole(5), Amount::native_whole(8))
+            .unwrap();
+        assert!(!changes.is_net_zero());
+
+        // Minting the credited amount balances the changes
+        changes
+            .record_opposite(
+                &nam(),
+                Amount::native_whole(100),
+                Amount::native_whole(103),
+            )
+            .unwrap();
+        assert!(changes.is_net_zero());
+
+        // As does burning a debited amount
+        changes
+            .record(&nam(), Amount::native_whole(2), Amount::zero())
+            .unwrap();
+        changes
+            .record_opposite(
+                &nam(),
+                Amount::native_whole(103),
+                Amount::native_whole(101),
+            )
+            .unwrap();
+        assert!(changes.is_net_zero());
+    }
+
+    #[test]
+    fn test_allowance_update() {
+        let owner = established_address_1();
+        let spender = established_address_2();
+        let validate = |owner: &Address, spender: &Address| {
+            let mut wl_storage = TestWlStorage::default();
+            let mut keys_changed = BTreeSet::new();
+            let allowance_key = allowance_key(&nam(), owner, spender);
+            wl_storage
+                .write_log
+                .write(
+                    &allowance_key,
+                    Amount::native_whole(10).serialize_to_vec(),
+                )
+                .expect("write failed");
+            keys_changed.insert(allowance_key);
+
+            let tx_index = TxIndex::default();
+            let tx = dummy_tx(&wl_storage);
+            let gas_meter = VpGasMeter::new_from_tx_meter(
+                &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+            );
+            let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+            let verifiers = BTreeSet::new();
+            let ctx = Ctx::new(
+                &ADDRESS,
+                &wl_storage.storage,
+                &wl_storage.write_log,
+                &tx,
+                &tx_index,
+                gas_meter,
+                &keys_changed,
+                &verifiers,
+                vp_wasm_cache,
+            );
+
+            let vp = MultitokenVp { ctx };
+            vp.validate_tx(&tx, &keys_changed, &verifiers)
+                .expect("validation failed")
+        };
+
+        assert!(validate(&owner, &spender));
+        // An owner can't be its own spender
+        assert!(!validate(&owner, &owner));
+    }
 }
@@ -0,0 +1,440 @@
+//! Native VP for the name service
+
+use std::collections::BTreeSet;
+
+use namada_governance::is_proposal_accepted;
+use namada_tx::Tx;
+use namada_vp_env::VpEnv;
+use thiserror::Error;
+
+use crate::account::name_service::{
+    self, is_name_key, is_name_service_key, is_params_key, is_reverse_name_key,
+    name_key, reverse_name_key, NameRecord, NameServiceParams, ADDRESS,
+};
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::token::storage_key::{balance_key, is_any_token_balance_key};
+use crate::token::Amount;
+use crate::types::address::Address;
+use crate::types::storage::{Epoch, Key};
+use crate::vm::WasmCacheAccess;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// Name service functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Name service VP. It checks that the names are registered, renewed and
+/// transferred within the bounds of the governance parameters, that their
+/// fees are credited to the name service account and that the reverse
+/// lookup names are owned by their addresses.
+pub struct NameServiceVp<'a, DB, H, CA>
+where
+    DB: namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: namada_state::StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+}
+
+impl<'a, DB, H, CA> NativeVp for NameServiceVp<'a, DB, H, CA>
+where
+    DB: 'static + namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: 'static + namada_state::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    fn validate_tx(
+        &self,
+        tx_data: &Tx,
+        keys_changed: &BTreeSet<Key>,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let epoch = self.ctx.get_block_epoch()?;
+        let params = name_service::read_params(&self.ctx.pre())?;
+        let mut fees = Amount::zero();
+        for key in keys_changed {
+            if let Some([_, owner]) = is_any_token_balance_key(key) {
+                if *owner != ADDRESS {
+                    continue;
+                }
+                let pre: Amount = self.ctx.read_pre(key)?.unwrap_or_default();
+                let post: Amount = self.ctx.read_post(key)?.unwrap_or_default();
+                if post < pre {
+                    tracing::info!(
+                        "Rejecting a debit of the name service balance in key \
+                         {key}"
+                    );
+                    return Ok(false);
+                }
+            } else if !is_name_service_key(key) {
+                continue;
+            } else if is_params_key(key) {
+                if !self.is_valid_params_change(tx_data, key)? {
+                    return Ok(false);
+                }
+            } else if let Some(name) = is_name_key(key) {
+                let Some(fee) =
+                    self.name_change_fee(name, epoch, &params, verifiers)?
+                else {
+                    tracing::info!("Rejecting a change of the name {name}");
+                    return Ok(false);
+                };
+                let Some(total) = fees.checked_add(fee) else {
+                    return Ok(false);
+                };
+                fees = total;
+            } else if let Some(owner) = is_reverse_name_key(key) {
+                if !self.is_valid_reverse_name(owner, epoch, verifiers)? {
+                    tracing::info!(
+                        "Rejecting a change of the reverse lookup name of \
+                         {owner}"
+                    );
+                    return Ok(false);
+                }
+            } else {
+                return Ok(false);
+            }
+        }
+
+        if fees.is_zero() {
+            return Ok(true);
+        }
+        let native_token = self.ctx.get_native_token()?;
+        let balance_key = balance_key(&native_token, &ADDRESS);
+        let pre: Amount = self.ctx.read_pre(&balance_key)?.unwrap_or_default();
+        let post: Amount =
+            self.ctx.read_post(&balance_key)?.unwrap_or_default();
+        let is_paid = post
+            .checked_sub(pre)
+            .map_or(false, |credited| credited >= fees);
+        if !is_paid {
+            tracing::info!(
+                "Rejecting the name changes whose fees of {} are not paid",
+                fees.to_string_native()
+            );
+        }
+        Ok(is_paid)
+    }
+}
+
+impl<'a, DB, H, CA> NameServiceVp<'a, DB, H, CA>
+where
+    DB: 'static + namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: 'static + namada_state::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Check that the parameters are changed by a governance proposal and
+    /// that the new ones are consistent.
+    fn is_valid_params_change(&self, tx: &Tx, key: &Key) -> Result<bool> {
+        let is_accepted = match tx.data() {
+            Some(data) => is_proposal_accepted(&self.ctx.pre(), data.as_ref())?,
+            None => false,
+        };
+        let params: Option<NameServiceParams> = self.ctx.read_post(key)?;
+        Ok(is_accepted
+            && params
+                .map_or(false, |params| params.min_epochs <= params.max_epochs))
+    }
+
+    /// Get the fee due for the change of the registration of a name, if the
+    /// change is valid.
+    fn name_change_fee(
+        &self,
+        name: &str,
+        epoch: Epoch,
+        params: &NameServiceParams,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<Option<Amount>> {
+        let key = name_key(name);
+        let pre: Option<NameRecord> = self.ctx.read_pre(&key)?;
+        let Some(post) = self.ctx.read_post::<NameRecord>(&key)? else {
+            // The names are never deleted
+            return Ok(None);
+        };
+        let is_within_bounds = |epochs: u64| {
+            epochs >= params.min_epochs
+                && post.expiration.0 - epoch.0 <= params.max_epochs
+        };
+        let epochs = match pre.filter(|pre| !pre.is_expired(epoch)) {
+            // A registration of a free or expired name must be authorized by
+            // its owner
+            None => {
+                if !name_service::is_valid_name(name)
+                    || post.is_expired(epoch)
+                    || !verifiers.contains(&post.owner)
+                {
+                    return Ok(None);
+                }
+                post.expiration.0 - epoch.0
+            }
+            // A transfer must be authorized by the previous owner, and a
+            // renewal can only extend the registration
+            Some(pre) => {
+                if (pre.owner != post.owner && !verifiers.contains(&pre.owner))
+                    || post.expiration < pre.expiration
+                {
+                    return Ok(None);
+                }
+                let epochs = post.expiration.0 - pre.expiration.0;
+                if epochs == 0 {
+                    return Ok(Some(Amount::zero()));
+                }
+                epochs
+            }
+        };
+        if !is_within_bounds(epochs) {
+            return Ok(None);
+        }
+        Ok(params.fee(epochs))
+    }
+
+    /// Check that the reverse lookup name of an address is owned by it, or
+    /// that it's removed either by its owner or after a transfer of the
+    /// name.
+    fn is_valid_reverse_name(
+        &self,
+        owner: &Address,
+        epoch: Epoch,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let key = reverse_name_key(owner);
+        let is_owned_post = |name: &str| -> Result<bool> {
+            let record: Option<NameRecord> =
+                self.ctx.read_post(&name_key(name))?;
+            Ok(record.map_or(false, |record| {
+                record.owner == *owner && !record.is_expired(epoch)
+            }))
+        };
+        match self.ctx.read_post::<String>(&key)? {
+            Some(name) => is_owned_post(&name),
+            None => {
+                if verifiers.contains(owner) {
+                    return Ok(true);
+                }
+                match self.ctx.read_pre::<String>(&key)? {
+                    Some(name) => Ok(!is_owned_post(&name)?),
+                    None => Ok(true),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use borsh_ext::BorshSerializeExt;
+    use namada_gas::TxGasMeter;
+    use namada_state::testing::TestWlStorage;
+    use namada_state::{StorageRead, StorageWrite};
+    use namada_tx::data::TxType;
+    use namada_tx::{Code, Data, Tx};
+
+    use super::*;
+    use crate::core::types::address::nam;
+    use crate::core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use crate::ledger::gas::VpGasMeter;
+    use crate::types::storage::TxIndex;
+    use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
+
+    fn dummy_tx(wl_storage: &TestWlStorage) -> Tx {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.header.chain_id = wl_storage.storage.chain_id.clone();
+        tx.set_code(Code::new(vec![], None));
+        tx.set_data(Data::new(vec![]));
+        tx
+    }
+
+    /// Give the owner some tokens to pay the fees.
+    fn init_storage(owner: &Address) -> TestWlStorage {
+        let mut wl_storage = TestWlStorage::default();
+        wl_storage
+            .storage
+            .write(
+                &balance_key(&nam(), owner),
+                Amount::native_whole(1000).serialize_to_vec(),
+            )
+            .expect("write failed");
+        wl_storage.commit_block().expect("commit failed");
+        wl_storage
+    }
+
+    /// Pay a fee from the owner to the name service account.
+    fn pay_fee(wl_storage: &mut TestWlStorage, owner: &Address, fee: Amount) {
+        let owner_key = balance_key(&nam(), owner);
+        let ns_key = balance_key(&nam(), &ADDRESS);
+        let owner_balance: Amount =
+            wl_storage.read(&owner_key).unwrap().unwrap_or_default();
+        let ns_balance: Amount =
+            wl_storage.read(&ns_key).unwrap().unwrap_or_default();
+        wl_storage
+            .write(&owner_key, owner_balance.checked_sub(fee).unwrap())
+            .unwrap();
+        wl_storage
+            .write(&ns_key, ns_balance.checked_add(fee).unwrap())
+            .unwrap();
+    }
+
+    fn validate(
+        wl_storage: &TestWlStorage,
+        verifiers: BTreeSet<Address>,
+    ) -> bool {
+        let keys_changed = wl_storage.write_log.get_keys();
+        let tx_index = TxIndex::default();
+        let tx = dummy_tx(wl_storage);
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+
+        let vp = NameServiceVp { ctx };
+        vp.validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("validation failed")
+    }
+
+    /// Test that a name is registered if its fee is paid and its owner
+    /// authorizes it.
+    #[test]
+    fn test_register_name() {
+        let owner = established_address_1();
+        let mut wl_storage = init_storage(&owner);
+        let fee =
+            name_service::register_name(&mut wl_storage, "alice", &owner, 10)
+                .unwrap();
+        assert_eq!(fee, Amount::native_whole(10));
+
+        // The fee is not paid
+        assert!(!validate(&wl_storage, [owner.clone()].into()));
+
+        pay_fee(&mut wl_storage, &owner, fee);
+        assert!(validate(&wl_storage, [owner.clone()].into()));
+        // The owner must authorize the registration
+        assert!(!validate(&wl_storage, BTreeSet::new()));
+
+        wl_storage.commit_tx();
+        assert_eq!(
+            name_service::resolve_name(&wl_storage, "alice").unwrap(),
+            Some(owner.clone())
+        );
+        assert_eq!(
+            name_service::reverse_name(&wl_storage, &owner).unwrap(),
+            Some("alice".to_string())
+        );
+
+        // A registered name can't be registered again
+        assert!(name_service::register_name(
+            &mut wl_storage,
+            "alice",
+            &owner,
+            10
+        )
+        .is_err());
+    }
+
+    /// Test that a name is transferred only with the authorization of its
+    /// owner and that the reverse lookup follows it.
+    #[test]
+    fn test_transfer_name() {
+        let owner = established_address_1();
+        let new_owner = established_address_2();
+        let mut wl_storage = init_storage(&owner);
+        let fee =
+            name_service::register_name(&mut wl_storage, "alice", &owner, 10)
+                .unwrap();
+        pay_fee(&mut wl_storage, &owner, fee);
+        wl_storage.commit_tx();
+        wl_storage.commit_block().expect("commit failed");
+
+        let previous =
+            name_service::transfer_name(&mut wl_storage, "alice", &new_owner)
+                .unwrap();
+        assert_eq!(previous, owner);
+        assert!(!validate(&wl_storage, [new_owner.clone()].into()));
+        assert!(validate(&wl_storage, [owner.clone()].into()));
+
+        wl_storage.commit_tx();
+        assert_eq!(
+            name_service::resolve_name(&wl_storage, "alice").unwrap(),
+            Some(new_owner.clone())
+        );
+        assert_eq!(
+            name_service::reverse_name(&wl_storage, &new_owner).unwrap(),
+            Some("alice".to_string())
+        );
+        assert_eq!(
+            name_service::reverse_name(&wl_storage, &owner).unwrap(),
+            None
+        );
+    }
+
+    /// Test the bounds of the renewals and that the name service balance
+    /// can't be debited.
+    #[test]
+    fn test_renew_name() {
+        let owner = established_address_1();
+        let mut wl_storage = init_storage(&owner);
+        let fee =
+            name_service::register_name(&mut wl_storage, "alice", &owner, 10)
+                .unwrap();
+        pay_fee(&mut wl_storage, &owner, fee);
+        wl_storage.commit_tx();
+        wl_storage.commit_block().expect("commit failed");
+
+        let max_epochs = NameServiceParams::default().max_epochs;
+        assert!(
+            name_service::renew_name(&mut wl_storage, "alice", max_epochs)
+                .is_err()
+        );
+        let (payer, fee) =
+            name_service::renew_name(&mut wl_storage, "alice", 5).unwrap();
+        assert_eq!(payer, owner);
+        assert!(!validate(&wl_storage, BTreeSet::new()));
+        pay_fee(&mut wl_storage, &owner, fee);
+        assert!(validate(&wl_storage, BTreeSet::new()));
+        wl_storage.commit_tx();
+        let record = name_service::name_record(&wl_storage, "alice")
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.expiration, Epoch(15));
+
+        // Take the fees back out of the name service account
+        let ns_key = balance_key(&nam(), &ADDRESS);
+        wl_storage.write(&ns_key, Amount::zero()).unwrap();
+        assert!(!validate(&wl_storage, [owner].into()));
+    }
+
+    /// Test that the parameters can only be changed by governance.
+    #[test]
+    fn test_params_change() {
+        let mut wl_storage = init_storage(&established_address_1());
+        let params = NameServiceParams {
+            fee_per_epoch: Amount::native_whole(2),
+            ..Default::default()
+        };
+        wl_storage
+            .write(&name_service::params_key(), params)
+            .unwrap();
+        assert!(!validate(&wl_storage, BTreeSet::new()));
+    }
+}
@@ -0,0 +1,306 @@
+//! Native VP for the non-fungible tokens
+
+use std::collections::BTreeSet;
+
+use namada_tx::Tx;
+use namada_vp_env::VpEnv;
+use thiserror::Error;
+
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::token::non_fungible::{
+    is_any_metadata_key, is_any_owner_key, is_nft_key, metadata_key, owner_key,
+    NftId,
+};
+use crate::types::address::Address;
+use crate::types::storage::Key;
+use crate::vm::WasmCacheAccess;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// Non-fungible tokens functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Non-fungible tokens VP. It checks that the NFTs are minted with the
+/// authorization of their class, that they're transferred and burnt with the
+/// authorization of their owner, and that their metadata is only set when
+/// they're minted and removed when they're burnt.
+pub struct NonFungibleVp<'a, DB, H, CA>
+where
+    DB: namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: namada_state::StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+}
+
+impl<'a, DB, H, CA> NativeVp for NonFungibleVp<'a, DB, H, CA>
+where
+    DB: 'static + namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: 'static + namada_state::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    fn validate_tx(
+        &self,
+        _tx_data: &Tx,
+        keys_changed: &BTreeSet<Key>,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        for key in keys_changed {
+            if !is_nft_key(key) {
+                continue;
+            }
+            let is_valid = if let Some((class, id)) = is_any_owner_key(key) {
+                self.is_valid_owner_change(class, id, verifiers)?
+            } else if let Some((class, id)) = is_any_metadata_key(key) {
+                self.is_valid_metadata_change(class, id)?
+            } else {
+                false
+            };
+            if !is_valid {
+                tracing::info!("Rejecting a change of the NFT key {key}");
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<'a, DB, H, CA> NonFungibleVp<'a, DB, H, CA>
+where
+    DB: 'static + namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: 'static + namada_state::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Check that a mint is authorized by the class of the NFT, and that a
+    /// transfer or a burn is authorized by its owner. A burnt NFT must not
+    /// keep its metadata.
+    fn is_valid_owner_change(
+        &self,
+        class: &Address,
+        id: &str,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let Ok(id) = NftId::new(id) else {
+            return Ok(false);
+        };
+        let key = owner_key(class, &id);
+        let pre: Option<Address> = self.ctx.read_pre(&key)?;
+        let post: Option<Address> = self.ctx.read_post(&key)?;
+        let is_authorized = match &pre {
+            Some(owner) => verifiers.contains(owner),
+            None => post.is_none() || verifiers.contains(class),
+        };
+        if !is_authorized {
+            return Ok(false);
+        }
+        if post.is_none() {
+            let metadata: Option<String> =
+                self.ctx.read_post(&metadata_key(class, &id))?;
+            return Ok(metadata.is_none());
+        }
+        Ok(true)
+    }
+
+    /// Check that the metadata of an NFT is only set when it's minted and
+    /// only removed when it's burnt.
+    fn is_valid_metadata_change(
+        &self,
+        class: &Address,
+        id: &str,
+    ) -> Result<bool> {
+        let Ok(id) = NftId::new(id) else {
+            return Ok(false);
+        };
+        let key = owner_key(class, &id);
+        let pre_owner: Option<Address> = self.ctx.read_pre(&key)?;
+        let post_owner: Option<Address> = self.ctx.read_post(&key)?;
+        match (pre_owner, post_owner) {
+            (None, Some(_)) => Ok(true),
+            (Some(_), None) => {
+                let metadata: Option<String> =
+                    self.ctx.read_post(&metadata_key(class, &id))?;
+                Ok(metadata.is_none())
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use namada_gas::TxGasMeter;
+    use namada_state::testing::TestWlStorage;
+    use namada_state::StorageWrite;
+    use namada_tx::data::TxType;
+    use namada_tx::{Code, Data, Tx};
+
+    use super::*;
+    use crate::core::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+    use crate::ledger::gas::VpGasMeter;
+    use crate::token::non_fungible::{
+        self, BurnNft, MintNft, TransferNft, ADDRESS,
+    };
+    use crate::types::storage::TxIndex;
+    use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
+
+    fn dummy_tx(wl_storage: &TestWlStorage) -> Tx {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.header.chain_id = wl_storage.storage.chain_id.clone();
+        tx.set_code(Code::new(vec![], None));
+        tx.set_data(Data::new(vec![]));
+        tx
+    }
+
+    fn validate(
+        wl_storage: &TestWlStorage,
+        verifiers: BTreeSet<Address>,
+    ) -> bool {
+        let keys_changed = wl_storage.write_log.get_keys();
+        let tx_index = TxIndex::default();
+        let tx = dummy_tx(wl_storage);
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+
+        let vp = NonFungibleVp { ctx };
+        vp.validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("validation failed")
+    }
+
+    fn mint_kitty(class: &Address, owner: &Address) -> MintNft {
+        MintNft {
+            class: class.clone(),
+            id: NftId::new("kitty").unwrap(),
+            owner: owner.clone(),
+            metadata: Some("ipfs://kitty".to_string()),
+        }
+    }
+
+    /// Test that an NFT is minted only with the authorization of its class.
+    #[test]
+    fn test_mint_nft() {
+        let class = established_address_1();
+        let owner = established_address_2();
+        let mut wl_storage = TestWlStorage::default();
+        let mint = mint_kitty(&class, &owner);
+        non_fungible::mint(&mut wl_storage, &mint).unwrap();
+        assert!(!validate(&wl_storage, [owner.clone()].into()));
+        assert!(validate(&wl_storage, [class.clone()].into()));
+        wl_storage.commit_tx();
+
+        assert_eq!(
+            non_fungible::read_owner(&wl_storage, &class, &mint.id).unwrap(),
+            Some(owner)
+        );
+        // An NFT can't be minted twice
+        assert!(non_fungible::mint(&mut wl_storage, &mint).is_err());
+    }
+
+    /// Test that an NFT is transferred only with the authorization of its
+    /// owner and that its metadata can't be changed.
+    #[test]
+    fn test_transfer_nft() {
+        let class = established_address_1();
+        let owner = established_address_2();
+        let target = established_address_3();
+        let mut wl_storage = TestWlStorage::default();
+        let mint = mint_kitty(&class, &owner);
+        non_fungible::mint(&mut wl_storage, &mint).unwrap();
+        wl_storage.commit_tx();
+        wl_storage.commit_block().expect("commit failed");
+
+        let transfer = TransferNft {
+            class: class.clone(),
+            id: mint.id.clone(),
+            source: target.clone(),
+            target: owner.clone(),
+        };
+        assert!(non_fungible::transfer(&mut wl_storage, &transfer).is_err());
+        let transfer = TransferNft {
+            source: owner.clone(),
+            target: target.clone(),
+            ..transfer
+        };
+        non_fungible::transfer(&mut wl_storage, &transfer).unwrap();
+        assert!(!validate(&wl_storage, [class.clone()].into()));
+        assert!(validate(&wl_storage, [owner.clone()].into()));
+        wl_storage.commit_tx();
+        assert_eq!(
+            non_fungible::read_owner(&wl_storage, &class, &mint.id).unwrap(),
+            Some(target.clone())
+        );
+
+        wl_storage
+            .write(&metadata_key(&class, &mint.id), "ipfs://doggy".to_string())
+            .unwrap();
+        assert!(!validate(&wl_storage, [class, owner, target].into()));
+    }
+
+    /// Test that an NFT is burnt only with the authorization of its owner
+    /// and together with its metadata.
+    #[test]
+    fn test_burn_nft() {
+        let class = established_address_1();
+        let owner = established_address_2();
+        let mut wl_storage = TestWlStorage::default();
+        let mint = mint_kitty(&class, &owner);
+        non_fungible::mint(&mut wl_storage, &mint).unwrap();
+        wl_storage.commit_tx();
+        wl_storage.commit_block().expect("commit failed");
+
+        // The metadata must be removed with the owner
+        wl_storage.delete(&owner_key(&class, &mint.id)).unwrap();
+        assert!(!validate(&wl_storage, [owner.clone()].into()));
+        wl_storage.drop_tx();
+
+        let burn = BurnNft {
+            class: class.clone(),
+            id: mint.id.clone(),
+            owner: owner.clone(),
+        };
+        non_fungible::burn(&mut wl_storage, &burn).unwrap();
+        assert!(!validate(&wl_storage, [class.clone()].into()));
+        assert!(validate(&wl_storage, [owner].into()));
+        wl_storage.commit_tx();
+        assert_eq!(
+            non_fungible::read_metadata(&wl_storage, &class, &mint.id).unwrap(),
+            None
+        );
+    }
+
+    /// Test that the keys of the NFTs must be well-formed.
+    #[test]
+    fn test_invalid_nft_keys() {
+        let class = established_address_1();
+        let mut wl_storage = TestWlStorage::default();
+        let key = non_fungible::class_prefix(&class)
+            .push(&"supply".to_string())
+            .unwrap();
+        wl_storage.write(&key, 1_u64).unwrap();
+        assert!(!validate(&wl_storage, [class].into()));
+    }
+}
@@ -0,0 +1,171 @@
+//! Native VP for the PoS slash pool
+
+use std::collections::BTreeSet;
+
+use namada_tx::Tx;
+use namada_vp_env::VpEnv;
+use thiserror::Error;
+
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::token::storage_key::is_any_token_balance_key;
+use crate::token::Amount;
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::{Key, KeySeg};
+use crate::vm::WasmCacheAccess;
+
+/// The slash pool address
+pub const ADDRESS: Address = Address::Internal(InternalAddress::PosSlashPool);
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// Slash pool functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Slash pool VP. Transactions may only credit the slash pool; its funds
+/// can only be moved out by the protocol when executing governance
+/// proposals.
+pub struct SlashPoolVp<'a, DB, H, CA>
+where
+    DB: namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: namada_state::StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+}
+
+impl<'a, DB, H, CA> NativeVp for SlashPoolVp<'a, DB, H, CA>
+where
+    DB: 'static + namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: 'static + namada_state::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    fn validate_tx(
+        &self,
+        _tx_data: &Tx,
+        keys_changed: &BTreeSet<Key>,
+        _verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        for key in keys_changed {
+            if let Some([_, owner]) = is_any_token_balance_key(key) {
+                if *owner != ADDRESS {
+                    continue;
+                }
+                let pre: Amount = self.ctx.read_pre(key)?.unwrap_or_default();
+                let post: Amount = self.ctx.read_post(key)?.unwrap_or_default();
+                if post < pre {
+                    tracing::info!(
+                        "Rejecting a debit of the slash pool balance in key \
+                         {key}"
+                    );
+                    return Ok(false);
+                }
+            } else if key.segments.first() == Some(&ADDRESS.to_db_key()) {
+                // The slash pool doesn't own any other storage
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use borsh_ext::BorshSerializeExt;
+    use namada_gas::TxGasMeter;
+    use namada_state::testing::TestWlStorage;
+    use namada_tx::data::TxType;
+    use namada_tx::{Code, Data, Tx};
+
+    use super::*;
+    use crate::core::types::address::nam;
+    use crate::core::types::address::testing::established_address_1;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::token::storage_key::balance_key;
+    use crate::types::storage::TxIndex;
+    use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
+
+    fn dummy_tx(wl_storage: &TestWlStorage) -> Tx {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.header.chain_id = wl_storage.storage.chain_id.clone();
+        tx.set_code(Code::new(vec![], None));
+        tx.set_data(Data::new(vec![]));
+        tx
+    }
+
+    /// Move 10 tokens between the slash pool and another account, in the
+    /// given direction, and run the VP on the resulting changes.
+    fn validate_flow(into_pool: bool) -> bool {
+        let mut wl_storage = TestWlStorage::default();
+        let mut keys_changed = BTreeSet::new();
+
+        let other = established_address_1();
+        let other_key = balance_key(&nam(), &other);
+        let pool_key = balance_key(&nam(), &ADDRESS);
+        let initial = Amount::native_whole(100);
+        for key in [&other_key, &pool_key] {
+            wl_storage
+                .storage
+                .write(key, initial.serialize_to_vec())
+                .expect("write failed");
+        }
+
+        let (debited, credited) = if into_pool {
+            (&other_key, &pool_key)
+        } else {
+            (&pool_key, &other_key)
+        };
+        wl_storage
+            .write_log
+            .write(debited, Amount::native_whole(90).serialize_to_vec())
+            .expect("write failed");
+        wl_storage
+            .write_log
+            .write(credited, Amount::native_whole(110).serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(other_key);
+        keys_changed.insert(pool_key);
+
+        let tx_index = TxIndex::default();
+        let tx = dummy_tx(&wl_storage);
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let verifiers = BTreeSet::new();
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+
+        let vp = SlashPoolVp { ctx };
+        vp.validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("validation failed")
+    }
+
+    #[test]
+    fn test_credit_slash_pool() {
+        assert!(validate_flow(true));
+    }
+
+    #[test]
+    fn test_debit_slash_pool() {
+        assert!(!validate_flow(false));
+    }
+}
@@ -0,0 +1,284 @@
+//! Native VP for the token factory
+
+use std::collections::BTreeSet;
+
+use namada_tx::Tx;
+use namada_vp_env::VpEnv;
+use thiserror::Error;
+
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::token::factory::{
+    is_any_origin_key, is_token_factory_key, origin_key, TokenOrigin,
+    MAX_SALT_LEN, TOKEN_VP_NAME,
+};
+use crate::token::storage_key::{denom_key, metadata_key};
+use crate::token::{Denomination, Metadata};
+use crate::types::address::Address;
+use crate::types::storage::Key;
+use crate::vm::WasmCacheAccess;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// Token factory functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Token factory VP. It checks that a token is created with the authorization
+/// of its creator, at the address derived from its origin, with the token VP
+/// and a denomination, and that its origin is never changed after that.
+pub struct TokenFactoryVp<'a, DB, H, CA>
+where
+    DB: namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: namada_state::StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+}
+
+impl<'a, DB, H, CA> NativeVp for TokenFactoryVp<'a, DB, H, CA>
+where
+    DB: 'static + namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: 'static + namada_state::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    fn validate_tx(
+        &self,
+        _tx_data: &Tx,
+        keys_changed: &BTreeSet<Key>,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        for key in keys_changed {
+            if !is_token_factory_key(key) {
+                continue;
+            }
+            let is_valid = match is_any_origin_key(key) {
+                Some(token) => self.is_valid_creation(token, verifiers)?,
+                None => false,
+            };
+            if !is_valid {
+                tracing::info!(
+                    "Rejecting a change of the token factory key {key}"
+                );
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<'a, DB, H, CA> TokenFactoryVp<'a, DB, H, CA>
+where
+    DB: 'static + namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: 'static + namada_state::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Check that the origin of a token is only written when the token is
+    /// created, that it's authorized by its creator and that the token is
+    /// created at the address derived from it, with the token VP, a
+    /// denomination and valid metadata.
+    fn is_valid_creation(
+        &self,
+        token: &Address,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let key = origin_key(token);
+        if self.ctx.has_key_pre(&key)? {
+            return Ok(false);
+        }
+        let Some(origin) = self.ctx.read_post::<TokenOrigin>(&key)? else {
+            return Ok(false);
+        };
+        if origin.salt.len() > MAX_SALT_LEN
+            || origin.token_address() != *token
+            || !verifiers.contains(&origin.creator)
+        {
+            return Ok(false);
+        }
+
+        // The token's account must be initialized by this tx with the token
+        // VP
+        let vp_key = Key::validity_predicate(token);
+        if self.ctx.has_key_pre(&vp_key)? {
+            return Ok(false);
+        }
+        let token_vp_hash =
+            self.ctx.read_bytes_pre(&Key::wasm_hash(TOKEN_VP_NAME))?;
+        let vp_hash = self.ctx.read_bytes_post(&vp_key)?;
+        if token_vp_hash.is_none() || vp_hash != token_vp_hash {
+            return Ok(false);
+        }
+
+        let denom: Option<Denomination> =
+            self.ctx.read_post(&denom_key(token))?;
+        if denom.is_none() {
+            return Ok(false);
+        }
+        let metadata: Option<Metadata> =
+            self.ctx.read_post(&metadata_key(token))?;
+        Ok(metadata.map_or(true, |metadata| metadata.is_valid()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use namada_gas::TxGasMeter;
+    use namada_state::testing::TestWlStorage;
+    use namada_state::StorageWrite;
+    use namada_tx::data::TxType;
+    use namada_tx::{Code, Data, Tx};
+
+    use super::*;
+    use crate::core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use crate::ledger::gas::VpGasMeter;
+    use crate::token::factory::{self, CreateToken, ADDRESS};
+    use crate::token::Amount;
+    use crate::types::hash::Hash;
+    use crate::types::storage::TxIndex;
+    use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
+
+    fn dummy_tx(wl_storage: &TestWlStorage) -> Tx {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.header.chain_id = wl_storage.storage.chain_id.clone();
+        tx.set_code(Code::new(vec![], None));
+        tx.set_data(Data::new(vec![]));
+        tx
+    }
+
+    fn validate(
+        wl_storage: &TestWlStorage,
+        verifiers: BTreeSet<Address>,
+    ) -> bool {
+        let keys_changed = wl_storage.write_log.get_keys();
+        let tx_index = TxIndex::default();
+        let tx = dummy_tx(wl_storage);
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+
+        let vp = TokenFactoryVp { ctx };
+        vp.validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("validation failed")
+    }
+
+    /// Write the hash of the token VP and return it
+    fn init_token_vp(wl_storage: &mut TestWlStorage) -> Hash {
+        let vp_hash = Hash::sha256(b"vp_token");
+        wl_storage
+            .write_bytes(&Key::wasm_hash(TOKEN_VP_NAME), vp_hash)
+            .unwrap();
+        wl_storage.commit_tx();
+        vp_hash
+    }
+
+    fn create_coin(creator: &Address) -> CreateToken {
+        CreateToken {
+            origin: TokenOrigin {
+                creator: creator.clone(),
+                salt: b"coin".to_vec(),
+            },
+            denom: 6u8.into(),
+            metadata: None,
+            initial_supply: Amount::native_whole(1_000),
+            vp_code_hash: Hash::zero(),
+        }
+    }
+
+    /// Test that a token is created only with the authorization of its
+    /// creator and with the token VP, and that it can't be created twice.
+    #[test]
+    fn test_create_token() {
+        let creator = established_address_1();
+        let mut wl_storage = TestWlStorage::default();
+        let vp_hash = init_token_vp(&mut wl_storage);
+        let create = create_coin(&creator);
+        let token = create.origin.token_address();
+
+        // The token account isn't initialized
+        factory::create_token(&mut wl_storage, &create).unwrap();
+        assert!(!validate(&wl_storage, [creator.clone()].into()));
+        wl_storage.drop_tx();
+
+        wl_storage.write_log.init_account_at(&token, Hash::zero());
+        factory::create_token(&mut wl_storage, &create).unwrap();
+        assert!(!validate(&wl_storage, [creator.clone()].into()));
+        wl_storage.drop_tx();
+
+        wl_storage.write_log.init_account_at(&token, vp_hash);
+        assert_eq!(
+            factory::create_token(&mut wl_storage, &create).unwrap(),
+            token
+        );
+        assert!(!validate(&wl_storage, [established_address_2()].into()));
+        assert!(validate(&wl_storage, [creator.clone()].into()));
+        wl_storage.commit_tx();
+
+        assert_eq!(
+            factory::read_origin(&wl_storage, &token).unwrap(),
+            Some(create.origin.clone())
+        );
+        assert_eq!(
+            crate::token::read_balance(&wl_storage, &token, &creator).unwrap(),
+            create.initial_supply
+        );
+        // A token can't be created twice
+        assert!(factory::create_token(&mut wl_storage, &create).is_err());
+        wl_storage
+            .write(&factory::origin_key(&token), &create.origin)
+            .unwrap();
+        assert!(!validate(&wl_storage, [creator].into()));
+    }
+
+    /// Test that the origin of a token must match its address.
+    #[test]
+    fn test_invalid_token_origin() {
+        let creator = established_address_1();
+        let mut wl_storage = TestWlStorage::default();
+        let vp_hash = init_token_vp(&mut wl_storage);
+        let create = create_coin(&creator);
+        let token = create.origin.token_address();
+
+        // The token is claimed by another creator
+        let other = established_address_2();
+        wl_storage.write_log.init_account_at(&token, vp_hash);
+        let origin = TokenOrigin {
+            creator: other.clone(),
+            salt: create.origin.salt.clone(),
+        };
+        wl_storage
+            .write(&factory::origin_key(&token), &origin)
+            .unwrap();
+        crate::token::write_denom(&mut wl_storage, &token, create.denom)
+            .unwrap();
+        assert!(!validate(&wl_storage, [creator, other].into()));
+        wl_storage.drop_tx();
+
+        let key = factory::origin_key(&token)
+            .push(&"other".to_string())
+            .unwrap();
+        wl_storage.write(&key, 0u8).unwrap();
+        assert!(!validate(&wl_storage, BTreeSet::new()));
+    }
+}
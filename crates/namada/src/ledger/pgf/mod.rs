@@ -11,7 +11,9 @@ use namada_tx::Tx;
 use thiserror::Error;
 
 use crate::ledger::native_vp;
-use crate::ledger::native_vp::{Ctx, NativeVp};
+use crate::ledger::native_vp::{Ctx, NativeVp, VpEnv};
+use crate::token::storage_key::balance_key;
+use crate::token::Amount;
 use crate::types::address::{Address, InternalAddress};
 use crate::types::storage::Key;
 use crate::vm::WasmCacheAccess;
@@ -98,6 +100,7 @@ where
                     Ok(is_valid)
                 }
                 KeyType::FUNDINGS => Ok(false),
+                KeyType::CLAIMS => self.is_valid_claim(key, verifiers),
                 KeyType::PGF_INFLATION_RATE
                 | KeyType::STEWARD_INFLATION_RATE => {
                     self.is_valid_parameter_change(tx_data)
@@ -107,7 +110,7 @@ where
             };
             result.unwrap_or(false)
         });
-        Ok(result)
+        Ok(result && self.is_valid_claims_transfer(keys_changed)?)
     }
 }
 
@@ -125,6 +128,78 @@ where
             None => Ok(false),
         }
     }
+
+    /// Validate a claim of a pgf retro funding, that must be signed by its
+    /// target and within the claimable amount
+    pub fn is_valid_claim(
+        &self,
+        key: &Key,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let Some(target) = pgf_storage::is_claims_key(key) else {
+            return Ok(false);
+        };
+        if !verifiers.contains(target) {
+            return Ok(false);
+        }
+        let Some(pre) = pgf::storage::get_claim(&self.ctx.pre(), target)?
+        else {
+            // retro claims can only be added via governance proposals
+            return Ok(false);
+        };
+        let post = pgf::storage::get_claim(&self.ctx.post(), target)?;
+        let epoch = self.ctx.get_block_epoch()?;
+        let post_remaining = post
+            .as_ref()
+            .map(|claim| claim.remaining)
+            .unwrap_or_default();
+        let expected = pre
+            .remaining
+            .checked_sub(post_remaining)
+            .and_then(|claimed| pre.claim(epoch, claimed));
+        Ok(match expected {
+            // a fully claimed funding is removed
+            Some(expected) if expected.remaining.is_zero() => post.is_none(),
+            Some(expected) => post == Some(expected),
+            None => false,
+        })
+    }
+
+    /// Check that the pgf balance of the native token is debited by exactly
+    /// the amount claimed from the pgf retro fundings, if any
+    fn is_valid_claims_transfer(
+        &self,
+        keys_changed: &BTreeSet<Key>,
+    ) -> Result<bool> {
+        let mut claims_changed = false;
+        let mut claimed = Amount::zero();
+        for target in keys_changed.iter().filter_map(pgf_storage::is_claims_key)
+        {
+            claims_changed = true;
+            let pre = pgf::storage::get_claim(&self.ctx.pre(), target)?
+                .map(|claim| claim.remaining)
+                .unwrap_or_default();
+            let post = pgf::storage::get_claim(&self.ctx.post(), target)?
+                .map(|claim| claim.remaining)
+                .unwrap_or_default();
+            let Some(amount) = pre
+                .checked_sub(post)
+                .and_then(|amount| claimed.checked_add(amount))
+            else {
+                return Ok(false);
+            };
+            claimed = amount;
+        }
+        if !claims_changed {
+            return Ok(true);
+        }
+        let native_token = self.ctx.get_native_token()?;
+        let balance_key = balance_key(&native_token, &ADDRESS);
+        let pre: Amount = self.ctx.read_pre(&balance_key)?.unwrap_or_default();
+        let post: Amount =
+            self.ctx.read_post(&balance_key)?.unwrap_or_default();
+        Ok(pre.checked_sub(post) == Some(claimed))
+    }
 }
 
 #[allow(clippy::upper_case_acronyms)]
@@ -135,6 +210,8 @@ enum KeyType {
     #[allow(non_camel_case_types)]
     FUNDINGS,
     #[allow(non_camel_case_types)]
+    CLAIMS,
+    #[allow(non_camel_case_types)]
     PGF_INFLATION_RATE,
     #[allow(non_camel_case_types)]
     STEWARD_INFLATION_RATE,
@@ -150,6 +227,8 @@ impl From<&Key> for KeyType {
             Self::STEWARDS
         } else if pgf_storage::is_fundings_key(key) {
             KeyType::FUNDINGS
+        } else if pgf_storage::is_claims_key(key).is_some() {
+            KeyType::CLAIMS
         } else if pgf_storage::is_pgf_inflation_rate_key(key) {
             Self::PGF_INFLATION_RATE
         } else if pgf_storage::is_steward_inflation_rate_key(key) {
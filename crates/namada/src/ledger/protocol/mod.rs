@@ -6,13 +6,14 @@ use eyre::{eyre, WrapErr};
 use masp_primitives::transaction::Transaction;
 use namada_core::types::hash::Hash;
 use namada_core::types::storage::Key;
-use namada_gas::TxGasMeter;
+use namada_gas::{Gas, TxGasMeter};
 use namada_sdk::tx::TX_TRANSFER_WASM;
 use namada_state::wl_storage::WriteLogAndStorage;
 use namada_state::StorageRead;
 use namada_tx::data::protocol::ProtocolTxType;
 use namada_tx::data::{
-    DecryptedTx, GasLimit, TxResult, TxType, VpsResult, WrapperTx,
+    DecryptedTx, GasLimit, InsufficientFunds, TxErrorData, TxResult, TxType,
+    VpsResult, WrapperTx,
 };
 use namada_tx::{Section, Tx};
 use namada_vote_ext::EthereumTxData;
@@ -27,14 +28,17 @@ use crate::ledger::native_vp::ethereum_bridge::vp::EthBridge;
 use crate::ledger::native_vp::ibc::Ibc;
 use crate::ledger::native_vp::masp::MaspVp;
 use crate::ledger::native_vp::multitoken::MultitokenVp;
+use crate::ledger::native_vp::name_service::{self, NameServiceVp};
+use crate::ledger::native_vp::non_fungible::{self, NonFungibleVp};
 use crate::ledger::native_vp::parameters::{self, ParametersVp};
+use crate::ledger::native_vp::slash_pool::{self, SlashPoolVp};
 use crate::ledger::native_vp::{self, NativeVp};
 use crate::ledger::pgf::PgfVp;
 use crate::ledger::pos::{self, PosVP};
 use crate::state::write_log::WriteLog;
 use crate::state::{DBIter, State, StorageHasher, WlStorage, DB};
-use crate::token::Amount;
-use crate::types::address::{Address, InternalAddress};
+use crate::token::{Amount, DenominatedAmount};
+use crate::types::address::{Address, InternalAddress, FEE_ESCROW};
 use crate::types::storage;
 use crate::types::storage::TxIndex;
 use crate::vm::wasm::{TxCache, VpCache};
@@ -61,6 +65,8 @@ pub enum Error {
     GasError(String),
     #[error("Error while processing transaction's fees: {0}")]
     FeeError(String),
+    #[error("Error while processing transaction's fees: {0}")]
+    InsufficientFeeBalance(String, InsufficientFunds),
     #[error("Invalid transaction signature")]
     InvalidTxSignature,
     #[error(
@@ -79,6 +85,8 @@ pub enum Error {
     PosNativeVpRuntime,
     #[error("Parameters native VP: {0}")]
     ParametersNativeVpError(parameters::Error),
+    #[error("Slash pool native VP error: {0}")]
+    SlashPoolNativeVpError(slash_pool::Error),
     #[error("IBC Token native VP: {0}")]
     MultitokenNativeVpError(crate::ledger::native_vp::multitoken::Error),
     #[error("Governance native VP error: {0}")]
@@ -91,6 +99,12 @@ pub enum Error {
     BridgePoolNativeVpError(native_vp::ethereum_bridge::bridge_pool_vp::Error),
     #[error("Non usable tokens native VP error: {0:?}")]
     NutNativeVpError(native_vp::ethereum_bridge::nut::Error),
+    #[error("Name service native VP error: {0}")]
+    NameServiceNativeVpError(name_service::Error),
+    #[error("Non-fungible tokens native VP error: {0}")]
+    NonFungibleNativeVpError(non_fungible::Error),
+    #[error("Token factory native VP error: {0}")]
+    TokenFactoryNativeVpError(token_factory::Error),
     #[error("MASP native VP error: {0}")]
     MaspNativeVpError(native_vp::masp::Error),
     #[error("Access to an internal address {0:?} is forbidden")]
@@ -99,6 +113,27 @@ pub enum Error {
     DisallowedTx,
 }
 
+impl Error {
+    /// Get the machine-readable data of this error, for the clients.
+    pub fn tx_error_data(&self) -> TxErrorData {
+        match self {
+            Error::MissingSection(_) => TxErrorData::MissingSection,
+            Error::TxRunnerError(_) => TxErrorData::WasmRuntimeError,
+            Error::GasError(_) => TxErrorData::GasExhausted,
+            Error::FeeError(_) | Error::FeeUnshieldingError(_) => {
+                TxErrorData::FeeError
+            }
+            Error::InsufficientFeeBalance(_, funds) => {
+                TxErrorData::InsufficientFunds(funds.clone())
+            }
+            Error::InvalidTxSignature => TxErrorData::InvalidSig,
+            Error::ReplayAttempt(_) => TxErrorData::ReplayTx,
+            Error::DisallowedTx => TxErrorData::DisallowedTx,
+            _ => TxErrorData::Other,
+        }
+    }
+}
+
 /// Shell parameters for running wasm transactions.
 #[allow(missing_docs)]
 pub struct ShellParams<'a, CA, WLS>
@@ -241,7 +276,7 @@ pub(crate) fn apply_wrapper_tx<'a, D, H, CA, WLS>(
     fee_unshield_transaction: Option<Transaction>,
     tx_bytes: &[u8],
     mut shell_params: ShellParams<'a, CA, WLS>,
-    wrapper_args: Option<&mut WrapperArgs>,
+    mut wrapper_args: Option<&mut WrapperArgs>,
 ) -> Result<BTreeSet<Key>>
 where
     CA: 'static + WasmCacheAccess + Sync,
@@ -263,14 +298,24 @@ where
         fee_unshield_transaction,
         &mut shell_params,
         &mut changed_keys,
-        wrapper_args,
+        wrapper_args.as_deref_mut(),
     )?;
 
     // Account for gas
-    shell_params
-        .tx_gas_meter
-        .add_wrapper_gas(tx_bytes)
-        .map_err(|err| Error::GasError(err.to_string()))?;
+    if let Err(err) = shell_params.tx_gas_meter.add_wrapper_gas(tx_bytes) {
+        // The inner tx won't be applied, so settle the escrowed fee right
+        // away: the wrapper consumed its whole gas limit
+        if let Some(WrapperArgs { block_proposer, .. }) = wrapper_args {
+            settle_fee(
+                shell_params.wl_storage,
+                block_proposer,
+                wrapper,
+                Gas::default(),
+            )?;
+            shell_params.wl_storage.write_log_mut().commit_tx();
+        }
+        return Err(Error::GasError(err.to_string()));
+    }
 
     Ok(changed_keys)
 }
@@ -408,8 +453,10 @@ where
     Ok(())
 }
 
-/// Perform the actual transfer of fess from the fee payer to the block
-/// proposer.
+/// Perform the actual transfer of fees from the fee payer to the fee escrow,
+/// where they're held until the inner tx is applied and the fees are settled
+/// with [`settle_fee`]. If the fee payer cannot pay the entire amount of the
+/// fees, all of its available balance is transferred to the block proposer.
 pub fn transfer_fee<WLS>(
     wl_storage: &mut WLS,
     block_proposer: &Address,
@@ -438,7 +485,7 @@ where
                     wl_storage,
                     &wrapper.fee.token,
                     &wrapper.fee_payer(),
-                    block_proposer,
+                    &FEE_ESCROW,
                     fees,
                 )
                 .map_err(|e| Error::FeeError(e.to_string()))
@@ -462,11 +509,17 @@ where
                 )
                 .map_err(|e| Error::FeeError(e.to_string()))?;
 
-                Err(Error::FeeError(
+                Err(Error::InsufficientFeeBalance(
                     "Transparent balance of wrapper's signer was insufficient \
                      to pay fee. All the available transparent funds have \
                      been moved to the block proposer"
                         .to_string(),
+                    InsufficientFunds {
+                        owner: wrapper.fee_payer(),
+                        token: wrapper.fee.token.clone(),
+                        needed: fees,
+                        available: balance,
+                    },
                 ))
             }
         }
@@ -483,6 +536,66 @@ where
     }
 }
 
+/// The settlement of the escrowed fee of a wrapper tx
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeSettlement {
+    /// The fee paid to the block proposer for the gas used
+    pub paid: DenominatedAmount,
+    /// The fee refunded to the fee payer for the unused gas
+    pub refund: DenominatedAmount,
+}
+
+/// Settle the fee escrowed by [`transfer_fee`] for the given wrapper, given
+/// the gas left unused by the wrapper and its inner tx. The fee for the gas
+/// used is paid to the proposer of the block that included the wrapper and
+/// the rest is refunded to the fee payer.
+pub fn settle_fee<WLS>(
+    wl_storage: &mut WLS,
+    block_proposer: &Address,
+    wrapper: &WrapperTx,
+    unused_gas: Gas,
+) -> Result<FeeSettlement>
+where
+    WLS: WriteLogAndStorage + StorageRead,
+{
+    let used_gas = Gas::from(wrapper.gas_limit)
+        .checked_sub(unused_gas)
+        .unwrap_or_default();
+    let fees = wrapper
+        .get_tx_fee()
+        .map_err(|e| Error::FeeError(e.to_string()))?;
+    let paid = wrapper
+        .get_used_gas_fee(used_gas)
+        .map_err(|e| Error::FeeError(e.to_string()))?;
+    let refund = fees.checked_sub(paid).ok_or_else(|| {
+        Error::FeeError(
+            "The fee for the gas used is higher than the escrowed fee"
+                .to_string(),
+        )
+    })?;
+
+    for (target, amount) in [
+        (block_proposer.clone(), paid),
+        (wrapper.fee_payer(), refund),
+    ] {
+        let amount = crate::token::denom_to_amount(
+            amount,
+            &wrapper.fee.token,
+            wl_storage,
+        )
+        .map_err(|e| Error::FeeError(e.to_string()))?;
+        token_transfer(
+            wl_storage,
+            &wrapper.fee.token,
+            &FEE_ESCROW,
+            &target,
+            amount,
+        )?;
+    }
+
+    Ok(FeeSettlement { paid, refund })
+}
+
 /// Transfer `token` from `src` to `dest`. Returns an `Err` if `src` has
 /// insufficient balance or if the transfer the `dest` would overflow (This can
 /// only happen if the total supply doesn't fit in `token::Amount`). Contrary to
@@ -530,7 +643,15 @@ where
                 )),
             }
         }
-        None => Err(Error::FeeError("Insufficient source balance".to_string())),
+        None => Err(Error::InsufficientFeeBalance(
+            "Insufficient source balance".to_string(),
+            InsufficientFunds {
+                owner: src.clone(),
+                token: token.clone(),
+                needed: amount,
+                available: src_balance,
+            },
+        )),
     }
 }
 
@@ -556,8 +677,14 @@ where
     if balance.checked_sub(fees).is_some() {
         Ok(())
     } else {
-        Err(Error::FeeError(
+        Err(Error::InsufficientFeeBalance(
             "Insufficient transparent balance to pay fees".to_string(),
+            InsufficientFunds {
+                owner: wrapper.fee_payer(),
+                token: wrapper.fee.token.clone(),
+                needed: fees,
+                available: balance,
+            },
         ))
     }
 }
@@ -726,6 +853,14 @@ where
             )
             .map_err(Error::ProtocolTxError)
         }
+        EthereumTxData::WithdrawalVetoVext(ext) => {
+            transactions::withdrawal_vetoes::apply_derived_tx(storage, ext)
+                .map_err(Error::ProtocolTxError)
+        }
+        EthereumTxData::CheckpointVext(ext) => {
+            transactions::checkpoints::apply_derived_tx(storage, ext)
+                .map_err(Error::ProtocolTxError)
+        }
         EthereumTxData::EthereumEvents(_)
         | EthereumTxData::BridgePool(_)
         | EthereumTxData::ValidatorSetUpdate(_) => {
@@ -847,6 +982,7 @@ where
         .par_iter()
         .try_fold(VpsResult::default, |mut result, addr| {
             let mut gas_meter = VpGasMeter::new_from_tx_meter(tx_gas_meter);
+            let mut wasm_vp_code_hash = None;
             let accept = match &addr {
                 Address::Implicit(_) | Address::Established(_) => {
                     let (vp_hash, gas) = storage
@@ -858,6 +994,7 @@ where
                     let Some(vp_code_hash) = vp_hash else {
                         return Err(Error::MissingAddress(addr.clone()));
                     };
+                    wasm_vp_code_hash = Some(vp_code_hash);
 
                     // NOTE: because of the whitelisted gas and the gas
                     // metering for the exposed vm
@@ -947,16 +1084,16 @@ where
                                 (result, parameters.ctx.sentinel.into_inner())
                             }
                             InternalAddress::PosSlashPool => {
+                                let slash_pool = SlashPoolVp { ctx };
+                                let result = slash_pool
+                                    .validate_tx(tx, &keys_changed, &verifiers)
+                                    .map_err(Error::SlashPoolNativeVpError);
                                 // Take the gas meter and the sentinel
                                 // back
                                 // out of the context
-                                gas_meter = ctx.gas_meter.into_inner();
-                                (
-                                    Err(Error::AccessForbidden(
-                                        (*internal_addr).clone(),
-                                    )),
-                                    ctx.sentinel.into_inner(),
-                                )
+                                gas_meter =
+                                    slash_pool.ctx.gas_meter.into_inner();
+                                (result, slash_pool.ctx.sentinel.into_inner())
                             }
                             InternalAddress::Governance => {
                                 let governance = GovernanceVp { ctx };
@@ -1058,11 +1195,48 @@ where
                                 gas_meter = masp.ctx.gas_meter.into_inner();
                                 (result, masp.ctx.sentinel.into_inner())
                             }
+                            InternalAddress::NameService => {
+                                let name_service = NameServiceVp { ctx };
+                                let result = name_service
+                                    .validate_tx(tx, &keys_changed, &verifiers)
+                                    .map_err(Error::NameServiceNativeVpError);
+                                gas_meter =
+                                    name_service.ctx.gas_meter.into_inner();
+                                (result, name_service.ctx.sentinel.into_inner())
+                            }
+                            InternalAddress::Nft => {
+                                let non_fungible = NonFungibleVp { ctx };
+                                let result = non_fungible
+                                    .validate_tx(tx, &keys_changed, &verifiers)
+                                    .map_err(Error::NonFungibleNativeVpError);
+                                gas_meter =
+                                    non_fungible.ctx.gas_meter.into_inner();
+                                (result, non_fungible.ctx.sentinel.into_inner())
+                            }
+                            InternalAddress::TokenFactory => {
+                                let token_factory = TokenFactoryVp { ctx };
+                                let result = token_factory
+                                    .validate_tx(tx, &keys_changed, &verifiers)
+                                    .map_err(Error::TokenFactoryNativeVpError);
+                                gas_meter =
+                                    token_factory.ctx.gas_meter.into_inner();
+                                (
+                                    result,
+                                    token_factory.ctx.sentinel.into_inner(),
+                                )
+                            }
+                            InternalAddress::FeeEscrow => {
+                                // The escrowed fees are only moved by the
+                                // protocol, reject any change from a tx
+                                gas_meter = ctx.gas_meter.into_inner();
+                                (Ok(false), ctx.sentinel.into_inner())
+                            }
                         };
 
-                    accepted.map_err(|err| {
-                        // No need to check invalid sig because internal vps
-                        // don't check the signature
+                    // The native VPs don't give a reason for a rejection
+                    accepted.map(|accepted| (accepted, None)).map_err(|err| {
+                        // No need to check invalid sig because internal
+                        // vps don't check the signature
                         if sentinel.is_out_of_gas() {
                             Error::GasError(err.to_string())
                         } else {
@@ -1073,11 +1247,19 @@ where
             };
 
             match accept {
-                Ok(accepted) => {
+                Ok((accepted, rejection)) => {
                     if accepted {
                         result.accepted_vps.insert(addr.clone());
                     } else {
                         result.rejected_vps.insert(addr.clone());
+                        if let Some(rejection) = rejection {
+                            tracing::debug!(
+                                %addr,
+                                %rejection,
+                                "The VP rejected the transaction"
+                            );
+                            result.rejections.insert(addr.clone(), rejection);
+                        }
                     }
                 }
                 Err(err) => match err {
@@ -1103,6 +1285,12 @@ where
                 },
             }
 
+            if let Some(code_hash) = wasm_vp_code_hash {
+                result.wasm_vps.insert(
+                    addr.clone(),
+                    (code_hash, gas_meter.get_vp_consumed_gas()),
+                );
+            }
             result
                 .gas_used
                 .set(gas_meter)
@@ -1129,7 +1317,11 @@ fn merge_vp_results(
     rejected_vps.extend(b.rejected_vps);
     let mut errors = a.errors;
     errors.append(&mut b.errors);
+    let mut rejections = a.rejections;
+    rejections.append(&mut b.rejections);
     let invalid_sig = a.invalid_sig || b.invalid_sig;
+    let mut wasm_vps = a.wasm_vps;
+    wasm_vps.append(&mut b.wasm_vps);
     let mut gas_used = a.gas_used;
 
     gas_used
@@ -1141,7 +1333,9 @@ fn merge_vp_results(
         rejected_vps,
         gas_used,
         errors,
+        rejections,
         invalid_sig,
+        wasm_vps,
     })
 }
 
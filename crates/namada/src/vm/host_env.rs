@@ -10,7 +10,7 @@ use masp_primitives::transaction::Transaction;
 use namada_core::types::address::ESTABLISHED_ADDRESS_BYTES_LEN;
 use namada_core::types::internal::KeyVal;
 use namada_core::types::storage::{Epochs, TX_INDEX_LENGTH};
-use namada_core::types::validity_predicate::VpSentinel;
+use namada_core::types::validity_predicate::{VpRejection, VpSentinel};
 use namada_gas::{
     self as gas, GasMetering, TxGasMeter, VpGasMeter,
     MEMORY_ACCESS_GAS_PER_BYTE,
@@ -28,15 +28,17 @@ use super::wasm::TxCache;
 use super::wasm::VpCache;
 use super::WasmCacheAccess;
 use crate::ledger::vp_host_fns;
+use crate::token::factory::{TokenOrigin, MAX_SALT_LEN};
 use crate::token::storage_key::{
-    balance_key, is_any_minted_balance_key, is_any_minter_key,
-    is_any_token_balance_key, minted_balance_key, minter_key,
+    balance_key, is_any_denom_key, is_any_minted_balance_key,
+    is_any_minter_key, is_any_token_balance_key, minted_balance_key,
+    minter_key,
 };
 use crate::types::address::{self, Address};
 use crate::types::hash::Hash;
 use crate::types::ibc::IbcEvent;
 use crate::types::internal::HostEnvResult;
-use crate::types::storage::{BlockHeight, Epoch, Key, TxIndex};
+use crate::types::storage::{BlockHeight, DbKeySeg, Epoch, Key, TxIndex};
 use crate::vm::memory::VmMemory;
 use crate::vm::prefix_iter::{PrefixIteratorId, PrefixIterators};
 use crate::vm::{HostRef, MutHostRef};
@@ -80,6 +82,10 @@ pub enum TxRuntimeError {
     NoValueInResultBuffer,
     #[error("VP code is not allowed in allowlist parameter.")]
     DisallowedVp,
+    #[error("The salt of a token can have at most {MAX_SALT_LEN} bytes")]
+    TokenSaltTooLong,
+    #[error("The token {0} already exists")]
+    TokenAlreadyExists(Address),
 }
 
 /// Result of a tx host env fn call
@@ -279,6 +285,8 @@ where
     pub gas_meter: MutHostRef<'a, &'a VpGasMeter>,
     /// Errors sentinel
     pub sentinel: MutHostRef<'a, &'a VpSentinel>,
+    /// The reason given by the VP for rejecting the transaction, if any
+    pub rejection: MutHostRef<'a, &'a Option<VpRejection>>,
     /// The transaction code is used for signature verification
     pub tx: HostRef<'a, &'a Tx>,
     /// The transaction index is used to identify a shielded transaction's
@@ -348,6 +356,7 @@ where
         write_log: &WriteLog,
         gas_meter: &mut VpGasMeter,
         sentinel: &mut VpSentinel,
+        rejection: &mut Option<VpRejection>,
         tx: &Tx,
         tx_index: &TxIndex,
         iterators: &mut PrefixIterators<'a, DB>,
@@ -363,6 +372,7 @@ where
             write_log,
             gas_meter,
             sentinel,
+            rejection,
             tx,
             tx_index,
             iterators,
@@ -415,6 +425,7 @@ where
         write_log: &WriteLog,
         gas_meter: &mut VpGasMeter,
         sentinel: &mut VpSentinel,
+        rejection: &mut Option<VpRejection>,
         tx: &Tx,
         tx_index: &TxIndex,
         iterators: &mut PrefixIterators<'a, DB>,
@@ -432,6 +443,7 @@ where
         let iterators = unsafe { MutHostRef::new(iterators) };
         let gas_meter = unsafe { MutHostRef::new(gas_meter) };
         let sentinel = unsafe { MutHostRef::new(sentinel) };
+        let rejection = unsafe { MutHostRef::new(rejection) };
         let verifiers = unsafe { HostRef::new(verifiers) };
         let result_buffer = unsafe { MutHostRef::new(result_buffer) };
         let keys_changed = unsafe { HostRef::new(keys_changed) };
@@ -445,6 +457,7 @@ where
             iterators,
             gas_meter,
             sentinel,
+            rejection,
             tx,
             tx_index,
             eval_runner,
@@ -474,6 +487,7 @@ where
             iterators: self.iterators.clone(),
             gas_meter: self.gas_meter.clone(),
             sentinel: self.sentinel.clone(),
+            rejection: self.rejection.clone(),
             tx: self.tx.clone(),
             tx_index: self.tx_index.clone(),
             eval_runner: self.eval_runner.clone(),
@@ -648,10 +662,21 @@ where
             len
         }
         None => {
-            // when not found in write log, try to read from the storage
-            let storage = unsafe { env.ctx.storage.get() };
-            let (value, gas) =
-                storage.read(&key).map_err(TxRuntimeError::StateError)?;
+            // when not found in write log, try to read from the storage,
+            // going through the block's read cache for the hot keys
+            let (value, gas) = match write_log.read_cached(&key) {
+                Some(cached) => cached,
+                None => {
+                    let storage = unsafe { env.ctx.storage.get() };
+                    let (value, gas) = storage
+                        .read(&key)
+                        .map_err(TxRuntimeError::StateError)?;
+                    if is_read_cached_key(&key) {
+                        write_log.cache_read(&key, value.clone());
+                    }
+                    (value, gas)
+                }
+            };
             tx_charge_gas(env, gas)?;
             match value {
                 Some(value) => {
@@ -669,6 +694,16 @@ where
     })
 }
 
+/// Check if the value of the given key is read often enough by txs to be kept
+/// in the block's read cache. These are the protocol parameters and the token
+/// denominations.
+fn is_read_cached_key(key: &Key) -> bool {
+    matches!(
+        key.segments.first(),
+        Some(DbKeySeg::AddressSeg(addr)) if *addr == namada_parameters::ADDRESS
+    ) || is_any_denom_key(key).is_some()
+}
+
 /// This function is a helper to handle the first step of reading var-len
 /// values from the host.
 ///
@@ -1521,11 +1556,11 @@ where
 
     tracing::debug!("tx_init_account");
 
-    let storage = unsafe { env.ctx.storage.get() };
+    let tx = unsafe { env.ctx.tx.get() };
     let write_log = unsafe { env.ctx.write_log.get() };
     let code_hash = Hash::try_from(&code_hash[..])
         .map_err(|e| TxRuntimeError::InvalidVpCodeHash(e.to_string()))?;
-    let (addr, gas) = write_log.init_account(&storage.address_gen, code_hash);
+    let (addr, gas) = write_log.init_account(&tx.raw_header_hash(), code_hash);
     let addr_bytes = addr.serialize_to_vec();
     tx_charge_gas(env, gas)?;
     let gas = env
@@ -1535,6 +1570,88 @@ where
     tx_charge_gas(env, gas)
 }
 
+/// Initialize the account of a new token at the address derived from its
+/// origin by the token factory.
+#[allow(clippy::too_many_arguments)]
+pub fn tx_init_token<MEM, DB, H, CA>(
+    env: &TxVmEnv<MEM, DB, H, CA>,
+    code_hash_ptr: u64,
+    code_hash_len: u64,
+    code_tag_ptr: u64,
+    code_tag_len: u64,
+    origin_ptr: u64,
+    origin_len: u64,
+    result_ptr: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    let (code_hash, gas) = env
+        .memory
+        .read_bytes(code_hash_ptr, code_hash_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_charge_gas(env, gas)?;
+
+    let (code_tag, gas) = env
+        .memory
+        .read_bytes(code_tag_ptr, code_tag_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_charge_gas(env, gas)?;
+    let code_tag = Option::<String>::try_from_slice(&code_tag)
+        .map_err(TxRuntimeError::EncodingError)?;
+
+    let (origin, gas) = env
+        .memory
+        .read_bytes(origin_ptr, origin_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_charge_gas(env, gas)?;
+    let origin = TokenOrigin::try_from_slice(&origin)
+        .map_err(TxRuntimeError::EncodingError)?;
+    if origin.salt.len() > MAX_SALT_LEN {
+        return Err(TxRuntimeError::TokenSaltTooLong);
+    }
+
+    tx_validate_vp_code_hash(env, &code_hash, &code_tag)?;
+
+    tracing::debug!("tx_init_token");
+
+    // The derived address must not be used by any account yet
+    let addr = origin.token_address();
+    let vp_key = Key::validity_predicate(&addr);
+    let write_log = unsafe { env.ctx.write_log.get() };
+    let (log_val, gas) = write_log.read(&vp_key);
+    tx_charge_gas(env, gas)?;
+    let exists = match log_val {
+        Some(write_log::StorageModification::Delete) => false,
+        Some(_) => true,
+        None => {
+            let storage = unsafe { env.ctx.storage.get() };
+            let (present, gas) = storage
+                .has_key(&vp_key)
+                .map_err(TxRuntimeError::StateError)?;
+            tx_charge_gas(env, gas)?;
+            present
+        }
+    };
+    if exists {
+        return Err(TxRuntimeError::TokenAlreadyExists(addr));
+    }
+
+    let code_hash = Hash::try_from(&code_hash[..])
+        .map_err(|e| TxRuntimeError::InvalidVpCodeHash(e.to_string()))?;
+    let gas = write_log.init_account_at(&addr, code_hash);
+    tx_charge_gas(env, gas)?;
+    let addr_bytes = addr.serialize_to_vec();
+    let gas = env
+        .memory
+        .write_bytes(result_ptr, addr_bytes)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_charge_gas(env, gas)
+}
+
 /// Getting the chain ID function exposed to the wasm VM Tx environment.
 pub fn tx_get_chain_id<MEM, DB, H, CA>(
     env: &TxVmEnv<MEM, DB, H, CA>,
@@ -2341,6 +2458,38 @@ where
     vp_host_fns::add_gas(gas_meter, gas, sentinel)
 }
 
+/// Set the reason for the VP to reject the transaction, exposed to the wasm VM
+/// VP environment. The reason replaces any previously set one.
+pub fn vp_set_rejection<MEM, DB, H, EVAL, CA>(
+    env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
+    rejection_ptr: u64,
+    rejection_len: u64,
+) -> vp_host_fns::EnvResult<()>
+where
+    MEM: VmMemory,
+    DB: namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+    CA: WasmCacheAccess,
+{
+    let (rejection, gas) = env
+        .memory
+        .read_bytes(rejection_ptr, rejection_len as _)
+        .map_err(|e| vp_host_fns::RuntimeError::MemoryError(Box::new(e)))?;
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    let sentinel = unsafe { env.ctx.sentinel.get() };
+    vp_host_fns::add_gas(gas_meter, gas, sentinel)?;
+    let rejection: VpRejection =
+        BorshDeserialize::try_from_slice(&rejection)
+            .map_err(vp_host_fns::RuntimeError::EncodingError)?;
+
+    let address = unsafe { env.ctx.address.get() };
+    tracing::debug!("VP of {address} set the rejection reason: {rejection}");
+    let vp_rejection = unsafe { env.ctx.rejection.get() };
+    *vp_rejection = Some(rejection);
+    Ok(())
+}
+
 /// Log a string from exposed to the wasm VM VP environment. The message will be
 /// printed at the [`tracing::Level::INFO`]. This function is for development
 /// only.
@@ -2841,6 +2990,7 @@ pub mod testing {
         iterators: &mut PrefixIterators<'static, DB>,
         gas_meter: &mut VpGasMeter,
         sentinel: &mut VpSentinel,
+        rejection: &mut Option<VpRejection>,
         tx: &Tx,
         tx_index: &TxIndex,
         verifiers: &BTreeSet<Address>,
@@ -2862,6 +3012,7 @@ pub mod testing {
             write_log,
             gas_meter,
             sentinel,
+            rejection,
             tx,
             tx_index,
             iterators,
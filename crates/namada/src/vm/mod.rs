@@ -8,6 +8,7 @@ use wasmparser::{Validator, WasmFeatures};
 
 pub mod host_env;
 pub mod memory;
+pub mod prefetch;
 pub mod prefix_iter;
 pub mod types;
 #[cfg(feature = "wasm-runtime")]
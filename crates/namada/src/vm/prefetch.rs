@@ -0,0 +1,189 @@
+//! Storage prefetching for transactions with predictable key accesses.
+//!
+//! Before a transaction's wasm is executed, the storage keys it is known to
+//! read are predicted from its code tag and data. These keys are read from
+//! the DB concurrently with the compilation of the wasm code and the values
+//! are saved in the block's read cache of the write log, from which the
+//! transaction's reads are then served.
+
+use std::time::{Duration, Instant};
+
+use borsh::BorshDeserialize;
+use namada_sdk::tx::TX_TRANSFER_WASM;
+use namada_state::write_log::WriteLog;
+use namada_tx::Tx;
+
+use crate::token::storage_key::{balance_key, denom_key};
+use crate::token::Transfer;
+use crate::types::storage::Key;
+
+/// The values of the prefetched keys, as read from the DB
+#[derive(Debug, Default)]
+pub struct Prefetched {
+    /// The keys with their committed values
+    pub values: Vec<(Key, Option<Vec<u8>>)>,
+    /// The time spent reading the keys
+    pub elapsed: Duration,
+}
+
+/// Predict the storage keys that will be read by the given transaction with
+/// the given code tag. Returns an empty list if the access pattern of the
+/// transaction is not known.
+pub fn predicted_keys(tx: &Tx, tag: Option<&str>) -> Vec<Key> {
+    match tag {
+        Some(TX_TRANSFER_WASM) => tx
+            .data()
+            .and_then(|data| Transfer::try_from_slice(&data).ok())
+            .map(|transfer| {
+                vec![
+                    balance_key(&transfer.token, &transfer.source),
+                    balance_key(&transfer.token, &transfer.target),
+                    denom_key(&transfer.token),
+                ]
+            })
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+/// Retain only the keys whose reads would hit the DB, i.e. the keys that were
+/// neither modified in the write log nor already cached
+pub fn keys_to_prefetch(write_log: &WriteLog, mut keys: Vec<Key>) -> Vec<Key> {
+    keys.sort();
+    keys.dedup();
+    keys.retain(|key| {
+        write_log.read(key).0.is_none() && write_log.read_cached(key).is_none()
+    });
+    keys
+}
+
+/// Read the given keys from the DB. Keys that fail to be read are skipped,
+/// the error is then reported by the read from the transaction.
+pub fn prefetch<DB>(db: &DB, keys: Vec<Key>) -> Prefetched
+where
+    DB: namada_state::DB,
+{
+    let start = Instant::now();
+    let values = keys
+        .into_iter()
+        .filter_map(|key| {
+            let value = db.read_subspace_val(&key).ok()?;
+            Some((key, value))
+        })
+        .collect();
+    Prefetched {
+        values,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Save the prefetched values into the block's read cache of the write log.
+/// Returns the number of cached keys.
+pub fn cache_prefetched(
+    write_log: &mut WriteLog,
+    prefetched: Prefetched,
+) -> usize {
+    let count = prefetched.values.len();
+    for (key, value) in prefetched.values {
+        write_log.cache_read(&key, value);
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh_ext::BorshSerializeExt;
+    use namada_state::testing::TestWlStorage;
+    use namada_tx::data::TxType;
+    use namada_tx::{Code, Data};
+
+    use super::*;
+    use crate::core::types::address::nam;
+    use crate::core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use crate::token::{Amount, DenominatedAmount};
+
+    fn transfer_tx() -> (Tx, Transfer) {
+        let transfer = Transfer {
+            source: established_address_1(),
+            target: established_address_2(),
+            token: nam(),
+            amount: DenominatedAmount::native(Amount::native_whole(1)),
+            key: None,
+            shielded: None,
+        };
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_code(Code::new(vec![], Some(TX_TRANSFER_WASM.to_string())));
+        tx.set_data(Data::new(transfer.serialize_to_vec()));
+        (tx, transfer)
+    }
+
+    #[test]
+    fn test_predicted_keys() {
+        let (tx, transfer) = transfer_tx();
+        let keys = predicted_keys(&tx, Some(TX_TRANSFER_WASM));
+        assert_eq!(
+            keys,
+            vec![
+                balance_key(&transfer.token, &transfer.source),
+                balance_key(&transfer.token, &transfer.target),
+                denom_key(&transfer.token),
+            ]
+        );
+        // Unknown transactions have no predicted keys
+        assert!(predicted_keys(&tx, Some("tx_unknown.wasm")).is_empty());
+        assert!(predicted_keys(&tx, None).is_empty());
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let mut wl_storage = TestWlStorage::default();
+        let (tx, transfer) = transfer_tx();
+        let source_key = balance_key(&transfer.token, &transfer.source);
+        let target_key = balance_key(&transfer.token, &transfer.target);
+        let balance = Amount::native_whole(10).serialize_to_vec();
+        wl_storage
+            .storage
+            .write(&source_key, balance.clone())
+            .expect("write failed");
+        wl_storage
+            .storage
+            .write(&target_key, balance.clone())
+            .expect("write failed");
+        // A key modified in the write log is not prefetched
+        wl_storage
+            .write_log
+            .write(&target_key, balance.clone())
+            .expect("write failed");
+
+        let keys = keys_to_prefetch(
+            &wl_storage.write_log,
+            predicted_keys(&tx, Some(TX_TRANSFER_WASM)),
+        );
+        assert_eq!(keys.len(), 2);
+        assert!(!keys.contains(&target_key));
+
+        let prefetched = prefetch(&wl_storage.storage.db, keys);
+        let cached = cache_prefetched(&mut wl_storage.write_log, prefetched);
+        assert_eq!(cached, 2);
+        let (value, _gas) = wl_storage
+            .write_log
+            .read_cached(&source_key)
+            .expect("the key must be cached");
+        assert_eq!(value, Some(balance));
+        // A missing key is cached as missing
+        let (value, _gas) = wl_storage
+            .write_log
+            .read_cached(&denom_key(&transfer.token))
+            .expect("the key must be cached");
+        assert_eq!(value, None);
+
+        // Cached keys are not prefetched again
+        assert!(keys_to_prefetch(
+            &wl_storage.write_log,
+            predicted_keys(&tx, Some(TX_TRANSFER_WASM)),
+        )
+        .is_empty());
+    }
+}
@@ -2,9 +2,10 @@
 
 use std::collections::BTreeSet;
 use std::marker::PhantomData;
+use std::time::Instant;
 
 use borsh::BorshDeserialize;
-use namada_core::types::validity_predicate::VpSentinel;
+use namada_core::types::validity_predicate::{VpRejection, VpSentinel};
 use namada_gas::{GasMetering, TxGasMeter, WASM_MEMORY_PAGE_GAS};
 use namada_state::write_log::StorageModification;
 use namada_state::{State, StorageHasher};
@@ -28,7 +29,7 @@ use crate::vm::types::VpInput;
 use crate::vm::wasm::host_env::{tx_imports, vp_imports};
 use crate::vm::wasm::{memory, Cache, CacheName, VpCache};
 use crate::vm::{
-    validate_untrusted_wasm, WasmCacheAccess, WasmValidationError,
+    prefetch, validate_untrusted_wasm, WasmCacheAccess, WasmValidationError,
 };
 
 const TX_ENTRYPOINT: &str = "_apply_tx";
@@ -50,6 +51,17 @@ pub enum Error {
     SerializationError(elements::Error),
     #[error("Unable to inject gas meter")]
     GasMeterInjection,
+    #[error("Non-deterministic wasm instrumentation")]
+    NonDeterministicInstrumentation,
+    #[error(
+        "Wasm instrumentation mismatch for code {code_hash}: expected \
+         {expected}, got {actual}"
+    )]
+    InstrumentationMismatch {
+        code_hash: Hash,
+        expected: Hash,
+        actual: Hash,
+    },
     #[error("Wasm compilation error: {0}")]
     CompileError(wasmer::CompileError),
     #[error("Missing wasm memory export, failed with: {0}")]
@@ -101,7 +113,10 @@ pub fn tx<DB, H, CA>(
     tx_wasm_cache: &mut TxCache<CA>,
 ) -> Result<BTreeSet<Address>>
 where
-    DB: 'static + namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    DB: 'static
+        + namada_state::DB
+        + for<'iter> namada_state::DBIter<'iter>
+        + Sync,
     H: 'static + StorageHasher,
     CA: 'static + WasmCacheAccess,
 {
@@ -145,13 +160,41 @@ where
         }
     }
 
-    let (module, store) = fetch_or_compile(
-        tx_wasm_cache,
-        &tx_code.code,
+    // Prefetch the storage keys that the tx is predicted to read while its
+    // code is being compiled
+    let prefetch_keys = prefetch::keys_to_prefetch(
         write_log,
-        storage,
-        gas_meter,
-    )?;
+        prefetch::predicted_keys(tx, tx_code.tag.as_deref()),
+    );
+    let compile_start = Instant::now();
+    let (compiled, prefetched) = std::thread::scope(|scope| {
+        let prefetched = (!prefetch_keys.is_empty()).then(|| {
+            let db = &storage.db;
+            scope.spawn(move || prefetch::prefetch(db, prefetch_keys))
+        });
+        let compiled = fetch_or_compile(
+            tx_wasm_cache,
+            &tx_code.code,
+            write_log,
+            storage,
+            gas_meter,
+        );
+        let prefetched = prefetched.and_then(|handle| handle.join().ok());
+        (compiled, prefetched)
+    });
+    let compile_elapsed = compile_start.elapsed();
+    if let Some(prefetched) = prefetched {
+        let prefetch_elapsed = prefetched.elapsed;
+        let count = prefetch::cache_prefetched(write_log, prefetched);
+        tracing::debug!(
+            "Prefetched {} storage keys in {:?} during the {:?} taken to \
+             fetch or compile the tx code",
+            count,
+            prefetch_elapsed,
+            compile_elapsed,
+        );
+    }
+    let (module, store) = compiled?;
 
     let mut iterators: PrefixIterators<'_, DB> = PrefixIterators::default();
     let mut verifiers = BTreeSet::new();
@@ -217,7 +260,8 @@ where
 
 /// Execute a validity predicate code. Returns whether the validity
 /// predicate accepted storage modifications performed by the transaction
-/// that triggered the execution.
+/// that triggered the execution, along with the reason it gave for rejecting
+/// them, if any.
 #[allow(clippy::too_many_arguments)]
 pub fn vp<DB, H, CA>(
     vp_code_hash: Hash,
@@ -230,7 +274,7 @@ pub fn vp<DB, H, CA>(
     keys_changed: &BTreeSet<Key>,
     verifiers: &BTreeSet<Address>,
     mut vp_wasm_cache: VpCache<CA>,
-) -> Result<bool>
+) -> Result<(bool, Option<VpRejection>)>
 where
     DB: 'static + namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
     H: 'static + StorageHasher,
@@ -254,6 +298,7 @@ where
     };
 
     let mut sentinel = VpSentinel::default();
+    let mut rejection = None;
     let env = VpVmEnv::new(
         WasmMemory::default(),
         address,
@@ -261,6 +306,7 @@ where
         write_log,
         gas_meter,
         &mut sentinel,
+        &mut rejection,
         tx,
         tx_index,
         &mut iterators,
@@ -300,8 +346,10 @@ where
                     );
                 }
                 Err(Error::InvalidTxSignature)
+            } else if accept {
+                Ok((true, None))
             } else {
-                Ok(accept)
+                Ok((false, rejection))
             }
         }
         Err(err) => {
@@ -502,6 +550,69 @@ pub fn prepare_wasm_code<T: AsRef<[u8]>>(code: T) -> Result<Vec<u8>> {
     elements::serialize(module).map_err(Error::SerializationError)
 }
 
+/// Get the hash of the given wasm code once instrumented with the gas meter
+/// and stack limiter. The code is instrumented twice to check that the
+/// instrumentation is deterministic.
+pub fn instrumented_code_hash<T: AsRef<[u8]>>(code: T) -> Result<Hash> {
+    let instrumented = prepare_wasm_code(&code)?;
+    if instrumented != prepare_wasm_code(&code)? {
+        return Err(Error::NonDeterministicInstrumentation);
+    }
+    Ok(Hash::sha256(instrumented))
+}
+
+/// Check that the instrumentation of the given wasm code matches the expected
+/// one, recorded when the code was allowlisted. A mismatch means that this
+/// node meters the code differently than the rest of the chain.
+pub fn verify_instrumentation<T: AsRef<[u8]>>(
+    code_hash: &Hash,
+    code: T,
+    expected: &Hash,
+) -> Result<()> {
+    let actual = instrumented_code_hash(code)?;
+    if actual != *expected {
+        return Err(Error::InstrumentationMismatch {
+            code_hash: *code_hash,
+            expected: *expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+// Read the expected hash of the instrumented wasm code recorded when the code
+// was allowlisted, if any. This is a local check that doesn't consume gas, so
+// that the gas used doesn't depend on the state of the compilation cache.
+fn read_instrumented_hash<DB, H>(
+    code_hash: &Hash,
+    write_log: &WriteLog,
+    storage: &State<DB, H>,
+) -> Result<Option<Hash>>
+where
+    DB: 'static + namada_state::DB + for<'iter> namada_state::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    let key = Key::wasm_instrumented_hash(code_hash);
+    let value = match write_log.read(&key).0 {
+        Some(StorageModification::Write { value }) => Some(value.clone()),
+        _ => {
+            storage
+                .read(&key)
+                .map_err(|e| {
+                    Error::LoadWasmCode(format!(
+                        "Read wasm instrumented hash failed from storage: key \
+                         {}, error {}",
+                        key, e
+                    ))
+                })?
+                .0
+        }
+    };
+    value
+        .map(|value| Hash::try_from(&value[..]).map_err(Error::CodeHash))
+        .transpose()
+}
+
 // Fetch or compile a WASM code from the cache or storage. Account for the
 // loading and code compilation gas costs.
 fn fetch_or_compile<DB, H, CN, CA>(
@@ -581,6 +692,16 @@ where
                     let tx_len = u64::try_from(code.len())
                         .map_err(|e| Error::ConversionError(e.to_string()))?;
 
+                    if let Some(expected) =
+                        read_instrumented_hash(code_hash, write_log, storage)?
+                    {
+                        verify_instrumentation(code_hash, &code, &expected)
+                            .map_err(|err| {
+                                tracing::error!("{err}");
+                                err
+                            })?;
+                    }
+
                     match wasm_cache.compile_or_fetch(code)? {
                         Some((module, store)) => (module, store, tx_len),
                         None => return Err(Error::NoCompiledWasmCode),
@@ -632,11 +753,13 @@ fn get_gas_rules() -> wasm_instrument::gas_metering::ConstantCostRules {
 mod tests {
     use std::error::Error as StdErrorTrait;
 
+    use assert_matches::assert_matches;
     use borsh_ext::BorshSerializeExt;
     use itertools::Either;
     use namada_test_utils::TestWasms;
     use namada_tx::data::TxType;
     use namada_tx::{Code, Data};
+    use proptest::prelude::*;
     use test_log::test;
     use wasmer_vm::TrapCode;
 
@@ -884,7 +1007,8 @@ mod tests {
             &verifiers,
             vp_cache.clone(),
         )
-        .unwrap();
+        .unwrap()
+        .0;
         assert!(passed);
 
         // Allocating `2^24` (16 MiB) should be above the memory limit and
@@ -916,7 +1040,8 @@ mod tests {
             &verifiers,
             vp_cache,
         )
-        .unwrap();
+        .unwrap()
+        .0;
 
         assert!(!passed);
     }
@@ -1303,11 +1428,102 @@ mod tests {
             &verifiers,
             vp_cache,
         )
-        .unwrap();
+        .unwrap()
+        .0;
         assert!(!passed);
     }
 
+    /// Test that the instrumentation of a wasm code is checked against the
+    /// one recorded when the code was allowlisted, before it's compiled.
+    #[test]
+    fn test_instrumentation_verification() {
+        let tx_code = TestWasms::TxNoOp.read_bytes();
+        let code_hash = Hash::sha256(&tx_code);
+        let instrumented_hash = instrumented_code_hash(&tx_code).unwrap();
+        assert_eq!(
+            instrumented_hash,
+            Hash::sha256(prepare_wasm_code(&tx_code).unwrap())
+        );
+        assert!(verify_instrumentation(
+            &code_hash,
+            &tx_code,
+            &instrumented_hash
+        )
+        .is_ok());
+
+        // The code runs if its instrumentation matches the recorded one
+        let result =
+            execute_tx_with_instrumentation(tx_code.clone(), instrumented_hash);
+        assert!(result.is_ok(), "Expected success. Got {:?}", result);
+
+        // It's rejected otherwise
+        let error = execute_tx_with_instrumentation(tx_code, Hash::zero())
+            .expect_err("The instrumentation mismatch must be detected");
+        assert_matches!(
+            error,
+            Error::InstrumentationMismatch { expected, actual, .. }
+                if expected == Hash::zero() && actual == instrumented_hash
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        /// Test that the instrumentation of wasm codes growing their memory,
+        /// which are the instructions metered in the wasm, is deterministic.
+        #[test]
+        fn test_instrumentation_is_deterministic(
+            grows in proptest::collection::vec(0_u32..16, 0..8),
+        ) {
+            let body: String = grows
+                .iter()
+                .map(|pages| {
+                    format!("(drop (memory.grow (i32.const {pages})))")
+                })
+                .collect();
+            let tx_code = wasmer::wat2wasm(
+                format!(
+                    r#"
+                (module
+                    (func $_apply_tx (param i64 i64) {body})
+                    (memory (;0;) 16)
+                    (export "memory" (memory 0))
+                    (export "_apply_tx" (func $_apply_tx)))
+                "#
+                )
+                .as_bytes(),
+            )
+            .expect("unexpected error converting wat2wasm")
+            .into_owned();
+
+            let instrumented = prepare_wasm_code(&tx_code).unwrap();
+            prop_assert_eq!(
+                instrumented_code_hash(&tx_code).unwrap(),
+                Hash::sha256(&instrumented)
+            );
+            let result = execute_tx_with_instrumentation(
+                tx_code,
+                Hash::sha256(instrumented),
+            );
+            prop_assert!(result.is_ok(), "Expected success. Got {:?}", result);
+        }
+    }
+
+    fn execute_tx_with_instrumentation(
+        tx_code: Vec<u8>,
+        instrumented_hash: Hash,
+    ) -> Result<BTreeSet<Address>> {
+        execute_tx(tx_code, Some(instrumented_hash))
+    }
+
     fn execute_tx_with_code(tx_code: Vec<u8>) -> Result<BTreeSet<Address>> {
+        execute_tx(tx_code, None)
+    }
+
+    fn execute_tx(
+        tx_code: Vec<u8>,
+        instrumented_hash: Option<Hash>,
+    ) -> Result<BTreeSet<Address>> {
         let tx_data = vec![];
         let tx_index = TxIndex::default();
         let storage = TestStorage::default();
@@ -1325,6 +1541,10 @@ mod tests {
         let len_key = Key::wasm_code_len(&code_hash);
         write_log.write(&key, tx_code).unwrap();
         write_log.write(&len_key, code_len).unwrap();
+        if let Some(instrumented_hash) = instrumented_hash {
+            let key = Key::wasm_instrumented_hash(&code_hash);
+            write_log.write(&key, instrumented_hash.to_vec()).unwrap();
+        }
 
         let mut outer_tx = Tx::from_type(TxType::Raw);
         outer_tx.set_code(Code::from_hash(code_hash, None));
@@ -1439,6 +1659,7 @@ mod tests {
             &verifiers,
             vp_cache,
         )
+        .map(|(accepted, _rejection)| accepted)
     }
 
     fn get_trap_code(error: &Error) -> Either<TrapCode, String> {
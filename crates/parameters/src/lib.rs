@@ -12,7 +12,7 @@ use namada_core::types::storage::Key;
 use namada_core::types::time::DurationSecs;
 use namada_core::types::token;
 use namada_storage::{self, ResultExt, StorageRead, StorageWrite};
-pub use storage::get_max_block_gas;
+pub use storage::{get_max_block_gas, get_max_tx_gas};
 use thiserror::Error;
 pub use wasm_allowlist::{is_tx_allowed, is_vp_allowed};
 
@@ -64,6 +64,9 @@ where
         minimum_gas_price,
         fee_unshielding_gas_limit,
         fee_unshielding_descriptions_limit,
+        max_native_mint_per_epoch,
+        masp_conversion_retention_epochs,
+        max_tx_gas,
     } = parameters;
 
     // write max tx bytes parameter
@@ -147,6 +150,24 @@ where
     let gas_cost_key = storage::get_gas_cost_key();
     storage.write(&gas_cost_key, minimum_gas_price)?;
 
+    // The max native mint is only written if there's a limit
+    if let Some(max_native_mint) = max_native_mint_per_epoch {
+        let max_native_mint_key = storage::get_max_native_mint_per_epoch_key();
+        storage.write(&max_native_mint_key, max_native_mint)?;
+    }
+
+    // The MASP conversion retention is only written if they're archived
+    if let Some(retention) = masp_conversion_retention_epochs {
+        let retention_key = storage::get_masp_conversion_retention_epochs_key();
+        storage.write(&retention_key, retention)?;
+    }
+
+    // The max tx gas is only written if it's lower than the max block gas
+    if let Some(max_tx_gas) = max_tx_gas {
+        let max_tx_gas_key = storage::get_max_tx_gas_key();
+        storage.write(&max_tx_gas_key, max_tx_gas)?;
+    }
+
     Ok(())
 }
 
@@ -278,6 +299,97 @@ where
     storage.write_bytes(&key, implicit_vp)
 }
 
+/// Update the max amount of native tokens minted per epoch. `None` removes
+/// the limit.
+pub fn update_max_native_mint_per_epoch_parameter<S>(
+    storage: &mut S,
+    value: Option<token::Amount>,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_max_native_mint_per_epoch_key();
+    match value {
+        Some(value) => storage.write(&key, value),
+        None => storage.delete(&key),
+    }
+}
+
+/// Read the max amount of native tokens minted per epoch. `None` if there's
+/// no limit.
+pub fn read_max_native_mint_per_epoch<S>(
+    storage: &S,
+) -> namada_storage::Result<Option<token::Amount>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::get_max_native_mint_per_epoch_key())
+}
+
+/// Update the number of epochs that the MASP conversions are kept in the
+/// conversion state. `None` keeps them all.
+pub fn update_masp_conversion_retention_epochs_parameter<S>(
+    storage: &mut S,
+    value: Option<u64>,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_masp_conversion_retention_epochs_key();
+    match value {
+        Some(value) => storage.write(&key, value),
+        None => storage.delete(&key),
+    }
+}
+
+/// Read the number of epochs that the MASP conversions are kept in the
+/// conversion state. `None` if they're all kept.
+pub fn read_masp_conversion_retention_epochs<S>(
+    storage: &S,
+) -> namada_storage::Result<Option<u64>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::get_masp_conversion_retention_epochs_key())
+}
+
+/// Update the max block gas parameter
+pub fn update_max_block_gas_parameter<S>(
+    storage: &mut S,
+    value: u64,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_max_block_gas_key();
+    storage.write(&key, value)
+}
+
+/// Update the max gas of a transaction. `None` only limits it by the max
+/// block gas.
+pub fn update_max_tx_gas_parameter<S>(
+    storage: &mut S,
+    value: Option<u64>,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_max_tx_gas_key();
+    match value {
+        Some(value) => storage.write(&key, value),
+        None => storage.delete(&key),
+    }
+}
+
+/// Read the max gas of a transaction parameter. `None` if it's only limited
+/// by the max block gas.
+pub fn read_max_tx_gas<S>(storage: &S) -> namada_storage::Result<Option<u64>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::get_max_tx_gas_key())
+}
+
 /// Update the max signatures per transaction storage parameter
 pub fn update_max_signature_per_tx<S>(
     storage: &mut S,
@@ -313,11 +425,65 @@ pub fn read_gas_cost<S>(
 where
     S: StorageRead,
 {
-    let gas_cost_table: BTreeMap<Address, token::Amount> = storage
+    let gas_cost_table = read_gas_cost_table(storage)?;
+    Ok(gas_cost_table.get(token).map(|amount| amount.to_owned()))
+}
+
+/// Read the cost per unit of gas of all the tokens allowed for fee payment
+pub fn read_gas_cost_table<S>(
+    storage: &S,
+) -> namada_storage::Result<BTreeMap<Address, token::Amount>>
+where
+    S: StorageRead,
+{
+    storage
         .read(&storage::get_gas_cost_key())?
         .ok_or(ReadError::ParametersMissing)
-        .into_storage_result()?;
-    Ok(gas_cost_table.get(token).map(|amount| amount.to_owned()))
+        .into_storage_result()
+}
+
+/// Read the minimum price per unit of gas accepted for the provided token,
+/// given the local minimum gas prices of a node. The cost per unit of gas set
+/// by governance is a floor to the local price. Returns `None` if the token is
+/// not allowed for fee payment.
+pub fn read_minimum_gas_price<S>(
+    storage: &S,
+    local_prices: &BTreeMap<Address, token::Amount>,
+    token: &Address,
+) -> namada_storage::Result<Option<token::Amount>>
+where
+    S: StorageRead,
+{
+    let gas_cost = read_gas_cost(storage, token)?;
+    Ok(gas_cost.map(|floor| minimum_gas_price(floor, local_prices.get(token))))
+}
+
+/// Read the minimum prices per unit of gas accepted for all the tokens allowed
+/// for fee payment, given the local minimum gas prices of a node. Local prices
+/// of tokens that are not allowed for fee payment are ignored.
+pub fn read_minimum_gas_prices<S>(
+    storage: &S,
+    local_prices: &BTreeMap<Address, token::Amount>,
+) -> namada_storage::Result<BTreeMap<Address, token::Amount>>
+where
+    S: StorageRead,
+{
+    let gas_cost_table = read_gas_cost_table(storage)?;
+    Ok(gas_cost_table
+        .into_iter()
+        .map(|(token, floor)| {
+            let price = minimum_gas_price(floor, local_prices.get(&token));
+            (token, price)
+        })
+        .collect())
+}
+
+/// The greater of the governance floor and the local price, if any
+fn minimum_gas_price(
+    floor: token::Amount,
+    local_price: Option<&token::Amount>,
+) -> token::Amount {
+    local_price.map_or(floor, |local_price| floor.max(*local_price))
 }
 
 /// Read all the parameters from storage. Returns the parameters and gas
@@ -437,6 +603,16 @@ where
         .ok_or(ReadError::ParametersMissing)
         .into_storage_result()?;
 
+    // read max native mint, that is not set if there's no limit
+    let max_native_mint_per_epoch = read_max_native_mint_per_epoch(storage)?;
+
+    // read MASP conversion retention, that is not set if they're all kept
+    let masp_conversion_retention_epochs =
+        read_masp_conversion_retention_epochs(storage)?;
+
+    // read max tx gas, that is not set if it's only limited by the block
+    let max_tx_gas = read_max_tx_gas(storage)?;
+
     Ok(Parameters {
         max_tx_bytes,
         epoch_duration,
@@ -453,6 +629,9 @@ where
         minimum_gas_price,
         fee_unshielding_gas_limit,
         fee_unshielding_descriptions_limit,
+        max_native_mint_per_epoch,
+        masp_conversion_retention_epochs,
+        max_tx_gas,
     })
 }
 
@@ -24,6 +24,9 @@ struct Keys {
     native_erc20: &'static str,
     /// Sub-lkey for storing the Ethereum address of the bridge contract.
     bridge_contract_address: &'static str,
+    /// Sub-key for storing the number of blocks between two checkpoints
+    /// of the application state.
+    checkpoint_interval: &'static str,
     // ========================================
     // PoS parameters
     // ========================================
@@ -45,6 +48,10 @@ struct Keys {
     fee_unshielding_gas_limit: &'static str,
     fee_unshielding_descriptions_limit: &'static str,
     max_signatures_per_transaction: &'static str,
+    max_native_mint_per_epoch: &'static str,
+    masp_conversion_retention_epochs: &'static str,
+    masp_conversion_backfill: &'static str,
+    max_tx_gas: &'static str,
 }
 
 /// Returns if the key is a parameter key.
@@ -190,6 +197,27 @@ pub fn get_max_signatures_per_transaction_key() -> Key {
     get_max_signatures_per_transaction_key_at_addr(ADDRESS)
 }
 
+/// Storage key used for the max amount of native tokens minted per epoch
+pub fn get_max_native_mint_per_epoch_key() -> Key {
+    get_max_native_mint_per_epoch_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the retention of the MASP conversions
+pub fn get_masp_conversion_retention_epochs_key() -> Key {
+    get_masp_conversion_retention_epochs_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the MASP conversion gaps to backfill, as approved by
+/// governance
+pub fn get_masp_conversion_backfill_key() -> Key {
+    get_masp_conversion_backfill_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the max gas of a transaction
+pub fn get_max_tx_gas_key() -> Key {
+    get_max_tx_gas_key_at_addr(ADDRESS)
+}
+
 /// Helper function to retrieve the `max_block_gas` protocol parameter from
 /// storage
 pub fn get_max_block_gas(
@@ -201,3 +229,15 @@ pub fn get_max_block_gas(
         ),
     )
 }
+
+/// Helper function to retrieve the max gas of a transaction from storage,
+/// i.e. the `max_tx_gas` protocol parameter if it's set, capped by the
+/// `max_block_gas` one
+pub fn get_max_tx_gas(
+    storage: &impl StorageRead,
+) -> std::result::Result<u64, namada_storage::Error> {
+    let max_block_gas = get_max_block_gas(storage)?;
+    let max_tx_gas: Option<u64> = storage.read(&get_max_tx_gas_key())?;
+    Ok(max_tx_gas
+        .map_or(max_block_gas, |max_tx_gas| max_tx_gas.min(max_block_gas)))
+}
@@ -129,6 +129,25 @@ where
         }
     }
 
+    /// Check if the value of the given epoch has been pruned, in which case
+    /// it can't be found with [`Epoched::get`] anymore.
+    pub fn is_pruned<S>(
+        &self,
+        storage: &S,
+        epoch: Epoch,
+        params: &PosParams,
+    ) -> namada_storage::Result<bool>
+    where
+        S: StorageRead,
+    {
+        let last_update = self.get_last_update(storage)?;
+        Ok(matches!(
+            last_update,
+            Some(last_update)
+                if epoch < Self::sub_past_epochs(params, last_update)
+        ))
+    }
+
     /// Initialize or set the value at the given epoch offset.
     pub fn set<S>(
         &self,
@@ -180,9 +199,8 @@ where
         if let (Some(last_update), Some(oldest_epoch)) =
             (last_update, oldest_epoch)
         {
-            let oldest_to_keep = current_epoch
-                .checked_sub(PastEpochs::value(params))
-                .unwrap_or_default();
+            let oldest_to_keep =
+                current_epoch.saturating_sub(PastEpochs::value(params));
             if oldest_epoch < oldest_to_keep {
                 let diff = u64::from(oldest_to_keep - oldest_epoch);
                 // Go through the epochs before the expected oldest epoch and
@@ -263,9 +281,7 @@ where
     }
 
     fn sub_past_epochs(params: &PosParams, epoch: Epoch) -> Epoch {
-        epoch
-            .checked_sub(PastEpochs::value(params))
-            .unwrap_or_default()
+        epoch.saturating_sub(PastEpochs::value(params))
     }
 
     fn get_oldest_epoch_storage_key(&self) -> storage::Key {
@@ -319,6 +335,20 @@ where
         NestedMap::open(key)
     }
 
+    /// Check if the data of the given epoch has been pruned, in which case
+    /// the collection at that epoch is empty.
+    pub fn is_pruned<S>(
+        &self,
+        storage: &S,
+        epoch: Epoch,
+    ) -> namada_storage::Result<bool>
+    where
+        S: StorageRead,
+    {
+        let oldest_epoch = self.get_oldest_epoch(storage)?;
+        Ok(matches!(oldest_epoch, Some(oldest_epoch) if epoch < oldest_epoch))
+    }
+
     /// Initialize new nested data at the given epoch.
     pub fn init<S>(
         &self,
@@ -394,9 +424,7 @@ where
     }
 
     fn sub_past_epochs(params: &PosParams, epoch: Epoch) -> Epoch {
-        epoch
-            .checked_sub(PastEpochs::value(params))
-            .unwrap_or_default()
+        epoch.saturating_sub(PastEpochs::value(params))
     }
 
     /// Update data by removing old epochs
@@ -414,9 +442,8 @@ where
         if let (Some(last_update), Some(oldest_epoch)) =
             (last_update, oldest_epoch)
         {
-            let oldest_to_keep = current_epoch
-                .checked_sub(PastEpochs::value(params))
-                .unwrap_or_default();
+            let oldest_to_keep =
+                current_epoch.saturating_sub(PastEpochs::value(params));
             if oldest_epoch < oldest_to_keep {
                 let diff = u64::from(oldest_to_keep - oldest_epoch);
                 // Go through the epochs before the expected oldest epoch and
@@ -547,6 +574,26 @@ where
         }
     }
 
+    /// Check if the deltas of the given epoch have been pruned, in which case
+    /// their sum up to it can't be found with [`EpochedDelta::get_sum`]
+    /// anymore.
+    pub fn is_pruned<S>(
+        &self,
+        storage: &S,
+        epoch: Epoch,
+        params: &PosParams,
+    ) -> namada_storage::Result<bool>
+    where
+        S: StorageRead,
+    {
+        let last_update = self.get_last_update(storage)?;
+        Ok(matches!(
+            last_update,
+            Some(last_update)
+                if epoch < Self::sub_past_epochs(params, last_update)
+        ))
+    }
+
     /// Initialize or add a value to the current delta value at the given epoch
     /// offset.
     pub fn add<S>(
@@ -617,9 +664,8 @@ where
         if let (Some(last_update), Some(oldest_epoch)) =
             (last_update, oldest_epoch)
         {
-            let oldest_to_keep = current_epoch
-                .checked_sub(PastEpochs::value(params))
-                .unwrap_or_default();
+            let oldest_to_keep =
+                current_epoch.saturating_sub(PastEpochs::value(params));
             if oldest_epoch < oldest_to_keep {
                 let diff = u64::from(oldest_to_keep - oldest_epoch);
                 // Go through the epochs before the expected oldest epoch and
@@ -720,9 +766,7 @@ where
     }
 
     fn sub_past_epochs(params: &PosParams, epoch: Epoch) -> Epoch {
-        epoch
-            .checked_sub(PastEpochs::value(params))
-            .unwrap_or_default()
+        epoch.saturating_sub(PastEpochs::value(params))
     }
 
     fn get_oldest_epoch_storage_key(&self) -> storage::Key {
@@ -46,6 +46,11 @@ pub enum BondError {
     InactiveValidator(Address),
     #[error("Voting power overflow: {0}")]
     VotingPowerOverflow(TryFromIntError),
+    #[error(
+        "Invalid scheduled bond start epoch {0}: it must be after the current \
+         epoch {1} and at most {2} epochs later"
+    )]
+    InvalidScheduledEpoch(Epoch, Epoch, u64),
 }
 
 #[allow(missing_docs)]
@@ -65,6 +70,11 @@ pub enum UnbondError {
     VotingPowerOverflow(TryFromIntError),
     #[error("Trying to unbond from a frozen validator: {0}")]
     ValidatorIsFrozen(Address),
+    #[error(
+        "Invalid scheduled unbond start epoch {0}: it must be after the \
+         current epoch {1} and at most {2} epochs later"
+    )]
+    InvalidScheduledEpoch(Epoch, Epoch, u64),
 }
 
 #[allow(missing_docs)]
@@ -167,6 +177,16 @@ pub enum ReactivationError {
 pub enum MetadataError {
     #[error("The validator email cannot be removed")]
     CannotRemoveEmail,
+    #[error(
+        "Invalid validator handle {}: a handle must be {} to {} lowercase \
+         ASCII letters, digits, `-` or `_`",
+        .0,
+        crate::storage::MIN_VALIDATOR_HANDLE_LEN,
+        crate::storage::MAX_VALIDATOR_HANDLE_LEN
+    )]
+    InvalidHandle(String),
+    #[error("The validator handle {0} is already registered by {1}")]
+    HandleTaken(String, Address),
 }
 
 #[allow(missing_docs)]
@@ -176,6 +196,16 @@ pub enum ConsensusKeyChangeError {
     MustBeEd25519,
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum HistoryError {
+    #[error(
+        "The PoS data of epoch {0} has been pruned and no summary of it has \
+         been archived"
+    )]
+    Pruned(Epoch),
+}
+
 impl From<BecomeValidatorError> for namada_storage::Error {
     fn from(err: BecomeValidatorError) -> Self {
         Self::new(err)
@@ -247,3 +277,9 @@ impl From<ConsensusKeyChangeError> for namada_storage::Error {
         Self::new(err)
     }
 }
+
+impl From<HistoryError> for namada_storage::Error {
+    fn from(err: HistoryError) -> Self {
+        Self::new(err)
+    }
+}
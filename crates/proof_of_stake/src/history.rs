@@ -0,0 +1,431 @@
+//! Historical PoS data.
+//!
+//! Most of the epoched PoS data is pruned a few epochs after it's no longer
+//! needed by the protocol. To keep serving historical queries after that, a
+//! summary of each epoch and of its validators is archived when the epoch
+//! ends. The historical reads below use the epoched data while it's still
+//! live and fall back to the archived summaries once it's pruned, flagging
+//! the [`Provenance`] of the values they return.
+//!
+//! The bonds, the slashes and the rewards products of the validators are
+//! never pruned and aren't summarized.
+
+use namada_core::ledger::inflation::RewardRate;
+use namada_core::types::address::Address;
+use namada_core::types::dec::Dec;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+use namada_parameters::storage as params_storage;
+use namada_storage::{OptionExt, StorageRead, StorageWrite};
+
+use crate::rewards::{
+    estimate_staking_reward_rate, estimate_validator_staking_reward_rate,
+    staking_reward_rate, validator_staking_reward_rate,
+};
+use crate::storage::{
+    consensus_validator_set_handle, epoch_summaries_handle,
+    read_all_validator_addresses,
+    read_consensus_validator_set_addresses_with_stake, read_total_stake,
+    read_validator_stake, total_consensus_stake_handle, total_deltas_handle,
+    validator_commission_rate_handle, validator_deltas_handle,
+    validator_epoch_summaries_handle, validator_state_handle,
+};
+use crate::types::{
+    EpochSummary, Historical, Provenance, ValidatorEpochSummary,
+    ValidatorState, WeightedValidator,
+};
+use crate::{is_validator, HistoryError, PosParams};
+
+/// Archive the summary of the given epoch and of its validators. This is done
+/// when the epoch has ended, once its data is final, so the PoS inflation
+/// minted for its rewards must have been applied already.
+pub fn record_epoch_summary<S>(
+    storage: &mut S,
+    params: &PosParams,
+    epoch: Epoch,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let total_stake = read_total_stake(storage, params, epoch)?;
+    let consensus_stake = total_consensus_stake_handle()
+        .get(storage, epoch, params)?
+        .unwrap_or_default();
+    let inflation: token::Amount = storage
+        .read(&params_storage::get_pos_inflation_amount_key())?
+        .unwrap_or_default();
+    epoch_summaries_handle().insert(
+        storage,
+        epoch,
+        EpochSummary {
+            total_stake,
+            consensus_stake,
+            inflation,
+        },
+    )?;
+
+    let validator_summaries = validator_epoch_summaries_handle().at(&epoch);
+    for validator in read_all_validator_addresses(storage, epoch)? {
+        let Some(state) =
+            validator_state_handle(&validator).get(storage, epoch, params)?
+        else {
+            continue;
+        };
+        let stake = read_validator_stake(storage, params, &validator, epoch)?;
+        let commission_rate = validator_commission_rate_handle(&validator)
+            .get(storage, epoch, params)?
+            .unwrap_or_default();
+        validator_summaries.insert(
+            storage,
+            validator,
+            ValidatorEpochSummary {
+                stake,
+                commission_rate,
+                state,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Read the archived summary of the given epoch.
+pub fn read_epoch_summary<S>(
+    storage: &S,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<EpochSummary>>
+where
+    S: StorageRead,
+{
+    epoch_summaries_handle().get(storage, &epoch)
+}
+
+/// Read the archived summary of a validator in the given epoch. Returns
+/// `None` if the address wasn't a validator in the epoch, or an error if the
+/// epoch hasn't been archived.
+pub fn read_validator_epoch_summary<S>(
+    storage: &S,
+    validator: &Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<ValidatorEpochSummary>>
+where
+    S: StorageRead,
+{
+    if !epoch_summaries_handle().contains(storage, &epoch)? {
+        return Err(HistoryError::Pruned(epoch).into());
+    }
+    validator_epoch_summaries_handle()
+        .at(&epoch)
+        .get(storage, validator)
+}
+
+/// Read the stake of a validator at the given epoch. Returns `None` if the
+/// address is not a validator.
+pub fn read_historical_validator_stake<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<Historical<token::Amount>>>
+where
+    S: StorageRead,
+{
+    if !validator_deltas_handle(validator).is_pruned(storage, epoch, params)? {
+        if !is_validator(storage, validator)? {
+            return Ok(None);
+        }
+        let stake = read_validator_stake(storage, params, validator, epoch)?;
+        return Ok(Some(Historical::live(stake)));
+    }
+    Ok(read_validator_epoch_summary(storage, validator, epoch)?
+        .map(|summary| Historical::archived(summary.stake)))
+}
+
+/// Read the commission rate of a validator at the given epoch. Returns `None`
+/// if the address wasn't a validator in the epoch.
+pub fn read_historical_validator_commission_rate<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<Historical<Dec>>>
+where
+    S: StorageRead,
+{
+    let handle = validator_commission_rate_handle(validator);
+    if !handle.is_pruned(storage, epoch, params)? {
+        return Ok(handle.get(storage, epoch, params)?.map(Historical::live));
+    }
+    Ok(read_validator_epoch_summary(storage, validator, epoch)?
+        .map(|summary| Historical::archived(summary.commission_rate)))
+}
+
+/// Read the state of a validator at the given epoch. Returns `None` if the
+/// address wasn't a validator in the epoch.
+pub fn read_historical_validator_state<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<Historical<ValidatorState>>>
+where
+    S: StorageRead,
+{
+    let handle = validator_state_handle(validator);
+    if !handle.is_pruned(storage, epoch, params)? {
+        return Ok(handle.get(storage, epoch, params)?.map(Historical::live));
+    }
+    Ok(read_validator_epoch_summary(storage, validator, epoch)?
+        .map(|summary| Historical::archived(summary.state)))
+}
+
+/// Read the total stake at the given epoch.
+pub fn read_historical_total_stake<S>(
+    storage: &S,
+    params: &PosParams,
+    epoch: Epoch,
+) -> namada_storage::Result<Historical<token::Amount>>
+where
+    S: StorageRead,
+{
+    if !total_deltas_handle().is_pruned(storage, epoch, params)? {
+        let stake = read_total_stake(storage, params, epoch)?;
+        return Ok(Historical::live(stake));
+    }
+    let summary = read_epoch_summary(storage, epoch)?
+        .ok_or(HistoryError::Pruned(epoch))?;
+    Ok(Historical::archived(summary.total_stake))
+}
+
+/// Read the consensus validators with their stake at the given epoch.
+pub fn read_historical_consensus_validator_set<S>(
+    storage: &S,
+    epoch: Epoch,
+) -> namada_storage::Result<Historical<Vec<WeightedValidator>>>
+where
+    S: StorageRead,
+{
+    if !consensus_validator_set_handle().is_pruned(storage, epoch)? {
+        let validators =
+            read_consensus_validator_set_addresses_with_stake(storage, epoch)?;
+        return Ok(Historical::live(validators.into_iter().collect()));
+    }
+    if !epoch_summaries_handle().contains(storage, &epoch)? {
+        return Err(HistoryError::Pruned(epoch).into());
+    }
+    let mut validators = vec![];
+    for result in validator_epoch_summaries_handle()
+        .at(&epoch)
+        .iter(storage)?
+    {
+        let (address, summary) = result?;
+        if summary.state == ValidatorState::Consensus {
+            validators.push(WeightedValidator {
+                bonded_stake: summary.stake,
+                address,
+            });
+        }
+    }
+    validators.sort();
+    Ok(Historical::archived(validators))
+}
+
+/// Read the yearly staking rewards rate of the network at the given epoch.
+/// The rate of an archived epoch is computed from the PoS inflation that was
+/// minted for it, while the rate of an epoch that hasn't been archived yet is
+/// estimated from the last inflation.
+pub fn read_historical_staking_reward_rate<S>(
+    storage: &S,
+    params: &PosParams,
+    epoch: Epoch,
+) -> namada_storage::Result<Historical<RewardRate>>
+where
+    S: StorageRead,
+{
+    match read_epoch_summary(storage, epoch)? {
+        Some(summary) => {
+            let epochs_per_year = read_epochs_per_year(storage)?;
+            staking_reward_rate(
+                summary.inflation,
+                summary.consensus_stake,
+                epochs_per_year,
+            )
+            .map(Historical::archived)
+        }
+        None if total_deltas_handle().is_pruned(storage, epoch, params)? => {
+            Err(HistoryError::Pruned(epoch).into())
+        }
+        None => estimate_staking_reward_rate(storage, params, epoch)
+            .map(Historical::live),
+    }
+}
+
+/// Read the yearly staking rewards rate of the delegations to the given
+/// validator at the given epoch, after its commission. Returns `None` if the
+/// address wasn't a validator in the epoch.
+pub fn read_historical_validator_staking_reward_rate<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<Historical<RewardRate>>>
+where
+    S: StorageRead,
+{
+    let network_rate =
+        read_historical_staking_reward_rate(storage, params, epoch)?;
+    match network_rate.provenance {
+        Provenance::Archived => {
+            let Some(summary) =
+                read_validator_epoch_summary(storage, validator, epoch)?
+            else {
+                return Ok(None);
+            };
+            let epochs_per_year = read_epochs_per_year(storage)?;
+            validator_staking_reward_rate(
+                &network_rate.value,
+                summary.state,
+                summary.commission_rate,
+                epochs_per_year,
+            )
+            .map(|rate| Some(Historical::archived(rate)))
+        }
+        Provenance::Live => estimate_validator_staking_reward_rate(
+            storage, params, validator, epoch,
+        )
+        .map(|rate| rate.map(Historical::live)),
+    }
+}
+
+fn read_epochs_per_year<S>(storage: &S) -> namada_storage::Result<u64>
+where
+    S: StorageRead,
+{
+    storage
+        .read(&params_storage::get_epochs_per_year_key())?
+        .ok_or_err_msg("Epochs per year should exist in storage")
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::address::testing::established_address_1;
+    use namada_governance::parameters::GovernanceParameters;
+    use namada_state::testing::TestWlStorage;
+
+    use super::*;
+    use crate::storage::{
+        read_pos_params, write_pos_params,
+        write_validator_max_commission_rate_change,
+    };
+    use crate::OwnedPosParams;
+
+    /// Test that the historical reads fall back to the archived summaries
+    /// once the epoched data is pruned, and fail clearly without them.
+    #[test]
+    fn test_historical_reads_from_summaries() {
+        let mut s = TestWlStorage::default();
+        GovernanceParameters::default()
+            .init_storage(&mut s)
+            .unwrap();
+        write_pos_params(&mut s, &OwnedPosParams::default()).unwrap();
+        let params = read_pos_params(&s).unwrap();
+        let validator = established_address_1();
+        let stake = token::Amount::native_whole(1_000);
+        let commission_rate = Dec::new(5, 2).unwrap();
+        write_validator_max_commission_rate_change(
+            &mut s,
+            &validator,
+            commission_rate,
+        )
+        .unwrap();
+
+        let pruned_epoch = Epoch(1);
+        let last_epoch = Epoch(100);
+        let deltas = validator_deltas_handle(&validator);
+        let total_deltas = total_deltas_handle();
+        deltas
+            .init_at_genesis(&mut s, stake.change(), Epoch(0))
+            .unwrap();
+        total_deltas
+            .init_at_genesis(&mut s, stake.change(), Epoch(0))
+            .unwrap();
+        assert!(!deltas.is_pruned(&s, pruned_epoch, &params).unwrap());
+
+        // Prune the deltas of the epoch with updates far past it
+        deltas
+            .set(&mut s, token::Change::zero(), last_epoch, 0)
+            .unwrap();
+        total_deltas
+            .set(&mut s, token::Change::zero(), last_epoch, 0)
+            .unwrap();
+        let consensus_set = consensus_validator_set_handle();
+        consensus_set.init(&mut s, Epoch(0)).unwrap();
+        consensus_set
+            .update_data(&mut s, &params, last_epoch)
+            .unwrap();
+        assert!(deltas.is_pruned(&s, pruned_epoch, &params).unwrap());
+        assert!(total_deltas.is_pruned(&s, pruned_epoch, &params).unwrap());
+        assert!(consensus_set.is_pruned(&s, pruned_epoch).unwrap());
+
+        // Without a summary, the pruned epoch can't be read
+        let err =
+            read_historical_total_stake(&s, &params, pruned_epoch).unwrap_err();
+        assert!(err.to_string().contains("pruned"));
+        assert!(
+            read_validator_epoch_summary(&s, &validator, pruned_epoch).is_err()
+        );
+
+        epoch_summaries_handle()
+            .insert(
+                &mut s,
+                pruned_epoch,
+                EpochSummary {
+                    total_stake: stake,
+                    consensus_stake: stake,
+                    inflation: token::Amount::native_whole(10),
+                },
+            )
+            .unwrap();
+        validator_epoch_summaries_handle()
+            .at(&pruned_epoch)
+            .insert(
+                &mut s,
+                validator.clone(),
+                ValidatorEpochSummary {
+                    stake,
+                    commission_rate,
+                    state: ValidatorState::Consensus,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            read_historical_validator_stake(
+                &s,
+                &params,
+                &validator,
+                pruned_epoch
+            )
+            .unwrap(),
+            Some(Historical::archived(stake))
+        );
+        assert_eq!(
+            read_historical_consensus_validator_set(&s, pruned_epoch).unwrap(),
+            Historical::archived(vec![WeightedValidator {
+                bonded_stake: stake,
+                address: validator.clone(),
+            }])
+        );
+        assert_eq!(
+            read_historical_total_stake(&s, &params, pruned_epoch).unwrap(),
+            Historical::archived(stake)
+        );
+        // The live data is still preferred when it's available
+        assert_eq!(
+            read_historical_validator_stake(
+                &s, &params, &validator, last_epoch
+            )
+            .unwrap(),
+            Some(Historical::live(stake))
+        );
+    }
+}
@@ -7,6 +7,7 @@
 #![deny(rustdoc::private_intra_doc_links)]
 
 pub mod epoched;
+pub mod history;
 pub mod parameters;
 pub mod pos_queries;
 pub mod queries;
@@ -32,8 +33,9 @@ use namada_core::types::dec::Dec;
 use namada_core::types::key::common;
 use namada_core::types::storage::BlockHeight;
 pub use namada_core::types::storage::{Epoch, Key, KeySeg};
+use namada_core::types::token::TrySum;
 use namada_storage::collections::lazy_map::{self, Collectable, LazyMap};
-use namada_storage::{StorageRead, StorageWrite};
+use namada_storage::{ResultExt, StorageRead, StorageWrite};
 pub use namada_trans_token as token;
 pub use parameters::{OwnedPosParams, PosParams};
 
@@ -54,28 +56,30 @@ use crate::storage::{
     read_consensus_validator_set_addresses, read_non_pos_owned_params,
     read_pos_params, read_validator_last_slash_epoch,
     read_validator_max_commission_rate_change, read_validator_stake,
+    record_slash_pool_inflow, scheduled_bonds_handle, scheduled_unbonds_handle,
     total_bonded_handle, total_consensus_stake_handle, total_unbonded_handle,
     try_insert_consensus_key, unbond_handle, update_total_deltas,
     update_validator_deltas, validator_addresses_handle,
     validator_commission_rate_handle, validator_consensus_key_handle,
     validator_deltas_handle, validator_eth_cold_key_handle,
     validator_eth_hot_key_handle, validator_incoming_redelegations_handle,
-    validator_outgoing_redelegations_handle, validator_protocol_key_handle,
-    validator_rewards_products_handle, validator_set_positions_handle,
-    validator_slashes_handle, validator_state_handle,
-    validator_total_redelegated_bonded_handle,
+    validator_outgoing_redelegations_handle, validator_performance_handle,
+    validator_protocol_key_handle, validator_rewards_products_handle,
+    validator_set_positions_handle, validator_slashes_handle,
+    validator_state_handle, validator_total_redelegated_bonded_handle,
     validator_total_redelegated_unbonded_handle, write_last_reward_claim_epoch,
     write_pos_params, write_validator_address_raw_hash, write_validator_avatar,
     write_validator_description, write_validator_discord_handle,
-    write_validator_email, write_validator_max_commission_rate_change,
-    write_validator_metadata, write_validator_website,
+    write_validator_email, write_validator_handle,
+    write_validator_max_commission_rate_change, write_validator_metadata,
+    write_validator_website,
 };
 use crate::storage_key::{bonds_for_source_prefix, is_bond_key};
 use crate::types::{
     BondId, ConsensusValidator, ConsensusValidatorSet,
     EagerRedelegatedBondsMap, RedelegatedBondsOrUnbonds, RedelegatedTokens,
-    ResultSlashing, Slash, Unbonds, ValidatorMetaData, ValidatorSetUpdate,
-    ValidatorState, VoteInfo,
+    ResultSlashing, ScheduledBonds, Slash, Unbonds, ValidatorMetaData,
+    ValidatorPerformance, ValidatorSetUpdate, ValidatorState, VoteInfo,
 };
 use crate::validator_set_update::{
     copy_validator_sets_and_positions, insert_validator_into_validator_set,
@@ -311,8 +315,7 @@ where
     consensus_validator_set_handle()
         .at(&epoch)
         .iter(storage)?
-        .fold(Ok(token::Amount::zero()), |acc, entry| {
-            let acc = acc?;
+        .map(|entry| {
             let (
                 lazy_map::NestedSubKey::Data {
                     key: amount,
@@ -320,10 +323,12 @@ where
                 },
                 _validator,
             ) = entry?;
-            Ok(acc.checked_add(amount).expect(
-                "Total consensus stake computation should not overflow.",
-            ))
+            Ok(amount)
         })
+        .collect::<namada_storage::Result<Vec<token::Amount>>>()?
+        .into_iter()
+        .try_sum()
+        .into_storage_result()
 }
 
 /// Compute and then store the total consensus stake
@@ -750,6 +755,286 @@ where
     Ok(result_slashing)
 }
 
+/// Schedule a self-bond or a delegation to be applied in the given future
+/// `start_epoch`, from which it takes effect at the pipeline offset like any
+/// other bond. The bonded tokens are transferred from the source to PoS right
+/// away and are returned to the source if the bond can no longer be applied
+/// when its start epoch is reached.
+pub fn schedule_bond_tokens<S>(
+    storage: &mut S,
+    source: Option<&Address>,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+    start_epoch: Epoch,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    tracing::debug!(
+        "Scheduling a bond of token amount {} at epoch {current_epoch} to be \
+         applied at epoch {start_epoch}",
+        amount.to_string_native()
+    );
+    if amount.is_zero() {
+        return Ok(());
+    }
+
+    let params = read_pos_params(storage)?;
+    if !is_valid_scheduled_epoch(&params, current_epoch, start_epoch) {
+        return Err(BondError::InvalidScheduledEpoch(
+            start_epoch,
+            current_epoch,
+            params.max_scheduling_offset,
+        )
+        .into());
+    }
+    if let Some(source) = source {
+        if source != validator && is_validator(storage, source)? {
+            return Err(
+                BondError::SourceMustNotBeAValidator(source.clone()).into()
+            );
+        }
+    }
+    if !is_validator(storage, validator)? {
+        return Err(BondError::NotAValidator(validator.clone()).into());
+    }
+    let source = source.unwrap_or(validator);
+
+    // Hold the tokens in PoS until the bond is applied
+    let staking_token = staking_token_address(storage);
+    token::transfer(storage, &staking_token, source, &ADDRESS, amount)?;
+
+    add_scheduled_bond(
+        storage,
+        &scheduled_bonds_handle(),
+        start_epoch,
+        source,
+        validator,
+        amount,
+    )
+}
+
+/// Schedule an unbond from a self-bond or a delegation to be applied in the
+/// given future `start_epoch`. The bond is only checked against the amount
+/// currently bonded at the pipeline offset; the unbond is dropped if there are
+/// not enough bonded tokens left when its start epoch is reached.
+pub fn schedule_unbond_tokens<S>(
+    storage: &mut S,
+    source: Option<&Address>,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+    start_epoch: Epoch,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    tracing::debug!(
+        "Scheduling an unbond of token amount {} at epoch {current_epoch} to \
+         be applied at epoch {start_epoch}",
+        amount.to_string_native()
+    );
+    if amount.is_zero() {
+        return Ok(());
+    }
+
+    let params = read_pos_params(storage)?;
+    if !is_valid_scheduled_epoch(&params, current_epoch, start_epoch) {
+        return Err(UnbondError::InvalidScheduledEpoch(
+            start_epoch,
+            current_epoch,
+            params.max_scheduling_offset,
+        )
+        .into());
+    }
+    if let Some(source) = source {
+        if source != validator && is_validator(storage, source)? {
+            return Err(
+                BondError::SourceMustNotBeAValidator(source.clone()).into()
+            );
+        }
+    }
+    if !is_validator(storage, validator)? {
+        return Err(BondError::NotAValidator(validator.clone()).into());
+    }
+    let source = source.unwrap_or(validator);
+
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let remaining_at_pipeline = bond_handle(source, validator)
+        .get_sum(storage, pipeline_epoch, &params)?
+        .unwrap_or_default();
+    if amount > remaining_at_pipeline {
+        return Err(UnbondError::UnbondAmountGreaterThanBond(
+            amount.to_string_native(),
+            remaining_at_pipeline.to_string_native(),
+        )
+        .into());
+    }
+
+    add_scheduled_bond(
+        storage,
+        &scheduled_unbonds_handle(),
+        start_epoch,
+        source,
+        validator,
+        amount,
+    )
+}
+
+/// Apply the bonds and unbonds that have been scheduled for the
+/// `current_epoch`. This must be called once at the beginning of every epoch,
+/// after the validator sets have been copied to the pipeline epoch.
+///
+/// Scheduled bonds and unbonds that are no longer valid (e.g. the source
+/// has become a validator, or there's not enough tokens left to unbond) are
+/// dropped, in which case the tokens held for a bond are returned to its
+/// source.
+pub fn process_scheduled_bonds<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let params = read_pos_params(storage)?;
+    let staking_token = staking_token_address(storage);
+
+    let bonds = take_scheduled_bonds(
+        storage,
+        &scheduled_bonds_handle(),
+        current_epoch,
+    )?;
+    for (source, validator, amount) in bonds {
+        // Release the held tokens back to the source, from which they get
+        // bonded
+        token::transfer(storage, &staking_token, &ADDRESS, &source, amount)?;
+
+        let is_valid_source =
+            source == validator || !is_validator(storage, &source)?;
+        if !is_valid_source || !is_validator(storage, &validator)? {
+            tracing::info!(
+                "Dropping the bond of {} from {source} to {validator} \
+                 scheduled for epoch {current_epoch}",
+                amount.to_string_native()
+            );
+            continue;
+        }
+        bond_tokens(
+            storage,
+            Some(&source),
+            &validator,
+            amount,
+            current_epoch,
+            None,
+        )?;
+    }
+
+    let unbonds = take_scheduled_bonds(
+        storage,
+        &scheduled_unbonds_handle(),
+        current_epoch,
+    )?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    for (source, validator, amount) in unbonds {
+        let is_valid_source =
+            source == validator || !is_validator(storage, &source)?;
+        let is_valid_unbond = is_valid_source
+            && is_validator(storage, &validator)?
+            && !is_validator_frozen(
+                storage,
+                &validator,
+                current_epoch,
+                &params,
+            )?
+            && amount
+                <= bond_handle(&source, &validator)
+                    .get_sum(storage, pipeline_epoch, &params)?
+                    .unwrap_or_default();
+        if !is_valid_unbond {
+            tracing::info!(
+                "Dropping the unbond of {} from {source} to {validator} \
+                 scheduled for epoch {current_epoch}",
+                amount.to_string_native()
+            );
+            continue;
+        }
+        unbond_tokens(
+            storage,
+            Some(&source),
+            &validator,
+            amount,
+            current_epoch,
+            false,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Check that the `start_epoch` of a scheduled bond or unbond is after the
+/// current epoch and within the maximum scheduling offset.
+fn is_valid_scheduled_epoch(
+    params: &PosParams,
+    current_epoch: Epoch,
+    start_epoch: Epoch,
+) -> bool {
+    start_epoch > current_epoch
+        && start_epoch <= current_epoch + params.max_scheduling_offset
+}
+
+/// Add the amount to a scheduled bond or unbond.
+fn add_scheduled_bond<S>(
+    storage: &mut S,
+    handle: &ScheduledBonds,
+    start_epoch: Epoch,
+    source: &Address,
+    validator: &Address,
+    amount: token::Amount,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let amounts = handle.at(&start_epoch).at(source);
+    let scheduled = amounts
+        .get(storage, validator)?
+        .unwrap_or_default()
+        .checked_add(amount)
+        .ok_or_else(|| {
+            namada_storage::Error::new_const("Scheduled bond amount overflowed")
+        })?;
+    amounts.insert(storage, validator.clone(), scheduled)?;
+    Ok(())
+}
+
+/// Remove and return all the bonds or unbonds scheduled for the given epoch,
+/// as a list of `(source, validator, amount)`.
+fn take_scheduled_bonds<S>(
+    storage: &mut S,
+    handle: &ScheduledBonds,
+    epoch: Epoch,
+) -> namada_storage::Result<Vec<(Address, Address, token::Amount)>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let scheduled = handle
+        .at(&epoch)
+        .iter(storage)?
+        .map(|res| {
+            let (
+                lazy_map::NestedSubKey::Data {
+                    key: source,
+                    nested_sub_key: lazy_map::SubKey::Data(validator),
+                },
+                amount,
+            ) = res?;
+            Ok((source, validator, amount))
+        })
+        .collect::<namada_storage::Result<Vec<_>>>()?;
+    handle.remove_all(storage, &epoch)?;
+    Ok(scheduled)
+}
+
 #[derive(Debug, Default, Eq, PartialEq)]
 struct FoldRedelegatedBondsResult {
     total_redelegated: token::Amount,
@@ -1444,6 +1729,14 @@ where
         "Withdrawing total {}",
         withdrawable_amount.to_string_native()
     );
+    let total_unbonded = unbonds_and_redelegated_unbonds
+        .values()
+        .map(|(amount, _)| *amount)
+        .try_sum()
+        .into_storage_result()?;
+    let total_slashed = total_unbonded
+        .checked_sub(withdrawable_amount)
+        .unwrap_or_default();
 
     // `updateDelegator` with `unbonded` and `redelegeatedUnbonded`
     for ((start_epoch, withdraw_epoch), _unbond_and_redelegations) in
@@ -1475,15 +1768,22 @@ where
         withdrawable_amount,
     )?;
 
-    // TODO: Transfer the slashed tokens from the PoS address to the Slash Pool
+    // Transfer the slashed tokens from the PoS address to the Slash Pool
     // address
-    // token::transfer(
-    //     storage,
-    //     &staking_token,
-    //     &ADDRESS,
-    //     &SLASH_POOL_ADDRESS,
-    //     total_slashed,
-    // )?;
+    if !total_slashed.is_zero() {
+        tracing::debug!(
+            "Moving slashed {} to the slash pool",
+            total_slashed.to_string_native()
+        );
+        token::transfer(
+            storage,
+            &staking_token,
+            &ADDRESS,
+            &SLASH_POOL_ADDRESS,
+            total_slashed,
+        )?;
+        record_slash_pool_inflow(storage, current_epoch, total_slashed)?;
+    }
 
     Ok(withdrawable_amount)
 }
@@ -1587,7 +1887,8 @@ where
         ) = next?;
         // This is the first epoch in which the unbond stops contributing to
         // voting power
-        let end = withdrawable_epoch - params.withdrawable_epoch_offset()
+        let end = withdrawable_epoch
+            .saturating_sub(params.withdrawable_epoch_offset())
             + params.pipeline_len;
 
         if start <= epoch && end > epoch {
@@ -1713,7 +2014,7 @@ where
         }
     }
 
-    Ok(amounts.values().cloned().sum())
+    amounts.values().cloned().try_sum().into_storage_result()
 }
 
 /// Get bond amounts within the `claim_start..=claim_end` epoch range for
@@ -1810,11 +2111,15 @@ where
         }
     }
 
-    Ok(amounts
+    amounts
         .into_iter()
         // Flatten the inner maps to discard bond start epochs
-        .map(|(ep, amounts)| (ep, amounts.values().cloned().sum()))
-        .collect())
+        .map(|(ep, amounts)| {
+            let amount =
+                amounts.values().cloned().try_sum().into_storage_result()?;
+            Ok((ep, amount))
+        })
+        .collect()
 }
 
 /// Get the genesis consensus validators stake and consensus key for Tendermint,
@@ -2478,6 +2783,109 @@ where
     Ok(())
 }
 
+/// Record the performance of the validators with liveness data at the end of
+/// an epoch, from their missed votes up to the given height of the last
+/// votes of the epoch.
+pub fn record_validator_performance<S>(
+    storage: &mut S,
+    params: &PosParams,
+    current_epoch: Epoch,
+    last_votes_height: BlockHeight,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let liveness_missed_votes = liveness_missed_votes_handle();
+    let validator_performance = validator_performance_handle();
+    let window = cmp::min(params.liveness_window_check, last_votes_height.0);
+
+    let validators: Vec<(Address, u64)> = liveness_sum_missed_votes_handle()
+        .iter(storage)?
+        .collect::<namada_storage::Result<_>>()?;
+
+    for (validator, missed_votes) in validators {
+        let mut missed_heights = liveness_missed_votes
+            .at(&validator)
+            .iter(storage)?
+            .collect::<namada_storage::Result<Vec<u64>>>()?;
+        missed_heights.sort_unstable();
+        let (current_missed_streak, longest_missed_streak) =
+            missed_votes_streaks(&missed_heights, last_votes_height.0);
+
+        let performance = validator_performance
+            .get(storage, &validator)?
+            .unwrap_or_default();
+        validator_performance.insert(
+            storage,
+            validator,
+            ValidatorPerformance {
+                epoch: current_epoch,
+                signed_blocks: window.saturating_sub(missed_votes),
+                window,
+                current_missed_streak,
+                longest_missed_streak,
+                ..performance
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Find the current and the longest streaks of consecutive missed votes, from
+/// the sorted heights of the missed votes. The current streak is the one that
+/// ends at the given height of the last votes.
+fn missed_votes_streaks(
+    missed_heights: &[u64],
+    last_height: u64,
+) -> (u64, u64) {
+    let mut longest = 0;
+    let mut streak = 0;
+    let mut prev_height: Option<u64> = None;
+    for &height in missed_heights {
+        streak = match prev_height {
+            Some(prev) if prev + 1 == height => streak + 1,
+            _ => 1,
+        };
+        longest = cmp::max(longest, streak);
+        prev_height = Some(height);
+    }
+    let current = if prev_height == Some(last_height) {
+        streak
+    } else {
+        0
+    };
+    (current, longest)
+}
+
+/// Record the participation of the validators in the vote on a governance
+/// proposal. The validators that could vote are the members of the consensus
+/// set in the last epoch of the voting period.
+pub fn record_governance_participation<S>(
+    storage: &mut S,
+    voting_end_epoch: Epoch,
+    voters: &HashSet<Address>,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let validator_performance = validator_performance_handle();
+    let eligible_validators =
+        read_consensus_validator_set_addresses(storage, voting_end_epoch)?;
+
+    for validator in eligible_validators {
+        let voted = u64::from(voters.contains(&validator));
+        let mut performance = validator_performance
+            .get(storage, &validator)?
+            .unwrap_or_default();
+        performance.proposals_eligible += 1;
+        performance.proposals_voted += voted;
+        validator_performance.insert(storage, validator, performance)?;
+    }
+
+    Ok(())
+}
+
 #[cfg(any(test, feature = "testing"))]
 /// PoS related utility functions to help set up tests.
 pub mod test_utils {
@@ -2574,8 +2982,8 @@ pub mod test_utils {
 }
 
 /// Change validator's metadata. In addition to changing any of the data from
-/// [`ValidatorMetaData`], the validator's commission rate can be changed within
-/// here as well.
+/// [`ValidatorMetaData`], the validator's unique handle and commission rate can
+/// be changed within here as well.
 #[allow(clippy::too_many_arguments)]
 pub fn change_validator_metadata<S>(
     storage: &mut S,
@@ -2585,6 +2993,7 @@ pub fn change_validator_metadata<S>(
     website: Option<String>,
     discord_handle: Option<String>,
     avatar: Option<String>,
+    handle: Option<String>,
     commission_rate: Option<Dec>,
     current_epoch: Epoch,
 ) -> namada_storage::Result<()>
@@ -2606,6 +3015,9 @@ where
     if let Some(avatar) = avatar {
         write_validator_avatar(storage, validator, &avatar)?;
     }
+    if let Some(handle) = handle {
+        write_validator_handle(storage, validator, &handle)?;
+    }
     if let Some(commission_rate) = commission_rate {
         change_validator_commission_rate(
             storage,
@@ -2625,6 +3037,23 @@ pub fn claim_reward_tokens<S>(
     validator: &Address,
     current_epoch: Epoch,
 ) -> namada_storage::Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    let target = source.unwrap_or(validator).clone();
+    claim_reward_tokens_to(storage, source, validator, &target, current_epoch)
+}
+
+/// Claim available rewards and transfer them to the given target, rather than
+/// to the source of the bond. This is used to deliver the rewards into the
+/// shielded pool.
+pub fn claim_reward_tokens_to<S>(
+    storage: &mut S,
+    source: Option<&Address>,
+    validator: &Address,
+    target: &Address,
+    current_epoch: Epoch,
+) -> namada_storage::Result<token::Amount>
 where
     S: StorageRead + StorageWrite,
 {
@@ -2646,9 +3075,9 @@ where
     // Update the last claim epoch in storage
     write_last_reward_claim_epoch(storage, &source, validator, current_epoch)?;
 
-    // Transfer the bonded tokens from PoS to the source
+    // Transfer the reward tokens from PoS to the target
     let staking_token = staking_token_address(storage);
-    token::transfer(storage, &staking_token, &ADDRESS, &source, reward_tokens)?;
+    token::transfer(storage, &staking_token, &ADDRESS, target, reward_tokens)?;
 
     Ok(reward_tokens)
 }
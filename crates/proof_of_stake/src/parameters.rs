@@ -66,6 +66,9 @@ pub struct OwnedPosParams {
     /// The minimum required activity of consesus validators, in percentage,
     /// over the `liveness_window_check`
     pub liveness_threshold: Dec,
+    /// The maximum number of epochs after the current epoch at which a bond
+    /// or an unbond may be scheduled to be applied
+    pub max_scheduling_offset: u64,
     /// PoS gain p (read only)
     pub rewards_gain_p: Dec,
     /// PoS gain d (read only)
@@ -107,6 +110,7 @@ impl Default for OwnedPosParams {
             validator_stake_threshold: token::Amount::native_whole(1_u64),
             liveness_window_check: 10_000,
             liveness_threshold: Dec::new(9, 1).expect("Test failed"),
+            max_scheduling_offset: 30,
             rewards_gain_p: Dec::from_str("0.25").expect("Test failed"),
             rewards_gain_d: Dec::from_str("0.25").expect("Test failed"),
         }
@@ -207,8 +211,8 @@ impl OwnedPosParams {
         &self,
         infraction_epoch: Epoch,
     ) -> (Epoch, Epoch) {
-        let start = infraction_epoch
-            .sub_or_default(Epoch(self.cubic_slashing_window_length));
+        let start =
+            infraction_epoch.saturating_sub(self.cubic_slashing_window_length);
         let end = infraction_epoch + self.cubic_slashing_window_length;
         (start, end)
     }
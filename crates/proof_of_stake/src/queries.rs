@@ -7,9 +7,9 @@ use borsh::BorshDeserialize;
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
 use namada_core::types::storage::Epoch;
-use namada_core::types::token;
+use namada_core::types::token::{self, TrySum};
 use namada_storage::collections::lazy_map::{NestedSubKey, SubKey};
-use namada_storage::{self, StorageRead};
+use namada_storage::{self, ResultExt, StorageRead};
 
 use crate::slashing::{find_validator_slashes, get_slashed_amount};
 use crate::storage::{bond_handle, read_pos_params, unbond_handle};
@@ -92,11 +92,12 @@ where
 {
     let max_epoch = Epoch(u64::MAX);
     let delegations = find_delegations(storage, source, &max_epoch)?;
-    Ok(!delegations
+    let total = delegations
         .values()
         .cloned()
-        .sum::<token::Amount>()
-        .is_zero())
+        .try_sum()
+        .into_storage_result()?;
+    Ok(!total.is_zero())
 }
 
 /// Find raw bond deltas for the given source and validator address.
@@ -429,12 +430,9 @@ fn make_unbond_details(
     for slash in slashes {
         if slash.epoch >= start
             && slash.epoch
-                < withdraw
-                    .checked_sub(
-                        params.unbonding_len
-                            + params.cubic_slashing_window_length,
-                    )
-                    .unwrap_or_default()
+                < withdraw.saturating_sub(
+                    params.unbonding_len + params.cubic_slashing_window_length,
+                )
         {
             let cur_rate = slash_rates_by_epoch.entry(slash.epoch).or_default();
             *cur_rate = cmp::min(Dec::one(), *cur_rate + slash.rate);
@@ -2,7 +2,7 @@
 
 use std::collections::{HashMap, HashSet};
 
-use namada_core::ledger::inflation;
+use namada_core::ledger::inflation::{self, RewardRate};
 use namada_core::types::address::{self, Address};
 use namada_core::types::dec::Dec;
 use namada_core::types::storage::Epoch;
@@ -10,7 +10,7 @@ use namada_core::types::token::{self, Amount};
 use namada_core::types::uint::{Uint, I256};
 use namada_parameters::storage as params_storage;
 use namada_storage::collections::lazy_map::NestedSubKey;
-use namada_storage::{ResultExt, StorageRead, StorageWrite};
+use namada_storage::{OptionExt, ResultExt, StorageRead, StorageWrite};
 use thiserror::Error;
 
 use crate::storage::{
@@ -19,7 +19,7 @@ use crate::storage::{
     rewards_accumulator_handle, validator_commission_rate_handle,
     validator_rewards_products_handle, validator_state_handle,
 };
-use crate::token::credit_tokens;
+use crate::token::mint_native_tokens;
 use crate::token::storage_key::minted_balance_key;
 use crate::types::{into_tm_voting_power, BondId, ValidatorState, VoteInfo};
 use crate::{
@@ -303,11 +303,21 @@ where
         d_gain_nom: pos_d_gain_nom,
         epochs_per_year,
     };
-    // Run the rewards controllers
-    let inflation::PosValsToUpdate {
-        locked_ratio,
-        inflation,
-    } = pos_controller.run();
+    // Run the rewards controllers. A malformed parameter must not halt the
+    // chain, so no tokens are minted if it's invalid.
+    let (locked_ratio, inflation) = match pos_controller.run() {
+        Ok(inflation::PosValsToUpdate {
+            locked_ratio,
+            inflation,
+        }) => (locked_ratio, inflation),
+        Err(err) => {
+            tracing::warn!(
+                "PoS inflation assumed to be 0 because it couldn't be \
+                 computed: {err}. Please check the inflation parameters."
+            );
+            (pos_last_staked_ratio, Uint::zero())
+        }
+    };
 
     let inflation =
         token::Amount::from_uint(inflation, 0).into_storage_result()?;
@@ -320,7 +330,6 @@ where
         last_epoch,
         num_blocks_in_last_epoch,
         inflation,
-        &staking_token,
     )?;
 
     // Write new rewards parameters that will be used for the inflation of
@@ -332,6 +341,100 @@ where
     Ok(())
 }
 
+/// Estimate the yearly staking rewards rate at the given epoch. The estimate
+/// is based on the PoS inflation of the last epoch, which is spread over the
+/// stake of the consensus validators.
+pub fn estimate_staking_reward_rate<S>(
+    storage: &S,
+    params: &PosParams,
+    epoch: Epoch,
+) -> namada_storage::Result<RewardRate>
+where
+    S: StorageRead,
+{
+    let epochs_per_year: u64 = storage
+        .read(&params_storage::get_epochs_per_year_key())?
+        .ok_or_err_msg("Epochs per year should exist in storage")?;
+    let last_inflation_amount: token::Amount = storage
+        .read(&params_storage::get_pos_inflation_amount_key())?
+        .unwrap_or_default();
+    let consensus_stake = get_total_consensus_stake(storage, epoch, params)?;
+    staking_reward_rate(last_inflation_amount, consensus_stake, epochs_per_year)
+}
+
+/// The yearly staking rewards rate of an epoch in which the given PoS
+/// inflation is spread over the given stake of the consensus validators.
+pub fn staking_reward_rate(
+    inflation: token::Amount,
+    consensus_stake: token::Amount,
+    epochs_per_year: u64,
+) -> namada_storage::Result<RewardRate> {
+    let apr = if consensus_stake.is_zero() {
+        Dec::zero()
+    } else {
+        (Dec::from(inflation) * epochs_per_year)
+            .trunc_div(&Dec::from(consensus_stake))
+            .ok_or_err_msg("Overflow in the staking rewards rate")?
+    };
+    RewardRate::from_apr(apr, epochs_per_year)
+        .ok_or_err_msg("Overflow in the staking rewards yield")
+}
+
+/// The yearly staking rewards rate of the delegations to a validator in the
+/// given state and with the given commission rate, from the rate of the
+/// network.
+pub fn validator_staking_reward_rate(
+    network_rate: &RewardRate,
+    state: ValidatorState,
+    commission_rate: Dec,
+    epochs_per_year: u64,
+) -> namada_storage::Result<RewardRate> {
+    if state != ValidatorState::Consensus {
+        return Ok(RewardRate::default());
+    }
+    let apr = network_rate.apr * (Dec::one() - commission_rate);
+    RewardRate::from_apr(apr, epochs_per_year)
+        .ok_or_err_msg("Overflow in the staking rewards yield")
+}
+
+/// Estimate the yearly staking rewards rate of the delegations to the given
+/// validator at the given epoch, after the validator's commission. Only the
+/// consensus validators earn rewards. Returns `None` if the address is not a
+/// validator.
+pub fn estimate_validator_staking_reward_rate<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<RewardRate>>
+where
+    S: StorageRead,
+{
+    let Some(state) =
+        validator_state_handle(validator).get(storage, epoch, params)?
+    else {
+        return Ok(None);
+    };
+    if state != ValidatorState::Consensus {
+        return Ok(Some(RewardRate::default()));
+    }
+    let commission_rate = validator_commission_rate_handle(validator)
+        .get(storage, epoch, params)?
+        .unwrap_or_default();
+    let epochs_per_year: u64 = storage
+        .read(&params_storage::get_epochs_per_year_key())?
+        .ok_or_err_msg("Epochs per year should exist in storage")?;
+
+    let network_rate = estimate_staking_reward_rate(storage, params, epoch)?;
+    validator_staking_reward_rate(
+        &network_rate,
+        state,
+        commission_rate,
+        epochs_per_year,
+    )
+    .map(Some)
+}
+
 #[derive(Clone, Debug)]
 struct Rewards {
     product: Dec,
@@ -339,7 +442,8 @@ struct Rewards {
 }
 
 /// Update validator and delegators rewards products and mint the inflation
-/// tokens into the PoS account.
+/// tokens into the PoS account, within the limit of native tokens minted per
+/// epoch.
 /// Any left-over inflation tokens from rounding error of the sum of the
 /// rewards is given to the governance address.
 pub fn update_rewards_products_and_mint_inflation<S>(
@@ -348,7 +452,6 @@ pub fn update_rewards_products_and_mint_inflation<S>(
     last_epoch: Epoch,
     num_blocks_in_last_epoch: u64,
     inflation: token::Amount,
-    staking_token: &Address,
 ) -> namada_storage::Result<()>
 where
     S: StorageRead + StorageWrite,
@@ -422,7 +525,15 @@ where
         pos_reward_tokens.to_string_native(),
         inflation.to_string_native(),
     );
-    credit_tokens(storage, staking_token, &address::POS, pos_reward_tokens)?;
+    let deferred =
+        mint_native_tokens(storage, &address::POS, pos_reward_tokens)?;
+    if !deferred.is_zero() {
+        tracing::warn!(
+            "Deferred minting {} tokens for PoS rewards distribution that \
+             exceed the limit of native tokens minted per epoch.",
+            deferred.to_string_native()
+        );
+    }
 
     if reward_tokens_remaining > token::Amount::zero() {
         tracing::info!(
@@ -430,12 +541,7 @@ where
              Governance account. Amount: {}.",
             reward_tokens_remaining.to_string_native()
         );
-        credit_tokens(
-            storage,
-            staking_token,
-            &address::GOV,
-            reward_tokens_remaining,
-        )?;
+        mint_native_tokens(storage, &address::GOV, reward_tokens_remaining)?;
     }
 
     // Clear validator rewards accumulators
@@ -107,11 +107,11 @@ where
 {
     let params = read_pos_params(storage)?;
 
-    if current_epoch.0 < params.slash_processing_epoch_offset() {
+    let Some(infraction_epoch) =
+        current_epoch.checked_sub(params.slash_processing_epoch_offset())
+    else {
         return Ok(());
-    }
-    let infraction_epoch =
-        current_epoch - params.slash_processing_epoch_offset();
+    };
 
     // Slashes to be processed in the current epoch
     let enqueued_slashes = enqueued_slashes_handle().at(&current_epoch);
@@ -286,7 +286,7 @@ where
     S: StorageRead,
 {
     let infraction_epoch =
-        current_epoch - params.slash_processing_epoch_offset();
+        current_epoch.saturating_sub(params.slash_processing_epoch_offset());
 
     for res in outgoing_redelegations.iter(storage)? {
         let (
@@ -353,7 +353,7 @@ where
     );
 
     let infraction_epoch =
-        current_epoch - params.slash_processing_epoch_offset();
+        current_epoch.saturating_sub(params.slash_processing_epoch_offset());
 
     // Slash redelegation destination validator from the next epoch only
     // as they won't be jailed
@@ -463,7 +463,7 @@ where
 {
     tracing::debug!("Slashing validator {} at rate {}", validator, slash_rate);
     let infraction_epoch =
-        current_epoch - params.slash_processing_epoch_offset();
+        current_epoch.saturating_sub(params.slash_processing_epoch_offset());
 
     let total_unbonded = total_unbonded_handle(validator);
     let total_redelegated_unbonded =
@@ -940,9 +940,9 @@ where
     {
         // TODO: check if slashes in the same epoch can be
         // folded into one effective slash
-        let end_epoch = *withdraw_epoch
-            - params.unbonding_len
-            - params.cubic_slashing_window_length;
+        let end_epoch = withdraw_epoch.saturating_sub(
+            params.unbonding_len + params.cubic_slashing_window_length,
+        );
         // Find slashes that apply to `start_epoch..end_epoch`
         let list_slashes = slashes
             .iter()
@@ -1,7 +1,7 @@
 //! PoS functions for reading and writing to storage and lazy collection handles
 //! associated with given `storage_key`s.
 
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use namada_account::protocol_pk_key;
 use namada_core::types::address::Address;
@@ -19,15 +19,16 @@ use crate::storage_key::consensus_keys_key;
 use crate::types::{
     BelowCapacityValidatorSets, BondId, Bonds, CommissionRates,
     ConsensusValidatorSets, DelegatorRedelegatedBonded,
-    DelegatorRedelegatedUnbonded, EpochedSlashes, IncomingRedelegations,
-    LivenessMissedVotes, LivenessSumMissedVotes, OutgoingRedelegations,
-    ReverseOrdTokenAmount, RewardsAccumulator, RewardsProducts, Slashes,
+    DelegatorRedelegatedUnbonded, EpochSummaries, EpochedSlashes,
+    IncomingRedelegations, LivenessMissedVotes, LivenessSumMissedVotes,
+    OutgoingRedelegations, ReverseOrdTokenAmount, RewardsAccumulator,
+    RewardsProducts, ScheduledBonds, SlashPoolFlows, Slashes,
     TotalConsensusStakes, TotalDeltas, TotalRedelegatedBonded,
     TotalRedelegatedUnbonded, Unbonds, ValidatorAddresses,
-    ValidatorConsensusKeys, ValidatorDeltas, ValidatorEthColdKeys,
-    ValidatorEthHotKeys, ValidatorMetaData, ValidatorProtocolKeys,
-    ValidatorSetPositions, ValidatorState, ValidatorStates,
-    ValidatorTotalUnbonded, WeightedValidator,
+    ValidatorConsensusKeys, ValidatorDeltas, ValidatorEpochSummaries,
+    ValidatorEthColdKeys, ValidatorEthHotKeys, ValidatorMetaData,
+    ValidatorPerformances, ValidatorProtocolKeys, ValidatorSetPositions,
+    ValidatorState, ValidatorStates, ValidatorTotalUnbonded, WeightedValidator,
 };
 use crate::{storage_key, MetadataError, OwnedPosParams, PosParams};
 
@@ -244,6 +245,52 @@ pub fn liveness_sum_missed_votes_handle() -> LivenessSumMissedVotes {
     LivenessSumMissedVotes::open(key)
 }
 
+/// Get the storage handle to the performance records of the validators
+pub fn validator_performance_handle() -> ValidatorPerformances {
+    let key = storage_key::validator_performance_key();
+    ValidatorPerformances::open(key)
+}
+
+/// Get the storage handle to the archived summaries of the past epochs
+pub fn epoch_summaries_handle() -> EpochSummaries {
+    let key = storage_key::epoch_summaries_key();
+    EpochSummaries::open(key)
+}
+
+/// Get the storage handle to the archived summaries of the validators in the
+/// past epochs
+pub fn validator_epoch_summaries_handle() -> ValidatorEpochSummaries {
+    let key = storage_key::validator_epoch_summaries_key();
+    ValidatorEpochSummaries::open(key)
+}
+
+/// Get the storage handle to the tokens moved into the slash pool per epoch
+pub fn slash_pool_inflows_handle() -> SlashPoolFlows {
+    let key = storage_key::slash_pool_inflows_key();
+    SlashPoolFlows::open(key)
+}
+
+/// Get the storage handle to the tokens disbursed or burned from the slash
+/// pool per epoch
+pub fn slash_pool_outflows_handle() -> SlashPoolFlows {
+    let key = storage_key::slash_pool_outflows_key();
+    SlashPoolFlows::open(key)
+}
+
+/// Get the storage handle to the bonds scheduled to be applied in a future
+/// epoch
+pub fn scheduled_bonds_handle() -> ScheduledBonds {
+    let key = storage_key::scheduled_bonds_key();
+    ScheduledBonds::open(key)
+}
+
+/// Get the storage handle to the unbonds scheduled to be applied in a future
+/// epoch
+pub fn scheduled_unbonds_handle() -> ScheduledBonds {
+    let key = storage_key::scheduled_unbonds_key();
+    ScheduledBonds::open(key)
+}
+
 // ---- Storage read + write ----
 
 /// Read PoS parameters
@@ -780,6 +827,85 @@ where
     }
 }
 
+/// The minimum length of a validator's handle
+pub const MIN_VALIDATOR_HANDLE_LEN: usize = 3;
+
+/// The maximum length of a validator's handle
+pub const MAX_VALIDATOR_HANDLE_LEN: usize = 20;
+
+/// Check that a validator's handle is made of [`MIN_VALIDATOR_HANDLE_LEN`] to
+/// [`MAX_VALIDATOR_HANDLE_LEN`] lowercase ASCII letters, digits, `-` or `_`.
+pub fn validate_validator_handle(handle: &str) -> Result<()> {
+    let is_valid = (MIN_VALIDATOR_HANDLE_LEN..=MAX_VALIDATOR_HANDLE_LEN)
+        .contains(&handle.len())
+        && handle.bytes().all(
+            |byte| matches!(byte, b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_'),
+        );
+    if is_valid {
+        Ok(())
+    } else {
+        Err(MetadataError::InvalidHandle(handle.to_string()).into())
+    }
+}
+
+/// Read PoS validator's handle.
+pub fn read_validator_handle<S>(
+    storage: &S,
+    validator: &Address,
+) -> namada_storage::Result<Option<String>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage_key::validator_handle_key(validator))
+}
+
+/// Get the address of the validator registered with the given handle
+pub fn find_validator_by_handle<S>(
+    storage: &S,
+    handle: impl AsRef<str>,
+) -> namada_storage::Result<Option<Address>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage_key::validator_by_handle_key(handle))
+}
+
+/// Write PoS validator's handle, which must not be registered by another
+/// validator, and release its previous handle. If the provided arg is an
+/// empty string, remove the handle.
+pub fn write_validator_handle<S>(
+    storage: &mut S,
+    validator: &Address,
+    handle: &String,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if !handle.is_empty() {
+        validate_validator_handle(handle)?;
+        match find_validator_by_handle(storage, handle)? {
+            Some(owner) if &owner == validator => return Ok(()),
+            Some(owner) => {
+                return Err(
+                    MetadataError::HandleTaken(handle.clone(), owner).into()
+                );
+            }
+            None => {}
+        }
+    }
+    let key = storage_key::validator_handle_key(validator);
+    if let Some(prev_handle) = read_validator_handle(storage, validator)? {
+        storage.delete(&storage_key::validator_by_handle_key(prev_handle))?;
+    }
+    if handle.is_empty() {
+        storage.delete(&key)
+    } else {
+        storage
+            .write(&storage_key::validator_by_handle_key(handle), validator)?;
+        storage.write(&key, handle)
+    }
+}
+
 /// Write validator's metadata.
 pub fn write_validator_metadata<S>(
     storage: &mut S,
@@ -889,3 +1015,61 @@ where
     let params = read_pos_params(storage)?;
     validator_consensus_key_handle(addr).get(storage, epoch, &params)
 }
+
+/// Record an amount of tokens that has been moved into the slash pool in the
+/// given epoch.
+pub fn record_slash_pool_inflow<S>(
+    storage: &mut S,
+    epoch: Epoch,
+    amount: token::Amount,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    add_slash_pool_flow(storage, &slash_pool_inflows_handle(), epoch, amount)
+}
+
+/// Record an amount of tokens that has been disbursed or burned from the slash
+/// pool in the given epoch.
+pub fn record_slash_pool_outflow<S>(
+    storage: &mut S,
+    epoch: Epoch,
+    amount: token::Amount,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    add_slash_pool_flow(storage, &slash_pool_outflows_handle(), epoch, amount)
+}
+
+fn add_slash_pool_flow<S>(
+    storage: &mut S,
+    handle: &SlashPoolFlows,
+    epoch: Epoch,
+    amount: token::Amount,
+) -> namada_storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let current = handle.get(storage, &epoch)?.unwrap_or_default();
+    let updated = current.checked_add(amount).ok_or_else(|| {
+        namada_storage::Error::new_const("Slash pool flow amount overflowed")
+    })?;
+    handle.insert(storage, epoch, updated)?;
+    Ok(())
+}
+
+/// Read all the recorded slash pool flows from the given handle, keyed by
+/// epoch.
+pub fn read_slash_pool_flows<S>(
+    storage: &S,
+    handle: &SlashPoolFlows,
+) -> namada_storage::Result<BTreeMap<Epoch, token::Amount>>
+where
+    S: StorageRead,
+{
+    handle.iter(storage)?.collect()
+}
@@ -2,6 +2,7 @@
 
 use namada_core::types::address::Address;
 use namada_core::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
+use namada_core::{define_key, key};
 use namada_storage::collections::{lazy_map, lazy_vec};
 
 use super::ADDRESS;
@@ -55,9 +56,19 @@ const VALIDATOR_DESCRIPTION_KEY: &str = "description";
 const VALIDATOR_WEBSITE_KEY: &str = "website";
 const VALIDATOR_DISCORD_KEY: &str = "discord_handle";
 const VALIDATOR_AVATAR_KEY: &str = "avatar";
+const VALIDATOR_HANDLE_KEY: &str = "handle";
+const VALIDATOR_HANDLES_KEY: &str = "validator_handles";
 const LIVENESS_PREFIX: &str = "liveness";
 const LIVENESS_MISSED_VOTES: &str = "missed_votes";
 const LIVENESS_MISSED_VOTES_SUM: &str = "sum_missed_votes";
+const VALIDATOR_PERFORMANCE_KEY: &str = "validator_performance";
+const EPOCH_SUMMARIES_KEY: &str = "epoch_summaries";
+const VALIDATOR_EPOCH_SUMMARIES_KEY: &str = "validator_epoch_summaries";
+const SLASH_POOL_PREFIX: &str = "slash_pool";
+const SLASH_POOL_INFLOWS: &str = "inflows";
+const SLASH_POOL_OUTFLOWS: &str = "outflows";
+const SCHEDULED_BONDS_KEY: &str = "scheduled_bonds";
+const SCHEDULED_UNBONDS_KEY: &str = "scheduled_unbonds";
 
 /// Is the given key a PoS storage key?
 pub fn is_pos_key(key: &Key) -> bool {
@@ -67,36 +78,23 @@ pub fn is_pos_key(key: &Key) -> bool {
     }
 }
 
-/// Storage key for PoS parameters.
-pub fn params_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&PARAMS_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+define_key! {
+    /// Storage key for PoS parameters.
+    pub fn params_key;
+    fn parse_params_key;
+    ADDRESS / [PARAMS_STORAGE_KEY]
 }
 
 /// Is storage key for PoS parameters?
 pub fn is_params_key(key: &Key) -> bool {
-    matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)] if addr == &ADDRESS && key == PARAMS_STORAGE_KEY)
-}
-
-/// Storage key prefix for validator data.
-fn validator_prefix(validator: &Address) -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&VALIDATOR_STORAGE_PREFIX.to_owned())
-        .expect("Cannot obtain a storage key")
-        .push(&validator.to_db_key())
-        .expect("Cannot obtain a storage key")
+    parse_params_key(key).is_some()
 }
 
 /// Storage key for validator's address raw hash for look-up from raw hash of an
 /// address to address.
 pub fn validator_address_raw_hash_key(raw_hash: impl AsRef<str>) -> Key {
     let raw_hash = raw_hash.as_ref().to_owned();
-    Key::from(ADDRESS.to_db_key())
-        .push(&VALIDATOR_ADDRESS_RAW_HASH.to_owned())
-        .expect("Cannot obtain a storage key")
-        .push(&raw_hash)
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [VALIDATOR_ADDRESS_RAW_HASH] / raw_hash)
 }
 
 /// Is storage key for validator's address raw hash?
@@ -115,9 +113,12 @@ pub fn is_validator_address_raw_hash_key(key: &Key) -> Option<&str> {
 
 /// Storage key for validator's consensus key.
 pub fn validator_consensus_key_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_CONSENSUS_KEY_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_CONSENSUS_KEY_STORAGE_KEY]
+    )
 }
 
 /// Is storage key for validator's consensus key?
@@ -144,9 +145,12 @@ pub fn is_validator_consensus_key_key(key: &Key) -> Option<&Address> {
 
 /// Storage key for validator's eth cold key.
 pub fn validator_eth_cold_key_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_ETH_COLD_KEY_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_ETH_COLD_KEY_STORAGE_KEY]
+    )
 }
 
 /// Is storage key for validator's eth cold key?
@@ -173,9 +177,12 @@ pub fn is_validator_eth_cold_key_key(key: &Key) -> Option<&Address> {
 
 /// Storage key for validator's eth hot key.
 pub fn validator_eth_hot_key_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_ETH_HOT_KEY_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_ETH_HOT_KEY_STORAGE_KEY]
+    )
 }
 
 /// Is storage key for validator's eth hot key?
@@ -202,9 +209,12 @@ pub fn is_validator_eth_hot_key_key(key: &Key) -> Option<&Address> {
 
 /// Storage key for validator's commission rate.
 pub fn validator_commission_rate_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_COMMISSION_RATE_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_COMMISSION_RATE_STORAGE_KEY]
+    )
 }
 
 /// Is storage key for validator's commission rate?
@@ -231,9 +241,12 @@ pub fn is_validator_commission_rate_key(key: &Key) -> Option<&Address> {
 
 /// Storage key for validator's maximum commission rate change per epoch.
 pub fn validator_max_commission_rate_change_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_MAX_COMMISSION_CHANGE_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_MAX_COMMISSION_CHANGE_STORAGE_KEY]
+    )
 }
 
 /// Is storage key for validator's maximum commission rate change per epoch?
@@ -273,6 +286,7 @@ pub fn is_validator_metadata_key(key: &Key) -> Option<&Address> {
                     | VALIDATOR_WEBSITE_KEY
                     | VALIDATOR_DISCORD_KEY
                     | VALIDATOR_AVATAR_KEY
+                    | VALIDATOR_HANDLE_KEY
             ) =>
         {
             Some(validator)
@@ -283,9 +297,12 @@ pub fn is_validator_metadata_key(key: &Key) -> Option<&Address> {
 
 /// Storage key for validator's rewards products.
 pub fn validator_rewards_product_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_REWARDS_PRODUCT_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_REWARDS_PRODUCT_KEY]
+    )
 }
 
 /// Is storage key for validator's rewards products?
@@ -308,9 +325,7 @@ pub fn is_validator_rewards_product_key(key: &Key) -> Option<&Address> {
 
 /// Storage prefix for rewards counter.
 pub fn rewards_counter_prefix() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&REWARDS_COUNTER_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [REWARDS_COUNTER_KEY])
 }
 
 /// Storage key for rewards counter.
@@ -341,33 +356,45 @@ pub fn is_rewards_counter_key(key: &Key) -> Option<BondId> {
 /// Storage key for a validator's incoming redelegations, where the prefixed
 /// validator is the destination validator.
 pub fn validator_incoming_redelegations_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_INCOMING_REDELEGATIONS_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_INCOMING_REDELEGATIONS_KEY]
+    )
 }
 
 /// Storage key for a validator's outgoing redelegations, where the prefixed
 /// validator is the source validator.
 pub fn validator_outgoing_redelegations_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_OUTGOING_REDELEGATIONS_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_OUTGOING_REDELEGATIONS_KEY]
+    )
 }
 
 /// Storage key for validator's total-redelegated-bonded amount to track for
 /// slashing
 pub fn validator_total_redelegated_bonded_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_TOTAL_REDELEGATED_BONDED_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_TOTAL_REDELEGATED_BONDED_KEY]
+    )
 }
 
 /// Storage key for validator's total-redelegated-unbonded amount to track for
 /// slashing
 pub fn validator_total_redelegated_unbonded_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_TOTAL_REDELEGATED_UNBONDED_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_TOTAL_REDELEGATED_UNBONDED_KEY]
+    )
 }
 
 /// Is the storage key's prefix matching one of validator's:
@@ -399,9 +426,7 @@ pub fn is_validator_redelegations_key(key: &Key) -> bool {
 
 /// Storage key prefix for all delegators' redelegated bonds.
 pub fn delegator_redelegated_bonds_prefix() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&DELEGATOR_REDELEGATED_BONDS_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [DELEGATOR_REDELEGATED_BONDS_KEY])
 }
 
 /// Storage key for a particular delegator's redelegated bond information.
@@ -413,9 +438,7 @@ pub fn delegator_redelegated_bonds_key(delegator: &Address) -> Key {
 
 /// Storage key prefix for all delegators' redelegated unbonds.
 pub fn delegator_redelegated_unbonds_prefix() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&DELEGATOR_REDELEGATED_UNBONDS_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [DELEGATOR_REDELEGATED_UNBONDS_KEY])
 }
 
 /// Storage key for a particular delegator's redelegated unbond information.
@@ -450,9 +473,12 @@ pub fn is_delegator_redelegations_key(key: &Key) -> Option<&Address> {
 
 /// Storage key for validator's last known rewards product epoch.
 pub fn validator_last_known_product_epoch_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_LAST_KNOWN_PRODUCT_EPOCH_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_LAST_KNOWN_PRODUCT_EPOCH_KEY]
+    )
 }
 
 /// Is storage key for validator's last known rewards product epoch?
@@ -477,9 +503,12 @@ pub fn is_validator_last_known_product_epoch_key(
 
 /// Storage key for validator's consensus key.
 pub fn validator_state_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_STATE_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_STATE_STORAGE_KEY]
+    )
 }
 
 /// Is storage key for validator's state?
@@ -533,9 +562,12 @@ pub fn is_validator_state_epoched_meta_key(key: &Key) -> bool {
 
 /// Storage key for validator's deltas.
 pub fn validator_deltas_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_DELTAS_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_DELTAS_STORAGE_KEY]
+    )
 }
 
 /// Is storage key for validator's total deltas?
@@ -562,9 +594,7 @@ pub fn is_validator_deltas_key(key: &Key) -> bool {
 /// Storage prefix for all active validators (consensus, below-capacity,
 /// below-threshold, inactive, jailed)
 pub fn validator_addresses_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&VALIDATOR_ADDRESSES_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [VALIDATOR_ADDRESSES_KEY])
 }
 
 /// Is the storage key a prefix for all active validators?
@@ -583,17 +613,13 @@ pub fn is_validator_addresses_key(key: &Key) -> bool {
 
 /// Storage prefix for slashes.
 pub fn slashes_prefix() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&SLASHES_PREFIX.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [SLASHES_PREFIX])
 }
 
 /// Storage key for all slashes.
 pub fn enqueued_slashes_key() -> Key {
     // slashes_prefix()
-    Key::from(ADDRESS.to_db_key())
-        .push(&ENQUEUED_SLASHES_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [ENQUEUED_SLASHES_KEY])
 }
 
 /// Storage key for validator's slashes.
@@ -629,16 +655,17 @@ pub fn is_validator_slashes_key(key: &Key) -> Option<Address> {
 /// Storage key for the last (most recent) epoch in which a slashable offense
 /// was detected for a given validator
 pub fn validator_last_slash_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_LAST_SLASH_EPOCH.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_LAST_SLASH_EPOCH]
+    )
 }
 
 /// Storage key prefix for all bonds.
 pub fn bonds_prefix() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&BOND_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [BOND_STORAGE_KEY])
 }
 
 /// Storage key prefix for all bonds of the given source address.
@@ -718,9 +745,12 @@ pub fn is_bond_epoched_meta_key(key: &Key) -> Option<BondId> {
 
 /// Storage key for the total bonds for a given validator.
 pub fn validator_total_bonded_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_TOTAL_BONDED_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_TOTAL_BONDED_STORAGE_KEY]
+    )
 }
 
 /// Is the storage key for the total bonds or unbonds for a validator?
@@ -747,9 +777,7 @@ pub fn is_validator_total_bond_or_unbond_key(key: &Key) -> bool {
 
 /// Storage key prefix for all unbonds.
 pub fn unbonds_prefix() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&UNBOND_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [UNBOND_STORAGE_KEY])
 }
 
 /// Storage key prefix for all unbonds of the given source address.
@@ -805,16 +833,17 @@ pub fn is_unbond_key(key: &Key) -> Option<(BondId, Epoch, Epoch)> {
 
 /// Storage key for validator's total-unbonded amount to track for slashing
 pub fn validator_total_unbonded_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_TOTAL_UNBONDED_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_TOTAL_UNBONDED_STORAGE_KEY]
+    )
 }
 
 /// Storage prefix for validator sets.
 pub fn validator_sets_prefix() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&VALIDATOR_SETS_STORAGE_PREFIX.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [VALIDATOR_SETS_STORAGE_PREFIX])
 }
 
 /// Storage key for consensus validator set
@@ -858,9 +887,7 @@ pub fn is_total_consensus_stake_key(key: &Key) -> bool {
 
 /// Storage key for total deltas of all validators.
 pub fn total_deltas_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&TOTAL_DELTAS_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [TOTAL_DELTAS_STORAGE_KEY])
 }
 
 /// Is storage key for total deltas of all validators?
@@ -879,9 +906,7 @@ pub fn is_total_deltas_key(key: &Key) -> bool {
 
 /// Storage key for block proposer address of the previous block.
 pub fn last_block_proposer_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&LAST_BLOCK_PROPOSER_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [LAST_BLOCK_PROPOSER_STORAGE_KEY])
 }
 
 /// Is storage key for block proposer address of the previous block?
@@ -891,9 +916,7 @@ pub fn is_last_block_proposer_key(key: &Key) -> bool {
 
 /// Storage key for the consensus validator set rewards accumulator.
 pub fn consensus_validator_rewards_accumulator_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&CONSENSUS_VALIDATOR_SET_ACCUMULATOR_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [CONSENSUS_VALIDATOR_SET_ACCUMULATOR_STORAGE_KEY])
 }
 
 /// Is storage key for the consensus validator set?
@@ -908,9 +931,7 @@ pub fn is_consensus_validator_set_accumulator_key(key: &Key) -> bool {
 /// Storage prefix for epoch at which an account last claimed PoS inflationary
 /// rewards.
 pub fn last_pos_reward_claim_epoch_prefix() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&LAST_REWARD_CLAIM_EPOCH.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [LAST_REWARD_CLAIM_EPOCH])
 }
 
 /// Storage key for epoch at which an account last claimed PoS inflationary
@@ -950,7 +971,7 @@ pub fn get_validator_address_from_bond(key: &Key) -> Option<Address> {
     match key.get_at(3) {
         Some(segment) => match segment {
             DbKeySeg::AddressSeg(addr) => Some(addr.clone()),
-            DbKeySeg::StringSeg(_) => None,
+            _ => None,
         },
         None => None,
     }
@@ -958,9 +979,7 @@ pub fn get_validator_address_from_bond(key: &Key) -> Option<Address> {
 
 /// Storage key for validator set positions
 pub fn validator_set_positions_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&VALIDATOR_SET_POSITIONS_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [VALIDATOR_SET_POSITIONS_KEY])
 }
 
 /// Is the storage key for validator set positions?
@@ -979,9 +998,7 @@ pub fn is_validator_set_positions_key(key: &Key) -> bool {
 
 /// Storage key for consensus keys set.
 pub fn consensus_keys_key() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&CONSENSUS_KEYS.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [CONSENSUS_KEYS])
 }
 
 /// Is storage key for consensus keys set?
@@ -991,44 +1008,74 @@ pub fn is_consensus_keys_key(key: &Key) -> bool {
 
 /// Storage key for a validator's email
 pub fn validator_email_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_EMAIL_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_EMAIL_KEY]
+    )
 }
 
 /// Storage key for a validator's description
 pub fn validator_description_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_DESCRIPTION_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_DESCRIPTION_KEY]
+    )
 }
 
 /// Storage key for a validator's website
 pub fn validator_website_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_WEBSITE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_WEBSITE_KEY]
+    )
 }
 
 /// Storage key for a validator's discord handle
 pub fn validator_discord_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_DISCORD_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_DISCORD_KEY]
+    )
 }
 
 /// Storage key for a validator's avatar
 pub fn validator_avatar_key(validator: &Address) -> Key {
-    validator_prefix(validator)
-        .push(&VALIDATOR_AVATAR_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_AVATAR_KEY]
+    )
+}
+
+/// Storage key for a validator's handle
+pub fn validator_handle_key(validator: &Address) -> Key {
+    key!(
+        ADDRESS
+            / [VALIDATOR_STORAGE_PREFIX]
+            / (*validator)
+            / [VALIDATOR_HANDLE_KEY]
+    )
+}
+
+/// Storage key for the validator registered with the given handle, for the
+/// look-up of a validator by its handle
+pub fn validator_by_handle_key(handle: impl AsRef<str>) -> Key {
+    let handle = handle.as_ref().to_owned();
+    key!(ADDRESS / [VALIDATOR_HANDLES_KEY] / handle)
 }
 
 /// Storage prefix for the liveness data of the cosnensus validator set.
 pub fn liveness_data_prefix() -> Key {
-    Key::from(ADDRESS.to_db_key())
-        .push(&LIVENESS_PREFIX.to_owned())
-        .expect("Cannot obtain a storage key")
+    key!(ADDRESS / [LIVENESS_PREFIX])
 }
 
 /// Storage key for the liveness records.
@@ -1044,3 +1091,49 @@ pub fn liveness_sum_missed_votes_key() -> Key {
         .push(&LIVENESS_MISSED_VOTES_SUM.to_owned())
         .expect("Cannot obtain a storage key")
 }
+
+/// Storage key for the performance records of the validators.
+pub fn validator_performance_key() -> Key {
+    key!(ADDRESS / [VALIDATOR_PERFORMANCE_KEY])
+}
+
+/// Storage key for the archived summaries of the past epochs.
+pub fn epoch_summaries_key() -> Key {
+    key!(ADDRESS / [EPOCH_SUMMARIES_KEY])
+}
+
+/// Storage key for the archived summaries of the validators in the past
+/// epochs.
+pub fn validator_epoch_summaries_key() -> Key {
+    key!(ADDRESS / [VALIDATOR_EPOCH_SUMMARIES_KEY])
+}
+
+/// Storage prefix for the slash pool accounting data.
+pub fn slash_pool_prefix() -> Key {
+    key!(ADDRESS / [SLASH_POOL_PREFIX])
+}
+
+/// Storage key for the amounts of tokens moved into the slash pool per epoch.
+pub fn slash_pool_inflows_key() -> Key {
+    slash_pool_prefix()
+        .push(&SLASH_POOL_INFLOWS.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the amounts of tokens disbursed or burned from the slash
+/// pool per epoch.
+pub fn slash_pool_outflows_key() -> Key {
+    slash_pool_prefix()
+        .push(&SLASH_POOL_OUTFLOWS.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the bonds scheduled to be applied in a future epoch.
+pub fn scheduled_bonds_key() -> Key {
+    key!(ADDRESS / [SCHEDULED_BONDS_KEY])
+}
+
+/// Storage key for the unbonds scheduled to be applied in a future epoch.
+pub fn scheduled_unbonds_key() -> Key {
+    key!(ADDRESS / [SCHEDULED_UNBONDS_KEY])
+}
@@ -17,7 +17,8 @@ use crate::validator_set_update::{
     copy_validator_sets_and_positions, validator_set_update_tendermint,
 };
 use crate::{
-    compute_and_store_total_consensus_stake, OwnedPosParams, PosParams,
+    compute_and_store_total_consensus_stake, process_scheduled_bonds,
+    OwnedPosParams, PosParams,
 };
 
 pub fn arb_params_and_genesis_validators(
@@ -71,6 +72,7 @@ pub fn advance_epoch(s: &mut TestWlStorage, params: &PosParams) -> Epoch {
         current_epoch + params.pipeline_len,
     )
     .unwrap();
+    process_scheduled_bonds(s, current_epoch).unwrap();
     // purge_validator_sets_for_old_epoch(s, current_epoch).unwrap();
     // process_slashes(s, current_epoch).unwrap();
     // dbg!(current_epoch);
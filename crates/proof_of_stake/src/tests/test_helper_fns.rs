@@ -24,12 +24,13 @@ use crate::storage::{
 };
 use crate::types::{
     EagerRedelegatedBondsMap, RedelegatedTokens, Slash, SlashType,
+    ValidatorPerformance,
 };
 use crate::{
     compute_modified_redelegation, compute_new_redelegated_unbonds,
     find_bonds_to_remove, fold_and_slash_redelegated_bonds,
-    EagerRedelegatedUnbonds, FoldRedelegatedBondsResult, ModifiedRedelegation,
-    OwnedPosParams,
+    missed_votes_streaks, EagerRedelegatedUnbonds, FoldRedelegatedBondsResult,
+    ModifiedRedelegation, OwnedPosParams,
 };
 
 /// `iterateBondsUpToAmountTest`
@@ -2036,3 +2037,30 @@ fn test_from_sm_case_1() {
 
     pretty_assertions::assert_eq!(mr, exp_mr);
 }
+
+/// Test the streaks of missed votes and the ratios of the validator
+/// performance.
+#[test]
+fn test_validator_performance() {
+    assert_eq!(missed_votes_streaks(&[], 10), (0, 0));
+    assert_eq!(missed_votes_streaks(&[3, 4, 5, 8], 10), (0, 3));
+    assert_eq!(missed_votes_streaks(&[3, 4, 5, 9, 10], 10), (2, 3));
+    assert_eq!(missed_votes_streaks(&[1, 3, 5, 7, 8, 9, 10], 10), (4, 4));
+
+    let performance = ValidatorPerformance::default();
+    assert_eq!(performance.uptime(), Dec::one());
+    assert_eq!(performance.governance_participation(), Dec::one());
+
+    let performance = ValidatorPerformance {
+        signed_blocks: 90,
+        window: 100,
+        proposals_voted: 1,
+        proposals_eligible: 4,
+        ..performance
+    };
+    assert_eq!(performance.uptime(), Dec::new(9, 1).unwrap());
+    assert_eq!(
+        performance.governance_participation(),
+        Dec::new(25, 2).unwrap()
+    );
+}
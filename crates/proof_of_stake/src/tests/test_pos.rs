@@ -47,9 +47,10 @@ use crate::{
     below_capacity_validator_set_handle, bond_handle, bond_tokens,
     change_consensus_key, consensus_validator_set_handle, is_delegator,
     is_validator, read_validator_stake, redelegate_tokens,
-    staking_token_address, token, unbond_handle, unbond_tokens,
-    unjail_validator, validator_consensus_key_handle,
-    validator_set_positions_handle, validator_state_handle, withdraw_tokens,
+    schedule_bond_tokens, schedule_unbond_tokens, staking_token_address, token,
+    unbond_handle, unbond_tokens, unjail_validator,
+    validator_consensus_key_handle, validator_set_positions_handle,
+    validator_state_handle, withdraw_tokens,
 };
 
 proptest! {
@@ -180,6 +181,22 @@ proptest! {
     }
 }
 
+proptest! {
+    // Generate arb valid input for `test_scheduled_bonds`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_scheduled_bonds(
+
+    genesis_validators in arb_genesis_validators(1..3, None),
+
+    ) {
+        test_scheduled_bonds_aux(genesis_validators)
+    }
+}
+
 /// Test genesis initialization
 fn test_test_init_genesis_aux(
     params: OwnedPosParams,
@@ -1389,7 +1406,6 @@ fn test_update_rewards_products_aux(validators: Vec<GenesisValidator>) {
         last_epoch,
         num_blocks_in_last_epoch,
         inflation,
-        &staking_token,
     )
     .unwrap();
 
@@ -1652,3 +1668,132 @@ fn test_is_delegator_aux(mut validators: Vec<GenesisValidator>) {
         .unwrap()
     );
 }
+
+/// Test bonds and unbonds scheduled for a future epoch
+fn test_scheduled_bonds_aux(validators: Vec<GenesisValidator>) {
+    let validator = validators[0].address.clone();
+
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        max_scheduling_offset: 5,
+        ..Default::default()
+    };
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::native_whole(1000);
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+
+    // The start epoch must be in the future and within the scheduling offset
+    for start_epoch in [
+        current_epoch,
+        current_epoch + params.max_scheduling_offset + 1,
+    ] {
+        assert!(schedule_bond_tokens(
+            &mut storage,
+            Some(&delegator),
+            &validator,
+            del_balance,
+            current_epoch,
+            start_epoch,
+        )
+        .is_err());
+    }
+
+    // Schedule a bond for epoch 2
+    let bond_epoch = current_epoch + 2;
+    schedule_bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        del_balance,
+        current_epoch,
+        bond_epoch,
+    )
+    .unwrap();
+
+    // The tokens are held by PoS until the bond is applied
+    let balance = read_balance(&storage, &staking_token, &delegator).unwrap();
+    assert!(balance.is_zero());
+
+    let bond_id = BondId {
+        source: delegator.clone(),
+        validator: validator.clone(),
+    };
+    let bond_amount_at = |storage: &TestWlStorage, epoch: Epoch| {
+        bond_handle(&bond_id.source, &bond_id.validator)
+            .get_sum(storage, epoch, &params)
+            .unwrap()
+            .unwrap_or_default()
+    };
+
+    // Nothing is bonded before the start epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    assert!(
+        bond_amount_at(&storage, current_epoch + params.pipeline_len).is_zero()
+    );
+
+    // The bond is applied at the start epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    assert_eq!(current_epoch, bond_epoch);
+    assert!(
+        bond_amount_at(&storage, current_epoch + params.pipeline_len - 1)
+            .is_zero()
+    );
+    assert_eq!(
+        bond_amount_at(&storage, current_epoch + params.pipeline_len),
+        del_balance
+    );
+
+    // Unbonding more than what's bonded cannot be scheduled
+    let unbond_epoch = current_epoch + 1;
+    assert!(schedule_unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        del_balance + token::Amount::native_whole(1),
+        current_epoch,
+        unbond_epoch,
+    )
+    .is_err());
+
+    // Schedule an unbond for the next epoch
+    let unbond_amount = token::Amount::native_whole(400);
+    schedule_unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        unbond_amount,
+        current_epoch,
+        unbond_epoch,
+    )
+    .unwrap();
+    assert_eq!(
+        bond_amount_at(&storage, current_epoch + params.pipeline_len),
+        del_balance
+    );
+
+    // The unbond is applied at the start epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    assert_eq!(
+        bond_amount_at(&storage, current_epoch + params.pipeline_len),
+        del_balance - unbond_amount
+    );
+    let unbonds = unbond_handle(&delegator, &validator)
+        .collect_map(&storage)
+        .unwrap();
+    assert!(!unbonds.is_empty());
+}
@@ -20,13 +20,14 @@ use test_log::test;
 use crate::epoched::DEFAULT_NUM_PAST_EPOCHS;
 use crate::storage::{
     below_capacity_validator_set_handle, bond_handle,
-    consensus_validator_set_handle, find_validator_by_raw_hash,
-    get_num_consensus_validators,
+    consensus_validator_set_handle, find_validator_by_handle,
+    find_validator_by_raw_hash, get_num_consensus_validators,
     read_below_capacity_validator_set_addresses_with_stake,
     read_below_threshold_validator_set_addresses,
-    read_consensus_validator_set_addresses_with_stake, update_validator_deltas,
-    validator_addresses_handle, validator_consensus_key_handle,
-    validator_set_positions_handle, write_validator_address_raw_hash,
+    read_consensus_validator_set_addresses_with_stake, read_validator_handle,
+    update_validator_deltas, validator_addresses_handle,
+    validator_consensus_key_handle, validator_set_positions_handle,
+    write_validator_address_raw_hash, write_validator_handle,
 };
 use crate::test_utils::{init_genesis_helper, test_init_genesis};
 use crate::tests::helpers::{
@@ -290,6 +291,67 @@ fn test_validator_raw_hash() {
     assert_eq!(found, Some(address));
 }
 
+#[test]
+fn test_validator_handle() {
+    let mut storage = TestWlStorage::default();
+    let validator_1 = address::testing::established_address_1();
+    let validator_2 = address::testing::established_address_2();
+
+    write_validator_handle(&mut storage, &validator_1, &"alice".to_string())
+        .unwrap();
+    assert_eq!(
+        read_validator_handle(&storage, &validator_1).unwrap(),
+        Some("alice".to_string())
+    );
+    assert_eq!(
+        find_validator_by_handle(&storage, "alice").unwrap(),
+        Some(validator_1.clone())
+    );
+
+    // A handle is unique
+    assert!(write_validator_handle(
+        &mut storage,
+        &validator_2,
+        &"alice".to_string()
+    )
+    .is_err());
+    // And must be valid
+    let too_long = "a".repeat(21);
+    for invalid in ["al", "Alice", "alice.nam", too_long.as_str()] {
+        assert!(write_validator_handle(
+            &mut storage,
+            &validator_2,
+            &invalid.to_string()
+        )
+        .is_err());
+    }
+    assert!(read_validator_handle(&storage, &validator_2)
+        .unwrap()
+        .is_none());
+
+    // Changing the handle releases the previous one
+    write_validator_handle(&mut storage, &validator_1, &"alice_2".to_string())
+        .unwrap();
+    assert!(find_validator_by_handle(&storage, "alice")
+        .unwrap()
+        .is_none());
+    write_validator_handle(&mut storage, &validator_2, &"alice".to_string())
+        .unwrap();
+    assert_eq!(
+        find_validator_by_handle(&storage, "alice").unwrap(),
+        Some(validator_2)
+    );
+
+    // An empty handle removes it
+    write_validator_handle(&mut storage, &validator_1, &String::new()).unwrap();
+    assert!(read_validator_handle(&storage, &validator_1)
+        .unwrap()
+        .is_none());
+    assert!(find_validator_by_handle(&storage, "alice_2")
+        .unwrap()
+        .is_none());
+}
+
 #[test]
 fn test_validator_sets() {
     let mut s = TestWlStorage::default();
@@ -260,6 +260,188 @@ pub type LivenessMissedVotes = NestedMap<Address, LazySet<u64>>;
 /// elements in the corresponding inner LazySet of [`LivenessMissedVotes`].
 pub type LivenessSumMissedVotes = LazyMap<Address, u64>;
 
+/// The performance records of the validators, updated at the end of every
+/// epoch in which they're in the consensus set.
+pub type ValidatorPerformances = LazyMap<Address, ValidatorPerformance>;
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+/// The performance of a validator in the liveness window and in governance,
+/// for delegators to compare validators.
+pub struct ValidatorPerformance {
+    /// The last epoch in which the liveness fields were updated
+    pub epoch: Epoch,
+    /// The number of blocks signed within the liveness window
+    pub signed_blocks: u64,
+    /// The number of blocks in the liveness window, which is shorter than
+    /// the liveness window check parameter at the start of the chain
+    pub window: u64,
+    /// The number of consecutive blocks missed up to the last block of the
+    /// epoch
+    pub current_missed_streak: u64,
+    /// The longest number of consecutive blocks missed within the liveness
+    /// window
+    pub longest_missed_streak: u64,
+    /// The number of governance proposals on which the validator voted
+    pub proposals_voted: u64,
+    /// The number of governance proposals on which the validator could
+    /// vote, as a member of the consensus set at the end of their voting
+    /// period
+    pub proposals_eligible: u64,
+}
+
+impl ValidatorPerformance {
+    /// The ratio of the signed blocks within the liveness window. A
+    /// validator without any blocks in the window has a full uptime.
+    pub fn uptime(&self) -> Dec {
+        if self.window == 0 {
+            Dec::one()
+        } else {
+            Dec::from(self.signed_blocks) / Dec::from(self.window)
+        }
+    }
+
+    /// The ratio of the governance proposals on which the validator voted.
+    /// A validator that couldn't vote on any proposal has a full
+    /// participation.
+    pub fn governance_participation(&self) -> Dec {
+        if self.proposals_eligible == 0 {
+            Dec::one()
+        } else {
+            Dec::from(self.proposals_voted) / Dec::from(self.proposals_eligible)
+        }
+    }
+}
+
+/// The archived summaries of the past epochs, recorded when they end.
+pub type EpochSummaries = LazyMap<Epoch, EpochSummary>;
+
+/// The archived summaries of the validators in the past epochs, recorded when
+/// they end, keyed by epoch and then by validator address.
+pub type ValidatorEpochSummaries =
+    NestedMap<Epoch, LazyMap<Address, ValidatorEpochSummary>>;
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+/// The economic record of an epoch, which is kept after the epoched data it's
+/// summarized from has been pruned.
+pub struct EpochSummary {
+    /// The total stake bonded in the epoch
+    pub total_stake: token::Amount,
+    /// The total stake of the consensus validators in the epoch
+    pub consensus_stake: token::Amount,
+    /// The PoS inflation minted for the rewards of the epoch
+    pub inflation: token::Amount,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+/// The economic record of a validator in an epoch, which is kept after the
+/// epoched data it's summarized from has been pruned.
+pub struct ValidatorEpochSummary {
+    /// The stake of the validator in the epoch
+    pub stake: token::Amount,
+    /// The commission rate of the validator in the epoch
+    pub commission_rate: Dec,
+    /// The state of the validator in the epoch
+    pub state: ValidatorState,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+/// Where the value of a historical PoS query was read from.
+pub enum Provenance {
+    /// From the epoched data, which hasn't been pruned yet
+    Live,
+    /// From the summary archived at the end of the epoch, after the epoched
+    /// data was pruned
+    Archived,
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Provenance::Live => write!(f, "live"),
+            Provenance::Archived => write!(f, "archived"),
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+/// The value of a historical PoS query with its provenance.
+pub struct Historical<T> {
+    /// The value
+    pub value: T,
+    /// Where the value was read from
+    pub provenance: Provenance,
+}
+
+impl<T> Historical<T> {
+    /// A value read from the epoched data
+    pub fn live(value: T) -> Self {
+        Self {
+            value,
+            provenance: Provenance::Live,
+        }
+    }
+
+    /// A value read from an archived epoch summary
+    pub fn archived(value: T) -> Self {
+        Self {
+            value,
+            provenance: Provenance::Archived,
+        }
+    }
+
+    /// Map the value, keeping its provenance
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Historical<U> {
+        Historical {
+            value: f(self.value),
+            provenance: self.provenance,
+        }
+    }
+}
+
+/// The amounts of staking tokens that have been moved into or out of the slash
+/// pool, keyed by the epoch in which the movement happened.
+pub type SlashPoolFlows = LazyMap<Epoch, token::Amount>;
+
+/// Bonds or unbonds scheduled to be applied in a future epoch.
+/// The map keys from outside in are:
+///
+/// - the epoch in which the bond or unbond will be applied
+/// - source address
+/// - validator address
+pub type ScheduledBonds =
+    NestedMap<Epoch, NestedMap<Address, LazyMap<Address, token::Amount>>>;
+
 #[derive(
     Debug, Clone, BorshSerialize, BorshDeserialize, Eq, Hash, PartialEq,
 )]
@@ -11,11 +11,11 @@ use namada_core::types::ethereum_events::EthAddress;
 use namada_core::types::keccak::KeccakHash;
 use namada_core::types::key::{common, SchemeType};
 use namada_core::types::masp::PaymentAddress;
-use namada_core::types::storage::Epoch;
+use namada_core::types::storage::{BlockHeight, Epoch};
 use namada_core::types::time::DateTimeUtc;
 use namada_core::types::{storage, token};
 use namada_governance::cli::onchain::{
-    DefaultProposal, PgfFundingProposal, PgfStewardProposal,
+    DefaultProposal, PgfFundingProposal, PgfStewardProposal, SlashPoolProposal,
 };
 use namada_tx::data::GasLimit;
 use namada_tx::Memo;
@@ -25,6 +25,7 @@ use zeroize::Zeroizing;
 use crate::eth_bridge::bridge_pool;
 use crate::ibc::core::host::types::identifiers::{ChannelId, PortId};
 use crate::signing::SigningTxData;
+use crate::wallet::interop::KeyExportFormat;
 use crate::{rpc, tx, Namada};
 
 /// [`Duration`](StdDuration) wrapper that provides a
@@ -236,6 +237,9 @@ pub struct TxTransfer<C: NamadaTypes = SdkTypes> {
     pub token: C::Address,
     /// Transferred token amount
     pub amount: InputAmount,
+    /// The memo of the shielded output of a transfer to a payment address,
+    /// encrypted to its receiver
+    pub shielded_memo: Option<Memo>,
     /// Path to the TX WASM code file
     pub tx_code_path: PathBuf,
 }
@@ -273,6 +277,15 @@ impl<C: NamadaTypes> TxTransfer<C> {
         Self { amount, ..self }
     }
 
+    /// The memo of the shielded output of a transfer to a payment address, of
+    /// at most [`crate::masp::MAX_SHIELDED_MEMO_LEN`] bytes
+    pub fn shielded_memo(self, shielded_memo: Memo) -> Self {
+        Self {
+            shielded_memo: Some(shielded_memo),
+            ..self
+        }
+    }
+
     /// Path to the TX WASM code file
     pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
         Self {
@@ -293,6 +306,213 @@ impl TxTransfer {
     }
 }
 
+/// A leg of a batched transfer
+#[derive(Clone, Debug)]
+pub struct TxTransferLeg<C: NamadaTypes = SdkTypes> {
+    /// Transfer source address
+    pub source: C::Address,
+    /// Transfer target address
+    pub target: C::Address,
+    /// Transferred token address
+    pub token: C::Address,
+    /// Transferred token amount
+    pub amount: InputAmount,
+}
+
+/// Batched transfer transaction arguments
+#[derive(Clone, Debug)]
+pub struct TxMultiTransfer<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The transfers of the batch, which must all have the same source
+    pub legs: Vec<TxTransferLeg<C>>,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TxMultiTransfer<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TxMultiTransfer {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> TxMultiTransfer<C> {
+    /// Add a transfer to the batch
+    pub fn leg(mut self, leg: TxTransferLeg<C>) -> Self {
+        self.legs.push(leg);
+        self
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl TxMultiTransfer {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_multi_transfer(context, self).await
+    }
+}
+
+/// Token allowance approval transaction arguments
+#[derive(Clone, Debug)]
+pub struct TxApprove<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The owner of the tokens
+    pub owner: C::Address,
+    /// The address allowed to transfer the owner's tokens
+    pub spender: C::Address,
+    /// The token address
+    pub token: C::Address,
+    /// The allowance, replacing the previous one
+    pub amount: InputAmount,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TxApprove<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TxApprove {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> TxApprove<C> {
+    /// The owner of the tokens
+    pub fn owner(self, owner: C::Address) -> Self {
+        Self { owner, ..self }
+    }
+
+    /// The address allowed to transfer the owner's tokens
+    pub fn spender(self, spender: C::Address) -> Self {
+        Self { spender, ..self }
+    }
+
+    /// The token address
+    pub fn token(self, token: C::Address) -> Self {
+        Self { token, ..self }
+    }
+
+    /// The allowance, replacing the previous one
+    pub fn amount(self, amount: InputAmount) -> Self {
+        Self { amount, ..self }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl TxApprove {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_approve(context, self).await
+    }
+}
+
+/// Transfer by an approved spender transaction arguments
+#[derive(Clone, Debug)]
+pub struct TxTransferFrom<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The owner of the tokens
+    pub owner: C::Address,
+    /// The approved spender that makes the transfer
+    pub spender: C::Address,
+    /// Transfer target address
+    pub target: C::Address,
+    /// Transferred token address
+    pub token: C::Address,
+    /// Transferred token amount
+    pub amount: InputAmount,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TxTransferFrom<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TxTransferFrom {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> TxTransferFrom<C> {
+    /// The owner of the tokens
+    pub fn owner(self, owner: C::Address) -> Self {
+        Self { owner, ..self }
+    }
+
+    /// The approved spender that makes the transfer
+    pub fn spender(self, spender: C::Address) -> Self {
+        Self { spender, ..self }
+    }
+
+    /// Transfer target address
+    pub fn target(self, target: C::Address) -> Self {
+        Self { target, ..self }
+    }
+
+    /// Transferred token address
+    pub fn token(self, token: C::Address) -> Self {
+        Self { token, ..self }
+    }
+
+    /// Transferred token amount
+    pub fn amount(self, amount: InputAmount) -> Self {
+        Self { amount, ..self }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl TxTransferFrom {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_transfer_from(context, self).await
+    }
+}
+
 /// IBC transfer transaction arguments
 #[derive(Clone, Debug)]
 pub struct TxIbcTransfer<C: NamadaTypes = SdkTypes> {
@@ -420,6 +640,8 @@ pub struct InitProposal<C: NamadaTypes = SdkTypes> {
     pub is_pgf_stewards: bool,
     /// Flag if proposal is of type Pgf funding
     pub is_pgf_funding: bool,
+    /// Flag if proposal is of type slash pool disbursement
+    pub is_slash_pool: bool,
     /// Path to the tx WASM file
     pub tx_code_path: PathBuf,
 }
@@ -466,6 +688,14 @@ impl<C: NamadaTypes> InitProposal<C> {
         }
     }
 
+    /// Flag if proposal is of type slash pool disbursement
+    pub fn is_slash_pool(self, is_slash_pool: bool) -> Self {
+        Self {
+            is_slash_pool,
+            ..self
+        }
+    }
+
     /// Path to the tx WASM file
     pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
         Self {
@@ -500,6 +730,21 @@ impl InitProposal {
             })?;
 
             tx::build_pgf_funding_proposal(context, self, proposal).await
+        } else if self.is_slash_pool {
+            let proposal = SlashPoolProposal::try_from(
+                self.proposal_data.as_ref(),
+            )
+            .map_err(|e| {
+                crate::error::TxSubmitError::FailedGovernaneProposalDeserialize(
+                    e.to_string(),
+                )
+            })?
+            .validate(&governance_parameters, current_epoch, self.tx.force)
+            .map_err(|e| {
+                crate::error::TxSubmitError::InvalidProposal(e.to_string())
+            })?;
+
+            tx::build_slash_pool_proposal(context, self, proposal).await
         } else if self.is_pgf_stewards {
             let proposal = PgfStewardProposal::try_from(
                 self.proposal_data.as_ref(),
@@ -871,6 +1116,236 @@ impl TxUpdateAccount {
     }
 }
 
+/// Transaction to update an account's spending policy arguments
+#[derive(Clone, Debug)]
+pub struct TxUpdatePolicy<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Address of the account whose policy is to be updated
+    pub addr: C::Address,
+    /// The JSON encoded policy. The current policy is removed if `None`.
+    pub policy_data: Option<C::Data>,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TxUpdatePolicy<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TxUpdatePolicy {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> TxUpdatePolicy<C> {
+    /// Address of the account whose policy is to be updated
+    pub fn addr(self, addr: C::Address) -> Self {
+        Self { addr, ..self }
+    }
+
+    /// The JSON encoded policy
+    pub fn policy_data(self, policy_data: C::Data) -> Self {
+        Self {
+            policy_data: Some(policy_data),
+            ..self
+        }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl TxUpdatePolicy {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_update_policy(context, self).await
+    }
+}
+
+/// Transaction to register a name arguments
+#[derive(Clone, Debug)]
+pub struct RegisterName<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The name to register, without its suffix
+    pub name: String,
+    /// The address that pays the fee and owns the name
+    pub owner: C::Address,
+    /// The number of epochs of the registration
+    pub epochs: u64,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for RegisterName<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        RegisterName {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> RegisterName<C> {
+    /// The name to register, without its suffix
+    pub fn name(self, name: String) -> Self {
+        Self { name, ..self }
+    }
+
+    /// The address that pays the fee and owns the name
+    pub fn owner(self, owner: C::Address) -> Self {
+        Self { owner, ..self }
+    }
+
+    /// The number of epochs of the registration
+    pub fn epochs(self, epochs: u64) -> Self {
+        Self { epochs, ..self }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl RegisterName {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_register_name(context, self).await
+    }
+}
+
+/// Transaction to extend the registration of a name arguments
+#[derive(Clone, Debug)]
+pub struct RenewName<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The name to renew, without its suffix
+    pub name: String,
+    /// The number of epochs by which to extend the registration
+    pub epochs: u64,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for RenewName<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        RenewName {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> RenewName<C> {
+    /// The name to renew, without its suffix
+    pub fn name(self, name: String) -> Self {
+        Self { name, ..self }
+    }
+
+    /// The number of epochs by which to extend the registration
+    pub fn epochs(self, epochs: u64) -> Self {
+        Self { epochs, ..self }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl RenewName {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_renew_name(context, self).await
+    }
+}
+
+/// Transaction to transfer a name arguments
+#[derive(Clone, Debug)]
+pub struct TransferName<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The name to transfer, without its suffix
+    pub name: String,
+    /// The new owner of the name
+    pub new_owner: C::Address,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TransferName<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TransferName {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> TransferName<C> {
+    /// The name to transfer, without its suffix
+    pub fn name(self, name: String) -> Self {
+        Self { name, ..self }
+    }
+
+    /// The new owner of the name
+    pub fn new_owner(self, new_owner: C::Address) -> Self {
+        Self { new_owner, ..self }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl TransferName {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_transfer_name(context, self).await
+    }
+}
+
 /// Bond arguments
 #[derive(Clone, Debug)]
 pub struct Bond<C: NamadaTypes = SdkTypes> {
@@ -879,10 +1354,12 @@ pub struct Bond<C: NamadaTypes = SdkTypes> {
     /// Validator address
     pub validator: C::Address,
     /// Amount of tokens to stake in a bond
-    pub amount: token::Amount,
+    pub amount: InputAmount,
     /// Source address for delegations. For self-bonds, the validator is
     /// also the source.
     pub source: Option<C::Address>,
+    /// An optional future epoch in which the bond should be applied
+    pub start_epoch: Option<Epoch>,
     /// Path to the TX WASM code file
     pub tx_code_path: PathBuf,
 }
@@ -906,7 +1383,7 @@ impl<C: NamadaTypes> Bond<C> {
     }
 
     /// Amount of tokens to stake in a bond
-    pub fn amount(self, amount: token::Amount) -> Self {
+    pub fn amount(self, amount: InputAmount) -> Self {
         Self { amount, ..self }
     }
 
@@ -919,6 +1396,14 @@ impl<C: NamadaTypes> Bond<C> {
         }
     }
 
+    /// A future epoch in which the bond should be applied
+    pub fn start_epoch(self, start_epoch: Epoch) -> Self {
+        Self {
+            start_epoch: Some(start_epoch),
+            ..self
+        }
+    }
+
     /// Path to the TX WASM code file
     pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
         Self {
@@ -946,10 +1431,12 @@ pub struct Unbond<C: NamadaTypes = SdkTypes> {
     /// Validator address
     pub validator: C::Address,
     /// Amount of tokens to unbond from a bond
-    pub amount: token::Amount,
+    pub amount: InputAmount,
     /// Source address for unbonding from delegations. For unbonding from
     /// self-bonds, the validator is also the source
     pub source: Option<C::Address>,
+    /// An optional future epoch in which the unbond should be applied
+    pub start_epoch: Option<Epoch>,
     /// Path to the TX WASM code file
     pub tx_code_path: PathBuf,
 }
@@ -987,7 +1474,7 @@ impl<C: NamadaTypes> Unbond<C> {
     }
 
     /// Amount of tokens to unbond from a bond
-    pub fn amount(self, amount: token::Amount) -> Self {
+    pub fn amount(self, amount: InputAmount) -> Self {
         Self { amount, ..self }
     }
 
@@ -1000,6 +1487,14 @@ impl<C: NamadaTypes> Unbond<C> {
         }
     }
 
+    /// A future epoch in which the unbond should be applied
+    pub fn start_epoch(self, start_epoch: Epoch) -> Self {
+        Self {
+            start_epoch: Some(start_epoch),
+            ..self
+        }
+    }
+
     /// Path to the TX WASM code file
     pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
         Self {
@@ -1021,7 +1516,7 @@ pub struct Redelegate<C: NamadaTypes = SdkTypes> {
     /// Owner of the bonds that are being redelegated
     pub owner: C::Address,
     /// The amount of tokens to redelegate
-    pub amount: token::Amount,
+    pub amount: InputAmount,
     /// Path to the TX WASM code file
     pub tx_code_path: PathBuf,
 }
@@ -1221,6 +1716,9 @@ pub struct ClaimRewards<C: NamadaTypes = SdkTypes> {
     /// Source address for claiming rewards due to bonds. For self-bonds, the
     /// validator is also the source
     pub source: Option<C::Address>,
+    /// The payment address to deliver the rewards to in the shielded pool,
+    /// instead of the source. This requires the shielded claim tx code.
+    pub shielded_target: Option<PaymentAddress>,
     /// Path to the TX WASM code file
     pub tx_code_path: PathBuf,
 }
@@ -1237,6 +1735,18 @@ impl<C: NamadaTypes> TxBuilder<C> for ClaimRewards<C> {
     }
 }
 
+impl<C: NamadaTypes> ClaimRewards<C> {
+    /// Deliver the rewards to the given payment address in the shielded pool,
+    /// using the shielded claim tx code
+    pub fn shielded_target(self, shielded_target: PaymentAddress) -> Self {
+        Self {
+            shielded_target: Some(shielded_target),
+            tx_code_path: PathBuf::from(tx::TX_SHIELDED_CLAIM_REWARDS_WASM),
+            ..self
+        }
+    }
+}
+
 impl ClaimRewards {
     /// Build a transaction from this builder
     pub async fn build(
@@ -1278,6 +1788,9 @@ pub struct QueryBalance<C: NamadaTypes = SdkTypes> {
     pub token: Option<C::Address>,
     /// Whether not to convert balances
     pub no_conversions: bool,
+    /// The height from which to search the notes of the viewing keys that
+    /// are new to the shielded context
+    pub from_height: Option<BlockHeight>,
 }
 
 /// Query historical transfer(s)
@@ -1457,6 +1970,8 @@ pub struct MetaDataChange<C: NamadaTypes = SdkTypes> {
     pub discord_handle: Option<String>,
     /// New validator avatar url
     pub avatar: Option<String>,
+    /// New validator unique handle
+    pub handle: Option<String>,
     /// New validator commission rate
     pub commission_rate: Option<Dec>,
     /// Path to the TX WASM code file
@@ -1529,6 +2044,14 @@ impl<C: NamadaTypes> MetaDataChange<C> {
         }
     }
 
+    /// New validator unique handle
+    pub fn handle(self, handle: String) -> Self {
+        Self {
+            handle: Some(handle),
+            ..self
+        }
+    }
+
     /// New validator commission rate
     pub fn commission_rate(self, commission_rate: Dec) -> Self {
         Self {
@@ -1651,6 +2174,64 @@ impl ResignSteward {
     }
 }
 
+#[derive(Clone, Debug)]
+/// Claim pgf retro funding args
+pub struct ClaimPgfFunding<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The target address of the retro funding
+    pub target: C::Address,
+    /// The amount to claim, or all the currently claimable tokens if `None`
+    pub amount: Option<InputAmount>,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for ClaimPgfFunding<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        ClaimPgfFunding {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> ClaimPgfFunding<C> {
+    /// The target address of the retro funding
+    pub fn target(self, target: C::Address) -> Self {
+        Self { target, ..self }
+    }
+
+    /// The amount to claim
+    pub fn amount(self, amount: InputAmount) -> Self {
+        Self {
+            amount: Some(amount),
+            ..self
+        }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl ClaimPgfFunding {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(namada_tx::Tx, SigningTxData)> {
+        tx::build_claim_pgf_funding(context, self).await
+    }
+}
+
 #[derive(Clone, Debug)]
 /// Re-activate a jailed validator args
 pub struct TxUnjailValidator<C: NamadaTypes = SdkTypes> {
@@ -1884,6 +2465,9 @@ pub struct Tx<C: NamadaTypes = SdkTypes> {
     pub dry_run_wrapper: bool,
     /// Dump the transaction bytes to file
     pub dump_tx: bool,
+    /// Simulate the transaction and display the storage and balance changes
+    /// it would make before it gets signed
+    pub preview: bool,
     /// The output directory path to where serialize the data
     pub output_folder: Option<PathBuf>,
     /// Submit the transaction even if it doesn't pass client checks
@@ -1950,6 +2534,11 @@ pub trait TxBuilder<C: NamadaTypes>: Sized {
     fn dump_tx(self, dump_tx: bool) -> Self {
         self.tx(|x| Tx { dump_tx, ..x })
     }
+    /// Simulate the transaction and display the storage and balance changes
+    /// it would make before it gets signed
+    fn preview(self, preview: bool) -> Self {
+        self.tx(|x| Tx { preview, ..x })
+    }
     /// The output directory path to where serialize the data
     fn output_folder(self, output_folder: PathBuf) -> Self {
         self.tx(|x| Tx {
@@ -2171,6 +2760,8 @@ pub struct KeyAddressFind {
 pub struct KeyExport {
     /// Key alias
     pub alias: String,
+    /// The format of the exported key
+    pub format: KeyExportFormat,
 }
 
 /// Wallet key import arguments
@@ -2208,6 +2799,14 @@ pub struct KeyAddressRemove {
     pub do_it: bool,
 }
 
+/// Wallet daemon arguments
+#[derive(Clone, Debug)]
+pub struct WalletDaemon {
+    /// The path of the unix socket to listen on. Defaults to a socket in the
+    /// base directory of the chain.
+    pub socket_path: Option<PathBuf>,
+}
+
 /// Generate payment address arguments
 #[derive(Clone, Debug)]
 pub struct PayAddressGen<C: NamadaTypes = SdkTypes> {
@@ -2456,6 +3055,28 @@ pub struct ValidatorSetUpdateRelay<C: NamadaTypes = SdkTypes> {
     pub safe_mode: bool,
 }
 
+/// Payment receipt generation arguments
+#[derive(Clone, Debug)]
+pub struct GenReceipt<C: NamadaTypes = SdkTypes> {
+    /// The query parameters.
+    pub query: Query<C>,
+    /// The hash of the applied transfer tx
+    pub tx_hash: String,
+    /// The path of the file to write the receipt to
+    pub output: Option<PathBuf>,
+}
+
+/// Chain registry metadata generation arguments
+#[derive(Clone, Debug)]
+pub struct GenChainRegistry<C: NamadaTypes = SdkTypes> {
+    /// The query parameters.
+    pub query: Query<C>,
+    /// The public RPC endpoints of the chain to list in the metadata
+    pub rpc_endpoints: Vec<String>,
+    /// The path of the file to write the metadata to
+    pub output: Option<PathBuf>,
+}
+
 /// IBC shielded transfer generation arguments
 #[derive(Clone, Debug)]
 pub struct GenIbcShieldedTransafer<C: NamadaTypes = SdkTypes> {
@@ -0,0 +1,191 @@
+//! Chain registry metadata of a chain, generated from the state of a node.
+//!
+//! A [`ChainRegistry`] bundles the metadata that wallets and registries of
+//! chains need to integrate a chain: its id, the RPC endpoints, the fee
+//! tokens with their denominations and display exponents, the bech32m
+//! prefixes of the encoded types, the hash of the genesis block and the
+//! versions of the node software. It is serialized as JSON, in the spirit of
+//! the `chain.json` files of the cosmos chain registry.
+
+use std::collections::BTreeMap;
+
+use namada_core::types::address::Address;
+use namada_core::types::{string_encoding, token};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{self, QueryError};
+use crate::queries::RPC;
+use crate::rpc::{self, query_gas_prices, query_handshake};
+use crate::tendermint::block::Height;
+
+/// The version of the format of the chain registry metadata
+pub const CHAIN_REGISTRY_VERSION: u8 = 1;
+
+/// The chain registry metadata of a chain
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainRegistry {
+    /// The version of the format
+    pub version: u8,
+    /// The chain id
+    pub chain_id: String,
+    /// The RPC endpoints of the nodes of the chain
+    pub rpc_endpoints: Vec<String>,
+    /// The native token of the chain
+    pub native_token: Address,
+    /// The tokens that the fees can be paid with
+    pub fee_tokens: Vec<FeeToken>,
+    /// The bech32m prefixes of the encoded types
+    pub bech32_prefixes: Bech32Prefixes,
+    /// The hash of the genesis block, if the node still has it
+    pub genesis_hash: Option<String>,
+    /// The versions of the software
+    pub versions: Versions,
+}
+
+/// A token that the fees can be paid with
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeToken {
+    /// The address of the token
+    pub address: Address,
+    /// The ticker symbol of the token, if it has metadata
+    pub symbol: Option<String>,
+    /// The denomination of the raw amounts of the token
+    pub denomination: u8,
+    /// The number of decimal places to display the amounts with. Defaults to
+    /// the denomination if the token has no metadata.
+    pub display_exponent: u8,
+    /// The minimum gas price in the token
+    pub min_gas_price: token::DenominatedAmount,
+}
+
+/// The bech32m prefixes of the encoded types
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bech32Prefixes {
+    /// The prefix of the transparent addresses
+    pub address: String,
+    /// The prefix of the public keys
+    pub public_key: String,
+    /// The prefix of the signatures
+    pub signature: String,
+    /// The prefix of the shielded payment addresses
+    pub payment_address: String,
+    /// The prefix of the shielded full viewing keys
+    pub viewing_key: String,
+    /// The prefix of the shielded spending keys
+    pub spending_key: String,
+}
+
+impl Default for Bech32Prefixes {
+    fn default() -> Self {
+        Self {
+            address: string_encoding::ADDRESS_HRP.to_string(),
+            public_key: string_encoding::COMMON_PK_HRP.to_string(),
+            signature: string_encoding::COMMON_SIG_HRP.to_string(),
+            payment_address: string_encoding::MASP_PAYMENT_ADDRESS_HRP
+                .to_string(),
+            viewing_key: string_encoding::MASP_EXT_FULL_VIEWING_KEY_HRP
+                .to_string(),
+            spending_key: string_encoding::MASP_EXT_SPENDING_KEY_HRP
+                .to_string(),
+        }
+    }
+}
+
+/// The versions of the software of the node and of the client that generated
+/// the metadata
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Versions {
+    /// The version of the node software, if the node serves the handshake
+    /// query
+    pub node: Option<String>,
+    /// The version of the ABCI interface of the node, if the node serves the
+    /// handshake query
+    pub abci: Option<String>,
+    /// The version of CometBFT run by the node
+    pub cometbft: String,
+    /// The version of the client
+    pub client: String,
+}
+
+/// Generate the chain registry metadata from the state of the node of the
+/// client. The given RPC endpoints are listed in the metadata, as the node
+/// doesn't know the public addresses it's served at.
+pub async fn gen_chain_registry<C: crate::queries::Client + Sync>(
+    client: &C,
+    rpc_endpoints: Vec<String>,
+) -> Result<ChainRegistry, error::Error> {
+    let query_err =
+        |err: &dyn std::fmt::Display| QueryError::General(err.to_string());
+    let status = client.status().await.map_err(|err| query_err(&err))?;
+    let native_token = rpc::query_native_token(client).await?;
+
+    let gas_prices: BTreeMap<Address, token::Amount> =
+        query_gas_prices(client).await?;
+    let mut fee_tokens = Vec::with_capacity(gas_prices.len());
+    for (address, min_gas_price) in gas_prices {
+        let denom = RPC
+            .vp()
+            .token()
+            .denomination(client, &address)
+            .await
+            .map_err(|err| query_err(&err))?
+            .ok_or_else(|| {
+                error::Error::Other(format!(
+                    "The fee token {address} has no denomination"
+                ))
+            })?;
+        let metadata = rpc::query_token_metadata(client, &address).await?;
+        fee_tokens.push(FeeToken {
+            symbol: metadata.as_ref().map(|meta| meta.symbol.clone()),
+            denomination: denom.0,
+            display_exponent: metadata.map_or(denom.0, |meta| meta.decimals.0),
+            min_gas_price: token::DenominatedAmount::new(min_gas_price, denom),
+            address,
+        });
+    }
+
+    // The genesis block may be pruned by the node
+    let genesis_hash = client
+        .block(Height::from(1_u32))
+        .await
+        .ok()
+        .map(|response| response.block_id.hash.to_string());
+
+    let handshake = query_handshake(client).await?;
+    let versions = Versions {
+        node: handshake.as_ref().map(|hs| hs.node_version.clone()),
+        abci: handshake.map(|hs| hs.abci_version),
+        cometbft: status.node_info.version.to_string(),
+        client: env!("CARGO_PKG_VERSION").to_string(),
+    };
+
+    Ok(ChainRegistry {
+        version: CHAIN_REGISTRY_VERSION,
+        chain_id: status.node_info.network.to_string(),
+        rpc_endpoints,
+        native_token,
+        fee_tokens,
+        bech32_prefixes: Bech32Prefixes::default(),
+        genesis_hash,
+        versions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::address::testing::established_address_1;
+    use namada_core::types::key::testing::keypair_1;
+    use namada_core::types::key::RefTo;
+
+    use super::*;
+
+    /// Test that the bech32m prefixes are the ones of the encoded types.
+    #[test]
+    fn test_bech32_prefixes() {
+        let prefixes = Bech32Prefixes::default();
+        let address = established_address_1().to_string();
+        assert!(address.starts_with(&format!("{}1", prefixes.address)));
+        let pk = keypair_1().ref_to().to_string();
+        assert!(pk.starts_with(&format!("{}1", prefixes.public_key)));
+    }
+}
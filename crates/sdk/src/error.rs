@@ -5,7 +5,7 @@ use namada_core::types::dec::Dec;
 use namada_core::types::ethereum_events::EthAddress;
 use namada_core::types::storage;
 use namada_core::types::storage::Epoch;
-use namada_tx::Tx;
+use namada_tx::{Tx, TxLimitError};
 use prost::EncodeError;
 use tendermint_rpc::Error as RpcError;
 use thiserror::Error;
@@ -96,6 +96,10 @@ pub enum QueryError {
     /// synchronizing with the network.
     #[error("Node is still catching up with the network")]
     CatchingUp,
+    /// The protocol of the queries of the node is incompatible with this
+    /// version
+    #[error("Incompatible node: {0}")]
+    IncompatibleNode(String),
 }
 
 /// Errors that deal with Decoding, Encoding, or Conversions
@@ -174,6 +178,15 @@ pub enum TxSubmitError {
     /// The address is not a valid steward
     #[error("The address {0} is not a valid steward.")]
     InvalidSteward(Address),
+    /// No pgf retro funding to claim
+    #[error("There is no pgf retro funding to claim for the address {0}.")]
+    NoPgfFundingToClaim(Address),
+    /// The claimed amount exceeds the claimable pgf retro funding
+    #[error(
+        "The amount {0} exceeds the claimable pgf retro funding of {1} in the \
+         current epoch."
+    )]
+    PgfClaimTooLarge(String, String),
     /// Rate of epoch change too large for current epoch
     #[error(
         "New rate, {0}, is too large of a change with respect to the \
@@ -189,6 +202,12 @@ pub enum TxSubmitError {
     /// Unond amount is zero
     #[error("The requested unbond amount is 0.")]
     UnbondIsZero,
+    /// Invalid start epoch of a scheduled bond or unbond
+    #[error(
+        "The requested start epoch {0} must be after the current epoch {1} \
+         and at most {2} epochs later."
+    )]
+    InvalidScheduledEpoch(Epoch, Epoch, u64),
     /// No unbonded bonds ready to withdraw in the current epoch
     #[error(
         "There are no unbonded bonds ready to withdraw in the current epoch \
@@ -201,6 +220,9 @@ pub enum TxSubmitError {
     /// No bonds found
     #[error("No bonds found")]
     NoBondFound,
+    /// No rewards to claim
+    #[error("There are no rewards to claim from the validator {0}.")]
+    NoRewardsToClaim(Address),
     /// Lower bond amount than the unbond
     #[error(
         "The total bonds of the source {0} is lower than the amount to be \
@@ -299,6 +321,37 @@ pub enum TxSubmitError {
     /// Invalid owner account
     #[error("The source account {0} is not valid or doesn't exist.")]
     InvalidAccount(String),
+    /// Invalid spending policy quorum
+    #[error(
+        "The policy quorum {0} must be positive and at most the number of the \
+         account's public keys {1}."
+    )]
+    InvalidPolicyQuorum(u8, usize),
+    /// Invalid account threshold
+    #[error(
+        "The account threshold {0} must be positive and at most the number of \
+         the account's public keys {1}."
+    )]
+    InvalidAccountThreshold(u8, usize),
+    /// The tx exceeds a protocol limit
+    #[error("The transaction exceeds a protocol limit: {0}")]
+    TxLimit(#[from] TxLimitError),
+    /// A batched transfer has no legs
+    #[error("A batched transfer must have at least one leg.")]
+    EmptyMultiTransfer,
+    /// The legs of a batched transfer have different sources
+    #[error(
+        "All the legs of a batched transfer must have the same source, found \
+         {0} and {1}."
+    )]
+    MultiTransferSources(Address, Address),
+    /// The allowance is too low for a transfer by the spender
+    #[error(
+        "The allowance of the spender {1} to transfer the token {2} of the \
+         owner {0} is lower than the amount to be transferred. Amount to \
+         transfer is {3} and the allowance is {4}."
+    )]
+    AllowanceTooLow(Address, Address, Address, String, String),
     /// The redelegation amount is larger than the remaining bond amount
     #[error(
         "The redelegation amount is larger than the remaining bond amount. \
@@ -324,12 +377,37 @@ pub enum TxSubmitError {
     /// An empty string was provided as a new email
     #[error("An empty string cannot be provided as a new email")]
     InvalidEmail,
+    /// The new handle of a validator is invalid
+    #[error("Invalid validator handle {0}")]
+    InvalidValidatorHandle(String),
+    /// The new handle of a validator is registered by another validator
+    #[error("The validator handle {0} is already registered by {1}")]
+    ValidatorHandleTaken(String, Address),
     /// The consensus key is not Ed25519
     #[error("The consensus key must be an ed25519 key")]
     ConsensusKeyNotEd25519,
     /// The consensus key is not unique
     #[error("The consensus key has already been registered and is not unique")]
     ConsensusKeyNotUnique,
+    /// The txs of a recipe can't be applied
+    #[error(
+        "The txs of a recipe must be applied one after the other, as the \
+         later txs depend on the earlier ones. Dry-runs, dumps and broadcasts \
+         only are not supported."
+    )]
+    UnappliedRecipe,
+    /// A tx of a recipe failed
+    #[error(
+        "The {0} tx of the recipe was not applied, its later txs were not \
+         submitted."
+    )]
+    RecipeStepFailed(&'static str),
+    /// The source of the IBC transfer of a recipe is not shielded
+    #[error("The source of the IBC transfer must be a spending key.")]
+    UnshieldedIbcSource,
+    /// The tx initializing the account of a recipe didn't initialize it
+    #[error("The tx initializing the account didn't initialize an account.")]
+    NoInitializedAccount,
     /// Other Errors that may show up when using the interface
     #[error("{0}")]
     Other(String),
@@ -0,0 +1,295 @@
+//! Double-entry events of the changes of the token balances.
+//!
+//! The balances changed in a step of a block, such as a tx, the payment of
+//! its fee or an epoch transition hook, are paired as ledger entries that
+//! move an amount of a token from a debited account to a credited one. Every
+//! entry is emitted as a debit and a credit event that reference each other,
+//! so that the balances can be exactly reconstructed off-chain.
+
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{self, Display};
+
+use namada_core::types::address::{self, Address};
+use namada_core::types::token;
+
+use crate::events::{Event, EventLevel, EventType};
+
+/// An account of the double-entry ledger of a token
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LedgerAccount {
+    /// The balance of an owner
+    Owner(Address),
+    /// The supply of the token, debited when tokens are minted and credited
+    /// when they're burnt
+    Supply,
+}
+
+impl Display for LedgerAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerAccount::Owner(owner) => write!(f, "{owner}"),
+            LedgerAccount::Supply => write!(f, "supply"),
+        }
+    }
+}
+
+/// The reason of a change of balances
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BalanceChangeReason {
+    /// The payment, the escrow or the refund of a tx fee
+    Fee,
+    /// A transfer by a tx
+    Tx,
+    /// A transfer by a tx to or from the IBC escrow
+    Ibc,
+    /// A transfer by a tx to or from the MASP
+    Masp,
+    /// An epoch transition hook, with its name
+    EpochHook(&'static str),
+    /// Any other change made by the protocol
+    Protocol,
+}
+
+impl BalanceChangeReason {
+    /// The reason of the given entry made in a step with this reason. The
+    /// transfers of a tx to or from the IBC escrow or the MASP have more
+    /// specific reasons.
+    pub fn of_entry(self, entry: &LedgerEntry) -> Self {
+        if self != BalanceChangeReason::Tx {
+            return self;
+        }
+        let involves = |addr: &Address| {
+            [&entry.debit, &entry.credit].into_iter().any(|account| {
+                matches!(account, LedgerAccount::Owner(owner) if owner == addr)
+            })
+        };
+        if involves(&address::IBC) {
+            BalanceChangeReason::Ibc
+        } else if involves(&address::MASP) {
+            BalanceChangeReason::Masp
+        } else {
+            self
+        }
+    }
+}
+
+impl Display for BalanceChangeReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BalanceChangeReason::Fee => write!(f, "fee"),
+            BalanceChangeReason::Tx => write!(f, "tx"),
+            BalanceChangeReason::Ibc => write!(f, "ibc"),
+            BalanceChangeReason::Masp => write!(f, "masp"),
+            BalanceChangeReason::EpochHook(name) => write!(f, "{name}"),
+            BalanceChangeReason::Protocol => write!(f, "protocol"),
+        }
+    }
+}
+
+/// An entry of the double-entry ledger, that moves an amount of a token from
+/// the debited account to the credited one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LedgerEntry {
+    /// The token
+    pub token: Address,
+    /// The debited account
+    pub debit: LedgerAccount,
+    /// The credited account
+    pub credit: LedgerAccount,
+    /// The amount moved
+    pub amount: token::Amount,
+}
+
+impl LedgerEntry {
+    /// Get the debit and the credit events of the entry, with the given id
+    /// of the entry in the block and the reason of the change.
+    pub fn to_events(
+        &self,
+        id: u64,
+        reason: BalanceChangeReason,
+    ) -> [Event; 2] {
+        let reason = reason.of_entry(self);
+        let event = |side: &str,
+                     account: &LedgerAccount,
+                     counterparty: &LedgerAccount| {
+            let attributes = HashMap::from([
+                ("entry".to_string(), id.to_string()),
+                ("side".to_string(), side.to_string()),
+                ("account".to_string(), account.to_string()),
+                ("counterparty".to_string(), counterparty.to_string()),
+                ("token".to_string(), self.token.to_string()),
+                ("amount".to_string(), self.amount.to_string()),
+                ("reason".to_string(), reason.to_string()),
+            ]);
+            Event {
+                event_type: EventType::BalanceChange,
+                level: EventLevel::Block,
+                attributes,
+            }
+        };
+        [
+            event("debit", &self.debit, &self.credit),
+            event("credit", &self.credit, &self.debit),
+        ]
+    }
+}
+
+/// Pair the changes of the balances as ledger entries. The changes are given
+/// as the token, the owner and the balances before and after the change. The
+/// changes of a token that don't sum to zero are balanced by its supply
+/// account. The debits and the credits of a token are paired in the order of
+/// their accounts, so a step with several debited and credited accounts may
+/// be split into more entries than the transfers that it made.
+pub fn ledger_entries(
+    changes: impl IntoIterator<
+        Item = (Address, Address, token::Amount, token::Amount),
+    >,
+) -> Vec<LedgerEntry> {
+    let mut debits: BTreeMap<Address, Vec<(LedgerAccount, token::Amount)>> =
+        BTreeMap::new();
+    let mut credits: BTreeMap<Address, Vec<(LedgerAccount, token::Amount)>> =
+        BTreeMap::new();
+    for (token, owner, pre, post) in changes {
+        let account = LedgerAccount::Owner(owner);
+        if post > pre {
+            credits
+                .entry(token)
+                .or_default()
+                .push((account, post - pre));
+        } else if pre > post {
+            debits.entry(token).or_default().push((account, pre - post));
+        }
+    }
+
+    let tokens = debits
+        .keys()
+        .chain(credits.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    let mut entries = vec![];
+    for token in tokens {
+        let mut debits = debits.remove(&token).unwrap_or_default();
+        let mut credits = credits.remove(&token).unwrap_or_default();
+        debits.sort();
+        credits.sort();
+        let total_debit = debits
+            .iter()
+            .fold(token::Amount::zero(), |acc, (_, amount)| acc + *amount);
+        let total_credit = credits
+            .iter()
+            .fold(token::Amount::zero(), |acc, (_, amount)| acc + *amount);
+        if total_credit > total_debit {
+            debits.push((LedgerAccount::Supply, total_credit - total_debit));
+        } else if total_debit > total_credit {
+            credits.push((LedgerAccount::Supply, total_debit - total_credit));
+        }
+
+        let (mut debit_ix, mut credit_ix) = (0, 0);
+        while debit_ix < debits.len() && credit_ix < credits.len() {
+            let (debit, debit_left) = &mut debits[debit_ix];
+            let (credit, credit_left) = &mut credits[credit_ix];
+            let amount = cmp::min(*debit_left, *credit_left);
+            entries.push(LedgerEntry {
+                token: token.clone(),
+                debit: debit.clone(),
+                credit: credit.clone(),
+                amount,
+            });
+            *debit_left = *debit_left - amount;
+            *credit_left = *credit_left - amount;
+            if debit_left.is_zero() {
+                debit_ix += 1;
+            }
+            if credit_left.is_zero() {
+                credit_ix += 1;
+            }
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+
+    use super::*;
+
+    /// Test that the changes of the balances are paired as entries that are
+    /// balanced by the supply.
+    #[test]
+    fn test_ledger_entries() {
+        let token = address::nam();
+        let [alice, bob, carol] = [
+            established_address_1(),
+            established_address_2(),
+            established_address_3(),
+        ];
+        let amount = token::Amount::from_u64;
+        let owner = LedgerAccount::Owner;
+
+        // A transfer from alice to bob and carol
+        let entries = ledger_entries([
+            (token.clone(), alice.clone(), amount(10), amount(4)),
+            (token.clone(), bob.clone(), amount(0), amount(2)),
+            (token.clone(), carol.clone(), amount(5), amount(9)),
+        ]);
+        assert_eq!(entries.len(), 2);
+        for (credit, credited) in [(bob.clone(), 2), (carol.clone(), 4)] {
+            assert!(entries.contains(&LedgerEntry {
+                token: token.clone(),
+                debit: owner(alice.clone()),
+                credit: owner(credit),
+                amount: amount(credited),
+            }));
+        }
+
+        // A mint to alice and a burn from bob, with an unchanged balance
+        let entries = ledger_entries([
+            (token.clone(), alice.clone(), amount(0), amount(3)),
+            (token.clone(), carol.clone(), amount(1), amount(1)),
+        ]);
+        assert_eq!(
+            entries,
+            vec![LedgerEntry {
+                token: token.clone(),
+                debit: LedgerAccount::Supply,
+                credit: owner(alice.clone()),
+                amount: amount(3),
+            }]
+        );
+        let entries = ledger_entries([(
+            token.clone(),
+            bob.clone(),
+            amount(3),
+            amount(0),
+        )]);
+        assert_eq!(
+            entries,
+            vec![LedgerEntry {
+                token: token.clone(),
+                debit: owner(bob.clone()),
+                credit: LedgerAccount::Supply,
+                amount: amount(3),
+            }]
+        );
+
+        // The transfers to the IBC escrow have a specific reason
+        let entries = ledger_entries([
+            (token.clone(), alice, amount(3), amount(0)),
+            (token, address::IBC, amount(0), amount(3)),
+        ]);
+        let [debit, credit] = entries[0].to_events(7, BalanceChangeReason::Tx);
+        assert_eq!(debit["side"], "debit");
+        assert_eq!(credit["side"], "credit");
+        assert_eq!(debit["counterparty"], credit["account"]);
+        assert_eq!(credit["counterparty"], address::IBC.to_string());
+        for event in [debit, credit] {
+            assert_eq!(event["entry"], "7");
+            assert_eq!(event["amount"], "3");
+            assert_eq!(event["reason"], "ibc");
+        }
+    }
+}
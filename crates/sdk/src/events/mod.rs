@@ -1,4 +1,5 @@
 //! Logic to do with events emitted by the ledger.
+pub mod balance;
 pub mod log;
 
 use std::collections::HashMap;
@@ -37,6 +38,7 @@ impl From<&EthBridgeEvent> for Event {
                         match status {
                             BpTransferStatus::Relayed => "bridge_pool_relayed",
                             BpTransferStatus::Expired => "bridge_pool_expired",
+                            BpTransferStatus::Vetoed => "bridge_pool_vetoed",
                         }
                         .into(),
                     );
@@ -86,6 +88,10 @@ pub enum EventType {
     PgfPayment,
     /// Ethereum Bridge event
     EthereumBridge,
+    /// The debit or the credit of a balance
+    BalanceChange,
+    /// The native tokens minted in an epoch exceeded the limit
+    MintLimit,
 }
 
 impl Display for EventType {
@@ -97,6 +103,8 @@ impl Display for EventType {
             EventType::Proposal => write!(f, "proposal"),
             EventType::PgfPayment => write!(f, "pgf_payment"),
             EventType::EthereumBridge => write!(f, "ethereum_bridge"),
+            EventType::BalanceChange => write!(f, "balance_change"),
+            EventType::MintLimit => write!(f, "mint_limit"),
         }?;
         Ok(())
     }
@@ -118,6 +126,8 @@ impl FromStr for EventType {
                 Ok(EventType::Ibc("write_acknowledgement".to_string()))
             }
             "ethereum_bridge" => Ok(EventType::EthereumBridge),
+            "balance_change" => Ok(EventType::BalanceChange),
+            "mint_limit" => Ok(EventType::MintLimit),
             _ => Err(EventError::InvalidEventType),
         }
     }
@@ -14,7 +14,9 @@ pub mod eth_bridge;
 pub mod rpc;
 
 pub mod args;
+pub mod chain_registry;
 pub mod masp;
+pub mod memo;
 pub mod signing;
 #[allow(clippy::result_large_err)]
 pub mod tx;
@@ -25,6 +27,8 @@ pub mod events;
 pub(crate) mod internal_macros;
 pub mod io;
 pub mod queries;
+pub mod receipt;
+pub mod recipes;
 pub mod wallet;
 
 use std::collections::HashSet;
@@ -36,12 +40,15 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use args::{InputAmount, SdkTypes};
+use namada_account::AccountPolicy;
 use namada_core::ibc::core::host::types::identifiers::{ChannelId, PortId};
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
 use namada_core::types::ethereum_events::EthAddress;
 use namada_core::types::key::*;
-use namada_core::types::masp::{TransferSource, TransferTarget};
+use namada_core::types::masp::{
+    ExtendedSpendingKey, TransferSource, TransferTarget,
+};
 use namada_core::types::token;
 use namada_tx::data::wrapper::GasLimit;
 use namada_tx::Tx;
@@ -55,15 +62,18 @@ use crate::rpc::{
 use crate::signing::SigningTxData;
 use crate::token::{DenominatedAmount, NATIVE_MAX_DECIMAL_PLACES};
 use crate::tx::{
-    ProcessTxResponse, TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM,
+    ProcessTxResponse, TX_APPROVE_WASM, TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM,
     TX_BRIDGE_POOL_WASM, TX_CHANGE_COMMISSION_WASM,
     TX_CHANGE_CONSENSUS_KEY_WASM, TX_CHANGE_METADATA_WASM,
-    TX_CLAIM_REWARDS_WASM, TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM,
-    TX_INIT_ACCOUNT_WASM, TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM,
-    TX_REDELEGATE_WASM, TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_WASM,
-    TX_UNBOND_WASM, TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
+    TX_CLAIM_PGF_FUNDING_WASM, TX_CLAIM_REWARDS_WASM,
+    TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM, TX_INIT_ACCOUNT_WASM,
+    TX_INIT_PROPOSAL, TX_MULTI_TRANSFER_WASM, TX_REACTIVATE_VALIDATOR_WASM,
+    TX_REDELEGATE_WASM, TX_REGISTER_NAME_WASM, TX_RENEW_NAME_WASM,
+    TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_FROM_WASM,
+    TX_TRANSFER_NAME_WASM, TX_TRANSFER_WASM, TX_UNBOND_WASM,
+    TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM, TX_UPDATE_POLICY_WASM,
     TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
-    VP_USER_WASM,
+    VP_POLICY_WASM, VP_USER_WASM,
 };
 use crate::wallet::{Wallet, WalletIo, WalletStorage};
 
@@ -124,6 +134,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             dry_run: false,
             dry_run_wrapper: false,
             dump_tx: false,
+            preview: false,
             output_folder: None,
             force: false,
             broadcast_only: false,
@@ -163,11 +174,62 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             target,
             token,
             amount,
+            shielded_memo: None,
             tx_code_path: PathBuf::from(TX_TRANSFER_WASM),
             tx: self.tx_builder(),
         }
     }
 
+    /// Make a TxMultiTransfer builder from the given minimum set of arguments
+    fn new_multi_transfer(
+        &self,
+        legs: Vec<args::TxTransferLeg>,
+    ) -> args::TxMultiTransfer {
+        args::TxMultiTransfer {
+            legs,
+            tx_code_path: PathBuf::from(TX_MULTI_TRANSFER_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
+    /// Make a TxApprove builder from the given minimum set of arguments
+    fn new_approve(
+        &self,
+        owner: Address,
+        spender: Address,
+        token: Address,
+        amount: InputAmount,
+    ) -> args::TxApprove {
+        args::TxApprove {
+            owner,
+            spender,
+            token,
+            amount,
+            tx_code_path: PathBuf::from(TX_APPROVE_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
+    /// Make a TxTransferFrom builder from the given minimum set of arguments
+    fn new_transfer_from(
+        &self,
+        owner: Address,
+        spender: Address,
+        target: Address,
+        token: Address,
+        amount: InputAmount,
+    ) -> args::TxTransferFrom {
+        args::TxTransferFrom {
+            owner,
+            spender,
+            target,
+            token,
+            amount,
+            tx_code_path: PathBuf::from(TX_TRANSFER_FROM_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
     /// Make a InitAccount builder from the given minimum set of arguments
     fn new_init_account(
         &self,
@@ -199,8 +261,11 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
     ) -> args::Bond {
         args::Bond {
             validator,
-            amount,
+            amount: InputAmount::Validated(token::DenominatedAmount::native(
+                amount,
+            )),
             source: None,
+            start_epoch: None,
             tx: self.tx_builder(),
             tx_code_path: PathBuf::from(TX_BOND_WASM),
         }
@@ -214,8 +279,11 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
     ) -> args::Unbond {
         args::Unbond {
             validator,
-            amount,
+            amount: InputAmount::Validated(token::DenominatedAmount::native(
+                amount,
+            )),
             source: None,
+            start_epoch: None,
             tx: self.tx_builder(),
             tx_code_path: PathBuf::from(TX_UNBOND_WASM),
         }
@@ -238,7 +306,9 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             /// Owner of the bonds that are being redelegated
             owner: source,
             /// The amount of tokens to redelegate
-            amount,
+            amount: InputAmount::Validated(token::DenominatedAmount::native(
+                amount,
+            )),
             /// Path to the TX WASM code file
             tx_code_path: PathBuf::from(TX_REDELEGATE_WASM),
         }
@@ -275,6 +345,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             is_offline: false,
             is_pgf_stewards: false,
             is_pgf_funding: false,
+            is_slash_pool: false,
             tx_code_path: PathBuf::from(TX_INIT_PROPOSAL),
             tx: self.tx_builder(),
         }
@@ -292,6 +363,56 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make a TxUpdatePolicy builder from the given minimum set of arguments
+    fn new_update_policy(&self, addr: Address) -> args::TxUpdatePolicy {
+        args::TxUpdatePolicy {
+            addr,
+            policy_data: None,
+            tx_code_path: PathBuf::from(TX_UPDATE_POLICY_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
+    /// Make a RegisterName builder from the given minimum set of arguments
+    fn new_register_name(
+        &self,
+        name: String,
+        owner: Address,
+        epochs: u64,
+    ) -> args::RegisterName {
+        args::RegisterName {
+            name,
+            owner,
+            epochs,
+            tx_code_path: PathBuf::from(TX_REGISTER_NAME_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
+    /// Make a RenewName builder from the given minimum set of arguments
+    fn new_renew_name(&self, name: String, epochs: u64) -> args::RenewName {
+        args::RenewName {
+            name,
+            epochs,
+            tx_code_path: PathBuf::from(TX_RENEW_NAME_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
+    /// Make a TransferName builder from the given minimum set of arguments
+    fn new_transfer_name(
+        &self,
+        name: String,
+        new_owner: Address,
+    ) -> args::TransferName {
+        args::TransferName {
+            name,
+            new_owner,
+            tx_code_path: PathBuf::from(TX_TRANSFER_NAME_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
     /// Make a VoteProposal builder from the given minimum set of arguments
     fn new_vote_prposal(
         &self,
@@ -348,6 +469,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             website: None,
             discord_handle: None,
             avatar: None,
+            handle: None,
             commission_rate: None,
             tx_code_path: PathBuf::from(TX_CHANGE_METADATA_WASM),
             tx: self.tx_builder(),
@@ -467,11 +589,61 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         args::ClaimRewards {
             validator,
             source: None,
+            shielded_target: None,
             tx_code_path: PathBuf::from(TX_CLAIM_REWARDS_WASM),
             tx: self.tx_builder(),
         }
     }
 
+    /// Make a StakeAndCompound recipe from the given minimum set of arguments
+    fn new_stake_and_compound(
+        &self,
+        validator: Address,
+        amount: token::Amount,
+    ) -> recipes::StakeAndCompound {
+        recipes::StakeAndCompound::new(self.new_bond(validator, amount))
+    }
+
+    /// Make a ShieldAndIbcTransfer recipe from the given minimum set of
+    /// arguments
+    fn new_shield_and_ibc_transfer(
+        &self,
+        source: Address,
+        spending_key: ExtendedSpendingKey,
+        receiver: String,
+        token: Address,
+        amount: InputAmount,
+        channel_id: ChannelId,
+    ) -> recipes::ShieldAndIbcTransfer {
+        recipes::ShieldAndIbcTransfer::new(
+            source,
+            self.new_ibc_transfer(
+                TransferSource::ExtendedSpendingKey(spending_key),
+                receiver,
+                token,
+                amount,
+                channel_id,
+            ),
+        )
+    }
+
+    /// Make an InitPolicyAccount recipe from the given minimum set of
+    /// arguments
+    fn new_init_policy_account(
+        &self,
+        public_keys: Vec<common::PublicKey>,
+        threshold: Option<u8>,
+        policy: AccountPolicy,
+        funder: Address,
+        funds: Vec<(Address, InputAmount)>,
+    ) -> recipes::InitPolicyAccount {
+        let init_account = args::TxInitAccount {
+            vp_code_path: PathBuf::from(VP_POLICY_WASM),
+            ..self.new_init_account(public_keys, threshold)
+        };
+        recipes::InitPolicyAccount::new(init_account, policy, funder, funds)
+    }
+
     /// Make a Withdraw builder from the given minimum set of arguments
     fn new_add_erc20_transfer(
         &self,
@@ -523,6 +695,17 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make a ClaimPgfFunding builder from the given minimum set of
+    /// arguments
+    fn new_claim_pgf_funding(&self, target: Address) -> args::ClaimPgfFunding {
+        args::ClaimPgfFunding {
+            target,
+            amount: None,
+            tx: self.tx_builder(),
+            tx_code_path: PathBuf::from(TX_CLAIM_PGF_FUNDING_WASM),
+        }
+    }
+
     /// Make a TxCustom builder from the given minimum set of arguments
     fn new_custom(&self, owner: Address) -> args::TxCustom {
         args::TxCustom {
@@ -612,6 +795,9 @@ where
     native_token: Address,
     /// The default builder for a Tx
     prototype: args::Tx,
+    /// The separator between the groups of thousands of the formatted
+    /// amounts, if any
+    amount_separator: Option<char>,
 }
 
 impl<C, U, V, I> NamadaImpl<C, U, V, I>
@@ -639,6 +825,7 @@ where
                 dry_run: false,
                 dry_run_wrapper: false,
                 dump_tx: false,
+                preview: false,
                 output_folder: None,
                 force: false,
                 broadcast_only: false,
@@ -663,9 +850,17 @@ where
                 memo: None,
                 use_device: false,
             },
+            amount_separator: None,
         }
     }
 
+    /// Format the amounts with the given separator between the groups of
+    /// thousands of their integer parts
+    pub fn with_amount_separator(mut self, sep: char) -> Self {
+        self.amount_separator = Some(sep);
+        self
+    }
+
     /// Construct a new Namada context looking up the native token address
     pub async fn new(
         client: C,
@@ -715,6 +910,28 @@ where
         &self.client
     }
 
+    async fn format_amount(
+        &self,
+        token: &Address,
+        amount: token::Amount,
+    ) -> String {
+        match self.amount_separator {
+            Some(sep) => self
+                .denominate_amount(token, amount)
+                .await
+                .format_with_separators(sep),
+            None => {
+                format_denominated_amount(
+                    self.client(),
+                    self.io(),
+                    token,
+                    amount,
+                )
+                .await
+            }
+        }
+    }
+
     async fn wallet(&self) -> RwLockReadGuard<Wallet<Self::WalletUtils>> {
         self.wallet.read().await
     }
@@ -766,7 +983,7 @@ pub mod testing {
     use governance::ProposalType;
     use ibc::primitives::proto::Any;
     use masp_primitives::transaction::TransparentAddress;
-    use namada_account::{InitAccount, UpdateAccount};
+    use namada_account::{InitAccount, UpdateAccount, UpdatePolicy};
     use namada_core::types::address::testing::{
         arb_established_address, arb_non_internal_address,
     };
@@ -796,7 +1013,9 @@ pub mod testing {
     use sha2::Digest;
 
     use super::*;
-    use crate::account::tests::{arb_init_account, arb_update_account};
+    use crate::account::tests::{
+        arb_init_account, arb_update_account, arb_update_policy,
+    };
     use crate::masp::testing::{
         arb_deshielding_transfer, arb_shielded_transfer, arb_shielding_transfer,
     };
@@ -809,8 +1028,10 @@ pub mod testing {
     use crate::tx::{Code, Commitment, Header, MaspBuilder, Section};
     use crate::types::chain::ChainId;
     use crate::types::eth_bridge_pool::testing::arb_pending_transfer;
-    use crate::types::key::testing::arb_common_pk;
-    use crate::types::time::{DateTime, DateTimeUtc, Utc};
+    use crate::types::key::testing::{arb_common_keypair, arb_common_pk};
+    use crate::types::time::{
+        DateTime, DateTimeUtc, Duration, DurationSecs, Utc,
+    };
 
     #[derive(Debug)]
     #[allow(clippy::large_enum_variant)]
@@ -829,6 +1050,7 @@ pub mod testing {
         Unbond(Unbond),
         UnjailValidator(Address),
         UpdateAccount(UpdateAccount),
+        UpdatePolicy(UpdatePolicy),
         VoteProposal(VoteProposalData),
         Withdraw(Withdraw),
         Transfer(Transfer),
@@ -1206,6 +1428,22 @@ pub mod testing {
         }
     }
 
+    prop_compose! {
+        // Generate an arbitrary account policy update transaction
+        pub fn arb_update_policy_tx()(
+            mut header in arb_header(),
+            wrapper in arb_wrapper_tx(),
+            update_policy in arb_update_policy(),
+            code_hash in arb_hash(),
+        ) -> (Tx, TxData) {
+            header.tx_type = TxType::Wrapper(Box::new(wrapper));
+            let mut tx = Tx { header, sections: vec![] };
+            tx.add_data(update_policy.clone());
+            tx.add_code_from_hash(code_hash, Some(TX_UPDATE_POLICY_WASM.to_owned()));
+            (tx, TxData::UpdatePolicy(update_policy))
+        }
+    }
+
     prop_compose! {
         // Generate an arbitrary reveal public key transaction
         pub fn arb_withdraw_tx()(
@@ -1429,6 +1667,7 @@ pub mod testing {
             arb_vote_proposal_tx(),
             arb_reveal_pk_tx(),
             arb_update_account_tx(),
+            arb_update_policy_tx(),
             arb_withdraw_tx(),
             arb_claim_rewards_tx(),
             arb_commission_change_tx(),
@@ -1444,4 +1683,148 @@ pub mod testing {
             arb_ibc_any_tx(),
         ]
     }
+
+    /// The maximum lifetime of the generated signed txs, in seconds
+    const MAX_SIGNED_TX_LIFETIME_SECS: u64 = 60 * 60 * 24;
+
+    /// A way to make a signed tx invalid, right past the boundary of its
+    /// validity
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum TxInvalidity {
+        /// The wrapper is signed by another key than the one of the fee payer
+        WrongWrapperSigner,
+        /// The header is modified after the tx was signed
+        TamperedHeader,
+        /// The tx is for another chain
+        WrongChainId,
+        /// The tx expired one second before the given time
+        Expired,
+        /// The gas limit of the wrapper is zero
+        ZeroGasLimit,
+    }
+
+    /// Set the chain, the expiration, the fee payer and the gas limit of a
+    /// wrapper tx, then sign it with the given keys
+    fn sign_tx(
+        mut tx: Tx,
+        chain_id: ChainId,
+        expiration: DateTimeUtc,
+        fee_payer: common::SecretKey,
+        signer: common::SecretKey,
+        gas_limit: GasLimit,
+    ) -> Tx {
+        tx.header.chain_id = chain_id;
+        tx.header.expiration = Some(expiration);
+        if let TxType::Wrapper(wrapper) = &mut tx.header.tx_type {
+            wrapper.pk = fee_payer.ref_to();
+            wrapper.gas_limit = gas_limit;
+        }
+        let public_keys = [signer.ref_to()].into_iter().collect();
+        tx.sign_raw(vec![signer], public_keys, None);
+        tx.sign_wrapper(fee_payer);
+        tx
+    }
+
+    /// Generate an arbitrary tx of any type, signed by its fee payer and by
+    /// an inner signer, for the given chain. The tx expires after `now`, and
+    /// its wrapper has the given gas limit.
+    pub fn arb_signed_tx(
+        chain_id: ChainId,
+        now: DateTimeUtc,
+        gas_limit: GasLimit,
+    ) -> impl Strategy<Value = (Tx, TxData)> {
+        (
+            arb_tx(),
+            arb_common_keypair(),
+            arb_common_keypair(),
+            1..=MAX_SIGNED_TX_LIFETIME_SECS,
+        )
+            .prop_map(
+                move |((tx, tx_data), fee_payer, signer, lifetime)| {
+                    let expiration = now + DurationSecs(lifetime);
+                    let tx = sign_tx(
+                        tx,
+                        chain_id.clone(),
+                        expiration,
+                        fee_payer,
+                        signer,
+                        gas_limit,
+                    );
+                    (tx, tx_data)
+                },
+            )
+    }
+
+    /// Generate an arbitrary invalidity of signed txs
+    pub fn arb_tx_invalidity() -> impl Strategy<Value = TxInvalidity> {
+        prop_oneof![
+            Just(TxInvalidity::WrongWrapperSigner),
+            Just(TxInvalidity::TamperedHeader),
+            Just(TxInvalidity::WrongChainId),
+            Just(TxInvalidity::Expired),
+            Just(TxInvalidity::ZeroGasLimit),
+        ]
+    }
+
+    /// Generate an arbitrary tx like [`arb_signed_tx`], which is then made
+    /// invalid in exactly one way, right past the boundary of its validity
+    pub fn arb_invalid_signed_tx(
+        chain_id: ChainId,
+        now: DateTimeUtc,
+        gas_limit: GasLimit,
+    ) -> impl Strategy<Value = (Tx, TxData, TxInvalidity)> {
+        (
+            arb_tx(),
+            arb_common_keypair(),
+            arb_common_keypair(),
+            arb_common_keypair(),
+            1..=MAX_SIGNED_TX_LIFETIME_SECS,
+            arb_tx_invalidity(),
+        )
+            .prop_map(
+                move |(
+                    (tx, tx_data),
+                    fee_payer,
+                    signer,
+                    other_key,
+                    lifetime,
+                    invalidity,
+                )| {
+                    let mut chain_id = chain_id.clone();
+                    let mut expiration = now + DurationSecs(lifetime);
+                    let mut gas_limit = gas_limit;
+                    match invalidity {
+                        TxInvalidity::WrongChainId => {
+                            chain_id = ChainId(format!("{chain_id}x"))
+                        }
+                        TxInvalidity::Expired => {
+                            expiration = now - Duration::seconds(1)
+                        }
+                        TxInvalidity::ZeroGasLimit => gas_limit = 0.into(),
+                        TxInvalidity::WrongWrapperSigner
+                        | TxInvalidity::TamperedHeader => {}
+                    }
+                    let mut tx = sign_tx(
+                        tx, chain_id, expiration, fee_payer, signer, gas_limit,
+                    );
+                    match invalidity {
+                        TxInvalidity::WrongWrapperSigner => {
+                            // Only keep the signature of the inner tx
+                            tx.sections
+                                .pop()
+                                .expect("The wrapper signature must be there");
+                            tx.sign_wrapper(other_key);
+                        }
+                        TxInvalidity::TamperedHeader => {
+                            tx.header.timestamp =
+                                tx.header.timestamp + DurationSecs(1);
+                        }
+                        TxInvalidity::WrongChainId
+                        | TxInvalidity::Expired
+                        | TxInvalidity::ZeroGasLimit => {}
+                    }
+                    (tx, tx_data, invalidity)
+                },
+            )
+    }
 }
@@ -19,8 +19,9 @@ use masp_primitives::asset_type::AssetType;
 use masp_primitives::consensus::MainNetwork;
 #[cfg(not(feature = "mainnet"))]
 use masp_primitives::consensus::TestNetwork;
+use masp_primitives::constants::SPENDING_KEY_GENERATOR;
 use masp_primitives::convert::AllowedConversion;
-use masp_primitives::ff::PrimeField;
+use masp_primitives::ff::{Field, PrimeField};
 use masp_primitives::group::GroupEncoding;
 use masp_primitives::memo::MemoBytes;
 use masp_primitives::merkle_tree::{
@@ -28,16 +29,17 @@ use masp_primitives::merkle_tree::{
 };
 use masp_primitives::sapling::keys::FullViewingKey;
 use masp_primitives::sapling::note_encryption::*;
-use masp_primitives::sapling::redjubjub::PublicKey;
+use masp_primitives::sapling::prover::TxProver;
+use masp_primitives::sapling::redjubjub::{PrivateKey, PublicKey, Signature};
 use masp_primitives::sapling::{
-    Diversifier, Node, Note, Nullifier, ViewingKey,
+    Diversifier, Node, Note, Nullifier, ProofGenerationKey, Rseed, ViewingKey,
 };
 use masp_primitives::transaction::builder::{self, *};
 use masp_primitives::transaction::components::sapling::builder::SaplingMetadata;
 use masp_primitives::transaction::components::transparent::builder::TransparentBuilder;
 use masp_primitives::transaction::components::{
     ConvertDescription, I128Sum, OutputDescription, SpendDescription, TxOut,
-    U64Sum, ValueSum,
+    U64Sum, ValueSum, GROTH_PROOF_SIZE,
 };
 use masp_primitives::transaction::fees::fixed::FeeRule;
 use masp_primitives::transaction::sighash::{signature_hash, SignableInput};
@@ -61,6 +63,9 @@ use namada_core::types::storage::{BlockHeight, Epoch, IndexedTx, TxIndex};
 use namada_core::types::time::{DateTimeUtc, DurationSecs};
 use namada_core::types::uint::Uint;
 use namada_ibc::IbcMessage;
+use namada_token::compact::{
+    CompactTransparent, CompactTx, MAX_COMPACT_BLOCKS_PER_QUERY,
+};
 use namada_token::{self as token, Denomination, MaspDigitPos, Transfer};
 use namada_tx::data::{TxResult, WrapperTx};
 use namada_tx::Tx;
@@ -75,7 +80,7 @@ use token::Amount;
 use crate::error::EncodingError;
 use crate::error::{Error, PinnedBalanceError, QueryError};
 use crate::io::Io;
-use crate::queries::Client;
+use crate::queries::{Capability, Client};
 use crate::rpc::{
     query_block, query_conversion, query_denom, query_epoch_at_height,
     query_native_token,
@@ -113,6 +118,13 @@ pub const OUTPUT_NAME: &str = "masp-output.params";
 /// Convert circuit name
 pub const CONVERT_NAME: &str = "masp-convert.params";
 
+/// The number of block heights of the ranges that start with a scan
+/// checkpoint. The shielded context is also saved after fetching each range.
+pub const SCAN_CHECKPOINT_INTERVAL: u64 = 10_000;
+
+/// The maximum length of the memos of the shielded outputs of transfers
+pub const MAX_SHIELDED_MEMO_LEN: usize = 512;
+
 /// Shielded transfer
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
 pub struct ShieldedTransfer {
@@ -316,6 +328,22 @@ fn partial_deauthorize(
     ))
 }
 
+/// Compute the sighash of a shielded transaction, which its spend
+/// authorizations and its binding signature sign.
+fn shielded_sighash(transaction: &Transaction) -> Option<[u8; 32]> {
+    // Partially deauthorize the transparent bundle
+    let unauth_tx_data = partial_deauthorize(transaction.deref())?;
+
+    let txid_parts = unauth_tx_data.digest(TxIdDigester);
+    // the commitment being signed is shared across all Sapling inputs; once
+    // V4 transactions are deprecated this should just be the txid, but
+    // for now we need to continue to compute it here.
+    let sighash =
+        signature_hash(&unauth_tx_data, &SignableInput::Shielded, &txid_parts);
+    let sighash: &[u8; 32] = sighash.as_ref();
+    Some(*sighash)
+}
+
 /// Verify a shielded transaction.
 pub fn verify_shielded_tx(transaction: &Transaction) -> bool {
     tracing::info!("entered verify_shielded_tx()");
@@ -325,21 +353,11 @@ pub fn verify_shielded_tx(transaction: &Transaction) -> bool {
     } else {
         return false;
     };
-    let tx_data = transaction.deref();
-
-    // Partially deauthorize the transparent bundle
-    let unauth_tx_data = match partial_deauthorize(tx_data) {
-        Some(tx_data) => tx_data,
+    let sighash = match shielded_sighash(transaction) {
+        Some(sighash) => sighash,
         None => return false,
     };
 
-    let txid_parts = unauth_tx_data.digest(TxIdDigester);
-    // the commitment being signed is shared across all Sapling inputs; once
-    // V4 transactions are deprecated this should just be the txid, but
-    // for now we need to continue to compute it here.
-    let sighash =
-        signature_hash(&unauth_tx_data, &SignableInput::Shielded, &txid_parts);
-
     tracing::info!("sighash computed");
 
     let PVKs {
@@ -352,7 +370,7 @@ pub fn verify_shielded_tx(transaction: &Transaction) -> bool {
     let spends_valid = sapling_bundle
         .shielded_spends
         .iter()
-        .all(|spend| check_spend(spend, sighash.as_ref(), &mut ctx, spend_vk));
+        .all(|spend| check_spend(spend, &sighash, &mut ctx, spend_vk));
     let converts_valid = sapling_bundle
         .shielded_converts
         .iter()
@@ -377,7 +395,7 @@ pub fn verify_shielded_tx(transaction: &Transaction) -> bool {
 
     let result = ctx.final_check(
         assets_and_values,
-        sighash.as_ref(),
+        &sighash,
         sapling_bundle.authorization.binding_sig,
     );
     tracing::info!("final check result {result}");
@@ -396,8 +414,10 @@ pub fn get_params_dir() -> PathBuf {
 }
 
 /// Freeze a Builder into the format necessary for inclusion in a Tx. This is
-/// the format used by hardware wallets to validate a MASP Transaction.
-struct WalletMap;
+/// the format used by hardware wallets to validate a MASP Transaction. The
+/// spending keys are replaced by the given viewing key, if any, e.g. the one
+/// of the external signer whose proving key the spends were made with.
+struct WalletMap(Option<ExtendedFullViewingKey>);
 
 impl<P1>
     masp_primitives::transaction::components::sapling::builder::MapBuilder<
@@ -410,7 +430,7 @@ impl<P1>
     fn map_params(&self, _s: P1) {}
 
     fn map_key(&self, s: ExtendedSpendingKey) -> ExtendedFullViewingKey {
-        (&s).into()
+        self.0.unwrap_or_else(|| (&s).into())
     }
 }
 
@@ -431,6 +451,320 @@ impl<P1, R1, N1>
     fn map_notifier(&self, _s: N1) {}
 }
 
+/// The offset of the spend authorizing key in the encoding of an extended
+/// spending key, which is also the offset of the spend validating key in the
+/// encoding of an extended full viewing key
+const ASK_OFFSET: usize = 41;
+/// The offset of the proof authorizing key in the encoding of an extended
+/// spending key
+const NSK_OFFSET: usize = ASK_OFFSET + 32;
+/// The size of the encoding of extended spending and full viewing keys
+const EXTENDED_KEY_SIZE: usize = 169;
+
+/// The source of a shielded transfer whose spend authorizing key is held by
+/// an external signer, e.g. a hardware wallet. The spends only need the proof
+/// generation key of the signer to be proven, so the transfer can be built
+/// and proven without it and only its spend authorizations are delegated to
+/// it, see [`UnsignedShieldedTransfer`].
+#[derive(Clone, Debug)]
+pub struct ExternalSpendingKey {
+    /// The viewing key of the source
+    pub viewing_key: ExtendedFullViewingKey,
+    /// The proof generation key given by the external signer
+    pub proof_generation_key: ProofGenerationKey,
+}
+
+impl ExternalSpendingKey {
+    /// Pair the viewing key of a source with the proof generation key given
+    /// by its external signer. Fails if they don't belong to the same key.
+    pub fn new(
+        viewing_key: ExtendedFullViewingKey,
+        proof_generation_key: ProofGenerationKey,
+    ) -> Result<Self, Error> {
+        if proof_generation_key.to_viewing_key().ivk().0
+            != viewing_key.fvk.vk.ivk().0
+        {
+            return Err(Error::Other(
+                "The proof generation key doesn't belong to the viewing key"
+                    .to_string(),
+            ));
+        }
+        Ok(Self {
+            viewing_key,
+            proof_generation_key,
+        })
+    }
+
+    /// A spending key the builder can make the spends of the source with. It
+    /// has the proof authorizing key of the signer, so that the nullifiers of
+    /// the spends are those of the source, but a random spend authorizing key.
+    /// The spends must be proven with [`ExternalSpendProver`] and their
+    /// authorization signatures replaced by the ones of the signer.
+    fn proving_key(&self) -> ExtendedSpendingKey {
+        let mut bytes = [0; EXTENDED_KEY_SIZE];
+        self.viewing_key
+            .write(&mut bytes[..])
+            .expect("should be able to serialize an ExtendedFullViewingKey");
+        let placeholder_ask = masp_proofs::jubjub::Fr::random(OsRng);
+        bytes[ASK_OFFSET..NSK_OFFSET]
+            .copy_from_slice(&placeholder_ask.to_bytes());
+        bytes[NSK_OFFSET..NSK_OFFSET + 32]
+            .copy_from_slice(&self.proof_generation_key.nsk.to_bytes());
+        ExtendedSpendingKey::read(&mut &bytes[..])
+            .expect("should be able to deserialize an ExtendedSpendingKey")
+    }
+}
+
+/// A prover that proves the spends of a transfer with the proof generation
+/// key of an external signer, if any, instead of the one derived from the
+/// spending key given to the builder, and records the randomness of their
+/// spend authorizations for the signer.
+struct ExternalSpendProver<P> {
+    prover: P,
+    proof_generation_key: Option<ProofGenerationKey>,
+    alphas: std::sync::Mutex<Vec<masp_proofs::jubjub::Fr>>,
+}
+
+impl<P> ExternalSpendProver<P> {
+    fn new(
+        prover: P,
+        proof_generation_key: Option<ProofGenerationKey>,
+    ) -> Self {
+        Self {
+            prover,
+            proof_generation_key,
+            alphas: Default::default(),
+        }
+    }
+
+    /// The randomness of the authorizations of the spends proven so far, in
+    /// the order of their descriptions
+    fn into_alphas(self) -> Vec<masp_proofs::jubjub::Fr> {
+        self.alphas.into_inner().unwrap()
+    }
+}
+
+impl<P: TxProver> TxProver for ExternalSpendProver<P> {
+    type SaplingProvingContext = P::SaplingProvingContext;
+
+    fn new_sapling_proving_context(&self) -> Self::SaplingProvingContext {
+        self.prover.new_sapling_proving_context()
+    }
+
+    fn spend_proof(
+        &self,
+        ctx: &mut Self::SaplingProvingContext,
+        proof_generation_key: ProofGenerationKey,
+        diversifier: Diversifier,
+        rseed: Rseed,
+        ar: masp_proofs::jubjub::Fr,
+        asset_type: AssetType,
+        value: u64,
+        anchor: masp_proofs::bls12_381::Scalar,
+        merkle_path: MerklePath<Node>,
+    ) -> Result<
+        (
+            [u8; GROTH_PROOF_SIZE],
+            masp_proofs::jubjub::ExtendedPoint,
+            PublicKey,
+        ),
+        (),
+    > {
+        self.alphas.lock().unwrap().push(ar);
+        self.prover.spend_proof(
+            ctx,
+            self.proof_generation_key
+                .clone()
+                .unwrap_or(proof_generation_key),
+            diversifier,
+            rseed,
+            ar,
+            asset_type,
+            value,
+            anchor,
+            merkle_path,
+        )
+    }
+
+    fn output_proof(
+        &self,
+        ctx: &mut Self::SaplingProvingContext,
+        esk: masp_proofs::jubjub::Fr,
+        payment_address: masp_primitives::sapling::PaymentAddress,
+        rcm: masp_proofs::jubjub::Fr,
+        asset_type: AssetType,
+        value: u64,
+    ) -> ([u8; GROTH_PROOF_SIZE], masp_proofs::jubjub::ExtendedPoint) {
+        self.prover.output_proof(
+            ctx,
+            esk,
+            payment_address,
+            rcm,
+            asset_type,
+            value,
+        )
+    }
+
+    fn convert_proof(
+        &self,
+        ctx: &mut Self::SaplingProvingContext,
+        allowed_conversion: AllowedConversion,
+        value: u64,
+        anchor: masp_proofs::bls12_381::Scalar,
+        merkle_path: MerklePath<Node>,
+    ) -> Result<([u8; GROTH_PROOF_SIZE], masp_proofs::jubjub::ExtendedPoint), ()>
+    {
+        self.prover.convert_proof(
+            ctx,
+            allowed_conversion,
+            value,
+            anchor,
+            merkle_path,
+        )
+    }
+
+    fn binding_sig(
+        &self,
+        ctx: &mut Self::SaplingProvingContext,
+        assets_and_values: &I128Sum,
+        sighash: &[u8; 32],
+    ) -> Result<Signature, ()> {
+        self.prover.binding_sig(ctx, assets_and_values, sighash)
+    }
+}
+
+/// The request of an external signer to authorize a spend of a shielded
+/// transfer
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SpendAuthorizationRequest {
+    /// The randomness the spend authorizing key is randomized with
+    pub alpha: [u8; 32],
+    /// The sighash of the transaction, which the spend authorization signs
+    pub sighash: [u8; 32],
+}
+
+impl SpendAuthorizationRequest {
+    /// Authorize the spend with the given spending key, like the external
+    /// signer holding it does.
+    pub fn sign(&self, sk: &ExtendedSpendingKey) -> Result<Signature, Error> {
+        let alpha =
+            Option::from(masp_proofs::jubjub::Fr::from_bytes(&self.alpha))
+                .ok_or_else(|| {
+                    Error::Other(
+                        "Invalid spend authorization randomness".to_string(),
+                    )
+                })?;
+        let ask = PrivateKey(sk.expsk.ask);
+        let rk = PublicKey::from_private(&ask, SPENDING_KEY_GENERATOR)
+            .randomize(alpha, SPENDING_KEY_GENERATOR);
+        Ok(ask.randomize(alpha).sign(
+            &spend_auth_message(&rk, &self.sighash),
+            &mut OsRng,
+            SPENDING_KEY_GENERATOR,
+        ))
+    }
+}
+
+/// A shielded transfer whose proofs have been generated, but whose spends are
+/// yet to be authorized by the external signer of its source. It can be
+/// serialized to be carried to the signer and back.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct UnsignedShieldedTransfer {
+    /// The transfer, with invalid spend authorization signatures
+    pub transfer: ShieldedTransfer,
+    /// The requests of authorization of the spends, in the order of their
+    /// descriptions
+    pub spends: Vec<SpendAuthorizationRequest>,
+}
+
+impl UnsignedShieldedTransfer {
+    /// Make the requests of authorization of the spends of a transfer with
+    /// the randomness recorded by the [`ExternalSpendProver`] it was built
+    /// with
+    fn new(
+        transfer: ShieldedTransfer,
+        alphas: Vec<masp_proofs::jubjub::Fr>,
+    ) -> Result<Self, Error> {
+        let sighash = shielded_sighash(&transfer.masp_tx).ok_or_else(|| {
+            Error::Other("Unable to compute the shielded sighash".to_string())
+        })?;
+        let spends = alphas
+            .into_iter()
+            .map(|alpha| SpendAuthorizationRequest {
+                alpha: alpha.to_bytes(),
+                sighash,
+            })
+            .collect();
+        Ok(Self { transfer, spends })
+    }
+
+    /// Authorize the spends of the transfer with the signatures of the
+    /// external signer, in the order of the requests. Fails if a signature
+    /// doesn't authorize its spend.
+    pub fn authorize(
+        self,
+        signatures: Vec<Signature>,
+    ) -> Result<ShieldedTransfer, Error> {
+        let Self {
+            mut transfer,
+            spends,
+        } = self;
+        let tx_data = transfer.masp_tx.deref();
+        let mut bundle =
+            tx_data.sapling_bundle().cloned().ok_or_else(|| {
+                Error::Other("The transfer has no shielded spends".to_string())
+            })?;
+        if bundle.shielded_spends.len() != spends.len()
+            || signatures.len() != spends.len()
+        {
+            return Err(Error::Other(format!(
+                "Expected the signatures of {} spends, got {}",
+                spends.len(),
+                signatures.len()
+            )));
+        }
+        for (idx, ((spend, request), signature)) in bundle
+            .shielded_spends
+            .iter_mut()
+            .zip(&spends)
+            .zip(signatures)
+            .enumerate()
+        {
+            let rk = PublicKey(spend.rk.0);
+            if !rk.verify(
+                &spend_auth_message(&rk, &request.sighash),
+                &signature,
+                SPENDING_KEY_GENERATOR,
+            ) {
+                return Err(Error::Other(format!(
+                    "The signature of spend {idx} doesn't authorize it"
+                )));
+            }
+            spend.spend_auth_sig = signature;
+        }
+        transfer.masp_tx = TransactionData::from_parts(
+            tx_data.version(),
+            tx_data.consensus_branch_id(),
+            tx_data.lock_time(),
+            tx_data.expiry_height(),
+            tx_data.transparent_bundle().cloned(),
+            Some(bundle),
+        )
+        .freeze()
+        .map_err(|err| Error::Other(err.to_string()))?;
+        Ok(transfer)
+    }
+}
+
+/// The message the authorization signature of a spend signs: its randomized
+/// spend validating key followed by the sighash of the transaction
+fn spend_auth_message(rk: &PublicKey, sighash: &[u8; 32]) -> [u8; 64] {
+    let mut message = [0; 64];
+    message[..32].copy_from_slice(&rk.0.to_bytes());
+    message[32..].copy_from_slice(sighash);
+    message
+}
+
 /// Abstracts platform specific details away from the logic of shielded pool
 /// operations.
 #[cfg_attr(feature = "async-send", async_trait::async_trait)]
@@ -442,7 +776,7 @@ pub trait ShieldedUtils:
     fn local_tx_prover(&self) -> LocalTxProver;
 
     /// Load up the currently saved ShieldedContext
-    async fn load<U: ShieldedUtils + MaybeSend>(
+    async fn load<U: ShieldedUtils + MaybeSend + MaybeSync>(
         &self,
         ctx: &mut ShieldedContext<U>,
     ) -> std::io::Result<()>;
@@ -500,6 +834,107 @@ pub struct MaspChange {
     pub change: token::Change,
 }
 
+/// The shielded balance of a token, with the rewards accrued by it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShieldedAssetBalance {
+    /// The balance of the token, without the accrued rewards
+    pub balance: token::DenominatedAmount,
+    /// The rewards accrued by the balance of the token, by the token in which
+    /// they are paid
+    pub rewards: BTreeMap<Address, token::DenominatedAmount>,
+}
+
+/// A shielded transaction fetched from a node
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub enum FetchedShieldedTx {
+    /// A full MASP transaction with the storage keys changed by it
+    Full(BTreeSet<namada_core::types::storage::Key>, Transaction),
+    /// A MASP transaction from a compact block
+    Compact(CompactTx),
+}
+
+/// A transaction that was applied to a shielded context
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ScannedTx {
+    /// The position of the transaction on chain
+    pub indexed_tx: IndexedTx,
+    /// The epoch of the transaction
+    pub epoch: Epoch,
+    /// The scanned transaction
+    pub tx: FetchedShieldedTx,
+    /// The native token used to scan the transaction
+    pub native_token: Address,
+}
+
+/// Records the transactions scanned into a shielded context since it was
+/// loaded, so that saving it only needs to persist the difference.
+#[derive(Clone, Debug)]
+pub struct ShieldedJournal {
+    /// The last indexed transaction of the context when it was loaded
+    pub base: Option<IndexedTx>,
+    /// The transactions scanned since the context was loaded, in order
+    pub scanned: Vec<ScannedTx>,
+    /// Whether the context has to be saved in full, e.g. because it wasn't
+    /// loaded from a consistent save
+    pub needs_checkpoint: bool,
+}
+
+/// Contexts that weren't loaded have to be saved in full
+impl Default for ShieldedJournal {
+    fn default() -> Self {
+        Self {
+            base: None,
+            scanned: Vec::new(),
+            needs_checkpoint: true,
+        }
+    }
+}
+
+impl ShieldedJournal {
+    /// Start a new journal on top of a loaded context whose last indexed
+    /// transaction is the given one
+    pub fn new(base: Option<IndexedTx>) -> Self {
+        Self {
+            base,
+            scanned: Vec::new(),
+            needs_checkpoint: false,
+        }
+    }
+
+    /// Get the scanned transactions that come after the given one, if the
+    /// journal connects to it. Returns `None` if the state of the context at
+    /// the given transaction isn't known to have been derived from the same
+    /// data as the journal.
+    pub fn scanned_after(
+        &self,
+        last_indexed: Option<IndexedTx>,
+    ) -> Option<&[ScannedTx]> {
+        if self.needs_checkpoint {
+            return None;
+        }
+        if self.base == last_indexed {
+            return Some(&self.scanned);
+        }
+        let last_indexed = last_indexed?;
+        self.scanned
+            .iter()
+            .position(|scanned| scanned.indexed_tx == last_indexed)
+            .map(|pos| &self.scanned[pos + 1..])
+    }
+}
+
+/// The state of the scan of a shielded context before the first transaction of
+/// a range of block heights. The notes of new keys can be searched from a
+/// checkpoint without scanning the transactions before it.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ScanCheckpoint {
+    /// The last transaction scanned before the checkpoint
+    pub last_indexed: Option<IndexedTx>,
+    /// The commitment tree produced by scanning all transactions up to
+    /// `last_indexed`
+    pub tree: CommitmentTree<Node>,
+}
+
 /// a masp amount
 pub type MaspAmount = ValueSum<(Option<Epoch>, Address), token::Change>;
 
@@ -508,6 +943,128 @@ fn cloned_pair<T: Clone, U: Clone>((a, b): (&T, &U)) -> (T, U) {
     (a.clone(), b.clone())
 }
 
+/// The hash committing to the given address in the transparent bundles of
+/// MASP transactions
+fn transparent_address_commit(address: &Address) -> [u8; 20] {
+    ripemd::Ripemd160::digest(sha2::Sha256::digest(&address.serialize_to_vec()))
+        .into()
+}
+
+/// Make the memo of a shielded output from the given bytes, of at most
+/// [`MAX_SHIELDED_MEMO_LEN`] bytes. The memo is encrypted to the diversified
+/// key of the receiving payment address, along with the note.
+pub fn shielded_memo(memo: &[u8]) -> Result<MemoBytes, Error> {
+    MemoBytes::from_bytes(memo).map_err(|_| {
+        Error::Other(format!(
+            "The shielded memo is {} bytes long, but it can't be longer than \
+             {MAX_SHIELDED_MEMO_LEN} bytes",
+            memo.len()
+        ))
+    })
+}
+
+/// Get the bytes of a decrypted memo, without its padding. Returns `None`
+/// for empty memos.
+pub fn shielded_memo_bytes(memo: &MemoBytes) -> Option<&[u8]> {
+    if *memo == MemoBytes::empty() {
+        return None;
+    }
+    let bytes = memo.as_slice();
+    let len = bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |i| i + 1);
+    Some(&bytes[..len])
+}
+
+/// A note decrypted from a shielded output, with the payment address that it
+/// was sent to and its memo
+pub type DecryptedNote =
+    (Note, masp_primitives::sapling::PaymentAddress, MemoBytes);
+
+/// The notes decrypted from each shielded output of a transaction, by the
+/// viewing keys that decrypted them
+pub type TrialDecryptions = Vec<HashMap<ViewingKey, DecryptedNote>>;
+
+/// Try to decrypt each shielded output of the given transaction with each of
+/// the given viewing keys. The outputs are decrypted in parallel if the
+/// `multicore` feature is enabled.
+pub fn trial_decrypt(
+    fetched: &FetchedShieldedTx,
+    vks: &[ViewingKey],
+) -> Result<TrialDecryptions, Error> {
+    let decrypt_with_keys =
+        |decrypt: &dyn Fn(&ViewingKey) -> Option<DecryptedNote>| {
+            vks.iter()
+                .filter_map(|vk| decrypt(vk).map(|decrypted| (*vk, decrypted)))
+                .collect()
+        };
+    match fetched {
+        FetchedShieldedTx::Full(_, transaction) => {
+            let outputs = transaction
+                .sapling_bundle()
+                .map_or(&[][..], |x| &x.shielded_outputs[..]);
+            Ok(maybe_par_map(outputs, |so| {
+                decrypt_with_keys(&|vk| {
+                    try_sapling_note_decryption::<_, OutputDescription<<<Authorized as Authorization>::SaplingAuth as masp_primitives::transaction::components::sapling::Authorization>::Proof>>(
+                        &NETWORK,
+                        1.into(),
+                        &PreparedIncomingViewingKey::new(&vk.ivk()),
+                        so,
+                    )
+                })
+            }))
+        }
+        FetchedShieldedTx::Compact(compact_tx) => {
+            maybe_par_map(&compact_tx.outputs, |output| {
+                let desc = output.to_description().ok_or_else(|| {
+                    Error::Other("found malformed compact output".to_string())
+                })?;
+                Ok(decrypt_with_keys(&|vk| {
+                    try_sapling_compact_note_decryption(
+                        &NETWORK,
+                        1.into(),
+                        &PreparedIncomingViewingKey::new(&vk.ivk()),
+                        &desc,
+                    )
+                    .map(|(note, pa)| (note, pa, MemoBytes::empty()))
+                }))
+            })
+            .into_iter()
+            .collect()
+        }
+    }
+}
+
+/// Try to decrypt each shielded output of each of the given transactions with
+/// each of the given viewing keys, like [`trial_decrypt`]. The transactions
+/// are decrypted in parallel if the `multicore` feature is enabled.
+pub fn trial_decrypt_txs(
+    txs: &[&FetchedShieldedTx],
+    vks: &[ViewingKey],
+) -> Vec<Result<TrialDecryptions, Error>> {
+    maybe_par_map(txs, |fetched| trial_decrypt(fetched, vks))
+}
+
+/// Map the given items in parallel if the `multicore` feature is enabled,
+/// otherwise in order
+fn maybe_par_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    #[cfg(feature = "multicore")]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "multicore"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
 /// Represents the amount used of different conversions
 pub type Conversions =
     BTreeMap<AssetType, (AllowedConversion, MerklePath<Node>, i128)>;
@@ -537,6 +1094,9 @@ pub struct ShieldedContext<U: ShieldedUtils> {
     pub note_map: HashMap<usize, Note>,
     /// Maps note positions to their corresponding memos
     pub memo_map: HashMap<usize, MemoBytes>,
+    /// Maps transactions to the positions of the notes they sent to our
+    /// viewing keys
+    pub tx_note_map: BTreeMap<IndexedTx, BTreeSet<usize>>,
     /// Maps note positions to the diversifier of their payment address
     pub div_map: HashMap<usize, Diversifier>,
     /// Maps note positions to their witness (used to make merkle paths)
@@ -550,6 +1110,13 @@ pub struct ShieldedContext<U: ShieldedUtils> {
     pub asset_types: HashMap<AssetType, AssetData>,
     /// Maps note positions to their corresponding viewing keys
     pub vk_map: HashMap<usize, ViewingKey>,
+    /// Maps the first heights of the ranges of
+    /// [`SCAN_CHECKPOINT_INTERVAL`] heights to the scan checkpoints before
+    /// them
+    pub scan_checkpoints: BTreeMap<BlockHeight, ScanCheckpoint>,
+    /// The changes made to this context since it was loaded
+    #[borsh(skip)]
+    pub journal: ShieldedJournal,
 }
 
 /// Default implementation to ease construction of TxContexts. Derive cannot be
@@ -564,12 +1131,15 @@ impl<U: ShieldedUtils + Default> Default for ShieldedContext<U> {
             nf_map: HashMap::default(),
             note_map: HashMap::default(),
             memo_map: HashMap::default(),
+            tx_note_map: BTreeMap::default(),
             div_map: HashMap::default(),
             witness_map: HashMap::default(),
             spents: HashSet::default(),
             delta_map: BTreeMap::default(),
             asset_types: HashMap::default(),
             vk_map: HashMap::default(),
+            scan_checkpoints: BTreeMap::default(),
+            journal: ShieldedJournal::default(),
         }
     }
 }
@@ -602,6 +1172,13 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         self.spents.extend(new_ctx.spents);
         self.asset_types.extend(new_ctx.asset_types);
         self.vk_map.extend(new_ctx.vk_map);
+        self.scan_checkpoints.extend(new_ctx.scan_checkpoints);
+        for (indexed_tx, notes) in new_ctx.tx_note_map {
+            self.tx_note_map
+                .entry(indexed_tx)
+                .or_default()
+                .extend(notes);
+        }
         // The deltas are the exception because different keys can reveal
         // different parts of the same transaction. Hence each delta needs to be
         // merged separately.
@@ -622,6 +1199,50 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         client: &C,
         sks: &[ExtendedSpendingKey],
         fvks: &[ViewingKey],
+    ) -> Result<(), Error> {
+        self.fetch_with(client, sks, fvks, false, None).await
+    }
+
+    /// Fetch the current state of the multi-asset shielded pool into a
+    /// ShieldedContext from the compact blocks served by the node. This
+    /// downloads much less data than [`Self::fetch`], but the notes
+    /// discovered from compact transactions have no memo.
+    pub async fn fetch_compact<C: Client + Sync>(
+        &mut self,
+        client: &C,
+        sks: &[ExtendedSpendingKey],
+        fvks: &[ViewingKey],
+    ) -> Result<(), Error> {
+        self.fetch_with(client, sks, fvks, true, None).await
+    }
+
+    /// Fetch the current state of the multi-asset shielded pool into a
+    /// ShieldedContext like [`Self::fetch`], but only search the notes of the
+    /// keys that are new to the context from the given height. Their scan
+    /// starts from the last scan checkpoint before the height, instead of the
+    /// first block.
+    pub async fn fetch_from_height<C: Client + Sync>(
+        &mut self,
+        client: &C,
+        sks: &[ExtendedSpendingKey],
+        fvks: &[ViewingKey],
+        from_height: BlockHeight,
+    ) -> Result<(), Error> {
+        self.fetch_with(client, sks, fvks, false, Some(from_height))
+            .await
+    }
+
+    /// Fetch the state of the shielded pool either from full transactions or
+    /// from compact blocks. The context is saved after each range of
+    /// [`SCAN_CHECKPOINT_INTERVAL`] heights, so that an interrupted fetch
+    /// resumes from the last saved range.
+    async fn fetch_with<C: Client + Sync>(
+        &mut self,
+        client: &C,
+        sks: &[ExtendedSpendingKey],
+        fvks: &[ViewingKey],
+        compact: bool,
+        from_height: Option<BlockHeight>,
     ) -> Result<(), Error> {
         // First determine which of the keys requested to be fetched are new.
         // Necessary because old transactions will need to be scanned for new
@@ -642,63 +1263,247 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         // If unknown keys are being used, we need to scan older transactions
         // for any unspent notes
         let native_token = query_native_token(client).await?;
-        let (txs, mut tx_iter);
-        if !unknown_keys.is_empty() {
-            // Load all transactions accepted until this point
-            txs = Self::fetch_shielded_transfers(client, None).await?;
-            tx_iter = txs.iter();
-            // Do this by constructing a shielding context only for unknown keys
+        if let (false, Some(last_indexed)) =
+            (unknown_keys.is_empty(), self.last_indexed)
+        {
+            // Do this by constructing a shielding context only for unknown
+            // keys, starting from the last checkpoint before the requested
+            // height, if any
+            let checkpoint = from_height
+                .and_then(|height| {
+                    self.scan_checkpoints.range(..=height).next_back()
+                })
+                .map(|(_, checkpoint)| checkpoint.clone());
             let mut tx_ctx = Self {
                 utils: self.utils.clone(),
                 ..Default::default()
             };
+            if let Some(checkpoint) = checkpoint {
+                tx_ctx.last_indexed = checkpoint.last_indexed;
+                tx_ctx.tree = checkpoint.tree;
+            }
             for vk in unknown_keys {
                 tx_ctx.pos_map.entry(vk).or_insert_with(BTreeSet::new);
             }
+            // Load the transactions accepted until the last one of self
+            let txs = Self::fetch_txs(
+                client,
+                tx_ctx.last_indexed,
+                BlockHeight::first(),
+                last_indexed.height,
+                compact,
+            )
+            .await?;
             // Update this unknown shielded context until it is level with self
-            while tx_ctx.last_indexed != self.last_indexed {
-                if let Some((indexed_tx, (epoch, changed_keys, stx))) =
-                    tx_iter.next()
-                {
-                    tx_ctx.scan_tx(
-                        *indexed_tx,
-                        *epoch,
-                        changed_keys,
-                        stx,
-                        native_token.clone(),
-                    )?;
-                } else {
-                    break;
-                }
+            if tx_ctx.last_indexed != self.last_indexed {
+                tx_ctx.scan_fetched_txs(
+                    &txs,
+                    &native_token,
+                    self.last_indexed,
+                )?;
             }
             // Merge the context data originating from the unknown keys into the
             // current context
             self.merge(tx_ctx);
         } else {
-            // Load only transactions accepted from last_txid until this point
-            txs = Self::fetch_shielded_transfers(client, self.last_indexed)
-                .await?;
-            tx_iter = txs.iter();
+            for vk in unknown_keys {
+                self.pos_map.entry(vk).or_insert_with(BTreeSet::new);
+            }
         }
         // Now that we possess the unspent notes corresponding to both old and
-        // new keys up until tx_pos, proceed to scan the new transactions.
-        for (indexed_tx, (epoch, changed_keys, stx)) in &mut tx_iter {
-            self.scan_tx(
-                *indexed_tx,
-                *epoch,
-                changed_keys,
-                stx,
-                native_token.clone(),
-            )?;
+        // new keys up until tx_pos, proceed to scan the new transactions,
+        // one range of heights at a time
+        let last_block_height = query_block(client)
+            .await?
+            .map_or_else(BlockHeight::first, |block| block.height);
+        let mut from = self
+            .last_indexed
+            .map_or_else(BlockHeight::first, |last| last.height);
+        loop {
+            let to = BlockHeight(std::cmp::min(
+                from.0 - from.0 % SCAN_CHECKPOINT_INTERVAL
+                    + SCAN_CHECKPOINT_INTERVAL
+                    - 1,
+                last_block_height.0,
+            ));
+            let txs =
+                Self::fetch_txs(client, self.last_indexed, from, to, compact)
+                    .await?;
+            self.scan_fetched_txs(&txs, &native_token, None)?;
+            if to >= last_block_height {
+                return Ok(());
+            }
+            // Persist the progress before fetching the next range
+            let _ = self.save().await;
+            from = to.next_height();
         }
-        Ok(())
     }
 
-    /// Obtain a chronologically-ordered list of all accepted shielded
-    /// transactions from a node.
+    /// Obtain a chronologically-ordered list of the shielded transactions
+    /// accepted after the given one, between the given heights, from a node,
+    /// either in full or compacted
+    async fn fetch_txs<C: Client + Sync>(
+        client: &C,
+        last_indexed_tx: Option<IndexedTx>,
+        from: BlockHeight,
+        to: BlockHeight,
+        compact: bool,
+    ) -> Result<BTreeMap<IndexedTx, (Epoch, FetchedShieldedTx)>, Error> {
+        let capabilities = rpc::query_capabilities(client).await?;
+        let compact = compact && {
+            let supported =
+                capabilities.supports(Capability::CompactShieldedSync);
+            if !supported {
+                tracing::warn!(
+                    "The node doesn't serve compact blocks, fetching the \
+                     shielded transactions in full"
+                );
+            }
+            supported
+        };
+        if compact {
+            Self::fetch_compact_shielded_transfers(
+                client,
+                last_indexed_tx,
+                from,
+                to,
+            )
+            .await
+        } else {
+            Ok(Self::fetch_shielded_transfers(
+                client,
+                last_indexed_tx,
+                from,
+                to,
+            )
+            .await?
+            .into_iter()
+            .map(|(indexed_tx, (epoch, changed_keys, transaction))| {
+                (
+                    indexed_tx,
+                    (epoch, FetchedShieldedTx::Full(changed_keys, transaction)),
+                )
+            })
+            .collect())
+        }
+    }
+
+    /// Obtain a chronologically-ordered list of the shielded transactions
+    /// accepted after the given one, between the given heights, from the
+    /// compact blocks served by a node. The transactions that the node could
+    /// not compact are fetched in full.
+    pub async fn fetch_compact_shielded_transfers<C: Client + Sync>(
+        client: &C,
+        last_indexed_tx: Option<IndexedTx>,
+        from: BlockHeight,
+        to: BlockHeight,
+    ) -> Result<BTreeMap<IndexedTx, (Epoch, FetchedShieldedTx)>, Error> {
+        let last_height = to;
+        let mut shielded_txs = BTreeMap::new();
+        // Fetch all the transactions we do not have yet
+        let is_new = |indexed_tx: &IndexedTx| {
+            last_indexed_tx.map_or(true, |last| *indexed_tx > last)
+        };
+        let mut from = std::cmp::max(
+            from,
+            last_indexed_tx.map_or_else(BlockHeight::first, |last| last.height),
+        );
+        while from <= last_height {
+            let to = BlockHeight(std::cmp::min(
+                from.0 + MAX_COMPACT_BLOCKS_PER_QUERY - 1,
+                last_height.0,
+            ));
+            let blocks = rpc::query_compact_blocks(client, from, to).await?;
+            for (height, block) in blocks {
+                let epoch = query_epoch_at_height(client, height)
+                    .await?
+                    .ok_or_else(|| {
+                        Error::from(QueryError::General(
+                            "Queried height is greater than the last \
+                             committed block height"
+                                .to_string(),
+                        ))
+                    })?;
+                for tx in block.txs {
+                    let indexed_tx = IndexedTx {
+                        height,
+                        index: tx.index,
+                    };
+                    if is_new(&indexed_tx) {
+                        shielded_txs.insert(
+                            indexed_tx,
+                            (epoch, FetchedShieldedTx::Compact(tx)),
+                        );
+                    }
+                }
+
+                // Fetch in full the transactions that were not compacted
+                let full_txs: BTreeSet<_> = block
+                    .full_txs
+                    .into_iter()
+                    .filter(|index| {
+                        is_new(&IndexedTx {
+                            height,
+                            index: *index,
+                        })
+                    })
+                    .collect();
+                if full_txs.is_empty() {
+                    continue;
+                }
+                let txs_results = get_indexed_masp_events_at_height(
+                    client,
+                    height,
+                    full_txs.first().copied(),
+                )
+                .await?
+                .unwrap_or_default();
+                let block = client
+                    .block(height.0 as u32)
+                    .await
+                    .map_err(|e| {
+                        Error::from(QueryError::General(e.to_string()))
+                    })?
+                    .block
+                    .data;
+                for (idx, tx_event) in txs_results {
+                    if !full_txs.contains(&idx) {
+                        continue;
+                    }
+                    let tx = Tx::try_from(block[idx.0 as usize].as_ref())
+                        .map_err(|e| Error::Other(e.to_string()))?;
+                    let (changed_keys, masp_transaction) =
+                        Self::extract_masp_tx(
+                            &tx,
+                            ExtractShieldedActionArg::Event::<C>(&tx_event),
+                            true,
+                        )
+                        .await?;
+                    shielded_txs.insert(
+                        IndexedTx { height, index: idx },
+                        (
+                            epoch,
+                            FetchedShieldedTx::Full(
+                                changed_keys,
+                                masp_transaction,
+                            ),
+                        ),
+                    );
+                }
+            }
+            from = to.next_height();
+        }
+
+        Ok(shielded_txs)
+    }
+
+    /// Obtain a chronologically-ordered list of the shielded transactions
+    /// accepted after the given one, between the given heights, from a node.
     pub async fn fetch_shielded_transfers<C: Client + Sync>(
         client: &C,
         last_indexed_tx: Option<IndexedTx>,
+        from: BlockHeight,
+        to: BlockHeight,
     ) -> Result<
         BTreeMap<
             IndexedTx,
@@ -710,18 +1515,13 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         >,
         Error,
     > {
-        // Query for the last produced block height
-        let last_block_height = query_block(client)
-            .await?
-            .map_or_else(BlockHeight::first, |block| block.height);
-
         let mut shielded_txs = BTreeMap::new();
         // Fetch all the transactions we do not have yet
-        let first_height_to_query =
-            last_indexed_tx.map_or_else(|| 1, |last| last.height.0);
-        let first_idx_to_query =
-            last_indexed_tx.map_or_else(|| 0, |last| last.index.0 + 1);
-        for height in first_height_to_query..=last_block_height.0 {
+        let first_height_to_query = std::cmp::max(
+            from,
+            last_indexed_tx.map_or_else(BlockHeight::first, |last| last.height),
+        );
+        for height in first_height_to_query.0..=to.0 {
             // Get the valid masp transactions at the specified height
             let epoch = query_epoch_at_height(client, height.into())
                 .await?
@@ -733,10 +1533,11 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                     ))
                 })?;
 
-            let first_index_to_query = if height == first_height_to_query {
-                Some(TxIndex(first_idx_to_query))
-            } else {
-                None
+            let first_index_to_query = match last_indexed_tx {
+                Some(last) if last.height.0 == height => {
+                    Some(TxIndex(last.index.0 + 1))
+                }
+                _ => None,
             };
 
             let txs_results = match get_indexed_masp_events_at_height(
@@ -947,95 +1748,248 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         shielded: &Transaction,
         native_token: Address,
     ) -> Result<(), Error> {
+        self.scan_fetched_tx(
+            indexed_tx,
+            epoch,
+            &FetchedShieldedTx::Full(tx_changed_keys.clone(), shielded.clone()),
+            native_token,
+        )
+    }
+
+    /// Applies the given fetched transaction to the supplied context
+    pub fn scan_fetched_tx(
+        &mut self,
+        indexed_tx: IndexedTx,
+        epoch: Epoch,
+        fetched: &FetchedShieldedTx,
+        native_token: Address,
+    ) -> Result<(), Error> {
+        let vks: Vec<_> = self.pos_map.keys().copied().collect();
+        let decryptions = trial_decrypt(fetched, &vks)?;
+        self.scan_decrypted_tx(
+            indexed_tx,
+            epoch,
+            fetched,
+            &decryptions,
+            native_token,
+        )
+    }
+
+    /// Applies the given compact transaction to the supplied context, like
+    /// [`Self::scan_tx`] does for full transactions. The compact transaction
+    /// doesn't carry the memos of its notes, so the notes discovered here are
+    /// associated to empty memos.
+    pub fn scan_compact_tx(
+        &mut self,
+        indexed_tx: IndexedTx,
+        epoch: Epoch,
+        compact_tx: &CompactTx,
+        native_token: Address,
+    ) -> Result<(), Error> {
+        self.scan_fetched_tx(
+            indexed_tx,
+            epoch,
+            &FetchedShieldedTx::Compact(compact_tx.clone()),
+            native_token,
+        )
+    }
+
+    /// Applies the given fetched transaction to the supplied context, given
+    /// the trial decryptions of its outputs with the viewing keys of the
+    /// context
+    fn scan_decrypted_tx(
+        &mut self,
+        indexed_tx: IndexedTx,
+        epoch: Epoch,
+        fetched: &FetchedShieldedTx,
+        decryptions: &TrialDecryptions,
+        native_token: Address,
+    ) -> Result<(), Error> {
+        let (changed_keys, nodes, nullifiers, transparent) = match fetched {
+            FetchedShieldedTx::Full(changed_keys, shielded) => {
+                let bundle = shielded.sapling_bundle();
+                let nodes: Vec<_> = bundle
+                    .map_or(&vec![], |x| &x.shielded_outputs)
+                    .iter()
+                    .map(|so| Node::new(so.cmu.to_repr()))
+                    .collect();
+                let nullifiers: Vec<_> = bundle
+                    .map_or(&vec![], |x| &x.shielded_spends)
+                    .iter()
+                    .map(|ss| ss.nullifier)
+                    .collect();
+                let transparent =
+                    shielded.transparent_bundle().map(|transp_bundle| {
+                        let vin = transp_bundle
+                            .vin
+                            .iter()
+                            .map(|vin| CompactTransparent {
+                                address: vin.address.0,
+                                value: vin.value,
+                            })
+                            .collect();
+                        let vout = transp_bundle
+                            .vout
+                            .iter()
+                            .map(|vout| CompactTransparent {
+                                address: vout.address.0,
+                                value: vout.value,
+                            })
+                            .collect();
+                        (vin, vout)
+                    });
+                (changed_keys, nodes, nullifiers, transparent)
+            }
+            FetchedShieldedTx::Compact(compact_tx) => {
+                let nodes: Vec<_> = compact_tx
+                    .outputs
+                    .iter()
+                    .map(|output| Node::new(output.cmu))
+                    .collect();
+                let transparent = if compact_tx.vin.is_empty()
+                    && compact_tx.vout.is_empty()
+                {
+                    None
+                } else {
+                    Some((compact_tx.vin.clone(), compact_tx.vout.clone()))
+                };
+                (
+                    &compact_tx.changed_balance_keys,
+                    nodes,
+                    compact_tx.nullifiers().collect(),
+                    transparent,
+                )
+            }
+        };
+        if nodes.len() != decryptions.len() {
+            return Err(Error::Other(
+                "the trial decryptions don't match the outputs of the \
+                 transaction"
+                    .to_string(),
+            ));
+        }
+
+        self.record_scan_checkpoint(indexed_tx.height);
         // For tracking the account changes caused by this Transaction
         let mut transaction_delta = TransactionDelta::new();
         // Listen for notes sent to our viewing keys
-        for so in shielded
-            .sapling_bundle()
-            .map_or(&vec![], |x| &x.shielded_outputs)
+        for (node, decrypted) in nodes.into_iter().zip(decryptions) {
+            self.scan_output(
+                indexed_tx,
+                node,
+                &mut transaction_delta,
+                decrypted,
+            )?;
+        }
+        // Cancel out those of our notes that have been spent
+        for nf in &nullifiers {
+            self.scan_spend(nf, &mut transaction_delta)?;
+        }
+        // Record the changes to the transparent accounts
+        self.record_scanned_tx(
+            indexed_tx,
+            epoch,
+            changed_keys,
+            transparent,
+            native_token.clone(),
+            transaction_delta,
+        )?;
+        self.journal.scanned.push(ScannedTx {
+            indexed_tx,
+            epoch,
+            tx: fetched.clone(),
+            native_token,
+        });
+        Ok(())
+    }
+
+    /// Applies the given fetched transactions to the supplied context, in
+    /// order. The outputs of all the transactions are trial-decrypted
+    /// beforehand, in parallel if the `multicore` feature is enabled.
+    /// Transactions are applied until the context reaches the given last
+    /// transaction, if any.
+    fn scan_fetched_txs(
+        &mut self,
+        txs: &BTreeMap<IndexedTx, (Epoch, FetchedShieldedTx)>,
+        native_token: &Address,
+        until: Option<IndexedTx>,
+    ) -> Result<(), Error> {
+        let vks: Vec<_> = self.pos_map.keys().copied().collect();
+        let fetched: Vec<_> =
+            txs.values().map(|(_, fetched)| fetched).collect();
+        let decryptions = trial_decrypt_txs(&fetched, &vks);
+        for ((indexed_tx, (epoch, fetched)), decryptions) in
+            txs.iter().zip(decryptions)
         {
-            // Create merkle tree leaf node from note commitment
-            let node = Node::new(so.cmu.to_repr());
-            // Update each merkle tree in the witness map with the latest
-            // addition
-            for (_, witness) in self.witness_map.iter_mut() {
-                witness.append(node).map_err(|()| {
-                    Error::Other("note commitment tree is full".to_string())
-                })?;
+            if until.is_some() && self.last_indexed == until {
+                break;
             }
-            let note_pos = self.tree.size();
-            self.tree.append(node).map_err(|()| {
+            self.scan_decrypted_tx(
+                *indexed_tx,
+                *epoch,
+                fetched,
+                &decryptions?,
+                native_token.clone(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Append the note commitment of a shielded output to the commitment tree
+    /// and record the note if one of our viewing keys decrypted it
+    fn scan_output(
+        &mut self,
+        indexed_tx: IndexedTx,
+        node: Node,
+        transaction_delta: &mut TransactionDelta,
+        decrypted: &HashMap<ViewingKey, DecryptedNote>,
+    ) -> Result<(), Error> {
+        // Update each merkle tree in the witness map with the latest
+        // addition
+        for (_, witness) in self.witness_map.iter_mut() {
+            witness.append(node).map_err(|()| {
                 Error::Other("note commitment tree is full".to_string())
             })?;
-            // Finally, make it easier to construct merkle paths to this new
-            // note
-            let witness = IncrementalWitness::<Node>::from_tree(&self.tree);
-            self.witness_map.insert(note_pos, witness);
-            // Let's try to see if any of our viewing keys can decrypt latest
-            // note
-            let mut pos_map = HashMap::new();
-            std::mem::swap(&mut pos_map, &mut self.pos_map);
-            for (vk, notes) in pos_map.iter_mut() {
-                let decres = try_sapling_note_decryption::<_, OutputDescription<<<Authorized as Authorization>::SaplingAuth as masp_primitives::transaction::components::sapling::Authorization>::Proof>>(
-                    &NETWORK,
-                    1.into(),
-                    &PreparedIncomingViewingKey::new(&vk.ivk()),
-                    so,
-                );
-                // So this current viewing key does decrypt this current note...
-                if let Some((note, pa, memo)) = decres {
-                    // Add this note to list of notes decrypted by this viewing
-                    // key
-                    notes.insert(note_pos);
-                    // Compute the nullifier now to quickly recognize when spent
-                    let nf = note.nf(
-                        &vk.nk,
-                        note_pos.try_into().map_err(|_| {
-                            Error::Other("Can not get nullifier".to_string())
-                        })?,
-                    );
-                    self.note_map.insert(note_pos, note);
-                    self.memo_map.insert(note_pos, memo);
-                    // The payment address' diversifier is required to spend
-                    // note
-                    self.div_map.insert(note_pos, *pa.diversifier());
-                    self.nf_map.insert(nf, note_pos);
-                    // Note the account changes
-                    let balance = transaction_delta
-                        .entry(*vk)
-                        .or_insert_with(I128Sum::zero);
-                    *balance += I128Sum::from_nonnegative(
-                        note.asset_type,
-                        note.value as i128,
-                    )
-                    .map_err(|()| {
-                        Error::Other(
-                            "found note with invalid value or asset type"
-                                .to_string(),
-                        )
-                    })?;
-
-                    self.vk_map.insert(note_pos, *vk);
-                    break;
-                }
-            }
-            std::mem::swap(&mut pos_map, &mut self.pos_map);
         }
-        // Cancel out those of our notes that have been spent
-        for ss in shielded
-            .sapling_bundle()
-            .map_or(&vec![], |x| &x.shielded_spends)
-        {
-            // If the shielded spend's nullifier is in our map, then target note
-            // is rendered unusable
-            if let Some(note_pos) = self.nf_map.get(&ss.nullifier) {
-                self.spents.insert(*note_pos);
+        let note_pos = self.tree.size();
+        self.tree.append(node).map_err(|()| {
+            Error::Other("note commitment tree is full".to_string())
+        })?;
+        // Finally, make it easier to construct merkle paths to this new
+        // note
+        let witness = IncrementalWitness::<Node>::from_tree(&self.tree);
+        self.witness_map.insert(note_pos, witness);
+        // Let's try to see if any of our viewing keys can decrypt latest
+        // note
+        let mut pos_map = HashMap::new();
+        std::mem::swap(&mut pos_map, &mut self.pos_map);
+        for (vk, notes) in pos_map.iter_mut() {
+            // So this current viewing key does decrypt this current note...
+            if let Some((note, pa, memo)) = decrypted.get(vk) {
+                // Add this note to list of notes decrypted by this viewing
+                // key
+                notes.insert(note_pos);
+                // Compute the nullifier now to quickly recognize when spent
+                let nf = note.nf(
+                    &vk.nk,
+                    note_pos.try_into().map_err(|_| {
+                        Error::Other("Can not get nullifier".to_string())
+                    })?,
+                );
+                self.note_map.insert(note_pos, *note);
+                self.memo_map.insert(note_pos, memo.clone());
+                self.tx_note_map
+                    .entry(indexed_tx)
+                    .or_default()
+                    .insert(note_pos);
+                // The payment address' diversifier is required to spend
+                // note
+                self.div_map.insert(note_pos, *pa.diversifier());
+                self.nf_map.insert(nf, note_pos);
                 // Note the account changes
-                let balance = transaction_delta
-                    .entry(self.vk_map[note_pos])
-                    .or_insert_with(I128Sum::zero);
-                let note = self.note_map[note_pos];
-                *balance -= I128Sum::from_nonnegative(
+                let balance =
+                    transaction_delta.entry(*vk).or_insert_with(I128Sum::zero);
+                *balance += I128Sum::from_nonnegative(
                     note.asset_type,
                     note.value as i128,
                 )
@@ -1045,19 +1999,64 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                             .to_string(),
                     )
                 })?;
+
+                self.vk_map.insert(note_pos, *vk);
+                break;
             }
         }
-        // Record the changes to the transparent accounts
+        std::mem::swap(&mut pos_map, &mut self.pos_map);
+        Ok(())
+    }
+
+    /// Mark the note with the given nullifier as spent, if it is ours
+    fn scan_spend(
+        &mut self,
+        nullifier: &Nullifier,
+        transaction_delta: &mut TransactionDelta,
+    ) -> Result<(), Error> {
+        // If the shielded spend's nullifier is in our map, then target note
+        // is rendered unusable
+        if let Some(note_pos) = self.nf_map.get(nullifier) {
+            self.spents.insert(*note_pos);
+            // Note the account changes
+            let balance = transaction_delta
+                .entry(self.vk_map[note_pos])
+                .or_insert_with(I128Sum::zero);
+            let note = self.note_map[note_pos];
+            *balance -=
+                I128Sum::from_nonnegative(note.asset_type, note.value as i128)
+                    .map_err(|()| {
+                        Error::Other(
+                            "found note with invalid value or asset type"
+                                .to_string(),
+                        )
+                    })?;
+        }
+        Ok(())
+    }
+
+    /// Record the changes to the transparent accounts caused by a scanned
+    /// transaction, given its transparent inputs and outputs, if any, and
+    /// mark the transaction as the last indexed one
+    fn record_scanned_tx(
+        &mut self,
+        indexed_tx: IndexedTx,
+        epoch: Epoch,
+        tx_changed_keys: &BTreeSet<namada_core::types::storage::Key>,
+        transparent: Option<(Vec<CompactTransparent>, Vec<CompactTransparent>)>,
+        native_token: Address,
+        transaction_delta: TransactionDelta,
+    ) -> Result<(), Error> {
         let mut transfer_delta = TransferDelta::new();
 
         let balance_keys: Vec<_> = tx_changed_keys
             .iter()
             .filter_map(is_any_shielded_action_balance_key)
             .collect();
-        let (source, token, amount) = match shielded.transparent_bundle() {
-            Some(transp_bundle) => {
+        let (source, token, amount) = match transparent {
+            Some((vin, vout)) => {
                 // Shielding/Unshielding transfer
-                match (transp_bundle.vin.len(), transp_bundle.vout.len()) {
+                match (vin.len(), vout.len()) {
                     (0, 0) => {
                         return Err(Error::Other(
                             "Expected shielding/unshielding transaction"
@@ -1070,20 +2069,15 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                             .iter()
                             .find(|addresses| {
                                 if addresses[1] != &MASP {
-                                    let transp_addr_commit = TransparentAddress(
-                                        ripemd::Ripemd160::digest(
-                                            sha2::Sha256::digest(
-                                                &addresses[1]
-                                                    .serialize_to_vec(),
-                                            ),
-                                        )
-                                        .into(),
-                                    );
+                                    let transp_addr_commit =
+                                        transparent_address_commit(
+                                            addresses[1],
+                                        );
                                     // Vins contain the same address, so we can
                                     // just examine the first one
-                                    transp_bundle.vin.first().is_some_and(
-                                        |vin| vin.address == transp_addr_commit,
-                                    )
+                                    vin.first().is_some_and(|vin| {
+                                        vin.address == transp_addr_commit
+                                    })
                                 } else {
                                     false
                                 }
@@ -1095,10 +2089,8 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                                 )
                             })?;
 
-                        let amount = transp_bundle
-                            .vin
-                            .iter()
-                            .fold(Amount::zero(), |acc, vin| {
+                        let amount =
+                            vin.iter().fold(Amount::zero(), |acc, vin| {
                                 acc + Amount::from_u64(vin.value)
                             });
 
@@ -1114,23 +2106,15 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                             .iter()
                             .find(|addresses| {
                                 if addresses[1] != &MASP {
-                                    let transp_addr_commit = TransparentAddress(
-                                        ripemd::Ripemd160::digest(
-                                            sha2::Sha256::digest(
-                                                &addresses[1]
-                                                    .serialize_to_vec(),
-                                            ),
-                                        )
-                                        .into(),
-                                    );
-
+                                    let transp_addr_commit =
+                                        transparent_address_commit(
+                                            addresses[1],
+                                        );
                                     // Vouts contain the same address, so we can
                                     // just examine the first one
-                                    transp_bundle.vout.first().is_some_and(
-                                        |vout| {
-                                            vout.address == transp_addr_commit
-                                        },
-                                    )
+                                    vout.first().is_some_and(|vout| {
+                                        vout.address == transp_addr_commit
+                                    })
                                 } else {
                                     false
                                 }
@@ -1142,10 +2126,8 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                                 )
                             })?[0];
 
-                        let amount = transp_bundle
-                            .vout
-                            .iter()
-                            .fold(Amount::zero(), |acc, vout| {
+                        let amount =
+                            vout.iter().fold(Amount::zero(), |acc, vout| {
                                 acc + Amount::from_u64(vout.value)
                             });
                         (MASP, token.to_owned(), amount)
@@ -1187,6 +2169,27 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         &self.delta_map
     }
 
+    /// Get the memos of the notes that the given transaction sent to our
+    /// viewing keys, by the viewing keys that decrypted them. A transfer
+    /// sends its memo with each of its notes, so the memos are deduplicated.
+    /// Empty memos are skipped, as are the memos of the notes discovered from
+    /// compact transactions.
+    pub fn tx_memos(&self, indexed_tx: &IndexedTx) -> Vec<(ViewingKey, &[u8])> {
+        let mut memos = Vec::new();
+        for note_pos in self.tx_note_map.get(indexed_tx).into_iter().flatten() {
+            let (Some(vk), Some(memo)) = (
+                self.vk_map.get(note_pos),
+                self.memo_map.get(note_pos).and_then(shielded_memo_bytes),
+            ) else {
+                continue;
+            };
+            if !memos.contains(&(*vk, memo)) {
+                memos.push((*vk, memo));
+            }
+        }
+        memos
+    }
+
     /// Compute the total unspent notes associated with the viewing key in the
     /// context. If the key is not in the context, then we do not know the
     /// balance and hence we return None.
@@ -1351,6 +2354,109 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         }
     }
 
+    /// Compute the total unspent notes associated with the viewing key in the
+    /// context, broken down by the tokens that their asset types decode to.
+    /// The balance of each token is exchanged to the target epoch to find
+    /// the rewards that it accrued. The asset types that can't be decoded are
+    /// left out. If the key is not in the context, then we do not know the
+    /// balance and hence we return None.
+    pub async fn balance_breakdown(
+        &mut self,
+        client: &(impl Client + Sync),
+        io: &impl Io,
+        vk: &ViewingKey,
+        target_epoch: Epoch,
+    ) -> Result<Option<BTreeMap<Address, ShieldedAssetBalance>>, Error> {
+        let Some(balance) = self.compute_shielded_balance(vk).await? else {
+            return Ok(None);
+        };
+        // Split the balance by the token of its asset types
+        let mut denoms = HashMap::<Address, Denomination>::new();
+        let mut by_token = BTreeMap::<Address, (I128Sum, token::Change)>::new();
+        for (asset_type, value) in balance.components() {
+            let Some(decoded) =
+                self.decode_asset_type(client, *asset_type).await
+            else {
+                continue;
+            };
+            let change =
+                token::Change::from_masp_denominated(*value, decoded.position)
+                    .expect("expected this to fit");
+            denoms.insert(decoded.token.clone(), decoded.denom);
+            let (amount, principal) = by_token
+                .entry(decoded.token)
+                .or_insert_with(|| (I128Sum::zero(), token::Change::zero()));
+            *amount += I128Sum::from_pair(*asset_type, *value)
+                .expect("expected this to fit");
+            *principal += change;
+        }
+
+        let mut breakdown = BTreeMap::new();
+        for (token, (amount, principal)) in by_token {
+            // The rewards are the difference between the balance exchanged to
+            // the target epoch and the balance itself
+            let exchanged = self
+                .compute_exchanged_amount(
+                    client,
+                    io,
+                    amount,
+                    target_epoch,
+                    BTreeMap::new(),
+                )
+                .await?
+                .0;
+            let (exchanged, _undecoded) = self
+                .decode_combine_sum_to_epoch(client, exchanged, target_epoch)
+                .await;
+            let mut rewards = BTreeMap::new();
+            for (reward_token, change) in exchanged.components() {
+                let reward = if *reward_token == token {
+                    *change - principal
+                } else {
+                    *change
+                };
+                if reward.is_positive() {
+                    let denom =
+                        Self::token_denom(client, &mut denoms, reward_token)
+                            .await?;
+                    rewards.insert(
+                        reward_token.clone(),
+                        token::DenominatedAmount::new(
+                            Amount::from_change(reward),
+                            denom,
+                        ),
+                    );
+                }
+            }
+            let denom = Self::token_denom(client, &mut denoms, &token).await?;
+            let balance = token::DenominatedAmount::new(
+                Amount::from_change(principal),
+                denom,
+            );
+            breakdown.insert(token, ShieldedAssetBalance { balance, rewards });
+        }
+        Ok(Some(breakdown))
+    }
+
+    /// Get the denomination of a token from the given cache, or query it and
+    /// cache it.
+    async fn token_denom(
+        client: &(impl Client + Sync),
+        denoms: &mut HashMap<Address, Denomination>,
+        token: &Address,
+    ) -> Result<Denomination, Error> {
+        if let Some(denom) = denoms.get(token) {
+            return Ok(*denom);
+        }
+        let Some(denom) = query_denom(client, token).await else {
+            return Err(Error::Query(QueryError::General(format!(
+                "denomination for token {token}"
+            ))));
+        };
+        denoms.insert(token.clone(), denom);
+        Ok(denom)
+    }
+
     /// Try to convert as much of the given asset type-value pair using the
     /// given allowed conversion. usage is incremented by the amount of the
     /// conversion used, the conversions are applied to the given input, and
@@ -1860,36 +2966,112 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
     /// UTXOs are sometimes used to make transactions balanced, but it is
     /// understood that transparent account changes are effected only by the
     /// amounts and signatures specified by the containing Transfer object.
+    /// The given memo is attached to the output sent to the target, which
+    /// must then be a payment address unless the memo is empty.
     pub async fn gen_shielded_transfer(
         context: &impl Namada,
         source: &TransferSource,
         target: &TransferTarget,
         token: &Address,
         amount: token::DenominatedAmount,
+        memo: MemoBytes,
     ) -> Result<Option<ShieldedTransfer>, TransferErr> {
-        // No shielded components are needed when neither source nor destination
-        // are shielded
+        Self::build_shielded_transfer(
+            context,
+            Either::Left(source),
+            target,
+            token,
+            amount,
+            memo,
+        )
+        .await
+        .map(|built| built.map(|(transfer, _alphas)| transfer))
+    }
+
+    /// Make the shielded components of a transfer from a source whose spend
+    /// authorizing key is held by an external signer, like
+    /// [`Self::gen_shielded_transfer`]. Only their proofs are generated, the
+    /// authorizations of their spends are left to the signer, see
+    /// [`UnsignedShieldedTransfer::authorize`].
+    pub async fn gen_unsigned_shielded_transfer(
+        context: &impl Namada,
+        source: &ExternalSpendingKey,
+        target: &TransferTarget,
+        token: &Address,
+        amount: token::DenominatedAmount,
+        memo: MemoBytes,
+    ) -> Result<UnsignedShieldedTransfer, TransferErr> {
+        let (transfer, alphas) = Self::build_shielded_transfer(
+            context,
+            Either::Right(source),
+            target,
+            token,
+            amount,
+            memo,
+        )
+        .await?
+        .expect("a transfer from a shielded source has shielded components");
+        Ok(UnsignedShieldedTransfer::new(transfer, alphas)?)
+    }
 
+    /// Make the shielded components of a transfer from either a transparent
+    /// or shielded source, or the source of an external signer. Also return
+    /// the randomness of the spend authorizations of the latter.
+    async fn build_shielded_transfer(
+        context: &impl Namada,
+        source: Either<&TransferSource, &ExternalSpendingKey>,
+        target: &TransferTarget,
+        token: &Address,
+        amount: token::DenominatedAmount,
+        memo: MemoBytes,
+    ) -> Result<
+        Option<(ShieldedTransfer, Vec<masp_proofs::jubjub::Fr>)>,
+        TransferErr,
+    > {
         use rand::rngs::StdRng;
         use rand_core::SeedableRng;
 
-        let spending_key = source.spending_key();
+        // We want to fund our transaction solely from supplied spending key,
+        // or the proving key of the external signer
+        let (spending_key, viewing_key, spending_keys, viewing_keys) =
+            match source {
+                Either::Left(source) => {
+                    let spending_key: Option<ExtendedSpendingKey> =
+                        source.spending_key().map(|x| x.into());
+                    (
+                        spending_key,
+                        spending_key.as_ref().map(ExtendedFullViewingKey::from),
+                        spending_key.into_iter().collect::<Vec<_>>(),
+                        vec![],
+                    )
+                }
+                Either::Right(external) => (
+                    Some(external.proving_key()),
+                    Some(external.viewing_key),
+                    vec![],
+                    vec![external.viewing_key.fvk.vk],
+                ),
+            };
         let payment_address = target.payment_address();
+        // Only shielded outputs carry memos
+        if payment_address.is_none() && memo != MemoBytes::empty() {
+            return Err(TransferErr::from(Error::Other(
+                "Shielded memos can only be sent to payment addresses"
+                    .to_string(),
+            )));
+        }
         // No shielded components are needed when neither source nor
         // destination are shielded
         if spending_key.is_none() && payment_address.is_none() {
             return Ok(None);
         }
-        // We want to fund our transaction solely from supplied spending key
-        let spending_key = spending_key.map(|x| x.into());
-        let spending_keys: Vec<_> = spending_key.into_iter().collect();
         {
             // Load the current shielded context given the spending key we
             // possess
             let mut shielded = context.shielded_mut().await;
             let _ = shielded.load().await;
             shielded
-                .fetch(context.client(), &spending_keys, &[])
+                .fetch(context.client(), &spending_keys, &viewing_keys)
                 .await?;
             // Save the update state so that future fetches can be
             // short-circuited
@@ -1897,9 +3079,6 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         }
         // Determine epoch in which to submit potential shielded transaction
         let epoch = rpc::query_epoch(context.client()).await?;
-        // Context required for storing which notes are in the source's
-        // possession
-        let memo = MemoBytes::empty();
 
         // Try to get a seed from env var, if any.
         let rng = if let Ok(seed) = env::var(ENV_VAR_MASP_TEST_SEED)
@@ -1991,14 +3170,14 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         };
 
         // If there are shielded inputs
-        if let Some(sk) = spending_key {
+        if let (Some(sk), Some(viewing_key)) = (spending_key, viewing_key) {
             // Locate unspent notes that can help us meet the transaction amount
             let (_, unspent_notes, used_convs) = context
                 .shielded_mut()
                 .await
                 .collect_unspent_notes(
                     context,
-                    &to_viewing_key(&sk).vk,
+                    &viewing_key.fvk.vk,
                     I128Sum::from_sum(masp_amount),
                     epoch,
                 )
@@ -2026,7 +3205,8 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
             // the parent Transfer object is used to validate fund
             // availability
             let source_enc = source
-                .address()
+                .left()
+                .and_then(TransferSource::address)
                 .ok_or_else(|| {
                     Error::Other(
                         "source address should be transparent".to_string(),
@@ -2159,7 +3339,7 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
         }
 
         // Now add outputs representing the change from this payment
-        if let Some(sk) = spending_key {
+        if let (Some(sk), Some(viewing_key)) = (spending_key, viewing_key) {
             // Represents the amount of inputs we are short by
             let mut additional = I128Sum::zero();
             for (asset_type, amt) in builder
@@ -2178,10 +3358,10 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                         builder
                             .add_sapling_output(
                                 Some(sk.expsk.ovk),
-                                sk.default_address().1,
+                                viewing_key.default_address().1,
                                 *asset_type,
                                 *amt as u64,
-                                memo.clone(),
+                                MemoBytes::empty(),
                             )
                             .map_err(builder::Error::SaplingBuild)?;
                     }
@@ -2211,9 +3391,11 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
 
         // To speed up integration tests, we can save and load proofs
         #[cfg(feature = "testing")]
-        let load_or_save = if let Ok(masp_proofs) =
-            env::var(ENV_VAR_MASP_TEST_PROOFS)
-        {
+        let load_or_save = if source.is_right() {
+            // The randomness of the spend authorizations of an external
+            // signer is only known when the proofs are generated
+            LoadOrSaveProofs::Neither
+        } else if let Ok(masp_proofs) = env::var(ENV_VAR_MASP_TEST_PROOFS) {
             let parsed = match masp_proofs.to_ascii_lowercase().as_str() {
                 "load" => LoadOrSaveProofs::Load,
                 "save" => LoadOrSaveProofs::Save,
@@ -2234,24 +3416,30 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
             LoadOrSaveProofs::Neither
         };
 
-        let builder_clone = builder.clone().map_builder(WalletMap);
+        let builder_clone = builder.clone().map_builder(WalletMap(
+            source.right().map(|external| external.viewing_key),
+        ));
         #[cfg(feature = "testing")]
         let builder_bytes = borsh::to_vec(&builder_clone).map_err(|e| {
             Error::from(EncodingError::Conversion(e.to_string()))
         })?;
 
+        let proof_generation_key =
+            source.right().map(|x| x.proof_generation_key.clone());
         let build_transfer = |prover: LocalTxProver| -> Result<
-            ShieldedTransfer,
+            (ShieldedTransfer, Vec<masp_proofs::jubjub::Fr>),
             builder::Error<std::convert::Infallible>,
         > {
+            let prover = ExternalSpendProver::new(prover, proof_generation_key);
             let (masp_tx, metadata) = builder
                 .build(&prover, &FeeRule::non_standard(U64Sum::zero()))?;
-            Ok(ShieldedTransfer {
+            let transfer = ShieldedTransfer {
                 builder: builder_clone,
                 masp_tx,
                 metadata,
                 epoch,
-            })
+            };
+            Ok((transfer, prover.into_alphas()))
         };
 
         #[cfg(feature = "testing")]
@@ -2291,14 +3479,14 @@ impl<U: ShieldedUtils + MaybeSend + MaybeSync> ShieldedContext<U> {
                     BorshDeserialize::try_from_slice(&loaded_bytes)
                         .map_err(|_e| Error::Other(exp_str))?;
 
-                Ok(Some(loaded))
+                Ok(Some((loaded, vec![])))
             } else {
                 // Build and return the constructed transaction
                 let built = build_transfer(
                     context.shielded().await.utils.local_tx_prover(),
                 )?;
                 if let LoadOrSaveProofs::Save = load_or_save {
-                    let built_bytes = borsh::to_vec(&built).map_err(|e| {
+                    let built_bytes = borsh::to_vec(&built.0).map_err(|e| {
                         Error::from(EncodingError::Conversion(e.to_string()))
                     })?;
                     tokio::fs::write(&saved_filepath, built_bytes)
@@ -2679,7 +3867,19 @@ async fn extract_payload_from_shielded_action<'args, C: Client + Sync>(
     Ok(result)
 }
 
+#[cfg(test)]
 mod tests {
+    use std::sync::Mutex;
+
+    use masp_primitives::merkle_tree::FrozenCommitmentTree;
+    use masp_primitives::transaction::components::transparent::testing::arb_transparent_address;
+    use proptest::prelude::*;
+
+    use super::testing::{
+        arb_pre_asset_type, arb_spend_description, MockTxProver,
+    };
+    use super::*;
+
     /// quick and dirty test. will fail on size check
     #[test]
     #[should_panic(expected = "parameter file size is not correct")]
@@ -2788,6 +3988,186 @@ mod tests {
             &fake_params_paths[2].0,
         );
     }
+
+    proptest! {
+        /// Test that the spends of a transfer proven with the proof
+        /// generation key of an external signer are only authorized by the
+        /// signatures of the signer.
+        #[test]
+        fn test_external_spend_authorization(
+            (asset_type, (sk, diversifier, note, node)) in
+                arb_pre_asset_type().prop_flat_map(|asset| {
+                    let asset_type = asset.encode().unwrap();
+                    (Just(asset_type), arb_spend_description(asset_type, 100))
+                }),
+            address in arb_transparent_address(),
+        ) {
+            let external = ExternalSpendingKey::new(
+                (&sk).into(),
+                sk.expsk.proof_generation_key(),
+            )
+            .unwrap();
+            let other_sk = ExtendedSpendingKey::master(b"other signer");
+            assert!(ExternalSpendingKey::new(
+                (&sk).into(),
+                other_sk.expsk.proof_generation_key(),
+            )
+            .is_err());
+
+            let tree = FrozenCommitmentTree::new(&[node]);
+            let mut builder = Builder::<TestNetwork, _>::new_with_rng(
+                NETWORK,
+                1.into(),
+                OsRng,
+            );
+            builder
+                .add_sapling_spend(
+                    external.proving_key(),
+                    diversifier,
+                    note,
+                    tree.path(0),
+                )
+                .unwrap();
+            builder
+                .add_transparent_output(&address, asset_type, note.value)
+                .unwrap();
+            let prover = ExternalSpendProver::new(
+                MockTxProver(Mutex::new(OsRng)),
+                Some(external.proof_generation_key.clone()),
+            );
+            let (masp_tx, metadata) = builder
+                .clone()
+                .build(&prover, &FeeRule::non_standard(U64Sum::zero()))
+                .unwrap();
+            let transfer = ShieldedTransfer {
+                builder: builder
+                    .map_builder(WalletMap(Some(external.viewing_key))),
+                masp_tx,
+                metadata,
+                epoch: Epoch(0),
+            };
+            let unsigned =
+                UnsignedShieldedTransfer::new(transfer, prover.into_alphas())
+                    .unwrap();
+            // The intermediate state survives its trip to the signer
+            let unsigned = UnsignedShieldedTransfer::try_from_slice(
+                &unsigned.serialize_to_vec(),
+            )
+            .unwrap();
+            assert_eq!(unsigned.spends.len(), 1);
+
+            // The placeholder authorizations and the ones of another key are
+            // rejected
+            let placeholder = unsigned
+                .transfer
+                .masp_tx
+                .sapling_bundle()
+                .unwrap()
+                .shielded_spends[0]
+                .spend_auth_sig;
+            assert!(unsigned.clone().authorize(vec![placeholder]).is_err());
+            let forged = unsigned.spends[0].sign(&other_sk).unwrap();
+            assert!(unsigned.clone().authorize(vec![forged]).is_err());
+            assert!(unsigned.clone().authorize(vec![]).is_err());
+
+            let signature = unsigned.spends[0].sign(&sk).unwrap();
+            let signed = unsigned.authorize(vec![signature]).unwrap();
+            let spend =
+                &signed.masp_tx.sapling_bundle().unwrap().shielded_spends[0];
+            let sighash = shielded_sighash(&signed.masp_tx).unwrap();
+            assert!(PublicKey(spend.rk.0).verify(
+                &spend_auth_message(&PublicKey(spend.rk.0), &sighash),
+                &spend.spend_auth_sig,
+                SPENDING_KEY_GENERATOR,
+            ));
+        }
+
+        /// Test that the memo of a shielded output is decrypted along with its
+        /// note by the viewing key of its receiver, and recorded with the
+        /// transaction that sent it.
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_shielded_memo_scanning(
+            asset_type in arb_pre_asset_type()
+                .prop_map(|asset| asset.encode().unwrap()),
+            address in arb_transparent_address(),
+        ) {
+            let invoice = b"deposit #42";
+            let memo = shielded_memo(invoice).unwrap();
+            assert!(shielded_memo(&[1; MAX_SHIELDED_MEMO_LEN]).is_ok());
+            assert!(shielded_memo(&[1; MAX_SHIELDED_MEMO_LEN + 1]).is_err());
+            assert_eq!(shielded_memo_bytes(&MemoBytes::empty()), None);
+
+            let sk = ExtendedSpendingKey::master(b"exchange");
+            let vk = ExtendedFullViewingKey::from(&sk).fvk.vk;
+            let (_, payment_addr) = sk.default_address();
+            let mut builder = Builder::<TestNetwork, _>::new_with_rng(
+                NETWORK,
+                1.into(),
+                OsRng,
+            );
+            builder
+                .add_transparent_input(TxOut {
+                    asset_type,
+                    value: 100,
+                    address,
+                })
+                .unwrap();
+            builder
+                .add_sapling_output(
+                    None,
+                    payment_addr,
+                    asset_type,
+                    100,
+                    memo.clone(),
+                )
+                .unwrap();
+            let (masp_tx, _) = builder
+                .build(
+                    &MockTxProver(Mutex::new(OsRng)),
+                    &FeeRule::non_standard(U64Sum::zero()),
+                )
+                .unwrap();
+            let node = Node::new(
+                masp_tx.sapling_bundle().unwrap().shielded_outputs[0]
+                    .cmu
+                    .to_repr(),
+            );
+
+            // Only the receiver decrypts the memo
+            let other_vk =
+                ExtendedFullViewingKey::from(&ExtendedSpendingKey::master(
+                    b"other",
+                ))
+                .fvk
+                .vk;
+            let fetched = FetchedShieldedTx::Full(BTreeSet::new(), masp_tx);
+            let decryptions = trial_decrypt(&fetched, &[vk, other_vk]).unwrap();
+            assert_eq!(decryptions.len(), 1);
+            assert_eq!(decryptions[0].len(), 1);
+            assert_eq!(decryptions[0][&vk].2, memo);
+
+            let mut ctx = ShieldedContext::<fs::FsShieldedUtils>::default();
+            ctx.pos_map.insert(vk, BTreeSet::new());
+            let indexed_tx = IndexedTx {
+                height: BlockHeight(1),
+                index: TxIndex(0),
+            };
+            ctx.scan_output(
+                indexed_tx,
+                node,
+                &mut TransactionDelta::new(),
+                &decryptions[0],
+            )
+            .unwrap();
+            assert!(ctx.tx_memos(&indexed_tx) == vec![(vk, &invoice[..])]);
+            let next_tx = IndexedTx {
+                index: TxIndex(1),
+                ..indexed_tx
+            };
+            assert!(ctx.tx_memos(&next_tx).is_empty());
+        }
+    }
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -2894,7 +4274,7 @@ pub mod testing {
     // An implementation of TxProver that does everything except generating
     // valid zero-knowledge proofs. Uses the supplied source of randomness to
     // carry out its operations.
-    pub struct MockTxProver<R: RngCore>(Mutex<R>);
+    pub struct MockTxProver<R: RngCore>(pub Mutex<R>);
 
     impl<R: RngCore> TxProver for MockTxProver<R> {
         type SaplingProvingContext = SaplingProvingContext;
@@ -3450,7 +4830,7 @@ pub mod testing {
                 &FeeRule::non_standard(U64Sum::zero()),
             ).unwrap();
             (ShieldedTransfer {
-                builder: builder.map_builder(WalletMap),
+                builder: builder.map_builder(WalletMap(None)),
                 metadata,
                 masp_tx,
                 epoch,
@@ -3476,7 +4856,7 @@ pub mod testing {
                 &FeeRule::non_standard(U64Sum::zero()),
             ).unwrap();
             (ShieldedTransfer {
-                builder: builder.map_builder(WalletMap),
+                builder: builder.map_builder(WalletMap(None)),
                 metadata,
                 masp_tx,
                 epoch,
@@ -3502,7 +4882,7 @@ pub mod testing {
                 &FeeRule::non_standard(U64Sum::zero()),
             ).unwrap();
             (ShieldedTransfer {
-                builder: builder.map_builder(WalletMap),
+                builder: builder.map_builder(WalletMap(None)),
                 metadata,
                 masp_tx,
                 epoch,
@@ -3511,20 +4891,257 @@ pub mod testing {
     }
 }
 
+fn checksum(bytes: &[u8]) -> [u8; 32] {
+    sha2::Sha256::digest(bytes).into()
+}
+
+fn invalid_data(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
+/// The digest of the viewing keys of the given context. Deltas can only be
+/// replayed on top of a checkpoint with the same viewing keys.
+fn viewing_keys_digest<U: ShieldedUtils>(ctx: &ShieldedContext<U>) -> [u8; 32] {
+    let keys: BTreeSet<_> =
+        ctx.pos_map.keys().map(|vk| vk.serialize_to_vec()).collect();
+    checksum(&keys.serialize_to_vec())
+}
+
+/// Apply the given delta records to the context. A record is given by its
+/// last scanned transaction and its serialized scanned transactions. Txs that
+/// the context already contains are skipped. Returns the number of records
+/// that were fully applied.
+fn replay_deltas<'a, U: ShieldedUtils + MaybeSend + MaybeSync>(
+    ctx: &mut ShieldedContext<U>,
+    records: impl IntoIterator<Item = (IndexedTx, &'a [u8])>,
+) -> usize {
+    let mut applied = 0;
+    for (last_indexed, payload) in records {
+        let Ok(scanned) = Vec::<ScannedTx>::try_from_slice(payload) else {
+            return applied;
+        };
+        if scanned.last().map(|tx| tx.indexed_tx) != Some(last_indexed) {
+            return applied;
+        }
+        for tx in scanned {
+            if Some(tx.indexed_tx) <= ctx.last_indexed {
+                continue;
+            }
+            if ctx
+                .scan_fetched_tx(
+                    tx.indexed_tx,
+                    tx.epoch,
+                    &tx.tx,
+                    tx.native_token,
+                )
+                .is_err()
+            {
+                return applied;
+            }
+        }
+        applied += 1;
+    }
+    applied
+}
+
+/// Load the MASP parameters from the directory given by the environment, or
+/// else from their default location
+fn load_local_tx_prover() -> LocalTxProver {
+    if let Ok(params_dir) = env::var(ENV_VAR_MASP_PARAMS_DIR) {
+        let params_dir = PathBuf::from(params_dir);
+        let spend_path = params_dir.join(SPEND_NAME);
+        let convert_path = params_dir.join(CONVERT_NAME);
+        let output_path = params_dir.join(OUTPUT_NAME);
+        LocalTxProver::new(&spend_path, &output_path, &convert_path)
+    } else {
+        LocalTxProver::with_default_location()
+            .expect("unable to load MASP Parameters")
+    }
+}
+
+pub mod store;
+
 #[cfg(feature = "std")]
 /// Implementation of MASP functionality depending on a standard filesystem
 pub mod fs {
     use std::fs::{File, OpenOptions};
-    use std::io::{Read, Write};
+    use std::io::{ErrorKind, Seek, SeekFrom, Write};
+    use std::path::Path;
 
     use super::*;
 
     /// Shielded context file name
     const FILE_NAME: &str = "shielded.dat";
     const TMP_FILE_NAME: &str = "shielded.tmp";
+    /// File name of the checkpoint preceding the current one
+    const PREV_FILE_NAME: &str = "shielded.prev";
+    /// File name of the deltas saved on top of a checkpoint
+    const DELTA_FILE_NAME: &str = "shielded.delta";
+    const TMP_DELTA_FILE_NAME: &str = "shielded.delta.tmp";
+
+    /// Magic bytes identifying versioned shielded context files
+    const MAGIC: [u8; 4] = *b"NMSC";
+    /// The version of the shielded context files format
+    const FORMAT_VERSION: u32 = 3;
+    /// The number of deltas after which the next save writes a new
+    /// checkpoint
+    const MAX_DELTAS_PER_CHECKPOINT: usize = 32;
+    /// The size of the deltas after which the next save writes a new
+    /// checkpoint, if they are also larger than the checkpoint
+    const MIN_DELTAS_LEN_TO_COMPACT: u64 = 1 << 20;
+
+    /// The header of a checkpoint file, followed by the serialized context
+    #[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+    struct CheckpointHeader {
+        magic: [u8; 4],
+        version: u32,
+        /// The identifier of this checkpoint
+        id: u64,
+        /// The last indexed transaction of the saved context
+        last_indexed: Option<IndexedTx>,
+        /// The digest of the viewing keys of the saved context
+        keys: [u8; 32],
+        /// The length of the serialized context
+        len: u64,
+        /// The hash of the serialized context
+        checksum: [u8; 32],
+    }
+
+    /// The header of a delta file, followed by the delta records
+    #[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+    struct DeltaFileHeader {
+        magic: [u8; 4],
+        version: u32,
+        /// The identifier of the checkpoint the deltas apply to
+        checkpoint: u64,
+    }
+
+    /// The header of a delta record, followed by the serialized scanned txs
+    #[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+    struct DeltaHeader {
+        /// The last scanned transaction of the record
+        last_indexed: IndexedTx,
+        /// The length of the serialized scanned txs
+        len: u32,
+        /// The hash of the serialized scanned txs
+        checksum: [u8; 32],
+    }
+
+    /// The consistent deltas read from a delta file
+    #[derive(Debug, Default)]
+    struct Deltas {
+        /// Whether the delta file exists and applies to the checkpoint
+        present: bool,
+        /// The consistent records, in order
+        records: Vec<(DeltaHeader, Vec<u8>)>,
+        /// The length of the consistent prefix of the delta file
+        valid_len: u64,
+        /// Whether an inconsistent suffix was found after the records
+        corrupt: bool,
+    }
+
+    impl Deltas {
+        /// The last transaction persisted by the deltas, if any
+        fn last_indexed(&self) -> Option<IndexedTx> {
+            self.records.last().map(|(header, _)| header.last_indexed)
+        }
+    }
+
+    /// Read only the header of the checkpoint at the given path
+    fn read_checkpoint_header(
+        path: &Path,
+    ) -> std::io::Result<CheckpointHeader> {
+        let header =
+            CheckpointHeader::deserialize_reader(&mut File::open(path)?)?;
+        if header.magic != MAGIC || header.version != FORMAT_VERSION {
+            return Err(invalid_data("unsupported shielded context format"));
+        }
+        Ok(header)
+    }
+
+    /// Read and check the checkpoint at the given path. The header is `None`
+    /// for contexts saved before the files were versioned.
+    fn read_checkpoint<U: ShieldedUtils>(
+        path: &Path,
+    ) -> std::io::Result<(Option<CheckpointHeader>, ShieldedContext<U>)> {
+        let bytes = std::fs::read(path)?;
+        let mut rest = &bytes[..];
+        match CheckpointHeader::deserialize(&mut rest) {
+            Ok(header) if header.magic == MAGIC => {
+                if header.version != FORMAT_VERSION {
+                    return Err(invalid_data(
+                        "unsupported shielded context version",
+                    ));
+                }
+                if rest.len() as u64 != header.len
+                    || checksum(rest) != header.checksum
+                {
+                    return Err(invalid_data("corrupt shielded context"));
+                }
+                let ctx = ShieldedContext::<U>::try_from_slice(rest)?;
+                Ok((Some(header), ctx))
+            }
+            _ => Ok((None, ShieldedContext::<U>::try_from_slice(&bytes)?)),
+        }
+    }
+
+    /// Read the consistent deltas of the given checkpoint from the delta file
+    /// at the given path
+    fn read_deltas(path: &Path, checkpoint: u64) -> std::io::Result<Deltas> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                return Ok(Deltas::default());
+            }
+            Err(err) => return Err(err),
+        };
+        let mut rest = &bytes[..];
+        match DeltaFileHeader::deserialize(&mut rest) {
+            Ok(header)
+                if header.magic == MAGIC
+                    && header.version == FORMAT_VERSION
+                    && header.checkpoint == checkpoint => {}
+            // The deltas of another checkpoint or a corrupt header
+            _ => return Ok(Deltas::default()),
+        }
+        let mut deltas = Deltas {
+            present: true,
+            valid_len: (bytes.len() - rest.len()) as u64,
+            ..Default::default()
+        };
+        while !rest.is_empty() {
+            let Ok(header) = DeltaHeader::deserialize(&mut rest) else {
+                deltas.corrupt = true;
+                break;
+            };
+            let len = header.len as usize;
+            if rest.len() < len || checksum(&rest[..len]) != header.checksum {
+                deltas.corrupt = true;
+                break;
+            }
+            deltas.records.push((header, rest[..len].to_vec()));
+            rest = &rest[len..];
+            deltas.valid_len = (bytes.len() - rest.len()) as u64;
+        }
+        Ok(deltas)
+    }
+
+    /// The scanned txs of the given delta records, to replay them
+    fn replayable(
+        records: &[(DeltaHeader, Vec<u8>)],
+    ) -> impl Iterator<Item = (IndexedTx, &[u8])> {
+        records
+            .iter()
+            .map(|(header, payload)| (header.last_indexed, &payload[..]))
+    }
 
     #[derive(Debug, BorshSerialize, BorshDeserialize, Clone)]
-    /// An implementation of ShieldedUtils for standard filesystems
+    /// An implementation of ShieldedUtils for standard filesystems. The
+    /// context is saved as a full checkpoint followed by deltas, which record
+    /// the transactions scanned since the checkpoint was written, so that
+    /// most saves only append to a small file. Once enough deltas have
+    /// accumulated, the next save compacts them into a new checkpoint. The
+    /// previous checkpoint is kept to recover from a corrupt one.
     pub struct FsShieldedUtils {
         #[borsh(skip)]
         context_dir: PathBuf,
@@ -3558,6 +5175,106 @@ pub mod fs {
                 ..Default::default()
             }
         }
+
+        /// Write the given context as a new checkpoint with the given
+        /// identifier, keeping the current checkpoint as the previous one
+        fn write_checkpoint<U: ShieldedUtils>(
+            &self,
+            ctx: &ShieldedContext<U>,
+            id: u64,
+        ) -> std::io::Result<()> {
+            // TODO: use mktemp crate?
+            let tmp_path = self.context_dir.join(TMP_FILE_NAME);
+            {
+                // First serialize the shielded context into a temporary file.
+                // Inability to create this file implies a simultaneuous write
+                // is in progress. In this case, immediately
+                // fail. This is unproblematic because the data
+                // intended to be stored can always be re-fetched
+                // from the blockchain.
+                let mut ctx_file = OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(tmp_path.clone())?;
+                let mut bytes = Vec::new();
+                ctx.serialize(&mut bytes)
+                    .expect("cannot serialize shielded context");
+                let header = CheckpointHeader {
+                    magic: MAGIC,
+                    version: FORMAT_VERSION,
+                    id,
+                    last_indexed: ctx.last_indexed,
+                    keys: viewing_keys_digest(ctx),
+                    len: bytes.len() as u64,
+                    checksum: checksum(&bytes),
+                };
+                ctx_file.write_all(&header.serialize_to_vec())?;
+                ctx_file.write_all(&bytes[..])?;
+                ctx_file.sync_all()?;
+            }
+            // Keep the current checkpoint to fall back to. Its deltas are
+            // only replaced once deltas are saved for the new checkpoint.
+            let path = self.context_dir.join(FILE_NAME);
+            if path.exists() {
+                std::fs::rename(&path, self.context_dir.join(PREV_FILE_NAME))?;
+            }
+            // Atomically update the old shielded context file with new data.
+            // Atomicity is required to prevent other client instances from
+            // reading corrupt data.
+            std::fs::rename(tmp_path, path)
+        }
+
+        /// Append a delta record with the given scanned txs on top of the
+        /// checkpoint with the given identifier
+        fn append_delta(
+            &self,
+            checkpoint: u64,
+            deltas: &Deltas,
+            scanned: &[ScannedTx],
+        ) -> std::io::Result<()> {
+            let Some(last) = scanned.last() else {
+                return Ok(());
+            };
+            let payload = borsh::to_vec(scanned)?;
+            let header = DeltaHeader {
+                last_indexed: last.indexed_tx,
+                len: payload.len().try_into().map_err(|_| {
+                    invalid_data("shielded context delta is too large")
+                })?,
+                checksum: checksum(&payload),
+            };
+            let mut record = header.serialize_to_vec();
+            record.extend(payload);
+
+            let path = self.context_dir.join(DELTA_FILE_NAME);
+            if deltas.present {
+                let mut delta_file =
+                    OpenOptions::new().write(true).open(path)?;
+                // Drop any inconsistent suffix left by an interrupted save
+                delta_file.set_len(deltas.valid_len)?;
+                delta_file.seek(SeekFrom::End(0))?;
+                delta_file.write_all(&record)?;
+                delta_file.sync_data()
+            } else {
+                // Start the deltas of this checkpoint in a new file
+                let tmp_path = self.context_dir.join(TMP_DELTA_FILE_NAME);
+                {
+                    let mut delta_file = OpenOptions::new()
+                        .write(true)
+                        .create_new(true)
+                        .open(tmp_path.clone())?;
+                    let header = DeltaFileHeader {
+                        magic: MAGIC,
+                        version: FORMAT_VERSION,
+                        checkpoint,
+                    };
+                    delta_file.write_all(&header.serialize_to_vec())?;
+                    delta_file.write_all(&record)?;
+                    delta_file.sync_all()?;
+                }
+                std::fs::rename(tmp_path, path)
+            }
+        }
     }
 
     impl Default for FsShieldedUtils {
@@ -3572,70 +5289,298 @@ pub mod fs {
     #[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
     impl ShieldedUtils for FsShieldedUtils {
         fn local_tx_prover(&self) -> LocalTxProver {
-            if let Ok(params_dir) = env::var(ENV_VAR_MASP_PARAMS_DIR) {
-                let params_dir = PathBuf::from(params_dir);
-                let spend_path = params_dir.join(SPEND_NAME);
-                let convert_path = params_dir.join(CONVERT_NAME);
-                let output_path = params_dir.join(OUTPUT_NAME);
-                LocalTxProver::new(&spend_path, &output_path, &convert_path)
-            } else {
-                LocalTxProver::with_default_location()
-                    .expect("unable to load MASP Parameters")
-            }
+            load_local_tx_prover()
         }
 
         /// Try to load the last saved shielded context from the given context
         /// directory. If this fails, then leave the current context unchanged.
-        async fn load<U: ShieldedUtils + MaybeSend>(
+        /// If the saved context turns out to be corrupt, then load the last
+        /// consistent state that can be recovered from it.
+        async fn load<U: ShieldedUtils + MaybeSend + MaybeSync>(
             &self,
             ctx: &mut ShieldedContext<U>,
         ) -> std::io::Result<()> {
-            // Try to load shielded context from file
-            let mut ctx_file = File::open(self.context_dir.join(FILE_NAME))?;
-            let mut bytes = Vec::new();
-            ctx_file.read_to_end(&mut bytes)?;
+            // Try to load the last checkpoint, falling back to the previous
+            // one if it is missing or corrupt
+            let mut checkpoint_path = self.context_dir.join(FILE_NAME);
+            let (header, mut new_ctx) =
+                match read_checkpoint::<U>(&checkpoint_path) {
+                    Ok(checkpoint) => checkpoint,
+                    Err(err) => {
+                        checkpoint_path = self.context_dir.join(PREV_FILE_NAME);
+                        read_checkpoint::<U>(&checkpoint_path)
+                            .map_err(|_| err)?
+                    }
+                };
+            let mut needs_checkpoint =
+                checkpoint_path != self.context_dir.join(FILE_NAME);
+            match header {
+                Some(header) => {
+                    // Replay the consistent deltas on top of the checkpoint
+                    let deltas = read_deltas(
+                        &self.context_dir.join(DELTA_FILE_NAME),
+                        header.id,
+                    )?;
+                    needs_checkpoint |= deltas.corrupt;
+                    let applied = replay_deltas(
+                        &mut new_ctx,
+                        replayable(&deltas.records),
+                    );
+                    if applied < deltas.records.len() {
+                        // A record could not be applied, so go back to the
+                        // state before it
+                        needs_checkpoint = true;
+                        new_ctx = read_checkpoint::<U>(&checkpoint_path)?.1;
+                        replay_deltas(
+                            &mut new_ctx,
+                            replayable(&deltas.records[..applied]),
+                        );
+                    }
+                }
+                // Rewrite contexts saved before the files were versioned
+                None => needs_checkpoint = true,
+            }
             // Fill the supplied context with the deserialized object
             *ctx = ShieldedContext {
                 utils: ctx.utils.clone(),
-                ..ShieldedContext::<U>::deserialize(&mut &bytes[..])?
+                journal: ShieldedJournal {
+                    needs_checkpoint,
+                    ..ShieldedJournal::new(new_ctx.last_indexed)
+                },
+                ..new_ctx
             };
             Ok(())
         }
 
-        /// Save this shielded context into its associated context directory
+        /// Save this shielded context into its associated context directory.
+        /// Only the transactions scanned since the last save are written,
+        /// unless a new checkpoint is due.
         async fn save<U: ShieldedUtils + MaybeSync>(
             &self,
             ctx: &ShieldedContext<U>,
         ) -> std::io::Result<()> {
-            // TODO: use mktemp crate?
-            let tmp_path = self.context_dir.join(TMP_FILE_NAME);
-            {
-                // First serialize the shielded context into a temporary file.
-                // Inability to create this file implies a simultaneuous write
-                // is in progress. In this case, immediately
-                // fail. This is unproblematic because the data
-                // intended to be stored can always be re-fetched
-                // from the blockchain.
-                let mut ctx_file = OpenOptions::new()
-                    .write(true)
-                    .create_new(true)
-                    .open(tmp_path.clone())?;
-                let mut bytes = Vec::new();
-                ctx.serialize(&mut bytes)
-                    .expect("cannot serialize shielded context");
-                ctx_file.write_all(&bytes[..])?;
+            let current =
+                read_checkpoint_header(&self.context_dir.join(FILE_NAME)).ok();
+            if let Some(current) = &current {
+                let deltas = read_deltas(
+                    &self.context_dir.join(DELTA_FILE_NAME),
+                    current.id,
+                )?;
+                let persisted = deltas.last_indexed().or(current.last_indexed);
+                let compaction_due = deltas.records.len()
+                    >= MAX_DELTAS_PER_CHECKPOINT
+                    || deltas.valid_len
+                        >= current.len.max(MIN_DELTAS_LEN_TO_COMPACT);
+                if !compaction_due && current.keys == viewing_keys_digest(ctx) {
+                    if let Some(scanned) = ctx.journal.scanned_after(persisted)
+                    {
+                        return self.append_delta(current.id, &deltas, scanned);
+                    }
+                }
             }
-            // Atomically update the old shielded context file with new data.
-            // Atomicity is required to prevent other client instances from
-            // reading corrupt data.
-            std::fs::rename(
-                tmp_path.clone(),
-                self.context_dir.join(FILE_NAME),
-            )?;
-            // Finally, remove our temporary file to allow future saving of
-            // shielded contexts.
-            std::fs::remove_file(tmp_path)?;
-            Ok(())
+            // Number the new checkpoint after the last one that was written
+            let id = current
+                .or_else(|| {
+                    read_checkpoint_header(
+                        &self.context_dir.join(PREV_FILE_NAME),
+                    )
+                    .ok()
+                })
+                .map_or(0, |header| header.id + 1);
+            self.write_checkpoint(ctx, id)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use namada_core::types::address::nam;
+
+        use super::*;
+
+        fn test_context(dir: &Path) -> ShieldedContext<FsShieldedUtils> {
+            ShieldedContext {
+                utils: FsShieldedUtils {
+                    context_dir: dir.to_path_buf(),
+                },
+                ..Default::default()
+            }
+        }
+
+        async fn load_context(dir: &Path) -> ShieldedContext<FsShieldedUtils> {
+            let mut ctx = test_context(dir);
+            ctx.load().await.expect("failed to load shielded context");
+            ctx
+        }
+
+        fn indexed_tx(height: u64) -> IndexedTx {
+            IndexedTx {
+                height: BlockHeight(height),
+                index: TxIndex(0),
+            }
+        }
+
+        /// Scan a compact shielded transfer at the given height
+        fn scan(ctx: &mut ShieldedContext<FsShieldedUtils>, height: u64) {
+            let compact_tx = CompactTx {
+                index: TxIndex(0),
+                changed_balance_keys: BTreeSet::new(),
+                outputs: vec![],
+                nullifiers: vec![],
+                vin: vec![],
+                vout: vec![],
+            };
+            ctx.scan_compact_tx(
+                indexed_tx(height),
+                Epoch(0),
+                &compact_tx,
+                nam(),
+            )
+            .expect("failed to scan tx");
+        }
+
+        /// Test that saving a loaded context only appends deltas
+        #[tokio::test]
+        async fn test_save_deltas() {
+            let dir = tempfile::tempdir().unwrap();
+            let mut ctx = test_context(dir.path());
+            scan(&mut ctx, 1);
+            ctx.save().await.unwrap();
+            let checkpoint = std::fs::read(dir.path().join(FILE_NAME)).unwrap();
+            assert!(!dir.path().join(DELTA_FILE_NAME).exists());
+
+            let mut ctx = load_context(dir.path()).await;
+            scan(&mut ctx, 2);
+            ctx.save().await.unwrap();
+            scan(&mut ctx, 3);
+            ctx.save().await.unwrap();
+            // Saving again without scanning changes nothing
+            ctx.save().await.unwrap();
+
+            assert_eq!(
+                std::fs::read(dir.path().join(FILE_NAME)).unwrap(),
+                checkpoint
+            );
+            let deltas =
+                read_deltas(&dir.path().join(DELTA_FILE_NAME), 0).unwrap();
+            assert_eq!(deltas.records.len(), 2);
+            assert!(!deltas.corrupt);
+
+            let ctx = load_context(dir.path()).await;
+            assert_eq!(ctx.last_indexed, Some(indexed_tx(3)));
+            assert_eq!(ctx.delta_map.len(), 3);
+            assert!(!ctx.journal.needs_checkpoint);
+        }
+
+        /// Test that the scan checkpoints are recorded before the first tx of
+        /// each range of heights, and that they are restored from the deltas
+        #[tokio::test]
+        async fn test_scan_checkpoints() {
+            let dir = tempfile::tempdir().unwrap();
+            let mut ctx = test_context(dir.path());
+            scan(&mut ctx, 1);
+            ctx.save().await.unwrap();
+
+            let mut ctx = load_context(dir.path()).await;
+            scan(&mut ctx, 5);
+            scan(&mut ctx, SCAN_CHECKPOINT_INTERVAL + 3);
+            scan(&mut ctx, SCAN_CHECKPOINT_INTERVAL + 4);
+            scan(&mut ctx, 3 * SCAN_CHECKPOINT_INTERVAL);
+            ctx.save().await.unwrap();
+
+            let ctx = load_context(dir.path()).await;
+            let checkpoints: Vec<_> = ctx
+                .scan_checkpoints
+                .iter()
+                .map(|(height, checkpoint)| (height.0, checkpoint.last_indexed))
+                .collect();
+            assert_eq!(
+                checkpoints,
+                vec![
+                    (0, None),
+                    (SCAN_CHECKPOINT_INTERVAL, Some(indexed_tx(5))),
+                    (
+                        3 * SCAN_CHECKPOINT_INTERVAL,
+                        Some(indexed_tx(SCAN_CHECKPOINT_INTERVAL + 4))
+                    ),
+                ]
+            );
+        }
+
+        /// Test that enough deltas get compacted into a new checkpoint
+        #[tokio::test]
+        async fn test_compaction() {
+            let dir = tempfile::tempdir().unwrap();
+            let mut ctx = test_context(dir.path());
+            ctx.save().await.unwrap();
+
+            let mut ctx = load_context(dir.path()).await;
+            let deltas = MAX_DELTAS_PER_CHECKPOINT as u64;
+            for height in 1..=deltas {
+                scan(&mut ctx, height);
+                ctx.save().await.unwrap();
+            }
+            let header =
+                read_checkpoint_header(&dir.path().join(FILE_NAME)).unwrap();
+            assert_eq!(header.id, 0);
+
+            scan(&mut ctx, deltas + 1);
+            ctx.save().await.unwrap();
+            let header =
+                read_checkpoint_header(&dir.path().join(FILE_NAME)).unwrap();
+            assert_eq!(header.id, 1);
+            assert_eq!(header.last_indexed, Some(indexed_tx(deltas + 1)));
+            assert!(dir.path().join(PREV_FILE_NAME).exists());
+
+            let ctx = load_context(dir.path()).await;
+            assert_eq!(ctx.last_indexed, Some(indexed_tx(deltas + 1)));
+            assert_eq!(ctx.delta_map.len() as u64, deltas + 1);
+        }
+
+        /// Test that corrupt saves are recovered to their last consistent
+        /// state
+        #[tokio::test]
+        async fn test_corruption_recovery() {
+            let dir = tempfile::tempdir().unwrap();
+            let delta_path = dir.path().join(DELTA_FILE_NAME);
+            let mut ctx = test_context(dir.path());
+            scan(&mut ctx, 1);
+            ctx.save().await.unwrap();
+            let mut ctx = load_context(dir.path()).await;
+            scan(&mut ctx, 2);
+            ctx.save().await.unwrap();
+            scan(&mut ctx, 3);
+            ctx.save().await.unwrap();
+
+            // Garbage after the last delta is ignored
+            let mut deltas = std::fs::read(&delta_path).unwrap();
+            let len = deltas.len();
+            deltas.extend([0xff; 10]);
+            std::fs::write(&delta_path, &deltas).unwrap();
+            let ctx = load_context(dir.path()).await;
+            assert_eq!(ctx.last_indexed, Some(indexed_tx(3)));
+            assert!(ctx.journal.needs_checkpoint);
+
+            // A truncated delta is dropped
+            std::fs::write(&delta_path, &deltas[..len - 1]).unwrap();
+            let ctx = load_context(dir.path()).await;
+            assert_eq!(ctx.last_indexed, Some(indexed_tx(2)));
+            assert!(ctx.journal.needs_checkpoint);
+
+            // The recovered context is saved as a new checkpoint
+            ctx.save().await.unwrap();
+            let header =
+                read_checkpoint_header(&dir.path().join(FILE_NAME)).unwrap();
+            assert_eq!(header.id, 1);
+
+            // A corrupt checkpoint falls back to the previous one and its
+            // deltas
+            let mut checkpoint =
+                std::fs::read(dir.path().join(FILE_NAME)).unwrap();
+            *checkpoint.last_mut().unwrap() ^= 0xff;
+            std::fs::write(dir.path().join(FILE_NAME), checkpoint).unwrap();
+            let ctx = load_context(dir.path()).await;
+            assert_eq!(ctx.last_indexed, Some(indexed_tx(2)));
+            assert!(ctx.journal.needs_checkpoint);
         }
     }
 }
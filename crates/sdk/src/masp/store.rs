@@ -0,0 +1,696 @@
+//! Storage backends of the shielded context.
+//!
+//! A [`ContextStore`] is a key-value store of blobs, like a directory of
+//! files, a table of a sqlite database or an object store of the IndexedDB of
+//! a browser. [`StoreShieldedUtils`] persists a shielded context on top of
+//! any such store, as a checkpoint of the full context followed by deltas
+//! that record the transactions scanned since the checkpoint, one blob per
+//! save. Wallets can thus persist their sync state natively on their platform,
+//! instead of serializing the whole context through their FFI.
+
+use super::*;
+
+/// The key of the checkpoint
+const CHECKPOINT_KEY: &str = "checkpoint";
+/// The prefix of the keys of the deltas
+const DELTA_KEY_PREFIX: &str = "delta.";
+/// The version of the format of the saved blobs
+const FORMAT_VERSION: u32 = 3;
+/// The number of deltas after which the next save writes a new checkpoint
+const MAX_DELTAS_PER_CHECKPOINT: usize = 32;
+
+/// A key-value store of blobs that a shielded context can be saved to. The
+/// writes of single keys must be atomic.
+#[cfg_attr(feature = "async-send", async_trait::async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+pub trait ContextStore: Sized + Default + Clone {
+    /// Read the blob of the given key, if any
+    async fn get(&self, key: &str) -> std::io::Result<Option<Vec<u8>>>;
+
+    /// Write the blob of the given key, replacing any previous one
+    async fn put(&self, key: &str, value: Vec<u8>) -> std::io::Result<()>;
+
+    /// Delete the blob of the given key, if any
+    async fn delete(&self, key: &str) -> std::io::Result<()>;
+
+    /// List the keys with the given prefix, in ascending order
+    async fn keys(&self, prefix: &str) -> std::io::Result<Vec<String>>;
+}
+
+/// The header of a checkpoint, followed by the serialized context
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+struct CheckpointHeader {
+    version: u32,
+    /// The identifier of this checkpoint
+    id: u64,
+    /// The last indexed transaction of the saved context
+    last_indexed: Option<IndexedTx>,
+    /// The digest of the viewing keys of the saved context
+    keys: [u8; 32],
+    /// The hash of the serialized context
+    checksum: [u8; 32],
+}
+
+/// The header of a delta, followed by the serialized scanned txs
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+struct DeltaHeader {
+    /// The last scanned transaction of the delta
+    last_indexed: IndexedTx,
+    /// The hash of the serialized scanned txs
+    checksum: [u8; 32],
+}
+
+/// The prefix of the keys of the deltas of the given checkpoint
+fn delta_prefix(checkpoint: u64) -> String {
+    format!("{DELTA_KEY_PREFIX}{checkpoint:020}.")
+}
+
+/// The key of the delta with the given sequence number on top of the given
+/// checkpoint
+fn delta_key(checkpoint: u64, seq: usize) -> String {
+    format!("{}{seq:010}", delta_prefix(checkpoint))
+}
+
+/// The checkpoint of the delta with the given key
+fn delta_checkpoint(key: &str) -> Option<u64> {
+    key.strip_prefix(DELTA_KEY_PREFIX)?.get(..20)?.parse().ok()
+}
+
+/// Split the given blob into its header and its payload
+fn decode<H: BorshDeserialize>(bytes: &[u8]) -> std::io::Result<(H, &[u8])> {
+    let mut rest = bytes;
+    let header = H::deserialize(&mut rest)?;
+    Ok((header, rest))
+}
+
+/// Join the given header and payload into a blob
+fn encode<H: BorshSerialize>(header: &H, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = header.serialize_to_vec();
+    bytes.extend_from_slice(payload);
+    bytes
+}
+
+/// The given deltas, to replay them
+fn replayable(
+    records: &[(IndexedTx, Vec<u8>)],
+) -> impl Iterator<Item = (IndexedTx, &[u8])> {
+    records
+        .iter()
+        .map(|(last_indexed, payload)| (*last_indexed, &payload[..]))
+}
+
+/// An implementation of ShieldedUtils that saves the context into the given
+/// store. The MASP parameters are loaded like by
+/// [`FsShieldedUtils`](super::fs::FsShieldedUtils).
+#[derive(Debug, Clone, Default)]
+pub struct StoreShieldedUtils<S> {
+    /// The store that the context is saved into
+    pub store: S,
+}
+
+impl<S: ContextStore> StoreShieldedUtils<S> {
+    /// Initialize a shielded context that is saved into the given store
+    pub fn new(store: S) -> ShieldedContext<Self> {
+        ShieldedContext {
+            utils: Self { store },
+            ..Default::default()
+        }
+    }
+
+    /// The last transaction persisted by the delta with the given key, if it
+    /// is consistent
+    async fn delta_last_indexed(
+        &self,
+        key: &str,
+    ) -> std::io::Result<Option<IndexedTx>> {
+        let delta = self.store.get(key).await?;
+        Ok(delta.and_then(|bytes| {
+            let (header, payload) = decode::<DeltaHeader>(&bytes).ok()?;
+            (checksum(payload) == header.checksum)
+                .then_some(header.last_indexed)
+        }))
+    }
+}
+
+/// The store is never serialized with the context
+impl<S> BorshSerialize for StoreShieldedUtils<S> {
+    fn serialize<W: std::io::Write>(
+        &self,
+        _writer: &mut W,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<S: Default> BorshDeserialize for StoreShieldedUtils<S> {
+    fn deserialize_reader<R: std::io::Read>(
+        _reader: &mut R,
+    ) -> std::io::Result<Self> {
+        Ok(Self::default())
+    }
+}
+
+#[cfg_attr(feature = "async-send", async_trait::async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+impl<S> ShieldedUtils for StoreShieldedUtils<S>
+where
+    S: ContextStore + MaybeSend + MaybeSync,
+{
+    fn local_tx_prover(&self) -> LocalTxProver {
+        load_local_tx_prover()
+    }
+
+    /// Load the checkpoint and replay its consistent deltas on top of it. If a
+    /// delta is corrupt, then the deltas that follow it are dropped.
+    async fn load<U: ShieldedUtils + MaybeSend + MaybeSync>(
+        &self,
+        ctx: &mut ShieldedContext<U>,
+    ) -> std::io::Result<()> {
+        let bytes = self.store.get(CHECKPOINT_KEY).await?.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no saved shielded context",
+            )
+        })?;
+        let (header, payload) = decode::<CheckpointHeader>(&bytes)?;
+        if header.version != FORMAT_VERSION {
+            return Err(invalid_data("unsupported shielded context version"));
+        }
+        if checksum(payload) != header.checksum {
+            return Err(invalid_data("corrupt shielded context"));
+        }
+        let mut new_ctx = ShieldedContext::<U>::try_from_slice(payload)?;
+
+        let mut needs_checkpoint = false;
+        let mut records = vec![];
+        for key in self.store.keys(&delta_prefix(header.id)).await? {
+            let delta = self.store.get(&key).await?.unwrap_or_default();
+            match decode::<DeltaHeader>(&delta) {
+                Ok((delta_header, delta_payload))
+                    if checksum(delta_payload) == delta_header.checksum =>
+                {
+                    records.push((
+                        delta_header.last_indexed,
+                        delta_payload.to_vec(),
+                    ));
+                }
+                _ => {
+                    needs_checkpoint = true;
+                    break;
+                }
+            }
+        }
+        let applied = replay_deltas(&mut new_ctx, replayable(&records));
+        if applied < records.len() {
+            // A delta could not be applied, so go back to the state before it
+            needs_checkpoint = true;
+            new_ctx = ShieldedContext::<U>::try_from_slice(payload)?;
+            replay_deltas(&mut new_ctx, replayable(&records[..applied]));
+        }
+
+        *ctx = ShieldedContext {
+            utils: ctx.utils.clone(),
+            journal: ShieldedJournal {
+                needs_checkpoint,
+                ..ShieldedJournal::new(new_ctx.last_indexed)
+            },
+            ..new_ctx
+        };
+        Ok(())
+    }
+
+    /// Save the transactions scanned since the last save as a new delta,
+    /// unless a new checkpoint is due
+    async fn save<U: ShieldedUtils + MaybeSync>(
+        &self,
+        ctx: &ShieldedContext<U>,
+    ) -> std::io::Result<()> {
+        let current = self
+            .store
+            .get(CHECKPOINT_KEY)
+            .await?
+            .and_then(|bytes| decode::<CheckpointHeader>(&bytes).ok())
+            .map(|(header, _payload)| header);
+        let all_deltas = self.store.keys(DELTA_KEY_PREFIX).await?;
+
+        if let Some(current) = &current {
+            let prefix = delta_prefix(current.id);
+            let deltas: Vec<_> = all_deltas
+                .iter()
+                .filter(|key| key.starts_with(&prefix))
+                .collect();
+            if deltas.len() < MAX_DELTAS_PER_CHECKPOINT
+                && current.keys == viewing_keys_digest(ctx)
+            {
+                // The last persisted tx, unless the last delta is corrupt
+                let persisted = match deltas.last() {
+                    Some(key) => self.delta_last_indexed(key).await?.map(Some),
+                    None => Some(current.last_indexed),
+                };
+                if let Some(scanned) = persisted
+                    .and_then(|persisted| ctx.journal.scanned_after(persisted))
+                {
+                    let Some(last) = scanned.last() else {
+                        return Ok(());
+                    };
+                    let payload = borsh::to_vec(scanned)?;
+                    let header = DeltaHeader {
+                        last_indexed: last.indexed_tx,
+                        checksum: checksum(&payload),
+                    };
+                    return self
+                        .store
+                        .put(
+                            &delta_key(current.id, deltas.len()),
+                            encode(&header, &payload),
+                        )
+                        .await;
+                }
+            }
+        }
+
+        // Number the new checkpoint after any that was written, even if its
+        // header is corrupt, so that its stale deltas don't apply to it
+        let id = all_deltas
+            .iter()
+            .filter_map(|key| delta_checkpoint(key))
+            .chain(current.map(|header| header.id))
+            .max()
+            .map_or(0, |id| id + 1);
+        let payload = ctx.serialize_to_vec();
+        let header = CheckpointHeader {
+            version: FORMAT_VERSION,
+            id,
+            last_indexed: ctx.last_indexed,
+            keys: viewing_keys_digest(ctx),
+            checksum: checksum(&payload),
+        };
+        self.store
+            .put(CHECKPOINT_KEY, encode(&header, &payload))
+            .await?;
+        // The deltas of the replaced checkpoints are obsolete
+        for key in all_deltas {
+            self.store.delete(&key).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+pub use dir::DirStore;
+
+#[cfg(feature = "std")]
+mod dir {
+    use std::fs::OpenOptions;
+    use std::io::{ErrorKind, Write};
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// The suffix of the files being written
+    const TMP_SUFFIX: &str = ".tmp";
+
+    /// A store of blobs as the files of a directory, named after their keys
+    #[derive(Debug, Clone)]
+    pub struct DirStore {
+        dir: PathBuf,
+    }
+
+    impl DirStore {
+        /// A store in the given directory
+        pub fn new(dir: PathBuf) -> Self {
+            Self { dir }
+        }
+    }
+
+    impl Default for DirStore {
+        fn default() -> Self {
+            Self::new(PathBuf::from("shielded"))
+        }
+    }
+
+    #[cfg_attr(feature = "async-send", async_trait::async_trait)]
+    #[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+    impl ContextStore for DirStore {
+        async fn get(&self, key: &str) -> std::io::Result<Option<Vec<u8>>> {
+            match std::fs::read(self.dir.join(key)) {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+                Err(err) => Err(err),
+            }
+        }
+
+        async fn put(&self, key: &str, value: Vec<u8>) -> std::io::Result<()> {
+            std::fs::create_dir_all(&self.dir)?;
+            // Write a temporary file first, failing if a simultaneous write
+            // is in progress, then atomically move it to the key
+            let tmp_path = self.dir.join(format!("{key}{TMP_SUFFIX}"));
+            {
+                let mut file = OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&tmp_path)?;
+                file.write_all(&value)?;
+                file.sync_all()?;
+            }
+            std::fs::rename(tmp_path, self.dir.join(key))
+        }
+
+        async fn delete(&self, key: &str) -> std::io::Result<()> {
+            match std::fs::remove_file(self.dir.join(key)) {
+                Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+                _ => Ok(()),
+            }
+        }
+
+        async fn keys(&self, prefix: &str) -> std::io::Result<Vec<String>> {
+            let entries = match std::fs::read_dir(&self.dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == ErrorKind::NotFound => {
+                    return Ok(vec![]);
+                }
+                Err(err) => return Err(err),
+            };
+            let mut keys = vec![];
+            for entry in entries {
+                let name = entry?.file_name();
+                let Some(name) = name.to_str() else {
+                    continue;
+                };
+                if name.starts_with(prefix) && !name.ends_with(TMP_SUFFIX) {
+                    keys.push(name.to_string());
+                }
+            }
+            keys.sort();
+            Ok(keys)
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::path::PathBuf;
+
+    use rusqlite::{params, Connection, OptionalExtension};
+
+    use super::*;
+
+    fn sqlite_error(err: rusqlite::Error) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::Other, err)
+    }
+
+    /// A store of blobs in a table of a sqlite database
+    #[derive(Debug, Clone)]
+    pub struct SqliteStore {
+        path: PathBuf,
+    }
+
+    impl SqliteStore {
+        /// A store in the database at the given path, created if missing
+        pub fn new(path: PathBuf) -> Self {
+            Self { path }
+        }
+
+        fn connect(&self) -> std::io::Result<Connection> {
+            let conn = Connection::open(&self.path).map_err(sqlite_error)?;
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS shielded_context (key TEXT \
+                 PRIMARY KEY, value BLOB NOT NULL)",
+                [],
+            )
+            .map_err(sqlite_error)?;
+            Ok(conn)
+        }
+    }
+
+    impl Default for SqliteStore {
+        fn default() -> Self {
+            Self::new(PathBuf::from("shielded.sqlite"))
+        }
+    }
+
+    #[cfg_attr(feature = "async-send", async_trait::async_trait)]
+    #[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+    impl ContextStore for SqliteStore {
+        async fn get(&self, key: &str) -> std::io::Result<Option<Vec<u8>>> {
+            self.connect()?
+                .query_row(
+                    "SELECT value FROM shielded_context WHERE key = ?1",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(sqlite_error)
+        }
+
+        async fn put(&self, key: &str, value: Vec<u8>) -> std::io::Result<()> {
+            self.connect()?
+                .execute(
+                    "INSERT OR REPLACE INTO shielded_context (key, value) \
+                     VALUES (?1, ?2)",
+                    params![key, value],
+                )
+                .map(|_rows| ())
+                .map_err(sqlite_error)
+        }
+
+        async fn delete(&self, key: &str) -> std::io::Result<()> {
+            self.connect()?
+                .execute(
+                    "DELETE FROM shielded_context WHERE key = ?1",
+                    params![key],
+                )
+                .map(|_rows| ())
+                .map_err(sqlite_error)
+        }
+
+        async fn keys(&self, prefix: &str) -> std::io::Result<Vec<String>> {
+            let conn = self.connect()?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT key FROM shielded_context WHERE substr(key, 1, \
+                     length(?1)) = ?1 ORDER BY key",
+                )
+                .map_err(sqlite_error)?;
+            let keys = stmt
+                .query_map(params![prefix], |row| row.get(0))
+                .map_err(sqlite_error)?
+                .collect::<Result<Vec<String>, _>>()
+                .map_err(sqlite_error)?;
+            Ok(keys)
+        }
+    }
+}
+
+#[cfg(all(target_family = "wasm", feature = "indexed-db"))]
+pub use indexed_db::IndexedDbStore;
+
+#[cfg(all(target_family = "wasm", feature = "indexed-db"))]
+mod indexed_db {
+    use js_sys::Uint8Array;
+    use rexie::{KeyRange, ObjectStore, Rexie, TransactionMode};
+    use wasm_bindgen::JsValue;
+
+    use super::*;
+
+    /// The name of the object store of the blobs
+    const OBJECT_STORE: &str = "shielded_context";
+
+    fn idb_error(err: rexie::Error) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::Other, err.to_string())
+    }
+
+    /// A store of blobs in an object store of the IndexedDB of a browser
+    #[derive(Debug, Clone)]
+    pub struct IndexedDbStore {
+        db_name: String,
+    }
+
+    impl IndexedDbStore {
+        /// A store in the database with the given name, created if missing
+        pub fn new(db_name: impl Into<String>) -> Self {
+            Self {
+                db_name: db_name.into(),
+            }
+        }
+
+        async fn open(&self) -> std::io::Result<Rexie> {
+            Rexie::builder(&self.db_name)
+                .version(1)
+                .add_object_store(ObjectStore::new(OBJECT_STORE))
+                .build()
+                .await
+                .map_err(idb_error)
+        }
+    }
+
+    impl Default for IndexedDbStore {
+        fn default() -> Self {
+            Self::new("namada-shielded")
+        }
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl ContextStore for IndexedDbStore {
+        async fn get(&self, key: &str) -> std::io::Result<Option<Vec<u8>>> {
+            let db = self.open().await?;
+            let tx = db
+                .transaction(&[OBJECT_STORE], TransactionMode::ReadOnly)
+                .map_err(idb_error)?;
+            let store = tx.store(OBJECT_STORE).map_err(idb_error)?;
+            let value = store
+                .get(&JsValue::from_str(key))
+                .await
+                .map_err(idb_error)?;
+            tx.done().await.map_err(idb_error)?;
+            Ok((!value.is_undefined())
+                .then(|| Uint8Array::new(&value).to_vec()))
+        }
+
+        async fn put(&self, key: &str, value: Vec<u8>) -> std::io::Result<()> {
+            let db = self.open().await?;
+            let tx = db
+                .transaction(&[OBJECT_STORE], TransactionMode::ReadWrite)
+                .map_err(idb_error)?;
+            let store = tx.store(OBJECT_STORE).map_err(idb_error)?;
+            let value = Uint8Array::from(&value[..]);
+            store
+                .put(&value.into(), Some(&JsValue::from_str(key)))
+                .await
+                .map_err(idb_error)?;
+            tx.done().await.map_err(idb_error)
+        }
+
+        async fn delete(&self, key: &str) -> std::io::Result<()> {
+            let db = self.open().await?;
+            let tx = db
+                .transaction(&[OBJECT_STORE], TransactionMode::ReadWrite)
+                .map_err(idb_error)?;
+            let store = tx.store(OBJECT_STORE).map_err(idb_error)?;
+            store
+                .delete(&JsValue::from_str(key))
+                .await
+                .map_err(idb_error)?;
+            tx.done().await.map_err(idb_error)
+        }
+
+        async fn keys(&self, prefix: &str) -> std::io::Result<Vec<String>> {
+            let db = self.open().await?;
+            let tx = db
+                .transaction(&[OBJECT_STORE], TransactionMode::ReadOnly)
+                .map_err(idb_error)?;
+            let store = tx.store(OBJECT_STORE).map_err(idb_error)?;
+            // The keys with the prefix are the ones between the prefix and
+            // the prefix followed by the greatest code point
+            let range = KeyRange::bound(
+                &JsValue::from_str(prefix),
+                &JsValue::from_str(&format!("{prefix}\u{10ffff}")),
+                false,
+                false,
+            )
+            .map_err(idb_error)?;
+            let keys = store
+                .get_all_keys(Some(&range), None)
+                .await
+                .map_err(idb_error)?;
+            tx.done().await.map_err(idb_error)?;
+            let mut keys: Vec<String> =
+                keys.into_iter().filter_map(|key| key.as_string()).collect();
+            keys.sort();
+            Ok(keys)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use namada_core::types::address::nam;
+
+    use super::*;
+
+    type Utils = StoreShieldedUtils<DirStore>;
+
+    async fn load_context(store: &DirStore) -> ShieldedContext<Utils> {
+        let mut ctx = Utils::new(store.clone());
+        ctx.load().await.expect("failed to load shielded context");
+        ctx
+    }
+
+    fn indexed_tx(height: u64) -> IndexedTx {
+        IndexedTx {
+            height: BlockHeight(height),
+            index: TxIndex(0),
+        }
+    }
+
+    /// Scan a compact shielded transfer at the given height
+    fn scan(ctx: &mut ShieldedContext<Utils>, height: u64) {
+        let compact_tx = CompactTx {
+            index: TxIndex(0),
+            changed_balance_keys: BTreeSet::new(),
+            outputs: vec![],
+            nullifiers: vec![],
+            vin: vec![],
+            vout: vec![],
+        };
+        ctx.scan_compact_tx(indexed_tx(height), Epoch(0), &compact_tx, nam())
+            .expect("failed to scan tx");
+    }
+
+    /// Test that the context is saved as a checkpoint followed by deltas,
+    /// which are compacted into a new checkpoint once there are enough of
+    /// them, and that corrupt deltas are dropped.
+    #[tokio::test]
+    async fn test_store_checkpoint_and_deltas() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DirStore::new(dir.path().to_path_buf());
+        let mut ctx = Utils::new(store.clone());
+        assert!(ctx.load().await.is_err());
+        scan(&mut ctx, 1);
+        ctx.save().await.unwrap();
+        assert_eq!(store.keys("").await.unwrap(), vec![CHECKPOINT_KEY]);
+
+        let mut ctx = load_context(&store).await;
+        scan(&mut ctx, 2);
+        ctx.save().await.unwrap();
+        scan(&mut ctx, 3);
+        ctx.save().await.unwrap();
+        // Saving again without scanning changes nothing
+        ctx.save().await.unwrap();
+        let deltas = store.keys(&delta_prefix(0)).await.unwrap();
+        assert_eq!(deltas, vec![delta_key(0, 0), delta_key(0, 1)]);
+
+        let ctx = load_context(&store).await;
+        assert_eq!(ctx.last_indexed, Some(indexed_tx(3)));
+        assert_eq!(ctx.delta_map.len(), 3);
+        assert!(!ctx.journal.needs_checkpoint);
+
+        // A corrupt delta is dropped with the ones that follow it
+        let mut delta = store.get(&deltas[0]).await.unwrap().unwrap();
+        *delta.last_mut().unwrap() ^= 0xff;
+        store.put(&deltas[0], delta).await.unwrap();
+        let ctx = load_context(&store).await;
+        assert_eq!(ctx.last_indexed, Some(indexed_tx(1)));
+        assert!(ctx.journal.needs_checkpoint);
+
+        // The recovered context is saved as a new checkpoint
+        ctx.save().await.unwrap();
+        assert_eq!(store.keys(DELTA_KEY_PREFIX).await.unwrap().len(), 0);
+        let mut ctx = load_context(&store).await;
+        assert!(!ctx.journal.needs_checkpoint);
+        let last_height = MAX_DELTAS_PER_CHECKPOINT as u64 + 3;
+        for height in 2..=last_height {
+            scan(&mut ctx, height);
+            ctx.save().await.unwrap();
+        }
+        let deltas = store.keys(DELTA_KEY_PREFIX).await.unwrap();
+        assert_eq!(deltas, vec![delta_key(2, 0)]);
+
+        let ctx = load_context(&store).await;
+        assert_eq!(ctx.last_indexed, Some(indexed_tx(last_height)));
+        assert_eq!(ctx.delta_map.len() as u64, last_height);
+    }
+}
@@ -0,0 +1,243 @@
+//! Memos encrypted to the public key of the recipient of a transfer.
+//!
+//! The memo of a tx is public. To attach e.g. an invoice to a transfer
+//! without exposing it on chain, the memo can be sealed in an
+//! [`EncryptedMemo`] envelope, in the style of ECIES: an ephemeral X25519 key
+//! is generated by the sender and agreed with the X25519 form of the ed25519
+//! key of the recipient, the encryption key is derived from the shared
+//! secret and the memo is encrypted with XChaCha20-Poly1305. Only the holder
+//! of the secret key of the recipient can open the envelope.
+//!
+//! The envelope is encoded in the memo field as [`ENCRYPTED_MEMO_PREFIX`]
+//! followed by its borsh encoding. It carries the hash of the public key of
+//! the recipient, so that a wallet can find the key to open it with.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use borsh_ext::BorshSerializeExt;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use namada_core::types::key::{common, PublicKeyHash, RefTo};
+use namada_tx::Memo;
+use orion::aead;
+use orion::hazardous::ecc::x25519;
+use sha2::{Digest, Sha256, Sha512};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// The prefix of the memos holding an [`EncryptedMemo`]
+pub const ENCRYPTED_MEMO_PREFIX: &[u8] = b"namada-encrypted-memo:";
+
+/// The version of the format of the encrypted memos
+pub const ENCRYPTED_MEMO_VERSION: u8 = 1;
+
+/// The domain separator of the derivation of the encryption key
+const KDF_DOMAIN: &[u8] = b"namada-encrypted-memo-key";
+
+/// Errors of the encryption and decryption of memos
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Memos can only be encrypted to ed25519 keys")]
+    UnsupportedKey,
+    #[error("Invalid public key of the recipient")]
+    InvalidPublicKey,
+    #[error("Unsupported encrypted memo version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Invalid encrypted memo: {0}")]
+    InvalidMemo(String),
+    #[error("The memo is encrypted to another key")]
+    WrongKey,
+    #[error("Failed to encrypt the memo")]
+    Encryption,
+    #[error("Failed to decrypt the memo")]
+    Decryption,
+}
+
+/// A memo encrypted to the public key of its recipient
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct EncryptedMemo {
+    /// The version of the format of the memo
+    pub version: u8,
+    /// The hash of the public key the memo is encrypted to
+    pub recipient: PublicKeyHash,
+    /// The ephemeral X25519 public key of the sender
+    pub ephemeral_pk: [u8; 32],
+    /// The nonce followed by the encrypted memo and its tag
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedMemo {
+    /// Encrypt the memo to the given public key of its recipient.
+    pub fn seal(
+        recipient: &common::PublicKey,
+        memo: &[u8],
+    ) -> Result<Self, Error> {
+        let recipient_pk = x25519_public_key(recipient)?;
+        let ephemeral_sk = x25519::PrivateKey::generate();
+        let ephemeral_pk = x25519::PublicKey::try_from(&ephemeral_sk)
+            .map_err(|_| Error::Encryption)?
+            .to_bytes();
+        let shared = x25519::key_agreement(&ephemeral_sk, &recipient_pk)
+            .map_err(|_| Error::InvalidPublicKey)?;
+        let key = encryption_key(
+            shared.unprotected_as_bytes(),
+            &ephemeral_pk,
+            &recipient_pk.to_bytes(),
+        )?;
+        let ciphertext =
+            aead::seal(&key, memo).map_err(|_| Error::Encryption)?;
+        Ok(Self {
+            version: ENCRYPTED_MEMO_VERSION,
+            recipient: recipient.into(),
+            ephemeral_pk,
+            ciphertext,
+        })
+    }
+
+    /// Decrypt the memo with the secret key of its recipient.
+    pub fn open(&self, sk: &common::SecretKey) -> Result<Vec<u8>, Error> {
+        if self.version != ENCRYPTED_MEMO_VERSION {
+            return Err(Error::UnsupportedVersion(self.version));
+        }
+        let pk = sk.ref_to();
+        if PublicKeyHash::from(&pk) != self.recipient {
+            return Err(Error::WrongKey);
+        }
+        let recipient_sk = x25519_secret_key(sk)?;
+        let recipient_pk = x25519_public_key(&pk)?;
+        let ephemeral_pk = x25519::PublicKey::from(self.ephemeral_pk);
+        let shared = x25519::key_agreement(&recipient_sk, &ephemeral_pk)
+            .map_err(|_| Error::Decryption)?;
+        let key = encryption_key(
+            shared.unprotected_as_bytes(),
+            &self.ephemeral_pk,
+            &recipient_pk.to_bytes(),
+        )?;
+        aead::open(&key, &self.ciphertext).map_err(|_| Error::Decryption)
+    }
+
+    /// Encode the envelope in a memo.
+    pub fn to_memo(&self) -> Memo {
+        [ENCRYPTED_MEMO_PREFIX, &self.serialize_to_vec()].concat()
+    }
+
+    /// Decode the envelope from a memo. Returns `Ok(None)` if the memo isn't
+    /// encrypted.
+    pub fn from_memo(memo: &[u8]) -> Result<Option<Self>, Error> {
+        let Some(bytes) = memo.strip_prefix(ENCRYPTED_MEMO_PREFIX) else {
+            return Ok(None);
+        };
+        Self::try_from_slice(bytes)
+            .map(Some)
+            .map_err(|err| Error::InvalidMemo(err.to_string()))
+    }
+}
+
+/// Encrypt the memo to the given public key and encode it in a memo.
+pub fn encrypt_memo(
+    recipient: &common::PublicKey,
+    memo: &[u8],
+) -> Result<Memo, Error> {
+    EncryptedMemo::seal(recipient, memo).map(|memo| memo.to_memo())
+}
+
+/// Decrypt the memo with the given secret key if it's encrypted, otherwise
+/// return it as is.
+pub fn decrypt_memo(
+    sk: &common::SecretKey,
+    memo: &[u8],
+) -> Result<Vec<u8>, Error> {
+    match EncryptedMemo::from_memo(memo)? {
+        Some(encrypted) => encrypted.open(sk),
+        None => Ok(memo.to_vec()),
+    }
+}
+
+/// The X25519 form of an ed25519 public key
+fn x25519_public_key(
+    pk: &common::PublicKey,
+) -> Result<x25519::PublicKey, Error> {
+    let common::PublicKey::Ed25519(pk) = pk else {
+        return Err(Error::UnsupportedKey);
+    };
+    let point = CompressedEdwardsY(pk.0.to_bytes())
+        .decompress()
+        .ok_or(Error::InvalidPublicKey)?;
+    Ok(x25519::PublicKey::from(point.to_montgomery().to_bytes()))
+}
+
+/// The X25519 form of an ed25519 secret key, i.e. the scalar derived from its
+/// seed
+fn x25519_secret_key(
+    sk: &common::SecretKey,
+) -> Result<x25519::PrivateKey, Error> {
+    let common::SecretKey::Ed25519(sk) = sk else {
+        return Err(Error::UnsupportedKey);
+    };
+    let mut scalar = Zeroizing::new([0u8; 32]);
+    scalar.copy_from_slice(&Sha512::digest(&sk.0.to_bytes())[..32]);
+    x25519::PrivateKey::from_slice(scalar.as_ref())
+        .map_err(|_| Error::Decryption)
+}
+
+/// Derive the encryption key from the shared secret and the public keys
+fn encryption_key(
+    shared: &[u8],
+    ephemeral_pk: &[u8; 32],
+    recipient_pk: &[u8; 32],
+) -> Result<aead::SecretKey, Error> {
+    let mut hasher = Sha256::new();
+    hasher.update(KDF_DOMAIN);
+    hasher.update(shared);
+    hasher.update(ephemeral_pk);
+    hasher.update(recipient_pk);
+    aead::SecretKey::from_slice(&hasher.finalize())
+        .map_err(|_| Error::Encryption)
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::key::secp256k1;
+    use namada_core::types::key::testing::{gen_keypair, keypair_1, keypair_2};
+
+    use super::*;
+
+    /// Test that an encrypted memo can only be opened by its recipient.
+    #[test]
+    fn test_encrypted_memo_roundtrip() {
+        let recipient = keypair_1();
+        let invoice = b"invoice #42: 100 NAM".to_vec();
+
+        let memo = encrypt_memo(&recipient.ref_to(), &invoice).unwrap();
+        assert!(memo.starts_with(ENCRYPTED_MEMO_PREFIX));
+        assert!(!memo.windows(invoice.len()).any(|bytes| bytes == invoice));
+        assert_eq!(decrypt_memo(&recipient, &memo).unwrap(), invoice);
+
+        // Another key can't open it
+        assert!(matches!(
+            decrypt_memo(&keypair_2(), &memo),
+            Err(Error::WrongKey)
+        ));
+        // Nor a tampered envelope
+        let mut encrypted = EncryptedMemo::from_memo(&memo).unwrap().unwrap();
+        *encrypted.ciphertext.last_mut().unwrap() ^= 1;
+        assert!(matches!(encrypted.open(&recipient), Err(Error::Decryption)));
+
+        // A plaintext memo is returned as is
+        assert_eq!(decrypt_memo(&recipient, &invoice).unwrap(), invoice);
+        // A truncated envelope is rejected
+        assert!(matches!(
+            decrypt_memo(&recipient, &memo[..memo.len() / 2]),
+            Err(Error::InvalidMemo(_))
+        ));
+    }
+
+    /// Test that memos can't be encrypted to secp256k1 keys.
+    #[test]
+    fn test_unsupported_key() {
+        let sk =
+            common::SecretKey::Secp256k1(gen_keypair::<secp256k1::SigScheme>());
+        assert!(matches!(
+            encrypt_memo(&sk.ref_to(), b"memo"),
+            Err(Error::UnsupportedKey)
+        ));
+    }
+}
@@ -7,8 +7,11 @@ use namada_state::{DBIter, StorageHasher, DB};
 pub use shell::Shell;
 use shell::SHELL;
 pub use types::{
-    EncodedResponseQuery, Error, RequestCtx, RequestQuery, ResponseQuery,
-    Router,
+    BalanceChange, BatchQueryRequest, BatchQueryResponse, Capabilities,
+    Capability, CodeStats, EncodedResponseQuery, Error, ExecutionStats,
+    Handshake, MempoolTx, MempoolTxStatus, Page, RequestCtx, RequestQuery,
+    ResponseQuery, Router, SortOrder, StorageDiff, TxSimulation, ABCI_VERSION,
+    MAX_BATCH_QUERIES, MAX_PAGE_SIZE, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION,
 };
 use vp::{Vp, VP};
 
@@ -203,9 +206,16 @@ mod testing {
 
 use std::fmt::{Debug, Display};
 
+/// Check if the given error of a query at the given path was caused by the
+/// node not knowing the path, i.e. the node predates the query
+pub(crate) fn is_unknown_path_error(err: &impl Display, path: &str) -> bool {
+    let unknown_path = router::Error::WrongPath(path.to_string()).to_string();
+    err.to_string().contains(&unknown_path)
+}
+
 use tendermint_rpc::endpoint::{
     abci_info, block, block_results, blockchain, commit, consensus_params,
-    consensus_state, health, net_info, status,
+    consensus_state, health, net_info, status, validators,
 };
 use tendermint_rpc::query::Query;
 use tendermint_rpc::{Error as RpcError, Order};
@@ -372,6 +382,41 @@ pub trait Client {
         self.perform(commit::Request::new(height.into())).await
     }
 
+    /// `/validators`: get all the validators at a given height, fetching
+    /// every page.
+    async fn validators<H>(
+        &self,
+        height: H,
+    ) -> Result<validators::Response, RpcError>
+    where
+        H: Into<Height> + Send,
+    {
+        const PER_PAGE: u8 = 100;
+        let height = height.into();
+        let mut all_validators = vec![];
+        let mut page: usize = 1;
+        loop {
+            let response = self
+                .perform(validators::Request::new(
+                    Some(height),
+                    Some(page.into()),
+                    Some(PER_PAGE.into()),
+                ))
+                .await?;
+            let total = usize::try_from(response.total).unwrap_or_default();
+            let is_last_page = response.validators.is_empty();
+            all_validators.extend(response.validators);
+            if is_last_page || all_validators.len() >= total {
+                return Ok(validators::Response::new(
+                    response.block_height,
+                    all_validators,
+                    response.total,
+                ));
+            }
+            page += 1;
+        }
+    }
+
     /// `/consensus_params`: get current consensus parameters at the specified
     /// height.
     async fn consensus_params<H>(
@@ -1136,4 +1136,33 @@ mod test {
 
         Ok(())
     }
+
+    /// Test the negotiation of the capabilities with a node serving the
+    /// handshake query and with a node that predates it.
+    #[tokio::test]
+    async fn test_capabilities_negotiation() {
+        use crate::queries::{Capabilities, Capability, Handshake, RPC};
+        use crate::rpc;
+
+        let client = TestClient::new(RPC);
+        let handshake = rpc::query_handshake(&client).await.unwrap();
+        assert_eq!(handshake, Some(Handshake::current()));
+        let capabilities = rpc::query_capabilities(&client).await.unwrap();
+        assert_eq!(capabilities, Capabilities::all());
+
+        // The nodes that predate the handshake have no capabilities
+        let client = TestClient::new(TEST_RPC);
+        let handshake = rpc::query_handshake(&client).await.unwrap();
+        assert_eq!(handshake, None);
+        let capabilities = rpc::query_capabilities(&client).await.unwrap();
+        assert!(!capabilities.supports(Capability::BatchedQueries));
+
+        // The batches are sent one by one to the nodes that predate them
+        let mut batch = rpc::QueryBatch::default();
+        let a = batch.push::<String>(TEST_RPC.a_path());
+        let x = batch.push::<String>(TEST_RPC.test_sub_rpc().x_path());
+        let results = rpc::query_batch(&client, &batch, None).await.unwrap();
+        assert_eq!(results.get(&a).unwrap(), "a");
+        assert_eq!(results.get(&x).unwrap(), "x");
+    }
 }
@@ -15,10 +15,14 @@ use namada_core::types::hash::Hash;
 use namada_core::types::storage::{
     self, BlockHeight, BlockResults, Epoch, KeySeg, PrefixValue,
 };
-use namada_core::types::token::{Denomination, MaspDigitPos};
+use namada_core::types::token::{
+    Amount, ConversionGap, Denomination, MaspDigitPos,
+};
 use namada_core::types::uint::Uint;
+use namada_ibc::escrow::{reconcile_escrow, TokenReconciliation};
 use namada_state::{DBIter, LastBlock, StorageHasher, DB};
 use namada_storage::{self, ResultExt, StorageRead};
+use namada_token::compact::{CompactBlock, MAX_COMPACT_BLOCKS_PER_QUERY};
 #[cfg(any(test, feature = "async-client"))]
 use namada_tx::data::TxResult;
 
@@ -29,8 +33,15 @@ use crate::ibc::core::host::types::identifiers::{
     ChannelId, ClientId, PortId, Sequence,
 };
 use crate::masp::MaspTokenRewardData;
-use crate::queries::types::{RequestCtx, RequestQuery};
-use crate::queries::{require_latest_height, EncodedResponseQuery};
+#[cfg(any(test, feature = "async-client"))]
+use crate::queries::types::TxSimulation;
+use crate::queries::types::{
+    BatchQueryRequest, BatchQueryResponse, ExecutionStats, Handshake,
+    MempoolTx, RequestCtx, RequestQuery, MAX_BATCH_QUERIES,
+};
+use crate::queries::{
+    require_latest_height, require_no_proof, EncodedResponseQuery, Router, RPC,
+};
 use crate::tendermint::merkle::proof::ProofOps;
 
 type ConversionWithoutPath = (
@@ -62,6 +73,21 @@ router! {SHELL,
     // The address of the native token
     ( "native_token" ) -> Address = native_token,
 
+    // The minimum gas prices per fee token accepted by the node
+    ( "gas_prices" ) -> BTreeMap<Address, Amount> = gas_prices,
+
+    // The execution statistics of the tx and VP codes recorded by the node
+    ( "code_stats" ) -> ExecutionStats = code_stats,
+
+    // The wrapper txs of a fee payer checked by the mempool of the node
+    ( "mempool" / [fee_payer: Address] ) -> Vec<MempoolTx> = mempool_txs,
+
+    // All the wrapper txs checked by the mempool of the node
+    ( "mempool_queue" ) -> Vec<MempoolTx> = mempool_queue,
+
+    // The versions and capabilities of the node
+    ( "handshake" ) -> Handshake = handshake,
+
     // Epoch of the input block height
     ( "epoch_at_height" / [height: BlockHeight]) -> Option<Epoch> = epoch_at_height,
 
@@ -75,6 +101,12 @@ router! {SHELL,
     // Dry run a transaction
     ( "dry_run_tx" ) -> TxResult = (with_options dry_run_tx),
 
+    // Dry run a transaction and get the storage changes of its inner tx
+    ( "simulate_tx" ) -> TxSimulation = (with_options simulate_tx),
+
+    // Handle a batch of queries in a single request
+    ( "batch" ) -> Vec<BatchQueryResponse> = (with_options query_batch),
+
     // Raw storage access - prefix iterator
     ( "prefix" / [storage_key: storage::Key] )
         -> Vec<PrefixValue> = (with_options storage_prefix),
@@ -92,6 +124,13 @@ router! {SHELL,
     // Conversion state access - read conversion
     ( "masp_reward_tokens" ) -> Vec<MaspTokenRewardData> = masp_reward_tokens,
 
+    // Conversion state access - find the gaps in the conversions
+    ( "conversion_gaps" ) -> Vec<ConversionGap> = read_conversion_gaps,
+
+    // Compact blocks of the valid masp transactions for shielded sync
+    ( "compact_blocks" / [from: BlockHeight] / [to: BlockHeight] )
+        -> Vec<(BlockHeight, CompactBlock)> = compact_blocks,
+
     // Block results access - read bit-vec
     ( "results" ) -> Vec<BlockResults> = read_results,
 
@@ -112,6 +151,9 @@ router! {SHELL,
 
     // IBC packet event
     ( "ibc_packet" / [event_type: EventType] / [source_port: PortId] / [source_channel: ChannelId] / [destination_port: PortId] / [destination_channel: ChannelId] / [sequence: Sequence]) -> Option<Event> = ibc_packet,
+
+    // Reconciliation of the IBC escrow accounting per channel and token
+    ( "ibc_escrow_reconciliation" ) -> Vec<TokenReconciliation> = ibc_escrow_reconciliation,
 }
 
 // Handlers:
@@ -127,6 +169,90 @@ where
     unimplemented!("Dry running tx requires \"wasm-runtime\" feature.")
 }
 
+fn simulate_tx<D, H, V, T>(
+    _ctx: RequestCtx<'_, D, H, V, T>,
+    _request: &RequestQuery,
+) -> namada_storage::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    unimplemented!("Simulating tx requires \"wasm-runtime\" feature.")
+}
+
+/// Handle a batch of borsh-encoded [`BatchQueryRequest`]s at the requested
+/// height. A failure of a query in the batch doesn't affect the other
+/// queries, its error is returned in place of its result instead.
+fn query_batch<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    request: &RequestQuery,
+) -> namada_storage::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    require_no_proof(request)?;
+    let queries: Vec<BatchQueryRequest> =
+        BorshDeserialize::try_from_slice(&request.data)
+            .into_storage_result()?;
+    if queries.len() > MAX_BATCH_QUERIES {
+        return Err(namada_storage::Error::new_const(
+            "The number of queries in the batch exceeds the maximum",
+        ));
+    }
+
+    let dry_run_path = RPC.shell().dry_run_tx_path();
+    let simulate_path = RPC.shell().simulate_tx_path();
+    let batch_path = RPC.shell().query_batch_path();
+    let gas_prices_path = RPC.shell().gas_prices_path();
+    let code_stats_path = RPC.shell().code_stats_path();
+    let mempool_queue_path = RPC.shell().mempool_queue_path();
+    // The paths of the mempool txs of the fee payers, followed by their
+    // address
+    let mempool_txs_prefix = "/shell/mempool/";
+    let responses: Vec<BatchQueryResponse> = queries
+        .into_iter()
+        .map(|BatchQueryRequest { path, data }| {
+            // Dry-running txs requires the wasm caches, nested batches
+            // could bypass the batch size limit and the gas prices and the
+            // execution statistics and the mempool of the node are only
+            // known to the shell
+            if path == dry_run_path
+                || path == simulate_path
+                || path == batch_path
+                || path == gas_prices_path
+                || path == code_stats_path
+                || path == mempool_queue_path
+                || path.starts_with(mempool_txs_prefix)
+            {
+                return Err(format!("The query {path} cannot be batched"));
+            }
+            let ctx = RequestCtx {
+                wl_storage: ctx.wl_storage,
+                event_log: ctx.event_log,
+                vp_wasm_cache: (),
+                tx_wasm_cache: (),
+                storage_read_past_height_limit: ctx
+                    .storage_read_past_height_limit,
+            };
+            let request = RequestQuery {
+                data: data.into(),
+                path,
+                height: request.height,
+                prove: false,
+            };
+            RPC.handle(ctx, &request)
+                .map(|response| response.data)
+                .map_err(|err| err.to_string())
+        })
+        .collect();
+
+    Ok(EncodedResponseQuery {
+        data: responses.serialize_to_vec(),
+        ..Default::default()
+    })
+}
+
 /// Query to read block results from storage
 pub fn read_results<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -189,6 +315,18 @@ where
         .collect())
 }
 
+/// Query to find the gaps in the conversions of the conversion state and of
+/// the archive
+fn read_conversion_gaps<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<Vec<ConversionGap>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_token::conversion::conversion_gaps(ctx.wl_storage)
+}
+
 /// Query to read a conversion from storage
 fn read_conversion<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -198,27 +336,63 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
+    let conversion_state = &ctx.wl_storage.storage.conversion_state;
+    // The conversions pruned from the conversion state are archived, but
+    // their leaves are still in the tree
+    let entry = match conversion_state.assets.get(&asset_type) {
+        Some(entry) => Some(entry.clone()),
+        None => {
+            namada_token::read_archived_conversion(ctx.wl_storage, &asset_type)?
+        }
+    };
     // Conversion values are constructed on request
-    if let Some(((addr, denom, digit), epoch, conv, pos)) = ctx
-        .wl_storage
-        .storage
-        .conversion_state
-        .assets
-        .get(&asset_type)
-    {
-        Ok(Some((
-            addr.clone(),
-            *denom,
-            *digit,
-            *epoch,
+    Ok(entry.map(|((addr, denom, digit), epoch, conv, pos)| {
+        (
+            addr,
+            denom,
+            digit,
+            epoch,
             Into::<masp_primitives::transaction::components::I128Sum>::into(
-                conv.clone(),
+                conv,
             ),
-            ctx.wl_storage.storage.conversion_state.tree.path(*pos),
-        )))
-    } else {
-        Ok(None)
+            conversion_state.tree.path(pos),
+        )
+    }))
+}
+
+/// Query the compact blocks in the given inclusive range of heights. Only the
+/// blocks containing valid masp transactions are returned. At most
+/// [`MAX_COMPACT_BLOCKS_PER_QUERY`] heights are covered by a single query,
+/// starting from `from`.
+fn compact_blocks<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    from: BlockHeight,
+    to: BlockHeight,
+) -> namada_storage::Result<Vec<(BlockHeight, CompactBlock)>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let last_height = ctx.wl_storage.storage.get_last_block_height();
+    let to = std::cmp::min(
+        std::cmp::min(to, last_height),
+        BlockHeight(
+            from.0
+                .saturating_add(MAX_COMPACT_BLOCKS_PER_QUERY)
+                .saturating_sub(1),
+        ),
+    );
+    let mut blocks = vec![];
+    for height in from.0..=to.0 {
+        let height = BlockHeight(height);
+        if let Some(block) = ctx
+            .wl_storage
+            .read(&namada_token::storage_key::masp_compact_block_key(height))?
+        {
+            blocks.push((height, block));
+        }
     }
+    Ok(blocks)
 }
 
 /// Query to read the tokens that earn masp rewards.
@@ -322,6 +496,71 @@ where
     Ok(data)
 }
 
+/// The minimum gas prices set by governance. The ledger node serves this
+/// query itself with its local minimum gas prices, where they are higher.
+fn gas_prices<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<BTreeMap<Address, Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_parameters::read_gas_cost_table(ctx.wl_storage)
+}
+
+/// The execution statistics are kept in the memory of the ledger node, which
+/// serves this query itself.
+fn code_stats<D, H, V, T>(
+    _ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<ExecutionStats>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    Err(namada_storage::Error::SimpleMessage(
+        "The execution statistics are only served by the ledger node",
+    ))
+}
+
+/// The mempool txs are kept in the memory of the ledger node, which serves
+/// this query itself.
+fn mempool_txs<D, H, V, T>(
+    _ctx: RequestCtx<'_, D, H, V, T>,
+    _fee_payer: Address,
+) -> namada_storage::Result<Vec<MempoolTx>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    Err(namada_storage::Error::SimpleMessage(
+        "The mempool txs are only served by the ledger node",
+    ))
+}
+
+/// The mempool txs are kept in the memory of the ledger node, which serves
+/// this query itself to its operator only.
+fn mempool_queue<D, H, V, T>(
+    _ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<Vec<MempoolTx>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    Err(namada_storage::Error::SimpleMessage(
+        "The mempool queue is only served by the ledger node",
+    ))
+}
+
+fn handshake<D, H, V, T>(
+    _ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<Handshake>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    Ok(Handshake::current())
+}
+
 fn epoch_at_height<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     height: BlockHeight,
@@ -576,6 +815,16 @@ where
         .cloned())
 }
 
+fn ibc_escrow_reconciliation<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<Vec<TokenReconciliation>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    reconcile_escrow(ctx.wl_storage)
+}
+
 fn account<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     owner: Address,
@@ -634,6 +883,27 @@ mod test {
         let path = RPC.shell().dry_run_tx_path();
         assert_eq!("/shell/dry_run_tx", path);
 
+        let path = RPC.shell().simulate_tx_path();
+        assert_eq!("/shell/simulate_tx", path);
+
+        let path = RPC.shell().query_batch_path();
+        assert_eq!("/shell/batch", path);
+
+        let path = RPC.shell().gas_prices_path();
+        assert_eq!("/shell/gas_prices", path);
+
+        let path = RPC.shell().code_stats_path();
+        assert_eq!("/shell/code_stats", path);
+
+        let path = RPC.shell().mempool_txs_path(&owner);
+        assert_eq!(format!("/shell/mempool/{owner}"), path);
+
+        let path = RPC.shell().mempool_queue_path();
+        assert_eq!("/shell/mempool_queue", path);
+
+        let path = RPC.shell().handshake_path();
+        assert_eq!("/shell/handshake", path);
+
         let path = RPC.shell().storage_prefix_path(&key);
         assert_eq!(format!("/shell/prefix/{}", key), path);
 
@@ -23,10 +23,17 @@ use namada_core::types::voting_power::FractionalVotingPower;
 use namada_ethereum_bridge::protocol::transactions::votes::{
     EpochedVotingPower, EpochedVotingPowerExt,
 };
-use namada_ethereum_bridge::storage::bridge_pool::get_key_from_hash;
+use namada_ethereum_bridge::storage::bridge_pool::{
+    get_delayed_transfers_prefix, get_key_from_hash, DelayedTransfer,
+};
+use namada_ethereum_bridge::storage::checkpoints::{
+    get_latest_certified_checkpoint_key, Checkpoint,
+};
 use namada_ethereum_bridge::storage::eth_bridge_queries::EthBridgeQueries;
 use namada_ethereum_bridge::storage::parameters::UpgradeableContract;
-use namada_ethereum_bridge::storage::proof::{sort_sigs, EthereumProof};
+use namada_ethereum_bridge::storage::proof::{
+    sort_sigs, CheckpointProof, EthereumProof,
+};
 use namada_ethereum_bridge::storage::vote_tallies::{eth_msgs_prefix, Keys};
 use namada_ethereum_bridge::storage::{
     bridge_contract_key, native_erc20_key, vote_tallies,
@@ -67,11 +74,18 @@ pub struct TransferToEthereumStatus {
     /// to be `pending`.
     pub pending: HashSet<KeccakHash>,
     /// Transfers in the query whose status it was determined
+    /// to be `delayed`, by the withdrawal veto window of their
+    /// asset.
+    pub delayed: HashSet<KeccakHash>,
+    /// Transfers in the query whose status it was determined
     /// to be `relayed`.
     pub relayed: HashSet<KeccakHash>,
     /// Transfers in the query whose status it was determined
     /// to be `expired`.
     pub expired: HashSet<KeccakHash>,
+    /// Transfers in the query whose status it was determined
+    /// to be `vetoed`, while they were delayed.
+    pub vetoed: HashSet<KeccakHash>,
     /// Hashes pertaining to bogus data that might have been queried,
     /// or transfers that were not in the event log, despite having
     /// been relayed to Ethereum or expiring from the Bridge pool.
@@ -152,6 +166,11 @@ router! {ETH_BRIDGE,
     ( "pool" / "signed_contents" )
         -> Vec<PendingTransfer> = read_signed_ethereum_bridge_pool,
 
+    // Get the transfers to Ethereum delayed by the withdrawal
+    // veto window of their asset.
+    ( "pool" / "delayed" )
+        -> Vec<DelayedTransfer> = read_delayed_transfers,
+
     // Generate a merkle proof for the inclusion of requested
     // transfers in the Ethereum bridge pool
     ( "pool" / "proof" )
@@ -164,7 +183,8 @@ router! {ETH_BRIDGE,
         = transfer_to_ethereum_progress,
 
     // Given a list of keccak hashes, check whether they have been
-    // relayed, expired or if they are still pending.
+    // relayed, expired, vetoed or if they are still pending or
+    // delayed.
     ( "pool" / "transfer_status" )
         -> TransferToEthereumStatus = (with_options pending_eth_transfer_status),
 
@@ -212,10 +232,23 @@ router! {ETH_BRIDGE,
     // ERC20 token in Namada.
     ( "erc20" / "flow_control" / [asset: EthAddress] )
         -> Erc20FlowControl = get_erc20_flow_control,
+
+    // Request the certificate of the checkpoint of the application
+    // state recorded at the given block height.
+    //
+    // The request may fail if the certificate is not complete yet.
+    ( "checkpoint" / "proof" / [height: BlockHeight] )
+        -> EncodeCell<EthereumProof<(Checkpoint, VotingPowersMap)>>
+        = read_checkpoint_proof,
+
+    // Read the block height of the latest checkpoint of the
+    // application state signed by a quorum of validators.
+    ( "checkpoint" / "latest" )
+        -> Option<BlockHeight> = read_latest_certified_checkpoint,
 }
 
 /// Given a list of keccak hashes, check whether they have been
-/// relayed, expired or if they are still pending.
+/// relayed, expired, vetoed or if they are still pending or delayed.
 fn pending_eth_transfer_status<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     request: &RequestQuery,
@@ -270,6 +303,19 @@ where
         }
     }
 
+    // check which of the remaining transfers are delayed
+    transfer_hashes.retain(|hash| {
+        let transfer_delayed = ctx
+            .wl_storage
+            .ethbridge_queries()
+            .get_delayed_transfer(hash)
+            .is_some();
+        if transfer_delayed {
+            status.delayed.insert(hash.clone());
+        }
+        !transfer_delayed
+    });
+
     if transfer_hashes.is_empty() {
         let data = status.serialize_to_vec();
         return Ok(EncodedResponseQuery {
@@ -288,11 +334,14 @@ where
         }
         let eth_event_kind =
             ev.attributes.get(&kind_key).map(|k| k.as_str())?;
-        let is_relayed = match eth_event_kind {
-            "bridge_pool_relayed" => true,
-            "bridge_pool_expired" => false,
-            _ => return None,
-        };
+        if !matches!(
+            eth_event_kind,
+            "bridge_pool_relayed"
+                | "bridge_pool_expired"
+                | "bridge_pool_vetoed"
+        ) {
+            return None;
+        }
         let tx_hash: KeccakHash = ev
             .attributes
             .get("tx_hash")
@@ -303,14 +352,15 @@ where
         if !transfer_hashes.remove(&tx_hash) {
             return None;
         }
-        Some((tx_hash, is_relayed, transfer_hashes.is_empty()))
+        Some((tx_hash, eth_event_kind, transfer_hashes.is_empty()))
     });
-    for (hash, is_relayed, early_exit) in completed_transfers {
-        if hints::likely(is_relayed) {
-            status.relayed.insert(hash.clone());
-        } else {
-            status.expired.insert(hash.clone());
-        }
+    for (hash, eth_event_kind, early_exit) in completed_transfers {
+        let completed = match eth_event_kind {
+            "bridge_pool_relayed" => &mut status.relayed,
+            "bridge_pool_expired" => &mut status.expired,
+            _ => &mut status.vetoed,
+        };
+        completed.insert(hash.clone());
         if early_exit {
             // early drop of the transfer hashes, in
             // case its storage capacity was big
@@ -437,6 +487,23 @@ where
     Ok(read_ethereum_bridge_pool_at_height(height, ctx))
 }
 
+/// Read the transfers to Ethereum delayed by the
+/// withdrawal veto window of their asset.
+fn read_delayed_transfers<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<Vec<DelayedTransfer>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_storage::iter_prefix::<DelayedTransfer>(
+        ctx.wl_storage,
+        &get_delayed_transfers_prefix(),
+    )?
+    .map(|result| result.map(|(_, delayed)| delayed))
+    .collect()
+}
+
 /// Read the Ethereum bridge pool contents at a specified height.
 fn read_ethereum_bridge_pool_at_height<D, H, V, T>(
     height: BlockHeight,
@@ -832,6 +899,67 @@ where
     Ok(voting_powers)
 }
 
+/// Read the certificate of the checkpoint of the application state
+/// recorded at the given [`BlockHeight`].
+///
+/// This method may fail if a quorum of validators has not signed
+/// the checkpoint yet.
+fn read_checkpoint_proof<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    height: BlockHeight,
+) -> namada_storage::Result<
+    EncodeCell<EthereumProof<(Checkpoint, VotingPowersMap)>>,
+>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    if !ctx
+        .wl_storage
+        .ethbridge_queries()
+        .checkpoint_certified(height)
+    {
+        return Err(namada_storage::Error::Custom(CustomError(
+            format!(
+                "The certificate of the checkpoint is not yet available for \
+                 the queried block height: {height:?}"
+            )
+            .into(),
+        )));
+    }
+    let epoch = ctx
+        .wl_storage
+        .pos_queries()
+        .get_epoch(height)
+        .expect("The epoch of a certified checkpoint should be known");
+
+    let checkpoint_keys = vote_tallies::Keys::from(&height);
+    let proof: CheckpointProof =
+        StorageRead::read(ctx.wl_storage, &checkpoint_keys.body())?.expect(
+            "EthereumProof is seen in storage, therefore it must exist",
+        );
+    let (_, voting_powers) = ctx
+        .wl_storage
+        .ethbridge_queries()
+        .get_bridge_validator_set(Some(epoch));
+
+    Ok(proof
+        .map(|checkpoint| (checkpoint, voting_powers.clone()))
+        .encode())
+}
+
+/// Read the [`BlockHeight`] of the latest checkpoint of the application
+/// state signed by a quorum of validators.
+fn read_latest_certified_checkpoint<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<Option<BlockHeight>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    StorageRead::read(ctx.wl_storage, &get_latest_certified_checkpoint_key())
+}
+
 #[cfg(test)]
 mod test_ethbridge_router {
     use std::collections::BTreeMap;
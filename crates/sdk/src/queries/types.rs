@@ -1,7 +1,16 @@
-use std::fmt::Debug;
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
 
-use namada_core::types::storage::BlockHeight;
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::types::address::Address;
+use namada_core::types::dec::Dec;
+use namada_core::types::hash::Hash;
+use namada_core::types::storage::{self, BlockHeight};
+use namada_core::types::token::Amount;
 use namada_state::{DBIter, StorageHasher, WlStorage, DB};
+use namada_token::storage_key::is_any_token_balance_key;
+use namada_tx::data::{Fee, TxResult};
 use thiserror::Error;
 
 use crate::events::log::EventLog;
@@ -94,3 +103,514 @@ pub struct ResponseQuery<T> {
 
 /// [`ResponseQuery`] with borsh-encoded `data` field
 pub type EncodedResponseQuery = ResponseQuery<Vec<u8>>;
+
+/// The maximum number of queries in a single batch request
+pub const MAX_BATCH_QUERIES: usize = 256;
+
+/// A query inside of a batch request
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct BatchQueryRequest {
+    /// The path of the query, as built by the routers' `*_path` methods
+    pub path: String,
+    /// Optional request data
+    pub data: Vec<u8>,
+}
+
+/// The borsh-encoded result of a query inside of a batch request or an error
+/// message if the query failed
+pub type BatchQueryResponse = Result<Vec<u8>, String>;
+
+/// The maximum number of items returned in a single page of a paginated query
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+/// The order in which the items of a paginated query are sorted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    /// Ascending order
+    #[default]
+    Asc,
+    /// Descending order
+    Desc,
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortOrder::Asc => write!(f, "asc"),
+            SortOrder::Desc => write!(f, "desc"),
+        }
+    }
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asc" => Ok(SortOrder::Asc),
+            "desc" => Ok(SortOrder::Desc),
+            _ => Err(format!("Invalid sort order: {s}")),
+        }
+    }
+}
+
+/// A single page of the results of a paginated query
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct Page<T> {
+    /// The items in this page
+    pub items: Vec<T>,
+    /// The total number of items available across all the pages
+    pub total: u64,
+}
+
+impl<T> Page<T> {
+    /// Take the page starting at `offset` out of the given already sorted
+    /// items, in the requested order. The `limit` is capped at
+    /// [`MAX_PAGE_SIZE`].
+    pub fn from_sorted(
+        mut items: Vec<T>,
+        order: SortOrder,
+        offset: u64,
+        limit: u64,
+    ) -> Self {
+        let total = items.len() as u64;
+        if order == SortOrder::Desc {
+            items.reverse();
+        }
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let items = items
+            .into_iter()
+            .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+            .take(limit as usize)
+            .collect();
+        Self { items, total }
+    }
+}
+
+/// The change of a storage value written by a simulated transaction
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct StorageDiff {
+    /// The storage key
+    pub key: storage::Key,
+    /// The value before the transaction, if any
+    pub pre: Option<Vec<u8>>,
+    /// The value after the transaction, if any
+    pub post: Option<Vec<u8>>,
+}
+
+/// The change of a token balance caused by a simulated transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalanceChange {
+    /// The token
+    pub token: Address,
+    /// The owner of the balance
+    pub owner: Address,
+    /// The balance before the transaction
+    pub pre: Amount,
+    /// The balance after the transaction
+    pub post: Amount,
+}
+
+/// The outcome of a simulated transaction
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct TxSimulation {
+    /// The result of the transaction
+    pub result: TxResult,
+    /// The changes of the storage values written by the inner transaction,
+    /// sorted by key
+    pub diff: Vec<StorageDiff>,
+}
+
+impl TxSimulation {
+    /// The changes of the token balances in the diff. Balances that fail to
+    /// decode are left out.
+    pub fn balance_changes(&self) -> Vec<BalanceChange> {
+        let decode = |value: &Option<Vec<u8>>| match value {
+            Some(bytes) => Amount::try_from_slice(bytes).ok(),
+            None => Some(Amount::zero()),
+        };
+        self.diff
+            .iter()
+            .filter_map(|StorageDiff { key, pre, post }| {
+                let [token, owner] = is_any_token_balance_key(key)?;
+                Some(BalanceChange {
+                    token: token.clone(),
+                    owner: owner.clone(),
+                    pre: decode(pre)?,
+                    post: decode(post)?,
+                })
+            })
+            .collect()
+    }
+
+    /// The changes of the storage values other than token balances
+    pub fn other_changes(&self) -> impl Iterator<Item = &StorageDiff> {
+        self.diff
+            .iter()
+            .filter(|diff| is_any_token_balance_key(&diff.key).is_none())
+    }
+}
+
+/// The statistics of the executions of a tx or VP code recorded by a node.
+/// The gas and failure statistics are computed over the last executions of
+/// the code only, while the count covers all of them.
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct CodeStats {
+    /// The number of executions since the node started
+    pub count: u64,
+    /// The number of last executions the other statistics are computed over
+    pub window: u64,
+    /// The number of failed executions in the window
+    pub failures: u64,
+    /// The average gas used, in whole gas units
+    pub avg_gas: u64,
+    /// The median gas used, in whole gas units
+    pub p50_gas: u64,
+    /// The 90th percentile of the gas used, in whole gas units
+    pub p90_gas: u64,
+    /// The 99th percentile of the gas used, in whole gas units
+    pub p99_gas: u64,
+}
+
+impl CodeStats {
+    /// The rate of failed executions in the window
+    pub fn failure_rate(&self) -> Dec {
+        if self.window == 0 {
+            Dec::zero()
+        } else {
+            Dec::from(self.failures) / self.window
+        }
+    }
+}
+
+/// The execution statistics recorded by a node, by code hash
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct ExecutionStats {
+    /// The statistics of the txs, by the hash of their code
+    pub txs: BTreeMap<Hash, CodeStats>,
+    /// The statistics of the wasm VPs, by the hash of their code
+    pub vps: BTreeMap<Hash, CodeStats>,
+}
+
+/// The outcome of the last check of a tx by the mempool of a node
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum MempoolTxStatus {
+    /// The tx passed the check and waits in the mempool to be included in a
+    /// block
+    Accepted,
+    /// The tx was rejected, or evicted from the mempool on a recheck
+    Rejected {
+        /// The result code of the check
+        code: u32,
+        /// The reason of the rejection
+        log: String,
+    },
+}
+
+/// A wrapper tx checked by the mempool of a node and not yet included in a
+/// block
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct MempoolTx {
+    /// The hash of the header of the wrapper tx
+    pub hash: Hash,
+    /// The address paying the fee of the tx
+    pub fee_payer: Address,
+    /// The fee per gas unit and the fee token
+    pub fee: Fee,
+    /// The gas limit of the tx
+    pub gas_limit: u64,
+    /// The size of the tx, in bytes
+    pub size: u64,
+    /// The outcome of the last check of the tx
+    pub status: MempoolTxStatus,
+    /// The height of the last committed block when the tx was first checked
+    pub first_checked: BlockHeight,
+    /// The height of the last committed block when the tx was last checked
+    pub last_checked: BlockHeight,
+}
+
+impl MempoolTx {
+    /// Whether the tx is still in the mempool of the node
+    pub fn is_pending(&self) -> bool {
+        self.status == MempoolTxStatus::Accepted
+    }
+}
+
+/// The version of the protocol of the queries served by the nodes, bumped on
+/// the breaking changes of their paths or encoding
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The oldest version of the protocol of the queries that is still supported
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// The version of the ABCI interface implemented by the nodes
+pub const ABCI_VERSION: &str = "0.37";
+
+/// An optional feature of the queries served by a node
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Capability {
+    /// Batches of queries in a single request
+    BatchedQueries = 0,
+    /// Compact blocks of the masp txs for the shielded sync
+    CompactShieldedSync = 1,
+    /// Simulation of txs with the changes of the storage
+    TxSimulation = 2,
+    /// The minimum gas prices accepted by the node
+    GasPrices = 3,
+    /// The execution statistics of the tx and VP codes
+    CodeStats = 4,
+    /// The txs in the mempool of the node
+    MempoolTxs = 5,
+}
+
+impl Capability {
+    /// All the capabilities known to this version
+    pub const ALL: [Self; 6] = [
+        Self::BatchedQueries,
+        Self::CompactShieldedSync,
+        Self::TxSimulation,
+        Self::GasPrices,
+        Self::CodeStats,
+        Self::MempoolTxs,
+    ];
+
+    fn flag(self) -> u64 {
+        1 << self as u8
+    }
+}
+
+/// A set of [`Capability`] flags. The flags of the capabilities unknown to
+/// this version are ignored.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct Capabilities(u64);
+
+impl Capabilities {
+    /// No capabilities, as assumed for the nodes that predate the handshake
+    pub fn none() -> Self {
+        Self(0)
+    }
+
+    /// All the capabilities known to this version
+    pub fn all() -> Self {
+        Capability::ALL.into_iter().collect()
+    }
+
+    /// Check if the given capability is supported
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.0 & capability.flag() != 0
+    }
+}
+
+impl FromIterator<Capability> for Capabilities {
+    fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> Self {
+        Self(iter.into_iter().fold(0, |flags, cap| flags | cap.flag()))
+    }
+}
+
+/// The versions and capabilities of a node, served by the handshake query.
+/// New fields may only be appended, so that older versions can still decode
+/// it with [`Handshake::decode`].
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct Handshake {
+    /// The version of the protocol of the queries served by the node
+    pub protocol_version: u32,
+    /// The oldest version of the protocol of the queries supported by the
+    /// node
+    pub min_protocol_version: u32,
+    /// The version of the ABCI interface implemented by the node
+    pub abci_version: String,
+    /// The version of the node software
+    pub node_version: String,
+    /// The optional features supported by the node
+    pub capabilities: Capabilities,
+}
+
+impl Handshake {
+    /// The handshake of this version
+    pub fn current() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            abci_version: ABCI_VERSION.to_string(),
+            node_version: env!("CARGO_PKG_VERSION").to_string(),
+            capabilities: Capabilities::all(),
+        }
+    }
+
+    /// Decode a handshake, ignoring the fields appended by newer versions
+    pub fn decode(bytes: &[u8]) -> std::io::Result<Self> {
+        Self::deserialize(&mut &bytes[..])
+    }
+
+    /// Check that the protocol of the queries of the node is compatible with
+    /// the one of this version
+    pub fn check_compatible(&self) -> std::result::Result<(), String> {
+        if self.protocol_version < MIN_PROTOCOL_VERSION {
+            Err(format!(
+                "The node serves the version {} of the queries protocol, \
+                 older than the oldest supported version {}. Node version: {}",
+                self.protocol_version, MIN_PROTOCOL_VERSION, self.node_version
+            ))
+        } else if PROTOCOL_VERSION < self.min_protocol_version {
+            Err(format!(
+                "The node only serves the versions {} and newer of the \
+                 queries protocol, but this is version {}. Node version: {}",
+                self.min_protocol_version, PROTOCOL_VERSION, self.node_version
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_page_from_sorted() {
+        let items: Vec<u64> = (0..250).collect();
+
+        let page = Page::from_sorted(items.clone(), SortOrder::Asc, 10, 5);
+        assert_eq!(page.items, vec![10, 11, 12, 13, 14]);
+        assert_eq!(page.total, 250);
+
+        let page = Page::from_sorted(items.clone(), SortOrder::Desc, 0, 3);
+        assert_eq!(page.items, vec![249, 248, 247]);
+
+        // The limit is capped
+        let page = Page::from_sorted(items.clone(), SortOrder::Asc, 0, 1000);
+        assert_eq!(page.items.len() as u64, MAX_PAGE_SIZE);
+
+        // Past the end
+        let page = Page::from_sorted(items, SortOrder::Asc, 300, 10);
+        assert!(page.items.is_empty());
+        assert_eq!(page.total, 250);
+    }
+
+    #[test]
+    fn test_sort_order_roundtrip() {
+        for order in [SortOrder::Asc, SortOrder::Desc] {
+            assert_eq!(order.to_string().parse::<SortOrder>(), Ok(order));
+        }
+        assert!("up".parse::<SortOrder>().is_err());
+    }
+
+    #[test]
+    fn test_tx_simulation_balance_changes() {
+        use namada_core::borsh::BorshSerializeExt;
+        use namada_core::types::address::nam;
+        use namada_core::types::address::testing::{
+            established_address_1, established_address_2,
+        };
+        use namada_token::storage_key::balance_key;
+
+        let source = established_address_1();
+        let target = established_address_2();
+        let other_key = storage::Key::parse("other").unwrap();
+        let simulation = TxSimulation {
+            result: TxResult::default(),
+            diff: vec![
+                StorageDiff {
+                    key: balance_key(&nam(), &source),
+                    pre: Some(Amount::native_whole(10).serialize_to_vec()),
+                    post: Some(Amount::native_whole(4).serialize_to_vec()),
+                },
+                StorageDiff {
+                    key: balance_key(&nam(), &target),
+                    pre: None,
+                    post: Some(Amount::native_whole(6).serialize_to_vec()),
+                },
+                // A malformed balance is left out
+                StorageDiff {
+                    key: balance_key(&nam(), &nam()),
+                    pre: None,
+                    post: Some(vec![1]),
+                },
+                StorageDiff {
+                    key: other_key.clone(),
+                    pre: None,
+                    post: Some(vec![2]),
+                },
+            ],
+        };
+        assert_eq!(
+            simulation.balance_changes(),
+            vec![
+                BalanceChange {
+                    token: nam(),
+                    owner: source,
+                    pre: Amount::native_whole(10),
+                    post: Amount::native_whole(4),
+                },
+                BalanceChange {
+                    token: nam(),
+                    owner: target,
+                    pre: Amount::zero(),
+                    post: Amount::native_whole(6),
+                },
+            ]
+        );
+        let other: Vec<_> = simulation.other_changes().collect();
+        assert_eq!(other.len(), 1);
+        assert_eq!(other[0].key, other_key);
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let caps: Capabilities =
+            [Capability::BatchedQueries].into_iter().collect();
+        assert!(caps.supports(Capability::BatchedQueries));
+        assert!(!caps.supports(Capability::CompactShieldedSync));
+        assert!(!Capabilities::none().supports(Capability::BatchedQueries));
+        for cap in Capability::ALL {
+            assert!(Capabilities::all().supports(cap));
+        }
+        // The flags unknown to this version are ignored
+        let caps = Capabilities(caps.0 | 1 << 63);
+        assert!(caps.supports(Capability::BatchedQueries));
+        assert!(!caps.supports(Capability::CodeStats));
+        assert!(!caps.supports(Capability::MempoolTxs));
+    }
+
+    #[test]
+    fn test_handshake_compatibility() {
+        use namada_core::borsh::BorshSerializeExt;
+
+        let handshake = Handshake::current();
+        assert!(handshake.check_compatible().is_ok());
+
+        // The fields appended by newer versions are ignored
+        let mut bytes = handshake.serialize_to_vec();
+        bytes.extend([1, 2, 3]);
+        assert_eq!(Handshake::decode(&bytes).unwrap(), handshake);
+
+        // A newer node still supporting this version is compatible
+        let newer = Handshake {
+            protocol_version: PROTOCOL_VERSION + 1,
+            ..handshake.clone()
+        };
+        assert!(newer.check_compatible().is_ok());
+
+        // A node dropping the support of this version is not
+        let incompatible = Handshake {
+            protocol_version: PROTOCOL_VERSION + 1,
+            min_protocol_version: PROTOCOL_VERSION + 1,
+            ..handshake.clone()
+        };
+        assert!(incompatible.check_compatible().is_err());
+        let outdated = Handshake {
+            protocol_version: MIN_PROTOCOL_VERSION - 1,
+            min_protocol_version: 0,
+            ..handshake
+        };
+        assert!(outdated.check_compatible().is_err());
+    }
+}
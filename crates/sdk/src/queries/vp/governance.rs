@@ -1,20 +1,76 @@
 // cd namada && cargo expand ledger::queries::vp::governance
 
+use std::fmt::Display;
+use std::str::FromStr;
+
 use namada_governance::parameters::GovernanceParameters;
+use namada_governance::storage::keys as governance_keys;
 use namada_governance::storage::proposal::StorageProposal;
-use namada_governance::utils::{ProposalResult, Vote};
+use namada_governance::utils::{ProposalResult, ProposalStatus, Vote};
 use namada_state::{DBIter, StorageHasher, DB};
+use namada_storage::StorageRead;
 
-use crate::queries::types::RequestCtx;
+use crate::queries::types::{Page, RequestCtx, SortOrder};
 
 // Governance queries
 router! {GOV,
     ( "proposal" / [id: u64 ] ) -> Option<StorageProposal> = proposal_id,
     ( "proposal" / [id: u64 ] / "votes" ) -> Vec<Vote> = proposal_id_votes,
+    ( "proposals" / [sort: ProposalSortKey] / [order: SortOrder] / [offset: u64] / [limit: u64] )
+        -> Page<StorageProposal> = proposals_page,
     ( "parameters" ) -> GovernanceParameters = parameters,
     ( "stored_proposal_result" / [id: u64] ) -> Option<ProposalResult> = proposal_result,
 }
 
+/// The field by which a page of proposals is sorted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ProposalSortKey {
+    /// Sort by the proposal id
+    #[default]
+    Id,
+    /// Sort by the epoch from which voting is allowed
+    VotingStart,
+    /// Sort by the epoch from which voting is stopped
+    VotingEnd,
+    /// Sort by the proposal status at the current epoch, from pending to
+    /// ended
+    Status,
+}
+
+impl Display for ProposalSortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProposalSortKey::Id => write!(f, "id"),
+            ProposalSortKey::VotingStart => write!(f, "voting-start"),
+            ProposalSortKey::VotingEnd => write!(f, "voting-end"),
+            ProposalSortKey::Status => write!(f, "status"),
+        }
+    }
+}
+
+impl FromStr for ProposalSortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "id" => Ok(ProposalSortKey::Id),
+            "voting-start" => Ok(ProposalSortKey::VotingStart),
+            "voting-end" => Ok(ProposalSortKey::VotingEnd),
+            "status" => Ok(ProposalSortKey::Status),
+            _ => Err(format!("Invalid proposal sort key: {s}")),
+        }
+    }
+}
+
+/// The rank of a proposal status used to sort proposals by status
+fn proposal_status_rank(status: ProposalStatus) -> u8 {
+    match status {
+        ProposalStatus::Pending => 0,
+        ProposalStatus::OnGoing => 1,
+        ProposalStatus::Ended => 2,
+    }
+}
+
 /// Query the provided proposal id
 fn proposal_id<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -27,6 +83,50 @@ where
     namada_governance::storage::get_proposal_by_id(ctx.wl_storage, id)
 }
 
+/// Query a page of proposals, sorted by the given key. Ties are broken by the
+/// proposal id, so that the pages are stable.
+fn proposals_page<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    sort: ProposalSortKey,
+    order: SortOrder,
+    offset: u64,
+    limit: u64,
+) -> namada_storage::Result<Page<StorageProposal>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    let counter: u64 = ctx
+        .wl_storage
+        .read(&governance_keys::get_counter_key())?
+        .unwrap_or_default();
+    let mut proposals = Vec::new();
+    for id in 0..counter {
+        if let Some(proposal) =
+            namada_governance::storage::get_proposal_by_id(ctx.wl_storage, id)?
+        {
+            proposals.push(proposal);
+        }
+    }
+
+    // Proposals are read in the order of their ids
+    match sort {
+        ProposalSortKey::Id => {}
+        ProposalSortKey::VotingStart => {
+            proposals.sort_by_key(|proposal| proposal.voting_start_epoch)
+        }
+        ProposalSortKey::VotingEnd => {
+            proposals.sort_by_key(|proposal| proposal.voting_end_epoch)
+        }
+        ProposalSortKey::Status => proposals.sort_by_key(|proposal| {
+            proposal_status_rank(proposal.get_status(current_epoch))
+        }),
+    }
+
+    Ok(Page::from_sorted(proposals, order, offset, limit))
+}
+
 /// Query all the votes for the given proposal id
 fn proposal_id_votes<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
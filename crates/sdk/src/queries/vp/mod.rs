@@ -7,7 +7,7 @@ pub use pos::Pos;
 use pos::POS;
 pub use token::Token;
 use token::TOKEN;
-mod governance;
+pub mod governance;
 pub use pgf::Pgf;
 use pgf::PGF;
 mod pgf;
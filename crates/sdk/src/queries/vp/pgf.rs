@@ -1,7 +1,9 @@
 use namada_core::types::address::Address;
 use namada_governance::pgf::parameters::PgfParameters;
 use namada_governance::pgf::storage::steward::StewardDetail;
-use namada_governance::storage::proposal::StoragePgfFunding;
+use namada_governance::storage::proposal::{
+    StoragePgfClaim, StoragePgfFunding,
+};
 use namada_state::{DBIter, StorageHasher, DB};
 
 use crate::queries::types::RequestCtx;
@@ -11,6 +13,8 @@ router! {PGF,
     ( "stewards" / [ address: Address ] ) -> bool = is_steward,
     ( "stewards" ) -> Vec<StewardDetail> = stewards,
     ( "fundings" ) -> Vec<StoragePgfFunding> = funding,
+    ( "claims" / [ target: Address ] ) -> Option<StoragePgfClaim> = claim,
+    ( "claims" ) -> Vec<StoragePgfClaim> = claims,
     ( "parameters" ) -> PgfParameters = parameters,
 }
 
@@ -48,6 +52,29 @@ where
     namada_governance::pgf::storage::get_payments(ctx.wl_storage)
 }
 
+/// Query the pgf retro funding claimed by the given target
+fn claim<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    target: Address,
+) -> namada_storage::Result<Option<StoragePgfClaim>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_governance::pgf::storage::get_claim(ctx.wl_storage, &target)
+}
+
+/// Query the pgf retro fundings claimed by their targets
+fn claims<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<Vec<StoragePgfClaim>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_governance::pgf::storage::get_claims(ctx.wl_storage)
+}
+
 /// Query the PGF parameters
 fn parameters<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -1,16 +1,30 @@
 //! Queries router and handlers for PoS validity predicate
 
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::ledger::inflation::RewardRate;
 use namada_core::types::address::Address;
+use namada_core::types::dec::Dec;
 use namada_core::types::key::common;
 use namada_core::types::storage::Epoch;
 use namada_core::types::token;
+use namada_proof_of_stake::history::{
+    read_epoch_summary, read_historical_consensus_validator_set,
+    read_historical_staking_reward_rate, read_historical_total_stake,
+    read_historical_validator_commission_rate, read_historical_validator_stake,
+    read_historical_validator_staking_reward_rate,
+    read_historical_validator_state,
+};
 use namada_proof_of_stake::parameters::PosParams;
 use namada_proof_of_stake::queries::{
     find_delegation_validators, find_delegations,
 };
+use namada_proof_of_stake::rewards::{
+    estimate_staking_reward_rate, estimate_validator_staking_reward_rate,
+};
 use namada_proof_of_stake::slashing::{
     find_all_enqueued_slashes, find_all_slashes,
 };
@@ -18,23 +32,30 @@ use namada_proof_of_stake::storage::{
     bond_handle, read_all_validator_addresses,
     read_below_capacity_validator_set_addresses_with_stake,
     read_consensus_validator_set_addresses_with_stake, read_pos_params,
-    read_total_stake, read_validator_avatar, read_validator_description,
-    read_validator_discord_handle, read_validator_email,
+    read_slash_pool_flows, read_total_stake, read_validator_avatar,
+    read_validator_description, read_validator_discord_handle,
+    read_validator_email, read_validator_handle,
     read_validator_last_slash_epoch, read_validator_max_commission_rate_change,
-    read_validator_stake, read_validator_website, unbond_handle,
+    read_validator_stake, read_validator_website, slash_pool_inflows_handle,
+    slash_pool_outflows_handle, unbond_handle,
     validator_commission_rate_handle, validator_incoming_redelegations_handle,
-    validator_slashes_handle, validator_state_handle,
+    validator_performance_handle, validator_slashes_handle,
+    validator_state_handle,
 };
 use namada_proof_of_stake::types::{
     BondId, BondsAndUnbondsDetail, BondsAndUnbondsDetails, CommissionPair,
-    Slash, ValidatorMetaData, ValidatorState, WeightedValidator,
+    EpochSummary, Historical, Slash, ValidatorMetaData, ValidatorPerformance,
+    ValidatorState, WeightedValidator,
+};
+use namada_proof_of_stake::{
+    self, bond_amount, query_reward_tokens, staking_token_address,
+    SLASH_POOL_ADDRESS,
 };
-use namada_proof_of_stake::{self, bond_amount, query_reward_tokens};
 use namada_state::{DBIter, StorageHasher, DB};
 use namada_storage::collections::lazy_map;
 use namada_storage::OptionExt;
 
-use crate::queries::types::RequestCtx;
+use crate::queries::types::{Page, RequestCtx, SortOrder};
 
 // PoS validity predicate queries
 router! {POS,
@@ -46,6 +67,9 @@ router! {POS,
         ( "addresses" / [epoch: opt Epoch] )
             -> HashSet<Address> = validator_addresses,
 
+        ( "page" / [sort: ValidatorSortKey] / [order: SortOrder] / [offset: u64] / [limit: u64] / [epoch: opt Epoch] )
+            -> Page<ValidatorSummary> = validators_page,
+
         ( "stake" / [validator: Address] / [epoch: opt Epoch] )
             -> Option<token::Amount> = validator_stake,
 
@@ -58,6 +82,9 @@ router! {POS,
         ( "metadata" / [validator: Address] )
             -> Option<ValidatorMetaData> = validator_metadata,
 
+        ( "handle" / [validator: Address] )
+            -> Option<String> = validator_handle,
+
         ( "state" / [validator: Address] / [epoch: opt Epoch] )
             -> Option<ValidatorState> = validator_state,
 
@@ -66,6 +93,9 @@ router! {POS,
 
         ( "last_infraction_epoch" / [validator: Address] )
             -> Option<Epoch> = validator_last_infraction_epoch,
+
+        ( "reward_rate" / [validator: Address] / [epoch: opt Epoch] )
+            -> Option<RewardRate> = validator_reward_rate,
     },
 
     ( "validator_set" ) = {
@@ -83,6 +113,9 @@ router! {POS,
     ( "total_stake" / [epoch: opt Epoch] )
         -> token::Amount = total_stake,
 
+    ( "reward_rate" / [epoch: opt Epoch] )
+        -> RewardRate = staking_reward_rate,
+
     ( "delegations" / [owner: Address] )
         -> HashSet<Address> = delegation_validators,
 
@@ -123,11 +156,47 @@ router! {POS,
     ( "validator_by_tm_addr" / [tm_addr: String] )
         -> Option<Address> = validator_by_tm_addr,
 
+    ( "validator_by_handle" / [handle: String] )
+        -> Option<Address> = validator_by_handle,
+
     ( "consensus_keys" ) -> BTreeSet<common::PublicKey> = consensus_key_set,
 
     ( "has_bonds" / [source: Address] )
         -> bool = has_bonds,
 
+    ( "history" ) = {
+        ( "epoch_summary" / [epoch: Epoch] )
+            -> Option<EpochSummary> = epoch_summary,
+
+        ( "validator_stake" / [validator: Address] / [epoch: Epoch] )
+            -> Option<Historical<token::Amount>> = historical_validator_stake,
+
+        ( "validator_commission" / [validator: Address] / [epoch: Epoch] )
+            -> Option<Historical<Dec>> = historical_validator_commission_rate,
+
+        ( "validator_state" / [validator: Address] / [epoch: Epoch] )
+            -> Option<Historical<ValidatorState>> = historical_validator_state,
+
+        ( "validator_reward_rate" / [validator: Address] / [epoch: Epoch] )
+            -> Option<Historical<RewardRate>> = historical_validator_reward_rate,
+
+        ( "consensus_validator_set" / [epoch: Epoch] )
+            -> Historical<Vec<WeightedValidator>> = historical_consensus_validator_set,
+
+        ( "total_stake" / [epoch: Epoch] )
+            -> Historical<token::Amount> = historical_total_stake,
+
+        ( "reward_rate" / [epoch: Epoch] )
+            -> Historical<RewardRate> = historical_staking_reward_rate,
+    },
+
+    ( "slash_pool" ) = {
+        ( "balance" ) -> token::Amount = slash_pool_balance,
+
+        ( "inflows" ) -> BTreeMap<Epoch, token::Amount> = slash_pool_inflows,
+
+        ( "outflows" ) -> BTreeMap<Epoch, token::Amount> = slash_pool_outflows,
+    },
 }
 
 /// Enriched bonds data with extra information calculated from the data queried
@@ -170,6 +239,80 @@ impl<T> Enriched<T> {
     }
 }
 
+/// The field by which a page of validators is sorted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ValidatorSortKey {
+    /// Sort by the validator address
+    #[default]
+    Address,
+    /// Sort by the validator stake
+    Stake,
+    /// Sort by the validator commission rate
+    Commission,
+    /// Sort by the validator state, from consensus to jailed
+    State,
+    /// Sort by the validator uptime in the liveness window
+    Uptime,
+    /// Sort by the validator participation in governance
+    Governance,
+}
+
+impl Display for ValidatorSortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidatorSortKey::Address => write!(f, "address"),
+            ValidatorSortKey::Stake => write!(f, "stake"),
+            ValidatorSortKey::Commission => write!(f, "commission"),
+            ValidatorSortKey::State => write!(f, "state"),
+            ValidatorSortKey::Uptime => write!(f, "uptime"),
+            ValidatorSortKey::Governance => write!(f, "governance"),
+        }
+    }
+}
+
+impl FromStr for ValidatorSortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "address" => Ok(ValidatorSortKey::Address),
+            "stake" => Ok(ValidatorSortKey::Stake),
+            "commission" => Ok(ValidatorSortKey::Commission),
+            "state" => Ok(ValidatorSortKey::State),
+            "uptime" => Ok(ValidatorSortKey::Uptime),
+            "governance" => Ok(ValidatorSortKey::Governance),
+            _ => Err(format!("Invalid validator sort key: {s}")),
+        }
+    }
+}
+
+/// The summary of a validator returned in a page of validators
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct ValidatorSummary {
+    /// The validator address
+    pub address: Address,
+    /// The validator stake
+    pub stake: token::Amount,
+    /// The validator commission rate
+    pub commission_rate: Option<Dec>,
+    /// The validator state
+    pub state: Option<ValidatorState>,
+    /// The validator performance, if it has been in the consensus set
+    pub performance: Option<ValidatorPerformance>,
+}
+
+/// The rank of a validator state used to sort validators by state
+fn validator_state_rank(state: Option<ValidatorState>) -> u8 {
+    match state {
+        Some(ValidatorState::Consensus) => 0,
+        Some(ValidatorState::BelowCapacity) => 1,
+        Some(ValidatorState::BelowThreshold) => 2,
+        Some(ValidatorState::Inactive) => 3,
+        Some(ValidatorState::Jailed) => 4,
+        None => 5,
+    }
+}
+
 // Handlers that implement the functions via `trait StorageRead`:
 
 /// Get the PoS parameters
@@ -239,6 +382,76 @@ where
     read_all_validator_addresses(ctx.wl_storage, epoch)
 }
 
+/// Get a page of validators in any state, sorted by the given key. Ties are
+/// broken by the validator address, so that the pages are stable.
+fn validators_page<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    sort: ValidatorSortKey,
+    order: SortOrder,
+    offset: u64,
+    limit: u64,
+    epoch: Option<Epoch>,
+) -> namada_storage::Result<Page<ValidatorSummary>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    let params = read_pos_params(ctx.wl_storage)?;
+    let mut validators = read_all_validator_addresses(ctx.wl_storage, epoch)?
+        .into_iter()
+        .map(|address| {
+            let stake =
+                read_validator_stake(ctx.wl_storage, &params, &address, epoch)?;
+            let commission_rate = validator_commission_rate_handle(&address)
+                .get(ctx.wl_storage, epoch, &params)?;
+            let state = validator_state_handle(&address).get(
+                ctx.wl_storage,
+                epoch,
+                &params,
+            )?;
+            let performance =
+                validator_performance_handle().get(ctx.wl_storage, &address)?;
+            Ok(ValidatorSummary {
+                address,
+                stake,
+                commission_rate,
+                state,
+                performance,
+            })
+        })
+        .collect::<namada_storage::Result<Vec<_>>>()?;
+
+    validators.sort_by(|a, b| {
+        let ordering = match sort {
+            ValidatorSortKey::Address => std::cmp::Ordering::Equal,
+            ValidatorSortKey::Stake => a.stake.cmp(&b.stake),
+            ValidatorSortKey::Commission => {
+                a.commission_rate.cmp(&b.commission_rate)
+            }
+            ValidatorSortKey::State => validator_state_rank(a.state)
+                .cmp(&validator_state_rank(b.state)),
+            ValidatorSortKey::Uptime => {
+                let uptime = |v: &ValidatorSummary| {
+                    v.performance.as_ref().map(ValidatorPerformance::uptime)
+                };
+                uptime(a).cmp(&uptime(b))
+            }
+            ValidatorSortKey::Governance => {
+                let participation = |v: &ValidatorSummary| {
+                    v.performance
+                        .as_ref()
+                        .map(ValidatorPerformance::governance_participation)
+                };
+                participation(a).cmp(&participation(b))
+            }
+        };
+        ordering.then_with(|| a.address.cmp(&b.address))
+    });
+
+    Ok(Page::from_sorted(validators, order, offset, limit))
+}
+
 /// Get the validator commission rate and max commission rate change per epoch
 fn validator_commission<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -299,6 +512,18 @@ where
     }
 }
 
+/// Get the unique handle of the validator
+fn validator_handle<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+) -> namada_storage::Result<Option<String>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_validator_handle(ctx.wl_storage, &validator)
+}
+
 /// Get the validator state
 fn validator_state<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -356,6 +581,27 @@ where
     }
 }
 
+/// Estimate the yearly staking rewards rate of the delegations to the given
+/// validator, after its commission
+fn validator_reward_rate<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    epoch: Option<Epoch>,
+) -> namada_storage::Result<Option<RewardRate>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    let params = read_pos_params(ctx.wl_storage)?;
+    estimate_validator_staking_reward_rate(
+        ctx.wl_storage,
+        &params,
+        &validator,
+        epoch,
+    )
+}
+
 /// Get the incoming redelegation epoch for a source validator - delegator pair,
 /// if there is any.
 fn validator_incoming_redelegation<D, H, V, T>(
@@ -414,6 +660,143 @@ where
     read_total_stake(ctx.wl_storage, &params, epoch)
 }
 
+/// Estimate the yearly staking rewards rate of the network
+fn staking_reward_rate<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Option<Epoch>,
+) -> namada_storage::Result<RewardRate>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    let params = read_pos_params(ctx.wl_storage)?;
+    estimate_staking_reward_rate(ctx.wl_storage, &params, epoch)
+}
+
+/// Get the archived summary of a past epoch, if any
+fn epoch_summary<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<EpochSummary>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_epoch_summary(ctx.wl_storage, epoch)
+}
+
+/// Get the stake of a validator at the given epoch, from the archived summary
+/// of the epoch if its epoched data was pruned
+fn historical_validator_stake<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<Historical<token::Amount>>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let params = read_pos_params(ctx.wl_storage)?;
+    read_historical_validator_stake(ctx.wl_storage, &params, &validator, epoch)
+}
+
+/// Get the commission rate of a validator at the given epoch, from the
+/// archived summary of the epoch if its epoched data was pruned
+fn historical_validator_commission_rate<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<Historical<Dec>>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let params = read_pos_params(ctx.wl_storage)?;
+    read_historical_validator_commission_rate(
+        ctx.wl_storage,
+        &params,
+        &validator,
+        epoch,
+    )
+}
+
+/// Get the state of a validator at the given epoch, from the archived summary
+/// of the epoch if its epoched data was pruned
+fn historical_validator_state<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<Historical<ValidatorState>>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let params = read_pos_params(ctx.wl_storage)?;
+    read_historical_validator_state(ctx.wl_storage, &params, &validator, epoch)
+}
+
+/// Get the yearly staking rewards rate of the delegations to a validator at
+/// the given epoch, from the archived summary of the epoch if any
+fn historical_validator_reward_rate<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    epoch: Epoch,
+) -> namada_storage::Result<Option<Historical<RewardRate>>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let params = read_pos_params(ctx.wl_storage)?;
+    read_historical_validator_staking_reward_rate(
+        ctx.wl_storage,
+        &params,
+        &validator,
+        epoch,
+    )
+}
+
+/// Get the consensus validators with their stake at the given epoch, from the
+/// archived summary of the epoch if the validator set was pruned
+fn historical_consensus_validator_set<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Epoch,
+) -> namada_storage::Result<Historical<Vec<WeightedValidator>>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_historical_consensus_validator_set(ctx.wl_storage, epoch)
+}
+
+/// Get the total stake at the given epoch, from the archived summary of the
+/// epoch if its epoched data was pruned
+fn historical_total_stake<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Epoch,
+) -> namada_storage::Result<Historical<token::Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let params = read_pos_params(ctx.wl_storage)?;
+    read_historical_total_stake(ctx.wl_storage, &params, epoch)
+}
+
+/// Get the yearly staking rewards rate of the network at the given epoch,
+/// from the archived summary of the epoch if any
+fn historical_staking_reward_rate<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Epoch,
+) -> namada_storage::Result<Historical<RewardRate>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let params = read_pos_params(ctx.wl_storage)?;
+    read_historical_staking_reward_rate(ctx.wl_storage, &params, epoch)
+}
+
 fn bond_deltas<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     source: Address,
@@ -657,6 +1040,21 @@ where
     )
 }
 
+/// Native validator address by looking up its unique handle
+fn validator_by_handle<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    handle: String,
+) -> namada_storage::Result<Option<Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_proof_of_stake::storage::find_validator_by_handle(
+        ctx.wl_storage,
+        handle,
+    )
+}
+
 /// Native validator address by looking up the Tendermint address
 fn consensus_key_set<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -680,6 +1078,45 @@ where
     namada_proof_of_stake::queries::has_bonds(ctx.wl_storage, &source)
 }
 
+/// The balance of staking tokens held by the slash pool
+fn slash_pool_balance<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<token::Amount>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let staking_token = staking_token_address(ctx.wl_storage);
+    namada_proof_of_stake::token::read_balance(
+        ctx.wl_storage,
+        &staking_token,
+        &SLASH_POOL_ADDRESS,
+    )
+}
+
+/// Slashed tokens moved into the slash pool, keyed by epoch
+fn slash_pool_inflows<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<BTreeMap<Epoch, token::Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_slash_pool_flows(ctx.wl_storage, &slash_pool_inflows_handle())
+}
+
+/// Tokens disbursed or burned from the slash pool by governance, keyed by
+/// epoch
+fn slash_pool_outflows<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<BTreeMap<Epoch, token::Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_slash_pool_flows(ctx.wl_storage, &slash_pool_outflows_handle())
+}
+
 /// Client-only methods for the router type are composed from router functions.
 #[cfg(any(test, feature = "async-client"))]
 pub mod client_only_methods {
@@ -1,15 +1,21 @@
 //! Token validity predicate queries
 
+use namada_core::ledger::inflation::RewardRate;
 use namada_core::types::address::Address;
 use namada_core::types::token;
 use namada_state::{DBIter, StorageHasher, DB};
-use namada_token::{read_denom, read_total_supply};
+use namada_token::conversion::estimate_masp_reward_rate;
+use namada_token::{
+    read_denom, read_effective_native_supply, read_total_supply,
+};
 
 use crate::queries::RequestCtx;
 
 router! {TOKEN,
     ( "denomination" / [addr: Address] ) -> Option<token::Denomination> = denomination,
     ( "total_supply" / [addr: Address] ) -> token::Amount = total_supply,
+    ( "effective_native_supply" ) -> token::Amount = effective_native_supply,
+    ( "masp_reward_rate" / [addr: Address] ) -> Option<RewardRate> = masp_reward_rate,
 }
 
 /// Get the number of decimal places (in base 10) for a
@@ -37,6 +43,30 @@ where
     read_total_supply(ctx.wl_storage, &addr)
 }
 
+/// Get the effective supply of the native token, without the tokens locked in
+/// the PoS and PGF accounts
+fn effective_native_supply<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> namada_storage::Result<token::Amount>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_effective_native_supply(ctx.wl_storage)
+}
+
+/// Estimate the yearly MASP rewards rate for a token address
+fn masp_reward_rate<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    addr: Address,
+) -> namada_storage::Result<Option<RewardRate>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    estimate_masp_reward_rate(ctx.wl_storage, &addr)
+}
+
 #[cfg(any(test, feature = "async-client"))]
 pub mod client_only_methods {
     use borsh::BorshDeserialize;
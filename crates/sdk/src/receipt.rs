@@ -0,0 +1,410 @@
+//! Receipts of applied transfers, verifiable offline.
+//!
+//! A [`PaymentReceipt`] bundles the bytes of an applied transfer tx with a
+//! proof of their inclusion in the txs of a block, the header of the block,
+//! the commit of the validators on the header and the set of these
+//! validators. It can be verified without access to a node, e.g. to settle a
+//! dispute between the parties of a transfer.
+//!
+//! The verification only relies on the given validator set, so unless the
+//! hash of a trusted validator set is provided, it only proves that the
+//! transfer was included in a block of a chain signed by the validators of
+//! the receipt. The result of the application of the tx is not committed to
+//! in the block header, so it is only checked by the node generating the
+//! receipt.
+
+use borsh::BorshDeserialize;
+use data_encoding::HEXUPPER;
+use namada_core::types::hash::Hash;
+use namada_core::types::token::Transfer;
+use namada_tx::data::TxType;
+use namada_tx::Tx;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tendermint_light_client_verifier::operations::{
+    ProdVotingPowerCalculator, VotingPowerCalculator,
+};
+use tendermint_light_client_verifier::types::TrustThreshold;
+use thiserror::Error;
+
+use crate::error::{self, QueryError};
+use crate::rpc::{query_tx_response, InnerTxResult, TxEventQuery};
+use crate::tendermint::block::signed_header::SignedHeader;
+use crate::tendermint::block::Height;
+use crate::tendermint::validator::Set as ValidatorSet;
+
+/// The version of the format of the receipts
+pub const RECEIPT_VERSION: u8 = 1;
+
+/// Errors of the verification of a receipt
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Unsupported receipt version {0}")]
+    UnsupportedVersion(u8),
+    #[error("The commit is not for the header of the block")]
+    CommitMismatch,
+    #[error("The validator set is not the one of the block")]
+    ValidatorsMismatch,
+    #[error("The validator set is not the trusted one")]
+    UntrustedValidators,
+    #[error("Invalid commit signatures: {0}")]
+    InvalidSignatures(String),
+    #[error(
+        "The commit is only signed by {signed} of the total voting power \
+         {total}"
+    )]
+    InsufficientVotingPower { signed: u64, total: u64 },
+    #[error("The tx is not included in the block")]
+    NotIncluded,
+    #[error("Invalid tx: {0}")]
+    InvalidTx(String),
+    #[error("The tx hash is {0}, not the hash of the receipt")]
+    TxHashMismatch(Hash),
+    #[error("The transfer doesn't match the transfer of the tx")]
+    TransferMismatch,
+}
+
+/// A proof of the inclusion of a tx in the txs of a block, committed to by
+/// the data hash of the block header. The txs are the leaves of a CometBFT
+/// simple Merkle tree, hashed with SHA-256.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxInclusionProof {
+    /// The index of the tx in the block
+    pub index: u64,
+    /// The number of txs in the block
+    pub total: u64,
+    /// The hashes of the sibling nodes on the path from the leaf of the tx to
+    /// the root, starting from the leaf
+    pub aunts: Vec<Hash>,
+}
+
+impl TxInclusionProof {
+    /// Generate the proof of inclusion of the tx at the given index of the
+    /// txs of a block
+    pub fn new(txs: &[Vec<u8>], index: usize) -> Option<Self> {
+        if index >= txs.len() {
+            return None;
+        }
+        let leaves: Vec<Hash> = txs.iter().map(Hash::sha256).collect();
+        Some(Self {
+            index: index as u64,
+            total: txs.len() as u64,
+            aunts: aunts(&leaves, index),
+        })
+    }
+
+    /// Compute the root of the tree from the given tx and the proof
+    pub fn root(&self, tx: &[u8]) -> Option<Hash> {
+        root_from_aunts(self.index, self.total, Hash::sha256(tx), &self.aunts)
+    }
+}
+
+/// The root of the simple Merkle tree of the given leaves
+pub fn merkle_root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => Hash::sha256(b""),
+        1 => leaf_hash(&leaves[0]),
+        len => {
+            let split = split_point(len);
+            inner_hash(
+                &merkle_root(&leaves[..split]),
+                &merkle_root(&leaves[split..]),
+            )
+        }
+    }
+}
+
+fn aunts(leaves: &[Hash], index: usize) -> Vec<Hash> {
+    if leaves.len() <= 1 {
+        return vec![];
+    }
+    let split = split_point(leaves.len());
+    let (mut aunts, sibling) = if index < split {
+        (
+            aunts(&leaves[..split], index),
+            merkle_root(&leaves[split..]),
+        )
+    } else {
+        (
+            aunts(&leaves[split..], index - split),
+            merkle_root(&leaves[..split]),
+        )
+    };
+    aunts.push(sibling);
+    aunts
+}
+
+fn root_from_aunts(
+    index: u64,
+    total: u64,
+    leaf: Hash,
+    aunts: &[Hash],
+) -> Option<Hash> {
+    if index >= total {
+        return None;
+    }
+    match total {
+        0 => None,
+        1 => aunts.is_empty().then(|| leaf_hash(&leaf)),
+        _ => {
+            let (last, rest) = aunts.split_last()?;
+            let split = split_point(total as usize) as u64;
+            if index < split {
+                let left = root_from_aunts(index, split, leaf, rest)?;
+                Some(inner_hash(&left, last))
+            } else {
+                let right =
+                    root_from_aunts(index - split, total - split, leaf, rest)?;
+                Some(inner_hash(last, &right))
+            }
+        }
+    }
+}
+
+/// The largest power of 2 strictly less than the given number of leaves,
+/// which must be at least 2
+fn split_point(len: usize) -> usize {
+    let mut split = 1;
+    while split * 2 < len {
+        split *= 2;
+    }
+    split
+}
+
+fn leaf_hash(leaf: &Hash) -> Hash {
+    Hash(
+        Sha256::new()
+            .chain_update([0])
+            .chain_update(leaf)
+            .finalize()
+            .into(),
+    )
+}
+
+fn inner_hash(left: &Hash, right: &Hash) -> Hash {
+    Hash(
+        Sha256::new()
+            .chain_update([1])
+            .chain_update(left)
+            .chain_update(right)
+            .finalize()
+            .into(),
+    )
+}
+
+/// A verifiable receipt of an applied transfer
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PaymentReceipt {
+    /// The version of the format of the receipt
+    pub version: u8,
+    /// The hash of the applied tx
+    pub tx_hash: Hash,
+    /// The bytes of the tx as included in the block, hex-encoded
+    pub tx: String,
+    /// The proof of inclusion of the tx in the block
+    pub inclusion_proof: TxInclusionProof,
+    /// The header of the block and the commit of the validators on it
+    pub signed_header: SignedHeader,
+    /// The validators of the block
+    pub validators: ValidatorSet,
+    /// The transfer decoded from the tx
+    pub transfer: Transfer,
+}
+
+impl PaymentReceipt {
+    /// Verify the receipt. If the hash of a trusted validator set is given,
+    /// the receipt must have been signed by this validator set.
+    pub fn verify(
+        &self,
+        trusted_validators_hash: Option<Hash>,
+    ) -> Result<(), Error> {
+        if self.version != RECEIPT_VERSION {
+            return Err(Error::UnsupportedVersion(self.version));
+        }
+
+        // The header is signed by more than 2/3 of the voting power of its
+        // validators
+        let header = &self.signed_header.header;
+        let commit = &self.signed_header.commit;
+        if commit.height != header.height
+            || commit.block_id.hash != header.hash()
+        {
+            return Err(Error::CommitMismatch);
+        }
+        let validators_hash = self.validators.hash();
+        if validators_hash != header.validators_hash {
+            return Err(Error::ValidatorsMismatch);
+        }
+        if let Some(trusted) = trusted_validators_hash {
+            if validators_hash.as_bytes() != trusted.as_ref() {
+                return Err(Error::UntrustedValidators);
+            }
+        }
+        let tally = ProdVotingPowerCalculator::default()
+            .voting_power_in(
+                &self.signed_header,
+                &self.validators,
+                TrustThreshold::TWO_THIRDS,
+            )
+            .map_err(|err| Error::InvalidSignatures(err.to_string()))?;
+        if u128::from(tally.tallied) * 3 <= u128::from(tally.total) * 2 {
+            return Err(Error::InsufficientVotingPower {
+                signed: tally.tallied,
+                total: tally.total,
+            });
+        }
+
+        // The tx is included in the block
+        let tx_bytes = HEXUPPER
+            .decode(self.tx.as_bytes())
+            .map_err(|err| Error::InvalidTx(err.to_string()))?;
+        let root = self.inclusion_proof.root(&tx_bytes);
+        match (root, header.data_hash) {
+            (Some(root), Some(data_hash))
+                if root.as_ref() == data_hash.as_bytes() => {}
+            _ => return Err(Error::NotIncluded),
+        }
+
+        // The tx is the transfer of the receipt
+        let tx = Tx::try_from(tx_bytes.as_slice())
+            .map_err(|err| Error::InvalidTx(err.to_string()))?;
+        let (tx_hash, transfer) = decode_transfer(&tx)?;
+        if tx_hash != self.tx_hash {
+            return Err(Error::TxHashMismatch(tx_hash));
+        }
+        if transfer != self.transfer {
+            return Err(Error::TransferMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Decode the hash and the transfer of an applied tx
+fn decode_transfer(tx: &Tx) -> Result<(Hash, Transfer), Error> {
+    if !matches!(tx.header().tx_type, TxType::Decrypted(_)) {
+        return Err(Error::InvalidTx("Not an applied tx".to_string()));
+    }
+    let tx_hash = tx.clone().update_header(TxType::Raw).header_hash();
+    let data = tx
+        .data()
+        .ok_or_else(|| Error::InvalidTx("Missing tx data".to_string()))?;
+    let transfer = Transfer::try_from_slice(&data)
+        .map_err(|err| Error::InvalidTx(format!("Not a transfer: {err}")))?;
+    Ok((tx_hash, transfer))
+}
+
+/// Generate the receipt of the transfer applied by the tx of the given hash
+pub async fn gen_payment_receipt<C: crate::queries::Client + Sync>(
+    client: &C,
+    tx_hash: Hash,
+) -> Result<PaymentReceipt, error::Error> {
+    let query_err =
+        |err: &dyn std::fmt::Display| QueryError::General(err.to_string());
+    let tx_hash_str = tx_hash.to_string();
+    let response =
+        query_tx_response(client, TxEventQuery::Applied(&tx_hash_str))
+            .await
+            .map_err(|err| query_err(&err))?;
+    if !matches!(response.inner_tx_result(), InnerTxResult::Success(_)) {
+        return Err(error::Error::Other(format!(
+            "The tx {tx_hash} was not successfully applied"
+        )));
+    }
+    let height =
+        Height::try_from(response.height.0).map_err(|err| query_err(&err))?;
+
+    let block = client
+        .block(height)
+        .await
+        .map_err(|err| query_err(&err))?
+        .block;
+    let txs = block.data;
+    let (index, tx_bytes, transfer) = txs
+        .iter()
+        .enumerate()
+        .find_map(|(index, bytes)| {
+            let tx = Tx::try_from(bytes.as_slice()).ok()?;
+            let (hash, transfer) = decode_transfer(&tx).ok()?;
+            (hash == tx_hash).then_some((index, bytes, transfer))
+        })
+        .ok_or_else(|| {
+            error::Error::Other(format!(
+                "The transfer tx {tx_hash} was not found in the block at \
+                 height {height}"
+            ))
+        })?;
+    let inclusion_proof = TxInclusionProof::new(&txs, index)
+        .expect("The index of the tx must be in the block");
+    let tx = HEXUPPER.encode(tx_bytes);
+
+    let commit = client.commit(height).await.map_err(|err| query_err(&err))?;
+    if !commit.canonical {
+        return Err(error::Error::Other(format!(
+            "The commit of the block at height {height} is not final yet"
+        )));
+    }
+    let validators = client
+        .validators(height)
+        .await
+        .map_err(|err| query_err(&err))?
+        .validators;
+
+    Ok(PaymentReceipt {
+        version: RECEIPT_VERSION,
+        tx_hash,
+        tx,
+        inclusion_proof,
+        signed_header: commit.signed_header,
+        validators: ValidatorSet::without_proposer(validators),
+        transfer,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Test the Merkle root against the empty and single leaf trees of
+    /// CometBFT's simple Merkle tree (RFC 6962)
+    #[test]
+    fn test_merkle_root() {
+        assert_eq!(
+            merkle_root(&[]).to_string(),
+            "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855"
+        );
+        let leaf = Hash::sha256(b"tx");
+        assert_eq!(merkle_root(&[leaf]), leaf_hash(&leaf));
+        let leaves = [leaf, Hash::sha256(b"tx2"), Hash::sha256(b"tx3")];
+        assert_eq!(
+            merkle_root(&leaves),
+            inner_hash(
+                &inner_hash(&leaf_hash(&leaves[0]), &leaf_hash(&leaves[1])),
+                &leaf_hash(&leaves[2])
+            )
+        );
+    }
+
+    proptest! {
+        /// Test that the inclusion proof of any tx of a block verifies
+        /// against the root of the txs, but not for another tx
+        #[test]
+        fn test_tx_inclusion_proof(
+            txs in prop::collection::vec(
+                prop::collection::vec(any::<u8>(), 1..32),
+                1..20,
+            ),
+            index in any::<prop::sample::Index>(),
+        ) {
+            let index = index.index(txs.len());
+            let leaves: Vec<Hash> = txs.iter().map(Hash::sha256).collect();
+            let root = merkle_root(&leaves);
+            let proof = TxInclusionProof::new(&txs, index).unwrap();
+            prop_assert_eq!(proof.root(&txs[index]), Some(root));
+            let mut other_tx = txs[index].clone();
+            other_tx.push(0);
+            prop_assert_ne!(proof.root(&other_tx), Some(root));
+        }
+    }
+}
@@ -0,0 +1,506 @@
+//! Recipes of the common flows that take several txs.
+//!
+//! A recipe validates its arguments up-front and then builds, signs and
+//! submits its txs in the order that the flow requires. Every tx must be
+//! applied before the next one is built, as the later txs depend on the
+//! effects of the earlier ones, so a recipe stops at the first tx that isn't
+//! applied. The public keys of the implicit sources of the txs are revealed
+//! first if they haven't been yet.
+
+use std::path::PathBuf;
+
+use namada_account::AccountPolicy;
+use namada_core::types::address::{Address, ImplicitAddress};
+use namada_core::types::masp::{
+    PaymentAddress, TransferSource, TransferTarget,
+};
+use namada_core::types::token;
+use namada_tx::Tx;
+
+use crate::args::{self, InputAmount};
+use crate::error::{Error, Result, TxSubmitError};
+use crate::rpc::{self, validate_amount};
+use crate::signing::{default_sign, SigningTxData};
+use crate::tx::{
+    self, ProcessTxResponse, TX_CLAIM_REWARDS_WASM, TX_MULTI_TRANSFER_WASM,
+    TX_TRANSFER_WASM, TX_UPDATE_POLICY_WASM,
+};
+use crate::Namada;
+
+/// Stake tokens with a validator and compound the rewards of the bond.
+///
+/// The protocol doesn't compound the staking rewards by itself, so the
+/// rewards that can be claimed from the bond are claimed and bonded together
+/// with the new stake. The amount of the bond may be zero, in which case the
+/// recipe only compounds the rewards and can be run periodically.
+#[derive(Clone, Debug)]
+pub struct StakeAndCompound {
+    /// The bond of the new stake, whose tx arguments are used for every tx
+    pub bond: args::Bond,
+    /// Path to the TX WASM code file of the claim of the rewards
+    pub claim_rewards_code_path: PathBuf,
+}
+
+impl StakeAndCompound {
+    /// Make the recipe of the given bond
+    pub fn new(bond: args::Bond) -> Self {
+        Self {
+            bond,
+            claim_rewards_code_path: PathBuf::from(TX_CLAIM_REWARDS_WASM),
+        }
+    }
+
+    /// Submit the txs of the recipe: the claim of the rewards, if there are
+    /// any, and the bond of the new stake and of the claimed rewards.
+    pub async fn execute(
+        &self,
+        context: &impl Namada,
+    ) -> Result<Vec<ProcessTxResponse>> {
+        let tx_args = &self.bond.tx;
+        check_tx_args(tx_args)?;
+        let validator = &self.bond.validator;
+        if !rpc::is_validator(context.client(), validator).await?
+            && !tx_args.force
+        {
+            return Err(Error::from(TxSubmitError::InvalidValidatorAddress(
+                validator.clone(),
+            )));
+        }
+        let stake = validate_amount(
+            context,
+            self.bond.amount,
+            &context.native_token(),
+            tx_args.force,
+        )
+        .await?
+        .amount();
+        // The rewards keep accruing until the claim is applied, so a few may
+        // be left to the next compounding
+        let rewards = rpc::get_reward_tokens(
+            context.client(),
+            validator,
+            &self.bond.source,
+        )
+        .await?;
+        let total = stake.checked_add(rewards).ok_or_else(|| {
+            Error::Other("Overflow in the amount to bond".to_string())
+        })?;
+        if total.is_zero() {
+            return Err(Error::from(TxSubmitError::BondIsZero));
+        }
+
+        let mut responses = vec![];
+        if let Some(source) = &self.bond.source {
+            reveal_pk_if_needed(context, tx_args, source, &mut responses)
+                .await?;
+        }
+        if !rewards.is_zero() {
+            let claim = args::ClaimRewards {
+                tx: tx_args.clone(),
+                validator: validator.clone(),
+                source: self.bond.source.clone(),
+                shielded_target: None,
+                tx_code_path: self.claim_rewards_code_path.clone(),
+            };
+            let built = claim.build(context).await?;
+            responses.push(
+                submit_step(context, "claim of the rewards", tx_args, built)
+                    .await?,
+            );
+        }
+        let bond = args::Bond {
+            amount: InputAmount::Validated(token::DenominatedAmount::native(
+                total,
+            )),
+            ..self.bond.clone()
+        };
+        let built = bond.build(context).await?;
+        responses.push(submit_step(context, "bond", tx_args, built).await?);
+        Ok(responses)
+    }
+}
+
+/// Shield tokens from a transparent address and transfer them out over IBC
+/// from the shielded pool.
+///
+/// The tokens are shielded to the default payment address of the spending
+/// key of the IBC transfer, which then spends them. The tx arguments of the
+/// IBC transfer are used for both txs.
+#[derive(Clone, Debug)]
+pub struct ShieldAndIbcTransfer {
+    /// The transparent address the tokens are shielded from
+    pub source: Address,
+    /// The IBC transfer, whose source must be a spending key
+    pub ibc_transfer: args::TxIbcTransfer,
+    /// Path to the TX WASM code file of the shielding transfer
+    pub shield_code_path: PathBuf,
+}
+
+impl ShieldAndIbcTransfer {
+    /// Make the recipe of the given IBC transfer, that shields its amount
+    /// from the given source first
+    pub fn new(source: Address, ibc_transfer: args::TxIbcTransfer) -> Self {
+        Self {
+            source,
+            ibc_transfer,
+            shield_code_path: PathBuf::from(TX_TRANSFER_WASM),
+        }
+    }
+
+    /// The shielding transfer of the recipe
+    pub fn shield(&self) -> Result<args::TxTransfer> {
+        let spending_key = self
+            .ibc_transfer
+            .source
+            .spending_key()
+            .ok_or(TxSubmitError::UnshieldedIbcSource)?;
+        let (_, payment_address) =
+            masp_primitives::zip32::ExtendedSpendingKey::from(spending_key)
+                .default_address();
+        Ok(args::TxTransfer {
+            tx: self.ibc_transfer.tx.clone(),
+            source: TransferSource::Address(self.source.clone()),
+            target: TransferTarget::PaymentAddress(PaymentAddress::from(
+                payment_address,
+            )),
+            token: self.ibc_transfer.token.clone(),
+            amount: self.ibc_transfer.amount,
+            shielded_memo: None,
+            tx_code_path: self.shield_code_path.clone(),
+        })
+    }
+
+    /// Submit the txs of the recipe: the shielding transfer and the IBC
+    /// transfer.
+    pub async fn execute(
+        &self,
+        context: &impl Namada,
+    ) -> Result<Vec<ProcessTxResponse>> {
+        let tx_args = &self.ibc_transfer.tx;
+        check_tx_args(tx_args)?;
+        let mut shield = self.shield()?;
+        let amount = validate_amount(
+            context,
+            self.ibc_transfer.amount,
+            &self.ibc_transfer.token,
+            tx_args.force,
+        )
+        .await?;
+        if amount.is_zero() {
+            return Err(Error::Other(
+                "The amount to transfer must be positive".to_string(),
+            ));
+        }
+
+        let mut responses = vec![];
+        reveal_pk_if_needed(context, tx_args, &self.source, &mut responses)
+            .await?;
+        let (tx, signing_data, _) = shield.build(context).await?;
+        responses.push(
+            submit_step(context, "shielding", tx_args, (tx, signing_data))
+                .await?,
+        );
+        // The shielded context is synced with the chain when the IBC transfer
+        // is built, so the shielded tokens can be spent
+        let (tx, signing_data, _) = self.ibc_transfer.build(context).await?;
+        responses.push(
+            submit_step(context, "IBC transfer", tx_args, (tx, signing_data))
+                .await?,
+        );
+        Ok(responses)
+    }
+}
+
+/// Initialize a multisig account with a spending policy and fund it.
+///
+/// The account is initialized with the policy account VP and its policy is
+/// set before it's funded, so that its funds are never held without the
+/// policy. The policy is set with the public keys of the account, of which
+/// the wallet must hold enough to reach the policy quorum, and the account
+/// is funded by a batched transfer from the funder.
+#[derive(Clone, Debug)]
+pub struct InitPolicyAccount {
+    /// The initialization of the account, whose tx arguments are used for
+    /// every tx
+    pub init_account: args::TxInitAccount,
+    /// The spending policy of the account
+    pub policy: AccountPolicy,
+    /// The address that funds the account
+    pub funder: Address,
+    /// The tokens and the amounts that fund the account. The account isn't
+    /// funded if this is empty.
+    pub funds: Vec<(Address, InputAmount)>,
+    /// Path to the TX WASM code file of the update of the policy
+    pub update_policy_code_path: PathBuf,
+    /// Path to the TX WASM code file of the funding transfer
+    pub fund_code_path: PathBuf,
+}
+
+impl InitPolicyAccount {
+    /// Make the recipe of the given account initialization, policy and
+    /// funding
+    pub fn new(
+        init_account: args::TxInitAccount,
+        policy: AccountPolicy,
+        funder: Address,
+        funds: Vec<(Address, InputAmount)>,
+    ) -> Self {
+        Self {
+            init_account,
+            policy,
+            funder,
+            funds,
+            update_policy_code_path: PathBuf::from(TX_UPDATE_POLICY_WASM),
+            fund_code_path: PathBuf::from(TX_MULTI_TRANSFER_WASM),
+        }
+    }
+
+    /// Check that the threshold of the account and the quorum of its policy
+    /// can be reached by its public keys
+    pub fn validate(&self) -> Result<()> {
+        let num_keys = self.init_account.public_keys.len();
+        let threshold = match self.init_account.threshold {
+            Some(threshold) => threshold,
+            None if num_keys == 1 => 1,
+            None => {
+                return Err(Error::from(
+                    TxSubmitError::MissingAccountThreshold,
+                ));
+            }
+        };
+        if threshold == 0 || usize::from(threshold) > num_keys {
+            return Err(Error::from(TxSubmitError::InvalidAccountThreshold(
+                threshold, num_keys,
+            )));
+        }
+        if self.policy.quorum == 0 || usize::from(self.policy.quorum) > num_keys
+        {
+            return Err(Error::from(TxSubmitError::InvalidPolicyQuorum(
+                self.policy.quorum,
+                num_keys,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Submit the txs of the recipe: the initialization of the account, the
+    /// update of its policy and its funding, if any.
+    pub async fn execute(
+        &self,
+        context: &impl Namada,
+    ) -> Result<Vec<ProcessTxResponse>> {
+        let tx_args = &self.init_account.tx;
+        check_tx_args(tx_args)?;
+        self.validate()?;
+        let mut funds = Vec::with_capacity(self.funds.len());
+        for (token, amount) in &self.funds {
+            let amount =
+                validate_amount(context, *amount, token, tx_args.force).await?;
+            funds.push((token.clone(), amount));
+        }
+
+        let mut responses = vec![];
+        if !funds.is_empty() {
+            reveal_pk_if_needed(context, tx_args, &self.funder, &mut responses)
+                .await?;
+        }
+        let built = self.init_account.build(context).await?;
+        let response =
+            submit_step(context, "account initialization", tx_args, built)
+                .await?;
+        let address = response
+            .is_applied_and_valid()
+            .and_then(|result| result.initialized_accounts.first().cloned())
+            .ok_or(TxSubmitError::NoInitializedAccount)?;
+        responses.push(response);
+
+        // The policy is signed by the keys of the new account
+        let policy_tx_args = args::Tx {
+            signing_keys: self.init_account.public_keys.clone(),
+            ..tx_args.clone()
+        };
+        let policy_data = serde_json::to_vec(&self.policy)
+            .map_err(|err| Error::Other(err.to_string()))?;
+        let update_policy = args::TxUpdatePolicy {
+            tx: policy_tx_args.clone(),
+            addr: address.clone(),
+            policy_data: Some(policy_data),
+            tx_code_path: self.update_policy_code_path.clone(),
+        };
+        let built = update_policy.build(context).await?;
+        responses.push(
+            submit_step(context, "policy update", &policy_tx_args, built)
+                .await?,
+        );
+
+        if !funds.is_empty() {
+            // The funding is signed by the funder
+            let fund_tx_args = args::Tx {
+                signing_keys: vec![],
+                ..tx_args.clone()
+            };
+            let fund = args::TxMultiTransfer {
+                tx: fund_tx_args.clone(),
+                legs: funds
+                    .into_iter()
+                    .map(|(token, amount)| args::TxTransferLeg {
+                        source: self.funder.clone(),
+                        target: address.clone(),
+                        token,
+                        amount: InputAmount::Validated(amount),
+                    })
+                    .collect(),
+                tx_code_path: self.fund_code_path.clone(),
+            };
+            let built = fund.build(context).await?;
+            responses.push(
+                submit_step(context, "funding", &fund_tx_args, built).await?,
+            );
+        }
+        Ok(responses)
+    }
+}
+
+/// Check that the txs of a recipe built with the given tx arguments are
+/// applied
+fn check_tx_args(args: &args::Tx) -> Result<()> {
+    if args.dry_run
+        || args.dry_run_wrapper
+        || args.dump_tx
+        || args.broadcast_only
+    {
+        return Err(Error::from(TxSubmitError::UnappliedRecipe));
+    }
+    Ok(())
+}
+
+/// Sign and submit a tx of a recipe, failing if it isn't applied
+async fn submit_step(
+    context: &impl Namada,
+    step: &'static str,
+    args: &args::Tx,
+    (mut tx, signing_data): (Tx, SigningTxData),
+) -> Result<ProcessTxResponse> {
+    context
+        .sign(&mut tx, args, signing_data, default_sign, ())
+        .await?;
+    let response = context.submit(tx, args).await?;
+    if response.is_applied_and_valid().is_none() {
+        return Err(Error::from(TxSubmitError::RecipeStepFailed(step)));
+    }
+    Ok(response)
+}
+
+/// Reveal the public key of the given address if it's an implicit address
+/// whose key hasn't been revealed yet
+async fn reveal_pk_if_needed(
+    context: &impl Namada,
+    args: &args::Tx,
+    address: &Address,
+    responses: &mut Vec<ProcessTxResponse>,
+) -> Result<()> {
+    let Address::Implicit(ImplicitAddress(pkh)) = address else {
+        return Ok(());
+    };
+    if tx::has_revealed_pk(context.client(), address).await? {
+        return Ok(());
+    }
+    let public_key = context
+        .wallet()
+        .await
+        .find_public_key_by_pkh(pkh)
+        .map_err(|err| Error::Other(err.to_string()))?;
+    let built = tx::build_reveal_pk(context, args, &public_key).await?;
+    responses
+        .push(submit_step(context, "public key reveal", args, built).await?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::address::testing::established_address_1;
+    use namada_core::types::key::testing::{keypair_1, keypair_2};
+    use namada_core::types::key::RefTo;
+
+    use super::*;
+    use crate::tx::{TX_INIT_ACCOUNT_WASM, VP_POLICY_WASM};
+
+    /// Test that the threshold of a policy account and the quorum of its
+    /// policy must be reachable by its public keys.
+    #[test]
+    fn test_init_policy_account_validation() {
+        let tx_args = || args::Tx {
+            dry_run: false,
+            dry_run_wrapper: false,
+            dump_tx: false,
+            preview: false,
+            output_folder: None,
+            force: false,
+            broadcast_only: false,
+            ledger_address: "127.0.0.1:26657".parse().unwrap(),
+            initialized_account_alias: None,
+            wallet_alias_force: false,
+            fee_amount: None,
+            wrapper_fee_payer: None,
+            fee_token: established_address_1(),
+            fee_unshield: None,
+            gas_limit: 20_000.into(),
+            expiration: None,
+            disposable_signing_key: false,
+            chain_id: None,
+            signing_keys: vec![],
+            signatures: vec![],
+            tx_reveal_code_path: PathBuf::new(),
+            password: None,
+            memo: None,
+            use_device: false,
+        };
+        let recipe = |threshold: Option<u8>, quorum: u8| {
+            InitPolicyAccount::new(
+                args::TxInitAccount {
+                    tx: tx_args(),
+                    vp_code_path: PathBuf::from(VP_POLICY_WASM),
+                    tx_code_path: PathBuf::from(TX_INIT_ACCOUNT_WASM),
+                    public_keys: vec![
+                        keypair_1().ref_to(),
+                        keypair_2().ref_to(),
+                    ],
+                    threshold,
+                },
+                AccountPolicy {
+                    quorum,
+                    ..Default::default()
+                },
+                established_address_1(),
+                vec![],
+            )
+        };
+
+        assert!(recipe(Some(2), 1).validate().is_ok());
+        assert!(recipe(Some(1), 2).validate().is_ok());
+        assert!(matches!(
+            recipe(None, 1).validate(),
+            Err(Error::Tx(TxSubmitError::MissingAccountThreshold))
+        ));
+        assert!(matches!(
+            recipe(Some(3), 1).validate(),
+            Err(Error::Tx(TxSubmitError::InvalidAccountThreshold(3, 2)))
+        ));
+        assert!(matches!(
+            recipe(Some(1), 0).validate(),
+            Err(Error::Tx(TxSubmitError::InvalidPolicyQuorum(0, 2)))
+        ));
+        assert!(matches!(
+            recipe(Some(1), 3).validate(),
+            Err(Error::Tx(TxSubmitError::InvalidPolicyQuorum(3, 2)))
+        ));
+
+        // The dry-runs can't apply the txs of a recipe
+        let mut recipe = recipe(Some(1), 1);
+        recipe.init_account.tx.dry_run = true;
+        assert!(matches!(
+            check_tx_args(&recipe.init_account.tx),
+            Err(Error::Tx(TxSubmitError::UnappliedRecipe))
+        ));
+    }
+}
@@ -2,19 +2,23 @@
 
 use std::cell::Cell;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::marker::PhantomData;
 use std::ops::ControlFlow;
 use std::str::FromStr;
 
 use borsh::BorshDeserialize;
+use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
 use masp_primitives::merkle_tree::MerklePath;
 use masp_primitives::sapling::Node;
+use namada_account::name_service::{self, NameRecord, NameServiceParams};
 use namada_account::Account;
+use namada_core::ledger::inflation::RewardRate;
 use namada_core::types::address::{Address, InternalAddress};
 use namada_core::types::hash::Hash;
 use namada_core::types::key::common;
 use namada_core::types::storage::{
-    BlockHeight, BlockResults, Epoch, Key, PrefixValue,
+    BlockHeight, BlockResults, Epoch, Key, PrefixValue, TxIndex,
 };
 use namada_core::types::token::{
     Amount, DenominatedAmount, Denomination, MaspDigitPos,
@@ -23,19 +27,23 @@ use namada_core::types::{storage, token};
 use namada_governance::parameters::GovernanceParameters;
 use namada_governance::pgf::parameters::PgfParameters;
 use namada_governance::pgf::storage::steward::StewardDetail;
-use namada_governance::storage::proposal::StorageProposal;
+use namada_governance::storage::proposal::{StoragePgfClaim, StorageProposal};
 use namada_governance::utils::{
     compute_proposal_result, ProposalResult, ProposalVotes, Vote,
 };
+use namada_ibc::escrow::TokenReconciliation;
 use namada_ibc::storage::{
     ibc_denom_key, ibc_denom_key_prefix, is_ibc_denom_key,
 };
 use namada_proof_of_stake::parameters::PosParams;
 use namada_proof_of_stake::types::{
-    BondsAndUnbondsDetails, CommissionPair, ValidatorMetaData, ValidatorState,
+    BondsAndUnbondsDetails, CommissionPair, EpochSummary, Historical,
+    ValidatorMetaData, ValidatorState, WeightedValidator,
 };
 use namada_state::LastBlock;
-use namada_tx::data::{ResultCode, TxResult};
+use namada_token::compact::CompactBlock;
+use namada_tx::data::{ResultCode, TxErrorData, TxResult};
+use namada_tx::{Memo, TxLimits};
 use serde::Serialize;
 
 use crate::args::InputAmount;
@@ -45,8 +53,15 @@ use crate::events::Event;
 use crate::internal_macros::echo_error;
 use crate::io::Io;
 use crate::masp::MaspTokenRewardData;
-use crate::queries::vp::pos::EnrichedBondsAndUnbondsDetails;
-use crate::queries::{Client, RPC};
+use crate::queries::vp::governance::ProposalSortKey;
+use crate::queries::vp::pos::{
+    EnrichedBondsAndUnbondsDetails, ValidatorSortKey, ValidatorSummary,
+};
+use crate::queries::{
+    is_unknown_path_error, BatchQueryRequest, BatchQueryResponse, Capabilities,
+    Client, ExecutionStats, Handshake, MempoolTx, Page, SortOrder,
+    TxSimulation, RPC,
+};
 use crate::tendermint::block::Height;
 use crate::tendermint::merkle::proof::ProofOps;
 use crate::tendermint_rpc::error::Error as TError;
@@ -126,6 +141,147 @@ pub async fn query_native_token<C: crate::queries::Client + Sync>(
     convert_response::<C, _>(RPC.shell().native_token(client).await)
 }
 
+/// Query the metadata of a token, if it has any
+pub async fn query_token_metadata<C: crate::queries::Client + Sync>(
+    client: &C,
+    token: &Address,
+) -> Result<Option<token::Metadata>, error::Error> {
+    let key = namada_token::storage_key::metadata_key(token);
+    let (data, _proof) =
+        query_storage_value_bytes(client, &key, None, false).await?;
+    data.map(|data| token::Metadata::try_from_slice(&data[..]))
+        .transpose()
+        .map_err(|err| Error::from(EncodingError::Decoding(err.to_string())))
+}
+
+/// Query the minimum gas prices per fee token accepted by the node
+pub async fn query_gas_prices<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<BTreeMap<Address, token::Amount>, error::Error> {
+    convert_response::<C, _>(RPC.shell().gas_prices(client).await)
+}
+
+/// Query the protocol limits that the transactions are checked against
+pub async fn query_tx_limits<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<TxLimits, error::Error> {
+    Ok(TxLimits {
+        max_tx_bytes: query_storage_value(
+            client,
+            &parameter_storage::get_max_tx_bytes_key(),
+        )
+        .await?,
+        max_signatures_per_transaction: query_storage_value(
+            client,
+            &parameter_storage::get_max_signatures_per_transaction_key(),
+        )
+        .await?,
+        max_block_gas: query_storage_value(
+            client,
+            &parameter_storage::get_max_block_gas_key(),
+        )
+        .await?,
+        max_tx_gas: query_max_tx_gas(client).await?,
+    })
+}
+
+/// The gas limits of the blocks and of their transactions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockGasLimits {
+    /// The maximum gas of a block
+    pub max_block_gas: u64,
+    /// The maximum gas of a transaction, if lower than the one of a block
+    pub max_tx_gas: Option<u64>,
+}
+
+/// Query the gas limits of the blocks and of their transactions
+pub async fn query_block_gas_limits<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<BlockGasLimits, error::Error> {
+    Ok(BlockGasLimits {
+        max_block_gas: query_storage_value(
+            client,
+            &parameter_storage::get_max_block_gas_key(),
+        )
+        .await?,
+        max_tx_gas: query_max_tx_gas(client).await?,
+    })
+}
+
+/// Query the optional maximum gas of a transaction
+async fn query_max_tx_gas<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<Option<u64>, error::Error> {
+    let (bytes, _proof) = query_storage_value_bytes(
+        client,
+        &parameter_storage::get_max_tx_gas_key(),
+        None,
+        false,
+    )
+    .await?;
+    bytes
+        .map(|bytes| u64::try_from_slice(&bytes[..]))
+        .transpose()
+        .map_err(|err| Error::from(EncodingError::Decoding(err.to_string())))
+}
+
+/// Query the execution statistics of the tx and VP codes recorded by the node
+pub async fn query_code_stats<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<ExecutionStats, error::Error> {
+    convert_response::<C, _>(RPC.shell().code_stats(client).await)
+}
+
+/// Query the wrapper txs of the given fee payer checked by the mempool of the
+/// node and not yet included in a block
+pub async fn query_mempool_txs<C: crate::queries::Client + Sync>(
+    client: &C,
+    fee_payer: &Address,
+) -> Result<Vec<MempoolTx>, error::Error> {
+    convert_response::<C, _>(RPC.shell().mempool_txs(client, fee_payer).await)
+}
+
+/// Query all the wrapper txs checked by the mempool of the node and not yet
+/// included in a block. The node only serves this query to its operator.
+pub async fn query_mempool_queue<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<Vec<MempoolTx>, error::Error> {
+    convert_response::<C, _>(RPC.shell().mempool_queue(client).await)
+}
+
+/// Query the versions and capabilities of the node. Returns `None` if the
+/// node predates the handshake query.
+pub async fn query_handshake<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<Option<Handshake>, error::Error> {
+    let path = RPC.shell().handshake_path();
+    match client.request(path.clone(), None, None, false).await {
+        // Decode leniently, newer nodes may append fields
+        Ok(response) => Handshake::decode(&response.data)
+            .map(Some)
+            .map_err(|err| EncodingError::Decoding(err.to_string()).into()),
+        Err(err) if is_unknown_path_error(&err, &path) => Ok(None),
+        Err(err) => Err(QueryError::NoResponse(err.to_string()).into()),
+    }
+}
+
+/// Negotiate the optional features to use with the node. The nodes that
+/// predate the handshake query are assumed to have none of them. Fails if the
+/// protocol of the queries of the node is incompatible with this version.
+pub async fn query_capabilities<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<Capabilities, error::Error> {
+    match query_handshake(client).await? {
+        Some(handshake) => {
+            handshake
+                .check_compatible()
+                .map_err(QueryError::IncompatibleNode)?;
+            Ok(handshake.capabilities)
+        }
+        None => Ok(Capabilities::none()),
+    }
+}
+
 /// Query the epoch of the given block height, if it exists.
 /// Will return none if the input block height is greater than
 /// the latest committed block height.
@@ -145,6 +301,29 @@ pub async fn query_block<C: crate::queries::Client + Sync>(
     convert_response::<C, _>(RPC.shell().last_block(client).await)
 }
 
+/// Query the memo of the tx at the given index of the block at the given
+/// height, if it has one.
+pub async fn query_tx_memo<C: crate::queries::Client + Sync>(
+    client: &C,
+    height: BlockHeight,
+    index: TxIndex,
+) -> Result<Option<Memo>, error::Error> {
+    let block = client
+        .block(height.0 as u32)
+        .await
+        .map_err(|e| Error::from(QueryError::General(e.to_string())))?
+        .block
+        .data;
+    let tx_bytes = block.get(index.0 as usize).ok_or_else(|| {
+        Error::from(QueryError::General(format!(
+            "No tx at index {index} of the block {height}"
+        )))
+    })?;
+    let tx = Tx::try_from(tx_bytes.as_ref())
+        .map_err(|e| Error::Other(e.to_string()))?;
+    Ok(tx.memo())
+}
+
 /// A helper to unwrap client's response. Will shut down process on error.
 fn unwrap_client_response<C: crate::queries::Client, T>(
     response: Result<T, C::Error>,
@@ -163,6 +342,115 @@ fn convert_response<C: crate::queries::Client, T>(
     response.map_err(|err| Error::from(QueryError::NoResponse(err.to_string())))
 }
 
+/// A batch of queries to be sent in a single request with [`query_batch`]
+#[derive(Clone, Debug, Default)]
+pub struct QueryBatch {
+    queries: Vec<BatchQueryRequest>,
+}
+
+/// A handle to the result of a query added to a [`QueryBatch`], which decodes
+/// to `T`
+#[derive(Debug)]
+pub struct BatchItem<T> {
+    index: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl QueryBatch {
+    /// Add a query at the given path, e.g. `RPC.shell().epoch_path()`, to the
+    /// batch. Note that the result of a `storage_value` query is the raw
+    /// stored value, so `T` is the type of the value itself.
+    pub fn push<T: BorshDeserialize>(&mut self, path: String) -> BatchItem<T> {
+        self.push_with_data(path, vec![])
+    }
+
+    /// Add a query at the given path with the given request data to the batch
+    pub fn push_with_data<T: BorshDeserialize>(
+        &mut self,
+        path: String,
+        data: Vec<u8>,
+    ) -> BatchItem<T> {
+        self.queries.push(BatchQueryRequest { path, data });
+        BatchItem {
+            index: self.queries.len() - 1,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// The number of queries in the batch
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Check if the batch contains no queries
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+}
+
+/// The results of a [`QueryBatch`]
+#[derive(Clone, Debug)]
+pub struct QueryBatchResults {
+    results: Vec<BatchQueryResponse>,
+}
+
+impl QueryBatchResults {
+    /// Decode the result of a query from the batch
+    pub fn get<T: BorshDeserialize>(
+        &self,
+        item: &BatchItem<T>,
+    ) -> Result<T, Error> {
+        match self.results.get(item.index) {
+            Some(Ok(data)) => T::try_from_slice(&data[..]).map_err(|err| {
+                Error::from(EncodingError::Decoding(err.to_string()))
+            }),
+            Some(Err(err)) => {
+                Err(Error::from(QueryError::General(err.clone())))
+            }
+            None => Err(Error::from(QueryError::General(format!(
+                "No result for the query {} in the batch",
+                item.index
+            )))),
+        }
+    }
+}
+
+/// Send a batch of queries in a single request, all of them at the given
+/// height. The batch is limited to [`crate::queries::MAX_BATCH_QUERIES`]
+/// queries. The failure of a query doesn't affect the other queries' results.
+pub async fn query_batch<C: crate::queries::Client + Sync>(
+    client: &C,
+    batch: &QueryBatch,
+    height: Option<BlockHeight>,
+) -> Result<QueryBatchResults, Error> {
+    let data = batch.queries.serialize_to_vec();
+    let response = RPC
+        .shell()
+        .query_batch(client, Some(data), height, false)
+        .await;
+    match response {
+        Ok(response) => Ok(QueryBatchResults {
+            results: response.data,
+        }),
+        // The nodes that predate the batches are sent the queries one by one
+        Err(err)
+            if is_unknown_path_error(&err, &RPC.shell().query_batch_path()) =>
+        {
+            let mut results = Vec::with_capacity(batch.len());
+            for BatchQueryRequest { path, data } in &batch.queries {
+                let result = client
+                    .request(path.clone(), Some(data.clone()), height, false)
+                    .await
+                    .map(|response| response.data)
+                    .map_err(|err| err.to_string());
+                results.push(result);
+            }
+            Ok(QueryBatchResults { results })
+        }
+        Err(err) => Err(QueryError::NoResponse(err.to_string()).into()),
+    }
+}
+
 /// Query the results of the last committed block
 pub async fn query_results<C: crate::queries::Client + Sync>(
     client: &C,
@@ -181,14 +469,40 @@ pub async fn get_token_balance<C: crate::queries::Client + Sync>(
     )
 }
 
-/// Query token total supply.
-pub async fn get_token_total_supply<C: crate::queries::Client + Sync>(
+/// Query the allowance of a spender to transfer the owner's tokens.
+pub async fn get_token_allowance<C: crate::queries::Client + Sync>(
+    client: &C,
+    token: &Address,
+    owner: &Address,
+    spender: &Address,
+) -> Result<token::Amount, error::Error> {
+    let key = namada_token::storage_key::allowance_key(token, owner, spender);
+    let (data, _proof) =
+        query_storage_value_bytes(client, &key, None, false).await?;
+    data.map(|data| token::Amount::try_from_slice(&data[..]))
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|err| Error::from(EncodingError::Decoding(err.to_string())))
+}
+
+/// Query the total supply of a token.
+pub async fn query_total_supply<C: crate::queries::Client + Sync>(
     client: &C,
     token: &Address,
 ) -> Result<token::Amount, error::Error> {
     convert_response::<C, _>(RPC.vp().token().total_supply(client, token).await)
 }
 
+/// Query the effective supply of the native token, that is its total supply
+/// without the tokens locked in the PoS and PGF accounts.
+pub async fn query_effective_native_supply<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<token::Amount, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().token().effective_native_supply(client).await,
+    )
+}
+
 /// Check if the given address is a known validator.
 pub async fn is_validator<C: crate::queries::Client + Sync>(
     client: &C,
@@ -248,6 +562,25 @@ pub async fn query_pgf_stewards<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query the pgf retro funding claimed by the given target, if any
+pub async fn query_pgf_claim<C: crate::queries::Client + Sync>(
+    client: &C,
+    target: &Address,
+) -> Result<Option<StoragePgfClaim>, error::Error> {
+    convert_response::<C, Option<StoragePgfClaim>>(
+        RPC.vp().pgf().claim(client, target).await,
+    )
+}
+
+/// Query the pgf retro fundings claimed by their targets
+pub async fn query_pgf_claims<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<Vec<StoragePgfClaim>, error::Error> {
+    convert_response::<C, Vec<StoragePgfClaim>>(
+        RPC.vp().pgf().claims(client).await,
+    )
+}
+
 /// Query the consensus key by validator address
 pub async fn query_validator_consensus_keys<
     C: crate::queries::Client + Sync,
@@ -322,6 +655,14 @@ pub async fn query_conversions<C: crate::queries::Client + Sync>(
     convert_response::<C, _>(RPC.shell().read_conversions(client).await)
 }
 
+/// Query the gaps in the conversions of the MASP, i.e. the epochs missing a
+/// conversion of an asset between its first and last epochs.
+pub async fn query_conversion_gaps<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<Vec<token::ConversionGap>, Error> {
+    convert_response::<C, _>(RPC.shell().read_conversion_gaps(client).await)
+}
+
 /// Query to read the tokens that earn masp rewards.
 pub async fn query_masp_reward_tokens<C: crate::queries::Client + Sync>(
     client: &C,
@@ -329,6 +670,43 @@ pub async fn query_masp_reward_tokens<C: crate::queries::Client + Sync>(
     convert_response::<C, _>(RPC.shell().masp_reward_tokens(client).await)
 }
 
+/// Query the reconciliation of the IBC escrow accounting per channel and token
+/// with the escrow balances and the voucher supplies.
+pub async fn query_ibc_escrow_reconciliation<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+) -> Result<Vec<TokenReconciliation>, Error> {
+    convert_response::<C, _>(
+        RPC.shell().ibc_escrow_reconciliation(client).await,
+    )
+}
+
+/// Query the estimated yearly MASP rewards rate of the given token. Returns
+/// `None` if the token doesn't earn MASP rewards.
+pub async fn query_masp_reward_rate<C: crate::queries::Client + Sync>(
+    client: &C,
+    token: &Address,
+) -> Result<Option<RewardRate>, Error> {
+    convert_response::<C, _>(
+        RPC.vp().token().masp_reward_rate(client, token).await,
+    )
+}
+
+/// Query the compact blocks of the non-empty blocks in the given range of
+/// heights. The node may return fewer blocks than were requested, up to
+/// [`namada_token::compact::MAX_COMPACT_BLOCKS_PER_QUERY`] heights and up to
+/// its last committed block height.
+pub async fn query_compact_blocks<C: crate::queries::Client + Sync>(
+    client: &C,
+    from: BlockHeight,
+    to: BlockHeight,
+) -> Result<Vec<(BlockHeight, CompactBlock)>, Error> {
+    convert_response::<C, _>(
+        RPC.shell().compact_blocks(client, &from, &to).await,
+    )
+}
+
 /// Query a wasm code hash
 pub async fn query_wasm_code_hash(
     context: &impl Namada,
@@ -546,6 +924,19 @@ pub async fn dry_run_tx<N: Namada>(
     Ok(result)
 }
 
+/// Simulate a transaction, getting the changes of the storage values written
+/// by its inner transaction along with its result
+pub async fn simulate_tx<C: crate::queries::Client + Sync>(
+    client: &C,
+    tx_bytes: Vec<u8>,
+) -> Result<TxSimulation, Error> {
+    let (data, height, prove) = (Some(tx_bytes), None, false);
+    convert_response::<C, _>(
+        RPC.shell().simulate_tx(client, data, height, prove).await,
+    )
+    .map(|response| response.data)
+}
+
 /// Data needed for broadcasting a tx and
 /// monitoring its progress on chain
 ///
@@ -584,6 +975,14 @@ pub struct TxResponse {
     pub code: ResultCode,
     /// Gas used. If there's an `inner_tx`, its gas is equal to this value.
     pub gas_used: String,
+    /// The fee paid for the gas used by the tx, once its inner tx has been
+    /// applied
+    pub fee_paid: Option<String>,
+    /// The fee refunded to the fee payer for the unused gas of the tx, once
+    /// its inner tx has been applied
+    pub fee_refund: Option<String>,
+    /// The machine-readable data of the failure of the tx, if it failed
+    pub error: Option<TxErrorData>,
 }
 
 /// Determines a result of an inner tx from [`TxResponse::inner_tx_result`].
@@ -633,6 +1032,13 @@ impl TryFrom<Event> for TxResponse {
             .get("gas_used")
             .ok_or_else(|| missing_field_err("gas_used"))?
             .clone();
+        let fee_paid = event.get("fee_paid").cloned();
+        let fee_refund = event.get("fee_refund").cloned();
+        let error = event
+            .get("error")
+            .map(|s| TxErrorData::from_str(s))
+            .transpose()
+            .map_err(|e| e.to_string())?;
 
         Ok(TxResponse {
             inner_tx,
@@ -642,6 +1048,9 @@ impl TryFrom<Event> for TxResponse {
             height,
             code,
             gas_used,
+            fee_paid,
+            fee_refund,
+            error,
         })
     }
 }
@@ -737,6 +1146,14 @@ pub async fn query_tx_response<C: crate::queries::Client + Sync>(
         .map_err(|_| TError::parse("Error parsing ResultCode".to_string()))?;
     let height = BlockHeight::from_str(event_map["height"])
         .map_err(|_| TError::parse("Error parsing BlockHeight".to_string()))?;
+    let error = event_map
+        .get("error")
+        .map(|s| {
+            TxErrorData::from_str(s).map_err(|_| {
+                TError::parse("Error parsing TxErrorData".to_string())
+            })
+        })
+        .transpose()?;
     let result = TxResponse {
         inner_tx,
         info: event_map["info"].to_string(),
@@ -745,6 +1162,9 @@ pub async fn query_tx_response<C: crate::queries::Client + Sync>(
         hash: event_map["hash"].to_string(),
         code,
         gas_used: event_map["gas_used"].to_string(),
+        fee_paid: event_map.get("fee_paid").map(|s| s.to_string()),
+        fee_refund: event_map.get("fee_refund").map(|s| s.to_string()),
+        error,
     };
     Ok(result)
 }
@@ -769,6 +1189,105 @@ pub async fn get_all_validators<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Get a page of the validators in the given epoch, sorted by the given key.
+/// At most [`crate::queries::MAX_PAGE_SIZE`] validators are returned.
+pub async fn get_validators_page<C: crate::queries::Client + Sync>(
+    client: &C,
+    sort: ValidatorSortKey,
+    order: SortOrder,
+    offset: u64,
+    limit: u64,
+    epoch: Epoch,
+) -> Result<Page<ValidatorSummary>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .validators_page(
+                client,
+                &sort,
+                &order,
+                &offset,
+                &limit,
+                &Some(epoch),
+            )
+            .await,
+    )
+}
+
+/// Get the rewards that can be claimed from a bond. For self-bonds, the source
+/// is the validator.
+pub async fn get_reward_tokens<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+    source: &Option<Address>,
+) -> Result<token::Amount, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().rewards(client, validator, source).await,
+    )
+}
+
+/// Get the estimated yearly staking rewards rate of the network in the given
+/// epoch
+pub async fn get_staking_reward_rate<C: crate::queries::Client + Sync>(
+    client: &C,
+    epoch: Option<Epoch>,
+) -> Result<RewardRate, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().staking_reward_rate(client, &epoch).await,
+    )
+}
+
+/// Get the estimated yearly staking rewards rate of the delegations to the
+/// given validator in the given epoch, after the validator's commission.
+/// Returns `None` if the address is not a validator.
+pub async fn get_validator_reward_rate<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+    epoch: Option<Epoch>,
+) -> Result<Option<RewardRate>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .validator_reward_rate(client, validator, &epoch)
+            .await,
+    )
+}
+
+/// Get the yearly staking rewards rate of the network in the given epoch, with
+/// its provenance. The rate of an archived epoch is computed from the PoS
+/// inflation that was minted for it.
+pub async fn get_historical_staking_reward_rate<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    epoch: Epoch,
+) -> Result<Historical<RewardRate>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .historical_staking_reward_rate(client, &epoch)
+            .await,
+    )
+}
+
+/// Get the yearly staking rewards rate of the delegations to the given
+/// validator in the given epoch, after its commission, with its provenance.
+/// Returns `None` if the address wasn't a validator in the epoch.
+pub async fn get_historical_validator_reward_rate<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    validator: &Address,
+    epoch: Epoch,
+) -> Result<Option<Historical<RewardRate>>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .historical_validator_reward_rate(client, validator, &epoch)
+            .await,
+    )
+}
+
 /// Get the total staked tokens in the given epoch
 pub async fn get_total_staked_tokens<C: crate::queries::Client + Sync>(
     client: &C,
@@ -808,6 +1327,77 @@ pub async fn get_validator_state<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Get the archived summary of the given past epoch, if any
+pub async fn query_epoch_summary<C: crate::queries::Client + Sync>(
+    client: &C,
+    epoch: Epoch,
+) -> Result<Option<EpochSummary>, error::Error> {
+    convert_response::<C, _>(RPC.vp().pos().epoch_summary(client, &epoch).await)
+}
+
+/// Get the given validator's stake at the given epoch, with its provenance.
+/// The stake is read from the archived summary of the epoch if the node has
+/// pruned its epoched data. Returns `None` if the address is not a validator.
+pub async fn get_historical_validator_stake<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    epoch: Epoch,
+    validator: &Address,
+) -> Result<Option<Historical<token::Amount>>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .historical_validator_stake(client, validator, &epoch)
+            .await,
+    )
+}
+
+/// Get the total staked tokens in the given epoch, with their provenance
+pub async fn get_historical_total_staked_tokens<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    epoch: Epoch,
+) -> Result<Historical<token::Amount>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().historical_total_stake(client, &epoch).await,
+    )
+}
+
+/// Get the consensus validators with their stake in the given epoch, with
+/// their provenance
+pub async fn get_historical_consensus_validator_set<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    epoch: Epoch,
+) -> Result<Historical<Vec<WeightedValidator>>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .historical_consensus_validator_set(client, &epoch)
+            .await,
+    )
+}
+
+/// Get the given validator's state in the given epoch, with its provenance.
+/// Returns `None` if the address wasn't a validator in the epoch.
+pub async fn get_historical_validator_state<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    validator: &Address,
+    epoch: Epoch,
+) -> Result<Option<Historical<ValidatorState>>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .historical_validator_state(client, validator, &epoch)
+            .await,
+    )
+}
+
 /// Get the delegator's delegation
 pub async fn get_delegators_delegation<C: crate::queries::Client + Sync>(
     client: &C,
@@ -842,6 +1432,23 @@ pub async fn query_proposal_by_id<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query a page of the governance proposals, sorted by the given key. At most
+/// [`crate::queries::MAX_PAGE_SIZE`] proposals are returned.
+pub async fn query_proposals_page<C: crate::queries::Client + Sync>(
+    client: &C,
+    sort: ProposalSortKey,
+    order: SortOrder,
+    offset: u64,
+    limit: u64,
+) -> Result<Page<StorageProposal>, Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .gov()
+            .proposals_page(client, &sort, &order, &offset, &limit)
+            .await,
+    )
+}
+
 /// Query and return validator's commission rate and max commission rate change
 /// per epoch
 pub async fn query_commission_rate<C: crate::queries::Client + Sync>(
@@ -876,6 +1483,30 @@ pub async fn query_metadata<C: crate::queries::Client + Sync>(
     Ok((metadata, commission_info))
 }
 
+/// Query the unique handle of a validator, if it registered one
+pub async fn query_validator_handle<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+) -> Result<Option<String>, Error> {
+    convert_response::<C, Option<String>>(
+        RPC.vp().pos().validator_handle(client, validator).await,
+    )
+}
+
+/// Look up the address of the validator registered with the given handle,
+/// without its `@` prefix
+pub async fn query_validator_by_handle<C: crate::queries::Client + Sync>(
+    client: &C,
+    handle: &str,
+) -> Result<Option<Address>, Error> {
+    convert_response::<C, Option<Address>>(
+        RPC.vp()
+            .pos()
+            .validator_by_handle(client, &handle.to_string())
+            .await,
+    )
+}
+
 /// Query and return the incoming redelegation epoch for a given pair of source
 /// validator and delegator, if there is any.
 pub async fn query_incoming_redelegations<C: crate::queries::Client + Sync>(
@@ -916,6 +1547,93 @@ pub async fn query_last_infraction_epoch<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query the balance of staking tokens held by the PoS slash pool
+pub async fn query_slash_pool_balance<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<token::Amount, error::Error> {
+    convert_response::<C, _>(RPC.vp().pos().slash_pool_balance(client).await)
+}
+
+/// Query the history of the PoS slash pool. Returns the amounts of tokens that
+/// were moved into the pool and the amounts that were disbursed or burned from
+/// it, both keyed by epoch.
+pub async fn query_slash_pool_history<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<
+    (
+        BTreeMap<Epoch, token::Amount>,
+        BTreeMap<Epoch, token::Amount>,
+    ),
+    error::Error,
+> {
+    let inflows = convert_response::<C, _>(
+        RPC.vp().pos().slash_pool_inflows(client).await,
+    )?;
+    let outflows = convert_response::<C, _>(
+        RPC.vp().pos().slash_pool_outflows(client).await,
+    )?;
+    Ok((inflows, outflows))
+}
+
+/// Query the registration of a name, without its suffix, even if it's
+/// expired
+pub async fn query_name_record<C: crate::queries::Client + Sync>(
+    client: &C,
+    name: &str,
+) -> Result<Option<NameRecord>, error::Error> {
+    let key = name_service::name_key(name);
+    let (data, _proof) =
+        query_storage_value_bytes(client, &key, None, false).await?;
+    data.map(|data| NameRecord::try_from_slice(&data[..]))
+        .transpose()
+        .map_err(|err| Error::from(EncodingError::Decoding(err.to_string())))
+}
+
+/// Query the name service parameters
+pub async fn query_name_service_params<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<NameServiceParams, error::Error> {
+    let key = name_service::params_key();
+    let (data, _proof) =
+        query_storage_value_bytes(client, &key, None, false).await?;
+    data.map(|data| NameServiceParams::try_from_slice(&data[..]))
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|err| Error::from(EncodingError::Decoding(err.to_string())))
+}
+
+/// Resolve a name, without its suffix, to the address of its owner, if it's
+/// registered and not expired
+pub async fn resolve_name<C: crate::queries::Client + Sync>(
+    client: &C,
+    name: &str,
+) -> Result<Option<Address>, error::Error> {
+    let Some(record) = query_name_record(client, name).await? else {
+        return Ok(None);
+    };
+    let epoch = query_epoch(client).await?;
+    Ok((!record.is_expired(epoch)).then_some(record.owner))
+}
+
+/// Look up the name of an address, without its suffix, if it still owns it
+pub async fn lookup_name<C: crate::queries::Client + Sync>(
+    client: &C,
+    owner: &Address,
+) -> Result<Option<String>, error::Error> {
+    let key = name_service::reverse_name_key(owner);
+    let (data, _proof) =
+        query_storage_value_bytes(client, &key, None, false).await?;
+    let Some(name) = data
+        .map(|data| String::try_from_slice(&data[..]))
+        .transpose()
+        .map_err(|err| Error::from(EncodingError::Decoding(err.to_string())))?
+    else {
+        return Ok(None);
+    };
+    let is_owned = resolve_name(client, &name).await?.as_ref() == Some(owner);
+    Ok(is_owned.then_some(name))
+}
+
 /// Query the accunt substorage space of an address
 pub async fn get_account_info<C: crate::queries::Client + Sync>(
     client: &C,
@@ -1023,6 +1741,7 @@ pub async fn query_proposal_result<C: crate::queries::Client + Sync>(
                 total_staked_token,
                 tally_type,
             )
+            .map_err(|err| Error::Other(err.to_string()))?
         }
     };
     Ok(Some(proposal_result))
@@ -1222,15 +1941,16 @@ pub async fn validate_amount<N: Namada>(
         }
     }?;
     if denom < input_amount.denom() && !force {
-        display_line!(
-            context.io(),
-            "The input amount contained a higher precision than allowed by \
-             {token}."
+        let msg = format!(
+            "The input amount {input_amount} has {} decimal places, but \
+             {token} only allows {}. Provide an amount with at most {} \
+             decimal places.",
+            input_amount.denom().0,
+            denom.0,
+            denom.0,
         );
-        Err(Error::from(QueryError::General(format!(
-            "the input amount. It contained a higher precision than allowed \
-             by {token}"
-        ))))
+        display_line!(context.io(), "{msg}");
+        Err(Error::from(QueryError::General(msg)))
     } else {
         input_amount.increase_precision(denom).map_err(|_err| {
             display_line!(
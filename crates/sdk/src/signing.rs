@@ -1,5 +1,5 @@
 //! Functions to sign transactions
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 
 use borsh::BorshDeserialize;
@@ -7,10 +7,13 @@ use borsh_ext::BorshSerializeExt;
 use data_encoding::HEXLOWER;
 use itertools::Itertools;
 use masp_primitives::asset_type::AssetType;
+use masp_primitives::memo::MemoBytes;
 use masp_primitives::transaction::components::sapling::fees::{
     InputView, OutputView,
 };
-use namada_account::{AccountPublicKeysMap, InitAccount, UpdateAccount};
+use namada_account::{
+    AccountPublicKeysMap, InitAccount, UpdateAccount, UpdatePolicy,
+};
 use namada_core::types::address::{
     Address, ImplicitAddress, InternalAddress, MASP,
 };
@@ -38,7 +41,10 @@ use serde::{Deserialize, Serialize};
 use sha2::Digest;
 use tokio::sync::RwLock;
 
-use super::masp::{ShieldedContext, ShieldedTransfer};
+use super::masp::{
+    ShieldedContext, ShieldedTransfer, SpendAuthorizationRequest,
+    UnsignedShieldedTransfer,
+};
 use crate::args::SdkTypes;
 use crate::error::{EncodingError, Error, TxSubmitError};
 use crate::ibc::apps::transfer::types::msgs::transfer::MsgTransfer;
@@ -52,9 +58,9 @@ use crate::tx::{
     TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM, TX_INIT_ACCOUNT_WASM,
     TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM, TX_REDELEGATE_WASM,
     TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_WASM, TX_UNBOND_WASM,
-    TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
+    TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM, TX_UPDATE_POLICY_WASM,
     TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
-    VP_USER_WASM,
+    VP_POLICY_WASM, VP_USER_WASM,
 };
 use crate::types::eth_bridge_pool::PendingTransfer;
 pub use crate::wallet::store::AddressVpType;
@@ -272,6 +278,10 @@ where
         }
     }
 
+    // Merge the signatures of the raw header into as few sections as possible
+    // before the wrapper signs over them
+    tx.aggregate_signatures();
+
     // Then try signing the fee header with the software wallet otherwise use
     // the fallback
     let key = {
@@ -297,6 +307,29 @@ where
     Ok(())
 }
 
+/// Authorize the shielded spends of a transfer built by
+/// [`ShieldedContext::gen_unsigned_shielded_transfer`] with the external
+/// signer of its source, e.g. a hardware wallet. `sign` is given the request
+/// of authorization of each spend in turn and must return the spend
+/// authorization signature of the signer.
+pub async fn sign_shielded_spends<D, F>(
+    unsigned: UnsignedShieldedTransfer,
+    sign: impl Fn(SpendAuthorizationRequest, D) -> F,
+    user_data: D,
+) -> Result<ShieldedTransfer, Error>
+where
+    D: Clone + MaybeSend,
+    F: std::future::Future<
+        Output = Result<masp_primitives::sapling::redjubjub::Signature, Error>,
+    >,
+{
+    let mut signatures = Vec::with_capacity(unsigned.spends.len());
+    for request in &unsigned.spends {
+        signatures.push(sign(request.clone(), user_data.clone()).await?);
+    }
+    unsigned.authorize(signatures)
+}
+
 /// Return the necessary data regarding an account to be able to generate a
 /// multisignature section
 pub async fn aux_signing_data(
@@ -427,23 +460,21 @@ pub async fn wrap_tx<N: Namada>(
     fee_payer: common::PublicKey,
 ) -> Result<(), Error> {
     let fee_payer_address = Address::from(&fee_payer);
-    // Validate fee amount and token
-    let gas_cost_key = parameter_storage::get_gas_cost_key();
-    let minimum_fee = match rpc::query_storage_value::<
-        _,
-        BTreeMap<Address, Amount>,
-    >(context.client(), &gas_cost_key)
-    .await
-    .and_then(|map| {
-        map.get(&args.fee_token)
-            .map(ToOwned::to_owned)
-            .ok_or_else(|| {
-                Error::Other(format!(
-                    "Could not retrieve from storage the gas cost for token {}",
-                    args.fee_token
-                ))
-            })
-    }) {
+    // Validate fee amount and token against the minimum gas prices accepted
+    // by the node
+    let minimum_fee = match rpc::query_gas_prices(context.client())
+        .await
+        .and_then(|map| {
+            map.get(&args.fee_token)
+                .map(ToOwned::to_owned)
+                .ok_or_else(|| {
+                    Error::Other(format!(
+                        "Could not retrieve the minimum gas price accepted by \
+                         the node for token {}",
+                        args.fee_token
+                    ))
+                })
+        }) {
         Ok(amount) => amount,
         Err(e) => {
             if !args.force {
@@ -525,6 +556,7 @@ pub async fn wrap_tx<N: Namada>(
                         &target,
                         &args.fee_token,
                         fee_amount,
+                        MemoBytes::empty(),
                     )
                     .await
                 {
@@ -633,6 +665,13 @@ pub async fn wrap_tx<N: Namada>(
         }
     };
 
+    display_line!(
+        context.io(),
+        "Estimated maximum fee: {}. The fee for the gas left unused by the \
+         transaction will be refunded once it is applied.",
+        context.format_amount(&args.fee_token, total_fee).await
+    );
+
     let unshield_section_hash = unshield.map(|masp_tx| {
         let section = Section::MaspTx(masp_tx);
         let mut hasher = sha2::Sha256::new();
@@ -924,6 +963,9 @@ impl<'a> Display for LedgerProposalType<'a> {
             }
             ProposalType::PGFSteward(_) => write!(f, "PGF Steward"),
             ProposalType::PGFPayment(_) => write!(f, "PGF Payment"),
+            ProposalType::SlashPoolDisbursement(_) => {
+                write!(f, "Slash Pool Disbursement")
+            }
         }
     }
 }
@@ -981,6 +1023,8 @@ pub async fn to_ledger_vector(
             })?;
         let vp_code = if extra.tag == Some(VP_USER_WASM.to_string()) {
             "User".to_string()
+        } else if extra.tag == Some(VP_POLICY_WASM.to_string()) {
+            "Policy".to_string()
         } else {
             HEXLOWER.encode(&extra.code.hash().0)
         };
@@ -1208,6 +1252,8 @@ pub async fn to_ledger_vector(
                     })?;
                 let vp_code = if extra.tag == Some(VP_USER_WASM.to_string()) {
                     "User".to_string()
+                } else if extra.tag == Some(VP_POLICY_WASM.to_string()) {
+                    "Policy".to_string()
                 } else {
                     HEXLOWER.encode(&extra.code.hash().0)
                 };
@@ -1236,6 +1282,56 @@ pub async fn to_ledger_vector(
                 HEXLOWER.encode(&extra_code_hash.0)
             )]);
         }
+    } else if code_sec.tag == Some(TX_UPDATE_POLICY_WASM.to_string()) {
+        let update_policy = UpdatePolicy::try_from_slice(
+            &tx.data()
+                .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
+        )
+        .map_err(|err| {
+            Error::from(EncodingError::Conversion(err.to_string()))
+        })?;
+
+        tv.name = "Update_Policy_0".to_string();
+        tv.output.extend(vec![
+            format!("Type : Update Policy"),
+            format!("Address : {}", update_policy.addr),
+        ]);
+        tv.output_expert
+            .extend(vec![format!("Address : {}", update_policy.addr)]);
+        match &update_policy.policy {
+            Some(policy) => {
+                let mut lines = vec![];
+                lines.extend(
+                    policy
+                        .allowed_targets
+                        .iter()
+                        .map(|target| format!("Allowed target : {}", target)),
+                );
+                lines.extend(policy.outflow_limits.iter().map(
+                    |(token, limit)| {
+                        format!("Outflow limit : {} {}", limit, token)
+                    },
+                ));
+                lines.extend(
+                    policy
+                        .cosigners
+                        .iter()
+                        .map(|cosigner| format!("Co-signer : {}", cosigner)),
+                );
+                lines.extend(policy.cosign_thresholds.iter().map(
+                    |(token, threshold)| {
+                        format!("Co-sign threshold : {} {}", threshold, token)
+                    },
+                ));
+                lines.push(format!("Quorum : {}", policy.quorum));
+                tv.output.extend(lines.clone());
+                tv.output_expert.extend(lines);
+            }
+            None => {
+                tv.output.push("Policy : Removed".to_string());
+                tv.output_expert.push("Policy : Removed".to_string());
+            }
+        }
     } else if code_sec.tag == Some(TX_TRANSFER_WASM.to_string()) {
         let transfer = Transfer::try_from_slice(
             &tx.data()
@@ -1374,6 +1470,9 @@ pub async fn to_ledger_vector(
                 to_ledger_decimal(&bond.amount.to_string_native())
             ),
         ]);
+        if let Some(start_epoch) = bond.start_epoch {
+            tv.output.push(format!("Start epoch : {}", start_epoch));
+        }
 
         if let Some(source) = bond.source.as_ref() {
             tv.output_expert.push(format!("Source : {}", source));
@@ -1385,6 +1484,10 @@ pub async fn to_ledger_vector(
                 to_ledger_decimal(&bond.amount.to_string_native())
             ),
         ]);
+        if let Some(start_epoch) = bond.start_epoch {
+            tv.output_expert
+                .push(format!("Start epoch : {}", start_epoch));
+        }
     } else if code_sec.tag == Some(TX_UNBOND_WASM.to_string()) {
         let unbond = pos::Unbond::try_from_slice(
             &tx.data()
@@ -1407,6 +1510,9 @@ pub async fn to_ledger_vector(
                 to_ledger_decimal(&unbond.amount.to_string_native())
             ),
         ]);
+        if let Some(start_epoch) = unbond.start_epoch {
+            tv.output.push(format!("Start epoch : {}", start_epoch));
+        }
 
         if let Some(source) = unbond.source.as_ref() {
             tv.output_expert.push(format!("Source : {}", source));
@@ -1418,6 +1524,10 @@ pub async fn to_ledger_vector(
                 to_ledger_decimal(&unbond.amount.to_string_native())
             ),
         ]);
+        if let Some(start_epoch) = unbond.start_epoch {
+            tv.output_expert
+                .push(format!("Start epoch : {}", start_epoch));
+        }
     } else if code_sec.tag == Some(TX_WITHDRAW_WASM.to_string()) {
         let withdraw = pos::Withdraw::try_from_slice(
             &tx.data()
@@ -1523,6 +1633,13 @@ pub async fn to_ledger_vector(
                     .push(format!("New discord handle : {}", discord_handle));
             }
         }
+        if let Some(handle) = metadata_change.handle {
+            if handle.is_empty() {
+                other_items.push("Handle removed".to_string());
+            } else {
+                other_items.push(format!("New handle : {}", handle));
+            }
+        }
 
         tv.output.extend(other_items.clone());
         tv.output_expert.extend(other_items);
@@ -9,6 +9,7 @@ use std::time::Duration;
 use borsh::BorshSerialize;
 use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
+use masp_primitives::memo::MemoBytes;
 use masp_primitives::transaction::builder;
 use masp_primitives::transaction::builder::Builder;
 use masp_primitives::transaction::components::sapling::fees::{
@@ -18,7 +19,10 @@ use masp_primitives::transaction::components::transparent::fees::{
     InputView as TransparentInputView, OutputView as TransparentOutputView,
 };
 use masp_primitives::transaction::components::I128Sum;
-use namada_account::{InitAccount, UpdateAccount};
+use namada_account::name_service::{
+    self, RegisterName, RenewName, TransferName,
+};
+use namada_account::{AccountPolicy, InitAccount, UpdateAccount, UpdatePolicy};
 use namada_core::ibc::apps::transfer::types::msgs::transfer::MsgTransfer;
 use namada_core::ibc::apps::transfer::types::packet::PacketData;
 use namada_core::ibc::apps::transfer::types::PrefixedCoin;
@@ -37,6 +41,7 @@ use namada_core::types::time::DateTimeUtc;
 use namada_core::types::{storage, token};
 use namada_governance::cli::onchain::{
     DefaultProposal, OnChainProposal, PgfFundingProposal, PgfStewardProposal,
+    SlashPoolProposal,
 };
 use namada_governance::pgf::cli::steward::Commission;
 use namada_governance::storage::proposal::{
@@ -45,9 +50,10 @@ use namada_governance::storage::proposal::{
 use namada_governance::storage::vote::ProposalVote;
 use namada_ibc::storage::channel_key;
 use namada_proof_of_stake::parameters::PosParams;
+use namada_proof_of_stake::storage::validate_validator_handle;
 use namada_proof_of_stake::types::{CommissionPair, ValidatorState};
 use namada_token::storage_key::balance_key;
-use namada_tx::data::pgf::UpdateStewardCommission;
+use namada_tx::data::pgf::{ClaimPgfFunding, UpdateStewardCommission};
 use namada_tx::data::{pos, ResultCode, TxResult};
 pub use namada_tx::{Signature, *};
 
@@ -56,8 +62,8 @@ use crate::control_flow::time;
 use crate::error::{EncodingError, Error, QueryError, Result, TxSubmitError};
 use crate::io::Io;
 use crate::masp::TransferErr::Build;
-use crate::masp::{ShieldedContext, ShieldedTransfer};
-use crate::queries::Client;
+use crate::masp::{shielded_memo, ShieldedContext, ShieldedTransfer};
+use crate::queries::{BalanceChange, Client, StorageDiff, TxSimulation};
 use crate::rpc::{
     self, query_wasm_code_hash, validate_amount, InnerTxResult,
     TxBroadcastData, TxResponse,
@@ -66,7 +72,7 @@ use crate::signing::{self, SigningTxData, TxSourcePostBalance};
 use crate::tendermint_rpc::endpoint::broadcast::tx_sync::Response;
 use crate::tendermint_rpc::error::Error as RpcError;
 use crate::wallet::WalletIo;
-use crate::{display_line, edisplay_line, Namada};
+use crate::{display_line, edisplay_line, prompt, Namada};
 
 /// Initialize account transaction WASM
 pub const TX_INIT_ACCOUNT_WASM: &str = "tx_init_account.wasm";
@@ -86,12 +92,30 @@ pub const TX_VOTE_PROPOSAL: &str = "tx_vote_proposal.wasm";
 pub const TX_REVEAL_PK: &str = "tx_reveal_pk.wasm";
 /// Update validity predicate WASM path
 pub const TX_UPDATE_ACCOUNT_WASM: &str = "tx_update_account.wasm";
+/// Update spending policy WASM path
+pub const TX_UPDATE_POLICY_WASM: &str = "tx_update_policy.wasm";
 /// Transfer transaction WASM path
 pub const TX_TRANSFER_WASM: &str = "tx_transfer.wasm";
+/// Batched transfer transaction WASM path
+pub const TX_MULTI_TRANSFER_WASM: &str = "tx_multi_transfer.wasm";
+/// Token allowance approval transaction WASM path
+pub const TX_APPROVE_WASM: &str = "tx_approve.wasm";
+/// Transfer by an approved spender transaction WASM path
+pub const TX_TRANSFER_FROM_WASM: &str = "tx_transfer_from.wasm";
+/// Register name transaction WASM path
+pub const TX_REGISTER_NAME_WASM: &str = "tx_register_name.wasm";
+/// Renew name transaction WASM path
+pub const TX_RENEW_NAME_WASM: &str = "tx_renew_name.wasm";
+/// Transfer name transaction WASM path
+pub const TX_TRANSFER_NAME_WASM: &str = "tx_transfer_name.wasm";
+/// Create token transaction WASM path
+pub const TX_CREATE_TOKEN_WASM: &str = "tx_create_token.wasm";
 /// IBC transaction WASM path
 pub const TX_IBC_WASM: &str = "tx_ibc.wasm";
 /// User validity predicate WASM path
 pub const VP_USER_WASM: &str = "vp_user.wasm";
+/// Policy account validity predicate WASM path
+pub const VP_POLICY_WASM: &str = "vp_policy.wasm";
 /// Bond WASM path
 pub const TX_BOND_WASM: &str = "tx_bond.wasm";
 /// Unbond WASM path
@@ -100,6 +124,9 @@ pub const TX_UNBOND_WASM: &str = "tx_unbond.wasm";
 pub const TX_WITHDRAW_WASM: &str = "tx_withdraw.wasm";
 /// Claim-rewards WASM path
 pub const TX_CLAIM_REWARDS_WASM: &str = "tx_claim_rewards.wasm";
+/// Shielded claim-rewards WASM path
+pub const TX_SHIELDED_CLAIM_REWARDS_WASM: &str =
+    "tx_shielded_claim_rewards.wasm";
 /// Bridge pool WASM path
 pub const TX_BRIDGE_POOL_WASM: &str = "tx_bridge_pool.wasm";
 /// Change commission WASM path
@@ -114,6 +141,8 @@ pub const TX_RESIGN_STEWARD: &str = "tx_resign_steward.wasm";
 /// Update steward commission WASM path
 pub const TX_UPDATE_STEWARD_COMMISSION: &str =
     "tx_update_steward_commission.wasm";
+/// Claim pgf retro funding WASM path
+pub const TX_CLAIM_PGF_FUNDING_WASM: &str = "tx_claim_pgf_funding.wasm";
 /// Redelegate transaction WASM path
 pub const TX_REDELEGATE_WASM: &str = "tx_redelegate.wasm";
 
@@ -188,6 +217,9 @@ pub async fn prepare_tx(
     fee_payer: common::PublicKey,
     tx_source_balance: Option<TxSourcePostBalance>,
 ) -> Result<()> {
+    if args.preview {
+        preview_tx(context, args, tx).await?;
+    }
     if !args.dry_run {
         let epoch = rpc::query_epoch(context.client()).await?;
 
@@ -198,6 +230,104 @@ pub async fn prepare_tx(
     }
 }
 
+/// Simulate the given unsigned transaction and display the balance and storage
+/// changes it would make. Unless forced, the user is then asked to confirm
+/// that the transaction should be signed.
+pub async fn preview_tx(
+    context: &impl Namada,
+    args: &args::Tx,
+    tx: &Tx,
+) -> Result<TxSimulation> {
+    let simulation = rpc::simulate_tx(context.client(), tx.to_bytes()).await?;
+    display_tx_preview(context, &simulation).await;
+    if !args.force {
+        let answer = prompt!(
+            context.io(),
+            "Do you want to sign the transaction? [y/N]: "
+        )
+        .await;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            return Err(Error::Other(
+                "The transaction was not confirmed after its preview"
+                    .to_string(),
+            ));
+        }
+    }
+    Ok(simulation)
+}
+
+/// Display the changes made by a simulated transaction
+async fn display_tx_preview(context: &impl Namada, simulation: &TxSimulation) {
+    display_line!(context.io(), "Transaction preview:");
+    let balance_changes = simulation.balance_changes();
+    if !balance_changes.is_empty() {
+        display_line!(context.io(), "  Balance changes:");
+    }
+    for BalanceChange {
+        token,
+        owner,
+        pre,
+        post,
+    } in balance_changes
+    {
+        let (owner_alias, token_alias) = {
+            let wallet = context.wallet().await;
+            (wallet.lookup_alias(&owner), wallet.lookup_alias(&token))
+        };
+        let pre = rpc::format_denominated_amount(
+            context.client(),
+            context.io(),
+            &token,
+            pre,
+        )
+        .await;
+        let post = rpc::format_denominated_amount(
+            context.client(),
+            context.io(),
+            &token,
+            post,
+        )
+        .await;
+        display_line!(
+            context.io(),
+            "    {owner_alias}: {pre} {token_alias} -> {post} {token_alias}"
+        );
+    }
+    let mut other_changes = simulation.other_changes().peekable();
+    if other_changes.peek().is_some() {
+        display_line!(context.io(), "  Other storage changes:");
+    }
+    for StorageDiff { key, pre, post } in other_changes {
+        let change = match (pre, post) {
+            (None, Some(_)) => "added",
+            (Some(_), None) => "deleted",
+            _ => "updated",
+        };
+        display_line!(context.io(), "    {key} ({change})");
+    }
+    display_line!(
+        context.io(),
+        "  Gas used by the inner transaction: {}",
+        simulation.result.gas_used
+    );
+    if !simulation.result.is_accepted() {
+        // The transaction is simulated before it gets signed, so the VPs
+        // checking its signatures reject it
+        display_line!(
+            context.io(),
+            "  Rejected by the VPs of the unsigned transaction: {}",
+            simulation
+                .result
+                .vps_result
+                .rejected_vps
+                .iter()
+                .map(|addr| addr.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+}
+
 /// Submit transaction and wait for result. Returns a list of addresses
 /// initialized in the transaction if any. In dry run, this is always empty.
 pub async fn process_tx(
@@ -218,6 +348,14 @@ pub async fn process_tx(
     if args.dry_run || args.dry_run_wrapper {
         expect_dry_broadcast(TxBroadcastData::DryRun(tx), context).await
     } else {
+        // Check the tx against the protocol limits, rather than have the node
+        // reject it
+        if !args.force {
+            let limits = rpc::query_tx_limits(context.client()).await?;
+            tx.weight()
+                .check(&limits)
+                .map_err(|err| Error::from(TxSubmitError::from(err)))?;
+        }
         // We use this to determine when the wrapper tx makes it on-chain
         let wrapper_hash = tx.header_hash().to_string();
         // We use this to determine when the decrypted inner tx makes it
@@ -491,6 +629,13 @@ pub fn display_inner_resp(context: &impl Namada, resp: &TxResponse) {
         }
     }
 
+    if let (Some(paid), Some(refund)) = (&resp.fee_paid, &resp.fee_refund) {
+        display_line!(
+            context.io(),
+            "Actual fee paid: {paid}. Refunded for the unused gas: {refund}.",
+        );
+    }
+
     tracing::debug!(
         "Full result: {}",
         serde_json::to_string_pretty(&resp).unwrap()
@@ -682,6 +827,7 @@ pub async fn build_validator_metadata_change(
         website,
         discord_handle,
         avatar,
+        handle,
         commission_rate,
         tx_code_path,
     }: &args::MetaDataChange,
@@ -717,6 +863,37 @@ pub async fn build_validator_metadata_change(
         }
     }
 
+    // If there is a new handle, it must be valid and not registered by
+    // another validator, unless it's an empty string to remove the handle
+    if let Some(handle) = handle.as_ref().filter(|handle| !handle.is_empty()) {
+        if let Err(err) = validate_validator_handle(handle) {
+            edisplay_line!(context.io(), "{err}");
+            if !tx_args.force {
+                return Err(Error::from(
+                    TxSubmitError::InvalidValidatorHandle(handle.clone()),
+                ));
+            }
+        }
+        match rpc::query_validator_by_handle(context.client(), handle).await? {
+            Some(owner) if owner != validator => {
+                edisplay_line!(
+                    context.io(),
+                    "The validator handle {handle} is already registered by \
+                     {owner}"
+                );
+                if !tx_args.force {
+                    return Err(Error::from(
+                        TxSubmitError::ValidatorHandleTaken(
+                            handle.clone(),
+                            owner,
+                        ),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
     // If there's a new commission rate, it must be valid
     if let Some(rate) = commission_rate.as_ref() {
         if *rate < Dec::zero() || *rate > Dec::one() {
@@ -788,6 +965,7 @@ pub async fn build_validator_metadata_change(
         description: description.clone(),
         discord_handle: discord_handle.clone(),
         avatar: avatar.clone(),
+        handle: handle.clone(),
         commission_rate: *commission_rate,
     };
 
@@ -907,6 +1085,93 @@ pub async fn build_resign_steward(
     .map(|tx| (tx, signing_data))
 }
 
+/// Build a tx to claim tokens of a pgf retro funding
+pub async fn build_claim_pgf_funding(
+    context: &impl Namada,
+    args::ClaimPgfFunding {
+        tx: tx_args,
+        target,
+        amount,
+        tx_code_path,
+    }: &args::ClaimPgfFunding,
+) -> Result<(Tx, SigningTxData)> {
+    let default_signer = Some(target.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(target.clone()),
+        default_signer,
+    )
+    .await?;
+
+    // The amount is denominated by the native token
+    let amount = match amount {
+        Some(amount) => Some(
+            validate_amount(
+                context,
+                *amount,
+                &context.native_token(),
+                tx_args.force,
+            )
+            .await?
+            .amount(),
+        ),
+        None => None,
+    };
+
+    let claimable = match rpc::query_pgf_claim(context.client(), target).await?
+    {
+        Some(claim) => {
+            let current_epoch = rpc::query_epoch(context.client()).await?;
+            claim.claimable(current_epoch)
+        }
+        None => token::Amount::zero(),
+    };
+    if claimable.is_zero() {
+        edisplay_line!(
+            context.io(),
+            "The address {} has no pgf retro funding to claim in the current \
+             epoch.",
+            target
+        );
+        if !tx_args.force {
+            return Err(Error::from(TxSubmitError::NoPgfFundingToClaim(
+                target.clone(),
+            )));
+        }
+    } else if let Some(amount) = amount.filter(|amount| *amount > claimable) {
+        edisplay_line!(
+            context.io(),
+            "The requested amount {} exceeds the claimable amount {}.",
+            amount.to_string_native(),
+            claimable.to_string_native()
+        );
+        if !tx_args.force {
+            return Err(Error::from(TxSubmitError::PgfClaimTooLarge(
+                amount.to_string_native(),
+                claimable.to_string_native(),
+            )));
+        }
+    }
+
+    let data = ClaimPgfFunding {
+        target: target.clone(),
+        amount,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
 /// Submit transaction to unjail a jailed validator
 pub async fn build_unjail_validator(
     context: &impl Namada,
@@ -1158,10 +1423,19 @@ pub async fn build_redelegation(
         src_validator,
         dest_validator,
         owner,
-        amount: redel_amount,
+        amount: input_amount,
         tx_code_path,
     }: &args::Redelegate,
 ) -> Result<(Tx, SigningTxData)> {
+    // The amount is denominated by the native token
+    let redel_amount = &validate_amount(
+        context,
+        *input_amount,
+        &context.native_token(),
+        tx_args.force,
+    )
+    .await?
+    .amount();
     // Require a positive amount of tokens to be redelegated
     if redel_amount.is_zero() {
         edisplay_line!(
@@ -1424,6 +1698,7 @@ pub async fn build_claim_rewards(
         tx: tx_args,
         validator,
         source,
+        shielded_target,
         tx_code_path,
     }: &args::ClaimRewards,
 ) -> Result<(Tx, SigningTxData)> {
@@ -1450,14 +1725,85 @@ pub async fn build_claim_rewards(
         None => Ok(source.clone()),
     }?;
 
-    let data = pos::ClaimRewards { validator, source };
+    let Some(shielded_target) = shielded_target else {
+        let data = pos::ClaimRewards { validator, source };
+
+        return build(
+            context,
+            tx_args,
+            tx_code_path.clone(),
+            data,
+            do_nothing,
+            &signing_data.fee_payer,
+            None,
+        )
+        .await
+        .map(|tx| (tx, signing_data));
+    };
+
+    // All the rewards are shielded, so their amount has to be known upfront.
+    // It only changes in a new epoch, which also invalidates the shielded
+    // part of the tx. A shielded claim of no rewards can't be valid.
+    let rewards =
+        rpc::get_reward_tokens(context.client(), &validator, &source).await?;
+    if rewards.is_zero() {
+        return Err(Error::from(TxSubmitError::NoRewardsToClaim(validator)));
+    }
+    let native_token = context.native_token();
+    let shielded_parts = construct_shielded_parts(
+        context,
+        &TransferSource::Address(Address::Internal(InternalAddress::PoS)),
+        &TransferTarget::PaymentAddress(*shielded_target),
+        &native_token,
+        token::DenominatedAmount::native(rewards),
+        MemoBytes::empty(),
+    )
+    .await?
+    .ok_or_else(|| {
+        Error::Other(
+            "Failed to build the shielded part of the claim".to_string(),
+        )
+    })?;
+
+    let data = pos::ShieldedClaimRewards {
+        validator,
+        source,
+        key: shielded_target.is_pinned().then(|| shielded_target.hash()),
+        // Linked to the MASP Transaction by hash code below
+        shielded: Hash::default(),
+    };
+    let add_shielded = |tx: &mut Tx, data: &mut pos::ShieldedClaimRewards| {
+        let (
+            ShieldedTransfer {
+                builder,
+                masp_tx,
+                metadata,
+                epoch: _,
+            },
+            asset_types,
+        ) = shielded_parts;
+        // Add a MASP Transaction section to the Tx and get the tx hash
+        let masp_tx_hash = tx.add_masp_tx_section(masp_tx).1;
+        data.shielded = masp_tx_hash;
+
+        tx.add_masp_builder(MaspBuilder {
+            asset_types,
+            // Store how the Info objects map to Descriptors/Outputs
+            metadata,
+            // Store the data that was used to construct the Transaction
+            builder,
+            // Link the Builder to the Transaction by hash code
+            target: masp_tx_hash,
+        });
+        Ok(())
+    };
 
     build(
         context,
         tx_args,
         tx_code_path.clone(),
         data,
-        do_nothing,
+        add_shielded,
         &signing_data.fee_payer,
         None,
     )
@@ -1465,17 +1811,60 @@ pub async fn build_claim_rewards(
     .map(|tx| (tx, signing_data))
 }
 
+/// Check that the start epoch of a scheduled bond or unbond, if any, is after
+/// the current epoch and within the maximum scheduling offset
+async fn check_scheduled_epoch(
+    context: &impl Namada,
+    start_epoch: Option<Epoch>,
+    force: bool,
+) -> Result<()> {
+    if let Some(start_epoch) = start_epoch {
+        let params: PosParams = rpc::get_pos_params(context.client()).await?;
+        let current_epoch = rpc::query_epoch(context.client()).await?;
+        if start_epoch <= current_epoch
+            || start_epoch > current_epoch + params.max_scheduling_offset
+        {
+            edisplay_line!(
+                context.io(),
+                "The requested start epoch {} must be after the current epoch \
+                 {} and at most {} epochs later.",
+                start_epoch,
+                current_epoch,
+                params.max_scheduling_offset
+            );
+            if !force {
+                return Err(Error::from(TxSubmitError::InvalidScheduledEpoch(
+                    start_epoch,
+                    current_epoch,
+                    params.max_scheduling_offset,
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Submit a transaction to unbond
 pub async fn build_unbond(
     context: &impl Namada,
     args::Unbond {
         tx: tx_args,
         validator,
-        amount,
+        amount: input_amount,
         source,
+        start_epoch,
         tx_code_path,
     }: &args::Unbond,
 ) -> Result<(Tx, SigningTxData, Option<(Epoch, token::Amount)>)> {
+    // The amount is denominated by the native token
+    let amount = &validate_amount(
+        context,
+        *input_amount,
+        &context.native_token(),
+        tx_args.force,
+    )
+    .await?
+    .amount();
     // Require a positive amount of tokens to be bonded
     if amount.is_zero() {
         edisplay_line!(
@@ -1488,6 +1877,8 @@ pub async fn build_unbond(
         }
     }
 
+    check_scheduled_epoch(context, *start_epoch, tx_args.force).await?;
+
     // The validator must actually be a validator
     let validator =
         known_validator_or_err(validator.clone(), tx_args.force, context)
@@ -1552,6 +1943,7 @@ pub async fn build_unbond(
         validator: validator.clone(),
         amount: *amount,
         source: source.clone(),
+        start_epoch: *start_epoch,
     };
 
     let tx = build(
@@ -1644,11 +2036,21 @@ pub async fn build_bond(
     args::Bond {
         tx: tx_args,
         validator,
-        amount,
+        amount: input_amount,
         source,
+        start_epoch,
         tx_code_path,
     }: &args::Bond,
 ) -> Result<(Tx, SigningTxData)> {
+    // The amount is denominated by the native token
+    let amount = &validate_amount(
+        context,
+        *input_amount,
+        &context.native_token(),
+        tx_args.force,
+    )
+    .await?
+    .amount();
     // Require a positive amount of tokens to be bonded
     if amount.is_zero() {
         edisplay_line!(
@@ -1661,6 +2063,8 @@ pub async fn build_bond(
         }
     }
 
+    check_scheduled_epoch(context, *start_epoch, tx_args.force).await?;
+
     // The validator must actually be a validator
     let validator =
         known_validator_or_err(validator.clone(), tx_args.force, context)
@@ -1737,6 +2141,7 @@ pub async fn build_bond(
         validator,
         amount: *amount,
         source,
+        start_epoch: *start_epoch,
     };
 
     build(
@@ -1761,6 +2166,7 @@ pub async fn build_default_proposal(
         is_offline: _,
         is_pgf_stewards: _,
         is_pgf_funding: _,
+        is_slash_pool: _,
         tx_code_path,
     }: &args::InitProposal,
     proposal: DefaultProposal,
@@ -1900,6 +2306,7 @@ pub async fn build_pgf_funding_proposal(
         is_offline: _,
         is_pgf_stewards: _,
         is_pgf_funding: _,
+        is_slash_pool: _,
         tx_code_path,
     }: &args::InitProposal,
     proposal: PgfFundingProposal,
@@ -1935,6 +2342,51 @@ pub async fn build_pgf_funding_proposal(
     .map(|tx| (tx, signing_data))
 }
 
+/// Build a slash pool disbursement proposal governance
+pub async fn build_slash_pool_proposal(
+    context: &impl Namada,
+    args::InitProposal {
+        tx,
+        proposal_data: _,
+        is_offline: _,
+        is_pgf_stewards: _,
+        is_pgf_funding: _,
+        is_slash_pool: _,
+        tx_code_path,
+    }: &args::InitProposal,
+    proposal: SlashPoolProposal,
+) -> Result<(Tx, SigningTxData)> {
+    let default_signer = Some(proposal.proposal.author.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx,
+        Some(proposal.proposal.author.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let init_proposal_data = InitProposalData::try_from(proposal.clone())
+        .map_err(|e| TxSubmitError::InvalidProposal(e.to_string()))?;
+
+    let add_section = |tx: &mut Tx, data: &mut InitProposalData| {
+        let (_, extra_section_hash) =
+            tx.add_extra_section(proposal_to_vec(proposal.proposal)?, None);
+        data.content = extra_section_hash;
+        Ok(())
+    };
+    build(
+        context,
+        tx,
+        tx_code_path.clone(),
+        init_proposal_data,
+        add_section,
+        &signing_data.fee_payer,
+        None, // TODO: need to pay the fee to submit a proposal
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
 /// Build a pgf funding proposal governance
 pub async fn build_pgf_stewards_proposal(
     context: &impl Namada,
@@ -1944,6 +2396,7 @@ pub async fn build_pgf_stewards_proposal(
         is_offline: _,
         is_pgf_stewards: _,
         is_pgf_funding: _,
+        is_slash_pool: _,
         tx_code_path,
     }: &args::InitProposal,
     proposal: PgfStewardProposal,
@@ -2041,6 +2494,7 @@ pub async fn build_ibc_transfer(
         &TransferTarget::Address(Address::Internal(InternalAddress::Ibc)),
         &args.token,
         validated_amount,
+        MemoBytes::empty(),
     )
     .await?;
     let shielded_tx_epoch = shielded_parts.as_ref().map(|trans| trans.0.epoch);
@@ -2350,12 +2804,17 @@ pub async fn build_transfer<N: Namada>(
         _ => None,
     };
 
+    let memo = match &args.shielded_memo {
+        Some(memo) => shielded_memo(memo)?,
+        None => MemoBytes::empty(),
+    };
     let shielded_parts = construct_shielded_parts(
         context,
         &args.source,
         &args.target,
         &args.token,
         validated_amount,
+        memo,
     )
     .await?;
     let shielded_tx_epoch = shielded_parts.as_ref().map(|trans| trans.0.epoch);
@@ -2421,6 +2880,7 @@ async fn construct_shielded_parts<N: Namada>(
     target: &TransferTarget,
     token: &Address,
     amount: token::DenominatedAmount,
+    memo: MemoBytes,
 ) -> Result<Option<(ShieldedTransfer, HashSet<AssetData>)>> {
     // Precompute asset types to increase chances of success in decoding
     let _ = context
@@ -2430,7 +2890,7 @@ async fn construct_shielded_parts<N: Namada>(
         .await;
     let stx_result =
         ShieldedContext::<N::ShieldedUtils>::gen_shielded_transfer(
-            context, source, target, token, amount,
+            context, source, target, token, amount, memo,
         )
         .await;
 
@@ -2516,6 +2976,13 @@ pub async fn build_init_account(
     .map(|tx| (tx, signing_data))
 }
 
+/// Predict the address of the account that an `init_account` tx initializes,
+/// before it's submitted. The address is derived from the raw header hash of
+/// the tx, so it doesn't change when the tx is signed or wrapped.
+pub fn predict_init_account_address(tx: &Tx) -> Address {
+    Address::derive_established(&tx.raw_header_hash(), 0)
+}
+
 /// Submit a transaction to update a VP
 pub async fn build_update_account(
     context: &impl Namada,
@@ -2603,6 +3070,413 @@ pub async fn build_update_account(
     .map(|tx| (tx, signing_data))
 }
 
+/// Submit a transaction to update an account's spending policy
+pub async fn build_update_policy(
+    context: &impl Namada,
+    args::TxUpdatePolicy {
+        tx: tx_args,
+        addr,
+        policy_data,
+        tx_code_path,
+    }: &args::TxUpdatePolicy,
+) -> Result<(Tx, SigningTxData)> {
+    let default_signer = Some(addr.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(addr.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let account = rpc::get_account_info(context.client(), addr).await?;
+    let addr = match &account {
+        Some(account) => account.address.clone(),
+        None if tx_args.force => addr.clone(),
+        None => {
+            return Err(Error::from(TxSubmitError::LocationDoesNotExist(
+                addr.clone(),
+            )));
+        }
+    };
+
+    let policy = policy_data
+        .as_ref()
+        .map(|data| serde_json::from_slice::<AccountPolicy>(data))
+        .transpose()
+        .map_err(|e| Error::Other(format!("Invalid policy data: {e}")))?;
+    if let (Some(policy), Some(account)) = (&policy, &account) {
+        let num_keys = account.public_keys_map.idx_to_pk.len();
+        if (policy.quorum == 0 || usize::from(policy.quorum) > num_keys)
+            && !tx_args.force
+        {
+            return Err(Error::from(TxSubmitError::InvalidPolicyQuorum(
+                policy.quorum,
+                num_keys,
+            )));
+        }
+    }
+
+    let data = UpdatePolicy { addr, policy };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
+/// Submit a batch of transparent transfers from a single source
+pub async fn build_multi_transfer(
+    context: &impl Namada,
+    args::TxMultiTransfer {
+        tx: tx_args,
+        legs,
+        tx_code_path,
+    }: &args::TxMultiTransfer,
+) -> Result<(Tx, SigningTxData)> {
+    // The source signs the whole batch
+    let source = &legs
+        .first()
+        .ok_or(TxSubmitError::EmptyMultiTransfer)?
+        .source;
+    if let Some(leg) = legs.iter().find(|leg| leg.source != *source) {
+        return Err(Error::from(TxSubmitError::MultiTransferSources(
+            source.clone(),
+            leg.source.clone(),
+        )));
+    }
+    let default_signer = Some(source.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(source.clone()),
+        default_signer,
+    )
+    .await?;
+
+    // Check that the source address exists on chain
+    source_exists_or_err(source.clone(), tx_args.force, context).await?;
+
+    let mut totals: BTreeMap<&Address, token::Amount> = BTreeMap::new();
+    let mut transfer_legs = Vec::with_capacity(legs.len());
+    for leg in legs {
+        // Check that the target address exists on chain
+        target_exists_or_err(leg.target.clone(), tx_args.force, context)
+            .await?;
+        let amount =
+            validate_amount(context, leg.amount, &leg.token, tx_args.force)
+                .await?;
+        let total = totals.entry(&leg.token).or_default();
+        *total = total.checked_add(amount.amount()).ok_or_else(|| {
+            Error::Other("Overflow in the total amount of a token".to_string())
+        })?;
+        transfer_legs.push(token::TransferLeg {
+            source: source.clone(),
+            target: leg.target.clone(),
+            token: leg.token.clone(),
+            amount,
+        });
+    }
+    // Check the source balance of every token against the sum of its legs
+    for (token, total) in totals {
+        check_balance_too_low_err(
+            token,
+            source,
+            total,
+            balance_key(token, source),
+            tx_args.force,
+            context,
+        )
+        .await?;
+    }
+
+    let data = token::MultiTransfer {
+        legs: transfer_legs,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
+/// Submit a transaction to approve a spender to transfer the owner's tokens
+pub async fn build_approve(
+    context: &impl Namada,
+    args::TxApprove {
+        tx: tx_args,
+        owner,
+        spender,
+        token,
+        amount,
+        tx_code_path,
+    }: &args::TxApprove,
+) -> Result<(Tx, SigningTxData)> {
+    let default_signer = Some(owner.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(owner.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let amount =
+        validate_amount(context, *amount, token, tx_args.force).await?;
+    let data = token::Approve {
+        owner: owner.clone(),
+        spender: spender.clone(),
+        token: token.clone(),
+        amount,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
+/// Submit a transfer of the owner's tokens by an approved spender
+pub async fn build_transfer_from(
+    context: &impl Namada,
+    args::TxTransferFrom {
+        tx: tx_args,
+        owner,
+        spender,
+        target,
+        token,
+        amount,
+        tx_code_path,
+    }: &args::TxTransferFrom,
+) -> Result<(Tx, SigningTxData)> {
+    // The spender signs the transfer, the owner has approved it beforehand
+    let default_signer = Some(spender.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(spender.clone()),
+        default_signer,
+    )
+    .await?;
+
+    // Check that the target address exists on chain
+    target_exists_or_err(target.clone(), tx_args.force, context).await?;
+
+    let amount =
+        validate_amount(context, *amount, token, tx_args.force).await?;
+    let allowance =
+        rpc::get_token_allowance(context.client(), token, owner, spender)
+            .await?;
+    if allowance < amount.amount() {
+        let err = TxSubmitError::AllowanceTooLow(
+            owner.clone(),
+            spender.clone(),
+            token.clone(),
+            amount.amount().to_string_native(),
+            allowance.to_string_native(),
+        );
+        if tx_args.force {
+            edisplay_line!(context.io(), "{}", err);
+        } else {
+            return Err(Error::from(err));
+        }
+    }
+    check_balance_too_low_err(
+        token,
+        owner,
+        amount.amount(),
+        balance_key(token, owner),
+        tx_args.force,
+        context,
+    )
+    .await?;
+
+    let data = token::TransferFrom {
+        owner: owner.clone(),
+        spender: spender.clone(),
+        target: target.clone(),
+        token: token.clone(),
+        amount,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
+/// Submit a transaction to register a name
+pub async fn build_register_name(
+    context: &impl Namada,
+    args::RegisterName {
+        tx: tx_args,
+        name,
+        owner,
+        epochs,
+        tx_code_path,
+    }: &args::RegisterName,
+) -> Result<(Tx, SigningTxData)> {
+    if !name_service::is_valid_name(name) {
+        return Err(Error::Other(format!("Invalid name {name}")));
+    }
+    if rpc::resolve_name(context.client(), name).await?.is_some()
+        && !tx_args.force
+    {
+        return Err(Error::Other(format!(
+            "The name {name}{} is already registered",
+            name_service::NAME_SUFFIX
+        )));
+    }
+
+    let default_signer = Some(owner.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(owner.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let data = RegisterName {
+        name: name.clone(),
+        owner: owner.clone(),
+        epochs: *epochs,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
+/// Query the owner of a name that is not expired, for the owner to sign a
+/// renewal or a transfer
+async fn query_name_owner(
+    context: &impl Namada,
+    name: &str,
+) -> Result<Address> {
+    rpc::resolve_name(context.client(), name)
+        .await?
+        .ok_or_else(|| {
+            Error::Other(format!(
+                "The name {name}{} is not registered",
+                name_service::NAME_SUFFIX
+            ))
+        })
+}
+
+/// Submit a transaction to extend the registration of a name
+pub async fn build_renew_name(
+    context: &impl Namada,
+    args::RenewName {
+        tx: tx_args,
+        name,
+        epochs,
+        tx_code_path,
+    }: &args::RenewName,
+) -> Result<(Tx, SigningTxData)> {
+    // The owner pays the fee of the renewal
+    let owner = query_name_owner(context, name).await?;
+    let default_signer = Some(owner.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(owner),
+        default_signer,
+    )
+    .await?;
+
+    let data = RenewName {
+        name: name.clone(),
+        epochs: *epochs,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
+/// Submit a transaction to transfer a name
+pub async fn build_transfer_name(
+    context: &impl Namada,
+    args::TransferName {
+        tx: tx_args,
+        name,
+        new_owner,
+        tx_code_path,
+    }: &args::TransferName,
+) -> Result<(Tx, SigningTxData)> {
+    let owner = query_name_owner(context, name).await?;
+    let default_signer = Some(owner.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(owner),
+        default_signer,
+    )
+    .await?;
+
+    let data = TransferName {
+        name: name.clone(),
+        new_owner: new_owner.clone(),
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|tx| (tx, signing_data))
+}
+
 /// Submit a custom transaction
 pub async fn build_custom(
     context: &impl Namada,
@@ -2703,6 +3577,7 @@ pub async fn gen_ibc_shielded_transfer<N: Namada>(
             &args.target,
             &token,
             validated_amount,
+            MemoBytes::empty(),
         )
         .await
         .map_err(|err| TxSubmitError::MaspError(err.to_string()))?;
@@ -0,0 +1,952 @@
+//! Interoperable encodings for exporting and importing wallet keys.
+//!
+//! Keys can be exchanged with other wallets and tooling either as unified
+//! keys, which bundle the transparent and shielded keys of an account into a
+//! single Bech32m string in the style of Zcash's unified viewing keys, or as
+//! a JSON keystore holding a password encrypted unified spending key.
+//!
+//! The shielded viewing keys of an account can also be exported as unified
+//! full or incoming viewing keys in the encoding of Zcash's
+//! [ZIP 316](https://zips.z.cash/zip-0316), so that auditors can be given a
+//! read-only access to the account with the tooling that supports it.
+
+use blake2b_simd::Params as Blake2bParams;
+use borsh::BorshDeserialize;
+use borsh_ext::BorshSerializeExt;
+use data_encoding::HEXLOWER;
+use masp_primitives::ff::PrimeField;
+use masp_primitives::jubjub;
+use masp_primitives::sapling::{Diversifier, SaplingIvk};
+use namada_core::impl_display_and_from_str_via_format;
+use namada_core::types::key::{common, RefTo};
+use namada_core::types::masp::{
+    ExtendedSpendingKey, ExtendedViewingKey, PaymentAddress,
+};
+use namada_core::types::secret::SecretBytes;
+use namada_core::types::string_encoding::{
+    self, DecodeError, UNIFIED_SPENDING_KEY_HRP, UNIFIED_VIEWING_KEY_HRP,
+    ZIP316_FULL_VIEWING_KEY_HRP, ZIP316_INCOMING_VIEWING_KEY_HRP,
+};
+use orion::{aead, kdf};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The typecode of transparent keys in unified keys
+const TRANSPARENT_TYPECODE: u8 = 0x00;
+/// The typecode of shielded keys in unified keys
+const SHIELDED_TYPECODE: u8 = 0x01;
+
+/// The typecode of Sapling keys in ZIP 316 unified keys
+const ZIP316_SAPLING_TYPECODE: u64 = 0x02;
+/// The length of the padding of ZIP 316 unified keys, made of their HRP
+const ZIP316_PADDING_LEN: usize = 16;
+/// The length of the Sapling full viewing key encoding `ak || nk || ovk ||
+/// dk` of ZIP 316
+const ZIP316_SAPLING_FVK_LEN: usize = 128;
+/// The length of the Sapling incoming viewing key encoding `dk || ivk` of ZIP
+/// 316
+const ZIP316_SAPLING_IVK_LEN: usize = 64;
+/// The offset of the full viewing key in the encoding of extended full
+/// viewing keys, after their depth, parent tag, child index and chain code
+const XFVK_FVK_OFFSET: usize = 41;
+/// The minimum length of the messages of F4Jumble
+const F4JUMBLE_MIN_LEN: usize = 48;
+/// The maximum length of the messages of F4Jumble
+const F4JUMBLE_MAX_LEN: usize = 4_194_368;
+
+/// The current version of the JSON keystore format
+pub const KEYSTORE_VERSION: u32 = 1;
+/// The cipher used by JSON keystores
+pub const KEYSTORE_CIPHER: &str = "xchacha20-poly1305";
+/// The key derivation function used by JSON keystores
+pub const KEYSTORE_KDF: &str = "argon2i";
+
+/// Errors of the key export and import encodings
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum InteropError {
+    #[error(
+        "Unknown key export format {0}, expected one of: raw, unified, \
+         unified-viewing, zip316-viewing, zip316-incoming-viewing, keystore"
+    )]
+    UnknownFormat(String),
+    #[error("Unsupported keystore version {0}, expected {KEYSTORE_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("Unsupported keystore cipher {0}, expected {KEYSTORE_CIPHER}")]
+    UnsupportedCipher(String),
+    #[error("Unsupported keystore KDF {0}, expected {KEYSTORE_KDF}")]
+    UnsupportedKdf(String),
+    #[error("Invalid keystore KDF parameters")]
+    InvalidKdfParams,
+    #[error("Invalid hex encoding in keystore: {0}")]
+    InvalidHex(data_encoding::DecodeError),
+    #[error("Failed to decrypt the keystore, the password may be wrong")]
+    Decryption,
+    #[error("Invalid unified key in keystore: {0}")]
+    InvalidKey(DecodeError),
+}
+
+/// The format of exported keys
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyExportFormat {
+    /// The binary encoding of the wallet's own keys
+    #[default]
+    Raw,
+    /// A unified spending key string
+    Unified,
+    /// A unified viewing key string, without any secret key
+    UnifiedViewing,
+    /// A ZIP 316 unified full viewing key string
+    Zip316Viewing,
+    /// A ZIP 316 unified incoming viewing key string
+    Zip316IncomingViewing,
+    /// A password encrypted JSON keystore
+    Keystore,
+}
+
+impl std::fmt::Display for KeyExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Raw => write!(f, "raw"),
+            Self::Unified => write!(f, "unified"),
+            Self::UnifiedViewing => write!(f, "unified-viewing"),
+            Self::Zip316Viewing => write!(f, "zip316-viewing"),
+            Self::Zip316IncomingViewing => {
+                write!(f, "zip316-incoming-viewing")
+            }
+            Self::Keystore => write!(f, "keystore"),
+        }
+    }
+}
+
+impl std::str::FromStr for KeyExportFormat {
+    type Err = InteropError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "raw" => Ok(Self::Raw),
+            "unified" => Ok(Self::Unified),
+            "unified-viewing" => Ok(Self::UnifiedViewing),
+            "zip316-viewing" => Ok(Self::Zip316Viewing),
+            "zip316-incoming-viewing" => Ok(Self::Zip316IncomingViewing),
+            "keystore" => Ok(Self::Keystore),
+            _ => Err(InteropError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// A unified spending key, bundling the secret keys of an account
+#[derive(Clone, Debug)]
+pub struct UnifiedSpendingKey {
+    /// The transparent secret key
+    pub transparent: Option<common::SecretKey>,
+    /// The shielded spending key
+    pub shielded: Option<ExtendedSpendingKey>,
+}
+
+/// A unified viewing key, bundling the public keys of an account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnifiedViewingKey {
+    /// The transparent public key
+    pub transparent: Option<common::PublicKey>,
+    /// The shielded full viewing key
+    pub shielded: Option<ExtendedViewingKey>,
+}
+
+impl UnifiedSpendingKey {
+    /// Get the viewing key corresponding to this spending key
+    pub fn to_viewing_key(&self) -> UnifiedViewingKey {
+        UnifiedViewingKey {
+            transparent: self.transparent.as_ref().map(RefTo::ref_to),
+            shielded: self.shielded.map(|xsk| {
+                let xsk =
+                    masp_primitives::zip32::ExtendedSpendingKey::from(xsk);
+                masp_primitives::zip32::ExtendedFullViewingKey::from(&xsk)
+                    .into()
+            }),
+        }
+    }
+}
+
+/// Encode the given items as a list of typecodes, lengths and values
+fn encode_items(items: &[(u8, Option<Vec<u8>>)]) -> Vec<u8> {
+    let mut bytes = vec![];
+    for (typecode, value) in items {
+        if let Some(value) = value {
+            let len = u16::try_from(value.len())
+                .expect("unified key items should fit in 64 KiB");
+            bytes.push(*typecode);
+            bytes.extend(len.to_le_bytes());
+            bytes.extend(value);
+        }
+    }
+    bytes
+}
+
+/// Decode a list of typecodes, lengths and values. The typecodes must be
+/// unique and in ascending order. Items with unknown typecodes are ignored,
+/// so that keys extended with new items can still be imported.
+fn decode_items(mut bytes: &[u8]) -> Result<Vec<(u8, &[u8])>, DecodeError> {
+    let mut items: Vec<(u8, &[u8])> = vec![];
+    while let [typecode, len_lo, len_hi, rest @ ..] = bytes {
+        let len = u16::from_le_bytes([*len_lo, *len_hi]) as usize;
+        if rest.len() < len {
+            return Err(DecodeError::InvalidInnerEncoding(
+                "truncated unified key item".to_string(),
+            ));
+        }
+        if items.last().is_some_and(|(last, _)| last >= typecode) {
+            return Err(DecodeError::UnexpectedDiscriminant(*typecode));
+        }
+        items.push((*typecode, &rest[..len]));
+        bytes = &rest[len..];
+    }
+    if !bytes.is_empty() {
+        return Err(DecodeError::InvalidInnerEncoding(
+            "trailing bytes in unified key".to_string(),
+        ));
+    }
+    if items.is_empty() {
+        return Err(DecodeError::InvalidInnerEncoding(
+            "empty unified key".to_string(),
+        ));
+    }
+    Ok(items)
+}
+
+/// Decode the known items of a unified key
+fn decode_known_items<T, S>(
+    bytes: &[u8],
+    decode_transparent: impl Fn(&[u8]) -> Result<T, DecodeError>,
+    decode_shielded: impl Fn(&[u8]) -> Result<S, DecodeError>,
+) -> Result<(Option<T>, Option<S>), DecodeError> {
+    let mut transparent = None;
+    let mut shielded = None;
+    for (typecode, value) in decode_items(bytes)? {
+        match typecode {
+            TRANSPARENT_TYPECODE => {
+                transparent = Some(decode_transparent(value)?)
+            }
+            SHIELDED_TYPECODE => shielded = Some(decode_shielded(value)?),
+            _ => {}
+        }
+    }
+    if transparent.is_none() && shielded.is_none() {
+        return Err(DecodeError::InvalidInnerEncoding(
+            "unified key without known items".to_string(),
+        ));
+    }
+    Ok((transparent, shielded))
+}
+
+fn decode_borsh<T: BorshDeserialize>(bytes: &[u8]) -> Result<T, DecodeError> {
+    T::try_from_slice(bytes).map_err(DecodeError::InvalidBytes)
+}
+
+impl string_encoding::Format for UnifiedSpendingKey {
+    type EncodedBytes<'a> = Vec<u8>;
+
+    const HRP: &'static str = UNIFIED_SPENDING_KEY_HRP;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        encode_items(&[
+            (
+                TRANSPARENT_TYPECODE,
+                self.transparent.as_ref().map(|sk| sk.serialize_to_vec()),
+            ),
+            (
+                SHIELDED_TYPECODE,
+                self.shielded
+                    .as_ref()
+                    .map(string_encoding::Format::to_bytes),
+            ),
+        ])
+    }
+
+    fn decode_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let (transparent, shielded) = decode_known_items(
+            bytes,
+            decode_borsh,
+            <ExtendedSpendingKey as string_encoding::Format>::decode_bytes,
+        )?;
+        Ok(Self {
+            transparent,
+            shielded,
+        })
+    }
+}
+
+impl_display_and_from_str_via_format!(UnifiedSpendingKey);
+
+impl string_encoding::Format for UnifiedViewingKey {
+    type EncodedBytes<'a> = Vec<u8>;
+
+    const HRP: &'static str = UNIFIED_VIEWING_KEY_HRP;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        encode_items(&[
+            (
+                TRANSPARENT_TYPECODE,
+                self.transparent.as_ref().map(|pk| pk.serialize_to_vec()),
+            ),
+            (SHIELDED_TYPECODE, self.shielded.map(|vk| vk.to_bytes())),
+        ])
+    }
+
+    fn decode_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let (transparent, shielded) =
+            decode_known_items(bytes, decode_borsh, |bytes| {
+                ExtendedViewingKey::decode_bytes(bytes).map_err(|err| {
+                    DecodeError::InvalidInnerEncoding(err.to_string())
+                })
+            })?;
+        Ok(Self {
+            transparent,
+            shielded,
+        })
+    }
+}
+
+impl_display_and_from_str_via_format!(UnifiedViewingKey);
+
+/// A unified full viewing key in the encoding of ZIP 316, holding the
+/// shielded full viewing key of an account. The encoding only keeps the keys
+/// of a full viewing key, so a decoded key has the depth, parent tag, child
+/// index and chain code of a master key and can't derive the child keys of
+/// the original key.
+#[derive(Clone, Copy, Debug)]
+pub struct Zip316FullViewingKey {
+    /// The shielded full viewing key
+    pub shielded: ExtendedViewingKey,
+}
+
+/// A unified incoming viewing key in the encoding of ZIP 316, which only
+/// detects the notes received by the payment addresses of an account
+#[derive(Clone, Debug)]
+pub struct Zip316IncomingViewingKey {
+    /// The diversifier key, from which the diversifiers of the payment
+    /// addresses are derived
+    pub diversifier_key: [u8; 32],
+    /// The shielded incoming viewing key
+    pub ivk: SaplingIvk,
+}
+
+impl Zip316FullViewingKey {
+    /// Get the incoming viewing key corresponding to this full viewing key
+    pub fn to_incoming_viewing_key(&self) -> Zip316IncomingViewingKey {
+        let xfvk =
+            masp_primitives::zip32::ExtendedFullViewingKey::from(self.shielded);
+        // The diversifier key ends the Sapling full viewing key encoding
+        let mut diversifier_key = [0; 32];
+        diversifier_key.copy_from_slice(
+            &self.shielded.to_bytes()
+                [XFVK_FVK_OFFSET + ZIP316_SAPLING_FVK_LEN - 32..],
+        );
+        Zip316IncomingViewingKey {
+            diversifier_key,
+            ivk: xfvk.fvk.vk.ivk(),
+        }
+    }
+}
+
+impl Zip316IncomingViewingKey {
+    /// Get the payment address with the given diversifier, if it's valid
+    pub fn to_payment_address(
+        &self,
+        diversifier: Diversifier,
+    ) -> Option<PaymentAddress> {
+        self.ivk.to_payment_address(diversifier).map(Into::into)
+    }
+}
+
+impl string_encoding::Format for Zip316FullViewingKey {
+    type EncodedBytes<'a> = Vec<u8>;
+
+    const HRP: &'static str = ZIP316_FULL_VIEWING_KEY_HRP;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let fvk = self.shielded.to_bytes()[XFVK_FVK_OFFSET..].to_vec();
+        encode_zip316_items(Self::HRP, &[(ZIP316_SAPLING_TYPECODE, fvk)])
+    }
+
+    fn decode_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let fvk = decode_zip316_sapling_item(
+            Self::HRP,
+            bytes,
+            ZIP316_SAPLING_FVK_LEN,
+        )?;
+        // Complete the keys with the derivation info of a master key
+        let mut xfvk = vec![0; XFVK_FVK_OFFSET];
+        xfvk.extend(fvk);
+        let shielded = ExtendedViewingKey::decode_bytes(&xfvk)
+            .map_err(DecodeError::InvalidBytes)?;
+        Ok(Self { shielded })
+    }
+}
+
+impl_display_and_from_str_via_format!(Zip316FullViewingKey);
+
+impl string_encoding::Format for Zip316IncomingViewingKey {
+    type EncodedBytes<'a> = Vec<u8>;
+
+    const HRP: &'static str = ZIP316_INCOMING_VIEWING_KEY_HRP;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut ivk = self.diversifier_key.to_vec();
+        ivk.extend(self.ivk.0.to_repr());
+        encode_zip316_items(Self::HRP, &[(ZIP316_SAPLING_TYPECODE, ivk)])
+    }
+
+    fn decode_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let ivk = decode_zip316_sapling_item(
+            Self::HRP,
+            bytes,
+            ZIP316_SAPLING_IVK_LEN,
+        )?;
+        let mut diversifier_key = [0; 32];
+        diversifier_key.copy_from_slice(&ivk[..32]);
+        let mut repr = [0; 32];
+        repr.copy_from_slice(&ivk[32..]);
+        let ivk = Option::from(jubjub::Fr::from_repr(repr))
+            .map(SaplingIvk)
+            .ok_or_else(|| {
+                DecodeError::InvalidInnerEncoding(
+                    "non-canonical incoming viewing key".to_string(),
+                )
+            })?;
+        Ok(Self {
+            diversifier_key,
+            ivk,
+        })
+    }
+}
+
+impl_display_and_from_str_via_format!(Zip316IncomingViewingKey);
+
+/// Encode the given items of a ZIP 316 unified key, in ascending order of
+/// typecode, with its padding and jumbled
+fn encode_zip316_items(hrp: &str, items: &[(u64, Vec<u8>)]) -> Vec<u8> {
+    let mut bytes = vec![];
+    for (typecode, value) in items {
+        write_compact_size(&mut bytes, *typecode);
+        write_compact_size(&mut bytes, value.len() as u64);
+        bytes.extend(value);
+    }
+    bytes.extend(zip316_padding(hrp));
+    f4jumble(&bytes).expect("unified keys should fit in F4Jumble messages")
+}
+
+/// Decode the items of a ZIP 316 unified key, whose typecodes must be unique
+/// and in ascending order
+fn decode_zip316_items(
+    hrp: &str,
+    bytes: &[u8],
+) -> Result<Vec<(u64, Vec<u8>)>, DecodeError> {
+    let invalid =
+        |msg: &str| DecodeError::InvalidInnerEncoding(msg.to_string());
+    let bytes = f4jumble_inv(bytes)
+        .ok_or_else(|| invalid("invalid length of ZIP 316 unified key"))?;
+    let (mut bytes, padding) = bytes.split_at(bytes.len() - ZIP316_PADDING_LEN);
+    if padding != zip316_padding(hrp) {
+        return Err(invalid("invalid padding of ZIP 316 unified key"));
+    }
+    let mut items: Vec<(u64, Vec<u8>)> = vec![];
+    while !bytes.is_empty() {
+        let typecode = read_compact_size(&mut bytes)
+            .ok_or_else(|| invalid("invalid ZIP 316 unified key typecode"))?;
+        let len = read_compact_size(&mut bytes)
+            .and_then(|len| usize::try_from(len).ok())
+            .filter(|len| *len <= bytes.len())
+            .ok_or_else(|| invalid("truncated ZIP 316 unified key item"))?;
+        if items.last().is_some_and(|(last, _)| *last >= typecode) {
+            return Err(invalid("unordered ZIP 316 unified key items"));
+        }
+        items.push((typecode, bytes[..len].to_vec()));
+        bytes = &bytes[len..];
+    }
+    Ok(items)
+}
+
+/// Decode the Sapling item of the given length of a ZIP 316 unified key. The
+/// items of other pools, such as Orchard's, are ignored.
+fn decode_zip316_sapling_item(
+    hrp: &str,
+    bytes: &[u8],
+    len: usize,
+) -> Result<Vec<u8>, DecodeError> {
+    let item = decode_zip316_items(hrp, bytes)?
+        .into_iter()
+        .find(|(typecode, _)| *typecode == ZIP316_SAPLING_TYPECODE)
+        .map(|(_, value)| value)
+        .ok_or_else(|| {
+            DecodeError::InvalidInnerEncoding(
+                "ZIP 316 unified key without a Sapling key".to_string(),
+            )
+        })?;
+    if item.len() != len {
+        return Err(DecodeError::InvalidInnerEncoding(format!(
+            "invalid length {} of the Sapling key, expected {len}",
+            item.len()
+        )));
+    }
+    Ok(item)
+}
+
+/// The padding of ZIP 316 unified keys, made of their HRP followed by zeros
+fn zip316_padding(hrp: &str) -> [u8; ZIP316_PADDING_LEN] {
+    let mut padding = [0; ZIP316_PADDING_LEN];
+    padding[..hrp.len()].copy_from_slice(hrp.as_bytes());
+    padding
+}
+
+/// Write a Bitcoin compact size
+fn write_compact_size(bytes: &mut Vec<u8>, value: u64) {
+    match value {
+        0..=0xfc => bytes.push(value as u8),
+        0xfd..=0xffff => {
+            bytes.push(0xfd);
+            bytes.extend((value as u16).to_le_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            bytes.push(0xfe);
+            bytes.extend((value as u32).to_le_bytes());
+        }
+        _ => {
+            bytes.push(0xff);
+            bytes.extend(value.to_le_bytes());
+        }
+    }
+}
+
+/// Read a Bitcoin compact size, which must be in its shortest encoding
+fn read_compact_size(bytes: &mut &[u8]) -> Option<u64> {
+    let (first, rest) = bytes.split_first()?;
+    let (len, min) = match first {
+        0xfd => (2, 0xfd),
+        0xfe => (4, 0x1_0000),
+        0xff => (8, 0x1_0000_0000),
+        value => {
+            *bytes = rest;
+            return Some(u64::from(*value));
+        }
+    };
+    let mut le_bytes = [0; 8];
+    le_bytes[..len].copy_from_slice(rest.get(..len)?);
+    *bytes = &rest[len..];
+    Some(u64::from_le_bytes(le_bytes)).filter(|value| *value >= min)
+}
+
+/// Jumble a message with the F4Jumble permutation of ZIP 316, which makes
+/// the partial collisions of unified keys as hard as their full collisions
+fn f4jumble(message: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = message.to_vec();
+    let (left, right) = f4jumble_split(&mut bytes)?;
+    xor(right, &f4jumble_g(0, left, right.len()));
+    xor(left, &f4jumble_h(0, right, left.len()));
+    xor(right, &f4jumble_g(1, left, right.len()));
+    xor(left, &f4jumble_h(1, right, left.len()));
+    Some(bytes)
+}
+
+/// Invert the F4Jumble permutation of ZIP 316
+fn f4jumble_inv(message: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = message.to_vec();
+    let (left, right) = f4jumble_split(&mut bytes)?;
+    xor(left, &f4jumble_h(1, right, left.len()));
+    xor(right, &f4jumble_g(1, left, right.len()));
+    xor(left, &f4jumble_h(0, right, left.len()));
+    xor(right, &f4jumble_g(0, left, right.len()));
+    Some(bytes)
+}
+
+/// Split a message of F4Jumble into its left part, of at most 64 bytes, and
+/// its right part
+fn f4jumble_split(bytes: &mut [u8]) -> Option<(&mut [u8], &mut [u8])> {
+    if !(F4JUMBLE_MIN_LEN..=F4JUMBLE_MAX_LEN).contains(&bytes.len()) {
+        return None;
+    }
+    let left_len = std::cmp::min(64, bytes.len() / 2);
+    Some(bytes.split_at_mut(left_len))
+}
+
+/// The hash of the right part of F4Jumble that masks its left part
+fn f4jumble_h(round: u8, input: &[u8], len: usize) -> Vec<u8> {
+    let mut personal = *b"UA_F4Jumble_H\0\0\0";
+    personal[13] = round;
+    Blake2bParams::new()
+        .hash_length(len)
+        .personal(&personal)
+        .hash(input)
+        .as_bytes()
+        .to_vec()
+}
+
+/// The hash of the left part of F4Jumble that masks its right part, made of
+/// as many 64 bytes hashes as needed
+fn f4jumble_g(round: u8, input: &[u8], len: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(len);
+    let mut index: u16 = 0;
+    while mask.len() < len {
+        let mut personal = *b"UA_F4Jumble_G\0\0\0";
+        personal[13] = round;
+        personal[14..].copy_from_slice(&index.to_le_bytes());
+        mask.extend_from_slice(
+            Blake2bParams::new()
+                .hash_length(64)
+                .personal(&personal)
+                .hash(input)
+                .as_bytes(),
+        );
+        index = index.wrapping_add(1);
+    }
+    mask.truncate(len);
+    mask
+}
+
+fn xor(bytes: &mut [u8], mask: &[u8]) {
+    bytes
+        .iter_mut()
+        .zip(mask)
+        .for_each(|(byte, mask)| *byte ^= mask);
+}
+
+/// The parameters of the key derivation function of a JSON keystore
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// The hex encoded salt
+    pub salt: String,
+    /// The number of iterations
+    pub iterations: u32,
+    /// The memory to use, in KiB
+    pub memory: u32,
+    /// The length of the derived key, in bytes
+    pub dklen: u32,
+}
+
+/// The encrypted part of a JSON keystore
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    /// The cipher
+    pub cipher: String,
+    /// The hex encoded nonce, ciphertext and authentication tag
+    pub ciphertext: String,
+    /// The key derivation function
+    pub kdf: String,
+    /// The parameters of the key derivation function
+    pub kdfparams: KdfParams,
+}
+
+/// A JSON keystore holding a password encrypted unified spending key
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    /// The version of the keystore format
+    pub version: u32,
+    /// The alias of the key in the exporting wallet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// The unified viewing key corresponding to the encrypted key
+    pub viewing_key: String,
+    /// The encrypted unified spending key
+    pub crypto: KeystoreCrypto,
+}
+
+impl Keystore {
+    /// Encrypt the given key with the given password
+    pub fn encrypt(
+        key: &UnifiedSpendingKey,
+        alias: Option<String>,
+        password: &str,
+    ) -> Self {
+        const ITERATIONS: u32 = 3;
+        const MEMORY: u32 = 1 << 17;
+        const DKLEN: u32 = 32;
+
+        let salt = kdf::Salt::default();
+        let encryption_key =
+            derive_key(&salt, password, ITERATIONS, MEMORY, DKLEN)
+                .expect("Generation of encryption secret key shouldn't fail");
+        let plaintext = SecretBytes::new(key.to_string().into_bytes());
+        let ciphertext = aead::seal(&encryption_key, plaintext.expose())
+            .expect("Encryption of data shouldn't fail");
+        Self {
+            version: KEYSTORE_VERSION,
+            alias,
+            viewing_key: key.to_viewing_key().to_string(),
+            crypto: KeystoreCrypto {
+                cipher: KEYSTORE_CIPHER.to_string(),
+                ciphertext: HEXLOWER.encode(&ciphertext),
+                kdf: KEYSTORE_KDF.to_string(),
+                kdfparams: KdfParams {
+                    salt: HEXLOWER.encode(salt.as_ref()),
+                    iterations: ITERATIONS,
+                    memory: MEMORY,
+                    dklen: DKLEN,
+                },
+            },
+        }
+    }
+
+    /// Decrypt the key with the given password
+    pub fn decrypt(
+        &self,
+        password: &str,
+    ) -> Result<UnifiedSpendingKey, InteropError> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(InteropError::UnsupportedVersion(self.version));
+        }
+        if self.crypto.cipher != KEYSTORE_CIPHER {
+            return Err(InteropError::UnsupportedCipher(
+                self.crypto.cipher.clone(),
+            ));
+        }
+        if self.crypto.kdf != KEYSTORE_KDF {
+            return Err(InteropError::UnsupportedKdf(self.crypto.kdf.clone()));
+        }
+        let KdfParams {
+            salt,
+            iterations,
+            memory,
+            dklen,
+        } = &self.crypto.kdfparams;
+        let salt = HEXLOWER
+            .decode(salt.as_bytes())
+            .map_err(InteropError::InvalidHex)?;
+        let salt = kdf::Salt::from_slice(&salt)
+            .map_err(|_| InteropError::InvalidKdfParams)?;
+        let encryption_key =
+            derive_key(&salt, password, *iterations, *memory, *dklen)
+                .ok_or(InteropError::InvalidKdfParams)?;
+        let ciphertext = HEXLOWER
+            .decode(self.crypto.ciphertext.as_bytes())
+            .map_err(InteropError::InvalidHex)?;
+        let plaintext = aead::open(&encryption_key, &ciphertext)
+            .map(SecretBytes::new)
+            .map_err(|_| InteropError::Decryption)?;
+        let plaintext = std::str::from_utf8(plaintext.expose())
+            .map_err(|_| InteropError::Decryption)?;
+        string_encoding::Format::decode(plaintext)
+            .map_err(InteropError::InvalidKey)
+    }
+}
+
+/// Make an encryption secret key from a password
+fn derive_key(
+    salt: &kdf::Salt,
+    password: &str,
+    iterations: u32,
+    memory: u32,
+    dklen: u32,
+) -> Option<kdf::SecretKey> {
+    kdf::Password::from_slice(password.as_bytes())
+        .and_then(|password| {
+            kdf::derive_key(&password, salt, iterations, memory, dklen)
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::key::testing::{keypair_1, keypair_2};
+    use namada_core::types::string_encoding::Format;
+    use rand_core::OsRng;
+
+    use super::*;
+    use crate::masp::find_valid_diversifier;
+
+    fn spending_key() -> ExtendedSpendingKey {
+        masp_primitives::zip32::ExtendedSpendingKey::master(&[0; 32]).into()
+    }
+
+    #[test]
+    fn test_unified_keys_roundtrip() {
+        let key = UnifiedSpendingKey {
+            transparent: Some(keypair_1()),
+            shielded: Some(spending_key()),
+        };
+        let encoded = key.to_string();
+        assert!(encoded.starts_with(UNIFIED_SPENDING_KEY_HRP));
+        let decoded: UnifiedSpendingKey = encoded.parse().unwrap();
+        assert_eq!(decoded.to_string(), encoded);
+
+        let viewing_key = key.to_viewing_key();
+        let decoded: UnifiedViewingKey =
+            viewing_key.to_string().parse().unwrap();
+        assert_eq!(decoded, viewing_key);
+
+        // A unified key may hold only some of the keys
+        let key = UnifiedSpendingKey {
+            transparent: None,
+            shielded: Some(spending_key()),
+        };
+        let decoded: UnifiedSpendingKey = key.to_string().parse().unwrap();
+        assert!(decoded.transparent.is_none());
+        assert!(decoded.shielded.is_some());
+    }
+
+    #[test]
+    fn test_unified_key_items() {
+        // Unknown items are ignored
+        let bytes = encode_items(&[
+            (SHIELDED_TYPECODE, Some(spending_key().to_bytes())),
+            (0x10, Some(vec![1, 2, 3])),
+        ]);
+        let key = UnifiedSpendingKey::decode_bytes(&bytes).unwrap();
+        assert!(key.shielded.is_some());
+
+        // Unordered, truncated and empty keys are rejected
+        let bytes = encode_items(&[
+            (SHIELDED_TYPECODE, Some(vec![1])),
+            (TRANSPARENT_TYPECODE, Some(vec![2])),
+        ]);
+        assert!(decode_items(&bytes).is_err());
+        let bytes = encode_items(&[(SHIELDED_TYPECODE, Some(vec![1, 2]))]);
+        assert!(decode_items(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode_items(&[]).is_err());
+        let bytes = encode_items(&[(0x10, Some(vec![1]))]);
+        assert!(UnifiedSpendingKey::decode_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_zip316_viewing_keys_roundtrip() {
+        let xsk =
+            masp_primitives::zip32::ExtendedSpendingKey::from(spending_key());
+        let xfvk = masp_primitives::zip32::ExtendedFullViewingKey::from(&xsk);
+        let key = Zip316FullViewingKey {
+            shielded: xfvk.into(),
+        };
+        let encoded = key.to_string();
+        assert!(encoded.starts_with(ZIP316_FULL_VIEWING_KEY_HRP));
+        let decoded: Zip316FullViewingKey = encoded.parse().unwrap();
+        assert_eq!(decoded.to_string(), encoded);
+        // The keys are kept, without the derivation info
+        let decoded = masp_primitives::zip32::ExtendedFullViewingKey::from(
+            decoded.shielded,
+        );
+        let (div, _g_d) = find_valid_diversifier(&mut OsRng);
+        assert_eq!(
+            decoded.fvk.vk.to_payment_address(div),
+            xfvk.fvk.vk.to_payment_address(div)
+        );
+
+        let ivk = key.to_incoming_viewing_key();
+        let encoded = ivk.to_string();
+        assert!(encoded.starts_with(ZIP316_INCOMING_VIEWING_KEY_HRP));
+        let decoded: Zip316IncomingViewingKey = encoded.parse().unwrap();
+        assert_eq!(decoded.to_string(), encoded);
+        assert_eq!(
+            decoded.to_payment_address(div),
+            xfvk.fvk
+                .vk
+                .to_payment_address(div)
+                .map(PaymentAddress::from)
+        );
+
+        // Full and incoming viewing keys are not mistaken for one another
+        assert!(encoded.parse::<Zip316FullViewingKey>().is_err());
+        assert!(key.to_string().parse::<Zip316IncomingViewingKey>().is_err());
+    }
+
+    #[test]
+    fn test_zip316_items() {
+        let fvk = vec![1; ZIP316_SAPLING_FVK_LEN];
+        // The items of other pools are ignored
+        let bytes = encode_zip316_items(
+            ZIP316_FULL_VIEWING_KEY_HRP,
+            &[(ZIP316_SAPLING_TYPECODE, fvk.clone()), (0x03, vec![2; 96])],
+        );
+        let item = decode_zip316_sapling_item(
+            ZIP316_FULL_VIEWING_KEY_HRP,
+            &bytes,
+            ZIP316_SAPLING_FVK_LEN,
+        )
+        .unwrap();
+        assert_eq!(item, fvk);
+
+        // Keys with another padding, unordered items or without a Sapling
+        // item of the expected length are rejected
+        assert!(decode_zip316_items(ZIP316_INCOMING_VIEWING_KEY_HRP, &bytes)
+            .is_err());
+        let bytes = encode_zip316_items(
+            ZIP316_FULL_VIEWING_KEY_HRP,
+            &[(0x03, vec![2; 96]), (ZIP316_SAPLING_TYPECODE, fvk)],
+        );
+        assert!(
+            decode_zip316_items(ZIP316_FULL_VIEWING_KEY_HRP, &bytes).is_err()
+        );
+        let bytes = encode_zip316_items(
+            ZIP316_FULL_VIEWING_KEY_HRP,
+            &[(0x03, vec![2; 96])],
+        );
+        assert!(decode_zip316_sapling_item(
+            ZIP316_FULL_VIEWING_KEY_HRP,
+            &bytes,
+            ZIP316_SAPLING_FVK_LEN
+        )
+        .is_err());
+        let bytes = encode_zip316_items(
+            ZIP316_FULL_VIEWING_KEY_HRP,
+            &[(ZIP316_SAPLING_TYPECODE, vec![1; 64])],
+        );
+        assert!(decode_zip316_sapling_item(
+            ZIP316_FULL_VIEWING_KEY_HRP,
+            &bytes,
+            ZIP316_SAPLING_FVK_LEN
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_f4jumble_roundtrip() {
+        for len in [F4JUMBLE_MIN_LEN, 83, 128, 129, 200, 1000] {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let jumbled = f4jumble(&message).unwrap();
+            assert_eq!(jumbled.len(), len);
+            assert_ne!(jumbled, message);
+            assert_eq!(f4jumble_inv(&jumbled).unwrap(), message);
+        }
+        assert!(f4jumble(&[0; F4JUMBLE_MIN_LEN - 1]).is_none());
+    }
+
+    #[test]
+    fn test_compact_size() {
+        for value in [0, 0xfc, 0xfd, 0xffff, 0x1_0000, 0x1_0000_0000] {
+            let mut bytes = vec![];
+            write_compact_size(&mut bytes, value);
+            let mut slice = &bytes[..];
+            assert_eq!(read_compact_size(&mut slice), Some(value));
+            assert!(slice.is_empty());
+        }
+        // Non-canonical and truncated sizes are rejected
+        assert_eq!(read_compact_size(&mut &[0xfd, 0xfc, 0x00][..]), None);
+        assert_eq!(read_compact_size(&mut &[0xfe, 0x00][..]), None);
+    }
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let key = UnifiedSpendingKey {
+            transparent: Some(keypair_2()),
+            shielded: None,
+        };
+        let keystore =
+            Keystore::encrypt(&key, Some("alice".to_string()), "password");
+        let json = serde_json::to_string(&keystore).unwrap();
+        let keystore: Keystore = serde_json::from_str(&json).unwrap();
+        assert_eq!(keystore.viewing_key, key.to_viewing_key().to_string());
+
+        let decrypted = keystore.decrypt("password").unwrap();
+        assert_eq!(decrypted.to_string(), key.to_string());
+        assert!(matches!(
+            keystore.decrypt("wrong"),
+            Err(InteropError::Decryption)
+        ));
+
+        let unsupported = Keystore {
+            version: KEYSTORE_VERSION + 1,
+            ..keystore
+        };
+        assert!(matches!(
+            unsupported.decrypt("password"),
+            Err(InteropError::UnsupportedVersion(_))
+        ));
+    }
+}
@@ -7,6 +7,7 @@ use std::str::FromStr;
 use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
 use data_encoding::HEXLOWER;
+use namada_core::types::secret::SecretBytes;
 use orion::{aead, kdf};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -202,9 +203,9 @@ impl<T: BorshSerialize + BorshDeserialize> EncryptedKeypair<T> {
         let salt = encryption_salt();
         let encryption_key = encryption_key(&salt, &password);
 
-        let data = keypair.serialize_to_vec();
+        let data = SecretBytes::new(keypair.serialize_to_vec());
 
-        let encrypted_keypair = aead::seal(&encryption_key, &data)
+        let encrypted_keypair = aead::seal(&encryption_key, data.expose())
             .expect("Encryption of data shouldn't fail");
 
         let encrypted_data = [salt.as_ref(), &encrypted_keypair].concat();
@@ -226,9 +227,10 @@ impl<T: BorshSerialize + BorshDeserialize> EncryptedKeypair<T> {
         let encryption_key = encryption_key(&salt, &password);
 
         let decrypted_data = aead::open(&encryption_key, cipher)
+            .map(SecretBytes::new)
             .map_err(|_| DecryptionError::DecryptionError)?;
 
-        T::try_from_slice(&decrypted_data)
+        T::try_from_slice(decrypted_data.expose())
             .map_err(|_| DecryptionError::DeserializingError)
     }
 }
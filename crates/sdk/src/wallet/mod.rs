@@ -1,6 +1,7 @@
 //! Provides functionality for managing keys and addresses for a user
 pub mod alias;
 mod derivation_path;
+pub mod interop;
 mod keys;
 pub mod pre_genesis;
 pub mod store;
@@ -17,6 +18,7 @@ use namada_core::types::key::*;
 use namada_core::types::masp::{
     ExtendedSpendingKey, ExtendedViewingKey, PaymentAddress,
 };
+use namada_core::types::secret::SecretBytes;
 pub use pre_genesis::gen_key_to_store;
 use rand::CryptoRng;
 use rand_core::RngCore;
@@ -225,10 +227,15 @@ pub fn gen_secret_key(
 fn gen_spending_key(
     csprng: &mut (impl CryptoRng + RngCore),
 ) -> ExtendedSpendingKey {
-    let mut spend_key = [0; 32];
-    csprng.fill_bytes(&mut spend_key);
-    masp_primitives::zip32::ExtendedSpendingKey::master(spend_key.as_ref())
-        .into()
+    let mut seed = vec![0; 32];
+    csprng.fill_bytes(&mut seed);
+    let seed = SecretBytes::new(seed);
+    masp_primitives::zip32::ExtendedSpendingKey::master(seed.expose()).into()
+}
+
+/// Derive the BIP39 seed of the given mnemonic and passphrase
+fn hd_seed(mnemonic: &Mnemonic, passphrase: &str) -> SecretBytes {
+    SecretBytes::from(Seed::new(mnemonic, passphrase).as_bytes())
 }
 
 /// The error that is produced when a given key cannot be obtained
@@ -522,9 +529,9 @@ impl<U: WalletIo> Wallet<U> {
             } else {
                 (U::read_mnemonic_code()?, U::read_mnemonic_passphrase(false))
             };
-        let seed = Seed::new(&mnemonic, &passphrase);
+        let seed = hd_seed(&mnemonic, &passphrase);
         let spend_key =
-            derive_hd_spending_key(seed.as_bytes(), derivation_path.clone());
+            derive_hd_spending_key(seed.expose(), derivation_path.clone());
 
         self.insert_spending_key(
             alias,
@@ -560,10 +567,10 @@ impl<U: WalletIo> Wallet<U> {
             } else {
                 (U::read_mnemonic_code()?, U::read_mnemonic_passphrase(false))
             };
-        let seed = Seed::new(&mnemonic, &passphrase);
+        let seed = hd_seed(&mnemonic, &passphrase);
         let sk = derive_hd_secret_key(
             scheme,
-            seed.as_bytes(),
+            seed.expose(),
             derivation_path.clone(),
         );
 
@@ -627,7 +634,7 @@ impl<U: WalletIo> Wallet<U> {
         passphrase: Option<Zeroizing<String>>,
         rng: &mut U::Rng,
         prompt_bip39_passphrase: bool,
-    ) -> (Mnemonic, Seed) {
+    ) -> (Mnemonic, SecretBytes) {
         const MNEMONIC_TYPE: MnemonicType = MnemonicType::Words24;
         let mnemonic = U::generate_mnemonic_code(MNEMONIC_TYPE, rng);
         println!(
@@ -643,7 +650,7 @@ impl<U: WalletIo> Wallet<U> {
                 Zeroizing::default()
             }
         });
-        let seed = Seed::new(&mnemonic, &passphrase);
+        let seed = hd_seed(&mnemonic, &passphrase);
         (mnemonic, seed)
     }
 
@@ -661,13 +668,13 @@ impl<U: WalletIo> Wallet<U> {
         scheme: SchemeType,
         alias: Option<String>,
         alias_force: bool,
-        seed: Seed,
+        seed: SecretBytes,
         derivation_path: DerivationPath,
         password: Option<Zeroizing<String>>,
     ) -> Option<(String, common::SecretKey)> {
         let sk = derive_hd_secret_key(
             scheme,
-            seed.as_bytes(),
+            seed.expose(),
             derivation_path.clone(),
         );
         self.insert_keypair(
@@ -693,12 +700,12 @@ impl<U: WalletIo> Wallet<U> {
         &mut self,
         alias: String,
         force_alias: bool,
-        seed: Seed,
+        seed: SecretBytes,
         derivation_path: DerivationPath,
         password: Option<Zeroizing<String>>,
     ) -> Option<(String, ExtendedSpendingKey)> {
         let spend_key =
-            derive_hd_spending_key(seed.as_bytes(), derivation_path.clone());
+            derive_hd_spending_key(seed.expose(), derivation_path.clone());
         self.insert_spending_key(
             alias,
             force_alias,
@@ -714,8 +714,10 @@ pub fn derive_hd_secret_key(
                 .collect_vec();
             // SLIP10 Ed25519 key derivation function promotes all indexes to
             // hardened indexes.
-            let sk = slip10_ed25519::derive_ed25519_private_key(seed, &indexes);
-            ed25519::SigScheme::from_bytes(sk).try_to_sk().unwrap()
+            let sk = Zeroizing::new(
+                slip10_ed25519::derive_ed25519_private_key(seed, &indexes),
+            );
+            ed25519::SigScheme::from_bytes(*sk).try_to_sk().unwrap()
         }
         SchemeType::Secp256k1 => {
             let xpriv = tiny_hderive::bip32::ExtendedPrivKey::derive(
@@ -723,9 +725,8 @@ pub fn derive_hd_secret_key(
                 derivation_path,
             )
             .expect("Secret key derivation should not fail.");
-            secp256k1::SigScheme::from_bytes(xpriv.secret())
-                .try_to_sk()
-                .unwrap()
+            let sk = Zeroizing::new(xpriv.secret());
+            secp256k1::SigScheme::from_bytes(*sk).try_to_sk().unwrap()
         }
         SchemeType::Common => {
             panic!(
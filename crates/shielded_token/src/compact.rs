@@ -0,0 +1,219 @@
+//! Compact blocks for shielded sync.
+//!
+//! A compact block only holds the parts of the valid MASP transactions of a
+//! block that a client needs to detect its notes and spends: the note
+//! commitments, the ephemeral keys and the compact note ciphertexts of the
+//! outputs, the nullifiers of the spends and a summary of the transparent
+//! bundle. The proofs, signatures and the remaining bytes of the note
+//! ciphertexts are left out. Notes detected from compact transactions carry
+//! no memo.
+
+use std::collections::BTreeSet;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use masp_primitives::ff::PrimeField;
+use masp_primitives::sapling::note_encryption::{
+    CompactOutputDescription, EphemeralKeyBytes,
+};
+use masp_primitives::sapling::Nullifier;
+use masp_primitives::transaction::Transaction;
+use namada_core::types::storage::{Key, TxIndex};
+use namada_trans_token::storage_key::is_any_shielded_action_balance_key;
+
+/// The maximum number of compact blocks returned by a single query
+pub const MAX_COMPACT_BLOCKS_PER_QUERY: u64 = 1000;
+
+/// A shielded output reduced to what is needed for trial decryption
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct CompactOutput {
+    /// The note commitment
+    pub cmu: [u8; 32],
+    /// The ephemeral public key
+    pub epk: [u8; 32],
+    /// The prefix of the note ciphertext holding the note plaintext
+    pub enc_ciphertext: Vec<u8>,
+}
+
+impl CompactOutput {
+    /// Get the description of the output used for compact trial decryption.
+    /// Returns `None` if the output is malformed.
+    pub fn to_description(&self) -> Option<CompactOutputDescription> {
+        let cmu = Option::from(masp_primitives::bls12_381::Scalar::from_repr(
+            self.cmu,
+        ))?;
+        let enc_ciphertext = self.enc_ciphertext.as_slice().try_into().ok()?;
+        Some(CompactOutputDescription {
+            ephemeral_key: EphemeralKeyBytes(self.epk),
+            cmu,
+            enc_ciphertext,
+        })
+    }
+}
+
+/// A transparent input or output of a MASP transaction
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct CompactTransparent {
+    /// The hash of the transparent address
+    pub address: [u8; 20],
+    /// The value
+    pub value: u64,
+}
+
+/// A valid MASP transaction reduced to what is needed for shielded sync
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct CompactTx {
+    /// The index of the transaction in its block
+    pub index: TxIndex,
+    /// The token balance keys changed by the transaction
+    pub changed_balance_keys: BTreeSet<Key>,
+    /// The shielded outputs, in the order of the transaction
+    pub outputs: Vec<CompactOutput>,
+    /// The nullifiers of the shielded spends
+    pub nullifiers: Vec<[u8; 32]>,
+    /// The transparent inputs
+    pub vin: Vec<CompactTransparent>,
+    /// The transparent outputs
+    pub vout: Vec<CompactTransparent>,
+}
+
+impl CompactTx {
+    /// Compact the given MASP transaction, found at the given index of its
+    /// block, with the storage keys it changed
+    pub fn new(
+        index: TxIndex,
+        changed_keys: &BTreeSet<Key>,
+        transaction: &Transaction,
+    ) -> Self {
+        let changed_balance_keys = changed_keys
+            .iter()
+            .filter(|key| is_any_shielded_action_balance_key(key).is_some())
+            .cloned()
+            .collect();
+        let (outputs, nullifiers) = transaction.sapling_bundle().map_or_else(
+            Default::default,
+            |bundle| {
+                let outputs = bundle
+                    .shielded_outputs
+                    .iter()
+                    .map(|output| {
+                        let compact =
+                            CompactOutputDescription::from(output.clone());
+                        CompactOutput {
+                            cmu: compact.cmu.to_repr(),
+                            epk: compact.ephemeral_key.0,
+                            enc_ciphertext: compact.enc_ciphertext.to_vec(),
+                        }
+                    })
+                    .collect();
+                let nullifiers = bundle
+                    .shielded_spends
+                    .iter()
+                    .map(|spend| spend.nullifier.0)
+                    .collect();
+                (outputs, nullifiers)
+            },
+        );
+        let (vin, vout) = transaction.transparent_bundle().map_or_else(
+            Default::default,
+            |bundle| {
+                let vin = bundle
+                    .vin
+                    .iter()
+                    .map(|vin| CompactTransparent {
+                        address: vin.address.0,
+                        value: vin.value,
+                    })
+                    .collect();
+                let vout = bundle
+                    .vout
+                    .iter()
+                    .map(|vout| CompactTransparent {
+                        address: vout.address.0,
+                        value: vout.value,
+                    })
+                    .collect();
+                (vin, vout)
+            },
+        );
+        Self {
+            index,
+            changed_balance_keys,
+            outputs,
+            nullifiers,
+            vin,
+            vout,
+        }
+    }
+
+    /// The nullifiers of the shielded spends
+    pub fn nullifiers(&self) -> impl Iterator<Item = Nullifier> + '_ {
+        self.nullifiers.iter().copied().map(Nullifier)
+    }
+}
+
+/// The valid MASP transactions of a block
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct CompactBlock {
+    /// The compacted transactions, in the order of the block
+    pub txs: Vec<CompactTx>,
+    /// The indices of the valid MASP transactions that could not be
+    /// compacted. Clients have to fetch these transactions in full.
+    pub full_txs: BTreeSet<TxIndex>,
+}
+
+impl CompactBlock {
+    /// Check if the block doesn't contain any valid MASP transaction
+    pub fn is_empty(&self) -> bool {
+        self.txs.is_empty() && self.full_txs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::borsh::BorshSerializeExt;
+
+    use super::*;
+
+    #[test]
+    fn test_malformed_compact_output() {
+        // A ciphertext of the wrong size is rejected
+        let output = CompactOutput {
+            cmu: [0; 32],
+            epk: [1; 32],
+            enc_ciphertext: vec![2; 1],
+        };
+        assert!(output.to_description().is_none());
+
+        // A non-canonical note commitment is rejected
+        let output = CompactOutput {
+            cmu: [0xff; 32],
+            ..output
+        };
+        assert!(output.to_description().is_none());
+    }
+
+    #[test]
+    fn test_compact_block_roundtrip() {
+        let block = CompactBlock {
+            txs: vec![CompactTx {
+                index: TxIndex(1),
+                changed_balance_keys: BTreeSet::new(),
+                outputs: vec![],
+                nullifiers: vec![[3; 32]],
+                vin: vec![CompactTransparent {
+                    address: [4; 20],
+                    value: 5,
+                }],
+                vout: vec![],
+            }],
+            full_txs: BTreeSet::from([TxIndex(2)]),
+        };
+        assert!(!block.is_empty());
+        let bytes = block.serialize_to_vec();
+        let decoded = CompactBlock::try_from_slice(&bytes).unwrap();
+        assert_eq!(decoded, block);
+        assert!(CompactBlock::default().is_empty());
+    }
+}
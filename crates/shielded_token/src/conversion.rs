@@ -1,16 +1,19 @@
 //! MASP rewards conversions
 
 use namada_core::ledger::inflation::{
-    ShieldedRewardsController, ShieldedValsToUpdate,
+    RewardRate, ShieldedRewardsController, ShieldedValsToUpdate,
 };
 use namada_core::types::address::{Address, MASP};
 use namada_core::types::dec::Dec;
 use namada_core::types::uint::Uint;
 use namada_parameters as parameters;
 use namada_state::{DBIter, StorageHasher, WlStorage, DB};
-use namada_storage::{StorageRead, StorageWrite};
+use namada_storage::{OptionExt, StorageRead, StorageWrite};
 use namada_trans_token::storage_key::{balance_key, minted_balance_key};
-use namada_trans_token::{read_denom, Amount, DenominatedAmount, Denomination};
+use namada_trans_token::{
+    read_denom, Amount, ConversionEntry, ConversionGap, DenominatedAmount,
+    Denomination,
+};
 
 use crate::storage_key::{
     masp_kd_gain_key, masp_kp_gain_key, masp_last_inflation_key,
@@ -43,6 +46,43 @@ where
     ))
 }
 
+/// Estimate the yearly MASP rewards rate of the given token. The estimate is
+/// based on the MASP inflation of the last epoch, which is spread over the
+/// amount of the token that was locked in the shielded pool. Returns `None`
+/// if the token doesn't earn MASP rewards.
+pub fn estimate_masp_reward_rate<S>(
+    storage: &S,
+    token: &Address,
+) -> namada_storage::Result<Option<RewardRate>>
+where
+    S: StorageRead,
+{
+    if !storage.has_key(&masp_max_reward_rate_key(token))? {
+        return Ok(None);
+    }
+    let epochs_per_year: u64 = storage
+        .read(&parameters::storage::get_epochs_per_year_key())?
+        .ok_or_err_msg("Epochs per year should exist in storage")?;
+    let last_inflation: Amount = storage
+        .read(&masp_last_inflation_key(token))?
+        .unwrap_or_default();
+    let last_locked_amount: Amount = storage
+        .read(&masp_last_locked_amount_key(token))?
+        .unwrap_or_default();
+
+    // Both amounts are denominated in the token itself
+    let apr = if last_locked_amount.is_zero() {
+        Dec::zero()
+    } else {
+        (Dec::from(last_inflation) * epochs_per_year)
+            .trunc_div(&Dec::from(last_locked_amount))
+            .ok_or_err_msg("Overflow in the MASP rewards rate")?
+    };
+    RewardRate::from_apr(apr, epochs_per_year)
+        .map(Some)
+        .ok_or_err_msg("Overflow in the MASP rewards yield")
+}
+
 /// Compute the MASP rewards by applying the PD-controller to the genesis
 /// parameters and the last inflation and last locked rewards ratio values.
 pub fn calculate_masp_rewards<D, H>(
@@ -113,8 +153,17 @@ where
         epochs_per_year,
     };
 
-    let ShieldedValsToUpdate { inflation } =
-        ShieldedRewardsController::run(controller);
+    let inflation = match ShieldedRewardsController::run(controller) {
+        Ok(ShieldedValsToUpdate { inflation }) => inflation,
+        Err(err) => {
+            tracing::warn!(
+                "MASP inflation for {} assumed to be 0 because it couldn't be \
+                 computed: {err}. Please check the inflation parameters.",
+                *token
+            );
+            Uint::zero()
+        }
+    };
 
     // inflation-per-token = inflation / locked tokens = n/PRECISION
     // ∴ n = (inflation * PRECISION) / locked tokens
@@ -181,6 +230,206 @@ where
     Ok(((noterized_inflation, precision), denomination))
 }
 
+/// Move the conversions of the asset types older than the given number of
+/// epochs from the conversion state to the archive. At least the previous
+/// epoch is kept. The archived conversions aren't updated anymore, so they
+/// convert to the asset types of the epoch they were archived at, which have
+/// conversions of their own.
+#[cfg(any(feature = "multicore", test))]
+fn archive_expired_conversions<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    retention: u64,
+    last_tree_size: usize,
+) -> namada_storage::Result<()>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    let retention = std::cmp::max(retention, 1);
+    let current_epoch = wl_storage.storage.block.epoch;
+    // Only the conversions with a leaf in the tree can be archived
+    let expired: Vec<_> = wl_storage
+        .storage
+        .conversion_state
+        .assets
+        .iter()
+        .filter(|(_, (_, epoch, _, pos))| {
+            *pos < last_tree_size
+                && epoch.saturating_add(retention) < current_epoch
+        })
+        .map(|(asset_type, _)| *asset_type)
+        .collect();
+    for asset_type in expired {
+        let entry = wl_storage
+            .storage
+            .conversion_state
+            .assets
+            .remove(&asset_type)
+            .expect("The expired conversion must be in the conversion state");
+        crate::write_archived_conversion(wl_storage, &asset_type, &entry)?;
+    }
+    Ok(())
+}
+
+/// Find the gaps in the given conversions: the epochs without a conversion
+/// between the first and the last epochs with a conversion of the same token,
+/// denomination and digit. The gaps are sorted.
+pub fn find_conversion_gaps<'a>(
+    entries: impl IntoIterator<Item = &'a ConversionEntry>,
+) -> Vec<ConversionGap> {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use namada_core::types::storage::Epoch;
+
+    let mut epochs = BTreeMap::<_, BTreeSet<Epoch>>::new();
+    for (asset, epoch, _conv, _pos) in entries {
+        epochs.entry(asset).or_default().insert(*epoch);
+    }
+    let mut gaps = vec![];
+    for ((token, denom, digit), epochs) in epochs {
+        let (Some(first), Some(last)) = (epochs.first(), epochs.last()) else {
+            continue;
+        };
+        for epoch in (first.0..last.0).map(Epoch) {
+            if !epochs.contains(&epoch) {
+                gaps.push(ConversionGap {
+                    token: token.clone(),
+                    denom: *denom,
+                    digit: *digit,
+                    epoch,
+                });
+            }
+        }
+    }
+    gaps
+}
+
+/// Find the gaps in the conversions of the conversion state and of the
+/// archive. The gaps are sorted.
+pub fn conversion_gaps<D, H>(
+    wl_storage: &WlStorage<D, H>,
+) -> namada_storage::Result<Vec<ConversionGap>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    let archived: Vec<ConversionEntry> = namada_storage::iter_prefix(
+        wl_storage,
+        &crate::storage_key::masp_conversion_archive_prefix(),
+    )?
+    .map(|entry| entry.map(|(_key, entry)| entry))
+    .collect::<namada_storage::Result<_>>()?;
+    Ok(find_conversion_gaps(
+        wl_storage
+            .storage
+            .conversion_state
+            .assets
+            .values()
+            .chain(&archived),
+    ))
+}
+
+/// Backfill the gaps in the conversions that were approved by governance,
+/// before the conversions of the last epoch are added. The conversion of a gap
+/// is the one of the next epoch, without the rewards of the gap's epoch, so
+/// that the notes of its asset type can still be converted. The gaps are
+/// backfilled from the latest epoch. A gap whose next epoch has no conversion
+/// in the conversion state, e.g. because it's archived or because it's a gap
+/// that isn't backfilled, is skipped.
+#[cfg(any(feature = "multicore", test))]
+fn backfill_conversion_gaps<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    current_convs: &std::collections::BTreeMap<
+        (Address, Denomination, namada_trans_token::MaspDigitPos),
+        masp_primitives::convert::AllowedConversion,
+    >,
+    last_tree_size: usize,
+) -> namada_storage::Result<()>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    use masp_primitives::transaction::components::I128Sum as MaspAmount;
+    use namada_core::types::masp::epoched_asset_type;
+    use namada_storage::ResultExt;
+
+    let backfill_key = parameters::storage::get_masp_conversion_backfill_key();
+    let Some(approved) =
+        wl_storage.read::<Vec<ConversionGap>>(&backfill_key)?
+    else {
+        return Ok(());
+    };
+    wl_storage.delete(&backfill_key)?;
+
+    for gap in conversion_gaps(wl_storage)?.into_iter().rev() {
+        if !approved.contains(&gap) {
+            continue;
+        }
+        let ConversionGap {
+            token,
+            denom,
+            digit,
+            epoch,
+        } = gap;
+        let asset = epoched_asset_type(&token, denom, digit, epoch)
+            .into_storage_result()?;
+        let next_asset = epoched_asset_type(&token, denom, digit, epoch.next())
+            .into_storage_result()?;
+        let assets = &mut wl_storage.storage.conversion_state.assets;
+        let Some((_, _, next_conv, _)) = assets.get(&next_asset) else {
+            tracing::warn!(
+                "The MASP conversion of {token} at epoch {epoch} can't be \
+                 backfilled, as the next epoch has no conversion"
+            );
+            continue;
+        };
+        let next_conv = MaspAmount::from(next_conv.clone());
+        // The amount of the next asset type that the conversion of the next
+        // epoch removes, once the conversion of the last epoch is added
+        let mut full_next_conv = next_conv.clone();
+        if let Some(current_conv) =
+            current_convs.get(&(token.clone(), denom, digit))
+        {
+            full_next_conv += MaspAmount::from(current_conv.clone());
+        }
+        let scale = full_next_conv
+            .components()
+            .find(|(asset_type, _)| **asset_type == next_asset)
+            .and_then(|(_, value)| value.checked_neg())
+            .filter(|scale| *scale > 0);
+        let Some(scale) = scale else {
+            tracing::warn!(
+                "The MASP conversion of {token} at epoch {epoch} can't be \
+                 backfilled, as the next epoch doesn't convert its asset type"
+            );
+            continue;
+        };
+        // Replace the next asset type with the one of the gap
+        let conv = next_conv
+            + MaspAmount::from_pair(next_asset, scale).into_storage_result()?
+            + MaspAmount::from_pair(asset, -scale).into_storage_result()?;
+        assets.insert(
+            asset,
+            (
+                (token.clone(), denom, digit),
+                epoch,
+                conv.into(),
+                last_tree_size,
+            ),
+        );
+        tracing::info!(
+            "Backfilled the MASP conversion of {token} at epoch {epoch}"
+        );
+    }
+    Ok(())
+}
+
+/// The number of leaves of the sub-trees of the tree of the conversions that
+/// are cached between its updates. Merkle trees must have exactly 2^n leaves
+/// to be mergeable.
+#[cfg(any(feature = "multicore", test))]
+const CONVERSION_TREE_CHUNK_SIZE: usize = 1 << 8;
+
 // This is only enabled when "wasm-runtime" is on, because we're using rayon
 #[cfg(any(feature = "multicore", test))]
 /// Update the MASP's allowed conversions
@@ -191,8 +440,8 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter>,
     H: 'static + StorageHasher,
 {
-    use std::cmp::Ordering;
-    use std::collections::BTreeMap;
+    use std::cmp::{self, Ordering};
+    use std::collections::{BTreeMap, BTreeSet};
 
     use masp_primitives::bls12_381;
     use masp_primitives::convert::AllowedConversion;
@@ -200,14 +449,11 @@ where
     use masp_primitives::merkle_tree::FrozenCommitmentTree;
     use masp_primitives::sapling::Node;
     use masp_primitives::transaction::components::I128Sum as MaspAmount;
-    use namada_core::types::masp::encode_asset_type;
+    use namada_core::types::masp::{digit_asset_types, epoched_asset_type};
     use namada_core::types::storage::Epoch;
     use namada_storage::ResultExt;
     use namada_trans_token::{MaspDigitPos, NATIVE_MAX_DECIMAL_PLACES};
-    use rayon::iter::{
-        IndexedParallelIterator, IntoParallelIterator, ParallelIterator,
-    };
-    use rayon::prelude::ParallelSlice;
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
     // The derived conversions will be placed in MASP address space
     let masp_addr = MASP;
@@ -239,36 +485,12 @@ where
     // reward tokens with the zeroth epoch to minimize the number of convert
     // notes clients have to use. This trick works under the assumption that
     // reward tokens will then be reinflated back to the current epoch.
-    let reward_assets = [
-        encode_asset_type(
-            native_token.clone(),
-            NATIVE_MAX_DECIMAL_PLACES.into(),
-            MaspDigitPos::Zero,
-            Some(Epoch(0)),
-        )
-        .into_storage_result()?,
-        encode_asset_type(
-            native_token.clone(),
-            NATIVE_MAX_DECIMAL_PLACES.into(),
-            MaspDigitPos::One,
-            Some(Epoch(0)),
-        )
-        .into_storage_result()?,
-        encode_asset_type(
-            native_token.clone(),
-            NATIVE_MAX_DECIMAL_PLACES.into(),
-            MaspDigitPos::Two,
-            Some(Epoch(0)),
-        )
-        .into_storage_result()?,
-        encode_asset_type(
-            native_token.clone(),
-            NATIVE_MAX_DECIMAL_PLACES.into(),
-            MaspDigitPos::Three,
-            Some(Epoch(0)),
-        )
-        .into_storage_result()?,
-    ];
+    let reward_assets = digit_asset_types(
+        &native_token,
+        NATIVE_MAX_DECIMAL_PLACES.into(),
+        Some(Epoch(0)),
+    )
+    .into_storage_result()?;
     // Conversions from the previous to current asset for each address
     let mut current_convs = BTreeMap::<
         (Address, Denomination, MaspDigitPos),
@@ -278,6 +500,11 @@ where
     let ref_inflation =
         calculate_masp_rewards_precision(wl_storage, &native_token)?.0;
 
+    // The assets with a leaf in the last tree of the conversions keep their
+    // position in the tree. The other assets are given this uncommitted
+    // position, until they're appended to the tree.
+    let last_tree_size = wl_storage.storage.conversion_state.tree.size();
+
     // Reward all tokens according to above reward rates
     for token in &masp_reward_keys {
         let (reward, denom) = calculate_masp_rewards(wl_storage, token)?;
@@ -297,18 +524,18 @@ where
             // Provide an allowed conversion from previous timestamp. The
             // negative sign allows each instance of the old asset to be
             // cancelled out/replaced with the new asset
-            let old_asset = encode_asset_type(
-                token.clone(),
+            let old_asset = epoched_asset_type(
+                token,
                 denom,
                 digit,
-                Some(wl_storage.storage.last_epoch),
+                wl_storage.storage.last_epoch,
             )
             .into_storage_result()?;
-            let new_asset = encode_asset_type(
-                token.clone(),
+            let new_asset = epoched_asset_type(
+                token,
                 denom,
                 digit,
-                Some(wl_storage.storage.block.epoch),
+                wl_storage.storage.block.epoch,
             )
             .into_storage_result()?;
             if *token == native_token {
@@ -410,44 +637,91 @@ where
                     (token.clone(), denom, digit),
                     wl_storage.storage.last_epoch,
                     MaspAmount::zero().into(),
-                    0,
+                    last_tree_size,
                 ),
             );
         }
     }
 
-    // Try to distribute Merkle leaf updating as evenly as possible across
-    // multiple cores
-    let num_threads = rayon::current_num_threads();
-    // Put assets into vector to enable computation batching
-    let assets: Vec<_> = wl_storage
-        .storage
-        .conversion_state
-        .assets
-        .values_mut()
-        .enumerate()
-        .collect();
-    // ceil(assets.len() / num_threads)
-    let notes_per_thread_max = (assets.len() + num_threads - 1) / num_threads;
-    // floor(assets.len() / num_threads)
-    let notes_per_thread_min = assets.len() / num_threads;
-    // Now on each core, add the latest conversion to each conversion
-    let conv_notes: Vec<Node> = assets
+    // Backfill the gaps in the conversions approved by governance
+    backfill_conversion_gaps(wl_storage, &current_convs, last_tree_size)?;
+
+    // Archive the conversions of the asset types older than the retention
+    // window. They keep their leaves in the tree.
+    if let Some(retention) =
+        namada_parameters::read_masp_conversion_retention_epochs(wl_storage)?
+    {
+        archive_expired_conversions(wl_storage, retention, last_tree_size)?;
+    }
+
+    // The cache is empty after a restart, so all the leaves are computed,
+    // including the ones of the archived conversions
+    let rebuild = wl_storage.storage.conversion_state.tree_cache.leaves.len()
+        != last_tree_size;
+    let archived: Vec<ConversionEntry> = if rebuild {
+        namada_storage::iter_prefix(
+            wl_storage,
+            &crate::storage_key::masp_conversion_archive_prefix(),
+        )?
+        .map(|entry| entry.map(|(_key, entry)| entry))
+        .collect::<namada_storage::Result<_>>()?
+    } else {
+        vec![]
+    };
+
+    // Add the latest conversion to each conversion and append the new assets
+    // to the tree. Only the leaves of the changed conversions have to be
+    // recomputed.
+    let conversion_state = &mut wl_storage.storage.conversion_state;
+    let tree_cache = &mut conversion_state.tree_cache;
+    if rebuild {
+        tree_cache.leaves.clear();
+        tree_cache.chunks.clear();
+    }
+    let mut tree_size = last_tree_size;
+    let mut changed_leaves = BTreeMap::new();
+    for (asset, _epoch, conv, pos) in conversion_state.assets.values_mut() {
+        let mut changed = rebuild;
+        if *pos >= last_tree_size {
+            *pos = tree_size;
+            tree_size += 1;
+            changed = true;
+        }
+        if let Some(current_conv) = current_convs.get(asset) {
+            // Use transitivity to update conversion
+            *conv += current_conv.clone();
+            changed = true;
+        }
+        if changed {
+            changed_leaves.insert(*pos, &*conv);
+        }
+    }
+    for (_, _, conv, pos) in &archived {
+        changed_leaves.insert(*pos, conv);
+    }
+
+    // Compute the changed leaves in parallel. The merkle tree need only
+    // provide the conversion commitment, the remaining information is
+    // provided through the storage API.
+    let changed_leaves: Vec<(usize, Node)> = changed_leaves
+        .into_iter()
+        .collect::<Vec<_>>()
         .into_par_iter()
-        .with_min_len(notes_per_thread_min)
-        .with_max_len(notes_per_thread_max)
-        .map(|(idx, (asset, _epoch, conv, pos))| {
-            if let Some(current_conv) = current_convs.get(asset) {
-                // Use transitivity to update conversion
-                *conv += current_conv.clone();
-            }
-            // Update conversion position to leaf we are about to create
-            *pos = idx;
-            // The merkle tree need only provide the conversion commitment,
-            // the remaining information is provided through the storage API
-            Node::new(conv.cmu().to_repr())
-        })
+        .map(|(pos, conv)| (pos, Node::new(conv.cmu().to_repr())))
         .collect();
+    // The new leaves are in the order of their positions, after the others
+    let tree_cache = &mut conversion_state.tree_cache;
+    let mut changed_chunks = BTreeSet::new();
+    for (pos, leaf) in changed_leaves {
+        if pos < tree_cache.leaves.len() {
+            tree_cache.leaves[pos] = leaf;
+        } else {
+            debug_assert_eq!(pos, tree_cache.leaves.len());
+            tree_cache.leaves.push(leaf);
+        }
+        changed_chunks.insert(pos / CONVERSION_TREE_CHUNK_SIZE);
+    }
+    debug_assert_eq!(tree_cache.leaves.len(), tree_size);
 
     // Update the MASP's transparent reward token balance to ensure that it
     // is sufficiently backed to redeem rewards
@@ -455,23 +729,41 @@ where
     let addr_bal: Amount = wl_storage.read(&reward_key)?.unwrap_or_default();
     let new_bal = addr_bal + total_reward;
     wl_storage.write(&reward_key, new_bal)?;
-    // Try to distribute Merkle tree construction as evenly as possible
-    // across multiple cores
-    // Merkle trees must have exactly 2^n leaves to be mergeable
-    let mut notes_per_thread_rounded = 1;
-    while notes_per_thread_max > notes_per_thread_rounded * 4 {
-        notes_per_thread_rounded *= 2;
-    }
-    // Make the sub-Merkle trees in parallel
-    let tree_parts: Vec<_> = conv_notes
-        .par_chunks(notes_per_thread_rounded)
-        .map(FrozenCommitmentTree::new)
+    // The rewards count towards the limit of native tokens minted in the
+    // epoch, but they can't be deferred as they back the new conversions
+    namada_trans_token::record_native_mint(wl_storage, total_reward)?;
+    // Remake the sub-Merkle trees of the chunks with changed leaves in
+    // parallel and reuse the others
+    let conversion_state = &mut wl_storage.storage.conversion_state;
+    let tree_cache = &mut conversion_state.tree_cache;
+    let changed_parts: Vec<_> = changed_chunks
+        .into_iter()
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|chunk| {
+            let start = chunk * CONVERSION_TREE_CHUNK_SIZE;
+            let end = cmp::min(
+                start + CONVERSION_TREE_CHUNK_SIZE,
+                tree_cache.leaves.len(),
+            );
+            (
+                chunk,
+                FrozenCommitmentTree::new(&tree_cache.leaves[start..end]),
+            )
+        })
         .collect();
+    for (chunk, part) in changed_parts {
+        if chunk < tree_cache.chunks.len() {
+            tree_cache.chunks[chunk] = part;
+        } else {
+            debug_assert_eq!(chunk, tree_cache.chunks.len());
+            tree_cache.chunks.push(part);
+        }
+    }
 
-    // Convert conversion vector into tree so that Merkle paths can be
+    // Merge the sub-trees into the tree, so that Merkle paths can be
     // obtained
-    wl_storage.storage.conversion_state.tree =
-        FrozenCommitmentTree::merge(&tree_parts);
+    conversion_state.tree = FrozenCommitmentTree::merge(&tree_cache.chunks);
     // Update the anchor in storage
     wl_storage.write(
         &crate::storage_key::masp_convert_anchor_key(),
@@ -494,11 +786,11 @@ where
         for digit in MaspDigitPos::iter() {
             // Add the decoding entry for the new asset type. An uncommitted
             // node position is used since this is not a conversion.
-            let new_asset = encode_asset_type(
-                addr.clone(),
+            let new_asset = epoched_asset_type(
+                &addr,
                 denom,
                 digit,
-                Some(wl_storage.storage.block.epoch),
+                wl_storage.storage.block.epoch,
             )
             .into_storage_result()?;
             wl_storage.storage.conversion_state.assets.insert(
@@ -518,16 +810,28 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
     use std::str::FromStr;
 
+    use masp_primitives::asset_type::AssetType;
+    use masp_primitives::bls12_381;
+    use masp_primitives::ff::PrimeField;
+    use masp_primitives::merkle_tree::FrozenCommitmentTree;
+    use masp_primitives::sapling::Node;
+    use masp_primitives::transaction::components::I128Sum;
     use namada_core::types::address;
     use namada_core::types::dec::testing::arb_non_negative_dec;
+    use namada_core::types::masp::{
+        derive_asset_type, digit_asset_types, epoched_asset_type,
+    };
+    use namada_core::types::storage::Epoch;
     use namada_core::types::time::DurationSecs;
     use namada_core::types::token::testing::arb_amount;
     use namada_parameters::{EpochDuration, Parameters};
     use namada_state::testing::TestWlStorage;
-    use namada_trans_token::{write_denom, Denomination, MaspParams};
+    use namada_trans_token::{
+        write_denom, ConversionEntry, Denomination, MaspDigitPos, MaspParams,
+    };
     use proptest::prelude::*;
     use proptest::test_runner::Config;
     use test_log::test;
@@ -554,6 +858,282 @@ mod tests {
     ) {
         const ROUNDS: usize = 10;
 
+        let mut s = init_storage(initial_balance, masp_locked_ratio);
+        for i in 0..ROUNDS {
+            println!("Round {i}");
+            update_allowed_conversions(&mut s).unwrap();
+            println!();
+            println!();
+        }
+    }
+
+    /// Derive the asset types that the MASP VP accepts at the given epoch from
+    /// the conversion state of the storage
+    fn derive_asset_types(
+        s: &TestWlStorage,
+        token: &Address,
+        denom: Denomination,
+        epoch: Epoch,
+    ) -> Vec<AssetType> {
+        MaspDigitPos::iter()
+            .map(|digit| {
+                derive_asset_type(token, denom, digit, epoch, |asset_type| {
+                    s.storage.conversion_state.assets.contains_key(asset_type)
+                })
+                .unwrap()
+                .0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_derive_asset_type_at_epoch_rollover() {
+        let mut s =
+            init_storage(Amount::native_whole(1_000), Dec::new(5, 1).unwrap());
+        let rewarded_tokens = tokens();
+        // A token that doesn't earn MASP rewards
+        let other_token = address::testing::established_address_1();
+        let other_denom = Denomination(6);
+
+        // Before the first conversions, all the tokens are unepoched
+        for (token, (_, denom)) in &rewarded_tokens {
+            assert_eq!(
+                derive_asset_types(&s, token, *denom, Epoch(0)),
+                digit_asset_types(token, *denom, None).unwrap()
+            );
+        }
+
+        // Roll over to the next epochs
+        for epoch in 1..=3 {
+            let epoch = Epoch(epoch);
+            let prev_epoch = epoch.prev();
+            // A tx built before the rollover doesn't find the asset types of
+            // the new epoch yet
+            let before_rollover: Vec<_> = rewarded_tokens
+                .iter()
+                .map(|(token, (_, denom))| {
+                    derive_asset_types(&s, token, *denom, epoch)
+                })
+                .collect();
+
+            s.storage.last_epoch = prev_epoch;
+            s.storage.block.epoch = epoch;
+            update_allowed_conversions(&mut s).unwrap();
+
+            for ((token, (_, denom)), before_rollover) in
+                rewarded_tokens.iter().zip(before_rollover)
+            {
+                // The rewarded tokens are epoched with the new epoch
+                let epoched = digit_asset_types(token, *denom, Some(epoch))
+                    .unwrap()
+                    .to_vec();
+                assert_eq!(
+                    derive_asset_types(&s, token, *denom, epoch),
+                    epoched
+                );
+                assert_ne!(before_rollover, epoched);
+                // The asset types of the previous epoch are still convertible,
+                // but not accepted in the new epoch anymore
+                for digit in MaspDigitPos::iter() {
+                    let prev_asset =
+                        epoched_asset_type(token, *denom, digit, prev_epoch)
+                            .unwrap();
+                    let (_, prev_asset_epoch, _, _) =
+                        &s.storage.conversion_state.assets[&prev_asset];
+                    assert_eq!(*prev_asset_epoch, prev_epoch);
+                }
+            }
+
+            // The tokens without conversions stay unepoched
+            assert_eq!(
+                derive_asset_types(&s, &other_token, other_denom, epoch),
+                digit_asset_types(&other_token, other_denom, None).unwrap()
+            );
+        }
+    }
+
+    /// Test that the incremental updates of the tree of the conversions give
+    /// the same anchors as rebuilding the tree from all the conversions,
+    /// including after a token stops earning rewards and after a restart.
+    #[test]
+    fn test_incremental_conversion_tree() {
+        const ROUNDS: u64 = 12;
+
+        let mut s =
+            init_storage(Amount::native_whole(1_000), Dec::new(5, 1).unwrap());
+        for epoch in 1..=ROUNDS {
+            if epoch == 6 {
+                // The leaves of btc don't change anymore
+                s.storage.conversion_state.tokens.remove("btc");
+            }
+            if epoch == 9 {
+                // A restart empties the cache
+                s.storage.conversion_state.tree_cache = Default::default();
+            }
+            s.storage.last_epoch = Epoch(epoch - 1);
+            s.storage.block.epoch = Epoch(epoch);
+            update_allowed_conversions(&mut s).unwrap();
+            assert_naive_conversion_tree(&s);
+        }
+        // The tree was made of several cached sub-trees
+        assert!(s.storage.conversion_state.tree_cache.chunks.len() > 1);
+    }
+
+    /// Test that the conversions older than the retention window are moved
+    /// to the archive and keep their leaves in the tree.
+    #[test]
+    fn test_conversions_archival() {
+        const ROUNDS: u64 = 8;
+        const RETENTION: u64 = 2;
+
+        let mut s =
+            init_storage(Amount::native_whole(1_000), Dec::new(5, 1).unwrap());
+        namada_parameters::update_masp_conversion_retention_epochs_parameter(
+            &mut s,
+            Some(RETENTION),
+        )
+        .unwrap();
+        let mut archived = BTreeMap::new();
+        for epoch in 1..=ROUNDS {
+            if epoch == 6 {
+                // A restart empties the cache
+                s.storage.conversion_state.tree_cache = Default::default();
+            }
+            s.storage.last_epoch = Epoch(epoch - 1);
+            s.storage.block.epoch = Epoch(epoch);
+            let assets = s.storage.conversion_state.assets.clone();
+            update_allowed_conversions(&mut s).unwrap();
+            assert_naive_conversion_tree(&s);
+
+            let conversion_state = &s.storage.conversion_state;
+            for (asset_type, entry) in assets {
+                if !conversion_state.assets.contains_key(&asset_type) {
+                    // The expired conversions are archived unchanged
+                    assert!(entry.1 .0 + RETENTION < epoch);
+                    assert_eq!(
+                        crate::read_archived_conversion(&s, &asset_type)
+                            .unwrap()
+                            .map(|entry| entry.2),
+                        Some(entry.2.clone())
+                    );
+                    archived.insert(asset_type, entry);
+                }
+            }
+            // The conversions within the window are kept
+            for (_, epoch, _, _) in conversion_state.assets.values() {
+                assert!(epoch.0 + RETENTION >= s.storage.block.epoch.0);
+            }
+        }
+        assert!(!archived.is_empty());
+        // The archived conversions are still served with their paths
+        for (asset_type, (_, epoch, _, pos)) in archived {
+            let entry = crate::read_archived_conversion(&s, &asset_type)
+                .unwrap()
+                .unwrap();
+            assert_eq!((entry.1, entry.3), (epoch, pos));
+        }
+    }
+
+    /// Test that the gaps in the conversions are found and that only the ones
+    /// approved by governance are backfilled, with the conversions of their
+    /// next epoch.
+    #[test]
+    fn test_conversion_gaps_backfill() {
+        let mut s =
+            init_storage(Amount::native_whole(1_000), Dec::new(5, 1).unwrap());
+        for epoch in 1..=4 {
+            s.storage.last_epoch = Epoch(epoch - 1);
+            s.storage.block.epoch = Epoch(epoch);
+            update_allowed_conversions(&mut s).unwrap();
+        }
+        assert!(conversion_gaps(&s).unwrap().is_empty());
+
+        // Lose the conversions of btc at the first epoch
+        let btc = address::btc();
+        let denom = Denomination(8);
+        for digit in MaspDigitPos::iter() {
+            let asset =
+                epoched_asset_type(&btc, denom, digit, Epoch(1)).unwrap();
+            s.storage.conversion_state.assets.remove(&asset).unwrap();
+        }
+        let gaps = conversion_gaps(&s).unwrap();
+        let expected: Vec<_> = MaspDigitPos::iter()
+            .map(|digit| ConversionGap {
+                token: btc.clone(),
+                denom,
+                digit,
+                epoch: Epoch(1),
+            })
+            .collect();
+        assert_eq!(gaps, expected);
+
+        // Backfill one of the gaps
+        let backfill_key =
+            parameters::storage::get_masp_conversion_backfill_key();
+        s.write(&backfill_key, gaps[..1].to_vec()).unwrap();
+        s.storage.last_epoch = Epoch(4);
+        s.storage.block.epoch = Epoch(5);
+        update_allowed_conversions(&mut s).unwrap();
+        assert_eq!(conversion_gaps(&s).unwrap(), gaps[1..]);
+        assert!(!s.has_key(&backfill_key).unwrap());
+
+        // The backfilled asset type converts like the one of the next epoch
+        let ConversionGap { digit, .. } = gaps[0];
+        let asset_1 = epoched_asset_type(&btc, denom, digit, Epoch(1)).unwrap();
+        let asset_2 = epoched_asset_type(&btc, denom, digit, Epoch(2)).unwrap();
+        let assets = &s.storage.conversion_state.assets;
+        let conv_1 = I128Sum::from(assets[&asset_1].2.clone());
+        let conv_2 = I128Sum::from(assets[&asset_2].2.clone());
+        let scale = -conv_2
+            .components()
+            .find(|(asset_type, _)| **asset_type == asset_2)
+            .map(|(_, value)| *value)
+            .unwrap();
+        assert!(scale > 0);
+        assert_eq!(
+            conv_1 + I128Sum::from_pair(asset_1, scale).unwrap(),
+            conv_2 + I128Sum::from_pair(asset_2, scale).unwrap()
+        );
+    }
+
+    /// Assert that the anchor and the tree of the conversions are the ones
+    /// rebuilt from all the committed conversions, archived or not
+    fn assert_naive_conversion_tree(s: &TestWlStorage) {
+        let conversion_state = &s.storage.conversion_state;
+        let tree_size = conversion_state.tree.size();
+        let archived: Vec<ConversionEntry> = namada_storage::iter_prefix(
+            s,
+            &crate::storage_key::masp_conversion_archive_prefix(),
+        )
+        .unwrap()
+        .map(|entry| entry.unwrap().1)
+        .collect();
+        let mut leaves: Vec<_> = conversion_state
+            .assets
+            .values()
+            .chain(&archived)
+            .filter(|(_, _, _, pos)| *pos < tree_size)
+            .map(|(_, _, conv, pos)| (*pos, Node::new(conv.cmu().to_repr())))
+            .collect();
+        // The committed assets have distinct positions in the tree
+        leaves.sort_by_key(|(pos, _)| *pos);
+        assert!(leaves.iter().map(|(pos, _)| *pos).eq(0..tree_size));
+
+        // The anchor is the root of the tree rebuilt from the leaves
+        let leaves: Vec<_> = leaves.into_iter().map(|(_, leaf)| leaf).collect();
+        let root = FrozenCommitmentTree::new(&leaves).root();
+        assert_eq!(conversion_state.tree.root(), root);
+        let anchor: namada_core::types::hash::Hash = s
+            .read(&crate::storage_key::masp_convert_anchor_key())
+            .unwrap()
+            .unwrap();
+        assert_eq!(anchor.0, bls12_381::Scalar::from(root).to_bytes());
+    }
+
+    fn init_storage(
+        initial_balance: Amount,
+        masp_locked_ratio: Dec,
+    ) -> TestWlStorage {
         let mut s = TestWlStorage::default();
         let params = Parameters {
             max_tx_bytes: 1024 * 1024,
@@ -574,6 +1154,9 @@ mod tests {
             fee_unshielding_gas_limit: 0,
             fee_unshielding_descriptions_limit: 0,
             minimum_gas_price: Default::default(),
+            max_native_mint_per_epoch: None,
+            masp_conversion_retention_epochs: None,
+            max_tx_gas: None,
         };
 
         // Initialize the state
@@ -615,13 +1198,7 @@ mod tests {
                     .insert(alias.to_string(), token_addr.clone());
             }
         }
-
-        for i in 0..ROUNDS {
-            println!("Round {i}");
-            update_allowed_conversions(&mut s).unwrap();
-            println!();
-            println!();
-        }
+        s
     }
 
     pub fn tokens() -> HashMap<Address, (&'static str, Denomination)> {
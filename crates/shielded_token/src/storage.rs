@@ -1,9 +1,11 @@
+use masp_primitives::asset_type::AssetType;
 use namada_core::types::address::Address;
 use namada_core::types::token;
 use namada_core::types::token::Amount;
 use namada_core::types::uint::Uint;
 use namada_storage as storage;
-use namada_storage::{StorageRead, StorageWrite};
+use namada_storage::{OptionExt, StorageRead, StorageWrite};
+use namada_trans_token::read_denom;
 use storage::ResultExt;
 
 use crate::storage_key::*;
@@ -18,21 +20,187 @@ pub fn write_params<S>(
 where
     S: StorageRead + StorageWrite,
 {
+    storage.write(&masp_last_inflation_key(address), Amount::zero())?;
+    storage.write(&masp_last_locked_amount_key(address), Amount::zero())?;
+    write_masp_params(params, storage, address, denom)
+}
+
+/// Read the MASP rewards parameters of a token. Returns `None` if the token
+/// doesn't earn MASP rewards. The locked amount target is truncated to whole
+/// units of the token.
+pub fn read_masp_params<S>(
+    storage: &S,
+    address: &Address,
+) -> storage::Result<Option<token::MaspParams>>
+where
+    S: StorageRead,
+{
+    let Some(max_reward_rate) =
+        storage.read(&masp_max_reward_rate_key(address))?
+    else {
+        return Ok(None);
+    };
+    let kp_gain_nom = storage
+        .read(&masp_kp_gain_key(address))?
+        .ok_or_err_msg("The MASP kp gain should exist in storage")?;
+    let kd_gain_nom = storage
+        .read(&masp_kd_gain_key(address))?
+        .ok_or_err_msg("The MASP kd gain should exist in storage")?;
+    let raw_target: Amount = storage
+        .read(&masp_locked_amount_target_key(address))?
+        .ok_or_err_msg(
+            "The MASP locked amount target should exist in storage",
+        )?;
+    let denom = read_denom(storage, address)?
+        .ok_or_err_msg("The token denomination should exist in storage")?;
+    let locked_amount_target =
+        u64::try_from(raw_target.raw_amount() / denom_unit(&denom)?)
+            .ok()
+            .ok_or_err_msg("The MASP locked amount target is too large")?;
+    Ok(Some(token::MaspParams {
+        max_reward_rate,
+        kd_gain_nom,
+        kp_gain_nom,
+        locked_amount_target,
+    }))
+}
+
+/// Update the MASP rewards parameters of a token that already earns MASP
+/// rewards, to be used by governance proposals. The new parameters are
+/// validated and take effect at the next epoch. The last inflation and locked
+/// amount of the token are kept for the PD controller to carry on from them.
+pub fn update_masp_params<S>(
+    storage: &mut S,
+    address: &Address,
+    params: &token::MaspParams,
+) -> storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if !storage.has_key(&masp_max_reward_rate_key(address))? {
+        return Err(storage::Error::new_const(
+            "Only the MASP parameters of the tokens that already earn MASP \
+             rewards can be updated",
+        ));
+    }
+    let denom = read_denom(storage, address)?
+        .ok_or_err_msg("The token denomination should exist in storage")?;
+    write_masp_params(params, storage, address, &denom)
+}
+
+/// Validate and write the MASP rewards parameters of a token
+fn write_masp_params<S>(
+    params: &token::MaspParams,
+    storage: &mut S,
+    address: &Address,
+    denom: &token::Denomination,
+) -> storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    params.validate().into_storage_result()?;
     let token::MaspParams {
         max_reward_rate: max_rate,
         kd_gain_nom,
         kp_gain_nom,
         locked_amount_target,
     } = params;
-    storage.write(&masp_last_inflation_key(address), Amount::zero())?;
-    storage.write(&masp_last_locked_amount_key(address), Amount::zero())?;
     storage.write(&masp_max_reward_rate_key(address), max_rate)?;
     storage.write(&masp_kp_gain_key(address), kp_gain_nom)?;
     storage.write(&masp_kd_gain_key(address), kd_gain_nom)?;
 
     let raw_target = Uint::from(*locked_amount_target)
-        * Uint::from(10).checked_pow(Uint::from(denom.0)).unwrap();
+        .checked_mul(denom_unit(denom)?)
+        .ok_or_err_msg("The MASP locked amount target is too large")?;
     let raw_target = Amount::from_uint(raw_target, 0).into_storage_result()?;
     storage.write(&masp_locked_amount_target_key(address), raw_target)?;
     Ok(())
 }
+
+/// Archive the conversion of an asset type that's pruned from the conversion
+/// state
+pub fn write_archived_conversion<S>(
+    storage: &mut S,
+    asset_type: &AssetType,
+    entry: &token::ConversionEntry,
+) -> storage::Result<()>
+where
+    S: StorageWrite,
+{
+    storage.write(&masp_conversion_archive_key(asset_type), entry)
+}
+
+/// Read the archived conversion of an asset type, if it was pruned from the
+/// conversion state
+pub fn read_archived_conversion<S>(
+    storage: &S,
+    asset_type: &AssetType,
+) -> storage::Result<Option<token::ConversionEntry>>
+where
+    S: StorageRead,
+{
+    storage.read(&masp_conversion_archive_key(asset_type))
+}
+
+/// The raw amount of one whole unit of a token with the given denomination
+fn denom_unit(denom: &token::Denomination) -> storage::Result<Uint> {
+    Uint::from(10)
+        .checked_pow(Uint::from(denom.0))
+        .ok_or_err_msg("The token denomination is too large")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use namada_core::types::address;
+    use namada_core::types::dec::Dec;
+    use namada_state::testing::TestWlStorage;
+    use namada_trans_token::write_denom;
+
+    use super::*;
+
+    /// Test that the MASP parameters of a token can be updated and read
+    /// back, and that the invalid ones are rejected.
+    #[test]
+    fn test_update_masp_params() {
+        let mut s = TestWlStorage::default();
+        let token = address::nam();
+        let denom = token::Denomination(6);
+        let mut params = token::MaspParams::default();
+
+        // A token that doesn't earn MASP rewards can't be updated
+        assert_eq!(read_masp_params(&s, &token).unwrap(), None);
+        assert!(update_masp_params(&mut s, &token, &params).is_err());
+
+        write_denom(&mut s, &token, denom).unwrap();
+        write_params(&params, &mut s, &token, &denom).unwrap();
+        assert_eq!(read_masp_params(&s, &token).unwrap(), Some(params.clone()));
+        assert_eq!(
+            s.read::<Amount>(&masp_locked_amount_target_key(&token))
+                .unwrap()
+                .unwrap(),
+            Amount::from_u64(params.locked_amount_target * 1_000_000)
+        );
+
+        params.max_reward_rate = Dec::from_str("0.2").unwrap();
+        params.locked_amount_target = 20_000;
+        update_masp_params(&mut s, &token, &params).unwrap();
+        assert_eq!(read_masp_params(&s, &token).unwrap(), Some(params.clone()));
+
+        // The invalid parameters are rejected and not written
+        for invalid in [
+            token::MaspParams {
+                kp_gain_nom: Dec::from_str("-0.1").unwrap(),
+                ..params
+            },
+            token::MaspParams {
+                max_reward_rate: Dec::from_str("1.5").unwrap(),
+                ..params
+            },
+        ] {
+            assert!(update_masp_params(&mut s, &token, &invalid).is_err());
+        }
+        assert_eq!(read_masp_params(&s, &token).unwrap(), Some(params));
+    }
+}
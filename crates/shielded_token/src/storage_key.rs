@@ -1,11 +1,14 @@
 //! Shielded token storage keys
 
+use masp_primitives::asset_type::AssetType;
 use masp_primitives::bls12_381::Scalar;
 use masp_primitives::sapling::Nullifier;
 use namada_core::types::address::{self, Address};
 use namada_core::types::hash::Hash;
-use namada_core::types::storage::{self, DbKeySeg, KeySeg};
-use namada_trans_token::storage_key::parameter_prefix;
+use namada_core::types::storage::{self, BlockHeight, DbKeySeg, KeySeg};
+use namada_trans_token::storage_key::{
+    is_any_token_parameter_key, parameter_prefix,
+};
 
 /// Key segment prefix for pinned shielded transactions
 pub const PIN_KEY_PREFIX: &str = "pin-";
@@ -17,6 +20,10 @@ pub const MASP_NOTE_COMMITMENT_TREE_KEY: &str = "commitment_tree";
 pub const MASP_NOTE_COMMITMENT_ANCHOR_PREFIX: &str = "note_commitment_anchor";
 /// Key segment prefix for the convert anchor
 pub const MASP_CONVERT_ANCHOR_KEY: &str = "convert_anchor";
+/// Key segment prefix for the archived conversions
+pub const MASP_CONVERSION_ARCHIVE_PREFIX: &str = "conversion_archive";
+/// Key segment prefix for the compact blocks
+pub const MASP_COMPACT_BLOCK_PREFIX: &str = "compact_block";
 /// Last calculated inflation value handed out
 pub const MASP_LAST_INFLATION_KEY: &str = "last_inflation";
 /// The last locked ratio
@@ -65,6 +72,26 @@ pub fn masp_last_inflation_key(token_address: &Address) -> storage::Key {
         .with_segment(MASP_LAST_INFLATION_KEY.to_owned())
 }
 
+/// Check if the given storage key is a MASP rewards parameter of a token, that
+/// can be updated by governance. If it is, returns the token.
+pub fn is_masp_param_key(key: &storage::Key) -> Option<&Address> {
+    let token = is_any_token_parameter_key(key)?;
+    match key.last() {
+        Some(DbKeySeg::StringSeg(param))
+            if [
+                MASP_MAX_REWARD_RATE_KEY,
+                MASP_KP_GAIN_KEY,
+                MASP_KD_GAIN_KEY,
+                MASP_LOCKED_AMOUNT_TARGET_KEY,
+            ]
+            .contains(&param.as_str()) =>
+        {
+            Some(token)
+        }
+        _ => None,
+    }
+}
+
 /// Check if the given storage key is a masp key
 pub fn is_masp_key(key: &storage::Key) -> bool {
     matches!(&key.segments[..],
@@ -146,3 +173,27 @@ pub fn masp_convert_anchor_key() -> storage::Key {
         .push(&MASP_CONVERT_ANCHOR_KEY.to_owned())
         .expect("Cannot obtain a storage key")
 }
+
+/// Get the prefix of the keys of the archived conversions
+pub fn masp_conversion_archive_prefix() -> storage::Key {
+    storage::Key::from(address::MASP.to_db_key())
+        .push(&MASP_CONVERSION_ARCHIVE_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key for the archived conversion of the given asset type
+pub fn masp_conversion_archive_key(asset_type: &AssetType) -> storage::Key {
+    masp_conversion_archive_prefix()
+        .push(&Hash(*asset_type.get_identifier()))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key for the compact block of the valid masp transactions at the
+/// given height
+pub fn masp_compact_block_key(height: BlockHeight) -> storage::Key {
+    storage::Key::from(address::MASP.to_db_key())
+        .push(&MASP_COMPACT_BLOCK_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&height)
+        .expect("Cannot obtain a storage key")
+}
@@ -8,6 +8,7 @@ use std::cmp::Ordering;
 use std::format;
 
 use namada_core::borsh::{BorshDeserialize, BorshSerialize, BorshSerializeExt};
+use namada_core::ledger::replay_protection;
 use namada_core::tendermint::merkle::proof::ProofOps;
 use namada_core::types::address::{
     Address, EstablishedAddressGen, InternalAddress,
@@ -186,6 +187,23 @@ where
         cache: Option<&D::Cache>,
         storage_read_past_height_limit: Option<u64>,
         merkle_tree_key_filter: fn(&storage::Key) -> bool,
+    ) -> Self {
+        Self::with_db(
+            D::open(db_path, cache),
+            chain_id,
+            native_token,
+            storage_read_past_height_limit,
+            merkle_tree_key_filter,
+        )
+    }
+
+    /// Create a new instance of the storage on top of an already opened db
+    pub fn with_db(
+        db: D,
+        chain_id: ChainId,
+        native_token: Address,
+        storage_read_past_height_limit: Option<u64>,
+        merkle_tree_key_filter: fn(&storage::Key) -> bool,
     ) -> Self {
         let block = BlockStorage {
             tree: MerkleTree::default(),
@@ -196,7 +214,7 @@ where
             results: BlockResults::default(),
         };
         State::<D, H> {
-            db: D::open(db_path, cache),
+            db,
             chain_id,
             block,
             header: None,
@@ -281,6 +299,9 @@ where
         // All states are written only when the first height or a new epoch
         let is_full_commit =
             self.block.height.0 == 1 || self.last_epoch != self.block.epoch;
+        // Write the roots of the subtrees updated in this block to the base
+        // tree
+        self.block.tree.update_base()?;
 
         // For convenience in tests, fill-in a header if it's missing.
         // Normally, the header is added in `FinalizeBlock`.
@@ -682,6 +703,8 @@ where
                 }
             }
         }
+        // Write the roots of the restored subtrees to the base tree at once
+        tree.update_base()?;
         if let Some(st) = store_type {
             // Add the base tree with the given height
             let mut stores = self
@@ -953,20 +976,16 @@ where
         &mut self,
         batch: &mut D::WriteBatch,
     ) -> Result<()> {
-        if self.block.epoch.0 == 0 {
+        let Some(prev_epoch) = self.block.epoch.checked_prev() else {
             return Ok(());
-        }
+        };
         // Prune non-provable stores at the previous epoch
         for st in StoreType::iter_non_provable() {
-            self.db.prune_merkle_tree_store(
-                batch,
-                st,
-                self.block.epoch.prev(),
-            )?;
+            self.db.prune_merkle_tree_store(batch, st, prev_epoch)?;
         }
         // Prune provable stores
         let oldest_epoch = self.get_oldest_epoch();
-        if oldest_epoch.0 > 0 {
+        if let Some(before_oldest_epoch) = oldest_epoch.checked_prev() {
             // Remove stores at the previous epoch because the Merkle tree
             // stores at the starting height of the epoch would be used to
             // restore stores at a height (> oldest_height) in the epoch
@@ -974,7 +993,7 @@ where
                 self.db.prune_merkle_tree_store(
                     batch,
                     st,
-                    oldest_epoch.prev(),
+                    before_oldest_epoch,
                 )?;
             }
 
@@ -1007,12 +1026,11 @@ where
 
     /// Get the oldest epoch where we can read a value
     pub fn get_oldest_epoch(&self) -> Epoch {
-        let oldest_height = match self.storage_read_past_height_limit {
-            Some(limit) if limit < self.get_last_block_height().0 => {
-                (self.get_last_block_height().0 - limit).into()
-            }
-            _ => BlockHeight(1),
-        };
+        let oldest_height = self
+            .storage_read_past_height_limit
+            .map(|limit| self.get_last_block_height().saturating_sub(limit))
+            .unwrap_or_default()
+            .max(BlockHeight::first());
         self.block
             .pred_epochs
             .get_epoch(oldest_height)
@@ -1091,6 +1109,37 @@ where
             raw_key.parse().expect("Failed hash conversion")
         }))
     }
+
+    /// Iterate the replay protection storage from all the blocks before the
+    /// last one
+    pub fn iter_all_replay_protection(
+        &self,
+    ) -> Box<dyn Iterator<Item = Hash> + '_> {
+        Box::new(
+            self.db
+                .iter_replay_protection_prefix(&replay_protection::all_prefix())
+                .map(|(raw_key, _, _)| {
+                    raw_key.parse().expect("Failed hash conversion")
+                }),
+        )
+    }
+
+    /// Iterate the expirations of the tx hashes in the replay protection
+    /// storage, ordered by the expiration
+    pub fn iter_replay_protection_expirations(
+        &self,
+    ) -> Box<dyn Iterator<Item = (u64, Hash)> + '_> {
+        Box::new(
+            self.db
+                .iter_replay_protection_prefix(
+                    &replay_protection::expiration_prefix(),
+                )
+                .map(|(raw_key, _, _)| {
+                    replay_protection::parse_expiration_key(&raw_key)
+                        .expect("Failed expiration key conversion")
+                }),
+        )
+    }
 }
 
 impl From<MerkleTreeError> for Error {
@@ -1266,6 +1315,9 @@ mod tests {
                 fee_unshielding_gas_limit: 20_000,
                 fee_unshielding_descriptions_limit: 15,
                 minimum_gas_price: BTreeMap::default(),
+                max_native_mint_per_epoch: None,
+                masp_conversion_retention_epochs: None,
+                max_tx_gas: None,
             };
             namada_parameters::init_storage(&parameters, &mut wl_storage).unwrap();
             // Initialize pred_epochs to the current height
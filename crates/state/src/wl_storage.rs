@@ -2,6 +2,7 @@
 
 use std::iter::Peekable;
 
+use namada_core::ledger::replay_protection;
 use namada_core::types::address::Address;
 use namada_core::types::hash::{Hash, StorageHasher};
 use namada_core::types::storage::{self, BlockHeight, Epochs};
@@ -260,6 +261,47 @@ where
         self.write_log.delete_tx_hash(hash)
     }
 
+    /// Record the expiration of the transaction with the provided hash, such
+    /// that its hash is pruned from storage once it has expired.
+    pub fn write_tx_expiration(
+        &mut self,
+        hash: Hash,
+        expiration: &DateTimeUtc,
+    ) {
+        self.write_log.write_tx_expiration(
+            hash,
+            replay_protection::expiration_timestamp(expiration),
+        )
+    }
+
+    /// Prune the hashes of the transactions that expired before the given
+    /// block time from storage, at most `max_pruned` of them. Expired
+    /// transactions are rejected, so that their hashes are no longer needed
+    /// to prevent replays. The hashes from the last block are only pruned
+    /// once they are finalized. Returns the number of pruned hashes.
+    pub fn prune_expired_tx_hashes(
+        &mut self,
+        block_time: &DateTimeUtc,
+        max_pruned: usize,
+    ) -> write_log::Result<usize> {
+        let block_time = replay_protection::expiration_timestamp(block_time);
+        let expired: Vec<(u64, Hash)> = self
+            .storage
+            .iter_replay_protection_expirations()
+            // The expiration is truncated to the second, so it has passed
+            // only if it's before the second of the block time
+            .take_while(|(expiration, _)| *expiration < block_time)
+            .filter(|(_, hash)| {
+                self.write_log.has_replay_protection_entry(hash).is_none()
+            })
+            .take(max_pruned)
+            .collect();
+        for (expiration, hash) in &expired {
+            self.write_log.prune_tx_hash(*hash, *expiration)?;
+        }
+        Ok(expired.len())
+    }
+
     #[inline]
     pub fn get_current_decision_height(&self) -> BlockHeight {
         self.storage.get_last_block_height() + 1
@@ -702,6 +744,44 @@ mod tests {
         itertools::assert_equal(expected_post, read_post);
     }
 
+    /// Test that the tx hashes are pruned once their txs have expired, but not
+    /// before they are finalized.
+    #[test]
+    fn test_prune_expired_tx_hashes() {
+        let mut s = TestWlStorage::default();
+        let expired = Hash::sha256(b"expired");
+        let expiring = Hash::sha256(b"expiring");
+        let never_expiring = Hash::sha256(b"never expiring");
+        for hash in [expired, expiring, never_expiring] {
+            s.write_tx_hash(hash).unwrap();
+        }
+        let timestamp = |secs| DateTimeUtc::from_unix_timestamp(secs).unwrap();
+        s.write_tx_expiration(expired, &timestamp(1_000));
+        s.write_tx_expiration(expiring, &timestamp(2_000));
+        s.commit_block().unwrap();
+
+        let block_time = timestamp(1_500);
+        let last_hashes: Vec<Hash> =
+            s.storage.iter_replay_protection().collect();
+        for hash in last_hashes {
+            s.write_log.finalize_tx_hash(hash).unwrap();
+        }
+        assert_eq!(s.prune_expired_tx_hashes(&block_time, 10).unwrap(), 0);
+        s.commit_block().unwrap();
+
+        assert_eq!(s.prune_expired_tx_hashes(&block_time, 10).unwrap(), 1);
+        assert!(!s.has_replay_protection_entry(&expired).unwrap());
+        s.commit_block().unwrap();
+        assert!(!s.storage.has_replay_protection_entry(&expired).unwrap());
+        for hash in [expiring, never_expiring] {
+            assert!(s.storage.has_replay_protection_entry(&hash).unwrap());
+        }
+        itertools::assert_equal(
+            s.storage.iter_replay_protection_expirations(),
+            [(2_000, expiring)],
+        );
+    }
+
     fn apply_to_wl_storage(s: &mut TestWlStorage, kvs: &[KeyVal<i8>]) {
         // Apply writes first
         for (key, val) in kvs {
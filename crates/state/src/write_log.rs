@@ -5,13 +5,14 @@ use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use itertools::Itertools;
 use namada_core::ledger::replay_protection;
-use namada_core::types::address::{
-    Address, EstablishedAddressGen, InternalAddress,
-};
+use namada_core::types::address::{Address, InternalAddress};
 use namada_core::types::hash::{Hash, StorageHasher};
 use namada_core::types::ibc::IbcEvent;
 use namada_core::types::storage;
-use namada_gas::{MEMORY_ACCESS_GAS_PER_BYTE, STORAGE_WRITE_GAS_PER_BYTE};
+use namada_gas::{
+    MEMORY_ACCESS_GAS_PER_BYTE, STORAGE_ACCESS_GAS_PER_BYTE,
+    STORAGE_WRITE_GAS_PER_BYTE,
+};
 use namada_trans_token::storage_key::{
     is_any_minted_balance_key, is_any_minter_key, is_any_token_balance_key,
     is_any_token_parameter_key,
@@ -77,13 +78,19 @@ enum ReProtStorageModification {
     Delete,
     /// Finalize an entry
     Finalize,
+    /// Prune an entry of a previous block, together with its expiration
+    Prune {
+        /// The unix timestamp of the expiration, in seconds
+        expiration: u64,
+    },
 }
 
 /// The write log storage
 #[derive(Debug, Clone)]
 pub struct WriteLog {
-    /// The generator of established addresses
-    address_gen: Option<EstablishedAddressGen>,
+    /// The number of accounts initialized by the current transaction, from
+    /// which their addresses are derived
+    tx_init_accounts: u64,
     /// All the storage modification accepted by validity predicates are stored
     /// in block write-log, before being committed to the storage
     block_write_log: HashMap<storage::Key, StorageModification>,
@@ -103,6 +110,17 @@ pub struct WriteLog {
     /// Storage modifications for the replay protection storage, always
     /// committed regardless of the result of the transaction
     replay_protection: HashMap<Hash, ReProtStorageModification>,
+    /// The unix timestamps of the expirations of the tx hashes written in the
+    /// block, committed together with the hashes
+    replay_protection_expirations: HashMap<Hash, u64>,
+    /// Block-level cache of the committed values of frequently read keys.
+    /// The value of a key is dropped from the cache when the key is written
+    /// and the whole cache is cleared when the block is committed.
+    read_cache: HashMap<storage::Key, Option<Vec<u8>>>,
+    /// The balance keys written since they were last taken, including the
+    /// writes of the dropped txs, to find the balances changed at every step
+    /// of a block. The keys are cleared when the block is committed.
+    balance_keys: BTreeSet<storage::Key>,
 }
 
 /// Write log prefix iterator
@@ -124,12 +142,15 @@ impl Iterator for PrefixIter {
 impl Default for WriteLog {
     fn default() -> Self {
         Self {
-            address_gen: None,
+            tx_init_accounts: 0,
             block_write_log: HashMap::with_capacity(100_000),
             tx_write_log: HashMap::with_capacity(100),
             tx_precommit_write_log: HashMap::with_capacity(100),
             ibc_events: BTreeSet::new(),
             replay_protection: HashMap::with_capacity(1_000),
+            replay_protection_expirations: HashMap::with_capacity(1_000),
+            read_cache: HashMap::new(),
+            balance_keys: BTreeSet::new(),
         }
     }
 }
@@ -199,6 +220,26 @@ impl WriteLog {
         }
     }
 
+    /// Read the committed value of the given key from the block's read cache
+    /// and return it with the gas cost of reading it from the storage, so
+    /// that the gas doesn't depend on the state of the cache. Returns
+    /// [`None`] if the key is not cached.
+    pub fn read_cached(
+        &self,
+        key: &storage::Key,
+    ) -> Option<(Option<Vec<u8>>, u64)> {
+        self.read_cache.get(key).map(|value| {
+            let len = key.len() + value.as_ref().map_or(0, Vec::len);
+            (value.clone(), len as u64 * STORAGE_ACCESS_GAS_PER_BYTE)
+        })
+    }
+
+    /// Save the committed value of the given key, as read from the storage,
+    /// into the block's read cache
+    pub fn cache_read(&mut self, key: &storage::Key, value: Option<Vec<u8>>) {
+        self.read_cache.insert(key.clone(), value);
+    }
+
     /// Write a key and a value and return the gas cost and the size difference
     /// Fails with [`Error::UpdateVpOfNewAccount`] when attempting to update a
     /// validity predicate of a new account that's not yet committed to storage.
@@ -209,6 +250,8 @@ impl WriteLog {
         key: &storage::Key,
         value: Vec<u8>,
     ) -> Result<(u64, i64)> {
+        self.read_cache.remove(key);
+        self.record_balance_key(key);
         let len = value.len();
         let gas = key.len() + len;
         let size_diff = match self
@@ -244,6 +287,8 @@ impl WriteLog {
         key: &storage::Key,
         value: Vec<u8>,
     ) -> Result<()> {
+        self.read_cache.remove(key);
+        self.record_balance_key(key);
         if let Some(prev) = self
             .block_write_log
             .insert(key.clone(), StorageModification::Write { value })
@@ -272,6 +317,7 @@ impl WriteLog {
         key: &storage::Key,
         value: Vec<u8>,
     ) -> Result<(u64, i64)> {
+        self.read_cache.remove(key);
         let len = value.len();
         let gas = key.len() + len;
         let size_diff = match self
@@ -309,6 +355,8 @@ impl WriteLog {
         if key.is_validity_predicate().is_some() {
             return Err(Error::DeleteVp);
         }
+        self.read_cache.remove(key);
+        self.record_balance_key(key);
         let size_diff = match self
             .tx_write_log
             .insert(key.clone(), StorageModification::Delete)
@@ -336,6 +384,8 @@ impl WriteLog {
         if key.is_validity_predicate().is_some() {
             return Err(Error::DeleteVp);
         }
+        self.read_cache.remove(key);
+        self.record_balance_key(key);
         if let Some(prev) = self
             .block_write_log
             .insert(key.clone(), StorageModification::Delete)
@@ -352,18 +402,29 @@ impl WriteLog {
         Ok(())
     }
 
-    /// Initialize a new account and return the gas cost.
+    /// Record the key if it's a balance key
+    fn record_balance_key(&mut self, key: &storage::Key) {
+        if is_any_token_balance_key(key).is_some() {
+            self.balance_keys.insert(key.clone());
+        }
+    }
+
+    /// Take the balance keys written since the last call, in the txs and by
+    /// the protocol
+    pub fn take_balance_keys(&mut self) -> BTreeSet<storage::Key> {
+        std::mem::take(&mut self.balance_keys)
+    }
+
+    /// Initialize a new account at the address derived from the hash of the
+    /// current transaction and the number of accounts it initialized before,
+    /// and return the gas cost.
     pub fn init_account(
         &mut self,
-        storage_address_gen: &EstablishedAddressGen,
+        tx_hash: &Hash,
         vp_code_hash: Hash,
     ) -> (Address, u64) {
-        // If we've previously generated a new account, we use the local copy of
-        // the generator. Otherwise, we create a new copy from the storage
-        let address_gen =
-            self.address_gen.get_or_insert(storage_address_gen.clone());
-        let addr =
-            address_gen.generate_address("TODO more randomness".as_bytes());
+        let addr = Address::derive_established(tx_hash, self.tx_init_accounts);
+        self.tx_init_accounts += 1;
         let key = storage::Key::validity_predicate(&addr);
         let gas = (key.len() + vp_code_hash.len()) as u64
             * STORAGE_WRITE_GAS_PER_BYTE;
@@ -372,6 +433,21 @@ impl WriteLog {
         (addr, gas)
     }
 
+    /// Initialize a new account at a given address, which must not be used by
+    /// another account, and return the gas cost.
+    pub fn init_account_at(
+        &mut self,
+        address: &Address,
+        vp_code_hash: Hash,
+    ) -> u64 {
+        let key = storage::Key::validity_predicate(address);
+        let gas = (key.len() + vp_code_hash.len()) as u64
+            * STORAGE_WRITE_GAS_PER_BYTE;
+        self.tx_write_log
+            .insert(key, StorageModification::InitAccount { vp_code_hash });
+        gas
+    }
+
     /// Set an IBC event and return the gas cost.
     pub fn emit_ibc_event(&mut self, event: IbcEvent) -> u64 {
         let len = event
@@ -475,6 +551,7 @@ impl WriteLog {
 
         self.block_write_log.extend(tx_precommit_write_log);
         self.take_ibc_events();
+        self.tx_init_accounts = 0;
     }
 
     /// Drop the current transaction's write log and precommit when it's
@@ -483,6 +560,7 @@ impl WriteLog {
     pub fn drop_tx(&mut self) {
         self.tx_precommit_write_log.clear();
         self.tx_write_log.clear();
+        self.tx_init_accounts = 0;
     }
 
     /// Drop the current transaction's write log but keep the precommit one.
@@ -529,14 +607,29 @@ impl WriteLog {
         // Replay protections specifically
         for (hash, entry) in self.replay_protection.iter() {
             match entry {
-                ReProtStorageModification::Write => storage
-                    .write_replay_protection_entry(
-                        batch,
-                        // Can only write tx hashes to the previous block, no
-                        // further
-                        &replay_protection::last_key(hash),
-                    )
-                    .map_err(Error::StorageError)?,
+                ReProtStorageModification::Write => {
+                    storage
+                        .write_replay_protection_entry(
+                            batch,
+                            // Can only write tx hashes to the previous block,
+                            // no further
+                            &replay_protection::last_key(hash),
+                        )
+                        .map_err(Error::StorageError)?;
+                    if let Some(expiration) =
+                        self.replay_protection_expirations.get(hash)
+                    {
+                        storage
+                            .write_replay_protection_entry(
+                                batch,
+                                &replay_protection::expiration_key(
+                                    *expiration,
+                                    hash,
+                                ),
+                            )
+                            .map_err(Error::StorageError)?
+                    }
+                }
                 ReProtStorageModification::Delete => storage
                     .delete_replay_protection_entry(
                         batch,
@@ -559,14 +652,31 @@ impl WriteLog {
                         )
                         .map_err(Error::StorageError)?
                 }
+                ReProtStorageModification::Prune { expiration } => {
+                    storage
+                        .delete_replay_protection_entry(
+                            batch,
+                            &replay_protection::all_key(hash),
+                        )
+                        .map_err(Error::StorageError)?;
+                    storage
+                        .delete_replay_protection_entry(
+                            batch,
+                            &replay_protection::expiration_key(
+                                *expiration,
+                                hash,
+                            ),
+                        )
+                        .map_err(Error::StorageError)?
+                }
             }
         }
 
-        if let Some(address_gen) = self.address_gen.take() {
-            storage.address_gen = address_gen
-        }
         self.block_write_log.clear();
         self.replay_protection.clear();
+        self.replay_protection_expirations.clear();
+        self.read_cache.clear();
+        self.balance_keys.clear();
         Ok(())
     }
 
@@ -665,9 +775,23 @@ impl WriteLog {
     /// Check if the given tx hash has already been processed. Returns `None` if
     /// the key is not known.
     pub fn has_replay_protection_entry(&self, hash: &Hash) -> Option<bool> {
+        self.replay_protection.get(hash).map(|action| {
+            !matches!(
+                action,
+                ReProtStorageModification::Delete
+                    | ReProtStorageModification::Prune { .. }
+            )
+        })
+    }
+
+    /// Get the tx hashes written in the current block
+    pub fn written_tx_hashes(&self) -> impl Iterator<Item = &Hash> {
         self.replay_protection
-            .get(hash)
-            .map(|action| !matches!(action, ReProtStorageModification::Delete))
+            .iter()
+            .filter(|(_, action)| {
+                matches!(action, ReProtStorageModification::Write)
+            })
+            .map(|(hash, _)| hash)
     }
 
     /// Write the transaction hash
@@ -687,6 +811,13 @@ impl WriteLog {
         Ok(())
     }
 
+    /// Record the unix timestamp of the expiration of a transaction, in
+    /// seconds. It's committed together with the hash of the transaction, if
+    /// the hash is written in the current block.
+    pub(crate) fn write_tx_expiration(&mut self, hash: Hash, expiration: u64) {
+        self.replay_protection_expirations.insert(hash, expiration);
+    }
+
     /// Remove the transaction hash
     pub(crate) fn delete_tx_hash(&mut self, hash: Hash) -> Result<()> {
         match self
@@ -726,6 +857,29 @@ impl WriteLog {
 
         Ok(())
     }
+
+    /// Prune the hash of an expired transaction from the list of all blocks,
+    /// together with the given unix timestamp of its expiration, in seconds.
+    /// The transaction can't be replayed anymore since it has expired.
+    pub(crate) fn prune_tx_hash(
+        &mut self,
+        hash: Hash,
+        expiration: u64,
+    ) -> Result<()> {
+        if self
+            .replay_protection
+            .insert(hash, ReProtStorageModification::Prune { expiration })
+            .is_some()
+        {
+            // Cannot prune an hash if other requests have already been
+            // committed for the same hash
+            return Err(Error::ReplayProtection(format!(
+                "Requested a prune on hash {hash} over a previous request"
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -738,6 +892,62 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_read_cache() {
+        let mut write_log = WriteLog::default();
+        let key =
+            storage::Key::parse("key").expect("cannot parse the key string");
+        assert!(write_log.read_cached(&key).is_none());
+
+        // The gas of a cached read is the same as of a storage read
+        let value = "value".as_bytes().to_vec();
+        write_log.cache_read(&key, Some(value.clone()));
+        assert_eq!(
+            write_log.read_cached(&key),
+            Some((
+                Some(value.clone()),
+                (key.len() + value.len()) as u64 * STORAGE_ACCESS_GAS_PER_BYTE
+            ))
+        );
+
+        // Writes drop the cached value
+        write_log.write(&key, vec![]).unwrap();
+        assert!(write_log.read_cached(&key).is_none());
+        write_log.cache_read(&key, None);
+        write_log.delete(&key).unwrap();
+        assert!(write_log.read_cached(&key).is_none());
+        write_log.cache_read(&key, None);
+        write_log.protocol_write(&key, vec![]).unwrap();
+        assert!(write_log.read_cached(&key).is_none());
+    }
+
+    #[test]
+    fn test_balance_keys() {
+        let mut write_log = WriteLog::default();
+        let token = address::nam();
+        let owner = address::testing::established_address_1();
+        let balance_key =
+            namada_trans_token::storage_key::balance_key(&token, &owner);
+        let other_key =
+            storage::Key::parse("key").expect("cannot parse the key string");
+
+        write_log.write(&balance_key, vec![]).unwrap();
+        write_log.write(&other_key, vec![]).unwrap();
+        // The keys of the dropped txs are kept
+        write_log.drop_tx();
+        assert_eq!(
+            write_log.take_balance_keys(),
+            BTreeSet::from([balance_key.clone()])
+        );
+        assert!(write_log.take_balance_keys().is_empty());
+
+        write_log.protocol_delete(&balance_key).unwrap();
+        assert_eq!(
+            write_log.take_balance_keys(),
+            BTreeSet::from([balance_key])
+        );
+    }
+
     #[test]
     fn test_crud_value() {
         let mut write_log = WriteLog::default();
@@ -819,12 +1029,12 @@ mod tests {
     #[test]
     fn test_crud_account() {
         let mut write_log = WriteLog::default();
-        let address_gen = EstablishedAddressGen::new("test");
+        let tx_hash = Hash::sha256(b"tx");
 
         // init
         let init_vp = "initialized".as_bytes().to_vec();
         let vp_hash = Hash::sha256(init_vp);
-        let (addr, gas) = write_log.init_account(&address_gen, vp_hash);
+        let (addr, gas) = write_log.init_account(&tx_hash, vp_hash);
         let vp_key = storage::Key::validity_predicate(&addr);
         assert_eq!(
             gas,
@@ -850,14 +1060,37 @@ mod tests {
         assert_eq!(init_accounts.len(), 1);
     }
 
+    /// Test that the accounts initialized by a tx are at the addresses derived
+    /// from its hash and their index.
+    #[test]
+    fn test_init_account_addresses() {
+        let mut write_log = WriteLog::default();
+        let tx_hash = Hash::sha256(b"tx");
+        let vp_hash = Hash::sha256(b"vp");
+
+        let (addr0, _) = write_log.init_account(&tx_hash, vp_hash);
+        let (addr1, _) = write_log.init_account(&tx_hash, vp_hash);
+        assert_eq!(addr0, Address::derive_established(&tx_hash, 0));
+        assert_eq!(addr1, Address::derive_established(&tx_hash, 1));
+
+        // The index starts over with the next tx
+        write_log.commit_tx();
+        let other_hash = Hash::sha256(b"other tx");
+        let (addr, _) = write_log.init_account(&other_hash, vp_hash);
+        assert_eq!(addr, Address::derive_established(&other_hash, 0));
+        write_log.drop_tx();
+        let (addr, _) = write_log.init_account(&other_hash, vp_hash);
+        assert_eq!(addr, Address::derive_established(&other_hash, 0));
+    }
+
     #[test]
     fn test_update_initialized_account_should_fail() {
         let mut write_log = WriteLog::default();
-        let address_gen = EstablishedAddressGen::new("test");
+        let tx_hash = Hash::sha256(b"tx");
 
         let init_vp = "initialized".as_bytes().to_vec();
         let vp_hash = Hash::sha256(init_vp);
-        let (addr, _) = write_log.init_account(&address_gen, vp_hash);
+        let (addr, _) = write_log.init_account(&tx_hash, vp_hash);
         let vp_key = storage::Key::validity_predicate(&addr);
 
         // update should fail
@@ -872,11 +1105,11 @@ mod tests {
     #[test]
     fn test_delete_initialized_account_should_fail() {
         let mut write_log = WriteLog::default();
-        let address_gen = EstablishedAddressGen::new("test");
+        let tx_hash = Hash::sha256(b"tx");
 
         let init_vp = "initialized".as_bytes().to_vec();
         let vp_hash = Hash::sha256(init_vp);
-        let (addr, _) = write_log.init_account(&address_gen, vp_hash);
+        let (addr, _) = write_log.init_account(&tx_hash, vp_hash);
         let vp_key = storage::Key::validity_predicate(&addr);
 
         // delete should fail
@@ -900,7 +1133,7 @@ mod tests {
         let mut storage = crate::testing::TestStorage::default();
         let mut write_log = WriteLog::default();
         let mut batch = crate::testing::TestStorage::batch();
-        let address_gen = EstablishedAddressGen::new("test");
+        let tx_hash = Hash::sha256(b"tx");
 
         let key1 =
             storage::Key::parse("key1").expect("cannot parse the key string");
@@ -913,7 +1146,7 @@ mod tests {
 
         // initialize an account
         let vp1 = Hash::sha256("vp1".as_bytes());
-        let (addr1, _) = write_log.init_account(&address_gen, vp1);
+        let (addr1, _) = write_log.init_account(&tx_hash, vp1);
         write_log.commit_tx();
 
         // write values
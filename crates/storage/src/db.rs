@@ -294,6 +294,13 @@ pub trait DBIter<'iter> {
 
     /// Read replay protection storage from the last block
     fn iter_replay_protection(&'iter self) -> Self::PrefixIter;
+
+    /// Read replay protection storage under the given prefix, stripped from
+    /// the keys
+    fn iter_replay_protection_prefix(
+        &'iter self,
+        prefix: &Key,
+    ) -> Self::PrefixIter;
 }
 
 /// Atomic batch write.
@@ -783,8 +783,14 @@ impl<'iter> DBIter<'iter> for MockDB {
     }
 
     fn iter_replay_protection(&'iter self) -> Self::PrefixIter {
-        let stripped_prefix =
-            format!("replay_protection/{}/", replay_protection::last_prefix());
+        self.iter_replay_protection_prefix(&replay_protection::last_prefix())
+    }
+
+    fn iter_replay_protection_prefix(
+        &'iter self,
+        prefix: &Key,
+    ) -> Self::PrefixIter {
+        let stripped_prefix = format!("replay_protection/{prefix}/");
         let prefix = stripped_prefix.clone();
         let iter = self.0.borrow().clone().into_iter();
         MockPrefixIterator::new(MockIterator { prefix, iter }, stripped_prefix)
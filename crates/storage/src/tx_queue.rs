@@ -1,4 +1,5 @@
 use namada_core::borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::types::address::Address;
 use namada_core::types::ethereum_events::EthereumEvent;
 use namada_gas::Gas;
 use namada_tx::Tx;
@@ -13,6 +14,9 @@ pub struct TxInQueue {
     /// This allows for a more detailed logging about the gas used by the
     /// wrapper and that used by the inner
     pub gas: Gas,
+    /// The proposer of the block that included the wrapper tx, to which the
+    /// escrowed fee for the used gas is paid once the inner tx is applied
+    pub block_proposer: Address,
 }
 
 #[derive(Default, Debug, Clone, BorshDeserialize, BorshSerialize)]
@@ -297,6 +297,7 @@ async fn test_bridge_pool_e2e() {
             genesis.ethereum_bridge_params = Some(EthereumBridgeParams {
                 eth_start_height: Default::default(),
                 min_confirmations: Default::default(),
+                checkpoint_interval: None,
                 contracts: Contracts {
                     native_erc20: wnam(),
                     bridge: UpgradeableContract {
@@ -492,6 +493,7 @@ async fn test_wnam_transfer() -> Result<()> {
             // of this type using 0 as argument.
             NonZeroU64::new_unchecked(10)
         }),
+        checkpoint_interval: None,
         contracts: Contracts {
             native_erc20: wnam(),
             bridge: UpgradeableContract {
@@ -599,6 +601,7 @@ fn test_configure_oracle_from_storage() -> Result<()> {
             // of this type using 0 as argument.
             NonZeroU64::new_unchecked(10)
         }),
+        checkpoint_interval: None,
         contracts: Contracts {
             native_erc20: EthAddress([1; 20]),
             bridge: UpgradeableContract {
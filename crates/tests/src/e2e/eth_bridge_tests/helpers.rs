@@ -90,6 +90,7 @@ pub fn setup_single_validator_test() -> Result<(Test, NamadaBgCmd)> {
             // of this type using 0 as argument.
             NonZeroU64::new_unchecked(10)
         }),
+        checkpoint_interval: None,
         contracts: Contracts {
             native_erc20: wnam(),
             bridge: UpgradeableContract {
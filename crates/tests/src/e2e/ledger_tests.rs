@@ -2326,6 +2326,7 @@ fn pgf_governance_proposal() -> Result<()> {
             amount: token::Amount::from_u64(5),
             target: christel,
         })],
+        retro_claims: vec![],
     };
 
     let valid_proposal_json_path =
@@ -85,6 +85,7 @@ pub fn initialize_genesis() -> Result<(MockNode, MockServicesController)> {
         chain_id: Some(chain_id.clone()),
         base_dir: test_dir.path().to_path_buf(),
         wasm_dir: Some(test_dir.path().join(chain_id.as_str()).join("wasm")),
+        pretty_amounts: false,
     };
     // setup genesis file
     namada_apps::client::utils::init_network(
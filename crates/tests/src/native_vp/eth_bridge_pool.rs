@@ -70,9 +70,11 @@ mod test_bridge_pool_vp {
             erc20_whitelist: vec![Erc20WhitelistEntry {
                 token_address: wnam(),
                 token_cap: Amount::from_u64(TOKEN_CAP).native_denominated(),
+                withdrawal_veto: None,
             }],
             eth_start_height: Default::default(),
             min_confirmations: Default::default(),
+            checkpoint_interval: None,
             contracts: Contracts {
                 native_erc20: wnam(),
                 bridge: UpgradeableContract {
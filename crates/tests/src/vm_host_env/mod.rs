@@ -38,6 +38,7 @@ mod tests {
     use namada::types::key::*;
     use namada::types::storage::{self, BlockHash, BlockHeight, Key, KeySeg};
     use namada::types::time::DateTimeUtc;
+    use namada::types::validity_predicate::VpRejection;
     use namada::types::{address, key};
     use namada_test_utils::TestWasms;
     use namada_tx_prelude::address::InternalAddress;
@@ -651,6 +652,30 @@ mod tests {
         );
     }
 
+    /// Test that the reason of a rejection is recorded in the env
+    #[test]
+    fn test_vp_reject_with() {
+        // The environment must be initialized first
+        vp_host_env::init();
+
+        let key = storage::Key::parse("key/a").unwrap();
+        let accepted = namada_vp_prelude::reject_with(
+            7,
+            "Debit over the limit",
+            Some(&key),
+        )
+        .unwrap();
+        assert!(!accepted);
+        assert_eq!(
+            vp_host_env::take().rejection,
+            Some(VpRejection {
+                code: 7,
+                message: "Debit over the limit".to_string(),
+                key: Some(key),
+            })
+        );
+    }
+
     #[test]
     fn test_vp_eval() {
         // The environment must be initialized first
@@ -498,6 +498,15 @@ mod native_tx_host_env {
         code_tag_len: u64,
         result_ptr: u64
     ));
+    native_host_fn!(tx_init_token(
+        code_hash_ptr: u64,
+        code_hash_len: u64,
+        code_tag_ptr: u64,
+        code_tag_len: u64,
+        origin_ptr: u64,
+        origin_len: u64,
+        result_ptr: u64
+    ));
     native_host_fn!(tx_emit_ibc_event(event_ptr: u64, event_len: u64));
     native_host_fn!(tx_get_ibc_events(event_type_ptr: u64, event_type_len: u64) -> i64);
     native_host_fn!(tx_get_chain_id(result_ptr: u64));
@@ -13,7 +13,7 @@ use namada::types::storage::{self, Key, TxIndex};
 use namada::vm::prefix_iter::PrefixIterators;
 use namada::vm::wasm::{self, VpCache};
 use namada::vm::{self, WasmCacheRwAccess};
-use namada_tx_prelude::validity_predicate::VpSentinel;
+use namada_tx_prelude::validity_predicate::{VpRejection, VpSentinel};
 use namada_vp_prelude::Ctx;
 use tempfile::TempDir;
 
@@ -46,6 +46,7 @@ pub struct TestVpEnv {
     pub iterators: PrefixIterators<'static, MockDB>,
     pub gas_meter: VpGasMeter,
     pub sentinel: VpSentinel,
+    pub rejection: Option<VpRejection>,
     pub tx: Tx,
     pub tx_index: TxIndex,
     pub keys_changed: BTreeSet<storage::Key>,
@@ -80,6 +81,7 @@ impl Default for TestVpEnv {
                 &TxGasMeter::new_from_sub_limit(10_000_000_000.into()),
             ),
             sentinel: VpSentinel::default(),
+            rejection: None,
             tx,
             tx_index: TxIndex::default(),
             keys_changed: BTreeSet::default(),
@@ -262,6 +264,7 @@ mod native_vp_host_env {
                                 iterators,
                                 gas_meter,
                                 sentinel,
+                                rejection,
                                 tx,
                                 tx_index,
                                 keys_changed,
@@ -279,6 +282,7 @@ mod native_vp_host_env {
                                 iterators,
                                 gas_meter,
                                 sentinel,
+                                rejection,
                                 tx,
                                 tx_index,
                                 verifiers,
@@ -307,6 +311,7 @@ mod native_vp_host_env {
                                 iterators,
                                 gas_meter,
                                 sentinel,
+                                rejection,
                                 tx,
                                 tx_index,
                                 keys_changed,
@@ -324,6 +329,7 @@ mod native_vp_host_env {
                                 iterators,
                                 gas_meter,
                                 sentinel,
+                                rejection,
                                 tx,
                                 tx_index,
                                 verifiers,
@@ -381,4 +387,5 @@ mod native_vp_host_env {
         max_signatures_len: u64,
     ) -> i64);
     native_host_fn!(vp_charge_gas(used_gas: u64));
+    native_host_fn!(vp_set_rejection(rejection_ptr: u64, rejection_len: u64));
 }
@@ -0,0 +1,51 @@
+//! Token factory types, storage keys and storage fns.
+
+use namada_core::types::address::Address;
+pub use namada_core::types::token::factory::*;
+use namada_storage as storage;
+use namada_storage::{StorageRead, StorageWrite};
+
+use crate::storage::{credit_tokens, write_denom, write_metadata};
+
+/// Read the origin of a token created by the factory, if any.
+pub fn read_origin<S>(
+    storage: &S,
+    token: &Address,
+) -> storage::Result<Option<TokenOrigin>>
+where
+    S: StorageRead,
+{
+    storage.read(&origin_key(token))
+}
+
+/// Initialize the storage of a token created by the factory, whose account
+/// must have been initialized at the address derived from its origin. Its
+/// initial supply is minted to its creator. Returns an `Err` if a token was
+/// already created with the same origin.
+pub fn create_token<S>(
+    storage: &mut S,
+    create: &CreateToken,
+) -> storage::Result<Address>
+where
+    S: StorageRead + StorageWrite,
+{
+    let token = create.origin.token_address();
+    let key = origin_key(&token);
+    if storage.has_key(&key)? {
+        return Err(storage::Error::new_const("The token already exists"));
+    }
+    storage.write(&key, &create.origin)?;
+    write_denom(storage, &token, create.denom)?;
+    if let Some(metadata) = &create.metadata {
+        write_metadata(storage, &token, metadata)?;
+    }
+    if !create.initial_supply.is_zero() {
+        credit_tokens(
+            storage,
+            &token,
+            &create.origin.creator,
+            create.initial_supply,
+        )?;
+    }
+    Ok(token)
+}
@@ -1,5 +1,7 @@
 //! Transparent token types, storage functions, and validation.
 
+pub mod factory;
+pub mod non_fungible;
 mod storage;
 pub mod storage_key;
 
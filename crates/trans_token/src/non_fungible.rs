@@ -0,0 +1,80 @@
+//! Non-fungible token types, storage keys and storage fns.
+
+use namada_core::types::address::Address;
+pub use namada_core::types::token::non_fungible::*;
+use namada_storage as storage;
+use namada_storage::{StorageRead, StorageWrite};
+
+/// Read the owner of an NFT, if it exists.
+pub fn read_owner<S>(
+    storage: &S,
+    class: &Address,
+    id: &NftId,
+) -> storage::Result<Option<Address>>
+where
+    S: StorageRead,
+{
+    storage.read(&owner_key(class, id))
+}
+
+/// Read the metadata URI of an NFT, if any.
+pub fn read_metadata<S>(
+    storage: &S,
+    class: &Address,
+    id: &NftId,
+) -> storage::Result<Option<String>>
+where
+    S: StorageRead,
+{
+    storage.read(&metadata_key(class, id))
+}
+
+/// Mint a new NFT. Returns an `Err` if an NFT with the same id already exists
+/// in its class.
+pub fn mint<S>(storage: &mut S, mint: &MintNft) -> storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = owner_key(&mint.class, &mint.id);
+    if storage.has_key(&key)? {
+        return Err(storage::Error::new_const("The NFT already exists"));
+    }
+    storage.write(&key, &mint.owner)?;
+    if let Some(metadata) = &mint.metadata {
+        storage.write(&metadata_key(&mint.class, &mint.id), metadata)?;
+    }
+    Ok(())
+}
+
+/// Transfer an NFT. Returns an `Err` if the NFT is not owned by the source.
+pub fn transfer<S>(
+    storage: &mut S,
+    transfer: &TransferNft,
+) -> storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let owner = read_owner(storage, &transfer.class, &transfer.id)?;
+    if owner.as_ref() != Some(&transfer.source) {
+        return Err(storage::Error::new_const(
+            "The NFT is not owned by the source",
+        ));
+    }
+    storage.write(&owner_key(&transfer.class, &transfer.id), &transfer.target)
+}
+
+/// Burn an NFT together with its metadata. Returns an `Err` if the NFT is not
+/// owned by the given owner.
+pub fn burn<S>(storage: &mut S, burn: &BurnNft) -> storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let owner = read_owner(storage, &burn.class, &burn.id)?;
+    if owner.as_ref() != Some(&burn.owner) {
+        return Err(storage::Error::new_const(
+            "The NFT is not owned by the given owner",
+        ));
+    }
+    storage.delete(&owner_key(&burn.class, &burn.id))?;
+    storage.delete(&metadata_key(&burn.class, &burn.id))
+}
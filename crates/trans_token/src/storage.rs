@@ -1,8 +1,12 @@
+use borsh::{BorshDeserialize, BorshSerialize};
 use namada_core::hints;
 use namada_core::types::address::{Address, InternalAddress};
-use namada_core::types::token::{self, Amount, DenominatedAmount};
+use namada_core::types::storage::{Epoch, Key};
+use namada_core::types::token::{
+    self, Amount, BalanceDelta, DenominatedAmount,
+};
 use namada_storage as storage;
-use namada_storage::{StorageRead, StorageWrite};
+use namada_storage::{ResultExt, StorageRead, StorageWrite};
 
 use crate::storage_key::*;
 
@@ -45,6 +49,158 @@ where
     Ok(balance)
 }
 
+/// Read the effective supply of the native token, that is its total supply
+/// without the tokens locked in the PoS accounts (bonded or slashed) and in
+/// the PGF account.
+pub fn read_effective_native_supply<S>(
+    storage: &S,
+) -> storage::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let native_token = storage.get_native_token()?;
+    let total_supply = read_total_supply(storage, &native_token)?;
+    [
+        InternalAddress::PoS,
+        InternalAddress::PosSlashPool,
+        InternalAddress::Pgf,
+    ]
+    .into_iter()
+    .try_fold(total_supply, |supply, locked| {
+        let locked_balance =
+            read_balance(storage, &native_token, &Address::Internal(locked))?;
+        supply.checked_sub(locked_balance).ok_or_else(|| {
+            storage::Error::new_const(
+                "The locked native tokens exceed the total supply",
+            )
+        })
+    })
+}
+
+/// Accumulate the changes of the token balances from the `pre` to the `post`
+/// storage in the changed keys. When an `owner` is given, only the changes of
+/// its balances are accumulated.
+pub fn read_balance_delta<'k, Pre, Post>(
+    pre: &Pre,
+    post: &Post,
+    keys_changed: impl IntoIterator<Item = &'k Key>,
+    owner: Option<&Address>,
+) -> storage::Result<BalanceDelta>
+where
+    Pre: StorageRead,
+    Post: StorageRead,
+{
+    let mut delta = BalanceDelta::default();
+    for key in keys_changed {
+        match is_any_token_balance_key(key) {
+            Some([token, key_owner])
+                if owner.map_or(true, |owner| owner == key_owner) =>
+            {
+                let pre_balance: Amount = pre.read(key)?.unwrap_or_default();
+                let post_balance: Amount = post.read(key)?.unwrap_or_default();
+                delta
+                    .record(token, pre_balance, post_balance)
+                    .into_storage_result()?;
+            }
+            _ => {}
+        }
+    }
+    Ok(delta)
+}
+
+/// Accumulate the changes of the allowances for spending the owner's tokens
+/// from the `pre` to the `post` storage in the changed keys.
+pub fn read_allowance_delta<'k, Pre, Post>(
+    pre: &Pre,
+    post: &Post,
+    keys_changed: impl IntoIterator<Item = &'k Key>,
+    owner: &Address,
+) -> storage::Result<BalanceDelta>
+where
+    Pre: StorageRead,
+    Post: StorageRead,
+{
+    let mut delta = BalanceDelta::default();
+    for key in keys_changed {
+        match is_any_allowance_key(key) {
+            Some([token, key_owner, _spender]) if key_owner == owner => {
+                let pre_allowance: Amount = pre.read(key)?.unwrap_or_default();
+                let post_allowance: Amount =
+                    post.read(key)?.unwrap_or_default();
+                delta
+                    .record(token, pre_allowance, post_allowance)
+                    .into_storage_result()?;
+            }
+            _ => {}
+        }
+    }
+    Ok(delta)
+}
+
+/// Read the allowance of a spender to transfer the owner's tokens.
+pub fn read_allowance<S>(
+    storage: &S,
+    token: &Address,
+    owner: &Address,
+    spender: &Address,
+) -> storage::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let key = allowance_key(token, owner, spender);
+    Ok(storage.read(&key)?.unwrap_or_default())
+}
+
+/// Set the allowance of a spender to transfer the owner's tokens, replacing
+/// the previous one. A zero allowance removes the approval.
+pub fn approve<S>(
+    storage: &mut S,
+    token: &Address,
+    owner: &Address,
+    spender: &Address,
+    amount: token::Amount,
+) -> storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if owner == spender {
+        return Err(storage::Error::new_const(
+            "An owner can't approve itself as a spender",
+        ));
+    }
+    let key = allowance_key(token, owner, spender);
+    if amount.is_zero() {
+        storage.delete(&key)
+    } else {
+        storage.write(&key, amount)
+    }
+}
+
+/// Transfer the owner's `token` to `dest` by an approved spender, spending
+/// its allowance. Returns an `Err` if the allowance or the owner's balance
+/// is insufficient.
+pub fn transfer_from<S>(
+    storage: &mut S,
+    token: &Address,
+    owner: &Address,
+    spender: &Address,
+    dest: &Address,
+    amount: token::Amount,
+) -> storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let allowance = read_allowance(storage, token, owner, spender)?;
+    let new_allowance = allowance
+        .checked_sub(amount)
+        .ok_or_else(|| storage::Error::new_const("Insufficient allowance"))?;
+    approve(storage, token, owner, spender, new_allowance)?;
+    transfer(storage, token, owner, dest, amount)
+}
+
 /// Read the denomination of a given token, if any. Note that native
 /// transparent tokens do not have this set and instead use the constant
 /// [`token::NATIVE_MAX_DECIMAL_PLACES`].
@@ -97,6 +253,33 @@ where
     storage.write(&key, denom)
 }
 
+/// Read the metadata of a given token, if any.
+pub fn read_metadata<S>(
+    storage: &S,
+    token: &Address,
+) -> storage::Result<Option<token::Metadata>>
+where
+    S: StorageRead,
+{
+    storage.read(&metadata_key(token))
+}
+
+/// Write the metadata of a given token. Returns an `Err` if the metadata is
+/// not valid.
+pub fn write_metadata<S>(
+    storage: &mut S,
+    token: &Address,
+    metadata: &token::Metadata,
+) -> storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if !metadata.is_valid() {
+        return Err(storage::Error::new_const("Invalid token metadata"));
+    }
+    storage.write(&metadata_key(token), metadata)
+}
+
 /// Transfer `token` from `src` to `dest`. Returns an `Err` if `src` has
 /// insufficient balance or if the transfer the `dest` would overflow (This can
 /// only happen if the total supply doesn't fit in `token::Amount`).
@@ -197,6 +380,129 @@ where
     storage.write(&total_supply_key, new_total_supply)
 }
 
+/// The native tokens minted by the protocol in an epoch, within the limit of
+/// the `max_native_mint_per_epoch` protocol parameter
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct MintVolume {
+    /// The epoch of the mints
+    pub epoch: Epoch,
+    /// The amount minted in the epoch
+    pub minted: Amount,
+    /// The amount whose minting was deferred to a later epoch because it
+    /// exceeded the limit
+    pub deferred: Amount,
+}
+
+/// Read the native tokens minted by the protocol in the current epoch.
+pub fn read_mint_volume<S>(storage: &S) -> storage::Result<MintVolume>
+where
+    S: StorageRead,
+{
+    let native_token = storage.get_native_token()?;
+    let epoch = storage.get_block_epoch()?;
+    let volume: Option<MintVolume> =
+        storage.read(&mint_volume_key(&native_token))?;
+    Ok(match volume {
+        Some(volume) if volume.epoch == epoch => volume,
+        _ => MintVolume {
+            epoch,
+            ..MintVolume::default()
+        },
+    })
+}
+
+/// Record native tokens that were minted by the protocol without
+/// [`mint_native_tokens`], such as the MASP rewards that back conversions
+/// already committed to. They count towards the limit of the epoch but
+/// can't be deferred.
+pub fn record_native_mint<S>(
+    storage: &mut S,
+    amount: token::Amount,
+) -> storage::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let native_token = storage.get_native_token()?;
+    let mut volume = read_mint_volume(storage)?;
+    volume.minted = volume.minted.checked_add(amount).ok_or_else(|| {
+        storage::Error::new_const("Native mint volume overflow")
+    })?;
+    storage.write(&mint_volume_key(&native_token), volume)
+}
+
+/// Mint native tokens to an account, to be used only by protocol. The amount
+/// in excess of the limit of native tokens minted per epoch is not minted,
+/// but deferred to be minted to the account in a later epoch by
+/// [`mint_deferred_native_tokens`]. Returns the deferred amount.
+pub fn mint_native_tokens<S>(
+    storage: &mut S,
+    dest: &Address,
+    amount: token::Amount,
+) -> storage::Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    let native_token = storage.get_native_token()?;
+    let mut volume = read_mint_volume(storage)?;
+    let mintable =
+        match namada_parameters::read_max_native_mint_per_epoch(storage)? {
+            Some(limit) => std::cmp::min(
+                amount,
+                limit.checked_sub(volume.minted).unwrap_or_default(),
+            ),
+            None => amount,
+        };
+    let deferred = amount - mintable;
+
+    if !mintable.is_zero() {
+        credit_tokens(storage, &native_token, dest, mintable)?;
+        volume.minted += mintable;
+    }
+    if !deferred.is_zero() {
+        let deferred_key = deferred_mint_key(&native_token, dest);
+        let cur_deferred: Amount =
+            storage.read(&deferred_key)?.unwrap_or_default();
+        let new_deferred =
+            cur_deferred.checked_add(deferred).ok_or_else(|| {
+                storage::Error::new_const("Deferred native mint overflow")
+            })?;
+        storage.write(&deferred_key, new_deferred)?;
+        volume.deferred += deferred;
+    }
+    storage.write(&mint_volume_key(&native_token), volume)?;
+    Ok(deferred)
+}
+
+/// Mint the native tokens deferred in the previous epochs, in the order of
+/// their accounts and within the limit of the current epoch. Returns the
+/// amount that is still deferred.
+pub fn mint_deferred_native_tokens<S>(
+    storage: &mut S,
+) -> storage::Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    let native_token = storage.get_native_token()?;
+    let deferred_mints = storage::iter_prefix::<Amount>(
+        storage,
+        &deferred_mint_prefix(&native_token),
+    )?
+    .collect::<storage::Result<Vec<_>>>()?;
+
+    let mut still_deferred = Amount::zero();
+    for (key, amount) in deferred_mints {
+        let Some([_, dest]) = is_any_deferred_mint_key(&key) else {
+            continue;
+        };
+        let dest = dest.clone();
+        storage.delete(&key)?;
+        still_deferred += mint_native_tokens(storage, &dest, amount)?;
+    }
+    Ok(still_deferred)
+}
+
 /// Add denomination info if it exists in storage.
 pub fn denominated(
     amount: token::Amount,
@@ -232,7 +538,11 @@ mod testing {
     use namada_core::types::{address, token};
     use namada_storage::testing::TestStorage;
 
-    use super::{burn_tokens, credit_tokens, read_balance, read_total_supply};
+    use super::{
+        burn_tokens, credit_tokens, read_balance, read_balance_delta,
+        read_total_supply,
+    };
+    use crate::storage_key::balance_key;
 
     #[test]
     fn test_burn_native_tokens() {
@@ -290,4 +600,178 @@ mod testing {
         let total_supply = read_total_supply(&storage, &native_token).unwrap();
         assert_eq!(total_supply, balance3);
     }
+
+    #[test]
+    fn test_read_balance_delta() {
+        let native_token = address::nam();
+        let addr1 = address::testing::gen_implicit_address();
+        let addr2 = address::testing::gen_implicit_address();
+
+        // A transfer of 6 from addr1 to addr2
+        let mut pre = TestStorage::default();
+        credit_tokens(&mut pre, &native_token, &addr1, token::Amount::from(10))
+            .unwrap();
+        let mut post = TestStorage::default();
+        credit_tokens(&mut post, &native_token, &addr1, token::Amount::from(4))
+            .unwrap();
+        credit_tokens(&mut post, &native_token, &addr2, token::Amount::from(6))
+            .unwrap();
+        let keys_changed = [
+            balance_key(&native_token, &addr1),
+            balance_key(&native_token, &addr2),
+        ];
+
+        let delta =
+            read_balance_delta(&pre, &post, &keys_changed, None).unwrap();
+        assert!(delta.is_net_zero());
+        let delta =
+            read_balance_delta(&pre, &post, &keys_changed, Some(&addr1))
+                .unwrap();
+        assert_eq!(delta.get(&native_token), -token::Change::from(6));
+        let delta =
+            read_balance_delta(&pre, &post, &keys_changed, Some(&addr2))
+                .unwrap();
+        assert_eq!(delta.get(&native_token), token::Change::from(6));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use namada_storage::testing::TestStorage;
+
+    use super::*;
+
+    /// Test that the effective supply of the native token doesn't count the
+    /// tokens locked in the PoS and PGF accounts.
+    #[test]
+    fn test_read_effective_native_supply() {
+        let mut storage = TestStorage::default();
+        let native_token = storage.get_native_token().unwrap();
+        let amount = Amount::native_whole;
+        let pos = Address::Internal(InternalAddress::PoS);
+        let slash_pool = Address::Internal(InternalAddress::PosSlashPool);
+        let pgf = Address::Internal(InternalAddress::Pgf);
+
+        credit_tokens(
+            &mut storage,
+            &native_token,
+            &established_address_1(),
+            amount(100),
+        )
+        .unwrap();
+        assert_eq!(
+            read_effective_native_supply(&storage).unwrap(),
+            amount(100)
+        );
+
+        credit_tokens(&mut storage, &native_token, &pos, amount(30)).unwrap();
+        credit_tokens(&mut storage, &native_token, &slash_pool, amount(2))
+            .unwrap();
+        credit_tokens(&mut storage, &native_token, &pgf, amount(8)).unwrap();
+        assert_eq!(
+            read_total_supply(&storage, &native_token).unwrap(),
+            amount(140)
+        );
+        assert_eq!(
+            read_effective_native_supply(&storage).unwrap(),
+            amount(100)
+        );
+
+        // The balances of the other tokens don't count
+        let other_token = established_address_2();
+        credit_tokens(&mut storage, &other_token, &pos, amount(10)).unwrap();
+        assert_eq!(
+            read_effective_native_supply(&storage).unwrap(),
+            amount(100)
+        );
+    }
+
+    /// Test that the native tokens minted in excess of the limit of the epoch
+    /// are deferred, then minted once there's room under the limit.
+    #[test]
+    fn test_mint_native_tokens_limit() {
+        let mut storage = TestStorage::default();
+        let native_token = storage.get_native_token().unwrap();
+        let [alice, bob] = [established_address_1(), established_address_2()];
+        let amount = Amount::native_whole;
+
+        // Without a limit, everything is minted
+        let deferred = mint_native_tokens(&mut storage, &alice, amount(50))
+            .expect("Test failed");
+        assert!(deferred.is_zero());
+
+        namada_parameters::update_max_native_mint_per_epoch_parameter(
+            &mut storage,
+            Some(amount(100)),
+        )
+        .expect("Test failed");
+        record_native_mint(&mut storage, amount(20)).expect("Test failed");
+        let deferred = mint_native_tokens(&mut storage, &bob, amount(40))
+            .expect("Test failed");
+        assert_eq!(deferred, amount(10));
+        let deferred = mint_native_tokens(&mut storage, &alice, amount(5))
+            .expect("Test failed");
+        assert_eq!(deferred, amount(5));
+
+        assert_eq!(
+            read_balance(&storage, &native_token, &alice).unwrap(),
+            amount(50)
+        );
+        assert_eq!(
+            read_balance(&storage, &native_token, &bob).unwrap(),
+            amount(30)
+        );
+        assert_eq!(
+            read_total_supply(&storage, &native_token).unwrap(),
+            amount(80)
+        );
+        assert_eq!(
+            read_mint_volume(&storage).unwrap(),
+            MintVolume {
+                epoch: storage.get_block_epoch().unwrap(),
+                minted: amount(100),
+                deferred: amount(15),
+            }
+        );
+
+        // Once the limit is raised, the deferred mints are minted in the
+        // order of their accounts up to the limit
+        namada_parameters::update_max_native_mint_per_epoch_parameter(
+            &mut storage,
+            Some(amount(112)),
+        )
+        .expect("Test failed");
+        let still_deferred =
+            mint_deferred_native_tokens(&mut storage).expect("Test failed");
+        assert_eq!(still_deferred, amount(3));
+        assert_eq!(
+            read_balance(&storage, &native_token, &alice).unwrap()
+                + read_balance(&storage, &native_token, &bob).unwrap(),
+            amount(92)
+        );
+
+        // Without a limit, all the deferred mints are minted
+        namada_parameters::update_max_native_mint_per_epoch_parameter(
+            &mut storage,
+            None,
+        )
+        .expect("Test failed");
+        let still_deferred =
+            mint_deferred_native_tokens(&mut storage).expect("Test failed");
+        assert!(still_deferred.is_zero());
+        assert_eq!(
+            read_total_supply(&storage, &native_token).unwrap(),
+            amount(95)
+        );
+        let deferred_mints = namada_storage::iter_prefix::<Amount>(
+            &storage,
+            &deferred_mint_prefix(&native_token),
+        )
+        .unwrap()
+        .count();
+        assert_eq!(deferred_mints, 0);
+    }
 }
@@ -13,6 +13,14 @@ pub const MINTER_STORAGE_KEY: &str = "minter";
 pub const MINTED_STORAGE_KEY: &str = "minted";
 /// Key segment for token parameters
 pub const PARAMETERS_STORAGE_KEY: &str = "parameters";
+/// Key segment for a token's metadata
+pub const METADATA_STORAGE_KEY: &str = "metadata";
+/// Key segment for the allowances of the spenders
+pub const ALLOWANCE_STORAGE_KEY: &str = "allowance";
+/// Key segment for the native tokens minted by the protocol in an epoch
+pub const MINT_VOLUME_STORAGE_KEY: &str = "mint_volume";
+/// Key segment for the native tokens whose minting was deferred
+pub const DEFERRED_MINT_STORAGE_KEY: &str = "deferred_mint";
 
 /// Gets the key for the given token address, error with the given
 /// message to expect if the key is not in the address
@@ -66,6 +74,95 @@ pub fn minter_key(token_addr: &Address) -> storage::Key {
     .expect("Cannot obtain a storage key")
 }
 
+/// Obtain a storage key for the allowance of a spender to transfer an owner's
+/// tokens.
+pub fn allowance_key(
+    token_addr: &Address,
+    owner: &Address,
+    spender: &Address,
+) -> storage::Key {
+    storage::Key::from(
+        Address::Internal(InternalAddress::Multitoken).to_db_key(),
+    )
+    .push(&token_addr.to_db_key())
+    .expect("Cannot obtain a storage key")
+    .push(&ALLOWANCE_STORAGE_KEY.to_owned())
+    .expect("Cannot obtain a storage key")
+    .push(&owner.to_db_key())
+    .expect("Cannot obtain a storage key")
+    .push(&spender.to_db_key())
+    .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is an allowance key for an unspecified
+/// token. If it is, return the token, owner and spender addresses.
+pub fn is_any_allowance_key(key: &storage::Key) -> Option<[&Address; 3]> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::AddressSeg(token),
+            DbKeySeg::StringSeg(allowance),
+            DbKeySeg::AddressSeg(owner),
+            DbKeySeg::AddressSeg(spender),
+        ]
+            if *addr == Address::Internal(InternalAddress::Multitoken)
+                && allowance == ALLOWANCE_STORAGE_KEY =>
+        {
+            Some([token, owner, spender])
+        }
+        _ => None,
+    }
+}
+
+/// Obtain a storage key for the amount of a native token minted by the
+/// protocol in the current epoch.
+pub fn mint_volume_key(token_addr: &Address) -> storage::Key {
+    storage::Key::from(
+        Address::Internal(InternalAddress::Multitoken).to_db_key(),
+    )
+    .push(&token_addr.to_db_key())
+    .expect("Cannot obtain a storage key")
+    .push(&MINT_VOLUME_STORAGE_KEY.to_owned())
+    .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for all the deferred mints of a native token.
+pub fn deferred_mint_prefix(token_addr: &Address) -> storage::Key {
+    storage::Key::from(
+        Address::Internal(InternalAddress::Multitoken).to_db_key(),
+    )
+    .push(&token_addr.to_db_key())
+    .expect("Cannot obtain a storage key")
+    .push(&DEFERRED_MINT_STORAGE_KEY.to_owned())
+    .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the amount of a native token whose minting to an
+/// account was deferred.
+pub fn deferred_mint_key(token_addr: &Address, dest: &Address) -> storage::Key {
+    deferred_mint_prefix(token_addr)
+        .push(&dest.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a deferred mint key for an unspecified
+/// token. If it is, return the token and the destination addresses.
+pub fn is_any_deferred_mint_key(key: &storage::Key) -> Option<[&Address; 2]> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::AddressSeg(token),
+            DbKeySeg::StringSeg(deferred_mint),
+            DbKeySeg::AddressSeg(dest),
+        ] if *addr == Address::Internal(InternalAddress::Multitoken)
+            && deferred_mint == DEFERRED_MINT_STORAGE_KEY =>
+        {
+            Some([token, dest])
+        }
+        _ => None,
+    }
+}
+
 /// Obtain a storage key for the minted multitoken balance.
 pub fn minted_balance_key(token_addr: &Address) -> storage::Key {
     balance_prefix(token_addr)
@@ -149,6 +246,39 @@ pub fn is_denom_key(token_addr: &Address, key: &storage::Key) -> bool {
         ] if key == DENOM_STORAGE_KEY && addr == token_addr)
 }
 
+/// Check if the given storage key is a denomination key for an unspecified
+/// token. If it is, returns the token.
+pub fn is_any_denom_key(key: &storage::Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), .., DbKeySeg::StringSeg(key)]
+            if key == DENOM_STORAGE_KEY =>
+        {
+            Some(addr)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain a storage key for the metadata of a token.
+pub fn metadata_key(token_addr: &Address) -> storage::Key {
+    storage::Key::from(token_addr.to_db_key())
+        .push(&METADATA_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a metadata key for an unspecified
+/// token. If it is, returns the token.
+pub fn is_any_metadata_key(key: &storage::Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)]
+            if key == METADATA_STORAGE_KEY =>
+        {
+            Some(addr)
+        }
+        _ => None,
+    }
+}
+
 /// Check if the given storage key is for a minter of a unspecified token.
 /// If it is, returns the token.
 pub fn is_any_minter_key(key: &storage::Key) -> Option<&Address> {
@@ -13,7 +13,7 @@ pub mod protocol;
 /// wrapper txs with encrypted payloads
 pub mod wrapper;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{self, Display};
 use std::str::FromStr;
 
@@ -26,6 +26,8 @@ use namada_core::types::ethereum_structs::EthBridgeEvent;
 use namada_core::types::hash::Hash;
 use namada_core::types::ibc::IbcEvent;
 use namada_core::types::storage;
+use namada_core::types::token::Amount;
+use namada_core::types::validity_predicate::VpRejection;
 use namada_gas::{Gas, VpsGas};
 use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::{FromPrimitive, ToPrimitive};
@@ -155,6 +157,175 @@ impl From<ResultCode> for namada_core::tendermint::abci::Code {
     }
 }
 
+/// The codes of the reasons why the application of a tx failed. Contrary to
+/// the [`ResultCode`], which tells at which stage a tx failed, they let the
+/// clients tell the users why without having to parse the logs.
+/// The codes must not change with versions, only new ones may be added.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    FromPrimitive,
+    ToPrimitive,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum TxErrorCode {
+    // WARN: These codes shouldn't be changed between version!
+    // =========================================================================
+    /// A failure not covered by the other codes
+    Other = 1,
+    /// Error in WASM tx execution
+    WasmRuntimeError = 2,
+    /// Some VPs rejected the tx
+    VpRejected = 3,
+    /// The tx ran out of gas
+    GasExhausted = 4,
+    /// The balance of an account was insufficient
+    InsufficientFunds = 5,
+    /// Invalid signature
+    InvalidSig = 6,
+    /// Error in paying tx fee
+    FeeError = 7,
+    /// A section of the tx is missing
+    MissingSection = 8,
+    /// Replayed tx
+    ReplayTx = 9,
+    /// The tx is not in the allowlist
+    DisallowedTx = 10,
+    // =========================================================================
+    // WARN: These codes shouldn't be changed between version!
+}
+
+impl TxErrorCode {
+    /// Convert to `u32`.
+    pub fn to_u32(&self) -> u32 {
+        ToPrimitive::to_u32(self).unwrap()
+    }
+
+    /// Convert from `u32`.
+    pub fn from_u32(raw: u32) -> Option<Self> {
+        FromPrimitive::from_u32(raw)
+    }
+}
+
+impl Display for TxErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_u32())
+    }
+}
+
+impl FromStr for TxErrorCode {
+    type Err = std::io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = u32::from_str(s).map_err(|e| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+        })?;
+        Self::from_u32(raw).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Unexpected error code",
+            )
+        })
+    }
+}
+
+/// The balance of an account was insufficient to apply a tx
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InsufficientFunds {
+    /// The owner of the balance
+    pub owner: Address,
+    /// The token of the balance
+    pub token: Address,
+    /// The amount that was needed
+    pub needed: Amount,
+    /// The amount that was available
+    pub available: Amount,
+}
+
+/// The machine-readable data of the failure of the application of a tx.
+/// It is included in the tx events as JSON, along with its [`TxErrorCode`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TxErrorData {
+    /// A failure not covered by the other variants
+    Other,
+    /// Error in WASM tx execution
+    WasmRuntimeError,
+    /// Some VPs rejected the tx
+    VpRejected {
+        /// The addresses whose VPs rejected the tx
+        rejected_vps: BTreeSet<Address>,
+        /// The errors that occurred in the VPs
+        errors: Vec<(Address, String)>,
+        /// The reasons given by the VPs that rejected the tx
+        #[serde(default)]
+        rejections: BTreeMap<Address, VpRejection>,
+    },
+    /// The tx ran out of gas
+    GasExhausted,
+    /// The balance of an account was insufficient
+    InsufficientFunds(InsufficientFunds),
+    /// Invalid signature
+    InvalidSig,
+    /// Error in paying tx fee
+    FeeError,
+    /// A section of the tx is missing
+    MissingSection,
+    /// Replayed tx
+    ReplayTx,
+    /// The tx is not in the allowlist
+    DisallowedTx,
+}
+
+impl TxErrorData {
+    /// Get the code of this failure.
+    pub fn code(&self) -> TxErrorCode {
+        match self {
+            Self::Other => TxErrorCode::Other,
+            Self::WasmRuntimeError => TxErrorCode::WasmRuntimeError,
+            Self::VpRejected { .. } => TxErrorCode::VpRejected,
+            Self::GasExhausted => TxErrorCode::GasExhausted,
+            Self::InsufficientFunds(_) => TxErrorCode::InsufficientFunds,
+            Self::InvalidSig => TxErrorCode::InvalidSig,
+            Self::FeeError => TxErrorCode::FeeError,
+            Self::MissingSection => TxErrorCode::MissingSection,
+            Self::ReplayTx => TxErrorCode::ReplayTx,
+            Self::DisallowedTx => TxErrorCode::DisallowedTx,
+        }
+    }
+
+    /// Get the failure of a tx that was applied but rejected by some VPs.
+    pub fn from_vps_result(vps_result: &VpsResult) -> Self {
+        if vps_result.invalid_sig {
+            Self::InvalidSig
+        } else {
+            Self::VpRejected {
+                rejected_vps: vps_result.rejected_vps.clone(),
+                errors: vps_result.errors.clone(),
+                rejections: vps_result.rejections.clone(),
+            }
+        }
+    }
+}
+
+impl Display for TxErrorData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", serde_json::to_string(self).unwrap())
+    }
+}
+
+impl FromStr for TxErrorData {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
 /// Get the hash of a transaction
 pub fn hash_tx(tx_bytes: &[u8]) -> Hash {
     let digest = Sha256::digest(tx_bytes);
@@ -214,8 +385,15 @@ pub struct VpsResult {
     pub gas_used: VpsGas,
     /// Errors occurred in any of the VPs, if any
     pub errors: Vec<(Address, String)>,
+    /// The reasons given by the VPs that rejected the transaction, if any
+    #[serde(default)]
+    pub rejections: BTreeMap<Address, VpRejection>,
     /// Sentinel to signal an invalid transaction signature
     pub invalid_sig: bool,
+    /// The code hashes of the wasm VPs that were run, with the gas used by
+    /// each of them. Only kept in memory for the statistics of the node.
+    #[serde(skip)]
+    pub wasm_vps: BTreeMap<Address, (Hash, Gas)>,
 }
 
 impl fmt::Display for TxResult {
@@ -251,7 +429,7 @@ impl fmt::Display for VpsResult {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "{}{}{}",
+            "{}{}{}{}",
             iterable_to_string("Accepted", self.accepted_vps.iter()),
             iterable_to_string("Rejected", self.rejected_vps.iter()),
             iterable_to_string(
@@ -260,6 +438,13 @@ impl fmt::Display for VpsResult {
                     .iter()
                     .map(|(addr, err)| format!("{} in {}", err, addr))
             ),
+            iterable_to_string(
+                "Rejections",
+                self.rejections.iter().map(|(addr, rejection)| format!(
+                    "{} by {}",
+                    rejection, addr
+                ))
+            ),
         )
     }
 }
@@ -338,6 +523,60 @@ impl TxSentinel {
     }
 }
 
+#[cfg(test)]
+mod test_tx_error {
+    use assert_matches::assert_matches;
+    use namada_core::types::address::nam;
+    use namada_core::types::address::testing::established_address_1;
+
+    use super::*;
+
+    /// Test that the error data round-trips through its JSON and that its
+    /// code is stable.
+    #[test]
+    fn test_tx_error_data_json() {
+        let error = TxErrorData::InsufficientFunds(InsufficientFunds {
+            owner: established_address_1(),
+            token: nam(),
+            needed: Amount::from_u64(100),
+            available: Amount::from_u64(10),
+        });
+        let json = error.to_string();
+        assert!(json.contains(r#""kind":"insufficient_funds""#));
+        assert!(json.contains(r#""needed":"100""#));
+        assert_eq!(TxErrorData::from_str(&json).unwrap(), error);
+        assert_eq!(error.code().to_u32(), 5);
+        assert_eq!(
+            TxErrorCode::from_str(&error.code().to_string()).unwrap(),
+            TxErrorCode::InsufficientFunds
+        );
+
+        let rejection = VpRejection {
+            code: 7,
+            message: "Debit over the limit".to_string(),
+            key: Some(storage::Key::parse("key/a").unwrap()),
+        };
+        let vps_result = VpsResult {
+            rejected_vps: [nam()].into(),
+            rejections: [(nam(), rejection.clone())].into(),
+            ..Default::default()
+        };
+        assert!(vps_result.to_string().contains(&format!(
+            "Rejections: {} by {};",
+            rejection,
+            nam()
+        )));
+        let error = TxErrorData::from_vps_result(&vps_result);
+        assert_eq!(error.code(), TxErrorCode::VpRejected);
+        assert_eq!(TxErrorData::from_str(&error.to_string()).unwrap(), error);
+        assert_matches!(
+            &error,
+            TxErrorData::VpRejected { rejections, .. }
+                if rejections.get(&nam()) == Some(&rejection)
+        );
+    }
+}
+
 #[cfg(test)]
 mod test_process_tx {
     use assert_matches::assert_matches;
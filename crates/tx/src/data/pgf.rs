@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use namada_core::borsh::{BorshDeserialize, BorshSerialize};
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
+use namada_core::types::token::Amount;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -30,6 +31,23 @@ pub struct UpdateStewardCommission {
     pub commission: HashMap<Address, Dec>,
 }
 
+/// A tx data type to claim tokens of a pgf retro funding
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ClaimPgfFunding {
+    /// The target address of the retro funding
+    pub target: Address,
+    /// The amount to claim, or all the currently claimable tokens if `None`
+    pub amount: Option<Amount>,
+}
+
 #[cfg(any(test, feature = "testing"))]
 /// Tests and strategies for PGF
 pub mod tests {
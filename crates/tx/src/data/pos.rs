@@ -3,7 +3,9 @@
 use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
+use namada_core::types::hash::Hash;
 use namada_core::types::key::{common, secp256k1};
+use namada_core::types::storage::Epoch;
 use namada_core::types::token;
 use serde::{Deserialize, Serialize};
 
@@ -70,6 +72,9 @@ pub struct Bond {
     /// Source address for delegations. For self-bonds, the validator is
     /// also the source.
     pub source: Option<Address>,
+    /// An optional future epoch in which the bond (or unbond) should be
+    /// applied. When `None`, it is applied in the current epoch.
+    pub start_epoch: Option<Epoch>,
 }
 
 /// An unbond of a bond.
@@ -117,6 +122,31 @@ pub struct ClaimRewards {
     pub source: Option<Address>,
 }
 
+/// A claim of pending rewards that are delivered into the shielded pool.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct ShieldedClaimRewards {
+    /// Validator address
+    pub validator: Address,
+    /// Source address for claiming rewards from a bond. For self-bonds, the
+    /// validator is also the source
+    pub source: Option<Address>,
+    /// The key of a pinned payment address, if any
+    pub key: Option<String>,
+    /// The hash of the MASP transaction section that shields the rewards
+    pub shielded: Hash,
+}
+
 /// A redelegation of bonded tokens from one validator to another.
 #[derive(
     Debug,
@@ -187,6 +217,8 @@ pub struct MetaDataChange {
     pub discord_handle: Option<String>,
     /// Validator's avatar url
     pub avatar: Option<String>,
+    /// Validator's unique handle
+    pub handle: Option<String>,
     /// Validator's commission rate
     pub commission_rate: Option<Dec>,
 }
@@ -217,6 +249,7 @@ pub mod tests {
     use namada_core::types::address::testing::arb_non_internal_address;
     use namada_core::types::dec::testing::arb_dec;
     use namada_core::types::key::testing::{arb_common_pk, arb_pk};
+    use namada_core::types::storage::testing::arb_epoch;
     use namada_core::types::token::testing::arb_amount;
     use proptest::{option, prop_compose};
 
@@ -228,11 +261,13 @@ pub mod tests {
             validator in arb_non_internal_address(),
             amount in arb_amount(),
             source in option::of(arb_non_internal_address()),
+            start_epoch in option::of(arb_epoch()),
         ) -> Bond {
             Bond {
                 validator,
                 amount,
                 source,
+                start_epoch,
             }
         }
     }
@@ -272,6 +307,7 @@ pub mod tests {
             website in option::of("[a-zA-Z0-9_]*"),
             discord_handle in option::of("[a-zA-Z0-9_]*"),
             avatar in option::of("[a-zA-Z0-9_]*"),
+            handle in option::of("[a-z0-9_-]{3,20}"),
             commission_rate in option::of(arb_dec()),
         ) -> MetaDataChange {
             MetaDataChange {
@@ -281,6 +317,7 @@ pub mod tests {
                 website,
                 discord_handle,
                 avatar,
+                handle,
                 commission_rate,
             }
         }
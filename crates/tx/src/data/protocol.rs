@@ -76,6 +76,11 @@ pub enum ProtocolTxType {
     BridgePoolVext,
     /// Validator set update signed by some validator
     ValSetUpdateVext,
+    /// Veto of a delayed transfer to Ethereum signed by some validator
+    WithdrawalVetoVext,
+    /// Signature over a checkpoint of the application state by some
+    /// validator
+    CheckpointVext,
 }
 
 impl ProtocolTxType {
@@ -91,6 +96,8 @@ impl ProtocolTxType {
                 | Self::EthEventsVext
                 | Self::BridgePoolVext
                 | Self::ValSetUpdateVext
+                | Self::WithdrawalVetoVext
+                | Self::CheckpointVext
         )
     }
 }
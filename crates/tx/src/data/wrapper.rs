@@ -329,6 +329,22 @@ pub mod wrapper_tx {
                 .checked_mul(Amount::from(self.gas_limit).into())
                 .ok_or(WrapperTxErr::OverflowingFee)
         }
+
+        /// Get the [`Amount`] of fees to be paid by the given wrapper for the
+        /// gas actually used, which is capped at the gas limit. Returns an
+        /// error if the amount overflows
+        pub fn get_used_gas_fee(
+            &self,
+            used_gas: Gas,
+        ) -> Result<DenominatedAmount, WrapperTxErr> {
+            let used_gas = used_gas
+                .get_whole_gas_units()
+                .min(u64::from(self.gas_limit));
+            self.fee
+                .amount_per_gas_unit
+                .checked_mul(Amount::from_u64(used_gas).into())
+                .ok_or(WrapperTxErr::OverflowingFee)
+        }
     }
 
     #[cfg(test)]
@@ -3,6 +3,7 @@
 pub mod data;
 pub mod proto;
 mod types;
+mod weight;
 
 pub use namada_core::types::key::SignableEthMessage;
 pub use namada_core::types::sign::SignatureIndex;
@@ -11,13 +12,19 @@ pub use types::{
     CompressedSignature, Data, DecodeError, Header, MaspBuilder, Memo, Section,
     Signature, Signed, Signer, Tx, TxError, VerifySigError,
 };
+pub use weight::{TxLimitError, TxLimits, TxWeight};
 
 #[cfg(test)]
 mod tests {
     use data_encoding::HEXLOWER;
+    use namada_core::types::account::AccountPublicKeysMap;
+    use namada_core::types::address::testing::established_address_1;
+    use namada_core::types::key::testing::common_sk_from_simple_seed;
+    use namada_core::types::key::{common, RefTo, SigScheme};
     use prost::Message;
 
     use super::*;
+    use crate::data::TxType;
 
     #[test]
     fn encoding_round_trip() {
@@ -33,4 +40,57 @@ mod tests {
         let tx_from_bytes = Tx::decode(&tx_from_hex[..]).unwrap();
         assert_eq!(tx, tx_from_bytes);
     }
+
+    /// Test that the signatures of a multisig account attached in separate
+    /// sections are aggregated into one section, verified in a single batch.
+    #[test]
+    fn test_aggregate_signatures() {
+        let owner = established_address_1();
+        let keys: Vec<common::SecretKey> =
+            (0..7).map(common_sk_from_simple_seed).collect();
+        let pks_map =
+            AccountPublicKeysMap::from_iter(keys.iter().map(RefTo::ref_to));
+        let mut tx = Tx::from_type(TxType::Raw);
+        for key in &keys[..5] {
+            tx.sign_raw(
+                vec![key.clone()],
+                pks_map.clone(),
+                Some(owner.clone()),
+            );
+        }
+        let signature_sections = |tx: &Tx| {
+            tx.sections
+                .iter()
+                .filter(|section| matches!(section, Section::Signature(_)))
+                .count()
+        };
+        let verify = |tx: &Tx, threshold| {
+            tx.verify_signatures(
+                &[tx.raw_header_hash()],
+                pks_map.clone(),
+                &Some(owner.clone()),
+                threshold,
+                None,
+                || Ok(()),
+            )
+            .map(|witnesses| witnesses.len())
+        };
+        assert_eq!(signature_sections(&tx), 5);
+        assert_eq!(verify(&tx, 5).unwrap(), 5);
+
+        let size = tx.to_bytes().len();
+        tx.aggregate_signatures();
+        assert_eq!(signature_sections(&tx), 1);
+        assert!(tx.to_bytes().len() < size);
+        assert_eq!(verify(&tx, 5).unwrap(), 1);
+        assert!(verify(&tx, 6).is_err());
+
+        // A single invalid signature fails the whole batch
+        let Some(Section::Signature(section)) = tx.sections.first_mut() else {
+            panic!("the signatures must be in the first section");
+        };
+        let forged = common::SigScheme::sign(&keys[6], section.get_raw_hash());
+        section.signatures.insert(0, forged);
+        assert!(verify(&tx, 5).is_err());
+    }
 }
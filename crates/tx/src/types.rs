@@ -430,6 +430,48 @@ impl Signature {
         self.signatures.len() as u8
     }
 
+    /// Add the signatures of the given section to this one. All the
+    /// signatures of a section are over the same hash, so this is only
+    /// possible if both sections are over the same targets and for the same
+    /// signer address, or both have their own public keys. Otherwise, the
+    /// given section is returned back.
+    pub fn merge(&mut self, other: Self) -> std::result::Result<(), Self> {
+        let mergeable = self.targets == other.targets
+            && match (&self.signer, &other.signer) {
+                (Signer::Address(addr), Signer::Address(other_addr)) => {
+                    addr == other_addr
+                }
+                // The public keys must stay indexable by a `u8`
+                (Signer::PubKeys(pks), Signer::PubKeys(other_pks)) => {
+                    pks.len() + other_pks.len() <= usize::from(u8::MAX) + 1
+                }
+                _ => false,
+            };
+        if !mergeable {
+            return Err(other);
+        }
+        match (&mut self.signer, other.signer) {
+            (Signer::PubKeys(pks), Signer::PubKeys(other_pks)) => {
+                for (idx, pk) in other_pks.into_iter().enumerate() {
+                    match other.signatures.get(&(idx as u8)) {
+                        Some(sig) if !pks.contains(&pk) => {
+                            self.signatures
+                                .insert(pks.len() as u8, sig.clone());
+                            pks.push(pk);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {
+                for (idx, sig) in other.signatures {
+                    self.signatures.entry(idx).or_insert(sig);
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Hash this signature section
     pub fn hash<'a>(&self, hasher: &'a mut Sha256) -> &'a mut Sha256 {
         hasher.update(self.serialize_to_vec());
@@ -463,8 +505,9 @@ impl Signature {
     where
         F: FnMut() -> std::result::Result<(), namada_gas::Error>,
     {
-        // Records whether there are any successful verifications
-        let mut verifications = 0;
+        // The signatures to verify, with the indices of their public keys in
+        // the given map
+        let mut pending = Vec::new();
         match &self.signer {
             // Verify the signatures against the given public keys if the
             // account addresses match
@@ -473,14 +516,7 @@ impl Signature {
                     if let Some(pk) =
                         public_keys_index_map.get_public_key_from_index(*idx)
                     {
-                        consume_verify_sig_gas()?;
-                        common::SigScheme::verify_signature(
-                            &pk,
-                            &self.get_raw_hash(),
-                            sig,
-                        )?;
-                        verified_pks.insert(*idx);
-                        verifications += 1;
+                        pending.push((*idx, pk, sig));
                     }
                 }
             }
@@ -494,19 +530,35 @@ impl Signature {
                     if let Some(map_idx) =
                         public_keys_index_map.get_index_from_public_key(pk)
                     {
-                        consume_verify_sig_gas()?;
-                        common::SigScheme::verify_signature(
-                            pk,
-                            &self.get_raw_hash(),
+                        pending.push((
+                            map_idx,
+                            pk.clone(),
                             &self.signatures[&(idx as u8)],
-                        )?;
-                        verified_pks.insert(map_idx);
-                        verifications += 1;
+                        ));
                     }
                 }
             }
         }
-        Ok(verifications)
+
+        // All the signatures are over the same hash, so the Ed25519 ones are
+        // verified together in a single batch
+        let raw_hash = self.get_raw_hash();
+        let mut batch = ed25519::BatchVerifier::default();
+        for (_, pk, sig) in &pending {
+            consume_verify_sig_gas()?;
+            match (pk, sig) {
+                (
+                    common::PublicKey::Ed25519(pk),
+                    common::Signature::Ed25519(sig),
+                ) => batch.queue(pk, sig),
+                _ => common::SigScheme::verify_signature(pk, &raw_hash, sig)?,
+            }
+        }
+        if !batch.is_empty() {
+            batch.verify(&raw_hash)?;
+        }
+        verified_pks.extend(pending.iter().map(|(idx, _, _)| *idx));
+        Ok(pending.len() as u8)
     }
 }
 
@@ -1563,4 +1615,57 @@ impl Tx {
         }
         self
     }
+
+    /// Merge the signature sections over the same targets and for the same
+    /// signer into one section, and drop the ones without signatures. The
+    /// targets and the signer are then only encoded once, and the signatures
+    /// are verified in a single batch. The signature sections that are
+    /// themselves signed over are left untouched, but the sections must
+    /// still be aggregated before signing the wrapper, as it signs over all
+    /// of them.
+    pub fn aggregate_signatures(&mut self) -> &mut Self {
+        let targeted: HashSet<_> = self
+            .sections
+            .iter()
+            .filter_map(|section| match section {
+                Section::Signature(signature) => Some(&signature.targets),
+                _ => None,
+            })
+            .flatten()
+            .cloned()
+            .collect();
+        // The indices of the sections that other sections can be merged into
+        let mut mergeable = Vec::new();
+        let mut sections = Vec::with_capacity(self.sections.len());
+        'sections: for section in std::mem::take(&mut self.sections) {
+            let mut signature = match section {
+                Section::Signature(_)
+                    if targeted.contains(&section.get_hash()) =>
+                {
+                    sections.push(section);
+                    continue;
+                }
+                Section::Signature(signature) => signature,
+                section => {
+                    sections.push(section);
+                    continue;
+                }
+            };
+            if signature.signatures.is_empty() {
+                continue;
+            }
+            for idx in &mergeable {
+                if let Section::Signature(aggregate) = &mut sections[*idx] {
+                    match aggregate.merge(signature) {
+                        Ok(()) => continue 'sections,
+                        Err(unmerged) => signature = unmerged,
+                    }
+                }
+            }
+            mergeable.push(sections.len());
+            sections.push(Section::Signature(signature));
+        }
+        self.sections = sections;
+        self
+    }
 }
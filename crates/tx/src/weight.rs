@@ -0,0 +1,248 @@
+//! The size and the weight of the transactions, to check them against the
+//! protocol limits before they are broadcast.
+
+use namada_core::types::parameters::Parameters;
+use thiserror::Error;
+
+use crate::types::{Commitment, Section, Tx};
+
+/// The breakdown of the size and the weight of a transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TxWeight {
+    /// The number of bytes of the transaction, as broadcast
+    pub bytes: usize,
+    /// The total number of signatures of the signature sections
+    pub signatures: usize,
+    /// The largest number of signatures of a single signature section
+    pub max_section_signatures: usize,
+    /// The number of bytes of the zero-knowledge proofs of the MASP
+    /// transactions
+    pub masp_proof_bytes: usize,
+    /// The number of bytes of the wasm codes included in the transaction, as
+    /// opposed to committed to by their hashes
+    pub code_bytes: usize,
+    /// The gas limit of the wrapper, if any
+    pub gas_limit: Option<u64>,
+}
+
+/// The protocol limits that the weight of a transaction is checked against
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxLimits {
+    /// The maximum number of bytes of a transaction
+    pub max_tx_bytes: u32,
+    /// The maximum number of signatures of a signature section
+    pub max_signatures_per_transaction: u8,
+    /// The maximum gas of a block, that the gas limit of a wrapper can't
+    /// exceed
+    pub max_block_gas: u64,
+    /// The maximum gas of a transaction, if lower than the one of a block
+    pub max_tx_gas: Option<u64>,
+}
+
+impl From<&Parameters> for TxLimits {
+    fn from(parameters: &Parameters) -> Self {
+        Self {
+            max_tx_bytes: parameters.max_tx_bytes,
+            max_signatures_per_transaction: parameters
+                .max_signatures_per_transaction,
+            max_block_gas: parameters.max_block_gas,
+            max_tx_gas: parameters.max_tx_gas,
+        }
+    }
+}
+
+impl TxLimits {
+    /// The maximum gas limit that a wrapper can declare
+    pub fn tx_gas_ceiling(&self) -> u64 {
+        self.max_tx_gas.map_or(self.max_block_gas, |max_tx_gas| {
+            max_tx_gas.min(self.max_block_gas)
+        })
+    }
+}
+
+/// A protocol limit exceeded by a transaction
+#[allow(missing_docs)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TxLimitError {
+    #[error(
+        "The transaction has {bytes} bytes, but at most {max} are allowed"
+    )]
+    TooManyBytes { bytes: usize, max: u32 },
+    #[error(
+        "A signature section has {signatures} signatures, but at most {max} \
+         are allowed"
+    )]
+    TooManySignatures { signatures: usize, max: u8 },
+    #[error(
+        "The gas limit {gas_limit} exceeds the maximum gas of a transaction \
+         {max}"
+    )]
+    GasLimitTooHigh { gas_limit: u64, max: u64 },
+}
+
+impl TxWeight {
+    /// Check the weight against the given protocol limits. Returns the first
+    /// limit that is exceeded, if any.
+    pub fn check(&self, limits: &TxLimits) -> Result<(), TxLimitError> {
+        if self.bytes > limits.max_tx_bytes as usize {
+            return Err(TxLimitError::TooManyBytes {
+                bytes: self.bytes,
+                max: limits.max_tx_bytes,
+            });
+        }
+        if self.max_section_signatures
+            > limits.max_signatures_per_transaction.into()
+        {
+            return Err(TxLimitError::TooManySignatures {
+                signatures: self.max_section_signatures,
+                max: limits.max_signatures_per_transaction,
+            });
+        }
+        let max_gas = limits.tx_gas_ceiling();
+        match self.gas_limit {
+            Some(gas_limit) if gas_limit > max_gas => {
+                Err(TxLimitError::GasLimitTooHigh {
+                    gas_limit,
+                    max: max_gas,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check if the weight is within all the given protocol limits
+    pub fn fits(&self, limits: &TxLimits) -> bool {
+        self.check(limits).is_ok()
+    }
+}
+
+impl Tx {
+    /// Compute the size and the weight of this transaction. The signatures
+    /// count towards them, so the weight of an unsigned transaction is lower
+    /// than once signed.
+    pub fn weight(&self) -> TxWeight {
+        let mut weight = TxWeight {
+            bytes: self.to_bytes().len(),
+            gas_limit: self
+                .header()
+                .wrapper()
+                .map(|wrapper| u64::from(wrapper.gas_limit)),
+            ..TxWeight::default()
+        };
+        for section in &self.sections {
+            match section {
+                Section::Signature(signature) => {
+                    let signatures = signature.signatures.len();
+                    weight.signatures += signatures;
+                    weight.max_section_signatures =
+                        weight.max_section_signatures.max(signatures);
+                }
+                Section::MaspTx(transaction) => {
+                    if let Some(bundle) = transaction.sapling_bundle() {
+                        let spends = bundle
+                            .shielded_spends
+                            .iter()
+                            .map(|spend| spend.zkproof.len());
+                        let converts = bundle
+                            .shielded_converts
+                            .iter()
+                            .map(|convert| convert.zkproof.len());
+                        let outputs = bundle
+                            .shielded_outputs
+                            .iter()
+                            .map(|output| output.zkproof.len());
+                        weight.masp_proof_bytes += spends
+                            .chain(converts)
+                            .chain(outputs)
+                            .sum::<usize>();
+                    }
+                }
+                Section::Code(code) | Section::ExtraData(code) => {
+                    if let Commitment::Id(bytes) = &code.code {
+                        weight.code_bytes += bytes.len();
+                    }
+                }
+                _ => {}
+            }
+        }
+        weight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::key::testing::keypair_1;
+
+    use super::*;
+    use crate::data::TxType;
+    use crate::types::Code;
+
+    fn limits() -> TxLimits {
+        TxLimits {
+            max_tx_bytes: 1024 * 1024,
+            max_signatures_per_transaction: 1,
+            max_block_gas: 1_000,
+            max_tx_gas: None,
+        }
+    }
+
+    /// Test the breakdown of the weight of a transaction and its checks
+    /// against the limits.
+    #[test]
+    fn test_tx_weight() {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_code(Code::new(vec![0; 100], None));
+        let weight = tx.weight();
+        assert_eq!(weight.bytes, tx.to_bytes().len());
+        assert_eq!(weight.code_bytes, 100);
+        assert_eq!(weight.signatures, 0);
+        assert_eq!(weight.masp_proof_bytes, 0);
+        assert_eq!(weight.gas_limit, None);
+        assert!(weight.fits(&limits()));
+
+        tx.sign_raw(vec![keypair_1(), keypair_1()], Default::default(), None);
+        let signed = tx.weight();
+        assert!(signed.bytes > weight.bytes);
+        assert_eq!(signed.signatures, 2);
+        assert_eq!(
+            signed.check(&limits()),
+            Err(TxLimitError::TooManySignatures {
+                signatures: 2,
+                max: 1
+            })
+        );
+
+        let small = TxLimits {
+            max_tx_bytes: weight.bytes as u32 - 1,
+            ..limits()
+        };
+        assert_eq!(
+            weight.check(&small),
+            Err(TxLimitError::TooManyBytes {
+                bytes: weight.bytes,
+                max: small.max_tx_bytes,
+            })
+        );
+        let heavy = TxWeight {
+            gas_limit: Some(1_001),
+            ..weight
+        };
+        assert!(!heavy.fits(&limits()));
+        let capped = TxLimits {
+            max_tx_gas: Some(500),
+            ..limits()
+        };
+        let medium = TxWeight {
+            gas_limit: Some(501),
+            ..weight
+        };
+        assert!(medium.fits(&limits()));
+        assert_eq!(
+            medium.check(&capped),
+            Err(TxLimitError::GasLimitTooHigh {
+                gas_limit: 501,
+                max: 500,
+            })
+        );
+    }
+}
@@ -5,6 +5,7 @@ use namada_core::borsh::BorshSerialize;
 use namada_core::types::address::Address;
 use namada_core::types::ibc::IbcEvent;
 use namada_core::types::storage;
+use namada_core::types::token::factory::TokenOrigin;
 use namada_storage::{Result, StorageRead, StorageWrite};
 
 /// Transaction host functions
@@ -40,6 +41,16 @@ pub trait TxEnv: StorageRead + StorageWrite {
         code_tag: &Option<String>,
     ) -> Result<Address>;
 
+    /// Initialize the account of a new token at the address derived from its
+    /// origin by the token factory and write the given code as its validity
+    /// predicate into the storage. Fails if the address is already used.
+    fn init_token(
+        &mut self,
+        code_hash: impl AsRef<[u8]>,
+        code_tag: &Option<String>,
+        origin: &TokenOrigin,
+    ) -> Result<Address>;
+
     /// Update a validity predicate
     fn update_validity_predicate(
         &mut self,
@@ -321,6 +321,37 @@ impl TxEnv for Ctx {
             .expect("Decoding address created by the ledger shouldn't fail"))
     }
 
+    fn init_token(
+        &mut self,
+        code_hash: impl AsRef<[u8]>,
+        code_tag: &Option<String>,
+        origin: &token::factory::TokenOrigin,
+    ) -> Result<Address, Error> {
+        let code_hash = code_hash.as_ref();
+        let code_tag = code_tag.serialize_to_vec();
+        let origin = origin.serialize_to_vec();
+        let result = Vec::with_capacity(address::ESTABLISHED_ADDRESS_BYTES_LEN);
+        unsafe {
+            namada_tx_init_token(
+                code_hash.as_ptr() as _,
+                code_hash.len() as _,
+                code_tag.as_ptr() as _,
+                code_tag.len() as _,
+                origin.as_ptr() as _,
+                origin.len() as _,
+                result.as_ptr() as _,
+            )
+        };
+        let slice = unsafe {
+            slice::from_raw_parts(
+                result.as_ptr(),
+                address::ESTABLISHED_ADDRESS_BYTES_LEN,
+            )
+        };
+        Ok(Address::try_from_slice(slice)
+            .expect("Decoding address created by the ledger shouldn't fail"))
+    }
+
     fn update_validity_predicate(
         &mut self,
         addr: &Address,
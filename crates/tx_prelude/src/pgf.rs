@@ -1,4 +1,4 @@
-use namada_tx::data::pgf::UpdateStewardCommission;
+use namada_tx::data::pgf::{ClaimPgfFunding, UpdateStewardCommission};
 
 use super::*;
 
@@ -20,3 +20,16 @@ pub fn remove_steward(ctx: &mut Ctx, data: &Address) -> EnvResult<()> {
 
     Ok(())
 }
+
+pub fn claim_retro_funding(
+    ctx: &mut Ctx,
+    data: ClaimPgfFunding,
+) -> EnvResult<token::Amount> {
+    let amount = namada_governance::pgf::storage::claim_retro_funding(
+        ctx,
+        &data.target,
+        data.amount,
+    )?;
+
+    Ok(amount)
+}
@@ -9,8 +9,9 @@ use namada_proof_of_stake::types::{ResultSlashing, ValidatorMetaData};
 use namada_proof_of_stake::{
     become_validator, bond_tokens, change_consensus_key,
     change_validator_commission_rate, change_validator_metadata,
-    claim_reward_tokens, deactivate_validator, reactivate_validator,
-    redelegate_tokens, unbond_tokens, unjail_validator, withdraw_tokens,
+    claim_reward_tokens, claim_reward_tokens_to, deactivate_validator,
+    reactivate_validator, redelegate_tokens, schedule_bond_tokens,
+    schedule_unbond_tokens, unbond_tokens, unjail_validator, withdraw_tokens,
 };
 pub use namada_proof_of_stake::{parameters, types};
 use namada_tx::data::pos::BecomeValidator;
@@ -44,6 +45,46 @@ impl Ctx {
         unbond_tokens(self, source, validator, amount, current_epoch, false)
     }
 
+    /// Schedule a self-bond or a delegation to be applied in the given
+    /// future `start_epoch`.
+    pub fn schedule_bond_tokens(
+        &mut self,
+        source: Option<&Address>,
+        validator: &Address,
+        amount: token::Amount,
+        start_epoch: Epoch,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        schedule_bond_tokens(
+            self,
+            source,
+            validator,
+            amount,
+            current_epoch,
+            start_epoch,
+        )
+    }
+
+    /// Schedule an unbond from a self-bond or a delegation to be applied in
+    /// the given future `start_epoch`.
+    pub fn schedule_unbond_tokens(
+        &mut self,
+        source: Option<&Address>,
+        validator: &Address,
+        amount: token::Amount,
+        start_epoch: Epoch,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        schedule_unbond_tokens(
+            self,
+            source,
+            validator,
+            amount,
+            current_epoch,
+            start_epoch,
+        )
+    }
+
     /// Withdraw unbonded tokens from a self-bond to a validator when
     /// `source` is `None` or equal to the `validator` address, or withdraw
     /// unbonded tokens delegated to the `validator` to the `source`.
@@ -111,6 +152,17 @@ impl Ctx {
         claim_reward_tokens(self, source, validator, current_epoch)
     }
 
+    /// Claim available reward tokens and transfer them to the given target
+    pub fn claim_reward_tokens_to(
+        &mut self,
+        source: Option<&Address>,
+        validator: &Address,
+        target: &Address,
+    ) -> EnvResult<token::Amount> {
+        let current_epoch = self.get_block_epoch()?;
+        claim_reward_tokens_to(self, source, validator, target, current_epoch)
+    }
+
     /// Attempt to initialize a validator account. On success, returns the
     /// initialized validator account's address.
     pub fn become_validator(
@@ -183,6 +235,7 @@ impl Ctx {
         website: Option<String>,
         discord_handle: Option<String>,
         avatar: Option<String>,
+        handle: Option<String>,
         commission_rate: Option<Dec>,
     ) -> TxResult {
         let current_epoch = self.get_block_epoch()?;
@@ -194,6 +247,7 @@ impl Ctx {
             website,
             discord_handle,
             avatar,
+            handle,
             commission_rate,
             current_epoch,
         )
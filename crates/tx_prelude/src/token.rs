@@ -4,7 +4,7 @@ use namada_proof_of_stake::token::storage_key::{
 };
 pub use namada_token::*;
 
-use crate::{log_string, Ctx, StorageRead, StorageWrite, TxResult};
+use crate::{account, log_string, Ctx, StorageRead, StorageWrite, TxResult};
 
 #[allow(clippy::too_many_arguments)]
 /// A token transfer that can be used in a transaction.
@@ -29,6 +29,8 @@ pub fn transfer(
         dest_bal.receive(&amount);
         ctx.write(&src_key, src_bal)?;
         ctx.write(&dest_key, dest_bal)?;
+        // Keep track of the outflow from accounts with a spending policy
+        account::record_policy_outflow(ctx, src, token, amount)?;
     }
     Ok(())
 }
@@ -54,6 +56,8 @@ pub fn undenominated_transfer(
         dest_bal.receive(&amount);
         ctx.write(&src_key, src_bal)?;
         ctx.write(&dest_key, dest_bal)?;
+        // Keep track of the outflow from accounts with a spending policy
+        account::record_policy_outflow(ctx, src, token, amount)?;
     }
     Ok(())
 }
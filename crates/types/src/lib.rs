@@ -0,0 +1,100 @@
+//! The Namada types consumed outside of the ledger, by indexers, wallets and
+//! other clients: the addresses, the token amounts, the keys and signatures,
+//! the transactions and the events.
+//!
+//! # Stability
+//!
+//! This crate is the stable surface of the Namada types. Unlike the rest of
+//! the protocol crates, its items follow semver:
+//!
+//! - The items re-exported from this crate are only renamed, moved or removed
+//!   in a major release.
+//! - The binary (borsh) encoding of the types and their string encoding, if
+//!   they have one, only change in a major release, such that the data written
+//!   by a node can be decoded by the clients of a previous minor release.
+//! - New items may be added in a minor release.
+//!
+//! The stability only covers what is reachable from this crate. The fns and
+//! the trait impls of the re-exported types that are only documented in the
+//! crate defining them may change in any release.
+//!
+//! The types are currently defined by the `namada_core` and `namada_tx`
+//! crates. Depending on this crate instead of them keeps clients building
+//! when the types are moved across the protocol crates, and once they are
+//! split out of these crates, without their heavier dependencies.
+
+#![doc(html_favicon_url = "https://dev.namada.net/master/favicon.png")]
+#![doc(html_logo_url = "https://dev.namada.net/master/rustdoc-logo.png")]
+#![warn(missing_docs)]
+#![deny(rustdoc::broken_intra_doc_links)]
+#![deny(rustdoc::private_intra_doc_links)]
+
+/// Addresses of the accounts
+pub mod address {
+    pub use namada_core::types::address::{
+        Address, DecodeError, EstablishedAddress, ImplicitAddress,
+        InternalAddress,
+    };
+}
+
+/// Token amounts and denominations
+pub mod token {
+    pub use namada_core::types::token::{
+        Amount, AmountParseError, DenominatedAmount, Denomination, Transfer,
+    };
+}
+
+/// Public keys and signatures
+pub mod key {
+    pub use namada_core::types::key::common::{PublicKey, Signature};
+    pub use namada_core::types::key::{PublicKeyHash, SchemeType};
+}
+
+/// Hashes, chain ids, heights, epochs and times
+pub mod chain {
+    pub use namada_core::types::chain::ChainId;
+    pub use namada_core::types::hash::Hash;
+    pub use namada_core::types::storage::{
+        BlockHash, BlockHeight, Epoch, TxIndex,
+    };
+    pub use namada_core::types::time::DateTimeUtc;
+}
+
+/// Transaction envelopes and their results
+pub mod tx {
+    pub use namada_tx::data::{
+        Fee, GasLimit, ResultCode, TxResult, TxType, WrapperTx,
+    };
+    pub use namada_tx::{Code, Data, Header, Section, Signature, Tx};
+}
+
+/// Events emitted by the ledger
+pub mod events {
+    pub use namada_core::types::ethereum_structs::{
+        BpTransferStatus, EthBridgeEvent,
+    };
+    pub use namada_core::types::ibc::IbcEvent;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::address::Address;
+    use super::chain::{BlockHeight, Epoch};
+
+    /// Test that the encodings covered by the stability policy don't change.
+    #[test]
+    fn test_stable_encodings() {
+        let encoded = "tnam1q8j5s6xp55p05yznwnftkv3kr9gjtsw3nq7x6tw5";
+        let address = Address::decode(encoded).unwrap();
+        assert_eq!(address.encode(), encoded);
+
+        assert_eq!(
+            borsh::to_vec(&Epoch(258)).unwrap(),
+            vec![2, 1, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            borsh::to_vec(&BlockHeight(258)).unwrap(),
+            vec![2, 1, 0, 0, 0, 0, 0, 0]
+        );
+    }
+}
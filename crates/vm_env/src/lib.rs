@@ -79,6 +79,17 @@ pub mod tx {
             result_ptr: u64,
         );
 
+        // Initialize the account of a new token of the token factory
+        pub fn namada_tx_init_token(
+            code_hash_ptr: u64,
+            code_hash_len: u64,
+            code_tag_ptr: u64,
+            code_tag_len: u64,
+            origin_ptr: u64,
+            origin_len: u64,
+            result_ptr: u64,
+        );
+
         // Emit an IBC event
         pub fn namada_tx_emit_ibc_event(event_ptr: u64, event_len: u64);
 
@@ -236,6 +247,9 @@ pub mod vp {
         // Requires a node running with "Info" log level
         pub fn namada_vp_log_string(str_ptr: u64, str_len: u64);
 
+        // Set the reason for rejecting the tx, a borsh encoded `VpRejection`
+        pub fn namada_vp_set_rejection(rejection_ptr: u64, rejection_len: u64);
+
         // Verify the signatures of a tx
         pub fn namada_vp_verify_tx_section_signature(
             hash_list_ptr: u64,
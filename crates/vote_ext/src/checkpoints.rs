@@ -0,0 +1,81 @@
+//! Vote extension types for co-signing the checkpoints
+//! of the application state, to be posted to external
+//! chains.
+use std::ops::Deref;
+
+use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::types::address::Address;
+use namada_core::types::key::common;
+use namada_core::types::key::common::Signature;
+use namada_core::types::storage::BlockHeight;
+use namada_tx::Signed;
+
+/// A vote extension containing a validator's signature
+/// of the checkpoint of the application state recorded
+/// at some block height.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Eq,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct CheckpointVext {
+    /// The validator signing the checkpoint.
+    pub validator_addr: Address,
+    /// The block height of the signed checkpoint.
+    ///
+    /// This allows validators to query the epoch
+    /// with the appropriate validator set to verify
+    /// the signatures.
+    pub block_height: BlockHeight,
+    /// The signature of the checkpoint, with the Ethereum
+    /// hot key of the validator. This is a signature over
+    /// `keccak(eth_header || keccak(height || app_hash))`.
+    pub sig: Signature,
+}
+
+/// Alias for [`CheckpointVext`].
+pub type Vext = CheckpointVext;
+
+/// A signed [`CheckpointVext`].
+#[derive(
+    Clone,
+    Debug,
+    BorshSerialize,
+    BorshSchema,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+pub struct SignedVext(pub Signed<CheckpointVext>);
+
+impl Deref for SignedVext {
+    type Target = Signed<CheckpointVext>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Signed<CheckpointVext>> for SignedVext {
+    fn from(value: Signed<CheckpointVext>) -> Self {
+        Self(value)
+    }
+}
+
+impl Vext {
+    /// Creates a new signed [`Vext`].
+    #[inline]
+    pub fn sign(&self, sk: &common::SecretKey) -> SignedVext {
+        SignedVext(Signed::new(sk, self.clone()))
+    }
+}
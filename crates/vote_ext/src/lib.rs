@@ -1,8 +1,10 @@
 //! This module contains types necessary for processing vote extensions.
 
 pub mod bridge_pool_roots;
+pub mod checkpoints;
 pub mod ethereum_events;
 pub mod validator_set_update;
+pub mod withdrawal_vetoes;
 
 use namada_core::borsh::{
     BorshDeserialize, BorshSchema, BorshSerialize, BorshSerializeExt,
@@ -105,6 +107,12 @@ ethereum_tx_data_declare! {
         BridgePoolVext(bridge_pool_roots::SignedVext),
         /// Validator set update signed by some validator
         ValSetUpdateVext(validator_set_update::SignedVext),
+        /// Veto of a delayed transfer to Ethereum signed by
+        /// some validator
+        WithdrawalVetoVext(withdrawal_vetoes::SignedVext),
+        /// Signature over a checkpoint of the application state
+        /// by some validator
+        CheckpointVext(checkpoints::SignedVext),
     }
 }
 
@@ -166,6 +174,8 @@ impl EthereumTxData {
             EthEventsVext,
             BridgePoolVext,
             ValSetUpdateVext,
+            WithdrawalVetoVext,
+            CheckpointVext,
         }
     }
 
@@ -199,6 +209,14 @@ impl EthereumTxData {
                 BorshDeserialize::try_from_slice(data)
                     .map(EthereumTxData::ValSetUpdateVext)
             },
+            ProtocolTxType::WithdrawalVetoVext => |data| {
+                BorshDeserialize::try_from_slice(data)
+                    .map(EthereumTxData::WithdrawalVetoVext)
+            },
+            ProtocolTxType::CheckpointVext => |data| {
+                BorshDeserialize::try_from_slice(data)
+                    .map(EthereumTxData::CheckpointVext)
+            },
         };
         deserialize(data)
             .map_err(|err| TxError::Deserialization(err.to_string()))
@@ -0,0 +1,78 @@
+//! Vote extension types for vetoing a transfer to
+//! Ethereum, while it is delayed by the withdrawal
+//! veto window of its asset.
+use std::ops::Deref;
+
+use namada_core::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_core::types::address::Address;
+use namada_core::types::keccak::KeccakHash;
+use namada_core::types::key::common;
+use namada_core::types::storage::BlockHeight;
+use namada_tx::Signed;
+
+/// A vote extension containing a validator's veto
+/// of a delayed transfer to Ethereum.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Eq,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct WithdrawalVetoVext {
+    /// The validator vetoing the transfer.
+    pub validator_addr: Address,
+    /// The block height at which the veto was sent.
+    ///
+    /// This allows validators to query the epoch
+    /// with the appropriate validator set to verify
+    /// the signature and to weigh the veto.
+    pub block_height: BlockHeight,
+    /// The hash of the vetoed transfer.
+    pub transfer_hash: KeccakHash,
+}
+
+/// Alias for [`WithdrawalVetoVext`].
+pub type Vext = WithdrawalVetoVext;
+
+/// A signed [`WithdrawalVetoVext`].
+#[derive(
+    Clone,
+    Debug,
+    BorshSerialize,
+    BorshSchema,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+pub struct SignedVext(pub Signed<WithdrawalVetoVext>);
+
+impl Deref for SignedVext {
+    type Target = Signed<WithdrawalVetoVext>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Signed<WithdrawalVetoVext>> for SignedVext {
+    fn from(value: Signed<WithdrawalVetoVext>) -> Self {
+        Self(value)
+    }
+}
+
+impl Vext {
+    /// Creates a new signed [`Vext`].
+    #[inline]
+    pub fn sign(&self, sk: &common::SecretKey) -> SignedVext {
+        SignedVext(Signed::new(sk, self.clone()))
+    }
+}
@@ -16,6 +16,7 @@ use namada_core::types::storage::{
 };
 use namada_core::types::token::Transfer;
 use namada_storage::{OptionExt, ResultExt, StorageRead};
+use namada_tx::data::pos::ShieldedClaimRewards;
 use namada_tx::Tx;
 
 /// Validity predicate's environment is available for native VPs and WASM VPs
@@ -135,6 +136,14 @@ where
             return Ok(message.shielded_transfer.masp_tx);
         }
 
+        if let Ok(claim) = ShieldedClaimRewards::try_from_slice(&data) {
+            let masp_tx = signed
+                .get_section(&claim.shielded)
+                .and_then(|x| x.as_ref().masp_tx())
+                .ok_or_err_msg("unable to find shielded section")?;
+            return Ok(masp_tx);
+        }
+
         // Shielded transfer over IBC
         let events = self.get_ibc_events(EVENT_TYPE_PACKET.to_string())?;
         // The receiving event should be only one in the single IBC transaction
@@ -68,12 +68,23 @@ pub fn is_proposal_accepted(ctx: &Ctx, proposal_id: u64) -> VpResult {
 
 /// Verify section signatures
 pub fn verify_signatures(ctx: &Ctx, tx: &Tx, owner: &Address) -> VpResult {
+    let threshold = account::threshold(&ctx.pre(), owner)?.unwrap_or(1);
+    verify_signatures_with_threshold(ctx, tx, owner, threshold)
+}
+
+/// Verify section signatures against the given threshold instead of the
+/// account's own threshold
+pub fn verify_signatures_with_threshold(
+    ctx: &Ctx,
+    tx: &Tx,
+    owner: &Address,
+    threshold: u8,
+) -> VpResult {
     let max_signatures_per_transaction =
         parameters::max_signatures_per_transaction(&ctx.pre())?;
 
     let public_keys_index_map =
         account::public_keys_index_map(&ctx.pre(), owner)?;
-    let threshold = account::threshold(&ctx.pre(), owner)?.unwrap_or(1);
 
     // Serialize parameters
     let max_signatures = max_signatures_per_transaction.serialize_to_vec();
@@ -191,6 +202,28 @@ pub fn reject() -> VpResult {
     Ok(false)
 }
 
+/// Reject a transaction with a reason, to be reported in the tx result
+pub fn reject_with(
+    code: u32,
+    message: impl ToString,
+    key: Option<&storage::Key>,
+) -> VpResult {
+    set_rejection(&validity_predicate::VpRejection {
+        code,
+        message: message.to_string(),
+        key: key.cloned(),
+    });
+    Ok(false)
+}
+
+/// Set the reason of the rejection of the transaction by this VP
+pub fn set_rejection(rejection: &validity_predicate::VpRejection) {
+    let rejection = rejection.serialize_to_vec();
+    unsafe {
+        namada_vp_set_rejection(rejection.as_ptr() as _, rejection.len() as _);
+    }
+}
+
 #[derive(Debug)]
 pub struct KeyValIterator<T>(pub u64, pub PhantomData<T>);
 
@@ -1,3 +1,5 @@
+#[cfg(feature = "tx_approve")]
+pub mod tx_approve;
 #[cfg(feature = "tx_become_validator")]
 pub mod tx_become_validator;
 #[cfg(feature = "tx_bond")]
@@ -10,8 +12,12 @@ pub mod tx_change_consensus_key;
 pub mod tx_change_validator_commission;
 #[cfg(feature = "tx_change_validator_metadata")]
 pub mod tx_change_validator_metadata;
+#[cfg(feature = "tx_claim_pgf_funding")]
+pub mod tx_claim_pgf_funding;
 #[cfg(feature = "tx_claim_rewards")]
 pub mod tx_claim_rewards;
+#[cfg(feature = "tx_create_token")]
+pub mod tx_create_token;
 #[cfg(feature = "tx_deactivate_validator")]
 pub mod tx_deactivate_validator;
 #[cfg(feature = "tx_ibc")]
@@ -20,22 +26,36 @@ pub mod tx_ibc;
 pub mod tx_init_account;
 #[cfg(feature = "tx_init_proposal")]
 pub mod tx_init_proposal;
+#[cfg(feature = "tx_multi_transfer")]
+pub mod tx_multi_transfer;
 #[cfg(feature = "tx_reactivate_validator")]
 pub mod tx_reactivate_validator;
 #[cfg(feature = "tx_redelegate")]
 pub mod tx_redelegate;
+#[cfg(feature = "tx_register_name")]
+pub mod tx_register_name;
+#[cfg(feature = "tx_renew_name")]
+pub mod tx_renew_name;
 #[cfg(feature = "tx_resign_steward")]
 pub mod tx_resign_steward;
 #[cfg(feature = "tx_reveal_pk")]
 pub mod tx_reveal_pk;
+#[cfg(feature = "tx_shielded_claim_rewards")]
+pub mod tx_shielded_claim_rewards;
 #[cfg(feature = "tx_transfer")]
 pub mod tx_transfer;
+#[cfg(feature = "tx_transfer_from")]
+pub mod tx_transfer_from;
+#[cfg(feature = "tx_transfer_name")]
+pub mod tx_transfer_name;
 #[cfg(feature = "tx_unbond")]
 pub mod tx_unbond;
 #[cfg(feature = "tx_unjail_validator")]
 pub mod tx_unjail_validator;
 #[cfg(feature = "tx_update_account")]
 pub mod tx_update_account;
+#[cfg(feature = "tx_update_policy")]
+pub mod tx_update_policy;
 #[cfg(feature = "tx_update_steward_commission")]
 pub mod tx_update_steward_commission;
 #[cfg(feature = "tx_vote_proposal")]
@@ -45,5 +65,9 @@ pub mod tx_withdraw;
 
 #[cfg(feature = "vp_implicit")]
 pub mod vp_implicit;
+#[cfg(feature = "vp_policy")]
+pub mod vp_policy;
+#[cfg(feature = "vp_token")]
+pub mod vp_token;
 #[cfg(feature = "vp_user")]
 pub mod vp_user;
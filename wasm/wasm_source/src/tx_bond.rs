@@ -13,7 +13,17 @@ fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
         .wrap_err("failed to decode Bond")
         .unwrap();
 
-    ctx.bond_tokens(bond.source.as_ref(), &bond.validator, bond.amount)
+    match bond.start_epoch {
+        Some(start_epoch) => ctx.schedule_bond_tokens(
+            bond.source.as_ref(),
+            &bond.validator,
+            bond.amount,
+            start_epoch,
+        ),
+        None => {
+            ctx.bond_tokens(bond.source.as_ref(), &bond.validator, bond.amount)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -367,6 +377,7 @@ mod tests {
                     validator: Address::Established(validator),
                     amount,
                     source,
+                    start_epoch: None,
                 }
             })
     }
@@ -0,0 +1,22 @@
+//! A tx to claim tokens of a pgf retro funding
+
+use namada_tx_prelude::transaction::pgf::ClaimPgfFunding;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1222239)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let claim = ClaimPgfFunding::try_from_slice(&data[..])
+        .wrap_err("failed to decode a ClaimPgfFunding")?;
+
+    debug_log!("apply_tx called with pgf claim: {:#?}", claim);
+
+    let amount = pgf::claim_retro_funding(ctx, claim)?;
+    debug_log!("claimed {} pgf tokens", amount.to_string_native());
+
+    Ok(())
+}
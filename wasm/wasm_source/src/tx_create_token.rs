@@ -0,0 +1,40 @@
+//! A tx to create a new token with the token factory, at the address derived
+//! from its creator and salt.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 885069)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let tx_data = token::factory::CreateToken::try_from_slice(&data[..])
+        .wrap_err("failed to decode CreateToken")?;
+    debug_log!("apply_tx called to create a new token");
+
+    let vp_code_sec = signed
+        .get_section(&tx_data.vp_code_hash)
+        .ok_or_err_msg("vp code section not found")
+        .map_err(|err| {
+            ctx.set_commitment_sentinel();
+            err
+        })?
+        .extra_data_sec()
+        .ok_or_err_msg("vp code section must be tagged as extra")
+        .map_err(|err| {
+            ctx.set_commitment_sentinel();
+            err
+        })?;
+
+    let address = ctx.init_token(
+        vp_code_sec.code.hash(),
+        &vp_code_sec.tag,
+        &tx_data.origin,
+    )?;
+    token::factory::create_token(ctx, &tx_data)?;
+    ctx.insert_verifier(&tx_data.origin.creator)?;
+    debug_log!("Created token {}", address.encode());
+    Ok(())
+}
@@ -0,0 +1,24 @@
+//! A tx for a batch of transparent token transfers.
+//! This tx uses `token::MultiTransfer` wrapped inside `SignedTxData`
+//! as its input as declared in `namada` crate.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1703358)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let transfer = token::MultiTransfer::try_from_slice(&data[..])
+        .wrap_err("failed to decode token::MultiTransfer")?;
+    debug_log!("apply_tx called with multi transfer: {:#?}", transfer);
+
+    // Any failed leg fails the whole tx, so that none of its changes are
+    // committed
+    for leg in &transfer.legs {
+        token::transfer(ctx, &leg.source, &leg.target, &leg.token, leg.amount)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+//! A tx for registering a name for an address and paying its fee.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1220000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let tx_data =
+        account::name_service::RegisterName::try_from_slice(&data[..])
+            .wrap_err("failed to decode RegisterName")?;
+    debug_log!("register name {} for: {}", tx_data.name, tx_data.owner);
+
+    let fee = account::name_service::register_name(
+        ctx,
+        &tx_data.name,
+        &tx_data.owner,
+        tx_data.epochs,
+    )?;
+    let native_token = ctx.get_native_token()?;
+    token::undenominated_transfer(
+        ctx,
+        &tx_data.owner,
+        &account::name_service::ADDRESS,
+        &native_token,
+        fee,
+    )
+}
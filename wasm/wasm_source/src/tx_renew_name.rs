@@ -0,0 +1,26 @@
+//! A tx for extending the registration of a name and paying its fee.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1220000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let tx_data = account::name_service::RenewName::try_from_slice(&data[..])
+        .wrap_err("failed to decode RenewName")?;
+    debug_log!("renew name {} by {} epochs", tx_data.name, tx_data.epochs);
+
+    let (owner, fee) =
+        account::name_service::renew_name(ctx, &tx_data.name, tx_data.epochs)?;
+    let native_token = ctx.get_native_token()?;
+    token::undenominated_transfer(
+        ctx,
+        &owner,
+        &account::name_service::ADDRESS,
+        &native_token,
+        fee,
+    )
+}
@@ -0,0 +1,37 @@
+//! A tx for a user to claim PoS inflationary rewards due to bonds used as
+//! voting power in consensus, and to deliver them into the shielded pool.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1703358)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let claim =
+        transaction::pos::ShieldedClaimRewards::try_from_slice(&data[..])
+            .wrap_err("failed to decode ShieldedClaimRewards")?;
+    debug_log!("apply_tx called with shielded claim: {:#?}", claim);
+
+    let shielded = signed
+        .get_section(&claim.shielded)
+        .and_then(|x| x.as_ref().masp_tx())
+        .ok_or_err_msg("unable to find shielded section")
+        .map_err(|err| {
+            ctx.set_commitment_sentinel();
+            err
+        })?;
+
+    // The rewards are transferred from PoS straight into the MASP, which
+    // validates that they match the transparent input of the shielded section
+    ctx.claim_reward_tokens_to(
+        claim.source.as_ref(),
+        &claim.validator,
+        &address::MASP,
+    )?;
+    token::utils::handle_masp_tx(ctx, &shielded, claim.key.as_deref())?;
+    update_masp_note_commitment_tree(&shielded)?;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+//! A tx for transferring a name to a new owner.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1060000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let tx_data =
+        account::name_service::TransferName::try_from_slice(&data[..])
+            .wrap_err("failed to decode TransferName")?;
+    debug_log!("transfer name {} to: {}", tx_data.name, tx_data.new_owner);
+
+    let owner = account::name_service::transfer_name(
+        ctx,
+        &tx_data.name,
+        &tx_data.new_owner,
+    )?;
+    // The transfer must be authorized by the previous owner
+    ctx.insert_verifier(&owner)?;
+
+    Ok(())
+}
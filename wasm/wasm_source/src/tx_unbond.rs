@@ -13,11 +13,20 @@ fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
     let unbond = transaction::pos::Unbond::try_from_slice(&data[..])
         .wrap_err("failed to decode Unbond")?;
 
-    ctx.unbond_tokens(
-        unbond.source.as_ref(),
-        &unbond.validator,
-        unbond.amount,
-    )?;
+    if let Some(start_epoch) = unbond.start_epoch {
+        ctx.schedule_unbond_tokens(
+            unbond.source.as_ref(),
+            &unbond.validator,
+            unbond.amount,
+            start_epoch,
+        )?;
+    } else {
+        ctx.unbond_tokens(
+            unbond.source.as_ref(),
+            &unbond.validator,
+            unbond.amount,
+        )?;
+    }
     // TODO: would using debug_log! be useful?
 
     Ok(())
@@ -382,6 +391,7 @@ mod tests {
                     validator,
                     amount,
                     source,
+                    start_epoch: None,
                 }
             })
     }
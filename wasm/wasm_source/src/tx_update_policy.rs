@@ -0,0 +1,23 @@
+//! A tx for setting or removing the spending policy of an account.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 968137)]
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let tx_data = account::UpdatePolicy::try_from_slice(&data[..])
+        .wrap_err("failed to decode UpdatePolicy")?;
+    debug_log!("update policy for: {:#?}", tx_data.addr);
+
+    if matches!(&tx_data.policy, Some(policy) if policy.quorum == 0) {
+        return Err(Error::new_const("The policy quorum must not be zero"));
+    }
+
+    account::update_policy(ctx, &tx_data.addr, tx_data.policy)?;
+
+    Ok(())
+}
@@ -20,8 +20,13 @@ enum KeyType<'a> {
     /// Public key - written once revealed
     Pk(&'a Address),
     TokenBalance {
+        token: &'a Address,
         owner: &'a Address,
     },
+    TokenAllowance {
+        owner: &'a Address,
+        spender: &'a Address,
+    },
     TokenMinted,
     TokenMinter(&'a Address),
     PoS,
@@ -29,6 +34,9 @@ enum KeyType<'a> {
     PgfSteward(&'a Address),
     GovernanceVote(&'a Address),
     Ibc,
+    NftOwner(&'a Address),
+    NftMetadata,
+    TokenOrigin,
     Unknown,
 }
 
@@ -36,10 +44,14 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
     fn from(key: &'a storage::Key) -> KeyType<'a> {
         if let Some(address) = account::is_pks_key(key) {
             Self::Pk(address)
-        } else if let Some([_, owner]) =
+        } else if let Some([token, owner]) =
             token::storage_key::is_any_token_balance_key(key)
         {
-            Self::TokenBalance { owner }
+            Self::TokenBalance { token, owner }
+        } else if let Some([_, owner, spender]) =
+            token::storage_key::is_any_allowance_key(key)
+        {
+            Self::TokenAllowance { owner, spender }
         } else if token::storage_key::is_any_minted_balance_key(key).is_some() {
             Self::TokenMinted
         } else if let Some(minter) = token::storage_key::is_any_minter_key(key)
@@ -60,6 +72,14 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             Self::Masp
         } else if ibc::is_ibc_key(key) {
             Self::Ibc
+        } else if let Some((class, _)) =
+            token::non_fungible::is_any_owner_key(key)
+        {
+            Self::NftOwner(class)
+        } else if token::non_fungible::is_any_metadata_key(key).is_some() {
+            Self::NftMetadata
+        } else if token::factory::is_any_origin_key(key).is_some() {
+            Self::TokenOrigin
         } else {
             Self::Unknown
         }
@@ -85,6 +105,22 @@ fn validate_tx(
         matches!(verify_signatures(ctx, &tx_data, &addr), Ok(true))
     });
 
+    // The changes of the token balances of this address
+    let balance_delta = token::read_balance_delta(
+        &ctx.pre(),
+        &ctx.post(),
+        &keys_changed,
+        Some(&addr),
+    )?;
+    // The changes of the allowances granted by this address, that are spent
+    // by the approved spenders
+    let allowance_delta = token::read_allowance_delta(
+        &ctx.pre(),
+        &ctx.post(),
+        &keys_changed,
+        &addr,
+    )?;
+
     for key in keys_changed.iter() {
         let key_type: KeyType = key.into();
         let is_valid = match key_type {
@@ -113,15 +149,14 @@ fn validate_tx(
                 }
                 true
             }
-            KeyType::TokenBalance { owner, .. } => {
+            KeyType::TokenBalance { token, owner } => {
                 if owner == &addr {
-                    let pre: token::Amount =
-                        ctx.read_pre(key)?.unwrap_or_default();
-                    let post: token::Amount =
-                        ctx.read_post(key)?.unwrap_or_default();
-                    let change = post.change() - pre.change();
-                    // debit has to signed, credit doesn't
-                    let valid = change.non_negative() || *valid_sig;
+                    let change = balance_delta.get(token);
+                    // debit has to signed, credit doesn't. A debit within the
+                    // allowances spent in the tx is signed by the spenders.
+                    let valid = change.non_negative()
+                        || *valid_sig
+                        || change >= allowance_delta.get(token);
                     let sign = if change.non_negative() { "" } else { "-" };
                     debug_log!(
                         "token key: {}, change: {}{:?}, valid_sig: {}, valid \
@@ -145,12 +180,43 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::TokenAllowance { owner, spender } => {
+                let pre: token::Amount = ctx.read_pre(key)?.unwrap_or_default();
+                let post: token::Amount =
+                    ctx.read_post(key)?.unwrap_or_default();
+                if owner == &addr {
+                    // Granting an allowance has to be signed, spending it is
+                    // checked with the debit of the balance
+                    post <= pre || *valid_sig
+                } else if spender == &addr {
+                    // Spending an allowance has to be signed
+                    post >= pre || *valid_sig
+                } else {
+                    true
+                }
+            }
             KeyType::TokenMinted => verifiers.contains(&address::MULTITOKEN),
             KeyType::TokenMinter(minter) => minter != &addr || *valid_sig,
             KeyType::PoS => validate_pos_changes(ctx, &addr, key, &valid_sig)?,
             KeyType::PgfSteward(address) => address != &addr || *valid_sig,
             KeyType::GovernanceVote(voter) => voter != &addr || *valid_sig,
-            KeyType::Masp | KeyType::Ibc => true,
+            KeyType::NftOwner(class) => {
+                match ctx.read_pre::<Address>(key)? {
+                    // Transferring or burning an owned NFT has to be signed
+                    Some(owner) => owner != addr || *valid_sig,
+                    // Minting an NFT of this class has to be signed
+                    None => class != &addr || *valid_sig,
+                }
+            }
+            KeyType::TokenOrigin => {
+                // Creating a token has to be signed by its creator
+                let origin: Option<token::factory::TokenOrigin> =
+                    ctx.read_post(key)?;
+                origin.map_or(true, |origin| origin.creator != addr)
+                    || *valid_sig
+            }
+            // The metadata is checked by the NFT VP together with the owner
+            KeyType::Masp | KeyType::Ibc | KeyType::NftMetadata => true,
             KeyType::Unknown => {
                 // Unknown changes require a valid signature
                 *valid_sig
@@ -0,0 +1,544 @@
+//! A VP for custodial accounts with a spending policy.
+//!
+//! Like the user VP, this VP requires a valid signature(s) to debit tokens
+//! from the account or to make any other changes to it (receiving tokens is
+//! permissive). When the account has a spending policy, every debit must also
+//! satisfy it:
+//!
+//! - if the policy has a set of allowed targets, all the other accounts
+//!   credited with the debited token must be in the set
+//! - the total amount of the token debited within the current epoch must not
+//!   exceed the token's outflow limit. The outflow is recorded by the tx.
+//! - a debit above the token's co-signing threshold must be also signed by all
+//!   the co-signers
+//!
+//! Changes of the policy, the account's keys, threshold or VP must be signed
+//! by the policy's quorum of the account's keys.
+
+use namada_vp_prelude::*;
+use once_cell::unsync::Lazy;
+
+enum KeyType<'a> {
+    TokenBalance {
+        token: &'a Address,
+        owner: &'a Address,
+    },
+    TokenMinted,
+    PolicyOutflow {
+        owner: &'a Address,
+        token: &'a Address,
+    },
+    Governed(&'a Address),
+    Vp(&'a Address),
+    Masp,
+    Ibc,
+    Unknown,
+}
+
+impl<'a> From<&'a storage::Key> for KeyType<'a> {
+    fn from(key: &'a storage::Key) -> KeyType<'a> {
+        if let Some([token, owner]) =
+            token::storage_key::is_any_token_balance_key(key)
+        {
+            Self::TokenBalance { token, owner }
+        } else if token::storage_key::is_any_minted_balance_key(key).is_some() {
+            Self::TokenMinted
+        } else if let Some((owner, token)) = account::is_policy_outflow_key(key)
+        {
+            Self::PolicyOutflow { owner, token }
+        } else if let Some(owner) = account::is_policy_key(key)
+            .or_else(|| account::is_pks_key(key))
+            .or_else(|| account::is_threshold_key(key))
+        {
+            Self::Governed(owner)
+        } else if let Some(address) = key.is_validity_predicate() {
+            Self::Vp(address)
+        } else if token::storage_key::is_masp_key(key) {
+            Self::Masp
+        } else if ibc::is_ibc_key(key) {
+            Self::Ibc
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+#[validity_predicate(gas = 137325)]
+fn validate_tx(
+    ctx: &Ctx,
+    tx_data: Tx,
+    addr: Address,
+    keys_changed: BTreeSet<storage::Key>,
+    verifiers: BTreeSet<Address>,
+) -> VpResult {
+    debug_log!(
+        "vp_policy called with user addr: {}, key_changed: {:?}, verifiers: \
+         {:?}",
+        addr,
+        keys_changed,
+        verifiers
+    );
+
+    let policy = account::policy(&ctx.pre(), &addr)?;
+
+    let valid_sig = Lazy::new(|| {
+        matches!(verify_signatures(ctx, &tx_data, &addr), Ok(true))
+    });
+    let valid_quorum_sig = Lazy::new(|| match &policy {
+        Some(policy) => matches!(
+            verify_signatures_with_threshold(
+                ctx,
+                &tx_data,
+                &addr,
+                policy.quorum
+            ),
+            Ok(true)
+        ),
+        None => *valid_sig,
+    });
+
+    for key in keys_changed.iter() {
+        let key_type: KeyType = key.into();
+        let is_valid = match key_type {
+            KeyType::TokenBalance { token, owner } => {
+                if owner == &addr {
+                    let pre: token::Amount =
+                        ctx.read_pre(key)?.unwrap_or_default();
+                    let post: token::Amount =
+                        ctx.read_post(key)?.unwrap_or_default();
+                    match pre.checked_sub(post) {
+                        // debit has to signed and allowed by the policy,
+                        // credit doesn't
+                        Some(debit) if !debit.is_zero() => {
+                            *valid_sig
+                                && match &policy {
+                                    Some(policy) => validate_debit(
+                                        ctx,
+                                        &tx_data,
+                                        &addr,
+                                        &keys_changed,
+                                        policy,
+                                        token,
+                                        debit,
+                                    )?,
+                                    None => true,
+                                }
+                        }
+                        _ => true,
+                    }
+                } else {
+                    // If this is not the owner, allow any change
+                    true
+                }
+            }
+            KeyType::TokenMinted => verifiers.contains(&address::MULTITOKEN),
+            KeyType::PolicyOutflow { owner, token } => {
+                owner != &addr || validate_outflow_record(ctx, &addr, token)?
+            }
+            KeyType::Governed(owner) => owner != &addr || *valid_quorum_sig,
+            KeyType::Vp(owner) => {
+                let has_post: bool = ctx.has_key_post(key)?;
+                if owner == &addr {
+                    has_post && *valid_quorum_sig
+                } else {
+                    true
+                }
+            }
+            KeyType::Masp | KeyType::Ibc => true,
+            KeyType::Unknown => {
+                // Unknown changes require a valid signature
+                *valid_sig
+            }
+        };
+        if !is_valid {
+            log_string(format!("key {} modification failed vp_policy", key));
+            return reject();
+        }
+    }
+
+    accept()
+}
+
+/// Check that a debit of the given token from the account satisfies its
+/// spending policy
+fn validate_debit(
+    ctx: &Ctx,
+    tx: &Tx,
+    owner: &Address,
+    keys_changed: &BTreeSet<storage::Key>,
+    policy: &account::AccountPolicy,
+    token: &Address,
+    debit: token::Amount,
+) -> VpResult {
+    // All the credited accounts must be allowed
+    if !policy.allowed_targets.is_empty() {
+        for key in keys_changed {
+            let Some([credited_token, target]) =
+                token::storage_key::is_any_token_balance_key(key)
+            else {
+                continue;
+            };
+            if credited_token != token
+                || target == owner
+                || policy.allowed_targets.contains(target)
+            {
+                continue;
+            }
+            let pre: token::Amount = ctx.read_pre(key)?.unwrap_or_default();
+            let post: token::Amount = ctx.read_post(key)?.unwrap_or_default();
+            if post > pre {
+                log_string(format!(
+                    "{target} is not an allowed target of {owner}"
+                ));
+                return reject();
+            }
+        }
+    }
+
+    // The outflow in the current epoch must be within the limit
+    if let Some(limit) = policy.outflow_limits.get(token) {
+        let epoch = ctx.get_block_epoch()?;
+        let post_outflow =
+            account::policy_outflow(&ctx.post(), owner, token, epoch)?;
+        if !validate_outflow_record(ctx, owner, token)? || post_outflow > *limit
+        {
+            log_string(format!(
+                "The outflow of {token} from {owner} exceeds the limit of \
+                 {limit}"
+            ));
+            return reject();
+        }
+    }
+
+    // A debit over the threshold must be signed by all the co-signers
+    if let Some(threshold) = policy.cosign_thresholds.get(token) {
+        if debit > *threshold {
+            for cosigner in &policy.cosigners {
+                if !verify_signatures(ctx, tx, cosigner)? {
+                    log_string(format!(
+                        "Missing a signature of the co-signer {cosigner}"
+                    ));
+                    return reject();
+                }
+            }
+        }
+    }
+
+    accept()
+}
+
+/// Check that the recorded outflow of the given token in the current epoch
+/// matches the token's debit from the account in this tx
+fn validate_outflow_record(
+    ctx: &Ctx,
+    owner: &Address,
+    token: &Address,
+) -> VpResult {
+    let balance_key = token::storage_key::balance_key(token, owner);
+    let pre: token::Amount = ctx.read_pre(&balance_key)?.unwrap_or_default();
+    let post: token::Amount = ctx.read_post(&balance_key)?.unwrap_or_default();
+    let debit = pre.checked_sub(post).unwrap_or_default();
+
+    let epoch = ctx.get_block_epoch()?;
+    let pre_outflow = account::policy_outflow(&ctx.pre(), owner, token, epoch)?;
+    let post_outflow =
+        account::policy_outflow(&ctx.post(), owner, token, epoch)?;
+    Ok(pre_outflow.checked_add(debit) == Some(post_outflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use namada::tx::data::TxType;
+    use namada::tx::{Code, Data, Signature};
+    use namada_test_utils::TestWasms;
+    // Use this as `#[test]` annotation to enable logging
+    use namada_tests::log::test;
+    use namada_tests::tx::{self, tx_host_env, TestTxEnv};
+    use namada_tests::vp::*;
+    use namada_tx_prelude::TxEnv;
+    use namada_vp_prelude::account::{AccountPolicy, AccountPublicKeysMap};
+    use namada_vp_prelude::key::RefTo;
+
+    use super::*;
+
+    /// Sign the tx from the VP env with the given keys of an account and
+    /// validate it
+    fn validate_signed(
+        mut vp_env: TestVpEnv,
+        vp_owner: Address,
+        signers: Vec<(Vec<key::common::SecretKey>, Option<Address>)>,
+    ) -> bool {
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        for (keypairs, signer) in signers {
+            let pks_map = AccountPublicKeysMap::from_iter(
+                keypairs.iter().map(|keypair| keypair.ref_to()),
+            );
+            tx.add_section(Section::Signature(Signature::new(
+                vec![tx.raw_header_hash()],
+                pks_map.index_secret_keys(keypairs),
+                signer,
+            )));
+        }
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers).unwrap()
+    }
+
+    /// Init a policy account with a single key and a NAM balance, run a
+    /// signed transfer of the given amount from it to the target and
+    /// validate the tx
+    fn validate_policy_transfer(
+        policy: AccountPolicy,
+        target: Address,
+        amount: token::Amount,
+        cosigner_keys: Vec<key::common::SecretKey>,
+    ) -> bool {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+        let token = address::nam();
+        let balance = token::Amount::native_whole(1_000);
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner, &target, &token]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key], 1);
+        let cosigners = policy.cosigners.clone();
+        for cosigner in &cosigners {
+            let pks = cosigner_keys.iter().map(|key| key.ref_to()).collect();
+            tx_env.init_account_storage(cosigner, pks, 1);
+        }
+        account::update_policy(&mut tx_env.wl_storage, &vp_owner, Some(policy))
+            .unwrap();
+
+        // Credit the tokens to the VP owner before running the transaction to
+        // be able to transfer from it
+        tx_env.credit_tokens(&vp_owner, &token, balance);
+        // write the denomination of NAM into storage
+        token::write_denom(
+            &mut tx_env.wl_storage,
+            &token,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        )
+        .unwrap();
+
+        let amount = token::DenominatedAmount::new(
+            amount,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        );
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Apply transfer in a transaction
+            tx_host_env::token::transfer(
+                tx::ctx(),
+                address,
+                &target,
+                &token,
+                amount,
+            )
+            .unwrap();
+        });
+
+        let vp_env = vp_host_env::take();
+        let mut signers = vec![(vec![keypair], None)];
+        if !cosigner_keys.is_empty() {
+            for cosigner in cosigners {
+                signers.push((cosigner_keys.clone(), Some(cosigner)));
+            }
+        }
+        validate_signed(vp_env, vp_owner, signers)
+    }
+
+    /// Test that a transfer to an allowed target within the limits is
+    /// accepted.
+    #[test]
+    fn test_transfer_within_policy_accepted() {
+        let target = address::testing::established_address_2();
+        let policy = AccountPolicy {
+            allowed_targets: [target.clone()].into_iter().collect(),
+            outflow_limits: [(
+                address::nam(),
+                token::Amount::native_whole(100),
+            )]
+            .into_iter()
+            .collect(),
+            quorum: 1,
+            ..Default::default()
+        };
+        assert!(validate_policy_transfer(
+            policy,
+            target,
+            token::Amount::native_whole(100),
+            vec![]
+        ));
+    }
+
+    /// Test that a transfer to a target that is not allowed is rejected.
+    #[test]
+    fn test_transfer_to_disallowed_target_rejected() {
+        let policy = AccountPolicy {
+            allowed_targets: [address::testing::established_address_3()]
+                .into_iter()
+                .collect(),
+            quorum: 1,
+            ..Default::default()
+        };
+        assert!(!validate_policy_transfer(
+            policy,
+            address::testing::established_address_2(),
+            token::Amount::native_whole(10),
+            vec![]
+        ));
+    }
+
+    /// Test that a transfer over the outflow limit is rejected.
+    #[test]
+    fn test_transfer_over_outflow_limit_rejected() {
+        let policy = AccountPolicy {
+            outflow_limits: [(
+                address::nam(),
+                token::Amount::native_whole(100),
+            )]
+            .into_iter()
+            .collect(),
+            quorum: 1,
+            ..Default::default()
+        };
+        assert!(!validate_policy_transfer(
+            policy,
+            address::testing::established_address_2(),
+            token::Amount::native_whole(101),
+            vec![]
+        ));
+    }
+
+    /// Test that a transfer over the co-signing threshold is only accepted
+    /// with the co-signers' signatures.
+    #[test]
+    fn test_transfer_over_cosign_threshold() {
+        let policy = AccountPolicy {
+            cosigners: [address::testing::established_address_3()]
+                .into_iter()
+                .collect(),
+            cosign_thresholds: [(
+                address::nam(),
+                token::Amount::native_whole(10),
+            )]
+            .into_iter()
+            .collect(),
+            quorum: 1,
+            ..Default::default()
+        };
+        let target = address::testing::established_address_2();
+        let amount = token::Amount::native_whole(11);
+        assert!(!validate_policy_transfer(
+            policy.clone(),
+            target.clone(),
+            amount,
+            vec![]
+        ));
+        assert!(validate_policy_transfer(
+            policy,
+            target,
+            amount,
+            vec![key::testing::keypair_2()]
+        ));
+    }
+
+    /// Test that a policy update requires the quorum of signatures.
+    #[test]
+    fn test_policy_update_quorum() {
+        let validate_update = |keypairs: Vec<key::common::SecretKey>| {
+            // Initialize a tx environment
+            let mut tx_env = TestTxEnv::default();
+            tx_env.init_parameters(None, None, None, None);
+
+            let vp_owner = address::testing::established_address_1();
+            let public_keys = vec![
+                key::testing::keypair_1().ref_to(),
+                key::testing::keypair_2().ref_to(),
+            ];
+
+            // Spawn the accounts to be able to modify their storage
+            tx_env.spawn_accounts([&vp_owner]);
+            tx_env.init_account_storage(&vp_owner, public_keys, 1);
+            let policy = AccountPolicy {
+                quorum: 2,
+                ..Default::default()
+            };
+            account::update_policy(
+                &mut tx_env.wl_storage,
+                &vp_owner,
+                Some(policy),
+            )
+            .unwrap();
+
+            // Initialize VP environment from a transaction
+            vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+                // Remove the policy in a transaction
+                account::update_policy(tx::ctx(), address, None).unwrap();
+            });
+
+            let vp_env = vp_host_env::take();
+            validate_signed(vp_env, vp_owner, vec![(keypairs, None)])
+        };
+
+        assert!(!validate_update(vec![key::testing::keypair_1()]));
+        assert!(validate_update(vec![
+            key::testing::keypair_1(),
+            key::testing::keypair_2()
+        ]));
+    }
+
+    /// Test that a validity predicate update requires the quorum of
+    /// signatures.
+    #[test]
+    fn test_vp_update_without_quorum_rejected() {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+        tx_env.init_parameters(None, None, None, None);
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_keys =
+            vec![keypair.ref_to(), key::testing::keypair_2().ref_to()];
+        let vp_code = TestWasms::VpAlwaysTrue.read_bytes();
+        let vp_hash = sha256(&vp_code);
+        // for the update
+        tx_env.store_wasm_code(vp_code);
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner]);
+        tx_env.init_account_storage(&vp_owner, public_keys, 1);
+        let policy = AccountPolicy {
+            quorum: 2,
+            ..Default::default()
+        };
+        account::update_policy(&mut tx_env.wl_storage, &vp_owner, Some(policy))
+            .unwrap();
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Update VP in a transaction
+            tx::ctx()
+                .update_validity_predicate(address, vp_hash, &None)
+                .unwrap();
+        });
+
+        let vp_env = vp_host_env::take();
+        assert!(!validate_signed(
+            vp_env,
+            vp_owner,
+            vec![(vec![keypair], None)]
+        ));
+    }
+}
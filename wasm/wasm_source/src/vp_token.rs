@@ -0,0 +1,35 @@
+//! A VP for the tokens created by the token factory.
+//!
+//! The denomination and the metadata of a token are written together with its
+//! account when it's created, and they can't be changed after that. Neither
+//! can its VP. The balances of the token are validated by the multitoken VP,
+//! so that any other change is allowed.
+
+use namada_vp_prelude::*;
+
+#[validity_predicate(gas = 1000)] // TODO: needs to be benchmarked
+fn validate_tx(
+    ctx: &Ctx,
+    _tx_data: Tx,
+    addr: Address,
+    keys_changed: BTreeSet<storage::Key>,
+    verifiers: BTreeSet<Address>,
+) -> VpResult {
+    debug_log!(
+        "vp_token called with token addr: {}, key_changed: {:?}, verifiers: \
+         {:?}",
+        addr,
+        keys_changed,
+        verifiers
+    );
+
+    let subspace = addr.to_db_key();
+    for key in keys_changed.iter() {
+        if key.segments.first() == Some(&subspace) {
+            log_string(format!("key {} modification failed vp_token", key));
+            return reject();
+        }
+    }
+
+    accept()
+}
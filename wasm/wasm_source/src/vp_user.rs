@@ -31,7 +31,14 @@ use proof_of_stake::storage_key::{
 };
 
 enum KeyType<'a> {
-    TokenBalance { owner: &'a Address },
+    TokenBalance {
+        token: &'a Address,
+        owner: &'a Address,
+    },
+    TokenAllowance {
+        owner: &'a Address,
+        spender: &'a Address,
+    },
     TokenMinted,
     TokenMinter(&'a Address),
     PoS,
@@ -40,15 +47,22 @@ enum KeyType<'a> {
     PgfSteward(&'a Address),
     GovernanceVote(&'a Address),
     Ibc,
+    NftOwner(&'a Address),
+    NftMetadata,
+    TokenOrigin,
     Unknown,
 }
 
 impl<'a> From<&'a storage::Key> for KeyType<'a> {
     fn from(key: &'a storage::Key) -> KeyType<'a> {
-        if let Some([_, owner]) =
+        if let Some([token, owner]) =
             token::storage_key::is_any_token_balance_key(key)
         {
-            Self::TokenBalance { owner }
+            Self::TokenBalance { token, owner }
+        } else if let Some([_, owner, spender]) =
+            token::storage_key::is_any_allowance_key(key)
+        {
+            Self::TokenAllowance { owner, spender }
         } else if token::storage_key::is_any_minted_balance_key(key).is_some() {
             Self::TokenMinted
         } else if let Some(minter) = token::storage_key::is_any_minter_key(key)
@@ -71,6 +85,14 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             Self::Masp
         } else if ibc::is_ibc_key(key) {
             Self::Ibc
+        } else if let Some((class, _)) =
+            token::non_fungible::is_any_owner_key(key)
+        {
+            Self::NftOwner(class)
+        } else if token::non_fungible::is_any_metadata_key(key).is_some() {
+            Self::NftMetadata
+        } else if token::factory::is_any_origin_key(key).is_some() {
+            Self::TokenOrigin
         } else {
             Self::Unknown
         }
@@ -96,18 +118,33 @@ fn validate_tx(
         matches!(verify_signatures(ctx, &tx_data, &addr), Ok(true))
     });
 
+    // The changes of the token balances of this address
+    let balance_delta = token::read_balance_delta(
+        &ctx.pre(),
+        &ctx.post(),
+        &keys_changed,
+        Some(&addr),
+    )?;
+    // The changes of the allowances granted by this address, that are spent
+    // by the approved spenders
+    let allowance_delta = token::read_allowance_delta(
+        &ctx.pre(),
+        &ctx.post(),
+        &keys_changed,
+        &addr,
+    )?;
+
     for key in keys_changed.iter() {
         let key_type: KeyType = key.into();
         let is_valid = match key_type {
-            KeyType::TokenBalance { owner, .. } => {
+            KeyType::TokenBalance { token, owner } => {
                 if owner == &addr {
-                    let pre: token::Amount =
-                        ctx.read_pre(key)?.unwrap_or_default();
-                    let post: token::Amount =
-                        ctx.read_post(key)?.unwrap_or_default();
-                    let change = post.change() - pre.change();
-                    // debit has to signed, credit doesn't
-                    let valid = change.non_negative() || *valid_sig;
+                    let change = balance_delta.get(token);
+                    // debit has to signed, credit doesn't. A debit within the
+                    // allowances spent in the tx is signed by the spenders.
+                    let valid = change.non_negative()
+                        || *valid_sig
+                        || change >= allowance_delta.get(token);
                     debug_log!(
                         "token key: {}, change: {:?}, valid_sig: {}, valid \
                          modification: {}",
@@ -129,6 +166,21 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::TokenAllowance { owner, spender } => {
+                let pre: token::Amount = ctx.read_pre(key)?.unwrap_or_default();
+                let post: token::Amount =
+                    ctx.read_post(key)?.unwrap_or_default();
+                if owner == &addr {
+                    // Granting an allowance has to be signed, spending it is
+                    // checked with the debit of the balance
+                    post <= pre || *valid_sig
+                } else if spender == &addr {
+                    // Spending an allowance has to be signed
+                    post >= pre || *valid_sig
+                } else {
+                    true
+                }
+            }
             KeyType::TokenMinted => verifiers.contains(&address::MULTITOKEN),
             KeyType::TokenMinter(minter) => minter != &addr || *valid_sig,
             KeyType::PoS => validate_pos_changes(ctx, &addr, key, &valid_sig)?,
@@ -142,7 +194,23 @@ fn validate_tx(
                     true
                 }
             }
-            KeyType::Masp | KeyType::Ibc => true,
+            KeyType::NftOwner(class) => {
+                match ctx.read_pre::<Address>(key)? {
+                    // Transferring or burning an owned NFT has to be signed
+                    Some(owner) => owner != addr || *valid_sig,
+                    // Minting an NFT of this class has to be signed
+                    None => class != &addr || *valid_sig,
+                }
+            }
+            KeyType::TokenOrigin => {
+                // Creating a token has to be signed by its creator
+                let origin: Option<token::factory::TokenOrigin> =
+                    ctx.read_post(key)?;
+                origin.map_or(true, |origin| origin.creator != addr)
+                    || *valid_sig
+            }
+            // The metadata is checked by the NFT VP together with the owner
+            KeyType::Masp | KeyType::Ibc | KeyType::NftMetadata => true,
             KeyType::Unknown => {
                 // Unknown changes require a valid signature
                 *valid_sig
@@ -479,6 +547,132 @@ mod tests {
         );
     }
 
+    /// Test that an NFT transfer without a valid signature of its owner is
+    /// rejected.
+    #[test]
+    fn test_unsigned_nft_transfer_rejected() {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        let class = address::testing::established_address_3();
+        let id = token::non_fungible::NftId::new("kitty").unwrap();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner, &target, &class]);
+        // Give the NFT to the VP owner before running the transaction to be
+        // able to transfer it
+        tx_env
+            .wl_storage
+            .storage
+            .write(
+                &token::non_fungible::owner_key(&class, &id),
+                vp_owner.serialize_to_vec(),
+            )
+            .unwrap();
+
+        let transfer = token::non_fungible::TransferNft {
+            class,
+            id,
+            source: vp_owner.clone(),
+            target,
+        };
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |_address| {
+            // Apply transfer in a transaction
+            token::non_fungible::transfer(tx::ctx(), &transfer).unwrap();
+        });
+
+        let vp_env = vp_host_env::take();
+        let mut tx_data = Tx::from_type(TxType::Raw);
+        tx_data.set_data(Data::new(vec![]));
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        assert!(
+            !validate_tx(&CTX, tx_data, vp_owner, keys_changed, verifiers)
+                .unwrap()
+        );
+    }
+
+    /// Test that an unsigned debit is accepted within the allowance spent by
+    /// an approved spender, and rejected beyond it.
+    #[test]
+    fn test_unsigned_debit_within_allowance() {
+        for extra_debit in [false, true] {
+            // Initialize a tx environment
+            let mut tx_env = TestTxEnv::default();
+
+            let vp_owner = address::testing::established_address_1();
+            let spender = address::testing::established_address_2();
+            let target = address::testing::established_address_3();
+            let token = address::nam();
+            let amount = token::Amount::from_uint(10_098_123, 0).unwrap();
+
+            // Spawn the accounts to be able to modify their storage
+            tx_env.spawn_accounts([&vp_owner, &spender, &target, &token]);
+            // write the denomination of NAM into storage
+            token::write_denom(
+                &mut tx_env.wl_storage,
+                &token,
+                token::NATIVE_MAX_DECIMAL_PLACES.into(),
+            )
+            .unwrap();
+
+            // Credit the tokens to the VP owner and approve the spender
+            // before running the transaction
+            let balance = token::Amount::from_uint(20_000_000, 0).unwrap();
+            tx_env.credit_tokens(&vp_owner, &token, balance);
+            token::approve(
+                &mut tx_env.wl_storage,
+                &token,
+                &vp_owner,
+                &spender,
+                amount,
+            )
+            .unwrap();
+
+            // Initialize VP environment from a transaction
+            vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+                // Spend the allowance in a transaction
+                token::transfer_from(
+                    tx::ctx(),
+                    &token,
+                    address,
+                    &spender,
+                    &target,
+                    amount,
+                )
+                .unwrap();
+                if extra_debit {
+                    token::transfer(
+                        tx::ctx(),
+                        &token,
+                        address,
+                        &target,
+                        token::Amount::from_uint(1, 0).unwrap(),
+                    )
+                    .unwrap();
+                }
+            });
+
+            let vp_env = vp_host_env::take();
+            let mut tx_data = Tx::from_type(TxType::Raw);
+            tx_data.set_data(Data::new(vec![]));
+            let keys_changed: BTreeSet<storage::Key> =
+                vp_env.all_touched_storage_keys();
+            let verifiers: BTreeSet<Address> = BTreeSet::default();
+            vp_host_env::set(vp_env);
+            assert_eq!(
+                validate_tx(&CTX, tx_data, vp_owner, keys_changed, verifiers)
+                    .unwrap(),
+                !extra_debit
+            );
+        }
+    }
+
     /// Test that a debit transfer with a valid signature is accepted.
     #[test]
     fn test_signed_debit_transfer_accepted() {
@@ -786,6 +980,7 @@ mod tests {
                     Some("website".to_owned()),
                     Some("discord".to_owned()),
                     Some("avatar".to_owned()),
+                    Some("handle".to_owned()),
                     Some(Dec::new(6, 2).unwrap()),
                 )
                 .unwrap();
@@ -1072,6 +1267,7 @@ mod tests {
                     Some("website".to_owned()),
                     Some("discord".to_owned()),
                     Some("avatar".to_owned()),
+                    Some("handle".to_owned()),
                     Some(Dec::new(6, 2).unwrap()),
                 )
                 .unwrap();